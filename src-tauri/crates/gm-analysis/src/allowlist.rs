@@ -42,10 +42,20 @@ const ICS_PROTOCOLS: &[&str] = &[
     "Bacnet",
     "OpcUa",
     "Iec104",
+    "Iec61850Mms",
     "ProfinetDcp",
     "HartIp",
     "GeSrtp",
     "WonderwareSuitelink",
+    "Fins",
+    "Slmp",
+    "Codesys",
+    "NiagaraFox",
+    "Crimson",
+    "VnetIp",
+    "TriStation",
+    "Ads",
+    "OpcDa",
     "FfHse",
 ];
 
@@ -327,6 +337,7 @@ fn prettify_device_type(dt: &str) -> String {
     match dt {
         "plc" => "PLC".to_string(),
         "rtu" => "RTU".to_string(),
+        "ied" => "IED".to_string(),
         "hmi" => "HMI".to_string(),
         "historian" => "Historian".to_string(),
         "scada_server" => "SCADA Server".to_string(),
@@ -6,6 +6,7 @@
 //! - **Role reversal**: Slave/outstation sending to non-master
 //! - **New device**: Previously unseen device on OT subnet
 //! - **Unexpected public IP**: Public routable IP on OT network
+//! - **Duplicate IP**: One IPv4 address claimed by several MACs in ARP
 
 use crate::{AnalysisInput, AnomalyScore, AnomalyType, Finding, FindingType, Severity};
 
@@ -31,6 +32,11 @@ pub fn detect_anomalies(input: &AnalysisInput) -> (Vec<AnomalyScore>, Vec<Findin
     anomalies.extend(pub_anomalies);
     findings.extend(pub_findings);
 
+    // Addresses claimed by more than one MAC
+    let (dup_anomalies, dup_findings) = detect_duplicate_ips(input);
+    anomalies.extend(dup_anomalies);
+    findings.extend(dup_findings);
+
     (anomalies, findings)
 }
 
@@ -252,6 +258,59 @@ fn detect_unexpected_public_ips(input: &AnalysisInput) -> (Vec<AnomalyScore>, Ve
     (anomalies, findings)
 }
 
+/// Detect IPv4 addresses claimed by more than one MAC in ARP.
+///
+/// Two devices configured with one address each lose traffic to the other;
+/// a device answering for an address it does not own (ARP spoofing) puts
+/// itself in the path of that address's traffic. ARP alone cannot tell the
+/// two apart, so both are raised for the analyst to confirm.
+fn detect_duplicate_ips(input: &AnalysisInput) -> (Vec<AnomalyScore>, Vec<Finding>) {
+    let mut anomalies = Vec::new();
+    let mut findings = Vec::new();
+
+    for binding in &input.arp {
+        if binding.mac_addresses.len() < 2 {
+            continue;
+        }
+        let ip = &binding.ip_address;
+        let macs = binding.mac_addresses.join(", ");
+        let evidence = format!(
+            "ARP claims for {}: {} ({} gratuitous)",
+            ip, macs, binding.gratuitous_count
+        );
+
+        anomalies.push(AnomalyScore {
+            anomaly_type: AnomalyType::DuplicateIp,
+            severity: Severity::High,
+            confidence: 0.9,
+            affected_asset: ip.clone(),
+            evidence: evidence.clone(),
+        });
+
+        findings.push(Finding::new(
+            FindingType::Anomaly,
+            Severity::High,
+            format!("Duplicate IP address: {}", ip),
+            format!(
+                "{} was claimed in ARP by {} different MAC addresses. Either two \
+                 devices are configured with the same address, so traffic for it \
+                 reaches whichever answered last, or a device is answering for an \
+                 address it does not own (ARP spoofing) to intercept that traffic. \
+                 Confirm which MAC belongs to the device at this address; redundant \
+                 controllers that move a shared address on failover show the same \
+                 pattern.",
+                ip,
+                binding.mac_addresses.len()
+            ),
+            vec![ip.clone()],
+            evidence,
+            None,
+        ));
+    }
+
+    (anomalies, findings)
+}
+
 fn is_ot_protocol_name(name: &str) -> bool {
     matches!(
         name,
@@ -268,6 +327,15 @@ fn is_ot_protocol_name(name: &str) -> bool {
             | "foundation_fieldbus"
             | "ge_srtp"
             | "wonderware_suitelink"
+            | "fins"
+            | "slmp"
+            | "codesys"
+            | "niagara_fox"
+            | "crimson"
+            | "vnet_ip"
+            | "tristation"
+            | "ads"
+            | "opc_da"
     )
 }
 
@@ -421,4 +489,24 @@ mod tests {
             "Public IT-only IP should not be flagged"
         );
     }
+
+    #[test]
+    fn test_duplicate_ip_from_arp() {
+        let mut input = AnalysisInput::default();
+        input.arp.push(ArpSnapshot {
+            ip_address: "10.0.0.5".to_string(),
+            mac_addresses: vec!["00:1b:1b:12:34:56".to_string()],
+            gratuitous_count: 1,
+        });
+        assert!(detect_duplicate_ips(&input).0.is_empty());
+
+        input.arp[0]
+            .mac_addresses
+            .push("00:0c:29:aa:bb:cc".to_string());
+        let (anomalies, findings) = detect_duplicate_ips(&input);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].anomaly_type, AnomalyType::DuplicateIp);
+        assert!(anomalies[0].evidence.contains("00:0c:29:aa:bb:cc"));
+        assert_eq!(findings[0].affected_assets, vec!["10.0.0.5".to_string()]);
+    }
 }
@@ -10,17 +10,27 @@
 //! | T0855 | Modbus broadcast/mass writes (FC 5/6/15/16 to unit 0/255) | Critical |
 //! | T0814 | Modbus FC 8 diagnostics from non-engineering workstation | High |
 //! | T0856 | DNP3 unsolicited response to unknown master | Medium |
+//! | T0848 | DNP3 master address claimed by more than one IP | High / Medium |
 //! | T0846 | Unknown device polling PLCs (new source targeting OT ports) | High |
+//! | T0830 | More than one DHCP server answering clients (rogue DHCP) | High / Medium |
 //! | T0886 | Cross-Purdue zone communication (L1 <-> L4) | Medium |
+//! | T0843 | CODESYS application download / online change | Critical / High |
+//! | T0858 | CODESYS application start/stop/reset | High |
+//! | T0843 / T0845 | Red Lion Crimson configuration download / upload | High / Medium |
+//! | T0843 / T0858 / T0845 | Triconex TriStation download / run-state change / upload | Critical / Critical / Medium |
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::{AnalysisInput, CaptureContext, Finding, FindingType, Severity};
+use crate::{
+    AnalysisInput, CaptureContext, DhcpSnapshot, Dnp3BindingSnapshot, Finding, FindingType,
+    Severity,
+};
 
 /// Well-known OT server ports used to identify PLCs/RTUs.
 const OT_SERVER_PORTS: &[u16] = &[
-    102, 502, 1089, 1090, 1091, 2222, 2404, 4840, 5007, 5094, 18245, 18246, 20000, 34962, 34963,
-    34964, 44818, 47808,
+    102, 502, 789, 1089, 1090, 1091, 1200, 1217, 1502, 1911, 2222, 2404, 2455, 4840, 4911, 5006,
+    5007, 5094, 9600, 11740, 18245, 18246, 20000, 20171, 20172, 34962, 34963, 34964, 44818, 47808,
+    48898,
 ];
 
 /// Modbus write function codes.
@@ -36,11 +46,16 @@ pub fn detect_attack_techniques(input: &AnalysisInput, ctx: &CaptureContext) ->
     findings.extend(detect_t0855_unauthorized_writes(input));
     findings.extend(detect_t0814_diagnostic_dos(input));
     findings.extend(detect_t0856_dnp3_unsolicited(input));
+    findings.extend(detect_t0848_dnp3_master_impersonation(input));
     findings.extend(detect_t0846_remote_discovery(input));
+    findings.extend(detect_t0830_rogue_dhcp(input));
     findings.extend(detect_enip_attacks(input));
     findings.extend(detect_s7_attacks(input));
     findings.extend(detect_bacnet_attacks(input));
     findings.extend(detect_iec104_attacks(input));
+    findings.extend(detect_codesys_attacks(input));
+    findings.extend(detect_crimson_attacks(input));
+    findings.extend(detect_tristation_attacks(input));
     findings.extend(detect_flat_network(input));
     findings.extend(detect_cleartext_ot(input));
     findings.extend(detect_internet_exposed_ot(input));
@@ -292,6 +307,200 @@ fn detect_t0856_dnp3_unsolicited(input: &AnalysisInput) -> Vec<Finding> {
     findings
 }
 
+/// T0848 — Rogue Master (DNP3 link address conflicts)
+///
+/// Outstations accept commands from whoever sends frames with the master's
+/// link address. When one (master, outstation) address pair is used from
+/// more than one IP, either a second station is impersonating the master
+/// or the master has moved. Overlapping use is rated High; a new IP taking
+/// over an address after the first went quiet is Medium, since hot-standby
+/// failover looks the same on the wire.
+fn detect_t0848_dnp3_master_impersonation(input: &AnalysisInput) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    // (master address, outstation address) → every IP that used it
+    let mut claims: BTreeMap<(u16, u16), Vec<(&String, &Dnp3BindingSnapshot)>> = BTreeMap::new();
+    for (ip, dp) in &input.deep_parse {
+        if let Some(dnp3) = &dp.dnp3 {
+            for binding in &dnp3.master_bindings {
+                claims
+                    .entry((binding.master_address, binding.outstation_address))
+                    .or_default()
+                    .push((ip, binding));
+            }
+        }
+    }
+
+    for ((master, outstation), mut claimants) in claims {
+        if claimants.len() < 2 {
+            continue;
+        }
+        claimants.sort_by(|a, b| a.1.first_seen.cmp(&b.1.first_seen).then(a.0.cmp(b.0)));
+
+        // Any claimant starting before an earlier one stopped means the
+        // address was live from two IPs at once
+        let mut latest_end = claimants[0].1.last_seen.as_str();
+        let mut concurrent = false;
+        for (_, binding) in &claimants[1..] {
+            if binding.first_seen.as_str() <= latest_end {
+                concurrent = true;
+            }
+            latest_end = latest_end.max(binding.last_seen.as_str());
+        }
+
+        let established = claimants[0].0;
+        let newcomers: Vec<&str> = claimants[1..].iter().map(|(ip, _)| ip.as_str()).collect();
+        let mut affected: Vec<String> = claimants.iter().map(|(ip, _)| (*ip).clone()).collect();
+        for (_, binding) in &claimants {
+            if !affected.contains(&binding.outstation_ip) {
+                affected.push(binding.outstation_ip.clone());
+            }
+        }
+        let evidence = claimants
+            .iter()
+            .map(|(ip, b)| {
+                format!(
+                    "{} → {} ({} to {})",
+                    ip, b.outstation_ip, b.first_seen, b.last_seen
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let (severity, title, description) = if concurrent {
+            (
+                Severity::High,
+                format!(
+                    "DNP3 master address {} used by multiple IPs at once",
+                    master
+                ),
+                "Frames carrying the same DNP3 master link address to the same \
+                 outstation were sent from more than one IP during the same \
+                 period. The outstation cannot tell them apart, so a second \
+                 station can issue controls as the master."
+                    .to_string(),
+            )
+        } else {
+            (
+                Severity::Medium,
+                format!(
+                    "DNP3 master address {} taken over by {}",
+                    master,
+                    newcomers.join(", ")
+                ),
+                "A new IP began using a DNP3 master link address previously used \
+                 by another station. Confirm this is a planned master failover or \
+                 replacement and not a rogue master."
+                    .to_string(),
+            )
+        };
+
+        findings.push(Finding::new(
+            FindingType::AttackTechnique,
+            severity,
+            title,
+            description,
+            affected,
+            format!(
+                "Master address {} to outstation address {} first used by {}, \
+                 also used by {}: {}",
+                master,
+                outstation,
+                established,
+                newcomers.join(", "),
+                evidence
+            ),
+            Some("T0848".to_string()),
+        ));
+    }
+
+    findings
+}
+
+/// T0830 — Adversary-in-the-Middle (rogue DHCP server)
+///
+/// A second DHCP server can hand clients its own gateway and DNS and
+/// sit in the middle of their traffic. When several servers reply, the
+/// one serving the most clients is taken as the established server and
+/// each other one is flagged: High when it answered the same clients as
+/// the established server (the two compete for every lease), Medium when
+/// it serves a separate set, which may be a legitimate second scope.
+fn detect_t0830_rogue_dhcp(input: &AnalysisInput) -> Vec<Finding> {
+    let mut servers: Vec<(&String, &DhcpSnapshot)> = input
+        .deep_parse
+        .iter()
+        .filter_map(|(ip, dp)| dp.dhcp.as_ref().map(|d| (ip, d)))
+        .filter(|(_, d)| d.role == "server")
+        .collect();
+    if servers.len() < 2 {
+        return Vec::new();
+    }
+    servers.sort_by(|a, b| {
+        b.1.clients
+            .len()
+            .cmp(&a.1.clients.len())
+            .then(b.1.replies.cmp(&a.1.replies))
+            .then(a.0.cmp(b.0))
+    });
+
+    let (established_ip, established) = servers[0];
+    servers[1..]
+        .iter()
+        .map(|(ip, dhcp)| {
+            let shared: Vec<&String> = dhcp
+                .clients
+                .iter()
+                .filter(|mac| established.clients.contains(mac))
+                .collect();
+            let (severity, title, description) = if shared.is_empty() {
+                (
+                    Severity::Medium,
+                    format!("Additional DHCP server {}", ip),
+                    "A second DHCP server is handing out leases on the captured \
+                     network. Confirm it is an authorized server or relay for a \
+                     separate scope; a rogue server can point clients at an \
+                     attacker-controlled gateway or DNS server."
+                        .to_string(),
+                )
+            } else {
+                (
+                    Severity::High,
+                    format!("Rogue DHCP server {} competing with {}", ip, established_ip),
+                    "Two DHCP servers answered the same clients. Whichever reply \
+                     arrives first wins the lease, so clients can silently receive \
+                     the rogue server's gateway and DNS settings."
+                        .to_string(),
+                )
+            };
+            let mut evidence = format!(
+                "{} sent {} offers/ACKs to {} clients; established server {} served {} clients",
+                ip,
+                dhcp.replies,
+                dhcp.clients.len(),
+                established_ip,
+                established.clients.len()
+            );
+            if !shared.is_empty() {
+                let macs: Vec<&str> = shared.iter().take(5).map(|m| m.as_str()).collect();
+                evidence.push_str(&format!(
+                    "; both answered {} clients ({})",
+                    shared.len(),
+                    macs.join(", ")
+                ));
+            }
+            Finding::new(
+                FindingType::AttackTechnique,
+                severity,
+                title,
+                description,
+                vec![(*ip).clone(), established_ip.clone()],
+                evidence,
+                Some("T0830".to_string()),
+            )
+        })
+        .collect()
+}
+
 /// T0846 — Remote System Discovery
 ///
 /// Detects unknown/IT devices polling OT devices on well-known
@@ -307,7 +516,13 @@ fn detect_t0846_remote_discovery(input: &AnalysisInput) -> Vec<Finding> {
         .filter(|a| {
             matches!(
                 a.device_type.as_str(),
-                "plc" | "rtu" | "hmi" | "historian" | "engineering_workstation" | "scada_server"
+                "plc"
+                    | "rtu"
+                    | "ied"
+                    | "hmi"
+                    | "historian"
+                    | "engineering_workstation"
+                    | "scada_server"
             )
         })
         .map(|a| a.ip_address.as_str())
@@ -545,6 +760,215 @@ fn detect_s7_attacks(input: &AnalysisInput) -> Vec<Finding> {
     findings
 }
 
+/// CODESYS engineering activity: T0843 (download, online change), T0858
+/// (application start/stop/reset), plus an informational runtime login.
+fn detect_codesys_attacks(input: &AnalysisInput) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (ip, dp) in &input.deep_parse {
+        let codesys = match &dp.codesys {
+            Some(c) => c,
+            None => continue,
+        };
+
+        if codesys.has_download {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::Critical,
+                format!("CODESYS application download from {}", ip),
+                "A CODESYS application download was detected. The download replaces \
+                 the control application on the runtime; outside a planned change \
+                 it may indicate an attacker pushing modified logic."
+                    .to_string(),
+                vec![ip.clone()],
+                format!("Source {} sent a CODESYS Application Download request", ip),
+                Some("T0843".to_string()),
+            ));
+        }
+
+        if codesys.has_online_change {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::High,
+                format!("CODESYS online change from {}", ip),
+                "A CODESYS online change was detected. Online changes patch the \
+                 running application without stopping the controller, so modified \
+                 logic takes effect immediately."
+                    .to_string(),
+                vec![ip.clone()],
+                format!(
+                    "Source {} sent a CODESYS Application Online Change request",
+                    ip
+                ),
+                Some("T0843".to_string()),
+            ));
+        }
+
+        if codesys.has_control_commands {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::High,
+                format!("CODESYS application start/stop from {}", ip),
+                "A CODESYS application start, stop or reset was detected. Changing the \
+                 application's run state interrupts or restarts the controlled process."
+                    .to_string(),
+                vec![ip.clone()],
+                format!(
+                    "Source {} sent a CODESYS Application Start/Stop/Reset request",
+                    ip
+                ),
+                Some("T0858".to_string()),
+            ));
+        }
+
+        if codesys.has_login && codesys.role == "client" {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::Info,
+                format!("CODESYS runtime login from {}", ip),
+                "A CODESYS programming session logged into a controller runtime. \
+                 Sessions should line up with authorized engineering work."
+                    .to_string(),
+                vec![ip.clone()],
+                format!("Source {} logged into a CODESYS runtime", ip),
+                None,
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Red Lion Crimson v3 configuration transfers: T0843 (download to the
+/// device) and T0845 (upload from it). The transfer opcodes are not public,
+/// so these are inferred from bulk data volume and rated one step lower
+/// than the CODESYS equivalents.
+fn detect_crimson_attacks(input: &AnalysisInput) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (ip, dp) in &input.deep_parse {
+        let crimson = match &dp.crimson {
+            Some(c) if c.role == "client" => c,
+            _ => continue,
+        };
+        let mut affected = vec![ip.clone()];
+        affected.extend(crimson.transfer_peers.iter().cloned());
+        let peers = crimson.transfer_peers.join(", ");
+
+        if crimson.has_download {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::High,
+                format!("Red Lion Crimson configuration download from {}", ip),
+                "A bulk Crimson v3 transfer to a Red Lion device was detected. A \
+                 download replaces the device's configuration database, including \
+                 its tags, screens and protocol drivers; outside a planned change it \
+                 may indicate modified logic being pushed to the HMI or RTU."
+                    .to_string(),
+                affected.clone(),
+                format!(
+                    "Source {} sent bulk Crimson v3 data to {} (TCP 789)",
+                    ip, peers
+                ),
+                Some("T0843".to_string()),
+            ));
+        }
+
+        if crimson.has_upload {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::Medium,
+                format!("Red Lion Crimson configuration upload to {}", ip),
+                "A bulk Crimson v3 transfer from a Red Lion device was detected. An \
+                 upload copies the configuration database off the device, which \
+                 exposes its tag map and communication settings."
+                    .to_string(),
+                affected,
+                format!(
+                    "Source {} received bulk Crimson v3 data from {} (TCP 789)",
+                    ip, peers
+                ),
+                Some("T0845".to_string()),
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Triconex TriStation engineering activity against a safety controller.
+/// Any program change or run-state change on an SIS removes a layer of
+/// protection from the process, so these rank above their PLC equivalents.
+fn detect_tristation_attacks(input: &AnalysisInput) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (ip, dp) in &input.deep_parse {
+        let tristation = match &dp.tristation {
+            Some(t) if t.role == "client" => t,
+            _ => continue,
+        };
+        let mut affected = vec![ip.clone()];
+        affected.extend(tristation.peers.iter().cloned());
+        let controllers = tristation.peers.join(", ");
+
+        if tristation.has_download {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::Critical,
+                format!("Triconex safety program download from {}", ip),
+                "A TriStation program or configuration download to a Triconex safety \
+                 controller was detected. Changing SIS logic outside a managed \
+                 change is how TRITON/TRISIS planted its payload; confirm the \
+                 download against an approved safety change."
+                    .to_string(),
+                affected.clone(),
+                format!(
+                    "Source {} sent TriStation download commands to {} (UDP 1502)",
+                    ip, controllers
+                ),
+                Some("T0843".to_string()),
+            ));
+        }
+
+        if tristation.has_run_state_change {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::Critical,
+                format!("Triconex safety program run/halt from {}", ip),
+                "A TriStation Run Program or Halt Program command was detected. \
+                 Halting the safety program leaves the process without its \
+                 safety instrumented functions."
+                    .to_string(),
+                affected.clone(),
+                format!(
+                    "Source {} sent TriStation run-state commands to {} (UDP 1502)",
+                    ip, controllers
+                ),
+                Some("T0858".to_string()),
+            ));
+        }
+
+        if tristation.has_upload {
+            findings.push(Finding::new(
+                FindingType::AttackTechnique,
+                Severity::Medium,
+                format!("Triconex safety program upload to {}", ip),
+                "A TriStation program upload was detected. Uploading the safety \
+                 program reveals trip setpoints and logic to whoever holds the copy."
+                    .to_string(),
+                affected,
+                format!(
+                    "Source {} uploaded the program from {} (UDP 1502)",
+                    ip, controllers
+                ),
+                Some("T0845".to_string()),
+            ));
+        }
+    }
+
+    findings
+}
+
 /// IEC 60870-5-104 ATT&CK detections: T0855 (control commands), T0816 (reset process), T0814 (interrogation flood).
 fn detect_iec104_attacks(input: &AnalysisInput) -> Vec<Finding> {
     let mut findings = Vec::new();
@@ -799,6 +1223,9 @@ fn detect_cleartext_ot(input: &AnalysisInput) -> Vec<Finding> {
         1883,  // MQTT (unencrypted)
         5094,  // HART-IP
         18245, 18246, // GE SRTP
+        9600,  // OMRON FINS
+        1200, 2455,  // CODESYS V2
+        48898, // Beckhoff ADS
     ];
 
     let mut findings = Vec::new();
@@ -807,7 +1234,8 @@ fn detect_cleartext_ot(input: &AnalysisInput) -> Vec<Finding> {
     let mut total_ot: u64 = 0;
 
     for conn in &input.connections {
-        if CLEARTEXT_OT_PORTS.contains(&conn.dst_port) {
+        // SLMP shares port 5007 with SuiteLink, so it is matched by protocol
+        if CLEARTEXT_OT_PORTS.contains(&conn.dst_port) || conn.protocol == "Slmp" {
             *cleartext_by_proto.entry(conn.protocol.clone()).or_insert(0) += conn.packet_count;
             total_cleartext += conn.packet_count;
             total_ot += conn.packet_count;
@@ -1121,6 +1549,7 @@ mod tests {
                         remote_role: "master".to_string(),
                         packet_count: 5,
                     }],
+                    master_bindings: vec![],
                 }),
                 ..Default::default()
             },
@@ -1131,6 +1560,148 @@ mod tests {
         assert_eq!(findings[0].technique_id, Some("T0856".to_string()));
     }
 
+    fn dnp3_master(bindings: &[(u16, u16, &str, &str)]) -> DeepParseSnapshot {
+        DeepParseSnapshot {
+            dnp3: Some(Dnp3Snapshot {
+                role: "master".to_string(),
+                has_unsolicited: false,
+                function_codes: vec![],
+                relationships: vec![],
+                master_bindings: bindings
+                    .iter()
+                    .map(|&(master, outstation, first, last)| Dnp3BindingSnapshot {
+                        master_address: master,
+                        outstation_address: outstation,
+                        outstation_ip: "10.0.0.10".to_string(),
+                        first_seen: first.to_string(),
+                        last_seen: last.to_string(),
+                    })
+                    .collect(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_t0848_concurrent_master_address() {
+        let mut input = make_input();
+        input.deep_parse.insert(
+            "10.0.0.1".to_string(),
+            dnp3_master(&[(
+                3,
+                10,
+                "2024-05-01T08:00:00+00:00",
+                "2024-05-01T09:00:00+00:00",
+            )]),
+        );
+        input.deep_parse.insert(
+            "10.0.0.66".to_string(),
+            dnp3_master(&[(
+                3,
+                10,
+                "2024-05-01T08:30:00+00:00",
+                "2024-05-01T08:31:00+00:00",
+            )]),
+        );
+
+        let findings = detect_t0848_dnp3_master_impersonation(&input);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].technique_id, Some("T0848".to_string()));
+        assert!(findings[0]
+            .affected_assets
+            .contains(&"10.0.0.66".to_string()));
+        assert!(findings[0]
+            .affected_assets
+            .contains(&"10.0.0.10".to_string()));
+    }
+
+    #[test]
+    fn test_t0848_takeover_and_distinct_links() {
+        let mut input = make_input();
+        // Master 3 moves to a new IP; master 4 talks to a different
+        // outstation address and must not conflict
+        input.deep_parse.insert(
+            "10.0.0.1".to_string(),
+            dnp3_master(&[(
+                3,
+                10,
+                "2024-05-01T08:00:00+00:00",
+                "2024-05-01T09:00:00+00:00",
+            )]),
+        );
+        input.deep_parse.insert(
+            "10.0.0.2".to_string(),
+            dnp3_master(&[
+                (
+                    3,
+                    10,
+                    "2024-05-01T10:00:00+00:00",
+                    "2024-05-01T11:00:00+00:00",
+                ),
+                (
+                    4,
+                    11,
+                    "2024-05-01T08:00:00+00:00",
+                    "2024-05-01T11:00:00+00:00",
+                ),
+            ]),
+        );
+
+        let findings = detect_t0848_dnp3_master_impersonation(&input);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert!(findings[0].title.contains("10.0.0.2"));
+    }
+
+    fn dhcp_server(clients: &[&str]) -> DeepParseSnapshot {
+        DeepParseSnapshot {
+            dhcp: Some(DhcpSnapshot {
+                role: "server".to_string(),
+                clients: clients.iter().map(|c| c.to_string()).collect(),
+                replies: clients.len() as u64 * 2,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_t0830_rogue_dhcp_competing_server() {
+        let mut input = make_input();
+        input.deep_parse.insert(
+            "10.0.0.1".to_string(),
+            dhcp_server(&[
+                "00:00:00:00:00:01",
+                "00:00:00:00:00:02",
+                "00:00:00:00:00:03",
+            ]),
+        );
+        input
+            .deep_parse
+            .insert("10.0.0.66".to_string(), dhcp_server(&["00:00:00:00:00:02"]));
+        input
+            .deep_parse
+            .insert("10.0.5.1".to_string(), dhcp_server(&["00:00:00:00:05:01"]));
+
+        let mut findings = detect_t0830_rogue_dhcp(&input);
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(findings[0].title.contains("10.0.0.66"));
+        assert_eq!(findings[0].technique_id, Some("T0830".to_string()));
+        assert_eq!(findings[1].severity, Severity::Medium);
+        assert!(findings[1].title.contains("10.0.5.1"));
+    }
+
+    #[test]
+    fn test_t0830_single_dhcp_server_ok() {
+        let mut input = make_input();
+        input
+            .deep_parse
+            .insert("10.0.0.1".to_string(), dhcp_server(&["00:00:00:00:00:01"]));
+        assert!(detect_t0830_rogue_dhcp(&input).is_empty());
+    }
+
     #[test]
     fn test_t0846_unknown_device_scanning() {
         let mut input = make_input();
@@ -1312,6 +1883,129 @@ mod tests {
         assert_eq!(findings[0].technique_id, Some("T0816".to_string()));
     }
 
+    #[test]
+    fn test_t0843_codesys_download_and_online_change() {
+        let mut input = make_input();
+        input.deep_parse.insert(
+            "10.0.0.25".to_string(),
+            DeepParseSnapshot {
+                codesys: Some(CodesysSnapshot {
+                    role: "client".to_string(),
+                    has_login: true,
+                    has_download: true,
+                    has_online_change: true,
+                    has_control_commands: false,
+                }),
+                ..Default::default()
+            },
+        );
+
+        let findings = detect_codesys_attacks(&input);
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[0].severity, Severity::Critical);
+        assert_eq!(findings[0].technique_id, Some("T0843".to_string()));
+        assert_eq!(findings[1].severity, Severity::High);
+        assert_eq!(findings[2].severity, Severity::Info);
+        assert_eq!(findings[2].technique_id, None);
+    }
+
+    #[test]
+    fn test_t0858_codesys_stop() {
+        let mut input = make_input();
+        input.deep_parse.insert(
+            "10.0.0.26".to_string(),
+            DeepParseSnapshot {
+                codesys: Some(CodesysSnapshot {
+                    role: "client".to_string(),
+                    has_login: false,
+                    has_download: false,
+                    has_online_change: false,
+                    has_control_commands: true,
+                }),
+                ..Default::default()
+            },
+        );
+
+        let findings = detect_codesys_attacks(&input);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].technique_id, Some("T0858".to_string()));
+    }
+
+    #[test]
+    fn test_crimson_download_and_upload() {
+        let mut input = make_input();
+        input.deep_parse.insert(
+            "10.0.0.27".to_string(),
+            DeepParseSnapshot {
+                crimson: Some(CrimsonSnapshot {
+                    role: "client".to_string(),
+                    has_download: true,
+                    has_upload: true,
+                    transfer_peers: vec!["10.0.0.80".to_string()],
+                }),
+                ..Default::default()
+            },
+        );
+        // The device side of the transfer does not produce its own finding
+        input.deep_parse.insert(
+            "10.0.0.80".to_string(),
+            DeepParseSnapshot {
+                crimson: Some(CrimsonSnapshot {
+                    role: "server".to_string(),
+                    has_download: false,
+                    has_upload: false,
+                    transfer_peers: vec!["10.0.0.27".to_string()],
+                }),
+                ..Default::default()
+            },
+        );
+
+        let findings = detect_crimson_attacks(&input);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].technique_id, Some("T0843".to_string()));
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].affected_assets, vec!["10.0.0.27", "10.0.0.80"]);
+        assert_eq!(findings[1].technique_id, Some("T0845".to_string()));
+    }
+
+    #[test]
+    fn test_tristation_download_and_halt() {
+        let mut input = make_input();
+        input.deep_parse.insert(
+            "10.0.0.31".to_string(),
+            DeepParseSnapshot {
+                tristation: Some(TriStationSnapshot {
+                    role: "client".to_string(),
+                    has_download: true,
+                    has_upload: false,
+                    has_run_state_change: true,
+                    peers: vec!["10.0.0.90".to_string()],
+                }),
+                ..Default::default()
+            },
+        );
+        input.deep_parse.insert(
+            "10.0.0.90".to_string(),
+            DeepParseSnapshot {
+                tristation: Some(TriStationSnapshot {
+                    role: "server".to_string(),
+                    has_download: false,
+                    has_upload: false,
+                    has_run_state_change: false,
+                    peers: vec!["10.0.0.31".to_string()],
+                }),
+                ..Default::default()
+            },
+        );
+
+        let findings = detect_tristation_attacks(&input);
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|f| f.severity == Severity::Critical));
+        assert_eq!(findings[0].technique_id, Some("T0843".to_string()));
+        assert_eq!(findings[0].affected_assets, vec!["10.0.0.31", "10.0.0.90"]);
+        assert_eq!(findings[1].technique_id, Some("T0858".to_string()));
+    }
+
     #[test]
     fn test_t0855_bacnet_write_output() {
         let mut input = make_input();
@@ -44,6 +44,12 @@ pub enum PatternAnomalyType {
     HighFrequency,
     /// Maximum inter-packet gap is 10× the average (bursty traffic).
     BurstTraffic,
+    /// Requests in a TCP session that never received a response.
+    UnansweredRequests,
+    /// TCP session whose handshake or teardown never completed.
+    HalfOpenSession,
+    /// TCP session that carried more than one ICS protocol.
+    ProtocolSwitch,
 }
 
 /// A detected anomaly in a connection's communication pattern.
@@ -256,7 +262,7 @@ impl PatternAnalyzer {
         }
 
         // Sort descending by packet count.
-        result.sort_by(|a, b| b.packet_count.cmp(&a.packet_count));
+        result.sort_by_key(|p| std::cmp::Reverse(p.packet_count));
         result
     }
 
@@ -369,10 +375,20 @@ fn is_ot_protocol(protocol: &str) -> bool {
             | "Bacnet"
             | "OpcUa"
             | "Iec104"
+            | "Iec61850Mms"
             | "ProfinetDcp"
             | "HartIp"
             | "GeSrtp"
             | "WonderwareSuitelink"
+            | "Fins"
+            | "Slmp"
+            | "Codesys"
+            | "NiagaraFox"
+            | "Crimson"
+            | "VnetIp"
+            | "TriStation"
+            | "Ads"
+            | "OpcDa"
             | "FfHse"
     )
 }
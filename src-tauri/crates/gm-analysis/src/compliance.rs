@@ -627,10 +627,20 @@ fn is_ot_protocol(proto: &str) -> bool {
             | "Bacnet"
             | "OpcUa"
             | "Iec104"
+            | "Iec61850Mms"
             | "ProfinetDcp"
             | "HartIp"
             | "GeSrtp"
             | "WonderwareSuitelink"
+            | "Fins"
+            | "Slmp"
+            | "Codesys"
+            | "NiagaraFox"
+            | "Crimson"
+            | "VnetIp"
+            | "TriStation"
+            | "Ads"
+            | "OpcDa"
             | "FfHse"
     )
 }
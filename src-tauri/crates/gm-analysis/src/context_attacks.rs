@@ -19,6 +19,7 @@
 //! | T0802 | Automated Collection (many OT targets polled) | Medium |
 //! | T0861 | Point and Tag Identification (wide Modbus unit-ID scan) | Medium |
 //! | T0840 | Network Connection Enumeration (OT port sweep) | High |
+//! | T0846 | Remote System Discovery (ICMP ping sweep) | High / Medium |
 //! | T0803/T0811 | Block Command / Modify I/O Image (PLC receiving no commands) | Medium |
 //! | T0804 | Block Reporting Message (DNP3 outstation not reporting) | Medium |
 //! | T0881 | Service Stop (OT device with very low traffic vs peers) | High |
@@ -35,8 +36,9 @@ use crate::{AnalysisInput, Finding, FindingType, Severity};
 
 /// Well-known OT server ports (mirrors the constant in `attack.rs`).
 const OT_PORTS: &[u16] = &[
-    102, 502, 1089, 1090, 1091, 2222, 2404, 4840, 5007, 5094, 18245, 18246, 20000, 34962, 34963,
-    34964, 44818, 47808,
+    102, 502, 789, 1089, 1090, 1091, 1200, 1217, 1502, 1911, 2222, 2404, 2455, 4840, 4911, 5006,
+    5007, 5094, 9600, 11740, 18245, 18246, 20000, 20171, 20172, 34962, 34963, 34964, 44818, 47808,
+    48898,
 ];
 
 /// Remote access / management ports that should not appear on OT segments.
@@ -77,6 +79,10 @@ pub struct CaptureContext {
     pub per_source_write_targets: HashMap<String, HashSet<String>>,
     /// Per-source: all destination ports contacted.
     pub per_source_dst_ports: HashMap<String, HashSet<u16>>,
+    /// Per-source: distinct IPs sent an ICMP echo request.
+    pub per_source_echo_targets: HashMap<String, HashSet<String>>,
+    /// Per-source: distinct echo targets that replied.
+    pub per_source_echo_responders: HashMap<String, HashSet<String>>,
     /// Per (src, dst): total write-class packet / command count.
     pub per_connection_write_rate: HashMap<(String, String), u64>,
     /// IPs confirmed as running OT protocols (PLCs, RTUs, HMIs, historians …).
@@ -101,6 +107,7 @@ pub fn detect_context_attacks(input: &AnalysisInput, ctx: &CaptureContext) -> Ve
     findings.extend(detect_t0802_automated_collection(input, ctx));
     findings.extend(detect_t0861_point_tag_identification(input));
     findings.extend(detect_t0840_network_connection_enumeration(input, ctx));
+    findings.extend(detect_t0846_ping_sweep(input, ctx));
     findings.extend(detect_t0803_block_command_reporting(input));
     findings.extend(detect_t0804_block_reporting_message(input));
     findings.extend(detect_t0881_service_stop(input));
@@ -122,6 +129,7 @@ fn is_ot_device_type(device_type: &str) -> bool {
         device_type,
         "plc"
             | "rtu"
+            | "ied"
             | "hmi"
             | "historian"
             | "engineering_workstation"
@@ -145,9 +153,18 @@ fn canonical_ot_ports(protocol: &str) -> &'static [u16] {
         "Bacnet" => &[47808],
         "OpcUa" => &[4840],
         "Iec104" => &[2404],
+        "Iec61850Mms" => &[102],
         "HartIp" => &[5094],
         "GeSrtp" => &[18245, 18246],
         "WonderwareSuitelink" => &[5007],
+        "Fins" => &[9600],
+        "Slmp" => &[5006, 5007],
+        "Codesys" => &[1200, 1217, 2455, 11740],
+        "NiagaraFox" => &[1911, 4911],
+        "Crimson" => &[789],
+        "VnetIp" => &[20171, 20172],
+        "TriStation" => &[1502],
+        "Ads" => &[48898],
         "FfHse" => &[1089, 1090, 1091],
         "ProfinetDcp" => &[34962, 34963, 34964],
         "Mqtt" => &[1883, 8883],
@@ -179,10 +196,20 @@ fn is_ot_protocol_name(protocol: &str) -> bool {
             | "Bacnet"
             | "OpcUa"
             | "Iec104"
+            | "Iec61850Mms"
             | "ProfinetDcp"
             | "HartIp"
             | "GeSrtp"
             | "WonderwareSuitelink"
+            | "Fins"
+            | "Slmp"
+            | "Codesys"
+            | "NiagaraFox"
+            | "Crimson"
+            | "VnetIp"
+            | "TriStation"
+            | "Ads"
+            | "OpcDa"
             | "FfHse"
             | "Mqtt"
             | "Snmp"
@@ -373,6 +400,15 @@ fn detect_t0849_masquerading(input: &AnalysisInput) -> Vec<Finding> {
         (18245, "GeSrtp"),
         (18246, "GeSrtp"),
         (5007, "WonderwareSuitelink"),
+        (9600, "Fins"),
+        (5006, "Slmp"),
+        (2455, "Codesys"),
+        (11740, "Codesys"),
+        (1911, "NiagaraFox"),
+        (789, "Crimson"),
+        (20171, "VnetIp"),
+        (1502, "TriStation"),
+        (48898, "Ads"),
     ];
 
     // Protocols that are clearly NOT OT (and would constitute masquerading).
@@ -755,6 +791,57 @@ fn detect_t0840_network_connection_enumeration(
     findings
 }
 
+/// T0846 — Remote System Discovery (ICMP ping sweep)
+///
+/// Detects a single source sending echo requests to many distinct
+/// addresses. Nothing on a control network needs to ping a whole subnet;
+/// monitoring systems that ping a fixed device list are the usual benign
+/// match, so a sweep that reaches OT devices is rated higher.
+fn detect_t0846_ping_sweep(input: &AnalysisInput, ctx: &CaptureContext) -> Vec<Finding> {
+    const HOST_THRESHOLD: usize = 10;
+    let ot_ips = effective_ot_ips(input, ctx);
+    let mut findings = Vec::new();
+
+    for (src, targets) in &ctx.per_source_echo_targets {
+        if targets.len() < HOST_THRESHOLD {
+            continue;
+        }
+        let ot_targets = targets
+            .iter()
+            .filter(|t| ot_ips.contains(t.as_str()))
+            .count();
+        let responders = ctx
+            .per_source_echo_responders
+            .get(src)
+            .map_or(0, HashSet::len);
+        let severity = if ot_targets > 0 {
+            Severity::High
+        } else {
+            Severity::Medium
+        };
+        findings.push(Finding::new(
+            FindingType::AttackTechnique,
+            severity,
+            format!("ICMP ping sweep by {} ({} hosts)", src, targets.len()),
+            "A single source sent ICMP echo requests to many different addresses. \
+             This is characteristic of host discovery mapping which systems on the \
+             network are live."
+                .to_string(),
+            vec![src.clone()],
+            format!(
+                "{} pinged {} distinct addresses ({} OT devices); {} replied",
+                src,
+                targets.len(),
+                ot_targets,
+                responders
+            ),
+            Some("T0846".to_string()),
+        ));
+    }
+
+    findings
+}
+
 /// T0803 / T0811 — Block Command Message / Modify I/O Image
 ///
 /// Detects PLC/RTU devices that have network activity but receive **zero**
@@ -766,7 +853,12 @@ fn detect_t0803_block_command_reporting(input: &AnalysisInput) -> Vec<Finding> {
     let field_device_ips: HashSet<&str> = input
         .assets
         .iter()
-        .filter(|a| matches!(a.device_type.as_str(), "plc" | "rtu" | "field_device"))
+        .filter(|a| {
+            matches!(
+                a.device_type.as_str(),
+                "plc" | "rtu" | "ied" | "field_device"
+            )
+        })
         .map(|a| a.ip_address.as_str())
         .collect();
 
@@ -1168,7 +1260,12 @@ fn detect_t0800_firmware_update_mode(input: &AnalysisInput) -> Vec<Finding> {
     let plc_rtu_ips: HashSet<&str> = input
         .assets
         .iter()
-        .filter(|a| matches!(a.device_type.as_str(), "plc" | "rtu" | "field_device"))
+        .filter(|a| {
+            matches!(
+                a.device_type.as_str(),
+                "plc" | "rtu" | "ied" | "field_device"
+            )
+        })
         .map(|a| a.ip_address.as_str())
         .collect();
 
@@ -1364,9 +1461,11 @@ mod tests {
     // ── T0822 ──
     #[test]
     fn test_t0822_ot_device_initiates_rdp() {
-        let mut input = AnalysisInput::default();
-        input.assets = vec![asset("10.0.0.1", "plc", &["Modbus"])];
-        input.connections = vec![conn("10.0.0.1", "1.2.3.4", 3389, "Unknown", 5)];
+        let input = AnalysisInput {
+            assets: vec![asset("10.0.0.1", "plc", &["Modbus"])],
+            connections: vec![conn("10.0.0.1", "1.2.3.4", 3389, "Unknown", 5)],
+            ..Default::default()
+        };
         let ctx = CaptureContext::default();
         let findings = detect_t0822_external_remote_services(&input, &ctx);
         assert!(!findings.is_empty(), "RDP from OT PLC should be flagged");
@@ -1375,9 +1474,11 @@ mod tests {
 
     #[test]
     fn test_t0822_it_device_rdp_not_flagged() {
-        let mut input = AnalysisInput::default();
-        input.assets = vec![asset("10.0.0.200", "it_device", &[])];
-        input.connections = vec![conn("10.0.0.200", "1.2.3.4", 3389, "Unknown", 5)];
+        let input = AnalysisInput {
+            assets: vec![asset("10.0.0.200", "it_device", &[])],
+            connections: vec![conn("10.0.0.200", "1.2.3.4", 3389, "Unknown", 5)],
+            ..Default::default()
+        };
         let ctx = CaptureContext::default();
         let findings = detect_t0822_external_remote_services(&input, &ctx);
         assert!(
@@ -1389,12 +1490,14 @@ mod tests {
     // ── T0867 ──
     #[test]
     fn test_t0867_ftp_between_ot_devices() {
-        let mut input = AnalysisInput::default();
-        input.assets = vec![
-            asset("10.0.0.1", "plc", &["Modbus"]),
-            asset("10.0.0.2", "engineering_workstation", &["Modbus"]),
-        ];
-        input.connections = vec![conn("10.0.0.2", "10.0.0.1", 21, "Ftp", 20)];
+        let input = AnalysisInput {
+            assets: vec![
+                asset("10.0.0.1", "plc", &["Modbus"]),
+                asset("10.0.0.2", "engineering_workstation", &["Modbus"]),
+            ],
+            connections: vec![conn("10.0.0.2", "10.0.0.1", 21, "Ftp", 20)],
+            ..Default::default()
+        };
         let ctx = CaptureContext::default();
         let findings = detect_t0867_lateral_tool_transfer(&input, &ctx);
         assert!(
@@ -1406,9 +1509,11 @@ mod tests {
 
     #[test]
     fn test_t0867_tftp_to_plc_flagged() {
-        let mut input = AnalysisInput::default();
-        input.assets = vec![asset("10.0.0.5", "plc", &["Modbus"])];
-        input.connections = vec![conn("192.168.0.1", "10.0.0.5", 69, "Tftp", 3)];
+        let input = AnalysisInput {
+            assets: vec![asset("10.0.0.5", "plc", &["Modbus"])],
+            connections: vec![conn("192.168.0.1", "10.0.0.5", 69, "Tftp", 3)],
+            ..Default::default()
+        };
         let ctx = CaptureContext::default();
         let findings = detect_t0867_lateral_tool_transfer(&input, &ctx);
         assert!(!findings.is_empty());
@@ -1418,9 +1523,11 @@ mod tests {
     // ── T0885 ──
     #[test]
     fn test_t0885_modbus_on_wrong_port() {
-        let mut input = AnalysisInput::default();
-        // Modbus identified on port 503 (not 502)
-        input.connections = vec![conn("10.0.0.1", "10.0.0.2", 503, "Modbus", 100)];
+        let input = AnalysisInput {
+            // Modbus identified on port 503 (not 502)
+            connections: vec![conn("10.0.0.1", "10.0.0.2", 503, "Modbus", 100)],
+            ..Default::default()
+        };
         let findings = detect_t0885_commonly_used_port(&input);
         assert!(
             !findings.is_empty(),
@@ -1431,8 +1538,10 @@ mod tests {
 
     #[test]
     fn test_t0885_modbus_standard_port_ok() {
-        let mut input = AnalysisInput::default();
-        input.connections = vec![conn("10.0.0.1", "10.0.0.2", 502, "Modbus", 100)];
+        let input = AnalysisInput {
+            connections: vec![conn("10.0.0.1", "10.0.0.2", 502, "Modbus", 100)],
+            ..Default::default()
+        };
         let findings = detect_t0885_commonly_used_port(&input);
         assert!(findings.is_empty(), "Modbus on port 502 is normal");
     }
@@ -1440,9 +1549,11 @@ mod tests {
     // ── T0849 ──
     #[test]
     fn test_t0849_http_on_modbus_port() {
-        let mut input = AnalysisInput::default();
-        // HTTP traffic on port 502
-        input.connections = vec![conn("10.0.0.100", "10.0.0.1", 502, "Http", 10)];
+        let input = AnalysisInput {
+            // HTTP traffic on port 502
+            connections: vec![conn("10.0.0.100", "10.0.0.1", 502, "Http", 10)],
+            ..Default::default()
+        };
         let findings = detect_t0849_masquerading(&input);
         assert!(
             !findings.is_empty(),
@@ -1485,8 +1596,10 @@ mod tests {
 
     #[test]
     fn test_t0806_modbus_fallback() {
-        let mut input = AnalysisInput::default();
-        input.assets = vec![asset("10.0.0.5", "plc", &["Modbus"])];
+        let mut input = AnalysisInput {
+            assets: vec![asset("10.0.0.5", "plc", &["Modbus"])],
+            ..Default::default()
+        };
         input.deep_parse.insert(
             "10.0.0.10".to_string(),
             DeepParseSnapshot {
@@ -1581,19 +1694,53 @@ mod tests {
         assert_eq!(findings[0].technique_id, Some("T0840".to_string()));
     }
 
+    // ── T0846 ──
+    #[test]
+    fn test_t0846_ping_sweep() {
+        let input = AnalysisInput {
+            assets: vec![asset("10.0.0.3", "plc", &["Modbus"])],
+            ..Default::default()
+        };
+        let mut ctx = CaptureContext::default();
+        ctx.per_source_echo_targets.insert(
+            "10.0.0.200".to_string(),
+            (1..=12).map(|i| format!("10.0.0.{}", i)).collect(),
+        );
+        ctx.per_source_echo_responders.insert(
+            "10.0.0.200".to_string(),
+            ["10.0.0.1", "10.0.0.3"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        );
+        // A monitoring host pinging a handful of devices is not a sweep
+        ctx.per_source_echo_targets.insert(
+            "10.0.0.50".to_string(),
+            (1..=4).map(|i| format!("10.0.0.{}", i)).collect(),
+        );
+
+        let findings = detect_t0846_ping_sweep(&input, &ctx);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].technique_id, Some("T0846".to_string()));
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(findings[0].evidence.contains("(1 OT devices); 2 replied"));
+    }
+
     // ── T0803 ──
     #[test]
     fn test_t0803_plc_receives_no_commands() {
-        let mut input = AnalysisInput::default();
-        input.assets = vec![
-            asset("10.0.0.1", "plc", &["Modbus"]),   // field device
-            asset("10.0.0.100", "hmi", &["Modbus"]), // controller
-        ];
-        // Controller sends to port 502 but to a different PLC (not 10.0.0.1)
-        input.connections = vec![
-            conn("10.0.0.100", "10.0.0.2", 502, "Modbus", 100), // not to 10.0.0.1
-            conn("10.0.0.1", "10.0.0.100", 49152, "Modbus", 10), // PLC has some traffic
-        ];
+        let input = AnalysisInput {
+            assets: vec![
+                asset("10.0.0.1", "plc", &["Modbus"]),   // field device
+                asset("10.0.0.100", "hmi", &["Modbus"]), // controller
+            ],
+            // Controller sends to port 502 but to a different PLC (not 10.0.0.1)
+            connections: vec![
+                conn("10.0.0.100", "10.0.0.2", 502, "Modbus", 100), // not to 10.0.0.1
+                conn("10.0.0.1", "10.0.0.100", 49152, "Modbus", 10), // PLC has some traffic
+            ],
+            ..Default::default()
+        };
         let findings = detect_t0803_block_command_reporting(&input);
         assert!(
             !findings.is_empty(),
@@ -1618,6 +1765,7 @@ mod tests {
                         remote_role: "master".to_string(),
                         packet_count: 50,
                     }],
+                    master_bindings: vec![],
                 }),
                 ..Default::default()
             },
@@ -1634,13 +1782,15 @@ mod tests {
     // ── T0881 ──
     #[test]
     fn test_t0881_silent_ot_device() {
-        let mut input = AnalysisInput::default();
-        // Three OT devices; one receives far less traffic.
-        input.assets = vec![
-            asset("10.0.0.1", "plc", &["Modbus"]),
-            asset("10.0.0.2", "plc", &["Modbus"]),
-            asset("10.0.0.3", "plc", &["Modbus"]),
-        ];
+        let mut input = AnalysisInput {
+            // Three OT devices; one receives far less traffic.
+            assets: vec![
+                asset("10.0.0.1", "plc", &["Modbus"]),
+                asset("10.0.0.2", "plc", &["Modbus"]),
+                asset("10.0.0.3", "plc", &["Modbus"]),
+            ],
+            ..Default::default()
+        };
         // 10.0.0.1 and 10.0.0.2 receive lots of traffic; 10.0.0.3 receives almost none.
         for _ in 0..10 {
             input
@@ -1710,17 +1860,19 @@ mod tests {
     // ── T0884 ──
     #[test]
     fn test_t0884_proxy_device() {
-        let mut input = AnalysisInput::default();
-        // 10.0.0.50 is NOT an OT device. It receives from 10.0.0.100 AND connects to 10.0.0.1,
-        // both on port 502 → proxy.
-        input.assets = vec![
-            asset("10.0.0.1", "plc", &["Modbus"]),
-            asset("10.0.0.100", "hmi", &["Modbus"]),
-        ];
-        input.connections = vec![
-            conn("10.0.0.100", "10.0.0.50", 502, "Modbus", 100), // HMI → proxy
-            conn("10.0.0.50", "10.0.0.1", 502, "Modbus", 100),   // proxy → PLC
-        ];
+        let input = AnalysisInput {
+            // 10.0.0.50 is NOT an OT device. It receives from 10.0.0.100 AND connects to 10.0.0.1,
+            // both on port 502 → proxy.
+            assets: vec![
+                asset("10.0.0.1", "plc", &["Modbus"]),
+                asset("10.0.0.100", "hmi", &["Modbus"]),
+            ],
+            connections: vec![
+                conn("10.0.0.100", "10.0.0.50", 502, "Modbus", 100), // HMI → proxy
+                conn("10.0.0.50", "10.0.0.1", 502, "Modbus", 100),   // proxy → PLC
+            ],
+            ..Default::default()
+        };
         let ctx = CaptureContext::default();
         let findings = detect_t0884_connection_proxy(&input, &ctx);
         assert!(!findings.is_empty(), "Relay device should trigger T0884");
@@ -1730,9 +1882,11 @@ mod tests {
     // ── T0866 ──
     #[test]
     fn test_t0866_external_host_sshing_to_plc() {
-        let mut input = AnalysisInput::default();
-        input.assets = vec![asset("10.0.0.1", "plc", &["Modbus"])];
-        input.connections = vec![conn("203.0.113.5", "10.0.0.1", 22, "Ssh", 8)];
+        let input = AnalysisInput {
+            assets: vec![asset("10.0.0.1", "plc", &["Modbus"])],
+            connections: vec![conn("203.0.113.5", "10.0.0.1", 22, "Ssh", 8)],
+            ..Default::default()
+        };
         let mut ctx = CaptureContext::default();
         ctx.ot_device_ips.insert("10.0.0.1".to_string());
         ctx.external_ips.insert("203.0.113.5".to_string());
@@ -1747,9 +1901,11 @@ mod tests {
     // ── T0800 ──
     #[test]
     fn test_t0800_cip_file_access_to_plc() {
-        let mut input = AnalysisInput::default();
-        input.assets = vec![asset("10.0.0.1", "plc", &["EthernetIp"])];
-        input.connections = vec![conn("10.0.0.200", "10.0.0.1", 44818, "EthernetIp", 50)];
+        let mut input = AnalysisInput {
+            assets: vec![asset("10.0.0.1", "plc", &["EthernetIp"])],
+            connections: vec![conn("10.0.0.200", "10.0.0.1", 44818, "EthernetIp", 50)],
+            ..Default::default()
+        };
         input.deep_parse.insert(
             "10.0.0.200".to_string(),
             DeepParseSnapshot {
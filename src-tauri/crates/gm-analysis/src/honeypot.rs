@@ -0,0 +1,285 @@
+//! ICS honeypot fingerprinting.
+//!
+//! Internet-facing and research networks often contain deception hosts that
+//! pretend to be PLCs. Counting them as real controllers inflates the
+//! inventory and the risk score, so this pass flags hosts whose identity
+//! data matches a known honeypot template or is implausible for real
+//! hardware.
+//!
+//! Evidence is scored in two tiers:
+//! - **conclusive** — a default banner from a known framework (Conpot's
+//!   S7-200 template, its FC 43 device identification, its SNMP system
+//!   group, or a `honeypot_*` signature match)
+//! - **circumstantial** — things real plants rarely do: one host serving
+//!   proprietary protocols from several PLC vendors, a PLC identity on a
+//!   virtual-machine NIC, or an FC 43 vendor that contradicts the MAC OUI
+//!
+//! A host is flagged on one conclusive or two circumstantial indicators.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Finding, FindingType, Severity};
+
+/// Signature names starting with this prefix are honeypot fingerprints.
+pub const HONEYPOT_SIGNATURE_PREFIX: &str = "honeypot_";
+
+/// Conpot default-template values (templates/default).
+const CONPOT_SNMP_SYS_DESCR: &str = "Siemens, SIMATIC, S7-200";
+const CONPOT_SNMP_SYS_NAME: &str = "CP 443-1 EX40";
+const CONPOT_SNMP_SYS_LOCATION: &str = "Venus";
+const CONPOT_MODBUS_VENDOR: &str = "Siemens";
+const CONPOT_MODBUS_PRODUCT_CODE: &str = "SIMATIC";
+const CONPOT_MODBUS_REVISION: &str = "S7-200";
+
+/// MAC prefixes of hypervisor / container virtual NICs.
+const VIRTUAL_NIC_PREFIXES: &[(&str, &str)] = &[
+    ("00:50:56", "VMware"),
+    ("00:0c:29", "VMware"),
+    ("00:05:69", "VMware"),
+    ("52:54:00", "QEMU/KVM"),
+    ("08:00:27", "VirtualBox"),
+    ("00:15:5d", "Hyper-V"),
+    ("02:42:", "Docker"),
+];
+
+/// Server-side protocols tied to a single PLC vendor ecosystem.
+const VENDOR_PROTOCOLS: &[(&str, &str)] = &[
+    ("s7comm", "Siemens"),
+    ("ethernet_ip", "Rockwell"),
+    ("fins", "Omron"),
+    ("slmp", "Mitsubishi"),
+    ("ge_srtp", "GE"),
+];
+
+/// Identity data collected for one host.
+#[derive(Debug, Clone, Default)]
+pub struct HoneypotProfile {
+    pub ip_address: String,
+    pub mac_address: Option<String>,
+    pub device_type: String,
+    /// Names of signatures that matched the host
+    pub signature_names: Vec<String>,
+    /// Protocols the host *serves* (responds to), snake_case names
+    pub server_protocols: Vec<String>,
+    /// Modbus FC 43/14 device identification
+    pub modbus_vendor: Option<String>,
+    pub modbus_product_code: Option<String>,
+    pub modbus_revision: Option<String>,
+    /// Vendor from the MAC OUI
+    pub oui_vendor: Option<String>,
+    /// SNMP system group
+    pub snmp_sys_descr: Option<String>,
+    pub snmp_sys_name: Option<String>,
+    pub snmp_sys_location: Option<String>,
+}
+
+/// A host identified as a probable honeypot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HoneypotVerdict {
+    pub ip_address: String,
+    /// Framework name when a template fingerprint matched (e.g. "Conpot")
+    pub framework: Option<String>,
+    /// Conclusive indicators
+    pub fingerprints: Vec<String>,
+    /// Circumstantial indicators
+    pub anomalies: Vec<String>,
+}
+
+impl HoneypotVerdict {
+    /// Informational finding describing the verdict.
+    pub fn finding(&self) -> Finding {
+        let what = match &self.framework {
+            Some(framework) => format!("{} honeypot", framework),
+            None => "Probable ICS honeypot".to_string(),
+        };
+        let mut evidence: Vec<&str> = self.fingerprints.iter().map(String::as_str).collect();
+        evidence.extend(self.anomalies.iter().map(String::as_str));
+        Finding::new(
+            FindingType::Anomaly,
+            Severity::Info,
+            format!("{} at {}", what, self.ip_address),
+            format!(
+                "{} presents identity data that matches honeypot software rather than \
+                 real control hardware. Confirm with the asset owner; if it is deception \
+                 infrastructure, exclude it from the device inventory and risk scoring. \
+                 Traffic towards it may still indicate reconnaissance.",
+                self.ip_address
+            ),
+            vec![self.ip_address.clone()],
+            evidence.join("; "),
+            None,
+        )
+    }
+}
+
+/// Assess each profile and return verdicts for the hosts that look like
+/// honeypots, in input order.
+pub fn detect_honeypots(profiles: &[HoneypotProfile]) -> Vec<HoneypotVerdict> {
+    profiles.iter().filter_map(assess).collect()
+}
+
+fn assess(p: &HoneypotProfile) -> Option<HoneypotVerdict> {
+    let mut framework = None;
+    let mut fingerprints = Vec::new();
+    let mut anomalies = Vec::new();
+
+    for name in &p.signature_names {
+        if let Some(rest) = name.strip_prefix(HONEYPOT_SIGNATURE_PREFIX) {
+            fingerprints.push(format!("signature {}", name));
+            if rest.starts_with("conpot") {
+                framework = Some("Conpot".to_string());
+            }
+        }
+    }
+
+    if eq(&p.modbus_vendor, CONPOT_MODBUS_VENDOR)
+        && eq(&p.modbus_product_code, CONPOT_MODBUS_PRODUCT_CODE)
+        && eq(&p.modbus_revision, CONPOT_MODBUS_REVISION)
+    {
+        fingerprints.push("Modbus FC 43 identity Siemens / SIMATIC / S7-200".to_string());
+        framework = Some("Conpot".to_string());
+    }
+
+    let snmp_hits = [
+        eq(&p.snmp_sys_descr, CONPOT_SNMP_SYS_DESCR),
+        eq(&p.snmp_sys_name, CONPOT_SNMP_SYS_NAME),
+        eq(&p.snmp_sys_location, CONPOT_SNMP_SYS_LOCATION),
+    ]
+    .iter()
+    .filter(|&&hit| hit)
+    .count();
+    if snmp_hits >= 2 {
+        fingerprints.push("SNMP system group from the Conpot default template".to_string());
+        framework = Some("Conpot".to_string());
+    }
+
+    // Circumstantial: proprietary protocols from several PLC vendors
+    let vendors: Vec<&str> = VENDOR_PROTOCOLS
+        .iter()
+        .filter(|(proto, _)| p.server_protocols.iter().any(|s| s == proto))
+        .map(|(_, vendor)| *vendor)
+        .collect();
+    if vendors.len() >= 2 {
+        anomalies.push(format!(
+            "serves proprietary protocols of {} vendors ({})",
+            vendors.len(),
+            vendors.join(", ")
+        ));
+    }
+
+    // Circumstantial: a controller identity on a virtual NIC
+    let is_controller = matches!(p.device_type.as_str(), "plc" | "rtu" | "ied");
+    let hypervisor = p.mac_address.as_deref().and_then(|mac| {
+        let mac = mac.to_ascii_lowercase();
+        VIRTUAL_NIC_PREFIXES
+            .iter()
+            .find(|(prefix, _)| mac.starts_with(prefix))
+            .map(|(_, name)| *name)
+    });
+    if let Some(hypervisor) = hypervisor {
+        if is_controller || !p.server_protocols.is_empty() {
+            anomalies.push(format!(
+                "serves ICS protocols from a {} virtual NIC",
+                hypervisor
+            ));
+        }
+    }
+
+    // Circumstantial: FC 43 vendor contradicts the MAC OUI (a virtual NIC
+    // already counted above would always contradict it)
+    if let (Some(claimed), Some(oui), None) = (
+        p.modbus_vendor.as_deref(),
+        p.oui_vendor.as_deref(),
+        hypervisor,
+    ) {
+        let claimed_l = claimed.to_lowercase();
+        let oui_l = oui.to_lowercase();
+        let first = |s: &str| s.split_whitespace().next().unwrap_or("").to_string();
+        if !claimed_l.is_empty()
+            && !oui_l.contains(&first(&claimed_l))
+            && !claimed_l.contains(&first(&oui_l))
+        {
+            anomalies.push(format!(
+                "Modbus identity vendor \"{}\" does not match MAC OUI vendor \"{}\"",
+                claimed, oui
+            ));
+        }
+    }
+
+    if fingerprints.is_empty() && anomalies.len() < 2 {
+        return None;
+    }
+    Some(HoneypotVerdict {
+        ip_address: p.ip_address.clone(),
+        framework,
+        fingerprints,
+        anomalies,
+    })
+}
+
+fn eq(value: &Option<String>, expected: &str) -> bool {
+    value.as_deref().map(str::trim) == Some(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> HoneypotProfile {
+        HoneypotProfile {
+            ip_address: "10.0.0.50".to_string(),
+            device_type: "plc".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_conpot_modbus_identity_is_conclusive() {
+        let mut p = profile();
+        p.modbus_vendor = Some("Siemens".to_string());
+        p.modbus_product_code = Some("SIMATIC".to_string());
+        p.modbus_revision = Some("S7-200".to_string());
+        let verdicts = detect_honeypots(&[p]);
+        assert_eq!(verdicts.len(), 1);
+        assert_eq!(verdicts[0].framework.as_deref(), Some("Conpot"));
+        assert_eq!(verdicts[0].finding().severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_honeypot_signature_and_snmp_template() {
+        let mut p = profile();
+        p.signature_names = vec!["honeypot_conpot_s7_szl".to_string()];
+        assert_eq!(detect_honeypots(&[p])[0].fingerprints.len(), 1);
+
+        let mut p = profile();
+        p.snmp_sys_descr = Some("Siemens, SIMATIC, S7-200".to_string());
+        p.snmp_sys_location = Some("Venus".to_string());
+        assert_eq!(detect_honeypots(&[p]).len(), 1);
+    }
+
+    #[test]
+    fn test_single_anomaly_is_not_enough() {
+        let mut p = profile();
+        p.server_protocols = vec!["s7comm".to_string(), "ethernet_ip".to_string()];
+        assert!(detect_honeypots(std::slice::from_ref(&p)).is_empty());
+
+        // Add a VM NIC and it crosses the threshold
+        p.mac_address = Some("00:0C:29:11:22:33".to_string());
+        let verdicts = detect_honeypots(&[p]);
+        assert_eq!(verdicts.len(), 1);
+        assert_eq!(verdicts[0].anomalies.len(), 2);
+        assert_eq!(verdicts[0].framework, None);
+    }
+
+    #[test]
+    fn test_real_plc_not_flagged() {
+        let mut p = profile();
+        p.mac_address = Some("00:80:f4:01:02:03".to_string());
+        p.oui_vendor = Some("Schneider Electric".to_string());
+        p.modbus_vendor = Some("Schneider Electric".to_string());
+        p.modbus_product_code = Some("BMX P34 2020".to_string());
+        p.server_protocols = vec!["modbus".to_string()];
+        p.signature_names = vec!["schneider_modbus".to_string()];
+        assert!(detect_honeypots(&[p]).is_empty());
+    }
+}
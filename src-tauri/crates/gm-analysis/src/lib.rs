@@ -7,6 +7,10 @@
 //! - **attack**: MITRE ATT&CK for ICS technique detection
 //! - **purdue**: Purdue Model auto-assignment and violation detection
 //! - **anomaly**: Anomaly scoring for network behavior deviations
+//! - **write_approval**: Approved write paths and suppression of their findings
+//! - **recurrence**: Merging findings that recur across analysis runs
+//! - **shifts**: Traffic compared across recurring shift windows
+//! - **modbus_transactions**: Modbus request/response pairing and per-slave health
 //!
 //! ## Architecture
 //!
@@ -24,12 +28,19 @@ pub mod context_attacks;
 pub mod cve_matcher;
 pub mod default_creds;
 pub mod error;
+pub mod honeypot;
 pub mod infrastructure;
+pub mod lifecycle;
 pub mod malware_patterns;
+pub mod modbus_transactions;
 pub mod naming;
 pub mod purdue;
+pub mod recurrence;
 pub mod risk;
+pub mod sessions;
+pub mod shifts;
 pub mod switch_security;
+pub mod write_approval;
 
 pub use context_attacks::CaptureContext;
 
@@ -38,7 +49,9 @@ pub use compliance::{
     generate_compliance_report, supported_frameworks, ComplianceMapping, ComplianceStatus,
 };
 pub use cve_matcher::{CveMatch, CveMatcher};
+pub use honeypot::{detect_honeypots, HoneypotProfile, HoneypotVerdict, HONEYPOT_SIGNATURE_PREFIX};
 pub use infrastructure::{classify_infrastructure, InfrastructureRole};
+pub use lifecycle::{detect_lifecycle_findings, AssetLifecycle, LifecycleObservation};
 pub use malware_patterns::{
     detect_malware_patterns, load_malware_patterns, MalwareFinding, MalwarePattern,
 };
@@ -52,10 +65,20 @@ use uuid::Uuid;
 
 pub use comm_patterns::{ConnectionStats, PatternAnalyzer, PatternAnomaly, PatternAnomalyType};
 pub use default_creds::{CredentialChecker, DefaultCredential};
+pub use modbus_transactions::{ModbusMessage, ModbusTransactionStats, ModbusTransactionTracker};
 pub use naming::{suggest_all as suggest_names_all, suggest_name, NamingSuggestion};
+pub use recurrence::{merge_recurring_findings, MAX_OCCURRENCE_HISTORY};
 pub use risk::{
-    assess_all as assess_criticality_all, assess_criticality, CriticalityAssessment,
-    CriticalityLevel,
+    assess_all as assess_criticality_all, assess_criticality, raise_for_choke_points,
+    CriticalityAssessment, CriticalityLevel,
+};
+pub use sessions::{
+    DeviceSessionHealth, ProtocolSession, SessionMessage, SessionPacket, SessionPdu, SessionState,
+    SessionTracker,
+};
+pub use shifts::{default_shifts, ShiftActivity, ShiftReport, ShiftWindow};
+pub use write_approval::{
+    detect_write_paths, suppress_approved_findings, WriteApproval, WritePath,
 };
 
 /// A security finding produced by analysis.
@@ -76,8 +99,29 @@ pub struct Finding {
     pub evidence: String,
     /// MITRE ATT&CK technique ID (e.g., "T0855"), if applicable
     pub technique_id: Option<String>,
-    /// When this finding was generated
+    /// When this condition was first detected
     pub created_at: String,
+    /// When this condition was most recently detected
+    #[serde(default)]
+    pub last_seen: String,
+    /// Analysis runs that detected this condition
+    #[serde(default = "default_occurrence_count")]
+    pub occurrence_count: u32,
+    /// Detection history, oldest first, capped at [`MAX_OCCURRENCE_HISTORY`]
+    #[serde(default)]
+    pub occurrences: Vec<FindingOccurrence>,
+}
+
+fn default_occurrence_count() -> u32 {
+    1
+}
+
+/// One detection of a finding's condition.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FindingOccurrence {
+    pub seen_at: String,
+    /// Severity at that detection; it may change between runs
+    pub severity: Severity,
 }
 
 impl Finding {
@@ -90,6 +134,7 @@ impl Finding {
         evidence: String,
         technique_id: Option<String>,
     ) -> Self {
+        let now = Utc::now().to_rfc3339();
         Self {
             id: Uuid::new_v4().to_string(),
             finding_type,
@@ -99,9 +144,47 @@ impl Finding {
             affected_assets,
             evidence,
             technique_id,
-            created_at: Utc::now().to_rfc3339(),
+            created_at: now.clone(),
+            last_seen: now.clone(),
+            occurrence_count: 1,
+            occurrences: vec![FindingOccurrence {
+                seen_at: now,
+                severity,
+            }],
         }
     }
+
+    /// Identity of the condition this finding reports, stable across
+    /// analysis runs: type, technique, affected assets (in any order) and
+    /// title with its numbers blanked, since titles such as "62% of OT
+    /// traffic is unencrypted" carry counts that change from run to run.
+    pub fn condition_key(&self) -> String {
+        let mut assets: Vec<&str> = self.affected_assets.iter().map(String::as_str).collect();
+        assets.sort_unstable();
+        assets.dedup();
+
+        let mut title = String::with_capacity(self.title.len());
+        let mut in_number = false;
+        for c in self.title.chars() {
+            if c.is_ascii_digit() {
+                if !in_number {
+                    title.push('#');
+                }
+                in_number = true;
+            } else {
+                title.push(c);
+                in_number = false;
+            }
+        }
+
+        format!(
+            "{:?}|{}|{}|{}",
+            self.finding_type,
+            self.technique_id.as_deref().unwrap_or(""),
+            assets.join(","),
+            title
+        )
+    }
 }
 
 /// Type classification for findings.
@@ -183,6 +266,8 @@ pub enum AnomalyType {
     NewDevice,
     /// Public IP on OT network
     UnexpectedPublicIp,
+    /// IPv4 address claimed in ARP by more than one MAC
+    DuplicateIp,
 }
 
 /// Input data for analysis — a snapshot of the current state.
@@ -194,6 +279,8 @@ pub struct AnalysisInput {
     pub assets: Vec<AssetSnapshot>,
     pub connections: Vec<ConnectionSnapshot>,
     pub deep_parse: std::collections::HashMap<String, DeepParseSnapshot>,
+    /// IP → MAC bindings learned passively from ARP
+    pub arp: Vec<ArpSnapshot>,
 }
 
 /// Minimal asset data needed for analysis.
@@ -212,6 +299,16 @@ pub struct AssetSnapshot {
     pub product_family: Option<String>,
 }
 
+/// An IPv4 address as seen in ARP.
+#[derive(Debug, Clone)]
+pub struct ArpSnapshot {
+    pub ip_address: String,
+    /// Every MAC that claimed the address, first claimant first
+    pub mac_addresses: Vec<String>,
+    /// Gratuitous ARPs announcing the address
+    pub gratuitous_count: u64,
+}
+
 /// Minimal connection data needed for analysis.
 #[derive(Debug, Clone)]
 pub struct ConnectionSnapshot {
@@ -233,6 +330,11 @@ pub struct DeepParseSnapshot {
     pub bacnet: Option<BacnetSnapshot>,
     pub iec104: Option<Iec104Snapshot>,
     pub profinet_dcp: Option<ProfinetDcpSnapshot>,
+    pub codesys: Option<CodesysSnapshot>,
+    pub crimson: Option<CrimsonSnapshot>,
+    pub tristation: Option<TriStationSnapshot>,
+    pub smb: Option<SmbSnapshot>,
+    pub dhcp: Option<DhcpSnapshot>,
 }
 
 /// Modbus data needed for ATT&CK detection.
@@ -252,6 +354,19 @@ pub struct Dnp3Snapshot {
     pub has_unsolicited: bool,
     pub function_codes: Vec<FcSnapshot>,
     pub relationships: Vec<RelationshipSnapshot>,
+    /// Master link addresses this IP sent from, per outstation
+    pub master_bindings: Vec<Dnp3BindingSnapshot>,
+}
+
+/// One (master address → outstation address) pairing seen from an IP.
+#[derive(Debug, Clone)]
+pub struct Dnp3BindingSnapshot {
+    pub master_address: u16,
+    pub outstation_address: u16,
+    pub outstation_ip: String,
+    /// RFC 3339 timestamps of the first and last frame
+    pub first_seen: String,
+    pub last_seen: String,
 }
 
 /// EtherNet/IP data needed for ATT&CK detection.
@@ -296,6 +411,83 @@ pub struct Iec104Snapshot {
     pub has_interrogation: bool,
 }
 
+/// CODESYS runtime protocol data needed for ATT&CK detection.
+#[derive(Debug, Clone)]
+pub struct CodesysSnapshot {
+    /// "client" (Development System) or "server" (runtime)
+    pub role: String,
+    /// Device logged into a runtime
+    pub has_login: bool,
+    /// Device downloaded an application — T0843
+    pub has_download: bool,
+    /// Device applied an online change to a running application — T0843
+    pub has_online_change: bool,
+    /// Device started, stopped or reset an application — T0858
+    pub has_control_commands: bool,
+}
+
+/// Red Lion Crimson v3 data needed for ATT&CK detection.
+#[derive(Debug, Clone)]
+pub struct CrimsonSnapshot {
+    /// "client" (Crimson software) or "server" (Red Lion device)
+    pub role: String,
+    /// Device sent a configuration database to a Red Lion device — T0843
+    pub has_download: bool,
+    /// Device read a configuration database from a Red Lion device — T0845
+    pub has_upload: bool,
+    /// Red Lion devices on the other end of the transfers
+    pub transfer_peers: Vec<String>,
+}
+
+/// Triconex TriStation data needed for ATT&CK detection.
+#[derive(Debug, Clone)]
+pub struct TriStationSnapshot {
+    /// "client" (TriStation 1131 workstation) or "server" (Triconex controller)
+    pub role: String,
+    /// Device downloaded a program or configuration to a controller — T0843
+    pub has_download: bool,
+    /// Device uploaded the safety program — T0845
+    pub has_upload: bool,
+    /// Device ran or halted the safety program — T0858
+    pub has_run_state_change: bool,
+    /// Controllers on the other end of the commands
+    pub peers: Vec<String>,
+}
+
+/// SMB share activity needed for cross-zone file transfer detection.
+#[derive(Debug, Clone)]
+pub struct SmbSnapshot {
+    /// "client" or "server"
+    pub role: String,
+    /// Shares this device mounted as a client
+    pub shares: Vec<SmbShareSnapshot>,
+}
+
+/// DHCP server activity needed for rogue DHCP server detection.
+#[derive(Debug, Clone)]
+pub struct DhcpSnapshot {
+    /// "client" or "server"
+    pub role: String,
+    /// Client MACs this server sent offers or ACKs to
+    pub clients: Vec<String>,
+    /// Offers and ACKs sent
+    pub replies: u64,
+}
+
+/// One share a client mounted on a file server.
+#[derive(Debug, Clone)]
+pub struct SmbShareSnapshot {
+    pub server_ip: String,
+    /// UNC path, e.g. `\\hist01\exports`
+    pub share: String,
+    /// Files opened on the share
+    pub files: Vec<String>,
+    /// Files created or opened for writing
+    pub files_written: Vec<String>,
+    pub bytes_written: u64,
+    pub bytes_read: u64,
+}
+
 /// BACnet data needed for ATT&CK detection.
 #[derive(Debug, Clone)]
 pub struct BacnetSnapshot {
@@ -363,6 +555,9 @@ pub struct AnalysisSummary {
     pub connections_analyzed: usize,
     /// Percentage of traffic that is unencrypted OT
     pub unencrypted_ot_percent: f64,
+    /// Findings removed by write-path approvals or disabled techniques
+    #[serde(default)]
+    pub suppressed_findings: usize,
 }
 
 /// Run the full analysis pipeline.
@@ -381,7 +576,11 @@ pub fn run_full_analysis(input: &AnalysisInput, ctx: &CaptureContext) -> Analysi
     let purdue_assignments = purdue::auto_assign_purdue_levels(input);
 
     // Run Purdue violation detection (needs assignments)
-    let purdue_findings = purdue::detect_purdue_violations(input, &purdue_assignments);
+    let mut purdue_findings = purdue::detect_purdue_violations(input, &purdue_assignments);
+    purdue_findings.extend(purdue::detect_smb_zone_transfers(
+        input,
+        &purdue_assignments,
+    ));
 
     // Run anomaly detection
     let (anomalies, anomaly_findings) = anomaly::detect_anomalies(input);
@@ -393,7 +592,7 @@ pub fn run_full_analysis(input: &AnalysisInput, ctx: &CaptureContext) -> Analysi
     findings.extend(anomaly_findings);
 
     // Sort by severity (critical first)
-    findings.sort_by(|a, b| b.severity.cmp(&a.severity));
+    findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
 
     // Compute unencrypted OT percentage
     let total_ot_packets: u64 = input
@@ -446,6 +645,7 @@ pub fn run_full_analysis(input: &AnalysisInput, ctx: &CaptureContext) -> Analysi
         assets_analyzed: input.assets.len(),
         connections_analyzed: input.connections.len(),
         unencrypted_ot_percent: (unencrypted_ot_percent * 10.0).round() / 10.0,
+        suppressed_findings: 0,
     };
 
     AnalysisResult {
@@ -456,6 +656,48 @@ pub fn run_full_analysis(input: &AnalysisInput, ctx: &CaptureContext) -> Analysi
     }
 }
 
+/// Drop findings for ATT&CK techniques disabled in the active settings
+/// profile. Summary counts are updated and the number removed is returned.
+pub fn suppress_disabled_techniques(result: &mut AnalysisResult, disabled: &[String]) -> usize {
+    if disabled.is_empty() {
+        return 0;
+    }
+    remove_findings(result, |f| {
+        f.technique_id
+            .as_deref()
+            .is_some_and(|t| disabled.iter().any(|d| d.eq_ignore_ascii_case(t)))
+    })
+}
+
+/// Remove findings matching `pred` and keep the summary counts in step.
+pub(crate) fn remove_findings(
+    result: &mut AnalysisResult,
+    pred: impl Fn(&Finding) -> bool,
+) -> usize {
+    let (removed, kept): (Vec<Finding>, Vec<Finding>) = std::mem::take(&mut result.findings)
+        .into_iter()
+        .partition(|f| pred(f));
+    result.findings = kept;
+
+    let summary = &mut result.summary;
+    for finding in &removed {
+        let count = match finding.severity {
+            Severity::Critical => &mut summary.critical_count,
+            Severity::High => &mut summary.high_count,
+            Severity::Medium => &mut summary.medium_count,
+            Severity::Low => &mut summary.low_count,
+            Severity::Info => &mut summary.info_count,
+        };
+        *count = count.saturating_sub(1);
+        if finding.finding_type == FindingType::PurdueViolation {
+            summary.purdue_violations = summary.purdue_violations.saturating_sub(1);
+        }
+    }
+    summary.total_findings = result.findings.len();
+    summary.suppressed_findings += removed.len();
+    removed.len()
+}
+
 /// Check if a protocol string represents an OT protocol.
 fn is_ot_protocol(proto: &str) -> bool {
     matches!(
@@ -468,11 +710,21 @@ fn is_ot_protocol(proto: &str) -> bool {
             | "OpcUa"
             | "Profinet"
             | "Iec104"
+            | "Iec61850Mms"
             | "Mqtt"
             | "HartIp"
             | "FoundationFieldbus"
             | "GeSrtp"
             | "WonderwareSuitelink"
+            | "Fins"
+            | "Slmp"
+            | "Codesys"
+            | "NiagaraFox"
+            | "Crimson"
+            | "VnetIp"
+            | "TriStation"
+            | "Ads"
+            | "OpcDa"
     )
 }
 
@@ -504,6 +756,53 @@ mod tests {
         assert_eq!(f.technique_id, Some("T0855".to_string()));
     }
 
+    #[test]
+    fn test_suppress_disabled_techniques() {
+        let finding = |technique: &str, severity: Severity| {
+            Finding::new(
+                FindingType::AttackTechnique,
+                severity,
+                "Test".to_string(),
+                String::new(),
+                vec!["10.0.0.1".to_string()],
+                String::new(),
+                Some(technique.to_string()),
+            )
+        };
+        let mut result = AnalysisResult {
+            findings: vec![
+                finding("T0846", Severity::Low),
+                finding("T0855", Severity::High),
+            ],
+            purdue_assignments: Vec::new(),
+            anomalies: Vec::new(),
+            summary: AnalysisSummary {
+                total_findings: 2,
+                critical_count: 0,
+                high_count: 1,
+                medium_count: 0,
+                low_count: 1,
+                info_count: 0,
+                purdue_violations: 0,
+                anomaly_count: 0,
+                assets_analyzed: 0,
+                connections_analyzed: 0,
+                unencrypted_ot_percent: 0.0,
+                suppressed_findings: 0,
+            },
+        };
+
+        assert_eq!(suppress_disabled_techniques(&mut result, &[]), 0);
+        assert_eq!(
+            suppress_disabled_techniques(&mut result, &["t0846".to_string()]),
+            1
+        );
+        assert_eq!(result.findings.len(), 1);
+        assert_eq!(result.summary.total_findings, 1);
+        assert_eq!(result.summary.low_count, 0);
+        assert_eq!(result.summary.suppressed_findings, 1);
+    }
+
     #[test]
     fn test_empty_analysis() {
         let input = AnalysisInput::default();
@@ -0,0 +1,208 @@
+//! Asset lifecycle states.
+//!
+//! Analysts mark assets as active, quarantined, decommissioned or planned.
+//! Analysis compares that intent against the traffic: a decommissioned
+//! device that talks again after it was retired is either a rogue device
+//! reusing its address or hardware that was never actually removed, and a
+//! planned device showing up means a commissioning step happened that the
+//! inventory should record.
+//!
+//! Only traffic *after* the state change counts, so re-analysing an older
+//! capture never flags a device for behaviour from before it was retired.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Finding, FindingType, Severity};
+
+/// Lifecycle state of an asset, set by the analyst.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetLifecycle {
+    /// In service (the default for every discovered asset)
+    #[default]
+    Active,
+    /// Isolated pending investigation or remediation
+    Quarantined,
+    /// Retired; should no longer appear on the network
+    Decommissioned,
+    /// Expected to be installed; not yet in service
+    Planned,
+}
+
+impl AssetLifecycle {
+    /// Storage / wire name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetLifecycle::Active => "active",
+            AssetLifecycle::Quarantined => "quarantined",
+            AssetLifecycle::Decommissioned => "decommissioned",
+            AssetLifecycle::Planned => "planned",
+        }
+    }
+
+    /// Parse a storage name. Unknown values fall back to `Active`.
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "quarantined" => AssetLifecycle::Quarantined,
+            "decommissioned" => AssetLifecycle::Decommissioned,
+            "planned" => AssetLifecycle::Planned,
+            _ => AssetLifecycle::Active,
+        }
+    }
+}
+
+/// Lifecycle state and observed traffic for one asset.
+#[derive(Debug, Clone)]
+pub struct LifecycleObservation {
+    pub ip_address: String,
+    pub lifecycle: AssetLifecycle,
+    /// When the state was last changed (RFC 3339); None means "always"
+    pub changed_at: Option<String>,
+    /// Last packet seen from or to the asset (RFC 3339)
+    pub last_seen: String,
+    /// Packets observed for the asset; 0 for inventory-only assets
+    pub packet_count: u64,
+}
+
+impl LifecycleObservation {
+    /// Whether the asset produced traffic after its state last changed.
+    fn seen_since_change(&self) -> bool {
+        if self.packet_count == 0 {
+            return false;
+        }
+        let Ok(last_seen) = DateTime::parse_from_rfc3339(&self.last_seen) else {
+            return false;
+        };
+        match self.changed_at.as_deref() {
+            None => true,
+            Some(changed) => DateTime::parse_from_rfc3339(changed)
+                .map(|changed| last_seen.with_timezone(&Utc) > changed.with_timezone(&Utc))
+                .unwrap_or(true),
+        }
+    }
+}
+
+/// Findings for assets whose traffic contradicts their lifecycle state.
+///
+/// - Decommissioned and seen since → Critical
+/// - Planned and seen since → Info (update the inventory)
+pub fn detect_lifecycle_findings(observations: &[LifecycleObservation]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for obs in observations {
+        if !obs.seen_since_change() {
+            continue;
+        }
+        let since = obs
+            .changed_at
+            .as_deref()
+            .map(|t| format!(" (marked {})", t))
+            .unwrap_or_default();
+        match obs.lifecycle {
+            AssetLifecycle::Decommissioned => findings.push(Finding::new(
+                FindingType::Anomaly,
+                Severity::Critical,
+                format!("Decommissioned device {} reappeared", obs.ip_address),
+                format!(
+                    "{} is marked decommissioned{} but was seen on the network again, \
+                     last at {}. Either the hardware was never removed or another device \
+                     is using its address. Locate the device and confirm its identity \
+                     before returning it to the inventory.",
+                    obs.ip_address, since, obs.last_seen
+                ),
+                vec![obs.ip_address.clone()],
+                format!(
+                    "lifecycle=decommissioned, packets={}, last_seen={}",
+                    obs.packet_count, obs.last_seen
+                ),
+                None,
+            )),
+            AssetLifecycle::Planned => findings.push(Finding::new(
+                FindingType::Anomaly,
+                Severity::Info,
+                format!("Planned device {} is now on the network", obs.ip_address),
+                format!(
+                    "{} is marked planned{} and has started communicating (last seen {}). \
+                     Mark it active once commissioning is confirmed.",
+                    obs.ip_address, since, obs.last_seen
+                ),
+                vec![obs.ip_address.clone()],
+                format!(
+                    "lifecycle=planned, packets={}, last_seen={}",
+                    obs.packet_count, obs.last_seen
+                ),
+                None,
+            )),
+            AssetLifecycle::Active | AssetLifecycle::Quarantined => {}
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obs(
+        lifecycle: AssetLifecycle,
+        changed_at: Option<&str>,
+        packets: u64,
+    ) -> LifecycleObservation {
+        LifecycleObservation {
+            ip_address: "10.0.0.5".to_string(),
+            lifecycle,
+            changed_at: changed_at.map(str::to_string),
+            last_seen: "2026-03-10T08:00:00Z".to_string(),
+            packet_count: packets,
+        }
+    }
+
+    #[test]
+    fn test_decommissioned_reappearing_is_critical() {
+        let findings = detect_lifecycle_findings(&[obs(
+            AssetLifecycle::Decommissioned,
+            Some("2026-03-01T00:00:00Z"),
+            120,
+        )]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Critical);
+        assert_eq!(findings[0].affected_assets, vec!["10.0.0.5".to_string()]);
+    }
+
+    #[test]
+    fn test_traffic_before_change_is_ignored() {
+        // Retired after the capture was taken
+        let findings = detect_lifecycle_findings(&[obs(
+            AssetLifecycle::Decommissioned,
+            Some("2026-03-20T00:00:00Z"),
+            120,
+        )]);
+        assert!(findings.is_empty());
+        // Inventory-only planned asset with no traffic
+        assert!(detect_lifecycle_findings(&[obs(AssetLifecycle::Planned, None, 0)]).is_empty());
+    }
+
+    #[test]
+    fn test_planned_appearing_is_info() {
+        let findings = detect_lifecycle_findings(&[
+            obs(AssetLifecycle::Planned, Some("2026-02-01T00:00:00Z"), 4),
+            obs(AssetLifecycle::Active, None, 4),
+            obs(AssetLifecycle::Quarantined, None, 4),
+        ]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_lifecycle_names_round_trip() {
+        for state in [
+            AssetLifecycle::Active,
+            AssetLifecycle::Quarantined,
+            AssetLifecycle::Decommissioned,
+            AssetLifecycle::Planned,
+        ] {
+            assert_eq!(AssetLifecycle::from_name(state.as_str()), state);
+        }
+        assert_eq!(AssetLifecycle::from_name("bogus"), AssetLifecycle::Active);
+    }
+}
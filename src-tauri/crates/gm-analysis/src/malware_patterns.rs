@@ -59,10 +59,20 @@ const ICS_PROTOCOLS: &[&str] = &[
     "Bacnet",
     "OpcUa",
     "Iec104",
+    "Iec61850Mms",
     "ProfinetDcp",
     "HartIp",
     "GeSrtp",
     "WonderwareSuitelink",
+    "Fins",
+    "Slmp",
+    "Codesys",
+    "NiagaraFox",
+    "Crimson",
+    "VnetIp",
+    "TriStation",
+    "Ads",
+    "OpcDa",
     "FfHse",
 ];
 
@@ -92,7 +102,7 @@ pub fn detect_malware_patterns(
     findings.extend(detect_industroyer2(deep_parse, connections));
 
     // Sort: critical first, then high, medium, low
-    findings.sort_by(|a, b| severity_rank(&b.severity).cmp(&severity_rank(&a.severity)));
+    findings.sort_by_key(|f| std::cmp::Reverse(severity_rank(&f.severity)));
 
     findings
 }
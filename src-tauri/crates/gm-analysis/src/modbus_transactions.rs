@@ -0,0 +1,393 @@
+//! Modbus request/response transaction pairing.
+//!
+//! Modbus/TCP masters tag every request with an MBAP transaction ID that the
+//! slave echoes back, so a response can be matched to its request even when
+//! several are pipelined on one connection. Pairing them gives per-slave
+//! response latency, exception rates, timeouts and retries — the numbers an
+//! engineer looks at when a field device is getting slow or flaky.
+//!
+//! RTU frames tunnelled over TCP carry no transaction ID; the parser reports
+//! 0 for them, which still pairs correctly because a serial slave answers one
+//! request at a time.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds after which an unanswered request counts as timed out.
+pub const DEFAULT_TRANSACTION_TIMEOUT_SECS: f64 = 5.0;
+
+/// Cap on requests awaiting a response; beyond it new requests are counted
+/// but not paired.
+const MAX_PENDING: usize = 65_536;
+
+/// One Modbus PDU, as seen by the tracker.
+#[derive(Debug, Clone, Copy)]
+pub struct ModbusMessage<'a> {
+    pub src_ip: &'a str,
+    pub src_port: u16,
+    pub dst_ip: &'a str,
+    pub dst_port: u16,
+    pub transaction_id: u16,
+    pub unit_id: u8,
+    /// Function code without the exception bit
+    pub function_code: u8,
+    /// True for master → slave requests, false for slave responses
+    pub is_request: bool,
+    /// Exception code, for exception responses
+    pub exception_code: Option<u8>,
+    /// Start address and quantity of the request, when it addresses registers
+    pub register: Option<(u16, u16)>,
+    pub timestamp_secs: f64,
+}
+
+/// Transaction health of one slave unit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModbusTransactionStats {
+    pub slave_ip: String,
+    pub unit_id: u8,
+    pub requests: u64,
+    /// Responses matched to a request within the timeout
+    pub responses: u64,
+    /// Matched responses that were exceptions
+    pub exceptions: u64,
+    /// Requests never answered, or answered after the timeout
+    pub timeouts: u64,
+    /// Requests repeated while an identical one was still unanswered
+    pub retries: u64,
+    /// Responses whose transaction ID matched no outstanding request
+    pub unmatched_responses: u64,
+    /// Exceptions as a fraction of matched responses (0.0-1.0)
+    pub exception_rate: f64,
+    pub avg_latency_ms: f64,
+    pub min_latency_ms: f64,
+    pub max_latency_ms: f64,
+    /// Exception code → count, sorted by code
+    pub exception_codes: Vec<(u8, u64)>,
+}
+
+type Endpoint = (String, u16);
+
+/// (master, slave, unit ID, transaction ID)
+type TransactionKey = (Endpoint, Endpoint, u8, u16);
+
+/// (master, slave, unit ID, function code, register range)
+type RequestSignature = (Endpoint, Endpoint, u8, u8, Option<(u16, u16)>);
+
+struct Pending {
+    sent_at: f64,
+    signature: RequestSignature,
+}
+
+#[derive(Default)]
+struct SlaveAccumulator {
+    requests: u64,
+    responses: u64,
+    exceptions: u64,
+    timeouts: u64,
+    retries: u64,
+    unmatched_responses: u64,
+    latency_sum_ms: f64,
+    min_latency_ms: f64,
+    max_latency_ms: f64,
+    exception_codes: HashMap<u8, u64>,
+}
+
+impl SlaveAccumulator {
+    fn record_latency(&mut self, latency_ms: f64) {
+        if self.responses == 0 || latency_ms < self.min_latency_ms {
+            self.min_latency_ms = latency_ms;
+        }
+        self.max_latency_ms = self.max_latency_ms.max(latency_ms);
+        self.latency_sum_ms += latency_ms;
+        self.responses += 1;
+    }
+}
+
+/// Pairs Modbus requests with their responses.
+///
+/// # Usage
+/// ```
+/// use gm_analysis::modbus_transactions::{ModbusMessage, ModbusTransactionTracker};
+///
+/// let mut tracker = ModbusTransactionTracker::new();
+/// let request = ModbusMessage {
+///     src_ip: "10.0.0.1",
+///     src_port: 49152,
+///     dst_ip: "10.0.0.2",
+///     dst_port: 502,
+///     transaction_id: 7,
+///     unit_id: 1,
+///     function_code: 3,
+///     is_request: true,
+///     exception_code: None,
+///     register: Some((0, 10)),
+///     timestamp_secs: 0.0,
+/// };
+/// tracker.record(&request);
+/// tracker.record(&ModbusMessage {
+///     src_ip: "10.0.0.2",
+///     src_port: 502,
+///     dst_ip: "10.0.0.1",
+///     dst_port: 49152,
+///     is_request: false,
+///     register: None,
+///     timestamp_secs: 0.012,
+///     ..request
+/// });
+/// let stats = tracker.stats();
+/// assert_eq!(stats[0].responses, 1);
+/// assert!((stats[0].avg_latency_ms - 12.0).abs() < 1e-6);
+/// ```
+pub struct ModbusTransactionTracker {
+    pending: HashMap<TransactionKey, Pending>,
+    /// Outstanding request per signature, for retry detection
+    by_signature: HashMap<RequestSignature, TransactionKey>,
+    slaves: HashMap<(String, u8), SlaveAccumulator>,
+    timeout_secs: f64,
+    capture_end: f64,
+}
+
+impl Default for ModbusTransactionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModbusTransactionTracker {
+    pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_TRANSACTION_TIMEOUT_SECS)
+    }
+
+    /// Create a tracker that counts requests as timed out after `secs`.
+    pub fn with_timeout(secs: f64) -> Self {
+        Self {
+            pending: HashMap::new(),
+            by_signature: HashMap::new(),
+            slaves: HashMap::new(),
+            timeout_secs: secs,
+            capture_end: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Record a single Modbus PDU. O(1).
+    pub fn record(&mut self, msg: &ModbusMessage) {
+        self.capture_end = self.capture_end.max(msg.timestamp_secs);
+        if msg.is_request {
+            self.record_request(msg);
+        } else {
+            self.record_response(msg);
+        }
+    }
+
+    fn record_request(&mut self, msg: &ModbusMessage) {
+        let master = (msg.src_ip.to_string(), msg.src_port);
+        let slave = (msg.dst_ip.to_string(), msg.dst_port);
+        let key = (
+            master.clone(),
+            slave.clone(),
+            msg.unit_id,
+            msg.transaction_id,
+        );
+        let signature = (master, slave, msg.unit_id, msg.function_code, msg.register);
+
+        let acc = self
+            .slaves
+            .entry((msg.dst_ip.to_string(), msg.unit_id))
+            .or_default();
+        acc.requests += 1;
+
+        // The same request again while the first is unanswered is a retry;
+        // the earlier attempt is given up on
+        if let Some(previous) = self.by_signature.remove(&signature) {
+            self.pending.remove(&previous);
+            acc.retries += 1;
+            acc.timeouts += 1;
+        }
+        // A reused transaction ID abandons whatever was pending under it
+        if let Some(stale) = self.pending.remove(&key) {
+            self.by_signature.remove(&stale.signature);
+            acc.timeouts += 1;
+        }
+
+        if self.pending.len() < MAX_PENDING {
+            self.by_signature.insert(signature.clone(), key.clone());
+            self.pending.insert(
+                key,
+                Pending {
+                    sent_at: msg.timestamp_secs,
+                    signature,
+                },
+            );
+        }
+    }
+
+    fn record_response(&mut self, msg: &ModbusMessage) {
+        let key = (
+            (msg.dst_ip.to_string(), msg.dst_port),
+            (msg.src_ip.to_string(), msg.src_port),
+            msg.unit_id,
+            msg.transaction_id,
+        );
+        let acc = self
+            .slaves
+            .entry((msg.src_ip.to_string(), msg.unit_id))
+            .or_default();
+
+        let Some(request) = self.pending.remove(&key) else {
+            acc.unmatched_responses += 1;
+            return;
+        };
+        self.by_signature.remove(&request.signature);
+
+        let elapsed = msg.timestamp_secs - request.sent_at;
+        if elapsed > self.timeout_secs {
+            acc.timeouts += 1;
+            return;
+        }
+        acc.record_latency(elapsed.max(0.0) * 1000.0);
+        if let Some(code) = msg.exception_code {
+            acc.exceptions += 1;
+            *acc.exception_codes.entry(code).or_insert(0) += 1;
+        }
+    }
+
+    /// Per-slave-unit statistics, sorted by slave IP and unit ID.
+    ///
+    /// Requests still pending longer than the timeout at the end of the
+    /// capture count as timeouts.
+    pub fn stats(&self) -> Vec<ModbusTransactionStats> {
+        let mut expired: HashMap<(&str, u8), u64> = HashMap::new();
+        for ((_, slave, unit_id, _), pending) in &self.pending {
+            if self.capture_end - pending.sent_at > self.timeout_secs {
+                *expired.entry((slave.0.as_str(), *unit_id)).or_insert(0) += 1;
+            }
+        }
+
+        let mut result: Vec<ModbusTransactionStats> = self
+            .slaves
+            .iter()
+            .map(|((ip, unit_id), acc)| {
+                let mut exception_codes: Vec<(u8, u64)> =
+                    acc.exception_codes.iter().map(|(&c, &n)| (c, n)).collect();
+                exception_codes.sort_unstable();
+                let (avg_latency_ms, exception_rate) = if acc.responses > 0 {
+                    (
+                        acc.latency_sum_ms / acc.responses as f64,
+                        acc.exceptions as f64 / acc.responses as f64,
+                    )
+                } else {
+                    (0.0, 0.0)
+                };
+                ModbusTransactionStats {
+                    slave_ip: ip.clone(),
+                    unit_id: *unit_id,
+                    requests: acc.requests,
+                    responses: acc.responses,
+                    exceptions: acc.exceptions,
+                    timeouts: acc.timeouts
+                        + expired.get(&(ip.as_str(), *unit_id)).copied().unwrap_or(0),
+                    retries: acc.retries,
+                    unmatched_responses: acc.unmatched_responses,
+                    exception_rate,
+                    avg_latency_ms,
+                    min_latency_ms: acc.min_latency_ms,
+                    max_latency_ms: acc.max_latency_ms,
+                    exception_codes,
+                }
+            })
+            .collect();
+        result.sort_by(|a, b| a.slave_ip.cmp(&b.slave_ip).then(a.unit_id.cmp(&b.unit_id)));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(tid: u16, fc: u8, ts: f64) -> ModbusMessage<'static> {
+        ModbusMessage {
+            src_ip: "10.0.0.1",
+            src_port: 49152,
+            dst_ip: "10.0.0.2",
+            dst_port: 502,
+            transaction_id: tid,
+            unit_id: 1,
+            function_code: fc,
+            is_request: true,
+            exception_code: None,
+            register: Some((100, 4)),
+            timestamp_secs: ts,
+        }
+    }
+
+    fn response(tid: u16, fc: u8, ts: f64, exception_code: Option<u8>) -> ModbusMessage<'static> {
+        ModbusMessage {
+            src_ip: "10.0.0.2",
+            src_port: 502,
+            dst_ip: "10.0.0.1",
+            dst_port: 49152,
+            transaction_id: tid,
+            unit_id: 1,
+            function_code: fc,
+            is_request: false,
+            exception_code,
+            register: None,
+            timestamp_secs: ts,
+        }
+    }
+
+    #[test]
+    fn test_latency_and_exceptions() {
+        let mut tracker = ModbusTransactionTracker::new();
+        tracker.record(&request(1, 3, 0.0));
+        tracker.record(&request(2, 16, 0.001));
+        // Pipelined responses come back out of order
+        tracker.record(&response(2, 16, 0.031, Some(2)));
+        tracker.record(&response(1, 3, 0.041, None));
+
+        let stats = tracker.stats();
+        assert_eq!(stats.len(), 1);
+        let s = &stats[0];
+        assert_eq!((s.slave_ip.as_str(), s.unit_id), ("10.0.0.2", 1));
+        assert_eq!((s.requests, s.responses, s.exceptions), (2, 2, 1));
+        assert_eq!(s.timeouts, 0);
+        assert!((s.min_latency_ms - 30.0).abs() < 1e-6);
+        assert!((s.max_latency_ms - 41.0).abs() < 1e-6);
+        assert!((s.avg_latency_ms - 35.5).abs() < 1e-6);
+        assert!((s.exception_rate - 0.5).abs() < 1e-9);
+        assert_eq!(s.exception_codes, vec![(2, 1)]);
+    }
+
+    #[test]
+    fn test_timeouts_and_retries() {
+        let mut tracker = ModbusTransactionTracker::new();
+        // Retried with a new transaction ID after going unanswered
+        tracker.record(&request(1, 3, 0.0));
+        tracker.record(&request(2, 3, 1.0));
+        tracker.record(&response(2, 3, 1.02, None));
+        // Answered, but too late
+        tracker.record(&request(3, 4, 2.0));
+        tracker.record(&response(3, 4, 8.0, None));
+        // Never answered by the end of the capture
+        tracker.record(&request(4, 6, 10.0));
+        tracker.record(&request(5, 1, 20.0));
+
+        let s = &tracker.stats()[0];
+        assert_eq!(s.requests, 5);
+        assert_eq!(s.responses, 1);
+        assert_eq!(s.retries, 1);
+        // Retried request, late response, and the FC 6 request
+        assert_eq!(s.timeouts, 3);
+        assert_eq!(s.unmatched_responses, 0);
+    }
+
+    #[test]
+    fn test_unmatched_response() {
+        let mut tracker = ModbusTransactionTracker::new();
+        tracker.record(&response(9, 3, 0.0, None));
+        let s = &tracker.stats()[0];
+        assert_eq!((s.requests, s.responses, s.unmatched_responses), (0, 0, 1));
+    }
+}
@@ -19,7 +19,8 @@
 //! ## Cross-Level Violations
 //!
 //! Direct communication between L1 and L4-5 is flagged as a security
-//! finding (T0886 Remote Services).
+//! finding (T0886 Remote Services). SMB shares mounted across levels are
+//! flagged separately with the files involved (T0867 Lateral Tool Transfer).
 
 use std::collections::HashMap;
 
@@ -90,7 +91,7 @@ fn assign_level(
 
     // Device type-based assignment (most reliable)
     match dt {
-        "plc" | "rtu" => {
+        "plc" | "rtu" | "ied" => {
             return (
                 1,
                 format!("Device type '{}' maps to L1 (Basic Control)", dt),
@@ -366,6 +367,105 @@ pub fn detect_purdue_violations(
     deduped
 }
 
+/// Detect SMB shares mounted across Purdue levels.
+///
+/// Connection-level findings say two zones talk; these say which share
+/// was mounted and which files moved. Writes from a higher-numbered (less
+/// trusted) level into a lower one, and any use of administrative shares
+/// (`C$`, `ADMIN$`), are how tools and malware get staged on control
+/// hosts, so they rate High. `IPC$` carries RPC rather than files and is
+/// ignored.
+pub fn detect_smb_zone_transfers(
+    input: &AnalysisInput,
+    assignments: &[PurdueAssignment],
+) -> Vec<Finding> {
+    let level_map: HashMap<&str, u8> = assignments
+        .iter()
+        .map(|a| (a.ip_address.as_str(), a.level))
+        .collect();
+
+    let mut clients: Vec<(&String, &crate::SmbSnapshot)> = input
+        .deep_parse
+        .iter()
+        .filter_map(|(ip, dp)| dp.smb.as_ref().map(|smb| (ip, smb)))
+        .collect();
+    clients.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut findings = Vec::new();
+    for (client_ip, smb) in clients {
+        let Some(&client_level) = level_map.get(client_ip.as_str()) else {
+            continue;
+        };
+        for share in &smb.shares {
+            let share_name = share.share.rsplit('\\').next().unwrap_or(&share.share);
+            if share_name.eq_ignore_ascii_case("IPC$") {
+                continue;
+            }
+            let Some(&server_level) = level_map.get(share.server_ip.as_str()) else {
+                continue;
+            };
+            if server_level == client_level {
+                continue;
+            }
+
+            let wrote = !share.files_written.is_empty() || share.bytes_written > 0;
+            let admin_share = share_name.ends_with('$');
+            let severity = if admin_share || (wrote && server_level < client_level) {
+                Severity::High
+            } else {
+                Severity::Medium
+            };
+
+            let mut evidence = format!(
+                "{} (L{}) mounted {} on {} (L{}): {} file(s) opened, {} bytes written, {} bytes read",
+                client_ip,
+                client_level,
+                share.share,
+                share.server_ip,
+                server_level,
+                share.files.len(),
+                share.bytes_written,
+                share.bytes_read
+            );
+            if !share.files_written.is_empty() {
+                let shown: Vec<&str> = share
+                    .files_written
+                    .iter()
+                    .take(5)
+                    .map(String::as_str)
+                    .collect();
+                evidence.push_str(&format!("; written: {}", shown.join(", ")));
+                if share.files_written.len() > shown.len() {
+                    evidence.push_str(&format!(
+                        " (+{} more)",
+                        share.files_written.len() - shown.len()
+                    ));
+                }
+            }
+
+            findings.push(Finding::new(
+                FindingType::PurdueViolation,
+                severity,
+                format!(
+                    "SMB share {} used across zones: L{} -> L{}",
+                    share.share, client_level, server_level
+                ),
+                format!(
+                    "A Purdue Level {} host mounted a file share on a Level {} host. \
+                     File shares spanning zones are a common path for tools, project \
+                     files and malware to cross the boundary; transfers between levels \
+                     should go through a DMZ (L3.5) with file inspection.",
+                    client_level, server_level
+                ),
+                vec![client_ip.clone(), share.server_ip.clone()],
+                evidence,
+                Some("T0867".to_string()),
+            ));
+        }
+    }
+    findings
+}
+
 /// Check if a protocol name is an OT protocol.
 fn is_ot_protocol_name(name: &str) -> bool {
     matches!(
@@ -383,6 +483,15 @@ fn is_ot_protocol_name(name: &str) -> bool {
             | "foundation_fieldbus"
             | "ge_srtp"
             | "wonderware_suitelink"
+            | "fins"
+            | "slmp"
+            | "codesys"
+            | "niagara_fox"
+            | "crimson"
+            | "vnet_ip"
+            | "tristation"
+            | "ads"
+            | "opc_da"
     )
 }
 
@@ -524,6 +633,7 @@ mod tests {
     fn test_cross_level_violation_l1_l4() {
         let input = AnalysisInput {
             assets: vec![],
+            arp: vec![],
             connections: vec![ConnectionSnapshot {
                 src_ip: "10.0.0.1".to_string(),
                 dst_ip: "192.168.1.50".to_string(),
@@ -560,10 +670,59 @@ mod tests {
             .any(|f| f.technique_id == Some("T0886".to_string())));
     }
 
+    #[test]
+    fn test_smb_write_into_control_zone() {
+        let share = |name: &str, written: Vec<String>| SmbShareSnapshot {
+            server_ip: "10.0.1.20".to_string(),
+            share: format!("\\\\10.0.1.20\\{}", name),
+            files: written.clone(),
+            files_written: written,
+            bytes_written: 0,
+            bytes_read: 0,
+        };
+        let mut input = AnalysisInput::default();
+        input.deep_parse.insert(
+            "192.168.1.50".to_string(),
+            DeepParseSnapshot {
+                smb: Some(SmbSnapshot {
+                    role: "client".to_string(),
+                    shares: vec![
+                        share("IPC$", vec![]),
+                        share("tools", vec!["setup.exe".to_string()]),
+                    ],
+                }),
+                ..Default::default()
+            },
+        );
+        let assignment = |ip: &str, level: u8| PurdueAssignment {
+            ip_address: ip.to_string(),
+            level,
+            method: PurdueMethod::Auto,
+            reason: String::new(),
+        };
+
+        let findings = detect_smb_zone_transfers(
+            &input,
+            &[assignment("192.168.1.50", 4), assignment("10.0.1.20", 2)],
+        );
+        assert_eq!(findings.len(), 1, "IPC$ should be ignored");
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].technique_id.as_deref(), Some("T0867"));
+        assert!(findings[0].evidence.contains("setup.exe"));
+
+        // Same share within one level is not a finding
+        let findings = detect_smb_zone_transfers(
+            &input,
+            &[assignment("192.168.1.50", 2), assignment("10.0.1.20", 2)],
+        );
+        assert!(findings.is_empty());
+    }
+
     #[test]
     fn test_same_level_no_violation() {
         let input = AnalysisInput {
             assets: vec![],
+            arp: vec![],
             connections: vec![ConnectionSnapshot {
                 src_ip: "10.0.0.1".to_string(),
                 dst_ip: "10.0.0.2".to_string(),
@@ -0,0 +1,236 @@
+//! Recurring findings.
+//!
+//! Every analysis run rebuilds its findings from scratch, so a condition
+//! that persists (an unencrypted Modbus master, a default-credential
+//! device) would otherwise come back as a new finding with a new ID each
+//! time. Merging against the previous run's findings keeps one finding per
+//! condition: its ID and first-seen time are kept, and each new detection
+//! inside the dedup window is added to its occurrence history.
+//!
+//! A condition not seen again within the window starts over as a new
+//! finding when it reappears.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::Finding;
+
+/// Detections kept per finding; older ones are dropped, the count is not.
+pub const MAX_OCCURRENCE_HISTORY: usize = 100;
+
+/// Merge `current` findings into the matching `previous` ones.
+///
+/// A current finding matches a previous one with the same
+/// [`Finding::condition_key`] whose last detection is no more than `window`
+/// before `now`. A match takes over the previous ID and first-seen time,
+/// and its count and history continue from the previous finding's.
+/// Returns the number of findings merged. A zero or negative window turns
+/// merging off.
+pub fn merge_recurring_findings(
+    previous: &[Finding],
+    current: &mut [Finding],
+    window: Duration,
+    now: DateTime<Utc>,
+) -> usize {
+    if window <= Duration::zero() {
+        return 0;
+    }
+
+    let mut by_key: HashMap<String, &Finding> = HashMap::new();
+    for prior in previous {
+        if last_detected(prior).is_some_and(|seen| now - seen <= window) {
+            by_key.insert(prior.condition_key(), prior);
+        }
+    }
+
+    let mut merged = 0;
+    for finding in current.iter_mut() {
+        let Some(prior) = by_key.remove(&finding.condition_key()) else {
+            continue;
+        };
+
+        let mut occurrences = prior.occurrences.clone();
+        occurrences.append(&mut finding.occurrences);
+        if occurrences.len() > MAX_OCCURRENCE_HISTORY {
+            occurrences.drain(..occurrences.len() - MAX_OCCURRENCE_HISTORY);
+        }
+
+        finding.id = prior.id.clone();
+        finding.created_at = prior.created_at.clone();
+        finding.occurrence_count = prior.occurrence_count.saturating_add(1);
+        finding.occurrences = occurrences;
+        merged += 1;
+    }
+    merged
+}
+
+/// When a finding was last detected; findings saved before occurrences
+/// were tracked only have a creation time.
+fn last_detected(finding: &Finding) -> Option<DateTime<Utc>> {
+    let seen = if finding.last_seen.is_empty() {
+        &finding.created_at
+    } else {
+        &finding.last_seen
+    };
+    DateTime::parse_from_rfc3339(seen)
+        .ok()
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FindingType, Severity};
+
+    fn finding(title: &str, assets: &[&str], seen_at: &str) -> Finding {
+        let mut f = Finding::new(
+            FindingType::AttackTechnique,
+            Severity::Medium,
+            title.to_string(),
+            "desc".to_string(),
+            assets.iter().map(|a| a.to_string()).collect(),
+            "evidence".to_string(),
+            Some("T0885".to_string()),
+        );
+        f.created_at = seen_at.to_string();
+        f.last_seen = seen_at.to_string();
+        f.occurrences[0].seen_at = seen_at.to_string();
+        f
+    }
+
+    fn at(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_recurrence_within_window_keeps_identity() {
+        let first = finding(
+            "Unencrypted Modbus",
+            &["10.0.0.5", "10.0.0.1"],
+            "2025-06-01T08:00:00+00:00",
+        );
+        let mut current = vec![finding(
+            "Unencrypted Modbus",
+            &["10.0.0.1", "10.0.0.5"],
+            "2025-06-01T20:00:00+00:00",
+        )];
+
+        let merged = merge_recurring_findings(
+            std::slice::from_ref(&first),
+            &mut current,
+            Duration::hours(24),
+            at("2025-06-01T20:00:00Z"),
+        );
+
+        assert_eq!(merged, 1);
+        let f = &current[0];
+        assert_eq!(f.id, first.id);
+        assert_eq!(f.created_at, "2025-06-01T08:00:00+00:00");
+        assert_eq!(f.last_seen, "2025-06-01T20:00:00+00:00");
+        assert_eq!(f.occurrence_count, 2);
+        assert_eq!(f.occurrences.len(), 2);
+        assert_eq!(f.occurrences[0].seen_at, "2025-06-01T08:00:00+00:00");
+    }
+
+    #[test]
+    fn test_recurrence_outside_window_starts_over() {
+        let first = finding(
+            "Unencrypted Modbus",
+            &["10.0.0.1"],
+            "2025-06-01T08:00:00+00:00",
+        );
+        let mut current = vec![finding(
+            "Unencrypted Modbus",
+            &["10.0.0.1"],
+            "2025-06-03T08:00:00+00:00",
+        )];
+        let id = current[0].id.clone();
+
+        let merged = merge_recurring_findings(
+            &[first],
+            &mut current,
+            Duration::hours(24),
+            at("2025-06-03T08:00:00Z"),
+        );
+
+        assert_eq!(merged, 0);
+        assert_eq!(current[0].id, id);
+        assert_eq!(current[0].occurrence_count, 1);
+    }
+
+    #[test]
+    fn test_recurrence_ignores_changing_counts_in_title() {
+        let first = finding(
+            "62% of OT traffic is unencrypted",
+            &[],
+            "2025-06-01T08:00:00+00:00",
+        );
+        let mut current = vec![
+            finding(
+                "71% of OT traffic is unencrypted",
+                &[],
+                "2025-06-01T09:00:00+00:00",
+            ),
+            finding("Default credentials", &[], "2025-06-01T09:00:00+00:00"),
+        ];
+
+        let merged = merge_recurring_findings(
+            &[first],
+            &mut current,
+            Duration::hours(24),
+            at("2025-06-01T09:00:00Z"),
+        );
+
+        assert_eq!(merged, 1);
+        assert_eq!(current[0].occurrence_count, 2);
+        assert_eq!(current[1].occurrence_count, 1);
+    }
+
+    #[test]
+    fn test_recurrence_history_is_capped() {
+        let mut first = finding("Unencrypted Modbus", &[], "2025-06-01T08:00:00+00:00");
+        first.occurrence_count = 250;
+        first.occurrences = vec![first.occurrences[0].clone(); MAX_OCCURRENCE_HISTORY];
+        let mut current = vec![finding(
+            "Unencrypted Modbus",
+            &[],
+            "2025-06-01T09:00:00+00:00",
+        )];
+
+        merge_recurring_findings(
+            &[first],
+            &mut current,
+            Duration::hours(1),
+            at("2025-06-01T09:00:00Z"),
+        );
+
+        assert_eq!(current[0].occurrence_count, 251);
+        assert_eq!(current[0].occurrences.len(), MAX_OCCURRENCE_HISTORY);
+        assert_eq!(
+            current[0].occurrences.last().unwrap().seen_at,
+            "2025-06-01T09:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_recurrence_disabled_by_zero_window() {
+        let first = finding("Unencrypted Modbus", &[], "2025-06-01T08:00:00+00:00");
+        let mut current = vec![finding(
+            "Unencrypted Modbus",
+            &[],
+            "2025-06-01T08:00:01+00:00",
+        )];
+        assert_eq!(
+            merge_recurring_findings(
+                &[first],
+                &mut current,
+                Duration::zero(),
+                at("2025-06-01T08:00:01Z")
+            ),
+            0
+        );
+    }
+}
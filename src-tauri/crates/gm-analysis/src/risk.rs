@@ -4,6 +4,8 @@
 //! protocols, and Purdue level assignment. Used for remediation
 //! prioritization in the assessment report.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Criticality level for an ICS asset.
@@ -27,6 +29,16 @@ impl CriticalityLevel {
             CriticalityLevel::Unknown => "unknown",
         }
     }
+
+    /// One level higher, saturating at Critical. Unknown stays Unknown.
+    fn raised(self) -> Self {
+        match self {
+            CriticalityLevel::Low => CriticalityLevel::Medium,
+            CriticalityLevel::Medium => CriticalityLevel::High,
+            CriticalityLevel::High | CriticalityLevel::Critical => CriticalityLevel::Critical,
+            CriticalityLevel::Unknown => CriticalityLevel::Unknown,
+        }
+    }
 }
 
 /// Criticality assessment for a single device.
@@ -130,6 +142,23 @@ pub fn assess_all(assets: &[crate::AssetSnapshot]) -> Vec<CriticalityAssessment>
         .collect()
 }
 
+/// Raise devices the rest of the network depends on by one level.
+///
+/// `choke_points` maps the IP of each choke-point device (one many others
+/// talk through, or that talks to many others) to a note on why, which is
+/// appended to the reason.
+pub fn raise_for_choke_points(
+    assessments: &mut [CriticalityAssessment],
+    choke_points: &HashMap<String, String>,
+) {
+    for assessment in assessments {
+        if let Some(note) = choke_points.get(&assessment.ip_address) {
+            assessment.level = assessment.level.raised();
+            assessment.reason = format!("{}; choke point ({})", assessment.reason, note);
+        }
+    }
+}
+
 fn build_reason(
     role: &str,
     _protocols: &[String],
@@ -216,6 +245,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_choke_point_raises_one_level() {
+        let mut assessments = vec![
+            CriticalityAssessment {
+                ip_address: "10.0.0.5".to_string(),
+                level: CriticalityLevel::High,
+                reason: "Supervisory device (hmi)".to_string(),
+            },
+            CriticalityAssessment {
+                ip_address: "10.0.0.9".to_string(),
+                level: CriticalityLevel::Low,
+                reason: "Purdue Level 4".to_string(),
+            },
+        ];
+        let choke_points =
+            HashMap::from([("10.0.0.5".to_string(), "talks to 12 devices".to_string())]);
+        raise_for_choke_points(&mut assessments, &choke_points);
+        assert_eq!(assessments[0].level, CriticalityLevel::Critical);
+        assert!(assessments[0]
+            .reason
+            .ends_with("choke point (talks to 12 devices)"));
+        assert_eq!(assessments[1].level, CriticalityLevel::Low);
+    }
+
     #[test]
     fn test_assess_all_empty() {
         let result = assess_all(&[]);
@@ -0,0 +1,721 @@
+//! Connection-oriented session state for Modbus/TCP and DNP3 over TCP.
+//!
+//! `SessionTracker` follows each TCP session through
+//! open → identified → request-outstanding ⇄ response-matched → torn down,
+//! using TCP flags for setup/teardown and the parsed PDU direction for the
+//! request/response cycle. From that state it reports:
+//!
+//! - requests that never got a response (within `response_timeout_secs`,
+//!   or before the session was torn down),
+//! - half-open sessions (SYN never answered, or only one side closed),
+//! - mid-stream protocol switches (one TCP session carrying more than one
+//!   ICS protocol), and
+//! - per-device session counts as a health metric.
+//!
+//! Like `PatternAnalyzer`, the tracker is O(1) per packet and stores no
+//! packet data; sessions are summarised on demand.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::comm_patterns::{PatternAnomaly, PatternAnomalyType};
+
+// TCP flag bits (same layout as gm_capture::tcp_flags)
+const FIN: u8 = 0x01;
+const SYN: u8 = 0x02;
+const RST: u8 = 0x04;
+const ACK: u8 = 0x10;
+
+/// Default time a request may stay unanswered before it counts as lost.
+pub const DEFAULT_RESPONSE_TIMEOUT_SECS: f64 = 5.0;
+
+/// Protocol-level state of a TCP session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    /// TCP traffic seen, no protocol PDU yet
+    Open,
+    /// A PDU identified the protocol, but no request/response cycle yet
+    Identified,
+    /// At least one request is waiting for its response
+    RequestOutstanding,
+    /// Every request so far has been answered
+    ResponseMatched,
+    /// Closed by FIN from both sides or by RST
+    TornDown,
+}
+
+/// Direction of a parsed PDU within the request/response cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionMessage {
+    /// Expects a response (Modbus request, DNP3 READ/OPERATE/...)
+    Request,
+    /// Answers a request (Modbus response, DNP3 RESPONSE)
+    Response,
+    /// Neither (DNP3 CONFIRM, unsolicited responses, link-layer frames)
+    Other,
+}
+
+/// The parsed application PDUs carried by a TCP segment.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionPdu<'a> {
+    /// Protocol name, e.g. "Modbus" or "Dnp3"
+    pub protocol: &'a str,
+    pub message: SessionMessage,
+    /// Number of PDUs in the segment (pipelined Modbus packs several)
+    pub count: u32,
+}
+
+/// One TCP segment as seen by the tracker.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionPacket<'a> {
+    pub src_ip: &'a str,
+    pub src_port: u16,
+    pub dst_ip: &'a str,
+    pub dst_port: u16,
+    /// TCP flag bits (FIN=0x01, SYN=0x02, RST=0x04, ACK=0x10)
+    pub tcp_flags: u8,
+    /// The PDU in this segment, if it parsed as a tracked protocol
+    pub pdu: Option<SessionPdu<'a>>,
+    pub timestamp_secs: f64,
+}
+
+/// Summary of one TCP session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolSession {
+    pub client_ip: String,
+    pub client_port: u16,
+    pub server_ip: String,
+    pub server_port: u16,
+    /// Protocols in the order they appeared; more than one is a mid-stream switch
+    pub protocols: Vec<String>,
+    pub state: SessionState,
+    /// Whether the TCP handshake was captured (false for mid-stream sessions)
+    pub handshake_seen: bool,
+    /// Whether the server side sent anything at all
+    pub server_responded: bool,
+    pub requests: u64,
+    pub responses: u64,
+    /// Requests that timed out or were still pending at teardown
+    pub unanswered_requests: u64,
+    /// Responses with no outstanding request
+    pub unmatched_responses: u64,
+    /// SYN never answered, or only one side sent FIN
+    pub half_open: bool,
+    pub packet_count: u64,
+    pub first_seen: f64,
+    pub last_seen: f64,
+}
+
+/// Per-device session health counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceSessionHealth {
+    pub ip: String,
+    pub total_sessions: u64,
+    /// Sessions not torn down by the end of the capture
+    pub active_sessions: u64,
+    pub torn_down_sessions: u64,
+    pub half_open_sessions: u64,
+    /// Sessions with at least one unanswered request
+    pub sessions_with_unanswered: u64,
+    pub unanswered_requests: u64,
+    pub protocol_switches: u64,
+}
+
+type Endpoint = (String, u16);
+
+/// Per-session accumulator.
+struct SessionEntry {
+    client: Endpoint,
+    server: Endpoint,
+    protocols: Vec<String>,
+    state: SessionState,
+    syn_seen: bool,
+    server_seen: bool,
+    client_fin: bool,
+    server_fin: bool,
+    rst: bool,
+    requests: u64,
+    responses: u64,
+    unanswered: u64,
+    unmatched_responses: u64,
+    /// Requests currently awaiting a response (Modbus/TCP allows pipelining)
+    outstanding: u64,
+    /// Timestamp of the oldest outstanding request
+    outstanding_since: Option<f64>,
+    packet_count: u64,
+    first_seen: f64,
+    last_seen: f64,
+}
+
+impl SessionEntry {
+    fn new(client: Endpoint, server: Endpoint, timestamp_secs: f64) -> Self {
+        Self {
+            client,
+            server,
+            protocols: Vec::new(),
+            state: SessionState::Open,
+            syn_seen: false,
+            server_seen: false,
+            client_fin: false,
+            server_fin: false,
+            rst: false,
+            requests: 0,
+            responses: 0,
+            unanswered: 0,
+            unmatched_responses: 0,
+            outstanding: 0,
+            outstanding_since: None,
+            packet_count: 0,
+            first_seen: timestamp_secs,
+            last_seen: timestamp_secs,
+        }
+    }
+
+    fn update(&mut self, pkt: &SessionPacket, timeout: f64) {
+        let from_client = self.client.0 == pkt.src_ip && self.client.1 == pkt.src_port;
+        self.packet_count += 1;
+        self.last_seen = self.last_seen.max(pkt.timestamp_secs);
+        if from_client {
+            if pkt.tcp_flags & SYN != 0 {
+                self.syn_seen = true;
+            }
+        } else {
+            self.server_seen = true;
+        }
+
+        // Retransmissions after teardown do not reopen the cycle
+        if let Some(pdu) = pkt.pdu.filter(|_| self.state != SessionState::TornDown) {
+            self.record_pdu(pdu, pkt.timestamp_secs, timeout);
+        }
+
+        if pkt.tcp_flags & RST != 0 {
+            self.rst = true;
+            self.tear_down();
+        } else if pkt.tcp_flags & FIN != 0 {
+            if from_client {
+                self.client_fin = true;
+            } else {
+                self.server_fin = true;
+            }
+            if self.client_fin && self.server_fin {
+                self.tear_down();
+            }
+        }
+    }
+
+    fn record_pdu(&mut self, pdu: SessionPdu, timestamp_secs: f64, timeout: f64) {
+        if self.protocols.last().map(String::as_str) != Some(pdu.protocol) {
+            self.protocols.push(pdu.protocol.to_string());
+        }
+        if self.state == SessionState::Open {
+            self.state = SessionState::Identified;
+        }
+
+        match pdu.message {
+            SessionMessage::Request => {
+                // Anything still pending past the timeout is not coming back
+                if self
+                    .outstanding_since
+                    .is_some_and(|since| timestamp_secs - since > timeout)
+                {
+                    self.unanswered += self.outstanding;
+                    self.outstanding = 0;
+                    self.outstanding_since = None;
+                }
+                self.requests += u64::from(pdu.count);
+                self.outstanding += u64::from(pdu.count);
+                self.outstanding_since.get_or_insert(timestamp_secs);
+                self.state = SessionState::RequestOutstanding;
+            }
+            SessionMessage::Response => {
+                for _ in 0..pdu.count {
+                    self.responses += 1;
+                    if self.outstanding == 0 {
+                        self.unmatched_responses += 1;
+                    } else {
+                        self.outstanding -= 1;
+                        if self.outstanding == 0 {
+                            self.outstanding_since = None;
+                            self.state = SessionState::ResponseMatched;
+                        }
+                    }
+                }
+            }
+            SessionMessage::Other => {}
+        }
+    }
+
+    fn tear_down(&mut self) {
+        self.unanswered += self.outstanding;
+        self.outstanding = 0;
+        self.outstanding_since = None;
+        self.state = SessionState::TornDown;
+    }
+
+    fn summary(&self, capture_end: f64, timeout: f64) -> ProtocolSession {
+        let timed_out = self
+            .outstanding_since
+            .is_some_and(|since| capture_end - since > timeout);
+        let unanswered = self.unanswered + if timed_out { self.outstanding } else { 0 };
+        let half_open = !self.rst
+            && ((self.syn_seen && !self.server_seen) || self.client_fin != self.server_fin);
+
+        ProtocolSession {
+            client_ip: self.client.0.clone(),
+            client_port: self.client.1,
+            server_ip: self.server.0.clone(),
+            server_port: self.server.1,
+            protocols: self.protocols.clone(),
+            state: self.state,
+            handshake_seen: self.syn_seen,
+            server_responded: self.server_seen,
+            requests: self.requests,
+            responses: self.responses,
+            unanswered_requests: unanswered,
+            unmatched_responses: self.unmatched_responses,
+            half_open,
+            packet_count: self.packet_count,
+            first_seen: self.first_seen,
+            last_seen: self.last_seen,
+        }
+    }
+}
+
+// Internal map key: both endpoints in sorted order, so either direction finds the session
+type SessionKey = (Endpoint, Endpoint);
+
+fn session_key(pkt: &SessionPacket) -> SessionKey {
+    let a = (pkt.src_ip.to_string(), pkt.src_port);
+    let b = (pkt.dst_ip.to_string(), pkt.dst_port);
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Which side of the first packet seen is the client.
+fn sender_is_client(pkt: &SessionPacket) -> bool {
+    if pkt.tcp_flags & SYN != 0 {
+        return pkt.tcp_flags & ACK == 0;
+    }
+    match pkt.pdu.map(|p| p.message) {
+        Some(SessionMessage::Request) => true,
+        Some(SessionMessage::Response) => false,
+        // Mid-stream with no hint: the well-known (lower) port is the server
+        _ => pkt.src_port > pkt.dst_port,
+    }
+}
+
+/// Tracks protocol state per TCP session.
+///
+/// # Usage
+/// ```
+/// use gm_analysis::sessions::{SessionMessage, SessionPacket, SessionPdu, SessionTracker};
+///
+/// let mut tracker = SessionTracker::new();
+/// let request = SessionPacket {
+///     src_ip: "10.0.0.1",
+///     src_port: 49152,
+///     dst_ip: "10.0.0.2",
+///     dst_port: 502,
+///     tcp_flags: 0x18,
+///     pdu: Some(SessionPdu { protocol: "Modbus", message: SessionMessage::Request, count: 1 }),
+///     timestamp_secs: 0.0,
+/// };
+/// tracker.record_packet(&request);
+/// assert_eq!(tracker.sessions()[0].requests, 1);
+/// ```
+pub struct SessionTracker {
+    sessions: HashMap<SessionKey, SessionEntry>,
+    /// Sessions torn down and then replaced by a new one on the same 4-tuple
+    closed: Vec<SessionEntry>,
+    response_timeout_secs: f64,
+    capture_end: f64,
+}
+
+impl SessionTracker {
+    pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_RESPONSE_TIMEOUT_SECS)
+    }
+
+    /// Create a tracker that counts requests as unanswered after `secs`.
+    pub fn with_timeout(secs: f64) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            closed: Vec::new(),
+            response_timeout_secs: secs,
+            capture_end: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Record a single TCP segment. O(1).
+    pub fn record_packet(&mut self, pkt: &SessionPacket) {
+        self.capture_end = self.capture_end.max(pkt.timestamp_secs);
+        let key = session_key(pkt);
+
+        // A fresh SYN on a torn-down 4-tuple starts a new session
+        let reopened = pkt.tcp_flags & SYN != 0
+            && pkt.tcp_flags & ACK == 0
+            && self
+                .sessions
+                .get(&key)
+                .is_some_and(|s| s.state == SessionState::TornDown);
+        if reopened {
+            if let Some(old) = self.sessions.remove(&key) {
+                self.closed.push(old);
+            }
+        }
+
+        let entry = self.sessions.entry(key).or_insert_with(|| {
+            let src = (pkt.src_ip.to_string(), pkt.src_port);
+            let dst = (pkt.dst_ip.to_string(), pkt.dst_port);
+            if sender_is_client(pkt) {
+                SessionEntry::new(src, dst, pkt.timestamp_secs)
+            } else {
+                SessionEntry::new(dst, src, pkt.timestamp_secs)
+            }
+        });
+        entry.update(pkt, self.response_timeout_secs);
+    }
+
+    /// Summarise every session seen, ordered by first appearance.
+    ///
+    /// Requests still pending longer than the timeout at the end of the
+    /// capture count as unanswered.
+    pub fn sessions(&self) -> Vec<ProtocolSession> {
+        let mut result: Vec<ProtocolSession> = self
+            .closed
+            .iter()
+            .chain(self.sessions.values())
+            .map(|s| s.summary(self.capture_end, self.response_timeout_secs))
+            .collect();
+        result.sort_by(|a, b| a.first_seen.total_cmp(&b.first_seen));
+        result
+    }
+
+    /// Per-device session counts. Each session counts for both its client
+    /// and its server. Sorted by IP.
+    pub fn device_health(sessions: &[ProtocolSession]) -> Vec<DeviceSessionHealth> {
+        let mut by_ip: HashMap<&str, DeviceSessionHealth> = HashMap::new();
+        for s in sessions {
+            for ip in [s.client_ip.as_str(), s.server_ip.as_str()] {
+                let h = by_ip.entry(ip).or_insert_with(|| DeviceSessionHealth {
+                    ip: ip.to_string(),
+                    ..Default::default()
+                });
+                h.total_sessions += 1;
+                if s.state == SessionState::TornDown {
+                    h.torn_down_sessions += 1;
+                } else {
+                    h.active_sessions += 1;
+                }
+                if s.half_open {
+                    h.half_open_sessions += 1;
+                }
+                if s.unanswered_requests > 0 {
+                    h.sessions_with_unanswered += 1;
+                    h.unanswered_requests += s.unanswered_requests;
+                }
+                if s.protocols.len() > 1 {
+                    h.protocol_switches += 1;
+                }
+            }
+        }
+        let mut result: Vec<DeviceSessionHealth> = by_ip.into_values().collect();
+        result.sort_by(|a, b| a.ip.cmp(&b.ip));
+        result
+    }
+
+    /// Report unanswered requests, half-open sessions, and protocol switches.
+    pub fn detect_anomalies(sessions: &[ProtocolSession]) -> Vec<PatternAnomaly> {
+        let mut anomalies = Vec::new();
+
+        for s in sessions {
+            let protocol = s.protocols.first().cloned().unwrap_or_default();
+            let anomaly = |anomaly_type, description, severity: &str| PatternAnomaly {
+                anomaly_type,
+                src_ip: s.client_ip.clone(),
+                dst_ip: s.server_ip.clone(),
+                port: s.server_port,
+                protocol: protocol.clone(),
+                description,
+                severity: severity.to_string(),
+            };
+
+            // ProtocolSwitch: one TCP session should carry one protocol
+            if s.protocols.len() > 1 {
+                anomalies.push(anomaly(
+                    PatternAnomalyType::ProtocolSwitch,
+                    format!(
+                        "Session {}:{} → {}:{} switched protocol mid-stream ({})",
+                        s.client_ip,
+                        s.client_port,
+                        s.server_ip,
+                        s.server_port,
+                        s.protocols.join(" → ")
+                    ),
+                    "high",
+                ));
+            }
+
+            // UnansweredRequests: the server did not respond
+            if s.unanswered_requests > 0 {
+                anomalies.push(anomaly(
+                    PatternAnomalyType::UnansweredRequests,
+                    format!(
+                        "{} of {} {} requests from {} to {}:{} went unanswered",
+                        s.unanswered_requests,
+                        s.requests,
+                        protocol,
+                        s.client_ip,
+                        s.server_ip,
+                        s.server_port
+                    ),
+                    "medium",
+                ));
+            }
+
+            // HalfOpenSession: handshake or teardown never completed
+            if s.half_open {
+                let detail = if s.handshake_seen && !s.server_responded {
+                    "connection attempt was never answered"
+                } else {
+                    "only one side closed the connection"
+                };
+                anomalies.push(anomaly(
+                    PatternAnomalyType::HalfOpenSession,
+                    format!(
+                        "Half-open session {}:{} → {}:{}: {}",
+                        s.client_ip, s.client_port, s.server_ip, s.server_port, detail
+                    ),
+                    "low",
+                ));
+            }
+        }
+
+        anomalies
+    }
+}
+
+impl Default for SessionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PSH_ACK: u8 = 0x18;
+
+    fn segment(
+        from_client: bool,
+        tcp_flags: u8,
+        message: Option<SessionMessage>,
+        timestamp_secs: f64,
+    ) -> SessionPacket<'static> {
+        let (src, dst) = if from_client {
+            (("10.0.0.1", 49152), ("10.0.0.2", 502))
+        } else {
+            (("10.0.0.2", 502), ("10.0.0.1", 49152))
+        };
+        SessionPacket {
+            src_ip: src.0,
+            src_port: src.1,
+            dst_ip: dst.0,
+            dst_port: dst.1,
+            tcp_flags,
+            pdu: message.map(|message| SessionPdu {
+                protocol: "Modbus",
+                message,
+                count: 1,
+            }),
+            timestamp_secs,
+        }
+    }
+
+    #[test]
+    fn test_full_lifecycle() {
+        let mut tracker = SessionTracker::new();
+        tracker.record_packet(&segment(true, SYN, None, 0.0));
+        tracker.record_packet(&segment(false, SYN | ACK, None, 0.001));
+        tracker.record_packet(&segment(true, ACK, None, 0.002));
+        assert_eq!(tracker.sessions()[0].state, SessionState::Open);
+
+        tracker.record_packet(&segment(true, PSH_ACK, Some(SessionMessage::Request), 0.1));
+        assert_eq!(
+            tracker.sessions()[0].state,
+            SessionState::RequestOutstanding
+        );
+        tracker.record_packet(&segment(
+            false,
+            PSH_ACK,
+            Some(SessionMessage::Response),
+            0.2,
+        ));
+        assert_eq!(tracker.sessions()[0].state, SessionState::ResponseMatched);
+
+        tracker.record_packet(&segment(true, FIN | ACK, None, 1.0));
+        tracker.record_packet(&segment(false, FIN | ACK, None, 1.001));
+
+        let sessions = tracker.sessions();
+        assert_eq!(sessions.len(), 1);
+        let s = &sessions[0];
+        assert_eq!(s.state, SessionState::TornDown);
+        assert_eq!(s.client_ip, "10.0.0.1");
+        assert_eq!(s.server_port, 502);
+        assert!(s.handshake_seen);
+        assert!(!s.half_open);
+        assert_eq!((s.requests, s.responses, s.unanswered_requests), (1, 1, 0));
+        assert!(SessionTracker::detect_anomalies(&sessions).is_empty());
+    }
+
+    #[test]
+    fn test_unanswered_requests() {
+        let mut tracker = SessionTracker::with_timeout(1.0);
+        // First request never answered; second one (after the timeout) is
+        tracker.record_packet(&segment(true, PSH_ACK, Some(SessionMessage::Request), 0.0));
+        tracker.record_packet(&segment(true, PSH_ACK, Some(SessionMessage::Request), 5.0));
+        tracker.record_packet(&segment(
+            false,
+            PSH_ACK,
+            Some(SessionMessage::Response),
+            5.1,
+        ));
+        // Third is still pending at teardown
+        tracker.record_packet(&segment(true, PSH_ACK, Some(SessionMessage::Request), 6.0));
+        tracker.record_packet(&segment(false, RST, None, 6.5));
+
+        let sessions = tracker.sessions();
+        let s = &sessions[0];
+        assert!(!s.handshake_seen);
+        assert_eq!(s.state, SessionState::TornDown);
+        assert_eq!(s.requests, 3);
+        assert_eq!(s.unanswered_requests, 2);
+
+        let anomalies = SessionTracker::detect_anomalies(&sessions);
+        assert_eq!(anomalies.len(), 1);
+        assert!(matches!(
+            anomalies[0].anomaly_type,
+            PatternAnomalyType::UnansweredRequests
+        ));
+    }
+
+    #[test]
+    fn test_pipelined_requests_within_timeout() {
+        let mut tracker = SessionTracker::with_timeout(1.0);
+        tracker.record_packet(&segment(true, PSH_ACK, Some(SessionMessage::Request), 0.0));
+        tracker.record_packet(&segment(true, PSH_ACK, Some(SessionMessage::Request), 0.1));
+        tracker.record_packet(&segment(
+            false,
+            PSH_ACK,
+            Some(SessionMessage::Response),
+            0.2,
+        ));
+        assert_eq!(
+            tracker.sessions()[0].state,
+            SessionState::RequestOutstanding
+        );
+        tracker.record_packet(&segment(
+            false,
+            PSH_ACK,
+            Some(SessionMessage::Response),
+            0.3,
+        ));
+        let s = &tracker.sessions()[0];
+        assert_eq!(s.state, SessionState::ResponseMatched);
+        assert_eq!(s.unanswered_requests, 0);
+        assert_eq!(s.unmatched_responses, 0);
+    }
+
+    #[test]
+    fn test_multiple_pdus_per_segment() {
+        let mut tracker = SessionTracker::with_timeout(1.0);
+        let mut requests = segment(true, PSH_ACK, Some(SessionMessage::Request), 0.0);
+        requests.pdu = requests.pdu.map(|p| SessionPdu { count: 3, ..p });
+        tracker.record_packet(&requests);
+        tracker.record_packet(&segment(
+            false,
+            PSH_ACK,
+            Some(SessionMessage::Response),
+            0.1,
+        ));
+        assert_eq!(tracker.sessions()[0].requests, 3);
+
+        let mut responses = segment(false, PSH_ACK, Some(SessionMessage::Response), 0.2);
+        responses.pdu = responses.pdu.map(|p| SessionPdu { count: 2, ..p });
+        tracker.record_packet(&responses);
+        let s = &tracker.sessions()[0];
+        assert_eq!(s.responses, 3);
+        assert_eq!(s.state, SessionState::ResponseMatched);
+        assert_eq!(s.unmatched_responses, 0);
+    }
+
+    #[test]
+    fn test_half_open() {
+        let mut tracker = SessionTracker::new();
+        // SYN with no answer
+        tracker.record_packet(&segment(true, SYN, None, 0.0));
+        tracker.record_packet(&segment(true, SYN, None, 1.0));
+        let sessions = tracker.sessions();
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].half_open);
+
+        // Only the client closes
+        let mut tracker = SessionTracker::new();
+        tracker.record_packet(&segment(
+            false,
+            PSH_ACK,
+            Some(SessionMessage::Response),
+            0.0,
+        ));
+        tracker.record_packet(&segment(true, FIN | ACK, None, 1.0));
+        let sessions = tracker.sessions();
+        assert!(sessions[0].half_open);
+        assert_eq!(sessions[0].client_ip, "10.0.0.1");
+        assert_eq!(sessions[0].unmatched_responses, 1);
+        let anomalies = SessionTracker::detect_anomalies(&sessions);
+        assert!(matches!(
+            anomalies[0].anomaly_type,
+            PatternAnomalyType::HalfOpenSession
+        ));
+    }
+
+    #[test]
+    fn test_protocol_switch_and_reopen() {
+        let mut tracker = SessionTracker::new();
+        tracker.record_packet(&segment(true, PSH_ACK, Some(SessionMessage::Request), 0.0));
+        let mut dnp3 = segment(true, PSH_ACK, Some(SessionMessage::Other), 0.5);
+        dnp3.pdu = Some(SessionPdu {
+            protocol: "Dnp3",
+            message: SessionMessage::Other,
+            count: 1,
+        });
+        tracker.record_packet(&dnp3);
+        tracker.record_packet(&segment(true, RST, None, 0.6));
+        // Same 4-tuple, new connection
+        tracker.record_packet(&segment(true, SYN, None, 2.0));
+
+        let sessions = tracker.sessions();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].protocols, vec!["Modbus", "Dnp3"]);
+        assert_eq!(sessions[1].state, SessionState::Open);
+
+        let anomalies = SessionTracker::detect_anomalies(&sessions);
+        assert!(anomalies
+            .iter()
+            .any(|a| matches!(a.anomaly_type, PatternAnomalyType::ProtocolSwitch)));
+
+        let health = SessionTracker::device_health(&sessions);
+        assert_eq!(health.len(), 2);
+        let server = health.iter().find(|h| h.ip == "10.0.0.2").unwrap();
+        assert_eq!(server.total_sessions, 2);
+        assert_eq!(server.torn_down_sessions, 1);
+        assert_eq!(server.active_sessions, 1);
+        assert_eq!(server.protocol_switches, 1);
+        assert_eq!(server.sessions_with_unanswered, 1);
+    }
+}
@@ -0,0 +1,512 @@
+//! Shift analysis: traffic sliced by recurring time-of-day windows.
+//!
+//! Whole-capture aggregates hide behavior that follows the plant schedule:
+//! a master that only polls on night shift, writes that only happen at the
+//! weekend. `ShiftActivity` buckets every packet into one of the 672
+//! quarter hours of the week as it arrives, so any set of shift windows can
+//! be applied afterwards without re-reading the capture.
+//!
+//! Buckets are kept in UTC; the site's UTC offset is applied when the
+//! report is built, rounded to the nearest quarter hour.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Width of one bucket.
+const SLOT_MINUTES: u16 = 15;
+const SLOTS_PER_DAY: usize = 96;
+const SLOTS_PER_WEEK: usize = 7 * SLOTS_PER_DAY;
+/// 1970-01-01 was a Thursday; days are counted from Monday.
+const EPOCH_WEEKDAY: i64 = 3;
+/// Name of the profile collecting traffic no window covers.
+pub const OUTSIDE_SHIFTS: &str = "Outside shifts";
+
+/// A recurring time-of-day window, e.g. night shift 22:00–06:00 Mon–Fri.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShiftWindow {
+    pub name: String,
+    /// Days the shift starts on, 0 = Monday … 6 = Sunday
+    pub days: Vec<u8>,
+    /// Start, in minutes after local midnight
+    pub start_minute: u16,
+    /// End, in minutes after local midnight. At or before `start_minute`
+    /// the shift runs past midnight into the next day.
+    pub end_minute: u16,
+}
+
+impl ShiftWindow {
+    /// Whether the shift covers a local week slot.
+    fn covers(&self, slot: usize) -> bool {
+        let day = (slot / SLOTS_PER_DAY) as u8;
+        let prev_day = (day + 6) % 7;
+        let minute = (slot % SLOTS_PER_DAY) as u16 * SLOT_MINUTES;
+        if self.start_minute < self.end_minute {
+            self.days.contains(&day) && (self.start_minute..self.end_minute).contains(&minute)
+        } else {
+            (self.days.contains(&day) && minute >= self.start_minute)
+                || (self.days.contains(&prev_day) && minute < self.end_minute)
+        }
+    }
+}
+
+/// A common three-shift weekday schedule with the weekend kept apart.
+///
+/// The weekend comes first so it claims Friday night's hours after
+/// midnight.
+pub fn default_shifts() -> Vec<ShiftWindow> {
+    let window = |name: &str, days: &[u8], start: u16, end: u16| ShiftWindow {
+        name: name.to_string(),
+        days: days.to_vec(),
+        start_minute: start,
+        end_minute: end,
+    };
+    let weekdays = [0, 1, 2, 3, 4];
+    vec![
+        window("Weekend", &[5, 6], 0, 0),
+        window("Day", &weekdays, 6 * 60, 14 * 60),
+        window("Swing", &weekdays, 14 * 60, 22 * 60),
+        window("Night", &weekdays, 22 * 60, 6 * 60),
+    ]
+}
+
+/// Traffic in one quarter hour of the week, summed over every week.
+#[derive(Debug, Clone, Default)]
+struct SlotActivity {
+    packets: u64,
+    bytes: u64,
+    /// protocol → (packets, bytes)
+    protocols: HashMap<String, (u64, u64)>,
+    /// master IP → request packets
+    masters: HashMap<String, u64>,
+    /// (master, target, protocol) → write requests
+    writes: HashMap<(String, String, String), u64>,
+}
+
+/// Per-packet accumulator behind the shift report.
+#[derive(Debug, Clone, Default)]
+pub struct ShiftActivity {
+    /// UTC week slot → traffic
+    slots: HashMap<usize, SlotActivity>,
+    /// Quarter hours since the epoch that carried any traffic, so gaps
+    /// between capture files do not count as observed time
+    quarters: HashSet<i64>,
+}
+
+impl ShiftActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quarters.is_empty()
+    }
+
+    /// Record one packet.
+    ///
+    /// `master` is the requesting host when the packet is a request to an
+    /// OT server; `write_target` is the server when that request writes.
+    pub fn record_packet(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        protocol: &str,
+        bytes: u64,
+        master: Option<&str>,
+        write_target: Option<&str>,
+    ) {
+        let quarter = timestamp
+            .timestamp()
+            .div_euclid(i64::from(SLOT_MINUTES) * 60);
+        self.quarters.insert(quarter);
+
+        let slot = self.slots.entry(week_slot(quarter)).or_default();
+        slot.packets += 1;
+        slot.bytes += bytes;
+        let volume = slot.protocols.entry(protocol.to_string()).or_default();
+        volume.0 += 1;
+        volume.1 += bytes;
+        if let Some(master) = master {
+            *slot.masters.entry(master.to_string()).or_default() += 1;
+            if let Some(target) = write_target {
+                *slot
+                    .writes
+                    .entry((master.to_string(), target.to_string(), protocol.to_string()))
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    /// Slice the recorded traffic by `shifts` and compare them.
+    ///
+    /// Each local quarter hour belongs to the first window covering it;
+    /// quarters no window covers go to an [`OUTSIDE_SHIFTS`] profile, which
+    /// is only listed when it saw traffic.
+    pub fn report(&self, shifts: &[ShiftWindow], utc_offset_minutes: i32) -> ShiftReport {
+        let offset_slots = (f64::from(utc_offset_minutes) / f64::from(SLOT_MINUTES)).round() as i64;
+        let outside = shifts.len();
+        let shift_of = |utc_slot: usize| -> usize {
+            let local = (utc_slot as i64 + offset_slots).rem_euclid(SLOTS_PER_WEEK as i64);
+            shifts
+                .iter()
+                .position(|s| s.covers(local as usize))
+                .unwrap_or(outside)
+        };
+
+        let mut acc: Vec<ProfileAccumulator> = vec![ProfileAccumulator::default(); outside + 1];
+        for &quarter in &self.quarters {
+            acc[shift_of(week_slot(quarter))].quarters += 1;
+        }
+        for (&utc_slot, slot) in &self.slots {
+            acc[shift_of(utc_slot)].add(slot);
+        }
+
+        let names = shifts
+            .iter()
+            .map(|s| s.name.as_str())
+            .chain(std::iter::once(OUTSIDE_SHIFTS));
+        let mut profiles: Vec<ShiftProfile> = names
+            .zip(acc)
+            .enumerate()
+            .filter(|(i, (_, a))| *i < outside || a.packets > 0)
+            .map(|(_, (name, a))| a.into_profile(name))
+            .collect();
+
+        let exclusive = exclusive_activity(&profiles);
+        for profile in &mut profiles {
+            profile.protocols.sort_by(|a, b| {
+                b.packets
+                    .cmp(&a.packets)
+                    .then_with(|| a.protocol.cmp(&b.protocol))
+            });
+            profile
+                .masters
+                .sort_by(|a, b| b.packets.cmp(&a.packets).then_with(|| a.ip.cmp(&b.ip)));
+            profile
+                .writes
+                .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.master.cmp(&b.master)));
+        }
+
+        ShiftReport {
+            utc_offset_minutes,
+            shifts: profiles,
+            exclusive,
+        }
+    }
+}
+
+/// Week slot (0 = Monday 00:00) of a quarter hour since the epoch.
+fn week_slot(quarter: i64) -> usize {
+    let day = (quarter.div_euclid(SLOTS_PER_DAY as i64) + EPOCH_WEEKDAY).rem_euclid(7);
+    day as usize * SLOTS_PER_DAY + quarter.rem_euclid(SLOTS_PER_DAY as i64) as usize
+}
+
+/// Slot traffic summed for one shift.
+#[derive(Clone, Default)]
+struct ProfileAccumulator {
+    quarters: u64,
+    packets: u64,
+    bytes: u64,
+    protocols: BTreeMap<String, (u64, u64)>,
+    masters: BTreeMap<String, u64>,
+    writes: BTreeMap<(String, String, String), u64>,
+}
+
+impl ProfileAccumulator {
+    fn add(&mut self, slot: &SlotActivity) {
+        self.packets += slot.packets;
+        self.bytes += slot.bytes;
+        for (protocol, &(packets, bytes)) in &slot.protocols {
+            let volume = self.protocols.entry(protocol.clone()).or_default();
+            volume.0 += packets;
+            volume.1 += bytes;
+        }
+        for (master, &packets) in &slot.masters {
+            *self.masters.entry(master.clone()).or_default() += packets;
+        }
+        for (path, &count) in &slot.writes {
+            *self.writes.entry(path.clone()).or_default() += count;
+        }
+    }
+
+    fn into_profile(self, name: &str) -> ShiftProfile {
+        let observed_hours = self.quarters as f64 * f64::from(SLOT_MINUTES) / 60.0;
+        let per_hour = |count: u64| {
+            if observed_hours > 0.0 {
+                count as f64 / observed_hours
+            } else {
+                0.0
+            }
+        };
+        ShiftProfile {
+            name: name.to_string(),
+            observed_hours,
+            packets: self.packets,
+            bytes: self.bytes,
+            packets_per_hour: per_hour(self.packets),
+            protocols: self
+                .protocols
+                .into_iter()
+                .map(|(protocol, (packets, bytes))| ShiftProtocolVolume {
+                    protocol,
+                    packets,
+                    bytes,
+                    packets_per_hour: per_hour(packets),
+                })
+                .collect(),
+            masters: self
+                .masters
+                .into_iter()
+                .map(|(ip, packets)| ShiftMaster { ip, packets })
+                .collect(),
+            write_count: self.writes.values().sum(),
+            writes: self
+                .writes
+                .into_iter()
+                .map(|((master, target, protocol), count)| ShiftWritePath {
+                    master,
+                    target,
+                    protocol,
+                    count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Protocols, masters and write paths active in exactly one shift.
+///
+/// Nothing is reported unless at least two shifts were observed; with one
+/// shift in the capture everything would be "exclusive" to it.
+fn exclusive_activity(profiles: &[ShiftProfile]) -> Vec<ShiftExclusive> {
+    if profiles.iter().filter(|p| p.observed_hours > 0.0).count() < 2 {
+        return Vec::new();
+    }
+
+    // (kind, subject) → [(shift, packets)]
+    let mut seen: BTreeMap<(ShiftActivityKind, String), Vec<(&str, u64)>> = BTreeMap::new();
+    for profile in profiles {
+        let name = profile.name.as_str();
+        for p in &profile.protocols {
+            seen.entry((ShiftActivityKind::Protocol, p.protocol.clone()))
+                .or_default()
+                .push((name, p.packets));
+        }
+        for m in &profile.masters {
+            seen.entry((ShiftActivityKind::Master, m.ip.clone()))
+                .or_default()
+                .push((name, m.packets));
+        }
+        for w in &profile.writes {
+            let subject = format!("{} → {} ({})", w.master, w.target, w.protocol);
+            seen.entry((ShiftActivityKind::WritePath, subject))
+                .or_default()
+                .push((name, w.count));
+        }
+    }
+
+    let mut exclusive: Vec<ShiftExclusive> = seen
+        .into_iter()
+        .filter_map(|((kind, subject), shifts)| match shifts[..] {
+            [(shift, packets)] => Some(ShiftExclusive {
+                kind,
+                subject,
+                shift: shift.to_string(),
+                packets,
+            }),
+            _ => None,
+        })
+        .collect();
+    exclusive.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then_with(|| b.packets.cmp(&a.packets))
+            .then_with(|| a.subject.cmp(&b.subject))
+    });
+    exclusive
+}
+
+/// Shift-by-shift comparison of a capture.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShiftReport {
+    pub utc_offset_minutes: i32,
+    /// One profile per window, in the order given, then traffic outside them
+    pub shifts: Vec<ShiftProfile>,
+    /// Activity seen in a single shift only
+    pub exclusive: Vec<ShiftExclusive>,
+}
+
+/// Traffic of one shift across every occurrence in the capture.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShiftProfile {
+    pub name: String,
+    /// Time in this shift that carried traffic; rates are per observed hour
+    pub observed_hours: f64,
+    pub packets: u64,
+    pub bytes: u64,
+    pub packets_per_hour: f64,
+    /// Busiest first
+    pub protocols: Vec<ShiftProtocolVolume>,
+    /// Hosts that sent requests to OT servers, busiest first
+    pub masters: Vec<ShiftMaster>,
+    /// Write requests in this shift
+    pub write_count: u64,
+    pub writes: Vec<ShiftWritePath>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShiftProtocolVolume {
+    pub protocol: String,
+    pub packets: u64,
+    pub bytes: u64,
+    pub packets_per_hour: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShiftMaster {
+    pub ip: String,
+    pub packets: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShiftWritePath {
+    pub master: String,
+    pub target: String,
+    pub protocol: String,
+    pub count: u64,
+}
+
+/// What a [`ShiftExclusive`] entry refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShiftActivityKind {
+    WritePath,
+    Master,
+    Protocol,
+}
+
+/// A protocol, master or write path seen in one shift only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShiftExclusive {
+    pub kind: ShiftActivityKind,
+    /// Protocol name, master IP, or "master → target (protocol)"
+    pub subject: String,
+    pub shift: String,
+    pub packets: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn shift(name: &str, days: &[u8], start: u16, end: u16) -> ShiftWindow {
+        ShiftWindow {
+            name: name.to_string(),
+            days: days.to_vec(),
+            start_minute: start,
+            end_minute: end,
+        }
+    }
+
+    #[test]
+    fn test_week_slot_epoch_is_thursday() {
+        // 1970-01-01 00:00 UTC
+        assert_eq!(week_slot(0), 3 * SLOTS_PER_DAY);
+        // 2025-06-02 was a Monday
+        let monday_0615 = at("2025-06-02T06:15:00Z").timestamp() / 900;
+        assert_eq!(week_slot(monday_0615), 25);
+        assert_eq!(week_slot(-1), SLOTS_PER_WEEK - 1 - 4 * SLOTS_PER_DAY);
+    }
+
+    #[test]
+    fn test_overnight_window_spans_midnight() {
+        let night = shift("Night", &[4], 22 * 60, 6 * 60);
+        let fri_2300 = 4 * SLOTS_PER_DAY + 92;
+        let sat_0545 = 5 * SLOTS_PER_DAY + 23;
+        let sat_0600 = 5 * SLOTS_PER_DAY + 24;
+        assert!(night.covers(fri_2300));
+        assert!(night.covers(sat_0545));
+        assert!(!night.covers(sat_0600));
+        assert!(!night.covers(4 * SLOTS_PER_DAY + 23));
+        let all_day = shift("Weekend", &[6], 0, 0);
+        assert!(all_day.covers(6 * SLOTS_PER_DAY));
+        assert!(all_day.covers(SLOTS_PER_WEEK - 1));
+        assert!(!all_day.covers(0));
+    }
+
+    #[test]
+    fn test_night_only_writes_surface() {
+        let mut activity = ShiftActivity::new();
+        // Monday day shift: HMI polls the PLC
+        for minute in 0..4 {
+            let ts = at(&format!("2025-06-02T09:{:02}:00Z", minute * 15));
+            activity.record_packet(ts, "modbus", 100, Some("10.0.0.10"), None);
+        }
+        // Monday night: an engineering laptop writes to the PLC
+        activity.record_packet(
+            at("2025-06-02T23:30:00Z"),
+            "modbus",
+            80,
+            Some("10.0.0.66"),
+            Some("10.0.0.5"),
+        );
+        activity.record_packet(at("2025-06-02T23:31:00Z"), "modbus", 80, None, None);
+
+        let report = activity.report(&default_shifts(), 0);
+        let names: Vec<&str> = report.shifts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Weekend", "Day", "Swing", "Night"]);
+
+        let day = &report.shifts[1];
+        assert_eq!(day.observed_hours, 1.0);
+        assert_eq!(day.packets_per_hour, 4.0);
+        assert_eq!(day.write_count, 0);
+        let night = &report.shifts[3];
+        assert_eq!(night.observed_hours, 0.25);
+        assert_eq!(night.packets, 2);
+        assert_eq!(night.write_count, 1);
+        assert_eq!(night.writes[0].target, "10.0.0.5");
+
+        let subjects: Vec<(ShiftActivityKind, &str, &str)> = report
+            .exclusive
+            .iter()
+            .map(|e| (e.kind, e.subject.as_str(), e.shift.as_str()))
+            .collect();
+        assert_eq!(
+            subjects,
+            [
+                (
+                    ShiftActivityKind::WritePath,
+                    "10.0.0.66 → 10.0.0.5 (modbus)",
+                    "Night"
+                ),
+                (ShiftActivityKind::Master, "10.0.0.10", "Day"),
+                (ShiftActivityKind::Master, "10.0.0.66", "Night"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_utc_offset_and_outside_shifts() {
+        let mut activity = ShiftActivity::new();
+        // 21:00 UTC Monday is 06:00 Tuesday at UTC+9
+        activity.record_packet(at("2025-06-02T21:00:00Z"), "s7comm", 60, None, None);
+        let morning = [shift("Morning", &[1], 6 * 60, 7 * 60)];
+
+        let local = activity.report(&morning, 9 * 60);
+        assert_eq!(local.shifts.len(), 1);
+        assert_eq!(local.shifts[0].packets, 1);
+
+        let utc = activity.report(&morning, 0);
+        assert_eq!(utc.shifts.len(), 2);
+        assert_eq!(utc.shifts[0].packets, 0);
+        assert_eq!(utc.shifts[1].name, OUTSIDE_SHIFTS);
+        // A single observed shift cannot have exclusive activity
+        assert!(local.exclusive.is_empty());
+    }
+}
@@ -0,0 +1,388 @@
+//! Write-path approval workflow.
+//!
+//! A write path is a master → slave/outstation relationship over which
+//! write-class commands were observed (Modbus FC 5/6/15/16/22/23, DNP3
+//! FC 2-6). Analysts can mark a path as approved/expected with a
+//! justification and optional expiry; while the approval is active, write
+//! findings (T0855, T0806) that only involve approved paths are suppressed.
+//! Unapproved paths are what the report lists for review.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{AnalysisInput, AnalysisResult, FcSnapshot, Finding};
+
+/// Modbus write function codes (coils, registers, mask write, read/write multiple).
+const MODBUS_WRITE_FCS: &[u8] = &[5, 6, 15, 16, 22, 23];
+
+/// DNP3 write/control function codes (Write, Select, Operate, Direct Operate).
+const DNP3_WRITE_FCS: &[u8] = &[2, 3, 4, 5, 6];
+
+/// ATT&CK techniques whose findings describe writes from a master to targets.
+const WRITE_TECHNIQUES: &[&str] = &["T0855", "T0806"];
+
+/// An analyst's approval of writes from one master to one target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteApproval {
+    pub master_ip: String,
+    pub slave_ip: String,
+    pub justification: String,
+    pub approved_by: String,
+    /// RFC 3339 timestamp or YYYY-MM-DD (valid through the end of that day, UTC)
+    pub expires_at: Option<String>,
+}
+
+impl WriteApproval {
+    /// Whether the approval is in effect at `now`.
+    ///
+    /// An unparseable expiry counts as expired, so a typo never silently
+    /// suppresses findings forever.
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        let Some(ref expires) = self.expires_at else {
+            return true;
+        };
+        let expires = expires.trim();
+        if let Ok(ts) = DateTime::parse_from_rfc3339(expires) {
+            return now < ts;
+        }
+        NaiveDate::parse_from_str(expires, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.succ_opt())
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|end| now < end.and_utc())
+            .unwrap_or(false)
+    }
+}
+
+/// An observed write-capable relationship.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WritePath {
+    pub master_ip: String,
+    pub slave_ip: String,
+    /// "Modbus" or "Dnp3"
+    pub protocol: String,
+    /// Write function codes the master used
+    pub function_codes: Vec<u8>,
+    /// Write commands sent by the master across all of its targets
+    pub write_count: u64,
+    /// The active approval covering this path, if any
+    pub approval: Option<WriteApproval>,
+}
+
+/// Find write paths in the deep-parse data and attach active approvals.
+///
+/// Function code counts are kept per master, not per target, so every
+/// slave a writing master talks to is treated as a write path.
+pub fn detect_write_paths(
+    input: &AnalysisInput,
+    approvals: &[WriteApproval],
+    now: DateTime<Utc>,
+) -> Vec<WritePath> {
+    let active: Vec<&WriteApproval> = approvals.iter().filter(|a| a.is_active_at(now)).collect();
+    let mut paths = Vec::new();
+
+    for (ip, dp) in &input.deep_parse {
+        let candidates = [
+            dp.modbus.as_ref().map(|m| {
+                (
+                    "Modbus",
+                    &m.role,
+                    &m.function_codes,
+                    &m.relationships,
+                    MODBUS_WRITE_FCS,
+                    "slave",
+                )
+            }),
+            dp.dnp3.as_ref().map(|d| {
+                (
+                    "Dnp3",
+                    &d.role,
+                    &d.function_codes,
+                    &d.relationships,
+                    DNP3_WRITE_FCS,
+                    "outstation",
+                )
+            }),
+        ];
+
+        for (protocol, role, fcs, relationships, write_fcs, target_role) in
+            candidates.into_iter().flatten()
+        {
+            if role != "master" && role != "both" {
+                continue;
+            }
+            let writes: Vec<&FcSnapshot> = fcs
+                .iter()
+                .filter(|fc| write_fcs.contains(&fc.code) && fc.count > 0)
+                .collect();
+            if writes.is_empty() {
+                continue;
+            }
+            let mut function_codes: Vec<u8> = writes.iter().map(|fc| fc.code).collect();
+            function_codes.sort_unstable();
+            let write_count = writes.iter().map(|fc| fc.count).sum();
+
+            for rel in relationships
+                .iter()
+                .filter(|r| r.remote_role == target_role)
+            {
+                let approval = active
+                    .iter()
+                    .find(|a| a.master_ip == *ip && a.slave_ip == rel.remote_ip)
+                    .map(|a| (*a).clone());
+                paths.push(WritePath {
+                    master_ip: ip.clone(),
+                    slave_ip: rel.remote_ip.clone(),
+                    protocol: protocol.to_string(),
+                    function_codes: function_codes.clone(),
+                    write_count,
+                    approval,
+                });
+            }
+        }
+    }
+
+    paths.sort_by(|a, b| {
+        (&a.master_ip, &a.slave_ip, &a.protocol).cmp(&(&b.master_ip, &b.slave_ip, &b.protocol))
+    });
+    paths
+}
+
+/// Remove write findings fully covered by active approvals.
+///
+/// Write findings list the writing master first, then its targets. A
+/// finding is suppressed when every listed target is approved for that
+/// master; a finding naming only the master is suppressed when all of the
+/// master's write paths are approved. Summary counts are updated and the
+/// number of suppressed findings is returned.
+pub fn suppress_approved_findings(
+    result: &mut AnalysisResult,
+    paths: &[WritePath],
+    approvals: &[WriteApproval],
+    now: DateTime<Utc>,
+) -> usize {
+    let approved: HashSet<(&str, &str)> = approvals
+        .iter()
+        .filter(|a| a.is_active_at(now))
+        .map(|a| (a.master_ip.as_str(), a.slave_ip.as_str()))
+        .collect();
+    if approved.is_empty() {
+        return 0;
+    }
+
+    let covered = |finding: &Finding| -> bool {
+        let is_write = finding
+            .technique_id
+            .as_deref()
+            .is_some_and(|t| WRITE_TECHNIQUES.contains(&t));
+        if !is_write {
+            return false;
+        }
+        let Some((master, targets)) = finding.affected_assets.split_first() else {
+            return false;
+        };
+        if targets.is_empty() {
+            let mut master_paths = paths.iter().filter(|p| p.master_ip == *master).peekable();
+            master_paths.peek().is_some()
+                && master_paths.all(|p| approved.contains(&(master.as_str(), p.slave_ip.as_str())))
+        } else {
+            targets
+                .iter()
+                .all(|t| approved.contains(&(master.as_str(), t.as_str())))
+        }
+    };
+
+    crate::remove_findings(result, covered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AnalysisSummary, DeepParseSnapshot, Dnp3Snapshot, FindingType, ModbusSnapshot,
+        RelationshipSnapshot, Severity,
+    };
+    use std::collections::HashMap;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn approval(master: &str, slave: &str, expires_at: Option<&str>) -> WriteApproval {
+        WriteApproval {
+            master_ip: master.to_string(),
+            slave_ip: slave.to_string(),
+            justification: "HMI setpoint writes".to_string(),
+            approved_by: "ops".to_string(),
+            expires_at: expires_at.map(str::to_string),
+        }
+    }
+
+    fn rel(ip: &str, role: &str) -> RelationshipSnapshot {
+        RelationshipSnapshot {
+            remote_ip: ip.to_string(),
+            remote_role: role.to_string(),
+            packet_count: 10,
+        }
+    }
+
+    fn input() -> AnalysisInput {
+        let mut deep_parse = HashMap::new();
+        deep_parse.insert(
+            "10.0.0.1".to_string(),
+            DeepParseSnapshot {
+                modbus: Some(ModbusSnapshot {
+                    role: "master".to_string(),
+                    unit_ids: vec![1],
+                    function_codes: vec![
+                        FcSnapshot {
+                            code: 3,
+                            count: 500,
+                            is_write: false,
+                        },
+                        FcSnapshot {
+                            code: 16,
+                            count: 20,
+                            is_write: true,
+                        },
+                    ],
+                    relationships: vec![rel("10.0.1.5", "slave"), rel("10.0.1.6", "slave")],
+                    polling_intervals: vec![],
+                }),
+                ..Default::default()
+            },
+        );
+        // DNP3 master that only reads: no write path
+        deep_parse.insert(
+            "10.0.0.2".to_string(),
+            DeepParseSnapshot {
+                dnp3: Some(Dnp3Snapshot {
+                    role: "master".to_string(),
+                    has_unsolicited: false,
+                    function_codes: vec![FcSnapshot {
+                        code: 1,
+                        count: 100,
+                        is_write: false,
+                    }],
+                    relationships: vec![rel("10.0.2.1", "outstation")],
+                    master_bindings: vec![],
+                }),
+                ..Default::default()
+            },
+        );
+        AnalysisInput {
+            assets: vec![],
+            connections: vec![],
+            deep_parse,
+            arp: vec![],
+        }
+    }
+
+    fn write_finding(assets: &[&str], severity: Severity) -> Finding {
+        Finding::new(
+            FindingType::AttackTechnique,
+            severity,
+            "write".to_string(),
+            String::new(),
+            assets.iter().map(|s| s.to_string()).collect(),
+            String::new(),
+            Some("T0806".to_string()),
+        )
+    }
+
+    fn result(findings: Vec<Finding>) -> AnalysisResult {
+        AnalysisResult {
+            summary: AnalysisSummary {
+                total_findings: findings.len(),
+                critical_count: 0,
+                high_count: findings.len(),
+                medium_count: 0,
+                low_count: 0,
+                info_count: 0,
+                purdue_violations: 0,
+                anomaly_count: 0,
+                assets_analyzed: 0,
+                connections_analyzed: 0,
+                unencrypted_ot_percent: 0.0,
+                suppressed_findings: 0,
+            },
+            findings,
+            purdue_assignments: vec![],
+            anomalies: vec![],
+        }
+    }
+
+    #[test]
+    fn test_approval_expiry() {
+        assert!(approval("a", "b", None).is_active_at(now()));
+        assert!(approval("a", "b", Some("2025-06-01")).is_active_at(now()));
+        assert!(!approval("a", "b", Some("2025-05-31")).is_active_at(now()));
+        assert!(approval("a", "b", Some("2025-06-01T13:00:00+00:00")).is_active_at(now()));
+        assert!(!approval("a", "b", Some("2025-06-01T11:00:00Z")).is_active_at(now()));
+        assert!(!approval("a", "b", Some("next tuesday")).is_active_at(now()));
+    }
+
+    #[test]
+    fn test_detect_write_paths() {
+        let approvals = vec![
+            approval("10.0.0.1", "10.0.1.5", None),
+            approval("10.0.0.1", "10.0.1.6", Some("2020-01-01")),
+        ];
+        let paths = detect_write_paths(&input(), &approvals, now());
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].slave_ip, "10.0.1.5");
+        assert_eq!(paths[0].function_codes, vec![16]);
+        assert_eq!(paths[0].write_count, 20);
+        assert!(paths[0].approval.is_some());
+        // Expired approval leaves the path unapproved
+        assert!(paths[1].approval.is_none());
+    }
+
+    #[test]
+    fn test_suppress_approved_findings() {
+        let approvals = vec![approval("10.0.0.1", "10.0.1.5", None)];
+        let paths = detect_write_paths(&input(), &approvals, now());
+        let mut res = result(vec![
+            write_finding(&["10.0.0.1", "10.0.1.5"], Severity::High),
+            write_finding(&["10.0.0.1", "10.0.1.5", "10.0.1.6"], Severity::High),
+            // Master-only finding: 10.0.1.6 is still unapproved
+            write_finding(&["10.0.0.1"], Severity::High),
+        ]);
+
+        let n = suppress_approved_findings(&mut res, &paths, &approvals, now());
+        assert_eq!(n, 1);
+        assert_eq!(res.findings.len(), 2);
+        assert_eq!(res.summary.total_findings, 2);
+        assert_eq!(res.summary.high_count, 2);
+        assert_eq!(res.summary.suppressed_findings, 1);
+
+        // Approving the remaining path covers the master-only finding too
+        let approvals = vec![
+            approval("10.0.0.1", "10.0.1.5", None),
+            approval("10.0.0.1", "10.0.1.6", Some("2025-12-31")),
+        ];
+        let paths = detect_write_paths(&input(), &approvals, now());
+        assert_eq!(
+            suppress_approved_findings(&mut res, &paths, &approvals, now()),
+            2
+        );
+        assert!(res.findings.is_empty());
+    }
+
+    #[test]
+    fn test_non_write_findings_not_suppressed() {
+        let approvals = vec![approval("10.0.0.1", "10.0.1.5", None)];
+        let mut finding = write_finding(&["10.0.0.1", "10.0.1.5"], Severity::High);
+        finding.technique_id = Some("T0846".to_string());
+        let mut res = result(vec![finding]);
+        assert_eq!(
+            suppress_approved_findings(&mut res, &[], &approvals, now()),
+            0
+        );
+        assert_eq!(res.findings.len(), 1);
+    }
+}
@@ -0,0 +1,129 @@
+//! Capture file discovery for batch (directory) import.
+//!
+//! Sensors usually rotate captures either by time (`tcpdump -G`, names like
+//! `ot-20240301-1400.pcap`) or by size (`tcpdump -C`, which appends a counter:
+//! `ot.pcap`, `ot.pcap1`, `ot.pcap2`, ...). Both are recognised, and files are
+//! returned in capture order: names compare with their trailing counter taken
+//! as a number, so `ot.pcap10` sorts after `ot.pcap9`. Rotated files that a
+//! post-rotate script compressed (`ot.pcap1.gz`) are included too.
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use crate::decompress::COMPRESSED_EXTENSIONS;
+use crate::error::CaptureError;
+
+/// Extensions recognised as capture files (compared case-insensitively).
+const CAPTURE_EXTENSIONS: &[&str] = &["pcap", "pcapng", "cap"];
+
+/// List the capture files directly inside `dir`, in capture order.
+///
+/// Subdirectories are not descended into, and hidden files are skipped.
+pub fn list_capture_files<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>, CaptureError> {
+    let dir = dir.as_ref();
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| CaptureError::FileOpen(format!("{}: {}", dir.display(), e)))?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(is_capture_file_name)
+        })
+        .collect();
+
+    files.sort_by(|a, b| {
+        let name = |p: &PathBuf| p.file_name().map(|n| n.to_string_lossy().into_owned());
+        compare_capture_names(&name(a).unwrap_or_default(), &name(b).unwrap_or_default())
+    });
+    Ok(files)
+}
+
+/// Whether a file name looks like a capture, including size-rotated
+/// names with a counter after the extension and gzip/zstd compressed ones.
+pub fn is_capture_file_name(name: &str) -> bool {
+    if name.starts_with('.') {
+        return false;
+    }
+    let name = strip_compressed_extension(name);
+    let Some((_, ext)) = name.rsplit_once('.') else {
+        return false;
+    };
+    let ext = ext
+        .trim_end_matches(|c: char| c.is_ascii_digit())
+        .to_ascii_lowercase();
+    CAPTURE_EXTENSIONS.contains(&ext.as_str())
+}
+
+fn strip_compressed_extension(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if COMPRESSED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()) => {
+            stem
+        }
+        _ => name,
+    }
+}
+
+/// Order two capture file names, comparing any trailing counter numerically.
+fn compare_capture_names(a: &str, b: &str) -> Ordering {
+    let (a_base, a_num) = split_counter(strip_compressed_extension(a));
+    let (b_base, b_num) = split_counter(strip_compressed_extension(b));
+    a_base.cmp(b_base).then(a_num.cmp(&b_num))
+}
+
+fn split_counter(name: &str) -> (&str, u64) {
+    let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let counter = name[base.len()..].parse().unwrap_or(0);
+    (base, counter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_file_names() {
+        assert!(is_capture_file_name("ot-20240301-1400.pcap"));
+        assert!(is_capture_file_name("site.PCAPNG"));
+        assert!(is_capture_file_name("ring.pcap12"));
+        assert!(is_capture_file_name("old.cap"));
+        assert!(!is_capture_file_name("notes.txt"));
+        assert!(!is_capture_file_name("pcap"));
+        assert!(!is_capture_file_name(".hidden.pcap"));
+        assert!(is_capture_file_name("ot.pcap.gz"));
+        assert!(is_capture_file_name("ring.pcap3.ZST"));
+        assert!(!is_capture_file_name("notes.txt.gz"));
+        assert!(!is_capture_file_name("pcap.gz"));
+    }
+
+    #[test]
+    fn test_rotated_files_in_capture_order() {
+        let dir = std::env::temp_dir().join(format!("gm-capture-dir-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub.pcap")).unwrap();
+        for name in [
+            "ot.pcap10",
+            "ot.pcap",
+            "ot.pcap2.gz",
+            "ot.pcap1",
+            "readme.md",
+        ] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let files = list_capture_files(&dir).unwrap();
+        let names: Vec<String> = files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["ot.pcap", "ot.pcap1", "ot.pcap2.gz", "ot.pcap10"]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(list_capture_files(&dir).is_err());
+    }
+}
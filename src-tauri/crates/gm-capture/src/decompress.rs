@@ -0,0 +1,149 @@
+//! Transparent decompression of gzip and zstd capture files.
+//!
+//! Zeek and rotation scripts often compress finished captures
+//! (`ot.pcap.gz`, `ot.pcap.zst`). libpcap only reads plain files, so a
+//! compressed capture is decompressed into a temporary file that lives as
+//! long as the capture handle reading it. The format is detected from the
+//! file's magic bytes, not its name.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use tempfile::TempPath;
+
+use crate::error::CaptureError;
+
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// File name suffixes of compressed captures (compared case-insensitively).
+pub(crate) const COMPRESSED_EXTENSIONS: &[&str] = &["gz", "zst"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zstd,
+}
+
+fn detect(header: &[u8]) -> Option<Compression> {
+    if header.starts_with(&GZIP_MAGIC) {
+        Some(Compression::Gzip)
+    } else if header.starts_with(&ZSTD_MAGIC) {
+        Some(Compression::Zstd)
+    } else {
+        None
+    }
+}
+
+/// An offline capture handle, plus the temporary file backing it when the
+/// input was compressed. The temporary file is removed on drop.
+pub(crate) struct OpenCapture {
+    pub capture: pcap::Capture<pcap::Offline>,
+    /// Size in bytes of the (decompressed) capture being read
+    pub size: u64,
+    pub decompressed: Option<TempPath>,
+}
+
+/// Open a capture file for reading, decompressing it first if it is gzip
+/// or zstd compressed. libpcap scales every record's timestamp to
+/// `precision`, whatever precision the file was written with.
+pub(crate) fn open_capture(
+    path: &Path,
+    precision: pcap::Precision,
+) -> Result<OpenCapture, CaptureError> {
+    let open_err =
+        |e: &dyn std::fmt::Display| CaptureError::FileOpen(format!("{}: {}", path.display(), e));
+
+    let mut header = [0u8; 4];
+    let header_len = File::open(path)
+        .and_then(|mut f| read_up_to(&mut f, &mut header))
+        .map_err(|e| open_err(&e))?;
+
+    let decompressed = match detect(&header[..header_len]) {
+        Some(compression) => Some(decompress(path, compression).map_err(|e| open_err(&e))?),
+        None => None,
+    };
+    let plain: &Path = decompressed.as_deref().unwrap_or(path);
+
+    let size = std::fs::metadata(plain).map(|m| m.len()).unwrap_or(0);
+    let capture =
+        pcap::Capture::from_file_with_precision(plain, precision).map_err(|e| open_err(&e))?;
+    Ok(OpenCapture {
+        capture,
+        size,
+        decompressed,
+    })
+}
+
+/// Stream-decompress `path` into a new temporary file.
+fn decompress(path: &Path, compression: Compression) -> io::Result<TempPath> {
+    let input = BufReader::new(File::open(path)?);
+    let mut output = tempfile::Builder::new()
+        .prefix("gm-capture-")
+        .suffix(".pcap")
+        .tempfile()?;
+    match compression {
+        // MultiGzDecoder: `cat a.gz b.gz` style multi-member files are valid gzip
+        Compression::Gzip => io::copy(&mut flate2::read::MultiGzDecoder::new(input), &mut output)?,
+        Compression::Zstd => io::copy(&mut zstd::stream::read::Decoder::new(input)?, &mut output)?,
+    };
+    log::debug!(
+        "Decompressed {} ({:?}) to {}",
+        path.display(),
+        compression,
+        output.path().display()
+    );
+    Ok(output.into_temp_path())
+}
+
+/// Fill as much of `buf` as the reader has, returning the count read.
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(&[0x1F, 0x8B, 0x08, 0x00]), Some(Compression::Gzip));
+        assert_eq!(detect(&ZSTD_MAGIC), Some(Compression::Zstd));
+        // Classic PCAP and PCAPNG magic
+        assert_eq!(detect(&[0xD4, 0xC3, 0xB2, 0xA1]), None);
+        assert_eq!(detect(&[0x0A, 0x0D, 0x0D, 0x0A]), None);
+        assert_eq!(detect(&[0x1F]), None);
+    }
+
+    #[test]
+    fn test_decompress_gzip_and_zstd() {
+        let content = b"\xD4\xC3\xB2\xA1 not really a capture".repeat(100);
+        let dir = tempfile::tempdir().unwrap();
+
+        let gz = dir.path().join("ot.pcap.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(File::create(&gz).unwrap(), Default::default());
+        encoder.write_all(&content).unwrap();
+        encoder.finish().unwrap();
+
+        let zst = dir.path().join("ot.pcap.zst");
+        std::fs::write(&zst, zstd::encode_all(&content[..], 3).unwrap()).unwrap();
+
+        for (path, compression) in [(gz, Compression::Gzip), (zst, Compression::Zstd)] {
+            let plain = decompress(&path, compression).unwrap();
+            assert_eq!(std::fs::read(&plain).unwrap(), content);
+            let plain_path = plain.to_path_buf();
+            drop(plain);
+            assert!(!plain_path.exists());
+        }
+    }
+}
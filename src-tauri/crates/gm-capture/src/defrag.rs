@@ -0,0 +1,270 @@
+//! IPv4 fragment reassembly.
+//!
+//! Lossy radio links and small-MTU tunnels fragment Modbus/TCP and DNP3
+//! datagrams; parsed one fragment at a time, the deep parsers see a
+//! truncated PDU and then a headerless tail. Fragments are held here until
+//! the whole datagram has arrived and are then handed on as one packet.
+//!
+//! Datagrams are keyed by (source, destination, protocol, identification)
+//! as in RFC 791. Incomplete datagrams are dropped after
+//! [`FRAGMENT_TIMEOUT_SECS`] of capture time, and the number held at once is
+//! bounded so a fragment flood cannot exhaust memory.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+
+/// Incomplete datagrams older than this (capture time) are discarded.
+/// Matches the Linux `ipfrag_time` default.
+pub(crate) const FRAGMENT_TIMEOUT_SECS: i64 = 30;
+
+/// Most incomplete datagrams held at once; further new datagrams are
+/// dropped until older ones complete or expire.
+const MAX_PENDING: usize = 4096;
+
+/// Largest datagram an IPv4 total length can describe.
+const MAX_DATAGRAM_LEN: usize = 65_535;
+
+const FLAG_MORE_FRAGMENTS: u8 = 0x20;
+
+/// What became of an IPv4 packet given to [`FragmentCache::process`].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Reassembly {
+    /// Not a fragment; parse the packet as captured
+    Whole,
+    /// A fragment of a datagram still missing pieces (or one that was dropped)
+    Pending,
+    /// The last missing fragment: the reassembled IPv4 packet
+    Complete(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct DatagramKey {
+    src: [u8; 4],
+    dst: [u8; 4],
+    protocol: u8,
+    identification: u16,
+}
+
+#[derive(Debug, Default)]
+struct PendingDatagram {
+    /// Header of the offset-0 fragment, once seen
+    header: Option<Vec<u8>>,
+    /// Fragment payloads by byte offset
+    parts: BTreeMap<usize, Vec<u8>>,
+    /// Payload length, known once the last fragment (MF clear) arrives
+    total_len: Option<usize>,
+    first_seen: i64,
+}
+
+impl PendingDatagram {
+    /// Reassemble the datagram if every byte of the payload has arrived.
+    fn assemble(&self) -> Option<Vec<u8>> {
+        let header = self.header.as_ref()?;
+        let total_len = self.total_len?;
+
+        let mut covered = 0;
+        for (&offset, data) in &self.parts {
+            if offset > covered {
+                return None; // hole
+            }
+            covered = covered.max(offset + data.len());
+        }
+        if covered < total_len || header.len() + total_len > MAX_DATAGRAM_LEN {
+            return None;
+        }
+
+        let mut packet = header.clone();
+        packet.resize(header.len() + total_len, 0);
+        for (&offset, data) in &self.parts {
+            let end = (offset + data.len()).min(total_len);
+            if offset < end {
+                packet[header.len() + offset..header.len() + end]
+                    .copy_from_slice(&data[..end - offset]);
+            }
+        }
+
+        // A single datagram now: fix the length, clear MF and the offset
+        // (keeping DF), and recompute the checksum
+        let packet_len = packet.len() as u16;
+        packet[2..4].copy_from_slice(&packet_len.to_be_bytes());
+        packet[6] &= 0x40;
+        packet[7] = 0;
+        packet[10..12].fill(0);
+        let checksum = header_checksum(&packet[..header.len()]);
+        packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+        Some(packet)
+    }
+}
+
+/// Holds IPv4 fragments until their datagrams are complete.
+#[derive(Debug, Default)]
+pub(crate) struct FragmentCache {
+    pending: HashMap<DatagramKey, PendingDatagram>,
+    last_sweep: i64,
+}
+
+impl FragmentCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one IPv4 packet (starting at the version nibble).
+    ///
+    /// Packets that are not fragments, and anything that is not a valid
+    /// IPv4 header, come back as [`Reassembly::Whole`].
+    pub fn process(&mut self, packet: &[u8], timestamp: DateTime<Utc>) -> Reassembly {
+        let Some((key, header_len, offset, more, total)) = fragment_info(packet) else {
+            return Reassembly::Whole;
+        };
+        let now = timestamp.timestamp();
+        self.expire(now);
+
+        if !self.pending.contains_key(&key) && self.pending.len() >= MAX_PENDING {
+            log::debug!("Fragment cache full; dropping fragment");
+            return Reassembly::Pending;
+        }
+        let datagram = self.pending.entry(key).or_insert_with(|| PendingDatagram {
+            first_seen: now,
+            ..Default::default()
+        });
+
+        let data = &packet[header_len..total];
+        if offset == 0 {
+            datagram.header = Some(packet[..header_len].to_vec());
+        }
+        if !more {
+            datagram.total_len = Some(offset + data.len());
+        }
+        datagram.parts.insert(offset, data.to_vec());
+
+        match datagram.assemble() {
+            Some(complete) => {
+                self.pending.remove(&key);
+                Reassembly::Complete(complete)
+            }
+            None => Reassembly::Pending,
+        }
+    }
+
+    /// Drop datagrams that have waited longer than the timeout, at most
+    /// once per second of capture time.
+    fn expire(&mut self, now: i64) {
+        if now == self.last_sweep {
+            return;
+        }
+        self.last_sweep = now;
+        self.pending
+            .retain(|_, d| now - d.first_seen <= FRAGMENT_TIMEOUT_SECS);
+    }
+}
+
+/// Key, header length, payload byte offset, MF flag and end of data
+/// (total length, clamped to the capture) of an IPv4 fragment. None for a
+/// packet that is not a fragment or not valid IPv4.
+fn fragment_info(packet: &[u8]) -> Option<(DatagramKey, usize, usize, bool, usize)> {
+    if packet.len() < 20 || packet[0] >> 4 != 4 {
+        return None;
+    }
+    let header_len = (packet[0] & 0x0F) as usize * 4;
+    let more = packet[6] & FLAG_MORE_FRAGMENTS != 0;
+    let offset = (u16::from_be_bytes([packet[6] & 0x1F, packet[7]]) as usize) * 8;
+    if (!more && offset == 0) || header_len < 20 || packet.len() < header_len {
+        return None;
+    }
+    let total = (u16::from_be_bytes([packet[2], packet[3]]) as usize).min(packet.len());
+    if total < header_len {
+        return None;
+    }
+    let key = DatagramKey {
+        src: packet[12..16].try_into().ok()?,
+        dst: packet[16..20].try_into().ok()?,
+        protocol: packet[9],
+        identification: u16::from_be_bytes([packet[4], packet[5]]),
+    };
+    Some((key, header_len, offset, more, total))
+}
+
+/// RFC 791 header checksum (the checksum field must be zero).
+fn header_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header
+        .chunks(2)
+        .map(|w| u16::from_be_bytes([w[0], *w.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An IPv4 fragment of a UDP datagram from 10.0.0.1 to 10.0.0.2.
+    fn fragment(id: u16, offset: usize, more: bool, data: &[u8]) -> Vec<u8> {
+        let total = (20 + data.len()) as u16;
+        let flags_offset = (offset / 8) as u16 | if more { 0x2000 } else { 0 };
+        let mut packet = vec![0x45, 0x00];
+        packet.extend_from_slice(&total.to_be_bytes());
+        packet.extend_from_slice(&id.to_be_bytes());
+        packet.extend_from_slice(&flags_offset.to_be_bytes());
+        packet.extend_from_slice(&[64, 17, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2]);
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    fn at(second: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + second, 0).unwrap()
+    }
+
+    #[test]
+    fn test_unfragmented_passes_through() {
+        let mut cache = FragmentCache::new();
+        let packet = fragment(1, 0, false, &[0; 8]);
+        assert_eq!(cache.process(&packet, at(0)), Reassembly::Whole);
+        assert_eq!(cache.process(&[0x60; 40], at(0)), Reassembly::Whole);
+        assert_eq!(cache.pending.len(), 0);
+    }
+
+    #[test]
+    fn test_out_of_order_reassembly() {
+        let payload: Vec<u8> = (0..40).collect();
+        let mut cache = FragmentCache::new();
+
+        // Last, first, then middle fragment
+        let last = fragment(7, 32, false, &payload[32..]);
+        let first = fragment(7, 0, true, &payload[..16]);
+        let middle = fragment(7, 16, true, &payload[16..32]);
+        assert_eq!(cache.process(&last, at(0)), Reassembly::Pending);
+        assert_eq!(cache.process(&first, at(0)), Reassembly::Pending);
+        let Reassembly::Complete(packet) = cache.process(&middle, at(1)) else {
+            panic!("datagram not reassembled");
+        };
+
+        assert_eq!(packet[20..], payload[..]);
+        assert_eq!(u16::from_be_bytes([packet[2], packet[3]]), 60);
+        assert_eq!(packet[6] & 0x3F, 0);
+        assert_eq!(packet[7], 0);
+        assert_eq!(header_checksum(&packet[..20]), 0); // valid checksum
+        assert_eq!(cache.pending.len(), 0);
+    }
+
+    #[test]
+    fn test_incomplete_datagrams_expire() {
+        let mut cache = FragmentCache::new();
+        let first = fragment(9, 0, true, &[0; 16]);
+        assert_eq!(cache.process(&first, at(0)), Reassembly::Pending);
+
+        // A different datagram arriving after the timeout sweeps the first
+        let other = fragment(10, 0, true, &[0; 16]);
+        cache.process(&other, at(FRAGMENT_TIMEOUT_SECS + 1));
+        assert_eq!(cache.pending.len(), 1);
+
+        let last = fragment(9, 16, false, &[0; 8]);
+        assert_eq!(
+            cache.process(&last, at(FRAGMENT_TIMEOUT_SECS + 2)),
+            Reassembly::Pending
+        );
+    }
+}
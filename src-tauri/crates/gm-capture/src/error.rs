@@ -20,6 +20,12 @@ pub enum CaptureError {
     #[error("Parse error: {0}")]
     Parse(String),
 
+    #[error("Remote capture error: {0}")]
+    Remote(String),
+
+    #[error("Flow export error: {0}")]
+    Export(String),
+
     #[error("Import cancelled by user")]
     Cancelled,
 }
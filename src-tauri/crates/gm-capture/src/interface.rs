@@ -4,10 +4,16 @@ use serde::Serialize;
 /// Represents a network interface available for capture.
 #[derive(Debug, Clone, Serialize)]
 pub struct NetworkInterface {
+    /// Name to capture on (a `\Device\NPF_{GUID}` path on Windows)
     pub name: String,
+    /// Name the OS shows the user ("Ethernet 2", "Wi-Fi"), where it differs
+    /// from `name`
+    pub friendly_name: Option<String>,
     pub description: Option<String>,
     pub addresses: Vec<InterfaceAddress>,
     pub flags: InterfaceFlags,
+    /// Negotiated link speed in Mbit/s, if the OS reports one
+    pub link_speed_mbps: Option<u64>,
 }
 
 /// An address associated with a network interface.
@@ -24,12 +30,26 @@ pub struct InterfaceFlags {
     pub is_up: bool,
     pub is_loopback: bool,
     pub is_running: bool,
+    pub is_wireless: bool,
+    /// Whether a cable or wireless association is present; None when the
+    /// OS cannot tell (loopback, tunnels, older libpcap)
+    pub connected: Option<bool>,
+}
+
+/// What the OS reports about an adapter beyond libpcap's device list.
+#[derive(Debug, Clone, Default)]
+struct AdapterDetails {
+    friendly_name: Option<String>,
+    description: Option<String>,
+    link_speed_mbps: Option<u64>,
+    /// Operational state: up (true) or down (false)
+    oper_up: Option<bool>,
 }
 
 /// List all available network interfaces on this system.
 ///
-/// This is the first Rust function you'll see working — it wraps
-/// libpcap's device enumeration and returns structured data to the frontend.
+/// Every call enumerates the devices afresh, so adapters plugged in since
+/// the last call (USB NICs) appear and removed ones disappear.
 ///
 /// # Example
 /// ```no_run
@@ -40,6 +60,7 @@ pub struct InterfaceFlags {
 /// ```
 pub fn list_interfaces() -> Result<Vec<NetworkInterface>, CaptureError> {
     let devices = pcap::Device::list().map_err(|e| CaptureError::InterfaceList(e.to_string()))?;
+    let mut details = platform::adapter_details();
 
     let interfaces = devices
         .into_iter()
@@ -54,23 +75,35 @@ pub fn list_interfaces() -> Result<Vec<NetworkInterface>, CaptureError> {
                 })
                 .collect();
 
-            // pcap doesn't directly expose all flags, so we infer what we can
-            let is_loopback = device.name.contains("lo")
-                || device.name.contains("Loopback")
+            let is_loopback = device.flags.is_loopback()
                 || device
                     .addresses
                     .iter()
                     .any(|a| a.addr.to_string() == "127.0.0.1" || a.addr.to_string() == "::1");
 
+            let detail = details
+                .remove(platform::adapter_key(&device.name))
+                .unwrap_or_default();
+            let connected = match device.flags.connection_status {
+                pcap::ConnectionStatus::Connected => Some(true),
+                pcap::ConnectionStatus::Disconnected => Some(false),
+                _ => detail.oper_up,
+            };
+
             NetworkInterface {
+                friendly_name: detail.friendly_name.filter(|f| *f != device.name),
+                // Npcap's own description is the driver name; prefer the OS's
+                description: detail.description.or(device.desc),
                 name: device.name,
-                description: device.desc,
                 addresses,
                 flags: InterfaceFlags {
-                    is_up: true, // pcap only lists active devices
+                    is_up: device.flags.is_up(),
                     is_loopback,
-                    is_running: true,
+                    is_running: device.flags.is_running(),
+                    is_wireless: device.flags.is_wireless(),
+                    connected,
                 },
+                link_speed_mbps: detail.link_speed_mbps,
             }
         })
         .collect();
@@ -78,6 +111,170 @@ pub fn list_interfaces() -> Result<Vec<NetworkInterface>, CaptureError> {
     Ok(interfaces)
 }
 
+/// Parse `/sys/class/net/<if>/speed` (Mbit/s; -1 or unreadable when the
+/// link is down or the driver does not say).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_sysfs_speed(text: &str) -> Option<u64> {
+    text.trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|mbps| u64::try_from(mbps).ok())
+        .filter(|&mbps| mbps > 0)
+}
+
+/// The `{GUID}` of an Npcap device name (`\Device\NPF_{GUID}`), which is
+/// the adapter name Windows knows it by.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn npf_guid(name: &str) -> &str {
+    name.strip_prefix(r"\Device\NPF_").unwrap_or(name)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    use super::{parse_sysfs_speed, AdapterDetails};
+
+    pub(super) fn adapter_key(name: &str) -> &str {
+        name
+    }
+
+    /// Link speed and operational state from sysfs. Linux interface names
+    /// are already what the user sees, so there is no friendly name.
+    pub(super) fn adapter_details() -> HashMap<String, AdapterDetails> {
+        let Ok(entries) = fs::read_dir("/sys/class/net") else {
+            return HashMap::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                (name, details(&entry.path()))
+            })
+            .collect()
+    }
+
+    fn details(dir: &Path) -> AdapterDetails {
+        let read = |file: &str| fs::read_to_string(dir.join(file)).ok();
+        AdapterDetails {
+            link_speed_mbps: read("speed").and_then(|s| parse_sysfs_speed(&s)),
+            oper_up: read("operstate").and_then(|s| match s.trim() {
+                "up" => Some(true),
+                "down" | "lowerlayerdown" | "notpresent" | "dormant" => Some(false),
+                _ => None,
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::collections::HashMap;
+    use std::ffi::CStr;
+
+    use windows_sys::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR};
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetAdaptersAddresses, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER,
+        GAA_FLAG_SKIP_MULTICAST, IP_ADAPTER_ADDRESSES_LH,
+    };
+    use windows_sys::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+    use windows_sys::Win32::Networking::WinSock::AF_UNSPEC;
+
+    use super::{npf_guid, AdapterDetails};
+
+    pub(super) fn adapter_key(name: &str) -> &str {
+        npf_guid(name)
+    }
+
+    /// Friendly names, descriptions, link speeds and states of every
+    /// adapter, keyed by adapter GUID.
+    pub(super) fn adapter_details() -> HashMap<String, AdapterDetails> {
+        let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+        let mut size: u32 = 16 * 1024;
+        // u64 elements keep the buffer aligned for IP_ADAPTER_ADDRESSES_LH
+        let mut buffer: Vec<u64> = Vec::new();
+        // The adapter list can grow between the size query and the call
+        for _ in 0..3 {
+            buffer.resize((size as usize).div_ceil(8), 0);
+            let first = buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH;
+            // SAFETY: `buffer` holds at least `size` bytes, suitably aligned
+            let ret = unsafe {
+                GetAdaptersAddresses(
+                    u32::from(AF_UNSPEC),
+                    flags,
+                    std::ptr::null(),
+                    first,
+                    &mut size,
+                )
+            };
+            match ret {
+                NO_ERROR => return walk(first),
+                ERROR_BUFFER_OVERFLOW => continue,
+                _ => break,
+            }
+        }
+        HashMap::new()
+    }
+
+    fn walk(mut adapter: *const IP_ADAPTER_ADDRESSES_LH) -> HashMap<String, AdapterDetails> {
+        let mut details = HashMap::new();
+        // SAFETY: GetAdaptersAddresses filled the buffer with a linked list
+        // of adapters whose string pointers point into the same buffer
+        unsafe {
+            while let Some(a) = adapter.as_ref() {
+                let guid = CStr::from_ptr(a.AdapterName as *const std::ffi::c_char)
+                    .to_string_lossy()
+                    .into_owned();
+                // u64::MAX means the speed is unknown
+                let speed = (a.TransmitLinkSpeed != u64::MAX && a.TransmitLinkSpeed > 0)
+                    .then(|| a.TransmitLinkSpeed / 1_000_000);
+                details.insert(
+                    guid,
+                    AdapterDetails {
+                        friendly_name: wide_to_string(a.FriendlyName),
+                        description: wide_to_string(a.Description),
+                        link_speed_mbps: speed,
+                        oper_up: Some(a.OperStatus == IfOperStatusUp),
+                    },
+                );
+                adapter = a.Next;
+            }
+        }
+        details
+    }
+
+    /// SAFETY: `s` must be null or point to a NUL-terminated UTF-16 string.
+    unsafe fn wide_to_string(s: *const u16) -> Option<String> {
+        if s.is_null() {
+            return None;
+        }
+        let mut len = 0;
+        while *s.add(len) != 0 {
+            len += 1;
+        }
+        let text = String::from_utf16_lossy(std::slice::from_raw_parts(s, len));
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    use std::collections::HashMap;
+
+    use super::AdapterDetails;
+
+    pub(super) fn adapter_key(name: &str) -> &str {
+        name
+    }
+
+    pub(super) fn adapter_details() -> HashMap<String, AdapterDetails> {
+        HashMap::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,4 +301,22 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_parse_sysfs_speed() {
+        assert_eq!(parse_sysfs_speed("1000\n"), Some(1000));
+        // Link down, or a virtual interface
+        assert_eq!(parse_sysfs_speed("-1\n"), None);
+        assert_eq!(parse_sysfs_speed(""), None);
+    }
+
+    #[test]
+    fn test_npf_guid() {
+        assert_eq!(
+            npf_guid(r"\Device\NPF_{4E2F3A1B-0000-4C5D-9E8F-112233445566}"),
+            "{4E2F3A1B-0000-4C5D-9E8F-112233445566}"
+        );
+        assert_eq!(npf_guid(r"\Device\NPF_Loopback"), "Loopback");
+        assert_eq!(npf_guid("eth0"), "eth0");
+    }
 }
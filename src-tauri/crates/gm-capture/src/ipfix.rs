@@ -0,0 +1,720 @@
+//! IPFIX (RFC 7011) flow export from live capture.
+//!
+//! Packets seen by the live pipeline are metered into unidirectional flows
+//! keyed by 5-tuple and exported over UDP to an external collector, so the
+//! tool can feed a SOC flow store while it builds its local topology.
+//!
+//! Sampling is systematic count-based (RFC 5475): with an interval of N,
+//! every Nth TCP/UDP packet is metered. Exported counters are the sampled
+//! counts; each record carries `samplingPacketInterval` so the collector
+//! can scale them. Flows are exported when idle, when they exceed the
+//! active timeout (the flow restarts with fresh delta counters), and when
+//! the capture stops.
+//!
+//! Export traffic leaves through whatever interface routes to the
+//! collector — the capture interface itself is still never written to.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::error::CaptureError;
+use crate::packet::{ParsedPacket, TransportProtocol};
+
+/// IPFIX protocol version number carried in every message header.
+const IPFIX_VERSION: u16 = 10;
+
+/// Set ID of a template set.
+const TEMPLATE_SET_ID: u16 = 2;
+
+/// Template ID for IPv4 flow records.
+pub const TEMPLATE_ID_V4: u16 = 256;
+
+/// Template ID for IPv6 flow records.
+pub const TEMPLATE_ID_V6: u16 = 257;
+
+/// Upper bound on an encoded message, kept under a typical path MTU.
+const MAX_MESSAGE_LEN: usize = 1400;
+
+/// How often templates are resent; UDP collectors may miss the first copy.
+const TEMPLATE_REFRESH: Duration = Duration::from_secs(60);
+
+/// Field length marking a variable-length information element.
+const VARIABLE_LENGTH: u16 = 0xFFFF;
+
+/// Longest applicationName exported (fits the one-byte length prefix).
+const MAX_APPLICATION_NAME: usize = 254;
+
+/// Information elements (IANA ID, length) shared by both templates, after
+/// the source/destination address fields.
+#[rustfmt::skip]
+const COMMON_FIELDS: [(u16, u16); 10] = [
+    (7, 2),                 // sourceTransportPort
+    (11, 2),                // destinationTransportPort
+    (4, 1),                 // protocolIdentifier
+    (2, 8),                 // packetDeltaCount
+    (1, 8),                 // octetDeltaCount
+    (152, 8),               // flowStartMilliseconds
+    (153, 8),               // flowEndMilliseconds
+    (136, 1),               // flowEndReason
+    (305, 4),               // samplingPacketInterval
+    (96, VARIABLE_LENGTH),  // applicationName
+];
+
+/// IPFIX exporter settings.
+#[derive(Debug, Clone)]
+pub struct IpfixConfig {
+    /// Collector address as "host:port" (UDP)
+    pub collector: String,
+    /// Observation domain ID placed in every message header
+    pub observation_domain_id: u32,
+    /// Meter one packet in every N (1 = every packet)
+    pub sampling_interval: u32,
+    /// Export a long-lived flow at least this often
+    pub active_timeout: Duration,
+    /// Export a flow once it has been silent this long
+    pub idle_timeout: Duration,
+}
+
+impl IpfixConfig {
+    /// Unsampled export to `collector` with the usual 60s/15s timeouts.
+    pub fn new(collector: impl Into<String>) -> Self {
+        Self {
+            collector: collector.into(),
+            observation_domain_id: 0,
+            sampling_interval: 1,
+            active_timeout: Duration::from_secs(60),
+            idle_timeout: Duration::from_secs(15),
+        }
+    }
+}
+
+/// Why a flow record was exported (IANA flowEndReason values).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowEndReason {
+    IdleTimeout = 1,
+    ActiveTimeout = 2,
+    ForcedEnd = 4,
+}
+
+/// Unidirectional flow key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FlowKey {
+    pub src_ip: IpAddr,
+    pub dst_ip: IpAddr,
+    pub src_port: u16,
+    pub dst_port: u16,
+    /// IP protocol number (6 = TCP, 17 = UDP)
+    pub protocol: u8,
+}
+
+/// A finished flow ready for export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowRecord {
+    pub key: FlowKey,
+    pub packets: u64,
+    pub octets: u64,
+    /// Epoch milliseconds of the first metered packet
+    pub start_ms: u64,
+    /// Epoch milliseconds of the last metered packet
+    pub end_ms: u64,
+    /// Application label (e.g. "modbus"), empty if unknown
+    pub application: String,
+    pub end_reason: FlowEndReason,
+}
+
+#[derive(Debug)]
+struct ActiveFlow {
+    packets: u64,
+    octets: u64,
+    start_ms: u64,
+    end_ms: u64,
+    application: String,
+}
+
+/// Flow cache with count-based sampling and active/idle timeouts.
+#[derive(Debug)]
+pub struct FlowMeter {
+    flows: HashMap<FlowKey, ActiveFlow>,
+    sampling_interval: u32,
+    sample_counter: u32,
+    active_timeout_ms: u64,
+    idle_timeout_ms: u64,
+}
+
+impl FlowMeter {
+    pub fn new(sampling_interval: u32, active_timeout: Duration, idle_timeout: Duration) -> Self {
+        Self {
+            flows: HashMap::new(),
+            sampling_interval: sampling_interval.max(1),
+            sample_counter: 0,
+            active_timeout_ms: active_timeout.as_millis() as u64,
+            idle_timeout_ms: idle_timeout.as_millis() as u64,
+        }
+    }
+
+    /// Number of flows currently being metered.
+    pub fn active_flows(&self) -> usize {
+        self.flows.len()
+    }
+
+    /// Meter one packet. Non-IP packets (L2 sentinels) and transports other
+    /// than TCP, UDP and ICMP are ignored; `application` labels the flow if it is new
+    /// or still unlabelled.
+    pub fn observe(&mut self, packet: &ParsedPacket, application: &str) {
+        let protocol = match packet.transport {
+            TransportProtocol::Tcp => 6,
+            TransportProtocol::Udp => 17,
+            TransportProtocol::Icmp => 1,
+            TransportProtocol::Icmpv6 => 58,
+            TransportProtocol::Other => return,
+        };
+        let (Ok(src_ip), Ok(dst_ip)) = (
+            packet.src_ip.parse::<IpAddr>(),
+            packet.dst_ip.parse::<IpAddr>(),
+        ) else {
+            return;
+        };
+
+        self.sample_counter += 1;
+        if self.sample_counter < self.sampling_interval {
+            return;
+        }
+        self.sample_counter = 0;
+
+        let key = FlowKey {
+            src_ip,
+            dst_ip,
+            src_port: packet.src_port,
+            dst_port: packet.dst_port,
+            protocol,
+        };
+        let ts = packet.timestamp.timestamp_millis().max(0) as u64;
+        let flow = self.flows.entry(key).or_insert_with(|| ActiveFlow {
+            packets: 0,
+            octets: 0,
+            start_ms: ts,
+            end_ms: ts,
+            application: String::new(),
+        });
+        flow.packets += 1;
+        flow.octets += packet.length as u64;
+        flow.start_ms = flow.start_ms.min(ts);
+        flow.end_ms = flow.end_ms.max(ts);
+        if flow.application.is_empty() {
+            flow.application = application.to_string();
+        }
+    }
+
+    /// Remove and return flows that hit the idle or active timeout at `now`.
+    pub fn expire(&mut self, now: DateTime<Utc>) -> Vec<FlowRecord> {
+        let now_ms = now.timestamp_millis().max(0) as u64;
+        let mut expired = Vec::new();
+        self.flows.retain(|key, flow| {
+            let reason = if now_ms.saturating_sub(flow.end_ms) >= self.idle_timeout_ms {
+                FlowEndReason::IdleTimeout
+            } else if now_ms.saturating_sub(flow.start_ms) >= self.active_timeout_ms {
+                FlowEndReason::ActiveTimeout
+            } else {
+                return true;
+            };
+            expired.push(to_record(*key, flow, reason));
+            false
+        });
+        expired
+    }
+
+    /// Remove and return every flow (capture stopped).
+    pub fn drain(&mut self) -> Vec<FlowRecord> {
+        self.flows
+            .drain()
+            .map(|(key, flow)| to_record(key, &flow, FlowEndReason::ForcedEnd))
+            .collect()
+    }
+}
+
+fn to_record(key: FlowKey, flow: &ActiveFlow, end_reason: FlowEndReason) -> FlowRecord {
+    FlowRecord {
+        key,
+        packets: flow.packets,
+        octets: flow.octets,
+        start_ms: flow.start_ms,
+        end_ms: flow.end_ms,
+        application: flow.application.clone(),
+        end_reason,
+    }
+}
+
+/// Builds IPFIX messages for one observation domain.
+#[derive(Debug)]
+pub struct IpfixEncoder {
+    observation_domain_id: u32,
+    sampling_interval: u32,
+    /// Data records sent so far (the header sequence number, mod 2^32)
+    sequence: u32,
+}
+
+impl IpfixEncoder {
+    pub fn new(observation_domain_id: u32, sampling_interval: u32) -> Self {
+        Self {
+            observation_domain_id,
+            sampling_interval: sampling_interval.max(1),
+            sequence: 0,
+        }
+    }
+
+    /// A message holding the IPv4 and IPv6 template records.
+    pub fn template_message(&self, export_time: DateTime<Utc>) -> Vec<u8> {
+        let mut msg = self.header(export_time);
+        let set_start = msg.len();
+        put_u16(&mut msg, TEMPLATE_SET_ID);
+        put_u16(&mut msg, 0);
+        for (template_id, addr_fields) in [
+            (TEMPLATE_ID_V4, [(8, 4), (12, 4)]),
+            (TEMPLATE_ID_V6, [(27, 16), (28, 16)]),
+        ] {
+            put_u16(&mut msg, template_id);
+            put_u16(&mut msg, (addr_fields.len() + COMMON_FIELDS.len()) as u16);
+            for (id, len) in addr_fields.into_iter().chain(COMMON_FIELDS) {
+                put_u16(&mut msg, id);
+                put_u16(&mut msg, len);
+            }
+        }
+        finish_set(&mut msg, set_start);
+        finish_message(&mut msg);
+        msg
+    }
+
+    /// Encode `records` into as many data messages as needed to stay under
+    /// [`MAX_MESSAGE_LEN`], advancing the sequence number.
+    pub fn data_messages(
+        &mut self,
+        records: &[FlowRecord],
+        export_time: DateTime<Utc>,
+    ) -> Vec<Vec<u8>> {
+        let mut messages = Vec::new();
+        let mut msg: Vec<u8> = Vec::new();
+        // (template ID, offset of the open set header)
+        let mut open_set: Option<(u16, usize)> = None;
+        let mut msg_records: u32 = 0;
+
+        for record in records {
+            let template_id = match record.key.src_ip {
+                IpAddr::V4(_) => TEMPLATE_ID_V4,
+                IpAddr::V6(_) => TEMPLATE_ID_V6,
+            };
+            let encoded = self.encode_record(record);
+
+            let needs_new_set = open_set.map(|(id, _)| id) != Some(template_id);
+            let added = encoded.len() + if needs_new_set { 4 } else { 0 };
+            if !msg.is_empty() && msg.len() + added > MAX_MESSAGE_LEN {
+                if let Some((_, start)) = open_set.take() {
+                    finish_set(&mut msg, start);
+                }
+                finish_message(&mut msg);
+                messages.push(std::mem::take(&mut msg));
+                self.sequence = self.sequence.wrapping_add(msg_records);
+                msg_records = 0;
+            }
+            if msg.is_empty() {
+                msg = self.header(export_time);
+            }
+            if open_set.map(|(id, _)| id) != Some(template_id) {
+                if let Some((_, start)) = open_set.take() {
+                    finish_set(&mut msg, start);
+                }
+                open_set = Some((template_id, msg.len()));
+                put_u16(&mut msg, template_id);
+                put_u16(&mut msg, 0);
+            }
+            msg.extend_from_slice(&encoded);
+            msg_records += 1;
+        }
+
+        if !msg.is_empty() {
+            if let Some((_, start)) = open_set {
+                finish_set(&mut msg, start);
+            }
+            finish_message(&mut msg);
+            messages.push(msg);
+            self.sequence = self.sequence.wrapping_add(msg_records);
+        }
+        messages
+    }
+
+    fn header(&self, export_time: DateTime<Utc>) -> Vec<u8> {
+        let mut msg = Vec::with_capacity(MAX_MESSAGE_LEN);
+        put_u16(&mut msg, IPFIX_VERSION);
+        put_u16(&mut msg, 0); // length, filled by finish_message
+        put_u32(&mut msg, export_time.timestamp().max(0) as u32);
+        put_u32(&mut msg, self.sequence);
+        put_u32(&mut msg, self.observation_domain_id);
+        msg
+    }
+
+    fn encode_record(&self, record: &FlowRecord) -> Vec<u8> {
+        let mut out = Vec::with_capacity(96);
+        match (record.key.src_ip, record.key.dst_ip) {
+            (IpAddr::V4(src), IpAddr::V4(dst)) => {
+                out.extend_from_slice(&src.octets());
+                out.extend_from_slice(&dst.octets());
+            }
+            (src, dst) => {
+                out.extend_from_slice(&to_v6(src).octets());
+                out.extend_from_slice(&to_v6(dst).octets());
+            }
+        }
+        put_u16(&mut out, record.key.src_port);
+        put_u16(&mut out, record.key.dst_port);
+        out.push(record.key.protocol);
+        put_u64(&mut out, record.packets);
+        put_u64(&mut out, record.octets);
+        put_u64(&mut out, record.start_ms);
+        put_u64(&mut out, record.end_ms);
+        out.push(record.end_reason as u8);
+        put_u32(&mut out, self.sampling_interval);
+        let name = truncate_utf8(&record.application, MAX_APPLICATION_NAME);
+        out.push(name.len() as u8);
+        out.extend_from_slice(name.as_bytes());
+        out
+    }
+}
+
+fn to_v6(ip: IpAddr) -> std::net::Ipv6Addr {
+    match ip {
+        IpAddr::V4(v4) => v4.to_ipv6_mapped(),
+        IpAddr::V6(v6) => v6,
+    }
+}
+
+fn truncate_utf8(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+fn put_u16(buf: &mut Vec<u8>, v: u16) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+fn put_u32(buf: &mut Vec<u8>, v: u32) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+fn put_u64(buf: &mut Vec<u8>, v: u64) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+fn finish_set(msg: &mut [u8], set_start: usize) {
+    let len = (msg.len() - set_start) as u16;
+    msg[set_start + 2..set_start + 4].copy_from_slice(&len.to_be_bytes());
+}
+
+fn finish_message(msg: &mut [u8]) {
+    let len = msg.len() as u16;
+    msg[2..4].copy_from_slice(&len.to_be_bytes());
+}
+
+/// Meters live packets and sends IPFIX messages to a UDP collector.
+#[derive(Debug)]
+pub struct IpfixExporter {
+    socket: UdpSocket,
+    meter: FlowMeter,
+    encoder: IpfixEncoder,
+    last_templates: Option<Instant>,
+    records_exported: u64,
+}
+
+impl IpfixExporter {
+    /// Resolve the collector and open the export socket.
+    pub fn new(config: &IpfixConfig) -> Result<Self, CaptureError> {
+        let collector = config
+            .collector
+            .to_socket_addrs()
+            .map_err(|e| CaptureError::Export(format!("{}: {}", config.collector, e)))?
+            .next()
+            .ok_or_else(|| {
+                CaptureError::Export(format!("{}: no address resolved", config.collector))
+            })?;
+        let bind_addr = if collector.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind_addr)
+            .and_then(|s| s.connect(collector).map(|_| s))
+            .map_err(|e| CaptureError::Export(format!("{}: {}", collector, e)))?;
+
+        Ok(Self {
+            socket,
+            meter: FlowMeter::new(
+                config.sampling_interval,
+                config.active_timeout,
+                config.idle_timeout,
+            ),
+            encoder: IpfixEncoder::new(config.observation_domain_id, config.sampling_interval),
+            last_templates: None,
+            records_exported: 0,
+        })
+    }
+
+    /// Meter one packet; see [`FlowMeter::observe`].
+    pub fn observe(&mut self, packet: &ParsedPacket, application: &str) {
+        self.meter.observe(packet, application);
+    }
+
+    /// Export flows that have timed out as of `now`. Returns the number of
+    /// records sent.
+    pub fn export_expired(&mut self, now: DateTime<Utc>) -> Result<usize, CaptureError> {
+        let records = self.meter.expire(now);
+        self.send(&records, now)
+    }
+
+    /// Export every remaining flow; call when the capture stops.
+    pub fn finish(&mut self) -> Result<usize, CaptureError> {
+        let records = self.meter.drain();
+        self.send(&records, Utc::now())
+    }
+
+    /// Total flow records sent to the collector.
+    pub fn records_exported(&self) -> u64 {
+        self.records_exported
+    }
+
+    fn send(&mut self, records: &[FlowRecord], now: DateTime<Utc>) -> Result<usize, CaptureError> {
+        if records.is_empty() {
+            return Ok(0);
+        }
+        let templates_due = self
+            .last_templates
+            .is_none_or(|t| t.elapsed() >= TEMPLATE_REFRESH);
+        if templates_due {
+            self.send_message(&self.encoder.template_message(now))?;
+            self.last_templates = Some(Instant::now());
+        }
+        for msg in self.encoder.data_messages(records, now) {
+            self.send_message(&msg)?;
+        }
+        self.records_exported += records.len() as u64;
+        Ok(records.len())
+    }
+
+    fn send_message(&self, msg: &[u8]) -> Result<(), CaptureError> {
+        self.socket
+            .send(msg)
+            .map(|_| ())
+            .map_err(|e| CaptureError::Export(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(src: &str, dst: &str, dst_port: u16, secs: i64, length: usize) -> ParsedPacket {
+        ParsedPacket {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            src_mac: None,
+            dst_mac: None,
+            vlan_id: None,
+            outer_vlan_id: None,
+            mpls_labels: Vec::new(),
+            vxlan_vni: None,
+            src_ip: src.to_string(),
+            dst_ip: dst.to_string(),
+            transport: TransportProtocol::Tcp,
+            src_port: 49152,
+            dst_port,
+            tcp_flags: 0,
+            tcp_seq: 0,
+            length,
+            payload: Vec::new(),
+            origin_file: "live".to_string(),
+        }
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn be16(b: &[u8], at: usize) -> u16 {
+        u16::from_be_bytes([b[at], b[at + 1]])
+    }
+
+    fn be32(b: &[u8], at: usize) -> u32 {
+        u32::from_be_bytes(b[at..at + 4].try_into().unwrap())
+    }
+
+    fn be64(b: &[u8], at: usize) -> u64 {
+        u64::from_be_bytes(b[at..at + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_meter_aggregates_and_expires() {
+        let mut meter = FlowMeter::new(1, Duration::from_secs(60), Duration::from_secs(15));
+        meter.observe(&packet("10.0.0.5", "10.0.0.100", 502, 0, 66), "modbus");
+        meter.observe(&packet("10.0.0.5", "10.0.0.100", 502, 5, 70), "modbus");
+        meter.observe(&packet("10.0.0.100", "10.0.0.5", 49152, 5, 60), "modbus");
+        // L2 sentinels carry no IP and are not metered
+        meter.observe(
+            &packet("goose:01:0c:cd:01:00:01", "goose:x", 0, 5, 100),
+            "goose",
+        );
+        assert_eq!(meter.active_flows(), 2);
+
+        assert!(meter.expire(at(10)).is_empty());
+        let mut expired = meter.expire(at(21));
+        expired.sort_by_key(|r| r.key.dst_port);
+        assert_eq!(expired.len(), 2);
+        let fwd = &expired[0];
+        assert_eq!(fwd.key.dst_port, 502);
+        assert_eq!(fwd.key.protocol, 6);
+        assert_eq!(fwd.packets, 2);
+        assert_eq!(fwd.octets, 136);
+        assert_eq!(fwd.end_ms - fwd.start_ms, 5000);
+        assert_eq!(fwd.application, "modbus");
+        assert_eq!(fwd.end_reason, FlowEndReason::IdleTimeout);
+        assert_eq!(meter.active_flows(), 0);
+    }
+
+    #[test]
+    fn test_meter_active_timeout_and_sampling() {
+        let mut meter = FlowMeter::new(1, Duration::from_secs(60), Duration::from_secs(15));
+        for s in 0..=70 {
+            meter.observe(&packet("10.0.0.5", "10.0.0.100", 502, s, 60), "modbus");
+        }
+        let expired = meter.expire(at(70));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].end_reason, FlowEndReason::ActiveTimeout);
+        assert_eq!(expired[0].packets, 71);
+
+        let mut sampled = FlowMeter::new(4, Duration::from_secs(60), Duration::from_secs(15));
+        for s in 0..10 {
+            sampled.observe(&packet("10.0.0.5", "10.0.0.100", 502, s, 60), "modbus");
+        }
+        let drained = sampled.drain();
+        assert_eq!(drained[0].packets, 2);
+        assert_eq!(drained[0].end_reason, FlowEndReason::ForcedEnd);
+    }
+
+    #[test]
+    fn test_template_message_layout() {
+        let encoder = IpfixEncoder::new(7, 1);
+        let msg = encoder.template_message(at(0));
+        assert_eq!(be16(&msg, 0), 10);
+        assert_eq!(be16(&msg, 2) as usize, msg.len());
+        assert_eq!(be32(&msg, 4), 1_700_000_000);
+        assert_eq!(be32(&msg, 12), 7);
+        // Template set spans the rest of the message
+        assert_eq!(be16(&msg, 16), TEMPLATE_SET_ID);
+        assert_eq!(be16(&msg, 18) as usize, msg.len() - 16);
+        // IPv4 template: 12 fields, starting with sourceIPv4Address
+        assert_eq!(be16(&msg, 20), TEMPLATE_ID_V4);
+        assert_eq!(be16(&msg, 22), 12);
+        assert_eq!((be16(&msg, 24), be16(&msg, 26)), (8, 4));
+        // IPv6 template follows 12 field specifiers
+        let v6 = 24 + 12 * 4;
+        assert_eq!(be16(&msg, v6), TEMPLATE_ID_V6);
+        assert_eq!((be16(&msg, v6 + 4), be16(&msg, v6 + 6)), (27, 16));
+    }
+
+    #[test]
+    fn test_data_record_encoding() {
+        let mut meter = FlowMeter::new(1, Duration::from_secs(60), Duration::from_secs(15));
+        meter.observe(&packet("10.0.0.5", "10.0.0.100", 502, 0, 66), "modbus");
+        let records = meter.drain();
+
+        let mut encoder = IpfixEncoder::new(0, 1);
+        let msgs = encoder.data_messages(&records, at(1));
+        assert_eq!(msgs.len(), 1);
+        let m = &msgs[0];
+        assert_eq!(be16(m, 2) as usize, m.len());
+        assert_eq!(be32(m, 8), 0);
+        assert_eq!(be16(m, 16), TEMPLATE_ID_V4);
+        assert_eq!(be16(m, 18) as usize, m.len() - 16);
+        let r = 20;
+        assert_eq!(&m[r..r + 4], &[10, 0, 0, 5]);
+        assert_eq!(&m[r + 4..r + 8], &[10, 0, 0, 100]);
+        assert_eq!(be16(m, r + 8), 49152);
+        assert_eq!(be16(m, r + 10), 502);
+        assert_eq!(m[r + 12], 6);
+        assert_eq!(be64(m, r + 13), 1);
+        assert_eq!(be64(m, r + 21), 66);
+        assert_eq!(be64(m, r + 29), 1_700_000_000_000);
+        assert_eq!(m[r + 45], FlowEndReason::ForcedEnd as u8);
+        assert_eq!(be32(m, r + 46), 1);
+        assert_eq!(m[r + 50], 6);
+        assert_eq!(&m[r + 51..], b"modbus");
+
+        // Next message's sequence number counts the record just sent
+        let msgs = encoder.data_messages(&records, at(2));
+        assert_eq!(be32(&msgs[0], 8), 1);
+    }
+
+    #[test]
+    fn test_data_messages_split_and_mix_families() {
+        let mut meter = FlowMeter::new(1, Duration::from_secs(60), Duration::from_secs(15));
+        for port in 0..100u16 {
+            meter.observe(&packet("10.0.0.5", "10.0.0.100", port, 0, 60), "");
+        }
+        meter.observe(&packet("fe80::1", "fe80::2", 502, 0, 80), "modbus");
+        let records = meter.drain();
+
+        let mut encoder = IpfixEncoder::new(0, 1);
+        let msgs = encoder.data_messages(&records, at(1));
+        assert!(msgs.len() > 1);
+        let mut expected_seq = 0;
+        for m in &msgs {
+            assert!(m.len() <= MAX_MESSAGE_LEN);
+            assert_eq!(be16(m, 2) as usize, m.len());
+            assert_eq!(be32(m, 8), expected_seq);
+            // Walk the sets and count records by template
+            let mut off = 16;
+            while off < m.len() {
+                let (id, len) = (be16(m, off), be16(m, off + 2) as usize);
+                let rec_len = if id == TEMPLATE_ID_V4 { 51 } else { 75 };
+                let mut r = off + 4;
+                while r < off + len {
+                    r += rec_len + m[r + rec_len - 1] as usize;
+                    expected_seq += 1;
+                }
+                assert_eq!(r, off + len);
+                off += len;
+            }
+        }
+        assert_eq!(expected_seq, 101);
+    }
+
+    #[test]
+    fn test_exporter_sends_templates_and_data() {
+        let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+        collector
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let config = IpfixConfig::new(collector.local_addr().unwrap().to_string());
+
+        let mut exporter = IpfixExporter::new(&config).unwrap();
+        exporter.observe(&packet("10.0.0.5", "10.0.0.100", 502, 0, 66), "modbus");
+        assert_eq!(exporter.export_expired(at(1)).unwrap(), 0);
+        assert_eq!(exporter.finish().unwrap(), 1);
+        assert_eq!(exporter.records_exported(), 1);
+
+        let mut buf = [0u8; 2048];
+        let n = collector.recv(&mut buf).unwrap();
+        assert_eq!(be16(&buf[..n], 16), TEMPLATE_SET_ID);
+        let n = collector.recv(&mut buf).unwrap();
+        assert_eq!(be16(&buf[..n], 16), TEMPLATE_ID_V4);
+    }
+
+    #[test]
+    fn test_exporter_rejects_bad_collector() {
+        assert!(IpfixExporter::new(&IpfixConfig::new("not a collector")).is_err());
+    }
+}
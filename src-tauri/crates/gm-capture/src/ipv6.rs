@@ -0,0 +1,158 @@
+//! IPv6 header helpers shared by the packet paths that do not go through
+//! etherparse (filtered export) and by address formatting.
+//!
+//! etherparse already walks extension headers for the import and live
+//! capture paths; the walker here exists so code that parses raw frames
+//! inline reaches the same upper-layer header.
+
+use std::net::Ipv6Addr;
+
+/// Fixed IPv6 header length.
+pub(crate) const HEADER_LEN: usize = 40;
+
+// Extension header next-header values
+const HOP_BY_HOP: u8 = 0;
+const ROUTING: u8 = 43;
+const FRAGMENT: u8 = 44;
+const AUTHENTICATION: u8 = 51;
+const DESTINATION_OPTIONS: u8 = 60;
+const MOBILITY: u8 = 135;
+const HIP: u8 = 139;
+const SHIM6: u8 = 140;
+
+/// Format an IPv6 address in its canonical (RFC 5952) text form, the same
+/// form the DNS, ingest and export paths use, so the same host is keyed by
+/// one string everywhere.
+pub(crate) fn format_address(bytes: [u8; 16]) -> String {
+    Ipv6Addr::from(bytes).to_string()
+}
+
+/// The upper-layer protocol carried by an IPv6 packet, after any extension
+/// headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UpperLayer<'a> {
+    /// Source address
+    pub src: [u8; 16],
+    /// Destination address
+    pub dst: [u8; 16],
+    /// Protocol number of the upper layer (6 = TCP, 17 = UDP, 58 = ICMPv6)
+    pub protocol: u8,
+    /// Bytes following the last extension header
+    pub payload: &'a [u8],
+    /// False for the second and later fragments of a fragmented packet,
+    /// whose payload does not start with the upper-layer header
+    pub starts_upper_header: bool,
+}
+
+/// Walk the IPv6 header and its extension headers.
+///
+/// `packet` starts at the IPv6 version nibble. Returns None if the header
+/// is truncated or is not version 6. An ESP header (50) ends the walk with
+/// protocol 50, since everything after it is encrypted.
+pub(crate) fn upper_layer(packet: &[u8]) -> Option<UpperLayer<'_>> {
+    if packet.len() < HEADER_LEN || packet[0] >> 4 != 6 {
+        return None;
+    }
+    let src: [u8; 16] = packet[8..24].try_into().ok()?;
+    let dst: [u8; 16] = packet[24..40].try_into().ok()?;
+
+    let mut next = packet[6];
+    let mut offset = HEADER_LEN;
+    let mut starts_upper_header = true;
+    loop {
+        let len = match next {
+            HOP_BY_HOP | ROUTING | DESTINATION_OPTIONS | MOBILITY | HIP | SHIM6 => {
+                (*packet.get(offset + 1)? as usize + 1) * 8
+            }
+            FRAGMENT => {
+                let fragment = packet.get(offset..offset + 8)?;
+                let fragment_offset = u16::from_be_bytes([fragment[2], fragment[3]]) >> 3;
+                if fragment_offset != 0 {
+                    starts_upper_header = false;
+                }
+                8
+            }
+            AUTHENTICATION => (*packet.get(offset + 1)? as usize + 2) * 4,
+            _ => break,
+        };
+        if packet.len() < offset + len {
+            return None;
+        }
+        next = packet[offset];
+        offset += len;
+    }
+
+    Some(UpperLayer {
+        src,
+        dst,
+        protocol: next,
+        payload: &packet[offset..],
+        starts_upper_header,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(next_header: u8) -> Vec<u8> {
+        let mut packet = vec![0x60, 0, 0, 0, 0, 0, next_header, 64];
+        packet.extend_from_slice(&[0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+        packet.extend_from_slice(&[
+            0x20, 0x01, 0x0D, 0xB8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02,
+        ]);
+        packet
+    }
+
+    #[test]
+    fn test_format_address_is_canonical() {
+        let mut bytes = [0u8; 16];
+        bytes[0] = 0xFE;
+        bytes[1] = 0x80;
+        bytes[15] = 0x01;
+        assert_eq!(format_address(bytes), "fe80::1");
+        assert_eq!(format_address([0; 16]), "::");
+    }
+
+    #[test]
+    fn test_walks_extension_headers() {
+        // Hop-by-hop (router alert) → destination options → UDP
+        let mut packet = header(HOP_BY_HOP);
+        packet.extend_from_slice(&[DESTINATION_OPTIONS, 0, 0x05, 0x02, 0, 0, 0x01, 0x00]);
+        packet.extend_from_slice(&[17, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        packet.extend_from_slice(&[0xBA, 0xC0, 0xBA, 0xC0, 0x00, 0x08, 0x00, 0x00]);
+
+        let upper = upper_layer(&packet).unwrap();
+        assert_eq!(upper.protocol, 17);
+        assert_eq!(upper.payload[..2], [0xBA, 0xC0]);
+        assert!(upper.starts_upper_header);
+        assert_eq!(format_address(upper.src), "fe80::1");
+        assert_eq!(format_address(upper.dst), "2001:db8::2");
+
+        // Truncated extension header
+        assert!(upper_layer(&packet[..HEADER_LEN + 12]).is_none());
+        // Not IPv6
+        let mut v4 = packet.clone();
+        v4[0] = 0x45;
+        assert!(upper_layer(&v4).is_none());
+    }
+
+    #[test]
+    fn test_fragment_header() {
+        // First fragment: TCP header follows
+        let mut first = header(FRAGMENT);
+        first.extend_from_slice(&[6, 0, 0x00, 0x01, 0, 0, 0x12, 0x34]);
+        first.extend_from_slice(&[0xC0, 0x00, 0x11, 0x5C]);
+        let upper = upper_layer(&first).unwrap();
+        assert_eq!(upper.protocol, 6);
+        assert!(upper.starts_upper_header);
+
+        // Later fragment (offset 1448 bytes)
+        let mut later = header(FRAGMENT);
+        later.extend_from_slice(&[6, 0, 0x05, 0xA8, 0, 0, 0x12, 0x34]);
+        later.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        let upper = upper_layer(&later).unwrap();
+        assert_eq!(upper.protocol, 6);
+        assert!(!upper.starts_upper_header);
+    }
+}
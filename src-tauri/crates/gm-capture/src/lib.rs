@@ -6,17 +6,39 @@
 //! This crate extracts Layer 2-4 information (MAC, IP, ports, transport)
 //! and passes raw payload bytes to gm-parsers for protocol identification.
 
+mod capture_dir;
+mod decompress;
+mod defrag;
 mod error;
 mod interface;
+pub mod ipfix;
+mod ipv6;
+mod link;
 pub mod live;
+mod mpls;
 mod packet;
 pub(crate) mod parsing;
 mod pcap_filter;
 mod pcap_reader;
+mod queue;
+mod remote;
+mod rotation;
+mod sampling;
+mod tcp_stream;
+mod vlan;
+mod vxlan;
 
+pub use capture_dir::{is_capture_file_name, list_capture_files};
 pub use error::CaptureError;
 pub use interface::{list_interfaces, InterfaceAddress, InterfaceFlags, NetworkInterface};
-pub use live::{CaptureStats, LiveCaptureConfig, LiveCaptureHandle};
-pub use packet::{ParsedPacket, TransportProtocol};
-pub use pcap_filter::filter_export_pcap;
-pub use pcap_reader::{FileProcessStats, PcapReader, ProgressUpdate};
+pub use ipfix::{IpfixConfig, IpfixExporter};
+pub use live::{
+    validate_bpf_filter, CaptureStats, LiveCaptureConfig, LiveCaptureHandle, MAX_SNAPLEN,
+};
+pub use packet::{tcp_flags, ParsedPacket, TransportProtocol};
+pub use pcap_filter::{export_conversations_pcap, filter_export_pcap, ConversationFilter};
+pub use pcap_reader::{FileProcessStats, PacketStream, PcapReader, ProgressUpdate};
+pub use queue::{QueuePolicy, QueueReceiver, QueueStats};
+pub use remote::RemoteSource;
+pub use rotation::RotationConfig;
+pub use sampling::ImportSampling;
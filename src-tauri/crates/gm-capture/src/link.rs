@@ -0,0 +1,372 @@
+//! Link-layer normalization.
+//!
+//! The parsers work on Ethernet II frames. Captures with other link-layer
+//! headers are rewritten to Ethernet first:
+//! - `tcpdump -i any` produces Linux "cooked" headers (SLL, or SLL2 on newer
+//!   libpcap), which carry the Ethertype and the sender's link address but
+//!   no destination address.
+//! - Industrial wireless bridges and monitor-mode captures produce 802.11
+//!   frames, usually behind a Radiotap header. Data frames carrying LLC/SNAP
+//!   map onto Ethernet directly; management, control and encrypted frames
+//!   have nothing for the parsers and are dropped.
+
+use std::borrow::Cow;
+
+/// DLT_EN10MB
+pub(crate) const LINKTYPE_ETHERNET: i32 = 1;
+/// DLT_IEEE802_11
+pub(crate) const LINKTYPE_IEEE802_11: i32 = 105;
+/// DLT_LINUX_SLL
+pub(crate) const LINKTYPE_LINUX_SLL: i32 = 113;
+/// DLT_IEEE802_11_RADIO
+pub(crate) const LINKTYPE_IEEE802_11_RADIOTAP: i32 = 127;
+/// DLT_LINUX_SLL2
+pub(crate) const LINKTYPE_LINUX_SLL2: i32 = 276;
+
+const SLL_HEADER_LEN: usize = 16;
+const SLL2_HEADER_LEN: usize = 20;
+/// ARPHRD_ETHER: the link address is a MAC
+const ARPHRD_ETHER: u16 = 1;
+
+/// 802.11 MAC header without the fourth address or QoS control
+const WLAN_HEADER_LEN: usize = 24;
+/// RFC 1042 and 802.1H (bridge tunnel) SNAP headers, minus the Ethertype
+const SNAP_RFC1042: [u8; 6] = [0xAA, 0xAA, 0x03, 0x00, 0x00, 0x00];
+const SNAP_BRIDGE_TUNNEL: [u8; 6] = [0xAA, 0xAA, 0x03, 0x00, 0x00, 0xF8];
+
+/// A captured frame as Ethernet II.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EthernetFrame<'a> {
+    pub data: Cow<'a, [u8]>,
+    /// Whether the source MAC is real (the capture recorded it)
+    pub src_mac_known: bool,
+    /// Whether the destination MAC is real
+    pub dst_mac_known: bool,
+}
+
+impl<'a> EthernetFrame<'a> {
+    fn native(data: &'a [u8]) -> Self {
+        Self {
+            data: Cow::Borrowed(data),
+            src_mac_known: true,
+            dst_mac_known: true,
+        }
+    }
+}
+
+/// Rewrite a captured frame of the given link type as Ethernet II.
+///
+/// Ethernet and unrecognized link types are passed through unchanged.
+/// Returns None for a truncated link header and for 802.11 frames that
+/// carry no unencrypted LLC/SNAP payload.
+pub(crate) fn to_ethernet(linktype: i32, data: &[u8]) -> Option<EthernetFrame<'_>> {
+    match linktype {
+        LINKTYPE_LINUX_SLL => {
+            let header = data.get(..SLL_HEADER_LEN)?;
+            let hatype = u16::from_be_bytes([header[2], header[3]]);
+            let addr_len = u16::from_be_bytes([header[4], header[5]]);
+            let protocol = u16::from_be_bytes([header[14], header[15]]);
+            Some(cooked(
+                (hatype == ARPHRD_ETHER && addr_len == 6).then(|| &header[6..12]),
+                protocol,
+                &data[SLL_HEADER_LEN..],
+            ))
+        }
+        LINKTYPE_LINUX_SLL2 => {
+            let header = data.get(..SLL2_HEADER_LEN)?;
+            let protocol = u16::from_be_bytes([header[0], header[1]]);
+            let hatype = u16::from_be_bytes([header[8], header[9]]);
+            let addr_len = header[11];
+            Some(cooked(
+                (hatype == ARPHRD_ETHER && addr_len == 6).then(|| &header[12..18]),
+                protocol,
+                &data[SLL2_HEADER_LEN..],
+            ))
+        }
+        LINKTYPE_IEEE802_11 => wlan(data),
+        LINKTYPE_IEEE802_11_RADIOTAP => {
+            let (header_len, has_fcs) = radiotap(data)?;
+            let mut frame = data.get(header_len..)?;
+            if has_fcs {
+                frame = frame.get(..frame.len().checked_sub(4)?)?;
+            }
+            wlan(frame)
+        }
+        _ => Some(EthernetFrame::native(data)),
+    }
+}
+
+/// Read a Radiotap header's length and whether the 802.11 frame that
+/// follows ends in an FCS. Only the Flags field is decoded; it is the
+/// second field, after the optional 8-byte TSFT.
+fn radiotap(data: &[u8]) -> Option<(usize, bool)> {
+    const PRESENT_TSFT: u32 = 1 << 0;
+    const PRESENT_FLAGS: u32 = 1 << 1;
+    const PRESENT_EXT: u32 = 1 << 31;
+    const FLAG_FCS: u8 = 0x10;
+
+    if *data.first()? != 0 {
+        return None; // only version 0 exists
+    }
+    let header_len = u16::from_le_bytes([*data.get(2)?, *data.get(3)?]) as usize;
+    let word = |offset: usize| {
+        let b = data.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    let present = word(4)?;
+    // Extended presence bitmaps follow while bit 31 is set
+    let mut offset = 8;
+    let mut last = present;
+    while last & PRESENT_EXT != 0 {
+        last = word(offset)?;
+        offset += 4;
+    }
+
+    let mut has_fcs = false;
+    if present & PRESENT_FLAGS != 0 {
+        if present & PRESENT_TSFT != 0 {
+            offset = offset.next_multiple_of(8) + 8;
+        }
+        has_fcs = *data.get(offset)? & FLAG_FCS != 0;
+    }
+    (header_len <= data.len()).then_some((header_len, has_fcs))
+}
+
+/// Map an 802.11 data frame onto Ethernet: the source and destination
+/// addresses come from whichever of the three or four address fields hold
+/// them for the frame's ToDS/FromDS bits, and the Ethertype from its
+/// LLC/SNAP header.
+fn wlan(frame: &[u8]) -> Option<EthernetFrame<'static>> {
+    const TYPE_DATA: u8 = 2;
+    const SUBTYPE_QOS: u8 = 0x08;
+    const SUBTYPE_NO_DATA: u8 = 0x04;
+    const TO_DS: u8 = 0x01;
+    const FROM_DS: u8 = 0x02;
+    const PROTECTED: u8 = 0x40;
+    const ORDER: u8 = 0x80;
+    const QOS_AMSDU: u8 = 0x80;
+
+    let header = frame.get(..WLAN_HEADER_LEN)?;
+    let (fc0, fc1) = (header[0], header[1]);
+    let subtype = fc0 >> 4;
+    if (fc0 >> 2) & 0x03 != TYPE_DATA || subtype & SUBTYPE_NO_DATA != 0 || fc1 & PROTECTED != 0 {
+        return None;
+    }
+
+    // Addresses 1-3 follow frame control and duration; the fourth comes
+    // after sequence control
+    let addr = |n: usize| match n {
+        3 => frame.get(24..30),
+        _ => frame.get(4 + 6 * n..10 + 6 * n),
+    };
+    let (dst, src) = match fc1 & (TO_DS | FROM_DS) {
+        0 => (addr(0)?, addr(1)?),
+        TO_DS => (addr(2)?, addr(1)?),
+        FROM_DS => (addr(0)?, addr(2)?),
+        _ => (addr(2)?, addr(3)?),
+    };
+
+    let mut offset = WLAN_HEADER_LEN;
+    if fc1 & (TO_DS | FROM_DS) == TO_DS | FROM_DS {
+        offset += 6;
+    }
+    if subtype & SUBTYPE_QOS != 0 {
+        // A-MSDU aggregates carry subframe headers instead of one LLC header
+        if *frame.get(offset)? & QOS_AMSDU != 0 {
+            return None;
+        }
+        offset += 2;
+        if fc1 & ORDER != 0 {
+            offset += 4; // HT control
+        }
+    }
+
+    let llc = frame.get(offset..offset + 8)?;
+    if llc[..6] != SNAP_RFC1042 && llc[..6] != SNAP_BRIDGE_TUNNEL {
+        return None;
+    }
+    Some(rebuild(
+        Some(dst),
+        Some(src),
+        u16::from_be_bytes([llc[6], llc[7]]),
+        &frame[offset + 8..],
+    ))
+}
+
+/// Build an Ethernet frame from a cooked header's fields. Protocol values
+/// below 0x0600 are not Ethertypes (0x0004 is 802.2 LLC, used by CDP and
+/// STP); those payloads get an 802.3 length field instead.
+fn cooked<'a>(src_mac: Option<&[u8]>, protocol: u16, payload: &[u8]) -> EthernetFrame<'a> {
+    let type_or_len = if protocol < 0x0600 {
+        payload.len().min(1500) as u16
+    } else {
+        protocol
+    };
+    rebuild(None, src_mac, type_or_len, payload)
+}
+
+/// Assemble an Ethernet frame; unknown addresses are zero-filled.
+fn rebuild<'a>(
+    dst_mac: Option<&[u8]>,
+    src_mac: Option<&[u8]>,
+    type_or_len: u16,
+    payload: &[u8],
+) -> EthernetFrame<'a> {
+    let mut frame = Vec::with_capacity(14 + payload.len());
+    frame.extend_from_slice(dst_mac.unwrap_or(&[0; 6]));
+    frame.extend_from_slice(src_mac.unwrap_or(&[0; 6]));
+    frame.extend_from_slice(&type_or_len.to_be_bytes());
+    frame.extend_from_slice(payload);
+    EthernetFrame {
+        data: Cow::Owned(frame),
+        src_mac_known: src_mac.is_some(),
+        dst_mac_known: dst_mac.is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IPV4: [u8; 4] = [0x45, 0x00, 0x00, 0x28];
+
+    #[test]
+    fn test_ethernet_passthrough() {
+        let frame = [0u8; 20];
+        let eth = to_ethernet(LINKTYPE_ETHERNET, &frame).unwrap();
+        assert!(matches!(eth.data, Cow::Borrowed(_)));
+        assert!(eth.dst_mac_known);
+    }
+
+    #[test]
+    fn test_linux_sll() {
+        #[rustfmt::skip]
+        let mut data = vec![
+            0x00, 0x00,                           // packet type: to us
+            0x00, 0x01,                           // ARPHRD_ETHER
+            0x00, 0x06,                           // address length
+            0x00, 0x0E, 0x8C, 0x12, 0x34, 0x56, 0x00, 0x00,
+            0x08, 0x00,                           // IPv4
+        ];
+        data.extend_from_slice(&IPV4);
+
+        let eth = to_ethernet(LINKTYPE_LINUX_SLL, &data).unwrap();
+        assert_eq!(eth.data[6..12], [0x00, 0x0E, 0x8C, 0x12, 0x34, 0x56]);
+        assert_eq!(eth.data[12..14], [0x08, 0x00]);
+        assert_eq!(eth.data[14..], IPV4);
+        assert!(eth.src_mac_known && !eth.dst_mac_known);
+
+        // Truncated header
+        assert!(to_ethernet(LINKTYPE_LINUX_SLL, &data[..10]).is_none());
+    }
+
+    #[test]
+    fn test_linux_sll2_loopback_and_llc() {
+        #[rustfmt::skip]
+        let mut data = vec![
+            0x86, 0xDD,                           // IPv6
+            0x00, 0x00,                           // reserved
+            0x00, 0x00, 0x00, 0x01,               // interface index
+            0x03, 0x04,                           // ARPHRD_LOOPBACK
+            0x00,                                 // packet type
+            0x06,                                 // address length
+            0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        data.push(0x60);
+        let eth = to_ethernet(LINKTYPE_LINUX_SLL2, &data).unwrap();
+        assert_eq!(eth.data[12..14], [0x86, 0xDD]);
+        assert!(!eth.src_mac_known);
+
+        // 802.2 LLC payload becomes an 802.3 frame with a length field
+        data[0] = 0x00;
+        data[1] = 0x04;
+        data[8] = 0x00;
+        data[9] = 0x01;
+        data.extend_from_slice(&[0x42, 0x42, 0x03]);
+        let eth = to_ethernet(LINKTYPE_LINUX_SLL2, &data).unwrap();
+        assert_eq!(eth.data[12..14], [0x00, 0x04]);
+        assert!(eth.src_mac_known);
+    }
+
+    /// QoS data frame from the AP (FromDS) carrying RFC 1042 SNAP + IPv4.
+    fn wlan_qos_data() -> Vec<u8> {
+        #[rustfmt::skip]
+        let mut frame = vec![
+            0x88, 0x02,                           // QoS data, FromDS
+            0x00, 0x00,                           // duration
+            0x00, 0x1D, 0x9C, 0x01, 0x02, 0x03,   // addr1: receiver (DA)
+            0x00, 0x0B, 0x86, 0xAA, 0xBB, 0xCC,   // addr2: BSSID
+            0x00, 0x80, 0xF4, 0x11, 0x22, 0x33,   // addr3: SA
+            0x00, 0x00,                           // sequence control
+            0x05, 0x00,                           // QoS control, TID 5
+            0xAA, 0xAA, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00,
+        ];
+        frame.extend_from_slice(&IPV4);
+        frame
+    }
+
+    #[test]
+    fn test_ieee802_11_data_frame() {
+        let frame = wlan_qos_data();
+        let eth = to_ethernet(LINKTYPE_IEEE802_11, &frame).unwrap();
+        assert_eq!(eth.data[..6], [0x00, 0x1D, 0x9C, 0x01, 0x02, 0x03]);
+        assert_eq!(eth.data[6..12], [0x00, 0x80, 0xF4, 0x11, 0x22, 0x33]);
+        assert_eq!(eth.data[12..14], [0x08, 0x00]);
+        assert_eq!(eth.data[14..], IPV4);
+        assert!(eth.src_mac_known && eth.dst_mac_known);
+
+        // Protected (encrypted) data and a beacon carry nothing to parse
+        let mut protected = frame.clone();
+        protected[1] |= 0x40;
+        assert!(to_ethernet(LINKTYPE_IEEE802_11, &protected).is_none());
+        let mut beacon = frame.clone();
+        beacon[0] = 0x80;
+        assert!(to_ethernet(LINKTYPE_IEEE802_11, &beacon).is_none());
+    }
+
+    #[test]
+    fn test_ieee802_11_four_address_frame() {
+        // A wireless bridge (WDS) link between two access points
+        #[rustfmt::skip]
+        let mut frame = vec![
+            0x08, 0x03,                           // data, ToDS|FromDS
+            0x00, 0x00,                           // duration
+            0x00, 0x0B, 0x86, 0x00, 0x00, 0x01,   // addr1: receiving AP
+            0x00, 0x0B, 0x86, 0x00, 0x00, 0x02,   // addr2: transmitting AP
+            0x00, 0x1D, 0x9C, 0x01, 0x02, 0x03,   // addr3: DA
+            0x99, 0x99,                           // sequence control
+            0x00, 0x80, 0xF4, 0x11, 0x22, 0x33,   // addr4: SA
+            0xAA, 0xAA, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00,
+        ];
+        frame.extend_from_slice(&IPV4);
+
+        let eth = to_ethernet(LINKTYPE_IEEE802_11, &frame).unwrap();
+        assert_eq!(eth.data[..6], [0x00, 0x1D, 0x9C, 0x01, 0x02, 0x03]);
+        assert_eq!(eth.data[6..12], [0x00, 0x80, 0xF4, 0x11, 0x22, 0x33]);
+        assert_eq!(eth.data[12..14], [0x08, 0x00]);
+        assert_eq!(eth.data[14..], IPV4);
+    }
+
+    #[test]
+    fn test_radiotap_with_tsft_and_fcs() {
+        #[rustfmt::skip]
+        let mut data = vec![
+            0x00, 0x00, 0x12, 0x00,               // version 0, length 18
+            0x03, 0x00, 0x00, 0x00,               // present: TSFT, Flags
+            0, 0, 0, 0, 0, 0, 0, 0,               // TSFT
+            0x10,                                 // Flags: FCS at end
+            0x00,                                 // padding
+        ];
+        data.extend_from_slice(&wlan_qos_data());
+        data.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let eth = to_ethernet(LINKTYPE_IEEE802_11_RADIOTAP, &data).unwrap();
+        assert_eq!(eth.data[12..14], [0x08, 0x00]);
+        assert_eq!(eth.data[14..], IPV4);
+
+        // Header length beyond the captured bytes
+        data[2] = 0xFF;
+        assert!(to_ethernet(LINKTYPE_IEEE802_11_RADIOTAP, &data).is_none());
+    }
+}
@@ -2,46 +2,90 @@
 //!
 //! Opens an interface in promiscuous mode (PASSIVE ONLY — never transmits)
 //! and captures packets in a background thread. Parsed packets are sent
-//! through a channel for processing. Raw packet data is kept in a ring
-//! buffer so the capture can be saved to a PCAP file on stop.
+//! through a bounded queue for processing (see [`QueuePolicy`]). Raw packet data is kept in a ring
+//! buffer so the capture can be saved to a PCAP file on stop, and can also
+//! be written to rotating PCAP files as it arrives (see [`RotationConfig`]).
+//! The same pipeline reads from a remote sensor when given a
+//! [`RemoteSource`].
 
 use std::collections::VecDeque;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Child;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{mpsc, Arc, Mutex};
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
+use crate::defrag::FragmentCache;
 use crate::error::CaptureError;
 use crate::packet::ParsedPacket;
 use crate::parsing;
+use crate::queue::{self, QueueMonitor, QueuePolicy, QueueReceiver};
+use crate::remote::{RemoteCapture, RemoteSource};
+use crate::rotation::{RotatingWriter, RotationConfig};
+use crate::tcp_stream::TcpReassembler;
 
 /// Configuration for starting a live capture.
 pub struct LiveCaptureConfig {
     /// Network interface name (e.g., "eth0", "en0")
     pub interface_name: String,
+    /// Capture on a remote sensor instead; `interface_name` is then unused
+    pub remote: Option<RemoteSource>,
     /// Optional BPF filter expression (e.g., "tcp port 502")
     pub bpf_filter: Option<String>,
     /// Enable promiscuous mode (capture all traffic, not just addressed to us)
     pub promiscuous: bool,
     /// Maximum packets to keep in the ring buffer for PCAP save
     pub ring_buffer_size: usize,
-    /// Maximum bytes to capture per packet
+    /// Maximum bytes to capture per packet (1 to [`MAX_SNAPLEN`])
     pub snaplen: i32,
+    /// Kernel capture buffer size in bytes (0 = libpcap's default, 2 MB on
+    /// Linux, which overflows within a second of a busy 1G SPAN port).
+    /// Local interfaces only.
+    pub buffer_size: i32,
+    /// Put a Wi-Fi interface in monitor (rfmon) mode. Local interfaces only.
+    pub monitor_mode: bool,
+    /// Also write every captured frame to rotating PCAP files
+    pub rotation: Option<RotationConfig>,
+    /// Reassemble TCP connections on these ports before handing packets on
+    /// (see [`crate::PcapReader::with_tcp_reassembly`]); empty = off
+    pub tcp_reassembly_ports: Vec<u16>,
+    /// Most parsed packets waiting for the processing thread
+    pub queue_capacity: usize,
+    /// What to do when the processing thread falls that far behind
+    pub queue_policy: QueuePolicy,
 }
 
 impl Default for LiveCaptureConfig {
     fn default() -> Self {
         Self {
             interface_name: String::new(),
+            remote: None,
             bpf_filter: None,
             promiscuous: true,
             ring_buffer_size: 1_000_000,
             snaplen: 65535,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            monitor_mode: false,
+            rotation: None,
+            tcp_reassembly_ports: Vec::new(),
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            queue_policy: QueuePolicy::DropOldest,
         }
     }
 }
 
+/// Largest snap length libpcap accepts.
+pub const MAX_SNAPLEN: i32 = 262_144;
+
+/// Kernel buffer for local captures: about a quarter second of a saturated
+/// 1G link.
+const DEFAULT_BUFFER_SIZE: i32 = 32 * 1024 * 1024;
+
+/// Parsed packets queued for processing: a few seconds of burst at the
+/// rates deep parsing keeps up with, in a few hundred MB at most.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 100_000;
+
 /// Snapshot of capture statistics.
 #[derive(Debug, Clone, Default)]
 pub struct CaptureStats {
@@ -51,6 +95,73 @@ pub struct CaptureStats {
     pub bytes_captured: u64,
     /// Elapsed time since capture started (seconds)
     pub elapsed_seconds: f64,
+    /// Packets the kernel dropped because the capture buffer was full
+    /// (always 0 for remote captures)
+    pub kernel_dropped: u64,
+    /// Packets the interface or its driver dropped before the kernel saw
+    /// them, where the platform reports it
+    pub interface_dropped: u64,
+    /// Parsed packets waiting for the processing thread
+    pub queue_depth: usize,
+    pub queue_capacity: usize,
+    /// Highest queue depth reached
+    pub queue_peak_depth: usize,
+    /// Packets discarded because the processing queue was full; they are
+    /// still in the ring buffer and capture files, but were not analyzed
+    pub queue_dropped: u64,
+}
+
+/// How often the capture thread reads libpcap's drop counters.
+const DROP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where captured frames come from.
+enum FrameSource {
+    Local(pcap::Capture<pcap::Active>),
+    Remote(RemoteCapture),
+}
+
+impl FrameSource {
+    /// The next frame, or None if none arrived within the read timeout.
+    fn next_frame(&mut self) -> Result<Option<(pcap::PacketHeader, Vec<u8>)>, CaptureError> {
+        match self {
+            FrameSource::Local(cap) => match cap.next_packet() {
+                Ok(packet) => Ok(Some((*packet.header, packet.data.to_vec()))),
+                Err(pcap::Error::TimeoutExpired) => Ok(None),
+                Err(e) => Err(CaptureError::Capture(e.to_string())),
+            },
+            FrameSource::Remote(remote) => match remote.stream.next_record() {
+                Ok(Some(record)) => {
+                    let header = pcap::PacketHeader {
+                        ts: libc::timeval {
+                            tv_sec: record.ts_sec as _,
+                            tv_usec: record.ts_usec as _,
+                        },
+                        caplen: record.data.len() as u32,
+                        len: record.len,
+                    };
+                    Ok(Some((header, record.data)))
+                }
+                Ok(None) => Err(remote.error(&std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "capture stream ended",
+                ))),
+                Err(e) => Err(remote.error(&e)),
+            },
+        }
+    }
+
+    /// Kernel and interface drop counts since the capture opened, if the
+    /// source reports them.
+    fn drops(&mut self) -> Option<(u64, u64)> {
+        match self {
+            FrameSource::Local(cap) => cap
+                .stats()
+                .ok()
+                .map(|s| (s.dropped as u64, s.if_dropped as u64)),
+            // tcpdump only reports drops when it exits
+            FrameSource::Remote(_) => None,
+        }
+    }
 }
 
 /// Raw captured packet data, stored in the ring buffer for PCAP save.
@@ -68,18 +179,26 @@ pub struct LiveCaptureHandle {
     pause_flag: Arc<AtomicBool>,
     packets_captured: Arc<AtomicU64>,
     bytes_captured: Arc<AtomicU64>,
+    kernel_dropped: Arc<AtomicU64>,
+    interface_dropped: Arc<AtomicU64>,
     start_time: Instant,
     thread_handle: Option<JoinHandle<Result<(), CaptureError>>>,
     /// Ring buffer of raw packets for PCAP save
     raw_packets: Arc<Mutex<VecDeque<RawCapturedPacket>>>,
+    /// Rotating capture files on disk, oldest first
+    capture_files: Arc<Mutex<Vec<PathBuf>>>,
     /// pcap linktype (needed for writing PCAP files)
     datalink: pcap::Linktype,
+    /// Process streaming a remote capture, killed on stop
+    remote_process: Option<Arc<Mutex<Child>>>,
+    /// Fill level and losses of the queue to the processing thread
+    queue: QueueMonitor<ParsedPacket>,
 }
 
 impl LiveCaptureHandle {
     /// Start a live capture on the specified interface.
     ///
-    /// Returns a handle for controlling the capture and a receiver channel
+    /// Returns a handle for controlling the capture and a bounded receiver
     /// that yields parsed packets as they are captured. The capture runs
     /// in a background thread in promiscuous mode (PASSIVE ONLY — never transmits).
     ///
@@ -89,73 +208,126 @@ impl LiveCaptureHandle {
     /// - The interface is not found
     /// - Insufficient privileges (need CAP_NET_RAW on Linux, admin on Windows, BPF on macOS)
     /// - Invalid BPF filter expression
+    /// - A remote capture cannot be started (tool missing, SSH refused,
+    ///   remote tcpdump failing)
     pub fn start(
         config: LiveCaptureConfig,
-    ) -> Result<(Self, mpsc::Receiver<ParsedPacket>), CaptureError> {
-        let (tx, rx) = mpsc::channel();
+    ) -> Result<(Self, QueueReceiver<ParsedPacket>), CaptureError> {
+        let (tx, rx) = queue::bounded(config.queue_capacity, config.queue_policy);
+        let queue = rx.monitor();
 
         let stop_flag = Arc::new(AtomicBool::new(false));
         let pause_flag = Arc::new(AtomicBool::new(false));
         let packets_captured = Arc::new(AtomicU64::new(0));
         let bytes_captured = Arc::new(AtomicU64::new(0));
+        let kernel_dropped = Arc::new(AtomicU64::new(0));
+        let interface_dropped = Arc::new(AtomicU64::new(0));
         let raw_packets: Arc<Mutex<VecDeque<RawCapturedPacket>>> =
             Arc::new(Mutex::new(VecDeque::new()));
 
-        // Find the requested network interface
-        let device = pcap::Device::list()
-            .map_err(|e| CaptureError::InterfaceList(e.to_string()))?
-            .into_iter()
-            .find(|d| d.name == config.interface_name)
-            .ok_or_else(|| CaptureError::InterfaceNotFound(config.interface_name.clone()))?;
-
-        // Open the capture — PROMISCUOUS MODE, PASSIVE ONLY (receive-only, never transmit)
-        let mut cap = pcap::Capture::from_device(device)
-            .map_err(|e| enhance_privilege_error(e, &config.interface_name))?
-            .promisc(config.promiscuous)
-            .snaplen(config.snaplen)
-            .timeout(100) // 100ms — keeps the loop responsive to stop/pause
-            .open()
-            .map_err(|e| enhance_privilege_error(e, &config.interface_name))?;
-
-        // Apply BPF filter if provided
-        if let Some(ref filter) = config.bpf_filter {
-            cap.filter(filter, true).map_err(|e| {
-                CaptureError::Capture(format!("Invalid BPF filter '{}': {}", filter, e))
-            })?;
+        // Reject a malformed filter before touching the interface, so a typo
+        // is reported as such rather than as a privilege or device error
+        let bpf_filter = config
+            .bpf_filter
+            .as_deref()
+            .map(str::trim)
+            .filter(|f| !f.is_empty());
+        if let Some(filter) = bpf_filter {
+            validate_bpf_filter(filter)?;
+        }
+        if !(1..=MAX_SNAPLEN).contains(&config.snaplen) || config.buffer_size < 0 {
+            return Err(CaptureError::Capture(format!(
+                "Invalid capture settings: snap length must be 1 to {} bytes (got {}) \
+                 and the buffer size must not be negative (got {})",
+                MAX_SNAPLEN, config.snaplen, config.buffer_size
+            )));
         }
 
-        // Store linktype for PCAP save
-        let datalink = cap.get_datalink();
+        let (mut source, datalink, interface_name, remote_process) = match &config.remote {
+            Some(remote) => {
+                // The filter runs on the sensor, so only matching traffic is sent
+                let capture =
+                    RemoteCapture::spawn(remote, bpf_filter, config.snaplen, config.promiscuous)?;
+                let datalink = pcap::Linktype(capture.stream.linktype());
+                let process = capture.process.clone();
+                (
+                    FrameSource::Remote(capture),
+                    datalink,
+                    remote.label(),
+                    Some(process),
+                )
+            }
+            None => {
+                let cap = open_interface(&config, bpf_filter)?;
+                let datalink = cap.get_datalink();
+                (
+                    FrameSource::Local(cap),
+                    datalink,
+                    config.interface_name.clone(),
+                    None,
+                )
+            }
+        };
+        let linktype = datalink.0;
         let ring_buffer_size = config.ring_buffer_size;
-        let interface_name = config.interface_name.clone();
+
+        // Open the capture directory now so a bad path fails the start
+        let capture_files: Arc<Mutex<Vec<PathBuf>>> = Default::default();
+        let disk_writer = config
+            .rotation
+            .map(|rotation| RotatingWriter::create(rotation, datalink, capture_files.clone()))
+            .transpose();
+        let mut disk_writer = match disk_writer {
+            Ok(writer) => writer,
+            Err(e) => {
+                if let Some(process) = &remote_process {
+                    RemoteCapture::kill(process);
+                }
+                return Err(e);
+            }
+        };
+        let tcp_reassembly_ports = config.tcp_reassembly_ports;
 
         // Clone Arc handles for the background thread
         let stop = stop_flag.clone();
         let pause = pause_flag.clone();
         let pkts_count = packets_captured.clone();
         let bytes_count = bytes_captured.clone();
+        let kernel_drops = kernel_dropped.clone();
+        let interface_drops = interface_dropped.clone();
         let raw_ring = raw_packets.clone();
 
         let thread_handle = thread::spawn(move || -> Result<(), CaptureError> {
             let origin = format!("live:{}", interface_name);
+            let mut fragments = FragmentCache::new();
+            let mut tcp_streams = (!tcp_reassembly_ports.is_empty())
+                .then(|| TcpReassembler::new(tcp_reassembly_ports));
             log::info!("Live capture started on {}", interface_name);
 
+            let poll_drops = |source: &mut FrameSource| {
+                if let Some((kernel, interface)) = source.drops() {
+                    kernel_drops.store(kernel, Ordering::Relaxed);
+                    interface_drops.store(interface, Ordering::Relaxed);
+                }
+            };
+            let mut last_drop_poll = Instant::now();
+
             loop {
                 // Check stop flag
                 if stop.load(Ordering::Relaxed) {
                     break;
                 }
 
-                // If paused, sleep briefly and loop
-                if pause.load(Ordering::Relaxed) {
-                    thread::sleep(Duration::from_millis(50));
-                    continue;
+                if last_drop_poll.elapsed() >= DROP_POLL_INTERVAL {
+                    poll_drops(&mut source);
+                    last_drop_poll = Instant::now();
                 }
 
-                match cap.next_packet() {
-                    Ok(raw_packet) => {
-                        let header = *raw_packet.header;
-                        let data = raw_packet.data.to_vec();
+                match source.next_frame() {
+                    // Frames arriving while paused are read and dropped, so a
+                    // remote stream keeps flowing
+                    Ok(Some(_)) if pause.load(Ordering::Relaxed) => continue,
+                    Ok(Some((header, data))) => {
                         let length = data.len() as u64;
 
                         // Update raw counters
@@ -173,31 +345,50 @@ impl LiveCaptureHandle {
                             });
                         }
 
-                        // Parse with etherparse and send to processing channel
-                        let timestamp = parsing::timestamp_from_pcap(header);
-                        if let Ok(parsed) = etherparse::SlicedPacket::from_ethernet(&data) {
-                            if let Some(packet) =
-                                parsing::extract_packet_info(&parsed, &data, timestamp, &origin)
-                            {
-                                // If channel is closed, stop capture
-                                if tx.send(packet).is_err() {
-                                    log::warn!("Packet channel closed, stopping capture");
-                                    break;
-                                }
+                        // Write to the rotating capture files; on failure (disk
+                        // full, directory removed) keep capturing without them
+                        if let Some(writer) = disk_writer.as_mut() {
+                            if let Err(e) = writer.write(&header, &data) {
+                                log::error!("{}; capture-to-disk stopped", e);
+                                disk_writer = None;
+                            }
+                        }
+
+                        // Capturing on "any" yields Linux cooked frames, so the
+                        // link type is passed through to the parser
+                        let timestamp =
+                            parsing::timestamp_from_pcap(header, pcap::Precision::Micro);
+                        if let Ok(Some(mut packet)) = parsing::parse_frame(
+                            linktype,
+                            &data,
+                            timestamp,
+                            &origin,
+                            &mut fragments,
+                        ) {
+                            if let Some(streams) = tcp_streams.as_mut() {
+                                streams.process(&mut packet);
+                            }
+                            // If the receiver is gone, stop capture
+                            if tx.send(packet, &stop).is_err() {
+                                log::warn!("Packet queue closed, stopping capture");
+                                break;
                             }
                         }
                     }
-                    Err(pcap::Error::TimeoutExpired) => {
+                    Ok(None) => {
                         // Normal — no packets available within the timeout window
                         continue;
                     }
+                    // A remote stream ends when stop() kills its process
+                    Err(_) if stop.load(Ordering::Relaxed) => break,
                     Err(e) => {
                         log::error!("Live capture error: {}", e);
-                        return Err(CaptureError::Capture(e.to_string()));
+                        return Err(e);
                     }
                 }
             }
 
+            poll_drops(&mut source);
             log::info!("Live capture stopped on {}", interface_name);
             Ok(())
         });
@@ -207,10 +398,15 @@ impl LiveCaptureHandle {
             pause_flag,
             packets_captured,
             bytes_captured,
+            kernel_dropped,
+            interface_dropped,
             start_time: Instant::now(),
             thread_handle: Some(thread_handle),
             raw_packets,
+            capture_files,
             datalink,
+            remote_process,
+            queue,
         };
 
         Ok((handle, rx))
@@ -219,6 +415,9 @@ impl LiveCaptureHandle {
     /// Stop the capture and wait for the capture thread to finish.
     pub fn stop(&mut self) -> Result<(), CaptureError> {
         self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(process) = &self.remote_process {
+            RemoteCapture::kill(process);
+        }
         if let Some(handle) = self.thread_handle.take() {
             handle
                 .join()
@@ -250,10 +449,17 @@ impl LiveCaptureHandle {
 
     /// Get a snapshot of current capture statistics.
     pub fn stats(&self) -> CaptureStats {
+        let queue = self.queue.stats();
         CaptureStats {
             packets_captured: self.packets_captured.load(Ordering::Relaxed),
             bytes_captured: self.bytes_captured.load(Ordering::Relaxed),
             elapsed_seconds: self.start_time.elapsed().as_secs_f64(),
+            kernel_dropped: self.kernel_dropped.load(Ordering::Relaxed),
+            interface_dropped: self.interface_dropped.load(Ordering::Relaxed),
+            queue_depth: queue.depth,
+            queue_capacity: queue.capacity,
+            queue_peak_depth: queue.peak_depth,
+            queue_dropped: queue.dropped,
         }
     }
 
@@ -295,12 +501,57 @@ impl LiveCaptureHandle {
         Ok(count)
     }
 
+    /// Rotating capture files written so far and still on disk, oldest
+    /// first. Empty unless the capture was started with a [`RotationConfig`].
+    pub fn capture_files(&self) -> Vec<PathBuf> {
+        self.capture_files
+            .lock()
+            .map(|f| f.clone())
+            .unwrap_or_default()
+    }
+
     /// Get the number of packets currently in the ring buffer.
     pub fn ring_buffer_count(&self) -> usize {
         self.raw_packets.lock().map(|r| r.len()).unwrap_or(0)
     }
 }
 
+/// Open a local interface — PROMISCUOUS MODE, PASSIVE ONLY (receive-only,
+/// never transmit) — and apply the BPF filter.
+fn open_interface(
+    config: &LiveCaptureConfig,
+    bpf_filter: Option<&str>,
+) -> Result<pcap::Capture<pcap::Active>, CaptureError> {
+    // Find the requested network interface
+    let device = pcap::Device::list()
+        .map_err(|e| CaptureError::InterfaceList(e.to_string()))?
+        .into_iter()
+        .find(|d| d.name == config.interface_name)
+        .ok_or_else(|| CaptureError::InterfaceNotFound(config.interface_name.clone()))?;
+
+    let mut inactive = pcap::Capture::from_device(device)
+        .map_err(|e| enhance_privilege_error(e, &config.interface_name))?
+        .promisc(config.promiscuous)
+        .rfmon(config.monitor_mode)
+        .snaplen(config.snaplen)
+        .timeout(100); // 100ms — keeps the loop responsive to stop/pause
+    if config.buffer_size > 0 {
+        inactive = inactive.buffer_size(config.buffer_size);
+    }
+    let mut cap = inactive
+        .open()
+        .map_err(|e| enhance_privilege_error(e, &config.interface_name))?;
+
+    // Apply BPF filter if provided. It is compiled again for the
+    // interface's link type, which can still reject link-specific terms.
+    if let Some(filter) = bpf_filter {
+        cap.filter(filter, true).map_err(|e| {
+            CaptureError::Capture(format!("Invalid BPF filter '{}': {}", filter, e))
+        })?;
+    }
+    Ok(cap)
+}
+
 /// Enhance pcap error messages with platform-specific privilege guidance.
 fn enhance_privilege_error(err: pcap::Error, interface: &str) -> CaptureError {
     let msg = err.to_string();
@@ -349,17 +600,45 @@ fn enhance_privilege_error(err: pcap::Error, interface: &str) -> CaptureError {
     }
 }
 
+/// Check that a BPF filter expression (e.g. `port 502 or port 20000`)
+/// compiles, without opening an interface or needing capture privileges.
+///
+/// The expression is compiled for Ethernet, the link type of nearly every
+/// OT capture interface.
+pub fn validate_bpf_filter(filter: &str) -> Result<(), CaptureError> {
+    let dead = pcap::Capture::dead(pcap::Linktype::ETHERNET)
+        .map_err(|e| CaptureError::Capture(format!("Cannot create dead capture: {}", e)))?;
+    dead.compile(filter, true)
+        .map(|_| ())
+        .map_err(|e| CaptureError::Capture(format!("Invalid BPF filter '{}': {}", filter, e)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_bpf_filter() {
+        assert!(validate_bpf_filter("port 502 or port 20000").is_ok());
+        assert!(validate_bpf_filter("vlan and tcp port 44818").is_ok());
+        let err = validate_bpf_filter("port modbus-ish").unwrap_err();
+        assert!(err.to_string().contains("Invalid BPF filter"));
+    }
+
     #[test]
     fn test_default_config() {
         let config = LiveCaptureConfig::default();
         assert!(config.promiscuous);
         assert_eq!(config.ring_buffer_size, 1_000_000);
         assert_eq!(config.snaplen, 65535);
+        assert_eq!(config.buffer_size, 32 * 1024 * 1024);
+        assert!(!config.monitor_mode);
         assert!(config.bpf_filter.is_none());
+        assert!(config.remote.is_none());
+        assert!(config.rotation.is_none());
+        assert!(config.tcp_reassembly_ports.is_empty());
+        assert_eq!(config.queue_capacity, DEFAULT_QUEUE_CAPACITY);
+        assert_eq!(config.queue_policy, QueuePolicy::DropOldest);
     }
 
     #[test]
@@ -367,5 +646,8 @@ mod tests {
         let stats = CaptureStats::default();
         assert_eq!(stats.packets_captured, 0);
         assert_eq!(stats.bytes_captured, 0);
+        assert_eq!(stats.kernel_dropped, 0);
+        assert_eq!(stats.interface_dropped, 0);
+        assert_eq!(stats.queue_dropped, 0);
     }
 }
@@ -0,0 +1,136 @@
+//! MPLS label stack handling (RFC 3032).
+//!
+//! Captures from provider-edge links carry customer traffic under one or
+//! more MPLS labels. The stack is skipped so the inner packet can be sliced
+//! like any other, and the labels are kept for the parsed packet.
+
+use crate::vlan;
+
+const ETHERTYPE_MPLS_UNICAST: u16 = 0x8847;
+const ETHERTYPE_MPLS_MULTICAST: u16 = 0x8848;
+
+/// Deeper stacks than this are treated as malformed.
+const MAX_LABELS: usize = 16;
+
+/// What follows the bottom of the label stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MplsPayload {
+    /// An IPv4 or IPv6 packet
+    Ip,
+    /// An Ethernet frame behind a pseudowire control word (EoMPLS / VPLS)
+    Ethernet,
+}
+
+/// A parsed MPLS label stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LabelStack {
+    /// Label values, outermost first
+    pub labels: Vec<u32>,
+    pub payload: MplsPayload,
+    /// Offset of the payload in the frame
+    pub payload_offset: usize,
+}
+
+/// Read the MPLS label stack of an Ethernet frame (after any VLAN tags).
+///
+/// Returns None if the frame is not MPLS, the stack is truncated, or the
+/// payload is neither IP nor a control-word pseudowire. Without a control
+/// word an Ethernet payload cannot be told apart from IP reliably, so those
+/// pseudowires are not decoded.
+pub(crate) fn label_stack(frame: &[u8]) -> Option<LabelStack> {
+    let ethertype_offset = vlan::parse(frame).ethertype_offset;
+    let ethertype = u16::from_be_bytes([
+        *frame.get(ethertype_offset)?,
+        *frame.get(ethertype_offset + 1)?,
+    ]);
+    if ethertype != ETHERTYPE_MPLS_UNICAST && ethertype != ETHERTYPE_MPLS_MULTICAST {
+        return None;
+    }
+
+    let mut offset = ethertype_offset + 2;
+    let mut labels = Vec::new();
+    loop {
+        let entry = frame.get(offset..offset + 4)?;
+        let entry = u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]);
+        labels.push(entry >> 12);
+        offset += 4;
+        if entry & 0x100 != 0 {
+            break; // bottom of stack
+        }
+        if labels.len() == MAX_LABELS {
+            return None;
+        }
+    }
+
+    let first = *frame.get(offset)?;
+    let payload = match first >> 4 {
+        4 if first & 0x0F >= 5 => MplsPayload::Ip,
+        6 => MplsPayload::Ip,
+        0 if frame.len() >= offset + 4 + 14 => {
+            offset += 4; // pseudowire control word
+            MplsPayload::Ethernet
+        }
+        _ => return None,
+    };
+
+    Some(LabelStack {
+        labels,
+        payload,
+        payload_offset: offset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ethernet(ethertype: [u8; 2]) -> Vec<u8> {
+        let mut frame = vec![
+            0x00, 0x1B, 0x54, 0x01, 0x02, 0x03, 0x00, 0x1B, 0x54, 0x0A, 0x0B, 0x0C,
+        ];
+        frame.extend_from_slice(&ethertype);
+        frame
+    }
+
+    #[test]
+    fn test_two_label_stack_over_ipv4() {
+        let mut frame = ethernet([0x88, 0x47]);
+        frame.extend_from_slice(&[0x00, 0x3E, 0x80, 0xFE]); // label 1000, TTL 254
+        frame.extend_from_slice(&[0x00, 0x01, 0x41, 0xFE]); // label 20, bottom of stack
+        frame.extend_from_slice(&[0x45, 0x00, 0x00, 0x28]);
+
+        let stack = label_stack(&frame).unwrap();
+        assert_eq!(stack.labels, vec![1000, 20]);
+        assert_eq!(stack.payload, MplsPayload::Ip);
+        assert_eq!(stack.payload_offset, 22);
+        assert_eq!(frame[stack.payload_offset], 0x45);
+    }
+
+    #[test]
+    fn test_pseudowire_with_control_word() {
+        // 802.1Q VLAN 300 → MPLS label 16 → control word → inner Ethernet
+        let mut frame = ethernet([0x81, 0x00]);
+        frame.extend_from_slice(&[0x01, 0x2C, 0x88, 0x47]);
+        frame.extend_from_slice(&[0x00, 0x01, 0x01, 0x40]);
+        frame.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        let inner = ethernet([0x08, 0x00]);
+        frame.extend_from_slice(&inner);
+
+        let stack = label_stack(&frame).unwrap();
+        assert_eq!(stack.labels, vec![16]);
+        assert_eq!(stack.payload, MplsPayload::Ethernet);
+        assert_eq!(frame[stack.payload_offset..], inner[..]);
+    }
+
+    #[test]
+    fn test_not_mpls_or_truncated() {
+        let mut ipv4 = ethernet([0x08, 0x00]);
+        ipv4.extend_from_slice(&[0x45, 0x00, 0x00, 0x28]);
+        assert!(label_stack(&ipv4).is_none());
+
+        // Stack without a bottom-of-stack entry before the frame ends
+        let mut truncated = ethernet([0x88, 0x47]);
+        truncated.extend_from_slice(&[0x00, 0x3E, 0x80, 0xFE, 0x00, 0x01]);
+        assert!(label_stack(&truncated).is_none());
+    }
+}
@@ -7,9 +7,22 @@ use serde::Serialize;
 pub enum TransportProtocol {
     Tcp,
     Udp,
+    /// ICMP (IP protocol 1)
+    Icmp,
+    /// ICMPv6 (IPv6 next header 58)
+    Icmpv6,
     Other,
 }
 
+/// TCP header flag bits, as stored in [`ParsedPacket::tcp_flags`].
+pub mod tcp_flags {
+    pub const FIN: u8 = 0x01;
+    pub const SYN: u8 = 0x02;
+    pub const RST: u8 = 0x04;
+    pub const PSH: u8 = 0x08;
+    pub const ACK: u8 = 0x10;
+}
+
 /// A packet parsed down to Layer 4 (transport).
 ///
 /// This struct contains everything extracted from the Ethernet/IP/TCP|UDP
@@ -26,13 +39,26 @@ pub struct ParsedPacket {
     /// Destination MAC address
     pub dst_mac: Option<String>,
 
+    /// 802.1Q VLAN ID (the inner, customer tag of a QinQ frame); None for
+    /// untagged and priority-tagged frames
+    pub vlan_id: Option<u16>,
+
+    /// Outer (802.1ad service) VLAN ID of a double-tagged frame
+    pub outer_vlan_id: Option<u16>,
+
+    /// MPLS labels the packet was carried under, outermost first
+    pub mpls_labels: Vec<u32>,
+
+    /// VXLAN network identifier of the overlay the packet was carried in
+    pub vxlan_vni: Option<u32>,
+
     /// Source IP address
     pub src_ip: String,
 
     /// Destination IP address
     pub dst_ip: String,
 
-    /// Transport protocol (TCP, UDP, or Other)
+    /// Transport protocol (TCP, UDP, ICMP/ICMPv6, or Other)
     pub transport: TransportProtocol,
 
     /// Source port (0 if not TCP/UDP)
@@ -41,10 +67,17 @@ pub struct ParsedPacket {
     /// Destination port (0 if not TCP/UDP)
     pub dst_port: u16,
 
+    /// TCP header flags (bits from [`tcp_flags`]); 0 if not TCP
+    pub tcp_flags: u8,
+
+    /// TCP sequence number; 0 if not TCP
+    pub tcp_seq: u32,
+
     /// Total packet length in bytes
     pub length: usize,
 
-    /// Raw application-layer payload for protocol parsers
+    /// Raw application-layer payload for protocol parsers; for ICMP, the
+    /// whole ICMP message including its header
     #[serde(skip)]
     pub payload: Vec<u8>,
 
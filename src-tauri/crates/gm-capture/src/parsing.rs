@@ -1,17 +1,185 @@
 //! Shared packet parsing utilities.
 //!
 //! These functions are used by both the PCAP file reader and live capture
-//! to extract structured packet information from raw Ethernet frames.
+//! to extract structured packet information from raw captured frames.
+
+use std::borrow::Cow;
 
 use chrono::{DateTime, Utc};
 use etherparse::{NetSlice, SlicedPacket, TransportSlice};
 
-use crate::packet::{ParsedPacket, TransportProtocol};
+use crate::defrag::{FragmentCache, Reassembly};
+use crate::ipv6;
+use crate::link;
+use crate::mpls::{self, MplsPayload};
+use crate::packet::{tcp_flags, ParsedPacket, TransportProtocol};
+use crate::vlan;
+use crate::vxlan;
+
+/// Parse a captured frame of the given link type (a pcap `Linktype` value).
+///
+/// Frames are rewritten to Ethernet first (see [`link::to_ethernet`]), then
+/// checked for the Layer-2-only protocols before IP parsing. MAC addresses
+/// the link header did not record (the destination of a Linux cooked
+/// capture) are reported as None. IPv4 fragments are held in `fragments`
+/// and the reassembled datagram is returned with the last one to arrive.
+/// Returns Err if etherparse cannot slice the frame, and Ok(None) for
+/// frames with nothing to analyze (yet).
+pub(crate) fn parse_frame(
+    linktype: i32,
+    data: &[u8],
+    timestamp: DateTime<Utc>,
+    origin_file: &str,
+    fragments: &mut FragmentCache,
+) -> Result<Option<ParsedPacket>, etherparse::err::packet::SliceError> {
+    let Some(frame) = link::to_ethernet(linktype, data) else {
+        return Ok(None);
+    };
+    let raw_data: &[u8] = &frame.data;
+
+    let l2_packet = try_extract_lldp_packet(raw_data, timestamp, origin_file)
+        .or_else(|| try_extract_cdp_packet(raw_data, timestamp, origin_file))
+        .or_else(|| try_extract_redundancy_packet(raw_data, timestamp, origin_file))
+        .or_else(|| try_extract_goose_packet(raw_data, timestamp, origin_file))
+        .or_else(|| try_extract_sv_packet(raw_data, timestamp, origin_file))
+        .or_else(|| try_extract_profinet_packet(raw_data, timestamp, origin_file))
+        .or_else(|| try_extract_arp_packet(raw_data, timestamp, origin_file));
+    let packet = match l2_packet {
+        Some(packet) => Some(packet),
+        None => parse_ethernet_frame(raw_data, timestamp, origin_file, fragments)?,
+    };
+
+    Ok(packet.map(|mut packet| {
+        if !frame.src_mac_known {
+            packet.src_mac = None;
+        }
+        if !frame.dst_mac_known {
+            packet.dst_mac = None;
+        }
+        packet.length = data.len();
+        packet
+    }))
+}
+
+/// Parse a captured Ethernet frame down to Layer 4.
+///
+/// Traffic under an MPLS label stack is sliced from the IP packet or
+/// Ethernet frame the stack carries, and VXLAN traffic from the encapsulated
+/// frame. Returns Err if etherparse cannot slice the frame, and Ok(None)
+/// for other non-IP frames.
+fn parse_ethernet_frame(
+    raw_data: &[u8],
+    timestamp: DateTime<Utc>,
+    origin_file: &str,
+    fragments: &mut FragmentCache,
+) -> Result<Option<ParsedPacket>, etherparse::err::packet::SliceError> {
+    let Some(stack) = mpls::label_stack(raw_data) else {
+        let Some(frame) = defragment_ethernet(raw_data, fragments, timestamp) else {
+            return Ok(None);
+        };
+        let raw_data: &[u8] = &frame;
+        let parsed = SlicedPacket::from_ethernet(raw_data)?;
+        if let Some((vni, inner)) = vxlan_frame(&parsed) {
+            return Ok(
+                parse_ethernet_frame(inner, timestamp, origin_file, fragments)?.map(
+                    |mut packet| {
+                        packet.vxlan_vni = Some(vni);
+                        packet.length = raw_data.len();
+                        packet
+                    },
+                ),
+            );
+        }
+        return Ok(extract_packet_info(
+            &parsed,
+            raw_data,
+            timestamp,
+            origin_file,
+        ));
+    };
+
+    let inner = &raw_data[stack.payload_offset..];
+    let packet = match stack.payload {
+        MplsPayload::Ip => {
+            let Some(inner) = defragment(inner, 0, fragments, timestamp) else {
+                return Ok(None);
+            };
+            let parsed = SlicedPacket::from_ip(&inner)?;
+            extract_packet_info(&parsed, raw_data, timestamp, origin_file).map(|mut packet| {
+                // The outer Ethernet header belongs to the provider link
+                // between label-switching routers, not to either host
+                packet.src_mac = None;
+                packet.dst_mac = None;
+                packet.vlan_id = None;
+                packet.outer_vlan_id = None;
+                packet
+            })
+        }
+        MplsPayload::Ethernet => {
+            let Some(inner) = defragment_ethernet(inner, fragments, timestamp) else {
+                return Ok(None);
+            };
+            let parsed = SlicedPacket::from_ethernet(&inner)?;
+            extract_packet_info(&parsed, &inner, timestamp, origin_file).map(|mut packet| {
+                packet.length = raw_data.len();
+                packet
+            })
+        }
+    };
+    Ok(packet.map(|mut packet| {
+        packet.mpls_labels = stack.labels;
+        packet
+    }))
+}
+
+/// Run an Ethernet frame carrying IPv4 through the fragment cache (see
+/// [`defragment`]); other frames are returned as they are.
+fn defragment_ethernet<'a>(
+    frame: &'a [u8],
+    fragments: &mut FragmentCache,
+    timestamp: DateTime<Utc>,
+) -> Option<Cow<'a, [u8]>> {
+    let ethertype_offset = vlan::parse(frame).ethertype_offset;
+    match frame.get(ethertype_offset..ethertype_offset + 2) {
+        Some([0x08, 0x00]) => defragment(frame, ethertype_offset + 2, fragments, timestamp),
+        _ => Some(Cow::Borrowed(frame)),
+    }
+}
+
+/// Run the IPv4 packet at `ip_offset` through the fragment cache. Returns
+/// the data to parse — as captured, or with the reassembled datagram in
+/// place of the final fragment — or None while fragments are outstanding.
+fn defragment<'a>(
+    data: &'a [u8],
+    ip_offset: usize,
+    fragments: &mut FragmentCache,
+    timestamp: DateTime<Utc>,
+) -> Option<Cow<'a, [u8]>> {
+    match fragments.process(data.get(ip_offset..)?, timestamp) {
+        Reassembly::Whole => Some(Cow::Borrowed(data)),
+        Reassembly::Pending => None,
+        Reassembly::Complete(datagram) => {
+            let mut rebuilt = data[..ip_offset].to_vec();
+            rebuilt.extend_from_slice(&datagram);
+            Some(Cow::Owned(rebuilt))
+        }
+    }
+}
+
+/// The VNI and encapsulated frame of a VXLAN packet.
+fn vxlan_frame<'a>(parsed: &SlicedPacket<'a>) -> Option<(u32, &'a [u8])> {
+    match &parsed.transport {
+        Some(TransportSlice::Udp(udp)) if udp.destination_port() == vxlan::VXLAN_PORT => {
+            vxlan::decapsulate(udp.payload())
+        }
+        _ => None,
+    }
+}
 
 /// Extract structured packet info from an etherparse SlicedPacket.
 ///
-/// Returns None for non-IP packets (ARP, etc.), which are silently skipped.
-pub(crate) fn extract_packet_info(
+/// Returns None for non-IP packets, which are silently skipped.
+fn extract_packet_info(
     parsed: &SlicedPacket,
     raw_data: &[u8],
     timestamp: DateTime<Utc>,
@@ -29,7 +197,12 @@ pub(crate) fn extract_packet_info(
         (None, None)
     };
 
-    // Extract IP addresses from network layer
+    // etherparse skips VLAN tags on its own; read their IDs from the frame
+    let tags = vlan::parse(raw_data);
+
+    // Extract IP addresses from network layer. etherparse has already
+    // walked any IPv6 extension headers, so `parsed.transport` is the
+    // upper-layer header for both versions (None for later fragments).
     let (src_ip, dst_ip) = match &parsed.net {
         Some(NetSlice::Ipv4(ipv4)) => {
             let header = ipv4.header();
@@ -38,14 +211,14 @@ pub(crate) fn extract_packet_info(
                 format_ipv4(header.destination()),
             )
         }
-        Some(NetSlice::Ipv6(ipv6)) => {
-            let header = ipv6.header();
+        Some(NetSlice::Ipv6(ipv6_slice)) => {
+            let header = ipv6_slice.header();
             (
-                format_ipv6(header.source()),
-                format_ipv6(header.destination()),
+                ipv6::format_address(header.source()),
+                ipv6::format_address(header.destination()),
             )
         }
-        _ => return None, // Skip non-IP packets (ARP, etc.)
+        _ => return None, // Skip non-IP packets
     };
 
     // Extract transport layer info (ports + protocol)
@@ -60,13 +233,45 @@ pub(crate) fn extract_packet_info(
             udp.source_port(),
             udp.destination_port(),
         ),
+        Some(TransportSlice::Icmpv4(_)) => (TransportProtocol::Icmp, 0, 0),
+        Some(TransportSlice::Icmpv6(_)) => (TransportProtocol::Icmpv6, 0, 0),
         _ => (TransportProtocol::Other, 0, 0),
     };
 
+    // TCP flags, used by the processor to follow session setup and teardown
+    let tcp_flags = match &parsed.transport {
+        Some(TransportSlice::Tcp(tcp)) => {
+            let mut flags = 0;
+            if tcp.fin() {
+                flags |= tcp_flags::FIN;
+            }
+            if tcp.syn() {
+                flags |= tcp_flags::SYN;
+            }
+            if tcp.rst() {
+                flags |= tcp_flags::RST;
+            }
+            if tcp.psh() {
+                flags |= tcp_flags::PSH;
+            }
+            if tcp.ack() {
+                flags |= tcp_flags::ACK;
+            }
+            flags
+        }
+        _ => 0,
+    };
+    let tcp_seq = match &parsed.transport {
+        Some(TransportSlice::Tcp(tcp)) => tcp.sequence_number(),
+        _ => 0,
+    };
+
     // Extract application-layer payload from the transport layer
     let payload = match &parsed.transport {
         Some(TransportSlice::Tcp(tcp)) => tcp.payload().to_vec(),
         Some(TransportSlice::Udp(udp)) => udp.payload().to_vec(),
+        Some(TransportSlice::Icmpv4(icmp)) => icmp.slice().to_vec(),
+        Some(TransportSlice::Icmpv6(icmp)) => icmp.slice().to_vec(),
         _ => Vec::new(),
     };
 
@@ -74,11 +279,17 @@ pub(crate) fn extract_packet_info(
         timestamp,
         src_mac,
         dst_mac,
+        vlan_id: tags.vlan_id,
+        outer_vlan_id: tags.outer_vlan_id,
+        mpls_labels: Vec::new(),
+        vxlan_vni: None,
         src_ip,
         dst_ip,
         transport,
         src_port,
         dst_port,
+        tcp_flags,
+        tcp_seq,
         length: raw_data.len(),
         payload,
         origin_file: origin_file.to_string(),
@@ -94,7 +305,7 @@ pub(crate) fn extract_packet_info(
 /// - `payload` = the LLDP PDU (everything after the 14-byte Ethernet header)
 ///
 /// Returns None if the frame is not LLDP or is too short.
-pub(crate) fn try_extract_lldp_packet(
+fn try_extract_lldp_packet(
     raw_data: &[u8],
     timestamp: chrono::DateTime<chrono::Utc>,
     origin_file: &str,
@@ -103,14 +314,14 @@ pub(crate) fn try_extract_lldp_packet(
     if raw_data.len() < 14 {
         return None;
     }
-    // Check Ethertype at bytes 12-13 (skip VLAN tag 0x8100 if present)
-    let (ethertype_offset, payload_start) =
-        if raw_data[12] == 0x81 && raw_data[13] == 0x00 && raw_data.len() >= 18 {
-            (14, 18) // 802.1Q VLAN tag
-        } else {
-            (12, 14)
-        };
-    if raw_data[ethertype_offset] != 0x88 || raw_data[ethertype_offset + 1] != 0xCC {
+    // Check the Ethertype after any VLAN tags
+    let tags = vlan::parse(raw_data);
+    let ethertype_offset = tags.ethertype_offset;
+    let payload_start = ethertype_offset + 2;
+    if raw_data.len() < payload_start
+        || raw_data[ethertype_offset] != 0x88
+        || raw_data[ethertype_offset + 1] != 0xCC
+    {
         return None;
     }
 
@@ -124,12 +335,180 @@ pub(crate) fn try_extract_lldp_packet(
         timestamp,
         src_mac: Some(src_mac_str.clone()),
         dst_mac: Some(dst_mac_str),
+        vlan_id: tags.vlan_id,
+        outer_vlan_id: tags.outer_vlan_id,
+        mpls_labels: Vec::new(),
+        vxlan_vni: None,
         // Use a sentinel prefix so the processor can identify LLDP packets
         src_ip: format!("lldp:{}", src_mac_str),
         dst_ip: "lldp:broadcast".to_string(),
         transport: crate::packet::TransportProtocol::Other,
         src_port: 0,
         dst_port: 0,
+        tcp_flags: 0,
+        tcp_seq: 0,
+        length: raw_data.len(),
+        payload: raw_data[payload_start..].to_vec(),
+        origin_file: origin_file.to_string(),
+    })
+}
+
+/// Try to extract a CDP frame from raw Ethernet data.
+///
+/// CDP is sent in 802.3 frames (length field instead of an Ethertype) to
+/// 01:00:0C:CC:CC:CC with an LLC/SNAP header: DSAP/SSAP 0xAA, control 0x03,
+/// Cisco OUI 00:00:0C and protocol ID 0x2000. Returns a synthetic
+/// `ParsedPacket` with:
+/// - `src_ip` = `"cdp:<mac>"` (the sending port's MAC)
+/// - `dst_ip` = `"cdp:multicast"`
+/// - `payload` = the CDP PDU, starting at the version byte
+///
+/// Returns None if the frame is not CDP or is too short.
+fn try_extract_cdp_packet(
+    raw_data: &[u8],
+    timestamp: chrono::DateTime<chrono::Utc>,
+    origin_file: &str,
+) -> Option<ParsedPacket> {
+    const CDP_MULTICAST: [u8; 6] = [0x01, 0x00, 0x0C, 0xCC, 0xCC, 0xCC];
+    const CDP_SNAP: [u8; 8] = [0xAA, 0xAA, 0x03, 0x00, 0x00, 0x0C, 0x20, 0x00];
+
+    if raw_data.len() < 26 || raw_data[0..6] != CDP_MULTICAST {
+        return None;
+    }
+    // The 802.3 length field sits after any VLAN tags
+    let tags = vlan::parse(raw_data);
+    let llc_start = tags.ethertype_offset + 2;
+    let length = u16::from_be_bytes([raw_data[llc_start - 2], raw_data[llc_start - 1]]);
+    if length > 1500 || raw_data.len() < llc_start + CDP_SNAP.len() + 4 {
+        return None;
+    }
+    if raw_data[llc_start..llc_start + CDP_SNAP.len()] != CDP_SNAP {
+        return None;
+    }
+
+    let src_mac: [u8; 6] = raw_data[6..12].try_into().ok()?;
+    let src_mac_str = ParsedPacket::format_mac(&src_mac);
+
+    Some(ParsedPacket {
+        timestamp,
+        src_mac: Some(src_mac_str.clone()),
+        dst_mac: Some(ParsedPacket::format_mac(&CDP_MULTICAST)),
+        vlan_id: tags.vlan_id,
+        outer_vlan_id: tags.outer_vlan_id,
+        mpls_labels: Vec::new(),
+        vxlan_vni: None,
+        src_ip: format!("cdp:{}", src_mac_str),
+        dst_ip: "cdp:multicast".to_string(),
+        transport: crate::packet::TransportProtocol::Other,
+        src_port: 0,
+        dst_port: 0,
+        tcp_flags: 0,
+        tcp_seq: 0,
+        length: raw_data.len(),
+        payload: raw_data[llc_start + CDP_SNAP.len()..].to_vec(),
+        origin_file: origin_file.to_string(),
+    })
+}
+
+/// Try to extract an IEC 61850 GOOSE frame from raw Ethernet data.
+///
+/// GOOSE uses Ethertype 0x88B8 and, like LLDP, has no IP header. Publishers
+/// normally send it behind an 802.1Q priority tag. Returns a synthetic
+/// `ParsedPacket` with:
+/// - `src_ip` = `"goose:<src mac>"` (the publishing IED)
+/// - `dst_ip` = `"goose:<dst mac>"` (the multicast group)
+/// - `payload` = everything after the Ethertype, starting at the APPID
+///
+/// Returns None if the frame is not GOOSE or is too short.
+fn try_extract_goose_packet(
+    raw_data: &[u8],
+    timestamp: chrono::DateTime<chrono::Utc>,
+    origin_file: &str,
+) -> Option<ParsedPacket> {
+    extract_ethertype_packet(raw_data, 0x88B8, "goose", timestamp, origin_file)
+}
+
+/// Try to extract an IEC 61850-9-2 Sampled Values frame from raw Ethernet data.
+///
+/// Same framing as GOOSE with Ethertype 0x88BA; the synthetic packet uses
+/// the `"sv:<mac>"` sentinel on both ends.
+fn try_extract_sv_packet(
+    raw_data: &[u8],
+    timestamp: chrono::DateTime<chrono::Utc>,
+    origin_file: &str,
+) -> Option<ParsedPacket> {
+    extract_ethertype_packet(raw_data, 0x88BA, "sv", timestamp, origin_file)
+}
+
+/// Try to extract a PROFINET frame (Ethertype 0x8892) from raw Ethernet data.
+///
+/// The synthetic packet uses the `"profinet:<mac>"` sentinel on both ends and
+/// its payload starts at the FrameID; the processor decides which FrameIDs
+/// (DCP Hello/Identify, cyclic RT) it understands.
+fn try_extract_profinet_packet(
+    raw_data: &[u8],
+    timestamp: chrono::DateTime<chrono::Utc>,
+    origin_file: &str,
+) -> Option<ParsedPacket> {
+    extract_ethertype_packet(raw_data, 0x8892, "profinet", timestamp, origin_file)
+}
+
+/// Try to extract an ARP packet (Ethertype 0x0806) from raw Ethernet data.
+///
+/// The synthetic packet uses the `"arp:<mac>"` sentinel on both ends (the
+/// destination is usually broadcast for requests) and its payload is the
+/// ARP packet itself; the sender's IP is read from it by the processor.
+fn try_extract_arp_packet(
+    raw_data: &[u8],
+    timestamp: chrono::DateTime<chrono::Utc>,
+    origin_file: &str,
+) -> Option<ParsedPacket> {
+    extract_ethertype_packet(raw_data, 0x0806, "arp", timestamp, origin_file)
+}
+
+/// Shared extraction for Layer-2 protocols (GOOSE, SV, PROFINET, ARP) that carry
+/// their PDU directly after the (optionally tagged) Ethertype.
+fn extract_ethertype_packet(
+    raw_data: &[u8],
+    ethertype: u16,
+    prefix: &str,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    origin_file: &str,
+) -> Option<ParsedPacket> {
+    if raw_data.len() < 14 {
+        return None;
+    }
+    // 802.1Q VLAN / priority tags, possibly stacked
+    let tags = vlan::parse(raw_data);
+    let ethertype_offset = tags.ethertype_offset;
+    let payload_start = ethertype_offset + 2;
+    let frame_type =
+        u16::from_be_bytes([raw_data[ethertype_offset], raw_data[ethertype_offset + 1]]);
+    if frame_type != ethertype {
+        return None;
+    }
+
+    let dst_mac: [u8; 6] = raw_data[0..6].try_into().ok()?;
+    let src_mac: [u8; 6] = raw_data[6..12].try_into().ok()?;
+
+    let src_mac_str = ParsedPacket::format_mac(&src_mac);
+    let dst_mac_str = ParsedPacket::format_mac(&dst_mac);
+
+    Some(ParsedPacket {
+        timestamp,
+        src_mac: Some(src_mac_str.clone()),
+        dst_mac: Some(dst_mac_str.clone()),
+        vlan_id: tags.vlan_id,
+        outer_vlan_id: tags.outer_vlan_id,
+        mpls_labels: Vec::new(),
+        vxlan_vni: None,
+        src_ip: format!("{}:{}", prefix, src_mac_str),
+        dst_ip: format!("{}:{}", prefix, dst_mac_str),
+        transport: crate::packet::TransportProtocol::Other,
+        src_port: 0,
+        dst_port: 0,
+        tcp_flags: 0,
+        tcp_seq: 0,
         length: raw_data.len(),
         payload: raw_data[payload_start..].to_vec(),
         origin_file: origin_file.to_string(),
@@ -150,7 +529,7 @@ pub(crate) fn try_extract_lldp_packet(
 ///
 /// NOTE: Detection logic is inlined here (not delegated to gm-parsers) to avoid
 /// a circular dependency: gm-parsers → gm-capture → gm-parsers.
-pub(crate) fn try_extract_redundancy_packet(
+fn try_extract_redundancy_packet(
     raw_data: &[u8],
     timestamp: chrono::DateTime<chrono::Utc>,
     origin_file: &str,
@@ -190,17 +569,24 @@ pub(crate) fn try_extract_redundancy_packet(
 
     let src_mac_str = ParsedPacket::format_mac(&src_mac);
     let dst_mac_str = ParsedPacket::format_mac(&dst_mac);
+    let tags = vlan::parse(raw_data);
 
     Some(ParsedPacket {
         timestamp,
         src_mac: Some(src_mac_str.clone()),
         dst_mac: Some(dst_mac_str),
+        vlan_id: tags.vlan_id,
+        outer_vlan_id: tags.outer_vlan_id,
+        mpls_labels: Vec::new(),
+        vxlan_vni: None,
         // Encode the protocol hint in src_ip so the processor can route it
         src_ip: format!("redundancy:{proto_hint}"),
         dst_ip: "redundancy:multicast".to_string(),
         transport: crate::packet::TransportProtocol::Other,
         src_port: 0,
         dst_port: 0,
+        tcp_flags: 0,
+        tcp_seq: 0,
         length: raw_data.len(),
         // Payload = everything after the 14-byte Ethernet header
         payload: raw_data[14..].to_vec(),
@@ -210,6 +596,9 @@ pub(crate) fn try_extract_redundancy_packet(
 
 /// Convert pcap packet header timestamp to chrono DateTime.
 ///
+/// `precision` is the one the capture handle was opened with: with
+/// [`pcap::Precision::Nano`] the `tv_usec` field holds nanoseconds.
+///
 /// Casts are required for cross-platform compatibility: the pcap crate's
 /// `PacketHeader` wraps C's `struct timeval`, where `tv_sec` and `tv_usec`
 /// are `long`. On Linux/macOS (LP64), `long` is 64-bit so these are `i64`.
@@ -217,25 +606,233 @@ pub(crate) fn try_extract_redundancy_packet(
 /// `DateTime::from_timestamp` expects `(i64, u32)`, so we cast explicitly
 /// to compile on all platforms.
 #[allow(clippy::unnecessary_cast)] // Casts ARE necessary on Windows (i32→i64), but redundant on Linux (i64→i64)
-pub(crate) fn timestamp_from_pcap(header: pcap::PacketHeader) -> DateTime<Utc> {
-    DateTime::from_timestamp(
-        header.ts.tv_sec as i64,
-        header.ts.tv_usec as u32 * 1000, // microseconds → nanoseconds
-    )
-    .unwrap_or_else(Utc::now)
+pub(crate) fn timestamp_from_pcap(
+    header: pcap::PacketHeader,
+    precision: pcap::Precision,
+) -> DateTime<Utc> {
+    let nanos = match precision {
+        pcap::Precision::Micro => header.ts.tv_usec as u32 * 1000,
+        pcap::Precision::Nano => header.ts.tv_usec as u32,
+    };
+    DateTime::from_timestamp(header.ts.tv_sec as i64, nanos).unwrap_or_else(Utc::now)
 }
 
 pub(crate) fn format_ipv4(bytes: [u8; 4]) -> String {
     format!("{}.{}.{}.{}", bytes[0], bytes[1], bytes[2], bytes[3])
 }
 
-pub(crate) fn format_ipv6(bytes: [u8; 16]) -> String {
-    let segments: Vec<String> = (0..8)
-        .map(|i| {
-            let high = bytes[i * 2] as u16;
-            let low = bytes[i * 2 + 1] as u16;
-            format!("{:x}", (high << 8) | low)
-        })
-        .collect();
-    segments.join(":")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_goose_packet_with_priority_tag() {
+        #[rustfmt::skip]
+        let frame: Vec<u8> = vec![
+            0x01, 0x0C, 0xCD, 0x01, 0x00, 0x01,   // dst (GOOSE multicast)
+            0x00, 0x0C, 0xCD, 0x00, 0x00, 0x10,   // src
+            0x81, 0x00, 0x80, 0x00,               // 802.1Q, priority 4, VLAN 0
+            0x88, 0xB8,                           // GOOSE
+            0x00, 0x01, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let pkt = try_extract_goose_packet(&frame, Utc::now(), "sub.pcap").unwrap();
+        assert_eq!(pkt.src_ip, "goose:00:0c:cd:00:00:10");
+        assert_eq!(pkt.dst_ip, "goose:01:0c:cd:01:00:01");
+        assert_eq!(pkt.payload[..2], [0x00, 0x01]);
+        assert_eq!(pkt.vlan_id, None); // priority tag only
+
+        // Untagged frame, and a non-GOOSE Ethertype
+        let mut untagged = frame[..12].to_vec();
+        untagged.extend_from_slice(&frame[16..]);
+        assert!(try_extract_goose_packet(&untagged, Utc::now(), "sub.pcap").is_some());
+        untagged[12] = 0x08;
+        untagged[13] = 0x00;
+        assert!(try_extract_goose_packet(&untagged, Utc::now(), "sub.pcap").is_none());
+    }
+
+    #[test]
+    fn test_extract_sv_packet() {
+        #[rustfmt::skip]
+        let frame: Vec<u8> = vec![
+            0x01, 0x0C, 0xCD, 0x04, 0x00, 0x01,   // dst (SV multicast)
+            0x00, 0x0C, 0xCD, 0x10, 0x00, 0x01,   // src (merging unit)
+            0x88, 0xBA,                           // Sampled Values
+            0x40, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let pkt = try_extract_sv_packet(&frame, Utc::now(), "bus.pcap").unwrap();
+        assert_eq!(pkt.src_ip, "sv:00:0c:cd:10:00:01");
+        assert_eq!(pkt.dst_ip, "sv:01:0c:cd:04:00:01");
+        assert_eq!(pkt.payload[..2], [0x40, 0x00]);
+        // SV is not mistaken for GOOSE and vice versa
+        assert!(try_extract_goose_packet(&frame, Utc::now(), "bus.pcap").is_none());
+    }
+
+    #[test]
+    fn test_extract_profinet_packet() {
+        #[rustfmt::skip]
+        let frame: Vec<u8> = vec![
+            0x01, 0x0E, 0xCF, 0x00, 0x00, 0x00,   // dst (DCP Identify multicast)
+            0x00, 0x1B, 0x1B, 0x12, 0x34, 0x56,   // src
+            0x81, 0x00, 0xC0, 0x00,               // 802.1Q, priority 6, VLAN 0
+            0x88, 0x92,                           // PROFINET
+            0xFE, 0xFE, 0x05, 0x00,               // FrameID, Identify request
+        ];
+        let pkt = try_extract_profinet_packet(&frame, Utc::now(), "cell.pcap").unwrap();
+        assert_eq!(pkt.src_ip, "profinet:00:1b:1b:12:34:56");
+        assert_eq!(pkt.payload[..2], [0xFE, 0xFE]);
+        assert!(try_extract_goose_packet(&frame, Utc::now(), "cell.pcap").is_none());
+    }
+
+    #[test]
+    fn test_extract_arp_packet() {
+        #[rustfmt::skip]
+        let frame: Vec<u8> = vec![
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,   // dst (broadcast)
+            0x00, 0x1B, 0x1B, 0x12, 0x34, 0x56,   // src
+            0x81, 0x00, 0x00, 0x14,               // 802.1Q, VLAN 20
+            0x08, 0x06,                           // ARP
+            0x00, 0x01, 0x08, 0x00, 0x06, 0x04,   // Ethernet / IPv4
+            0x00, 0x01,                           // request
+        ];
+        let pkt = try_extract_arp_packet(&frame, Utc::now(), "cell.pcap").unwrap();
+        assert_eq!(pkt.src_ip, "arp:00:1b:1b:12:34:56");
+        assert_eq!(pkt.dst_ip, "arp:ff:ff:ff:ff:ff:ff");
+        assert_eq!(pkt.vlan_id, Some(20));
+        assert_eq!(pkt.payload[..2], [0x00, 0x01]);
+    }
+
+    #[test]
+    fn test_extract_lldp_packet_behind_qinq() {
+        #[rustfmt::skip]
+        let frame: Vec<u8> = vec![
+            0x01, 0x80, 0xC2, 0x00, 0x00, 0x0E,   // dst (LLDP multicast)
+            0x00, 0x1E, 0x7A, 0x11, 0x22, 0x33,   // src
+            0x88, 0xA8, 0x00, 0x64,               // 802.1ad, VLAN 100
+            0x81, 0x00, 0x00, 0x0A,               // 802.1Q, VLAN 10
+            0x88, 0xCC,                           // LLDP
+            0x02, 0x07, 0x04, 0x00, 0x1E, 0x7A,   // Chassis ID TLV
+        ];
+        let pkt = try_extract_lldp_packet(&frame, Utc::now(), "trunk.pcap").unwrap();
+        assert_eq!(pkt.src_ip, "lldp:00:1e:7a:11:22:33");
+        assert_eq!(pkt.vlan_id, Some(10));
+        assert_eq!(pkt.outer_vlan_id, Some(100));
+        assert_eq!(pkt.payload[..2], [0x02, 0x07]);
+    }
+
+    #[test]
+    fn test_extract_cdp_packet() {
+        #[rustfmt::skip]
+        let frame: Vec<u8> = vec![
+            0x01, 0x00, 0x0C, 0xCC, 0xCC, 0xCC,   // dst (CDP multicast)
+            0x00, 0x1E, 0x7A, 0x11, 0x22, 0x33,   // src
+            0x00, 0x16,                           // 802.3 length
+            0xAA, 0xAA, 0x03, 0x00, 0x00, 0x0C,   // LLC/SNAP, Cisco OUI
+            0x20, 0x00,                           // CDP
+            0x02, 0xB4, 0x00, 0x00,               // version 2, TTL 180, checksum
+            0x00, 0x01, 0x00, 0x06, b's', b'w',   // Device ID "sw"
+        ];
+        let pkt = try_extract_cdp_packet(&frame, Utc::now(), "span.pcap").unwrap();
+        assert_eq!(pkt.src_ip, "cdp:00:1e:7a:11:22:33");
+        assert_eq!(pkt.payload[..2], [0x02, 0xB4]);
+
+        // Same MAC but a different SNAP protocol (VTP, 0x2003) is not CDP
+        let mut vtp = frame.clone();
+        vtp[21] = 0x03;
+        assert!(try_extract_cdp_packet(&vtp, Utc::now(), "span.pcap").is_none());
+    }
+
+    #[test]
+    fn test_parse_linux_cooked_frame() {
+        #[rustfmt::skip]
+        let data: Vec<u8> = vec![
+            0x00, 0x04,                           // SLL: sent by us
+            0x00, 0x01, 0x00, 0x06,               // ARPHRD_ETHER, 6-byte address
+            0x00, 0x50, 0x56, 0x0A, 0x0B, 0x0C, 0x00, 0x00,
+            0x08, 0x00,                           // IPv4
+            0x45, 0x00, 0x00, 0x1C, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00,
+            0x0A, 0x00, 0x00, 0x01, 0x0A, 0x00, 0x00, 0x02,
+            0x04, 0xD2, 0x01, 0xF6, 0x00, 0x08, 0x00, 0x00, // UDP 1234 → 502
+        ];
+        let pkt = parse_frame(
+            link::LINKTYPE_LINUX_SLL,
+            &data,
+            Utc::now(),
+            "any.pcap",
+            &mut FragmentCache::new(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(pkt.src_ip, "10.0.0.1");
+        assert_eq!(pkt.dst_port, 502);
+        assert_eq!(pkt.src_mac.as_deref(), Some("00:50:56:0a:0b:0c"));
+        assert_eq!(pkt.dst_mac, None);
+        assert_eq!(pkt.length, data.len());
+    }
+
+    #[test]
+    fn test_parse_icmp_echo_request() {
+        #[rustfmt::skip]
+        let data: Vec<u8> = vec![
+            0x00, 0x1D, 0x9C, 0x01, 0x02, 0x03, 0x00, 0x80, 0xF4, 0x11, 0x22, 0x33,
+            0x08, 0x00,                           // IPv4
+            0x45, 0x00, 0x00, 0x20, 0x00, 0x00, 0x40, 0x00, 0x40, 0x01, 0x00, 0x00,
+            0x0A, 0x00, 0x00, 0x01, 0x0A, 0x00, 0x00, 0x02,
+            0x08, 0x00, 0x00, 0x00, 0x12, 0x34, 0x00, 0x01, // echo request
+            0x61, 0x62, 0x63, 0x64,
+        ];
+        let pkt = parse_frame(
+            link::LINKTYPE_ETHERNET,
+            &data,
+            Utc::now(),
+            "sweep.pcap",
+            &mut FragmentCache::new(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(pkt.transport, TransportProtocol::Icmp);
+        assert_eq!(pkt.dst_port, 0);
+        // The whole ICMP message, header included
+        assert_eq!(pkt.payload[..2], [0x08, 0x00]);
+        assert_eq!(pkt.payload.len(), 12);
+    }
+
+    #[test]
+    fn test_parse_fragmented_udp_datagram() {
+        // A 32-byte UDP datagram (DNP3 port) split after 16 bytes
+        let payload: Vec<u8> = (0..24).collect();
+        let mut datagram = vec![0x4E, 0x20, 0x4E, 0x20, 0x00, 0x20, 0x00, 0x00];
+        datagram.extend_from_slice(&payload);
+
+        let frame = |flags_offset: [u8; 2], data: &[u8]| {
+            let mut frame = vec![
+                0x00, 0x1D, 0x9C, 0x01, 0x02, 0x03, 0x00, 0x80, 0xF4, 0x11, 0x22, 0x33, 0x08, 0x00,
+                0x45, 0x00,
+            ];
+            frame.extend_from_slice(&(20 + data.len() as u16).to_be_bytes());
+            frame.extend_from_slice(&[0x12, 0x34]);
+            frame.extend_from_slice(&flags_offset);
+            frame.extend_from_slice(&[64, 17, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2]);
+            frame.extend_from_slice(data);
+            frame
+        };
+        let first = frame([0x20, 0x00], &datagram[..16]); // MF
+        let last = frame([0x00, 0x02], &datagram[16..]); // offset 16
+
+        let mut fragments = FragmentCache::new();
+        let parse = |data: &[u8], fragments: &mut FragmentCache| {
+            parse_frame(
+                link::LINKTYPE_ETHERNET,
+                data,
+                Utc::now(),
+                "wan.pcap",
+                fragments,
+            )
+            .unwrap()
+        };
+        assert!(parse(&first, &mut fragments).is_none());
+        let pkt = parse(&last, &mut fragments).unwrap();
+        assert_eq!(pkt.transport, TransportProtocol::Udp);
+        assert_eq!(pkt.dst_port, 20000);
+        assert_eq!(pkt.payload, payload);
+    }
 }
@@ -1,14 +1,49 @@
 //! Filtered PCAP export.
 //!
-//! Reads raw packets from one or more PCAP files, filters by IP address
-//! and/or port number, and writes matching packets to an output file.
+//! Reads raw packets from one or more PCAP files, keeps those matching a
+//! filter, and writes them to an output file with their full payloads:
 //!
-//! Used by the `export_filtered_pcap` Tauri command.
+//! - [`filter_export_pcap`] keeps packets to or from given IPs and/or ports
+//!   (everything an asset sent or received, for instance);
+//! - [`export_conversations_pcap`] keeps the packets of given conversations,
+//!   so one suspicious session can be handed to Wireshark or an IR team.
+//!
+//! Used by the `export_filtered_pcap` and `export_connection_pcap` Tauri
+//! commands.
 
 use std::collections::HashSet;
 use std::path::Path;
 
+use crate::decompress;
 use crate::error::CaptureError;
+use crate::ipv6;
+use crate::link;
+
+/// Both directions of one conversation between two endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversationFilter {
+    pub ip_a: String,
+    pub port_a: u16,
+    pub ip_b: String,
+    pub port_b: u16,
+    /// IP protocol number (6 = TCP, 17 = UDP); `None` matches any protocol
+    /// between the two addresses, ignoring ports
+    pub ip_protocol: Option<u8>,
+}
+
+impl ConversationFilter {
+    fn matches(&self, frame: &FrameEndpoints) -> bool {
+        let Some(protocol) = self.ip_protocol else {
+            return (frame.src_ip == self.ip_a && frame.dst_ip == self.ip_b)
+                || (frame.src_ip == self.ip_b && frame.dst_ip == self.ip_a);
+        };
+        let a = (self.ip_a.as_str(), self.port_a);
+        let b = (self.ip_b.as_str(), self.port_b);
+        let src = (frame.src_ip.as_str(), frame.src_port);
+        let dst = (frame.dst_ip.as_str(), frame.dst_port);
+        frame.ip_protocol == protocol && ((src == a && dst == b) || (src == b && dst == a))
+    }
+}
 
 /// Export packets matching the given filters from one or more input PCAPs.
 ///
@@ -31,9 +66,54 @@ pub fn filter_export_pcap(
 ) -> Result<u64, CaptureError> {
     let ip_set: HashSet<&str> = filter_ips.iter().map(|s| s.as_str()).collect();
     let port_set: HashSet<u16> = filter_ports.iter().copied().collect();
+    let written = write_matching(input_paths, output_path, |frame| {
+        packet_matches_filter(frame, &ip_set, &port_set)
+    })?;
+
+    log::info!(
+        "filter_export_pcap: wrote {} packets to '{}'",
+        written,
+        output_path
+    );
+    Ok(written)
+}
+
+/// Export the packets of the given conversations (both directions) from
+/// one or more input PCAPs, in file order. Input paths are handled as in
+/// [`filter_export_pcap`].
+///
+/// Returns the number of packets written.
+pub fn export_conversations_pcap(
+    input_paths: &[String],
+    conversations: &[ConversationFilter],
+    output_path: &str,
+) -> Result<u64, CaptureError> {
+    let written = write_matching(input_paths, output_path, |frame| {
+        frame_endpoints(frame).is_some_and(|endpoints| {
+            conversations
+                .iter()
+                .any(|conversation| conversation.matches(&endpoints))
+        })
+    })?;
 
+    log::info!(
+        "export_conversations_pcap: wrote {} packets of {} conversations to '{}'",
+        written,
+        conversations.len(),
+        output_path
+    );
+    Ok(written)
+}
+
+/// Copy every frame `keep` accepts from the input files to a new Ethernet
+/// PCAP at `output_path`, returning the number written.
+fn write_matching(
+    input_paths: &[String],
+    output_path: &str,
+    mut keep: impl FnMut(&[u8]) -> bool,
+) -> Result<u64, CaptureError> {
     // Create a dead (offline) capture handle for writing Ethernet frames
-    let dead = pcap::Capture::dead(pcap::Linktype(1))
+    let dead = pcap::Capture::dead(pcap::Linktype(link::LINKTYPE_ETHERNET))
         .map_err(|e| CaptureError::Capture(format!("Cannot create dead capture: {}", e)))?;
 
     let mut savefile = dead.savefile(output_path).map_err(|e| {
@@ -50,95 +130,145 @@ pub fn filter_export_pcap(
 
         let path = Path::new(raw_path);
         if !path.exists() {
-            log::warn!("filter_export_pcap: file not found, skipping: {}", raw_path);
+            log::warn!("PCAP export: file not found, skipping: {}", raw_path);
             continue;
         }
 
-        let mut capture = match pcap::Capture::from_file(path) {
+        // Records are written back out unchanged, at the output's precision
+        let mut opened = match decompress::open_capture(path, pcap::Precision::Micro) {
             Ok(c) => c,
             Err(e) => {
-                log::warn!("filter_export_pcap: cannot open '{}': {}", raw_path, e);
+                log::warn!("PCAP export: cannot open '{}': {}", raw_path, e);
                 continue;
             }
         };
 
+        // The output is Ethernet, so other link types (Linux cooked captures)
+        // are rewritten before matching and written in their Ethernet form
+        let capture = &mut opened.capture;
+        let linktype = capture.get_datalink().0;
         while let Ok(packet) = capture.next_packet() {
-            if packet_matches_filter(packet.data, &ip_set, &port_set) {
-                savefile.write(&packet);
-                written += 1;
+            let Some(frame) = link::to_ethernet(linktype, packet.data) else {
+                continue;
+            };
+            if !keep(&frame.data) {
+                continue;
             }
+            let mut header = *packet.header;
+            header.len =
+                (header.len as usize + frame.data.len()).saturating_sub(packet.data.len()) as u32;
+            header.caplen = frame.data.len() as u32;
+            savefile.write(&pcap::Packet::new(&header, &frame.data));
+            written += 1;
         }
     }
-
-    log::info!(
-        "filter_export_pcap: wrote {} packets to '{}'",
-        written,
-        output_path
-    );
     Ok(written)
 }
 
-/// Check whether a raw Ethernet frame's IPs/ports match the given filters.
-///
-/// Parses IPv4 headers inline (no etherparse overhead) to extract src/dst
-/// IP and port. Non-IP packets (ARP, IPv6, etc.) are excluded.
-fn packet_matches_filter(data: &[u8], ip_set: &HashSet<&str>, port_set: &HashSet<u16>) -> bool {
-    // No filters → include everything
-    if ip_set.is_empty() && port_set.is_empty() {
-        return true;
-    }
+/// Addresses, IP protocol and ports of an IP frame. Ports are 0 when the
+/// packet is not TCP/UDP or carries no port header.
+#[derive(Debug, PartialEq, Eq)]
+struct FrameEndpoints {
+    src_ip: String,
+    dst_ip: String,
+    ip_protocol: u8,
+    src_port: u16,
+    dst_port: u16,
+}
 
+/// Parse a raw Ethernet frame's IPv4 or IPv6 header inline (no etherparse
+/// overhead), looking through 802.1Q/802.1ad tags. None for non-IP frames
+/// (ARP, LLDP, etc.).
+fn frame_endpoints(data: &[u8]) -> Option<FrameEndpoints> {
     // Need at least an Ethernet header (14 bytes)
     if data.len() < 14 {
-        return false;
+        return None;
     }
 
-    let ethertype = u16::from_be_bytes([data[12], data[13]]);
-
-    // Only handle IPv4 (0x0800)
-    if ethertype != 0x0800 {
-        return false;
-    }
-
-    if data.len() < 20 + 14 {
-        return false;
+    let mut ethertype = u16::from_be_bytes([data[12], data[13]]);
+    let mut ip_start = 14;
+    while matches!(ethertype, 0x8100 | 0x88A8) && data.len() >= ip_start + 4 {
+        ethertype = u16::from_be_bytes([data[ip_start + 2], data[ip_start + 3]]);
+        ip_start += 4;
     }
 
-    let ip_start = 14;
-    let ihl = ((data[ip_start] & 0x0f) as usize) * 4;
-    let proto = data[ip_start + 9];
-
-    // Format src/dst IP as dotted decimal strings for Set lookup
-    let src_ip = format!(
-        "{}.{}.{}.{}",
-        data[ip_start + 12],
-        data[ip_start + 13],
-        data[ip_start + 14],
-        data[ip_start + 15]
-    );
-    let dst_ip = format!(
-        "{}.{}.{}.{}",
-        data[ip_start + 16],
-        data[ip_start + 17],
-        data[ip_start + 18],
-        data[ip_start + 19]
-    );
+    // Src/dst IP as strings for Set lookup, plus the transport header
+    // (None when the packet is not TCP/UDP or carries no port header)
+    let (src_ip, dst_ip, ip_protocol, transport) = match ethertype {
+        0x0800 => {
+            if data.len() < ip_start + 20 {
+                return None;
+            }
+            let ihl = ((data[ip_start] & 0x0f) as usize) * 4;
+            let proto = data[ip_start + 9];
+            let src_ip = format!(
+                "{}.{}.{}.{}",
+                data[ip_start + 12],
+                data[ip_start + 13],
+                data[ip_start + 14],
+                data[ip_start + 15]
+            );
+            let dst_ip = format!(
+                "{}.{}.{}.{}",
+                data[ip_start + 16],
+                data[ip_start + 17],
+                data[ip_start + 18],
+                data[ip_start + 19]
+            );
+            let transport = (proto == 6 || proto == 17)
+                .then(|| data.get(ip_start + ihl..))
+                .flatten();
+            (src_ip, dst_ip, proto, transport)
+        }
+        0x86DD => {
+            let upper = ipv6::upper_layer(&data[ip_start..])?;
+            let transport = ((upper.protocol == 6 || upper.protocol == 17)
+                && upper.starts_upper_header)
+                .then_some(upper.payload);
+            (
+                ipv6::format_address(upper.src),
+                ipv6::format_address(upper.dst),
+                upper.protocol,
+                transport,
+            )
+        }
+        _ => return None,
+    };
 
     // Extract TCP/UDP ports if present
-    let (src_port, dst_port) = if (proto == 6 || proto == 17) && data.len() >= ip_start + ihl + 4 {
-        let port_start = ip_start + ihl;
-        let sp = u16::from_be_bytes([data[port_start], data[port_start + 1]]);
-        let dp = u16::from_be_bytes([data[port_start + 2], data[port_start + 3]]);
-        (sp, dp)
-    } else {
-        (0, 0)
+    let (src_port, dst_port) = match transport {
+        Some(header) if header.len() >= 4 => (
+            u16::from_be_bytes([header[0], header[1]]),
+            u16::from_be_bytes([header[2], header[3]]),
+        ),
+        _ => (0, 0),
+    };
+
+    Some(FrameEndpoints {
+        src_ip,
+        dst_ip,
+        ip_protocol,
+        src_port,
+        dst_port,
+    })
+}
+
+/// Check whether a raw Ethernet frame's IPs/ports match the given filters.
+/// Non-IP packets are excluded unless there are no filters.
+fn packet_matches_filter(data: &[u8], ip_set: &HashSet<&str>, port_set: &HashSet<u16>) -> bool {
+    // No filters → include everything
+    if ip_set.is_empty() && port_set.is_empty() {
+        return true;
+    }
+    let Some(frame) = frame_endpoints(data) else {
+        return false;
     };
 
     let ip_match = !ip_set.is_empty()
-        && (ip_set.contains(src_ip.as_str()) || ip_set.contains(dst_ip.as_str()));
+        && (ip_set.contains(frame.src_ip.as_str()) || ip_set.contains(frame.dst_ip.as_str()));
 
-    let port_match =
-        !port_set.is_empty() && (port_set.contains(&src_port) || port_set.contains(&dst_port));
+    let port_match = !port_set.is_empty()
+        && (port_set.contains(&frame.src_port) || port_set.contains(&frame.dst_port));
 
     match (!ip_set.is_empty(), !port_set.is_empty()) {
         (true, true) => ip_match || port_match, // either filter matches
@@ -204,4 +334,73 @@ mod tests {
         let ip_set3: HashSet<&str> = ["10.0.0.99"].iter().copied().collect();
         assert!(!packet_matches_filter(&data, &ip_set3, &HashSet::new()));
     }
+
+    #[test]
+    fn test_packet_matches_ipv6_behind_extension_header() {
+        // Ethernet + IPv6 (hop-by-hop) + 8-byte extension + UDP
+        let mut data = vec![0u8; 14 + 40 + 8 + 8];
+        data[12] = 0x86;
+        data[13] = 0xDD;
+        data[14] = 0x60;
+        data[20] = 0; // next header: hop-by-hop
+        data[22] = 0xFE; // src fe80::10
+        data[23] = 0x80;
+        data[37] = 0x10;
+        data[38] = 0xFF; // dst ff02::1
+        data[39] = 0x02;
+        data[53] = 0x01;
+        data[54] = 17; // hop-by-hop → UDP
+        data[62..64].copy_from_slice(&47808u16.to_be_bytes());
+        data[64..66].copy_from_slice(&47808u16.to_be_bytes());
+
+        let ip_set: HashSet<&str> = ["fe80::10"].iter().copied().collect();
+        assert!(packet_matches_filter(&data, &ip_set, &HashSet::new()));
+        let port_set: HashSet<u16> = [47808].into_iter().collect();
+        assert!(packet_matches_filter(&data, &HashSet::new(), &port_set));
+        let other_port: HashSet<u16> = [502].into_iter().collect();
+        assert!(!packet_matches_filter(&data, &HashSet::new(), &other_port));
+    }
+
+    /// Ethernet + IPv4 + TCP header, optionally behind an 802.1Q tag.
+    fn tcp_frame(src: [u8; 4], src_port: u16, dst: [u8; 4], dst_port: u16, vlan: bool) -> Vec<u8> {
+        let mut data = vec![0u8; 12];
+        if vlan {
+            data.extend_from_slice(&[0x81, 0x00, 0x00, 0x0A]);
+        }
+        data.extend_from_slice(&[0x08, 0x00, 0x45]);
+        data.extend_from_slice(&[0; 8]);
+        data.push(6); // TCP
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(&src);
+        data.extend_from_slice(&dst);
+        data.extend_from_slice(&src_port.to_be_bytes());
+        data.extend_from_slice(&dst_port.to_be_bytes());
+        data.extend_from_slice(&[0; 16]);
+        data
+    }
+
+    #[test]
+    fn test_conversation_matches_both_directions() {
+        let hmi = [10, 0, 0, 10];
+        let plc = [10, 0, 0, 1];
+        let conversation = ConversationFilter {
+            ip_a: "10.0.0.10".to_string(),
+            port_a: 49152,
+            ip_b: "10.0.0.1".to_string(),
+            port_b: 502,
+            ip_protocol: Some(6),
+        };
+        let matches = |data: &[u8]| conversation.matches(&frame_endpoints(data).unwrap());
+
+        assert!(matches(&tcp_frame(hmi, 49152, plc, 502, false)));
+        assert!(matches(&tcp_frame(plc, 502, hmi, 49152, true)));
+        // Same hosts, another client port
+        assert!(!matches(&tcp_frame(hmi, 49153, plc, 502, false)));
+
+        let any_protocol = ConversationFilter {
+            ip_protocol: None,
+            ..conversation.clone()
+        };
+        assert!(any_protocol.matches(&frame_endpoints(&tcp_frame(hmi, 1, plc, 2, false)).unwrap()));
+    }
 }
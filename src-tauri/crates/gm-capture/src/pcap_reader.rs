@@ -1,10 +1,16 @@
+use chrono::Duration;
 use log::{debug, warn};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::decompress::{self, OpenCapture};
+use crate::defrag::FragmentCache;
 use crate::error::CaptureError;
 use crate::packet::ParsedPacket;
 use crate::parsing;
+use crate::sampling::{ImportSampling, Sampler};
+use crate::tcp_stream::TcpReassembler;
 
 /// Progress update emitted roughly every 500ms during streaming PCAP processing.
 #[derive(Debug, Clone)]
@@ -23,6 +29,8 @@ pub struct FileProcessStats {
     pub packet_count: u64,
     pub bytes_processed: u64,
     pub skipped: u64,
+    /// Packets left out by the reader's [`ImportSampling`]
+    pub filtered: u64,
 }
 
 /// Reads and parses packets from a PCAP/PCAPNG file.
@@ -32,22 +40,59 @@ pub struct FileProcessStats {
 /// use gm_capture::PcapReader;
 ///
 /// let reader = PcapReader::new();
-/// let packets = reader.read_file("capture.pcap").unwrap();
-/// println!("Parsed {} packets", packets.len());
+/// for packet in reader.packets("capture.pcap").unwrap() {
+///     println!("{} -> {}", packet.src_ip, packet.dst_ip);
+/// }
 /// ```
-pub struct PcapReader;
+///
+/// Timestamps are read at nanosecond resolution; files written with
+/// microsecond timestamps are scaled up by libpcap.
+pub struct PcapReader {
+    tcp_reassembly_ports: Vec<u16>,
+    sampling: ImportSampling,
+    clock_offsets: HashMap<PathBuf, Duration>,
+}
 
 impl PcapReader {
     pub fn new() -> Self {
-        PcapReader
+        PcapReader {
+            tcp_reassembly_ports: Vec::new(),
+            sampling: ImportSampling::default(),
+            clock_offsets: HashMap::new(),
+        }
     }
 
-    /// Read all packets from a PCAP or PCAPNG file.
+    /// Reassemble TCP connections on these ports, so packets carry their
+    /// direction's stream in order and without retransmitted bytes rather
+    /// than the segment as captured.
+    pub fn with_tcp_reassembly(mut self, ports: impl IntoIterator<Item = u16>) -> Self {
+        self.tcp_reassembly_ports = ports.into_iter().collect();
+        self
+    }
+
+    /// Import only the packets `sampling` selects. Each file is sampled on
+    /// its own: per-flow limits and every-Nth counting restart per file.
+    pub fn with_sampling(mut self, sampling: ImportSampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Shift every timestamp read from the file at `path` by `offset`, to
+    /// line up captures from sensors whose clocks disagree. The shifted
+    /// time is the one the sampling time window is checked against.
+    pub fn with_clock_offset(mut self, path: impl Into<PathBuf>, offset: Duration) -> Self {
+        self.clock_offsets.insert(path.into(), offset);
+        self
+    }
+
+    /// Open a PCAP or PCAPNG file for incremental reading. Gzip and zstd
+    /// compressed files are decompressed to a temporary file first.
     ///
-    /// Returns a Vec of parsed packets with Layer 2-4 information extracted.
-    /// Each packet is tagged with the origin filename for multi-PCAP tracking.
-    /// Packets that fail to parse are silently skipped (logged at debug level).
-    pub fn read_file<P: AsRef<Path>>(&self, path: P) -> Result<Vec<ParsedPacket>, CaptureError> {
+    /// The returned iterator parses one packet per `next()` call, so only
+    /// the packet being handled is in memory regardless of file size.
+    /// Packets that fail to parse are skipped (logged at debug level) and
+    /// counted in [`PacketStream::skipped`].
+    pub fn packets<P: AsRef<Path>>(&self, path: P) -> Result<PacketStream, CaptureError> {
         let path = path.as_ref();
 
         // Extract just the filename (not the full path) for origin tracking
@@ -56,63 +101,53 @@ impl PcapReader {
             .map(|f| f.to_string_lossy().into_owned())
             .unwrap_or_else(|| path.display().to_string());
 
-        let mut capture = pcap::Capture::from_file(path)
-            .map_err(|e| CaptureError::FileOpen(format!("{}: {}", path.display(), e)))?;
-
-        let mut packets = Vec::new();
-        let mut skipped = 0u64;
-
-        while let Ok(raw_packet) = capture.next_packet() {
-            // Extract timestamp from pcap header
-            let timestamp = parsing::timestamp_from_pcap(*raw_packet.header);
+        let OpenCapture {
+            capture,
+            size,
+            decompressed,
+        } = decompress::open_capture(path, pcap::Precision::Nano)?;
+        let linktype = capture.get_datalink().0;
 
-            // Check for LLDP (Ethertype 0x88CC) before IP parsing
-            if let Some(lldp_pkt) =
-                parsing::try_extract_lldp_packet(raw_packet.data, timestamp, &origin_file)
-            {
-                packets.push(lldp_pkt);
-                continue;
-            }
-
-            // Check for Layer-2 redundancy protocols (MRP/RSTP/HSR/PRP/DLR)
-            if let Some(red_pkt) =
-                parsing::try_extract_redundancy_packet(raw_packet.data, timestamp, &origin_file)
-            {
-                packets.push(red_pkt);
-                continue;
-            }
+        Ok(PacketStream {
+            capture,
+            _decompressed: decompressed,
+            size,
+            linktype,
+            origin_file,
+            clock_offset: self.clock_offsets.get(path).copied(),
+            bytes_read: 0,
+            skipped: 0,
+            filtered: 0,
+            sampler: (!self.sampling.is_full()).then(|| Sampler::new(self.sampling.clone())),
+            fragments: FragmentCache::new(),
+            tcp_streams: (!self.tcp_reassembly_ports.is_empty())
+                .then(|| TcpReassembler::new(self.tcp_reassembly_ports.iter().copied())),
+        })
+    }
 
-            // Parse with etherparse — zero-copy slicing of packet headers
-            match etherparse::SlicedPacket::from_ethernet(raw_packet.data) {
-                Ok(parsed) => {
-                    if let Some(packet) = parsing::extract_packet_info(
-                        &parsed,
-                        raw_packet.data,
-                        timestamp,
-                        &origin_file,
-                    ) {
-                        packets.push(packet);
-                    } else {
-                        skipped += 1;
-                        debug!("Skipped non-IP packet");
-                    }
-                }
-                Err(e) => {
-                    skipped += 1;
-                    debug!("Failed to parse packet: {}", e);
-                }
-            }
-        }
+    /// Read all packets from a PCAP or PCAPNG file.
+    ///
+    /// Returns a Vec of parsed packets with Layer 2-4 information extracted.
+    /// Each packet is tagged with the origin filename for multi-PCAP tracking.
+    /// Buffers the whole file; prefer [`PcapReader::packets`] or
+    /// [`PcapReader::stream_file`] for large captures.
+    pub fn read_file<P: AsRef<Path>>(&self, path: P) -> Result<Vec<ParsedPacket>, CaptureError> {
+        let mut stream = self.packets(path.as_ref())?;
+        let packets: Vec<ParsedPacket> = stream.by_ref().collect();
 
-        if skipped > 0 {
+        if stream.skipped() > 0 {
             warn!(
                 "Skipped {} unparseable packets out of {}",
-                skipped,
-                packets.len() + skipped as usize
+                stream.skipped(),
+                packets.len() + stream.skipped() as usize
             );
         }
 
-        log::info!("Parsed {} packets from {}", packets.len(), path.display());
+        log::info!(
+            "Parsed {} packets from {}",
+            packets.len(),
+            path.as_ref().display()
+        );
 
         Ok(packets)
     }
@@ -132,93 +167,147 @@ impl PcapReader {
         mut on_progress: impl FnMut(ProgressUpdate),
         cancelled: &AtomicBool,
     ) -> Result<FileProcessStats, CaptureError> {
-        let file_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
-
-        let origin_file = Path::new(path)
-            .file_name()
-            .map(|f| f.to_string_lossy().into_owned())
-            .unwrap_or_else(|| path.to_string());
-
-        let mut capture = pcap::Capture::from_file(path)
-            .map_err(|e| CaptureError::FileOpen(format!("{}: {}", path, e)))?;
-
-        let mut stats = FileProcessStats::default();
+        let mut stream = self.packets(path)?;
+        // Progress is measured against the decompressed size
+        let file_size = stream.size;
+        let mut packet_count = 0u64;
         let start = std::time::Instant::now();
         let mut last_progress = std::time::Instant::now();
 
-        while let Ok(raw_packet) = capture.next_packet() {
+        let progress =
+            |stream: &PacketStream, packet_count: u64, progress_percent: f64| ProgressUpdate {
+                current_file: stream.origin_file.clone(),
+                packets_processed: packet_count,
+                bytes_processed: stream.bytes_read,
+                file_size,
+                progress_percent,
+                elapsed_secs: start.elapsed().as_secs_f64(),
+            };
+
+        while let Some(packet) = stream.next() {
             if cancelled.load(Ordering::Relaxed) {
                 return Err(CaptureError::Cancelled);
             }
 
-            let header = *raw_packet.header;
-            let cap_len = header.caplen as u64;
-            let timestamp = parsing::timestamp_from_pcap(header);
-
-            // Check for LLDP (Ethertype 0x88CC) before IP parsing
-            if let Some(lldp_pkt) =
-                parsing::try_extract_lldp_packet(raw_packet.data, timestamp, &origin_file)
-            {
-                on_packet(&lldp_pkt);
-                stats.packet_count += 1;
-            } else if let Some(red_pkt) =
-                parsing::try_extract_redundancy_packet(raw_packet.data, timestamp, &origin_file)
-            {
-                on_packet(&red_pkt);
-                stats.packet_count += 1;
-            } else {
-                match etherparse::SlicedPacket::from_ethernet(raw_packet.data) {
-                    Ok(parsed) => {
-                        if let Some(packet) = parsing::extract_packet_info(
-                            &parsed,
-                            raw_packet.data,
-                            timestamp,
-                            &origin_file,
-                        ) {
-                            on_packet(&packet);
-                            stats.packet_count += 1;
-                        } else {
-                            stats.skipped += 1;
-                        }
-                    }
-                    Err(_) => {
-                        stats.skipped += 1;
-                    }
-                }
-            }
-
-            stats.bytes_processed += cap_len;
+            on_packet(&packet);
+            packet_count += 1;
 
             // Throttle progress events to ~2/sec to avoid overhead
             if last_progress.elapsed() > std::time::Duration::from_millis(500) {
                 let progress_percent = if file_size > 0 {
-                    (stats.bytes_processed as f64 / file_size as f64 * 100.0).min(100.0)
+                    (stream.bytes_read as f64 / file_size as f64 * 100.0).min(100.0)
                 } else {
                     0.0
                 };
-                on_progress(ProgressUpdate {
-                    current_file: origin_file.clone(),
-                    packets_processed: stats.packet_count,
-                    bytes_processed: stats.bytes_processed,
-                    file_size,
-                    progress_percent,
-                    elapsed_secs: start.elapsed().as_secs_f64(),
-                });
+                on_progress(progress(&stream, packet_count, progress_percent));
                 last_progress = std::time::Instant::now();
             }
         }
 
         // Final progress event at completion
-        on_progress(ProgressUpdate {
-            current_file: origin_file.clone(),
-            packets_processed: stats.packet_count,
-            bytes_processed: stats.bytes_processed,
-            file_size,
-            progress_percent: 100.0,
-            elapsed_secs: start.elapsed().as_secs_f64(),
-        });
+        on_progress(progress(&stream, packet_count, 100.0));
+
+        Ok(FileProcessStats {
+            packet_count,
+            bytes_processed: stream.bytes_read,
+            skipped: stream.skipped,
+            filtered: stream.filtered,
+        })
+    }
+}
+
+/// Iterator over the parsed packets of a capture file, returned by
+/// [`PcapReader::packets`].
+///
+/// Reading stops at the end of the file or at the first read error (a
+/// truncated final record, for instance).
+pub struct PacketStream {
+    capture: pcap::Capture<pcap::Offline>,
+    /// Keeps a decompressed copy of the input alive while it is read
+    _decompressed: Option<tempfile::TempPath>,
+    size: u64,
+    linktype: i32,
+    origin_file: String,
+    /// Added to every timestamp read from the file
+    clock_offset: Option<Duration>,
+    bytes_read: u64,
+    skipped: u64,
+    filtered: u64,
+    /// Present when only part of the file is imported
+    sampler: Option<Sampler>,
+    /// IPv4 fragments awaiting the rest of their datagram
+    fragments: FragmentCache,
+    /// Present when TCP reassembly was requested
+    tcp_streams: Option<TcpReassembler>,
+}
+
+impl PacketStream {
+    /// Captured bytes read so far, including skipped packets.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
 
-        Ok(stats)
+    /// Packets read so far that had nothing to analyze or failed to parse.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Packets read so far that the import sampling left out.
+    pub fn filtered(&self) -> u64 {
+        self.filtered
+    }
+}
+
+impl Iterator for PacketStream {
+    type Item = ParsedPacket;
+
+    fn next(&mut self) -> Option<ParsedPacket> {
+        while let Ok(raw_packet) = self.capture.next_packet() {
+            // Extract timestamp from pcap header, on the aligned clock
+            let mut timestamp =
+                parsing::timestamp_from_pcap(*raw_packet.header, pcap::Precision::Nano);
+            if let Some(offset) = self.clock_offset {
+                timestamp += offset;
+            }
+            self.bytes_read += raw_packet.header.caplen as u64;
+            if let Some(sampler) = &self.sampler {
+                if !sampler.in_window(timestamp) {
+                    self.filtered += 1;
+                    continue;
+                }
+            }
+
+            // Layer-2 protocols (LLDP, CDP, GOOSE, ...) first, then etherparse
+            match parsing::parse_frame(
+                self.linktype,
+                raw_packet.data,
+                timestamp,
+                &self.origin_file,
+                &mut self.fragments,
+            ) {
+                Ok(Some(mut packet)) => {
+                    if let Some(streams) = self.tcp_streams.as_mut() {
+                        streams.process(&mut packet);
+                    }
+                    if let Some(sampler) = self.sampler.as_mut() {
+                        if !sampler.keep(&packet) {
+                            self.filtered += 1;
+                            continue;
+                        }
+                    }
+                    return Some(packet);
+                }
+                Ok(None) => {
+                    self.skipped += 1;
+                    debug!("Skipped non-IP packet");
+                }
+                Err(e) => {
+                    self.skipped += 1;
+                    debug!("Failed to parse packet: {}", e);
+                }
+            }
+        }
+        None
     }
 }
 
@@ -229,3 +318,118 @@ impl Default for PcapReader {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    /// A classic little-endian PCAP holding the given Ethernet frames.
+    fn pcap_file(frames: &[Vec<u8>]) -> Vec<u8> {
+        let mut file = vec![0xD4, 0xC3, 0xB2, 0xA1, 0x02, 0x00, 0x04, 0x00];
+        file.extend_from_slice(&[0; 8]); // thiszone, sigfigs
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes()); // Ethernet
+        for (i, frame) in frames.iter().enumerate() {
+            file.extend_from_slice(&(1_700_000_000 + i as u32).to_le_bytes());
+            file.extend_from_slice(&0u32.to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(frame);
+        }
+        file
+    }
+
+    #[rustfmt::skip]
+    const UDP_FRAME: [u8; 42] = [
+        0x00, 0x1D, 0x9C, 0x01, 0x02, 0x03, 0x00, 0x80, 0xF4, 0x11, 0x22, 0x33,
+        0x08, 0x00,
+        0x45, 0x00, 0x00, 0x1C, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00,
+        0x0A, 0x00, 0x00, 0x01, 0x0A, 0x00, 0x00, 0x02,
+        0x04, 0xD2, 0x4E, 0x20, 0x00, 0x08, 0x00, 0x00, // UDP 1234 → 20000
+    ];
+
+    #[test]
+    fn test_packet_stream() {
+        let udp = UDP_FRAME.to_vec();
+        // A non-IP frame nothing extracts (Ethernet configuration testing)
+        let mut loopback = udp[..12].to_vec();
+        loopback.extend_from_slice(&[0x90, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]);
+
+        let path = std::env::temp_dir().join(format!("gm-stream-{}.pcap", std::process::id()));
+        std::fs::write(&path, pcap_file(&[loopback.clone(), udp.clone()])).unwrap();
+
+        let mut stream = PcapReader::new().packets(&path).unwrap();
+        let packet = stream.next().unwrap();
+        assert_eq!(packet.dst_port, 20000);
+        assert!(packet.origin_file.starts_with("gm-stream-"));
+        assert!(stream.next().is_none());
+        assert_eq!(stream.skipped(), 1);
+        assert_eq!(stream.bytes_read(), (loopback.len() + udp.len()) as u64);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(PcapReader::new().packets(&path).is_err());
+    }
+
+    #[test]
+    fn test_sampling() {
+        // Ten packets of one flow, one second apart
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sampled.pcap");
+        std::fs::write(&path, pcap_file(&vec![UDP_FRAME.to_vec(); 10])).unwrap();
+        let at = |second: i64| DateTime::from_timestamp(1_700_000_000 + second, 0);
+
+        let sampled = |sampling: ImportSampling| {
+            let mut stream = PcapReader::new()
+                .with_sampling(sampling)
+                .packets(&path)
+                .unwrap();
+            let seconds: Vec<i64> = stream
+                .by_ref()
+                .map(|p| p.timestamp.timestamp() - 1_700_000_000)
+                .collect();
+            (seconds, stream.filtered())
+        };
+
+        // Seconds 2..=7, every other one
+        let window = ImportSampling {
+            start: at(2),
+            end: at(7),
+            every_nth: 2,
+            ..Default::default()
+        };
+        assert_eq!(sampled(window), (vec![2, 4, 6], 7));
+
+        let per_flow = ImportSampling {
+            max_packets_per_flow: 3,
+            ..Default::default()
+        };
+        assert_eq!(sampled(per_flow), (vec![0, 1, 2], 7));
+    }
+
+    #[test]
+    fn test_timestamp_precision_and_clock_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let micro = dir.path().join("micro.pcap");
+        let nano = dir.path().join("nano.pcap");
+        let mut file = pcap_file(&[UDP_FRAME.to_vec()]);
+        file[28..32].copy_from_slice(&250_000u32.to_le_bytes());
+        std::fs::write(&micro, &file).unwrap();
+        // Nanosecond-resolution magic and fraction
+        file[..4].copy_from_slice(&[0x4D, 0x3C, 0xB2, 0xA1]);
+        file[28..32].copy_from_slice(&123_456_789u32.to_le_bytes());
+        std::fs::write(&nano, &file).unwrap();
+
+        let first = |reader: &PcapReader, path: &Path| {
+            let packet = reader.packets(path).unwrap().next().unwrap();
+            (
+                packet.timestamp.timestamp() - 1_700_000_000,
+                packet.timestamp.timestamp_subsec_nanos(),
+            )
+        };
+        let reader = PcapReader::new().with_clock_offset(&nano, Duration::milliseconds(-1500));
+        assert_eq!(first(&reader, &micro), (0, 250_000_000));
+        assert_eq!(first(&reader, &nano), (-2, 623_456_789));
+        assert_eq!(first(&PcapReader::new(), &nano), (0, 123_456_789));
+    }
+}
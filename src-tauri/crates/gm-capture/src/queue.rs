@@ -0,0 +1,319 @@
+//! Bounded hand-off queue between the live capture thread and the
+//! processing thread.
+//!
+//! Deep parsing is slower than capture on a saturated link, so an unbounded
+//! channel between the two grows until the process runs out of memory. This
+//! queue holds at most `capacity` packets and, when full, either discards
+//! the oldest queued packet ([`QueuePolicy::DropOldest`], keeping the view
+//! current) or makes the capture thread wait ([`QueuePolicy::Block`], which
+//! pushes the loss down to the kernel buffer where it shows up as kernel
+//! drops). Every discarded packet is counted.
+//!
+//! Only the processing pipeline loses packets this way: the ring buffer and
+//! capture-to-disk files are written before the hand-off.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// How long a blocked sender waits before re-checking the stop flag.
+const BLOCK_POLL: Duration = Duration::from_millis(100);
+
+/// What the capture thread does when the queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuePolicy {
+    /// Discard the oldest queued packet to make room
+    #[default]
+    DropOldest,
+    /// Wait for the processing thread to catch up
+    Block,
+}
+
+/// Snapshot of a queue's fill level and losses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Packets waiting to be processed
+    pub depth: usize,
+    pub capacity: usize,
+    /// Highest depth reached so far
+    pub peak_depth: usize,
+    /// Packets discarded because the queue was full
+    pub dropped: u64,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    items: VecDeque<T>,
+    peak_depth: usize,
+    dropped: u64,
+    sender_alive: bool,
+    receiver_alive: bool,
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    policy: QueuePolicy,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        // A panicking peer leaves the queue itself consistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Create a queue holding at most `capacity` items (at least 1).
+pub fn bounded<T>(capacity: usize, policy: QueuePolicy) -> (QueueSender<T>, QueueReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            items: VecDeque::new(),
+            peak_depth: 0,
+            dropped: 0,
+            sender_alive: true,
+            receiver_alive: true,
+        }),
+        not_empty: Condvar::new(),
+        not_full: Condvar::new(),
+        capacity: capacity.max(1),
+        policy,
+    });
+    (
+        QueueSender {
+            shared: shared.clone(),
+        },
+        QueueReceiver { shared },
+    )
+}
+
+/// Sending half, owned by the capture thread.
+#[derive(Debug)]
+pub struct QueueSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The receiving half has been dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disconnected;
+
+impl<T> QueueSender<T> {
+    /// Queue an item, applying the policy if the queue is full.
+    ///
+    /// Under [`QueuePolicy::Block`] the call waits for room, but gives up
+    /// (counting the item as dropped) once `stop` is set, so stopping a
+    /// capture never waits on a consumer that has stopped reading.
+    pub fn send(&self, item: T, stop: &AtomicBool) -> Result<(), Disconnected> {
+        let shared = &*self.shared;
+        let mut state = shared.lock();
+        if !state.receiver_alive {
+            return Err(Disconnected);
+        }
+        if state.items.len() >= shared.capacity {
+            match shared.policy {
+                QueuePolicy::DropOldest => {
+                    state.items.pop_front();
+                    state.dropped += 1;
+                }
+                QueuePolicy::Block => {
+                    while state.items.len() >= shared.capacity {
+                        if !state.receiver_alive {
+                            return Err(Disconnected);
+                        }
+                        if stop.load(Ordering::Relaxed) {
+                            state.dropped += 1;
+                            return Ok(());
+                        }
+                        state = shared
+                            .not_full
+                            .wait_timeout(state, BLOCK_POLL)
+                            .unwrap_or_else(|e| e.into_inner())
+                            .0;
+                    }
+                }
+            }
+        }
+        state.items.push_back(item);
+        state.peak_depth = state.peak_depth.max(state.items.len());
+        drop(state);
+        shared.not_empty.notify_one();
+        Ok(())
+    }
+}
+
+impl<T> Drop for QueueSender<T> {
+    fn drop(&mut self) {
+        self.shared.lock().sender_alive = false;
+        self.shared.not_empty.notify_all();
+    }
+}
+
+/// Receiving half, owned by the processing thread.
+///
+/// Mirrors the `std::sync::mpsc::Receiver` calls the processing loops use;
+/// once the sender is gone, queued items are still delivered before
+/// `Disconnected` is reported.
+#[derive(Debug)]
+pub struct QueueReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> QueueReceiver<T> {
+    /// Wait up to `timeout` for the next item.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let shared = &*self.shared;
+        let deadline = Instant::now() + timeout;
+        let mut state = shared.lock();
+        loop {
+            if let Some(item) = state.items.pop_front() {
+                drop(state);
+                shared.not_full.notify_one();
+                return Ok(item);
+            }
+            if !state.sender_alive {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            state = shared
+                .not_empty
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Take the next item if one is queued.
+    pub fn try_recv(&self) -> Option<T> {
+        let item = self.shared.lock().items.pop_front();
+        if item.is_some() {
+            self.shared.not_full.notify_one();
+        }
+        item
+    }
+
+    /// Drain the items queued right now, without waiting.
+    pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.try_recv())
+    }
+
+    /// A handle for reading the queue's statistics.
+    pub fn monitor(&self) -> QueueMonitor<T> {
+        QueueMonitor {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for QueueReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_alive = false;
+        self.shared.not_full.notify_all();
+    }
+}
+
+/// Reads a queue's statistics without taking part in the hand-off.
+#[derive(Debug)]
+pub struct QueueMonitor<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> QueueMonitor<T> {
+    pub fn stats(&self) -> QueueStats {
+        let state = self.shared.lock();
+        QueueStats {
+            depth: state.items.len(),
+            capacity: self.shared.capacity,
+            peak_depth: state.peak_depth,
+            dropped: state.dropped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    static NO_STOP: AtomicBool = AtomicBool::new(false);
+
+    #[test]
+    fn test_drop_oldest_keeps_newest() {
+        let (tx, rx) = bounded(3, QueuePolicy::DropOldest);
+        let monitor = rx.monitor();
+        for i in 0..5 {
+            tx.send(i, &NO_STOP).unwrap();
+        }
+        let stats = monitor.stats();
+        assert_eq!(stats.depth, 3);
+        assert_eq!(stats.peak_depth, 3);
+        assert_eq!(stats.dropped, 2);
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!(monitor.stats().depth, 0);
+    }
+
+    #[test]
+    fn test_block_waits_for_room() {
+        let (tx, rx) = bounded(2, QueuePolicy::Block);
+        let producer = thread::spawn(move || {
+            for i in 0..50 {
+                tx.send(i, &NO_STOP).unwrap();
+            }
+        });
+        let mut received = Vec::new();
+        while let Ok(item) = rx.recv_timeout(Duration::from_secs(5)) {
+            received.push(item);
+        }
+        producer.join().unwrap();
+        // Nothing lost, in order, and the sender's drop ended the loop
+        assert_eq!(received, (0..50).collect::<Vec<_>>());
+        assert_eq!(rx.monitor().stats().dropped, 0);
+        assert_eq!(rx.monitor().stats().peak_depth, 2);
+    }
+
+    #[test]
+    fn test_block_gives_up_on_stop() {
+        let (tx, rx) = bounded(1, QueuePolicy::Block);
+        let stop = AtomicBool::new(true);
+        tx.send(1, &stop).unwrap();
+        // Full and stopping: the item is counted as dropped instead of waiting
+        tx.send(2, &stop).unwrap();
+        assert_eq!(rx.monitor().stats().dropped, 1);
+        assert_eq!(rx.try_recv(), Some(1));
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let (tx, rx) = bounded(4, QueuePolicy::DropOldest);
+        tx.send(7, &NO_STOP).unwrap();
+        drop(tx);
+        // Queued items outlive the sender
+        assert_eq!(rx.recv_timeout(Duration::from_millis(10)), Ok(7));
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+
+        let (tx, rx) = bounded::<u8>(4, QueuePolicy::Block);
+        drop(rx);
+        assert_eq!(tx.send(1, &NO_STOP), Err(Disconnected));
+    }
+
+    #[test]
+    fn test_recv_timeout_expires() {
+        let (_tx, rx) = bounded::<u8>(4, QueuePolicy::DropOldest);
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(5)),
+            Err(RecvTimeoutError::Timeout)
+        );
+    }
+}
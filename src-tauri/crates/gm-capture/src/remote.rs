@@ -0,0 +1,500 @@
+//! Remote capture from a sensor on the SPAN port.
+//!
+//! The workstation running the GUI is rarely the machine plugged into the
+//! OT switch. A remote source runs a capture tool on (or against) the
+//! sensor and reads the classic PCAP stream it writes to stdout:
+//!
+//! - `ssh://[user@]host[:port]/interface` runs `tcpdump -w -` over SSH
+//!   (key-based authentication; there is no password prompt)
+//! - `rpcap://host[:port]/interface` runs a local `dumpcap` (Wireshark)
+//!   against an rpcapd daemon on the sensor
+//!
+//! The BPF filter is applied by the remote capture so that only matching
+//! traffic crosses the management network.
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use crate::error::CaptureError;
+
+/// Default rpcapd control port.
+const RPCAP_DEFAULT_PORT: u16 = 2002;
+
+/// Seconds SSH waits for the sensor to answer before giving up.
+const SSH_CONNECT_TIMEOUT_SECS: u32 = 10;
+
+/// Most stderr output kept for error messages.
+const MAX_STDERR_LEN: usize = 2048;
+
+/// A remote capture source, parsed from an `ssh://` or `rpcap://` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteSource {
+    /// `tcpdump` on the sensor, streamed over SSH
+    Ssh {
+        /// `host` or `user@host`
+        destination: String,
+        port: Option<u16>,
+        interface: String,
+    },
+    /// An rpcapd daemon on the sensor, read through a local `dumpcap`
+    Rpcap {
+        host: String,
+        port: Option<u16>,
+        interface: String,
+    },
+}
+
+impl RemoteSource {
+    /// Parse `ssh://[user@]host[:port]/interface` or
+    /// `rpcap://host[:port]/interface`.
+    pub fn parse(url: &str) -> Result<Self, CaptureError> {
+        let invalid = |why: &str| CaptureError::Remote(format!("'{}': {}", url, why));
+
+        let (scheme, rest) = url
+            .trim()
+            .split_once("://")
+            .ok_or_else(|| invalid("expected ssh://host/interface or rpcap://host/interface"))?;
+        let (authority, interface) = rest
+            .split_once('/')
+            .filter(|(_, iface)| !iface.is_empty())
+            .ok_or_else(|| invalid("no interface given"))?;
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, host_port)) => (Some(user), host_port),
+            None => (None, authority),
+        };
+        let (host, port) = split_host_port(host_port).ok_or_else(|| invalid("bad host or port"))?;
+        if host.is_empty() {
+            return Err(invalid("no host given"));
+        }
+
+        let interface = interface.to_string();
+        match scheme.to_ascii_lowercase().as_str() {
+            "ssh" => Ok(RemoteSource::Ssh {
+                destination: match user {
+                    Some(user) => format!("{}@{}", user, host),
+                    None => host.to_string(),
+                },
+                port,
+                interface,
+            }),
+            "rpcap" if user.is_none() => Ok(RemoteSource::Rpcap {
+                host: host.to_string(),
+                port,
+                interface,
+            }),
+            "rpcap" => Err(invalid("rpcap URLs do not take a user name")),
+            _ => Err(invalid("unsupported scheme")),
+        }
+    }
+
+    /// Short name for logs and packet origins, e.g. `ssh:ops@sensor1/eth1`.
+    pub fn label(&self) -> String {
+        match self {
+            RemoteSource::Ssh {
+                destination,
+                interface,
+                ..
+            } => format!("ssh:{}/{}", destination, interface),
+            RemoteSource::Rpcap {
+                host, interface, ..
+            } => format!("rpcap:{}/{}", host, interface),
+        }
+    }
+
+    /// The local command that writes the remote capture to stdout.
+    fn command(&self, filter: Option<&str>, snaplen: i32, promiscuous: bool) -> Command {
+        match self {
+            RemoteSource::Ssh {
+                destination,
+                port,
+                interface,
+            } => {
+                // ssh joins its arguments into one remote shell command
+                let mut remote = format!(
+                    "tcpdump -U -n -s {} -w - -i {}",
+                    snaplen,
+                    shell_quote(interface)
+                );
+                if !promiscuous {
+                    remote.push_str(" -p");
+                }
+                if let Some(filter) = filter {
+                    remote.push(' ');
+                    remote.push_str(&shell_quote(filter));
+                }
+
+                let mut command = Command::new("ssh");
+                command
+                    .args(["-o", "BatchMode=yes"])
+                    .arg("-o")
+                    .arg(format!("ConnectTimeout={}", SSH_CONNECT_TIMEOUT_SECS));
+                if let Some(port) = port {
+                    command.arg("-p").arg(port.to_string());
+                }
+                command.arg(destination).arg(remote);
+                command
+            }
+            RemoteSource::Rpcap {
+                host,
+                port,
+                interface,
+            } => {
+                let mut command = Command::new("dumpcap");
+                command
+                    .arg("-i")
+                    .arg(format!(
+                        "rpcap://{}:{}/{}",
+                        host,
+                        port.unwrap_or(RPCAP_DEFAULT_PORT),
+                        interface
+                    ))
+                    .args(["-w", "-", "-P", "-q", "-s"])
+                    .arg(snaplen.to_string());
+                if !promiscuous {
+                    command.arg("-p");
+                }
+                if let Some(filter) = filter {
+                    command.arg("-f").arg(filter);
+                }
+                command
+            }
+        }
+    }
+}
+
+/// Split `host`, `host:port`, `[v6]` or `[v6]:port`.
+fn split_host_port(s: &str) -> Option<(&str, Option<u16>)> {
+    if let Some(bracketed) = s.strip_prefix('[') {
+        let (host, rest) = bracketed.split_once(']')?;
+        return match rest {
+            "" => Some((host, None)),
+            _ => Some((host, Some(rest.strip_prefix(':')?.parse().ok()?))),
+        };
+    }
+    match s.split_once(':') {
+        Some((host, port)) => Some((host, Some(port.parse().ok()?))),
+        None => Some((s, None)),
+    }
+}
+
+/// Quote an argument for a POSIX shell.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// One record of a PCAP stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PcapRecord {
+    pub ts_sec: i64,
+    pub ts_usec: u32,
+    /// Original length on the wire
+    pub len: u32,
+    pub data: Vec<u8>,
+}
+
+/// Reads classic PCAP from a byte stream (a pipe, unlike libpcap's files).
+pub(crate) struct PcapStream<R> {
+    reader: R,
+    big_endian: bool,
+    nanosecond: bool,
+    linktype: i32,
+}
+
+impl<R: Read> PcapStream<R> {
+    /// Read the global header.
+    pub fn open(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 24];
+        reader.read_exact(&mut header)?;
+        let (big_endian, nanosecond) = match header[..4] {
+            [0xD4, 0xC3, 0xB2, 0xA1] => (false, false),
+            [0xA1, 0xB2, 0xC3, 0xD4] => (true, false),
+            [0x4D, 0x3C, 0xB2, 0xA1] => (false, true),
+            [0xA1, 0xB2, 0x3C, 0x4D] => (true, true),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "not a classic PCAP stream",
+                ))
+            }
+        };
+        let mut stream = Self {
+            reader,
+            big_endian,
+            nanosecond,
+            linktype: 0,
+        };
+        stream.linktype = (stream.u32_at(&header, 20) & 0x0FFF_FFFF) as i32;
+        Ok(stream)
+    }
+
+    pub fn linktype(&self) -> i32 {
+        self.linktype
+    }
+
+    /// The next record, or None at a clean end of stream.
+    pub fn next_record(&mut self) -> io::Result<Option<PcapRecord>> {
+        let mut header = [0u8; 16];
+        match self.reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let caplen = self.u32_at(&header, 8) as usize;
+        if caplen > 256 * 1024 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("record of {} bytes; stream out of sync", caplen),
+            ));
+        }
+        let mut data = vec![0u8; caplen];
+        self.reader.read_exact(&mut data)?;
+
+        let fraction = self.u32_at(&header, 4);
+        Ok(Some(PcapRecord {
+            ts_sec: self.u32_at(&header, 0) as i64,
+            ts_usec: if self.nanosecond {
+                fraction / 1000
+            } else {
+                fraction
+            },
+            len: self.u32_at(&header, 12),
+            data,
+        }))
+    }
+
+    fn u32_at(&self, bytes: &[u8], offset: usize) -> u32 {
+        let word = [
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ];
+        if self.big_endian {
+            u32::from_be_bytes(word)
+        } else {
+            u32::from_le_bytes(word)
+        }
+    }
+}
+
+/// A running remote capture process and the PCAP stream on its stdout.
+pub(crate) struct RemoteCapture {
+    pub stream: PcapStream<BufReader<ChildStdout>>,
+    /// Shared so the capture can be stopped while a read is blocked
+    pub process: Arc<Mutex<Child>>,
+    stderr: Arc<Mutex<String>>,
+    label: String,
+}
+
+impl RemoteCapture {
+    /// Start the capture command and read the stream header. Fails if the
+    /// tool is missing, the connection is refused, or the remote end exits
+    /// before sending any data.
+    pub fn spawn(
+        source: &RemoteSource,
+        filter: Option<&str>,
+        snaplen: i32,
+        promiscuous: bool,
+    ) -> Result<Self, CaptureError> {
+        let label = source.label();
+        let mut command = source.command(filter, snaplen, promiscuous);
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let program = command.get_program().to_string_lossy().into_owned();
+        let mut child = command.spawn().map_err(|e| {
+            CaptureError::Remote(format!("{}: cannot run {}: {}", label, program, e))
+        })?;
+        log::info!("Remote capture from {} via {}", label, program);
+
+        // Drain stderr so a chatty tool cannot block on a full pipe, keeping
+        // the tail for error messages
+        let stderr = Arc::new(Mutex::new(String::new()));
+        if let Some(pipe) = child.stderr.take() {
+            let tail = stderr.clone();
+            let label = label.clone();
+            std::thread::spawn(move || {
+                for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                    log::debug!("{}: {}", label, line);
+                    if let Ok(mut tail) = tail.lock() {
+                        tail.push_str(&line);
+                        tail.push('\n');
+                        if tail.len() > MAX_STDERR_LEN {
+                            let mut cut = tail.len() - MAX_STDERR_LEN;
+                            while !tail.is_char_boundary(cut) {
+                                cut += 1;
+                            }
+                            tail.drain(..cut);
+                        }
+                    }
+                }
+            });
+        }
+
+        let stdout = child
+            .stdout
+            .take()
+            .expect("stdout is piped for the remote capture");
+        let process = Arc::new(Mutex::new(child));
+        match PcapStream::open(BufReader::new(stdout)) {
+            Ok(stream) => Ok(Self {
+                stream,
+                process,
+                stderr,
+                label,
+            }),
+            Err(e) => Err(stream_error(&label, &process, &stderr, &e)),
+        }
+    }
+
+    /// Stop the capture process, ending the stream.
+    pub fn kill(process: &Mutex<Child>) {
+        if let Ok(mut child) = process.lock() {
+            let _ = child.kill();
+        }
+    }
+
+    /// The error to report when the stream breaks (see [`stream_error`]).
+    pub fn error(&self, err: &io::Error) -> CaptureError {
+        stream_error(&self.label, &self.process, &self.stderr, err)
+    }
+}
+
+/// Stop the capture process and describe why its stream ended: the tool's
+/// own stderr (an SSH or permission failure, usually) when it wrote any,
+/// else `err`.
+fn stream_error(
+    label: &str,
+    process: &Mutex<Child>,
+    stderr: &Mutex<String>,
+    err: &io::Error,
+) -> CaptureError {
+    let status = process.lock().ok().and_then(|mut child| {
+        let _ = child.kill();
+        child.wait().ok()
+    });
+    // Give the stderr thread a moment to collect the last lines
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    let stderr = stderr
+        .lock()
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    let detail = if stderr.is_empty() {
+        err.to_string()
+    } else {
+        stderr
+    };
+    match status {
+        Some(status) => CaptureError::Remote(format!("{}: {} ({})", label, detail, status)),
+        None => CaptureError::Remote(format!("{}: {}", label, detail)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &Command) -> Vec<String> {
+        command
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_urls() {
+        assert_eq!(
+            RemoteSource::parse("ssh://ops@sensor1:2222/eth1").unwrap(),
+            RemoteSource::Ssh {
+                destination: "ops@sensor1".to_string(),
+                port: Some(2222),
+                interface: "eth1".to_string(),
+            }
+        );
+        assert_eq!(
+            RemoteSource::parse("rpcap://[fd00::5]/enp3s0").unwrap(),
+            RemoteSource::Rpcap {
+                host: "fd00::5".to_string(),
+                port: None,
+                interface: "enp3s0".to_string(),
+            }
+        );
+        for bad in [
+            "sensor1/eth1",
+            "ssh://sensor1",
+            "ssh://sensor1/",
+            "ssh://sensor1:ssh/eth1",
+            "rpcap://ops@sensor1/eth1",
+            "ftp://sensor1/eth1",
+        ] {
+            assert!(RemoteSource::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_commands() {
+        let ssh = RemoteSource::parse("ssh://ops@sensor1:2222/eth1").unwrap();
+        let command = ssh.command(Some("port 502 and host '10.0.0.1'"), 65535, false);
+        assert_eq!(command.get_program(), "ssh");
+        let argv = args(&command);
+        assert_eq!(
+            &argv[argv.len() - 4..argv.len() - 1],
+            ["-p", "2222", "ops@sensor1"]
+        );
+        assert_eq!(
+            argv.last().unwrap(),
+            r"tcpdump -U -n -s 65535 -w - -i 'eth1' -p 'port 502 and host '\''10.0.0.1'\'''"
+        );
+
+        let rpcap = RemoteSource::parse("rpcap://sensor1/eth1").unwrap();
+        let command = rpcap.command(None, 1500, true);
+        assert_eq!(command.get_program(), "dumpcap");
+        assert_eq!(
+            args(&command),
+            [
+                "-i",
+                "rpcap://sensor1:2002/eth1",
+                "-w",
+                "-",
+                "-P",
+                "-q",
+                "-s",
+                "1500"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pcap_stream() {
+        // Big-endian, nanosecond-resolution stream, as some tools write
+        let mut bytes = vec![0xA1, 0xB2, 0x3C, 0x4D, 0x00, 0x02, 0x00, 0x04];
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&65535u32.to_be_bytes());
+        bytes.extend_from_slice(&113u32.to_be_bytes()); // Linux cooked
+        bytes.extend_from_slice(&1_700_000_000u32.to_be_bytes());
+        bytes.extend_from_slice(&123_456_789u32.to_be_bytes());
+        bytes.extend_from_slice(&3u32.to_be_bytes());
+        bytes.extend_from_slice(&60u32.to_be_bytes());
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let mut stream = PcapStream::open(&bytes[..]).unwrap();
+        assert_eq!(stream.linktype(), 113);
+        assert_eq!(
+            stream.next_record().unwrap(),
+            Some(PcapRecord {
+                ts_sec: 1_700_000_000,
+                ts_usec: 123_456,
+                len: 60,
+                data: vec![1, 2, 3],
+            })
+        );
+        assert_eq!(stream.next_record().unwrap(), None);
+
+        // A truncated record is an error, and PCAPNG is refused
+        let mut stream = PcapStream::open(&bytes[..bytes.len() - 1]).unwrap();
+        assert!(stream.next_record().is_err());
+        assert!(PcapStream::open(&[0x0A, 0x0D, 0x0D, 0x0A, 0, 0, 0, 0][..]).is_err());
+    }
+}
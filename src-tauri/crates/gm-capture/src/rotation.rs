@@ -0,0 +1,230 @@
+//! Rotating capture-to-disk for live sessions.
+//!
+//! The in-memory ring buffer only keeps the most recent packets for "Stop &
+//! Save". With rotation enabled, every captured frame is also written to a
+//! series of PCAP files, starting a new file when the current one reaches a
+//! size or time limit (like `tcpdump -C` / `-G`) and deleting the oldest
+//! once a file count is exceeded (`-W`), so analysts can go back to full
+//! payloads after a long session.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use chrono::DateTime;
+
+use crate::error::CaptureError;
+use crate::parsing::timestamp_from_pcap;
+
+/// Per-file and per-record header sizes of the classic PCAP format.
+const FILE_HEADER_LEN: u64 = 24;
+const RECORD_HEADER_LEN: u64 = 16;
+
+/// Where and how to write rotating capture files.
+#[derive(Debug, Clone)]
+pub struct RotationConfig {
+    /// Directory the files are written to (created if missing)
+    pub directory: PathBuf,
+    /// File name prefix; files are named `<prefix>-<YYYYmmdd-HHMMSS>-<n>.pcap`
+    /// after the timestamp of their first packet
+    pub file_prefix: String,
+    /// Start a new file before one would exceed this many bytes (0 = no limit)
+    pub max_file_bytes: u64,
+    /// Start a new file once one spans this many seconds of traffic (0 = no limit)
+    pub max_file_seconds: u64,
+    /// Keep at most this many files, deleting the oldest (0 = keep all)
+    pub max_files: usize,
+}
+
+impl RotationConfig {
+    /// Rotate every 100 MB into `directory`, keeping every file.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            file_prefix: "capture".to_string(),
+            max_file_bytes: 100 * 1024 * 1024,
+            max_file_seconds: 0,
+            max_files: 0,
+        }
+    }
+}
+
+struct OpenFile {
+    savefile: pcap::Savefile,
+    bytes: u64,
+    first_second: i64,
+}
+
+/// Writes raw frames to rotating PCAP files. The list of files currently on
+/// disk, oldest first, is shared through `files`.
+pub(crate) struct RotatingWriter {
+    config: RotationConfig,
+    dead: pcap::Capture<pcap::Dead>,
+    current: Option<OpenFile>,
+    sequence: u32,
+    files: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl RotatingWriter {
+    pub fn create(
+        config: RotationConfig,
+        linktype: pcap::Linktype,
+        files: Arc<Mutex<Vec<PathBuf>>>,
+    ) -> Result<Self, CaptureError> {
+        std::fs::create_dir_all(&config.directory).map_err(|e| {
+            CaptureError::Capture(format!(
+                "Cannot create capture directory '{}': {}",
+                config.directory.display(),
+                e
+            ))
+        })?;
+        let dead = pcap::Capture::dead(linktype)
+            .map_err(|e| CaptureError::Capture(format!("Cannot create dead capture: {}", e)))?;
+        Ok(Self {
+            config,
+            dead,
+            current: None,
+            sequence: 0,
+            files,
+        })
+    }
+
+    /// Append one frame, rotating to a new file first if a limit is reached.
+    pub fn write(&mut self, header: &pcap::PacketHeader, data: &[u8]) -> Result<(), CaptureError> {
+        let second = timestamp_from_pcap(*header, pcap::Precision::Micro).timestamp();
+        let record_len = RECORD_HEADER_LEN + data.len() as u64;
+
+        let current = match self.current.take() {
+            Some(current) if !self.should_rotate(&current, second, record_len) => current,
+            previous => {
+                // Dropping the savefile flushes and closes it
+                drop(previous);
+                self.open(second)?
+            }
+        };
+        let current = self.current.insert(current);
+
+        current.savefile.write(&pcap::Packet::new(header, data));
+        current.bytes += record_len;
+        Ok(())
+    }
+
+    fn should_rotate(&self, current: &OpenFile, second: i64, record_len: u64) -> bool {
+        let limits = &self.config;
+        // A frame larger than the size limit still gets a file of its own
+        let too_big = limits.max_file_bytes > 0
+            && current.bytes > FILE_HEADER_LEN
+            && current.bytes + record_len > limits.max_file_bytes;
+        let too_old = limits.max_file_seconds > 0
+            && second - current.first_second >= limits.max_file_seconds as i64;
+        too_big || too_old
+    }
+
+    fn open(&mut self, second: i64) -> Result<OpenFile, CaptureError> {
+        self.sequence += 1;
+        let stamp = DateTime::from_timestamp(second, 0)
+            .map(|t| t.format("%Y%m%d-%H%M%S").to_string())
+            .unwrap_or_default();
+        let path = self.config.directory.join(format!(
+            "{}-{}-{}.pcap",
+            self.config.file_prefix, stamp, self.sequence
+        ));
+
+        let savefile = self.dead.savefile(&path).map_err(|e| {
+            CaptureError::Capture(format!(
+                "Failed to create capture file '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        log::info!("Writing capture to {}", path.display());
+
+        if let Ok(mut files) = self.files.lock() {
+            files.push(path);
+            if self.config.max_files > 0 && files.len() > self.config.max_files {
+                let excess = files.len() - self.config.max_files;
+                for old in files.drain(..excess) {
+                    if let Err(e) = std::fs::remove_file(&old) {
+                        log::warn!("Cannot remove old capture file {}: {}", old.display(), e);
+                    }
+                }
+            }
+        }
+
+        Ok(OpenFile {
+            savefile,
+            bytes: FILE_HEADER_LEN,
+            first_second: second,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(second: i64, len: u32) -> pcap::PacketHeader {
+        pcap::PacketHeader {
+            ts: libc::timeval {
+                tv_sec: second as _,
+                tv_usec: 0,
+            },
+            caplen: len,
+            len,
+        }
+    }
+
+    fn writer(dir: &std::path::Path, config: impl FnOnce(&mut RotationConfig)) -> RotatingWriter {
+        let mut rotation = RotationConfig::new(dir);
+        config(&mut rotation);
+        RotatingWriter::create(rotation, pcap::Linktype::ETHERNET, Default::default()).unwrap()
+    }
+
+    fn file_names(writer: &RotatingWriter) -> Vec<String> {
+        writer
+            .files
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_prunes_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let frame = [0u8; 100];
+        let mut writer = writer(dir.path(), |c| {
+            c.file_prefix = "ot".to_string();
+            c.max_file_bytes = FILE_HEADER_LEN + 2 * (RECORD_HEADER_LEN + 100);
+            c.max_files = 2;
+        });
+        for i in 0..5 {
+            writer
+                .write(&header(1_700_000_000 + i, 100), &frame)
+                .unwrap();
+        }
+
+        // Two frames per file: files 1 and 2 are full, file 3 holds the last;
+        // file 1 was deleted when file 3 was opened
+        assert_eq!(
+            file_names(&writer),
+            vec!["ot-20231114-221322-2.pcap", "ot-20231114-221324-3.pcap"]
+        );
+        assert!(!dir.path().join("ot-20231114-221320-1.pcap").exists());
+    }
+
+    #[test]
+    fn test_rotates_by_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = writer(dir.path(), |c| {
+            c.max_file_bytes = 0;
+            c.max_file_seconds = 60;
+        });
+        for second in [0, 30, 59, 60, 200] {
+            writer
+                .write(&header(1_700_000_000 + second, 60), &[0; 60])
+                .unwrap();
+        }
+        assert_eq!(file_names(&writer).len(), 3);
+    }
+}
@@ -0,0 +1,182 @@
+//! Import sampling: take only part of a capture file.
+//!
+//! A multi-gigabyte capture can take longer to import than the question it
+//! is meant to answer. [`ImportSampling`] narrows an import to a time
+//! window, to every Nth packet, and/or to the first packets of each flow
+//! (enough to identify the protocols and the polling pattern) so a capture
+//! can be triaged first and imported in full later if needed.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::packet::{ParsedPacket, TransportProtocol};
+
+/// Which packets of a capture to import. The default imports everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ImportSampling {
+    /// Skip packets captured before this time
+    pub start: Option<DateTime<Utc>>,
+    /// Skip packets captured after this time
+    pub end: Option<DateTime<Utc>>,
+    /// Keep one packet in this many, counted after the time window
+    /// (0 or 1 = every packet)
+    pub every_nth: u64,
+    /// Keep at most this many packets of each flow, both directions
+    /// together (0 = no limit)
+    pub max_packets_per_flow: u64,
+}
+
+impl ImportSampling {
+    /// Whether every packet is imported.
+    pub fn is_full(&self) -> bool {
+        self.start.is_none()
+            && self.end.is_none()
+            && self.every_nth <= 1
+            && self.max_packets_per_flow == 0
+    }
+}
+
+/// Transport and both endpoints, lower endpoint first, so both directions
+/// of a conversation count towards the same flow.
+type FlowKey = (TransportProtocol, (String, u16), (String, u16));
+
+/// Applies an [`ImportSampling`] to one capture file.
+#[derive(Debug)]
+pub(crate) struct Sampler {
+    sampling: ImportSampling,
+    /// Packets still to drop before the next every-Nth packet is kept
+    until_next: u64,
+    flow_packets: HashMap<FlowKey, u64>,
+}
+
+impl Sampler {
+    pub fn new(sampling: ImportSampling) -> Self {
+        Self {
+            sampling,
+            until_next: 0,
+            flow_packets: HashMap::new(),
+        }
+    }
+
+    /// Whether a frame captured at `timestamp` falls inside the time
+    /// window. Checked before parsing, so frames outside it cost nothing.
+    pub fn in_window(&self, timestamp: DateTime<Utc>) -> bool {
+        !matches!(self.sampling.start, Some(start) if timestamp < start)
+            && !matches!(self.sampling.end, Some(end) if timestamp > end)
+    }
+
+    /// Whether a parsed packet from inside the window is kept.
+    pub fn keep(&mut self, packet: &ParsedPacket) -> bool {
+        if self.until_next > 0 {
+            self.until_next -= 1;
+            return false;
+        }
+        self.until_next = self.sampling.every_nth.saturating_sub(1);
+
+        let limit = self.sampling.max_packets_per_flow;
+        if limit > 0 {
+            let count = self.flow_packets.entry(flow_key(packet)).or_default();
+            if *count >= limit {
+                return false;
+            }
+            *count += 1;
+        }
+        true
+    }
+}
+
+fn flow_key(packet: &ParsedPacket) -> FlowKey {
+    let src = (packet.src_ip.clone(), packet.src_port);
+    let dst = (packet.dst_ip.clone(), packet.dst_port);
+    if src <= dst {
+        (packet.transport, src, dst)
+    } else {
+        (packet.transport, dst, src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(src: &str, src_port: u16, dst: &str, dst_port: u16) -> ParsedPacket {
+        ParsedPacket {
+            timestamp: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            src_mac: None,
+            dst_mac: None,
+            vlan_id: None,
+            outer_vlan_id: None,
+            mpls_labels: Vec::new(),
+            vxlan_vni: None,
+            src_ip: src.to_string(),
+            dst_ip: dst.to_string(),
+            transport: TransportProtocol::Tcp,
+            src_port,
+            dst_port,
+            tcp_flags: 0,
+            tcp_seq: 0,
+            length: 60,
+            payload: Vec::new(),
+            origin_file: "test.pcap".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_time_window() {
+        let at = |second: i64| DateTime::from_timestamp(1_700_000_000 + second, 0).unwrap();
+        let sampler = Sampler::new(ImportSampling {
+            start: Some(at(10)),
+            end: Some(at(20)),
+            ..Default::default()
+        });
+        assert!(!sampler.in_window(at(9)));
+        assert!(sampler.in_window(at(10)));
+        assert!(sampler.in_window(at(20)));
+        assert!(!sampler.in_window(at(21)));
+        assert!(Sampler::new(ImportSampling::default()).in_window(at(0)));
+    }
+
+    #[test]
+    fn test_every_nth() {
+        let mut sampler = Sampler::new(ImportSampling {
+            every_nth: 3,
+            ..Default::default()
+        });
+        let p = packet("10.0.0.1", 49152, "10.0.0.2", 502);
+        let kept: Vec<bool> = (0..7).map(|_| sampler.keep(&p)).collect();
+        assert_eq!(kept, [true, false, false, true, false, false, true]);
+    }
+
+    #[test]
+    fn test_max_packets_per_flow_counts_both_directions() {
+        let mut sampler = Sampler::new(ImportSampling {
+            max_packets_per_flow: 2,
+            ..Default::default()
+        });
+        let request = packet("10.0.0.1", 49152, "10.0.0.2", 502);
+        let response = packet("10.0.0.2", 502, "10.0.0.1", 49152);
+        let other = packet("10.0.0.3", 49152, "10.0.0.2", 502);
+        assert!(sampler.keep(&request));
+        assert!(sampler.keep(&response));
+        assert!(!sampler.keep(&request));
+        assert!(sampler.keep(&other));
+    }
+
+    #[test]
+    fn test_is_full() {
+        assert!(ImportSampling::default().is_full());
+        let nth_one = ImportSampling {
+            every_nth: 1,
+            ..Default::default()
+        };
+        assert!(nth_one.is_full());
+        let per_flow = ImportSampling {
+            max_packets_per_flow: 10,
+            ..Default::default()
+        };
+        assert!(!per_flow.is_full());
+    }
+}
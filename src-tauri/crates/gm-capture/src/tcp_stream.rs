@@ -0,0 +1,351 @@
+//! TCP stream reassembly for OT ports.
+//!
+//! SPAN ports and taps deliver TCP segments as they were seen on the wire:
+//! retransmitted, duplicated, and out of order. The PDU reassembler in
+//! gm-parsers appends payloads in arrival order, so a retransmitted Modbus
+//! request is parsed twice and a reordered S7 upload is parsed as garbage.
+//! [`TcpReassembler`] sits between the packet parser and the consumer and
+//! rewrites each segment's payload to the bytes it adds to its direction's
+//! contiguous stream:
+//!
+//! - in-order data passes through, minus any prefix already delivered
+//!   (retransmissions and overlaps);
+//! - data beyond a hole is held, and the segment goes on with an empty
+//!   payload;
+//! - the segment that fills a hole carries the held data after its own.
+//!
+//! Every packet is still handed on, so connection counts and byte totals
+//! are unchanged. Holes that never fill (capture drops) are skipped after
+//! [`HOLE_TIMEOUT_SECS`] or once too much is held behind them. Only
+//! connections with an endpoint on one of the configured ports are tracked.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use crate::packet::{tcp_flags, ParsedPacket, TransportProtocol};
+
+/// A hole still open after this long (capture time) is treated as lost data.
+pub(crate) const HOLE_TIMEOUT_SECS: i64 = 5;
+
+/// Directions idle for this long are forgotten.
+const STREAM_TIMEOUT_SECS: i64 = 300;
+
+/// Connection directions tracked at once; segments of further connections
+/// pass through unchanged.
+const MAX_STREAMS: usize = 16_384;
+
+/// Most bytes held behind a hole in one direction. Data further ahead than
+/// this is taken as a sign the stream was lost, and tracking restarts there.
+const MAX_HELD_BYTES: usize = 1024 * 1024;
+
+/// Most segments held behind a hole in one direction.
+const MAX_HELD_SEGMENTS: usize = 64;
+
+/// One direction of a TCP connection.
+type StreamKey = (String, u16, String, u16);
+
+#[derive(Debug)]
+struct HeldSegment {
+    seq: u32,
+    data: Vec<u8>,
+    /// Capture time (seconds) the segment arrived
+    since: i64,
+}
+
+/// Reassembly state of one connection direction.
+#[derive(Debug)]
+struct Stream {
+    /// Sequence number of the next byte to deliver
+    next_seq: u32,
+    /// Segments beyond the next expected byte, in arrival order
+    held: Vec<HeldSegment>,
+    held_bytes: usize,
+    last_seen: i64,
+}
+
+impl Stream {
+    fn new(next_seq: u32, now: i64) -> Self {
+        Self {
+            next_seq,
+            held: Vec::new(),
+            held_bytes: 0,
+            last_seen: now,
+        }
+    }
+
+    /// Take a segment's data and return the bytes it makes contiguous.
+    fn accept(&mut self, seq: u32, data: Vec<u8>, now: i64) -> Vec<u8> {
+        let ahead = seq.wrapping_sub(self.next_seq) as i32;
+        let mut delivered = if data.is_empty() {
+            data
+        } else if ahead > MAX_HELD_BYTES as i32 {
+            // Far beyond anything we were waiting for: the stream was lost
+            // (or picked up mid-flight on a retransmission); start again here
+            self.held.clear();
+            self.held_bytes = 0;
+            self.next_seq = seq;
+            self.advance(data)
+        } else if ahead > 0 {
+            self.hold(seq, data, now);
+            Vec::new()
+        } else {
+            // Drop the part of the segment that was already delivered
+            let fresh = skip(data, ahead.unsigned_abs() as usize);
+            self.advance(fresh)
+        };
+
+        self.release_held(&mut delivered);
+        if self.hole_is_stale(now) {
+            // Give up on the missing bytes and carry on from the next held
+            // segment; the PDU reassembler resynchronises on its framing
+            if let Some(seq) = self.earliest_held() {
+                self.next_seq = seq;
+                self.release_held(&mut delivered);
+            }
+        }
+        delivered
+    }
+
+    /// Move past contiguous data, returning it.
+    fn advance(&mut self, data: Vec<u8>) -> Vec<u8> {
+        self.next_seq = self.next_seq.wrapping_add(data.len() as u32);
+        data
+    }
+
+    fn hold(&mut self, seq: u32, data: Vec<u8>, now: i64) {
+        // A retransmission of a segment already held
+        if let Some(held) = self.held.iter_mut().find(|h| h.seq == seq) {
+            if data.len() > held.data.len() {
+                self.held_bytes += data.len() - held.data.len();
+                held.data = data;
+            }
+            return;
+        }
+        self.held_bytes += data.len();
+        self.held.push(HeldSegment {
+            seq,
+            data,
+            since: now,
+        });
+    }
+
+    /// Append every held segment that is now contiguous to `delivered`.
+    fn release_held(&mut self, delivered: &mut Vec<u8>) {
+        while let Some(index) = self
+            .held
+            .iter()
+            .position(|h| h.seq.wrapping_sub(self.next_seq) as i32 <= 0)
+        {
+            let held = self.held.swap_remove(index);
+            self.held_bytes -= held.data.len();
+            let overlap = self.next_seq.wrapping_sub(held.seq) as usize;
+            let fresh = skip(held.data, overlap);
+            delivered.extend_from_slice(&self.advance(fresh));
+        }
+    }
+
+    fn hole_is_stale(&self, now: i64) -> bool {
+        !self.held.is_empty()
+            && (self.held_bytes > MAX_HELD_BYTES
+                || self.held.len() > MAX_HELD_SEGMENTS
+                || self.held.iter().any(|h| now - h.since > HOLE_TIMEOUT_SECS))
+    }
+
+    /// Sequence number of the held segment closest after the hole.
+    fn earliest_held(&self) -> Option<u32> {
+        self.held
+            .iter()
+            .map(|h| h.seq)
+            .min_by_key(|seq| seq.wrapping_sub(self.next_seq))
+    }
+}
+
+/// `data` without its first `count` bytes.
+fn skip(mut data: Vec<u8>, count: usize) -> Vec<u8> {
+    if count >= data.len() {
+        data.clear();
+    } else {
+        data.drain(..count);
+    }
+    data
+}
+
+/// Per-direction TCP reassembly for connections on a set of ports.
+#[derive(Debug, Default)]
+pub(crate) struct TcpReassembler {
+    ports: HashSet<u16>,
+    streams: HashMap<StreamKey, Stream>,
+    last_sweep: i64,
+}
+
+impl TcpReassembler {
+    /// Reassemble connections with either endpoint on one of `ports`.
+    pub fn new(ports: impl IntoIterator<Item = u16>) -> Self {
+        Self {
+            ports: ports.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Replace the packet's payload with the new contiguous bytes of its
+    /// direction's stream. Packets of other connections are left as they are.
+    pub fn process(&mut self, packet: &mut ParsedPacket) {
+        if packet.transport != TransportProtocol::Tcp
+            || !(self.ports.contains(&packet.src_port) || self.ports.contains(&packet.dst_port))
+        {
+            return;
+        }
+        let now = packet.timestamp.timestamp();
+        self.expire(now);
+
+        let key = (
+            packet.src_ip.clone(),
+            packet.src_port,
+            packet.dst_ip.clone(),
+            packet.dst_port,
+        );
+        let flags = packet.tcp_flags;
+        if flags & tcp_flags::RST != 0 {
+            self.streams.remove(&key);
+            return;
+        }
+
+        // The SYN occupies one sequence number; data starts after it
+        let syn = flags & tcp_flags::SYN != 0;
+        let seq = if syn {
+            packet.tcp_seq.wrapping_add(1)
+        } else {
+            packet.tcp_seq
+        };
+        let full = self.streams.len() >= MAX_STREAMS;
+        let stream = match self.streams.entry(key.clone()) {
+            Entry::Occupied(entry) => {
+                let stream = entry.into_mut();
+                if syn {
+                    *stream = Stream::new(seq, now);
+                }
+                stream
+            }
+            Entry::Vacant(_) if full => return,
+            Entry::Vacant(entry) => entry.insert(Stream::new(seq, now)),
+        };
+        stream.last_seen = now;
+
+        let payload = std::mem::take(&mut packet.payload);
+        packet.payload = stream.accept(seq, payload, now);
+
+        if flags & tcp_flags::FIN != 0 && stream.held.is_empty() {
+            self.streams.remove(&key);
+        }
+    }
+
+    /// Forget idle directions, at most once per second of capture time.
+    fn expire(&mut self, now: i64) {
+        if now == self.last_sweep {
+            return;
+        }
+        self.last_sweep = now;
+        self.streams
+            .retain(|_, s| now - s.last_seen <= STREAM_TIMEOUT_SECS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    const ISN: u32 = 0xFFFF_FFF0; // wraps within the first few segments
+
+    fn segment(seq: u32, flags: u8, payload: &[u8], second: i64) -> ParsedPacket {
+        ParsedPacket {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + second, 0).unwrap(),
+            src_mac: None,
+            dst_mac: None,
+            vlan_id: None,
+            outer_vlan_id: None,
+            mpls_labels: Vec::new(),
+            vxlan_vni: None,
+            src_ip: "10.0.0.10".to_string(),
+            dst_ip: "10.0.0.1".to_string(),
+            transport: TransportProtocol::Tcp,
+            src_port: 49152,
+            dst_port: 502,
+            tcp_flags: flags,
+            tcp_seq: seq,
+            length: 54 + payload.len(),
+            payload: payload.to_vec(),
+            origin_file: "test.pcap".to_string(),
+        }
+    }
+
+    fn feed(reassembler: &mut TcpReassembler, mut packet: ParsedPacket) -> Vec<u8> {
+        reassembler.process(&mut packet);
+        packet.payload
+    }
+
+    #[test]
+    fn test_reorders_and_dedups() {
+        let data: Vec<u8> = (0..30).collect();
+        let mut r = TcpReassembler::new([502]);
+        let start = ISN.wrapping_add(1);
+        let at = |offset: usize| start.wrapping_add(offset as u32);
+
+        assert!(feed(&mut r, segment(ISN, tcp_flags::SYN, &[], 0)).is_empty());
+        assert_eq!(
+            feed(&mut r, segment(at(0), tcp_flags::ACK, &data[..10], 0)),
+            data[..10]
+        );
+        // Third segment before the second: held
+        assert!(feed(&mut r, segment(at(20), tcp_flags::ACK, &data[20..], 0)).is_empty());
+        // The second fills the hole and carries the third
+        assert_eq!(
+            feed(&mut r, segment(at(10), tcp_flags::ACK, &data[10..20], 0)),
+            data[10..]
+        );
+        // A retransmission adds nothing; an overlapping one only its new bytes
+        assert!(feed(&mut r, segment(at(0), tcp_flags::ACK, &data[..10], 1)).is_empty());
+        let more = [30, 31];
+        let mut overlap = data[25..].to_vec();
+        overlap.extend_from_slice(&more);
+        assert_eq!(
+            feed(&mut r, segment(at(25), tcp_flags::ACK, &overlap, 1)),
+            more
+        );
+    }
+
+    #[test]
+    fn test_skips_hole_that_never_fills() {
+        let mut r = TcpReassembler::new([502]);
+        assert_eq!(
+            feed(&mut r, segment(1000, tcp_flags::ACK, b"abc", 0)),
+            b"abc"
+        );
+        // Bytes 1003..1006 were lost by the capture
+        assert!(feed(&mut r, segment(1006, tcp_flags::ACK, b"ghi", 0)).is_empty());
+        assert!(feed(&mut r, segment(1009, tcp_flags::ACK, b"jkl", 1)).is_empty());
+        assert_eq!(
+            feed(
+                &mut r,
+                segment(1012, tcp_flags::ACK, b"mno", HOLE_TIMEOUT_SECS + 1)
+            ),
+            b"ghijklmno"
+        );
+    }
+
+    #[test]
+    fn test_other_ports_and_fin() {
+        let mut r = TcpReassembler::new([20000]);
+        // Not an opted-in port: passed through even when retransmitted
+        let web = segment(1, tcp_flags::ACK, b"GET", 0);
+        assert_eq!(feed(&mut r, web.clone()), b"GET");
+        assert_eq!(feed(&mut r, web), b"GET");
+        assert!(r.streams.is_empty());
+
+        let mut r = TcpReassembler::new([502]);
+        feed(&mut r, segment(1, tcp_flags::ACK, b"abc", 0));
+        assert_eq!(r.streams.len(), 1);
+        feed(&mut r, segment(4, tcp_flags::FIN | tcp_flags::ACK, b"d", 0));
+        assert!(r.streams.is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+//! 802.1Q and 802.1ad (QinQ) VLAN tag extraction.
+//!
+//! Works on the raw Ethernet frame so the etherparse path and the Layer-2
+//! extractors (LLDP, GOOSE, PROFINET, ...) report VLANs the same way.
+
+/// 802.1Q customer tag
+const TPID_8021Q: u16 = 0x8100;
+/// 802.1ad service tag
+const TPID_8021AD: u16 = 0x88A8;
+/// Pre-standard QinQ service tag still used by some switches
+const TPID_QINQ_LEGACY: u16 = 0x9100;
+
+/// VLAN tags found on a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct VlanTags {
+    /// VLAN ID of the innermost tag — the access VLAN the sender sits on
+    pub vlan_id: Option<u16>,
+    /// VLAN ID of the outer (service) tag of a double-tagged frame
+    pub outer_vlan_id: Option<u16>,
+    /// Offset of the Ethertype (or 802.3 length) following the tags
+    pub ethertype_offset: usize,
+}
+
+/// Read the VLAN tags of an Ethernet frame.
+///
+/// Priority-tagged frames (VLAN ID 0) carry no VLAN and report None, but
+/// their tag is still skipped. With more than two tags the outermost and
+/// innermost are kept.
+pub(crate) fn parse(frame: &[u8]) -> VlanTags {
+    let mut tags = VlanTags {
+        vlan_id: None,
+        outer_vlan_id: None,
+        ethertype_offset: 12,
+    };
+    let mut ids: Vec<Option<u16>> = Vec::new();
+    while frame.len() >= tags.ethertype_offset + 6 {
+        let offset = tags.ethertype_offset;
+        let tpid = u16::from_be_bytes([frame[offset], frame[offset + 1]]);
+        if !matches!(tpid, TPID_8021Q | TPID_8021AD | TPID_QINQ_LEGACY) {
+            break;
+        }
+        let vid = u16::from_be_bytes([frame[offset + 2], frame[offset + 3]]) & 0x0FFF;
+        ids.push((vid != 0 && vid != 0x0FFF).then_some(vid));
+        tags.ethertype_offset += 4;
+    }
+
+    match ids.as_slice() {
+        [] => {}
+        [only] => tags.vlan_id = *only,
+        [outer, .., inner] => {
+            tags.outer_vlan_id = *outer;
+            tags.vlan_id = *inner;
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACS: [u8; 12] = [
+        0x01, 0x0C, 0xCD, 0x01, 0x00, 0x01, 0x00, 0x0C, 0xCD, 0x00, 0x00, 0x10,
+    ];
+
+    #[test]
+    fn test_untagged_and_single_tag() {
+        let mut frame = MACS.to_vec();
+        frame.extend_from_slice(&[0x08, 0x00, 0x45, 0x00]);
+        assert_eq!(
+            parse(&frame),
+            VlanTags {
+                vlan_id: None,
+                outer_vlan_id: None,
+                ethertype_offset: 12
+            }
+        );
+
+        // 802.1Q, priority 5, VLAN 110
+        let mut tagged = MACS.to_vec();
+        tagged.extend_from_slice(&[0x81, 0x00, 0xA0, 0x6E, 0x08, 0x00, 0x45, 0x00]);
+        let tags = parse(&tagged);
+        assert_eq!(tags.vlan_id, Some(110));
+        assert_eq!(tags.outer_vlan_id, None);
+        assert_eq!(tags.ethertype_offset, 16);
+    }
+
+    #[test]
+    fn test_priority_tag_has_no_vlan() {
+        // GOOSE behind a priority-only tag (VLAN 0)
+        let mut frame = MACS.to_vec();
+        frame.extend_from_slice(&[0x81, 0x00, 0x80, 0x00, 0x88, 0xB8, 0x00, 0x01]);
+        let tags = parse(&frame);
+        assert_eq!(tags.vlan_id, None);
+        assert_eq!(tags.ethertype_offset, 16);
+    }
+
+    #[test]
+    fn test_qinq() {
+        // 802.1ad S-tag 200 → 802.1Q C-tag 30 → IPv4
+        let mut frame = MACS.to_vec();
+        frame.extend_from_slice(&[0x88, 0xA8, 0x00, 0xC8, 0x81, 0x00, 0x00, 0x1E]);
+        frame.extend_from_slice(&[0x08, 0x00, 0x45, 0x00]);
+        let tags = parse(&frame);
+        assert_eq!(tags.vlan_id, Some(30));
+        assert_eq!(tags.outer_vlan_id, Some(200));
+        assert_eq!(tags.ethertype_offset, 20);
+
+        // Legacy 0x9100 outer tag
+        frame[12] = 0x91;
+        frame[13] = 0x00;
+        assert_eq!(parse(&frame).outer_vlan_id, Some(200));
+
+        // A tag cut off by the snap length is not read
+        assert_eq!(parse(&frame[..17]).vlan_id, None);
+    }
+}
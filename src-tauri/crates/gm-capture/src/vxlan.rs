@@ -0,0 +1,47 @@
+//! VXLAN decapsulation (RFC 7348).
+//!
+//! Virtualized control systems and hosted historians often sit on VXLAN
+//! overlays, so a capture on the underlay sees VTEP-to-VTEP UDP. The
+//! encapsulated Ethernet frame is what the rest of the pipeline analyzes.
+
+/// IANA-assigned VXLAN UDP port.
+pub(crate) const VXLAN_PORT: u16 = 4789;
+
+const HEADER_LEN: usize = 8;
+/// "I" flag: the VNI field is valid
+const FLAG_VNI_VALID: u8 = 0x08;
+
+/// Split a VXLAN UDP payload into its VNI and the inner Ethernet frame.
+///
+/// Returns None if the payload is too short to hold a header and an
+/// Ethernet header, or the VNI-valid flag is not set.
+pub(crate) fn decapsulate(udp_payload: &[u8]) -> Option<(u32, &[u8])> {
+    if udp_payload.len() < HEADER_LEN + 14 || udp_payload[0] & FLAG_VNI_VALID == 0 {
+        return None;
+    }
+    let vni = u32::from_be_bytes([0, udp_payload[4], udp_payload[5], udp_payload[6]]);
+    Some((vni, &udp_payload[HEADER_LEN..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decapsulate() {
+        let mut payload = vec![0x08, 0x00, 0x00, 0x00, 0x00, 0x13, 0x88, 0x00]; // VNI 5000
+        let inner = [
+            0x00, 0x50, 0x56, 0x01, 0x02, 0x03, 0x00, 0x50, 0x56, 0x0A, 0x0B, 0x0C, 0x08, 0x00,
+        ];
+        payload.extend_from_slice(&inner);
+
+        let (vni, frame) = decapsulate(&payload).unwrap();
+        assert_eq!(vni, 5000);
+        assert_eq!(frame, inner);
+
+        // VNI flag clear, and a header with no room for a frame
+        payload[0] = 0x00;
+        assert!(decapsulate(&payload).is_none());
+        assert!(decapsulate(&[0x08, 0, 0, 0, 0, 0x13, 0x88, 0]).is_none());
+    }
+}
@@ -0,0 +1,152 @@
+//! Write-path approval persistence.
+//!
+//! An approval records that a master is expected to write to a given
+//! slave/outstation, with the analyst's justification and an optional
+//! expiry. Approvals are global rather than per-session so they carry over
+//! to later captures of the same site.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::error::DbError;
+
+/// Input fields for approving a write path.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WriteApprovalInput {
+    pub master_ip: String,
+    pub slave_ip: String,
+    pub justification: String,
+    pub approved_by: String,
+    /// RFC 3339 timestamp or YYYY-MM-DD date; None = never expires
+    pub expires_at: Option<String>,
+}
+
+/// A stored write-path approval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteApprovalRow {
+    pub id: i64,
+    pub master_ip: String,
+    pub slave_ip: String,
+    pub justification: String,
+    pub approved_by: String,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+}
+
+/// Approve a write path, replacing any earlier approval of the same pair.
+pub fn upsert_write_approval(
+    conn: &Connection,
+    input: &WriteApprovalInput,
+) -> Result<WriteApprovalRow, DbError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO write_approvals (master_ip, slave_ip, justification, approved_by,
+             created_at, expires_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT (master_ip, slave_ip) DO UPDATE SET
+             justification = excluded.justification,
+             approved_by = excluded.approved_by,
+             created_at = excluded.created_at,
+             expires_at = excluded.expires_at",
+        params![
+            input.master_ip,
+            input.slave_ip,
+            input.justification,
+            input.approved_by,
+            now,
+            input.expires_at
+        ],
+    )?;
+    conn.query_row(
+        "SELECT id, master_ip, slave_ip, justification, approved_by, created_at, expires_at
+         FROM write_approvals WHERE master_ip = ?1 AND slave_ip = ?2",
+        params![input.master_ip, input.slave_ip],
+        row_to_approval,
+    )
+    .map_err(DbError::from)
+}
+
+/// List all write-path approvals, including expired ones.
+pub fn list_write_approvals(conn: &Connection) -> Result<Vec<WriteApprovalRow>, DbError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, master_ip, slave_ip, justification, approved_by, created_at, expires_at
+         FROM write_approvals ORDER BY master_ip, slave_ip",
+    )?;
+
+    let rows = stmt
+        .query_map([], row_to_approval)?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(rows)
+}
+
+/// Revoke a write-path approval.
+pub fn delete_write_approval(conn: &Connection, id: i64) -> Result<(), DbError> {
+    let affected = conn.execute("DELETE FROM write_approvals WHERE id = ?1", params![id])?;
+    if affected == 0 {
+        return Err(DbError::NotFound(format!("Write approval {}", id)));
+    }
+    Ok(())
+}
+
+fn row_to_approval(row: &rusqlite::Row<'_>) -> rusqlite::Result<WriteApprovalRow> {
+    Ok(WriteApprovalRow {
+        id: row.get(0)?,
+        master_ip: row.get(1)?,
+        slave_ip: row.get(2)?,
+        justification: row.get(3)?,
+        approved_by: row.get(4)?,
+        created_at: row.get(5)?,
+        expires_at: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize(&conn).unwrap();
+        conn
+    }
+
+    fn input(master: &str, slave: &str, justification: &str) -> WriteApprovalInput {
+        WriteApprovalInput {
+            master_ip: master.to_string(),
+            slave_ip: slave.to_string(),
+            justification: justification.to_string(),
+            approved_by: "J. Operator".to_string(),
+            expires_at: Some("2030-01-01".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_upsert_replaces_same_pair() {
+        let conn = setup();
+        let first = upsert_write_approval(&conn, &input("10.0.0.1", "10.0.1.5", "HMI")).unwrap();
+        let second =
+            upsert_write_approval(&conn, &input("10.0.0.1", "10.0.1.5", "HMI setpoints")).unwrap();
+        assert_eq!(first.id, second.id);
+        upsert_write_approval(&conn, &input("10.0.0.1", "10.0.1.6", "HMI")).unwrap();
+
+        let list = list_write_approvals(&conn).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].justification, "HMI setpoints");
+        assert_eq!(list[0].expires_at.as_deref(), Some("2030-01-01"));
+    }
+
+    #[test]
+    fn test_delete_write_approval() {
+        let conn = setup();
+        let row = upsert_write_approval(&conn, &input("10.0.0.1", "10.0.1.5", "HMI")).unwrap();
+        delete_write_approval(&conn, row.id).unwrap();
+        assert!(list_write_approvals(&conn).unwrap().is_empty());
+        assert!(matches!(
+            delete_write_approval(&conn, row.id),
+            Err(DbError::NotFound(_))
+        ));
+    }
+}
@@ -28,6 +28,20 @@ pub struct AssetRow {
     pub is_public_ip: bool,
     pub first_seen: String,
     pub last_seen: String,
+    /// Lifecycle state: active, quarantined, decommissioned or planned
+    pub lifecycle: String,
+    /// When the lifecycle state was last changed (RFC 3339)
+    pub lifecycle_changed_at: Option<String>,
+    /// Where the hostname came from ("manual" for analyst edits, else the
+    /// protocol that named the host)
+    #[serde(default)]
+    pub hostname_source: Option<String>,
+    /// How each protocol was identified (JSON array)
+    #[serde(default)]
+    pub protocol_evidence: String,
+    /// 802.1Q VLAN IDs the asset was seen on (JSON array)
+    #[serde(default)]
+    pub vlan_ids: String,
 }
 
 /// Change history for an asset field.
@@ -48,15 +62,18 @@ pub fn insert_asset(conn: &Connection, asset: &AssetRow) -> Result<(), DbError>
             id, session_id, ip_address, mac_address, hostname, device_type,
             vendor, product_family, protocols, confidence, purdue_level, tags,
             notes, packet_count, signature_matches, oui_vendor, country,
-            is_public_ip, first_seen, last_seen
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+            is_public_ip, first_seen, last_seen, lifecycle, lifecycle_changed_at,
+            hostname_source, protocol_evidence, vlan_ids
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25)",
         params![
             asset.id, asset.session_id, asset.ip_address, asset.mac_address,
             asset.hostname, asset.device_type, asset.vendor, asset.product_family,
             asset.protocols, asset.confidence, asset.purdue_level, asset.tags,
             asset.notes, asset.packet_count, asset.signature_matches,
             asset.oui_vendor, asset.country, asset.is_public_ip,
-            asset.first_seen, asset.last_seen
+            asset.first_seen, asset.last_seen, asset.lifecycle,
+            asset.lifecycle_changed_at, asset.hostname_source, asset.protocol_evidence,
+            asset.vlan_ids
         ],
     )?;
     Ok(())
@@ -68,7 +85,8 @@ pub fn get_asset(conn: &Connection, id: &str) -> Result<AssetRow, DbError> {
         "SELECT id, session_id, ip_address, mac_address, hostname, device_type,
                 vendor, product_family, protocols, confidence, purdue_level, tags,
                 notes, packet_count, signature_matches, oui_vendor, country,
-                is_public_ip, first_seen, last_seen
+                is_public_ip, first_seen, last_seen, lifecycle, lifecycle_changed_at,
+                hostname_source, protocol_evidence, vlan_ids
          FROM assets WHERE id = ?1",
         params![id],
         row_to_asset,
@@ -85,7 +103,8 @@ pub fn list_assets(conn: &Connection, session_id: &str) -> Result<Vec<AssetRow>,
         "SELECT id, session_id, ip_address, mac_address, hostname, device_type,
                 vendor, product_family, protocols, confidence, purdue_level, tags,
                 notes, packet_count, signature_matches, oui_vendor, country,
-                is_public_ip, first_seen, last_seen
+                is_public_ip, first_seen, last_seen, lifecycle, lifecycle_changed_at,
+                hostname_source, protocol_evidence, vlan_ids
          FROM assets WHERE session_id = ?1
          ORDER BY packet_count DESC",
     )?;
@@ -109,6 +128,7 @@ pub fn update_field(
     let column = match field_name {
         "device_type" | "hostname" | "notes" | "tags" | "vendor" | "product_family" => field_name,
         "purdue_level" => "purdue_level",
+        "lifecycle" => "lifecycle",
         _ => return Err(DbError::NotFound(format!("Unknown field: {}", field_name))),
     };
 
@@ -134,6 +154,20 @@ pub fn update_field(
 
     // Record history
     let now = chrono::Utc::now().to_rfc3339();
+    if column == "lifecycle" {
+        conn.execute(
+            "UPDATE assets SET lifecycle_changed_at = ?1 WHERE id = ?2",
+            params![now, asset_id],
+        )?;
+    }
+    if column == "hostname" {
+        // A cleared hostname may be filled again from traffic
+        conn.execute(
+            "UPDATE assets SET hostname_source = CASE WHEN ?1 = '' THEN NULL ELSE 'manual' END
+             WHERE id = ?2",
+            params![new_value, asset_id],
+        )?;
+    }
     conn.execute(
         "INSERT INTO asset_history (asset_id, field_name, old_value, new_value, changed_at)
          VALUES (?1, ?2, ?3, ?4, ?5)",
@@ -205,6 +239,11 @@ fn row_to_asset(row: &rusqlite::Row) -> rusqlite::Result<AssetRow> {
         is_public_ip: row.get(17)?,
         first_seen: row.get(18)?,
         last_seen: row.get(19)?,
+        lifecycle: row.get(20)?,
+        lifecycle_changed_at: row.get(21)?,
+        hostname_source: row.get(22)?,
+        protocol_evidence: row.get(23)?,
+        vlan_ids: row.get(24)?,
     })
 }
 
@@ -246,6 +285,11 @@ mod tests {
             is_public_ip: false,
             first_seen: "2024-01-01T00:00:00Z".into(),
             last_seen: "2024-01-01T01:00:00Z".into(),
+            lifecycle: "active".into(),
+            lifecycle_changed_at: None,
+            hostname_source: None,
+            protocol_evidence: "[]".into(),
+            vlan_ids: "[10]".into(),
         }
     }
 
@@ -259,6 +303,7 @@ mod tests {
         assert_eq!(fetched.ip_address, "192.168.1.100");
         assert_eq!(fetched.vendor, Some("Siemens".into()));
         assert_eq!(fetched.oui_vendor, Some("Siemens AG".into()));
+        assert_eq!(fetched.vlan_ids, "[10]");
     }
 
     #[test]
@@ -292,6 +337,44 @@ mod tests {
         assert_eq!(history[0].new_value, Some("Test note".into()));
     }
 
+    #[test]
+    fn test_manual_hostname_marked() {
+        let conn = setup();
+        let mut asset = sample_asset();
+        asset.hostname = Some("plc1.plant.local".into());
+        asset.hostname_source = Some("dns".into());
+        insert_asset(&conn, &asset).unwrap();
+        assert_eq!(
+            get_asset(&conn, "a1").unwrap().hostname_source.as_deref(),
+            Some("dns")
+        );
+
+        update_field(&conn, "a1", "hostname", "PLC-Line1").unwrap();
+        let fetched = get_asset(&conn, "a1").unwrap();
+        assert_eq!(fetched.hostname.as_deref(), Some("PLC-Line1"));
+        assert_eq!(fetched.hostname_source.as_deref(), Some("manual"));
+
+        update_field(&conn, "a1", "hostname", "").unwrap();
+        assert!(get_asset(&conn, "a1").unwrap().hostname_source.is_none());
+    }
+
+    #[test]
+    fn test_lifecycle_transition_recorded() {
+        let conn = setup();
+        insert_asset(&conn, &sample_asset()).unwrap();
+
+        update_field(&conn, "a1", "lifecycle", "decommissioned").unwrap();
+
+        let fetched = get_asset(&conn, "a1").unwrap();
+        assert_eq!(fetched.lifecycle, "decommissioned");
+        assert!(fetched.lifecycle_changed_at.is_some());
+
+        let history = get_history(&conn, "a1").unwrap();
+        assert_eq!(history[0].field_name, "lifecycle");
+        assert_eq!(history[0].old_value, Some("active".into()));
+        assert_eq!(history[0].new_value, Some("decommissioned".into()));
+    }
+
     #[test]
     fn test_bulk_update() {
         let conn = setup();
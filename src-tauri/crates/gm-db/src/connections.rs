@@ -23,6 +23,17 @@ pub struct ConnectionRow {
     pub first_seen: String,
     pub last_seen: String,
     pub origin_files: String, // JSON array
+    pub provenance: String,   // JSON array
+    /// How the client/server orientation was decided (see
+    /// `gm_parsers::DirectionBasis`); src is the client
+    #[serde(default)]
+    pub direction_basis: String,
+    /// 802.1Q VLAN IDs the connection's frames were tagged with (JSON array)
+    #[serde(default)]
+    pub vlan_ids: String,
+    /// VXLAN network identifiers the connection was carried in (JSON array)
+    #[serde(default)]
+    pub vxlan_vnis: String,
 }
 
 /// Insert a connection into the database.
@@ -30,8 +41,9 @@ pub fn insert_connection(conn: &Connection, row: &ConnectionRow) -> Result<(), D
     conn.execute(
         "INSERT OR REPLACE INTO connections (
             id, session_id, src_ip, src_port, src_mac, dst_ip, dst_port, dst_mac,
-            protocol, transport, packet_count, byte_count, first_seen, last_seen, origin_files
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            protocol, transport, packet_count, byte_count, first_seen, last_seen, origin_files,
+            provenance, direction_basis, vlan_ids, vxlan_vnis
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
         params![
             row.id,
             row.session_id,
@@ -47,7 +59,11 @@ pub fn insert_connection(conn: &Connection, row: &ConnectionRow) -> Result<(), D
             row.byte_count,
             row.first_seen,
             row.last_seen,
-            row.origin_files
+            row.origin_files,
+            row.provenance,
+            row.direction_basis,
+            row.vlan_ids,
+            row.vxlan_vnis
         ],
     )?;
     Ok(())
@@ -60,7 +76,8 @@ pub fn list_connections(
 ) -> Result<Vec<ConnectionRow>, DbError> {
     let mut stmt = conn.prepare(
         "SELECT id, session_id, src_ip, src_port, src_mac, dst_ip, dst_port, dst_mac,
-                protocol, transport, packet_count, byte_count, first_seen, last_seen, origin_files
+                protocol, transport, packet_count, byte_count, first_seen, last_seen, origin_files,
+                provenance, direction_basis, vlan_ids, vxlan_vnis
          FROM connections WHERE session_id = ?1
          ORDER BY packet_count DESC",
     )?;
@@ -83,6 +100,10 @@ pub fn list_connections(
                 first_seen: row.get(12)?,
                 last_seen: row.get(13)?,
                 origin_files: row.get(14)?,
+                provenance: row.get(15)?,
+                direction_basis: row.get(16)?,
+                vlan_ids: row.get(17)?,
+                vxlan_vnis: row.get(18)?,
             })
         })?
         .filter_map(|r| r.ok())
@@ -121,6 +142,10 @@ mod tests {
             first_seen: "2024-01-01T00:00:00Z".into(),
             last_seen: "2024-01-01T01:00:00Z".into(),
             origin_files: "[\"test.pcap\"]".into(),
+            provenance: "[]".into(),
+            direction_basis: "server_port".into(),
+            vlan_ids: "[110]".into(),
+            vxlan_vnis: "[5000]".into(),
         };
 
         insert_connection(&conn, &row).unwrap();
@@ -129,5 +154,8 @@ mod tests {
         assert_eq!(list.len(), 1);
         assert_eq!(list[0].src_ip, "192.168.1.100");
         assert_eq!(list[0].dst_port, 502);
+        assert_eq!(list[0].direction_basis, "server_port");
+        assert_eq!(list[0].vlan_ids, "[110]");
+        assert_eq!(list[0].vxlan_vnis, "[5000]");
     }
 }
@@ -0,0 +1,349 @@
+//! Bundled reference data: manifest, location, and integrity checks.
+//!
+//! The app ships default datasets (IEEE OUI table, GeoIP country database,
+//! device signatures) as Tauri resources. A [`DataManifest`] compiled into
+//! the binary lists each dataset with its SHA-256, so a truncated install or
+//! a hand-edited file is reported instead of silently producing empty vendor
+//! columns.
+//!
+//! Resolution tries each candidate root in order (the bundle's resource
+//! directory first, then the dev-tree locations). The first copy whose
+//! checksum verifies wins; if none verify, the first copy found is used and
+//! reported as [`DatasetState::Modified`].
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::DbError;
+
+/// Kind of reference dataset, which determines how the app loads it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetKind {
+    /// IEEE OUI TSV (MAC prefix → vendor)
+    Oui,
+    /// MaxMind-format country database (.mmdb)
+    GeoIp,
+    /// Directory of signature YAML files
+    Signatures,
+}
+
+/// One dataset listed in the manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetEntry {
+    /// Display name, e.g. "IEEE OUI"
+    pub name: String,
+    pub kind: DatasetKind,
+    /// Path relative to a data root (file, or directory for signatures)
+    pub path: String,
+    /// Expected SHA-256 (lowercase hex); `None` if not pinned
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Whether the app works without it (a missing optional dataset is informational)
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// List of bundled datasets and their expected checksums.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataManifest {
+    pub datasets: Vec<DatasetEntry>,
+}
+
+impl DataManifest {
+    /// Parse a manifest from JSON.
+    pub fn from_json(json: &str) -> Result<Self, DbError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Outcome of locating and verifying a dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetState {
+    /// Found and checksum matches the manifest
+    Verified,
+    /// Found, but no checksum is pinned for it
+    Unverified,
+    /// Found, but the checksum does not match (edited or corrupted copy)
+    Modified,
+    /// Not found in any data root
+    Missing,
+    /// Found but could not be read or loaded
+    Failed,
+}
+
+/// UI-visible status of one dataset after startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetStatus {
+    pub name: String,
+    pub kind: DatasetKind,
+    pub state: DatasetState,
+    pub optional: bool,
+    /// Path the dataset was loaded from
+    pub path: Option<String>,
+    /// Number of records loaded (OUI prefixes, signatures); 0 for GeoIP
+    pub records: usize,
+    /// Human-readable explanation for any state other than `Verified`
+    pub message: Option<String>,
+}
+
+/// A dataset resolved to a concrete location.
+#[derive(Debug, Clone)]
+pub struct ResolvedDataset {
+    pub entry: DatasetEntry,
+    pub path: Option<PathBuf>,
+    pub state: DatasetState,
+    pub message: Option<String>,
+}
+
+impl ResolvedDataset {
+    /// Status for the UI once the app has (or hasn't) loaded the dataset.
+    pub fn status(&self, records: usize) -> DatasetStatus {
+        DatasetStatus {
+            name: self.entry.name.clone(),
+            kind: self.entry.kind,
+            state: self.state,
+            optional: self.entry.optional,
+            path: self.path.as_ref().map(|p| p.display().to_string()),
+            records,
+            message: self.message.clone(),
+        }
+    }
+
+    /// Mark the dataset as failed to load.
+    pub fn failed(&mut self, error: impl std::fmt::Display) {
+        self.state = DatasetState::Failed;
+        self.message = Some(error.to_string());
+    }
+}
+
+/// Locate `entry` under the first suitable root and verify its checksum.
+pub fn resolve(entry: &DatasetEntry, roots: &[PathBuf]) -> ResolvedDataset {
+    let mut fallback: Option<(PathBuf, String)> = None;
+
+    for root in roots {
+        let candidate = root.join(&entry.path);
+        if !candidate.exists() {
+            continue;
+        }
+        let Some(ref expected) = entry.sha256 else {
+            return ResolvedDataset {
+                entry: entry.clone(),
+                path: Some(candidate),
+                state: DatasetState::Unverified,
+                message: Some("No checksum pinned in the data manifest".to_string()),
+            };
+        };
+        match checksum(&candidate) {
+            Ok(actual) if actual.eq_ignore_ascii_case(expected) => {
+                return ResolvedDataset {
+                    entry: entry.clone(),
+                    path: Some(candidate),
+                    state: DatasetState::Verified,
+                    message: None,
+                };
+            }
+            Ok(actual) => {
+                log::warn!(
+                    "{} at {} does not match the bundled checksum (sha256 {})",
+                    entry.name,
+                    candidate.display(),
+                    actual
+                );
+                fallback.get_or_insert((
+                    candidate,
+                    "Checksum does not match the bundled copy; file was edited or is corrupt"
+                        .to_string(),
+                ));
+            }
+            Err(e) => {
+                log::warn!("Failed to checksum {}: {}", candidate.display(), e);
+                fallback.get_or_insert((candidate, format!("Could not read: {}", e)));
+            }
+        }
+    }
+
+    match fallback {
+        Some((path, message)) => ResolvedDataset {
+            entry: entry.clone(),
+            path: Some(path),
+            state: DatasetState::Modified,
+            message: Some(message),
+        },
+        None => ResolvedDataset {
+            entry: entry.clone(),
+            path: None,
+            state: DatasetState::Missing,
+            message: Some(if entry.optional {
+                "Not bundled with this build".to_string()
+            } else {
+                format!("{} not found in any data location", entry.path)
+            }),
+        },
+    }
+}
+
+/// SHA-256 of a dataset as lowercase hex.
+///
+/// For a file this is the digest of its contents. For a directory it covers
+/// every regular file directly inside it, in file-name order, as
+/// `name NUL contents NUL` — so adding, removing, renaming, or editing any
+/// file changes the digest.
+pub fn checksum(path: &Path) -> Result<String, DbError> {
+    let mut hasher = Sha256::new();
+    if path.is_dir() {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(path)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file())
+            .collect();
+        files.sort();
+        for file in files {
+            let name = file
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            hasher.update(name.as_bytes());
+            hasher.update([0u8]);
+            hash_file(&mut hasher, &file)?;
+            hasher.update([0u8]);
+        }
+    } else {
+        hash_file(&mut hasher, path)?;
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn hash_file(hasher: &mut Sha256, path: &Path) -> Result<(), DbError> {
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, sha256: Option<String>, optional: bool) -> DatasetEntry {
+        DatasetEntry {
+            name: "Test".to_string(),
+            kind: DatasetKind::Oui,
+            path: path.to_string(),
+            sha256,
+            optional,
+        }
+    }
+
+    #[test]
+    fn test_checksum_known_vector() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("abc.txt");
+        std::fs::write(&file, b"abc").unwrap();
+        assert_eq!(
+            checksum(&file).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_directory_checksum_tracks_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.yaml"), b"name: a").unwrap();
+        std::fs::write(dir.path().join("b.yaml"), b"name: b").unwrap();
+        let original = checksum(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join("b.yaml"), b"name: b2").unwrap();
+        let edited = checksum(dir.path()).unwrap();
+        assert_ne!(original, edited);
+
+        std::fs::write(dir.path().join("b.yaml"), b"name: b").unwrap();
+        assert_eq!(checksum(dir.path()).unwrap(), original);
+
+        std::fs::write(dir.path().join("c.yaml"), b"").unwrap();
+        assert_ne!(checksum(dir.path()).unwrap(), original);
+    }
+
+    #[test]
+    fn test_resolve_prefers_verified_copy() {
+        let corrupt = tempfile::tempdir().unwrap();
+        let good = tempfile::tempdir().unwrap();
+        std::fs::create_dir(corrupt.path().join("data")).unwrap();
+        std::fs::create_dir(good.path().join("data")).unwrap();
+        std::fs::write(corrupt.path().join("data/oui.tsv"), b"truncat").unwrap();
+        std::fs::write(good.path().join("data/oui.tsv"), b"00:00:0C\tCisco\n").unwrap();
+        let expected = checksum(&good.path().join("data/oui.tsv")).unwrap();
+
+        let roots = vec![corrupt.path().to_path_buf(), good.path().to_path_buf()];
+        let resolved = resolve(
+            &entry("data/oui.tsv", Some(expected.clone()), false),
+            &roots,
+        );
+        assert_eq!(resolved.state, DatasetState::Verified);
+        assert_eq!(resolved.path, Some(good.path().join("data/oui.tsv")));
+
+        // Only the corrupt copy: still used, but flagged
+        let resolved = resolve(&entry("data/oui.tsv", Some(expected), false), &roots[..1]);
+        assert_eq!(resolved.state, DatasetState::Modified);
+        assert!(resolved.path.is_some());
+        assert!(resolved.message.is_some());
+    }
+
+    #[test]
+    fn test_resolve_missing_and_unpinned() {
+        let root = tempfile::tempdir().unwrap();
+        let roots = vec![root.path().to_path_buf()];
+
+        let missing = resolve(&entry("data/geo.mmdb", None, true), &roots);
+        assert_eq!(missing.state, DatasetState::Missing);
+        assert!(missing.path.is_none());
+        assert!(missing.status(0).optional);
+
+        std::fs::write(root.path().join("extra.tsv"), b"x").unwrap();
+        let unpinned = resolve(&entry("extra.tsv", None, false), &roots);
+        assert_eq!(unpinned.state, DatasetState::Unverified);
+    }
+
+    #[test]
+    fn test_manifest_from_json() {
+        let manifest = DataManifest::from_json(
+            r#"{"datasets":[{"name":"IEEE OUI","kind":"oui","path":"data/oui.tsv","sha256":"ab"},
+                {"name":"GeoIP","kind":"geo_ip","path":"data/x.mmdb","optional":true}]}"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.datasets.len(), 2);
+        assert_eq!(manifest.datasets[1].kind, DatasetKind::GeoIp);
+        assert!(manifest.datasets[1].sha256.is_none());
+        assert!(manifest.datasets[1].optional);
+    }
+
+    /// The pins in the bundled data/manifest.json must match the datasets
+    /// shipped next to it, or every install reports them as Modified.
+    #[test]
+    fn test_bundled_manifest_pins_match() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let json = std::fs::read_to_string(root.join("data/manifest.json")).unwrap();
+        let manifest = DataManifest::from_json(&json).unwrap();
+        for entry in &manifest.datasets {
+            let Some(ref expected) = entry.sha256 else {
+                continue;
+            };
+            assert_eq!(
+                &checksum(&root.join(&entry.path)).unwrap(),
+                expected,
+                "{}: stale sha256 in data/manifest.json",
+                entry.name
+            );
+        }
+    }
+}
@@ -3,7 +3,7 @@
 #[derive(thiserror::Error, Debug)]
 pub enum DbError {
     #[error("SQLite error: {0}")]
-    Sqlite(#[from] rusqlite::Error),
+    Sqlite(rusqlite::Error),
 
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
@@ -19,4 +19,22 @@ pub enum DbError {
 
     #[error("OUI lookup error: {0}")]
     Oui(String),
+
+    #[error("Settings error: {0}")]
+    Settings(String),
+
+    #[error("Database locked: {0}")]
+    Locked(String),
+
+    #[error("Database is open read-only; changes cannot be saved")]
+    ReadOnly,
+}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::ReadOnly) => DbError::ReadOnly,
+            _ => DbError::Sqlite(e),
+        }
+    }
 }
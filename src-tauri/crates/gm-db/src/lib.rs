@@ -4,57 +4,133 @@
 //!
 //! Provides:
 //! - Session save/load with full state serialization
+//! - Single-writer locking with a read-only fallback
 //! - Asset CRUD with field-level change history
 //! - Connection storage
+//! - Write-path approvals
+//! - Versioned settings profiles and GUI preferences
 //! - IEEE OUI vendor lookup
 //! - GeoIP country identification
+//! - Bundled dataset manifest and checksum verification
 
+pub mod approvals;
 pub mod assets;
 pub mod connections;
+pub mod datasets;
 pub mod error;
 pub mod geoip;
+pub mod lock;
 pub mod oui;
 pub mod projects;
 pub mod schema;
 pub mod sessions;
+pub mod settings;
 
+pub use approvals::{WriteApprovalInput, WriteApprovalRow};
 pub use assets::{AssetRow, HistoryRow};
 pub use connections::ConnectionRow;
+pub use datasets::{DataManifest, DatasetKind, DatasetState, DatasetStatus};
 pub use error::DbError;
 pub use geoip::GeoIpLookup;
+pub use lock::{DbLock, OpenMode};
 pub use oui::OuiLookup;
 pub use projects::{Project, ProjectInput, ProjectSummary};
 pub use sessions::SessionRow;
+pub use settings::{
+    AnalysisRules, CaptureLimits, DeepParseBudget, NamedProfile, PortMapping, QueuePolicy,
+    RetentionPolicy, SettingsProfile, UserSettings, DEFAULT_PROFILE, SETTINGS_VERSION,
+};
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rusqlite::OpenFlags;
+
+/// How long a statement waits on another connection's SQLite lock.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Database connection wrapper.
 ///
 /// Wraps a rusqlite Connection and provides high-level operations.
 pub struct Database {
     conn: rusqlite::Connection,
+    path: Option<PathBuf>,
+    mode: OpenMode,
+    /// Held for the lifetime of a read-write database
+    _lock: Option<DbLock>,
 }
 
 impl Database {
     /// Open (or create) a database at the given path and initialize the schema.
+    ///
+    /// Takes the single-writer lock; fails with [`DbError::Locked`] if
+    /// another instance has the same file open for writing.
     pub fn open(path: &Path) -> Result<Self, DbError> {
-        // Ensure parent directory exists
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+        Self::open_with_mode(path, OpenMode::ReadWrite)
+    }
 
-        let conn = rusqlite::Connection::open(path)?;
-        schema::initialize(&conn)?;
+    /// Open a database at the given path in the given mode.
+    ///
+    /// Read-only databases must already exist, are not migrated and take
+    /// no lock, so they can be opened alongside a writer.
+    pub fn open_with_mode(path: &Path, mode: OpenMode) -> Result<Self, DbError> {
+        let (conn, lock) = match mode {
+            OpenMode::ReadWrite => {
+                // Ensure parent directory exists
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let lock = DbLock::acquire(path)?;
+                let conn = rusqlite::Connection::open(path)?;
+                conn.busy_timeout(BUSY_TIMEOUT)?;
+                schema::initialize(&conn)?;
+                (conn, Some(lock))
+            }
+            OpenMode::ReadOnly => {
+                if !path.exists() {
+                    return Err(DbError::NotFound(path.display().to_string()));
+                }
+                let conn = rusqlite::Connection::open_with_flags(
+                    path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )?;
+                conn.busy_timeout(BUSY_TIMEOUT)?;
+                (conn, None)
+            }
+        };
 
-        log::info!("Database opened at {}", path.display());
-        Ok(Self { conn })
+        log::info!("Database opened at {} ({:?})", path.display(), mode);
+        Ok(Self {
+            conn,
+            path: Some(path.to_path_buf()),
+            mode,
+            _lock: lock,
+        })
     }
 
     /// Open an in-memory database (for testing).
     pub fn open_in_memory() -> Result<Self, DbError> {
         let conn = rusqlite::Connection::open_in_memory()?;
         schema::initialize(&conn)?;
-        Ok(Self { conn })
+        Ok(Self {
+            conn,
+            path: None,
+            mode: OpenMode::ReadWrite,
+            _lock: None,
+        })
+    }
+
+    /// File backing this database (None for in-memory databases).
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn mode(&self) -> OpenMode {
+        self.mode
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.mode == OpenMode::ReadOnly
     }
 
     // ─── Session Operations ────────────────────────────────────
@@ -169,6 +245,73 @@ impl Database {
     pub fn list_sessions_for_project(&self, project_id: i64) -> Result<Vec<SessionRow>, DbError> {
         projects::list_sessions_for_project(&self.conn, project_id)
     }
+
+    // ─── Write Approval Operations ─────────────────────────────
+
+    pub fn upsert_write_approval(
+        &self,
+        input: &WriteApprovalInput,
+    ) -> Result<WriteApprovalRow, DbError> {
+        approvals::upsert_write_approval(&self.conn, input)
+    }
+
+    pub fn list_write_approvals(&self) -> Result<Vec<WriteApprovalRow>, DbError> {
+        approvals::list_write_approvals(&self.conn)
+    }
+
+    pub fn delete_write_approval(&self, id: i64) -> Result<(), DbError> {
+        approvals::delete_write_approval(&self.conn, id)
+    }
+
+    // ─── Settings Operations ───────────────────────────────────
+
+    pub fn get_user_settings(&self) -> Result<UserSettings, DbError> {
+        settings::get_user_settings(&self.conn)
+    }
+
+    pub fn save_user_settings(&self, user: &UserSettings) -> Result<(), DbError> {
+        settings::save_user_settings(&self.conn, user)
+    }
+
+    pub fn list_settings_profiles(&self) -> Result<Vec<NamedProfile>, DbError> {
+        settings::list_profiles(&self.conn)
+    }
+
+    pub fn get_settings_profile(&self, name: &str) -> Result<NamedProfile, DbError> {
+        settings::get_profile(&self.conn, name)
+    }
+
+    pub fn save_settings_profile(
+        &self,
+        name: &str,
+        profile: &SettingsProfile,
+    ) -> Result<NamedProfile, DbError> {
+        settings::save_profile(&self.conn, name, profile)
+    }
+
+    pub fn delete_settings_profile(&self, name: &str) -> Result<(), DbError> {
+        settings::delete_profile(&self.conn, name)
+    }
+
+    pub fn active_settings_profile(&self) -> Result<NamedProfile, DbError> {
+        settings::active_profile(&self.conn)
+    }
+
+    pub fn activate_settings_profile(&self, name: &str) -> Result<NamedProfile, DbError> {
+        settings::activate_profile(&self.conn, name)
+    }
+
+    pub fn export_settings_profiles(&self, names: &[String]) -> Result<String, DbError> {
+        settings::export_profiles(&self.conn, names)
+    }
+
+    pub fn import_settings_profiles(&self, json: &str) -> Result<Vec<String>, DbError> {
+        settings::import_profiles(&self.conn, json)
+    }
+
+    pub fn apply_retention(&self, policy: &RetentionPolicy) -> Result<usize, DbError> {
+        settings::apply_retention(&self.conn, policy)
+    }
 }
 
 #[cfg(test)]
@@ -211,6 +354,11 @@ mod tests {
             is_public_ip: false,
             first_seen: "2024-01-01T00:00:00Z".into(),
             last_seen: "2024-01-01T01:00:00Z".into(),
+            lifecycle: "active".into(),
+            lifecycle_changed_at: None,
+            hostname_source: None,
+            protocol_evidence: "[]".into(),
+            vlan_ids: "[]".into(),
         };
         db.insert_asset(&asset).unwrap();
 
@@ -223,4 +371,39 @@ mod tests {
         db.delete_session("s1").unwrap();
         assert!(db.list_assets("s1").unwrap().is_empty());
     }
+
+    #[test]
+    fn test_single_writer_with_read_only_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.db");
+
+        let writer = Database::open(&path).unwrap();
+        writer.create_session("s1", "Shift A", "", "{}").unwrap();
+        assert_eq!(writer.path(), Some(path.as_path()));
+        assert!(!writer.is_read_only());
+
+        assert!(matches!(Database::open(&path), Err(DbError::Locked(_))));
+
+        let reader = Database::open_with_mode(&path, OpenMode::ReadOnly).unwrap();
+        assert!(reader.is_read_only());
+        assert_eq!(reader.list_sessions().unwrap().len(), 1);
+        assert!(matches!(
+            reader.create_session("s2", "Shift B", "", "{}"),
+            Err(DbError::ReadOnly)
+        ));
+
+        drop(writer);
+        assert!(Database::open(&path).is_ok());
+    }
+
+    #[test]
+    fn test_read_only_requires_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.db");
+        assert!(matches!(
+            Database::open_with_mode(&path, OpenMode::ReadOnly),
+            Err(DbError::NotFound(_))
+        ));
+        assert!(!path.exists());
+    }
 }
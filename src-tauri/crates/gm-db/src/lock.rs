@@ -0,0 +1,129 @@
+//! Single-writer protection for a database file.
+//!
+//! SQLite tolerates concurrent readers, but two app instances saving
+//! sessions into the same `data.db` interleave multi-statement writes and
+//! leave sessions half-replaced. A read-write [`Database`](crate::Database)
+//! therefore holds an exclusive advisory lock on a `<db>.lock` sidecar file
+//! for its whole lifetime; a second writer gets [`DbError::Locked`] and can
+//! fall back to [`OpenMode::ReadOnly`].
+//!
+//! The lock is an OS file lock (flock / LockFileEx), so it is released when
+//! the holder exits or crashes — a stale lock file on disk is harmless.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize};
+
+use crate::DbError;
+
+/// How a database file is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenMode {
+    /// Take the writer lock, create the file and migrate the schema
+    #[default]
+    ReadWrite,
+    /// Open an existing database without locking; every write fails
+    /// with [`DbError::ReadOnly`]
+    ReadOnly,
+}
+
+/// Exclusive writer lock on a database, released on drop.
+#[derive(Debug)]
+pub struct DbLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl DbLock {
+    /// Path of the lock file guarding `db_path`.
+    pub fn lock_path(db_path: &Path) -> PathBuf {
+        let mut name = db_path.as_os_str().to_os_string();
+        name.push(".lock");
+        PathBuf::from(name)
+    }
+
+    /// Take the writer lock for `db_path` without blocking.
+    ///
+    /// Fails with [`DbError::Locked`] naming the current holder if another
+    /// process (or another open handle in this one) already has it.
+    pub fn acquire(db_path: &Path) -> Result<Self, DbError> {
+        let path = Self::lock_path(db_path);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        if !file.try_lock_exclusive()? {
+            let mut holder = String::new();
+            // Windows refuses reads of a locked range; the holder is then unknown
+            let _ = file.read_to_string(&mut holder);
+            let holder = holder.trim();
+            return Err(DbError::Locked(format!(
+                "{} is in use by another Kusanagi Kajiki instance ({})",
+                db_path.display(),
+                if holder.is_empty() {
+                    "holder unknown"
+                } else {
+                    holder
+                }
+            )));
+        }
+
+        // Record who holds the lock, for the error shown to the next opener
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(
+            file,
+            "pid {} since {}",
+            std::process::id(),
+            chrono::Utc::now().to_rfc3339()
+        )?;
+        file.flush()?;
+
+        Ok(Self { file, path })
+    }
+
+    /// Path of the held lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for DbLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_lock_reports_holder() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("data.db");
+
+        let lock = DbLock::acquire(&db_path).unwrap();
+        assert_eq!(lock.path(), dir.path().join("data.db.lock"));
+
+        match DbLock::acquire(&db_path) {
+            Err(DbError::Locked(msg)) => {
+                assert!(msg.contains("data.db"));
+                if cfg!(unix) {
+                    assert!(msg.contains(&format!("pid {}", std::process::id())));
+                }
+            }
+            other => panic!("expected Locked, got {:?}", other),
+        }
+
+        drop(lock);
+        assert!(DbLock::acquire(&db_path).is_ok());
+    }
+}
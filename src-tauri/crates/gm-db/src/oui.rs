@@ -81,7 +81,7 @@ mod tests {
         writeln!(file, "00:0E:8C\tSiemens AG").unwrap();
         writeln!(file, "00:00:BC\tRockwell Automation").unwrap();
         writeln!(file, "00:80:F4\tSchneider Electric").unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "# Another comment").unwrap();
         writeln!(file, "00:1D:9C\tRockwell Automation").unwrap();
         file
@@ -51,6 +51,11 @@ CREATE TABLE IF NOT EXISTS assets (
     is_public_ip      INTEGER NOT NULL DEFAULT 0,
     first_seen        TEXT NOT NULL,
     last_seen         TEXT NOT NULL,
+    lifecycle         TEXT NOT NULL DEFAULT 'active',
+    lifecycle_changed_at TEXT,
+    hostname_source   TEXT,
+    protocol_evidence TEXT NOT NULL DEFAULT '[]',
+    vlan_ids          TEXT NOT NULL DEFAULT '[]',
     FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
 );
 
@@ -73,6 +78,10 @@ CREATE TABLE IF NOT EXISTS connections (
     first_seen      TEXT NOT NULL,
     last_seen       TEXT NOT NULL,
     origin_files    TEXT NOT NULL DEFAULT '[]',
+    provenance      TEXT NOT NULL DEFAULT '[]',
+    direction_basis TEXT NOT NULL DEFAULT 'unknown',
+    vlan_ids        TEXT NOT NULL DEFAULT '[]',
+    vxlan_vnis      TEXT NOT NULL DEFAULT '[]',
     FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
 );
 
@@ -105,6 +114,29 @@ CREATE TABLE IF NOT EXISTS findings (
 );
 
 CREATE INDEX IF NOT EXISTS idx_findings_session ON findings(session_id);
+
+CREATE TABLE IF NOT EXISTS write_approvals (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    master_ip     TEXT NOT NULL,
+    slave_ip      TEXT NOT NULL,
+    justification TEXT NOT NULL DEFAULT '',
+    approved_by   TEXT NOT NULL DEFAULT '',
+    created_at    TEXT NOT NULL,
+    expires_at    TEXT,
+    UNIQUE (master_ip, slave_ip)
+);
+
+CREATE TABLE IF NOT EXISTS settings_profiles (
+    name          TEXT PRIMARY KEY,
+    version       INTEGER NOT NULL,
+    data          TEXT NOT NULL,
+    updated_at    TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS app_settings (
+    key           TEXT PRIMARY KEY,
+    value         TEXT NOT NULL
+);
 "#;
 
 /// Initialize the database schema (creates tables if they don't exist).
@@ -128,6 +160,87 @@ pub fn initialize(conn: &Connection) -> Result<(), DbError> {
         )?;
     }
 
+    // Migration: add provenance to connections (correlated Zeek/Suricata flow records).
+    let has_provenance = conn
+        .prepare("SELECT provenance FROM connections LIMIT 0")
+        .is_ok();
+    if !has_provenance {
+        conn.execute(
+            "ALTER TABLE connections ADD COLUMN provenance TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    // Migration: add how each connection's client/server orientation was decided.
+    let has_direction = conn
+        .prepare("SELECT direction_basis FROM connections LIMIT 0")
+        .is_ok();
+    if !has_direction {
+        conn.execute(
+            "ALTER TABLE connections ADD COLUMN direction_basis TEXT NOT NULL DEFAULT 'unknown'",
+            [],
+        )?;
+    }
+
+    // Migration: add lifecycle state to assets (active/quarantined/decommissioned/planned).
+    let has_lifecycle = conn.prepare("SELECT lifecycle FROM assets LIMIT 0").is_ok();
+    if !has_lifecycle {
+        conn.execute_batch(
+            "ALTER TABLE assets ADD COLUMN lifecycle TEXT NOT NULL DEFAULT 'active';
+             ALTER TABLE assets ADD COLUMN lifecycle_changed_at TEXT;",
+        )?;
+    }
+
+    // Migration: add where each asset's hostname came from (manual, dns, ...).
+    let has_hostname_source = conn
+        .prepare("SELECT hostname_source FROM assets LIMIT 0")
+        .is_ok();
+    if !has_hostname_source {
+        conn.execute("ALTER TABLE assets ADD COLUMN hostname_source TEXT", [])?;
+    }
+
+    // Migration: add how each of an asset's protocols was identified.
+    let has_protocol_evidence = conn
+        .prepare("SELECT protocol_evidence FROM assets LIMIT 0")
+        .is_ok();
+    if !has_protocol_evidence {
+        conn.execute(
+            "ALTER TABLE assets ADD COLUMN protocol_evidence TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    // Migration: add the 802.1Q VLANs assets and connections were seen on.
+    let has_asset_vlans = conn.prepare("SELECT vlan_ids FROM assets LIMIT 0").is_ok();
+    if !has_asset_vlans {
+        conn.execute(
+            "ALTER TABLE assets ADD COLUMN vlan_ids TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+    let has_connection_vlans = conn
+        .prepare("SELECT vlan_ids FROM connections LIMIT 0")
+        .is_ok();
+    if !has_connection_vlans {
+        conn.execute(
+            "ALTER TABLE connections ADD COLUMN vlan_ids TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    // Migration: add the VXLAN overlays connections were carried in.
+    let has_connection_vnis = conn
+        .prepare("SELECT vxlan_vnis FROM connections LIMIT 0")
+        .is_ok();
+    if !has_connection_vnis {
+        conn.execute(
+            "ALTER TABLE connections ADD COLUMN vxlan_vnis TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    crate::settings::seed_builtin_profiles(conn)?;
+
     log::info!("Database schema initialized");
     Ok(())
 }
@@ -156,6 +269,79 @@ mod tests {
         assert!(tables.contains(&"connections".to_string()));
         assert!(tables.contains(&"asset_history".to_string()));
         assert!(tables.contains(&"findings".to_string()));
+        assert!(tables.contains(&"write_approvals".to_string()));
+        assert!(tables.contains(&"settings_profiles".to_string()));
+        assert!(tables.contains(&"app_settings".to_string()));
+    }
+
+    #[test]
+    fn test_connections_provenance_migration() {
+        let conn = Connection::open_in_memory().unwrap();
+        // Connections table as created before provenance was tracked
+        conn.execute_batch(
+            "CREATE TABLE connections (
+                id TEXT PRIMARY KEY, session_id TEXT NOT NULL,
+                src_ip TEXT NOT NULL, src_port INTEGER NOT NULL, src_mac TEXT,
+                dst_ip TEXT NOT NULL, dst_port INTEGER NOT NULL, dst_mac TEXT,
+                protocol TEXT NOT NULL, transport TEXT NOT NULL,
+                packet_count INTEGER NOT NULL DEFAULT 0, byte_count INTEGER NOT NULL DEFAULT 0,
+                first_seen TEXT NOT NULL, last_seen TEXT NOT NULL,
+                origin_files TEXT NOT NULL DEFAULT '[]'
+            );",
+        )
+        .unwrap();
+        initialize(&conn).unwrap();
+        assert!(conn
+            .prepare("SELECT provenance FROM connections LIMIT 0")
+            .is_ok());
+        assert!(conn
+            .prepare("SELECT direction_basis FROM connections LIMIT 0")
+            .is_ok());
+        assert!(conn
+            .prepare("SELECT vlan_ids FROM connections LIMIT 0")
+            .is_ok());
+        assert!(conn
+            .prepare("SELECT vxlan_vnis FROM connections LIMIT 0")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_assets_lifecycle_migration() {
+        let conn = Connection::open_in_memory().unwrap();
+        // Assets table as created before lifecycle states existed
+        conn.execute_batch(
+            "CREATE TABLE assets (
+                id TEXT PRIMARY KEY, session_id TEXT NOT NULL,
+                ip_address TEXT NOT NULL, mac_address TEXT, hostname TEXT,
+                device_type TEXT NOT NULL DEFAULT 'unknown', vendor TEXT, product_family TEXT,
+                protocols TEXT NOT NULL DEFAULT '[]', confidence INTEGER NOT NULL DEFAULT 0,
+                purdue_level INTEGER, tags TEXT NOT NULL DEFAULT '[]',
+                notes TEXT NOT NULL DEFAULT '', packet_count INTEGER NOT NULL DEFAULT 0,
+                signature_matches TEXT NOT NULL DEFAULT '[]', oui_vendor TEXT, country TEXT,
+                is_public_ip INTEGER NOT NULL DEFAULT 0,
+                first_seen TEXT NOT NULL, last_seen TEXT NOT NULL
+            );
+            INSERT INTO assets (id, session_id, ip_address, first_seen, last_seen)
+            VALUES ('a1', 's1', '10.0.0.1', '2024-01-01', '2024-01-01');",
+        )
+        .unwrap();
+        initialize(&conn).unwrap();
+        let lifecycle: String = conn
+            .query_row("SELECT lifecycle FROM assets WHERE id = 'a1'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(lifecycle, "active");
+        assert!(conn
+            .prepare("SELECT lifecycle_changed_at FROM assets LIMIT 0")
+            .is_ok());
+        assert!(conn
+            .prepare("SELECT hostname_source FROM assets LIMIT 0")
+            .is_ok());
+        assert!(conn
+            .prepare("SELECT protocol_evidence FROM assets LIMIT 0")
+            .is_ok());
+        assert!(conn.prepare("SELECT vlan_ids FROM assets LIMIT 0").is_ok());
     }
 
     #[test]
@@ -0,0 +1,714 @@
+//! Versioned settings store with named profiles.
+//!
+//! Settings live in the database rather than a GUI-owned JSON file so a
+//! headless sensor and the desktop app share the same store. A profile
+//! bundles everything that shapes a run — capture limits, retention,
+//! analysis rules, custom port maps and function code files — under a name such as
+//! "low-memory sensor". Exactly one profile is active at a time.
+//!
+//! Plain key/value preferences (theme, active profile) go in
+//! `app_settings`; profiles are stored as versioned JSON documents in
+//! `settings_profiles` so fields can be added without schema migrations.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::error::DbError;
+
+/// Current version of the profile document format.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Name of the profile used when nothing else has been activated.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Identifies a settings export file.
+pub const SETTINGS_EXPORT_FORMAT: &str = "kusanaginokajiki-settings";
+
+const ACTIVE_PROFILE_KEY: &str = "active_profile";
+const THEME_KEY: &str = "theme";
+
+/// Live capture limits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureLimits {
+    /// Maximum packets kept in the ring buffer for PCAP save
+    pub ring_buffer_size: usize,
+    /// Maximum bytes captured per packet
+    pub snaplen: i32,
+    /// Capture all traffic on the segment, not just traffic addressed to us
+    pub promiscuous: bool,
+    /// Kernel capture buffer in MB (0 = libpcap default); raise it when the
+    /// capture reports kernel drops
+    pub buffer_size_mb: u32,
+    /// Capture Wi-Fi interfaces in monitor (rfmon) mode
+    pub monitor_mode: bool,
+    /// Per-protocol deep-parse limits for live capture (empty = parse everything)
+    pub deep_parse_budgets: Vec<DeepParseBudget>,
+    /// Capture-to-disk: start a new file at this size (0 = no size limit)
+    pub rotate_file_mb: u64,
+    /// Capture-to-disk: start a new file after this long (0 = no time limit)
+    pub rotate_file_minutes: u64,
+    /// Capture-to-disk: keep at most this many files, deleting the oldest
+    /// (0 = keep all)
+    pub rotate_max_files: usize,
+    /// Most parsed packets queued between capture and processing
+    pub queue_capacity: usize,
+    /// What capture does when that queue is full
+    pub queue_policy: QueuePolicy,
+}
+
+/// Behaviour of a full capture-to-processing queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuePolicy {
+    /// Discard the oldest queued packets so analysis stays current
+    #[default]
+    DropOldest,
+    /// Stall capture until processing catches up; the kernel buffer then
+    /// overflows instead, and its drops are reported as kernel drops
+    Block,
+}
+
+impl Default for CaptureLimits {
+    fn default() -> Self {
+        Self {
+            ring_buffer_size: 1_000_000,
+            snaplen: 65535,
+            promiscuous: true,
+            buffer_size_mb: 32,
+            monitor_mode: false,
+            deep_parse_budgets: Vec::new(),
+            rotate_file_mb: 100,
+            rotate_file_minutes: 0,
+            rotate_max_files: 0,
+            queue_capacity: 100_000,
+            queue_policy: QueuePolicy::DropOldest,
+        }
+    }
+}
+
+/// Deep parse at most `max_per_second` packets of one protocol during live
+/// capture; the rest are counted but not inspected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeepParseBudget {
+    /// Protocol name as accepted by `IcsProtocol::from_name` (e.g. "modbus")
+    pub protocol: String,
+    pub max_per_second: u32,
+}
+
+/// How long saved sessions are kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Delete sessions not updated for this many days (None = keep forever)
+    pub max_session_age_days: Option<u32>,
+    /// Keep at most this many sessions, newest first (None = unlimited)
+    pub max_sessions: Option<usize>,
+}
+
+/// Which findings analysis reports and how many are returned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisRules {
+    /// ATT&CK technique IDs whose findings are dropped (e.g. "T0846")
+    pub disabled_techniques: Vec<String>,
+    /// Maximum findings returned to the UI
+    pub max_findings: usize,
+    /// Maximum anomaly scores returned to the UI
+    pub max_anomalies: usize,
+    /// A finding detected again within this many seconds of its last
+    /// detection updates the existing finding; 0 disables merging
+    pub finding_dedup_window_secs: u64,
+}
+
+impl Default for AnalysisRules {
+    fn default() -> Self {
+        Self {
+            disabled_techniques: Vec::new(),
+            max_findings: 1_000,
+            max_anomalies: 500,
+            finding_dedup_window_secs: 86_400,
+        }
+    }
+}
+
+/// A site-specific port → protocol assignment, e.g. Modbus on 5020.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortMapping {
+    pub port: u16,
+    /// Protocol name as accepted by `IcsProtocol::from_name` (e.g. "modbus")
+    pub protocol: String,
+}
+
+/// The settings that make up one profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsProfile {
+    /// Document format version (see `SETTINGS_VERSION`)
+    pub version: u32,
+    pub description: String,
+    pub capture: CaptureLimits,
+    pub retention: RetentionPolicy,
+    pub analysis: AnalysisRules,
+    pub port_maps: Vec<PortMapping>,
+    /// YAML files of site- or vendor-specific Modbus/DNP3 function codes,
+    /// loaded on top of the built-in table
+    pub function_code_files: Vec<String>,
+    /// The plant's subnets in CIDR notation (e.g. "10.20.0.0/16"), used to
+    /// group devices; addresses outside them fall back to their /24 or /64
+    pub subnets: Vec<String>,
+    /// Width in seconds of the time buckets each connection's traffic is
+    /// counted in, for bandwidth-over-time charts
+    pub traffic_bucket_secs: u32,
+}
+
+impl Default for SettingsProfile {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            description: String::new(),
+            capture: CaptureLimits::default(),
+            retention: RetentionPolicy::default(),
+            analysis: AnalysisRules::default(),
+            port_maps: Vec::new(),
+            function_code_files: Vec::new(),
+            subnets: Vec::new(),
+            traffic_bucket_secs: 60,
+        }
+    }
+}
+
+/// A stored profile with its name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedProfile {
+    pub name: String,
+    pub profile: SettingsProfile,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// Settings export file for fleet-standardized deployments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsExport {
+    pub format: String,
+    pub version: u32,
+    pub profiles: Vec<NamedProfile>,
+}
+
+/// GUI preferences that are not part of a profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSettings {
+    /// Theme mode: "dark", "light", or "system"
+    #[serde(default = "default_theme")]
+    pub theme: String,
+}
+
+fn default_theme() -> String {
+    "dark".to_string()
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            theme: default_theme(),
+        }
+    }
+}
+
+/// Profiles shipped with the application.
+pub fn builtin_profiles() -> Vec<(&'static str, SettingsProfile)> {
+    vec![
+        (
+            DEFAULT_PROFILE,
+            SettingsProfile {
+                description: "Balanced defaults for assessment laptops".to_string(),
+                ..Default::default()
+            },
+        ),
+        (
+            "low-memory sensor",
+            SettingsProfile {
+                description: "Small capture buffer and short retention for embedded sensors"
+                    .to_string(),
+                capture: CaptureLimits {
+                    ring_buffer_size: 50_000,
+                    snaplen: 1518,
+                    promiscuous: true,
+                    buffer_size_mb: 8,
+                    monitor_mode: false,
+                    deep_parse_budgets: ["modbus", "dnp3", "ethernet_ip", "s7comm"]
+                        .into_iter()
+                        .map(|protocol| DeepParseBudget {
+                            protocol: protocol.to_string(),
+                            max_per_second: 200,
+                        })
+                        .collect(),
+                    rotate_file_mb: 20,
+                    rotate_file_minutes: 60,
+                    rotate_max_files: 24,
+                    queue_capacity: 10_000,
+                    queue_policy: QueuePolicy::DropOldest,
+                },
+                retention: RetentionPolicy {
+                    max_session_age_days: Some(7),
+                    max_sessions: Some(20),
+                },
+                analysis: AnalysisRules {
+                    max_findings: 250,
+                    max_anomalies: 100,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ),
+        (
+            "full fidelity lab",
+            SettingsProfile {
+                description: "Large capture buffer and unlimited retention for lab analysis"
+                    .to_string(),
+                capture: CaptureLimits {
+                    ring_buffer_size: 5_000_000,
+                    snaplen: 65535,
+                    promiscuous: true,
+                    buffer_size_mb: 256,
+                    monitor_mode: false,
+                    deep_parse_budgets: Vec::new(),
+                    rotate_file_mb: 1024,
+                    rotate_file_minutes: 0,
+                    rotate_max_files: 0,
+                    queue_capacity: 1_000_000,
+                    queue_policy: QueuePolicy::Block,
+                },
+                analysis: AnalysisRules {
+                    max_findings: 10_000,
+                    max_anomalies: 5_000,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ),
+    ]
+}
+
+/// Insert the built-in profiles if they are missing. Existing profiles
+/// with the same names are left untouched so local edits survive upgrades.
+pub fn seed_builtin_profiles(conn: &Connection) -> Result<(), DbError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    for (name, profile) in builtin_profiles() {
+        conn.execute(
+            "INSERT OR IGNORE INTO settings_profiles (name, version, data, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![name, profile.version, serde_json::to_string(&profile)?, now],
+        )?;
+    }
+    Ok(())
+}
+
+/// Parse a stored profile document, upgrading older versions.
+fn decode_profile(name: &str, data: &str) -> Result<SettingsProfile, DbError> {
+    let mut profile: SettingsProfile = serde_json::from_str(data)?;
+    check_version(name, &mut profile)?;
+    Ok(profile)
+}
+
+/// Reject documents from a newer release and stamp older ones as current.
+/// Version 1 is the first format, so there is nothing to migrate yet;
+/// missing fields are filled from defaults by serde.
+fn check_version(name: &str, profile: &mut SettingsProfile) -> Result<(), DbError> {
+    if profile.version > SETTINGS_VERSION {
+        return Err(DbError::Settings(format!(
+            "profile '{}' uses settings version {} (this build supports {})",
+            name, profile.version, SETTINGS_VERSION
+        )));
+    }
+    profile.version = SETTINGS_VERSION;
+    Ok(())
+}
+
+/// Get a profile by name.
+pub fn get_profile(conn: &Connection, name: &str) -> Result<NamedProfile, DbError> {
+    let (data, updated_at): (String, String) = conn
+        .query_row(
+            "SELECT data, updated_at FROM settings_profiles WHERE name = ?1",
+            params![name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                DbError::NotFound(format!("Settings profile '{}'", name))
+            }
+            other => DbError::Sqlite(other),
+        })?;
+    Ok(NamedProfile {
+        name: name.to_string(),
+        profile: decode_profile(name, &data)?,
+        updated_at,
+    })
+}
+
+/// List all profiles, ordered by name. Profiles that fail to decode are
+/// skipped with a warning rather than hiding every other profile.
+pub fn list_profiles(conn: &Connection) -> Result<Vec<NamedProfile>, DbError> {
+    let mut stmt =
+        conn.prepare("SELECT name, data, updated_at FROM settings_profiles ORDER BY name")?;
+    let rows: Vec<(String, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut profiles = Vec::with_capacity(rows.len());
+    for (name, data, updated_at) in rows {
+        match decode_profile(&name, &data) {
+            Ok(profile) => profiles.push(NamedProfile {
+                name,
+                profile,
+                updated_at,
+            }),
+            Err(e) => log::warn!("Skipping settings profile '{}': {}", name, e),
+        }
+    }
+    Ok(profiles)
+}
+
+/// Create or replace a profile.
+pub fn save_profile(
+    conn: &Connection,
+    name: &str,
+    profile: &SettingsProfile,
+) -> Result<NamedProfile, DbError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(DbError::Settings("profile name is required".to_string()));
+    }
+    let mut profile = profile.clone();
+    check_version(name, &mut profile)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO settings_profiles (name, version, data, updated_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (name) DO UPDATE SET
+             version = excluded.version,
+             data = excluded.data,
+             updated_at = excluded.updated_at",
+        params![name, profile.version, serde_json::to_string(&profile)?, now],
+    )?;
+    Ok(NamedProfile {
+        name: name.to_string(),
+        profile,
+        updated_at: now,
+    })
+}
+
+/// Delete a profile. The default and the active profile cannot be deleted.
+pub fn delete_profile(conn: &Connection, name: &str) -> Result<(), DbError> {
+    if name == DEFAULT_PROFILE {
+        return Err(DbError::Settings(
+            "the default profile cannot be deleted".to_string(),
+        ));
+    }
+    if active_profile_name(conn)? == name {
+        return Err(DbError::Settings(format!(
+            "profile '{}' is active; activate another profile first",
+            name
+        )));
+    }
+    let affected = conn.execute(
+        "DELETE FROM settings_profiles WHERE name = ?1",
+        params![name],
+    )?;
+    if affected == 0 {
+        return Err(DbError::NotFound(format!("Settings profile '{}'", name)));
+    }
+    Ok(())
+}
+
+fn get_value(conn: &Connection, key: &str) -> Result<Option<String>, DbError> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(DbError::from)
+}
+
+fn set_value(conn: &Connection, key: &str, value: &str) -> Result<(), DbError> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Name of the active profile (the default profile if none was chosen).
+pub fn active_profile_name(conn: &Connection) -> Result<String, DbError> {
+    Ok(get_value(conn, ACTIVE_PROFILE_KEY)?.unwrap_or_else(|| DEFAULT_PROFILE.to_string()))
+}
+
+/// Load the active profile, falling back to the default profile if the
+/// active one has been removed.
+pub fn active_profile(conn: &Connection) -> Result<NamedProfile, DbError> {
+    let name = active_profile_name(conn)?;
+    match get_profile(conn, &name) {
+        Err(DbError::NotFound(_)) if name != DEFAULT_PROFILE => {
+            log::warn!(
+                "Active settings profile '{}' is missing, using default",
+                name
+            );
+            get_profile(conn, DEFAULT_PROFILE)
+        }
+        other => other,
+    }
+}
+
+/// Make a profile the active one and return it.
+pub fn activate_profile(conn: &Connection, name: &str) -> Result<NamedProfile, DbError> {
+    let profile = get_profile(conn, name)?;
+    set_value(conn, ACTIVE_PROFILE_KEY, name)?;
+    Ok(profile)
+}
+
+/// Load GUI preferences.
+pub fn get_user_settings(conn: &Connection) -> Result<UserSettings, DbError> {
+    Ok(UserSettings {
+        theme: get_value(conn, THEME_KEY)?.unwrap_or_else(default_theme),
+    })
+}
+
+/// Save GUI preferences.
+pub fn save_user_settings(conn: &Connection, settings: &UserSettings) -> Result<(), DbError> {
+    set_value(conn, THEME_KEY, &settings.theme)
+}
+
+/// Serialize the named profiles (all profiles if `names` is empty) to an
+/// export document.
+pub fn export_profiles(conn: &Connection, names: &[String]) -> Result<String, DbError> {
+    let profiles = if names.is_empty() {
+        list_profiles(conn)?
+    } else {
+        names
+            .iter()
+            .map(|n| get_profile(conn, n))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    let export = SettingsExport {
+        format: SETTINGS_EXPORT_FORMAT.to_string(),
+        version: SETTINGS_VERSION,
+        profiles,
+    };
+    Ok(serde_json::to_string_pretty(&export)?)
+}
+
+/// Import profiles from an export document, replacing profiles with the
+/// same names. Returns the imported profile names.
+pub fn import_profiles(conn: &Connection, json: &str) -> Result<Vec<String>, DbError> {
+    let export: SettingsExport = serde_json::from_str(json)?;
+    if export.format != SETTINGS_EXPORT_FORMAT {
+        return Err(DbError::Settings(format!(
+            "not a settings export (format '{}')",
+            export.format
+        )));
+    }
+    if export.version > SETTINGS_VERSION {
+        return Err(DbError::Settings(format!(
+            "export uses settings version {} (this build supports {})",
+            export.version, SETTINGS_VERSION
+        )));
+    }
+
+    let mut imported = Vec::with_capacity(export.profiles.len());
+    for named in &export.profiles {
+        let saved = save_profile(conn, &named.name, &named.profile)?;
+        imported.push(saved.name);
+    }
+    Ok(imported)
+}
+
+/// Delete sessions that fall outside the retention policy. Returns the
+/// number of sessions removed.
+pub fn apply_retention(conn: &Connection, policy: &RetentionPolicy) -> Result<usize, DbError> {
+    let mut removed = 0;
+
+    if let Some(days) = policy.max_session_age_days {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(i64::from(days))).to_rfc3339();
+        removed += conn.execute(
+            "DELETE FROM sessions WHERE updated_at < ?1",
+            params![cutoff],
+        )?;
+    }
+
+    if let Some(max) = policy.max_sessions {
+        removed += conn.execute(
+            "DELETE FROM sessions WHERE id NOT IN
+                 (SELECT id FROM sessions ORDER BY updated_at DESC LIMIT ?1)",
+            params![max as i64],
+        )?;
+    }
+
+    if removed > 0 {
+        log::info!("Retention policy removed {} session(s)", removed);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        schema::initialize(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_builtin_profiles_seeded() {
+        let conn = setup();
+        let names: Vec<String> = list_profiles(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["default", "full fidelity lab", "low-memory sensor"]
+        );
+        assert_eq!(active_profile(&conn).unwrap().name, DEFAULT_PROFILE);
+
+        let sensor = get_profile(&conn, "low-memory sensor").unwrap();
+        assert!(sensor
+            .profile
+            .capture
+            .deep_parse_budgets
+            .iter()
+            .any(|b| b.protocol == "modbus" && b.max_per_second == 200));
+    }
+
+    #[test]
+    fn test_activate_and_delete_profile() {
+        let conn = setup();
+        let mut profile = SettingsProfile::default();
+        profile.port_maps.push(PortMapping {
+            port: 5020,
+            protocol: "modbus".to_string(),
+        });
+        save_profile(&conn, "site A", &profile).unwrap();
+
+        let active = activate_profile(&conn, "site A").unwrap();
+        assert_eq!(active.profile.port_maps[0].port, 5020);
+        assert_eq!(active_profile(&conn).unwrap().name, "site A");
+
+        assert!(matches!(
+            delete_profile(&conn, "site A"),
+            Err(DbError::Settings(_))
+        ));
+        assert!(matches!(
+            delete_profile(&conn, DEFAULT_PROFILE),
+            Err(DbError::Settings(_))
+        ));
+        activate_profile(&conn, DEFAULT_PROFILE).unwrap();
+        delete_profile(&conn, "site A").unwrap();
+        assert!(matches!(
+            get_profile(&conn, "site A"),
+            Err(DbError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_newer_version_rejected() {
+        let conn = setup();
+        let profile = SettingsProfile {
+            version: SETTINGS_VERSION + 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            save_profile(&conn, "future", &profile),
+            Err(DbError::Settings(_))
+        ));
+
+        // Older documents missing newer fields load with defaults
+        conn.execute(
+            "INSERT INTO settings_profiles (name, version, data, updated_at)
+             VALUES ('old', 0, '{\"version\":0,\"capture\":{\"snaplen\":256}}', '')",
+            [],
+        )
+        .unwrap();
+        let old = get_profile(&conn, "old").unwrap().profile;
+        assert_eq!(old.version, SETTINGS_VERSION);
+        assert_eq!(old.capture.snaplen, 256);
+        assert_eq!(old.capture.ring_buffer_size, 1_000_000);
+        assert_eq!(old.capture.rotate_file_mb, 100);
+        assert_eq!(old.capture.queue_capacity, 100_000);
+        assert_eq!(old.capture.queue_policy, QueuePolicy::DropOldest);
+        assert_eq!(old.analysis.max_findings, 1_000);
+        assert_eq!(old.analysis.finding_dedup_window_secs, 86_400);
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let src = setup();
+        let json = export_profiles(&src, &["low-memory sensor".to_string()]).unwrap();
+
+        let dst = Connection::open_in_memory().unwrap();
+        schema::initialize(&dst).unwrap();
+        delete_profile(&dst, "low-memory sensor").unwrap();
+        let imported = import_profiles(&dst, &json).unwrap();
+        assert_eq!(imported, vec!["low-memory sensor"]);
+        assert_eq!(
+            get_profile(&dst, "low-memory sensor").unwrap().profile,
+            get_profile(&src, "low-memory sensor").unwrap().profile
+        );
+
+        assert!(matches!(
+            import_profiles(&dst, "{\"format\":\"other\",\"version\":1,\"profiles\":[]}"),
+            Err(DbError::Settings(_))
+        ));
+    }
+
+    #[test]
+    fn test_user_settings_roundtrip() {
+        let conn = setup();
+        assert_eq!(get_user_settings(&conn).unwrap().theme, "dark");
+        save_user_settings(
+            &conn,
+            &UserSettings {
+                theme: "light".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(get_user_settings(&conn).unwrap().theme, "light");
+    }
+
+    #[test]
+    fn test_apply_retention() {
+        let conn = setup();
+        for (id, updated) in [
+            ("s1", "2020-01-01T00:00:00+00:00"),
+            ("s2", "2099-01-01T00:00:00+00:00"),
+            ("s3", "2099-01-02T00:00:00+00:00"),
+        ] {
+            conn.execute(
+                "INSERT INTO sessions (id, name, created_at, updated_at) VALUES (?1, ?1, ?2, ?2)",
+                params![id, updated],
+            )
+            .unwrap();
+        }
+
+        let policy = RetentionPolicy {
+            max_session_age_days: Some(30),
+            max_sessions: Some(1),
+        };
+        assert_eq!(apply_retention(&conn, &policy).unwrap(), 2);
+        let remaining: String = conn
+            .query_row("SELECT id FROM sessions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, "s3");
+        assert_eq!(
+            apply_retention(&conn, &RetentionPolicy::default()).unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Cross-source flow correlation.
+//!
+//! The same conversation is often imported several times: once from a PCAP,
+//! once from Zeek's conn.log (plus modbus.log/dnp3.log rows sharing the
+//! conn.log `uid`), and once from Suricata's eve.json (`flow_id`). These
+//! helpers decide when two records describe the same flow so the command
+//! layer can merge them onto one connection instead of duplicating it.
+//!
+//! Two records correlate when their 5-tuple matches in either direction
+//! (Zeek and Suricata report bidirectional flows keyed by originator, PCAP
+//! connections are per direction) and their time windows overlap within
+//! [`MATCH_SLACK_SECS`].
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::IngestedConnection;
+
+/// Tolerance when comparing flow time windows from different sources.
+///
+/// Sensor clocks drift and Zeek timestamps the first packet it saw rather
+/// than the SYN, so windows a few seconds apart still count as overlapping.
+pub const MATCH_SLACK_SECS: i64 = 5;
+
+/// One external record that contributed to a connection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlowProvenance {
+    /// Source display name ("Zeek", "Suricata")
+    pub source: String,
+    /// Source-native flow identifier (Zeek `uid`, Suricata `flow_id`)
+    pub uid: Option<String>,
+}
+
+/// Direction-insensitive 5-tuple.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlowKey {
+    low: (String, u16),
+    high: (String, u16),
+    transport: String,
+}
+
+impl FlowKey {
+    pub fn new(src_ip: &str, src_port: u16, dst_ip: &str, dst_port: u16, transport: &str) -> Self {
+        let a = (src_ip.to_string(), src_port);
+        let b = (dst_ip.to_string(), dst_port);
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        Self {
+            low,
+            high,
+            transport: transport.to_ascii_lowercase(),
+        }
+    }
+
+    pub fn of(conn: &IngestedConnection) -> Self {
+        Self::new(
+            &conn.src_ip,
+            conn.src_port,
+            &conn.dst_ip,
+            conn.dst_port,
+            &conn.transport,
+        )
+    }
+}
+
+/// Whether two time windows overlap within [`MATCH_SLACK_SECS`].
+///
+/// A window with no timestamps cannot be ruled out and is treated as
+/// overlapping; a window with only one bound is treated as an instant.
+pub fn windows_overlap(
+    a: (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+    b: (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+) -> bool {
+    let (Some(a_start), Some(a_end)) = (a.0.or(a.1), a.1.or(a.0)) else {
+        return true;
+    };
+    let (Some(b_start), Some(b_end)) = (b.0.or(b.1), b.1.or(b.0)) else {
+        return true;
+    };
+    let slack = Duration::seconds(MATCH_SLACK_SECS);
+    a_start - slack <= b_end && b_start - slack <= a_end
+}
+
+/// Parse an RFC 3339 timestamp as stored on connections; empty → `None`.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Merge records that share a source-native UID into one connection.
+///
+/// Zeek writes one conn.log row per flow and one protocol-log row per
+/// request on that flow, all with the same `uid`. The conn.log row carries
+/// the packet/byte totals and the protocol log carries the precise
+/// application protocol, so the merged record keeps the larger counts, the
+/// more specific protocol, and the widest time window. Records without a
+/// UID are left untouched. Order of first appearance is preserved.
+pub fn coalesce_by_uid(conns: &mut Vec<IngestedConnection>) {
+    let mut by_uid: HashMap<String, usize> = HashMap::new();
+    let mut merged: Vec<IngestedConnection> = Vec::with_capacity(conns.len());
+
+    for conn in conns.drain(..) {
+        let Some(uid) = conn.uid.clone() else {
+            merged.push(conn);
+            continue;
+        };
+        match by_uid.get(&uid) {
+            Some(&idx) => {
+                let existing = &mut merged[idx];
+                existing.packet_count = existing.packet_count.max(conn.packet_count);
+                existing.byte_count = existing.byte_count.max(conn.byte_count);
+                if is_generic_protocol(&existing.protocol) && !is_generic_protocol(&conn.protocol) {
+                    existing.protocol = conn.protocol;
+                }
+                existing.first_seen = earliest(existing.first_seen, conn.first_seen);
+                existing.last_seen = latest(existing.last_seen, conn.last_seen);
+            }
+            None => {
+                by_uid.insert(uid, merged.len());
+                merged.push(conn);
+            }
+        }
+    }
+
+    *conns = merged;
+}
+
+/// Protocol labels that a more specific label from another record should replace.
+///
+/// Zeek's conn.log `service` is often empty (port guess) or a generic
+/// analyzer name, while the protocol logs always name the ICS protocol.
+pub fn is_generic_protocol(protocol: &str) -> bool {
+    protocol.is_empty() || protocol.eq_ignore_ascii_case("unknown")
+}
+
+fn earliest(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn latest(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IngestSource;
+
+    fn ts(secs: i64) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0)
+    }
+
+    fn conn(
+        uid: Option<&str>,
+        protocol: &str,
+        pkts: u64,
+        start: i64,
+        end: i64,
+    ) -> IngestedConnection {
+        IngestedConnection {
+            src_ip: "10.0.0.5".to_string(),
+            src_port: 49152,
+            dst_ip: "10.0.0.100".to_string(),
+            dst_port: 502,
+            protocol: protocol.to_string(),
+            transport: "tcp".to_string(),
+            packet_count: pkts,
+            byte_count: pkts * 60,
+            first_seen: ts(start),
+            last_seen: ts(end),
+            source: IngestSource::Zeek,
+            uid: uid.map(|u| u.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_flow_key_is_direction_insensitive() {
+        let fwd = FlowKey::new("10.0.0.5", 49152, "10.0.0.100", 502, "tcp");
+        let rev = FlowKey::new("10.0.0.100", 502, "10.0.0.5", 49152, "TCP");
+        assert_eq!(fwd, rev);
+        assert_ne!(
+            fwd,
+            FlowKey::new("10.0.0.5", 49152, "10.0.0.100", 502, "udp")
+        );
+        assert_ne!(
+            fwd,
+            FlowKey::new("10.0.0.5", 49153, "10.0.0.100", 502, "tcp")
+        );
+    }
+
+    #[test]
+    fn test_windows_overlap() {
+        assert!(windows_overlap((ts(0), ts(60)), (ts(30), ts(90))));
+        // Within slack
+        assert!(windows_overlap((ts(0), ts(60)), (ts(63), ts(90))));
+        // Port reuse an hour later is a different flow
+        assert!(!windows_overlap((ts(0), ts(60)), (ts(3600), ts(3660))));
+        // Missing timestamps can't rule a match out
+        assert!(windows_overlap((None, None), (ts(3600), ts(3660))));
+        // Single-bound window is an instant
+        assert!(windows_overlap((ts(10), None), (ts(0), ts(60))));
+        assert!(!windows_overlap((ts(100), None), (ts(0), ts(60))));
+    }
+
+    #[test]
+    fn test_coalesce_by_uid() {
+        let mut conns = vec![
+            conn(Some("CAbc1"), "unknown", 200, 0, 60),
+            conn(Some("CAbc1"), "modbus", 1, 5, 5),
+            conn(Some("CAbc1"), "modbus", 1, 70, 70),
+            conn(Some("CXyz2"), "dnp3", 10, 0, 1),
+            conn(None, "modbus", 1, 0, 0),
+        ];
+        coalesce_by_uid(&mut conns);
+
+        assert_eq!(conns.len(), 3);
+        assert_eq!(conns[0].uid.as_deref(), Some("CAbc1"));
+        assert_eq!(conns[0].protocol, "modbus");
+        assert_eq!(conns[0].packet_count, 200);
+        assert_eq!(conns[0].first_seen, ts(0));
+        assert_eq!(conns[0].last_seen, ts(70));
+        assert_eq!(conns[1].uid.as_deref(), Some("CXyz2"));
+        assert!(conns[2].uid.is_none());
+    }
+}
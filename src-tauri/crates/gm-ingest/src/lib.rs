@@ -5,18 +5,24 @@
 //! - **Suricata**: eve.json (line-delimited JSON with flow/alert/protocol metadata)
 //! - **Nmap**: XML output (-oX format, host/port/service/OS detection)
 //! - **Masscan**: JSON list format (IP/port/service results)
+//! - **Windows event logs**: Security/System events exported as XML or JSON
+//!   (logons, service installs, account creation, log clearing)
 //!
 //! Each parser produces [`IngestResult`] containing assets and connections
-//! compatible with the existing pipeline.
+//! compatible with the existing pipeline. [`correlate`] matches ingested
+//! flows against connections from other sources.
 
+pub mod correlate;
 pub mod error;
 pub mod masscan;
 pub mod nmap;
 pub mod sinema;
 pub mod suricata;
 pub mod wazuh;
+pub mod winevent;
 pub mod zeek;
 
+pub use correlate::FlowProvenance;
 pub use error::IngestError;
 
 use chrono::{DateTime, Utc};
@@ -43,6 +49,8 @@ pub enum IngestSource {
     Sinema,
     /// TIA Portal network configuration XML export — passive configuration data
     TiaPortal,
+    /// Windows Security/System event log export from an HMI or workstation
+    WindowsEventLog,
 }
 
 impl IngestSource {
@@ -61,6 +69,7 @@ impl IngestSource {
             IngestSource::Wazuh => "Wazuh",
             IngestSource::Sinema => "SINEMA",
             IngestSource::TiaPortal => "TIA Portal",
+            IngestSource::WindowsEventLog => "Windows Event Log",
         }
     }
 }
@@ -108,6 +117,8 @@ pub struct IngestedConnection {
     pub first_seen: Option<DateTime<Utc>>,
     pub last_seen: Option<DateTime<Utc>>,
     pub source: IngestSource,
+    /// Source-native flow identifier (Zeek `uid`, Suricata `flow_id`)
+    pub uid: Option<String>,
 }
 
 /// An alert/finding from Suricata or similar IDS.
@@ -125,6 +136,49 @@ pub struct IngestedAlert {
     pub source: IngestSource,
 }
 
+/// Kind of a host event kept from a Windows event log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostEventKind {
+    /// 4624: an account logged on
+    Logon,
+    /// 4625: a logon attempt failed
+    LogonFailure,
+    /// 4648: a process logged on with explicitly supplied credentials
+    ExplicitCredentials,
+    /// 4697 (Security) / 7045 (System): a service was installed
+    ServiceInstall,
+    /// 4720: a local or domain account was created
+    AccountCreated,
+    /// 1102 (Security) / 104 (System): an event log was cleared
+    LogCleared,
+}
+
+/// A security-relevant event from a host's own event log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestedHostEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Computer that logged the event, as recorded (often a FQDN)
+    pub computer: String,
+    /// Address of that computer, when given at import
+    pub host_ip: Option<String>,
+    pub event_id: u32,
+    /// Event log channel ("Security", "System")
+    pub channel: String,
+    pub kind: HostEventKind,
+    /// Account that acted, as `DOMAIN\user`
+    pub user: Option<String>,
+    /// Windows logon type (2 interactive, 3 network, 10 remote interactive)
+    pub logon_type: Option<u8>,
+    /// Address a logon came from
+    pub remote_ip: Option<String>,
+    /// Workstation name a logon came from
+    pub remote_host: Option<String>,
+    /// Event-specific detail: service name and image, new account, failure status
+    pub detail: Option<String>,
+    pub source: IngestSource,
+}
+
 /// Result of ingesting external tool data.
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct IngestResult {
@@ -132,6 +186,7 @@ pub struct IngestResult {
     pub assets: Vec<IngestedAsset>,
     pub connections: Vec<IngestedConnection>,
     pub alerts: Vec<IngestedAlert>,
+    pub host_events: Vec<IngestedHostEvent>,
     pub files_processed: usize,
     pub errors: Vec<String>,
 }
@@ -144,6 +144,7 @@ fn port_to_protocol(port: u16, service_name: Option<&str>) -> String {
             "ssh" => return "ssh".to_string(),
             "ms-wbt-server" | "rdp" => return "rdp".to_string(),
             "snmp" => return "snmp".to_string(),
+            "microsoft-ds" | "netbios-ssn" | "smb" => return "smb".to_string(),
             _ => {}
         }
     }
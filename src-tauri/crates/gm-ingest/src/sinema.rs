@@ -123,6 +123,7 @@ pub(crate) fn parse_sinema_csv(content: &str) -> Result<IngestResult, IngestErro
         assets,
         connections: Vec::new(),
         alerts: Vec::new(),
+        host_events: Vec::new(),
         files_processed: 1,
         errors,
     })
@@ -219,6 +220,7 @@ pub(crate) fn parse_tia_xml(content: &str) -> Result<IngestResult, IngestError>
         assets,
         connections: Vec::new(),
         alerts: Vec::new(),
+        host_events: Vec::new(),
         files_processed: 1,
         errors,
     })
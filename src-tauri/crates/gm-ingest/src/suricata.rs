@@ -85,6 +85,7 @@ fn process_eve_event(event: &EveEvent, result: &mut IngestResult) {
                     first_seen: flow.start.or(Some(ts)),
                     last_seen: flow.end.or(Some(ts)),
                     source: IngestSource::Suricata,
+                    uid: event.flow_id.map(|id| id.to_string()),
                 };
 
                 // Create assets for endpoints
@@ -188,6 +189,8 @@ struct EveEvent {
     #[serde(default)]
     timestamp: Option<DateTime<Utc>>,
     event_type: String,
+    #[serde(default)]
+    flow_id: Option<u64>,
     src_ip: String,
     src_port: Option<u16>,
     dest_ip: String,
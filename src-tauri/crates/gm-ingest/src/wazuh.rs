@@ -105,6 +105,7 @@ pub fn parse_wazuh_alerts(path: &Path) -> Result<IngestResult, IngestError> {
         assets: Vec::new(),
         connections: Vec::new(),
         alerts,
+        host_events: Vec::new(),
         files_processed: 1,
         errors,
     })
@@ -0,0 +1,573 @@
+//! Windows event log parser.
+//!
+//! Reads Security and System events exported from HMIs and engineering
+//! workstations and keeps the ones that tell who got onto a host and what
+//! changed on it: logons, explicit-credential logons, service installs,
+//! account creation and log clearing. Other event IDs are skipped.
+//!
+//! Accepted exports:
+//! - XML: `wevtutil qe Security /f:xml` (bare `<Event>` elements) or Event
+//!   Viewer "Save As… XML" (`<Events>` root)
+//! - JSON: `evtx_dump` output (JSON lines, an array, or the default stream
+//!   with `Record N` headers) and Winlogbeat / Elastic documents (`winlog.*`)
+//!
+//! An event only names the computer that logged it; the command layer
+//! resolves that name (or an address given at import) to an asset.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+
+use crate::error::IngestError;
+use crate::{HostEventKind, IngestResult, IngestSource, IngestedAsset, IngestedHostEvent};
+
+/// Accounts Windows logs on by itself; their logons say nothing about people.
+const BUILTIN_ACCOUNTS: &[&str] = &[
+    "SYSTEM",
+    "LOCAL SERVICE",
+    "NETWORK SERVICE",
+    "ANONYMOUS LOGON",
+];
+
+// ─── Format-neutral event ─────────────────────────────────────
+
+/// The parts of an event both export formats carry.
+#[derive(Debug, Default)]
+struct RawEvent {
+    event_id: u32,
+    timestamp: Option<DateTime<Utc>>,
+    computer: String,
+    channel: String,
+    /// EventData values by name, plus the leaf fields of UserData
+    data: HashMap<String, String>,
+}
+
+impl RawEvent {
+    /// A data field, with Windows' "-" placeholder treated as absent.
+    fn get(&self, key: &str) -> Option<&str> {
+        self.data
+            .get(key)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty() && *v != "-")
+    }
+
+    /// `DOMAIN\user` from a pair of domain / user fields.
+    fn account(&self, domain_key: &str, user_key: &str) -> Option<String> {
+        let user = self.get(user_key)?;
+        Some(match self.get(domain_key) {
+            Some(domain) => format!("{}\\{}", domain, user),
+            None => user.to_string(),
+        })
+    }
+}
+
+// ─── Public API ───────────────────────────────────────────────
+
+/// Parse Windows event log exports.
+///
+/// Each file may be XML or JSON; the format is taken from its first
+/// non-blank character. A file that cannot be parsed is reported in
+/// `errors` and the others are still read. Events without a timestamp are
+/// skipped.
+pub fn parse_windows_events(paths: &[&Path]) -> Result<IngestResult, IngestError> {
+    let mut result = IngestResult {
+        source: Some(IngestSource::WindowsEventLog),
+        ..Default::default()
+    };
+    let mut asset_ips: HashSet<String> = HashSet::new();
+
+    for path in paths {
+        let content = fs::read_to_string(path)?;
+        let content = content.trim_start_matches('\u{feff}').trim_start();
+        let parsed = match content.chars().next() {
+            Some('<') => read_xml(content),
+            Some('{') | Some('[') | Some('R') => read_json(content),
+            _ => Err(IngestError::InvalidFormat(
+                "not an XML or JSON event export".to_string(),
+            )),
+        };
+        let raw_events = match parsed {
+            Ok(events) => events,
+            Err(e) => {
+                result.errors.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        };
+        result.files_processed += 1;
+
+        for raw in &raw_events {
+            let Some(event) = convert_event(raw) else {
+                continue;
+            };
+            // A remote logon also places the machine it came from
+            if matches!(
+                event.kind,
+                HostEventKind::Logon | HostEventKind::LogonFailure
+            ) {
+                if let Some(ip) = event.remote_ip.as_ref() {
+                    if asset_ips.insert(ip.clone()) {
+                        result.assets.push(IngestedAsset {
+                            ip_address: ip.clone(),
+                            mac_address: None,
+                            hostname: event.remote_host.clone(),
+                            device_type: None,
+                            vendor: None,
+                            protocols: Vec::new(),
+                            open_ports: Vec::new(),
+                            os_info: None,
+                            source: IngestSource::WindowsEventLog,
+                            is_active: false,
+                        });
+                    }
+                }
+            }
+            result.host_events.push(event);
+        }
+    }
+
+    result.host_events.sort_by_key(|e| e.timestamp);
+    Ok(result)
+}
+
+// ─── XML ──────────────────────────────────────────────────────
+
+/// Read every `<Event>` element of an XML export.
+fn read_xml(content: &str) -> Result<Vec<RawEvent>, IngestError> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut events = Vec::new();
+    let mut current: Option<RawEvent> = None;
+    // Element names from <Event> down to the open element
+    let mut path: Vec<String> = Vec::new();
+    // Name attribute of the open <Data> element
+    let mut data_name: Option<String> = None;
+
+    loop {
+        let xml_event = reader
+            .read_event()
+            .map_err(|e| IngestError::Parse(format!("XML: {}", e)))?;
+        match xml_event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                if name == "Event" {
+                    current = Some(RawEvent::default());
+                    path.clear();
+                }
+                if let Some(raw) = current.as_mut() {
+                    read_attributes(raw, &name, &e, &mut data_name);
+                    path.push(name);
+                }
+            }
+            Event::Empty(e) => {
+                if let Some(raw) = current.as_mut() {
+                    read_attributes(raw, &element_name(&e), &e, &mut None);
+                }
+            }
+            Event::Text(t) => {
+                if let Some(raw) = current.as_mut() {
+                    let text = t
+                        .unescape()
+                        .map_err(|e| IngestError::Parse(format!("XML: {}", e)))?;
+                    assign_text(raw, &path, data_name.as_deref(), text.trim());
+                }
+            }
+            Event::End(_) if current.is_some() => match path.pop().as_deref() {
+                Some("Data") => data_name = None,
+                Some("Event") => events.extend(current.take()),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(events)
+}
+
+fn element_name(e: &BytesStart<'_>) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// Pick up `TimeCreated/@SystemTime` and the `Name` of a `<Data>` element.
+fn read_attributes(
+    raw: &mut RawEvent,
+    element: &str,
+    e: &BytesStart<'_>,
+    data_name: &mut Option<String>,
+) {
+    let wanted: &[u8] = match element {
+        "TimeCreated" => b"SystemTime",
+        "Data" => b"Name",
+        _ => return,
+    };
+    for attr in e.attributes().flatten() {
+        if attr.key.local_name().as_ref() != wanted {
+            continue;
+        }
+        let Ok(value) = attr.unescape_value() else {
+            continue;
+        };
+        if element == "TimeCreated" {
+            raw.timestamp = parse_time(&value);
+        } else {
+            *data_name = Some(value.into_owned());
+        }
+    }
+}
+
+/// Store element text by where it sits under `<Event>`.
+fn assign_text(raw: &mut RawEvent, path: &[String], data_name: Option<&str>, text: &str) {
+    let Some(leaf) = path.last() else {
+        return;
+    };
+    let parent = path.len().checked_sub(2).map(|i| path[i].as_str());
+    match parent {
+        Some("System") => match leaf.as_str() {
+            "EventID" => raw.event_id = text.parse().unwrap_or(0),
+            "Computer" => raw.computer = text.to_string(),
+            "Channel" => raw.channel = text.to_string(),
+            _ => {}
+        },
+        Some("EventData") if leaf == "Data" => {
+            if let Some(name) = data_name {
+                raw.data.insert(name.to_string(), text.to_string());
+            }
+        }
+        // UserData wraps its fields in one event-specific element
+        _ if path.get(1).map(String::as_str) == Some("UserData") && path.len() == 4 => {
+            raw.data.insert(leaf.clone(), text.to_string());
+        }
+        _ => {}
+    }
+}
+
+// ─── JSON ─────────────────────────────────────────────────────
+
+/// Read every event of a JSON export: one object per line, an array, or
+/// `evtx_dump`'s stream of objects separated by `Record N` lines.
+fn read_json(content: &str) -> Result<Vec<RawEvent>, IngestError> {
+    let cleaned: Vec<&str> = content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("Record "))
+        .collect();
+    let cleaned = cleaned.join("\n");
+
+    let mut events = Vec::new();
+    for value in serde_json::Deserializer::from_str(&cleaned).into_iter::<Value>() {
+        match value? {
+            Value::Array(items) => events.extend(items.iter().filter_map(json_event)),
+            item => events.extend(json_event(&item)),
+        }
+    }
+    Ok(events)
+}
+
+/// An `evtx_dump` record (`Event.System`, `Event.EventData`) or a
+/// Winlogbeat document (`winlog.*`).
+fn json_event(value: &Value) -> Option<RawEvent> {
+    if let Some(event) = value.get("Event") {
+        let system = event.get("System")?;
+        let mut raw = RawEvent {
+            event_id: json_u32(system.get("EventID")?)?,
+            timestamp: system
+                .pointer("/TimeCreated/#attributes/SystemTime")
+                .and_then(Value::as_str)
+                .and_then(parse_time),
+            computer: system
+                .get("Computer")
+                .and_then(json_text)
+                .unwrap_or_default(),
+            channel: system
+                .get("Channel")
+                .and_then(json_text)
+                .unwrap_or_default(),
+            data: HashMap::new(),
+        };
+        if let Some(Value::Object(fields)) = event.get("EventData") {
+            collect_fields(&mut raw.data, fields);
+        }
+        if let Some(Value::Object(wrapper)) = event.get("UserData") {
+            for inner in wrapper.values() {
+                if let Value::Object(fields) = inner {
+                    collect_fields(&mut raw.data, fields);
+                }
+            }
+        }
+        return Some(raw);
+    }
+
+    let winlog = value.get("winlog")?;
+    let mut raw = RawEvent {
+        event_id: json_u32(winlog.get("event_id")?)?,
+        timestamp: value
+            .get("@timestamp")
+            .and_then(Value::as_str)
+            .and_then(parse_time),
+        computer: winlog
+            .get("computer_name")
+            .and_then(json_text)
+            .unwrap_or_default(),
+        channel: winlog
+            .get("channel")
+            .and_then(json_text)
+            .unwrap_or_default(),
+        data: HashMap::new(),
+    };
+    for key in ["event_data", "user_data"] {
+        if let Some(Value::Object(fields)) = winlog.get(key) {
+            collect_fields(&mut raw.data, fields);
+        }
+    }
+    Some(raw)
+}
+
+fn collect_fields(data: &mut HashMap<String, String>, fields: &Map<String, Value>) {
+    for (key, value) in fields {
+        if let Some(text) = json_text(value) {
+            data.insert(key.clone(), text);
+        }
+    }
+}
+
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Event ID as a number, a string, or `evtx_dump`'s `{"#text": n}` form
+/// (used when the element has a Qualifiers attribute).
+fn json_u32(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Object(_) => value.get("#text").and_then(json_u32),
+        _ => None,
+    }
+}
+
+// ─── Conversion ───────────────────────────────────────────────
+
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s.trim())
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Keep the event if it is one of the tracked kinds.
+///
+/// Logons Windows performs for itself (service and system logons, machine
+/// and built-in accounts) are dropped so the list shows people.
+fn convert_event(raw: &RawEvent) -> Option<IngestedHostEvent> {
+    let timestamp = raw.timestamp?;
+    let channel = raw.channel.to_ascii_lowercase();
+    // Exports without a channel field are taken at their word
+    let on = |expected: &str| channel.is_empty() || channel == expected;
+
+    let subject = || raw.account("SubjectDomainName", "SubjectUserName");
+    let target = || raw.account("TargetDomainName", "TargetUserName");
+
+    let (kind, user, detail) = match raw.event_id {
+        4624 if on("security") => {
+            if is_system_logon(raw) {
+                return None;
+            }
+            (HostEventKind::Logon, target(), None)
+        }
+        4625 if on("security") => {
+            let status = raw
+                .get("SubStatus")
+                .filter(|s| !s.eq_ignore_ascii_case("0x0"))
+                .or_else(|| raw.get("Status"));
+            (
+                HostEventKind::LogonFailure,
+                target(),
+                status.map(|s| format!("status {}", s)),
+            )
+        }
+        4648 if on("security") => {
+            let detail = target().map(|t| match raw.get("TargetServerName") {
+                Some(server) => format!("as {} on {}", t, server),
+                None => format!("as {}", t),
+            });
+            (HostEventKind::ExplicitCredentials, subject(), detail)
+        }
+        4697 if on("security") => (
+            HostEventKind::ServiceInstall,
+            subject(),
+            service_detail(raw.get("ServiceName"), raw.get("ServiceFileName")),
+        ),
+        7045 if on("system") => {
+            let detail = service_detail(raw.get("ServiceName"), raw.get("ImagePath")).map(|d| {
+                match raw.get("AccountName") {
+                    Some(account) => format!("{}, runs as {}", d, account),
+                    None => d,
+                }
+            });
+            (HostEventKind::ServiceInstall, None, detail)
+        }
+        4720 if on("security") => (HostEventKind::AccountCreated, subject(), target()),
+        1102 if on("security") => (
+            HostEventKind::LogCleared,
+            subject(),
+            Some("Security log".to_string()),
+        ),
+        104 if on("system") => (
+            HostEventKind::LogCleared,
+            subject(),
+            raw.get("Channel").map(|c| format!("{} log", c)),
+        ),
+        _ => return None,
+    };
+
+    let remote = matches!(
+        kind,
+        HostEventKind::Logon | HostEventKind::LogonFailure | HostEventKind::ExplicitCredentials
+    );
+    Some(IngestedHostEvent {
+        timestamp,
+        computer: raw.computer.clone(),
+        host_ip: None,
+        event_id: raw.event_id,
+        channel: raw.channel.clone(),
+        kind,
+        user,
+        logon_type: raw.get("LogonType").and_then(|t| t.parse().ok()),
+        remote_ip: remote
+            .then(|| raw.get("IpAddress").and_then(remote_address))
+            .flatten(),
+        remote_host: remote
+            .then(|| raw.get("WorkstationName").map(str::to_string))
+            .flatten(),
+        detail,
+        source: IngestSource::WindowsEventLog,
+    })
+}
+
+/// Service (5) and system (0) logons, and logons of machine (`NAME$`) or
+/// built-in accounts.
+fn is_system_logon(raw: &RawEvent) -> bool {
+    if matches!(raw.get("LogonType"), Some("0") | Some("5")) {
+        return true;
+    }
+    let Some(user) = raw.get("TargetUserName") else {
+        return true;
+    };
+    let upper = user.to_ascii_uppercase();
+    upper.ends_with('$')
+        || BUILTIN_ACCOUNTS.contains(&upper.as_str())
+        || upper.starts_with("DWM-")
+        || upper.starts_with("UMFD-")
+}
+
+fn service_detail(name: Option<&str>, image: Option<&str>) -> Option<String> {
+    match (name, image) {
+        (Some(name), Some(image)) => Some(format!("{} ({})", name, image)),
+        (Some(name), None) => Some(name.to_string()),
+        (None, Some(image)) => Some(image.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// The address a logon came from, without IPv4-mapped prefixes; loopback
+/// and unspecified addresses mean the logon was local.
+fn remote_address(value: &str) -> Option<String> {
+    let value = value.strip_prefix("::ffff:").unwrap_or(value);
+    let ip: IpAddr = value.parse().ok()?;
+    (!ip.is_loopback() && !ip.is_unspecified()).then(|| ip.to_string())
+}
+
+// ─── Tests ────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempPath;
+
+    const WEVTUTIL_XML: &str = r#"<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Microsoft-Windows-Security-Auditing'/><EventID>4624</EventID><TimeCreated SystemTime='2024-03-05T10:31:44.1234567Z'/><Channel>Security</Channel><Computer>EWS01.plant.local</Computer></System><EventData><Data Name='TargetUserName'>jdoe</Data><Data Name='TargetDomainName'>PLANT</Data><Data Name='LogonType'>10</Data><Data Name='WorkstationName'>VENDOR-LT</Data><Data Name='IpAddress'>::ffff:10.10.5.44</Data></EventData></Event>
+<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><EventID>4624</EventID><TimeCreated SystemTime='2024-03-05T10:30:00Z'/><Channel>Security</Channel><Computer>EWS01.plant.local</Computer></System><EventData><Data Name='TargetUserName'>SYSTEM</Data><Data Name='LogonType'>5</Data><Data Name='IpAddress'>-</Data></EventData></Event>
+<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><EventID Qualifiers='16384'>7045</EventID><TimeCreated SystemTime='2024-03-05T10:35:02.5Z'/><Channel>System</Channel><Computer>EWS01.plant.local</Computer></System><EventData><Data Name='ServiceName'>PSEXESVC</Data><Data Name='ImagePath'>%SystemRoot%\PSEXESVC.exe</Data><Data Name='AccountName'>LocalSystem</Data></EventData></Event>"#;
+
+    #[test]
+    fn test_parse_wevtutil_xml() {
+        let tmp = tempfile_with_content(WEVTUTIL_XML);
+        let result = parse_windows_events(&[tmp.as_ref()]).unwrap();
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        // The service logon of SYSTEM is dropped
+        assert_eq!(result.host_events.len(), 2);
+
+        let logon = &result.host_events[0];
+        assert_eq!(logon.kind, HostEventKind::Logon);
+        assert_eq!(logon.computer, "EWS01.plant.local");
+        assert_eq!(logon.user.as_deref(), Some("PLANT\\jdoe"));
+        assert_eq!(logon.logon_type, Some(10));
+        assert_eq!(logon.remote_ip.as_deref(), Some("10.10.5.44"));
+        assert_eq!(logon.remote_host.as_deref(), Some("VENDOR-LT"));
+
+        let install = &result.host_events[1];
+        assert_eq!(install.kind, HostEventKind::ServiceInstall);
+        assert_eq!(install.event_id, 7045);
+        assert_eq!(
+            install.detail.as_deref(),
+            Some("PSEXESVC (%SystemRoot%\\PSEXESVC.exe), runs as LocalSystem")
+        );
+
+        // The RDP client becomes an asset named by its workstation name
+        assert_eq!(result.assets.len(), 1);
+        assert_eq!(result.assets[0].ip_address, "10.10.5.44");
+        assert_eq!(result.assets[0].hostname.as_deref(), Some("VENDOR-LT"));
+    }
+
+    #[test]
+    fn test_parse_evtx_dump_and_winlogbeat_json() {
+        let evtx_dump = r##"Record 1
+{"Event":{"System":{"EventID":1102,"TimeCreated":{"#attributes":{"SystemTime":"2024-03-05T11:00:00.000000Z"}},"Channel":"Security","Computer":"HMI-02"},"UserData":{"LogFileCleared":{"SubjectUserName":"operator","SubjectDomainName":"HMI-02"}}}}
+Record 2
+{"Event":{"System":{"EventID":{"#attributes":{"Qualifiers":0},"#text":4688},"TimeCreated":{"#attributes":{"SystemTime":"2024-03-05T11:01:00Z"}},"Channel":"Security","Computer":"HMI-02"}}}"##;
+        let winlogbeat = r#"{"@timestamp":"2024-03-05T09:15:00.000Z","winlog":{"event_id":"4625","channel":"Security","computer_name":"HMI-02","event_data":{"TargetUserName":"admin","TargetDomainName":"HMI-02","LogonType":"3","Status":"0xc000006d","SubStatus":"0xc000006a","IpAddress":"10.10.5.44"}}}"#;
+
+        let a = tempfile_with_content(evtx_dump);
+        let b = tempfile_with_content(winlogbeat);
+        let result = parse_windows_events(&[a.as_ref(), b.as_ref()]).unwrap();
+
+        assert_eq!(result.files_processed, 2);
+        // 4688 (process creation) is not tracked; events come back in time order
+        assert_eq!(result.host_events.len(), 2);
+        let failure = &result.host_events[0];
+        assert_eq!(failure.kind, HostEventKind::LogonFailure);
+        assert_eq!(failure.detail.as_deref(), Some("status 0xc000006a"));
+        assert_eq!(failure.remote_ip.as_deref(), Some("10.10.5.44"));
+
+        let cleared = &result.host_events[1];
+        assert_eq!(cleared.kind, HostEventKind::LogCleared);
+        assert_eq!(cleared.user.as_deref(), Some("HMI-02\\operator"));
+    }
+
+    #[test]
+    fn test_unreadable_file_reported() {
+        let bad = tempfile_with_content("EventID,Time\n4624,yesterday");
+        let good = tempfile_with_content(WEVTUTIL_XML);
+        let result = parse_windows_events(&[bad.as_ref(), good.as_ref()]).unwrap();
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.files_processed, 1);
+        assert_eq!(result.host_events.len(), 2);
+    }
+
+    /// Helper: write content to a temp file and return the tempfile handle.
+    fn tempfile_with_content(content: &str) -> TempPath {
+        use std::io::Write;
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file.into_temp_path()
+    }
+}
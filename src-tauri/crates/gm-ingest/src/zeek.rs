@@ -45,6 +45,9 @@ pub fn parse_zeek_logs(paths: &[&Path]) -> Result<IngestResult, IngestError> {
         }
     }
 
+    // conn.log and the protocol logs describe the same flow under one uid
+    crate::correlate::coalesce_by_uid(&mut result.connections);
+
     // Deduplicate assets by IP
     deduplicate_assets(&mut result.assets);
 
@@ -170,6 +173,10 @@ fn parse_conn_record(record: &HashMap<String, &str>) -> Option<IngestedConnectio
         .unwrap_or_else(|| zeek_port_to_protocol(dst_port));
 
     let ts = record.get("ts").and_then(|t| parse_zeek_timestamp(t));
+    let end = ts.and_then(|start| {
+        let secs: f64 = record.get("duration")?.parse().ok()?;
+        Some(start + chrono::Duration::microseconds((secs * 1_000_000.0) as i64))
+    });
 
     // Zeek conn.log has orig_pkts/resp_pkts and orig_bytes/resp_bytes
     let orig_pkts: u64 = record
@@ -199,8 +206,9 @@ fn parse_conn_record(record: &HashMap<String, &str>) -> Option<IngestedConnectio
         packet_count: orig_pkts + resp_pkts,
         byte_count: orig_bytes + resp_bytes,
         first_seen: ts,
-        last_seen: ts,
+        last_seen: end.or(ts),
         source: IngestSource::Zeek,
+        uid: record.get("uid").map(|u| u.to_string()),
     })
 }
 
@@ -224,6 +232,7 @@ fn parse_modbus_record(record: &HashMap<String, &str>) -> Option<IngestedConnect
         first_seen: ts,
         last_seen: ts,
         source: IngestSource::Zeek,
+        uid: record.get("uid").map(|u| u.to_string()),
     })
 }
 
@@ -247,6 +256,7 @@ fn parse_dnp3_record(record: &HashMap<String, &str>) -> Option<IngestedConnectio
         first_seen: ts,
         last_seen: ts,
         source: IngestSource::Zeek,
+        uid: record.get("uid").map(|u| u.to_string()),
     })
 }
 
@@ -270,6 +280,7 @@ fn parse_s7comm_record(record: &HashMap<String, &str>) -> Option<IngestedConnect
         first_seen: ts,
         last_seen: ts,
         source: IngestSource::Zeek,
+        uid: record.get("uid").map(|u| u.to_string()),
     })
 }
 
@@ -409,6 +420,34 @@ mod tests {
         assert_eq!(result.connections[0].dst_port, 20000);
     }
 
+    #[test]
+    fn test_conn_and_protocol_logs_share_uid() {
+        let conn_log = "\
+#path\tconn
+#fields\tts\tuid\tid.orig_h\tid.orig_p\tid.resp_h\tid.resp_p\tproto\tservice\tduration\torig_pkts\tresp_pkts\torig_bytes\tresp_bytes
+#types\ttime\tstring\taddr\tport\taddr\tport\tenum\tstring\tinterval\tcount\tcount\tcount\tcount
+1609459200.000000\tCmod123\t192.168.1.10\t49152\t192.168.1.100\t502\ttcp\t-\t30.500000\t100\t100\t5000\t3000
+";
+        let modbus_log = "\
+#path\tmodbus
+#fields\tts\tuid\tid.orig_h\tid.orig_p\tid.resp_h\tid.resp_p\tfunc\texception
+#types\ttime\tstring\taddr\tport\taddr\tport\tstring\tstring
+1609459201.000000\tCmod123\t192.168.1.10\t49152\t192.168.1.100\t502\tREAD_HOLDING_REGISTERS\t-
+1609459202.000000\tCmod123\t192.168.1.10\t49152\t192.168.1.100\t502\tWRITE_SINGLE_REGISTER\t-
+";
+        let f1 = write_temp_file(conn_log);
+        let f2 = write_temp_file(modbus_log);
+        let result = parse_zeek_logs(&[f1.path(), f2.path()]).unwrap();
+
+        assert_eq!(result.connections.len(), 1);
+        let c = &result.connections[0];
+        assert_eq!(c.uid.as_deref(), Some("Cmod123"));
+        assert_eq!(c.protocol, "modbus");
+        assert_eq!(c.packet_count, 200);
+        let span = c.last_seen.unwrap() - c.first_seen.unwrap();
+        assert_eq!(span.num_milliseconds(), 30_500);
+    }
+
     #[test]
     fn test_asset_deduplication() {
         let mut assets = vec![
@@ -0,0 +1,422 @@
+//! Beckhoff ADS/AMS protocol parser.
+//!
+//! ADS (Automation Device Specification) is how TwinCAT components talk to
+//! each other: the XAE engineering environment, HMIs and OPC servers read
+//! and write PLC variables and change the runtime state through the AMS
+//! router on TCP 48898.
+//!
+//! Every message is an AMS/TCP header followed by a fixed AMS header:
+//!
+//! ```text
+//! AMS/TCP: [0..2]   reserved (0)
+//!          [2..6]   length of AMS header + data (LE)
+//! AMS:     [0..6]   target AMS NetID      [6..8]   target AMS port
+//!          [8..14]  source AMS NetID      [14..16] source AMS port
+//!          [16..18] command ID            [18..20] state flags
+//!          [20..24] data length           [24..28] error code
+//!          [28..32] invoke ID
+//! ```
+//!
+//! All integers are little-endian. State flag 0x0001 marks a response.
+//! AMS NetIDs are six bytes written like IPv4 with two extra octets
+//! (e.g. `5.23.20.78.1.1`); AMS ports select the service on the target
+//! (851 is the first TwinCAT 3 PLC runtime, 10000 the system service).
+
+use serde::{Deserialize, Serialize};
+
+/// ADS over TCP port (AMS router).
+pub const ADS_PORT: u16 = 48898;
+
+const AMS_TCP_HEADER_LEN: usize = 6;
+const AMS_HEADER_LEN: usize = 32;
+/// Response bit in the AMS state flags.
+const STATE_FLAG_RESPONSE: u16 = 0x0001;
+/// Upper bound on a plausible AMS message (ADS limits single requests to a few MB).
+const MAX_AMS_LEN: usize = 8 * 1024 * 1024;
+
+// ─── Enums ────────────────────────────────────────────────────────────────────
+
+/// Client/server role for an ADS device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdsRole {
+    /// Device is sending requests (engineering station, HMI, OPC server)
+    Client,
+    /// Device is answering on 48898 (TwinCAT runtime / AMS router)
+    Server,
+}
+
+/// ADS command ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdsCommand {
+    ReadDeviceInfo,
+    Read,
+    Write,
+    ReadState,
+    WriteControl,
+    AddDeviceNotification,
+    DeleteDeviceNotification,
+    DeviceNotification,
+    ReadWrite,
+    Other(u16),
+}
+
+impl AdsCommand {
+    pub fn from_u16(id: u16) -> Self {
+        match id {
+            1 => AdsCommand::ReadDeviceInfo,
+            2 => AdsCommand::Read,
+            3 => AdsCommand::Write,
+            4 => AdsCommand::ReadState,
+            5 => AdsCommand::WriteControl,
+            6 => AdsCommand::AddDeviceNotification,
+            7 => AdsCommand::DeleteDeviceNotification,
+            8 => AdsCommand::DeviceNotification,
+            9 => AdsCommand::ReadWrite,
+            other => AdsCommand::Other(other),
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            AdsCommand::ReadDeviceInfo => "Read Device Info".to_string(),
+            AdsCommand::Read => "Read".to_string(),
+            AdsCommand::Write => "Write".to_string(),
+            AdsCommand::ReadState => "Read State".to_string(),
+            AdsCommand::WriteControl => "Write Control".to_string(),
+            AdsCommand::AddDeviceNotification => "Add Device Notification".to_string(),
+            AdsCommand::DeleteDeviceNotification => "Delete Device Notification".to_string(),
+            AdsCommand::DeviceNotification => "Device Notification".to_string(),
+            AdsCommand::ReadWrite => "Read Write".to_string(),
+            AdsCommand::Other(id) => format!("Command {}", id),
+        }
+    }
+
+    /// Commands that change PLC data.
+    pub fn is_write(self) -> bool {
+        matches!(self, AdsCommand::Write | AdsCommand::ReadWrite)
+    }
+}
+
+/// ADS state of a device (as read with ReadState or set with WriteControl).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdsState {
+    Invalid,
+    Idle,
+    Reset,
+    Init,
+    Start,
+    Run,
+    Stop,
+    SaveConfig,
+    LoadConfig,
+    PowerFailure,
+    PowerGood,
+    Error,
+    Shutdown,
+    Suspend,
+    Resume,
+    Config,
+    Reconfig,
+    Other(u16),
+}
+
+impl AdsState {
+    pub fn from_u16(value: u16) -> Self {
+        match value {
+            0 => AdsState::Invalid,
+            1 => AdsState::Idle,
+            2 => AdsState::Reset,
+            3 => AdsState::Init,
+            4 => AdsState::Start,
+            5 => AdsState::Run,
+            6 => AdsState::Stop,
+            7 => AdsState::SaveConfig,
+            8 => AdsState::LoadConfig,
+            9 => AdsState::PowerFailure,
+            10 => AdsState::PowerGood,
+            11 => AdsState::Error,
+            12 => AdsState::Shutdown,
+            13 => AdsState::Suspend,
+            14 => AdsState::Resume,
+            15 => AdsState::Config,
+            16 => AdsState::Reconfig,
+            other => AdsState::Other(other),
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            AdsState::Invalid => "Invalid".to_string(),
+            AdsState::Idle => "Idle".to_string(),
+            AdsState::Reset => "Reset".to_string(),
+            AdsState::Init => "Init".to_string(),
+            AdsState::Start => "Start".to_string(),
+            AdsState::Run => "Run".to_string(),
+            AdsState::Stop => "Stop".to_string(),
+            AdsState::SaveConfig => "Save Config".to_string(),
+            AdsState::LoadConfig => "Load Config".to_string(),
+            AdsState::PowerFailure => "Power Failure".to_string(),
+            AdsState::PowerGood => "Power Good".to_string(),
+            AdsState::Error => "Error".to_string(),
+            AdsState::Shutdown => "Shutdown".to_string(),
+            AdsState::Suspend => "Suspend".to_string(),
+            AdsState::Resume => "Resume".to_string(),
+            AdsState::Config => "Config".to_string(),
+            AdsState::Reconfig => "Reconfig".to_string(),
+            AdsState::Other(value) => format!("State {}", value),
+        }
+    }
+}
+
+// ─── Structs ──────────────────────────────────────────────────────────────────
+
+/// AMS address: NetID plus port.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AmsAddr {
+    /// NetID in dotted form, e.g. "5.23.20.78.1.1"
+    pub net_id: String,
+    pub port: u16,
+}
+
+/// Device name and TwinCAT version from a Read Device Info response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdsDeviceInfo {
+    pub name: String,
+    pub major: u8,
+    pub minor: u8,
+    pub build: u16,
+}
+
+impl AdsDeviceInfo {
+    /// Version in TwinCAT's "major.minor.build" form.
+    pub fn version(&self) -> String {
+        format!("{}.{}.{}", self.major, self.minor, self.build)
+    }
+}
+
+/// One AMS message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmsMessage {
+    pub target: AmsAddr,
+    pub source: AmsAddr,
+    pub command: AdsCommand,
+    pub is_response: bool,
+    pub error_code: u32,
+    /// State requested by Write Control, or reported by Read State
+    pub ads_state: Option<AdsState>,
+    /// Identity from a successful Read Device Info response
+    pub device_info: Option<AdsDeviceInfo>,
+}
+
+/// Parsed ADS segment information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdsInfo {
+    pub role: AdsRole,
+    /// Complete AMS messages in this segment
+    pub messages: Vec<AmsMessage>,
+}
+
+// ─── Parser ───────────────────────────────────────────────────────────────────
+
+/// Attempt to parse an ADS/AMS segment.
+///
+/// Returns None unless the payload starts with a complete, well-formed
+/// AMS message. Trailing partial messages are ignored.
+pub fn parse(payload: &[u8], src_port: u16, dst_port: u16) -> Option<AdsInfo> {
+    let role = if src_port == ADS_PORT && dst_port != ADS_PORT {
+        AdsRole::Server
+    } else {
+        AdsRole::Client
+    };
+
+    let mut messages = Vec::new();
+    let mut pos = 0;
+    while pos + AMS_TCP_HEADER_LEN + AMS_HEADER_LEN <= payload.len() {
+        if payload[pos] != 0 || payload[pos + 1] != 0 {
+            break;
+        }
+        let len = le_u32(&payload[pos + 2..]) as usize;
+        if !(AMS_HEADER_LEN..=MAX_AMS_LEN).contains(&len) {
+            break;
+        }
+        let start = pos + AMS_TCP_HEADER_LEN;
+        let end = start + len;
+        if end > payload.len() {
+            break;
+        }
+        match parse_message(&payload[start..end]) {
+            Some(message) => messages.push(message),
+            None => break,
+        }
+        pos = end;
+    }
+
+    if messages.is_empty() {
+        return None;
+    }
+    Some(AdsInfo { role, messages })
+}
+
+fn parse_message(ams: &[u8]) -> Option<AmsMessage> {
+    let data_len = le_u32(&ams[20..]) as usize;
+    if AMS_HEADER_LEN + data_len != ams.len() {
+        return None;
+    }
+    let command_id = le_u16(&ams[16..]);
+    if command_id == 0 {
+        return None;
+    }
+    let command = AdsCommand::from_u16(command_id);
+    let is_response = le_u16(&ams[18..]) & STATE_FLAG_RESPONSE != 0;
+    let data = &ams[AMS_HEADER_LEN..];
+
+    let mut message = AmsMessage {
+        target: ams_addr(&ams[0..8]),
+        source: ams_addr(&ams[8..16]),
+        command,
+        is_response,
+        error_code: le_u32(&ams[24..]),
+        ads_state: None,
+        device_info: None,
+    };
+
+    // Responses start with a 4-byte ADS result code
+    let ok_response = is_response && data.len() >= 4 && le_u32(data) == 0;
+    match command {
+        AdsCommand::WriteControl if !is_response && data.len() >= 2 => {
+            message.ads_state = Some(AdsState::from_u16(le_u16(data)));
+        }
+        AdsCommand::ReadState if ok_response && data.len() >= 6 => {
+            message.ads_state = Some(AdsState::from_u16(le_u16(&data[4..])));
+        }
+        AdsCommand::ReadDeviceInfo if ok_response && data.len() >= 24 => {
+            let raw = &data[8..24];
+            let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+            let name = String::from_utf8_lossy(&raw[..end]).trim().to_string();
+            message.device_info = Some(AdsDeviceInfo {
+                name,
+                major: data[4],
+                minor: data[5],
+                build: le_u16(&data[6..]),
+            });
+        }
+        _ => {}
+    }
+
+    Some(message)
+}
+
+fn ams_addr(bytes: &[u8]) -> AmsAddr {
+    AmsAddr {
+        net_id: bytes[..6]
+            .iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<_>>()
+            .join("."),
+        port: le_u16(&bytes[6..]),
+    }
+}
+
+fn le_u16(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLC: [u8; 6] = [5, 23, 20, 78, 1, 1];
+    const XAE: [u8; 6] = [192, 168, 1, 20, 1, 1];
+
+    fn message(
+        target: ([u8; 6], u16),
+        source: ([u8; 6], u16),
+        command: u16,
+        flags: u16,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut ams = Vec::new();
+        ams.extend_from_slice(&target.0);
+        ams.extend_from_slice(&target.1.to_le_bytes());
+        ams.extend_from_slice(&source.0);
+        ams.extend_from_slice(&source.1.to_le_bytes());
+        ams.extend_from_slice(&command.to_le_bytes());
+        ams.extend_from_slice(&flags.to_le_bytes());
+        ams.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        ams.extend_from_slice(&0u32.to_le_bytes());
+        ams.extend_from_slice(&1u32.to_le_bytes());
+        ams.extend_from_slice(data);
+
+        let mut frame = vec![0, 0];
+        frame.extend_from_slice(&(ams.len() as u32).to_le_bytes());
+        frame.extend(ams);
+        frame
+    }
+
+    #[test]
+    fn test_write_control_request() {
+        // Stop the PLC runtime on port 851: ADS state 6, device state 0
+        let frame = message(
+            (PLC, 851),
+            (XAE, 32905),
+            5,
+            0x0004,
+            &[6, 0, 0, 0, 0, 0, 0, 0],
+        );
+        let info = parse(&frame, 50123, ADS_PORT).unwrap();
+        assert_eq!(info.role, AdsRole::Client);
+        let msg = &info.messages[0];
+        assert_eq!(msg.target.net_id, "5.23.20.78.1.1");
+        assert_eq!(msg.target.port, 851);
+        assert_eq!(msg.source.net_id, "192.168.1.20.1.1");
+        assert_eq!(msg.command, AdsCommand::WriteControl);
+        assert!(!msg.is_response);
+        assert_eq!(msg.ads_state, Some(AdsState::Stop));
+    }
+
+    #[test]
+    fn test_device_info_and_state_responses() {
+        let mut data = vec![0, 0, 0, 0, 3, 1];
+        data.extend_from_slice(&4024u16.to_le_bytes());
+        let mut name = [0u8; 16];
+        name[..9].copy_from_slice(b"Plc30 App");
+        data.extend_from_slice(&name);
+        let mut segment = message((XAE, 32905), (PLC, 851), 1, 0x0005, &data);
+        // Second message in the same segment: Read State → Run
+        segment.extend(message(
+            (XAE, 32905),
+            (PLC, 851),
+            4,
+            0x0005,
+            &[0, 0, 0, 0, 5, 0, 0, 0],
+        ));
+
+        let info = parse(&segment, ADS_PORT, 50123).unwrap();
+        assert_eq!(info.role, AdsRole::Server);
+        assert_eq!(info.messages.len(), 2);
+        let device = info.messages[0].device_info.as_ref().unwrap();
+        assert_eq!(device.name, "Plc30 App");
+        assert_eq!(device.version(), "3.1.4024");
+        assert_eq!(info.messages[1].ads_state, Some(AdsState::Run));
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        assert!(parse(&[], 50123, ADS_PORT).is_none());
+        // Data length disagrees with the AMS/TCP length
+        let mut frame = message((PLC, 851), (XAE, 32905), 2, 0x0004, &[0; 12]);
+        frame[26] = 0xFF;
+        assert!(parse(&frame, 50123, ADS_PORT).is_none());
+        // Non-zero reserved bytes
+        let mut frame = message((PLC, 851), (XAE, 32905), 2, 0x0004, &[0; 12]);
+        frame[0] = 1;
+        assert!(parse(&frame, 50123, ADS_PORT).is_none());
+    }
+}
@@ -0,0 +1,237 @@
+//! ARP (Address Resolution Protocol) parser.
+//!
+//! Every IPv4 host on a segment ARPs for its gateway and peers, so a SPAN
+//! port sees the IP → MAC binding of each local host without querying a
+//! router's ARP cache. The sender fields of a request or reply are the
+//! binding the sender vouches for; the target fields of a request are only
+//! a question.
+//!
+//! ARP rides directly in Ethernet (EtherType 0x0806). `gm-capture::parsing`
+//! intercepts the frame and hands over everything after the EtherType, with
+//! `"arp:<mac>"` sentinels in place of IP addresses. For IPv4 over Ethernet
+//! the packet is 28 bytes:
+//!
+//! ```text
+//! [0..2]   hardware type   1 (Ethernet)
+//! [2..4]   protocol type   0x0800 (IPv4)
+//! [4]      hardware len    6
+//! [5]      protocol len    4
+//! [6..8]   operation       1 = request, 2 = reply
+//! [8..14]  sender MAC
+//! [14..18] sender IP
+//! [18..24] target MAC
+//! [24..28] target IP
+//! ```
+//!
+//! Two special forms (RFC 5227) matter for analysis:
+//! - a **probe** has sender IP 0.0.0.0 — a host checking whether an address
+//!   is free before using it, which binds nothing;
+//! - a **gratuitous** ARP (announcement) has sender IP == target IP — a host
+//!   claiming an address, at boot or on failover. One claiming an address
+//!   another MAC already holds is a duplicate IP or an ARP spoof.
+//!
+//! Reference: RFC 826, RFC 5227
+
+use serde::{Deserialize, Serialize};
+
+/// ARP EtherType.
+pub const ARP_ETHERTYPE: u16 = 0x0806;
+
+/// Length of an IPv4-over-Ethernet ARP packet.
+const ARP_LEN: usize = 28;
+
+/// Most MACs remembered per address, so a spoofing tool cycling random
+/// MACs cannot grow a binding without bound.
+const MAX_CLAIMS: usize = 16;
+
+/// ARP operation code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArpOperation {
+    Request,
+    Reply,
+}
+
+/// Fields of one IPv4-over-Ethernet ARP packet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArpInfo {
+    pub operation: ArpOperation,
+    pub sender_mac: String,
+    pub sender_ip: String,
+    /// All zeros in a request
+    pub target_mac: String,
+    pub target_ip: String,
+}
+
+impl ArpInfo {
+    /// An address probe (sender IP 0.0.0.0); its sender binds no address.
+    pub fn is_probe(&self) -> bool {
+        self.sender_ip == "0.0.0.0"
+    }
+
+    /// A gratuitous ARP: the sender announcing its own address.
+    pub fn is_gratuitous(&self) -> bool {
+        !self.is_probe() && self.sender_ip == self.target_ip
+    }
+}
+
+/// Everything ARP has shown about one IPv4 address.
+///
+/// More than one MAC claiming the address means a duplicate IP, an ARP
+/// spoof, or a failover pair sharing a service address; analysis decides
+/// which is worth raising.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArpBinding {
+    pub ip_address: String,
+    /// Every MAC that has claimed the address, in the order first seen
+    pub mac_addresses: Vec<String>,
+    /// VLAN the first claim was seen on
+    pub vlan: Option<u16>,
+    /// ARP packets that carried this binding as their sender
+    pub packet_count: u64,
+    /// Gratuitous ARPs announcing the address
+    pub gratuitous_count: u64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+impl ArpBinding {
+    /// Start tracking an address from the first packet that claims it.
+    pub fn new(info: &ArpInfo, vlan: Option<u16>, timestamp: &str) -> Self {
+        Self {
+            ip_address: info.sender_ip.clone(),
+            mac_addresses: vec![info.sender_mac.clone()],
+            vlan,
+            packet_count: 1,
+            gratuitous_count: u64::from(info.is_gratuitous()),
+            first_seen: timestamp.to_string(),
+            last_seen: timestamp.to_string(),
+        }
+    }
+
+    /// Update the binding with a later packet from a sender claiming the
+    /// same address.
+    pub fn observe(&mut self, info: &ArpInfo, timestamp: &str) {
+        self.packet_count += 1;
+        if info.is_gratuitous() {
+            self.gratuitous_count += 1;
+        }
+        if self.mac_addresses.len() < MAX_CLAIMS && !self.mac_addresses.contains(&info.sender_mac) {
+            self.mac_addresses.push(info.sender_mac.clone());
+        }
+        self.last_seen = timestamp.to_string();
+    }
+
+    /// The MAC that first claimed the address.
+    pub fn mac_address(&self) -> &str {
+        &self.mac_addresses[0]
+    }
+
+    /// True if more than one MAC has claimed the address.
+    pub fn is_duplicate(&self) -> bool {
+        self.mac_addresses.len() > 1
+    }
+}
+
+/// Parse an ARP packet (payload starting after the EtherType).
+///
+/// Returns None for anything other than IPv4 over Ethernet, unknown
+/// operations (RARP, InARP) and truncated packets.
+pub fn parse(payload: &[u8]) -> Option<ArpInfo> {
+    if payload.len() < ARP_LEN || payload[0..6] != [0x00, 0x01, 0x08, 0x00, 6, 4] {
+        return None;
+    }
+    let operation = match u16::from_be_bytes([payload[6], payload[7]]) {
+        1 => ArpOperation::Request,
+        2 => ArpOperation::Reply,
+        _ => return None,
+    };
+    Some(ArpInfo {
+        operation,
+        sender_mac: format_mac(&payload[8..14]),
+        sender_ip: format_ipv4(&payload[14..18]),
+        target_mac: format_mac(&payload[18..24]),
+        target_ip: format_ipv4(&payload[24..28]),
+    })
+}
+
+fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn format_ipv4(bytes: &[u8]) -> String {
+    format!("{}.{}.{}.{}", bytes[0], bytes[1], bytes[2], bytes[3])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    fn packet(operation: u8, sender_ip: [u8; 4], target_ip: [u8; 4]) -> Vec<u8> {
+        let mut p = vec![
+            0x00, 0x01, 0x08, 0x00, 6, 4,       // Ethernet / IPv4
+            0x00, operation,
+            0x00, 0x1B, 0x1B, 0x12, 0x34, 0x56, // sender MAC
+        ];
+        p.extend_from_slice(&sender_ip);
+        p.extend_from_slice(&[0; 6]);           // target MAC
+        p.extend_from_slice(&target_ip);
+        p
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let info = parse(&packet(1, [10, 0, 0, 5], [10, 0, 0, 1])).unwrap();
+        assert_eq!(info.operation, ArpOperation::Request);
+        assert_eq!(info.sender_mac, "00:1b:1b:12:34:56");
+        assert_eq!(info.sender_ip, "10.0.0.5");
+        assert_eq!(info.target_mac, "00:00:00:00:00:00");
+        assert_eq!(info.target_ip, "10.0.0.1");
+        assert!(!info.is_gratuitous());
+        assert!(!info.is_probe());
+    }
+
+    #[test]
+    fn test_probe_and_gratuitous() {
+        let probe = parse(&packet(1, [0, 0, 0, 0], [10, 0, 0, 5])).unwrap();
+        assert!(probe.is_probe());
+        assert!(!probe.is_gratuitous());
+
+        let announcement = parse(&packet(2, [10, 0, 0, 5], [10, 0, 0, 5])).unwrap();
+        assert_eq!(announcement.operation, ArpOperation::Reply);
+        assert!(announcement.is_gratuitous());
+    }
+
+    #[test]
+    fn test_rejects_other_packets() {
+        assert!(parse(&packet(1, [10, 0, 0, 5], [10, 0, 0, 1])[..27]).is_none());
+        // RARP request
+        assert!(parse(&packet(3, [10, 0, 0, 5], [10, 0, 0, 1])).is_none());
+        // IPv6 protocol type
+        let mut ipv6 = packet(1, [10, 0, 0, 5], [10, 0, 0, 1]);
+        ipv6[2..4].copy_from_slice(&[0x86, 0xDD]);
+        assert!(parse(&ipv6).is_none());
+    }
+
+    #[test]
+    fn test_binding_tracks_duplicate_claims() {
+        let first = parse(&packet(2, [10, 0, 0, 5], [10, 0, 0, 1])).unwrap();
+        let mut binding = ArpBinding::new(&first, Some(10), "t0");
+        binding.observe(&first, "t1");
+        assert!(!binding.is_duplicate());
+
+        let mut other = parse(&packet(1, [10, 0, 0, 5], [10, 0, 0, 5])).unwrap();
+        other.sender_mac = "00:0c:29:aa:bb:cc".to_string();
+        binding.observe(&other, "t2");
+        assert!(binding.is_duplicate());
+        assert_eq!(binding.mac_address(), "00:1b:1b:12:34:56");
+        assert_eq!(binding.packet_count, 3);
+        assert_eq!(binding.gratuitous_count, 1);
+        assert_eq!(binding.last_seen, "t2");
+    }
+}
@@ -1,8 +1,9 @@
 //! BACnet/IP (ASHRAE 135 Annex J) deep protocol parser.
 //!
 //! Parses BVLCI → NPDU → APDU protocol layers for BACnet over UDP.
-//! Extracts device identity from I-Am broadcasts, classifies services,
-//! and detects client/server roles.
+//! Extracts device identity from I-Am broadcasts and from ReadProperty /
+//! ReadPropertyMultiple acknowledgements of Device object properties,
+//! classifies services, and detects client/server roles.
 //!
 //! Reference: ASHRAE 135-2016, BACnet/IP (Annex J)
 //! Port: 47808 UDP (0xBAC0)
@@ -172,6 +173,89 @@ pub struct BacnetIAm {
     pub vendor_id: u16,
 }
 
+/// Identity properties of a Device object, read from ReadProperty and
+/// ReadPropertyMultiple acknowledgements. A single packet usually carries
+/// only some of them; [`merge`](Self::merge) accumulates them per device.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BacnetDeviceProperties {
+    /// Device object instance number
+    pub device_instance: Option<u32>,
+    /// object-name (77)
+    pub object_name: Option<String>,
+    /// vendor-name (121)
+    pub vendor_name: Option<String>,
+    /// vendor-identifier (120)
+    pub vendor_id: Option<u16>,
+    /// model-name (70)
+    pub model_name: Option<String>,
+    /// firmware-revision (44)
+    pub firmware_revision: Option<String>,
+    /// application-software-version (12)
+    pub application_software_version: Option<String>,
+}
+
+impl BacnetDeviceProperties {
+    /// Overlay every property present in `other` onto `self`.
+    pub fn merge(&mut self, other: BacnetDeviceProperties) {
+        self.device_instance = other.device_instance.or(self.device_instance);
+        self.object_name = other.object_name.or(self.object_name.take());
+        self.vendor_name = other.vendor_name.or(self.vendor_name.take());
+        self.vendor_id = other.vendor_id.or(self.vendor_id);
+        self.model_name = other.model_name.or(self.model_name.take());
+        self.firmware_revision = other.firmware_revision.or(self.firmware_revision.take());
+        self.application_software_version = other
+            .application_software_version
+            .or(self.application_software_version.take());
+    }
+
+    /// Whether any property beyond the instance number was decoded.
+    pub fn has_identity(&self) -> bool {
+        self.object_name.is_some()
+            || self.vendor_name.is_some()
+            || self.vendor_id.is_some()
+            || self.model_name.is_some()
+            || self.firmware_revision.is_some()
+            || self.application_software_version.is_some()
+    }
+
+    /// Store a decoded property value if it is one of the identity properties.
+    fn apply_property(&mut self, property_id: u32, value: &Tag) {
+        let TagKind::Value(bytes) = value.kind else {
+            return;
+        };
+        if value.context {
+            return;
+        }
+        match (property_id, value.number) {
+            (PROP_VENDOR_IDENTIFIER, 2) if !bytes.is_empty() => {
+                self.vendor_id = Some(read_uint_be(bytes) as u16);
+            }
+            (_, 7) => {
+                let slot = match property_id {
+                    PROP_OBJECT_NAME => &mut self.object_name,
+                    PROP_VENDOR_NAME => &mut self.vendor_name,
+                    PROP_MODEL_NAME => &mut self.model_name,
+                    PROP_FIRMWARE_REVISION => &mut self.firmware_revision,
+                    PROP_APPLICATION_SOFTWARE_VERSION => &mut self.application_software_version,
+                    _ => return,
+                };
+                if let Some(text) = decode_character_string(bytes) {
+                    *slot = Some(text);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Device object property identifiers (ASHRAE 135 clause 21, BACnetPropertyIdentifier)
+const PROP_APPLICATION_SOFTWARE_VERSION: u32 = 12;
+const PROP_FIRMWARE_REVISION: u32 = 44;
+const PROP_MODEL_NAME: u32 = 70;
+const PROP_OBJECT_NAME: u32 = 77;
+const PROP_VENDOR_IDENTIFIER: u32 = 120;
+const PROP_VENDOR_NAME: u32 = 121;
+
 /// Parsed BACnet/IP packet information.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BacnetInfo {
@@ -193,6 +277,9 @@ pub struct BacnetInfo {
     pub object_instance: Option<u32>,
     /// Property ID being accessed (if extractable)
     pub property_id: Option<u32>,
+    /// Device object identity properties carried by a ReadProperty or
+    /// ReadPropertyMultiple acknowledgement
+    pub device_properties: Option<BacnetDeviceProperties>,
     /// Detected role
     pub role: BacnetRole,
 }
@@ -228,6 +315,165 @@ fn read_tag(data: &[u8], offset: usize) -> Option<(u8, u8, &[u8], usize)> {
     Some((tag_number, tag_class, value, value_start + value_len))
 }
 
+/// Content of a fully decoded tag.
+enum TagKind<'a> {
+    /// Primitive value bytes (empty for an application Boolean, whose value
+    /// lives in the length field)
+    Value(&'a [u8]),
+    /// Opening tag of a constructed context value
+    Opening,
+    /// Closing tag of a constructed context value
+    Closing,
+}
+
+/// A BACnet tag decoded per ASHRAE 135 clause 20.2.1.
+struct Tag<'a> {
+    number: u8,
+    context: bool,
+    kind: TagKind<'a>,
+    next: usize,
+}
+
+impl<'a> Tag<'a> {
+    /// Value bytes if this is primitive context tag `number`.
+    fn context_value(&self, number: u8) -> Option<&'a [u8]> {
+        match self.kind {
+            TagKind::Value(v) if self.context && self.number == number => Some(v),
+            _ => None,
+        }
+    }
+
+    fn is_opening(&self, number: u8) -> bool {
+        matches!(self.kind, TagKind::Opening) && self.number == number
+    }
+
+    fn is_closing(&self, number: u8) -> bool {
+        matches!(self.kind, TagKind::Closing) && self.number == number
+    }
+}
+
+/// Read a tag including the encodings [`read_tag`] does not need for
+/// request headers: extended tag numbers, 2- and 4-byte extended lengths,
+/// opening/closing tags and application Booleans.
+fn read_full_tag(data: &[u8], offset: usize) -> Option<Tag<'_>> {
+    let first = *data.get(offset)?;
+    let context = first & 0x08 != 0;
+    let lvt = first & 0x07;
+    let mut pos = offset + 1;
+    let mut number = first >> 4;
+    if number == 0x0F {
+        number = *data.get(pos)?;
+        pos += 1;
+    }
+
+    if context {
+        match lvt {
+            6 => {
+                return Some(Tag {
+                    number,
+                    context,
+                    kind: TagKind::Opening,
+                    next: pos,
+                })
+            }
+            7 => {
+                return Some(Tag {
+                    number,
+                    context,
+                    kind: TagKind::Closing,
+                    next: pos,
+                })
+            }
+            _ => {}
+        }
+    } else if number == 1 {
+        return Some(Tag {
+            number,
+            context,
+            kind: TagKind::Value(&[]),
+            next: pos,
+        });
+    }
+
+    let len = if lvt == 5 {
+        let ext = *data.get(pos)?;
+        pos += 1;
+        let width = match ext {
+            254 => 2,
+            255 => 4,
+            n => {
+                let len = n as usize;
+                let value = data.get(pos..pos.checked_add(len)?)?;
+                return Some(Tag {
+                    number,
+                    context,
+                    kind: TagKind::Value(value),
+                    next: pos + len,
+                });
+            }
+        };
+        let len = read_uint_be(data.get(pos..pos + width)?) as usize;
+        pos += width;
+        len
+    } else {
+        lvt as usize
+    };
+
+    let value = data.get(pos..pos.checked_add(len)?)?;
+    Some(Tag {
+        number,
+        context,
+        kind: TagKind::Value(value),
+        next: pos + len,
+    })
+}
+
+/// Skip past the closing tag matching an already-consumed opening tag
+/// `number`, stepping over any nested constructed values.
+fn skip_to_closing(data: &[u8], mut offset: usize, number: u8) -> Option<usize> {
+    let mut depth = 0usize;
+    loop {
+        let tag = read_full_tag(data, offset)?;
+        offset = tag.next;
+        match tag.kind {
+            TagKind::Opening => depth += 1,
+            TagKind::Closing if depth == 0 => return (tag.number == number).then_some(offset),
+            TagKind::Closing => depth -= 1,
+            TagKind::Value(_) => {}
+        }
+    }
+}
+
+/// Decode a CharacterString value (leading character-set byte).
+///
+/// Handles ANSI X3.4/UTF-8 (0), UCS-2 big-endian (4) and ISO 8859-1 (5);
+/// DBCS, JIS and UCS-4 strings are rare enough in the field to skip.
+fn decode_character_string(value: &[u8]) -> Option<String> {
+    let (&charset, text) = value.split_first()?;
+    let decoded: String = match charset {
+        0 => String::from_utf8_lossy(text).into_owned(),
+        4 => char::decode_utf16(
+            text.chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]])),
+        )
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect(),
+        5 => text.iter().map(|&b| char::from(b)).collect(),
+        _ => return None,
+    };
+    // Fixed-width fields are often NUL- or space-padded
+    let trimmed = decoded.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Split a 32-bit Object Identifier into object type and instance.
+fn split_object_id(oid: u32) -> (BacnetObjectType, u32) {
+    (
+        BacnetObjectType::from_code(((oid >> 22) & 0x3FF) as u16),
+        oid & 0x3F_FFFF,
+    )
+}
+
 /// Read a big-endian unsigned integer from `bytes` (up to 4 bytes).
 fn read_uint_be(bytes: &[u8]) -> u32 {
     let mut result = 0u32;
@@ -356,6 +602,138 @@ fn parse_confirmed_object_property(
     (object_type, object_instance, property_id)
 }
 
+/// Decode a ReadProperty-ACK body starting at `offset`.
+///
+/// Encoding: context 0 Object Identifier, context 1 Property Identifier,
+/// optional context 2 array index, then the value between opening and
+/// closing tag 3. Returns `(object_identifier, property_id, device_properties)`;
+/// device properties are only decoded for a whole (non-indexed) property of
+/// a Device object.
+fn parse_read_property_ack(
+    payload: &[u8],
+    offset: usize,
+) -> Option<(u32, u32, Option<BacnetDeviceProperties>)> {
+    let oid_tag = read_full_tag(payload, offset)?;
+    let oid = read_uint_be(oid_tag.context_value(0).filter(|v| v.len() == 4)?);
+    let prop_tag = read_full_tag(payload, oid_tag.next)?;
+    let property_id = read_uint_be(prop_tag.context_value(1).filter(|v| !v.is_empty())?);
+
+    let mut tag = read_full_tag(payload, prop_tag.next)?;
+    let indexed = tag.context_value(2).is_some();
+    if indexed {
+        tag = read_full_tag(payload, tag.next)?;
+    }
+
+    let (object_type, instance) = split_object_id(oid);
+    let mut device = None;
+    if tag.is_opening(3) && !indexed && object_type == BacnetObjectType::Device {
+        if let Some(value) = read_full_tag(payload, tag.next) {
+            let mut props = BacnetDeviceProperties {
+                device_instance: Some(instance),
+                ..Default::default()
+            };
+            props.apply_property(property_id, &value);
+            device = Some(props).filter(BacnetDeviceProperties::has_identity);
+        }
+    }
+
+    Some((oid, property_id, device))
+}
+
+/// Decode a ReadPropertyMultiple-ACK body starting at `offset`.
+///
+/// The body is a list of ReadAccessResults: context 0 Object Identifier,
+/// then between opening and closing tag 1 a run of context 2 Property
+/// Identifier, optional context 3 array index, and either the value
+/// (opening/closing 4) or a property access error (opening/closing 5).
+/// Decoding is best effort — properties read before a malformed or
+/// truncated element are kept. Returns the first object identifier and the
+/// identity properties of the first Device object in the list.
+fn parse_read_property_multiple_ack(
+    payload: &[u8],
+    mut offset: usize,
+) -> (Option<u32>, Option<BacnetDeviceProperties>) {
+    let mut first_oid = None;
+    let mut device: Option<BacnetDeviceProperties> = None;
+
+    'results: while let Some(oid_tag) = read_full_tag(payload, offset) {
+        let Some(oid) = oid_tag
+            .context_value(0)
+            .filter(|v| v.len() == 4)
+            .map(read_uint_be)
+        else {
+            break;
+        };
+        first_oid.get_or_insert(oid);
+        let (object_type, instance) = split_object_id(oid);
+        let harvest = object_type == BacnetObjectType::Device
+            && device
+                .as_ref()
+                .and_then(|d| d.device_instance)
+                .unwrap_or(instance)
+                == instance;
+
+        match read_full_tag(payload, oid_tag.next) {
+            Some(open) if open.is_opening(1) => offset = open.next,
+            _ => break,
+        }
+
+        loop {
+            let Some(tag) = read_full_tag(payload, offset) else {
+                break 'results;
+            };
+            if tag.is_closing(1) {
+                offset = tag.next;
+                break;
+            }
+            let Some(property_id) = tag
+                .context_value(2)
+                .filter(|v| !v.is_empty())
+                .map(read_uint_be)
+            else {
+                break 'results;
+            };
+            let Some(mut tag) = read_full_tag(payload, tag.next) else {
+                break 'results;
+            };
+            let indexed = tag.context_value(3).is_some();
+            if indexed {
+                match read_full_tag(payload, tag.next) {
+                    Some(t) => tag = t,
+                    None => break 'results,
+                }
+            }
+
+            let close = if tag.is_opening(4) {
+                if harvest && !indexed {
+                    if let Some(value) = read_full_tag(payload, tag.next) {
+                        device
+                            .get_or_insert_with(|| BacnetDeviceProperties {
+                                device_instance: Some(instance),
+                                ..Default::default()
+                            })
+                            .apply_property(property_id, &value);
+                    }
+                }
+                4
+            } else if tag.is_opening(5) {
+                5
+            } else {
+                break 'results;
+            };
+            match skip_to_closing(payload, tag.next, close) {
+                Some(next) => offset = next,
+                None => break 'results,
+            }
+        }
+    }
+
+    (
+        first_oid,
+        device.filter(BacnetDeviceProperties::has_identity),
+    )
+}
+
 /// Attempt to parse a BACnet/IP UDP payload (BVLCI + NPDU + APDU).
 ///
 /// Returns `None` if the payload is too short (< 6 bytes) or the BVLCI
@@ -422,6 +800,7 @@ pub fn parse(payload: &[u8]) -> Option<BacnetInfo> {
             object_type: None,
             object_instance: None,
             property_id: None,
+            device_properties: None,
             role: BacnetRole::Unknown,
         });
     }
@@ -430,6 +809,7 @@ pub fn parse(payload: &[u8]) -> Option<BacnetInfo> {
     let apdu_first = *payload.get(apdu_start)?;
     let pdu_type = BacnetPduType::from_nibble((apdu_first >> 4) & 0x0F);
 
+    let mut device_properties = None;
     let (service, iam, object_type, object_instance, property_id, role) = match pdu_type {
         BacnetPduType::UnconfirmedRequest => {
             // [apdu_start+0]: PDU type nibble + flags
@@ -485,9 +865,56 @@ pub fn parse(payload: &[u8]) -> Option<BacnetInfo> {
             )
         }
 
-        // Responses (Ack, Error, Reject, Abort) originate from the server
+        BacnetPduType::ComplexAck => {
+            // [apdu_start+0]: PDU type nibble + SEG/MOR flags
+            // [apdu_start+1]: invoke-id
+            // segmented only: [+2] sequence number, [+3] proposed window size
+            // then the service choice and the service ACK
+            let segmented = apdu_first & 0x08 != 0;
+            let service_pos = apdu_start + if segmented { 4 } else { 2 };
+            match payload.get(service_pos) {
+                Some(&service_byte) => {
+                    let svc = map_confirmed_service(service_byte);
+                    // Later segments continue mid-value; only the first one
+                    // starts at a tag boundary
+                    let first_segment = !segmented || payload.get(apdu_start + 2) == Some(&0);
+                    let body = service_pos + 1;
+                    let (oid, prop_id) = match svc {
+                        BacnetService::ReadProperty if first_segment => {
+                            match parse_read_property_ack(payload, body) {
+                                Some((oid, prop_id, props)) => {
+                                    device_properties = props;
+                                    (Some(oid), Some(prop_id))
+                                }
+                                None => (None, None),
+                            }
+                        }
+                        BacnetService::ReadPropertyMultiple if first_segment => {
+                            let (oid, props) = parse_read_property_multiple_ack(payload, body);
+                            device_properties = props;
+                            (oid, None)
+                        }
+                        _ => (None, None),
+                    };
+                    let (obj_type, obj_inst) = match oid.map(split_object_id) {
+                        Some((t, i)) => (Some(t), Some(i)),
+                        None => (None, None),
+                    };
+                    (
+                        Some(svc),
+                        None,
+                        obj_type,
+                        obj_inst,
+                        prop_id,
+                        BacnetRole::Server,
+                    )
+                }
+                None => (None, None, None, None, None, BacnetRole::Server),
+            }
+        }
+
+        // Other responses (SimpleAck, Error, Reject, Abort) originate from the server
         BacnetPduType::SimpleAck
-        | BacnetPduType::ComplexAck
         | BacnetPduType::Error
         | BacnetPduType::Reject
         | BacnetPduType::Abort => (None, None, None, None, None, BacnetRole::Server),
@@ -505,6 +932,7 @@ pub fn parse(payload: &[u8]) -> Option<BacnetInfo> {
         object_type,
         object_instance,
         property_id,
+        device_properties,
         role,
     })
 }
@@ -613,6 +1041,118 @@ mod tests {
         assert_eq!(info.property_id, Some(85));
     }
 
+    #[test]
+    fn test_read_property_ack_object_name() {
+        // ComplexAck for ReadProperty of Device 1001 object-name = "AHU-1"
+        let payload: Vec<u8> = vec![
+            0x81, 0x0A, 0x00, 0x19, // BVLCI: OriginalUnicast, total=25
+            0x01, 0x00, // NPDU: version=1, control=0
+            0x30, // PDU type=3 (ComplexAck), unsegmented
+            0x01, // invoke_id = 1
+            0x0C, // Service ACK: ReadProperty
+            0x0C, 0x02, 0x00, 0x03, 0xE9, // Context 0: Device(8), instance=1001
+            0x19, 0x4D, // Context 1: Property 77 = object-name
+            0x3E, // Opening tag 3
+            0x75, 0x06, // App tag 7 (CharacterString), extended length 6
+            0x00, b'A', b'H', b'U', b'-', b'1', // UTF-8 "AHU-1"
+            0x3F, // Closing tag 3
+        ];
+
+        let info = parse(&payload).unwrap();
+        assert!(matches!(info.pdu_type, Some(BacnetPduType::ComplexAck)));
+        assert!(matches!(info.service, Some(BacnetService::ReadProperty)));
+        assert!(matches!(info.role, BacnetRole::Server));
+        assert!(matches!(info.object_type, Some(BacnetObjectType::Device)));
+        assert_eq!(info.object_instance, Some(1001));
+        assert_eq!(info.property_id, Some(77));
+        let props = info.device_properties.unwrap();
+        assert_eq!(props.device_instance, Some(1001));
+        assert_eq!(props.object_name.as_deref(), Some("AHU-1"));
+        assert!(props.model_name.is_none());
+    }
+
+    #[test]
+    fn test_read_property_multiple_ack_device_identity() {
+        // ComplexAck for ReadPropertyMultiple of Device 1001: vendor-name,
+        // model-name, vendor-identifier, and an error for firmware-revision
+        let payload: Vec<u8> = vec![
+            0x81, 0x0A, 0x00, 0x36, // BVLCI: OriginalUnicast
+            0x01, 0x00, // NPDU
+            0x30, 0x02, // ComplexAck, invoke_id = 2
+            0x0E, // Service ACK: ReadPropertyMultiple
+            0x0C, 0x02, 0x00, 0x03, 0xE9, // Context 0: Device(8), instance=1001
+            0x1E, // Opening tag 1 (list of results)
+            0x29, 0x79, // Context 2: Property 121 = vendor-name
+            0x4E, // Opening tag 4
+            0x75, 0x08, 0x00, b'S', b'i', b'e', b'm', b'e', b'n', b's', // "Siemens"
+            0x4F, // Closing tag 4
+            0x29, 0x46, // Context 2: Property 70 = model-name
+            0x4E, 0x74, 0x00, b'P', b'X', b'C', 0x4F, // "PXC"
+            0x29, 0x78, // Context 2: Property 120 = vendor-identifier
+            0x4E, 0x21, 0x07, 0x4F, // Unsigned 7
+            0x29, 0x2C, // Context 2: Property 44 = firmware-revision
+            0x5E, // Opening tag 5 (property access error)
+            0x91, 0x02, // error-class = property
+            0x91, 0x20, // error-code = unknown-property
+            0x5F, // Closing tag 5
+            0x1F, // Closing tag 1
+        ];
+
+        let info = parse(&payload).unwrap();
+        assert!(matches!(
+            info.service,
+            Some(BacnetService::ReadPropertyMultiple)
+        ));
+        assert_eq!(info.object_instance, Some(1001));
+        let props = info.device_properties.unwrap();
+        assert_eq!(props.vendor_name.as_deref(), Some("Siemens"));
+        assert_eq!(props.model_name.as_deref(), Some("PXC"));
+        assert_eq!(props.vendor_id, Some(7));
+        assert!(props.firmware_revision.is_none());
+
+        // A later segment starts mid-value and is not decoded
+        let mut segmented = payload.clone();
+        segmented.splice(6..8, [0x38, 0x02, 0x01, 0x04]);
+        let info = parse(&segmented).unwrap();
+        assert!(matches!(
+            info.service,
+            Some(BacnetService::ReadPropertyMultiple)
+        ));
+        assert!(info.device_properties.is_none());
+    }
+
+    #[test]
+    fn test_character_string_charsets() {
+        // UCS-2 big-endian "1.2"
+        assert_eq!(
+            decode_character_string(&[0x04, 0x00, b'1', 0x00, b'.', 0x00, b'2']).as_deref(),
+            Some("1.2")
+        );
+        // ISO 8859-1 with NUL padding
+        assert_eq!(
+            decode_character_string(&[0x05, b'M', 0xFC, b'n', 0x00, 0x00]).as_deref(),
+            Some("M\u{fc}n")
+        );
+        assert!(decode_character_string(&[0x00, b' ', 0x00]).is_none());
+        assert!(decode_character_string(&[0x03, b'x']).is_none());
+    }
+
+    #[test]
+    fn test_device_properties_merge() {
+        let mut props = BacnetDeviceProperties {
+            device_instance: Some(1001),
+            object_name: Some("AHU-1".to_string()),
+            ..Default::default()
+        };
+        props.merge(BacnetDeviceProperties {
+            device_instance: Some(1001),
+            model_name: Some("PXC".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(props.object_name.as_deref(), Some("AHU-1"));
+        assert_eq!(props.model_name.as_deref(), Some("PXC"));
+    }
+
     #[test]
     fn test_write_property() {
         // Confirmed WriteProperty — unicast, no routing (ATT&CK T0855)
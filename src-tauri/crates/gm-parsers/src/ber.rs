@@ -0,0 +1,115 @@
+//! Minimal BER (ASN.1 Basic Encoding Rules) reader shared by the IEC 61850
+//! parsers (MMS, GOOSE).
+//!
+//! Only definite-length encodings are supported; every IEC 61850 stack seen
+//! in the field uses them.
+
+/// One BER TLV: identifier byte, tag number, and content range.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Tlv {
+    /// First identifier octet (class + constructed bit + short tag)
+    pub ident: u8,
+    /// Tag number (decoded from the high-tag-number form when needed)
+    pub number: u32,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Tlv {
+    pub fn is_context(&self) -> bool {
+        self.ident & 0xC0 == 0x80
+    }
+
+    pub fn is_constructed(&self) -> bool {
+        self.ident & 0x20 != 0
+    }
+}
+
+/// Read one definite-length BER TLV starting at `data[pos]`.
+pub(crate) fn read_tlv(data: &[u8], pos: usize) -> Option<Tlv> {
+    let ident = *data.get(pos)?;
+    let mut i = pos + 1;
+
+    let number = if ident & 0x1F == 0x1F {
+        // High-tag-number form: base-128, high bit = more bytes follow
+        let mut n: u32 = 0;
+        loop {
+            let b = *data.get(i)?;
+            i += 1;
+            n = n.checked_mul(128)? | (b & 0x7F) as u32;
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+        n
+    } else {
+        (ident & 0x1F) as u32
+    };
+
+    let first = *data.get(i)?;
+    i += 1;
+    let len = if first & 0x80 == 0 {
+        first as usize
+    } else {
+        let count = (first & 0x7F) as usize;
+        // 0x80 = indefinite length (unsupported); cap long-form at 4 bytes
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let mut l = 0usize;
+        for _ in 0..count {
+            l = (l << 8) | *data.get(i)? as usize;
+            i += 1;
+        }
+        l
+    };
+
+    let end = i.checked_add(len)?;
+    if end > data.len() {
+        return None;
+    }
+    Some(Tlv {
+        ident,
+        number,
+        start: i,
+        end,
+    })
+}
+
+/// Iterate the direct children of a constructed TLV's content.
+pub(crate) fn children(data: &[u8], start: usize, end: usize) -> Vec<Tlv> {
+    let mut out = Vec::new();
+    let mut pos = start;
+    while pos < end {
+        match read_tlv(&data[..end], pos) {
+            Some(t) => {
+                pos = t.end;
+                out.push(t);
+            }
+            None => break,
+        }
+    }
+    out
+}
+
+/// Decode a BER unsigned integer body (up to 4 bytes).
+pub(crate) fn ber_uint(body: &[u8]) -> Option<u32> {
+    if body.is_empty() || body.len() > 5 {
+        return None;
+    }
+    Some(body.iter().fold(0u32, |acc, &b| (acc << 8) | b as u32))
+}
+
+/// Printable ASCII view of a string body.
+pub(crate) fn ber_string(body: &[u8]) -> Option<String> {
+    let s: String = body
+        .iter()
+        .filter(|&&b| (0x20..=0x7e).contains(&b))
+        .map(|&b| b as char)
+        .collect();
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
@@ -0,0 +1,144 @@
+//! Per-protocol deep-parse budgets for live capture.
+//!
+//! On very chatty networks (hundreds of Modbus polls per second) deep parsing
+//! every packet dominates CPU. A [`DeepParseLimiter`] caps how many packets of
+//! each protocol are deep parsed per second of capture time; the rest are
+//! still counted by the caller (connections, packet totals) but skip payload
+//! inspection. Per-protocol parsed/skipped counters make the sampling visible.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::IcsProtocol;
+
+/// Deep-parse sampling counters for one budgeted protocol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeepParseSampling {
+    /// Protocol name as returned by `IcsProtocol::to_name()`
+    pub protocol: String,
+    pub max_per_second: u32,
+    /// Packets that were deep parsed
+    pub parsed: u64,
+    /// Packets over budget that were only counted
+    pub skipped: u64,
+}
+
+#[derive(Debug, Default)]
+struct ProtocolWindow {
+    second: i64,
+    used: u32,
+    parsed: u64,
+    skipped: u64,
+}
+
+/// Admits at most N packets per protocol per second for deep parsing.
+///
+/// Windows are keyed by the packet's own timestamp (whole seconds), so the
+/// budget follows capture time rather than processing time. Protocols
+/// without a budget, or with a budget of 0, are always admitted and not
+/// counted.
+#[derive(Debug, Default)]
+pub struct DeepParseLimiter {
+    budgets: HashMap<IcsProtocol, u32>,
+    windows: HashMap<IcsProtocol, ProtocolWindow>,
+}
+
+impl DeepParseLimiter {
+    pub fn new(budgets: HashMap<IcsProtocol, u32>) -> Self {
+        Self {
+            budgets: budgets.into_iter().filter(|(_, max)| *max > 0).collect(),
+            windows: HashMap::new(),
+        }
+    }
+
+    /// True if no protocol is budgeted.
+    pub fn is_unlimited(&self) -> bool {
+        self.budgets.is_empty()
+    }
+
+    /// Decide whether a packet of `protocol` seen at `second` (Unix time)
+    /// should be deep parsed, and record the decision.
+    pub fn admit(&mut self, protocol: IcsProtocol, second: i64) -> bool {
+        let Some(&max) = self.budgets.get(&protocol) else {
+            return true;
+        };
+        let window = self.windows.entry(protocol).or_default();
+        if window.second != second {
+            window.second = second;
+            window.used = 0;
+        }
+        if window.used < max {
+            window.used += 1;
+            window.parsed += 1;
+            true
+        } else {
+            window.skipped += 1;
+            false
+        }
+    }
+
+    /// Counters for every budgeted protocol, sorted by protocol name.
+    pub fn sampling(&self) -> Vec<DeepParseSampling> {
+        let mut out: Vec<DeepParseSampling> = self
+            .budgets
+            .iter()
+            .map(|(protocol, &max)| {
+                let window = self.windows.get(protocol);
+                DeepParseSampling {
+                    protocol: protocol.to_name().to_string(),
+                    max_per_second: max,
+                    parsed: window.map_or(0, |w| w.parsed),
+                    skipped: window.map_or(0, |w| w.skipped),
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| a.protocol.cmp(&b.protocol));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_resets_each_second() {
+        let mut limiter = DeepParseLimiter::new(HashMap::from([(IcsProtocol::Modbus, 2)]));
+        assert!(limiter.admit(IcsProtocol::Modbus, 100));
+        assert!(limiter.admit(IcsProtocol::Modbus, 100));
+        assert!(!limiter.admit(IcsProtocol::Modbus, 100));
+        assert!(!limiter.admit(IcsProtocol::Modbus, 100));
+        assert!(limiter.admit(IcsProtocol::Modbus, 101));
+
+        let sampling = limiter.sampling();
+        assert_eq!(
+            sampling,
+            vec![DeepParseSampling {
+                protocol: "modbus".to_string(),
+                max_per_second: 2,
+                parsed: 3,
+                skipped: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unbudgeted_protocols_always_admitted() {
+        let mut limiter = DeepParseLimiter::new(HashMap::from([
+            (IcsProtocol::Modbus, 1),
+            (IcsProtocol::Dnp3, 0),
+        ]));
+        for _ in 0..10 {
+            assert!(limiter.admit(IcsProtocol::Dnp3, 5));
+            assert!(limiter.admit(IcsProtocol::S7comm, 5));
+        }
+        let sampling = limiter.sampling();
+        assert_eq!(sampling.len(), 1);
+        assert_eq!(sampling[0].protocol, "modbus");
+        assert_eq!(sampling[0].parsed, 0);
+
+        assert!(DeepParseLimiter::default().is_unlimited());
+        assert!(!limiter.is_unlimited());
+    }
+}
@@ -0,0 +1,267 @@
+//! CDP (Cisco Discovery Protocol) parser.
+//!
+//! Cisco switches, routers, phones and APs send a CDP frame out of every
+//! port once a minute, naming the device, the port it left from, the
+//! platform and its addresses. On a SPAN port these frames give the same
+//! neighbor data as `show cdp neighbors detail`, with no device access.
+//!
+//! CDP rides in 802.3 frames to 01:00:0C:CC:CC:CC with an LLC/SNAP header
+//! (OUI 00:00:0C, protocol ID 0x2000). The PDU after the SNAP header is:
+//!
+//! ```text
+//! [0]    version   1 or 2
+//! [1]    ttl       seconds
+//! [2..4] checksum
+//! [4..]  TLVs: type (u16 BE), length (u16 BE, includes the 4-byte header), value
+//! ```
+//!
+//! ## TLV Types
+//!
+//! | Type   | Name                 |
+//! |--------|----------------------|
+//! | 0x0001 | Device ID            |
+//! | 0x0002 | Addresses            |
+//! | 0x0003 | Port ID              |
+//! | 0x0004 | Capabilities         |
+//! | 0x0005 | Software Version     |
+//! | 0x0006 | Platform             |
+//! | 0x000A | Native VLAN          |
+//! | 0x000B | Duplex               |
+//! | 0x0016 | Management Addresses |
+
+use serde::{Deserialize, Serialize};
+
+/// CDP capability bits (TLV 0x0004).
+pub mod caps {
+    pub const ROUTER: u32 = 0x01;
+    pub const TRANS_BRIDGE: u32 = 0x02;
+    pub const SOURCE_ROUTE_BRIDGE: u32 = 0x04;
+    pub const SWITCH: u32 = 0x08;
+    pub const HOST: u32 = 0x10;
+    pub const IGMP: u32 = 0x20;
+    pub const REPEATER: u32 = 0x40;
+    pub const PHONE: u32 = 0x80;
+}
+
+/// Parsed CDP PDU.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CdpInfo {
+    /// CDP version (1 or 2)
+    pub version: u8,
+    /// Device ID — usually the hostname, sometimes a serial number
+    pub device_id: Option<String>,
+    /// Sending port, e.g. "GigabitEthernet1/0/24"
+    pub port_id: Option<String>,
+    /// Platform string, e.g. "cisco WS-C2960X-24PS-L"
+    pub platform: Option<String>,
+    /// First line of the software version banner
+    pub software_version: Option<String>,
+    /// IPv4 addresses from the Addresses TLV
+    pub addresses: Vec<String>,
+    /// IPv4 addresses from the Management Addresses TLV
+    pub management_addresses: Vec<String>,
+    /// Capability bitmap (see [`caps`])
+    pub capabilities: u32,
+    /// Native VLAN of the sending port
+    pub native_vlan: Option<u16>,
+    /// Whether the sending port runs full duplex
+    pub full_duplex: Option<bool>,
+}
+
+impl CdpInfo {
+    /// Capability names in CDP's own wording ("Router", "Switch", ...).
+    pub fn capability_names(&self) -> Vec<String> {
+        [
+            (caps::ROUTER, "Router"),
+            (caps::TRANS_BRIDGE, "Trans-Bridge"),
+            (caps::SOURCE_ROUTE_BRIDGE, "Source-Route-Bridge"),
+            (caps::SWITCH, "Switch"),
+            (caps::HOST, "Host"),
+            (caps::IGMP, "IGMP"),
+            (caps::REPEATER, "Repeater"),
+            (caps::PHONE, "Phone"),
+        ]
+        .iter()
+        .filter(|(bit, _)| self.capabilities & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+    }
+
+    /// The address to reach the device on: management address first.
+    pub fn primary_address(&self) -> Option<&str> {
+        self.management_addresses
+            .first()
+            .or_else(|| self.addresses.first())
+            .map(String::as_str)
+    }
+}
+
+/// Parse a CDP PDU.
+///
+/// `payload` starts at the CDP version byte, i.e. after the Ethernet and
+/// LLC/SNAP headers. Returns `None` for anything that is not CDP v1/v2 or
+/// carries no Device ID. Unknown TLVs are skipped.
+pub fn parse(payload: &[u8]) -> Option<CdpInfo> {
+    if payload.len() < 4 || !matches!(payload[0], 1 | 2) {
+        return None;
+    }
+
+    let mut info = CdpInfo {
+        version: payload[0],
+        ..Default::default()
+    };
+    let mut offset = 4;
+
+    while offset + 4 <= payload.len() {
+        let tlv_type = u16::from_be_bytes([payload[offset], payload[offset + 1]]);
+        let tlv_length = u16::from_be_bytes([payload[offset + 2], payload[offset + 3]]) as usize;
+        if tlv_length < 4 || offset + tlv_length > payload.len() {
+            break;
+        }
+        let value = &payload[offset + 4..offset + tlv_length];
+        offset += tlv_length;
+
+        match tlv_type {
+            0x0001 => info.device_id = text(value),
+            0x0002 => info.addresses = decode_addresses(value),
+            0x0003 => info.port_id = text(value),
+            0x0004 if value.len() >= 4 => {
+                info.capabilities = u32::from_be_bytes([value[0], value[1], value[2], value[3]]);
+            }
+            0x0005 => {
+                info.software_version = text(value).and_then(|v| {
+                    v.lines()
+                        .map(str::trim)
+                        .find(|l| !l.is_empty())
+                        .map(str::to_string)
+                });
+            }
+            0x0006 => info.platform = text(value),
+            0x000A if value.len() >= 2 => {
+                info.native_vlan = Some(u16::from_be_bytes([value[0], value[1]]));
+            }
+            0x000B if !value.is_empty() => info.full_duplex = Some(value[0] != 0),
+            0x0016 => info.management_addresses = decode_addresses(value),
+            _ => {}
+        }
+    }
+
+    info.device_id.is_some().then_some(info)
+}
+
+/// Decode a printable TLV value, trimming padding.
+fn text(value: &[u8]) -> Option<String> {
+    let s = String::from_utf8_lossy(value)
+        .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+        .to_string();
+    (!s.is_empty()).then_some(s)
+}
+
+/// Decode an Addresses / Management Addresses TLV, keeping IPv4 entries.
+///
+/// Layout: count (u32), then per address: protocol type (1), protocol
+/// length (1), protocol (NLPID 0xCC = IP), address length (u16), address.
+fn decode_addresses(value: &[u8]) -> Vec<String> {
+    let mut out = Vec::new();
+    if value.len() < 4 {
+        return out;
+    }
+    let count = u32::from_be_bytes([value[0], value[1], value[2], value[3]]);
+    let mut pos = 4;
+    for _ in 0..count {
+        let Some(&proto_len) = value.get(pos + 1) else {
+            break;
+        };
+        let proto_len = proto_len as usize;
+        let proto_start = pos + 2;
+        let len_pos = proto_start + proto_len;
+        if len_pos + 2 > value.len() {
+            break;
+        }
+        let addr_len = u16::from_be_bytes([value[len_pos], value[len_pos + 1]]) as usize;
+        let addr_start = len_pos + 2;
+        if addr_start + addr_len > value.len() {
+            break;
+        }
+        let is_ip = value[pos] == 1 && proto_len == 1 && value[proto_start] == 0xCC;
+        if is_ip && addr_len == 4 {
+            let a = &value[addr_start..addr_start + 4];
+            out.push(format!("{}.{}.{}.{}", a[0], a[1], a[2], a[3]));
+        }
+        pos = addr_start + addr_len;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_tlv(buf: &mut Vec<u8>, tlv_type: u16, value: &[u8]) {
+        buf.extend_from_slice(&tlv_type.to_be_bytes());
+        buf.extend_from_slice(&((value.len() + 4) as u16).to_be_bytes());
+        buf.extend_from_slice(value);
+    }
+
+    fn ip_addresses(ip: [u8; 4]) -> Vec<u8> {
+        let mut v = vec![0, 0, 0, 1, 0x01, 0x01, 0xCC, 0x00, 0x04];
+        v.extend_from_slice(&ip);
+        v
+    }
+
+    fn switch_pdu() -> Vec<u8> {
+        let mut pdu = vec![0x02, 180, 0x00, 0x00];
+        push_tlv(&mut pdu, 0x0001, b"sw-access-3.plant.local");
+        push_tlv(&mut pdu, 0x0002, &ip_addresses([10, 20, 0, 3]));
+        push_tlv(&mut pdu, 0x0003, b"GigabitEthernet1/0/48");
+        push_tlv(&mut pdu, 0x0004, &0x0000_0028u32.to_be_bytes());
+        push_tlv(
+            &mut pdu,
+            0x0005,
+            b"Cisco IOS Software, C2960X Software (C2960X-UNIVERSALK9-M), Version 15.2(7)E4\nTechnical Support",
+        );
+        push_tlv(&mut pdu, 0x0006, b"cisco WS-C2960X-24PS-L");
+        push_tlv(&mut pdu, 0x000A, &30u16.to_be_bytes());
+        push_tlv(&mut pdu, 0x000B, &[0x01]);
+        push_tlv(&mut pdu, 0x0016, &ip_addresses([10, 99, 0, 3]));
+        pdu
+    }
+
+    #[test]
+    fn test_parse_switch_advertisement() {
+        let info = parse(&switch_pdu()).expect("should parse CDP");
+        assert_eq!(info.version, 2);
+        assert_eq!(info.device_id.as_deref(), Some("sw-access-3.plant.local"));
+        assert_eq!(info.port_id.as_deref(), Some("GigabitEthernet1/0/48"));
+        assert_eq!(info.platform.as_deref(), Some("cisco WS-C2960X-24PS-L"));
+        assert!(info
+            .software_version
+            .as_deref()
+            .unwrap()
+            .ends_with("Version 15.2(7)E4"));
+        assert_eq!(info.addresses, vec!["10.20.0.3"]);
+        assert_eq!(info.primary_address(), Some("10.99.0.3"));
+        assert_eq!(info.capability_names(), vec!["Switch", "IGMP"]);
+        assert_eq!(info.native_vlan, Some(30));
+        assert_eq!(info.full_duplex, Some(true));
+    }
+
+    #[test]
+    fn test_truncated_tlv_keeps_earlier_fields() {
+        let pdu = switch_pdu();
+        // Cut inside the Addresses TLV
+        let info = parse(&pdu[..40]).expect("device ID precedes the cut");
+        assert_eq!(info.device_id.as_deref(), Some("sw-access-3.plant.local"));
+        assert!(info.port_id.is_none());
+    }
+
+    #[test]
+    fn test_rejects_non_cdp() {
+        assert!(parse(&[]).is_none());
+        assert!(parse(&[0x05, 180, 0, 0]).is_none());
+        // Valid header but no Device ID
+        let mut pdu = vec![0x02, 180, 0x00, 0x00];
+        push_tlv(&mut pdu, 0x0003, b"Gi0/1");
+        assert!(parse(&pdu).is_none());
+    }
+}
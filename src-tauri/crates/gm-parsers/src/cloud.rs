@@ -0,0 +1,205 @@
+//! Cloud and remote-access endpoint classification.
+//!
+//! Maps host names seen on the wire (TLS SNI, MQTT usernames, OPC UA
+//! endpoint URLs) to the cloud IoT platform, vendor telemetry service or
+//! remote-access broker they belong to. Matching is by DNS suffix so that
+//! tenant-specific names (`<hub>.azure-devices.net`,
+//! `<prefix>-ats.iot.<region>.amazonaws.com`) resolve to the service.
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of external service an OT asset talks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloudCategory {
+    /// Hyperscaler IoT ingestion (AWS IoT Core, Azure IoT Hub, ...)
+    IotPlatform,
+    /// OT vendor telemetry / analytics cloud
+    VendorCloud,
+    /// Vendor remote-access / VPN broker
+    RemoteAccess,
+    /// MQTT broker on a public address that is not a known platform
+    MqttBroker,
+    /// OPC UA server dialling out with ReverseHello
+    OpcUaReverse,
+}
+
+impl CloudCategory {
+    /// Human-readable category name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CloudCategory::IotPlatform => "IoT platform",
+            CloudCategory::VendorCloud => "Vendor cloud",
+            CloudCategory::RemoteAccess => "Remote access",
+            CloudCategory::MqttBroker => "MQTT broker",
+            CloudCategory::OpcUaReverse => "OPC UA reverse connect",
+        }
+    }
+}
+
+/// A known cloud service matched from a host name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CloudService {
+    /// Service name (e.g., "AWS IoT Core")
+    pub name: &'static str,
+    /// Service category
+    pub category: CloudCategory,
+}
+
+/// (DNS suffix, service name, category). A suffix only matches on a label
+/// boundary, so "azure-devices.net" matches "plant.azure-devices.net" but
+/// not "notazure-devices.net".
+const CLOUD_SUFFIXES: &[(&str, &str, CloudCategory)] = &[
+    // Hyperscaler IoT platforms
+    (
+        "azure-devices.net",
+        "Azure IoT Hub",
+        CloudCategory::IotPlatform,
+    ),
+    (
+        "azure-devices-provisioning.net",
+        "Azure IoT Hub DPS",
+        CloudCategory::IotPlatform,
+    ),
+    (
+        "servicebus.windows.net",
+        "Azure Event Hubs",
+        CloudCategory::IotPlatform,
+    ),
+    (
+        "azureiotcentral.com",
+        "Azure IoT Central",
+        CloudCategory::IotPlatform,
+    ),
+    (
+        "cloudiot.googleapis.com",
+        "Google Cloud IoT",
+        CloudCategory::IotPlatform,
+    ),
+    (
+        "mqtt.googleapis.com",
+        "Google Cloud IoT",
+        CloudCategory::IotPlatform,
+    ),
+    ("hivemq.cloud", "HiveMQ Cloud", CloudCategory::IotPlatform),
+    (
+        "cumulocity.com",
+        "Cumulocity IoT",
+        CloudCategory::IotPlatform,
+    ),
+    // OT vendor clouds
+    (
+        "mindsphere.io",
+        "Siemens Insights Hub (MindSphere)",
+        CloudCategory::VendorCloud,
+    ),
+    ("predix.io", "GE Predix", CloudCategory::VendorCloud),
+    (
+        "factorytalkhub.com",
+        "Rockwell FactoryTalk Hub",
+        CloudCategory::VendorCloud,
+    ),
+    (
+        "connect.aveva.com",
+        "AVEVA Connect",
+        CloudCategory::VendorCloud,
+    ),
+    (
+        "ecostruxure.com",
+        "Schneider EcoStruxure",
+        CloudCategory::VendorCloud,
+    ),
+    // Remote-access brokers
+    ("talk2m.com", "Ewon Talk2M", CloudCategory::RemoteAccess),
+    ("ewon.biz", "Ewon Talk2M", CloudCategory::RemoteAccess),
+    ("tosibox.com", "Tosibox", CloudCategory::RemoteAccess),
+    ("secomea.com", "Secomea", CloudCategory::RemoteAccess),
+    ("ixon.cloud", "IXON Cloud", CloudCategory::RemoteAccess),
+];
+
+/// Classify a host name as a known cloud service.
+pub fn classify_host(host: &str) -> Option<CloudService> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+
+    // AWS IoT Core data endpoints: <prefix>[-ats].iot.<region>.amazonaws.com
+    if let Some(rest) = host.strip_suffix(".amazonaws.com") {
+        let labels: Vec<&str> = rest.split('.').collect();
+        if labels.len() >= 3 && labels[labels.len() - 2] == "iot" {
+            return Some(CloudService {
+                name: "AWS IoT Core",
+                category: CloudCategory::IotPlatform,
+            });
+        }
+        if labels.len() >= 2 && labels[labels.len() - 2] == "greengrass" {
+            return Some(CloudService {
+                name: "AWS IoT Greengrass",
+                category: CloudCategory::IotPlatform,
+            });
+        }
+    }
+
+    CLOUD_SUFFIXES
+        .iter()
+        .find(|(suffix, _, _)| {
+            host == *suffix
+                || host
+                    .strip_suffix(suffix)
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+        .map(|&(_, name, category)| CloudService { name, category })
+}
+
+/// Extract the host part of a URL such as `opc.tcp://host:4840/path`.
+pub fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+    let authority = rest.split('/').next()?;
+    let authority = authority.rsplit('@').next()?;
+    let host = if let Some(v6) = authority.strip_prefix('[') {
+        v6.split(']').next()?
+    } else {
+        authority.split(':').next()?
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aws_iot_endpoints() {
+        let svc = classify_host("a3k7odshaiipe8-ats.iot.us-east-1.amazonaws.com").unwrap();
+        assert_eq!(svc.name, "AWS IoT Core");
+        assert_eq!(svc.category, CloudCategory::IotPlatform);
+        assert!(classify_host("greengrass-ats.iot.eu-central-1.amazonaws.com").is_some());
+        // Other AWS services are not IoT endpoints
+        assert_eq!(classify_host("s3.us-east-1.amazonaws.com"), None);
+    }
+
+    #[test]
+    fn test_suffix_matching_on_label_boundary() {
+        let svc = classify_host("Contoso-Plant.azure-devices.net.").unwrap();
+        assert_eq!(svc.name, "Azure IoT Hub");
+        assert_eq!(classify_host("notazure-devices.net"), None);
+        assert_eq!(
+            classify_host("m.talk2m.com").unwrap().category,
+            CloudCategory::RemoteAccess
+        );
+        assert_eq!(
+            classify_host("gateway.eu1.mindsphere.io").unwrap().category,
+            CloudCategory::VendorCloud
+        );
+        assert_eq!(classify_host("www.example.com"), None);
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("opc.tcp://collector.example.com:4843/ua"),
+            Some("collector.example.com")
+        );
+        assert_eq!(url_host("opc.tcp://[fe80::1]:4840"), Some("fe80::1"));
+        assert_eq!(url_host("opc.tcp://plc01"), Some("plc01"));
+        assert_eq!(url_host("opc.tcp://"), None);
+    }
+}
@@ -0,0 +1,320 @@
+//! CODESYS runtime (programming system ↔ PLC) protocol parser.
+//!
+//! CODESYS is the IEC 61131-3 runtime behind hundreds of OEM controllers
+//! (WAGO, Beckhoff CX-V2, Schneider M2xx, Festo, Bosch Rexroth, ABB AC500).
+//! The Development System logs into the runtime, downloads the application
+//! and applies online changes over one of two stacks:
+//!
+//! **V2** (ports 1200 gateway, 2455 runtime):
+//!   [0..2]  Magic `BB BB`
+//!   [2..6]  Body length (LE)
+//!   [6]     Service code (0x01 = login / target info)
+//!
+//! **V3** (ports 11740 TCP, 1217 gateway), PDU layers:
+//!   Block driver: [0..4] magic `00 01 17 E8`, [4..8] total length (LE)
+//!   Datagram:     [8] magic `C5`, variable-length router addresses
+//!   Channel:      channel id, block/ack counters
+//!   Services:     [0..2] protocol id `55 CD` (0xCD55), [2..4] header size,
+//!                 [4..6] service group, [6..8] service id,
+//!                 [8..12] session id, [12..16] content size
+//!
+//! Responses set bit 0x80 in the V3 service group. The datagram addresses
+//! and channel header are variable-length, so the services header is found
+//! by its protocol id rather than by walking every layer.
+
+use serde::{Deserialize, Serialize};
+
+/// TCP ports used by CODESYS V2 (1200, 2455) and V3 (1217, 11740) runtimes.
+pub const CODESYS_PORTS: [u16; 4] = [1200, 1217, 2455, 11740];
+
+const V2_MAGIC: [u8; 2] = [0xBB, 0xBB];
+const V2_HEADER_LEN: usize = 6;
+const V3_BLOCK_MAGIC: [u8; 4] = [0x00, 0x01, 0x17, 0xE8];
+const V3_BLOCK_HEADER_LEN: usize = 8;
+const V3_DATAGRAM_MAGIC: u8 = 0xC5;
+/// Services-layer protocol id 0xCD55, little-endian on the wire.
+const V3_SERVICE_TAG: [u8; 2] = [0x55, 0xCD];
+const V3_SERVICE_HEADER_LEN: usize = 16;
+/// Response bit in the V3 service group.
+const V3_RESPONSE_FLAG: u16 = 0x80;
+
+/// V3 service group: CmpDevice (target info, device login)
+pub const GROUP_DEVICE: u16 = 0x01;
+/// V3 service group: CmpApp (application login, download, run/stop)
+pub const GROUP_APPLICATION: u16 = 0x02;
+/// V3 service group: CmpFileTransfer
+pub const GROUP_FILE_TRANSFER: u16 = 0x08;
+
+// ─── Enums ────────────────────────────────────────────────────────────────────
+
+/// CODESYS protocol stack generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodesysVersion {
+    V2,
+    V3,
+}
+
+impl CodesysVersion {
+    pub fn name(self) -> &'static str {
+        match self {
+            CodesysVersion::V2 => "V2",
+            CodesysVersion::V3 => "V3",
+        }
+    }
+}
+
+/// Client/server role for a CODESYS device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodesysRole {
+    /// Device is sending requests (Development System, gateway, HMI)
+    Client,
+    /// Device is answering requests (PLC runtime)
+    Server,
+}
+
+// ─── Structs ──────────────────────────────────────────────────────────────────
+
+/// Parsed CODESYS message information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodesysInfo {
+    pub version: CodesysVersion,
+    pub is_response: bool,
+    /// V3 service group with the response bit cleared (None for V2 and
+    /// for channel-layer-only frames)
+    pub service_group: Option<u16>,
+    /// V3 service id, or the V2 service code
+    pub service_id: Option<u16>,
+    /// V3 session id assigned at login
+    pub session_id: Option<u32>,
+    /// Request logs into the runtime (device or application login)
+    pub is_login: bool,
+    /// Request downloads an application to the runtime
+    pub is_download: bool,
+    /// Request applies an online change to the running application
+    pub is_online_change: bool,
+    /// Request starts, stops or resets the application
+    pub is_control: bool,
+    pub role: CodesysRole,
+}
+
+// ─── Parse Functions ──────────────────────────────────────────────────────────
+
+/// Attempt to parse a CODESYS V2 or V3 message.
+///
+/// Ports only decide the role where the frame itself does not (V2, and V3
+/// frames without a services layer).
+pub fn parse(payload: &[u8], src_port: u16, dst_port: u16) -> Option<CodesysInfo> {
+    let from_runtime = CODESYS_PORTS.contains(&src_port) && !CODESYS_PORTS.contains(&dst_port);
+
+    if payload.starts_with(&V3_BLOCK_MAGIC) {
+        parse_v3(payload, from_runtime)
+    } else if payload.starts_with(&V2_MAGIC) {
+        parse_v2(payload, from_runtime)
+    } else {
+        None
+    }
+}
+
+fn parse_v2(payload: &[u8], from_runtime: bool) -> Option<CodesysInfo> {
+    if payload.len() < V2_HEADER_LEN {
+        return None;
+    }
+    let mut info = empty_info(CodesysVersion::V2, from_runtime);
+    if let Some(&code) = payload.get(V2_HEADER_LEN) {
+        info.service_id = Some(code as u16);
+        info.is_login = !from_runtime && code == 0x01;
+    }
+    Some(info)
+}
+
+fn parse_v3(payload: &[u8], from_runtime: bool) -> Option<CodesysInfo> {
+    if payload.len() <= V3_BLOCK_HEADER_LEN || payload[V3_BLOCK_HEADER_LEN] != V3_DATAGRAM_MAGIC {
+        return None;
+    }
+    let mut info = empty_info(CodesysVersion::V3, from_runtime);
+
+    let start = V3_BLOCK_HEADER_LEN + 1;
+    let Some(pos) = payload[start..]
+        .windows(2)
+        .position(|w| w == V3_SERVICE_TAG)
+        .map(|p| start + p)
+    else {
+        // Channel acks, keepalives and channel setup carry no service
+        return Some(info);
+    };
+    let header = payload.get(pos..pos + V3_SERVICE_HEADER_LEN)?;
+    let header_size = u16::from_le_bytes([header[2], header[3]]);
+    if header_size < 12 {
+        return Some(info);
+    }
+
+    let raw_group = u16::from_le_bytes([header[4], header[5]]);
+    let group = raw_group & !V3_RESPONSE_FLAG;
+    let service = u16::from_le_bytes([header[6], header[7]]);
+    info.is_response = raw_group & V3_RESPONSE_FLAG != 0;
+    info.role = if info.is_response {
+        CodesysRole::Server
+    } else {
+        CodesysRole::Client
+    };
+    info.service_group = Some(group);
+    info.service_id = Some(service);
+    info.session_id = Some(u32::from_le_bytes([
+        header[8], header[9], header[10], header[11],
+    ]));
+
+    if !info.is_response {
+        info.is_login = matches!(
+            (group, service),
+            (GROUP_DEVICE, 0x02) | (GROUP_APPLICATION, 0x01)
+        );
+        info.is_download = matches!((group, service), (GROUP_APPLICATION, 0x05 | 0x07));
+        info.is_online_change = (group, service) == (GROUP_APPLICATION, 0x06);
+        info.is_control = matches!((group, service), (GROUP_APPLICATION, 0x10..=0x12));
+    }
+    Some(info)
+}
+
+fn empty_info(version: CodesysVersion, from_runtime: bool) -> CodesysInfo {
+    CodesysInfo {
+        version,
+        is_response: from_runtime,
+        service_group: None,
+        service_id: None,
+        session_id: None,
+        is_login: false,
+        is_download: false,
+        is_online_change: false,
+        is_control: false,
+        role: if from_runtime {
+            CodesysRole::Server
+        } else {
+            CodesysRole::Client
+        },
+    }
+}
+
+/// Human-readable name for a CODESYS service.
+///
+/// `group` is None for V2, whose service codes are a single flat byte.
+pub fn service_name(group: Option<u16>, service: u16) -> String {
+    let name = match (group, service) {
+        (None, 0x01) => "Login",
+        (Some(GROUP_DEVICE), 0x01) => "Device: Get Target Info",
+        (Some(GROUP_DEVICE), 0x02) => "Device: Login",
+        (Some(GROUP_DEVICE), 0x03) => "Device: Logout",
+        (Some(GROUP_DEVICE), 0x04) => "Device: Reset Origin",
+        (Some(GROUP_DEVICE), 0x05) => "Device: Echo",
+        (Some(GROUP_APPLICATION), 0x01) => "Application: Login",
+        (Some(GROUP_APPLICATION), 0x02) => "Application: Logout",
+        (Some(GROUP_APPLICATION), 0x03) => "Application: Create",
+        (Some(GROUP_APPLICATION), 0x04) => "Application: Delete",
+        (Some(GROUP_APPLICATION), 0x05) => "Application: Download",
+        (Some(GROUP_APPLICATION), 0x06) => "Application: Online Change",
+        (Some(GROUP_APPLICATION), 0x07) => "Application: Device Download",
+        (Some(GROUP_APPLICATION), 0x10) => "Application: Start",
+        (Some(GROUP_APPLICATION), 0x11) => "Application: Stop",
+        (Some(GROUP_APPLICATION), 0x12) => "Application: Reset",
+        (Some(GROUP_FILE_TRANSFER), _) => "File Transfer",
+        (None, code) => return format!("Service 0x{:02X}", code),
+        (Some(group), code) => return format!("Group 0x{:02X} Service 0x{:02X}", group, code),
+    };
+    name.to_string()
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// V3 frame: block driver + datagram (2-byte addresses) + channel + services.
+    fn v3_frame(group: u16, service: u16, session: u32) -> Vec<u8> {
+        let mut f = Vec::new();
+        f.extend_from_slice(&V3_BLOCK_MAGIC);
+        f.extend_from_slice(&0u32.to_le_bytes()); // length, patched below
+                                                  // Datagram: magic, hop info, packet info, service id, message id,
+                                                  // address lengths, dst/src addresses
+        f.extend_from_slice(&[0xC5, 0x6C, 0x40, 0x40, 0x00, 0x11, 0x00, 0x01, 0x00, 0x02]);
+        // Channel data header: type, flags, version, channel id, blk id, ack id
+        f.extend_from_slice(&[0x01, 0x81, 0x01, 0x00, 0x11, 0x22, 0, 0, 0, 0x01, 0, 0, 0]);
+        f.extend_from_slice(&V3_SERVICE_TAG);
+        f.extend_from_slice(&16u16.to_le_bytes());
+        f.extend_from_slice(&group.to_le_bytes());
+        f.extend_from_slice(&service.to_le_bytes());
+        f.extend_from_slice(&session.to_le_bytes());
+        f.extend_from_slice(&0u32.to_le_bytes());
+        let len = f.len() as u32;
+        f[4..8].copy_from_slice(&len.to_le_bytes());
+        f
+    }
+
+    #[test]
+    fn test_v3_device_login() {
+        let info = parse(&v3_frame(0x01, 0x02, 0), 50000, 11740).unwrap();
+        assert_eq!(info.version, CodesysVersion::V3);
+        assert_eq!(info.role, CodesysRole::Client);
+        assert_eq!(info.service_group, Some(GROUP_DEVICE));
+        assert!(info.is_login);
+        assert!(!info.is_download);
+        assert_eq!(service_name(info.service_group, 0x02), "Device: Login");
+    }
+
+    #[test]
+    fn test_v3_download_and_online_change() {
+        let download = parse(&v3_frame(0x02, 0x05, 0x1234), 50000, 11740).unwrap();
+        assert!(download.is_download);
+        assert_eq!(download.session_id, Some(0x1234));
+
+        let online = parse(&v3_frame(0x02, 0x06, 0x1234), 50000, 11740).unwrap();
+        assert!(online.is_online_change);
+        assert!(!online.is_download);
+
+        let stop = parse(&v3_frame(0x02, 0x11, 0x1234), 50000, 11740).unwrap();
+        assert!(stop.is_control);
+        assert_eq!(service_name(Some(0x02), 0x11), "Application: Stop");
+    }
+
+    #[test]
+    fn test_v3_response_flag() {
+        let info = parse(&v3_frame(0x82, 0x05, 0x1234), 11740, 50000).unwrap();
+        assert!(info.is_response);
+        assert_eq!(info.role, CodesysRole::Server);
+        assert_eq!(info.service_group, Some(GROUP_APPLICATION));
+        // Responses never raise request flags
+        assert!(!info.is_download);
+    }
+
+    #[test]
+    fn test_v3_channel_only_frame() {
+        let mut f = V3_BLOCK_MAGIC.to_vec();
+        f.extend_from_slice(&14u32.to_le_bytes());
+        f.extend_from_slice(&[0xC5, 0x6C, 0x40, 0x40, 0x00, 0x00]);
+        let info = parse(&f, 11740, 50000).unwrap();
+        assert_eq!(info.role, CodesysRole::Server);
+        assert_eq!(info.service_group, None);
+    }
+
+    #[test]
+    fn test_v2_login() {
+        let req = [0xBB, 0xBB, 0x01, 0x00, 0x00, 0x00, 0x01];
+        let info = parse(&req, 50000, 2455).unwrap();
+        assert_eq!(info.version, CodesysVersion::V2);
+        assert_eq!(info.role, CodesysRole::Client);
+        assert!(info.is_login);
+        assert_eq!(service_name(None, 0x01), "Login");
+
+        let resp = parse(&req, 2455, 50000).unwrap();
+        assert_eq!(resp.role, CodesysRole::Server);
+        assert!(!resp.is_login);
+    }
+
+    #[test]
+    fn test_rejects_non_codesys() {
+        assert!(parse(&[0x00, 0x01, 0x17, 0xE8, 0, 0, 0, 0, 0x00], 50000, 11740).is_none());
+        assert!(parse(&[0xBB, 0xBB, 0x01], 50000, 2455).is_none());
+        assert!(parse(b"GET / HTTP/1.1\r\n", 50000, 1200).is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! Red Lion Crimson v3 protocol parser.
+//!
+//! Crimson 3 is the configuration software for Red Lion G3/Graphite HMIs,
+//! Data Stations and FlexEdge controllers. The Crimson software talks to the
+//! device on TCP 789 to download and upload the configuration database,
+//! and the same port answers identification queries.
+//!
+//! Frames are length-prefixed:
+//!
+//! ```text
+//! ┌──────────────┬──────────────────────────────────────┐
+//! │ Length (BE)  │ Body (Length bytes)                   │
+//! │ 2 bytes      │ [0]=0x01 [1..3]=opcode [3]=0x00 ...   │
+//! └──────────────┴──────────────────────────────────────┘
+//! ```
+//!
+//! Two opcodes are known publicly: `2b 1b` reads the manufacturer and
+//! `2a 1a` the model; the device echoes the request header and appends a
+//! NUL-terminated string. The opcodes used for database transfer are not
+//! documented, so downloads and uploads are recognised by volume instead:
+//! large frames (and full segments continuing them) are counted as bulk
+//! data, and the direction they flow in says which operation it was.
+
+use serde::{Deserialize, Serialize};
+
+/// Crimson v3 TCP port.
+pub const CRIMSON_PORT: u16 = 789;
+
+/// Read Manufacturer request/response opcode.
+pub const OPCODE_MANUFACTURER: u16 = 0x2b1b;
+/// Read Model request/response opcode.
+pub const OPCODE_MODEL: u16 = 0x2a1a;
+
+/// Frames with bodies this large carry database blocks, not queries.
+pub const BULK_FRAME_LEN: usize = 256;
+/// Upper bound on a plausible frame length.
+const MAX_FRAME_LEN: usize = 16_384;
+
+/// Client/server role for a Crimson device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrimsonRole {
+    /// Crimson configuration software (engineering workstation)
+    Client,
+    /// Red Lion device answering on port 789
+    Server,
+}
+
+/// Identity string returned by the device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrimsonIdentity {
+    Manufacturer(String),
+    Model(String),
+}
+
+/// Parsed Crimson v3 segment information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrimsonInfo {
+    pub role: CrimsonRole,
+    /// Opcodes of the complete frames in this segment
+    pub opcodes: Vec<u16>,
+    /// Identity strings from identification responses
+    pub identity: Vec<CrimsonIdentity>,
+    /// Bytes of bulk (database transfer) data in this segment
+    pub bulk_bytes: usize,
+}
+
+/// Attempt to parse a Crimson v3 segment.
+///
+/// Returns None unless the payload starts with a plausible frame header,
+/// or is a full-size segment continuing a large frame.
+pub fn parse(payload: &[u8], src_port: u16, dst_port: u16) -> Option<CrimsonInfo> {
+    let role = if src_port == CRIMSON_PORT && dst_port != CRIMSON_PORT {
+        CrimsonRole::Server
+    } else {
+        CrimsonRole::Client
+    };
+
+    let mut info = CrimsonInfo {
+        role,
+        opcodes: Vec::new(),
+        identity: Vec::new(),
+        bulk_bytes: 0,
+    };
+
+    let mut pos = 0;
+    while pos + 2 <= payload.len() {
+        let len = u16::from_be_bytes([payload[pos], payload[pos + 1]]) as usize;
+        if !(4..=MAX_FRAME_LEN).contains(&len) {
+            break;
+        }
+        let body_start = pos + 2;
+        let body_end = body_start + len;
+        if body_end > payload.len() {
+            // Frame continues in the next segment
+            if len >= BULK_FRAME_LEN {
+                info.bulk_bytes += payload.len() - body_start;
+                pos = payload.len();
+            }
+            break;
+        }
+        let body = &payload[body_start..body_end];
+        if len >= BULK_FRAME_LEN {
+            info.bulk_bytes += len;
+        } else if body[0] == 0x01 {
+            let opcode = u16::from_be_bytes([body[1], body[2]]);
+            info.opcodes.push(opcode);
+            if role == CrimsonRole::Server {
+                if let Some(id) = identity(opcode, &body[4..]) {
+                    info.identity.push(id);
+                }
+            }
+        }
+        pos = body_end;
+    }
+
+    if pos == 0 {
+        // No frame header: a full segment is the middle of a bulk frame
+        return (payload.len() >= BULK_FRAME_LEN).then_some(CrimsonInfo {
+            bulk_bytes: payload.len(),
+            ..info
+        });
+    }
+    Some(info)
+}
+
+fn identity(opcode: u16, data: &[u8]) -> Option<CrimsonIdentity> {
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    let text = std::str::from_utf8(&data[..end]).ok()?.trim();
+    if text.is_empty() || !text.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+        return None;
+    }
+    match opcode {
+        OPCODE_MANUFACTURER => Some(CrimsonIdentity::Manufacturer(text.to_string())),
+        OPCODE_MODEL => Some(CrimsonIdentity::Model(text.to_string())),
+        _ => None,
+    }
+}
+
+/// Whether a model string names a Data Station / edge controller rather
+/// than an operator panel (G3, Graphite HMI, CR1000/CR3000).
+pub fn is_data_station(model: &str) -> bool {
+    let m = model.trim().to_ascii_uppercase();
+    ["DA", "DSP", "GC", "FLEXEDGE"]
+        .iter()
+        .any(|prefix| m.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(body: &[u8]) -> Vec<u8> {
+        let mut f = (body.len() as u16).to_be_bytes().to_vec();
+        f.extend_from_slice(body);
+        f
+    }
+
+    #[test]
+    fn test_identification_exchange() {
+        let request = frame(&[0x01, 0x2b, 0x1b, 0x00]);
+        let info = parse(&request, 50123, CRIMSON_PORT).unwrap();
+        assert_eq!(info.role, CrimsonRole::Client);
+        assert_eq!(info.opcodes, vec![OPCODE_MANUFACTURER]);
+        assert!(info.identity.is_empty());
+
+        let mut body = vec![0x01, 0x2b, 0x1b, 0x00];
+        body.extend_from_slice(b"Red Lion Controls\0");
+        let mut response = frame(&body);
+        let mut model = vec![0x01, 0x2a, 0x1a, 0x00];
+        model.extend_from_slice(b"G306A\0");
+        response.extend(frame(&model));
+        let info = parse(&response, CRIMSON_PORT, 50123).unwrap();
+        assert_eq!(info.role, CrimsonRole::Server);
+        assert_eq!(
+            info.identity,
+            vec![
+                CrimsonIdentity::Manufacturer("Red Lion Controls".to_string()),
+                CrimsonIdentity::Model("G306A".to_string()),
+            ]
+        );
+        assert_eq!(info.bulk_bytes, 0);
+    }
+
+    #[test]
+    fn test_bulk_frames_and_continuations() {
+        // A 1000-byte frame split across two segments
+        let mut first = 1000u16.to_be_bytes().to_vec();
+        first.extend(vec![0xAA; 600]);
+        let info = parse(&first, 50123, CRIMSON_PORT).unwrap();
+        assert_eq!(info.bulk_bytes, 600);
+        assert!(info.opcodes.is_empty());
+
+        let info = parse(&[0xAA; 400], 50123, CRIMSON_PORT).unwrap();
+        assert_eq!(info.bulk_bytes, 400);
+
+        // Short junk without a header is not Crimson
+        assert!(parse(&[0xFF, 0xFF, 0x00], 50123, CRIMSON_PORT).is_none());
+        assert!(parse(&[], 50123, CRIMSON_PORT).is_none());
+    }
+
+    #[test]
+    fn test_data_station_models() {
+        assert!(is_data_station("DA30D"));
+        assert!(is_data_station("DSPSX000"));
+        assert!(!is_data_station("G306A"));
+        assert!(!is_data_station("CR1000"));
+    }
+}
@@ -0,0 +1,289 @@
+//! DHCP (BOOTP) message parser.
+//!
+//! DHCP is not an ICS protocol, but it is the one place many hosts say
+//! their own name: the client puts its hostname (option 12) and a vendor
+//! class string (option 60, e.g. "MSFT 5.0" or a PLC firmware banner) into
+//! Discover/Request messages, and the server's ACK names the address it
+//! handed out. Server replies also reveal every DHCP server on the segment.
+//!
+//! ## Packet Format (UDP 67/68)
+//! ```text
+//! [0]        op       1 = BOOTREQUEST, 2 = BOOTREPLY
+//! [1]        htype    1 = Ethernet
+//! [2]        hlen     6 for Ethernet
+//! [3]        hops
+//! [4..8]     xid      transaction ID
+//! [8..12]    secs, flags
+//! [12..16]   ciaddr   client's current address (renewals)
+//! [16..20]   yiaddr   address offered / assigned to the client
+//! [20..24]   siaddr   next server (boot)
+//! [24..28]   giaddr   relay agent
+//! [28..44]   chaddr   client hardware address
+//! [44..236]  sname, file
+//! [236..240] magic    63 82 53 63
+//! [240..]    options  code (1), length (1), value
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// UDP port of DHCP servers (and relays).
+pub const DHCP_SERVER_PORT: u16 = 67;
+/// UDP port of DHCP clients.
+pub const DHCP_CLIENT_PORT: u16 = 68;
+
+const MAGIC_COOKIE: [u8; 4] = [0x63, 0x82, 0x53, 0x63];
+const OPTIONS_OFFSET: usize = 240;
+
+/// DHCP message type (option 53).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DhcpMessageType {
+    Discover,
+    Offer,
+    Request,
+    Decline,
+    Ack,
+    Nak,
+    Release,
+    Inform,
+    Other(u8),
+}
+
+impl DhcpMessageType {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => DhcpMessageType::Discover,
+            2 => DhcpMessageType::Offer,
+            3 => DhcpMessageType::Request,
+            4 => DhcpMessageType::Decline,
+            5 => DhcpMessageType::Ack,
+            6 => DhcpMessageType::Nak,
+            7 => DhcpMessageType::Release,
+            8 => DhcpMessageType::Inform,
+            other => DhcpMessageType::Other(other),
+        }
+    }
+
+    /// Messages only a server sends.
+    pub fn is_server_reply(&self) -> bool {
+        matches!(
+            self,
+            DhcpMessageType::Offer | DhcpMessageType::Ack | DhcpMessageType::Nak
+        )
+    }
+}
+
+/// Parsed DHCP message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DhcpInfo {
+    pub message_type: DhcpMessageType,
+    /// Transaction ID linking a client's Discover/Request to the replies
+    pub transaction_id: u32,
+    /// Client hardware address from chaddr ("aa:bb:cc:dd:ee:ff")
+    pub client_mac: String,
+    /// ciaddr — the client's current address, set on renewals
+    pub client_ip: Option<String>,
+    /// yiaddr — the address offered or assigned by the server
+    pub your_ip: Option<String>,
+    /// giaddr — relay agent that forwarded the message
+    pub relay_ip: Option<String>,
+    /// Option 54 — server identifier
+    pub server_id: Option<String>,
+    /// Option 50 — address the client asks for
+    pub requested_ip: Option<String>,
+    /// Option 12 — client hostname (or the option 81 FQDN when absent)
+    pub hostname: Option<String>,
+    /// Option 60 — vendor class identifier
+    pub vendor_class: Option<String>,
+    /// Option 51 — lease time in seconds
+    pub lease_seconds: Option<u32>,
+}
+
+impl DhcpInfo {
+    /// The address this message binds to `client_mac`, if any: the
+    /// assigned address of an ACK, or the address a client renews.
+    pub fn bound_ip(&self) -> Option<&str> {
+        match self.message_type {
+            DhcpMessageType::Ack => self.your_ip.as_deref().or(self.client_ip.as_deref()),
+            DhcpMessageType::Request | DhcpMessageType::Inform => self.client_ip.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a DHCP message from a UDP payload.
+///
+/// Returns `None` for BOOTP without the DHCP magic cookie, non-Ethernet
+/// hardware addresses, or messages without a message type option.
+pub fn parse(payload: &[u8]) -> Option<DhcpInfo> {
+    if payload.len() < OPTIONS_OFFSET || payload[236..240] != MAGIC_COOKIE {
+        return None;
+    }
+    if !matches!(payload[0], 1 | 2) || payload[1] != 1 || payload[2] != 6 {
+        return None;
+    }
+
+    let mut message_type = None;
+    let mut info = DhcpInfo {
+        message_type: DhcpMessageType::Other(0),
+        transaction_id: u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]),
+        client_mac: payload[28..34]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(":"),
+        client_ip: ipv4(&payload[12..16]),
+        your_ip: ipv4(&payload[16..20]),
+        relay_ip: ipv4(&payload[24..28]),
+        server_id: None,
+        requested_ip: None,
+        hostname: None,
+        vendor_class: None,
+        lease_seconds: None,
+    };
+    let mut fqdn = None;
+
+    let mut pos = OPTIONS_OFFSET;
+    while pos < payload.len() {
+        let code = payload[pos];
+        match code {
+            0 => {
+                pos += 1;
+                continue;
+            }
+            255 => break,
+            _ => {}
+        }
+        let Some(&len) = payload.get(pos + 1) else {
+            break;
+        };
+        let start = pos + 2;
+        let end = start + len as usize;
+        if end > payload.len() {
+            break;
+        }
+        let value = &payload[start..end];
+        pos = end;
+
+        match code {
+            53 if len == 1 => message_type = Some(DhcpMessageType::from_u8(value[0])),
+            12 => info.hostname = text(value),
+            60 => info.vendor_class = text(value),
+            54 if len == 4 => info.server_id = ipv4(value),
+            50 if len == 4 => info.requested_ip = ipv4(value),
+            51 if len == 4 => {
+                info.lease_seconds =
+                    Some(u32::from_be_bytes([value[0], value[1], value[2], value[3]]))
+            }
+            // Client FQDN: flags, two deprecated RCODE bytes, then the name.
+            // Only the ASCII form (E flag clear) is read.
+            81 if len > 3 && value[0] & 0x04 == 0 => fqdn = text(&value[3..]),
+            _ => {}
+        }
+    }
+
+    info.message_type = message_type?;
+    if info.hostname.is_none() {
+        info.hostname = fqdn;
+    }
+    Some(info)
+}
+
+/// Dotted-quad form of a 4-byte address, `None` for 0.0.0.0.
+fn ipv4(bytes: &[u8]) -> Option<String> {
+    (bytes != [0, 0, 0, 0]).then(|| format!("{}.{}.{}.{}", bytes[0], bytes[1], bytes[2], bytes[3]))
+}
+
+/// Printable option text, trimmed of padding and trailing NULs.
+fn text(value: &[u8]) -> Option<String> {
+    let s: String = String::from_utf8_lossy(value)
+        .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    (!s.is_empty()).then_some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(op: u8, msg_type: u8, yiaddr: [u8; 4], options: &[(u8, &[u8])]) -> Vec<u8> {
+        let mut pkt = vec![0u8; OPTIONS_OFFSET];
+        pkt[0] = op;
+        pkt[1] = 1;
+        pkt[2] = 6;
+        pkt[4..8].copy_from_slice(&0x3903_f326u32.to_be_bytes());
+        pkt[16..20].copy_from_slice(&yiaddr);
+        pkt[28..34].copy_from_slice(&[0x00, 0x1d, 0x9c, 0x12, 0x34, 0x56]);
+        pkt[236..240].copy_from_slice(&MAGIC_COOKIE);
+        pkt.extend_from_slice(&[53, 1, msg_type]);
+        for (code, value) in options {
+            pkt.push(*code);
+            pkt.push(value.len() as u8);
+            pkt.extend_from_slice(value);
+        }
+        pkt.push(255);
+        pkt
+    }
+
+    #[test]
+    fn test_parse_request_with_hostname_and_vendor_class() {
+        let pkt = message(
+            1,
+            3,
+            [0; 4],
+            &[
+                (50, &[192, 168, 10, 40]),
+                (54, &[192, 168, 10, 1]),
+                (12, b"HMI-LINE2\0"),
+                (60, b"MSFT 5.0"),
+            ],
+        );
+        let info = parse(&pkt).expect("should parse DHCP");
+        assert_eq!(info.message_type, DhcpMessageType::Request);
+        assert_eq!(info.transaction_id, 0x3903_f326);
+        assert_eq!(info.client_mac, "00:1d:9c:12:34:56");
+        assert_eq!(info.hostname.as_deref(), Some("HMI-LINE2"));
+        assert_eq!(info.vendor_class.as_deref(), Some("MSFT 5.0"));
+        assert_eq!(info.requested_ip.as_deref(), Some("192.168.10.40"));
+        assert_eq!(info.server_id.as_deref(), Some("192.168.10.1"));
+        assert!(info.client_ip.is_none());
+        assert!(info.bound_ip().is_none());
+    }
+
+    #[test]
+    fn test_parse_ack_binds_address() {
+        let pkt = message(
+            2,
+            5,
+            [192, 168, 10, 40],
+            &[(54, &[192, 168, 10, 1]), (51, &86400u32.to_be_bytes())],
+        );
+        let info = parse(&pkt).unwrap();
+        assert!(info.message_type.is_server_reply());
+        assert_eq!(info.bound_ip(), Some("192.168.10.40"));
+        assert_eq!(info.lease_seconds, Some(86400));
+    }
+
+    #[test]
+    fn test_fqdn_fallback_and_rejects() {
+        let pkt = message(1, 1, [0; 4], &[(81, b"\x00\x00\x00plc-7.plant.local")]);
+        assert_eq!(
+            parse(&pkt).unwrap().hostname.as_deref(),
+            Some("plc-7.plant.local")
+        );
+
+        // Plain BOOTP (no cookie) and truncated packets
+        let mut bootp = message(1, 1, [0; 4], &[]);
+        bootp[236] = 0;
+        assert!(parse(&bootp).is_none());
+        assert!(parse(&[0u8; 100]).is_none());
+
+        // No message type option
+        let mut no_type = message(1, 1, [0; 4], &[]);
+        no_type.truncate(OPTIONS_OFFSET);
+        no_type.push(255);
+        assert!(parse(&no_type).is_none());
+    }
+}
@@ -0,0 +1,199 @@
+//! Client/server orientation of a conversation.
+//!
+//! Packets of one conversation travel both ways, so a connection keyed by
+//! the first packet's direction may end up with the client as destination
+//! (a capture that starts mid-stream on a response) or be recorded twice.
+//! [`orient`] decides which end is the server so connections can always be
+//! shown client → server. Evidence is taken in order of strength:
+//!
+//! 1. The sender of a bare SYN (SYN without ACK) is the client.
+//! 2. A well-known service port on exactly one end marks the server; failing
+//!    that, a system port (< 1024) facing a non-system port.
+//! 3. Protocol role data (e.g. the Modbus slave or DNP3 outstation end).
+//! 4. Otherwise the first packet's sender is assumed to be the client.
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::{identify_by_port, IcsProtocol};
+
+/// Which evidence decided a connection's orientation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectionBasis {
+    /// A SYN without ACK was seen from the client
+    Syn,
+    /// The server end listens on a well-known or system port
+    ServerPort,
+    /// Protocol role data names the server end
+    Role,
+    /// Taken from an external flow record's originator (Zeek, Suricata)
+    FlowRecord,
+    /// No evidence; the first packet's sender is taken as the client
+    FirstPacket,
+    /// Not recorded (sessions saved before orientation was tracked)
+    #[default]
+    Unknown,
+}
+
+impl DirectionBasis {
+    /// Storage / wire name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DirectionBasis::Syn => "syn",
+            DirectionBasis::ServerPort => "server_port",
+            DirectionBasis::Role => "role",
+            DirectionBasis::FlowRecord => "flow_record",
+            DirectionBasis::FirstPacket => "first_packet",
+            DirectionBasis::Unknown => "unknown",
+        }
+    }
+
+    /// Parse a storage name. Unknown values fall back to `Unknown`.
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "syn" => DirectionBasis::Syn,
+            "server_port" => DirectionBasis::ServerPort,
+            "role" => DirectionBasis::Role,
+            "flow_record" => DirectionBasis::FlowRecord,
+            "first_packet" => DirectionBasis::FirstPacket,
+            _ => DirectionBasis::Unknown,
+        }
+    }
+}
+
+/// Evidence gathered for one conversation, relative to its current
+/// source → destination orientation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirectionEvidence {
+    pub src_port: u16,
+    pub dst_port: u16,
+    /// Who sent the first bare SYN: `Some(true)` for the current source
+    pub syn_from_src: Option<bool>,
+    /// Which end role data marks as server: `Some(true)` for the current
+    /// destination; `None` when neither or both ends claim the role
+    pub role_server_is_dst: Option<bool>,
+}
+
+/// Result of [`orient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Orientation {
+    /// Source and destination must be exchanged so the server is the destination
+    pub swap: bool,
+    pub basis: DirectionBasis,
+}
+
+/// Decide which end of a conversation is the server.
+pub fn orient(evidence: &DirectionEvidence) -> Orientation {
+    if let Some(from_src) = evidence.syn_from_src {
+        return Orientation {
+            swap: !from_src,
+            basis: DirectionBasis::Syn,
+        };
+    }
+
+    if let Some(server_is_dst) = server_by_port(evidence.src_port, evidence.dst_port) {
+        return Orientation {
+            swap: !server_is_dst,
+            basis: DirectionBasis::ServerPort,
+        };
+    }
+
+    if let Some(server_is_dst) = evidence.role_server_is_dst {
+        return Orientation {
+            swap: !server_is_dst,
+            basis: DirectionBasis::Role,
+        };
+    }
+
+    Orientation {
+        swap: false,
+        basis: DirectionBasis::FirstPacket,
+    }
+}
+
+/// `Some(true)` when ports mark the destination as the server, `Some(false)`
+/// for the source, `None` when they do not tell the ends apart.
+fn server_by_port(src_port: u16, dst_port: u16) -> Option<bool> {
+    let known = |port: u16| identify_by_port(0, port) != IcsProtocol::Unknown;
+    match (known(src_port), known(dst_port)) {
+        (false, true) => return Some(true),
+        (true, false) => return Some(false),
+        _ => {}
+    }
+
+    let system = |port: u16| port != 0 && port < 1024;
+    match (system(src_port), system(dst_port)) {
+        (false, true) => Some(true),
+        (true, false) => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evidence(src_port: u16, dst_port: u16) -> DirectionEvidence {
+        DirectionEvidence {
+            src_port,
+            dst_port,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_server_port_orientation() {
+        // Request to a PLC: already client → server
+        let o = orient(&evidence(49152, 502));
+        assert_eq!(o.basis, DirectionBasis::ServerPort);
+        assert!(!o.swap);
+
+        // Capture started on the response
+        assert!(orient(&evidence(502, 49152)).swap);
+
+        // System port without a known protocol
+        assert!(orient(&evidence(873, 50000)).swap);
+    }
+
+    #[test]
+    fn test_syn_outranks_ports() {
+        // A PLC opening a connection from 502 to a historian's ephemeral port
+        let o = orient(&DirectionEvidence {
+            syn_from_src: Some(false),
+            ..evidence(502, 51000)
+        });
+        assert_eq!(o.basis, DirectionBasis::Syn);
+        assert!(o.swap);
+    }
+
+    #[test]
+    fn test_role_and_fallback() {
+        // BACnet/IP: both ends on 47808, so only role data can tell
+        let both = evidence(47808, 47808);
+        let o = orient(&DirectionEvidence {
+            role_server_is_dst: Some(false),
+            ..both
+        });
+        assert_eq!(o.basis, DirectionBasis::Role);
+        assert!(o.swap);
+
+        let o = orient(&both);
+        assert_eq!(o.basis, DirectionBasis::FirstPacket);
+        assert!(!o.swap);
+    }
+
+    #[test]
+    fn test_basis_names_round_trip() {
+        for basis in [
+            DirectionBasis::Syn,
+            DirectionBasis::ServerPort,
+            DirectionBasis::Role,
+            DirectionBasis::FlowRecord,
+            DirectionBasis::FirstPacket,
+            DirectionBasis::Unknown,
+        ] {
+            assert_eq!(DirectionBasis::from_name(basis.as_str()), basis);
+        }
+        assert_eq!(DirectionBasis::from_name(""), DirectionBasis::Unknown);
+    }
+}
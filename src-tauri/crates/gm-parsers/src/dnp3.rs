@@ -2,10 +2,12 @@
 //!
 //! Extracts application-layer details from DNP3 payloads:
 //! - Data link layer: start bytes (0x05 0x64), length, control, source/destination addresses
-//! - Transport layer: FIN/FIR bits, sequence number
+//! - Data link CRCs: header and per-16-byte block CRCs checked and stripped
+//! - Transport layer: FIN/FIR bits, sequence number, multi-frame reassembly
 //! - Application layer: function codes, object headers
 //! - Master/outstation role detection
 //! - Unsolicited response detection (FC 130)
+//! - Secure Authentication (SAv5) detection: FC 32/33/131 and group 120 objects
 //!
 //! Reference: IEEE 1815-2012 (DNP3)
 //!
@@ -15,8 +17,13 @@
 //! The Control byte's DIR bit (bit 7) indicates direction:
 //!   1 = from master, 0 = from outstation
 
+use std::collections::HashMap;
+
+use gm_capture::ParsedPacket;
 use serde::Serialize;
 
+use crate::function_codes::{self, FunctionCodeProtocol};
+
 /// DNP3 start bytes — every DNP3 data link frame begins with these
 const DNP3_START_1: u8 = 0x05;
 const DNP3_START_2: u8 = 0x64;
@@ -24,6 +31,9 @@ const DNP3_START_2: u8 = 0x64;
 /// Minimum DNP3 data link header size: start(2) + length(1) + control(1) + dst(2) + src(2) + crc(2) = 10
 const DNP3_LINK_HEADER_SIZE: usize = 10;
 
+/// Object group carrying Secure Authentication messages (IEEE 1815-2012 §7)
+pub const SECURE_AUTH_GROUP: u8 = 120;
+
 /// Parsed DNP3 packet information.
 #[derive(Debug, Clone, Serialize)]
 pub struct Dnp3Info {
@@ -53,6 +63,19 @@ pub struct Dnp3Info {
     pub app_confirm_requested: bool,
     /// Application UNS bit (unsolicited)
     pub app_unsolicited: bool,
+    /// Group of the first object header (if present)
+    pub object_group: Option<u8>,
+    /// Variation of the first object header (if present)
+    pub object_variation: Option<u8>,
+    /// Whether this fragment is part of a Secure Authentication exchange
+    /// (an SA function code or a group 120 object)
+    pub is_secure_auth: bool,
+    /// Link-layer CRC mismatches in this frame (header and data blocks)
+    pub crc_errors: u32,
+    /// Transport segments the application fields were read from: 1 for a
+    /// single-frame fragment, more once a multi-frame fragment completes,
+    /// 0 for frames without application data
+    pub transport_segments: u16,
 }
 
 /// Master/outstation role classification for a DNP3 device.
@@ -67,17 +90,76 @@ pub enum Dnp3Role {
     Unknown,
 }
 
+/// Whether a payload starts with a DNP3 link header whose length and CRC
+/// check out, which is how DNP3 is recognized off port 20000.
+pub fn is_dnp3(payload: &[u8]) -> bool {
+    payload.len() >= DNP3_LINK_HEADER_SIZE
+        && payload[0] == DNP3_START_1
+        && payload[1] == DNP3_START_2
+        // Length counts control, addresses and user data: at least 5
+        && payload[2] >= 5
+        && crc16_dnp(&payload[..8]) == u16::from_le_bytes([payload[8], payload[9]])
+}
+
+/// CRC-16/DNP (polynomial 0x3D65, reflected as 0xA6BC, output inverted),
+/// sent little-endian after the link header and after each data block.
+pub fn crc16_dnp(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= u16::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA6BC
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Largest application fragment held while its transport segments arrive.
+/// IEEE 1815 recommends 2048 bytes; some vendors allow more.
+const MAX_FRAGMENT_SIZE: usize = 65_536;
+
+/// Partially received fragments kept at once before new ones are ignored.
+const MAX_PENDING_FRAGMENTS: usize = 4_096;
+
 /// Attempt to parse a DNP3 payload.
 ///
 /// The payload should be the TCP or UDP application-layer data.
 /// Returns None if the payload doesn't start with DNP3 start bytes
 /// or is too short to be a valid frame.
 ///
+/// The link header and data-block CRCs are checked (mismatches are counted
+/// in `crc_errors`) and stripped before the transport and application
+/// layers are read. Only the frame's own transport segment is decoded;
+/// use [`TransportReassembler`] to decode fragments spanning several frames.
+///
 /// # Arguments
 /// * `payload` - Raw application-layer payload bytes
 /// * `src_port` - Source port (used as secondary role detection)
 /// * `dst_port` - Destination port (used as secondary role detection)
 pub fn parse_dnp3(payload: &[u8], src_port: u16, dst_port: u16) -> Option<Dnp3Info> {
+    let (mut info, user_data) = parse_link_frame(payload, src_port, dst_port)?;
+    if let Some((&transport, segment)) = user_data.split_first() {
+        info.apply_transport(transport);
+        // A segment other than the first does not start with an
+        // application header
+        if info.transport_fir {
+            info.apply_application(segment);
+        }
+    }
+    Some(info)
+}
+
+/// Read the data link layer of one frame and return it with the frame's
+/// user data (transport segment), CRCs removed.
+///
+/// A frame whose header CRC does not match is still read, with the user
+/// data taken as-is: the start of the segment (transport and application
+/// headers) is usable even if the blocks are not.
+fn parse_link_frame(payload: &[u8], src_port: u16, dst_port: u16) -> Option<(Dnp3Info, Vec<u8>)> {
     // Validate minimum length and start bytes
     if payload.len() < DNP3_LINK_HEADER_SIZE {
         return None;
@@ -87,7 +169,7 @@ pub fn parse_dnp3(payload: &[u8], src_port: u16, dst_port: u16) -> Option<Dnp3In
         return None;
     }
 
-    let _length = payload[2];
+    let length = payload[2];
     let control = payload[3];
 
     // DNP3 addresses are little-endian
@@ -114,113 +196,207 @@ pub fn parse_dnp3(payload: &[u8], src_port: u16, dst_port: u16) -> Option<Dnp3In
         }
     };
 
-    // Try to extract transport and application layer info
-    // After the 10-byte link header, we have data blocks with CRC
-    // The transport header is the first byte of the first data block
-    let mut transport_seq: Option<u8> = None;
-    let mut transport_fin = false;
-    let mut transport_fir = false;
-    let mut function_code: Option<u8> = None;
-    let mut is_unsolicited = false;
-    let mut app_sequence: Option<u8> = None;
-    let mut app_confirm_requested = false;
-    let mut app_unsolicited = false;
-
-    // After the 10-byte link header, the user data starts.
-    // In DNP3 over TCP (as used in most modern systems), the CRC bytes
-    // may or may not be present. Many implementations strip CRCs for TCP.
-    // We attempt to read transport + application layer starting at offset 10.
-    if payload.len() > DNP3_LINK_HEADER_SIZE {
-        let transport_byte = payload[DNP3_LINK_HEADER_SIZE];
-        // Transport header: FIN(bit7) | FIR(bit6) | SEQUENCE(bits 5-0)
-        transport_fin = (transport_byte & 0x80) != 0;
-        transport_fir = (transport_byte & 0x40) != 0;
-        transport_seq = Some(transport_byte & 0x3F);
-
-        // Application layer starts after transport header (offset 11)
-        if payload.len() > DNP3_LINK_HEADER_SIZE + 1 {
-            let app_offset = DNP3_LINK_HEADER_SIZE + 1;
-
-            // Application control byte
-            if payload.len() > app_offset {
-                let app_control = payload[app_offset];
-                // Application control: FIR(bit7) | FIN(bit6) | CON(bit5) | UNS(bit4) | SEQ(bits 3-0)
-                app_confirm_requested = (app_control & 0x20) != 0;
-                app_unsolicited = (app_control & 0x10) != 0;
-                app_sequence = Some(app_control & 0x0F);
+    // After the header, user data comes in blocks of up to 16 bytes, each
+    // followed by its own CRC
+    let mut crc_errors = 0;
+    let mut user_data = Vec::new();
+    let body = &payload[DNP3_LINK_HEADER_SIZE..];
+    if crc16_dnp(&payload[..8]) == u16::from_le_bytes([payload[8], payload[9]]) {
+        let mut remaining = (length as usize).saturating_sub(5);
+        let mut blocks = body;
+        while remaining > 0 && !blocks.is_empty() {
+            let size = remaining.min(16);
+            if blocks.len() < size + 2 {
+                // Truncated capture: keep what there is, unchecked
+                user_data.extend_from_slice(&blocks[..size.min(blocks.len())]);
+                break;
             }
-
-            // Application function code is next byte
-            if payload.len() > app_offset + 1 {
-                let fc = payload[app_offset + 1];
-                function_code = Some(fc);
-
-                // FC 130 (0x82) is Unsolicited Response
-                is_unsolicited = fc == 130;
+            let (block, crc) = (&blocks[..size], &blocks[size..size + 2]);
+            if crc16_dnp(block) != u16::from_le_bytes([crc[0], crc[1]]) {
+                crc_errors += 1;
             }
+            user_data.extend_from_slice(block);
+            blocks = &blocks[size + 2..];
+            remaining -= size;
         }
+    } else {
+        crc_errors += 1;
+        user_data.extend_from_slice(body);
     }
 
-    Some(Dnp3Info {
+    let info = Dnp3Info {
         source_address,
         destination_address,
         from_master,
         is_primary,
-        function_code,
-        is_unsolicited,
+        function_code: None,
+        is_unsolicited: false,
         role,
-        transport_seq,
-        transport_fin,
-        transport_fir,
-        app_sequence,
-        app_confirm_requested,
-        app_unsolicited,
-    })
+        transport_seq: None,
+        transport_fin: false,
+        transport_fir: false,
+        app_sequence: None,
+        app_confirm_requested: false,
+        app_unsolicited: false,
+        object_group: None,
+        object_variation: None,
+        is_secure_auth: false,
+        crc_errors,
+        transport_segments: 0,
+    };
+    Some((info, user_data))
 }
 
-/// Human-readable name for a DNP3 application layer function code.
-pub fn function_code_name(fc: u8) -> &'static str {
-    match fc {
-        0 => "Confirm",
-        1 => "Read",
-        2 => "Write",
-        3 => "Select",
-        4 => "Operate",
-        5 => "Direct Operate",
-        6 => "Direct Operate No Ack",
-        7 => "Immediate Freeze",
-        8 => "Immediate Freeze No Ack",
-        9 => "Freeze and Clear",
-        10 => "Freeze and Clear No Ack",
-        11 => "Freeze At Time",
-        12 => "Freeze At Time No Ack",
-        13 => "Cold Restart",
-        14 => "Warm Restart",
-        15 => "Initialize Data",
-        16 => "Initialize Application",
-        17 => "Start Application",
-        18 => "Stop Application",
-        20 => "Enable Unsolicited",
-        21 => "Disable Unsolicited",
-        22 => "Assign Class",
-        23 => "Delay Measurement",
-        24 => "Record Current Time",
-        25 => "Open File",
-        26 => "Close File",
-        27 => "Delete File",
-        28 => "Get File Info",
-        29 => "Authenticate File",
-        30 => "Abort File",
-        129 => "Response",
-        130 => "Unsolicited Response",
-        _ => "Unknown",
+impl Dnp3Info {
+    /// Transport header: FIN(bit7) | FIR(bit6) | SEQUENCE(bits 5-0)
+    fn apply_transport(&mut self, transport: u8) {
+        self.transport_fin = (transport & 0x80) != 0;
+        self.transport_fir = (transport & 0x40) != 0;
+        self.transport_seq = Some(transport & 0x3F);
+    }
+
+    /// Read the application header and first object header of a fragment.
+    fn apply_application(&mut self, apdu: &[u8]) {
+        self.transport_segments = self.transport_segments.max(1);
+
+        // Application control: FIR(bit7) | FIN(bit6) | CON(bit5) | UNS(bit4) | SEQ(bits 3-0)
+        if let Some(&app_control) = apdu.first() {
+            self.app_confirm_requested = (app_control & 0x20) != 0;
+            self.app_unsolicited = (app_control & 0x10) != 0;
+            self.app_sequence = Some(app_control & 0x0F);
+        }
+
+        // Application function code is next byte
+        if let Some(&fc) = apdu.get(1) {
+            self.function_code = Some(fc);
+
+            // FC 130 (0x82) is Unsolicited Response
+            self.is_unsolicited = fc == 130;
+
+            // Responses carry 2 bytes of internal indications before
+            // the first object header: [Group: 1][Variation: 1][Qualifier: 1]
+            let objects_offset = if fc >= 129 { 4 } else { 2 };
+            if apdu.len() > objects_offset + 1 {
+                self.object_group = Some(apdu[objects_offset]);
+                self.object_variation = Some(apdu[objects_offset + 1]);
+            }
+        }
+
+        self.is_secure_auth = matches!(self.function_code, Some(32 | 33 | 131))
+            || self.object_group == Some(SECURE_AUTH_GROUP);
     }
 }
 
+/// Who a fragment travels between: IPs and DNP3 link addresses.
+type FragmentKey = (String, String, u16, u16);
+
+#[derive(Debug)]
+struct PendingFragment {
+    next_seq: u8,
+    segments: u16,
+    data: Vec<u8>,
+}
+
+/// Joins DNP3 transport segments (one per link frame) into application
+/// fragments.
+///
+/// A fragment larger than a link frame's 249 bytes of user data is sent as
+/// a run of segments: FIR on the first, FIN on the last, sequence numbers
+/// counting up modulo 64. Frames in the middle of a run are returned with
+/// only their link and transport fields; the frame carrying FIN gets the
+/// application fields of the whole fragment. A gap in the sequence drops
+/// the fragment.
+#[derive(Debug, Default)]
+pub struct TransportReassembler {
+    pending: HashMap<FragmentKey, PendingFragment>,
+}
+
+impl TransportReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse one link frame sent in `packet`.
+    pub fn parse(&mut self, packet: &ParsedPacket, frame: &[u8]) -> Option<Dnp3Info> {
+        let (mut info, user_data) = parse_link_frame(frame, packet.src_port, packet.dst_port)?;
+        // Link-layer-only frames (reset, ACK, status) carry no segment
+        let Some((&transport, segment)) = user_data.split_first() else {
+            return Some(info);
+        };
+        info.apply_transport(transport);
+        let seq = transport & 0x3F;
+        let key = (
+            packet.src_ip.clone(),
+            packet.dst_ip.clone(),
+            info.source_address,
+            info.destination_address,
+        );
+
+        if info.transport_fir {
+            if info.transport_fin {
+                self.pending.remove(&key);
+                info.apply_application(segment);
+            } else if self.pending.len() < MAX_PENDING_FRAGMENTS || self.pending.contains_key(&key)
+            {
+                self.pending.insert(
+                    key,
+                    PendingFragment {
+                        next_seq: (seq + 1) & 0x3F,
+                        segments: 1,
+                        data: segment.to_vec(),
+                    },
+                );
+            }
+            return Some(info);
+        }
+
+        let Some(fragment) = self.pending.get_mut(&key) else {
+            return Some(info);
+        };
+        if fragment.next_seq != seq || fragment.data.len() + segment.len() > MAX_FRAGMENT_SIZE {
+            self.pending.remove(&key);
+            return Some(info);
+        }
+        fragment.data.extend_from_slice(segment);
+        fragment.segments = fragment.segments.saturating_add(1);
+        fragment.next_seq = (seq + 1) & 0x3F;
+
+        if info.transport_fin {
+            if let Some(fragment) = self.pending.remove(&key) {
+                info.apply_application(&fragment.data);
+                info.transport_segments = fragment.segments;
+            }
+        }
+        Some(info)
+    }
+}
+
+/// Human-readable name for a DNP3 application layer function code.
+///
+/// Reads the function code registry, so codes registered at runtime are
+/// named too.
+pub fn function_code_name(fc: u8) -> String {
+    function_codes::function_code_name(FunctionCodeProtocol::Dnp3, fc)
+}
+
+/// Whether a request with this function code writes to the outstation.
+pub fn is_write_function_code(fc: u8) -> bool {
+    function_codes::is_write_function_code(FunctionCodeProtocol::Dnp3, fc)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_crc16_dnp() {
+        // CRC-16/DNP check value
+        assert_eq!(crc16_dnp(b"123456789"), 0xEA82);
+        let header = [0x05, 0x64, 0x05, 0xC0, 0x01, 0x00, 0x00, 0x04, 0xE9, 0x21];
+        assert!(is_dnp3(&header));
+        assert!(!is_dnp3(&header[..9]));
+    }
+
     #[test]
     fn test_parse_dnp3_master_request() {
         // DNP3 frame: start bytes, length, control (DIR=1, PRM=1), dst=1, src=100
@@ -293,6 +469,154 @@ mod tests {
         assert_eq!(info.role, Dnp3Role::Outstation);
     }
 
+    #[test]
+    fn test_parse_dnp3_secure_auth_challenge() {
+        // Outstation challenges a critical request: FC 131 + g120v1
+        let payload: Vec<u8> = vec![
+            0x05, 0x64, 0x14, 0x44, // Start, length, control (from outstation)
+            0x64, 0x00, // Destination
+            0x01, 0x00, // Source
+            0x00, 0x00, // CRC
+            0xC0, // Transport
+            0xC1, // App control
+            0x83, // FC 131: Authenticate Response
+            0x00, 0x00, // IIN
+            0x78, 0x01, 0x5B, // g120v1, qualifier 0x5B (free format)
+        ];
+
+        let info = parse_dnp3(&payload, 20000, 49152).unwrap();
+        assert_eq!(info.function_code, Some(131));
+        assert_eq!(info.object_group, Some(SECURE_AUTH_GROUP));
+        assert_eq!(info.object_variation, Some(1));
+        assert!(info.is_secure_auth);
+    }
+
+    #[test]
+    fn test_parse_dnp3_aggressive_mode_request() {
+        // Master sends a Direct Operate prefixed with g120v3 (aggressive mode)
+        let payload: Vec<u8> = vec![
+            0x05, 0x64, 0x14, 0xC4, // Start, length, control (from master)
+            0x01, 0x00, // Destination
+            0x64, 0x00, // Source
+            0x00, 0x00, // CRC
+            0xC0, // Transport
+            0xC2, // App control
+            0x05, // FC 5: Direct Operate
+            0x78, 0x03, 0x07, // g120v3, qualifier 0x07
+        ];
+
+        let info = parse_dnp3(&payload, 49152, 20000).unwrap();
+        assert_eq!(info.object_group, Some(120));
+        assert_eq!(info.object_variation, Some(3));
+        assert!(info.is_secure_auth);
+
+        // The plaintext read from the first test carries no SA objects
+        let read: Vec<u8> = vec![
+            0x05, 0x64, 0x0B, 0xC4, 0x01, 0x00, 0x64, 0x00, 0x00, 0x00, 0xC0, 0xC0, 0x01, 0x3C,
+            0x02, 0x06,
+        ];
+        let info = parse_dnp3(&read, 49152, 20000).unwrap();
+        assert_eq!(info.object_group, Some(60));
+        assert!(!info.is_secure_auth);
+    }
+
+    /// Build a link frame with correct header and block CRCs.
+    fn frame(control: u8, dst: u16, src: u16, user_data: &[u8]) -> Vec<u8> {
+        let mut frame = vec![
+            DNP3_START_1,
+            DNP3_START_2,
+            5 + user_data.len() as u8,
+            control,
+        ];
+        frame.extend_from_slice(&dst.to_le_bytes());
+        frame.extend_from_slice(&src.to_le_bytes());
+        frame.extend_from_slice(&crc16_dnp(&frame).to_le_bytes());
+        for block in user_data.chunks(16) {
+            frame.extend_from_slice(block);
+            frame.extend_from_slice(&crc16_dnp(block).to_le_bytes());
+        }
+        frame
+    }
+
+    fn packet_from_outstation() -> ParsedPacket {
+        ParsedPacket {
+            timestamp: Default::default(),
+            src_mac: None,
+            dst_mac: None,
+            vlan_id: None,
+            outer_vlan_id: None,
+            mpls_labels: Vec::new(),
+            vxlan_vni: None,
+            src_ip: "10.0.0.20".to_string(),
+            dst_ip: "10.0.0.1".to_string(),
+            transport: gm_capture::TransportProtocol::Tcp,
+            src_port: 20000,
+            dst_port: 49152,
+            tcp_flags: 0,
+            tcp_seq: 0,
+            length: 0,
+            payload: Vec::new(),
+            origin_file: "test.pcap".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_block_crcs_checked_and_stripped() {
+        // Response with 20 bytes of user data: two blocks, the first CRC
+        // sitting where a CRC-unaware reader would expect object data
+        let mut user_data = vec![0xC0, 0xC0, 0x81, 0x00, 0x00, 0x1E, 0x02, 0x00];
+        user_data.extend_from_slice(&[0x00, 0x03, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00]);
+        user_data.extend_from_slice(&[0x00, 0x00, 0x01, 0x00]);
+        let mut payload = frame(0x44, 100, 1, &user_data);
+
+        let info = parse_dnp3(&payload, 20000, 49152).unwrap();
+        assert_eq!(info.crc_errors, 0);
+        assert_eq!(info.function_code, Some(129));
+        assert_eq!(info.object_group, Some(30));
+        assert_eq!(info.object_variation, Some(2));
+        assert_eq!(info.transport_segments, 1);
+
+        // Flip a bit in the second block, as a noisy serial line would
+        let last = payload.len() - 3;
+        payload[last] ^= 0x01;
+        let info = parse_dnp3(&payload, 20000, 49152).unwrap();
+        assert_eq!(info.crc_errors, 1);
+        assert_eq!(info.function_code, Some(129));
+    }
+
+    #[test]
+    fn test_transport_reassembly_across_frames() {
+        // A Read response split into two transport segments
+        let first = frame(0x44, 100, 1, &[0x40, 0xC3, 0x81, 0x00, 0x00]);
+        let second = frame(0x44, 100, 1, &[0x81, 0x01, 0x02, 0x00, 0x00, 0x07]);
+        let packet = packet_from_outstation();
+        let mut reassembler = TransportReassembler::new();
+
+        let info = reassembler.parse(&packet, &first).unwrap();
+        assert!(info.transport_fir && !info.transport_fin);
+        assert_eq!(info.function_code, None);
+        assert_eq!(info.transport_segments, 0);
+
+        let info = reassembler.parse(&packet, &second).unwrap();
+        assert!(info.transport_fin);
+        assert_eq!(info.function_code, Some(129));
+        assert_eq!(info.object_group, Some(1));
+        assert_eq!(info.object_variation, Some(2));
+        assert_eq!(info.transport_segments, 2);
+
+        // Out of sequence: the continuation is dropped, not misread
+        reassembler.parse(&packet, &first).unwrap();
+        let gap = frame(0x44, 100, 1, &[0x85, 0x01, 0x02, 0x00, 0x00, 0x07]);
+        let info = reassembler.parse(&packet, &gap).unwrap();
+        assert_eq!(info.function_code, None);
+
+        // Stateless parsing never reads a continuation as an application header
+        assert_eq!(
+            parse_dnp3(&second, 20000, 49152).unwrap().function_code,
+            None
+        );
+    }
+
     #[test]
     fn test_parse_dnp3_invalid_start_bytes() {
         let payload: Vec<u8> = vec![
@@ -314,6 +638,7 @@ mod tests {
         assert_eq!(function_code_name(2), "Write");
         assert_eq!(function_code_name(129), "Response");
         assert_eq!(function_code_name(130), "Unsolicited Response");
+        assert_eq!(function_code_name(131), "Authenticate Response");
         assert_eq!(function_code_name(200), "Unknown");
     }
 }
@@ -0,0 +1,430 @@
+//! DNS response parser.
+//!
+//! Only responses are of interest: an A/AAAA answer ties a name to an
+//! address, and a PTR answer for `d.c.b.a.in-addr.arpa` names the host at
+//! `a.b.c.d`. Either lets an asset seen only by IP be shown by the name the
+//! site's DNS gives it.
+//!
+//! Multicast DNS (UDP 5353) and LLMNR (UDP 5355) use the same message
+//! format, so their responses go through [`parse`] too. Hosts answer for
+//! their own names there, often unsolicited at boot, which names Windows
+//! and Apple machines on networks without a DNS server.
+//!
+//! ## Message Format (UDP/TCP 53)
+//! ```text
+//! [0..2]   ID
+//! [2..4]   flags    QR (0x8000), opcode, AA, TC, RD, RA, RCODE (low 4 bits)
+//! [4..6]   QDCOUNT
+//! [6..8]   ANCOUNT
+//! [8..10]  NSCOUNT
+//! [10..12] ARCOUNT
+//! [12..]   questions: name, type (2), class (2)
+//!          answers:   name, type (2), class (2), TTL (4), RDLENGTH (2), RDATA
+//! ```
+//! Names are label sequences, possibly ending in a compression pointer
+//! (two bytes, top bits `11`) to an earlier name in the message. Over TCP
+//! the message is preceded by a 2-byte length. mDNS sets the top bit of a
+//! record's class as its cache-flush flag and often puts the A record in
+//! the additional section, so records of all three sections are read.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+
+/// UDP/TCP port of DNS servers.
+pub const DNS_PORT: u16 = 53;
+/// UDP port of multicast DNS (RFC 6762).
+pub const MDNS_PORT: u16 = 5353;
+/// UDP port of Link-Local Multicast Name Resolution (RFC 4795).
+pub const LLMNR_PORT: u16 = 5355;
+
+const HEADER_LEN: usize = 12;
+const TYPE_A: u16 = 1;
+const TYPE_CNAME: u16 = 5;
+const TYPE_PTR: u16 = 12;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+/// mDNS cache-flush bit in a record's class field
+const CLASS_FLUSH: u16 = 0x8000;
+/// Pointer hops allowed while decoding one name (guards against loops)
+const MAX_POINTER_HOPS: usize = 16;
+/// Longest name on the wire (RFC 1035 §3.1)
+const MAX_NAME_LEN: usize = 255;
+
+/// Data of an answer record. Record types other than these are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "value")]
+pub enum DnsRecordData {
+    A(String),
+    Aaaa(String),
+    Cname(String),
+    Ptr(String),
+}
+
+/// One answer record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsAnswer {
+    /// Owner name, without the trailing dot
+    pub name: String,
+    pub ttl: u32,
+    pub data: DnsRecordData,
+}
+
+/// How a [`DnsBinding`] was learned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsBindingSource {
+    /// Reverse lookup: PTR for the address
+    Ptr,
+    /// Forward lookup: A or AAAA answer
+    Forward,
+}
+
+/// A name the DNS gives an address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsBinding {
+    pub ip: String,
+    pub name: String,
+    pub source: DnsBindingSource,
+}
+
+/// Parsed DNS response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsInfo {
+    pub id: u16,
+    /// Response code (0 = NOERROR, 3 = NXDOMAIN)
+    pub rcode: u8,
+    /// Names asked about in the question section
+    pub questions: Vec<String>,
+    /// Records of the supported types from the answer, authority and
+    /// additional sections
+    pub answers: Vec<DnsAnswer>,
+}
+
+impl DnsInfo {
+    /// Address → name pairs carried by the answers.
+    pub fn bindings(&self) -> Vec<DnsBinding> {
+        self.answers
+            .iter()
+            .filter_map(|answer| match &answer.data {
+                DnsRecordData::A(ip) | DnsRecordData::Aaaa(ip) => Some(DnsBinding {
+                    ip: ip.clone(),
+                    name: answer.name.clone(),
+                    source: DnsBindingSource::Forward,
+                }),
+                DnsRecordData::Ptr(target) => reverse_name_ip(&answer.name).map(|ip| DnsBinding {
+                    ip,
+                    name: target.clone(),
+                    source: DnsBindingSource::Ptr,
+                }),
+                DnsRecordData::Cname(_) => None,
+            })
+            .filter(|b| !b.name.is_empty())
+            .collect()
+    }
+}
+
+/// Parse a DNS response from a UDP payload.
+///
+/// Returns `None` for queries, malformed headers, and responses whose
+/// question section cannot be decoded. Answers are read until the first
+/// malformed record; what was read before it is kept.
+pub fn parse(payload: &[u8]) -> Option<DnsInfo> {
+    if payload.len() < HEADER_LEN {
+        return None;
+    }
+    let flags = u16::from_be_bytes([payload[2], payload[3]]);
+    if flags & 0x8000 == 0 {
+        return None;
+    }
+    let qdcount = u16::from_be_bytes([payload[4], payload[5]]);
+    let rrcount = [6, 8, 10]
+        .iter()
+        .map(|&i| u16::from_be_bytes([payload[i], payload[i + 1]]) as usize)
+        .sum::<usize>();
+
+    let mut info = DnsInfo {
+        id: u16::from_be_bytes([payload[0], payload[1]]),
+        rcode: (flags & 0x000F) as u8,
+        questions: Vec::new(),
+        answers: Vec::new(),
+    };
+
+    let mut pos = HEADER_LEN;
+    for _ in 0..qdcount {
+        let (name, next) = read_name(payload, pos)?;
+        if next + 4 > payload.len() {
+            return None;
+        }
+        info.questions.push(name);
+        pos = next + 4;
+    }
+
+    for _ in 0..rrcount {
+        let Some((name, next)) = read_name(payload, pos) else {
+            break;
+        };
+        if next + 10 > payload.len() {
+            break;
+        }
+        let rtype = u16::from_be_bytes([payload[next], payload[next + 1]]);
+        let class = u16::from_be_bytes([payload[next + 2], payload[next + 3]]);
+        let ttl = u32::from_be_bytes([
+            payload[next + 4],
+            payload[next + 5],
+            payload[next + 6],
+            payload[next + 7],
+        ]);
+        let rdlength = u16::from_be_bytes([payload[next + 8], payload[next + 9]]) as usize;
+        let rdata_start = next + 10;
+        let rdata_end = rdata_start + rdlength;
+        if rdata_end > payload.len() {
+            break;
+        }
+        pos = rdata_end;
+        if class & !CLASS_FLUSH != CLASS_IN {
+            continue;
+        }
+
+        let rdata = &payload[rdata_start..rdata_end];
+        let data = match rtype {
+            TYPE_A if rdlength == 4 => Some(DnsRecordData::A(
+                Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]).to_string(),
+            )),
+            TYPE_AAAA if rdlength == 16 => {
+                let octets: [u8; 16] = rdata.try_into().ok()?;
+                Some(DnsRecordData::Aaaa(Ipv6Addr::from(octets).to_string()))
+            }
+            // Name RDATA may use pointers into the rest of the message
+            TYPE_CNAME => read_name(payload, rdata_start).map(|(n, _)| DnsRecordData::Cname(n)),
+            TYPE_PTR => read_name(payload, rdata_start).map(|(n, _)| DnsRecordData::Ptr(n)),
+            _ => None,
+        };
+        if let Some(data) = data {
+            info.answers.push(DnsAnswer { name, ttl, data });
+        }
+    }
+
+    Some(info)
+}
+
+/// Parse a DNS response carried over TCP (2-byte length prefix).
+///
+/// Only a segment holding a whole message is parsed.
+pub fn parse_tcp(payload: &[u8]) -> Option<DnsInfo> {
+    if payload.len() < 2 {
+        return None;
+    }
+    let len = u16::from_be_bytes([payload[0], payload[1]]) as usize;
+    payload.get(2..2 + len).and_then(parse)
+}
+
+/// Decode the name at `pos`. Returns the name (lowercase, no trailing dot)
+/// and the offset just past it in the original byte stream.
+pub(crate) fn read_name(msg: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut wire_len = 0;
+    let mut end = None;
+    let mut hops = 0;
+
+    loop {
+        let len = *msg.get(pos)? as usize;
+        match len & 0xC0 {
+            0x00 => {
+                if len == 0 {
+                    return Some((labels.join("."), end.unwrap_or(pos + 1)));
+                }
+                let label = msg.get(pos + 1..pos + 1 + len)?;
+                wire_len += len + 1;
+                if wire_len > MAX_NAME_LEN {
+                    return None;
+                }
+                labels.push(String::from_utf8_lossy(label).to_ascii_lowercase());
+                pos += len + 1;
+            }
+            0xC0 => {
+                let low = *msg.get(pos + 1)? as usize;
+                hops += 1;
+                if hops > MAX_POINTER_HOPS {
+                    return None;
+                }
+                end.get_or_insert(pos + 2);
+                pos = ((len & 0x3F) << 8) | low;
+            }
+            // 0x40 / 0x80 label types are obsolete or reserved
+            _ => return None,
+        }
+    }
+}
+
+/// Address named by an `in-addr.arpa` reverse name ("40.10.168.192.in-addr.arpa"
+/// → "192.168.10.40"). IPv6 `ip6.arpa` names are not decoded.
+fn reverse_name_ip(name: &str) -> Option<String> {
+    let octets = name.strip_suffix(".in-addr.arpa")?;
+    let mut parts: Vec<u8> = octets
+        .split('.')
+        .map(|p| p.parse::<u8>().ok())
+        .collect::<Option<_>>()?;
+    if parts.len() != 4 {
+        return None;
+    }
+    parts.reverse();
+    Some(Ipv4Addr::new(parts[0], parts[1], parts[2], parts[3]).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_name(name: &str) -> Vec<u8> {
+        let mut out = Vec::new();
+        for label in name.split('.') {
+            out.push(label.len() as u8);
+            out.extend_from_slice(label.as_bytes());
+        }
+        out.push(0);
+        out
+    }
+
+    fn response(question: &str, qtype: u16, answers: &[(Vec<u8>, u16, Vec<u8>)]) -> Vec<u8> {
+        let mut msg = vec![0x12, 0x34, 0x81, 0x80, 0, 1];
+        msg.extend_from_slice(&(answers.len() as u16).to_be_bytes());
+        msg.extend_from_slice(&[0, 0, 0, 0]);
+        msg.extend_from_slice(&encode_name(question));
+        msg.extend_from_slice(&qtype.to_be_bytes());
+        msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+        for (name, rtype, rdata) in answers {
+            msg.extend_from_slice(name);
+            msg.extend_from_slice(&rtype.to_be_bytes());
+            msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+            msg.extend_from_slice(&300u32.to_be_bytes());
+            msg.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            msg.extend_from_slice(rdata);
+        }
+        msg
+    }
+
+    /// Pointer to the question name, which always starts at offset 12
+    const QNAME_PTR: [u8; 2] = [0xC0, 0x0C];
+
+    #[test]
+    fn test_parse_a_response_with_cname() {
+        let msg = response(
+            "historian.plant.local",
+            TYPE_A,
+            &[
+                (
+                    QNAME_PTR.to_vec(),
+                    TYPE_CNAME,
+                    encode_name("hist01.plant.local"),
+                ),
+                (
+                    encode_name("hist01.plant.local"),
+                    TYPE_A,
+                    vec![10, 20, 0, 15],
+                ),
+            ],
+        );
+        let info = parse(&msg).expect("should parse DNS response");
+        assert_eq!(info.id, 0x1234);
+        assert_eq!(info.rcode, 0);
+        assert_eq!(info.questions, vec!["historian.plant.local"]);
+        assert_eq!(info.answers.len(), 2);
+        assert_eq!(
+            info.answers[0].data,
+            DnsRecordData::Cname("hist01.plant.local".into())
+        );
+
+        let bindings = info.bindings();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].ip, "10.20.0.15");
+        assert_eq!(bindings[0].name, "hist01.plant.local");
+        assert_eq!(bindings[0].source, DnsBindingSource::Forward);
+    }
+
+    #[test]
+    fn test_parse_ptr_response_over_tcp() {
+        let msg = response(
+            "40.10.168.192.in-addr.arpa",
+            TYPE_PTR,
+            &[(
+                QNAME_PTR.to_vec(),
+                TYPE_PTR,
+                encode_name("HMI-Line2.plant.local"),
+            )],
+        );
+        let mut tcp = (msg.len() as u16).to_be_bytes().to_vec();
+        tcp.extend_from_slice(&msg);
+
+        let info = parse_tcp(&tcp).expect("should parse DNS over TCP");
+        let bindings = info.bindings();
+        assert_eq!(
+            bindings,
+            vec![DnsBinding {
+                ip: "192.168.10.40".into(),
+                name: "hmi-line2.plant.local".into(),
+                source: DnsBindingSource::Ptr,
+            }]
+        );
+        // Truncated TCP segment
+        assert!(parse_tcp(&tcp[..tcp.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn test_rejects_queries_and_pointer_loops() {
+        let mut query = response("plc1.plant.local", TYPE_A, &[]);
+        query[2] = 0x01; // QR clear
+        assert!(parse(&query).is_none());
+        assert!(parse(&[0u8; 5]).is_none());
+
+        // Answer name pointing at itself: dropped, response still parsed
+        let looped = response(
+            "plc1.plant.local",
+            TYPE_A,
+            &[(vec![0xC0, 34], TYPE_A, vec![10, 0, 0, 1])],
+        );
+        assert_eq!(looped[34], 0xC0);
+        let info = parse(&looped).unwrap();
+        assert!(info.answers.is_empty());
+
+        assert_eq!(
+            reverse_name_ip("1.0.0.10.in-addr.arpa").as_deref(),
+            Some("10.0.0.1")
+        );
+        assert!(reverse_name_ip("256.0.0.10.in-addr.arpa").is_none());
+        assert!(reverse_name_ip("1.0.10.in-addr.arpa").is_none());
+    }
+
+    #[test]
+    fn test_parse_mdns_announcement() {
+        // Unsolicited announcement: no question, PTR answer, cache-flush A
+        // record in the additional section
+        let mut msg = vec![0, 0, 0x84, 0x00, 0, 0, 0, 1, 0, 0, 0, 1];
+        msg.extend_from_slice(&encode_name("_workstation._tcp.local"));
+        msg.extend_from_slice(&TYPE_PTR.to_be_bytes());
+        msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+        msg.extend_from_slice(&4500u32.to_be_bytes());
+        let target = encode_name("eng-ws02.local");
+        msg.extend_from_slice(&(target.len() as u16).to_be_bytes());
+        msg.extend_from_slice(&target);
+        msg.extend_from_slice(&encode_name("ENG-WS02.local"));
+        msg.extend_from_slice(&TYPE_A.to_be_bytes());
+        msg.extend_from_slice(&(CLASS_IN | CLASS_FLUSH).to_be_bytes());
+        msg.extend_from_slice(&120u32.to_be_bytes());
+        msg.extend_from_slice(&4u16.to_be_bytes());
+        msg.extend_from_slice(&[192, 168, 10, 22]);
+
+        let info = parse(&msg).expect("should parse mDNS response");
+        assert!(info.questions.is_empty());
+        assert_eq!(info.answers.len(), 2);
+        // The service PTR is not a reverse name, so only the A record binds
+        assert_eq!(
+            info.bindings(),
+            vec![DnsBinding {
+                ip: "192.168.10.22".into(),
+                name: "eng-ws02.local".into(),
+                source: DnsBindingSource::Forward,
+            }]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Engineering software fingerprints.
+//!
+//! Recognises traffic that is only ever generated by PLC programming and
+//! configuration tools, so the sending host can be classified as an
+//! engineering workstation without manual tagging:
+//!
+//! | Software | Wire evidence |
+//! |----------|---------------|
+//! | Siemens STEP 7 / TIA Portal | COTP CR with PG connection-type TSAP (`0x01xx`), S7comm-plus (`0x72`) PDUs, S7 block upload/download/PLC stop jobs |
+//! | Rockwell RSLogix 5000 / Studio 5000 | CIP requests to the Logix Program (`0x68`) or Template (`0x6C`) objects |
+//! | Schneider Unity Pro / Control Expert | Modbus FC 90 (`0x5A`, UMAS) requests |
+//! | CODESYS Development System | CODESYS V3 block-driver (`00 01 17 E8`) or V2 (`BB BB`) requests to the runtime |
+//!
+//! All checks look at client → server requests only (destination port is the
+//! well-known service port), so the returned fingerprint always describes the
+//! packet's *source* host.
+
+use serde::{Deserialize, Serialize};
+
+use crate::enip::{self, CipClass};
+use crate::modbus;
+
+/// S7comm-plus protocol identifier (first byte after COTP DT header).
+const S7COMM_PLUS_PROTOCOL_ID: u8 = 0x72;
+
+/// Classic S7comm protocol identifier.
+const S7COMM_PROTOCOL_ID: u8 = 0x32;
+
+/// Modbus function code used by Schneider UMAS (Unity Pro / Control Expert).
+const MODBUS_FC_UMAS: u8 = 0x5A;
+
+/// CODESYS V3 TCP block-driver magic.
+const CODESYS_V3_MAGIC: [u8; 4] = [0x00, 0x01, 0x17, 0xE8];
+
+/// CODESYS V2 gateway/runtime header magic.
+const CODESYS_V2_MAGIC: [u8; 2] = [0xBB, 0xBB];
+
+/// Engineering / programming software identified from the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineeringSoftware {
+    /// Siemens SIMATIC STEP 7 / TIA Portal
+    SiemensTiaPortal,
+    /// Rockwell RSLogix 5000 / Studio 5000 Logix Designer
+    RockwellStudio5000,
+    /// Schneider Electric Unity Pro / EcoStruxure Control Expert
+    SchneiderUnityPro,
+    /// CODESYS Development System (and OEM rebrands)
+    Codesys,
+}
+
+impl EngineeringSoftware {
+    /// Human-readable product name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            EngineeringSoftware::SiemensTiaPortal => "Siemens STEP 7 / TIA Portal",
+            EngineeringSoftware::RockwellStudio5000 => "Rockwell Studio 5000 / RSLogix 5000",
+            EngineeringSoftware::SchneiderUnityPro => "Schneider Unity Pro / Control Expert",
+            EngineeringSoftware::Codesys => "CODESYS Development System",
+        }
+    }
+}
+
+/// A single engineering-software observation.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineeringFingerprint {
+    /// Software family that produced the packet
+    pub software: EngineeringSoftware,
+    /// Short description of the matching wire evidence
+    pub evidence: &'static str,
+    /// Whether the evidence alone proves programming software. A PG
+    /// connection request is not: HMIs and diagnostic tools open PG
+    /// connections too.
+    pub conclusive: bool,
+}
+
+/// Check a client request payload for engineering software fingerprints.
+///
+/// Returns `None` for responses, non-matching traffic, or payloads too short
+/// to carry the relevant header.
+pub fn detect(payload: &[u8], src_port: u16, dst_port: u16) -> Option<EngineeringFingerprint> {
+    if payload.is_empty() {
+        return None;
+    }
+    match dst_port {
+        102 => detect_siemens(payload),
+        502 => detect_umas(payload, src_port, dst_port),
+        44818 => detect_logix(payload),
+        1200 | 1217 | 2455 | 11740 => detect_codesys(payload),
+        _ => None,
+    }
+}
+
+/// TPKT/COTP: PG connection requests, S7comm-plus, and S7 block transfer jobs.
+fn detect_siemens(payload: &[u8]) -> Option<EngineeringFingerprint> {
+    // TPKT: version 3, reserved 0
+    if payload.len() < 7 || payload[0] != 0x03 || payload[1] != 0x00 {
+        return None;
+    }
+    let cotp_len = payload[4] as usize;
+    let pdu_type = payload[5];
+
+    match pdu_type {
+        // Connection Request — walk the TLV parameters for the called TSAP
+        0xE0 => {
+            let params_end = (5 + cotp_len).min(payload.len());
+            let mut i = 11; // TPKT(4) + LI(1) + type(1) + dst_ref(2) + src_ref(2) + class(1)
+            while i + 2 <= params_end {
+                let code = payload[i];
+                let len = payload[i + 1] as usize;
+                // 0xC2 = destination TSAP; first byte is the connection type
+                if code == 0xC2 && len >= 1 && i + 2 < params_end && payload[i + 2] == 0x01 {
+                    return Some(EngineeringFingerprint {
+                        software: EngineeringSoftware::SiemensTiaPortal,
+                        evidence: "COTP connection request with PG TSAP",
+                        conclusive: false,
+                    });
+                }
+                i += 2 + len;
+            }
+            None
+        }
+        // DT Data — inspect the first byte of the S7 layer
+        0xF0 => {
+            let s7 = payload.get(5 + cotp_len..)?;
+            match *s7.first()? {
+                S7COMM_PLUS_PROTOCOL_ID => Some(EngineeringFingerprint {
+                    software: EngineeringSoftware::SiemensTiaPortal,
+                    evidence: "S7comm-plus session",
+                    conclusive: true,
+                }),
+                // S7 Job (ROSCTR 1): parameter starts after the 10-byte header
+                S7COMM_PROTOCOL_ID if s7.get(1) == Some(&0x01) => match s7.get(10)? {
+                    0x1A | 0x1D | 0x29 => Some(EngineeringFingerprint {
+                        software: EngineeringSoftware::SiemensTiaPortal,
+                        evidence: "S7 block download/upload or PLC stop",
+                        conclusive: true,
+                    }),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Modbus FC 90 (UMAS) requests from a master.
+fn detect_umas(payload: &[u8], src_port: u16, dst_port: u16) -> Option<EngineeringFingerprint> {
+    let pdus = modbus::parse_modbus(payload, src_port, dst_port);
+    if pdus.iter().any(|info| info.function_code == MODBUS_FC_UMAS) {
+        Some(EngineeringFingerprint {
+            software: EngineeringSoftware::SchneiderUnityPro,
+            evidence: "Modbus FC 90 (UMAS) request",
+            conclusive: true,
+        })
+    } else {
+        None
+    }
+}
+
+/// CIP requests to Logix project objects (Program 0x68, Template 0x6C).
+///
+/// HMIs read and write tags through the Symbol object; only the programming
+/// software walks programs and UDT templates.
+fn detect_logix(payload: &[u8]) -> Option<EngineeringFingerprint> {
+    let info = enip::parse(payload)?;
+    if info.is_response {
+        return None;
+    }
+    match info.cip_class {
+        Some(CipClass::Unknown(0x68)) | Some(CipClass::Unknown(0x6C)) => {
+            Some(EngineeringFingerprint {
+                software: EngineeringSoftware::RockwellStudio5000,
+                evidence: "CIP request to Logix Program/Template object",
+                conclusive: true,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// CODESYS V3 block-driver or V2 gateway header.
+fn detect_codesys(payload: &[u8]) -> Option<EngineeringFingerprint> {
+    if payload.starts_with(&CODESYS_V3_MAGIC) {
+        Some(EngineeringFingerprint {
+            software: EngineeringSoftware::Codesys,
+            evidence: "CODESYS V3 block-driver handshake",
+            conclusive: true,
+        })
+    } else if payload.starts_with(&CODESYS_V2_MAGIC) {
+        Some(EngineeringFingerprint {
+            software: EngineeringSoftware::Codesys,
+            evidence: "CODESYS V2 gateway request",
+            conclusive: true,
+        })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_s7_pg_connection_request() {
+        let payload: Vec<u8> = vec![
+            0x03, 0x00, 0x00, 0x16, // TPKT: version 3, length 22
+            0x11, // COTP length indicator (17)
+            0xE0, // CR
+            0x00, 0x00, // dst ref
+            0x00, 0x01, // src ref
+            0x00, // class 0
+            0xC1, 0x02, 0x01, 0x00, // calling TSAP 0x0100
+            0xC2, 0x02, 0x01, 0x02, // called TSAP 0x0102 (PG, rack 0 slot 2)
+            0xC0, 0x01, 0x0A, // TPDU size 1024
+        ];
+        let fp = detect(&payload, 49152, 102).unwrap();
+        assert_eq!(fp.software, EngineeringSoftware::SiemensTiaPortal);
+        assert!(!fp.conclusive);
+    }
+
+    #[test]
+    fn test_s7_op_connection_not_engineering() {
+        let payload: Vec<u8> = vec![
+            0x03, 0x00, 0x00, 0x16, // TPKT
+            0x11, 0xE0, // COTP CR
+            0x00, 0x00, 0x00, 0x01, 0x00, // refs + class
+            0xC1, 0x02, 0x02, 0x00, // calling TSAP
+            0xC2, 0x02, 0x02, 0x02, // called TSAP 0x0202 (OP / HMI)
+            0xC0, 0x01, 0x0A,
+        ];
+        assert!(detect(&payload, 49152, 102).is_none());
+    }
+
+    #[test]
+    fn test_detect_s7comm_plus() {
+        let payload: Vec<u8> = vec![
+            0x03, 0x00, 0x00, 0x0B, // TPKT
+            0x02, 0xF0, 0x80, // COTP DT
+            0x72, 0x01, 0x00, 0x00, // S7comm-plus header
+        ];
+        let fp = detect(&payload, 49152, 102).unwrap();
+        assert_eq!(fp.software, EngineeringSoftware::SiemensTiaPortal);
+        assert!(fp.conclusive);
+    }
+
+    #[test]
+    fn test_detect_umas_request() {
+        let payload: Vec<u8> = vec![
+            0x00, 0x01, // Transaction ID
+            0x00, 0x00, // Protocol ID
+            0x00, 0x04, // Length
+            0x00, // Unit ID
+            0x5A, // FC 90 (UMAS)
+            0x00, 0x02, // UMAS session + sub-function (Read PLC ID)
+        ];
+        let fp = detect(&payload, 49152, 502).unwrap();
+        assert_eq!(fp.software, EngineeringSoftware::SchneiderUnityPro);
+
+        // Ordinary read holding registers is not a fingerprint
+        let read: Vec<u8> = vec![
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A,
+        ];
+        assert!(detect(&read, 49152, 502).is_none());
+    }
+
+    #[test]
+    fn test_detect_logix_template_read() {
+        #[rustfmt::skip]
+        let payload: Vec<u8> = vec![
+            // Encapsulation header
+            0x6F, 0x00,                                     // Command: SendRRData
+            0x18, 0x00,                                     // Length: 24
+            0x01, 0x00, 0x00, 0x00,                         // Session Handle
+            0x00, 0x00, 0x00, 0x00,                         // Status
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Sender Context
+            0x00, 0x00, 0x00, 0x00,                         // Options
+            // SendRRData
+            0x00, 0x00, 0x00, 0x00,                         // Interface Handle
+            0x0A, 0x00,                                     // Timeout
+            0x02, 0x00,                                     // Item Count
+            0x00, 0x00, 0x00, 0x00,                         // Null Address item
+            0xB2, 0x00, 0x06, 0x00,                         // Unconnected Data, 6 bytes
+            0x03, 0x02, 0x20, 0x6C, 0x24, 0x01,             // GetAttributeList Template/1
+        ];
+        let fp = detect(&payload, 49152, 44818).unwrap();
+        assert_eq!(fp.software, EngineeringSoftware::RockwellStudio5000);
+    }
+
+    #[test]
+    fn test_detect_codesys_v3() {
+        let payload: Vec<u8> = vec![0x00, 0x01, 0x17, 0xE8, 0x28, 0x00, 0x00, 0x00];
+        let fp = detect(&payload, 50000, 11740).unwrap();
+        assert_eq!(fp.software, EngineeringSoftware::Codesys);
+        // Same bytes in the response direction are ignored
+        assert!(detect(&payload, 11740, 50000).is_none());
+    }
+}
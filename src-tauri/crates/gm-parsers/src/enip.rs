@@ -513,6 +513,21 @@ fn parse_send_data(data: &[u8]) -> Option<CipResult> {
 
 // ─── Main entry point ─────────────────────────────────────────────────────────
 
+/// Whether a payload on an unrecognized port is an EtherNet/IP
+/// encapsulation message: a known command, a length field that matches the
+/// payload exactly and zero options.
+pub fn is_enip(payload: &[u8]) -> bool {
+    if payload.len() < ENIP_HEADER_SIZE {
+        return false;
+    }
+    let command = map_command(u16::from_le_bytes([payload[0], payload[1]]));
+    let length = u16::from_le_bytes([payload[2], payload[3]]) as usize;
+    // NOP is excluded: an all-zero header would otherwise match
+    !matches!(command, EnipCommand::Nop | EnipCommand::Unknown(_))
+        && ENIP_HEADER_SIZE + length == payload.len()
+        && payload[20..24] == [0; 4]
+}
+
 /// Parse an EtherNet/IP encapsulated payload.
 ///
 /// Returns `None` if:
@@ -545,12 +560,10 @@ pub fn parse(payload: &[u8]) -> Option<EnipInfo> {
     let mut cip_result: Option<CipResult> = None;
 
     match &command {
-        EnipCommand::ListIdentity => {
-            // Non-zero length field means this is a response carrying identity data.
-            // Zero length is a broadcast request (no data to parse).
-            if length > 0 {
-                identity = parse_list_identity(data);
-            }
+        // Non-zero length field means this is a response carrying identity data.
+        // Zero length is a broadcast request (no data to parse).
+        EnipCommand::ListIdentity if length > 0 => {
+            identity = parse_list_identity(data);
         }
         EnipCommand::SendRRData | EnipCommand::SendUnitData => {
             cip_result = parse_send_data(data);
@@ -0,0 +1,485 @@
+//! OMRON FINS deep protocol parser.
+//!
+//! FINS (Factory Interface Network Service) is OMRON's command protocol for
+//! CS/CJ/CP/NJ/NX-series PLCs. It runs over UDP as a bare FINS frame and over
+//! TCP inside a 16-byte FINS/TCP header, both on port 9600.
+//!
+//! Reference: OMRON W342 "FINS Commands Reference Manual", W421 (FINS/TCP)
+//! Port: 9600 TCP/UDP
+//!
+//! FINS/TCP header (16 bytes, big-endian):
+//!   [0..4]   Magic "FINS"
+//!   [4..8]   Length (bytes following this field)
+//!   [8..12]  Command (0 = node address request, 1 = node address response,
+//!            2 = FINS frame, 3 = error notification, 6 = connection confirmation)
+//!   [12..16] Error code
+//!
+//! FINS frame header (10 bytes):
+//!   [0] ICF  (bit 7 gateway, bit 6 response, bit 0 no response required)
+//!   [1] RSV  [2] GCT
+//!   [3] DNA  [4] DA1  [5] DA2   destination network / node / unit
+//!   [6] SNA  [7] SA1  [8] SA2   source network / node / unit
+//!   [9] SID  service ID
+//! followed by MRC/SRC command code and, in responses, a 2-byte end code.
+//!
+//! A Controller Data Read (05 01) response carries the CPU model and
+//! version as two 20-byte ASCII fields, which identifies the PLC.
+
+use serde::{Deserialize, Serialize};
+
+/// FINS/TCP magic bytes.
+const FINS_TCP_MAGIC: &[u8; 4] = b"FINS";
+
+/// FINS/TCP header size.
+const FINS_TCP_HEADER: usize = 16;
+
+/// FINS frame header size (ICF through SID).
+const FINS_HEADER: usize = 10;
+
+/// Controller Data Read command code (MRC 05, SRC 01).
+pub const CONTROLLER_DATA_READ: u16 = 0x0501;
+
+/// Width of the model and version fields in a Controller Data Read response.
+const CONTROLLER_FIELD_LEN: usize = 20;
+
+// ─── Enums ────────────────────────────────────────────────────────────────────
+
+/// FINS/TCP header command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinsTcpCommand {
+    /// 0 — client asks the server to assign it a FINS node address
+    NodeAddressRequest,
+    /// 1 — server returns the client and server node addresses
+    NodeAddressResponse,
+    /// 2 — FINS frame follows the header
+    Frame,
+    /// 3 — FINS frame send error notification
+    ErrorNotification,
+    /// 6 — connection confirmation
+    ConnectionConfirmation,
+    Unknown(u32),
+}
+
+impl FinsTcpCommand {
+    fn from_u32(v: u32) -> Self {
+        match v {
+            0 => FinsTcpCommand::NodeAddressRequest,
+            1 => FinsTcpCommand::NodeAddressResponse,
+            2 => FinsTcpCommand::Frame,
+            3 => FinsTcpCommand::ErrorNotification,
+            6 => FinsTcpCommand::ConnectionConfirmation,
+            v => FinsTcpCommand::Unknown(v),
+        }
+    }
+}
+
+/// Client/server role for a FINS device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinsRole {
+    /// Device is sending commands (HMI, SCADA, engineering tool)
+    Client,
+    /// Device is answering commands (PLC CPU or communication unit)
+    Server,
+    Unknown,
+}
+
+// ─── Structs ──────────────────────────────────────────────────────────────────
+
+/// FINS network / node / unit address triple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinsAddress {
+    pub network: u8,
+    pub node: u8,
+    pub unit: u8,
+}
+
+/// CPU identity from a Controller Data Read (05 01) response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinsControllerData {
+    /// Controller model, e.g. "CJ2M-CPU33"
+    pub model: String,
+    /// Controller version, e.g. "02.01"
+    pub version: String,
+}
+
+/// Parsed FINS packet information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinsInfo {
+    /// FINS/TCP header command (None for FINS/UDP)
+    pub tcp_command: Option<FinsTcpCommand>,
+    /// Client node address from a FINS/TCP node address exchange
+    pub client_node: Option<u8>,
+    /// Server node address from a FINS/TCP node address response
+    pub server_node: Option<u8>,
+    /// True for response frames (ICF bit 6)
+    pub is_response: bool,
+    pub destination: Option<FinsAddress>,
+    pub source: Option<FinsAddress>,
+    /// Service ID used to pair commands and responses
+    pub sid: Option<u8>,
+    /// Command code, MRC in the high byte and SRC in the low byte
+    pub command_code: Option<u16>,
+    /// Response end code (MRES/SRES), responses only
+    pub end_code: Option<u16>,
+    /// CPU model/version from a Controller Data Read response
+    pub controller: Option<FinsControllerData>,
+    /// True when the command writes memory, parameters, files or the clock
+    pub is_write: bool,
+    /// True when the command changes the operating mode or the user program
+    pub is_control: bool,
+    /// Detected role of the sending device
+    pub role: FinsRole,
+}
+
+// ─── Parse Functions ──────────────────────────────────────────────────────────
+
+/// Attempt to parse a FINS payload (FINS/TCP or bare FINS/UDP frame).
+///
+/// Returns None if the payload is neither a FINS/TCP header nor a plausible
+/// FINS frame header.
+pub fn parse(payload: &[u8]) -> Option<FinsInfo> {
+    if payload.starts_with(FINS_TCP_MAGIC) {
+        parse_tcp(payload)
+    } else {
+        parse_frame(payload)
+    }
+}
+
+fn parse_tcp(payload: &[u8]) -> Option<FinsInfo> {
+    if payload.len() < FINS_TCP_HEADER {
+        return None;
+    }
+    let command = FinsTcpCommand::from_u32(be_u32(&payload[8..12]));
+    let data = &payload[FINS_TCP_HEADER..];
+
+    let mut info = match command {
+        FinsTcpCommand::Frame => parse_frame(data)?,
+        _ => empty_info(),
+    };
+    info.tcp_command = Some(command);
+
+    match command {
+        FinsTcpCommand::NodeAddressRequest => {
+            info.role = FinsRole::Client;
+            // 0 asks the server to pick one
+            if data.len() >= 4 && be_u32(&data[..4]) != 0 {
+                info.client_node = Some(data[3]);
+            }
+        }
+        FinsTcpCommand::NodeAddressResponse => {
+            info.role = FinsRole::Server;
+            if data.len() >= 8 {
+                info.client_node = Some(data[3]);
+                info.server_node = Some(data[7]);
+            }
+        }
+        _ => {}
+    }
+
+    Some(info)
+}
+
+fn parse_frame(frame: &[u8]) -> Option<FinsInfo> {
+    if frame.len() < FINS_HEADER + 2 {
+        return None;
+    }
+    let icf = frame[0];
+    // Gateway bit always set; bits 1-5 reserved; RSV is 0; GCT is 0-7
+    if icf & 0x80 == 0 || icf & 0x3E != 0 || frame[1] != 0 || frame[2] > 0x07 {
+        return None;
+    }
+
+    let is_response = icf & 0x40 != 0;
+    let command_code = u16::from_be_bytes([frame[10], frame[11]]);
+    let end_code = (is_response && frame.len() >= FINS_HEADER + 4)
+        .then(|| u16::from_be_bytes([frame[12], frame[13]]));
+
+    let controller = if is_response && command_code == CONTROLLER_DATA_READ {
+        parse_controller_data(&frame[FINS_HEADER + 2..])
+    } else {
+        None
+    };
+
+    Some(FinsInfo {
+        is_response,
+        destination: Some(FinsAddress {
+            network: frame[3],
+            node: frame[4],
+            unit: frame[5],
+        }),
+        source: Some(FinsAddress {
+            network: frame[6],
+            node: frame[7],
+            unit: frame[8],
+        }),
+        sid: Some(frame[9]),
+        command_code: Some(command_code),
+        end_code,
+        controller,
+        is_write: !is_response && is_write_command(command_code),
+        is_control: !is_response && is_control_command(command_code),
+        role: if is_response {
+            FinsRole::Server
+        } else {
+            FinsRole::Client
+        },
+        ..empty_info()
+    })
+}
+
+/// Extract model/version from the data following a 05 01 command code.
+fn parse_controller_data(data: &[u8]) -> Option<FinsControllerData> {
+    // End code must be normal completion
+    if data.len() < 2 + CONTROLLER_FIELD_LEN * 2 || data[0] != 0 || data[1] != 0 {
+        return None;
+    }
+    let fields = &data[2..];
+    let model = ascii_field(&fields[..CONTROLLER_FIELD_LEN]);
+    let version = ascii_field(&fields[CONTROLLER_FIELD_LEN..CONTROLLER_FIELD_LEN * 2]);
+    (!model.is_empty()).then_some(FinsControllerData { model, version })
+}
+
+fn empty_info() -> FinsInfo {
+    FinsInfo {
+        tcp_command: None,
+        client_node: None,
+        server_node: None,
+        is_response: false,
+        destination: None,
+        source: None,
+        sid: None,
+        command_code: None,
+        end_code: None,
+        controller: None,
+        is_write: false,
+        is_control: false,
+        role: FinsRole::Unknown,
+    }
+}
+
+fn be_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+}
+
+/// Printable ASCII up to the first NUL, trimmed.
+fn ascii_field(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|&&b| b != 0)
+        .filter(|b| b.is_ascii_graphic() || **b == b' ')
+        .map(|&b| b as char)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// ─── Command Classification ───────────────────────────────────────────────────
+
+/// Commands that modify memory, parameters, files, forced bits or the clock.
+pub fn is_write_command(code: u16) -> bool {
+    matches!(
+        code,
+        0x0102 // Memory Area Write
+            | 0x0103 // Memory Area Fill
+            | 0x0105 // Memory Area Transfer
+            | 0x0202 // Parameter Area Write
+            | 0x0203 // Parameter Area Clear
+            | 0x0702 // Clock Write
+            | 0x2203 // Single File Write
+            | 0x2205 // File Delete
+            | 0x220A // Memory Area–File Transfer
+            | 0x220B // Parameter Area–File Transfer
+            | 0x2301 // Forced Set/Reset
+    )
+}
+
+/// Commands that change the operating mode or the user program.
+pub fn is_control_command(code: u16) -> bool {
+    matches!(
+        code,
+        0x0307 // Program Area Write
+            | 0x0308 // Program Area Clear
+            | 0x0401 // Run
+            | 0x0402 // Stop
+            | 0x220C // Program Area–File Transfer
+    )
+}
+
+/// Human-readable name for a FINS command code.
+pub fn command_code_name(code: u16) -> &'static str {
+    match code {
+        0x0101 => "Memory Area Read",
+        0x0102 => "Memory Area Write",
+        0x0103 => "Memory Area Fill",
+        0x0104 => "Multiple Memory Area Read",
+        0x0105 => "Memory Area Transfer",
+        0x0201 => "Parameter Area Read",
+        0x0202 => "Parameter Area Write",
+        0x0203 => "Parameter Area Clear",
+        0x0306 => "Program Area Read",
+        0x0307 => "Program Area Write",
+        0x0308 => "Program Area Clear",
+        0x0401 => "Run",
+        0x0402 => "Stop",
+        0x0501 => "Controller Data Read",
+        0x0502 => "Connection Data Read",
+        0x0601 => "Controller Status Read",
+        0x0620 => "Cycle Time Read",
+        0x0701 => "Clock Read",
+        0x0702 => "Clock Write",
+        0x0920 => "Message Read/Clear",
+        0x0C01 => "Access Right Acquire",
+        0x0C02 => "Access Right Forced Acquire",
+        0x0C03 => "Access Right Release",
+        0x2101 => "Error Clear",
+        0x2102 => "Error Log Read",
+        0x2103 => "Error Log Clear",
+        0x2201 => "File Name Read",
+        0x2202 => "Single File Read",
+        0x2203 => "Single File Write",
+        0x2204 => "Memory Card Format",
+        0x2205 => "File Delete",
+        0x2207 => "File Copy",
+        0x2208 => "File Name Change",
+        0x220A => "Memory Area–File Transfer",
+        0x220B => "Parameter Area–File Transfer",
+        0x220C => "Program Area–File Transfer",
+        0x2215 => "Directory Create/Delete",
+        0x2301 => "Forced Set/Reset",
+        0x2302 => "Forced Set/Reset Cancel",
+        _ => "Unknown",
+    }
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// FINS header: ICF, RSV, GCT, DNA, DA1, DA2, SNA, SA1, SA2, SID
+    fn header(icf: u8) -> Vec<u8> {
+        vec![icf, 0x00, 0x02, 0x00, 0x0A, 0x00, 0x00, 0x22, 0x00, 0x07]
+    }
+
+    fn tcp_wrap(command: u32, data: &[u8]) -> Vec<u8> {
+        let mut out = b"FINS".to_vec();
+        out.extend_from_slice(&(8 + data.len() as u32).to_be_bytes());
+        out.extend_from_slice(&command.to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn field(s: &str) -> Vec<u8> {
+        let mut f = s.as_bytes().to_vec();
+        f.resize(CONTROLLER_FIELD_LEN, 0x20);
+        f
+    }
+
+    #[test]
+    fn test_udp_memory_area_read_command() {
+        let mut frame = header(0x80);
+        // 01 01, DM area (0x82), word 100, bit 0, 2 words
+        frame.extend_from_slice(&[0x01, 0x01, 0x82, 0x00, 0x64, 0x00, 0x00, 0x02]);
+        let info = parse(&frame).unwrap();
+        assert!(info.tcp_command.is_none());
+        assert!(!info.is_response);
+        assert_eq!(info.command_code, Some(0x0101));
+        assert_eq!(
+            info.destination,
+            Some(FinsAddress {
+                network: 0,
+                node: 0x0A,
+                unit: 0
+            })
+        );
+        assert_eq!(info.source.unwrap().node, 0x22);
+        assert_eq!(info.sid, Some(0x07));
+        assert_eq!(info.role, FinsRole::Client);
+        assert!(!info.is_write);
+        assert_eq!(command_code_name(0x0101), "Memory Area Read");
+    }
+
+    #[test]
+    fn test_tcp_controller_data_read_response() {
+        let mut frame = header(0xC0);
+        frame.extend_from_slice(&[0x05, 0x01, 0x00, 0x00]);
+        frame.extend(field("CJ2M-CPU33"));
+        frame.extend(field("02.01"));
+        frame.extend(vec![0u8; 40]);
+        let info = parse(&tcp_wrap(2, &frame)).unwrap();
+
+        assert_eq!(info.tcp_command, Some(FinsTcpCommand::Frame));
+        assert!(info.is_response);
+        assert_eq!(info.end_code, Some(0x0000));
+        assert_eq!(info.role, FinsRole::Server);
+        assert_eq!(
+            info.controller,
+            Some(FinsControllerData {
+                model: "CJ2M-CPU33".to_string(),
+                version: "02.01".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_controller_data_error_end_code_ignored() {
+        let mut frame = header(0xC0);
+        frame.extend_from_slice(&[0x05, 0x01, 0x04, 0x01]);
+        frame.extend(field("CJ2M-CPU33"));
+        frame.extend(field("02.01"));
+        let info = parse(&frame).unwrap();
+        assert_eq!(info.end_code, Some(0x0401));
+        assert!(info.controller.is_none());
+    }
+
+    #[test]
+    fn test_tcp_node_address_exchange() {
+        let request = parse(&tcp_wrap(0, &[0, 0, 0, 0])).unwrap();
+        assert_eq!(
+            request.tcp_command,
+            Some(FinsTcpCommand::NodeAddressRequest)
+        );
+        assert_eq!(request.role, FinsRole::Client);
+        assert!(request.client_node.is_none());
+        assert!(request.command_code.is_none());
+
+        let response = parse(&tcp_wrap(1, &[0, 0, 0, 0x22, 0, 0, 0, 0x0A])).unwrap();
+        assert_eq!(response.role, FinsRole::Server);
+        assert_eq!(response.client_node, Some(0x22));
+        assert_eq!(response.server_node, Some(0x0A));
+    }
+
+    #[test]
+    fn test_write_and_stop_commands_flagged() {
+        let mut write = header(0x80);
+        write.extend_from_slice(&[0x01, 0x02, 0x82, 0x00, 0x64, 0x00, 0x00, 0x01, 0x12, 0x34]);
+        let info = parse(&write).unwrap();
+        assert!(info.is_write);
+        assert!(!info.is_control);
+
+        let mut stop = header(0x80);
+        stop.extend_from_slice(&[0x04, 0x02, 0xFF, 0xFF]);
+        let info = parse(&stop).unwrap();
+        assert!(info.is_control);
+        assert_eq!(command_code_name(0x0402), "Stop");
+    }
+
+    #[test]
+    fn test_rejects_non_fins() {
+        assert!(parse(&[]).is_none());
+        assert!(parse(b"FINS").is_none());
+        // Gateway bit clear
+        let mut frame = header(0x00);
+        frame.extend_from_slice(&[0x01, 0x01]);
+        assert!(parse(&frame).is_none());
+        // Reserved byte set
+        let mut frame = header(0x80);
+        frame[1] = 0x55;
+        frame.extend_from_slice(&[0x01, 0x01]);
+        assert!(parse(&frame).is_none());
+    }
+}
@@ -0,0 +1,233 @@
+//! Tridium Niagara Fox protocol parser.
+//!
+//! Fox is the station ↔ Workbench / station ↔ Supervisor protocol of the
+//! Niagara Framework used by building management systems (JACE controllers,
+//! Niagara Supervisors, OEM-branded variants like Honeywell WEBs and
+//! Distech EC-Net). Plain Fox runs on TCP 1911; Foxs (TLS) on 4911 is only
+//! identifiable by port.
+//!
+//! Every session opens with a text `hello` exchange that identifies both
+//! ends. The client sends its hello, the station answers with its own:
+//!
+//! ```text
+//! fox a 0 -1 fox hello
+//! {
+//! fox.version=s:1.0
+//! hostName=s:jace-ahu1
+//! app.name=s:Station
+//! app.version=s:4.10.1.36
+//! os.name=s:QNX
+//! station.name=s:AHU_Building_A
+//! brandId=s:vykon
+//! authAgentTypeSpecs=s:fox:FoxScramSha256AuthAgent
+//! };;
+//! ```
+//!
+//! Each property is `name=type:value`, with type `s` (string), `i` (int),
+//! `b` (boolean) or `o` (encoded object). Only the properties used for asset
+//! identification are kept.
+
+use serde::{Deserialize, Serialize};
+
+/// TCP ports used by Fox (1911) and Foxs over TLS (4911).
+pub const FOX_PORTS: [u16; 2] = [1911, 4911];
+
+const FOX_MAGIC: &[u8] = b"fox a ";
+/// Hello payloads are a few hundred bytes; cap the scan for the body.
+const MAX_HELLO_LEN: usize = 8192;
+
+/// Client/server role for a Fox device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoxRole {
+    /// Device opened the session (Workbench, Supervisor, another station)
+    Client,
+    /// Device answered on the Fox port (Niagara station)
+    Server,
+}
+
+/// Identity fields from a Fox hello message.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoxHello {
+    pub fox_version: Option<String>,
+    pub host_name: Option<String>,
+    pub host_address: Option<String>,
+    /// "Station" on a station, "Workbench" on an engineering PC
+    pub app_name: Option<String>,
+    /// Niagara version, e.g. "3.8.38" (AX) or "4.10.1.36" (Niagara 4)
+    pub app_version: Option<String>,
+    pub vm_name: Option<String>,
+    pub vm_version: Option<String>,
+    pub os_name: Option<String>,
+    pub os_version: Option<String>,
+    /// Station name (station hello only)
+    pub station_name: Option<String>,
+    /// Hardware host ID, e.g. "Qnx-JVLN-0000-05A1-7A5D"
+    pub host_id: Option<String>,
+    /// OEM brand, e.g. "vykon", "Honeywell", "distech"
+    pub brand_id: Option<String>,
+    /// Authentication agents the station offers (Niagara 4)
+    pub auth_agents: Vec<String>,
+}
+
+impl FoxHello {
+    /// Authentication scheme offered by the station, strongest first.
+    ///
+    /// Niagara 4 advertises its agents in the hello; Niagara AX stations do
+    /// not, and AX Fox authentication is weak regardless of configuration.
+    pub fn auth_mode(&self) -> Option<&'static str> {
+        let has = |needle: &str| {
+            self.auth_agents
+                .iter()
+                .any(|a| a.to_ascii_lowercase().contains(needle))
+        };
+        if self.auth_agents.is_empty() {
+            None
+        } else if has("scram") {
+            Some("SCRAM-SHA256")
+        } else if has("digest") {
+            Some("Digest")
+        } else if has("usernamepassword") || has("basic") {
+            Some("Username/password (cleartext)")
+        } else {
+            Some("Other")
+        }
+    }
+
+    /// Niagara major version (3 = AX, 4 = Niagara 4), from `app.version`.
+    pub fn major_version(&self) -> Option<u32> {
+        self.app_version.as_deref()?.split('.').next()?.parse().ok()
+    }
+}
+
+/// Parsed Fox message information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoxInfo {
+    /// Hello properties (None for non-hello Fox messages)
+    pub hello: Option<FoxHello>,
+    pub role: FoxRole,
+}
+
+/// Attempt to parse a Fox message.
+///
+/// Any payload starting with the `fox a ` frame prefix is Fox; hello
+/// messages additionally carry the identity properties.
+pub fn parse(payload: &[u8], src_port: u16, dst_port: u16) -> Option<FoxInfo> {
+    if !payload.starts_with(FOX_MAGIC) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&payload[..payload.len().min(MAX_HELLO_LEN)]);
+    let header = text.lines().next()?;
+
+    // Header: "fox a <type> <channel> fox hello"
+    let hello = header
+        .trim_end()
+        .ends_with(" fox hello")
+        .then(|| parse_hello_body(&text));
+
+    let from_station = FOX_PORTS.contains(&src_port) && !FOX_PORTS.contains(&dst_port);
+    let role = if from_station
+        || hello
+            .as_ref()
+            .is_some_and(|h| h.station_name.is_some() && !FOX_PORTS.contains(&dst_port))
+    {
+        FoxRole::Server
+    } else {
+        FoxRole::Client
+    };
+
+    Some(FoxInfo { hello, role })
+}
+
+/// Read `name=type:value` lines between `{` and `};;`.
+fn parse_hello_body(text: &str) -> FoxHello {
+    let mut hello = FoxHello::default();
+    let body = text
+        .split_once('{')
+        .map(|(_, rest)| rest.split("};;").next().unwrap_or(rest))
+        .unwrap_or("");
+
+    for line in body.lines() {
+        let Some((name, typed)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let Some((kind, raw)) = typed.split_once(':') else {
+            continue;
+        };
+        if kind != "s" || raw.is_empty() {
+            continue;
+        }
+        let value = Some(raw.to_string());
+        match name {
+            "fox.version" => hello.fox_version = value,
+            "hostName" => hello.host_name = value,
+            "hostAddress" => hello.host_address = value,
+            "app.name" => hello.app_name = value,
+            "app.version" => hello.app_version = value,
+            "vm.name" => hello.vm_name = value,
+            "vm.version" => hello.vm_version = value,
+            "os.name" => hello.os_name = value,
+            "os.version" => hello.os_version = value,
+            "station.name" => hello.station_name = value,
+            "hostId" => hello.host_id = value,
+            "brandId" => hello.brand_id = value,
+            "authAgentTypeSpecs" => {
+                hello.auth_agents = raw
+                    .split(',')
+                    .map(|a| a.trim().to_string())
+                    .filter(|a| !a.is_empty())
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    hello
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATION_HELLO: &str = "fox a 0 -1 fox hello\n{\nfox.version=s:1.0\nid=i:255\n\
+        hostName=s:jace-ahu1\nhostAddress=s:10.20.0.15\napp.name=s:Station\n\
+        app.version=s:4.10.1.36\nvm.name=s:Java HotSpot(TM) Embedded Client VM\n\
+        os.name=s:QNX\nos.version=s:6.5.0\nstation.name=s:AHU_Building_A\n\
+        hostId=s:Qnx-JVLN-0000-05A1-7A5D\nbrandId=s:vykon\n\
+        authAgentTypeSpecs=s:fox:FoxScramSha256AuthAgent,fox:FoxDigestAuthAgent\n};;\n";
+
+    #[test]
+    fn test_station_hello() {
+        let info = parse(STATION_HELLO.as_bytes(), 1911, 51234).unwrap();
+        assert_eq!(info.role, FoxRole::Server);
+        let hello = info.hello.unwrap();
+        assert_eq!(hello.station_name.as_deref(), Some("AHU_Building_A"));
+        assert_eq!(hello.app_version.as_deref(), Some("4.10.1.36"));
+        assert_eq!(hello.major_version(), Some(4));
+        assert_eq!(hello.os_name.as_deref(), Some("QNX"));
+        assert_eq!(hello.host_id.as_deref(), Some("Qnx-JVLN-0000-05A1-7A5D"));
+        assert_eq!(hello.auth_agents.len(), 2);
+        assert_eq!(hello.auth_mode(), Some("SCRAM-SHA256"));
+    }
+
+    #[test]
+    fn test_workbench_hello_ax() {
+        let payload = "fox a 1 -1 fox hello\n{\nfox.version=s:1.0\nid=i:1\n\
+            hostName=s:ENG-PC-07\napp.name=s:Workbench\napp.version=s:3.8.38\n\
+            os.name=s:Windows 10\n};;\n";
+        let info = parse(payload.as_bytes(), 51234, 1911).unwrap();
+        assert_eq!(info.role, FoxRole::Client);
+        let hello = info.hello.unwrap();
+        assert_eq!(hello.app_name.as_deref(), Some("Workbench"));
+        assert_eq!(hello.major_version(), Some(3));
+        assert_eq!(hello.station_name, None);
+        assert_eq!(hello.auth_mode(), None);
+    }
+
+    #[test]
+    fn test_non_hello_and_non_fox() {
+        let info = parse(b"fox a 3 12 fox keepalive\n{\n};;\n", 1911, 51234).unwrap();
+        assert!(info.hello.is_none());
+        assert!(parse(b"GET / HTTP/1.1\r\n", 51234, 1911).is_none());
+        assert!(parse(b"", 51234, 1911).is_none());
+    }
+}
@@ -0,0 +1,260 @@
+//! Function code registry for Modbus and DNP3.
+//!
+//! Names and the write/control classification of function codes come from
+//! `data/function_codes.yaml`, compiled in as the built-in table. Vendors
+//! put proprietary operations in the user-defined ranges (Modbus 65-72 and
+//! 100-110, for example), so sites can register their own codes at runtime
+//! — from a YAML file of the same shape or one at a time — without a
+//! rebuild. A registered code replaces the built-in entry with that number.
+//!
+//! The process-wide registry behind [`function_code_info`] is what the
+//! parsers' `function_code_name` helpers and the write/control checks read.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use serde::{Deserialize, Serialize};
+
+/// Built-in function code table.
+const BUILTIN_FUNCTION_CODES_YAML: &str = include_str!("../data/function_codes.yaml");
+
+/// Protocols with a function code registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunctionCodeProtocol {
+    Modbus,
+    Dnp3,
+}
+
+impl FunctionCodeProtocol {
+    /// Parse a protocol name as used in settings ("modbus", "dnp3").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "modbus" => Some(Self::Modbus),
+            "dnp3" => Some(Self::Dnp3),
+            _ => None,
+        }
+    }
+}
+
+/// One function code definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionCodeDef {
+    pub code: u8,
+    pub name: String,
+    /// Changes data on the target
+    #[serde(default)]
+    pub write: bool,
+    /// Changes how the target operates (operate, restart, start/stop)
+    #[serde(default)]
+    pub control: bool,
+}
+
+impl FunctionCodeDef {
+    /// Whether the operation is security-relevant (write or control).
+    pub fn is_write_or_control(&self) -> bool {
+        self.write || self.control
+    }
+}
+
+/// Layout of a function code file: one list per protocol.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionCodeFile {
+    #[serde(default)]
+    pub modbus: Vec<FunctionCodeDef>,
+    #[serde(default)]
+    pub dnp3: Vec<FunctionCodeDef>,
+}
+
+/// Function code definitions per protocol.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionCodeRegistry {
+    tables: HashMap<FunctionCodeProtocol, HashMap<u8, FunctionCodeDef>>,
+}
+
+impl FunctionCodeRegistry {
+    /// Registry holding only the built-in table.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry
+            .load_yaml(BUILTIN_FUNCTION_CODES_YAML)
+            .expect("built-in data/function_codes.yaml is valid");
+        registry
+    }
+
+    /// Add or replace a definition.
+    pub fn register(&mut self, protocol: FunctionCodeProtocol, def: FunctionCodeDef) {
+        self.tables
+            .entry(protocol)
+            .or_default()
+            .insert(def.code, def);
+    }
+
+    /// Register every definition in a YAML function code file.
+    /// Returns how many were registered.
+    pub fn load_yaml(&mut self, yaml: &str) -> Result<usize, serde_yaml::Error> {
+        let file: FunctionCodeFile = serde_yaml::from_str(yaml)?;
+        let count = file.modbus.len() + file.dnp3.len();
+        for def in file.modbus {
+            self.register(FunctionCodeProtocol::Modbus, def);
+        }
+        for def in file.dnp3 {
+            self.register(FunctionCodeProtocol::Dnp3, def);
+        }
+        Ok(count)
+    }
+
+    pub fn get(&self, protocol: FunctionCodeProtocol, code: u8) -> Option<&FunctionCodeDef> {
+        self.tables.get(&protocol)?.get(&code)
+    }
+
+    /// All definitions for a protocol, sorted by code.
+    pub fn list(&self, protocol: FunctionCodeProtocol) -> Vec<FunctionCodeDef> {
+        let mut defs: Vec<FunctionCodeDef> = self
+            .tables
+            .get(&protocol)
+            .map(|t| t.values().cloned().collect())
+            .unwrap_or_default();
+        defs.sort_by_key(|d| d.code);
+        defs
+    }
+}
+
+fn global() -> &'static RwLock<FunctionCodeRegistry> {
+    static REGISTRY: OnceLock<RwLock<FunctionCodeRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(FunctionCodeRegistry::builtin()))
+}
+
+// A panic while holding the lock cannot leave a HashMap half-inserted in a
+// way that matters here, so poisoning is ignored.
+fn read() -> RwLockReadGuard<'static, FunctionCodeRegistry> {
+    global().read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write() -> RwLockWriteGuard<'static, FunctionCodeRegistry> {
+    global().write().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Look up a function code in the process-wide registry.
+pub fn function_code_info(protocol: FunctionCodeProtocol, code: u8) -> Option<FunctionCodeDef> {
+    read().get(protocol, code).cloned()
+}
+
+/// Human-readable name, or "Unknown" for unregistered codes.
+pub fn function_code_name(protocol: FunctionCodeProtocol, code: u8) -> String {
+    read()
+        .get(protocol, code)
+        .map_or_else(|| "Unknown".to_string(), |d| d.name.clone())
+}
+
+/// Whether a request with this code writes data on the target.
+pub fn is_write_function_code(protocol: FunctionCodeProtocol, code: u8) -> bool {
+    read().get(protocol, code).is_some_and(|d| d.write)
+}
+
+/// Whether a request with this code is a write or control operation.
+pub fn is_write_or_control_function_code(protocol: FunctionCodeProtocol, code: u8) -> bool {
+    read()
+        .get(protocol, code)
+        .is_some_and(FunctionCodeDef::is_write_or_control)
+}
+
+/// Add or replace one definition in the process-wide registry.
+pub fn register_function_code(protocol: FunctionCodeProtocol, def: FunctionCodeDef) {
+    write().register(protocol, def);
+}
+
+/// Register every definition in a YAML function code file with the
+/// process-wide registry. Returns how many were registered.
+pub fn register_function_codes_yaml(yaml: &str) -> Result<usize, serde_yaml::Error> {
+    // Parse before taking the lock so a bad file changes nothing
+    let mut parsed = FunctionCodeRegistry::default();
+    let count = parsed.load_yaml(yaml)?;
+    let mut registry = write();
+    for (protocol, table) in parsed.tables {
+        for def in table.into_values() {
+            registry.register(protocol, def);
+        }
+    }
+    Ok(count)
+}
+
+/// Drop all registered codes, leaving the built-in table.
+pub fn reset_function_codes() {
+    *write() = FunctionCodeRegistry::builtin();
+}
+
+/// All definitions for a protocol in the process-wide registry.
+pub fn list_function_codes(protocol: FunctionCodeProtocol) -> Vec<FunctionCodeDef> {
+    read().list(protocol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_table() {
+        let registry = FunctionCodeRegistry::builtin();
+        let fc16 = registry.get(FunctionCodeProtocol::Modbus, 16).unwrap();
+        assert_eq!(fc16.name, "Write Multiple Registers");
+        assert!(fc16.write && !fc16.control);
+        assert!(!registry.get(FunctionCodeProtocol::Modbus, 3).unwrap().write);
+
+        let operate = registry.get(FunctionCodeProtocol::Dnp3, 4).unwrap();
+        assert!(operate.write && operate.control);
+        let restart = registry.get(FunctionCodeProtocol::Dnp3, 13).unwrap();
+        assert!(restart.is_write_or_control() && !restart.write);
+        assert!(registry.get(FunctionCodeProtocol::Dnp3, 200).is_none());
+    }
+
+    #[test]
+    fn test_load_yaml_overrides_and_extends() {
+        let mut registry = FunctionCodeRegistry::builtin();
+        let yaml = "\
+modbus:
+  - { code: 65, name: Vendor Firmware Upload, write: true, control: true }
+  - { code: 8, name: Diagnostics (restricted), control: true }
+";
+        assert_eq!(registry.load_yaml(yaml).unwrap(), 2);
+        let vendor = registry.get(FunctionCodeProtocol::Modbus, 65).unwrap();
+        assert_eq!(vendor.name, "Vendor Firmware Upload");
+        assert!(vendor.control);
+        assert!(
+            registry
+                .get(FunctionCodeProtocol::Modbus, 8)
+                .unwrap()
+                .control
+        );
+        // DNP3 untouched
+        assert_eq!(
+            registry.get(FunctionCodeProtocol::Dnp3, 1).unwrap().name,
+            "Read"
+        );
+
+        assert!(registry
+            .load_yaml("modbus: [{ name: missing code }]")
+            .is_err());
+    }
+
+    #[test]
+    fn test_global_registration() {
+        // Code 104 is in Modbus' user-defined range and unused by other tests
+        assert_eq!(
+            function_code_name(FunctionCodeProtocol::Modbus, 104),
+            "Unknown"
+        );
+        register_function_codes_yaml(
+            "modbus:\n  - { code: 104, name: Vendor Setpoint, write: true }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            function_code_name(FunctionCodeProtocol::Modbus, 104),
+            "Vendor Setpoint"
+        );
+        assert!(is_write_function_code(FunctionCodeProtocol::Modbus, 104));
+        assert!(list_function_codes(FunctionCodeProtocol::Modbus)
+            .iter()
+            .any(|d| d.code == 104));
+    }
+}
@@ -0,0 +1,493 @@
+//! GE SRTP (Service Request Transport Protocol) deep parser.
+//!
+//! SRTP carries GE Series 90 / VersaMax / PACSystems (RX3i, RX7i) service
+//! requests over TCP. Every message starts with a fixed 56-byte header;
+//! small requests and replies fit inside it, larger data follows it.
+//!
+//! References: GE GFK-0582 (SNP service requests and PLC status word);
+//! Denton et al., "Leveraging the SRTP protocol for over-the-network memory
+//! acquisition of a GE Fanuc Series 90-30", DFRWS 2017.
+//! Ports: 18245 TCP (18246 on some Ethernet modules)
+//!
+//! Header (56 bytes, little-endian):
+//!   [0]      Packet type (0x00 init, 0x01 init ack, 0x02 request, 0x03 response)
+//!   [2]      Sequence number (echoed in the response)
+//!   [4..6]   Text length (bytes following the header)
+//!   [31]     Mailbox message type (0xC0/0x80 request, 0xD4/0x94 ack, 0xD1 nack)
+//!   Requests:  [42] service request code, [43] segment selector,
+//!              [44..46] offset, [46..48] length
+//!   Responses: [42] major status, [43] minor status, [44..46] PLC status word
+//!
+//! Responses do not echo the service code; callers pair them with the
+//! request by sequence number.
+
+use serde::{Deserialize, Serialize};
+
+/// Fixed SRTP header size.
+pub const SRTP_HEADER_LEN: usize = 56;
+
+/// Return Controller Type and ID Information service.
+pub const RETURN_CONTROLLER_INFO: u8 = 0x43;
+
+/// Mailbox message types carrying a request.
+const MAILBOX_REQUEST: [u8; 2] = [0xC0, 0x80];
+/// Mailbox message types carrying an acknowledgement or NACK.
+const MAILBOX_RESPONSE: [u8; 3] = [0xD4, 0x94, 0xD1];
+
+// ─── Enums ────────────────────────────────────────────────────────────────────
+
+/// SRTP packet type (header byte 0).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SrtpPacketType {
+    /// 0x00 — 56 zero bytes opening a session
+    Init,
+    /// 0x01 — PLC accepts the session
+    InitAck,
+    /// 0x02 — service request
+    Request,
+    /// 0x03 — service response
+    Response,
+    Unknown(u8),
+}
+
+impl SrtpPacketType {
+    fn from_u8(v: u8) -> Self {
+        match v {
+            0x00 => SrtpPacketType::Init,
+            0x01 => SrtpPacketType::InitAck,
+            0x02 => SrtpPacketType::Request,
+            0x03 => SrtpPacketType::Response,
+            v => SrtpPacketType::Unknown(v),
+        }
+    }
+}
+
+/// PLC operating state from bits 12-15 of the PLC status word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SrtpPlcState {
+    RunIoEnabled,
+    RunIoDisabled,
+    StopIoDisabled,
+    StopFaulted,
+    Halted,
+    Suspended,
+    StopIoEnabled,
+    Unknown,
+}
+
+impl SrtpPlcState {
+    fn from_status_word(word: u16) -> Self {
+        match word >> 12 {
+            0 => SrtpPlcState::RunIoEnabled,
+            1 => SrtpPlcState::RunIoDisabled,
+            2 => SrtpPlcState::StopIoDisabled,
+            3 => SrtpPlcState::StopFaulted,
+            4 => SrtpPlcState::Halted,
+            5 => SrtpPlcState::Suspended,
+            6 => SrtpPlcState::StopIoEnabled,
+            _ => SrtpPlcState::Unknown,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SrtpPlcState::RunIoEnabled => "Run (I/O enabled)",
+            SrtpPlcState::RunIoDisabled => "Run (I/O disabled)",
+            SrtpPlcState::StopIoDisabled => "Stop (I/O disabled)",
+            SrtpPlcState::StopFaulted => "Stop (faulted)",
+            SrtpPlcState::Halted => "Halted",
+            SrtpPlcState::Suspended => "Suspended",
+            SrtpPlcState::StopIoEnabled => "Stop (I/O enabled)",
+            SrtpPlcState::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Client/server role for an SRTP device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SrtpRole {
+    /// Device is sending requests (HMI, historian, Proficy Machine Edition)
+    Client,
+    /// Device is answering requests (PLC CPU or Ethernet module)
+    Server,
+}
+
+// ─── Structs ──────────────────────────────────────────────────────────────────
+
+/// Memory range addressed by a read/write memory request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SrtpMemoryAccess {
+    /// Memory type, e.g. "%R", "%M" (hex selector if unrecognized)
+    pub segment: String,
+    /// Zero-based offset into the segment
+    pub offset: u16,
+    /// Number of units (registers, bytes or bits) requested
+    pub length: u16,
+}
+
+/// Controller identity from a Return Controller Type and ID response.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SrtpControllerInfo {
+    /// Catalog number, e.g. "IC695CPE330"
+    pub catalog_number: Option<String>,
+    /// User-assigned controller (CPU) ID
+    pub controller_id: Option<String>,
+    /// Firmware revision, e.g. "10.30"
+    pub firmware: Option<String>,
+}
+
+/// Parsed SRTP packet information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SrtpInfo {
+    pub packet_type: SrtpPacketType,
+    /// Sequence number pairing requests and responses
+    pub sequence: u8,
+    /// Bytes following the 56-byte header
+    pub text_length: u16,
+    /// Mailbox message type (header byte 31)
+    pub mailbox_type: u8,
+    /// Service request code (requests only)
+    pub service_code: Option<u8>,
+    /// Memory range for read/write memory requests
+    pub memory_access: Option<SrtpMemoryAccess>,
+    /// Major status (responses only, 0 = success)
+    pub status: Option<u8>,
+    /// Minor status (responses only)
+    pub minor_status: Option<u8>,
+    /// PLC status word (responses only)
+    pub plc_status_word: Option<u16>,
+    /// Operating state decoded from the PLC status word
+    pub plc_state: Option<SrtpPlcState>,
+    /// Data following the header (responses only)
+    #[serde(skip)]
+    pub response_data: Vec<u8>,
+    /// True when the request writes PLC memory, program or configuration
+    pub is_write: bool,
+    /// True when the request changes the PLC state, privilege or program
+    pub is_control: bool,
+    pub role: SrtpRole,
+}
+
+// ─── Parse Functions ──────────────────────────────────────────────────────────
+
+/// Attempt to parse an SRTP message.
+///
+/// Returns None if the payload is shorter than the 56-byte header or the
+/// header does not look like SRTP.
+pub fn parse(payload: &[u8]) -> Option<SrtpInfo> {
+    if payload.len() < SRTP_HEADER_LEN {
+        return None;
+    }
+    let packet_type = SrtpPacketType::from_u8(payload[0]);
+    let mailbox_type = payload[31];
+    let role = match packet_type {
+        SrtpPacketType::Init | SrtpPacketType::Request => SrtpRole::Client,
+        SrtpPacketType::InitAck | SrtpPacketType::Response => SrtpRole::Server,
+        SrtpPacketType::Unknown(_) => return None,
+    };
+
+    let mut info = SrtpInfo {
+        packet_type,
+        sequence: payload[2],
+        text_length: u16::from_le_bytes([payload[4], payload[5]]),
+        mailbox_type,
+        service_code: None,
+        memory_access: None,
+        status: None,
+        minor_status: None,
+        plc_status_word: None,
+        plc_state: None,
+        response_data: Vec::new(),
+        is_write: false,
+        is_control: false,
+        role,
+    };
+
+    match packet_type {
+        SrtpPacketType::Init => {
+            // The opening message is all zeros
+            if payload[..SRTP_HEADER_LEN].iter().any(|&b| b != 0) {
+                return None;
+            }
+        }
+        SrtpPacketType::Request => {
+            if !MAILBOX_REQUEST.contains(&mailbox_type) {
+                return None;
+            }
+            let code = payload[42];
+            info.service_code = Some(code);
+            info.is_write = is_write_service(code);
+            info.is_control = is_control_service(code);
+            if matches!(code, 0x04..=0x09) {
+                info.memory_access = Some(SrtpMemoryAccess {
+                    segment: segment_name(payload[43])
+                        .map(str::to_string)
+                        .unwrap_or_else(|| format!("0x{:02X}", payload[43])),
+                    offset: u16::from_le_bytes([payload[44], payload[45]]),
+                    length: u16::from_le_bytes([payload[46], payload[47]]),
+                });
+            }
+        }
+        SrtpPacketType::Response => {
+            if !MAILBOX_RESPONSE.contains(&mailbox_type) {
+                return None;
+            }
+            info.status = Some(payload[42]);
+            info.minor_status = Some(payload[43]);
+            let word = u16::from_le_bytes([payload[44], payload[45]]);
+            info.plc_status_word = Some(word);
+            info.plc_state = Some(SrtpPlcState::from_status_word(word));
+            let end = (SRTP_HEADER_LEN + info.text_length as usize).min(payload.len());
+            info.response_data = payload[SRTP_HEADER_LEN..end].to_vec();
+        }
+        SrtpPacketType::InitAck | SrtpPacketType::Unknown(_) => {}
+    }
+
+    Some(info)
+}
+
+/// Extract controller identity from a Return Controller Type and ID
+/// Information (0x43) response.
+///
+/// The reply is a run of fixed-width ASCII fields whose layout differs
+/// between CPU families, so fields are recognized by shape: a GE catalog
+/// number ("IC69…"/"IC200…"), an "NN.NN" firmware revision, and the first
+/// other printable string as the controller ID.
+pub fn parse_controller_info(data: &[u8]) -> Option<SrtpControllerInfo> {
+    let mut info = SrtpControllerInfo::default();
+    for field in ascii_runs(data, 3) {
+        if info.catalog_number.is_none() && is_catalog_number(&field) {
+            info.catalog_number = Some(field);
+        } else if info.firmware.is_none() && is_revision(&field) {
+            info.firmware = Some(field);
+        } else if info.controller_id.is_none() {
+            info.controller_id = Some(field);
+        }
+    }
+    (info.catalog_number.is_some() || info.firmware.is_some()).then_some(info)
+}
+
+/// Product family for a GE catalog number prefix.
+pub fn product_family(catalog_number: &str) -> Option<&'static str> {
+    let upper = catalog_number.to_ascii_uppercase();
+    [
+        ("IC695", "PACSystems RX3i"),
+        ("IC698", "PACSystems RX7i"),
+        ("IC697", "Series 90-70"),
+        ("IC693", "Series 90-30"),
+        ("IC200", "VersaMax"),
+    ]
+    .iter()
+    .find(|(prefix, _)| upper.starts_with(prefix))
+    .map(|(_, family)| *family)
+}
+
+fn ascii_runs(data: &[u8], min_len: usize) -> Vec<String> {
+    data.split(|b| !(b.is_ascii_graphic() || *b == b' '))
+        .map(|run| String::from_utf8_lossy(run).trim().to_string())
+        .filter(|s| s.len() >= min_len)
+        .collect()
+}
+
+fn is_catalog_number(s: &str) -> bool {
+    let upper = s.to_ascii_uppercase();
+    upper.starts_with("IC") && upper.len() >= 8 && upper[2..5].bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_revision(s: &str) -> bool {
+    let mut parts = s.split('.');
+    matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some(major), Some(minor), None)
+            if !major.is_empty()
+                && !minor.is_empty()
+                && major.bytes().all(|b| b.is_ascii_digit())
+                && minor.bytes().all(|b| b.is_ascii_digit())
+    )
+}
+
+// ─── Service Classification ───────────────────────────────────────────────────
+
+/// Services that write PLC memory, program blocks, configuration or the clock.
+pub fn is_write_service(code: u8) -> bool {
+    matches!(
+        code,
+        0x07 // Write System Memory
+            | 0x08 // Write Task Memory
+            | 0x09 // Write Program Block Memory
+            | 0x22 // Set Controller ID
+            | 0x24 // Set PLC Time/Date
+            | 0x39 // Clear Fault Table
+            | 0x44 // Toggle Force System Memory
+    )
+}
+
+/// Services that change the PLC state, privilege level or program.
+pub fn is_control_service(code: u8) -> bool {
+    matches!(
+        code,
+        0x21 // Change PLC CPU Privilege Level
+            | 0x23 // Set PLC State (run/stop)
+            | 0x40 // Program Load (download)
+    )
+}
+
+/// Human-readable name for an SRTP service request code.
+pub fn service_name(code: u8) -> &'static str {
+    match code {
+        0x00 => "PLC Short Status",
+        0x01 => "PLC Long Status",
+        0x03 => "Return Control Program Names",
+        0x04 => "Read System Memory",
+        0x05 => "Read Task Memory",
+        0x06 => "Read Program Block Memory",
+        0x07 => "Write System Memory",
+        0x08 => "Write Task Memory",
+        0x09 => "Write Program Block Memory",
+        0x20 => "Programmer Logon",
+        0x21 => "Change PLC CPU Privilege Level",
+        0x22 => "Set Controller ID",
+        0x23 => "Set PLC State",
+        0x24 => "Set PLC Time/Date",
+        0x25 => "Return PLC Time/Date",
+        0x38 => "Return Fault Table",
+        0x39 => "Clear Fault Table",
+        0x3F => "Program Store (Upload)",
+        0x40 => "Program Load (Download)",
+        0x43 => "Return Controller Type and ID",
+        0x44 => "Toggle Force System Memory",
+        _ => "Unknown",
+    }
+}
+
+/// Memory type for an SRTP segment selector.
+pub fn segment_name(selector: u8) -> Option<&'static str> {
+    Some(match selector {
+        0x08 => "%R",
+        0x0A => "%AI",
+        0x0C => "%AQ",
+        0x10 | 0x46 => "%I",
+        0x12 | 0x48 => "%Q",
+        0x14 | 0x4A => "%T",
+        0x16 | 0x4C => "%M",
+        0x18 | 0x4E => "%SA",
+        0x1A | 0x50 => "%SB",
+        0x1C | 0x52 => "%SC",
+        0x1E | 0x54 => "%S",
+        0x38 | 0x56 => "%G",
+        _ => return None,
+    })
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(packet_type: u8, sequence: u8, mailbox: u8) -> Vec<u8> {
+        let mut h = vec![0u8; SRTP_HEADER_LEN];
+        h[0] = packet_type;
+        h[2] = sequence;
+        h[31] = mailbox;
+        h
+    }
+
+    #[test]
+    fn test_init_handshake() {
+        let init = parse(&[0u8; 56]).unwrap();
+        assert_eq!(init.packet_type, SrtpPacketType::Init);
+        assert_eq!(init.role, SrtpRole::Client);
+
+        let mut ack = vec![0u8; 56];
+        ack[0] = 0x01;
+        let ack = parse(&ack).unwrap();
+        assert_eq!(ack.packet_type, SrtpPacketType::InitAck);
+        assert_eq!(ack.role, SrtpRole::Server);
+    }
+
+    #[test]
+    fn test_read_register_request() {
+        let mut req = header(0x02, 5, 0xC0);
+        req[42] = 0x04; // Read System Memory
+        req[43] = 0x08; // %R
+        req[44..46].copy_from_slice(&99u16.to_le_bytes());
+        req[46..48].copy_from_slice(&10u16.to_le_bytes());
+        let info = parse(&req).unwrap();
+        assert_eq!(info.packet_type, SrtpPacketType::Request);
+        assert_eq!(info.sequence, 5);
+        assert_eq!(info.service_code, Some(0x04));
+        assert_eq!(service_name(0x04), "Read System Memory");
+        assert!(!info.is_write);
+        assert_eq!(
+            info.memory_access,
+            Some(SrtpMemoryAccess {
+                segment: "%R".to_string(),
+                offset: 99,
+                length: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn test_write_and_run_stop_flagged() {
+        let mut write = header(0x02, 1, 0xC0);
+        write[42] = 0x07;
+        write[43] = 0x16; // %M
+        let info = parse(&write).unwrap();
+        assert!(info.is_write);
+        assert_eq!(info.memory_access.unwrap().segment, "%M");
+
+        let mut stop = header(0x02, 2, 0xC0);
+        stop[42] = 0x23;
+        assert!(parse(&stop).unwrap().is_control);
+    }
+
+    #[test]
+    fn test_response_plc_state() {
+        let mut resp = header(0x03, 5, 0xD4);
+        // Stopped, I/O disabled; programmer attached (bit 6)
+        resp[44..46].copy_from_slice(&0x2040u16.to_le_bytes());
+        let info = parse(&resp).unwrap();
+        assert_eq!(info.role, SrtpRole::Server);
+        assert_eq!(info.status, Some(0));
+        assert_eq!(info.plc_state, Some(SrtpPlcState::StopIoDisabled));
+        assert_eq!(SrtpPlcState::StopIoDisabled.name(), "Stop (I/O disabled)");
+    }
+
+    #[test]
+    fn test_controller_info_response() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"LINE4_PLC\0\0\0\0\0\0\0");
+        data.extend_from_slice(b"IC695CPE330\0\0\0\0\0");
+        data.extend_from_slice(b"10.30\0\0\0");
+        let mut resp = header(0x03, 9, 0x94);
+        resp[4..6].copy_from_slice(&(data.len() as u16).to_le_bytes());
+        resp.extend_from_slice(&data);
+
+        let info = parse(&resp).unwrap();
+        let controller = parse_controller_info(&info.response_data).unwrap();
+        assert_eq!(controller.catalog_number.as_deref(), Some("IC695CPE330"));
+        assert_eq!(controller.controller_id.as_deref(), Some("LINE4_PLC"));
+        assert_eq!(controller.firmware.as_deref(), Some("10.30"));
+        assert_eq!(product_family("IC695CPE330"), Some("PACSystems RX3i"));
+        assert_eq!(product_family("IC693CPU374"), Some("Series 90-30"));
+    }
+
+    #[test]
+    fn test_rejects_non_srtp() {
+        assert!(parse(&[0x02; 20]).is_none());
+        // Non-zero init
+        let mut bad = vec![0u8; 56];
+        bad[10] = 1;
+        assert!(parse(&bad).is_none());
+        // Request with an unexpected mailbox type
+        assert!(parse(&header(0x02, 1, 0x55)).is_none());
+        // Unknown packet type
+        assert!(parse(&header(0x09, 1, 0xC0)).is_none());
+        assert!(parse_controller_info(b"\0\0\x01\x02").is_none());
+    }
+}
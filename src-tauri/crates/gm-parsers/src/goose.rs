@@ -0,0 +1,382 @@
+//! IEC 61850 GOOSE (Generic Object Oriented Substation Event) parser.
+//!
+//! GOOSE is the Layer-2 multicast protocol IEDs use to publish trip/interlock
+//! status to each other on the station bus. It has no IP header: frames carry
+//! EtherType 0x88B8 (usually behind an 802.1Q priority tag) and are sent to
+//! multicast MACs in the 01:0C:CD:01:xx:xx range.
+//!
+//! In KusanagiNoKajiki the parser receives everything after the EtherType —
+//! `gm-capture::parsing` intercepts the frame (same pattern as LLDP) and
+//! encodes the MACs into the synthetic `src_ip`/`dst_ip` as `"goose:<mac>"`.
+//!
+//! Frame layout after the EtherType:
+//!   [0..1]  u16 BE  APPID
+//!   [2..3]  u16 BE  Length (APPID through end of PDU)
+//!   [4..5]  Reserved 1 (bit 15 = Simulation in Ed. 2)
+//!   [6..7]  Reserved 2
+//!   [8..]   goosePdu  [APPLICATION 1] (0x61), BER encoded:
+//!             [0] gocbRef  [1] timeAllowedtoLive  [2] datSet  [3] goID
+//!             [4] t  [5] stNum  [6] sqNum  [7] simulation  [8] confRev
+//!             [9] ndsCom  [10] numDatSetEntries  [11] allData
+//!
+//! Sequence semantics (IEC 61850-8-1 §18.1): a publisher increments `stNum`
+//! and resets `sqNum` to 0 whenever the dataset changes, and otherwise
+//! retransmits with `sqNum` incrementing. Both wrap from 2^32-1 to 1. An
+//! older `stNum` reappearing on a live stream is the classic GOOSE replay /
+//! injection signature, which [`GooseStream`] tracks across frames.
+//!
+//! Reference: IEC 61850-8-1 Annex A, Wireshark GOOSE dissector
+//! EtherType: 0x88B8
+
+use serde::{Deserialize, Serialize};
+
+use crate::ber::{ber_string, ber_uint, children, read_tlv};
+
+/// GOOSE EtherType.
+pub const GOOSE_ETHERTYPE: u16 = 0x88B8;
+
+/// Fixed header before the goosePdu (APPID, Length, Reserved 1, Reserved 2).
+const HEADER_LEN: usize = 8;
+
+/// goosePdu tag ([APPLICATION 1] constructed).
+const GOOSE_PDU_TAG: u8 = 0x61;
+
+// ─── Result Structs ───────────────────────────────────────────────────────────
+
+/// Fields extracted from one GOOSE frame.
+#[derive(Debug, Clone, Serialize)]
+pub struct GooseInfo {
+    /// Application identifier from the frame header
+    pub app_id: u16,
+    /// GOOSE control block reference, e.g. "IED1LD0/LLN0$GO$gcbTrip"
+    pub gocb_ref: String,
+    /// How long (ms) subscribers should consider this message valid
+    pub time_allowed_to_live: Option<u32>,
+    /// Dataset reference, e.g. "IED1LD0/LLN0$dsTrip"
+    pub dat_set: Option<String>,
+    /// Application-assigned GOOSE identifier
+    pub go_id: Option<String>,
+    /// Time of the last state change (Unix seconds), from the `t` field
+    pub event_time: Option<f64>,
+    /// State number — increments on every dataset change
+    pub st_num: u32,
+    /// Sequence number — increments on every retransmission of the same state
+    pub sq_num: u32,
+    /// Simulation / test flag (PDU field or Ed. 2 header bit)
+    pub simulation: bool,
+    /// Configuration revision of the dataset
+    pub conf_rev: Option<u32>,
+    /// "Needs commissioning" — dataset is misconfigured on the publisher
+    pub nds_com: bool,
+    /// Number of dataset entries carried in allData
+    pub num_dat_set_entries: Option<u32>,
+}
+
+/// Sequence state of one GOOSE control block across frames.
+///
+/// One stream per (publisher MAC, gocbRef). Regressions are counted rather
+/// than flagged once so analysis can tell a one-off glitch from sustained
+/// injection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GooseStream {
+    pub gocb_ref: String,
+    /// Source MAC of the publishing IED
+    pub publisher_mac: String,
+    /// Multicast destination MAC
+    pub destination_mac: String,
+    pub app_id: u16,
+    pub go_id: Option<String>,
+    pub dat_set: Option<String>,
+    pub conf_rev: Option<u32>,
+    pub frame_count: u64,
+    /// Number of stNum increments observed (dataset state changes)
+    pub state_changes: u64,
+    pub last_st_num: u32,
+    pub last_sq_num: u32,
+    /// Frames whose stNum went backwards (not a wrap or publisher restart)
+    pub st_num_regressions: u64,
+    /// Frames that repeated the current stNum with an older sqNum
+    pub sq_num_regressions: u64,
+    /// Times the stream restarted at stNum 1 / sqNum 0 (publisher reboot)
+    pub restarts: u64,
+    /// confRev changed mid-stream (publisher reconfigured)
+    pub conf_rev_changed: bool,
+    /// Any frame carried the simulation / test flag
+    pub simulation_seen: bool,
+}
+
+impl GooseStream {
+    /// Start tracking a control block from its first frame.
+    pub fn new(info: &GooseInfo, publisher_mac: &str, destination_mac: &str) -> Self {
+        Self {
+            gocb_ref: info.gocb_ref.clone(),
+            publisher_mac: publisher_mac.to_string(),
+            destination_mac: destination_mac.to_string(),
+            app_id: info.app_id,
+            go_id: info.go_id.clone(),
+            dat_set: info.dat_set.clone(),
+            conf_rev: info.conf_rev,
+            frame_count: 1,
+            state_changes: 0,
+            last_st_num: info.st_num,
+            last_sq_num: info.sq_num,
+            st_num_regressions: 0,
+            sq_num_regressions: 0,
+            restarts: 0,
+            conf_rev_changed: false,
+            simulation_seen: info.simulation,
+        }
+    }
+
+    /// Update the stream with a subsequent frame from the same control block.
+    pub fn observe(&mut self, info: &GooseInfo) {
+        self.frame_count += 1;
+        self.simulation_seen |= info.simulation;
+        if info.conf_rev.is_some() && info.conf_rev != self.conf_rev {
+            if self.conf_rev.is_some() {
+                self.conf_rev_changed = true;
+            }
+            self.conf_rev = info.conf_rev;
+        }
+
+        let (st, sq) = (info.st_num, info.sq_num);
+        let (last_st, last_sq) = (self.last_st_num, self.last_sq_num);
+
+        if st == last_st {
+            // Same state: retransmission. Equal sqNum is a duplicate (PRP/HSR
+            // or a mirrored port) and is ignored.
+            if sq < last_sq && !wrapped(last_sq, sq) {
+                self.sq_num_regressions += 1;
+                return;
+            }
+        } else if st > last_st || wrapped(last_st, st) {
+            self.state_changes += 1;
+        } else if st == 1 && sq == 0 {
+            self.restarts += 1;
+        } else {
+            // An older state reappeared — do not move the stream backwards,
+            // so every replayed frame after the first is also counted.
+            self.st_num_regressions += 1;
+            return;
+        }
+
+        self.last_st_num = st;
+        self.last_sq_num = sq;
+    }
+
+    /// True if the stream shows stNum/sqNum going backwards.
+    pub fn has_replay_indicators(&self) -> bool {
+        self.st_num_regressions > 0 || self.sq_num_regressions > 0
+    }
+}
+
+/// Counter rollover: 2^32-1 → 1 (IEC 61850-8-1 skips 0 after the first wrap).
+fn wrapped(last: u32, next: u32) -> bool {
+    last == u32::MAX && next <= 1
+}
+
+// ─── Parser ───────────────────────────────────────────────────────────────────
+
+/// Parse a GOOSE frame (payload starting at the APPID, after the EtherType).
+///
+/// Returns None if the header is truncated, the PDU is not a goosePdu, or the
+/// mandatory gocbRef / stNum / sqNum fields are missing.
+pub fn parse(payload: &[u8]) -> Option<GooseInfo> {
+    if payload.len() < HEADER_LEN {
+        return None;
+    }
+    let app_id = u16::from_be_bytes([payload[0], payload[1]]);
+    let header_simulation = payload[4] & 0x80 != 0;
+
+    let pdu = read_tlv(payload, HEADER_LEN)?;
+    if pdu.ident != GOOSE_PDU_TAG {
+        return None;
+    }
+
+    let mut gocb_ref = None;
+    let mut st_num = None;
+    let mut sq_num = None;
+    let mut info = GooseInfo {
+        app_id,
+        gocb_ref: String::new(),
+        time_allowed_to_live: None,
+        dat_set: None,
+        go_id: None,
+        event_time: None,
+        st_num: 0,
+        sq_num: 0,
+        simulation: header_simulation,
+        conf_rev: None,
+        nds_com: false,
+        num_dat_set_entries: None,
+    };
+
+    for field in children(payload, pdu.start, pdu.end) {
+        if !field.is_context() {
+            continue;
+        }
+        let body = &payload[field.start..field.end];
+        match field.number {
+            0 => gocb_ref = ber_string(body),
+            1 => info.time_allowed_to_live = ber_uint(body),
+            2 => info.dat_set = ber_string(body),
+            3 => info.go_id = ber_string(body),
+            4 => info.event_time = utc_time(body),
+            5 => st_num = ber_uint(body),
+            6 => sq_num = ber_uint(body),
+            7 => info.simulation |= ber_bool(body),
+            8 => info.conf_rev = ber_uint(body),
+            9 => info.nds_com = ber_bool(body),
+            10 => info.num_dat_set_entries = ber_uint(body),
+            _ => {}
+        }
+    }
+
+    info.gocb_ref = gocb_ref?;
+    info.st_num = st_num?;
+    info.sq_num = sq_num?;
+    Some(info)
+}
+
+fn ber_bool(body: &[u8]) -> bool {
+    body.first().is_some_and(|&b| b != 0)
+}
+
+/// Decode an IEC 61850 UtcTime: 4 bytes seconds, 3 bytes binary fraction,
+/// 1 byte time quality.
+fn utc_time(body: &[u8]) -> Option<f64> {
+    if body.len() != 8 {
+        return None;
+    }
+    let secs = u32::from_be_bytes([body[0], body[1], body[2], body[3]]);
+    let frac = u32::from_be_bytes([0, body[4], body[5], body[6]]);
+    Some(secs as f64 + frac as f64 / (1u32 << 24) as f64)
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a GOOSE payload (from APPID onwards) around the given PDU fields.
+    fn frame(fields: &[u8]) -> Vec<u8> {
+        let mut pdu = vec![GOOSE_PDU_TAG, fields.len() as u8];
+        pdu.extend_from_slice(fields);
+        let len = (HEADER_LEN + pdu.len()) as u16;
+        let mut out = vec![0x00, 0x01];
+        out.extend_from_slice(&len.to_be_bytes());
+        out.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        out.extend_from_slice(&pdu);
+        out
+    }
+
+    #[rustfmt::skip]
+    fn trip_fields(st: u8, sq: u8) -> Vec<u8> {
+        vec![
+            0x80, 0x0F, b'I', b'E', b'D', b'1', b'L', b'D', b'0', b'/',
+                        b'L', b'L', b'N', b'0', b'$', b'G', b'O',      // gocbRef
+            0x81, 0x02, 0x07, 0xD0,                                   // timeAllowedtoLive 2000
+            0x82, 0x06, b'd', b's', b'T', b'r', b'i', b'p',           // datSet
+            0x83, 0x04, b'T', b'R', b'I', b'P',                       // goID
+            0x84, 0x08, 0x65, 0x53, 0xF1, 0x00, 0x80, 0x00, 0x00, 0x0A, // t
+            0x85, 0x01, st,                                           // stNum
+            0x86, 0x01, sq,                                           // sqNum
+            0x87, 0x01, 0x00,                                         // simulation
+            0x88, 0x01, 0x03,                                         // confRev
+            0x89, 0x01, 0x00,                                         // ndsCom
+            0x8A, 0x01, 0x02,                                         // numDatSetEntries
+            0xAB, 0x06, 0x83, 0x01, 0x01, 0x84, 0x01, 0x00,           // allData
+        ]
+    }
+
+    fn info(st: u8, sq: u8) -> GooseInfo {
+        parse(&frame(&trip_fields(st, sq))).unwrap()
+    }
+
+    #[test]
+    fn test_parse_goose_frame() {
+        let info = info(5, 12);
+        assert_eq!(info.app_id, 1);
+        assert_eq!(info.gocb_ref, "IED1LD0/LLN0$GO");
+        assert_eq!(info.time_allowed_to_live, Some(2000));
+        assert_eq!(info.dat_set.as_deref(), Some("dsTrip"));
+        assert_eq!(info.go_id.as_deref(), Some("TRIP"));
+        assert_eq!(info.st_num, 5);
+        assert_eq!(info.sq_num, 12);
+        assert_eq!(info.conf_rev, Some(3));
+        assert_eq!(info.num_dat_set_entries, Some(2));
+        assert!(!info.simulation);
+        assert!(!info.nds_com);
+        let t = info.event_time.unwrap();
+        assert!((t - 1_700_000_000.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_header_simulation_bit() {
+        let mut payload = frame(&trip_fields(1, 0));
+        payload[4] = 0x80;
+        assert!(parse(&payload).unwrap().simulation);
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        assert!(parse(&[]).is_none());
+        assert!(parse(&[0x00, 0x01, 0x00, 0x08, 0, 0, 0, 0]).is_none());
+        // Not a goosePdu (SV savPdu tag 0x60)
+        let mut payload = frame(&trip_fields(1, 0));
+        payload[HEADER_LEN] = 0x60;
+        assert!(parse(&payload).is_none());
+        // Missing stNum/sqNum
+        #[rustfmt::skip]
+        let fields = [0x80, 0x03, b'g', b'c', b'b'];
+        assert!(parse(&frame(&fields)).is_none());
+    }
+
+    #[test]
+    fn test_stream_normal_sequence() {
+        let mut stream = GooseStream::new(&info(1, 0), "00:0c:cd:00:00:01", "01:0c:cd:01:00:01");
+        for sq in 1..5 {
+            stream.observe(&info(1, sq));
+        }
+        stream.observe(&info(2, 0));
+        stream.observe(&info(2, 1));
+        // Duplicate (PRP/HSR) is not a regression
+        stream.observe(&info(2, 1));
+        assert_eq!(stream.frame_count, 8);
+        assert_eq!(stream.state_changes, 1);
+        assert_eq!((stream.last_st_num, stream.last_sq_num), (2, 1));
+        assert!(!stream.has_replay_indicators());
+    }
+
+    #[test]
+    fn test_stream_detects_st_num_replay() {
+        let mut stream = GooseStream::new(&info(7, 3), "00:0c:cd:00:00:01", "01:0c:cd:01:00:01");
+        // Attacker replays a captured older trip state
+        stream.observe(&info(4, 0));
+        stream.observe(&info(4, 1));
+        // Legitimate publisher keeps retransmitting
+        stream.observe(&info(7, 4));
+        assert_eq!(stream.st_num_regressions, 2);
+        assert_eq!(stream.last_st_num, 7);
+        assert!(stream.has_replay_indicators());
+
+        stream.observe(&info(7, 2));
+        assert_eq!(stream.sq_num_regressions, 1);
+    }
+
+    #[test]
+    fn test_stream_restart_and_wrap() {
+        let mut stream = GooseStream::new(&info(9, 2), "00:0c:cd:00:00:01", "01:0c:cd:01:00:01");
+        stream.observe(&info(1, 0));
+        assert_eq!(stream.restarts, 1);
+        assert!(!stream.has_replay_indicators());
+
+        let mut at_max = info(1, 0);
+        at_max.st_num = u32::MAX;
+        stream.observe(&at_max);
+        stream.observe(&info(1, 0));
+        assert_eq!(stream.st_num_regressions, 0);
+        assert_eq!(stream.restarts, 1);
+    }
+}
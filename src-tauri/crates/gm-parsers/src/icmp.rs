@@ -0,0 +1,388 @@
+//! ICMP and ICMPv6 parser.
+//!
+//! ICMP carries no application data worth inventorying, but it is how hosts
+//! are found: a ping sweep is a run of echo requests from one source to many
+//! addresses, and the replies (or the destination-unreachable errors routers
+//! send back) tell the sweeper which addresses are live. Error messages also
+//! quote the header of the packet that failed, so an unreachable names the
+//! host and port that could not be reached.
+//!
+//! `gm-capture` hands over the whole ICMP message (header included) as the
+//! payload of packets whose transport is `Icmp` or `Icmpv6`:
+//!
+//! ```text
+//! [0]      type
+//! [1]      code
+//! [2..4]   checksum
+//! [4..8]   rest of header: identifier + sequence for echo,
+//!          unused (or MTU / pointer) for errors
+//! [8..]    echo data, or the quoted IP header + first 8 bytes of the
+//!          offending packet for errors
+//! ```
+//!
+//! Reference: RFC 792, RFC 4443, RFC 4861
+
+use std::collections::BTreeSet;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+
+/// Most distinct addresses remembered per list of an [`IcmpActivity`].
+const MAX_TARGETS: usize = 4096;
+
+/// What an ICMP message is, across both versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IcmpKind {
+    EchoRequest,
+    EchoReply,
+    DestinationUnreachable,
+    TimeExceeded,
+    Redirect,
+    RouterSolicitation,
+    RouterAdvertisement,
+    /// ICMPv6 neighbor discovery (the IPv6 replacement for ARP)
+    NeighborSolicitation,
+    NeighborAdvertisement,
+    Other,
+}
+
+impl IcmpKind {
+    fn from_v4(icmp_type: u8) -> Self {
+        match icmp_type {
+            0 => IcmpKind::EchoReply,
+            3 => IcmpKind::DestinationUnreachable,
+            5 => IcmpKind::Redirect,
+            8 => IcmpKind::EchoRequest,
+            9 => IcmpKind::RouterAdvertisement,
+            10 => IcmpKind::RouterSolicitation,
+            11 => IcmpKind::TimeExceeded,
+            _ => IcmpKind::Other,
+        }
+    }
+
+    fn from_v6(icmp_type: u8) -> Self {
+        match icmp_type {
+            1 => IcmpKind::DestinationUnreachable,
+            3 => IcmpKind::TimeExceeded,
+            128 => IcmpKind::EchoRequest,
+            129 => IcmpKind::EchoReply,
+            133 => IcmpKind::RouterSolicitation,
+            134 => IcmpKind::RouterAdvertisement,
+            135 => IcmpKind::NeighborSolicitation,
+            136 => IcmpKind::NeighborAdvertisement,
+            137 => IcmpKind::Redirect,
+            _ => IcmpKind::Other,
+        }
+    }
+
+    /// An error message, which quotes the packet that caused it.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            IcmpKind::DestinationUnreachable | IcmpKind::TimeExceeded
+        )
+    }
+}
+
+/// Fields of one ICMP or ICMPv6 message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IcmpInfo {
+    /// True for ICMPv6
+    pub v6: bool,
+    pub icmp_type: u8,
+    pub code: u8,
+    pub kind: IcmpKind,
+    /// Echo identifier and sequence number (echo request/reply only)
+    pub echo_id: Option<u16>,
+    pub echo_seq: Option<u16>,
+    /// Destination of the packet an error message reports on
+    pub quoted_dst_ip: Option<String>,
+    /// IP protocol number of the quoted packet
+    pub quoted_protocol: Option<u8>,
+    /// Destination port of the quoted packet, if it was TCP or UDP
+    pub quoted_dst_port: Option<u16>,
+}
+
+/// Parse an ICMP (`v6 == false`) or ICMPv6 message, header included.
+///
+/// Returns None if the message is shorter than the 8-byte ICMP header.
+pub fn parse(payload: &[u8], v6: bool) -> Option<IcmpInfo> {
+    if payload.len() < 8 {
+        return None;
+    }
+    let icmp_type = payload[0];
+    let kind = if v6 {
+        IcmpKind::from_v6(icmp_type)
+    } else {
+        IcmpKind::from_v4(icmp_type)
+    };
+    let mut info = IcmpInfo {
+        v6,
+        icmp_type,
+        code: payload[1],
+        kind,
+        echo_id: None,
+        echo_seq: None,
+        quoted_dst_ip: None,
+        quoted_protocol: None,
+        quoted_dst_port: None,
+    };
+    if matches!(kind, IcmpKind::EchoRequest | IcmpKind::EchoReply) {
+        info.echo_id = Some(u16::from_be_bytes([payload[4], payload[5]]));
+        info.echo_seq = Some(u16::from_be_bytes([payload[6], payload[7]]));
+    } else if kind.is_error() {
+        let quoted = if v6 {
+            parse_quoted_v6(&payload[8..])
+        } else {
+            parse_quoted_v4(&payload[8..])
+        };
+        if let Some((dst_ip, protocol, dst_port)) = quoted {
+            info.quoted_dst_ip = Some(dst_ip);
+            info.quoted_protocol = Some(protocol);
+            info.quoted_dst_port = dst_port;
+        }
+    }
+    Some(info)
+}
+
+/// Destination, protocol and destination port of a quoted IPv4 packet.
+fn parse_quoted_v4(quoted: &[u8]) -> Option<(String, u8, Option<u16>)> {
+    if quoted.len() < 20 || quoted[0] >> 4 != 4 {
+        return None;
+    }
+    let ihl = usize::from(quoted[0] & 0x0F) * 4;
+    let protocol = quoted[9];
+    let dst = Ipv4Addr::new(quoted[16], quoted[17], quoted[18], quoted[19]);
+    Some((
+        dst.to_string(),
+        protocol,
+        quoted_port(protocol, quoted.get(ihl..)),
+    ))
+}
+
+/// Destination, next header and destination port of a quoted IPv6 packet.
+/// Extension headers are not walked; the port is only read when TCP or UDP
+/// follows the fixed header directly.
+fn parse_quoted_v6(quoted: &[u8]) -> Option<(String, u8, Option<u16>)> {
+    if quoted.len() < 40 || quoted[0] >> 4 != 6 {
+        return None;
+    }
+    let protocol = quoted[6];
+    let mut dst = [0u8; 16];
+    dst.copy_from_slice(&quoted[24..40]);
+    Some((
+        Ipv6Addr::from(dst).to_string(),
+        protocol,
+        quoted_port(protocol, quoted.get(40..)),
+    ))
+}
+
+fn quoted_port(protocol: u8, transport: Option<&[u8]>) -> Option<u16> {
+    match transport {
+        Some(t) if matches!(protocol, 6 | 17) && t.len() >= 4 => {
+            Some(u16::from_be_bytes([t[2], t[3]]))
+        }
+        _ => None,
+    }
+}
+
+/// ICMP discovery activity of one host: what it pinged, what answered, and
+/// what the network reported unreachable back to it.
+///
+/// A host pinging many distinct addresses is sweeping for live hosts; the
+/// responsive and unreachable lists show what the sweep learned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcmpActivity {
+    pub ip_address: String,
+    pub echo_requests: u64,
+    /// Distinct addresses sent an echo request
+    pub echo_targets: BTreeSet<String>,
+    pub echo_replies: u64,
+    /// Distinct addresses that answered an echo request
+    pub responsive_targets: BTreeSet<String>,
+    /// Destination-unreachable and time-exceeded errors sent to this host
+    pub errors_received: u64,
+    /// Destinations those errors reported as unreachable
+    pub unreachable_targets: BTreeSet<String>,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+impl IcmpActivity {
+    pub fn new(ip_address: &str, timestamp: &str) -> Self {
+        Self {
+            ip_address: ip_address.to_string(),
+            echo_requests: 0,
+            echo_targets: BTreeSet::new(),
+            echo_replies: 0,
+            responsive_targets: BTreeSet::new(),
+            errors_received: 0,
+            unreachable_targets: BTreeSet::new(),
+            first_seen: timestamp.to_string(),
+            last_seen: timestamp.to_string(),
+        }
+    }
+
+    /// The host an ICMP message between `src_ip` and `dst_ip` is activity
+    /// of: the sender of an echo request, the receiver of a reply or error.
+    /// None for messages that say nothing about discovery.
+    pub fn subject<'a>(info: &IcmpInfo, src_ip: &'a str, dst_ip: &'a str) -> Option<&'a str> {
+        match info.kind {
+            IcmpKind::EchoRequest => Some(src_ip),
+            IcmpKind::EchoReply => Some(dst_ip),
+            kind if kind.is_error() => Some(dst_ip),
+            _ => None,
+        }
+    }
+
+    /// Record a message whose [`subject`](Self::subject) is this host;
+    /// `peer` is the other end of the packet.
+    pub fn observe(&mut self, info: &IcmpInfo, peer: &str, timestamp: &str) {
+        match info.kind {
+            IcmpKind::EchoRequest => {
+                self.echo_requests += 1;
+                insert_capped(&mut self.echo_targets, peer);
+            }
+            IcmpKind::EchoReply => {
+                self.echo_replies += 1;
+                insert_capped(&mut self.responsive_targets, peer);
+            }
+            kind if kind.is_error() => {
+                self.errors_received += 1;
+                if let Some(ref dst) = info.quoted_dst_ip {
+                    if kind == IcmpKind::DestinationUnreachable {
+                        insert_capped(&mut self.unreachable_targets, dst);
+                    }
+                }
+            }
+            _ => return,
+        }
+        self.last_seen = timestamp.to_string();
+    }
+
+    /// Number of distinct addresses this host has pinged.
+    pub fn fan_out(&self) -> usize {
+        self.echo_targets.len()
+    }
+}
+
+fn insert_capped(set: &mut BTreeSet<String>, ip: &str) {
+    if set.len() < MAX_TARGETS && !set.contains(ip) {
+        set.insert(ip.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn echo(icmp_type: u8, id: u16, seq: u16) -> Vec<u8> {
+        let mut p = vec![icmp_type, 0, 0, 0];
+        p.extend_from_slice(&id.to_be_bytes());
+        p.extend_from_slice(&seq.to_be_bytes());
+        p.extend_from_slice(b"abcdefgh");
+        p
+    }
+
+    #[rustfmt::skip]
+    fn port_unreachable_v4() -> Vec<u8> {
+        let mut p = vec![3, 3, 0, 0, 0, 0, 0, 0];
+        // Quoted IPv4 header: UDP from 10.0.0.5 to 10.0.0.9
+        p.extend_from_slice(&[
+            0x45, 0x00, 0x00, 0x24, 0x00, 0x01, 0x00, 0x00,
+            0x40, 17, 0x00, 0x00,
+            10, 0, 0, 5,
+            10, 0, 0, 9,
+        ]);
+        // Quoted UDP header: 50000 → 47808
+        p.extend_from_slice(&[0xC3, 0x50, 0xBA, 0xC0, 0x00, 0x10, 0x00, 0x00]);
+        p
+    }
+
+    #[test]
+    fn test_parse_echo() {
+        let info = parse(&echo(8, 0x1234, 7), false).unwrap();
+        assert_eq!(info.kind, IcmpKind::EchoRequest);
+        assert_eq!(info.echo_id, Some(0x1234));
+        assert_eq!(info.echo_seq, Some(7));
+
+        let reply = parse(&echo(129, 1, 1), true).unwrap();
+        assert!(reply.v6);
+        assert_eq!(reply.kind, IcmpKind::EchoReply);
+        // Type 129 is not an ICMPv4 type
+        assert_eq!(
+            parse(&echo(129, 1, 1), false).unwrap().kind,
+            IcmpKind::Other
+        );
+    }
+
+    #[test]
+    fn test_parse_unreachable_quotes_original() {
+        let info = parse(&port_unreachable_v4(), false).unwrap();
+        assert_eq!(info.kind, IcmpKind::DestinationUnreachable);
+        assert_eq!(info.code, 3);
+        assert_eq!(info.quoted_dst_ip.as_deref(), Some("10.0.0.9"));
+        assert_eq!(info.quoted_protocol, Some(17));
+        assert_eq!(info.quoted_dst_port, Some(47808));
+        assert_eq!(info.echo_id, None);
+    }
+
+    #[test]
+    fn test_parse_v6_unreachable() {
+        let mut p = vec![1, 0, 0, 0, 0, 0, 0, 0];
+        let mut header = [0u8; 40];
+        header[0] = 0x60;
+        header[6] = 58;
+        header[24] = 0xfd;
+        header[39] = 0x09;
+        p.extend_from_slice(&header);
+        let info = parse(&p, true).unwrap();
+        assert_eq!(info.kind, IcmpKind::DestinationUnreachable);
+        assert_eq!(info.quoted_dst_ip.as_deref(), Some("fd00::9"));
+        assert_eq!(info.quoted_protocol, Some(58));
+        assert_eq!(info.quoted_dst_port, None);
+    }
+
+    #[test]
+    fn test_rejects_truncated() {
+        assert!(parse(&[8, 0, 0, 0, 0, 1], false).is_none());
+        // An error with a truncated quote still parses, without the quote
+        let info = parse(&port_unreachable_v4()[..16], false).unwrap();
+        assert_eq!(info.kind, IcmpKind::DestinationUnreachable);
+        assert_eq!(info.quoted_dst_ip, None);
+    }
+
+    #[test]
+    fn test_activity_tracks_sweep() {
+        let request = parse(&echo(8, 1, 1), false).unwrap();
+        let reply = parse(&echo(0, 1, 1), false).unwrap();
+        let unreachable = parse(&port_unreachable_v4(), false).unwrap();
+
+        let sweeper = "10.0.0.5";
+        assert_eq!(
+            IcmpActivity::subject(&request, sweeper, "10.0.0.1"),
+            Some(sweeper)
+        );
+        assert_eq!(
+            IcmpActivity::subject(&reply, "10.0.0.1", sweeper),
+            Some(sweeper)
+        );
+
+        let mut activity = IcmpActivity::new(sweeper, "t0");
+        for last in 1..=20 {
+            activity.observe(&request, &format!("10.0.0.{}", last), "t1");
+        }
+        activity.observe(&request, "10.0.0.1", "t2");
+        activity.observe(&reply, "10.0.0.1", "t3");
+        activity.observe(&unreachable, "10.0.0.254", "t4");
+
+        assert_eq!(activity.echo_requests, 21);
+        assert_eq!(activity.fan_out(), 20);
+        assert_eq!(activity.echo_replies, 1);
+        assert!(activity.responsive_targets.contains("10.0.0.1"));
+        assert_eq!(activity.errors_received, 1);
+        assert!(activity.unreachable_targets.contains("10.0.0.9"));
+        assert_eq!(activity.last_seen, "t4");
+    }
+}
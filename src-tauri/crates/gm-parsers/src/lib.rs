@@ -9,63 +9,185 @@
 //! 1. **Port-based detection** (fast, works on every packet):
 //!    Maps well-known ports to likely protocols. This gives us an initial
 //!    classification that's correct ~95% of the time for standard deployments.
+//!    Packets on ports nothing claims get lightweight payload probes
+//!    (MBAP, DNP3 link header, TPKT/COTP, ENIP header) as a second pass,
+//!    and Modbus RTU frames on serial device server ports ([`serial_tunnel`]).
 //!
 //! 2. **Deep parsing** (Phase 4, per-protocol inspection):
 //!    Extracts application-layer details: function codes, device IDs,
 //!    master/slave roles, register ranges, polling patterns.
+//!    [`deep_parse_stream`] reassembles PDUs that span TCP segments first.
+//!
+//! Modbus and DNP3 function code names and their write/control flags come
+//! from a registry ([`function_codes`]) that can be extended at runtime.
 //!
 //! ## Adding a New Protocol
 //!
 //! 1. Add a variant to `IcsProtocol`
 //! 2. Add port mappings in `identify_by_port()`
 //! 3. Add a parser module (e.g., `modbus.rs`, `dnp3.rs`)
+//! 4. For TCP protocols with a length field, add its framing to
+//!    `reassembly::framing_for()` so PDUs split across segments parse
 
+pub mod ads;
+pub mod arp;
 pub mod bacnet;
+mod ber;
+pub mod budget;
+pub mod cdp;
+pub mod cloud;
+pub mod codesys;
+pub mod crimson;
+pub mod dhcp;
+pub mod direction;
 pub mod dnp3;
+pub mod dns;
+pub mod engineering;
 pub mod enip;
+pub mod fins;
+pub mod fox;
+pub mod function_codes;
+pub mod ge_srtp;
+pub mod goose;
+pub mod icmp;
 pub mod iec104;
 pub mod lldp;
+pub mod mms;
 pub mod modbus;
+pub mod mqtt;
+pub mod nbns;
+pub mod opc_da;
+pub mod opcua;
 pub mod profinet_dcp;
+pub mod profinet_rt;
 mod protocol;
+pub mod reassembly;
 pub mod redundancy;
+pub mod register_map;
 pub mod s7comm;
+pub mod serial_tunnel;
+pub mod slmp;
+pub mod smb;
 pub mod snmp;
+pub mod sv;
+pub mod tls;
+pub mod tristation;
 pub mod vendor_tables;
+pub mod vnetip;
 
+pub use ads::{
+    parse as parse_ads, AdsCommand, AdsDeviceInfo, AdsInfo, AdsRole, AdsState, AmsAddr, AmsMessage,
+};
+pub use arp::{parse as parse_arp, ArpBinding, ArpInfo, ArpOperation, ARP_ETHERTYPE};
 pub use bacnet::{
-    parse as parse_bacnet, BacnetIAm, BacnetInfo, BacnetObjectType, BacnetPduType, BacnetRole,
-    BacnetService, BvlcFunction,
+    parse as parse_bacnet, BacnetDeviceProperties, BacnetIAm, BacnetInfo, BacnetObjectType,
+    BacnetPduType, BacnetRole, BacnetService, BvlcFunction,
+};
+pub use budget::{DeepParseLimiter, DeepParseSampling};
+pub use cdp::{parse as parse_cdp, CdpInfo};
+pub use cloud::{classify_host as classify_cloud_host, url_host, CloudCategory, CloudService};
+pub use codesys::{
+    parse as parse_codesys, service_name as codesys_service_name, CodesysInfo, CodesysRole,
+    CodesysVersion,
+};
+pub use crimson::{parse as parse_crimson, CrimsonIdentity, CrimsonInfo, CrimsonRole};
+pub use dhcp::{
+    parse as parse_dhcp, DhcpInfo, DhcpMessageType, DHCP_CLIENT_PORT, DHCP_SERVER_PORT,
+};
+pub use direction::{orient, DirectionBasis, DirectionEvidence, Orientation};
+pub use dnp3::{
+    function_code_name as dnp3_function_code_name,
+    is_write_function_code as dnp3_is_write_function_code, parse_dnp3, Dnp3Info, Dnp3Role,
+    TransportReassembler as Dnp3TransportReassembler,
+};
+pub use dns::{
+    parse as parse_dns, parse_tcp as parse_dns_tcp, DnsAnswer, DnsBinding, DnsBindingSource,
+    DnsInfo, DnsRecordData, DNS_PORT, LLMNR_PORT, MDNS_PORT,
+};
+pub use engineering::{
+    detect as detect_engineering_software, EngineeringFingerprint, EngineeringSoftware,
 };
-pub use dnp3::{function_code_name as dnp3_function_code_name, parse_dnp3, Dnp3Info, Dnp3Role};
 pub use enip::{
     parse as parse_enip, CipClass, CipService, EnipCommand, EnipIdentity, EnipInfo, EnipRole,
 };
+pub use fins::{
+    command_code_name as fins_command_code_name, parse as parse_fins, FinsAddress,
+    FinsControllerData, FinsInfo, FinsRole, FinsTcpCommand,
+};
+pub use fox::{parse as parse_fox, FoxHello, FoxInfo, FoxRole};
+pub use function_codes::{
+    function_code_info, is_write_or_control_function_code, list_function_codes,
+    register_function_code, register_function_codes_yaml, reset_function_codes, FunctionCodeDef,
+    FunctionCodeFile, FunctionCodeProtocol, FunctionCodeRegistry,
+};
+pub use ge_srtp::{
+    parse as parse_ge_srtp, parse_controller_info as parse_ge_srtp_controller_info,
+    service_name as ge_srtp_service_name, SrtpControllerInfo, SrtpInfo, SrtpMemoryAccess,
+    SrtpPacketType, SrtpPlcState, SrtpRole,
+};
+pub use goose::{parse as parse_goose, GooseInfo, GooseStream, GOOSE_ETHERTYPE};
+pub use icmp::{parse as parse_icmp, IcmpActivity, IcmpInfo, IcmpKind};
 pub use iec104::{
     parse as parse_iec104, AsduTypeId, CauseOfTransmission, Iec104FrameType, Iec104Info,
     Iec104Role, UFrameFunction,
 };
 pub use lldp::{parse as parse_lldp, LldpInfo, LldpMgmtAddress};
+pub use mms::{parse as parse_mms, MmsIdentity, MmsInfo, MmsPduType, MmsRole, MmsService};
 pub use modbus::{
-    function_code_name as modbus_function_code_name, parse_modbus, ModbusDeviceId, ModbusInfo,
-    ModbusRole, RegisterRange, RegisterType,
+    function_code_name as modbus_function_code_name,
+    is_write_function_code as modbus_is_write_function_code, parse_modbus, ModbusDeviceId,
+    ModbusFraming, ModbusInfo, ModbusRole, RegisterRange, RegisterType,
+};
+pub use mqtt::{
+    parse_connect as parse_mqtt_connect, parse_packets as parse_mqtt_packets, MqttConnect,
+    MqttPacket, MqttPublish, MqttSubscribe,
 };
+pub use nbns::{parse as parse_nbns, NbnsInfo, NbnsOpcode, NetbiosName, NBNS_PORT};
+pub use opc_da::{parse as parse_opc_da, DceRpcPacketType, OpcDaInfo, OpcInterface};
+pub use opcua::{parse_reverse_hello as parse_opcua_reverse_hello, OpcUaReverseHello};
 pub use profinet_dcp::{
-    parse as parse_profinet_dcp, DcpDeviceInfo, DcpServiceId, DcpServiceType, ProfinetDcpInfo,
-    ProfinetRole,
+    parse as parse_profinet_dcp, parse_frame as parse_profinet_dcp_frame, DcpDeviceInfo,
+    DcpServiceId, DcpServiceType, ProfinetDcpInfo, ProfinetRole, PROFINET_ETHERTYPE,
 };
-pub use protocol::{identify_by_port, identify_protocol, IcsProtocol};
+pub use profinet_rt::{parse as parse_profinet_rt, ProfinetRtFrame, ProfinetRtStream, RtClass};
+pub use protocol::{
+    identify_by_payload, identify_by_port, identify_protocol, IcsProtocol, IdentificationMethod,
+    ProtocolIdentification,
+};
+pub use reassembly::PduReassembler;
 pub use redundancy::{
     detect_protocol as detect_redundancy_protocol, parse as parse_redundancy, RedundancyInfo,
     RedundancyProtocol,
 };
+pub use register_map::{build_register_map, RegisterAccess, RegisterRegion};
 pub use s7comm::{
     function_code_name as s7_function_code_name, parse as parse_s7, CotpParams, CotpPduType,
     S7Function, S7Info, S7PduType, S7Role,
 };
+pub use serial_tunnel::{
+    detect as detect_serial_tunnel, ComPortSettings, SerialFraming, SerialGateway,
+    SerialTunnelInfo, TunneledProtocol,
+};
+pub use slmp::{
+    command_name as slmp_command_name, parse as parse_slmp,
+    parse_cpu_model as parse_slmp_cpu_model, SlmpCpuModel, SlmpDeviceAccess, SlmpFrame, SlmpInfo,
+    SlmpRole,
+};
+pub use smb::{
+    parse as parse_smb, NtlmIdentity, Smb2Command, Smb2Message, SmbInfo, SmbRole, SMB_PORTS,
+};
 pub use snmp::{parse_snmp_community, parse_snmp_response, SnmpDeviceInfo, SnmpInfo};
+pub use sv::{parse as parse_sv, SvAsdu, SvInfo, SvStream, SV_ETHERTYPE};
+pub use tls::{
+    client_hello_sni as tls_client_hello_sni, parse as parse_tls_hello,
+    version_name as tls_version_name, TlsHello, TlsHelloKind,
+};
+pub use tristation::{
+    parse as parse_tristation, TcmMessageType, TriStationCommand, TriStationInfo, TriStationRole,
+};
+pub use vnetip::{parse as parse_vnetip, VnetIpInfo, VnetIpStation};
 
-use gm_capture::ParsedPacket;
+use gm_capture::{ParsedPacket, TransportProtocol};
 use serde::Serialize;
 
 /// Unified result from deep protocol parsing.
@@ -76,8 +198,9 @@ use serde::Serialize;
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "protocol_type")]
 pub enum DeepParseResult {
-    /// Modbus TCP deep parse result
-    Modbus(ModbusInfo),
+    /// Modbus TCP deep parse result: every PDU in the segment, since
+    /// pipelining masters send several per packet
+    Modbus { pdus: Vec<ModbusInfo> },
     /// DNP3 deep parse result
     Dnp3(Dnp3Info),
     /// EtherNet/IP + CIP deep parse result
@@ -86,10 +209,34 @@ pub enum DeepParseResult {
     S7(S7Info),
     /// BACnet/IP (BVLCI/NPDU/APDU) deep parse result
     Bacnet(BacnetInfo),
+    /// OMRON FINS (FINS/TCP or FINS/UDP) deep parse result
+    Fins(FinsInfo),
+    /// Mitsubishi MELSEC MC protocol / SLMP (binary 3E/4E) deep parse result
+    Slmp(SlmpInfo),
+    /// GE SRTP (Series 90 / PACSystems) deep parse result
+    GeSrtp(SrtpInfo),
+    /// CODESYS V2/V3 runtime protocol deep parse result
+    Codesys(CodesysInfo),
+    /// Niagara Fox (hello identity) deep parse result
+    Fox(FoxInfo),
+    /// Red Lion Crimson v3 deep parse result
+    Crimson(CrimsonInfo),
+    /// Yokogawa Vnet/IP (station addressing) deep parse result
+    VnetIp(VnetIpInfo),
+    /// Triconex TriStation deep parse result
+    TriStation(TriStationInfo),
+    /// Beckhoff ADS/AMS deep parse result
+    Ads(AdsInfo),
     /// IEC 60870-5-104 deep parse result
     Iec104(Iec104Info),
+    /// IEC 61850 MMS (ISO/OSI stack over TPKT/COTP) deep parse result
+    Mms(MmsInfo),
     /// PROFINET DCP deep parse result
     ProfinetDcp(ProfinetDcpInfo),
+    /// SMB2/3 (session setup, tree connect, file open) deep parse result
+    Smb(SmbInfo),
+    /// Classic OPC DA / A&E (DCE/RPC) deep parse result
+    OpcDa(OpcDaInfo),
     /// LLDP (Link Layer Discovery Protocol) parse result
     Lldp(LldpInfo),
 }
@@ -104,19 +251,97 @@ pub enum DeepParseResult {
 /// * `packet` - The parsed packet with payload bytes
 /// * `protocol` - The protocol identified by port-based detection
 pub fn deep_parse(packet: &ParsedPacket, protocol: IcsProtocol) -> Option<DeepParseResult> {
+    deep_parse_payload(packet, &packet.payload, protocol)
+}
+
+/// Deep-parse a TCP stream PDU by PDU rather than segment by segment.
+///
+/// For protocols with a length-delimited framing (see
+/// [`reassembly::framing_for`]) the packet's payload is added to its
+/// connection's buffer in `reassembler`, and every PDU it completes is
+/// parsed on its own: a PDU split over segments parses once its last
+/// segment arrives, and a segment holding several PDUs yields one result
+/// each. DNP3 link frames (over TCP or UDP) also go through transport
+/// reassembly, so an application fragment spanning frames is read whole.
+/// Other protocols and UDP fall back to [`deep_parse`].
+pub fn deep_parse_stream(
+    reassembler: &mut PduReassembler,
+    packet: &ParsedPacket,
+    protocol: IcsProtocol,
+) -> Vec<DeepParseResult> {
+    let framing = match (packet.transport, reassembly::framing_for(protocol)) {
+        (TransportProtocol::Tcp, Some(framing)) => framing,
+        _ if protocol == IcsProtocol::Dnp3 => {
+            return reassembler
+                .dnp3
+                .parse(packet, &packet.payload)
+                .map(DeepParseResult::Dnp3)
+                .into_iter()
+                .collect();
+        }
+        _ => return deep_parse(packet, protocol).into_iter().collect(),
+    };
+    let pdus = reassembler.push(packet, framing);
+    pdus.iter()
+        .filter_map(|pdu| match protocol {
+            IcsProtocol::Dnp3 => reassembler
+                .dnp3
+                .parse(packet, pdu)
+                .map(DeepParseResult::Dnp3),
+            _ => deep_parse_payload(packet, pdu, protocol),
+        })
+        .collect()
+}
+
+/// Parse `payload` as `protocol`, taking addresses and ports from `packet`.
+fn deep_parse_payload(
+    packet: &ParsedPacket,
+    payload: &[u8],
+    protocol: IcsProtocol,
+) -> Option<DeepParseResult> {
     match protocol {
-        IcsProtocol::Modbus => parse_modbus(&packet.payload, packet.src_port, packet.dst_port)
-            .map(DeepParseResult::Modbus),
+        IcsProtocol::Modbus => {
+            let pdus = parse_modbus(payload, packet.src_port, packet.dst_port);
+            (!pdus.is_empty()).then_some(DeepParseResult::Modbus { pdus })
+        }
         IcsProtocol::Dnp3 => {
-            parse_dnp3(&packet.payload, packet.src_port, packet.dst_port).map(DeepParseResult::Dnp3)
+            parse_dnp3(payload, packet.src_port, packet.dst_port).map(DeepParseResult::Dnp3)
+        }
+        IcsProtocol::EthernetIp => enip::parse(payload).map(DeepParseResult::Enip),
+        IcsProtocol::S7comm => s7comm::parse(payload).map(DeepParseResult::S7),
+        IcsProtocol::Bacnet => bacnet::parse(payload).map(DeepParseResult::Bacnet),
+        IcsProtocol::Fins => fins::parse(payload).map(DeepParseResult::Fins),
+        IcsProtocol::Slmp => slmp::parse(payload).map(DeepParseResult::Slmp),
+        IcsProtocol::GeSrtp => ge_srtp::parse(payload).map(DeepParseResult::GeSrtp),
+        IcsProtocol::Codesys => {
+            codesys::parse(payload, packet.src_port, packet.dst_port).map(DeepParseResult::Codesys)
+        }
+        IcsProtocol::NiagaraFox => {
+            fox::parse(payload, packet.src_port, packet.dst_port).map(DeepParseResult::Fox)
+        }
+        IcsProtocol::Crimson => {
+            crimson::parse(payload, packet.src_port, packet.dst_port).map(DeepParseResult::Crimson)
+        }
+        IcsProtocol::VnetIp => vnetip::parse(
+            payload,
+            &packet.src_ip,
+            &packet.dst_ip,
+            packet.src_port,
+            packet.dst_port,
+        )
+        .map(DeepParseResult::VnetIp),
+        IcsProtocol::TriStation => tristation::parse(payload, packet.src_port, packet.dst_port)
+            .map(DeepParseResult::TriStation),
+        IcsProtocol::Ads => {
+            ads::parse(payload, packet.src_port, packet.dst_port).map(DeepParseResult::Ads)
         }
-        IcsProtocol::EthernetIp => enip::parse(&packet.payload).map(DeepParseResult::Enip),
-        IcsProtocol::S7comm => s7comm::parse(&packet.payload).map(DeepParseResult::S7),
-        IcsProtocol::Bacnet => bacnet::parse(&packet.payload).map(DeepParseResult::Bacnet),
-        IcsProtocol::Iec104 => iec104::parse(&packet.payload).map(DeepParseResult::Iec104),
-        IcsProtocol::Profinet => {
-            profinet_dcp::parse(&packet.payload).map(DeepParseResult::ProfinetDcp)
+        IcsProtocol::Iec104 => iec104::parse(payload).map(DeepParseResult::Iec104),
+        IcsProtocol::Iec61850Mms => mms::parse(payload).map(DeepParseResult::Mms),
+        IcsProtocol::Profinet => profinet_dcp::parse(payload).map(DeepParseResult::ProfinetDcp),
+        IcsProtocol::Smb => {
+            smb::parse(payload, packet.src_port, packet.dst_port).map(DeepParseResult::Smb)
         }
+        IcsProtocol::OpcDa => opc_da::parse(payload).map(DeepParseResult::OpcDa),
         _ => None,
     }
 }
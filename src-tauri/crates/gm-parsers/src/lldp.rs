@@ -280,10 +280,8 @@ fn handle_org_specific(info: &mut LldpInfo, oui: &[u8], subtype: u8, data: &[u8]
             enrich_from_description(info, text.trim());
         }
         // PROFINET OUI: 00-0E-CF (Siemens and third-party PROFINET devices)
-        ([0x00, 0x0E, 0xCF], _) => {
-            if info.vendor.is_none() {
-                info.vendor = Some("PROFINET Device".to_string());
-            }
+        ([0x00, 0x0E, 0xCF], _) if info.vendor.is_none() => {
+            info.vendor = Some("PROFINET Device".to_string());
         }
         _ => {}
     }
@@ -0,0 +1,707 @@
+//! IEC 61850 MMS (ISO 9506) deep protocol parser.
+//!
+//! IEC 61850 client/server traffic (station bus, SCADA ↔ IED) runs MMS over
+//! the full OSI upper-layer stack on TCP port 102 — the same TPKT/COTP
+//! transport Siemens S7comm uses. The two only diverge after the COTP header:
+//!
+//! ```text
+//! TPKT(4) → COTP DT(3) → 0x32 ........................ S7comm
+//!                      → 0x72 ........................ S7comm-plus
+//!                      → Session SPDU → Presentation → [ACSE] → MMS PDU
+//! ```
+//!
+//! Session SPDUs handled:
+//!   0x0D CONNECT / 0x0E ACCEPT — user data carries ACSE AARQ/AARE wrapping
+//!                                the MMS initiate-Request/Response
+//!   01 00 01 00               — GIVE-TOKENS + DATA TRANSFER, followed by
+//!                                presentation fully-encoded data (0x61) and
+//!                                the MMS PDU
+//!
+//! COTP connection requests are recognised by their TSAPs: MMS stacks use
+//! `00 01` / `00 00 00 01` style selectors, whereas S7 TSAPs encode the
+//! connection type (PG/OP/S7 basic) in a non-zero first byte.
+//!
+//! Everything above COTP is BER encoded. Only definite-length encodings are
+//! accepted — every MMS implementation seen on substation networks uses them.
+//!
+//! Reference: ISO 9506-2 (MMS), ISO 8327-1 (Session), ISO 8823 (Presentation),
+//! IEC 61850-8-1 (mapping), Wireshark MMS/PRES/SES dissectors
+//! Port: 102 TCP (ISO-TSAP)
+
+use serde::{Deserialize, Serialize};
+
+use crate::ber::{ber_string, ber_uint, children, read_tlv, Tlv};
+
+/// Offset of the COTP header within the payload (after the 4-byte TPKT).
+const COTP_OFFSET: usize = 4;
+
+/// Session layer GIVE-TOKENS + DATA TRANSFER SPDU pair.
+const SESSION_DATA_TRANSFER: [u8; 4] = [0x01, 0x00, 0x01, 0x00];
+
+/// Session CONNECT SPDU identifier.
+const SPDU_CONNECT: u8 = 0x0D;
+
+/// Session ACCEPT SPDU identifier.
+const SPDU_ACCEPT: u8 = 0x0E;
+
+/// Session parameter: User Data.
+const SESSION_PI_USER_DATA: u8 = 0xC1;
+
+/// Presentation fully-encoded-data tag ([APPLICATION 1] constructed).
+const PRES_FULLY_ENCODED: u8 = 0x61;
+
+/// Maximum BER nesting depth walked when collecting object names.
+const MAX_DEPTH: usize = 10;
+
+/// Cap on object names collected from a single PDU.
+const MAX_OBJECT_NAMES: usize = 32;
+
+// ─── Enums ────────────────────────────────────────────────────────────────────
+
+/// MMS PDU type (the outer CHOICE tag of MMSpdu).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MmsPduType {
+    /// [0] confirmed-RequestPDU
+    ConfirmedRequest,
+    /// [1] confirmed-ResponsePDU
+    ConfirmedResponse,
+    /// [2] confirmed-ErrorPDU
+    ConfirmedError,
+    /// [3] unconfirmed-PDU (InformationReport — IEC 61850 reports)
+    Unconfirmed,
+    /// [4] rejectPDU
+    Reject,
+    /// [5]–[7] cancel request/response/error
+    Cancel,
+    /// [8] initiate-RequestPDU (association setup from the client)
+    InitiateRequest,
+    /// [9] initiate-ResponsePDU
+    InitiateResponse,
+    /// [10] initiate-ErrorPDU
+    InitiateError,
+    /// [11] conclude-RequestPDU
+    ConcludeRequest,
+    /// [12]/[13] conclude response/error
+    ConcludeResponse,
+    /// Unrecognised tag number
+    Unknown(u8),
+}
+
+impl MmsPduType {
+    fn from_tag(number: u32) -> Self {
+        match number {
+            0 => MmsPduType::ConfirmedRequest,
+            1 => MmsPduType::ConfirmedResponse,
+            2 => MmsPduType::ConfirmedError,
+            3 => MmsPduType::Unconfirmed,
+            4 => MmsPduType::Reject,
+            5..=7 => MmsPduType::Cancel,
+            8 => MmsPduType::InitiateRequest,
+            9 => MmsPduType::InitiateResponse,
+            10 => MmsPduType::InitiateError,
+            11 => MmsPduType::ConcludeRequest,
+            12 | 13 => MmsPduType::ConcludeResponse,
+            n => MmsPduType::Unknown(n.min(u8::MAX as u32) as u8),
+        }
+    }
+}
+
+/// MMS service carried by a confirmed or unconfirmed PDU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MmsService {
+    /// [0] status
+    Status,
+    /// [1] getNameList — directory browsing of logical devices / nodes
+    GetNameList,
+    /// [2] identify — vendor, model, revision
+    Identify,
+    /// [4] read — data attribute reads (polling)
+    Read,
+    /// [5] write — control / setting changes (ATT&CK T0855)
+    Write,
+    /// [6] getVariableAccessAttributes — data model discovery
+    GetVariableAccessAttributes,
+    /// [11] defineNamedVariableList — dataset creation
+    DefineNamedVariableList,
+    /// [12] getNamedVariableListAttributes — dataset discovery
+    GetNamedVariableListAttributes,
+    /// [13] deleteNamedVariableList
+    DeleteNamedVariableList,
+    /// [26]–[28], [32] domain download (configuration push)
+    DomainDownload,
+    /// [29]–[31], [33] domain upload (configuration pull)
+    DomainUpload,
+    /// [38]–[44] program invocation create/delete/start/stop/resume/reset/kill
+    ProgramInvocation,
+    /// [46] obtainFile
+    ObtainFile,
+    /// [72]–[74] fileOpen / fileRead / fileClose
+    FileTransfer,
+    /// [76] fileDelete
+    FileDelete,
+    /// [77] fileDirectory — COMTRADE / SCL file listing
+    FileDirectory,
+    /// Unconfirmed [0] informationReport — IEC 61850 buffered/unbuffered report
+    InformationReport,
+    /// Unrecognised service tag
+    Unknown(u32),
+}
+
+impl MmsService {
+    fn from_confirmed_tag(number: u32) -> Self {
+        match number {
+            0 => MmsService::Status,
+            1 => MmsService::GetNameList,
+            2 => MmsService::Identify,
+            4 => MmsService::Read,
+            5 => MmsService::Write,
+            6 => MmsService::GetVariableAccessAttributes,
+            11 => MmsService::DefineNamedVariableList,
+            12 => MmsService::GetNamedVariableListAttributes,
+            13 => MmsService::DeleteNamedVariableList,
+            26..=28 | 32 => MmsService::DomainDownload,
+            29..=31 | 33 => MmsService::DomainUpload,
+            38..=44 => MmsService::ProgramInvocation,
+            46 => MmsService::ObtainFile,
+            72..=74 => MmsService::FileTransfer,
+            76 => MmsService::FileDelete,
+            77 => MmsService::FileDirectory,
+            n => MmsService::Unknown(n),
+        }
+    }
+
+    /// snake_case name used in aggregated deep-parse details.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MmsService::Status => "status",
+            MmsService::GetNameList => "get_name_list",
+            MmsService::Identify => "identify",
+            MmsService::Read => "read",
+            MmsService::Write => "write",
+            MmsService::GetVariableAccessAttributes => "get_variable_access_attributes",
+            MmsService::DefineNamedVariableList => "define_named_variable_list",
+            MmsService::GetNamedVariableListAttributes => "get_named_variable_list_attributes",
+            MmsService::DeleteNamedVariableList => "delete_named_variable_list",
+            MmsService::DomainDownload => "domain_download",
+            MmsService::DomainUpload => "domain_upload",
+            MmsService::ProgramInvocation => "program_invocation",
+            MmsService::ObtainFile => "obtain_file",
+            MmsService::FileTransfer => "file_transfer",
+            MmsService::FileDelete => "file_delete",
+            MmsService::FileDirectory => "file_directory",
+            MmsService::InformationReport => "information_report",
+            MmsService::Unknown(_) => "unknown",
+        }
+    }
+
+    /// True for services that change IED state or configuration.
+    pub fn is_write(&self) -> bool {
+        matches!(
+            self,
+            MmsService::Write
+                | MmsService::DefineNamedVariableList
+                | MmsService::DeleteNamedVariableList
+                | MmsService::DomainDownload
+                | MmsService::ProgramInvocation
+                | MmsService::FileDelete
+        )
+    }
+}
+
+/// Client/server role for an MMS device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MmsRole {
+    /// Sends requests and initiates associations (SCADA gateway / HMI / engineering tool)
+    Client,
+    /// Answers requests and publishes reports (IED)
+    Server,
+    /// Cannot determine role from this packet
+    Unknown,
+}
+
+// ─── Structs ──────────────────────────────────────────────────────────────────
+
+/// Identify response contents (vendor / model / revision of the MMS server).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MmsIdentity {
+    pub vendor: Option<String>,
+    pub model: Option<String>,
+    pub revision: Option<String>,
+}
+
+/// Parsed IEC 61850 MMS packet information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MmsInfo {
+    /// MMS PDU type
+    pub pdu_type: MmsPduType,
+    /// Invoke ID (confirmed request/response/error only)
+    pub invoke_id: Option<u32>,
+    /// Service carried by the PDU
+    pub service: Option<MmsService>,
+    /// Domain-specific object names referenced, as "LD/LN$FC$DO..."
+    pub object_names: Vec<String>,
+    /// Report ID (RptID) of an InformationReport
+    pub report_id: Option<String>,
+    /// Identify response contents
+    pub identity: Option<MmsIdentity>,
+    /// Detected role
+    pub role: MmsRole,
+}
+
+impl MmsInfo {
+    fn new(pdu_type: MmsPduType) -> Self {
+        let role = match pdu_type {
+            MmsPduType::ConfirmedRequest
+            | MmsPduType::InitiateRequest
+            | MmsPduType::ConcludeRequest => MmsRole::Client,
+            MmsPduType::ConfirmedResponse
+            | MmsPduType::ConfirmedError
+            | MmsPduType::Unconfirmed
+            | MmsPduType::InitiateResponse
+            | MmsPduType::InitiateError
+            | MmsPduType::ConcludeResponse => MmsRole::Server,
+            _ => MmsRole::Unknown,
+        };
+        MmsInfo {
+            pdu_type,
+            invoke_id: None,
+            service: None,
+            object_names: Vec::new(),
+            report_id: None,
+            identity: None,
+            role,
+        }
+    }
+}
+
+/// Split a domain-specific object name into logical device and logical node.
+///
+/// `"IED1LD0/XCBR1$ST$Pos$stVal"` → `("IED1LD0", Some("XCBR1"))`
+pub fn split_object_name(name: &str) -> (&str, Option<&str>) {
+    match name.split_once('/') {
+        Some((ld, rest)) => {
+            let ln = rest.split('$').next().filter(|s| !s.is_empty());
+            (ld, ln)
+        }
+        None => (name, None),
+    }
+}
+
+/// Walk a subtree and collect domain-specific ObjectNames.
+///
+/// ObjectName ::= domain-specific [1] SEQUENCE { domainId, itemId } — two
+/// VisibleStrings (0x1A) under one constructed node.
+fn collect_object_names(
+    data: &[u8],
+    start: usize,
+    end: usize,
+    depth: usize,
+    out: &mut Vec<String>,
+) {
+    if depth > MAX_DEPTH || out.len() >= MAX_OBJECT_NAMES {
+        return;
+    }
+    let kids = children(data, start, end);
+    if kids.len() == 2 && kids.iter().all(|k| k.ident == 0x1A) {
+        if let (Some(domain), Some(item)) = (
+            ber_string(&data[kids[0].start..kids[0].end]),
+            ber_string(&data[kids[1].start..kids[1].end]),
+        ) {
+            let name = format!("{}/{}", domain, item);
+            if !out.contains(&name) {
+                out.push(name);
+            }
+            return;
+        }
+    }
+    for k in kids {
+        if k.is_constructed() {
+            collect_object_names(data, k.start, k.end, depth + 1, out);
+        }
+    }
+}
+
+// ─── Upper-layer unwrapping ───────────────────────────────────────────────────
+
+/// Locate the MMS PDU inside a session DATA TRANSFER SPDU.
+///
+/// `01 00 01 00` → `61 len` (fully-encoded data) → `30 len` (PDV-list) →
+/// `02 01 ctx` (presentation-context-id) → `a0 len` (single-ASN1-type) → MMS
+fn mms_in_data_transfer(data: &[u8], session: usize) -> Option<Tlv> {
+    if data.get(session..session + 4)? != SESSION_DATA_TRANSFER {
+        return None;
+    }
+    let pres = read_tlv(data, session + 4)?;
+    if pres.ident != PRES_FULLY_ENCODED {
+        return None;
+    }
+    let pdv = read_tlv(data, pres.start)?;
+    if pdv.ident != 0x30 {
+        return None;
+    }
+    let kids = children(data, pdv.start, pdv.end);
+    let single = kids.iter().find(|k| k.ident == 0xA0)?;
+    read_tlv(&data[..single.end], single.start)
+}
+
+/// Locate the MMS initiate PDU inside a session CONNECT/ACCEPT SPDU.
+///
+/// Rather than unwrap presentation CP/CPA and ACSE AARQ/AARE in full, the
+/// session user data is scanned for an initiate tag (0xA8–0xAA) whose TLV
+/// ends exactly at the end of the user data — the MMS PDU is always the
+/// innermost, last element of the nested structure.
+fn mms_in_connect(data: &[u8], session: usize) -> Option<Tlv> {
+    // SPDU: SI(1) LI(1 | 0xFF + 2) then parameters (code(1) len(1 | 0xFF + 2) value)
+    let (li, mut pos) = match *data.get(session + 1)? {
+        0xFF => {
+            let hi = *data.get(session + 2)? as usize;
+            let lo = *data.get(session + 3)? as usize;
+            ((hi << 8) | lo, session + 4)
+        }
+        b => (b as usize, session + 2),
+    };
+    let spdu_end = (pos + li).min(data.len());
+
+    while pos + 2 <= spdu_end {
+        let code = data[pos];
+        let (len, value_start) = if data[pos + 1] == 0xFF {
+            let hi = *data.get(pos + 2)? as usize;
+            let lo = *data.get(pos + 3)? as usize;
+            ((hi << 8) | lo, pos + 4)
+        } else {
+            (data[pos + 1] as usize, pos + 2)
+        };
+        let value_end = (value_start + len).min(data.len());
+
+        if code == SESSION_PI_USER_DATA {
+            let user = &data[..value_end];
+            return (value_start..value_end)
+                .filter(|&i| matches!(user[i], 0xA8..=0xAA))
+                .filter_map(|i| read_tlv(user, i))
+                .find(|t| t.end == value_end);
+        }
+
+        // PGI units (e.g. 0x05 connection identifier) nest PIs — step into them
+        if matches!(code, 0x01 | 0x05 | 0x11 | 0x21) {
+            pos = value_start;
+        } else {
+            pos = value_end;
+        }
+    }
+    None
+}
+
+/// Offset of the session SPDU after a COTP DT header, if this is a DT TPDU.
+fn session_offset(payload: &[u8]) -> Option<usize> {
+    if payload.len() < 7 || payload[0] != 0x03 || payload[1] != 0x00 {
+        return None;
+    }
+    let cotp_len = payload[COTP_OFFSET] as usize;
+    if payload[COTP_OFFSET + 1] != 0xF0 {
+        return None;
+    }
+    let session = COTP_OFFSET + 1 + cotp_len;
+    if session >= payload.len() {
+        return None;
+    }
+    Some(session)
+}
+
+// ─── Public API ───────────────────────────────────────────────────────────────
+
+/// Quick check whether a port-102 payload is MMS rather than S7comm.
+///
+/// True for COTP DT TPDUs carrying an ISO session SPDU, and for COTP
+/// connection request/confirm TPDUs whose called TSAP starts with 0x00.
+pub fn is_mms(payload: &[u8]) -> bool {
+    if payload.len() < 7 || payload[0] != 0x03 || payload[1] != 0x00 {
+        return false;
+    }
+    match payload[COTP_OFFSET + 1] {
+        0xF0 => match session_offset(payload) {
+            Some(s) => {
+                payload[s..].starts_with(&SESSION_DATA_TRANSFER)
+                    || matches!(payload[s], SPDU_CONNECT | SPDU_ACCEPT)
+            }
+            None => false,
+        },
+        0xE0 | 0xD0 => {
+            // Walk COTP TLV parameters for the called TSAP (0xC2)
+            let tlv_end = (COTP_OFFSET + 1 + payload[COTP_OFFSET] as usize).min(payload.len());
+            let mut i = COTP_OFFSET + 7;
+            while i + 2 <= tlv_end {
+                let code = payload[i];
+                let len = payload[i + 1] as usize;
+                if code == 0xC2 && len >= 2 && i + 2 + len <= tlv_end {
+                    return payload[i + 2] == 0x00;
+                }
+                i += 2 + len;
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+/// Attempt to parse an IEC 61850 MMS TCP payload (TPKT + COTP + OSI + MMS).
+///
+/// Returns `None` if the payload is not a COTP DT TPDU, does not carry an
+/// ISO session SPDU, or the MMS PDU cannot be located (e.g. segmented).
+///
+/// # Arguments
+/// * `payload` - Raw TCP payload bytes (starting from TPKT header)
+pub fn parse(payload: &[u8]) -> Option<MmsInfo> {
+    let session = session_offset(payload)?;
+
+    let pdu = match payload[session] {
+        SPDU_CONNECT | SPDU_ACCEPT => mms_in_connect(payload, session)?,
+        _ => mms_in_data_transfer(payload, session)?,
+    };
+    if !pdu.is_context() {
+        return None;
+    }
+
+    let mut info = MmsInfo::new(MmsPduType::from_tag(pdu.number));
+
+    match info.pdu_type {
+        MmsPduType::ConfirmedRequest | MmsPduType::ConfirmedResponse => {
+            let kids = children(payload, pdu.start, pdu.end);
+            // invokeID Unsigned32 (universal INTEGER), then the service CHOICE
+            if let Some(id) = kids.first().filter(|k| k.ident == 0x02) {
+                info.invoke_id = ber_uint(&payload[id.start..id.end]);
+            }
+            if let Some(svc) = kids.get(1).filter(|k| k.is_context()) {
+                let service = MmsService::from_confirmed_tag(svc.number);
+                info.service = Some(service);
+
+                if service == MmsService::Identify && info.pdu_type == MmsPduType::ConfirmedResponse
+                {
+                    info.identity = Some(parse_identity(payload, svc));
+                }
+                if svc.is_constructed() {
+                    collect_object_names(payload, svc.start, svc.end, 0, &mut info.object_names);
+                }
+            }
+        }
+        MmsPduType::ConfirmedError => {
+            // invokeID is [0] IMPLICIT Unsigned32
+            if let Some(id) = children(payload, pdu.start, pdu.end)
+                .first()
+                .filter(|k| k.ident == 0x80)
+            {
+                info.invoke_id = ber_uint(&payload[id.start..id.end]);
+            }
+        }
+        MmsPduType::Unconfirmed => {
+            if let Some(svc) = children(payload, pdu.start, pdu.end).first() {
+                if svc.is_context() && svc.number == 0 {
+                    info.service = Some(MmsService::InformationReport);
+                    info.report_id = parse_report_id(payload, svc);
+                    collect_object_names(payload, svc.start, svc.end, 0, &mut info.object_names);
+                } else {
+                    info.service = Some(MmsService::Unknown(svc.number));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Some(info)
+}
+
+/// Identify-Response ::= SEQUENCE { vendorName [0], modelName [1], revision [2] }
+fn parse_identity(data: &[u8], svc: &Tlv) -> MmsIdentity {
+    let mut id = MmsIdentity::default();
+    for k in children(data, svc.start, svc.end) {
+        let value = ber_string(&data[k.start..k.end]);
+        match k.ident {
+            0x80 => id.vendor = value,
+            0x81 => id.model = value,
+            0x82 => id.revision = value,
+            _ => {}
+        }
+    }
+    id
+}
+
+/// RptID of an IEC 61850 report: the first visible-string ([10] = 0x8A)
+/// data value in the listOfAccessResult ([0]) of the InformationReport.
+fn parse_report_id(data: &[u8], report: &Tlv) -> Option<String> {
+    let kids = children(data, report.start, report.end);
+    let results = kids.iter().find(|k| k.ident == 0xA0)?;
+    let first = children(data, results.start, results.end)
+        .into_iter()
+        .next()?;
+    if first.ident == 0x8A {
+        ber_string(&data[first.start..first.end])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wrap an MMS PDU in TPKT + COTP DT + session DATA TRANSFER + presentation.
+    fn wrap(mms: &[u8]) -> Vec<u8> {
+        let mut single = vec![0xA0, mms.len() as u8];
+        single.extend_from_slice(mms);
+        let mut pdv = vec![0x02, 0x01, 0x03];
+        pdv.extend_from_slice(&single);
+        let mut pdv_list = vec![0x30, pdv.len() as u8];
+        pdv_list.extend_from_slice(&pdv);
+        let mut pres = vec![0x61, pdv_list.len() as u8];
+        pres.extend_from_slice(&pdv_list);
+
+        let mut out = vec![0x03, 0x00, 0x00, 0x00, 0x02, 0xF0, 0x80];
+        out.extend_from_slice(&SESSION_DATA_TRANSFER);
+        out.extend_from_slice(&pres);
+        let len = out.len() as u16;
+        out[2..4].copy_from_slice(&len.to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn test_read_request_object_names() {
+        #[rustfmt::skip]
+        let mms: Vec<u8> = vec![
+            0xA0, 0x26,                         // confirmed-RequestPDU
+            0x02, 0x01, 0x07,                   // invokeID 7
+            0xA4, 0x21,                         // read
+            0xA1, 0x1F,                         // variableAccessSpecification: listOfVariable
+            0xA0, 0x1D,
+            0x30, 0x1B,                         // SEQUENCE
+            0xA0, 0x19,                         // variableSpecification: name
+            0xA1, 0x17,                         // domain-specific
+            0x1A, 0x07, b'I', b'E', b'D', b'1', b'L', b'D', b'0',
+            0x1A, 0x0C, b'X', b'C', b'B', b'R', b'1', b'$', b'S', b'T', b'$', b'P', b'o', b's',
+        ];
+        let info = parse(&wrap(&mms)).unwrap();
+        assert_eq!(info.pdu_type, MmsPduType::ConfirmedRequest);
+        assert_eq!(info.invoke_id, Some(7));
+        assert_eq!(info.service, Some(MmsService::Read));
+        assert_eq!(info.role, MmsRole::Client);
+        assert_eq!(info.object_names, vec!["IED1LD0/XCBR1$ST$Pos".to_string()]);
+        assert_eq!(
+            split_object_name(&info.object_names[0]),
+            ("IED1LD0", Some("XCBR1"))
+        );
+    }
+
+    #[test]
+    fn test_write_request_is_write() {
+        #[rustfmt::skip]
+        let mms: Vec<u8> = vec![
+            0xA0, 0x1A,
+            0x02, 0x01, 0x01,                   // invokeID 1
+            0xA5, 0x15,                         // write
+            0xA0, 0x10,                         // listOfVariable
+            0x30, 0x0E, 0xA0, 0x0C, 0xA1, 0x0A,
+            0x1A, 0x03, b'L', b'D', b'0',
+            0x1A, 0x03, b'C', b'S', b'W',
+            0xA0, 0x01, 0x83,                   // listOfData (truncated boolean)
+        ];
+        let info = parse(&wrap(&mms)).unwrap();
+        assert_eq!(info.service, Some(MmsService::Write));
+        assert!(info.service.unwrap().is_write());
+        assert_eq!(info.object_names, vec!["LD0/CSW".to_string()]);
+    }
+
+    #[test]
+    fn test_information_report_rpt_id() {
+        #[rustfmt::skip]
+        let mms: Vec<u8> = vec![
+            0xA3, 0x16,                         // unconfirmed-PDU
+            0xA0, 0x14,                         // informationReport
+            0xA1, 0x05, 0x80, 0x03, b'R', b'P', b'T', // variableListName "RPT"
+            0xA0, 0x0B,                         // listOfAccessResult
+            0x8A, 0x09, b'u', b'r', b'c', b'b', b'M', b'e', b'a', b's', b'1',
+        ];
+        let info = parse(&wrap(&mms)).unwrap();
+        assert_eq!(info.pdu_type, MmsPduType::Unconfirmed);
+        assert_eq!(info.service, Some(MmsService::InformationReport));
+        assert_eq!(info.report_id.as_deref(), Some("urcbMeas1"));
+        assert_eq!(info.role, MmsRole::Server);
+    }
+
+    #[test]
+    fn test_identify_response() {
+        #[rustfmt::skip]
+        let mms: Vec<u8> = vec![
+            0xA1, 0x17,                         // confirmed-ResponsePDU
+            0x02, 0x01, 0x02,                   // invokeID 2
+            0xA2, 0x12,                         // identify
+            0x80, 0x03, b'A', b'B', b'B',
+            0x81, 0x06, b'R', b'E', b'F', b'6', b'1', b'5',
+            0x82, 0x03, b'2', b'.', b'0',
+        ];
+        let info = parse(&wrap(&mms)).unwrap();
+        assert_eq!(info.service, Some(MmsService::Identify));
+        let id = info.identity.unwrap();
+        assert_eq!(id.vendor.as_deref(), Some("ABB"));
+        assert_eq!(id.model.as_deref(), Some("REF615"));
+        assert_eq!(id.revision.as_deref(), Some("2.0"));
+    }
+
+    #[test]
+    fn test_initiate_request_in_connect_spdu() {
+        #[rustfmt::skip]
+        let initiate: Vec<u8> = vec![
+            0xA8, 0x0B,                         // initiate-RequestPDU
+            0x80, 0x03, 0x00, 0xFD, 0xE8,       // localDetailCalling 65000
+            0x81, 0x01, 0x05,                   // proposedMaxServOutstandingCalling
+            0x82, 0x01, 0x05,                   // proposedMaxServOutstandingCalled
+        ];
+        // Presentation/ACSE wrapping collapsed to an opaque prefix for the test
+        let mut user_data = vec![0x31, 0x00, 0x61, 0x00];
+        user_data.extend_from_slice(&initiate);
+
+        let mut payload = vec![0x03, 0x00, 0x00, 0x00, 0x02, 0xF0, 0x80];
+        payload.push(SPDU_CONNECT);
+        payload.push((user_data.len() + 2) as u8);
+        payload.push(SESSION_PI_USER_DATA);
+        payload.push(user_data.len() as u8);
+        payload.extend_from_slice(&user_data);
+
+        assert!(is_mms(&payload));
+        let info = parse(&payload).unwrap();
+        assert_eq!(info.pdu_type, MmsPduType::InitiateRequest);
+        assert_eq!(info.role, MmsRole::Client);
+    }
+
+    #[test]
+    fn test_cotp_cr_tsap_distinguishes_mms_from_s7() {
+        let mms_cr: Vec<u8> = vec![
+            0x03, 0x00, 0x00, 0x16, // TPKT
+            0x11, 0xE0, // COTP CR
+            0x00, 0x00, 0x00, 0x01, 0x00, // refs + class
+            0xC0, 0x01, 0x0D, // TPDU size
+            0xC1, 0x02, 0x00, 0x01, // calling TSAP 0x0001
+            0xC2, 0x02, 0x00, 0x01, // called TSAP 0x0001
+        ];
+        assert!(is_mms(&mms_cr));
+
+        let s7_cr: Vec<u8> = vec![
+            0x03, 0x00, 0x00, 0x16, 0x11, 0xE0, 0x00, 0x00, 0x00, 0x01, 0x00, 0xC1, 0x02, 0x01,
+            0x00, // calling TSAP
+            0xC2, 0x02, 0x01, 0x02, // called TSAP (PG, rack 0 slot 2)
+            0xC0, 0x01, 0x0A,
+        ];
+        assert!(!is_mms(&s7_cr));
+    }
+
+    #[test]
+    fn test_s7comm_dt_is_not_mms() {
+        let s7: Vec<u8> = vec![
+            0x03, 0x00, 0x00, 0x19, 0x02, 0xF0, 0x80, // TPKT + COTP DT
+            0x32, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x00, // S7 Job header
+            0xF0, 0x00, 0x00, 0x01, 0x00, 0x01, 0x01, 0xE0, // Setup Communication
+        ];
+        assert!(!is_mms(&s7));
+        assert!(parse(&s7).is_none());
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Extracts application-layer details from Modbus TCP payloads:
 //! - MBAP header (transaction ID, protocol ID, length, unit ID)
+//! - Pipelined requests: every MBAP frame packed into one TCP segment
+//! - Raw RTU frames tunnelled over TCP by serial gateways (CRC-validated)
 //! - Function codes and response/request classification
 //! - FC 43/14 Read Device Identification (vendor, product, revision)
 //! - Master/slave role detection
@@ -10,8 +12,11 @@
 //! Reference: Modbus Application Protocol Specification V1.1b3
 //! MBAP Header: [Transaction ID: 2][Protocol ID: 2][Length: 2][Unit ID: 1]
 //! PDU:         [Function Code: 1][Data: variable]
+//! RTU frame:   [Address: 1][Function Code: 1][Data: variable][CRC-16: 2, little-endian]
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::function_codes::{self, FunctionCodeProtocol};
 
 /// Minimum MBAP header size: 7 bytes (transaction_id=2 + protocol_id=2 + length=2 + unit_id=1)
 const MBAP_HEADER_SIZE: usize = 7;
@@ -19,6 +24,15 @@ const MBAP_HEADER_SIZE: usize = 7;
 /// Modbus TCP protocol identifier (always 0x0000 for Modbus)
 const MODBUS_PROTOCOL_ID: u16 = 0x0000;
 
+/// Smallest RTU frame: address + function code + exception code + CRC
+const RTU_MIN_FRAME_SIZE: usize = 5;
+
+/// Largest RTU frame allowed by the serial line spec
+const RTU_MAX_FRAME_SIZE: usize = 256;
+
+/// Highest unicast RTU slave address (248-255 are reserved)
+const RTU_MAX_ADDRESS: u8 = 247;
+
 /// Parsed Modbus TCP packet information.
 #[derive(Debug, Clone, Serialize)]
 pub struct ModbusInfo {
@@ -40,6 +54,19 @@ pub struct ModbusInfo {
     pub device_id: Option<ModbusDeviceId>,
     /// Diagnostic sub-function (for FC 8)
     pub diagnostic_subfunction: Option<u16>,
+    /// How the PDU was framed on the wire
+    pub framing: ModbusFraming,
+}
+
+/// Application data unit framing of a Modbus packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModbusFraming {
+    /// Standard Modbus TCP with an MBAP header
+    Tcp,
+    /// Serial RTU frame (address + PDU + CRC) forwarded over TCP without
+    /// MBAP, as done by many serial-to-Ethernet gateways
+    RtuOverTcp,
 }
 
 /// Master/slave role classification for a Modbus device.
@@ -66,7 +93,7 @@ pub struct RegisterRange {
 }
 
 /// Type of Modbus register being accessed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum RegisterType {
     Coil,
@@ -97,13 +124,88 @@ pub struct ModbusDeviceId {
 /// Attempt to parse a Modbus TCP payload.
 ///
 /// The payload should be the TCP application-layer data (after the TCP header).
-/// Returns None if the payload is too short or has an invalid Modbus protocol ID.
+/// Pipelining masters pack several MBAP frames into one segment, so every
+/// frame whose MBAP length fits in the payload is decoded, in order.
+/// Payloads without a valid MBAP header are accepted as RTU-over-TCP when
+/// they end in a valid Modbus CRC-16. Returns an empty Vec if the payload is
+/// too short or matches neither framing.
 ///
 /// # Arguments
 /// * `payload` - Raw TCP payload bytes
 /// * `src_port` - Source port (used for master/slave detection)
 /// * `dst_port` - Destination port (used for master/slave detection)
-pub fn parse_modbus(payload: &[u8], src_port: u16, dst_port: u16) -> Option<ModbusInfo> {
+pub fn parse_modbus(payload: &[u8], src_port: u16, dst_port: u16) -> Vec<ModbusInfo> {
+    // An MBAP length that fits in the segment is a strong signal. RTU
+    // requests often look like MBAP with protocol ID 0 (a start address
+    // below 256), so check the CRC before falling back to a loose MBAP match.
+    let mut pdus = Vec::new();
+    let mut rest = payload;
+    while let Some(frame_len) = mbap_frame_len(rest) {
+        let (frame, tail) = rest.split_at(frame_len);
+        pdus.extend(parse_mbap(frame, src_port, dst_port));
+        rest = tail;
+    }
+    if !pdus.is_empty() {
+        return pdus;
+    }
+
+    if is_rtu_frame(payload) {
+        let pdu = &payload[1..payload.len() - 2];
+        return vec![parse_pdu(
+            0,
+            payload[0],
+            pdu,
+            src_port,
+            dst_port,
+            ModbusFraming::RtuOverTcp,
+        )];
+    }
+    parse_mbap(payload, src_port, dst_port)
+        .into_iter()
+        .collect()
+}
+
+/// Length of the MBAP frame at the start of `payload`, if it has a Modbus
+/// protocol ID and its length field fits within the payload.
+fn mbap_frame_len(payload: &[u8]) -> Option<usize> {
+    if payload.len() <= MBAP_HEADER_SIZE
+        || u16::from_be_bytes([payload[2], payload[3]]) != MODBUS_PROTOCOL_ID
+    {
+        return None;
+    }
+    // Length counts the unit ID plus the PDU (at least a function code)
+    let length = u16::from_be_bytes([payload[4], payload[5]]) as usize;
+    (length >= 2 && 6 + length <= payload.len()).then_some(6 + length)
+}
+
+/// Whether a payload on an unrecognized port is Modbus TCP: MBAP frames
+/// with protocol ID 0 that exactly fill the segment, each carrying a
+/// public function code.
+pub fn is_modbus_tcp(payload: &[u8]) -> bool {
+    let mut rest = payload;
+    while let Some(frame_len) = mbap_frame_len(rest) {
+        if !is_public_function_code(rest[MBAP_HEADER_SIZE]) {
+            return false;
+        }
+        rest = &rest[frame_len..];
+        if rest.is_empty() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Function codes defined by the Modbus specification (or their exception
+/// responses).
+fn is_public_function_code(function_code: u8) -> bool {
+    matches!(
+        function_code & 0x7F,
+        1..=8 | 11 | 12 | 15..=17 | 20..=24 | 43
+    )
+}
+
+/// Parse a payload that starts with an MBAP header.
+fn parse_mbap(payload: &[u8], src_port: u16, dst_port: u16) -> Option<ModbusInfo> {
     // Need at least MBAP header (7 bytes) + function code (1 byte) = 8 bytes
     if payload.len() < MBAP_HEADER_SIZE + 1 {
         return None;
@@ -120,7 +222,56 @@ pub fn parse_modbus(payload: &[u8], src_port: u16, dst_port: u16) -> Option<Modb
         return None;
     }
 
-    let function_code = payload[7];
+    Some(parse_pdu(
+        transaction_id,
+        unit_id,
+        &payload[MBAP_HEADER_SIZE..],
+        src_port,
+        dst_port,
+        ModbusFraming::Tcp,
+    ))
+}
+
+/// Whether a payload is a single Modbus RTU frame with a valid CRC.
+pub(crate) fn is_rtu_frame(payload: &[u8]) -> bool {
+    if !(RTU_MIN_FRAME_SIZE..=RTU_MAX_FRAME_SIZE).contains(&payload.len()) {
+        return false;
+    }
+    let address = payload[0];
+    let function_code = payload[1] & 0x7F;
+    if address > RTU_MAX_ADDRESS || function_code == 0 {
+        return false;
+    }
+    let (frame, crc) = payload.split_at(payload.len() - 2);
+    crc16_modbus(frame) == u16::from_le_bytes([crc[0], crc[1]])
+}
+
+/// CRC-16/MODBUS (reflected polynomial 0xA001, initial value 0xFFFF).
+pub fn crc16_modbus(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in data {
+        crc ^= u16::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// Decode a Modbus PDU ([Function Code: 1][Data: variable]).
+fn parse_pdu(
+    transaction_id: u16,
+    unit_id: u8,
+    pdu: &[u8],
+    src_port: u16,
+    dst_port: u16,
+    framing: ModbusFraming,
+) -> ModbusInfo {
+    let function_code = pdu[0];
     let is_exception = function_code >= 0x80;
     let actual_fc = if is_exception {
         function_code & 0x7F
@@ -131,24 +282,30 @@ pub fn parse_modbus(payload: &[u8], src_port: u16, dst_port: u16) -> Option<Modb
     // Determine role based on port numbers:
     // - Requests go TO port 502 (dst_port=502 → this device is master)
     // - Responses come FROM port 502 (src_port=502 → this device is slave)
+    // - Off port 502 (e.g. 5020, serial gateways), the slave listens on the
+    //   lower port and the master connects from an ephemeral one
     let role = if dst_port == 502 {
         ModbusRole::Master
     } else if src_port == 502 {
         ModbusRole::Slave
+    } else if dst_port < src_port {
+        ModbusRole::Master
+    } else if src_port < dst_port {
+        ModbusRole::Slave
     } else {
         ModbusRole::Unknown
     };
 
+    // PDU data follows the function code
+    let pdu_data = &pdu[1..];
+
     // Parse exception code
-    let exception_code = if is_exception && payload.len() >= 9 {
-        Some(payload[8])
+    let exception_code = if is_exception {
+        pdu_data.first().copied()
     } else {
         None
     };
 
-    // PDU data starts at offset 8 (after MBAP header + FC)
-    let pdu_data = &payload[8..];
-
     // Extract register range for read/write function codes
     let register_range = parse_register_range(actual_fc, pdu_data, &role);
 
@@ -166,7 +323,7 @@ pub fn parse_modbus(payload: &[u8], src_port: u16, dst_port: u16) -> Option<Modb
         None
     };
 
-    Some(ModbusInfo {
+    ModbusInfo {
         transaction_id,
         unit_id,
         function_code: actual_fc,
@@ -176,7 +333,8 @@ pub fn parse_modbus(payload: &[u8], src_port: u16, dst_port: u16) -> Option<Modb
         register_range,
         device_id,
         diagnostic_subfunction,
-    })
+        framing,
+    }
 }
 
 /// Extract register range from Modbus request PDU data.
@@ -309,35 +467,29 @@ fn parse_device_id(pdu_data: &[u8], role: &ModbusRole) -> Option<ModbusDeviceId>
 }
 
 /// Human-readable name for a Modbus function code.
-pub fn function_code_name(fc: u8) -> &'static str {
-    match fc {
-        1 => "Read Coils",
-        2 => "Read Discrete Inputs",
-        3 => "Read Holding Registers",
-        4 => "Read Input Registers",
-        5 => "Write Single Coil",
-        6 => "Write Single Register",
-        7 => "Read Exception Status",
-        8 => "Diagnostics",
-        11 => "Get Comm Event Counter",
-        12 => "Get Comm Event Log",
-        15 => "Write Multiple Coils",
-        16 => "Write Multiple Registers",
-        17 => "Report Server ID",
-        20 => "Read File Record",
-        21 => "Write File Record",
-        22 => "Mask Write Register",
-        23 => "Read/Write Multiple Registers",
-        24 => "Read FIFO Queue",
-        43 => "Read Device Identification",
-        _ => "Unknown",
-    }
+///
+/// Reads the function code registry, so codes registered at runtime are
+/// named too.
+pub fn function_code_name(fc: u8) -> String {
+    function_codes::function_code_name(FunctionCodeProtocol::Modbus, fc)
+}
+
+/// Whether a request with this function code writes to the slave.
+pub fn is_write_function_code(fc: u8) -> bool {
+    function_codes::is_write_function_code(FunctionCodeProtocol::Modbus, fc)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Parse a payload expected to hold exactly one Modbus PDU.
+    fn single(payload: &[u8], src_port: u16, dst_port: u16) -> ModbusInfo {
+        let mut pdus = parse_modbus(payload, src_port, dst_port);
+        assert_eq!(pdus.len(), 1);
+        pdus.remove(0)
+    }
+
     #[test]
     fn test_parse_modbus_read_holding_registers_request() {
         // MBAP header + FC 3 (Read Holding Registers) request
@@ -353,7 +505,7 @@ mod tests {
             0x00, 0x0A, // Quantity: 10
         ];
 
-        let info = parse_modbus(&payload, 49152, 502).unwrap();
+        let info = single(&payload, 49152, 502);
         assert_eq!(info.transaction_id, 1);
         assert_eq!(info.unit_id, 1);
         assert_eq!(info.function_code, 3);
@@ -380,7 +532,7 @@ mod tests {
             0x00, 0x08, 0x00, 0x09, 0x00, 0x0A,
         ];
 
-        let info = parse_modbus(&payload, 502, 49152).unwrap();
+        let info = single(&payload, 502, 49152);
         assert_eq!(info.function_code, 3);
         assert_eq!(info.role, ModbusRole::Slave);
         assert!(!info.is_exception);
@@ -391,7 +543,7 @@ mod tests {
         // Exception response: FC 0x83 (FC 3 + 0x80), exception code 2 (illegal data address)
         let payload: Vec<u8> = vec![0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x01, 0x83, 0x02];
 
-        let info = parse_modbus(&payload, 502, 49152).unwrap();
+        let info = single(&payload, 502, 49152);
         assert_eq!(info.function_code, 3);
         assert!(info.is_exception);
         assert_eq!(info.exception_code, Some(2));
@@ -406,13 +558,13 @@ mod tests {
             0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A,
         ];
 
-        assert!(parse_modbus(&payload, 49152, 502).is_none());
+        assert!(parse_modbus(&payload, 49152, 502).is_empty());
     }
 
     #[test]
     fn test_parse_modbus_too_short() {
         let payload: Vec<u8> = vec![0x00, 0x01, 0x00, 0x00];
-        assert!(parse_modbus(&payload, 49152, 502).is_none());
+        assert!(parse_modbus(&payload, 49152, 502).is_empty());
     }
 
     #[test]
@@ -421,7 +573,7 @@ mod tests {
         let payload: Vec<u8> = vec![
             0x00, 0x01, // Transaction ID
             0x00, 0x00, // Protocol ID
-            0x00, 0x1C, // Length
+            0x00, 0x1E, // Length (30: unit ID + PDU)
             0x01, // Unit ID
             0x2B, // FC 43
             0x0E, // MEI Type: 14 (Read Device Identification)
@@ -443,7 +595,7 @@ mod tests {
             b'2', b'.', b'1',
         ];
 
-        let info = parse_modbus(&payload, 502, 49152).unwrap();
+        let info = single(&payload, 502, 49152);
         assert_eq!(info.function_code, 43);
         assert_eq!(info.role, ModbusRole::Slave);
 
@@ -460,12 +612,125 @@ mod tests {
             0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x08, 0x00, 0x00, 0xAB, 0xCD,
         ];
 
-        let info = parse_modbus(&payload, 49152, 502).unwrap();
+        let info = single(&payload, 49152, 502);
         assert_eq!(info.function_code, 8);
         assert_eq!(info.diagnostic_subfunction, Some(0x0000));
         assert_eq!(info.role, ModbusRole::Master);
     }
 
+    #[test]
+    fn test_crc16_modbus() {
+        // Standard check value for CRC-16/MODBUS
+        assert_eq!(crc16_modbus(b"123456789"), 0x4B37);
+    }
+
+    #[test]
+    fn test_nonstandard_port_probe_and_roles() {
+        let request = [
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A,
+        ];
+        let mut pipelined = request.to_vec();
+        pipelined.extend_from_slice(&request);
+        assert!(is_modbus_tcp(&request));
+        assert!(is_modbus_tcp(&pipelined));
+        // A trailing partial frame or an undefined function code is not Modbus
+        assert!(!is_modbus_tcp(&pipelined[..20]));
+        let mut undefined = request;
+        undefined[7] = 0x41;
+        assert!(!is_modbus_tcp(&undefined));
+
+        // On 5020 the server is the lower port
+        assert_eq!(
+            parse_modbus(&request, 49152, 5020)[0].role,
+            ModbusRole::Master
+        );
+        assert_eq!(
+            parse_modbus(&request, 5020, 49152)[0].role,
+            ModbusRole::Slave
+        );
+    }
+
+    #[test]
+    fn test_parse_rtu_over_tcp_request() {
+        // Unit 1, FC 3, start 0, quantity 10, CRC 0xCDC5 (sent low byte first).
+        // Bytes 2-3 are zero, so this also resembles an MBAP header.
+        let payload: Vec<u8> = vec![0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD];
+
+        let info = single(&payload, 49152, 502);
+        assert_eq!(info.framing, ModbusFraming::RtuOverTcp);
+        assert_eq!(info.unit_id, 1);
+        assert_eq!(info.function_code, 3);
+        assert_eq!(info.transaction_id, 0);
+        assert_eq!(info.role, ModbusRole::Master);
+
+        let range = info.register_range.unwrap();
+        assert_eq!(range.start, 0);
+        assert_eq!(range.count, 10);
+    }
+
+    #[test]
+    fn test_parse_rtu_over_tcp_exception() {
+        let mut payload: Vec<u8> = vec![0x11, 0x83, 0x02];
+        let crc = crc16_modbus(&payload);
+        payload.extend_from_slice(&crc.to_le_bytes());
+
+        let info = single(&payload, 502, 49152);
+        assert_eq!(info.framing, ModbusFraming::RtuOverTcp);
+        assert_eq!(info.unit_id, 0x11);
+        assert!(info.is_exception);
+        assert_eq!(info.exception_code, Some(2));
+        assert_eq!(info.role, ModbusRole::Slave);
+    }
+
+    #[test]
+    fn test_parse_rtu_bad_crc_rejected() {
+        // Same frame as above with a corrupted CRC and no MBAP protocol ID
+        let payload: Vec<u8> = vec![0x01, 0x03, 0x00, 0x01, 0x00, 0x0A, 0xC5, 0xCD];
+        assert!(parse_modbus(&payload, 49152, 502).is_empty());
+    }
+
+    #[test]
+    fn test_mbap_framing_preferred() {
+        let payload: Vec<u8> = vec![
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A,
+        ];
+        let info = single(&payload, 49152, 502);
+        assert_eq!(info.framing, ModbusFraming::Tcp);
+        assert_eq!(info.transaction_id, 1);
+    }
+
+    #[test]
+    fn test_parse_pipelined_requests() {
+        // Three requests in one segment: FC 3, FC 1, FC 6
+        let payload: Vec<u8> = vec![
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, // tid 1
+            0x00, 0x02, 0x00, 0x00, 0x00, 0x06, 0x01, 0x01, 0x00, 0x10, 0x00, 0x08, // tid 2
+            0x00, 0x03, 0x00, 0x00, 0x00, 0x06, 0x02, 0x06, 0x00, 0x05, 0x12, 0x34, // tid 3
+        ];
+
+        let pdus = parse_modbus(&payload, 49152, 502);
+        assert_eq!(pdus.len(), 3);
+        let tids: Vec<u16> = pdus.iter().map(|p| p.transaction_id).collect();
+        assert_eq!(tids, vec![1, 2, 3]);
+        let fcs: Vec<u8> = pdus.iter().map(|p| p.function_code).collect();
+        assert_eq!(fcs, vec![3, 1, 6]);
+        assert_eq!(pdus[2].unit_id, 2);
+        assert_eq!(pdus[1].register_range.as_ref().unwrap().start, 0x10);
+    }
+
+    #[test]
+    fn test_parse_pipelined_with_truncated_tail() {
+        // One complete request followed by the start of the next (split by TCP)
+        let payload: Vec<u8> = vec![
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, // tid 1
+            0x00, 0x02, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, // tid 2, cut short
+        ];
+
+        let pdus = parse_modbus(&payload, 49152, 502);
+        assert_eq!(pdus.len(), 1);
+        assert_eq!(pdus[0].transaction_id, 1);
+    }
+
     #[test]
     fn test_function_code_name() {
         assert_eq!(function_code_name(1), "Read Coils");
@@ -0,0 +1,434 @@
+//! MQTT parser.
+//!
+//! CONNECT is sent once per session by the client and carries the
+//! identifiers that tie an OT gateway to a cloud tenant (client ID and, for
+//! Azure IoT Hub, a username of the form
+//! `<hub>.azure-devices.net/<device>/?api-version=...`). PUBLISH and
+//! SUBSCRIBE carry the topic names that show which data an IIoT gateway
+//! moves and which it listens for. Other control packets are skipped.
+//!
+//! CONNECT layout (MQTT 3.1 / 3.1.1 / 5.0):
+//!   [0]      packet type (0x10 = CONNECT)
+//!   [1..]    remaining length (variable-length integer, 1-4 bytes)
+//!   protocol name (u16 len + "MQTT" or "MQIsdp"), protocol level (3/4/5),
+//!   connect flags, keep alive (u16),
+//!   [v5 only] properties (varint len + bytes),
+//!   payload: client id, [will properties, will topic, will message],
+//!            [username], [password]   — each u16 len + bytes
+//!
+//! PUBLISH: flags in the low nibble of byte 0 (DUP, QoS x2, RETAIN), then
+//! topic name (u16 len + bytes), packet id if QoS > 0, [v5] properties,
+//! application payload.
+//!
+//! SUBSCRIBE (flags 0x2): packet id (u16), [v5] properties, then one or
+//! more topic filters (u16 len + bytes) each followed by an options byte
+//! whose low two bits are the requested QoS.
+
+use serde::{Deserialize, Serialize};
+
+/// MQTT control packet type byte for CONNECT.
+const PACKET_CONNECT: u8 = 0x10;
+/// MQTT control packet type nibble for PUBLISH.
+const PACKET_PUBLISH: u8 = 0x30;
+/// MQTT control packet type byte for SUBSCRIBE (fixed flags 0x2).
+const PACKET_SUBSCRIBE: u8 = 0x82;
+
+/// Connect flag bits.
+const FLAG_USERNAME: u8 = 0x80;
+const FLAG_WILL: u8 = 0x04;
+
+/// Decoded MQTT CONNECT packet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MqttConnect {
+    /// Protocol level: 3 = 3.1, 4 = 3.1.1, 5 = 5.0
+    pub protocol_level: u8,
+    /// Client identifier (may be empty)
+    pub client_id: String,
+    /// Username, if the username flag is set
+    pub username: Option<String>,
+}
+
+impl MqttConnect {
+    /// Human-readable protocol version.
+    pub fn version_name(&self) -> &'static str {
+        match self.protocol_level {
+            3 => "3.1",
+            4 => "3.1.1",
+            5 => "5.0",
+            _ => "unknown",
+        }
+    }
+
+    /// Host name embedded in the username, if it looks like one.
+    ///
+    /// Azure IoT Hub requires `<hub>.azure-devices.net/<device-id>/...` as
+    /// the username, which identifies the hub even when the connection
+    /// itself is cleartext or the SNI was not captured.
+    pub fn username_host(&self) -> Option<&str> {
+        let user = self.username.as_deref()?;
+        let host = user.split(['/', '?']).next()?;
+        (host.contains('.') && !host.contains('@') && !host.contains(' ')).then_some(host)
+    }
+}
+
+/// A PUBLISH: one application message sent to a topic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MqttPublish {
+    pub topic: String,
+    /// Delivery QoS: 0, 1 or 2
+    pub qos: u8,
+    /// Broker keeps the message for future subscribers
+    pub retain: bool,
+    /// Application payload length in bytes (0 when the payload is cut off)
+    pub payload_len: usize,
+}
+
+/// A SUBSCRIBE: topic filters with the QoS requested for each.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MqttSubscribe {
+    pub packet_id: u16,
+    /// (topic filter, requested QoS), in request order
+    pub filters: Vec<(String, u8)>,
+}
+
+/// A decoded MQTT control packet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MqttPacket {
+    Connect(MqttConnect),
+    Publish(MqttPublish),
+    Subscribe(MqttSubscribe),
+}
+
+/// Parse every CONNECT, PUBLISH and SUBSCRIBE in a TCP segment.
+///
+/// A segment often carries several control packets back to back (a burst
+/// of PUBLISHes, or CONNECT followed by SUBSCRIBE). Other packet types are
+/// skipped. Parsing stops at the first packet whose header does not fit in
+/// the segment; a PUBLISH cut off after its topic is still returned.
+pub fn parse_packets(payload: &[u8]) -> Vec<MqttPacket> {
+    let mut packets = Vec::new();
+    let mut pos = 0;
+    while pos < payload.len() {
+        let first = payload[pos];
+        let Some((remaining, len_bytes)) = read_varint(&payload[pos + 1..]) else {
+            break;
+        };
+        let header_len = 1 + len_bytes;
+        let end = pos + header_len + remaining as usize;
+        let packet = &payload[pos..end.min(payload.len())];
+
+        let parsed = match first {
+            PACKET_CONNECT => parse_connect(packet).map(MqttPacket::Connect),
+            PACKET_SUBSCRIBE => payload
+                .get(pos + header_len..end)
+                .and_then(parse_subscribe)
+                .map(MqttPacket::Subscribe),
+            b if b & 0xF0 == PACKET_PUBLISH => {
+                parse_publish(first, &packet[header_len..], remaining as usize)
+                    .map(MqttPacket::Publish)
+            }
+            _ => {
+                pos = end;
+                continue;
+            }
+        };
+        // A malformed packet means the stream is not MQTT or not aligned
+        let Some(parsed) = parsed else {
+            break;
+        };
+        packets.push(parsed);
+        pos = end;
+    }
+    packets
+}
+
+/// Decode a PUBLISH variable header. `body` starts after the fixed header
+/// and may be truncated; `remaining` is the declared remaining length.
+fn parse_publish(first: u8, body: &[u8], remaining: usize) -> Option<MqttPublish> {
+    let qos = (first >> 1) & 0x03;
+    if qos == 3 {
+        return None;
+    }
+    let mut pos = 0;
+    let topic = std::str::from_utf8(read_string(body, &mut pos)?).ok()?;
+    // Topic names must not contain wildcards; this also screens out
+    // non-MQTT traffic that happens to run on 1883
+    if topic.is_empty() || topic.contains(['#', '+', '\0']) {
+        return None;
+    }
+    if qos > 0 {
+        pos += 2; // packet id
+    }
+    // v5 properties cannot be told apart from payload without the session's
+    // protocol level, so the length counts them as payload
+    let payload_len = if body.len() >= remaining {
+        remaining.saturating_sub(pos)
+    } else {
+        0
+    };
+    Some(MqttPublish {
+        topic: topic.to_string(),
+        qos,
+        retain: first & 0x01 != 0,
+        payload_len,
+    })
+}
+
+/// Decode a SUBSCRIBE body (after the fixed header).
+///
+/// The packet does not say which protocol version it uses, so it is tried
+/// as 3.1.1 first and then with a 5.0 property block; only a reading that
+/// consumes the body exactly is accepted.
+fn parse_subscribe(body: &[u8]) -> Option<MqttSubscribe> {
+    let packet_id = u16::from_be_bytes([*body.first()?, *body.get(1)?]);
+    let v311 = read_filters(body.get(2..)?, 0xFC);
+    let filters = v311.or_else(|| {
+        let (props_len, n) = read_varint(body.get(2..)?)?;
+        read_filters(body.get(2 + n + props_len as usize..)?, 0xC0)
+    })?;
+    Some(MqttSubscribe { packet_id, filters })
+}
+
+/// Read topic filter / options pairs filling `data` exactly. Options bits
+/// under `reserved` must be clear.
+fn read_filters(data: &[u8], reserved: u8) -> Option<Vec<(String, u8)>> {
+    let mut filters = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let filter = std::str::from_utf8(read_string(data, &mut pos)?).ok()?;
+        let options = *data.get(pos)?;
+        pos += 1;
+        if filter.is_empty() || options & reserved != 0 || options & 0x03 == 3 {
+            return None;
+        }
+        filters.push((filter.to_string(), options & 0x03));
+    }
+    (!filters.is_empty()).then_some(filters)
+}
+
+/// Parse an MQTT CONNECT packet. Returns None for any other packet type.
+pub fn parse_connect(payload: &[u8]) -> Option<MqttConnect> {
+    if payload.first()? & 0xF0 != PACKET_CONNECT {
+        return None;
+    }
+    let (_, len_bytes) = read_varint(payload.get(1..)?)?;
+    let mut pos = 1 + len_bytes;
+
+    let name = read_string(payload, &mut pos)?;
+    if name != b"MQTT" && name != b"MQIsdp" {
+        return None;
+    }
+    let protocol_level = *payload.get(pos)?;
+    let flags = *payload.get(pos + 1)?;
+    pos += 4; // level, flags, keep alive
+
+    if protocol_level == 5 {
+        let (props_len, n) = read_varint(payload.get(pos..)?)?;
+        pos += n + props_len as usize;
+    }
+
+    let client_id = String::from_utf8_lossy(read_string(payload, &mut pos)?).into_owned();
+
+    let mut username = None;
+    if flags & FLAG_USERNAME != 0 {
+        if flags & FLAG_WILL != 0 {
+            if protocol_level == 5 {
+                let (props_len, n) = read_varint(payload.get(pos..)?)?;
+                pos += n + props_len as usize;
+            }
+            read_string(payload, &mut pos)?; // will topic
+            read_string(payload, &mut pos)?; // will message
+        }
+        username = read_string(payload, &mut pos).map(|u| String::from_utf8_lossy(u).into_owned());
+    }
+
+    Some(MqttConnect {
+        protocol_level,
+        client_id,
+        username,
+    })
+}
+
+/// Decode an MQTT variable-length integer. Returns (value, bytes consumed).
+fn read_varint(data: &[u8]) -> Option<(u32, usize)> {
+    let mut value: u32 = 0;
+    for (i, &b) in data.iter().take(4).enumerate() {
+        value |= ((b & 0x7F) as u32) << (7 * i);
+        if b & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Read a u16-length-prefixed field and advance `pos` past it.
+fn read_string<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    let len = u16::from_be_bytes([*data.get(*pos)?, *data.get(*pos + 1)?]) as usize;
+    let out = data.get(*pos + 2..*pos + 2 + len)?;
+    *pos += 2 + len;
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(out: &mut Vec<u8>, s: &[u8]) {
+        out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+        out.extend_from_slice(s);
+    }
+
+    fn connect(level: u8, client_id: &str, username: Option<&str>, will: bool) -> Vec<u8> {
+        let mut body = Vec::new();
+        field(&mut body, b"MQTT");
+        body.push(level);
+        let mut flags = 0x02;
+        if username.is_some() {
+            flags |= FLAG_USERNAME;
+        }
+        if will {
+            flags |= FLAG_WILL;
+        }
+        body.push(flags);
+        body.extend_from_slice(&60u16.to_be_bytes());
+        if level == 5 {
+            body.extend_from_slice(&[0x05, 0x11, 0x00, 0x00, 0x0E, 0x10]); // session expiry
+        }
+        field(&mut body, client_id.as_bytes());
+        if will {
+            if level == 5 {
+                body.push(0);
+            }
+            field(&mut body, b"plant/status");
+            field(&mut body, b"offline");
+        }
+        if let Some(u) = username {
+            field(&mut body, u.as_bytes());
+        }
+        let mut pkt = vec![PACKET_CONNECT, body.len() as u8];
+        pkt.extend_from_slice(&body);
+        pkt
+    }
+
+    #[test]
+    fn test_connect_311_azure_username() {
+        let pkt = connect(
+            4,
+            "line3-gateway",
+            Some("contoso-plant.azure-devices.net/line3-gateway/?api-version=2021-04-12"),
+            false,
+        );
+        let c = parse_connect(&pkt).unwrap();
+        assert_eq!(c.version_name(), "3.1.1");
+        assert_eq!(c.client_id, "line3-gateway");
+        assert_eq!(c.username_host(), Some("contoso-plant.azure-devices.net"));
+    }
+
+    #[test]
+    fn test_connect_v5_with_will() {
+        let pkt = connect(5, "edge-01", Some("operator"), true);
+        let c = parse_connect(&pkt).unwrap();
+        assert_eq!(c.protocol_level, 5);
+        assert_eq!(c.client_id, "edge-01");
+        assert_eq!(c.username.as_deref(), Some("operator"));
+        assert_eq!(c.username_host(), None);
+    }
+
+    #[test]
+    fn test_non_connect_rejected() {
+        // PUBLISH
+        assert_eq!(parse_connect(&[0x30, 0x02, 0x00, 0x00]), None);
+        let mut pkt = connect(4, "x", None, false);
+        pkt[4] = b'X'; // corrupt protocol name
+        assert_eq!(parse_connect(&pkt), None);
+        assert_eq!(parse_connect(&[]), None);
+    }
+
+    fn publish(topic: &str, qos: u8, retain: bool, data: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        field(&mut body, topic.as_bytes());
+        if qos > 0 {
+            body.extend_from_slice(&7u16.to_be_bytes());
+        }
+        body.extend_from_slice(data);
+        let mut pkt = vec![PACKET_PUBLISH | (qos << 1) | retain as u8, body.len() as u8];
+        pkt.extend_from_slice(&body);
+        pkt
+    }
+
+    #[test]
+    fn test_segment_with_connect_subscribe_and_publishes() {
+        let mut seg = connect(4, "edge-01", None, false);
+        // SUBSCRIBE id 1: plant/+/cmd QoS 1, plant/alarms/# QoS 0
+        let mut sub = vec![0x00, 0x01];
+        field(&mut sub, b"plant/+/cmd");
+        sub.push(0x01);
+        field(&mut sub, b"plant/alarms/#");
+        sub.push(0x00);
+        seg.extend_from_slice(&[PACKET_SUBSCRIBE, sub.len() as u8]);
+        seg.extend_from_slice(&sub);
+        seg.extend_from_slice(&[0xC0, 0x00]); // PINGREQ, skipped
+        seg.extend(publish("plant/line3/temp", 1, false, b"21.5"));
+        seg.extend(publish("plant/line3/state", 0, true, b"running"));
+
+        let packets = parse_packets(&seg);
+        assert_eq!(packets.len(), 4);
+        assert!(matches!(&packets[0], MqttPacket::Connect(c) if c.client_id == "edge-01"));
+        let MqttPacket::Subscribe(s) = &packets[1] else {
+            panic!("expected SUBSCRIBE");
+        };
+        assert_eq!(s.packet_id, 1);
+        assert_eq!(
+            s.filters,
+            vec![
+                ("plant/+/cmd".to_string(), 1),
+                ("plant/alarms/#".to_string(), 0)
+            ]
+        );
+        assert_eq!(
+            packets[2],
+            MqttPacket::Publish(MqttPublish {
+                topic: "plant/line3/temp".to_string(),
+                qos: 1,
+                retain: false,
+                payload_len: 4,
+            })
+        );
+        assert!(matches!(&packets[3], MqttPacket::Publish(p) if p.retain && p.qos == 0));
+    }
+
+    #[test]
+    fn test_v5_subscribe_and_truncated_publish() {
+        // SUBSCRIBE with a subscription-identifier property and v5 options
+        let mut sub = vec![0x00, 0x09, 0x02, 0x0B, 0x05];
+        field(&mut sub, b"sensors/#");
+        sub.push(0x2E); // retain handling 2, RAP, NL, QoS 2
+        let mut pkt = vec![PACKET_SUBSCRIBE, sub.len() as u8];
+        pkt.extend_from_slice(&sub);
+        let packets = parse_packets(&pkt);
+        assert_eq!(
+            packets,
+            vec![MqttPacket::Subscribe(MqttSubscribe {
+                packet_id: 9,
+                filters: vec![("sensors/#".to_string(), 2)],
+            })]
+        );
+
+        // Payload cut off by the snap length: topic still recovered
+        let full = publish("historian/batch", 0, false, &[0u8; 100]);
+        let packets = parse_packets(&full[..30]);
+        assert!(matches!(
+            &packets[..],
+            [MqttPacket::Publish(p)] if p.topic == "historian/batch" && p.payload_len == 0
+        ));
+    }
+
+    #[test]
+    fn test_non_mqtt_segments_yield_nothing() {
+        assert!(parse_packets(&[]).is_empty());
+        assert!(parse_packets(b"GET / HTTP/1.1\r\n").is_empty());
+        // PUBLISH to a wildcard topic and QoS 3 are both invalid
+        assert!(parse_packets(&publish("plant/#", 0, false, b"x")).is_empty());
+        assert!(parse_packets(&[0x36, 0x03, 0x00, 0x01, b'a']).is_empty());
+    }
+}
@@ -0,0 +1,340 @@
+//! NetBIOS Name Service (NBNS) parser.
+//!
+//! Windows machines register their NetBIOS names by broadcast at boot and
+//! refresh them periodically, and answer name and node-status queries for
+//! them. In an OT capture that is usually the only place an HMI or
+//! engineering workstation states its computer name.
+//!
+//! ## Message Format (UDP 137, RFC 1002)
+//! ```text
+//! [0..2]   transaction ID
+//! [2..4]   flags    R (0x8000), opcode (bits 11-14), NM flags, RCODE (low 4 bits)
+//! [4..6]   QDCOUNT
+//! [6..8]   ANCOUNT
+//! [8..10]  NSCOUNT
+//! [10..12] ARCOUNT
+//! [12..]   question: name, type (2), class (2)
+//!          records:  name, type (2), class (2), TTL (4), RDLENGTH (2), RDATA
+//! ```
+//! Names follow DNS label rules, but the first label is the 16-byte NetBIOS
+//! name (15 characters padded with spaces, then a suffix byte) in
+//! "first-level encoding": each nibble becomes a letter `'A' + nibble`.
+//!
+//! NB records carry 6-byte entries: flags (group bit 0x8000) and an IPv4
+//! address. A node-status (NBSTAT) response lists every name registered on
+//! the responding host: a count, then 18 bytes per name (16-byte name and
+//! 2-byte flags).
+
+use std::net::Ipv4Addr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dns::read_name;
+
+/// UDP port of the NetBIOS name service.
+pub const NBNS_PORT: u16 = 137;
+
+const HEADER_LEN: usize = 12;
+const TYPE_NB: u16 = 0x0020;
+const TYPE_NBSTAT: u16 = 0x0021;
+const NB_GROUP: u16 = 0x8000;
+/// Encoded length of a NetBIOS name label
+const ENCODED_LEN: usize = 32;
+/// Workstation service suffix: the computer name
+pub const SUFFIX_WORKSTATION: u8 = 0x00;
+/// File server service suffix
+pub const SUFFIX_SERVER: u8 = 0x20;
+
+/// NBNS operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NbnsOpcode {
+    Query,
+    Registration,
+    Release,
+    Wack,
+    Refresh,
+    Other(u8),
+}
+
+impl NbnsOpcode {
+    fn from_code(code: u8) -> Self {
+        match code {
+            0 => NbnsOpcode::Query,
+            5 => NbnsOpcode::Registration,
+            6 => NbnsOpcode::Release,
+            7 => NbnsOpcode::Wack,
+            // RFC 1002 gives 8; Windows sends 9
+            8 | 9 => NbnsOpcode::Refresh,
+            other => NbnsOpcode::Other(other),
+        }
+    }
+}
+
+/// A NetBIOS name carried by a message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetbiosName {
+    /// Name with the padding removed ("HMI-LINE2")
+    pub name: String,
+    /// Service suffix (16th byte)
+    pub suffix: u8,
+    /// Group (domain / workgroup) name rather than a unique one
+    pub group: bool,
+    /// Address the name is bound to; `None` for node-status entries, which
+    /// belong to the responding host
+    pub address: Option<String>,
+}
+
+/// Parsed NBNS message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NbnsInfo {
+    pub transaction_id: u16,
+    pub opcode: NbnsOpcode,
+    pub is_response: bool,
+    pub rcode: u8,
+    /// Name asked about or being registered
+    pub question: Option<String>,
+    /// Names bound by NB records or listed in a node-status response
+    pub names: Vec<NetbiosName>,
+}
+
+impl NbnsInfo {
+    /// The computer name `ip` claims for itself: a unique workstation or
+    /// server name bound to `ip` (or listed in its node-status response).
+    ///
+    /// Releases and negative responses claim nothing.
+    pub fn host_name(&self, ip: &str) -> Option<&str> {
+        if self.opcode == NbnsOpcode::Release || self.rcode != 0 {
+            return None;
+        }
+        let owned = |n: &&NetbiosName| {
+            !n.group && !matches!(n.address.as_deref(), Some(addr) if addr != ip)
+        };
+        let by_suffix = |suffix: u8| {
+            self.names
+                .iter()
+                .filter(owned)
+                .find(|n| n.suffix == suffix && !n.name.is_empty())
+        };
+        by_suffix(SUFFIX_WORKSTATION)
+            .or_else(|| by_suffix(SUFFIX_SERVER))
+            .map(|n| n.name.as_str())
+    }
+}
+
+/// Parse an NBNS message from a UDP payload.
+///
+/// Returns `None` for malformed headers or an undecodable question. Records
+/// are read until the first malformed one.
+pub fn parse(payload: &[u8]) -> Option<NbnsInfo> {
+    if payload.len() < HEADER_LEN {
+        return None;
+    }
+    let flags = u16::from_be_bytes([payload[2], payload[3]]);
+    let qdcount = u16::from_be_bytes([payload[4], payload[5]]);
+    let rrcount = [6, 8, 10]
+        .iter()
+        .map(|&i| u16::from_be_bytes([payload[i], payload[i + 1]]) as usize)
+        .sum::<usize>();
+
+    let mut info = NbnsInfo {
+        transaction_id: u16::from_be_bytes([payload[0], payload[1]]),
+        opcode: NbnsOpcode::from_code(((flags >> 11) & 0x0F) as u8),
+        is_response: flags & 0x8000 != 0,
+        rcode: (flags & 0x000F) as u8,
+        question: None,
+        names: Vec::new(),
+    };
+
+    let mut pos = HEADER_LEN;
+    for _ in 0..qdcount {
+        let (name, next) = read_name(payload, pos)?;
+        if next + 4 > payload.len() {
+            return None;
+        }
+        if info.question.is_none() {
+            info.question = decode_name(&name).map(|(n, _)| n);
+        }
+        pos = next + 4;
+    }
+
+    for _ in 0..rrcount {
+        let Some((name, next)) = read_name(payload, pos) else {
+            break;
+        };
+        if next + 10 > payload.len() {
+            break;
+        }
+        let rtype = u16::from_be_bytes([payload[next], payload[next + 1]]);
+        let rdlength = u16::from_be_bytes([payload[next + 8], payload[next + 9]]) as usize;
+        let rdata_start = next + 10;
+        let rdata_end = rdata_start + rdlength;
+        if rdata_end > payload.len() {
+            break;
+        }
+        pos = rdata_end;
+        let rdata = &payload[rdata_start..rdata_end];
+
+        match rtype {
+            TYPE_NB => {
+                let Some((owner, suffix)) = decode_name(&name) else {
+                    continue;
+                };
+                for entry in rdata.chunks_exact(6) {
+                    let nb_flags = u16::from_be_bytes([entry[0], entry[1]]);
+                    info.names.push(NetbiosName {
+                        name: owner.clone(),
+                        suffix,
+                        group: nb_flags & NB_GROUP != 0,
+                        address: Some(
+                            Ipv4Addr::new(entry[2], entry[3], entry[4], entry[5]).to_string(),
+                        ),
+                    });
+                }
+            }
+            TYPE_NBSTAT => {
+                let Some((&count, entries)) = rdata.split_first() else {
+                    continue;
+                };
+                for entry in entries.chunks_exact(18).take(count as usize) {
+                    let nb_flags = u16::from_be_bytes([entry[16], entry[17]]);
+                    info.names.push(NetbiosName {
+                        name: trim_name(&entry[..15]),
+                        suffix: entry[15],
+                        group: nb_flags & NB_GROUP != 0,
+                        address: None,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+/// Decode the first-level encoded first label of `name` into the NetBIOS
+/// name and its suffix. Any scope ID after the first label is ignored.
+fn decode_name(name: &str) -> Option<(String, u8)> {
+    let label = name.split('.').next()?.as_bytes();
+    if label.len() != ENCODED_LEN {
+        return None;
+    }
+    let mut raw = [0u8; 16];
+    for (i, pair) in label.chunks_exact(2).enumerate() {
+        let nibble = |c: u8| {
+            let n = c.to_ascii_uppercase().wrapping_sub(b'A');
+            (n < 16).then_some(n)
+        };
+        raw[i] = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Some((trim_name(&raw[..15]), raw[15]))
+}
+
+/// NetBIOS name bytes without space/NUL padding; non-printable bytes are
+/// dropped.
+fn trim_name(raw: &[u8]) -> String {
+    let text: String = raw
+        .iter()
+        .filter(|b| b.is_ascii_graphic() || **b == b' ')
+        .map(|&b| b as char)
+        .collect();
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(name: &str, suffix: u8) -> Vec<u8> {
+        let mut raw = [b' '; 16];
+        raw[..name.len()].copy_from_slice(name.as_bytes());
+        raw[15] = suffix;
+        let mut out = vec![ENCODED_LEN as u8];
+        for b in raw {
+            out.push(b'A' + (b >> 4));
+            out.push(b'A' + (b & 0x0F));
+        }
+        out.push(0);
+        out
+    }
+
+    fn record(name: &[u8], rtype: u16, rdata: &[u8]) -> Vec<u8> {
+        let mut out = name.to_vec();
+        out.extend_from_slice(&rtype.to_be_bytes());
+        out.extend_from_slice(&1u16.to_be_bytes());
+        out.extend_from_slice(&300_000u32.to_be_bytes());
+        out.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        out.extend_from_slice(rdata);
+        out
+    }
+
+    #[test]
+    fn test_parse_registration() {
+        // Broadcast registration: question + additional NB record via pointer
+        let mut msg = vec![0x80, 0x01, 0x29, 0x10, 0, 1, 0, 0, 0, 0, 0, 1];
+        msg.extend_from_slice(&encode("ENG-WS01", SUFFIX_WORKSTATION));
+        msg.extend_from_slice(&[0x00, 0x20, 0x00, 0x01]);
+        msg.extend_from_slice(&record(
+            &[0xC0, 0x0C],
+            TYPE_NB,
+            &[0x00, 0x00, 192, 168, 10, 21],
+        ));
+
+        let info = parse(&msg).expect("should parse NBNS registration");
+        assert_eq!(info.opcode, NbnsOpcode::Registration);
+        assert!(!info.is_response);
+        assert_eq!(info.question.as_deref(), Some("ENG-WS01"));
+        assert_eq!(info.names.len(), 1);
+        assert_eq!(info.names[0].address.as_deref(), Some("192.168.10.21"));
+        assert_eq!(info.host_name("192.168.10.21"), Some("ENG-WS01"));
+        // Registered for another address: not this host's name
+        assert_eq!(info.host_name("192.168.10.99"), None);
+
+        // The same name being released claims nothing
+        msg[2] = 0x30;
+        let release = parse(&msg).unwrap();
+        assert_eq!(release.opcode, NbnsOpcode::Release);
+        assert_eq!(release.host_name("192.168.10.21"), None);
+    }
+
+    #[test]
+    fn test_parse_node_status_response() {
+        let mut rdata = vec![3u8];
+        for (name, suffix, flags) in [
+            ("PLANT", 0x00u8, 0x8400u16),
+            ("HMI-LINE2", 0x20, 0x0400),
+            ("HMI-LINE2", 0x00, 0x0400),
+        ] {
+            let mut raw = [b' '; 16];
+            raw[..name.len()].copy_from_slice(name.as_bytes());
+            raw[15] = suffix;
+            rdata.extend_from_slice(&raw);
+            rdata.extend_from_slice(&flags.to_be_bytes());
+        }
+        rdata.extend_from_slice(&[0u8; 46]); // statistics
+
+        let mut msg = vec![0x00, 0x07, 0x84, 0x00, 0, 0, 0, 1, 0, 0, 0, 0];
+        msg.extend_from_slice(&record(&encode("*", 0x00), TYPE_NBSTAT, &rdata));
+
+        let info = parse(&msg).expect("should parse node status");
+        assert!(info.is_response);
+        assert_eq!(info.names.len(), 3);
+        assert!(info.names[0].group);
+        // Group name skipped, workstation name preferred over server
+        assert_eq!(info.host_name("10.0.0.5"), Some("HMI-LINE2"));
+    }
+
+    #[test]
+    fn test_decode_name_rejects_bad_encoding() {
+        assert!(decode_name("abc").is_none());
+        assert!(decode_name(&"Z".repeat(ENCODED_LEN)).is_none());
+        // Lowercased by the name reader, scope ID ignored
+        let encoded = String::from_utf8(encode("PLC", 0x20)[1..33].to_vec()).unwrap();
+        let (name, suffix) =
+            decode_name(&format!("{}.plant", encoded.to_ascii_lowercase())).unwrap();
+        assert_eq!(name, "PLC");
+        assert_eq!(suffix, 0x20);
+        assert!(parse(&[0u8; 4]).is_none());
+    }
+}
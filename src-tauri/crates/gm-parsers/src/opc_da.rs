@@ -0,0 +1,408 @@
+//! Classic OPC (OPC DA / OPC A&E over DCOM) detection.
+//!
+//! Classic OPC has no port of its own: a client asks the server's DCOM
+//! activator or endpoint mapper on 135 for an object, then binds to the
+//! OPC interface on a dynamic port the server hands out. Both steps carry
+//! the OPC interface IIDs in client PDUs: the activation / endpoint-map
+//! request lists the requested interfaces, and the Bind or Alter Context
+//! names the interface as its abstract syntax. Later requests on the bound
+//! connection only carry a context ID, so the caller has to remember which
+//! connections were bound to an OPC interface.
+//!
+//! DCE/RPC connection-oriented header (16 bytes):
+//!   [0]      rpc_vers (5)
+//!   [1]      rpc_vers_minor (0 or 1)
+//!   [2]      PTYPE (0 = request, 2 = response, 11 = bind, 12 = bind_ack,
+//!            14 = alter_context, 15 = alter_context_resp, ...)
+//!   [3]      pfc_flags
+//!   [4..8]   packed data representation (byte 4 high nibble 1 = little endian)
+//!   [8..10]  frag_length
+//!   [10..12] auth_length
+//!   [12..16] call_id
+//! Request PDUs continue with alloc_hint (u32), context ID (u16), opnum (u16).
+
+use serde::{Deserialize, Serialize};
+
+/// DCE/RPC connection-oriented PDU types this module distinguishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DceRpcPacketType {
+    Request,
+    Response,
+    Fault,
+    Bind,
+    BindAck,
+    AlterContext,
+    AlterContextResp,
+    Other(u8),
+}
+
+impl DceRpcPacketType {
+    fn from_u8(ptype: u8) -> Self {
+        match ptype {
+            0 => Self::Request,
+            2 => Self::Response,
+            3 => Self::Fault,
+            11 => Self::Bind,
+            12 => Self::BindAck,
+            14 => Self::AlterContext,
+            15 => Self::AlterContextResp,
+            other => Self::Other(other),
+        }
+    }
+
+    /// PDU sent by the calling (client) side.
+    pub fn is_client(self) -> bool {
+        matches!(self, Self::Request | Self::Bind | Self::AlterContext)
+    }
+}
+
+/// OPC COM interfaces recognized by IID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpcInterface {
+    Server,
+    ServerPublicGroups,
+    BrowseServerAddressSpace,
+    GroupStateMgt,
+    SyncIo,
+    AsyncIo,
+    ItemMgt,
+    DataCallback,
+    AsyncIo2,
+    ItemProperties,
+    Common,
+    Shutdown,
+    ServerList,
+    ServerList2,
+    EventServer,
+}
+
+/// IIDs as `(data1, data2, data3, data4)` from the OPC Foundation IDL
+/// (opcda.idl, opccomn.idl, opc_ae.idl).
+const OPC_IIDS: &[(OpcInterface, u32, u16, u16, [u8; 8])] = &[
+    (
+        OpcInterface::Server,
+        0x39C13A4D,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::ServerPublicGroups,
+        0x39C13A4E,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::BrowseServerAddressSpace,
+        0x39C13A4F,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::GroupStateMgt,
+        0x39C13A50,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::SyncIo,
+        0x39C13A52,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::AsyncIo,
+        0x39C13A53,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::ItemMgt,
+        0x39C13A54,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::DataCallback,
+        0x39C13A70,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::AsyncIo2,
+        0x39C13A71,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::ItemProperties,
+        0x39C13A72,
+        0x011E,
+        0x11D0,
+        DA_IID_TAIL,
+    ),
+    (
+        OpcInterface::Common,
+        0xF31DFDE2,
+        0x07B6,
+        0x11D2,
+        COMN_IID_TAIL,
+    ),
+    (
+        OpcInterface::Shutdown,
+        0xF31DFDE1,
+        0x07B6,
+        0x11D2,
+        COMN_IID_TAIL,
+    ),
+    (
+        OpcInterface::ServerList,
+        0x13486D50,
+        0x4821,
+        0x11D2,
+        [0xA4, 0x94, 0x3C, 0xB3, 0x06, 0xC1, 0x00, 0x00],
+    ),
+    (
+        OpcInterface::ServerList2,
+        0x9DD0B56C,
+        0xAD9E,
+        0x43EE,
+        [0x83, 0x05, 0x48, 0x7F, 0x31, 0x88, 0xBF, 0x7A],
+    ),
+    (
+        OpcInterface::EventServer,
+        0x65168851,
+        0x5783,
+        0x11D1,
+        [0x84, 0xA0, 0x00, 0x60, 0x8C, 0xB8, 0xA7, 0xE9],
+    ),
+];
+const DA_IID_TAIL: [u8; 8] = [0x96, 0x75, 0x00, 0x20, 0xAF, 0xD8, 0xAD, 0xB3];
+const COMN_IID_TAIL: [u8; 8] = [0xB2, 0xD8, 0x00, 0x60, 0x08, 0x3B, 0xA1, 0xFB];
+
+impl OpcInterface {
+    /// COM interface name, e.g. "IOPCServer".
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Server => "IOPCServer",
+            Self::ServerPublicGroups => "IOPCServerPublicGroups",
+            Self::BrowseServerAddressSpace => "IOPCBrowseServerAddressSpace",
+            Self::GroupStateMgt => "IOPCGroupStateMgt",
+            Self::SyncIo => "IOPCSyncIO",
+            Self::AsyncIo => "IOPCAsyncIO",
+            Self::ItemMgt => "IOPCItemMgt",
+            Self::DataCallback => "IOPCDataCallback",
+            Self::AsyncIo2 => "IOPCAsyncIO2",
+            Self::ItemProperties => "IOPCItemProperties",
+            Self::Common => "IOPCCommon",
+            Self::Shutdown => "IOPCShutdown",
+            Self::ServerList => "IOPCServerList",
+            Self::ServerList2 => "IOPCServerList2",
+            Self::EventServer => "IOPCEventServer",
+        }
+    }
+
+    /// Interfaces the client implements and the server calls back on.
+    pub fn is_callback(self) -> bool {
+        matches!(self, Self::DataCallback | Self::Shutdown)
+    }
+}
+
+/// A DCE/RPC PDU, with any OPC interfaces it names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpcDaInfo {
+    pub packet_type: DceRpcPacketType,
+    pub call_id: u32,
+    /// Operation number of a Request
+    pub opnum: Option<u16>,
+    /// OPC interfaces named in a client PDU, in IID table order; empty for
+    /// server PDUs and for requests on an already-bound connection
+    pub interfaces: Vec<OpcInterface>,
+}
+
+/// Parse a connection-oriented DCE/RPC PDU.
+///
+/// Returns any well-formed PDU; `interfaces` says whether it is OPC.
+pub fn parse(payload: &[u8]) -> Option<OpcDaInfo> {
+    if payload.len() < 16 || payload[0] != 5 || payload[1] > 1 {
+        return None;
+    }
+    let little_endian = match payload[4] & 0xF0 {
+        0x10 => true,
+        0x00 => false,
+        _ => return None,
+    };
+    let u16_at = |at: usize| {
+        let b = [payload[at], payload[at + 1]];
+        if little_endian {
+            u16::from_le_bytes(b)
+        } else {
+            u16::from_be_bytes(b)
+        }
+    };
+    let frag_len = u16_at(8) as usize;
+    if frag_len < 16 {
+        return None;
+    }
+    let call_id = {
+        let b = [payload[12], payload[13], payload[14], payload[15]];
+        if little_endian {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        }
+    };
+    let packet_type = DceRpcPacketType::from_u8(payload[2]);
+    let opnum =
+        (packet_type == DceRpcPacketType::Request && payload.len() >= 24).then(|| u16_at(22));
+
+    let interfaces = if packet_type.is_client() {
+        let body = &payload[16..frag_len.min(payload.len())];
+        OPC_IIDS
+            .iter()
+            .filter(|(_, d1, d2, d3, d4)| {
+                let iid = encode_uuid(*d1, *d2, *d3, d4, little_endian);
+                body.windows(16).any(|w| w == iid)
+            })
+            .map(|(iface, ..)| *iface)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Some(OpcDaInfo {
+        packet_type,
+        call_id,
+        opnum,
+        interfaces,
+    })
+}
+
+/// True if the payload is a client DCE/RPC PDU naming an OPC interface.
+pub fn is_opc_da(payload: &[u8]) -> bool {
+    parse(payload).is_some_and(|info| !info.interfaces.is_empty())
+}
+
+/// NDR wire form of a UUID in the PDU's byte order.
+fn encode_uuid(d1: u32, d2: u16, d3: u16, d4: &[u8; 8], little_endian: bool) -> [u8; 16] {
+    let mut out = [0u8; 16];
+    if little_endian {
+        out[0..4].copy_from_slice(&d1.to_le_bytes());
+        out[4..6].copy_from_slice(&d2.to_le_bytes());
+        out[6..8].copy_from_slice(&d3.to_le_bytes());
+    } else {
+        out[0..4].copy_from_slice(&d1.to_be_bytes());
+        out[4..6].copy_from_slice(&d2.to_be_bytes());
+        out[6..8].copy_from_slice(&d3.to_be_bytes());
+    }
+    out[8..].copy_from_slice(d4);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// NDR transfer syntax 8a885d04-1ceb-11c9-9fe8-08002b104860 v2
+    const NDR: [u8; 20] = [
+        0x04, 0x5D, 0x88, 0x8A, 0xEB, 0x1C, 0xC9, 0x11, 0x9F, 0xE8, 0x08, 0x00, 0x2B, 0x10, 0x48,
+        0x60, 0x02, 0x00, 0x00, 0x00,
+    ];
+
+    fn header(ptype: u8, body_len: usize, call_id: u32) -> Vec<u8> {
+        let mut pdu = vec![5, 0, ptype, 0x03, 0x10, 0, 0, 0];
+        pdu.extend_from_slice(&((16 + body_len) as u16).to_le_bytes());
+        pdu.extend_from_slice(&0u16.to_le_bytes());
+        pdu.extend_from_slice(&call_id.to_le_bytes());
+        pdu
+    }
+
+    fn bind(iid: [u8; 16]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&5840u16.to_le_bytes());
+        body.extend_from_slice(&5840u16.to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&[1, 0, 0, 0]); // one context
+        body.extend_from_slice(&[0, 0, 1, 0]); // context 0, one transfer syntax
+        body.extend_from_slice(&iid);
+        body.extend_from_slice(&0u32.to_le_bytes()); // interface version 0.0
+        body.extend_from_slice(&NDR);
+        let mut pdu = header(11, body.len(), 2);
+        pdu.extend_from_slice(&body);
+        pdu
+    }
+
+    fn iid(iface: OpcInterface) -> [u8; 16] {
+        let (_, d1, d2, d3, d4) = OPC_IIDS.iter().find(|e| e.0 == iface).unwrap();
+        encode_uuid(*d1, *d2, *d3, d4, true)
+    }
+
+    #[test]
+    fn test_bind_to_iopcserver() {
+        let pdu = bind(iid(OpcInterface::Server));
+        // 4d3ac139-1e01-d011-9675-0020afd8adb3 on the wire
+        assert_eq!(&pdu[32..36], &[0x4D, 0x3A, 0xC1, 0x39]);
+        let info = parse(&pdu).unwrap();
+        assert_eq!(info.packet_type, DceRpcPacketType::Bind);
+        assert_eq!(info.call_id, 2);
+        assert_eq!(info.interfaces, vec![OpcInterface::Server]);
+        assert!(is_opc_da(&pdu));
+    }
+
+    #[test]
+    fn test_request_naming_interfaces_and_plain_request() {
+        // RemoteCreateInstance-style request listing two requested IIDs
+        let mut body = vec![0u8; 8];
+        body[6] = 4; // opnum 4
+        body.extend_from_slice(&[0xAA; 40]);
+        body.extend_from_slice(&iid(OpcInterface::ServerList2));
+        body.extend_from_slice(&iid(OpcInterface::Common));
+        let mut pdu = header(0, body.len(), 7);
+        pdu.extend_from_slice(&body);
+        let info = parse(&pdu).unwrap();
+        assert_eq!(info.opnum, Some(4));
+        assert_eq!(
+            info.interfaces,
+            vec![OpcInterface::Common, OpcInterface::ServerList2]
+        );
+
+        // A request on a bound connection only carries a context ID
+        let mut plain = header(0, 8, 8);
+        plain.extend_from_slice(&[0, 0, 0, 0, 1, 0, 5, 0]);
+        let info = parse(&plain).unwrap();
+        assert_eq!(info.opnum, Some(5));
+        assert!(info.interfaces.is_empty());
+        assert!(!is_opc_da(&plain));
+    }
+
+    #[test]
+    fn test_server_pdus_and_non_dcerpc() {
+        // IIDs echoed by the server do not make it a client PDU
+        let mut ack = header(12, 16, 2);
+        ack.extend_from_slice(&iid(OpcInterface::Server));
+        let info = parse(&ack).unwrap();
+        assert_eq!(info.packet_type, DceRpcPacketType::BindAck);
+        assert!(info.interfaces.is_empty());
+
+        // Non-OPC bind (EPM) parses but is not OPC
+        let epm = bind([
+            0x08, 0x83, 0xAF, 0xE1, 0x1F, 0x5D, 0xC9, 0x11, 0x91, 0xA4, 0x08, 0x00, 0x2B, 0x14,
+            0xA0, 0xFA,
+        ]);
+        assert!(parse(&epm).is_some());
+        assert!(!is_opc_da(&epm));
+
+        assert!(parse(b"GET / HTTP/1.1\r\nHost: x\r\n").is_none());
+        assert!(parse(&[5, 0, 11]).is_none());
+    }
+}
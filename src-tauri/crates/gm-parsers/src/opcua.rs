@@ -0,0 +1,98 @@
+//! OPC UA Connection Protocol (UA-TCP) message inspection.
+//!
+//! Only the ReverseHello message is decoded. With reverse connect
+//! (OPC UA Part 6 §7.1.2.6) the *server* opens the TCP connection to a
+//! client outside its network and announces itself, which is how OPC UA
+//! servers on the plant floor are typically exposed to cloud collectors
+//! without inbound firewall rules.
+//!
+//! Layout (little-endian):
+//!   [0..3]  message type "RHE"
+//!   [3]     chunk type 'F'
+//!   [4..8]  message size (u32)
+//!   ServerUri   (i32 length + UTF-8 bytes)
+//!   EndpointUrl (i32 length + UTF-8 bytes)
+
+use serde::{Deserialize, Serialize};
+
+/// UA-TCP ReverseHello message header.
+const REVERSE_HELLO: &[u8; 4] = b"RHEF";
+
+/// Decoded OPC UA ReverseHello message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpcUaReverseHello {
+    /// ApplicationUri of the server opening the connection
+    pub server_uri: String,
+    /// Endpoint URL the client should use on the reverse connection
+    pub endpoint_url: String,
+}
+
+/// Parse an OPC UA ReverseHello. Returns None for any other UA-TCP message.
+pub fn parse_reverse_hello(payload: &[u8]) -> Option<OpcUaReverseHello> {
+    if payload.get(..4)? != REVERSE_HELLO {
+        return None;
+    }
+    let size = u32::from_le_bytes(payload.get(4..8)?.try_into().ok()?) as usize;
+    if size < 16 {
+        return None;
+    }
+    let mut pos = 8;
+    let server_uri = read_string(payload, &mut pos)?;
+    let endpoint_url = read_string(payload, &mut pos)?;
+    Some(OpcUaReverseHello {
+        server_uri,
+        endpoint_url,
+    })
+}
+
+/// Read a UA String (i32 length, -1 = null) and advance `pos` past it.
+fn read_string(data: &[u8], pos: &mut usize) -> Option<String> {
+    let len = i32::from_le_bytes(data.get(*pos..*pos + 4)?.try_into().ok()?);
+    *pos += 4;
+    if len <= 0 {
+        return Some(String::new());
+    }
+    let len = len as usize;
+    let bytes = data.get(*pos..*pos + len)?;
+    *pos += len;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reverse_hello(server_uri: &str, endpoint_url: &str) -> Vec<u8> {
+        let mut body = Vec::new();
+        for s in [server_uri, endpoint_url] {
+            body.extend_from_slice(&(s.len() as i32).to_le_bytes());
+            body.extend_from_slice(s.as_bytes());
+        }
+        let mut msg = REVERSE_HELLO.to_vec();
+        msg.extend_from_slice(&((body.len() + 8) as u32).to_le_bytes());
+        msg.extend_from_slice(&body);
+        msg
+    }
+
+    #[test]
+    fn test_parse_reverse_hello() {
+        let msg = reverse_hello(
+            "urn:plc01:UnifiedAutomation:UaServer",
+            "opc.tcp://plc01:4840",
+        );
+        let rh = parse_reverse_hello(&msg).unwrap();
+        assert_eq!(rh.server_uri, "urn:plc01:UnifiedAutomation:UaServer");
+        assert_eq!(rh.endpoint_url, "opc.tcp://plc01:4840");
+    }
+
+    #[test]
+    fn test_other_messages_rejected() {
+        // Hello
+        let mut hel = reverse_hello("a", "b");
+        hel[..4].copy_from_slice(b"HELF");
+        assert_eq!(parse_reverse_hello(&hel), None);
+        // Truncated
+        let msg = reverse_hello("urn:server", "opc.tcp://host:4840");
+        assert_eq!(parse_reverse_hello(&msg[..14]), None);
+    }
+}
@@ -4,13 +4,21 @@
 //! configuration, and role. DCP is the Layer-2 discovery/configuration
 //! protocol used by all PROFINET IO devices.
 //!
-//! In KusanagiNoKajiki the parser receives the UDP payload from port 34964
-//! packets — the DCP header starts at byte 0 of that payload.
+//! DCP normally travels directly over Ethernet (Ethertype 0x8892) behind a
+//! 2-byte PROFINET FrameID; `parse_frame` takes that Layer-2 payload. Some
+//! stacks also carry it over UDP port 34964, where `parse` receives the UDP
+//! payload with the DCP header at byte 0.
 //!
 //! Reference: IEC 61158-6-10 (PROFINET DCP protocol), also covered in
 //!   Siemens Application Note "PROFINET Technology and Application".
 //!
-//! Port: 34964 UDP (DCP multicast port)
+//! Ethertype: 0x8892 (FrameID 0xFEFC-0xFEFF) / Port: 34964 UDP
+//!
+//! FrameIDs:
+//!   0xFEFC  Hello (device announcement)
+//!   0xFEFD  Get / Set
+//!   0xFEFE  Identify request (multicast)
+//!   0xFEFF  Identify response (unicast)
 //!
 //! DCP Header (10 bytes):
 //!   [0]     Service ID (0x03=Get, 0x04=Set, 0x05=Identify, 0x06=Hello)
@@ -23,13 +31,19 @@
 //!   [0]     Option
 //!   [1]     Suboption
 //!   [2..3]  u16 BE  Block Length
-//!   [4..5]  u16 BE  Block Info (RESPONSES ONLY — not present in requests)
+//!   [4..5]  u16 BE  Block Info (responses and Hello only — not present in other requests)
 //!   [6..n]  Data     (responses: starting after BlockInfo; requests: starting at offset+4)
 //!
 //! Blocks are padded to even byte boundaries.
 
 use serde::{Deserialize, Serialize};
 
+/// Ethertype for PROFINET real-time frames (DCP, cyclic RT, alarms).
+pub const PROFINET_ETHERTYPE: u16 = 0x8892;
+
+/// FrameID range reserved for DCP (Hello, Get/Set, Identify).
+const DCP_FRAME_IDS: std::ops::RangeInclusive<u16> = 0xFEFC..=0xFEFF;
+
 // ─── Enums ────────────────────────────────────────────────────────────────────
 
 /// DCP service identifier.
@@ -147,11 +161,25 @@ pub struct ProfinetDcpInfo {
 
 // ─── Parse Function ───────────────────────────────────────────────────────────
 
+/// Attempt to parse a PROFINET frame received over Ethernet.
+///
+/// The payload starts at the 2-byte FrameID following Ethertype 0x8892.
+/// Returns None for non-DCP frames (cyclic RT data, alarms) or if the DCP
+/// header is truncated.
+pub fn parse_frame(payload: &[u8]) -> Option<ProfinetDcpInfo> {
+    let frame_id = u16::from_be_bytes([*payload.first()?, *payload.get(1)?]);
+    if !DCP_FRAME_IDS.contains(&frame_id) {
+        return None;
+    }
+    parse(&payload[2..])
+}
+
 /// Attempt to parse a PROFINET DCP payload.
 ///
-/// The payload should be the UDP application-layer data from port 34964,
-/// starting directly at the 10-byte DCP header. Returns None if the
-/// payload is shorter than the minimum header size.
+/// The payload should start directly at the 10-byte DCP header (the UDP
+/// application-layer data from port 34964, or a Layer-2 frame after its
+/// FrameID). Returns None if the payload is shorter than the minimum
+/// header size.
 pub fn parse(payload: &[u8]) -> Option<ProfinetDcpInfo> {
     // Minimum: 10-byte DCP header
     if payload.len() < 10 {
@@ -165,8 +193,10 @@ pub fn parse(payload: &[u8]) -> Option<ProfinetDcpInfo> {
     let dcp_data_length = u16::from_be_bytes([payload[8], payload[9]]) as usize;
 
     // Responses include a 2-byte BlockInfo field at the start of each block's data.
-    // Requests do not have BlockInfo.
-    let has_block_info = matches!(service_type, DcpServiceType::ResponseSuccess);
+    // Requests do not, except Hello, which announces the device's own settings
+    // in response format.
+    let has_block_info = matches!(service_type, DcpServiceType::ResponseSuccess)
+        || matches!(service_id, DcpServiceId::Hello);
 
     let mut device_info = DcpDeviceInfo::default();
 
@@ -445,6 +475,45 @@ mod tests {
         assert_eq!(result.xid, 2);
     }
 
+    #[test]
+    fn test_hello_frame() {
+        // Hello request over Ethernet: FrameID 0xFEFC, blocks carry BlockInfo
+        #[rustfmt::skip]
+        let frame: &[u8] = &[
+            0xFE, 0xFC,             // FrameID: Hello
+            0x06, 0x00,             // Hello Request
+            0x00, 0x00, 0x00, 0x07, // XID
+            0x00, 0x00,             // Response delay
+            0x00, 0x1E,             // DCP data length = 30
+            // Name of Station "io-01" (5 bytes + BlockInfo, padded)
+            0x02, 0x02, 0x00, 0x07, 0x00, 0x00, b'i', b'o', b'-', b'0', b'1', 0x00,
+            // IP Suite 10.0.0.5/24, gateway 0.0.0.0
+            0x01, 0x02, 0x00, 0x0E, 0x00, 0x01,
+            10, 0, 0, 5, 255, 255, 255, 0, 0, 0, 0, 0,
+        ];
+        let result = parse_frame(frame).expect("should parse hello frame");
+        assert!(matches!(result.service_id, DcpServiceId::Hello));
+        assert!(matches!(result.service_type, DcpServiceType::Request));
+        assert_eq!(result.device_info.name_of_station.as_deref(), Some("io-01"));
+        assert_eq!(result.device_info.ip_address, Some([10, 0, 0, 5]));
+    }
+
+    #[test]
+    fn test_frame_rejects_cyclic_rt() {
+        // FrameID 0x8000 is cyclic RT data, not DCP
+        let data = build_identify_response();
+        let mut frame = vec![0x80, 0x00];
+        frame.extend_from_slice(&data);
+        assert!(parse_frame(&frame).is_none());
+
+        frame[0..2].copy_from_slice(&[0xFE, 0xFF]);
+        let result = parse_frame(&frame).expect("should parse identify response frame");
+        assert_eq!(
+            result.device_info.name_of_station.as_deref(),
+            Some("plc-001")
+        );
+    }
+
     #[test]
     fn test_truncated() {
         // Less than 10 bytes — should return None
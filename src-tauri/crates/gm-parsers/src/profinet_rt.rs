@@ -0,0 +1,322 @@
+//! PROFINET IO cyclic real-time (RT class 1/2) frame parser.
+//!
+//! Once an Application Relation is up, the IO-controller and each IO-device
+//! exchange process data cyclically as Layer-2 frames (EtherType 0x8892):
+//! the controller sends outputs to the device on one communication relation
+//! (CR), the device sends inputs back on another. Each CR has its own
+//! FrameID, negotiated at connect time.
+//!
+//! `gm-capture::parsing` hands over everything after the EtherType as a
+//! `"profinet:<mac>"` sentinel packet, starting at the FrameID.
+//!
+//! Frame layout after the EtherType:
+//!   [0..1]      u16 BE  FrameID
+//!   [2..n-4]    C_SDU   IO data + IOPS/IOCS (at least 40 bytes, padded)
+//!   [n-4..n-3]  u16 BE  CycleCounter (units of 31.25 µs)
+//!   [n-2]       DataStatus
+//!   [n-1]       TransferStatus
+//!
+//! FrameID ranges:
+//!   0x0100-0x0FFF  RT_CLASS_3 (IRT, scheduled) — not handled here
+//!   0x1000-0x7FFF  RT_CLASS_2
+//!   0x8000-0xFBFF  RT_CLASS_1 (unicast 0x8000-0xBFFF, multicast above)
+//!   0xFC01/0xFE01  Alarms (high/low)
+//!   0xFEFC-0xFEFF  DCP — see `profinet_dcp`
+//!
+//! The CycleCounter advances by SendClockFactor × ReductionRatio per frame,
+//! so the smallest step between consecutive frames gives the configured
+//! update time independently of capture timestamp jitter.
+//!
+//! Reference: IEC 61158-6-10 §4.2 (RT frame format), Wireshark PN-RT dissector
+//! EtherType: 0x8892
+
+use serde::{Deserialize, Serialize};
+
+/// Minimum C_SDU length (Ethernet minimum frame size minus headers).
+const MIN_C_SDU_LEN: usize = 40;
+
+/// APDU status trailer: CycleCounter (2) + DataStatus (1) + TransferStatus (1).
+const APDU_STATUS_LEN: usize = 4;
+
+/// CycleCounter tick in milliseconds (31.25 µs).
+const CYCLE_COUNTER_TICK_MS: f64 = 0.03125;
+
+/// Capture intervals above this are treated as pauses, not cycles.
+const MAX_CYCLE_INTERVAL_MS: f64 = 60_000.0;
+
+/// DataStatus bits (IEC 61158-6-10, APDU_Status).
+pub mod data_status {
+    /// 1 = primary, 0 = backup (system redundancy)
+    pub const STATE_PRIMARY: u8 = 0x01;
+    /// 1 = data valid
+    pub const DATA_VALID: u8 = 0x04;
+    /// 1 = provider in RUN, 0 = STOP
+    pub const PROVIDER_RUN: u8 = 0x10;
+    /// 0 = station reports a problem (diagnosis pending), 1 = normal
+    pub const STATION_PROBLEM_NORMAL: u8 = 0x20;
+}
+
+// ─── Result Structs ───────────────────────────────────────────────────────────
+
+/// PROFINET real-time class, from the FrameID range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RtClass {
+    /// FrameID 0x8000-0xFBFF — standard RT over switched Ethernet
+    RtClass1,
+    /// FrameID 0x1000-0x7FFF — RT with bandwidth reservation
+    RtClass2,
+}
+
+impl RtClass {
+    /// Classify a FrameID; None for IRT, alarms, DCP, and reserved ranges.
+    pub fn from_frame_id(frame_id: u16) -> Option<Self> {
+        match frame_id {
+            0x1000..=0x7FFF => Some(RtClass::RtClass2),
+            0x8000..=0xFBFF => Some(RtClass::RtClass1),
+            _ => None,
+        }
+    }
+}
+
+/// Fields extracted from one cyclic RT frame.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfinetRtFrame {
+    pub frame_id: u16,
+    pub rt_class: RtClass,
+    /// Cycle counter in 31.25 µs units
+    pub cycle_counter: u16,
+    /// APDU DataStatus byte (bits in [`data_status`])
+    pub data_status: u8,
+    pub transfer_status: u8,
+    /// Length of the IO data block (C_SDU) including padding
+    pub data_length: usize,
+}
+
+/// State of one cyclic communication relation (provider MAC → consumer MAC,
+/// FrameID) across frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfinetRtStream {
+    pub frame_id: u16,
+    pub rt_class: RtClass,
+    /// Source MAC (the station providing the IO data)
+    pub provider_mac: String,
+    /// Destination MAC (the consuming station, or a multicast group)
+    pub consumer_mac: String,
+    /// Length of the IO data block (C_SDU) in the last frame
+    pub data_length: usize,
+    pub frame_count: u64,
+    /// Update time derived from the smallest CycleCounter step
+    pub counter_cycle_ms: Option<f64>,
+    /// Average interval between frames on the wire, in milliseconds
+    pub avg_interval_ms: Option<f64>,
+    pub min_interval_ms: Option<f64>,
+    pub max_interval_ms: Option<f64>,
+    /// Number of inter-frame intervals measured
+    pub interval_count: u64,
+    pub last_cycle_counter: u16,
+    /// DataStatus of the last frame
+    pub data_status: u8,
+    /// Frames whose DataStatus marked the data invalid
+    pub invalid_data_frames: u64,
+    /// Frames sent while the provider was in STOP
+    pub provider_stopped_frames: u64,
+    /// Any frame carried the station problem indicator (diagnosis pending)
+    pub station_problem_seen: bool,
+    #[serde(skip)]
+    last_timestamp_secs: f64,
+    #[serde(skip)]
+    interval_sum_ms: f64,
+}
+
+impl ProfinetRtStream {
+    /// Start tracking a relation from its first frame.
+    pub fn new(
+        frame: &ProfinetRtFrame,
+        provider_mac: &str,
+        consumer_mac: &str,
+        timestamp_secs: f64,
+    ) -> Self {
+        let mut stream = Self {
+            frame_id: frame.frame_id,
+            rt_class: frame.rt_class,
+            provider_mac: provider_mac.to_string(),
+            consumer_mac: consumer_mac.to_string(),
+            data_length: frame.data_length,
+            frame_count: 0,
+            counter_cycle_ms: None,
+            avg_interval_ms: None,
+            min_interval_ms: None,
+            max_interval_ms: None,
+            interval_count: 0,
+            last_cycle_counter: frame.cycle_counter,
+            data_status: frame.data_status,
+            invalid_data_frames: 0,
+            provider_stopped_frames: 0,
+            station_problem_seen: false,
+            last_timestamp_secs: timestamp_secs,
+            interval_sum_ms: 0.0,
+        };
+        stream.record_status(frame);
+        stream
+    }
+
+    /// Update the relation with a subsequent frame.
+    pub fn observe(&mut self, frame: &ProfinetRtFrame, timestamp_secs: f64) {
+        let step = frame.cycle_counter.wrapping_sub(self.last_cycle_counter);
+        if step == 0 {
+            // Duplicate (mirrored port or MRP ring)
+            return;
+        }
+        self.record_status(frame);
+        self.data_length = frame.data_length;
+        self.last_cycle_counter = frame.cycle_counter;
+
+        let step_ms = step as f64 * CYCLE_COUNTER_TICK_MS;
+        self.counter_cycle_ms = Some(self.counter_cycle_ms.map_or(step_ms, |c| c.min(step_ms)));
+
+        let interval_ms = (timestamp_secs - self.last_timestamp_secs) * 1000.0;
+        self.last_timestamp_secs = timestamp_secs;
+        if interval_ms > 0.0 && interval_ms < MAX_CYCLE_INTERVAL_MS {
+            self.interval_count += 1;
+            self.interval_sum_ms += interval_ms;
+            let avg = self.interval_sum_ms / self.interval_count as f64;
+            self.avg_interval_ms = Some(round_ms(avg));
+            self.min_interval_ms = Some(round_ms(
+                self.min_interval_ms
+                    .map_or(interval_ms, |m| m.min(interval_ms)),
+            ));
+            self.max_interval_ms = Some(round_ms(
+                self.max_interval_ms
+                    .map_or(interval_ms, |m| m.max(interval_ms)),
+            ));
+        }
+    }
+
+    fn record_status(&mut self, frame: &ProfinetRtFrame) {
+        self.frame_count += 1;
+        self.data_status = frame.data_status;
+        if frame.data_status & data_status::DATA_VALID == 0 {
+            self.invalid_data_frames += 1;
+        }
+        if frame.data_status & data_status::PROVIDER_RUN == 0 {
+            self.provider_stopped_frames += 1;
+        }
+        if frame.data_status & data_status::STATION_PROBLEM_NORMAL == 0 {
+            self.station_problem_seen = true;
+        }
+    }
+}
+
+/// Round to 0.01 ms; RT cycles go down to 0.25 ms.
+fn round_ms(ms: f64) -> f64 {
+    (ms * 100.0).round() / 100.0
+}
+
+// ─── Parser ───────────────────────────────────────────────────────────────────
+
+/// Parse a cyclic RT class 1/2 frame (payload starting at the FrameID).
+///
+/// Returns None for other FrameIDs (DCP, alarms, IRT) or frames too short
+/// to hold the minimum C_SDU and the APDU status trailer.
+pub fn parse(payload: &[u8]) -> Option<ProfinetRtFrame> {
+    if payload.len() < 2 + MIN_C_SDU_LEN + APDU_STATUS_LEN {
+        return None;
+    }
+    let frame_id = u16::from_be_bytes([payload[0], payload[1]]);
+    let rt_class = RtClass::from_frame_id(frame_id)?;
+
+    let status = &payload[payload.len() - APDU_STATUS_LEN..];
+    Some(ProfinetRtFrame {
+        frame_id,
+        rt_class,
+        cycle_counter: u16::from_be_bytes([status[0], status[1]]),
+        data_status: status[2],
+        transfer_status: status[3],
+        data_length: payload.len() - 2 - APDU_STATUS_LEN,
+    })
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Good data, provider running, no problem, primary.
+    const STATUS_OK: u8 = 0x35;
+
+    fn frame(frame_id: u16, cycle_counter: u16, status: u8) -> Vec<u8> {
+        let mut f = frame_id.to_be_bytes().to_vec();
+        f.extend_from_slice(&[0x80; MIN_C_SDU_LEN]); // IOPS good
+        f.extend_from_slice(&cycle_counter.to_be_bytes());
+        f.push(status);
+        f.push(0x00);
+        f
+    }
+
+    #[test]
+    fn test_parse_rt_class_1() {
+        let info = parse(&frame(0x8001, 0x1234, STATUS_OK)).unwrap();
+        assert_eq!(info.frame_id, 0x8001);
+        assert_eq!(info.rt_class, RtClass::RtClass1);
+        assert_eq!(info.cycle_counter, 0x1234);
+        assert_eq!(info.data_status, STATUS_OK);
+        assert_eq!(info.data_length, MIN_C_SDU_LEN);
+
+        let info = parse(&frame(0x2001, 0, STATUS_OK)).unwrap();
+        assert_eq!(info.rt_class, RtClass::RtClass2);
+    }
+
+    #[test]
+    fn test_parse_rejects_non_cyclic() {
+        // DCP Identify, alarm, IRT
+        assert!(parse(&frame(0xFEFE, 0, 0)).is_none());
+        assert!(parse(&frame(0xFC01, 0, 0)).is_none());
+        assert!(parse(&frame(0x0100, 0, 0)).is_none());
+        // Truncated
+        assert!(parse(&[0x80, 0x01, 0x00, 0x00, 0x35, 0x00]).is_none());
+    }
+
+    #[test]
+    fn test_stream_cycle_time() {
+        // 2 ms update time: SendClockFactor 32 × ReductionRatio 2 = 64 ticks
+        let first = parse(&frame(0x8001, 0xFFC0, STATUS_OK)).unwrap();
+        let mut stream =
+            ProfinetRtStream::new(&first, "00:1b:1b:00:00:01", "00:0e:8c:00:00:02", 10.0);
+        let mut counter = 0xFFC0u16;
+        for i in 1..=10 {
+            counter = counter.wrapping_add(64);
+            let f = parse(&frame(0x8001, counter, STATUS_OK)).unwrap();
+            stream.observe(&f, 10.0 + i as f64 * 0.002);
+        }
+        assert_eq!(stream.frame_count, 11);
+        assert_eq!(stream.counter_cycle_ms, Some(2.0));
+        assert_eq!(stream.interval_count, 10);
+        assert_eq!(stream.avg_interval_ms, Some(2.0));
+        assert_eq!(stream.invalid_data_frames, 0);
+        assert!(!stream.station_problem_seen);
+
+        // Duplicates are ignored; a lost frame doubles the step but not the cycle
+        let dup = parse(&frame(0x8001, counter, STATUS_OK)).unwrap();
+        stream.observe(&dup, 10.021);
+        let skip = parse(&frame(0x8001, counter.wrapping_add(128), STATUS_OK)).unwrap();
+        stream.observe(&skip, 10.024);
+        assert_eq!(stream.frame_count, 12);
+        assert_eq!(stream.counter_cycle_ms, Some(2.0));
+        assert_eq!(stream.max_interval_ms, Some(4.0));
+    }
+
+    #[test]
+    fn test_stream_status_flags() {
+        let first = parse(&frame(0x8002, 0, STATUS_OK)).unwrap();
+        let mut stream = ProfinetRtStream::new(&first, "a", "b", 0.0);
+        // Provider in STOP, data invalid, problem indicator set
+        let stopped = parse(&frame(0x8002, 32, data_status::STATE_PRIMARY)).unwrap();
+        stream.observe(&stopped, 0.001);
+        assert_eq!(stream.invalid_data_frames, 1);
+        assert_eq!(stream.provider_stopped_frames, 1);
+        assert!(stream.station_problem_seen);
+        assert_eq!(stream.data_status, data_status::STATE_PRIMARY);
+    }
+}
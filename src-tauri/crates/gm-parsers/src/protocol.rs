@@ -1,4 +1,4 @@
-use gm_capture::ParsedPacket;
+use gm_capture::{ParsedPacket, TransportProtocol};
 use serde::{Deserialize, Serialize};
 
 /// ICS/SCADA and common IT protocols recognized by Kusanagi Kajiki.
@@ -21,6 +21,12 @@ pub enum IcsProtocol {
     Profinet,
     /// IEC 60870-5-104 (port 2404)
     Iec104,
+    /// IEC 61850 MMS — substation IEDs (port 102, shared with S7comm)
+    Iec61850Mms,
+    /// IEC 61850 GOOSE — Layer-2 multicast between IEDs (EtherType 0x88B8)
+    Goose,
+    /// IEC 61850-9-2 Sampled Values — Layer-2 multicast from merging units (EtherType 0x88BA)
+    SampledValues,
     /// MQTT — IoT/IIoT messaging (port 1883, 8883)
     Mqtt,
     /// HART-IP — process instrumentation (port 5094)
@@ -31,6 +37,24 @@ pub enum IcsProtocol {
     GeSrtp,
     /// Wonderware SuiteLink (port 5007)
     WonderwareSuitelink,
+    /// OMRON FINS — OMRON PLCs (port 9600)
+    Fins,
+    /// Mitsubishi MELSEC MC protocol / SLMP (ports 5006-5007, shared with SuiteLink)
+    Slmp,
+    /// CODESYS runtime programming protocol (V2 ports 1200/2455, V3 ports 1217/11740)
+    Codesys,
+    /// Tridium Niagara Fox building-automation protocol (1911, Foxs/TLS 4911)
+    NiagaraFox,
+    /// Red Lion Crimson v3 configuration protocol (789)
+    Crimson,
+    /// Yokogawa Vnet/IP DCS control network (UDP 20171/20172)
+    VnetIp,
+    /// Triconex TriStation safety controller engineering protocol (UDP 1502)
+    TriStation,
+    /// Beckhoff ADS/AMS — TwinCAT runtimes and engineering (48898)
+    Ads,
+    /// Classic OPC DA / A&E over DCOM (DCE/RPC on 135 and dynamic ports)
+    OpcDa,
 
     // Common IT protocols for context
     Http,
@@ -39,6 +63,10 @@ pub enum IcsProtocol {
     Ssh,
     Rdp,
     Snmp,
+    /// SMB2/3 file sharing (445, NetBIOS session 139)
+    Smb,
+    /// ICMP and ICMPv6 (IP protocols 1 and 58; no ports)
+    Icmp,
 
     /// Protocol could not be identified
     Unknown,
@@ -59,17 +87,31 @@ impl IcsProtocol {
             "opc_ua" => IcsProtocol::OpcUa,
             "profinet" => IcsProtocol::Profinet,
             "iec104" => IcsProtocol::Iec104,
+            "iec61850_mms" => IcsProtocol::Iec61850Mms,
+            "goose" => IcsProtocol::Goose,
+            "sampled_values" => IcsProtocol::SampledValues,
             "mqtt" => IcsProtocol::Mqtt,
             "hart_ip" => IcsProtocol::HartIp,
             "foundation_fieldbus" => IcsProtocol::FoundationFieldbus,
             "ge_srtp" => IcsProtocol::GeSrtp,
             "wonderware_suitelink" => IcsProtocol::WonderwareSuitelink,
+            "fins" => IcsProtocol::Fins,
+            "slmp" => IcsProtocol::Slmp,
+            "codesys" => IcsProtocol::Codesys,
+            "niagara_fox" => IcsProtocol::NiagaraFox,
+            "crimson" => IcsProtocol::Crimson,
+            "vnet_ip" => IcsProtocol::VnetIp,
+            "tristation" => IcsProtocol::TriStation,
+            "ads" => IcsProtocol::Ads,
+            "opc_da" => IcsProtocol::OpcDa,
             "http" => IcsProtocol::Http,
             "https" => IcsProtocol::Https,
             "dns" => IcsProtocol::Dns,
             "ssh" => IcsProtocol::Ssh,
             "rdp" => IcsProtocol::Rdp,
             "snmp" => IcsProtocol::Snmp,
+            "smb" => IcsProtocol::Smb,
+            "icmp" => IcsProtocol::Icmp,
             _ => IcsProtocol::Unknown,
         }
     }
@@ -86,11 +128,23 @@ impl IcsProtocol {
                 | IcsProtocol::OpcUa
                 | IcsProtocol::Profinet
                 | IcsProtocol::Iec104
+                | IcsProtocol::Iec61850Mms
+                | IcsProtocol::Goose
+                | IcsProtocol::SampledValues
                 | IcsProtocol::Mqtt
                 | IcsProtocol::HartIp
                 | IcsProtocol::FoundationFieldbus
                 | IcsProtocol::GeSrtp
                 | IcsProtocol::WonderwareSuitelink
+                | IcsProtocol::Fins
+                | IcsProtocol::Slmp
+                | IcsProtocol::Codesys
+                | IcsProtocol::NiagaraFox
+                | IcsProtocol::Crimson
+                | IcsProtocol::VnetIp
+                | IcsProtocol::TriStation
+                | IcsProtocol::Ads
+                | IcsProtocol::OpcDa
         )
     }
 
@@ -108,17 +162,31 @@ impl IcsProtocol {
             IcsProtocol::OpcUa => "opc_ua",
             IcsProtocol::Profinet => "profinet",
             IcsProtocol::Iec104 => "iec104",
+            IcsProtocol::Iec61850Mms => "iec61850_mms",
+            IcsProtocol::Goose => "goose",
+            IcsProtocol::SampledValues => "sampled_values",
             IcsProtocol::Mqtt => "mqtt",
             IcsProtocol::HartIp => "hart_ip",
             IcsProtocol::FoundationFieldbus => "foundation_fieldbus",
             IcsProtocol::GeSrtp => "ge_srtp",
             IcsProtocol::WonderwareSuitelink => "wonderware_suitelink",
+            IcsProtocol::Fins => "fins",
+            IcsProtocol::Slmp => "slmp",
+            IcsProtocol::Codesys => "codesys",
+            IcsProtocol::NiagaraFox => "niagara_fox",
+            IcsProtocol::Crimson => "crimson",
+            IcsProtocol::VnetIp => "vnet_ip",
+            IcsProtocol::TriStation => "tristation",
+            IcsProtocol::Ads => "ads",
+            IcsProtocol::OpcDa => "opc_da",
             IcsProtocol::Http => "http",
             IcsProtocol::Https => "https",
             IcsProtocol::Dns => "dns",
             IcsProtocol::Ssh => "ssh",
             IcsProtocol::Rdp => "rdp",
             IcsProtocol::Snmp => "snmp",
+            IcsProtocol::Smb => "smb",
+            IcsProtocol::Icmp => "icmp",
             IcsProtocol::Unknown => "unknown",
         }
     }
@@ -134,41 +202,186 @@ impl IcsProtocol {
             IcsProtocol::OpcUa => "OPC UA",
             IcsProtocol::Profinet => "PROFINET",
             IcsProtocol::Iec104 => "IEC 60870-5-104",
+            IcsProtocol::Iec61850Mms => "IEC 61850 MMS",
+            IcsProtocol::Goose => "IEC 61850 GOOSE",
+            IcsProtocol::SampledValues => "IEC 61850 Sampled Values",
             IcsProtocol::Mqtt => "MQTT",
             IcsProtocol::HartIp => "HART-IP",
             IcsProtocol::FoundationFieldbus => "Foundation Fieldbus HSE",
             IcsProtocol::GeSrtp => "GE SRTP",
             IcsProtocol::WonderwareSuitelink => "Wonderware SuiteLink",
+            IcsProtocol::Fins => "OMRON FINS",
+            IcsProtocol::Slmp => "MELSEC SLMP",
+            IcsProtocol::Codesys => "CODESYS",
+            IcsProtocol::NiagaraFox => "Niagara Fox",
+            IcsProtocol::Crimson => "Red Lion Crimson v3",
+            IcsProtocol::VnetIp => "Yokogawa Vnet/IP",
+            IcsProtocol::TriStation => "Triconex TriStation",
+            IcsProtocol::Ads => "Beckhoff ADS/AMS",
+            IcsProtocol::OpcDa => "OPC DA",
             IcsProtocol::Http => "HTTP",
             IcsProtocol::Https => "HTTPS/TLS",
             IcsProtocol::Dns => "DNS",
             IcsProtocol::Ssh => "SSH",
             IcsProtocol::Rdp => "RDP",
             IcsProtocol::Snmp => "SNMP",
+            IcsProtocol::Smb => "SMB",
+            IcsProtocol::Icmp => "ICMP",
             IcsProtocol::Unknown => "Unknown",
         }
     }
 }
 
+/// How a protocol label was arrived at, weakest evidence first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentificationMethod {
+    /// Nothing recognized the traffic
+    Unidentified,
+    /// A well-known port number
+    Port,
+    /// A port assigned by the analyst in the settings profile's port map
+    PortMap,
+    /// A payload check: a port shared by two protocols, or a probe of a
+    /// port nothing claims
+    PayloadProbe,
+    /// A deep parser decoded the payload as this protocol
+    DeepParse,
+}
+
+impl IdentificationMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdentificationMethod::Unidentified => "unidentified",
+            IdentificationMethod::Port => "port",
+            IdentificationMethod::PortMap => "port_map",
+            IdentificationMethod::PayloadProbe => "payload_probe",
+            IdentificationMethod::DeepParse => "deep_parse",
+        }
+    }
+
+    /// Confidence (0-100) that a label reached this way is right.
+    ///
+    /// A port on its own is a coin toss on busy networks; an analyst's port
+    /// map is a deliberate assertion about the site; a payload that passes
+    /// a protocol's framing checks, or decodes fully, is hard to mistake.
+    pub fn confidence(&self) -> u8 {
+        match self {
+            IdentificationMethod::Unidentified => 0,
+            IdentificationMethod::Port => 50,
+            IdentificationMethod::PortMap => 70,
+            IdentificationMethod::PayloadProbe => 85,
+            IdentificationMethod::DeepParse => 95,
+        }
+    }
+}
+
+/// A packet's protocol together with how it was identified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolIdentification {
+    pub protocol: IcsProtocol,
+    pub method: IdentificationMethod,
+    /// 0-100, see [`IdentificationMethod::confidence`]
+    pub confidence: u8,
+}
+
+impl ProtocolIdentification {
+    pub fn new(protocol: IcsProtocol, method: IdentificationMethod) -> Self {
+        Self {
+            protocol,
+            method,
+            confidence: method.confidence(),
+        }
+    }
+
+    pub fn unidentified() -> Self {
+        Self::new(IcsProtocol::Unknown, IdentificationMethod::Unidentified)
+    }
+
+    /// The same label, now backed by a successful deep parse.
+    pub fn confirmed(self) -> Self {
+        Self::new(self.protocol, IdentificationMethod::DeepParse)
+    }
+}
+
 /// Identify the application-layer protocol of a parsed packet.
 ///
-/// Currently uses port-based identification (Phase 1).
-/// Payload-based deep inspection will be added in Phase 3.
-pub fn identify_protocol(packet: &ParsedPacket) -> IcsProtocol {
+/// Well-known ports decide first (with payload checks where two protocols
+/// share a port). Traffic on ports nothing claims goes through a second
+/// pass of payload probes, so an OT protocol moved off its standard port
+/// (Modbus on 5020, DNP3 on a vendor port) is still recognized. The result
+/// records which of these decided; callers that go on to deep parse the
+/// packet can upgrade it with [`ProtocolIdentification::confirmed`].
+pub fn identify_protocol(packet: &ParsedPacket) -> ProtocolIdentification {
+    use IdentificationMethod::{PayloadProbe, Port};
+
+    // ICMP has no ports or payload to probe; the IP header names it
+    if matches!(
+        packet.transport,
+        TransportProtocol::Icmp | TransportProtocol::Icmpv6
+    ) {
+        return ProtocolIdentification::new(IcsProtocol::Icmp, Port);
+    }
+
     // First pass: port-based identification
     let by_port = identify_by_port(packet.src_port, packet.dst_port);
 
+    // Port 102 (ISO-TSAP) carries both S7comm and IEC 61850 MMS
+    if by_port == IcsProtocol::S7comm && crate::mms::is_mms(&packet.payload) {
+        return ProtocolIdentification::new(IcsProtocol::Iec61850Mms, PayloadProbe);
+    }
+
+    // Port 5007 is SuiteLink's default and also MELSEC's SLMP/MC TCP port
+    if by_port == IcsProtocol::WonderwareSuitelink && crate::slmp::is_slmp(&packet.payload) {
+        return ProtocolIdentification::new(IcsProtocol::Slmp, PayloadProbe);
+    }
+
     if by_port != IcsProtocol::Unknown {
-        return by_port;
+        return ProtocolIdentification::new(by_port, Port);
     }
 
-    // TODO Phase 3: Payload-based identification
-    // - Check for Modbus MBAP header (transaction ID + protocol ID 0x0000)
-    // - Check for DNP3 start bytes (0x0564)
-    // - Check for EtherNet/IP encapsulation header
-    // - Check for BACnet/IP BVLC header (0x81)
+    // Classic OPC binds on 135 and on ports handed out at runtime; only the
+    // interface IIDs in the DCE/RPC PDU give it away
+    if crate::opc_da::is_opc_da(&packet.payload) {
+        return ProtocolIdentification::new(IcsProtocol::OpcDa, PayloadProbe);
+    }
 
-    IcsProtocol::Unknown
+    // Serial device servers pass RTU frames through untouched; a bare CRC
+    // check is too weak to run on every unclaimed port, but on a gateway's
+    // tunnel port it is what the traffic is expected to be
+    if let Some(crate::serial_tunnel::TunneledProtocol::ModbusRtu) =
+        crate::serial_tunnel::detect(&packet.payload, packet.src_port, packet.dst_port)
+            .filter(|t| t.framing == crate::serial_tunnel::SerialFraming::Raw)
+            .and_then(|t| t.tunneled)
+    {
+        return ProtocolIdentification::new(IcsProtocol::Modbus, PayloadProbe);
+    }
+
+    identify_by_payload(&packet.payload)
+        .map(|protocol| ProtocolIdentification::new(protocol, PayloadProbe))
+        .unwrap_or_else(ProtocolIdentification::unidentified)
+}
+
+/// Second-pass classifier: lightweight probes of the payload for OT
+/// protocols seen on non-standard ports.
+///
+/// The strongest checks run first: the DNP3 header carries a CRC, and an
+/// EtherNet/IP header's length must match the payload exactly. MMS shares
+/// S7comm's TPKT/COTP framing, so it is tested before S7comm.
+pub fn identify_by_payload(payload: &[u8]) -> Option<IcsProtocol> {
+    if crate::dnp3::is_dnp3(payload) {
+        Some(IcsProtocol::Dnp3)
+    } else if crate::enip::is_enip(payload) {
+        Some(IcsProtocol::EthernetIp)
+    } else if crate::mms::is_mms(payload) {
+        Some(IcsProtocol::Iec61850Mms)
+    } else if crate::s7comm::is_s7comm(payload) {
+        Some(IcsProtocol::S7comm)
+    } else if crate::modbus::is_modbus_tcp(payload) {
+        Some(IcsProtocol::Modbus)
+    } else {
+        None
+    }
 }
 
 /// Identify protocol based on well-known port numbers.
@@ -196,6 +409,14 @@ pub fn identify_by_port(src_port: u16, dst_port: u16) -> IcsProtocol {
             1089..=1091 => return IcsProtocol::FoundationFieldbus,
             18245 | 18246 => return IcsProtocol::GeSrtp,
             5007 => return IcsProtocol::WonderwareSuitelink,
+            9600 => return IcsProtocol::Fins,
+            5006 => return IcsProtocol::Slmp,
+            1200 | 1217 | 2455 | 11740 => return IcsProtocol::Codesys,
+            1911 | 4911 => return IcsProtocol::NiagaraFox,
+            789 => return IcsProtocol::Crimson,
+            20171 | 20172 => return IcsProtocol::VnetIp,
+            1502 => return IcsProtocol::TriStation,
+            48898 => return IcsProtocol::Ads,
 
             // ─── Common IT Protocols ──────────────────────
             80 | 8080 | 8443 => return IcsProtocol::Http,
@@ -204,6 +425,7 @@ pub fn identify_by_port(src_port: u16, dst_port: u16) -> IcsProtocol {
             22 => return IcsProtocol::Ssh,
             3389 => return IcsProtocol::Rdp,
             161 | 162 => return IcsProtocol::Snmp,
+            445 | 139 => return IcsProtocol::Smb,
 
             _ => continue,
         }
@@ -227,11 +449,128 @@ mod tests {
         assert_eq!(identify_by_port(49152, 20000), IcsProtocol::Dnp3);
     }
 
+    #[test]
+    fn test_smb_port_detection() {
+        assert_eq!(identify_by_port(49152, 445), IcsProtocol::Smb);
+        assert_eq!(identify_by_port(139, 49152), IcsProtocol::Smb);
+        assert_eq!(IcsProtocol::from_name("smb"), IcsProtocol::Smb);
+    }
+
     #[test]
     fn test_unknown_port() {
         assert_eq!(identify_by_port(12345, 54321), IcsProtocol::Unknown);
     }
 
+    #[test]
+    fn test_payload_probes_on_nonstandard_ports() {
+        // Read Holding Registers, as sent to a Modbus server on 5020
+        let modbus = [
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A,
+        ];
+        assert_eq!(identify_by_payload(&modbus), Some(IcsProtocol::Modbus));
+        // Reset Link States with a valid header CRC
+        let dnp3 = [0x05, 0x64, 0x05, 0xC0, 0x01, 0x00, 0x00, 0x04, 0xE9, 0x21];
+        assert_eq!(identify_by_payload(&dnp3), Some(IcsProtocol::Dnp3));
+        // COTP Connection Request to TSAP 0x0102 (rack 0, slot 2)
+        let s7 = [
+            0x03, 0x00, 0x00, 0x16, 0x11, 0xE0, 0x00, 0x00, 0x00, 0x01, 0x00, 0xC1, 0x02, 0x01,
+            0x00, 0xC2, 0x02, 0x01, 0x02, 0xC0, 0x01, 0x0A,
+        ];
+        assert_eq!(identify_by_payload(&s7), Some(IcsProtocol::S7comm));
+        let mut list_identity = [0u8; 24];
+        list_identity[0] = 0x63;
+        assert_eq!(
+            identify_by_payload(&list_identity),
+            Some(IcsProtocol::EthernetIp)
+        );
+    }
+
+    #[test]
+    fn test_payload_probes_reject_near_misses() {
+        // MBAP length that does not match the segment
+        let short = [
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x09, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A,
+        ];
+        assert_eq!(identify_by_payload(&short), None);
+        // DNP3 start bytes with a corrupted header CRC
+        let dnp3 = [0x05, 0x64, 0x05, 0xC0, 0x01, 0x00, 0x00, 0x04, 0xE9, 0x22];
+        assert_eq!(identify_by_payload(&dnp3), None);
+        // An all-zero buffer would be an ENIP NOP and a Modbus-looking header
+        assert_eq!(identify_by_payload(&[0u8; 24]), None);
+        assert_eq!(identify_by_payload(b"GET / HTTP/1.1\r\n\r\n"), None);
+    }
+
+    fn tcp_packet(src_port: u16, dst_port: u16, payload: &[u8]) -> ParsedPacket {
+        ParsedPacket {
+            timestamp: Default::default(),
+            src_mac: None,
+            dst_mac: None,
+            vlan_id: None,
+            outer_vlan_id: None,
+            mpls_labels: Vec::new(),
+            vxlan_vni: None,
+            src_ip: "10.0.0.1".to_string(),
+            dst_ip: "10.0.0.2".to_string(),
+            transport: gm_capture::TransportProtocol::Tcp,
+            src_port,
+            dst_port,
+            tcp_flags: 0,
+            tcp_seq: 0,
+            length: 54 + payload.len(),
+            payload: payload.to_vec(),
+            origin_file: "test.pcap".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_identification_method_and_confidence() {
+        let read_holding = [
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A,
+        ];
+
+        let by_port = identify_protocol(&tcp_packet(49152, 502, &read_holding));
+        assert_eq!(by_port.protocol, IcsProtocol::Modbus);
+        assert_eq!(by_port.method, IdentificationMethod::Port);
+
+        let probed = identify_protocol(&tcp_packet(49152, 5020, &read_holding));
+        assert_eq!(probed.protocol, IcsProtocol::Modbus);
+        assert_eq!(probed.method, IdentificationMethod::PayloadProbe);
+        assert!(probed.confidence > by_port.confidence);
+
+        let confirmed = by_port.confirmed();
+        assert_eq!(confirmed.method, IdentificationMethod::DeepParse);
+        assert!(confirmed.confidence > probed.confidence);
+
+        // Modbus RTU through a Moxa NPort in TCP Server mode
+        let rtu = [0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD];
+        let tunneled = identify_protocol(&tcp_packet(49152, 4001, &rtu));
+        assert_eq!(tunneled.protocol, IcsProtocol::Modbus);
+        assert_eq!(tunneled.method, IdentificationMethod::PayloadProbe);
+        assert_eq!(
+            identify_protocol(&tcp_packet(49152, 5020, &rtu)),
+            ProtocolIdentification::unidentified()
+        );
+
+        let unknown = identify_protocol(&tcp_packet(49152, 5020, b"hello"));
+        assert_eq!(unknown, ProtocolIdentification::unidentified());
+        assert_eq!(unknown.confidence, 0);
+    }
+
+    #[test]
+    fn test_icmp_identified_by_transport() {
+        // An echo request whose data happens to look like an MBAP header
+        let mut ping = tcp_packet(0, 0, &[0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03]);
+        ping.transport = gm_capture::TransportProtocol::Icmp;
+        let id = identify_protocol(&ping);
+        assert_eq!(id.protocol, IcsProtocol::Icmp);
+        assert_eq!(id.method, IdentificationMethod::Port);
+        assert_eq!(
+            IcsProtocol::from_name(id.protocol.to_name()),
+            IcsProtocol::Icmp
+        );
+        assert!(!IcsProtocol::Icmp.is_ot());
+    }
+
     #[test]
     fn test_ot_classification() {
         assert!(IcsProtocol::Modbus.is_ot());
@@ -241,9 +580,21 @@ mod tests {
         assert!(IcsProtocol::FoundationFieldbus.is_ot());
         assert!(IcsProtocol::GeSrtp.is_ot());
         assert!(IcsProtocol::WonderwareSuitelink.is_ot());
+        assert!(IcsProtocol::Fins.is_ot());
+        assert!(IcsProtocol::Slmp.is_ot());
+        assert!(IcsProtocol::Codesys.is_ot());
+        assert!(IcsProtocol::NiagaraFox.is_ot());
+        assert!(IcsProtocol::Crimson.is_ot());
+        assert!(IcsProtocol::VnetIp.is_ot());
+        assert!(IcsProtocol::TriStation.is_ot());
+        assert!(IcsProtocol::Ads.is_ot());
+        assert!(IcsProtocol::OpcDa.is_ot());
         assert!(IcsProtocol::Mqtt.is_ot());
+        assert!(IcsProtocol::Goose.is_ot());
+        assert!(IcsProtocol::SampledValues.is_ot());
         assert!(!IcsProtocol::Http.is_ot());
         assert!(!IcsProtocol::Dns.is_ot());
+        assert!(!IcsProtocol::Smb.is_ot());
         assert!(!IcsProtocol::Unknown.is_ot());
     }
 
@@ -259,6 +610,16 @@ mod tests {
             identify_by_port(49152, 5007),
             IcsProtocol::WonderwareSuitelink
         );
+        assert_eq!(identify_by_port(49152, 9600), IcsProtocol::Fins);
+        assert_eq!(identify_by_port(49152, 5006), IcsProtocol::Slmp);
+        assert_eq!(identify_by_port(49152, 2455), IcsProtocol::Codesys);
+        assert_eq!(identify_by_port(49152, 11740), IcsProtocol::Codesys);
+        assert_eq!(identify_by_port(1911, 49152), IcsProtocol::NiagaraFox);
+        assert_eq!(identify_by_port(49152, 4911), IcsProtocol::NiagaraFox);
+        assert_eq!(identify_by_port(49152, 789), IcsProtocol::Crimson);
+        assert_eq!(identify_by_port(20171, 20171), IcsProtocol::VnetIp);
+        assert_eq!(identify_by_port(49152, 1502), IcsProtocol::TriStation);
+        assert_eq!(identify_by_port(48898, 49152), IcsProtocol::Ads);
         assert_eq!(identify_by_port(49152, 2404), IcsProtocol::Iec104);
         assert_eq!(identify_by_port(49152, 34962), IcsProtocol::Profinet);
     }
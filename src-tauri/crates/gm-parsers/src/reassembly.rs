@@ -0,0 +1,410 @@
+//! Cross-segment PDU reassembly for TCP-borne OT protocols.
+//!
+//! Deep parsers work on one buffer at a time, and a TCP segment boundary has
+//! no relation to an application PDU boundary: a Modbus FC 43 response, a
+//! long DNP3 frame or an S7 block upload can arrive split over several
+//! segments, and one segment can carry several PDUs. [`PduReassembler`]
+//! keeps the unfinished tail of each connection direction and hands back
+//! whole PDUs, using each protocol's own length field to find boundaries.
+//!
+//! Protocols opt in through [`framing_for`]; everything else (and all UDP
+//! traffic) is parsed segment by segment as before. Segments are appended in
+//! arrival order, so retransmissions and out-of-order delivery are not
+//! repaired here; readers built with `PcapReader::with_tcp_reassembly` (for
+//! instance on [`STREAM_PORTS`]) hand over in-order, deduplicated streams.
+//! Otherwise a length field that stops making sense drops the buffer and
+//! the stream resynchronises on the next segment.
+
+use std::collections::HashMap;
+
+use gm_capture::{tcp_flags, ParsedPacket};
+
+use crate::IcsProtocol;
+
+/// Largest PDU the reassembler will wait for. Bigger declared lengths are
+/// treated as a framing error rather than buffered.
+pub const MAX_PDU_LEN: usize = 65_542;
+
+/// Connection directions with a partial PDU buffered at once; beyond this,
+/// new partial PDUs are dropped instead of buffered.
+pub const MAX_STREAMS: usize = 16_384;
+
+/// Well-known TCP ports of the protocols [`framing_for`] covers: the ports
+/// worth reassembling in gm-capture before PDUs are split here.
+pub const STREAM_PORTS: &[u16] = &[502, 20000, 44818, 102, 2404, 48898, 9600];
+
+/// What a protocol's framing says about the start of a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// The next PDU is this many bytes long (it may not all be here yet)
+    Pdu(usize),
+    /// Too few bytes to read the length field
+    NeedMore,
+    /// The bytes do not start a PDU of this protocol
+    Invalid,
+}
+
+/// Reads the length of the PDU at the start of a buffer.
+pub type FramingFn = fn(&[u8]) -> Framing;
+
+/// The framing function for protocols whose parsers accept reassembled PDUs.
+pub fn framing_for(protocol: IcsProtocol) -> Option<FramingFn> {
+    match protocol {
+        IcsProtocol::Modbus => Some(modbus_framing),
+        IcsProtocol::Dnp3 => Some(dnp3_framing),
+        IcsProtocol::EthernetIp => Some(enip_framing),
+        IcsProtocol::S7comm | IcsProtocol::Iec61850Mms => Some(tpkt_framing),
+        IcsProtocol::Iec104 => Some(iec104_framing),
+        IcsProtocol::Ads => Some(ams_tcp_framing),
+        IcsProtocol::Fins => Some(fins_tcp_framing),
+        _ => None,
+    }
+}
+
+/// MBAP header: 2-byte transaction ID, protocol ID 0, then the byte count
+/// of unit ID + PDU. RTU frames tunnelled over TCP have no MBAP header but
+/// often look like one (a start address of 0 reads as protocol ID 0), so a
+/// buffer that is exactly one CRC-valid RTU frame is not MBAP.
+fn modbus_framing(buf: &[u8]) -> Framing {
+    if (buf.len() >= 4 && buf[2..4] != [0, 0]) || crate::modbus::is_rtu_frame(buf) {
+        return Framing::Invalid;
+    }
+    if buf.len() < 6 {
+        return Framing::NeedMore;
+    }
+    match u16::from_be_bytes([buf[4], buf[5]]) as usize {
+        len @ 2..=254 => Framing::Pdu(6 + len),
+        _ => Framing::Invalid,
+    }
+}
+
+/// DNP3 link frame: 0x05 0x64, then LEN counting control, addresses and
+/// user data; every 16 user-data bytes (and the header) carry a 2-byte CRC.
+fn dnp3_framing(buf: &[u8]) -> Framing {
+    match buf {
+        [] | [0x05] | [0x05, 0x64] => Framing::NeedMore,
+        [0x05, 0x64, len, ..] if *len >= 5 => {
+            let user_data = *len as usize - 5;
+            Framing::Pdu(10 + user_data + 2 * user_data.div_ceil(16))
+        }
+        _ => Framing::Invalid,
+    }
+}
+
+/// EtherNet/IP encapsulation: 24-byte header with a little-endian data
+/// length at offset 2.
+fn enip_framing(buf: &[u8]) -> Framing {
+    if buf.len() < 4 {
+        return Framing::NeedMore;
+    }
+    Framing::Pdu(24 + u16::from_le_bytes([buf[2], buf[3]]) as usize)
+}
+
+/// TPKT (RFC 1006), shared by S7comm and MMS: version 3, reserved 0, then
+/// the big-endian length of the whole packet.
+fn tpkt_framing(buf: &[u8]) -> Framing {
+    match buf {
+        [] | [0x03] | [0x03, 0x00] | [0x03, 0x00, _] => Framing::NeedMore,
+        [0x03, 0x00, hi, lo, ..] => match u16::from_be_bytes([*hi, *lo]) as usize {
+            len if len >= 7 => Framing::Pdu(len),
+            _ => Framing::Invalid,
+        },
+        _ => Framing::Invalid,
+    }
+}
+
+/// IEC 60870-5-104 APCI: start byte 0x68 and the length of the rest.
+fn iec104_framing(buf: &[u8]) -> Framing {
+    match buf {
+        [] => Framing::NeedMore,
+        [0x68] => Framing::NeedMore,
+        [0x68, len, ..] if *len >= 4 => Framing::Pdu(2 + *len as usize),
+        _ => Framing::Invalid,
+    }
+}
+
+/// AMS/TCP header: 2 reserved zero bytes, then a little-endian u32 length.
+fn ams_tcp_framing(buf: &[u8]) -> Framing {
+    if buf.iter().take(2).any(|b| *b != 0) {
+        return Framing::Invalid;
+    }
+    if buf.len() < 6 {
+        return Framing::NeedMore;
+    }
+    Framing::Pdu(6 + u32::from_le_bytes([buf[2], buf[3], buf[4], buf[5]]) as usize)
+}
+
+/// FINS/TCP header: "FINS" magic, then a big-endian u32 length.
+fn fins_tcp_framing(buf: &[u8]) -> Framing {
+    let magic = &b"FINS"[..buf.len().min(4)];
+    if buf[..magic.len()] != *magic {
+        return Framing::Invalid;
+    }
+    if buf.len() < 8 {
+        return Framing::NeedMore;
+    }
+    Framing::Pdu(8 + u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]) as usize)
+}
+
+/// One direction of a TCP connection.
+type StreamKey = (String, u16, String, u16);
+
+/// Per-connection buffers of PDUs still waiting for their remaining bytes.
+#[derive(Debug, Default)]
+pub struct PduReassembler {
+    streams: HashMap<StreamKey, Vec<u8>>,
+    /// DNP3 application fragments spread over several link frames
+    pub(crate) dnp3: crate::dnp3::TransportReassembler,
+}
+
+impl PduReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connection directions currently holding a partial PDU.
+    pub fn pending_streams(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Append a segment to its direction's buffer and return every PDU it
+    /// completes, in order.
+    ///
+    /// A segment that does not start with a valid header while nothing is
+    /// buffered is returned whole, so parsers that cope with odd framing
+    /// (Modbus RTU over TCP) still see it. FIN and RST release the buffer.
+    pub fn push(&mut self, packet: &ParsedPacket, framing: FramingFn) -> Vec<Vec<u8>> {
+        let key = stream_key(packet);
+        let mut pdus = Vec::new();
+        let buffered = self.streams.remove(&key).unwrap_or_default();
+
+        let rest = if buffered.is_empty() {
+            split_pdus(&packet.payload, framing, &mut pdus).map(<[u8]>::to_vec)
+        } else {
+            let mut joined = buffered;
+            joined.extend_from_slice(&packet.payload);
+            match split_pdus(&joined, framing, &mut pdus) {
+                Some(rest) => Some(rest.to_vec()),
+                // The buffered bytes were not a PDU after all: drop them and
+                // try the new segment on its own
+                None if pdus.is_empty() => {
+                    split_pdus(&packet.payload, framing, &mut pdus).map(<[u8]>::to_vec)
+                }
+                None => None,
+            }
+        };
+
+        let closing = packet.tcp_flags & (tcp_flags::FIN | tcp_flags::RST) != 0;
+        match rest {
+            Some(rest) if !rest.is_empty() && !closing && self.streams.len() < MAX_STREAMS => {
+                self.streams.insert(key, rest);
+            }
+            Some(_) => {}
+            None if pdus.is_empty() && !packet.payload.is_empty() => {
+                pdus.push(packet.payload.clone());
+            }
+            None => {}
+        }
+        pdus
+    }
+
+    /// Forget a direction's partial PDU, e.g. when one of its segments is
+    /// not going to be fed in.
+    pub fn discard(&mut self, packet: &ParsedPacket) {
+        self.streams.remove(&stream_key(packet));
+    }
+}
+
+fn stream_key(packet: &ParsedPacket) -> StreamKey {
+    (
+        packet.src_ip.clone(),
+        packet.src_port,
+        packet.dst_ip.clone(),
+        packet.dst_port,
+    )
+}
+
+/// Push every complete PDU at the front of `data` into `pdus` and return the
+/// incomplete remainder, or `None` if the framing broke.
+fn split_pdus<'a>(
+    mut data: &'a [u8],
+    framing: FramingFn,
+    pdus: &mut Vec<Vec<u8>>,
+) -> Option<&'a [u8]> {
+    while !data.is_empty() {
+        match framing(data) {
+            Framing::Pdu(len) if len > MAX_PDU_LEN => return None,
+            Framing::Pdu(len) if len <= data.len() => {
+                pdus.push(data[..len].to_vec());
+                data = &data[len..];
+            }
+            Framing::Pdu(_) | Framing::NeedMore => return Some(data),
+            Framing::Invalid => return None,
+        }
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gm_capture::TransportProtocol;
+
+    fn segment(payload: &[u8]) -> ParsedPacket {
+        ParsedPacket {
+            timestamp: Default::default(),
+            src_mac: None,
+            dst_mac: None,
+            vlan_id: None,
+            outer_vlan_id: None,
+            mpls_labels: Vec::new(),
+            vxlan_vni: None,
+            src_ip: "10.0.0.10".to_string(),
+            dst_ip: "10.0.0.1".to_string(),
+            transport: TransportProtocol::Tcp,
+            src_port: 502,
+            dst_port: 49152,
+            tcp_flags: tcp_flags::ACK,
+            tcp_seq: 0,
+            length: 54 + payload.len(),
+            payload: payload.to_vec(),
+            origin_file: "test.pcap".to_string(),
+        }
+    }
+
+    /// Read Holding Registers response carrying 10 registers.
+    fn modbus_response() -> Vec<u8> {
+        let mut pdu = vec![0x00, 0x01, 0x00, 0x00, 0x00, 0x17, 0x01, 0x03, 0x14];
+        pdu.extend((0..20).map(|i| i as u8));
+        pdu
+    }
+
+    #[test]
+    fn test_pdu_split_across_segments() {
+        let pdu = modbus_response();
+        let mut reassembler = PduReassembler::new();
+
+        assert!(reassembler
+            .push(&segment(&pdu[..10]), modbus_framing)
+            .is_empty());
+        assert_eq!(reassembler.pending_streams(), 1);
+        let pdus = reassembler.push(&segment(&pdu[10..]), modbus_framing);
+        assert_eq!(pdus, vec![pdu]);
+        assert_eq!(reassembler.pending_streams(), 0);
+    }
+
+    #[test]
+    fn test_several_pdus_in_one_segment() {
+        let pdu = modbus_response();
+        let mut payload = pdu.clone();
+        payload.extend_from_slice(&pdu);
+        payload.extend_from_slice(&pdu[..4]);
+
+        let mut reassembler = PduReassembler::new();
+        let pdus = reassembler.push(&segment(&payload), modbus_framing);
+        assert_eq!(pdus, vec![pdu.clone(), pdu.clone()]);
+        assert_eq!(reassembler.pending_streams(), 1);
+
+        let pdus = reassembler.push(&segment(&pdu[4..]), modbus_framing);
+        assert_eq!(pdus, vec![pdu]);
+    }
+
+    #[test]
+    fn test_unframed_segment_passes_through() {
+        let mut reassembler = PduReassembler::new();
+        // Modbus RTU framing over TCP has no MBAP header
+        let rtu = [0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD];
+        assert_eq!(
+            reassembler.push(&segment(&rtu), modbus_framing),
+            vec![rtu.to_vec()]
+        );
+        assert_eq!(reassembler.pending_streams(), 0);
+    }
+
+    #[test]
+    fn test_resync_after_broken_stream() {
+        let pdu = modbus_response();
+        let mut reassembler = PduReassembler::new();
+        // A stray 2-byte tail is buffered while the length is unknown
+        assert!(reassembler
+            .push(&segment(&[0x00, 0x07]), modbus_framing)
+            .is_empty());
+        assert_eq!(reassembler.pending_streams(), 1);
+
+        // Joined with the next segment it reads as protocol ID 0x0001, so
+        // the tail is dropped and the segment parsed on its own
+        assert_eq!(
+            reassembler.push(&segment(&pdu), modbus_framing),
+            vec![pdu.clone()]
+        );
+        assert_eq!(reassembler.pending_streams(), 0);
+
+        assert!(reassembler
+            .push(&segment(&pdu[..10]), modbus_framing)
+            .is_empty());
+        reassembler.discard(&segment(&[]));
+        assert_eq!(reassembler.pending_streams(), 0);
+    }
+
+    #[test]
+    fn test_fin_releases_buffer() {
+        let pdu = modbus_response();
+        let mut reassembler = PduReassembler::new();
+        let mut last = segment(&pdu[..10]);
+        last.tcp_flags |= tcp_flags::FIN;
+        assert!(reassembler.push(&last, modbus_framing).is_empty());
+        assert_eq!(reassembler.pending_streams(), 0);
+    }
+
+    #[test]
+    fn test_deep_parse_stream_fc43_across_segments() {
+        // FC 43 Read Device Identification response: vendor "Schneider"
+        let mut response = vec![
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x13, 0x01, 0x2B, 0x0E, 0x01, 0x01, 0x00, 0x00, 0x01,
+            0x00, 0x09,
+        ];
+        response.extend_from_slice(b"Schneider");
+        let (head, tail) = response.split_at(12);
+
+        let mut reassembler = PduReassembler::new();
+        assert!(
+            crate::deep_parse_stream(&mut reassembler, &segment(head), IcsProtocol::Modbus)
+                .is_empty()
+        );
+        let results =
+            crate::deep_parse_stream(&mut reassembler, &segment(tail), IcsProtocol::Modbus);
+        let [crate::DeepParseResult::Modbus { pdus }] = results.as_slice() else {
+            panic!("expected one Modbus result, got {:?}", results);
+        };
+        let device_id = pdus[0].device_id.as_ref().unwrap();
+        assert_eq!(device_id.vendor_name.as_deref(), Some("Schneider"));
+    }
+
+    #[test]
+    fn test_stream_ports_have_framing() {
+        for &port in STREAM_PORTS {
+            let protocol = crate::identify_by_port(49152, port);
+            assert!(framing_for(protocol).is_some(), "port {}", port);
+        }
+    }
+
+    #[test]
+    fn test_protocol_framings() {
+        // DNP3 frame with 20 bytes of user data: 10 + 20 + 2 CRC blocks
+        assert_eq!(dnp3_framing(&[0x05, 0x64, 25]), Framing::Pdu(34));
+        assert_eq!(dnp3_framing(&[0x05, 0x64, 5]), Framing::Pdu(10));
+        assert_eq!(dnp3_framing(&[0x05]), Framing::NeedMore);
+        assert_eq!(dnp3_framing(&[0x05, 0x65, 5]), Framing::Invalid);
+        assert_eq!(tpkt_framing(&[0x03, 0x00, 0x00, 0x16]), Framing::Pdu(22));
+        assert_eq!(tpkt_framing(&[0x03, 0x01]), Framing::Invalid);
+        assert_eq!(iec104_framing(&[0x68, 0x04]), Framing::Pdu(6));
+        assert_eq!(enip_framing(&[0x6F, 0x00, 0x10, 0x00]), Framing::Pdu(40));
+        assert_eq!(
+            ams_tcp_framing(&[0x00, 0x00, 0x20, 0x00, 0x00, 0x00]),
+            Framing::Pdu(38)
+        );
+        assert_eq!(fins_tcp_framing(b"FINS\x00\x00\x00\x0c"), Framing::Pdu(20));
+        assert_eq!(fins_tcp_framing(b"FIX"), Framing::Invalid);
+        assert_eq!(fins_tcp_framing(b"FI"), Framing::NeedMore);
+    }
+}
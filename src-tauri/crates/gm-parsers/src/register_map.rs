@@ -0,0 +1,191 @@
+//! Modbus register address-space maps.
+//!
+//! Masters poll a slave with a handful of fixed requests ("read 40 holding
+//! registers at 100", "write coil 7"), and the requests of different
+//! masters overlap. This module folds the observed request ranges of one
+//! slave into a map of the addresses that were actually exercised: for
+//! each register table, non-overlapping regions with how often each was
+//! read and written.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::modbus::{RegisterRange, RegisterType};
+
+/// The four Modbus register tables, in address-map display order.
+const TABLES: [RegisterType; 4] = [
+    RegisterType::Coil,
+    RegisterType::DiscreteInput,
+    RegisterType::InputRegister,
+    RegisterType::HoldingRegister,
+];
+
+/// A request range sent to a slave and how often it was sent.
+#[derive(Debug, Clone)]
+pub struct RegisterAccess {
+    pub range: RegisterRange,
+    /// Write request (FC 5/6/15/16) rather than a read
+    pub write: bool,
+    /// Requests seen with exactly this range
+    pub requests: u64,
+}
+
+/// A run of addresses in one register table that saw the same traffic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterRegion {
+    pub register_type: RegisterType,
+    /// First address of the region
+    pub start: u16,
+    /// Last address of the region (inclusive)
+    pub end: u16,
+    /// Read requests covering every address in the region
+    pub reads: u64,
+    /// Write requests covering every address in the region
+    pub writes: u64,
+}
+
+impl RegisterRegion {
+    /// Number of addresses in the region.
+    pub fn address_count(&self) -> u32 {
+        u32::from(self.end) - u32::from(self.start) + 1
+    }
+}
+
+/// Fold a slave's request ranges into non-overlapping regions.
+///
+/// Overlapping requests split each other at their boundaries, so every
+/// region carries the exact read and write counts of its addresses.
+/// Adjacent regions with identical counts are joined. Regions are ordered
+/// by table (coils, discrete inputs, input registers, holding registers)
+/// and then by address. Zero-length ranges are ignored.
+pub fn build_register_map(accesses: &[RegisterAccess]) -> Vec<RegisterRegion> {
+    let mut regions = Vec::new();
+
+    for table in TABLES {
+        // Half-open [start, end) spans as u32 so a range ending at 0xFFFF fits
+        let spans: Vec<(u32, u32, &RegisterAccess)> = accesses
+            .iter()
+            .filter(|a| a.range.register_type == table && a.range.count > 0)
+            .map(|a| {
+                let start = u32::from(a.range.start);
+                let end = (start + u32::from(a.range.count)).min(0x1_0000);
+                (start, end, a)
+            })
+            .collect();
+        let bounds: BTreeSet<u32> = spans.iter().flat_map(|&(s, e, _)| [s, e]).collect();
+        let bounds: Vec<u32> = bounds.into_iter().collect();
+
+        let mut current: Option<RegisterRegion> = None;
+        for pair in bounds.windows(2) {
+            let (lo, hi) = (pair[0], pair[1]);
+            let (mut reads, mut writes) = (0u64, 0u64);
+            for &(s, e, access) in &spans {
+                if s <= lo && hi <= e {
+                    if access.write {
+                        writes += access.requests;
+                    } else {
+                        reads += access.requests;
+                    }
+                }
+            }
+
+            if reads == 0 && writes == 0 {
+                regions.extend(current.take());
+                continue;
+            }
+            match current.as_mut() {
+                // Segments are contiguous here; a gap would have flushed `current`
+                Some(region) if region.reads == reads && region.writes == writes => {
+                    region.end = (hi - 1) as u16;
+                }
+                _ => {
+                    regions.extend(current.take());
+                    current = Some(RegisterRegion {
+                        register_type: table,
+                        start: lo as u16,
+                        end: (hi - 1) as u16,
+                        reads,
+                        writes,
+                    });
+                }
+            }
+        }
+        regions.extend(current);
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access(
+        register_type: RegisterType,
+        start: u16,
+        count: u16,
+        write: bool,
+        requests: u64,
+    ) -> RegisterAccess {
+        RegisterAccess {
+            range: RegisterRange {
+                start,
+                count,
+                register_type,
+            },
+            write,
+            requests,
+        }
+    }
+
+    #[test]
+    fn test_overlapping_reads_and_writes_split() {
+        let map = build_register_map(&[
+            access(RegisterType::HoldingRegister, 0, 10, false, 100),
+            access(RegisterType::HoldingRegister, 5, 10, false, 50),
+            access(RegisterType::HoldingRegister, 8, 1, true, 3),
+        ]);
+        let spans: Vec<(u16, u16, u64, u64)> = map
+            .iter()
+            .map(|r| (r.start, r.end, r.reads, r.writes))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (0, 4, 100, 0),
+                (5, 7, 150, 0),
+                (8, 8, 150, 3),
+                (9, 9, 150, 0),
+                (10, 14, 50, 0),
+            ]
+        );
+        assert_eq!(map[1].address_count(), 3);
+    }
+
+    #[test]
+    fn test_tables_kept_apart_and_gaps_preserved() {
+        let map = build_register_map(&[
+            access(RegisterType::HoldingRegister, 100, 4, false, 10),
+            access(RegisterType::Coil, 0, 16, true, 2),
+            access(RegisterType::HoldingRegister, 200, 2, false, 10),
+            access(RegisterType::HoldingRegister, 104, 4, false, 10),
+        ]);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map[0].register_type, RegisterType::Coil);
+        assert_eq!((map[0].start, map[0].end, map[0].writes), (0, 15, 2));
+        // Back-to-back requests with the same counts join into one region
+        assert_eq!((map[1].start, map[1].end, map[1].reads), (100, 107, 10));
+        assert_eq!((map[2].start, map[2].end), (200, 201));
+    }
+
+    #[test]
+    fn test_range_at_top_of_address_space() {
+        let map = build_register_map(&[
+            access(RegisterType::InputRegister, 0xFFF0, 0x20, false, 1),
+            access(RegisterType::InputRegister, 0, 0, false, 1),
+        ]);
+        assert_eq!(map.len(), 1);
+        assert_eq!((map[0].start, map[0].end), (0xFFF0, 0xFFFF));
+    }
+}
@@ -15,6 +15,9 @@ const COTP_OFFSET: usize = 4;
 /// S7 protocol identifier (first byte of S7 header).
 const S7_PROTOCOL_ID: u8 = 0x32;
 
+/// S7comm-plus protocol identifier (S7-1200/1500 with TIA Portal).
+const S7_PLUS_PROTOCOL_ID: u8 = 0x72;
+
 /// COTP PDU type byte.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -270,6 +273,35 @@ fn make_cotp_only_info(cotp_pdu_type: CotpPduType, cotp_params: Option<CotpParam
     }
 }
 
+/// Whether a payload on an unrecognized port is S7comm over ISO-on-TCP.
+///
+/// Requires a TPKT header whose length fits the payload and either a COTP
+/// connection request/confirm or a DT TPDU carrying an S7 (or S7comm-plus)
+/// header. Check `mms::is_mms` first: MMS uses the same TPKT/COTP framing.
+pub fn is_s7comm(payload: &[u8]) -> bool {
+    if payload.len() < 7 || payload[0] != 0x03 || payload[1] != 0x00 {
+        return false;
+    }
+    let tpkt_length = u16::from_be_bytes([payload[2], payload[3]]) as usize;
+    let cotp_length = payload[COTP_OFFSET] as usize;
+    if tpkt_length < 7 || tpkt_length > payload.len() || COTP_OFFSET + 1 + cotp_length > tpkt_length
+    {
+        return false;
+    }
+    match payload[COTP_OFFSET + 1] {
+        // Fixed part of a CR/CC: type, dst-ref, src-ref, class
+        0xE0 | 0xD0 => cotp_length >= 6,
+        0xF0 => {
+            cotp_length == 2
+                && matches!(
+                    payload.get(COTP_OFFSET + 3),
+                    Some(&S7_PROTOCOL_ID) | Some(&S7_PLUS_PROTOCOL_ID)
+                )
+        }
+        _ => false,
+    }
+}
+
 /// Attempt to parse an S7comm TCP payload (TPKT + COTP + S7).
 ///
 /// Returns `None` if:
@@ -0,0 +1,399 @@
+//! Serial device server tunnel detection (Moxa NPort, Lantronix).
+//!
+//! Serial device servers bridge RS-232/422/485 lines onto TCP so a SCADA
+//! host can poll field devices that have no Ethernet port. The gateway
+//! listens on a per-line TCP port and passes the serial bytes through
+//! unchanged, so a Modbus RTU or DNP3 serial frame shows up whole in the
+//! TCP payload — CRC and all — and the devices on the line are only
+//! visible through their addresses in those frames.
+//!
+//! Recognized tunnels, by the port the gateway listens on:
+//! - Moxa Real COM data channels (950-965), driven by the NPort driver on
+//!   the host
+//! - Moxa TCP Server mode (4001-4016)
+//! - Lantronix tunnel ports (10001-10016)
+//!
+//! In RFC 2217 mode the same ports carry Telnet framing: serial data with
+//! `0xFF` doubled, plus COM-PORT-OPTION subnegotiations that set the line's
+//! baud rate, data bits, parity and stop bits. Those settings are decoded
+//! and the data unescaped before looking for a tunneled protocol.
+
+use serde::{Deserialize, Serialize};
+
+/// Serial device server operating mode, identified by listening port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SerialGateway {
+    /// Moxa NPort Real COM mode (virtual COM port driver on the host)
+    MoxaRealCom,
+    /// Moxa NPort TCP Server mode
+    MoxaTcpServer,
+    /// Lantronix device server tunnel
+    LantronixTunnel,
+}
+
+impl SerialGateway {
+    pub fn vendor(&self) -> &'static str {
+        match self {
+            SerialGateway::MoxaRealCom | SerialGateway::MoxaTcpServer => "Moxa",
+            SerialGateway::LantronixTunnel => "Lantronix",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SerialGateway::MoxaRealCom => "Moxa NPort (Real COM)",
+            SerialGateway::MoxaTcpServer => "Moxa NPort (TCP Server)",
+            SerialGateway::LantronixTunnel => "Lantronix tunnel",
+        }
+    }
+}
+
+/// Gateway mode and 1-based serial line number for a listening port.
+pub fn gateway_port(port: u16) -> Option<(SerialGateway, u8)> {
+    match port {
+        950..=965 => Some((SerialGateway::MoxaRealCom, (port - 949) as u8)),
+        4001..=4016 => Some((SerialGateway::MoxaTcpServer, (port - 4000) as u8)),
+        10001..=10016 => Some((SerialGateway::LantronixTunnel, (port - 10000) as u8)),
+        _ => None,
+    }
+}
+
+/// How serial data is carried in the TCP stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SerialFraming {
+    /// Serial bytes as-is
+    Raw,
+    /// Telnet with the RFC 2217 COM-PORT-OPTION
+    Rfc2217,
+}
+
+/// Serial protocol found inside a tunnel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TunneledProtocol {
+    /// Modbus RTU frame with a valid CRC-16
+    ModbusRtu,
+    /// DNP3 serial link frame with a valid header CRC
+    Dnp3,
+}
+
+impl TunneledProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TunneledProtocol::ModbusRtu => "modbus_rtu",
+            TunneledProtocol::Dnp3 => "dnp3",
+        }
+    }
+}
+
+/// Serial line settings from RFC 2217 COM-PORT-OPTION subnegotiations.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComPortSettings {
+    pub baud_rate: Option<u32>,
+    /// Data bits (5-8)
+    pub data_size: Option<u8>,
+    /// RFC 2217 parity code: 1 none, 2 odd, 3 even, 4 mark, 5 space
+    pub parity: Option<u8>,
+    /// RFC 2217 stop size code: 1 one, 2 two, 3 one and a half
+    pub stop_size: Option<u8>,
+}
+
+impl ComPortSettings {
+    /// Overlay every setting present in `other` onto `self`.
+    pub fn merge(&mut self, other: &ComPortSettings) {
+        self.baud_rate = other.baud_rate.or(self.baud_rate);
+        self.data_size = other.data_size.or(self.data_size);
+        self.parity = other.parity.or(self.parity);
+        self.stop_size = other.stop_size.or(self.stop_size);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == ComPortSettings::default()
+    }
+
+    /// Conventional shorthand such as "8N1", once all three are known.
+    pub fn line_format(&self) -> Option<String> {
+        let parity = match self.parity? {
+            1 => 'N',
+            2 => 'O',
+            3 => 'E',
+            4 => 'M',
+            5 => 'S',
+            _ => return None,
+        };
+        let stop = match self.stop_size? {
+            1 => "1",
+            2 => "2",
+            3 => "1.5",
+            _ => return None,
+        };
+        Some(format!("{}{}{}", self.data_size?, parity, stop))
+    }
+
+    /// Apply one COM-PORT-OPTION subnegotiation (the bytes after option 44).
+    /// Server notifications repeat the client command code plus 100. A value
+    /// of zero asks for the current setting rather than changing it.
+    fn apply(&mut self, body: &[u8]) {
+        let Some((&command, value)) = body.split_first() else {
+            return;
+        };
+        match (command % 100, value) {
+            (1, &[a, b, c, d]) => {
+                let baud = u32::from_be_bytes([a, b, c, d]);
+                if baud != 0 {
+                    self.baud_rate = Some(baud);
+                }
+            }
+            (2, &[size]) if (5..=8).contains(&size) => self.data_size = Some(size),
+            (3, &[parity]) if (1..=5).contains(&parity) => self.parity = Some(parity),
+            (4, &[stop]) if (1..=3).contains(&stop) => self.stop_size = Some(stop),
+            _ => {}
+        }
+    }
+}
+
+/// A TCP segment of a serial tunnel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialTunnelInfo {
+    pub gateway: SerialGateway,
+    /// 1-based serial line on the gateway
+    pub channel: u8,
+    /// TCP port the gateway listens on for this line
+    pub gateway_port: u16,
+    /// True when the segment travels from the host to the gateway
+    pub to_gateway: bool,
+    pub framing: SerialFraming,
+    /// Line settings carried in this segment (RFC 2217 only)
+    pub settings: ComPortSettings,
+    /// Protocol of the serial data, when it passes that protocol's CRC check
+    pub tunneled: Option<TunneledProtocol>,
+}
+
+const IAC: u8 = 255;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const COM_PORT_OPTION: u8 = 44;
+
+/// Detect a serial tunnel segment between `src_port` and `dst_port`.
+///
+/// A port in a gateway range is not enough on its own — the same numbers
+/// are used by unrelated software — so the payload must either be a
+/// CRC-valid Modbus RTU or DNP3 frame, or carry RFC 2217 negotiation.
+pub fn detect(payload: &[u8], src_port: u16, dst_port: u16) -> Option<SerialTunnelInfo> {
+    if payload.is_empty() {
+        return None;
+    }
+    let (port, to_gateway) = if gateway_port(dst_port).is_some() {
+        (dst_port, true)
+    } else if gateway_port(src_port).is_some() {
+        (src_port, false)
+    } else {
+        return None;
+    };
+    let (gateway, channel) = gateway_port(port)?;
+
+    // Raw mode first: binary serial data may contain 0xFF on its own
+    let (framing, settings, tunneled) = match tunneled_protocol(payload) {
+        Some(protocol) => (
+            SerialFraming::Raw,
+            ComPortSettings::default(),
+            Some(protocol),
+        ),
+        None => {
+            let (data, settings, com_port) = parse_telnet(payload);
+            if !com_port {
+                return None;
+            }
+            (SerialFraming::Rfc2217, settings, tunneled_protocol(&data))
+        }
+    };
+
+    Some(SerialTunnelInfo {
+        gateway,
+        channel,
+        gateway_port: port,
+        to_gateway,
+        framing,
+        settings,
+        tunneled,
+    })
+}
+
+fn tunneled_protocol(data: &[u8]) -> Option<TunneledProtocol> {
+    if crate::dnp3::is_dnp3(data) {
+        Some(TunneledProtocol::Dnp3)
+    } else if crate::modbus::is_rtu_frame(data) {
+        Some(TunneledProtocol::ModbusRtu)
+    } else {
+        None
+    }
+}
+
+/// Split a Telnet stream into serial data and COM-PORT-OPTION settings.
+///
+/// Returns `(data, settings, saw_com_port_option)`.
+fn parse_telnet(payload: &[u8]) -> (Vec<u8>, ComPortSettings, bool) {
+    let mut data = Vec::with_capacity(payload.len());
+    let mut settings = ComPortSettings::default();
+    let mut com_port = false;
+    let mut i = 0;
+
+    while i < payload.len() {
+        if payload[i] != IAC {
+            data.push(payload[i]);
+            i += 1;
+            continue;
+        }
+        match payload.get(i + 1) {
+            // Escaped 0xFF data byte
+            Some(&IAC) => {
+                data.push(IAC);
+                i += 2;
+            }
+            // WILL / WONT / DO / DONT <option>
+            Some(251..=254) => {
+                com_port |= payload.get(i + 2) == Some(&COM_PORT_OPTION);
+                i += 3;
+            }
+            Some(&SB) => {
+                // Subnegotiation runs to IAC SE; IAC IAC inside it is a literal 0xFF
+                let mut body = Vec::new();
+                let mut j = i + 2;
+                while j < payload.len() {
+                    match (payload[j], payload.get(j + 1)) {
+                        (IAC, Some(&SE)) => break,
+                        (IAC, Some(&IAC)) => {
+                            body.push(IAC);
+                            j += 2;
+                        }
+                        (b, _) => {
+                            body.push(b);
+                            j += 1;
+                        }
+                    }
+                }
+                if body.first() == Some(&COM_PORT_OPTION) {
+                    com_port = true;
+                    settings.apply(&body[1..]);
+                }
+                i = j + 2;
+            }
+            // Two-byte commands (NOP, GA, ...) or a trailing IAC
+            _ => i += 2,
+        }
+    }
+
+    (data, settings, com_port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Read Holding Registers: slave 1, address 0, count 10
+    const RTU_REQUEST: [u8; 8] = [0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD];
+
+    #[test]
+    fn test_gateway_ports() {
+        assert_eq!(gateway_port(950), Some((SerialGateway::MoxaRealCom, 1)));
+        assert_eq!(gateway_port(4002), Some((SerialGateway::MoxaTcpServer, 2)));
+        assert_eq!(
+            gateway_port(10001),
+            Some((SerialGateway::LantronixTunnel, 1))
+        );
+        assert_eq!(gateway_port(4017), None);
+        assert_eq!(gateway_port(502), None);
+    }
+
+    #[test]
+    fn test_raw_modbus_rtu_and_dnp3() {
+        let info = detect(&RTU_REQUEST, 49152, 4001).unwrap();
+        assert_eq!(info.gateway, SerialGateway::MoxaTcpServer);
+        assert_eq!(info.channel, 1);
+        assert!(info.to_gateway);
+        assert_eq!(info.framing, SerialFraming::Raw);
+        assert_eq!(info.tunneled, Some(TunneledProtocol::ModbusRtu));
+
+        // Reset Link States with a valid header CRC, answered from the gateway
+        let dnp3 = [0x05, 0x64, 0x05, 0xC0, 0x01, 0x00, 0x00, 0x04, 0xE9, 0x21];
+        let info = detect(&dnp3, 10001, 49152).unwrap();
+        assert_eq!(info.gateway, SerialGateway::LantronixTunnel);
+        assert!(!info.to_gateway);
+        assert_eq!(info.tunneled, Some(TunneledProtocol::Dnp3));
+    }
+
+    #[test]
+    fn test_rejects_unframed_payloads() {
+        // Right port, but nothing that checks out
+        assert!(detect(b"hello", 49152, 4001).is_none());
+        // CRC-valid RTU frame on a port no gateway uses
+        assert!(detect(&RTU_REQUEST, 49152, 5000).is_none());
+        // Plain Telnet echo negotiation is not RFC 2217
+        assert!(detect(&[IAC, 251, 1], 49152, 4001).is_none());
+    }
+
+    #[test]
+    fn test_rfc2217_settings_and_escaped_data() {
+        let mut payload = vec![
+            IAC,
+            251,
+            COM_PORT_OPTION, // WILL COM-PORT-OPTION
+            IAC,
+            SB,
+            COM_PORT_OPTION,
+            1,
+            0x00,
+            0x00,
+            0x25,
+            0x80,
+            IAC,
+            SE, // 9600 baud
+            IAC,
+            SB,
+            COM_PORT_OPTION,
+            2,
+            8,
+            IAC,
+            SE, // 8 data bits
+            IAC,
+            SB,
+            COM_PORT_OPTION,
+            103,
+            3,
+            IAC,
+            SE, // server: even parity
+            IAC,
+            SB,
+            COM_PORT_OPTION,
+            4,
+            1,
+            IAC,
+            SE, // 1 stop bit
+        ];
+        payload.extend_from_slice(&RTU_REQUEST);
+
+        let info = detect(&payload, 49152, 4001).unwrap();
+        assert_eq!(info.framing, SerialFraming::Rfc2217);
+        assert_eq!(info.settings.baud_rate, Some(9600));
+        assert_eq!(info.settings.line_format().as_deref(), Some("8E1"));
+        // With the negotiation stripped, the rest is the RTU request
+        assert_eq!(info.tunneled, Some(TunneledProtocol::ModbusRtu));
+
+        // An RTU response whose data holds 0xFF, escaped by Telnet
+        let response = [0x01, 0x03, 0x02, 0xFF, 0x00, 0xF9, 0xB4];
+        let mut escaped = vec![IAC, 253, COM_PORT_OPTION];
+        for &b in &response {
+            escaped.push(b);
+            if b == IAC {
+                escaped.push(IAC);
+            }
+        }
+        let (data, _, com_port) = parse_telnet(&escaped);
+        assert!(com_port);
+        assert_eq!(data, response);
+        let info = detect(&escaped, 4001, 49152).unwrap();
+        assert_eq!(info.tunneled, Some(TunneledProtocol::ModbusRtu));
+    }
+}
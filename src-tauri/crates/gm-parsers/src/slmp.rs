@@ -0,0 +1,481 @@
+//! Mitsubishi MELSEC communication (MC) protocol / SLMP deep parser.
+//!
+//! SLMP (Seamless Message Protocol) is the CC-Link IE name for the MC
+//! protocol binary 3E/4E frames used by MELSEC Q, L, iQ-R and iQ-F PLCs.
+//! Only binary frames are parsed; ASCII-coded frames are left to port
+//! identification.
+//!
+//! Reference: Mitsubishi SH(NA)-080956ENG "SLMP Reference Manual"
+//! Ports: 5007 TCP, 5006 UDP (defaults; user-configurable on the PLC)
+//!
+//! 3E request (little-endian):
+//!   [0..2]   Subheader 0x50 0x00
+//!   [2]      Network No.   [3] PC No.
+//!   [4..6]   Request destination module I/O No. (0x03FF = own station CPU)
+//!   [6]      Request destination module station No.
+//!   [7..9]   Request data length (from the monitoring timer onward)
+//!   [9..11]  Monitoring timer
+//!   [11..13] Command   [13..15] Subcommand   [15..] Request data
+//!
+//! 3E response: subheader 0xD0 0x00, same routing fields, data length,
+//! then a 2-byte end code and the response data. Responses do not echo
+//! the command, so callers pair them with requests.
+//!
+//! 4E frames use subheader 0x54 0x00 / 0xD4 0x00 followed by a 2-byte
+//! serial number and 2 reserved bytes, then the 3E layout from Network No.
+
+use serde::{Deserialize, Serialize};
+
+/// 3E request subheader.
+const SUBHEADER_3E_REQUEST: [u8; 2] = [0x50, 0x00];
+/// 3E response subheader.
+const SUBHEADER_3E_RESPONSE: [u8; 2] = [0xD0, 0x00];
+/// 4E request subheader.
+const SUBHEADER_4E_REQUEST: [u8; 2] = [0x54, 0x00];
+/// 4E response subheader.
+const SUBHEADER_4E_RESPONSE: [u8; 2] = [0xD4, 0x00];
+
+/// Read CPU Model Name command.
+pub const READ_CPU_MODEL: u16 = 0x0101;
+
+/// Width of the model name in a Read CPU Model Name response.
+const CPU_MODEL_NAME_LEN: usize = 16;
+
+// ─── Enums ────────────────────────────────────────────────────────────────────
+
+/// MC protocol frame type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlmpFrame {
+    /// 3E frame (no serial number)
+    ThreeE,
+    /// 4E frame (serial number pairs requests and responses)
+    FourE,
+}
+
+/// Client/server role for an SLMP device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlmpRole {
+    /// Device is sending requests (HMI, SCADA, GX Works)
+    Client,
+    /// Device is answering requests (MELSEC CPU or Ethernet module)
+    Server,
+}
+
+// ─── Structs ──────────────────────────────────────────────────────────────────
+
+/// Device range addressed by a Batch Read/Write (0401/1401) request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlmpDeviceAccess {
+    /// Device mnemonic, e.g. "D", "M", "X" (hex code if unrecognized)
+    pub device: String,
+    /// Head device number
+    pub head: u32,
+    /// Number of device points
+    pub points: u16,
+}
+
+/// CPU identity from a Read CPU Model Name (0101) response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlmpCpuModel {
+    /// Model name, e.g. "Q03UDVCPU", "R04CPU"
+    pub model: String,
+    /// Model code, e.g. 0x4800
+    pub model_code: u16,
+}
+
+/// Parsed MC protocol / SLMP frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlmpInfo {
+    pub frame: SlmpFrame,
+    pub is_response: bool,
+    /// 4E serial number used to pair requests and responses
+    pub serial: Option<u16>,
+    pub network: u8,
+    pub pc: u8,
+    /// Request destination module I/O number (0x03FF = own station CPU)
+    pub module_io: u16,
+    pub station: u8,
+    /// Command (requests only)
+    pub command: Option<u16>,
+    /// Subcommand (requests only)
+    pub subcommand: Option<u16>,
+    /// End code (responses only, 0 = normal completion)
+    pub end_code: Option<u16>,
+    /// Device range for batch read/write requests
+    pub device_access: Option<SlmpDeviceAccess>,
+    /// Response data following the end code (responses only)
+    #[serde(skip)]
+    pub response_data: Vec<u8>,
+    /// True when the request writes devices, buffer memory or files
+    pub is_write: bool,
+    /// True when the request changes CPU state (remote RUN/STOP/RESET, ...)
+    pub is_control: bool,
+    pub role: SlmpRole,
+}
+
+// ─── Parse Functions ──────────────────────────────────────────────────────────
+
+/// Check for a binary 3E/4E subheader with a consistent data length.
+pub fn is_slmp(payload: &[u8]) -> bool {
+    parse(payload).is_some()
+}
+
+/// Attempt to parse a binary MC protocol 3E/4E frame.
+///
+/// Returns None if the subheader is not 3E/4E or the data length field
+/// disagrees with the payload.
+pub fn parse(payload: &[u8]) -> Option<SlmpInfo> {
+    if payload.len() < 2 {
+        return None;
+    }
+    let subheader = [payload[0], payload[1]];
+    let (frame, is_response) = match subheader {
+        SUBHEADER_3E_REQUEST => (SlmpFrame::ThreeE, false),
+        SUBHEADER_3E_RESPONSE => (SlmpFrame::ThreeE, true),
+        SUBHEADER_4E_REQUEST => (SlmpFrame::FourE, false),
+        SUBHEADER_4E_RESPONSE => (SlmpFrame::FourE, true),
+        _ => return None,
+    };
+
+    let (serial, body) = match frame {
+        SlmpFrame::ThreeE => (None, &payload[2..]),
+        SlmpFrame::FourE => {
+            if payload.len() < 6 || payload[4] != 0 || payload[5] != 0 {
+                return None;
+            }
+            (Some(le_u16(&payload[2..4])), &payload[6..])
+        }
+    };
+
+    // Routing (5) + data length (2) + end code or monitoring timer (2)
+    if body.len() < 9 {
+        return None;
+    }
+    let data_len = le_u16(&body[5..7]) as usize;
+    let data = &body[7..];
+    if data_len < 2 || data.len() < data_len {
+        return None;
+    }
+    let data = &data[..data_len];
+
+    let mut info = SlmpInfo {
+        frame,
+        is_response,
+        serial,
+        network: body[0],
+        pc: body[1],
+        module_io: le_u16(&body[2..4]),
+        station: body[4],
+        command: None,
+        subcommand: None,
+        end_code: None,
+        device_access: None,
+        response_data: Vec::new(),
+        is_write: false,
+        is_control: false,
+        role: if is_response {
+            SlmpRole::Server
+        } else {
+            SlmpRole::Client
+        },
+    };
+
+    if is_response {
+        info.end_code = Some(le_u16(&data[..2]));
+        info.response_data = data[2..].to_vec();
+    } else {
+        // Monitoring timer (2) + command (2) + subcommand (2)
+        if data.len() < 6 {
+            return None;
+        }
+        let command = le_u16(&data[2..4]);
+        let subcommand = le_u16(&data[4..6]);
+        info.command = Some(command);
+        info.subcommand = Some(subcommand);
+        info.is_write = is_write_command(command);
+        info.is_control = is_control_command(command);
+        if matches!(command, 0x0401 | 0x1401) {
+            info.device_access = parse_device_access(subcommand, &data[6..]);
+        }
+    }
+
+    Some(info)
+}
+
+/// Parse the head device / device code / points of a batch request.
+fn parse_device_access(subcommand: u16, data: &[u8]) -> Option<SlmpDeviceAccess> {
+    // Subcommands 0002/0003 (iQ-R) widen the device number and code
+    let (head, code, points_at) = if subcommand & 0x0002 != 0 {
+        if data.len() < 8 {
+            return None;
+        }
+        (le_u32(&data[..4]), le_u16(&data[4..6]), 6)
+    } else {
+        if data.len() < 6 {
+            return None;
+        }
+        let head = u32::from_le_bytes([data[0], data[1], data[2], 0]);
+        (head, data[3] as u16, 4)
+    };
+    Some(SlmpDeviceAccess {
+        device: device_code_name(code)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("0x{:02X}", code)),
+        head,
+        points: le_u16(&data[points_at..points_at + 2]),
+    })
+}
+
+/// Decode the response data of a Read CPU Model Name (0101) request.
+///
+/// The caller must have paired the response with a 0101 request, since
+/// MC protocol responses do not carry the command code.
+pub fn parse_cpu_model(response_data: &[u8]) -> Option<SlmpCpuModel> {
+    if response_data.len() < CPU_MODEL_NAME_LEN + 2 {
+        return None;
+    }
+    let name = &response_data[..CPU_MODEL_NAME_LEN];
+    if !name
+        .iter()
+        .all(|b| b.is_ascii_graphic() || *b == b' ' || *b == 0)
+    {
+        return None;
+    }
+    let model = String::from_utf8_lossy(name)
+        .trim_end_matches(['\0', ' '])
+        .to_string();
+    (!model.is_empty()).then(|| SlmpCpuModel {
+        model,
+        model_code: le_u16(&response_data[CPU_MODEL_NAME_LEN..CPU_MODEL_NAME_LEN + 2]),
+    })
+}
+
+fn le_u16(b: &[u8]) -> u16 {
+    u16::from_le_bytes([b[0], b[1]])
+}
+
+fn le_u32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+// ─── Command / Device Classification ──────────────────────────────────────────
+
+/// Commands that write devices, buffer memory, module memory or files.
+pub fn is_write_command(command: u16) -> bool {
+    matches!(
+        command,
+        0x1401 // Batch Write
+            | 0x1402 // Random Write
+            | 0x1406 // Multiple Block Batch Write
+            | 0x1601 // Intelligent Module Write
+            | 0x1613 // Buffer Memory Write
+            | 0x1820 // New File
+            | 0x1822 // Delete File
+            | 0x1829 // Write File
+    )
+}
+
+/// Commands that change the CPU operating state.
+pub fn is_control_command(command: u16) -> bool {
+    matches!(
+        command,
+        0x1001 // Remote RUN
+            | 0x1002 // Remote STOP
+            | 0x1003 // Remote PAUSE
+            | 0x1005 // Remote Latch Clear
+            | 0x1006 // Remote RESET
+    )
+}
+
+/// Human-readable name for an MC protocol / SLMP command.
+pub fn command_name(command: u16) -> &'static str {
+    match command {
+        0x0101 => "Read CPU Model Name",
+        0x0401 => "Batch Read",
+        0x0403 => "Random Read",
+        0x0406 => "Multiple Block Batch Read",
+        0x0601 => "Intelligent Module Read",
+        0x0613 => "Buffer Memory Read",
+        0x0619 => "Loopback Test",
+        0x0801 => "Monitor Register",
+        0x0802 => "Monitor",
+        0x1001 => "Remote RUN",
+        0x1002 => "Remote STOP",
+        0x1003 => "Remote PAUSE",
+        0x1005 => "Remote Latch Clear",
+        0x1006 => "Remote RESET",
+        0x1401 => "Batch Write",
+        0x1402 => "Random Write",
+        0x1406 => "Multiple Block Batch Write",
+        0x1601 => "Intelligent Module Write",
+        0x1613 => "Buffer Memory Write",
+        0x1617 => "Clear Error",
+        0x1630 => "Remote Unlock",
+        0x1631 => "Remote Lock",
+        0x1810 => "Read Directory/File",
+        0x1811 => "Search Directory/File",
+        0x1820 => "New File",
+        0x1822 => "Delete File",
+        0x1827 => "Open File",
+        0x1828 => "Read File",
+        0x1829 => "Write File",
+        0x182A => "Close File",
+        _ => "Unknown",
+    }
+}
+
+/// Device mnemonic for a binary device code.
+pub fn device_code_name(code: u16) -> Option<&'static str> {
+    Some(match code {
+        0x91 => "SM",
+        0xA9 => "SD",
+        0x9C => "X",
+        0x9D => "Y",
+        0x90 => "M",
+        0x92 => "L",
+        0x93 => "F",
+        0x94 => "V",
+        0xA0 => "B",
+        0xA8 => "D",
+        0xB4 => "W",
+        0xC1 => "TS",
+        0xC0 => "TC",
+        0xC2 => "TN",
+        0xC7 => "SS",
+        0xC6 => "SC",
+        0xC8 => "SN",
+        0xC4 => "CS",
+        0xC3 => "CC",
+        0xC5 => "CN",
+        0xA1 => "SB",
+        0xB5 => "SW",
+        0x98 => "S",
+        0xA2 => "DX",
+        0xA3 => "DY",
+        0xCC => "Z",
+        0xAF => "R",
+        0xB0 => "ZR",
+        _ => return None,
+    })
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wrap request data (timer + command + subcommand + data) in a 3E header.
+    fn request_3e(command: u16, subcommand: u16, data: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x10, 0x00]; // monitoring timer 16 × 250 ms
+        body.extend_from_slice(&command.to_le_bytes());
+        body.extend_from_slice(&subcommand.to_le_bytes());
+        body.extend_from_slice(data);
+        let mut out = vec![0x50, 0x00, 0x00, 0xFF, 0xFF, 0x03, 0x00];
+        out.extend_from_slice(&(body.len() as u16).to_le_bytes());
+        out.extend(body);
+        out
+    }
+
+    fn response_3e(end_code: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0xD0, 0x00, 0x00, 0xFF, 0xFF, 0x03, 0x00];
+        out.extend_from_slice(&(2 + data.len() as u16).to_le_bytes());
+        out.extend_from_slice(&end_code.to_le_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    #[test]
+    fn test_batch_read_d_registers() {
+        // D100, 10 points
+        let frame = request_3e(0x0401, 0x0000, &[0x64, 0x00, 0x00, 0xA8, 0x0A, 0x00]);
+        let info = parse(&frame).unwrap();
+        assert_eq!(info.frame, SlmpFrame::ThreeE);
+        assert!(!info.is_response);
+        assert_eq!(info.pc, 0xFF);
+        assert_eq!(info.module_io, 0x03FF);
+        assert_eq!(info.command, Some(0x0401));
+        assert_eq!(info.role, SlmpRole::Client);
+        assert!(!info.is_write);
+        assert_eq!(
+            info.device_access,
+            Some(SlmpDeviceAccess {
+                device: "D".to_string(),
+                head: 100,
+                points: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn test_iqr_batch_write_flagged() {
+        // Subcommand 0003: 4-byte device number, 2-byte device code
+        let frame = request_3e(
+            0x1401,
+            0x0003,
+            &[0x00, 0x10, 0x00, 0x00, 0x90, 0x00, 0x01, 0x00, 0x01],
+        );
+        let info = parse(&frame).unwrap();
+        assert!(info.is_write);
+        let access = info.device_access.unwrap();
+        assert_eq!(access.device, "M");
+        assert_eq!(access.head, 0x1000);
+        assert_eq!(access.points, 1);
+    }
+
+    #[test]
+    fn test_remote_stop_flagged() {
+        let frame = request_3e(0x1002, 0x0000, &[0x01, 0x00]);
+        let info = parse(&frame).unwrap();
+        assert!(info.is_control);
+        assert_eq!(command_name(0x1002), "Remote STOP");
+    }
+
+    #[test]
+    fn test_cpu_model_response() {
+        let mut data = b"Q03UDVCPU       ".to_vec();
+        data.extend_from_slice(&0x0366u16.to_le_bytes());
+        let info = parse(&response_3e(0, &data)).unwrap();
+        assert!(info.is_response);
+        assert_eq!(info.end_code, Some(0));
+        assert_eq!(info.role, SlmpRole::Server);
+        assert_eq!(
+            parse_cpu_model(&info.response_data),
+            Some(SlmpCpuModel {
+                model: "Q03UDVCPU".to_string(),
+                model_code: 0x0366,
+            })
+        );
+    }
+
+    #[test]
+    fn test_4e_serial_number() {
+        let mut frame = vec![0x54, 0x00, 0x34, 0x12, 0x00, 0x00];
+        frame.extend_from_slice(&request_3e(0x0101, 0x0000, &[])[2..]);
+        let info = parse(&frame).unwrap();
+        assert_eq!(info.frame, SlmpFrame::FourE);
+        assert_eq!(info.serial, Some(0x1234));
+        assert_eq!(info.command, Some(READ_CPU_MODEL));
+
+        let mut response = vec![0xD4, 0x00, 0x34, 0x12, 0x00, 0x00];
+        response.extend_from_slice(&response_3e(0xC059, &[])[2..]);
+        let info = parse(&response).unwrap();
+        assert_eq!(info.serial, Some(0x1234));
+        assert_eq!(info.end_code, Some(0xC059));
+    }
+
+    #[test]
+    fn test_rejects_non_slmp() {
+        assert!(!is_slmp(&[]));
+        // SuiteLink-style payload
+        assert!(!is_slmp(&[0x0B, 0x00, 0x01, 0x00, 0x00, 0x00]));
+        // ASCII 3E frame is not parsed
+        assert!(!is_slmp(b"500000FF03FF000018001004010000D*0001000001"));
+        // Data length longer than the payload
+        let mut frame = request_3e(0x0401, 0x0000, &[0x64, 0x00, 0x00, 0xA8, 0x0A, 0x00]);
+        frame.truncate(frame.len() - 3);
+        assert!(parse(&frame).is_none());
+    }
+}
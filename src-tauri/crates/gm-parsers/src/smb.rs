@@ -0,0 +1,464 @@
+//! SMB2/3 file sharing parser.
+//!
+//! File shares are the most common way tools, project files and malware
+//! cross from the business network into the control network (and data
+//! back out), so the parser extracts just enough to say who authenticated,
+//! which shares were mounted and which files were opened or written:
+//!
+//! - **Session Setup** requests: the NTLMSSP AUTHENTICATE message inside
+//!   the security blob carries the domain, user and workstation names.
+//! - **Tree Connect** requests: the UNC path of the share (`\\host\share`).
+//! - **Create** requests: the file name relative to the share, and whether
+//!   it was opened with write access.
+//! - **Write** requests and **Read** responses: data lengths.
+//!
+//! Messages are carried in NetBIOS session frames (4-byte header, 24-bit
+//! big-endian length) on TCP 445 or 139, and may be compounded via the
+//! NextCommand field. All SMB2 integers are little-endian; header layout:
+//!
+//! ```text
+//! [0..4]   ProtocolId FE 'S' 'M' 'B'   [4..6]   StructureSize (64)
+//! [8..12]  Status                      [12..14] Command
+//! [16..20] Flags (0x1 = response)      [20..24] NextCommand
+//! [24..32] MessageId                   [36..40] TreeId
+//! [40..48] SessionId                   [48..64] Signature
+//! ```
+//!
+//! SMB1 is only recognised, not decoded. Encrypted SMB3 traffic (transform
+//! header `FD 'S' 'M' 'B'`) carries no readable operations.
+
+use serde::{Deserialize, Serialize};
+
+/// SMB ports: direct-hosted SMB (445) and NetBIOS session service (139).
+pub const SMB_PORTS: [u16; 2] = [445, 139];
+
+const NBSS_HEADER_LEN: usize = 4;
+const SMB2_HEADER_LEN: usize = 64;
+const SMB2_MAGIC: [u8; 4] = [0xFE, b'S', b'M', b'B'];
+const SMB1_MAGIC: [u8; 4] = [0xFF, b'S', b'M', b'B'];
+const SMB2_FLAGS_RESPONSE: u32 = 0x0000_0001;
+
+/// Access mask bits that allow modifying a file's contents.
+const WRITE_ACCESS_MASK: u32 = 0x0000_0002 // FILE_WRITE_DATA
+    | 0x0000_0004 // FILE_APPEND_DATA
+    | 0x1000_0000 // GENERIC_ALL
+    | 0x4000_0000; // GENERIC_WRITE
+
+// ─── Enums ────────────────────────────────────────────────────────────────────
+
+/// Client/server role for an SMB host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmbRole {
+    /// Host mounting shares
+    Client,
+    /// File server answering on 445/139
+    Server,
+}
+
+/// SMB2 command code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Smb2Command {
+    Negotiate,
+    SessionSetup,
+    Logoff,
+    TreeConnect,
+    TreeDisconnect,
+    Create,
+    Close,
+    Read,
+    Write,
+    Ioctl,
+    QueryDirectory,
+    QueryInfo,
+    SetInfo,
+    Other(u16),
+}
+
+impl Smb2Command {
+    pub fn from_u16(code: u16) -> Self {
+        match code {
+            0x00 => Smb2Command::Negotiate,
+            0x01 => Smb2Command::SessionSetup,
+            0x02 => Smb2Command::Logoff,
+            0x03 => Smb2Command::TreeConnect,
+            0x04 => Smb2Command::TreeDisconnect,
+            0x05 => Smb2Command::Create,
+            0x06 => Smb2Command::Close,
+            0x08 => Smb2Command::Read,
+            0x09 => Smb2Command::Write,
+            0x0B => Smb2Command::Ioctl,
+            0x0E => Smb2Command::QueryDirectory,
+            0x10 => Smb2Command::QueryInfo,
+            0x11 => Smb2Command::SetInfo,
+            other => Smb2Command::Other(other),
+        }
+    }
+}
+
+// ─── Structs ──────────────────────────────────────────────────────────────────
+
+/// Account names from an NTLMSSP AUTHENTICATE message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NtlmIdentity {
+    pub domain: String,
+    pub user: String,
+    pub workstation: String,
+}
+
+impl NtlmIdentity {
+    /// Account in `DOMAIN\user` form (just `user` without a domain).
+    pub fn account(&self) -> String {
+        if self.domain.is_empty() {
+            self.user.clone()
+        } else {
+            format!("{}\\{}", self.domain, self.user)
+        }
+    }
+}
+
+/// One SMB2 message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Smb2Message {
+    pub command: Smb2Command,
+    pub is_response: bool,
+    pub status: u32,
+    pub message_id: u64,
+    pub tree_id: u32,
+    pub session_id: u64,
+    /// NTLM account from a Session Setup request
+    pub identity: Option<NtlmIdentity>,
+    /// UNC share path from a Tree Connect request
+    pub share_path: Option<String>,
+    /// File name from a Create request (relative to the share)
+    pub file_name: Option<String>,
+    /// Create request asked for write access or creates/overwrites the file
+    pub write_access: bool,
+    /// Data length of a Write request or Read response
+    pub data_length: u32,
+}
+
+/// Parsed SMB segment information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmbInfo {
+    pub role: SmbRole,
+    /// Segment carries SMB1 rather than SMB2/3
+    pub smb1: bool,
+    pub messages: Vec<Smb2Message>,
+}
+
+// ─── Parser ───────────────────────────────────────────────────────────────────
+
+/// Attempt to parse an SMB segment.
+///
+/// Returns None unless the payload starts with a NetBIOS session frame
+/// holding an SMB1 or SMB2 header. A frame that continues past the end of
+/// the segment (e.g. a large Write) is parsed as far as it goes.
+pub fn parse(payload: &[u8], src_port: u16, dst_port: u16) -> Option<SmbInfo> {
+    let role = if SMB_PORTS.contains(&src_port) && !SMB_PORTS.contains(&dst_port) {
+        SmbRole::Server
+    } else {
+        SmbRole::Client
+    };
+
+    let mut info = SmbInfo {
+        role,
+        smb1: false,
+        messages: Vec::new(),
+    };
+
+    let mut pos = 0;
+    while pos + NBSS_HEADER_LEN + 4 <= payload.len() && payload[pos] == 0x00 {
+        let len =
+            u32::from_be_bytes([0, payload[pos + 1], payload[pos + 2], payload[pos + 3]]) as usize;
+        let start = pos + NBSS_HEADER_LEN;
+        let end = (start + len).min(payload.len());
+        let frame = &payload[start..end];
+
+        if frame.starts_with(&SMB1_MAGIC) {
+            info.smb1 = true;
+        } else if frame.starts_with(&SMB2_MAGIC) {
+            parse_chain(frame, &mut info.messages);
+        } else {
+            break;
+        }
+        pos = start + len;
+    }
+
+    (info.smb1 || !info.messages.is_empty()).then_some(info)
+}
+
+/// Parse a (possibly compounded) chain of SMB2 messages.
+fn parse_chain(frame: &[u8], messages: &mut Vec<Smb2Message>) {
+    let mut offset = 0;
+    while offset + SMB2_HEADER_LEN <= frame.len() {
+        let msg = &frame[offset..];
+        if !msg.starts_with(&SMB2_MAGIC) || le_u16(&msg[4..]) as usize != SMB2_HEADER_LEN {
+            return;
+        }
+        messages.push(parse_message(msg));
+        let next = le_u32(&msg[20..]) as usize;
+        if next < SMB2_HEADER_LEN {
+            return;
+        }
+        offset += next;
+    }
+}
+
+fn parse_message(msg: &[u8]) -> Smb2Message {
+    let command = Smb2Command::from_u16(le_u16(&msg[12..]));
+    let is_response = le_u32(&msg[16..]) & SMB2_FLAGS_RESPONSE != 0;
+    let mut message = Smb2Message {
+        command,
+        is_response,
+        status: le_u32(&msg[8..]),
+        message_id: le_u64(&msg[24..]),
+        tree_id: le_u32(&msg[36..]),
+        session_id: le_u64(&msg[40..]),
+        identity: None,
+        share_path: None,
+        file_name: None,
+        write_access: false,
+        data_length: 0,
+    };
+    let body = &msg[SMB2_HEADER_LEN..];
+
+    match (command, is_response) {
+        (Smb2Command::SessionSetup, false) if body.len() >= 16 => {
+            let blob = slice_at(msg, le_u16(&body[12..]), le_u16(&body[14..]));
+            message.identity = blob.and_then(ntlm_authenticate);
+        }
+        (Smb2Command::TreeConnect, false) if body.len() >= 8 => {
+            message.share_path = slice_at(msg, le_u16(&body[4..]), le_u16(&body[6..]))
+                .map(utf16le)
+                .filter(|p| !p.is_empty());
+        }
+        (Smb2Command::Create, false) if body.len() >= 48 => {
+            let access = le_u32(&body[24..]);
+            let disposition = le_u32(&body[36..]);
+            // Supersede, Create, Overwrite, OverwriteIf all write the file
+            message.write_access =
+                access & WRITE_ACCESS_MASK != 0 || matches!(disposition, 0 | 2 | 4 | 5);
+            message.file_name = slice_at(msg, le_u16(&body[44..]), le_u16(&body[46..]))
+                .map(utf16le)
+                .filter(|n| !n.is_empty());
+        }
+        (Smb2Command::Write, false) | (Smb2Command::Read, true) if body.len() >= 8 => {
+            message.data_length = le_u32(&body[4..]);
+        }
+        _ => {}
+    }
+
+    message
+}
+
+/// Extract the account names from an NTLMSSP AUTHENTICATE (type 3) message
+/// somewhere inside a SPNEGO security blob.
+fn ntlm_authenticate(blob: &[u8]) -> Option<NtlmIdentity> {
+    const SIGNATURE: &[u8] = b"NTLMSSP\0";
+    const NEGOTIATE_UNICODE: u32 = 0x0000_0001;
+
+    let start = blob.windows(SIGNATURE.len()).position(|w| w == SIGNATURE)?;
+    let ntlm = &blob[start..];
+    if ntlm.len() < 64 || le_u32(&ntlm[8..]) != 3 {
+        return None;
+    }
+    let unicode = le_u32(&ntlm[60..]) & NEGOTIATE_UNICODE != 0;
+    let field = |at: usize| -> String {
+        let len = le_u16(&ntlm[at..]) as usize;
+        let offset = le_u32(&ntlm[at + 4..]) as usize;
+        match ntlm.get(offset..offset + len) {
+            Some(raw) if unicode => utf16le(raw),
+            Some(raw) => String::from_utf8_lossy(raw).into_owned(),
+            None => String::new(),
+        }
+    };
+
+    let identity = NtlmIdentity {
+        domain: field(28),
+        user: field(36),
+        workstation: field(44),
+    };
+    // Anonymous / null sessions carry no user
+    (!identity.user.is_empty()).then_some(identity)
+}
+
+/// Slice `len` bytes at `offset` from the start of the SMB2 header.
+fn slice_at(msg: &[u8], offset: u16, len: u16) -> Option<&[u8]> {
+    let offset = offset as usize;
+    msg.get(offset..offset + len as usize)
+}
+
+fn utf16le(raw: &[u8]) -> String {
+    let units: Vec<u16> = raw
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+fn le_u16(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn le_u64(bytes: &[u8]) -> u64 {
+    let mut b = [0u8; 8];
+    b.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(|u| u.to_le_bytes()).collect()
+    }
+
+    fn header(command: u16, flags: u32, message_id: u64, tree_id: u32) -> Vec<u8> {
+        let mut h = SMB2_MAGIC.to_vec();
+        h.extend_from_slice(&64u16.to_le_bytes());
+        h.extend_from_slice(&[0; 2]); // credit charge
+        h.extend_from_slice(&0u32.to_le_bytes()); // status
+        h.extend_from_slice(&command.to_le_bytes());
+        h.extend_from_slice(&[1, 0]); // credits
+        h.extend_from_slice(&flags.to_le_bytes());
+        h.extend_from_slice(&0u32.to_le_bytes()); // next command
+        h.extend_from_slice(&message_id.to_le_bytes());
+        h.extend_from_slice(&0u32.to_le_bytes()); // process id
+        h.extend_from_slice(&tree_id.to_le_bytes());
+        h.extend_from_slice(&0x1122u64.to_le_bytes()); // session id
+        h.extend_from_slice(&[0; 16]); // signature
+        h
+    }
+
+    fn nbss(messages: &[u8]) -> Vec<u8> {
+        let len = (messages.len() as u32).to_be_bytes();
+        let mut f = vec![0, len[1], len[2], len[3]];
+        f.extend_from_slice(messages);
+        f
+    }
+
+    fn tree_connect(path: &str) -> Vec<u8> {
+        let path = utf16(path);
+        let mut m = header(0x03, 0, 4, 0);
+        m.extend_from_slice(&9u16.to_le_bytes());
+        m.extend_from_slice(&[0; 2]);
+        m.extend_from_slice(&72u16.to_le_bytes());
+        m.extend_from_slice(&(path.len() as u16).to_le_bytes());
+        m.extend(path);
+        m
+    }
+
+    fn create(name: &str, access: u32, disposition: u32) -> Vec<u8> {
+        let name = utf16(name);
+        let mut body = vec![0u8; 56];
+        body[0..2].copy_from_slice(&57u16.to_le_bytes());
+        body[24..28].copy_from_slice(&access.to_le_bytes());
+        body[36..40].copy_from_slice(&disposition.to_le_bytes());
+        body[44..46].copy_from_slice(&120u16.to_le_bytes());
+        body[46..48].copy_from_slice(&(name.len() as u16).to_le_bytes());
+        let mut m = header(0x05, 0, 5, 7);
+        m.extend(body);
+        m.extend(name);
+        m
+    }
+
+    #[test]
+    fn test_tree_connect_and_compound_create() {
+        let info = parse(&nbss(&tree_connect("\\\\hist01\\exports")), 50000, 445).unwrap();
+        assert_eq!(info.role, SmbRole::Client);
+        assert_eq!(
+            info.messages[0].share_path.as_deref(),
+            Some("\\\\hist01\\exports")
+        );
+
+        // Compounded Create (write) + Write on tree 7
+        let mut first = create("tools\\update.exe", 0x0012_0116, 5);
+        first.resize(first.len().next_multiple_of(8), 0);
+        let next = first.len() as u32;
+        first[20..24].copy_from_slice(&next.to_le_bytes());
+        let mut write = header(0x09, 0, 6, 7);
+        write.extend_from_slice(&49u16.to_le_bytes());
+        write.extend_from_slice(&112u16.to_le_bytes());
+        write.extend_from_slice(&65536u32.to_le_bytes());
+        write.extend_from_slice(&[0; 8]);
+        first.extend(write);
+
+        let info = parse(&nbss(&first), 50000, 445).unwrap();
+        assert_eq!(info.messages.len(), 2);
+        let open = &info.messages[0];
+        assert_eq!(open.command, Smb2Command::Create);
+        assert_eq!(open.file_name.as_deref(), Some("tools\\update.exe"));
+        assert!(open.write_access);
+        assert_eq!(open.tree_id, 7);
+        assert_eq!(info.messages[1].data_length, 65536);
+
+        // Read-only open of an existing file
+        let info = parse(&nbss(&create("recipe.csv", 0x0012_0089, 1)), 50000, 445).unwrap();
+        assert!(!info.messages[0].write_access);
+    }
+
+    #[test]
+    fn test_session_setup_ntlm_identity() {
+        let domain = utf16("PLANT");
+        let user = utf16("operator");
+        let host = utf16("EWS01");
+        let mut ntlm = b"NTLMSSP\0".to_vec();
+        ntlm.extend_from_slice(&3u32.to_le_bytes());
+        ntlm.resize(64, 0);
+        ntlm[60..64].copy_from_slice(&1u32.to_le_bytes()); // unicode
+        let mut offset = 64u32;
+        for (at, value) in [(28, &domain), (36, &user), (44, &host)] {
+            ntlm[at..at + 2].copy_from_slice(&(value.len() as u16).to_le_bytes());
+            ntlm[at + 4..at + 8].copy_from_slice(&offset.to_le_bytes());
+            offset += value.len() as u32;
+        }
+        ntlm.extend(domain);
+        ntlm.extend(user);
+        ntlm.extend(host);
+        // SPNEGO wrapping bytes before the NTLMSSP message
+        let mut blob = vec![0xA1, 0x82, 0x01, 0x00];
+        blob.extend(ntlm);
+
+        let mut m = header(0x01, 0, 2, 0);
+        let mut body = vec![0u8; 24];
+        body[0..2].copy_from_slice(&25u16.to_le_bytes());
+        body[12..14].copy_from_slice(&88u16.to_le_bytes());
+        body[14..16].copy_from_slice(&(blob.len() as u16).to_le_bytes());
+        m.extend(body);
+        m.extend(blob);
+
+        let info = parse(&nbss(&m), 50000, 445).unwrap();
+        let identity = info.messages[0].identity.as_ref().unwrap();
+        assert_eq!(identity.account(), "PLANT\\operator");
+        assert_eq!(identity.workstation, "EWS01");
+    }
+
+    #[test]
+    fn test_responses_smb1_and_junk() {
+        let mut read = header(0x08, SMB2_FLAGS_RESPONSE, 9, 7);
+        read.extend_from_slice(&17u16.to_le_bytes());
+        read.extend_from_slice(&[80, 0]);
+        read.extend_from_slice(&4096u32.to_le_bytes());
+        read.extend_from_slice(&[0; 8]);
+        let info = parse(&nbss(&read), 445, 50000).unwrap();
+        assert_eq!(info.role, SmbRole::Server);
+        assert!(info.messages[0].is_response);
+        assert_eq!(info.messages[0].data_length, 4096);
+
+        let mut smb1 = SMB1_MAGIC.to_vec();
+        smb1.extend_from_slice(&[0x72; 28]);
+        assert!(parse(&nbss(&smb1), 50000, 445).unwrap().smb1);
+
+        assert!(parse(&[], 50000, 445).is_none());
+        assert!(parse(b"GET / HTTP/1.1\r\n", 50000, 445).is_none());
+    }
+}
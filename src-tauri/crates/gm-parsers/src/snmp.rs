@@ -135,6 +135,99 @@ pub struct SnmpDeviceInfo {
     pub vendor: Option<String>,
 }
 
+impl SnmpDeviceInfo {
+    /// Fold a later GET-Response into this one.
+    ///
+    /// Pollers often fetch the system group in several requests, so a
+    /// field only present in an earlier response must not be lost.
+    pub fn merge(&mut self, newer: SnmpDeviceInfo) {
+        fn take<T>(slot: &mut Option<T>, value: Option<T>) {
+            if value.is_some() {
+                *slot = value;
+            }
+        }
+        take(&mut self.sys_descr, newer.sys_descr);
+        take(&mut self.sys_name, newer.sys_name);
+        take(&mut self.sys_location, newer.sys_location);
+        take(&mut self.sys_object_id, newer.sys_object_id);
+        take(&mut self.sys_uptime_cs, newer.sys_uptime_cs);
+        take(&mut self.sys_contact, newer.sys_contact);
+        take(&mut self.vendor, newer.vendor);
+    }
+
+    /// Vendor from sysObjectID, if the enterprise arc names a hardware vendor.
+    ///
+    /// The Net-SNMP arc is shared by every Linux box running snmpd and says
+    /// nothing about who built the device.
+    pub fn known_vendor(&self) -> Option<&str> {
+        self.vendor
+            .as_deref()
+            .filter(|v| *v != "Unknown" && !v.starts_with("Net-SNMP"))
+    }
+
+    /// Best-effort model string from sysDescr.
+    ///
+    /// Takes the first comma-separated segment that looks like a model
+    /// designation, e.g. "SCALANCE XM408-8C" from
+    /// "Siemens, SIMATIC NET, SCALANCE XM408-8C, HW: 2, FW: V6.2" or
+    /// "C2960" from "Cisco IOS Software, C2960 Software (...), Version ...".
+    /// General-purpose OS banners (Linux, Windows) yield `None`.
+    pub fn model(&self) -> Option<String> {
+        let descr = self.sys_descr.as_deref()?.lines().next()?.trim();
+        if descr.starts_with("Linux ") || descr.starts_with("Hardware:") {
+            return None;
+        }
+        let vendor_word = self
+            .known_vendor()
+            .and_then(|v| v.split([' ', '/']).next())
+            .map(str::to_lowercase);
+
+        for segment in descr.split([',', ';']) {
+            let mut segment = segment.trim();
+            let lower = segment.to_lowercase();
+            if [
+                "version",
+                "release",
+                "firmware",
+                "fw",
+                "hw",
+                "rev",
+                "copyright",
+                "(c)",
+            ]
+            .iter()
+            .any(|p| lower.starts_with(p))
+            {
+                continue;
+            }
+            // "C2960 Software (C2960-LANBASEK9-M)" → "C2960"
+            if let Some(idx) = segment.find(" Software") {
+                segment = segment[..idx].trim();
+            }
+            // Drop a leading vendor name: "Hirschmann MACH104 Release 09.0.00"
+            if let Some(ref vw) = vendor_word {
+                if segment.to_lowercase().starts_with(vw.as_str()) {
+                    segment = segment[vw.len()..].trim();
+                }
+            }
+            // Trailing firmware revision in the same segment
+            if let Some(idx) = [" Release ", " Version ", " V"]
+                .iter()
+                .filter_map(|m| segment.find(m))
+                .min()
+            {
+                segment = segment[..idx].trim();
+            }
+            let has_digit = segment.chars().any(|c| c.is_ascii_digit());
+            let has_alpha = segment.chars().any(|c| c.is_ascii_alphabetic());
+            if has_digit && has_alpha && segment.len() <= 64 {
+                return Some(segment.to_string());
+            }
+        }
+        None
+    }
+}
+
 /// Parse an SNMP GET-Response PDU and extract MIB-2 system group values.
 ///
 /// `data` should be the raw UDP payload (starts with `0x30` SEQUENCE).
@@ -231,63 +324,51 @@ pub fn parse_snmp_response(data: &[u8]) -> Option<SnmpDeviceInfo> {
             let val_end = val_start + val_len;
             if val_end <= data.len() {
                 match sub_id {
-                    1 => {
-                        // sysDescr — OCTET STRING
-                        if val_tag == 0x04 {
-                            result.sys_descr = Some(
-                                String::from_utf8_lossy(&data[val_start..val_end])
-                                    .trim()
-                                    .to_string(),
-                            );
-                        }
+                    // sysDescr — OCTET STRING
+                    1 if val_tag == 0x04 => {
+                        result.sys_descr = Some(
+                            String::from_utf8_lossy(&data[val_start..val_end])
+                                .trim()
+                                .to_string(),
+                        );
                     }
-                    2 => {
-                        // sysObjectID — OID
-                        if val_tag == 0x06 {
-                            let oid_str = decode_oid(&data[val_start..val_end]);
-                            result.vendor = Some(enterprise_vendor(&oid_str).to_string());
-                            result.sys_object_id = Some(oid_str);
-                        }
+                    // sysObjectID — OID
+                    2 if val_tag == 0x06 => {
+                        let oid_str = decode_oid(&data[val_start..val_end]);
+                        result.vendor = Some(enterprise_vendor(&oid_str).to_string());
+                        result.sys_object_id = Some(oid_str);
                     }
-                    3 => {
-                        // sysUpTime — TimeTicks (APPLICATION 3 = 0x43)
-                        if val_tag == 0x43 && val_len <= 4 {
-                            let mut ticks = 0u32;
-                            for b in &data[val_start..val_end] {
-                                ticks = (ticks << 8) | (*b as u32);
-                            }
-                            result.sys_uptime_cs = Some(ticks);
+                    // sysUpTime — TimeTicks (APPLICATION 3 = 0x43)
+                    3 if val_tag == 0x43 && val_len <= 4 => {
+                        let mut ticks = 0u32;
+                        for b in &data[val_start..val_end] {
+                            ticks = (ticks << 8) | (*b as u32);
                         }
+                        result.sys_uptime_cs = Some(ticks);
                     }
-                    4 => {
-                        // sysContact — OCTET STRING
-                        if val_tag == 0x04 {
-                            result.sys_contact = Some(
-                                String::from_utf8_lossy(&data[val_start..val_end])
-                                    .trim()
-                                    .to_string(),
-                            );
-                        }
+                    // sysContact — OCTET STRING
+                    4 if val_tag == 0x04 => {
+                        result.sys_contact = Some(
+                            String::from_utf8_lossy(&data[val_start..val_end])
+                                .trim()
+                                .to_string(),
+                        );
                     }
-                    5 => {
-                        // sysName — OCTET STRING
-                        if val_tag == 0x04 {
-                            result.sys_name = Some(
-                                String::from_utf8_lossy(&data[val_start..val_end])
-                                    .trim()
-                                    .to_string(),
-                            );
-                        }
+                    // sysName — OCTET STRING
+                    5 if val_tag == 0x04 => {
+                        result.sys_name = Some(
+                            String::from_utf8_lossy(&data[val_start..val_end])
+                                .trim()
+                                .to_string(),
+                        );
                     }
-                    6 => {
-                        // sysLocation — OCTET STRING
-                        if val_tag == 0x04 {
-                            result.sys_location = Some(
-                                String::from_utf8_lossy(&data[val_start..val_end])
-                                    .trim()
-                                    .to_string(),
-                            );
-                        }
+                    // sysLocation — OCTET STRING
+                    6 if val_tag == 0x04 => {
+                        result.sys_location = Some(
+                            String::from_utf8_lossy(&data[val_start..val_end])
+                                .trim()
+                                .to_string(),
+                        );
                     }
                     _ => {}
                 }
@@ -582,4 +663,71 @@ mod tests {
     fn test_enterprise_vendor_unknown() {
         assert_eq!(enterprise_vendor("1.3.6.1.4.1.99999.1"), "Unknown");
     }
+
+    fn device(descr: &str, vendor: &str) -> SnmpDeviceInfo {
+        SnmpDeviceInfo {
+            sys_descr: Some(descr.to_string()),
+            sys_name: None,
+            sys_location: None,
+            sys_object_id: None,
+            sys_uptime_cs: None,
+            sys_contact: None,
+            vendor: Some(vendor.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_earlier_fields() {
+        let name_pkt = build_get_response(b"public", &sys_oid(5), 0x04, b"sw-cell-3");
+        let descr_pkt = build_get_response(b"public", &sys_oid(1), 0x04, b"MOXA EDS-408A");
+        let mut info = parse_snmp_response(&name_pkt).unwrap();
+        info.merge(parse_snmp_response(&descr_pkt).unwrap());
+        assert_eq!(info.sys_name.as_deref(), Some("sw-cell-3"));
+        assert_eq!(info.sys_descr.as_deref(), Some("MOXA EDS-408A"));
+    }
+
+    #[test]
+    fn test_model_from_sys_descr() {
+        let cases = [
+            (
+                "Siemens, SIMATIC NET, SCALANCE XM408-8C, HW: 2, FW: V6.2",
+                "Siemens",
+                Some("SCALANCE XM408-8C"),
+            ),
+            (
+                "Cisco IOS Software, C2960 Software (C2960-LANBASEK9-M), Version 12.2(55)SE",
+                "Cisco",
+                Some("C2960"),
+            ),
+            (
+                "Hirschmann MACH104 Release 09.0.00",
+                "Hirschmann / Belden",
+                Some("MACH104"),
+            ),
+            (
+                "Linux plc-gw 4.19.0 #1 SMP armv7l",
+                "Net-SNMP (Linux / generic)",
+                None,
+            ),
+            ("Ethernet switch", "Unknown", None),
+        ];
+        for (descr, vendor, expected) in cases {
+            assert_eq!(
+                device(descr, vendor).model().as_deref(),
+                expected,
+                "{}",
+                descr
+            );
+        }
+    }
+
+    #[test]
+    fn test_known_vendor_skips_generic() {
+        assert_eq!(device("x", "Moxa").known_vendor(), Some("Moxa"));
+        assert_eq!(device("x", "Unknown").known_vendor(), None);
+        assert_eq!(
+            device("x", "Net-SNMP (Linux / generic)").known_vendor(),
+            None
+        );
+    }
 }
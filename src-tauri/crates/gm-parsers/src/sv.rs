@@ -0,0 +1,359 @@
+//! IEC 61850-9-2 Sampled Values (SV) parser.
+//!
+//! Merging units publish digitised CT/VT measurements as SV streams on the
+//! process bus; protection relays and bay controllers subscribe to them.
+//! Like GOOSE, SV is Layer-2 multicast with no IP header: frames carry
+//! EtherType 0x88BA and go to multicast MACs in 01:0C:CD:04:xx:xx.
+//!
+//! `gm-capture::parsing` intercepts the frame and encodes the MACs into the
+//! synthetic `src_ip`/`dst_ip` as `"sv:<mac>"`; the parser receives
+//! everything after the EtherType.
+//!
+//! Frame layout after the EtherType:
+//!   [0..1]  u16 BE  APPID
+//!   [2..3]  u16 BE  Length (APPID through end of PDU)
+//!   [4..5]  Reserved 1 (bit 15 = Simulation in Ed. 2)
+//!   [6..7]  Reserved 2
+//!   [8..]   savPdu  [APPLICATION 0] (0x60), BER encoded:
+//!             [0] noASDU  [1] security  [2] seqASDU
+//!           each ASDU is a SEQUENCE (0x30):
+//!             [0] svID  [1] datSet  [2] smpCnt  [3] confRev  [4] refrTm
+//!             [5] smpSynch  [6] smpRate  [7] seqData  [8] smpMod
+//!
+//! `smpCnt` counts samples and rolls over to 0 once per second under the
+//! 9-2LE profile (4000 Hz at 50 Hz nominal, 4800 Hz at 60 Hz), so the
+//! rollover point gives the sample rate even when `smpRate` is omitted.
+//!
+//! Reference: IEC 61850-9-2 §8.5, UCA 9-2LE implementation guideline,
+//! Wireshark SV dissector
+//! EtherType: 0x88BA
+
+use serde::{Deserialize, Serialize};
+
+use crate::ber::{ber_string, ber_uint, children, read_tlv};
+
+/// Sampled Values EtherType.
+pub const SV_ETHERTYPE: u16 = 0x88BA;
+
+/// Fixed header before the savPdu (APPID, Length, Reserved 1, Reserved 2).
+const HEADER_LEN: usize = 8;
+
+/// savPdu tag ([APPLICATION 0] constructed).
+const SAV_PDU_TAG: u8 = 0x60;
+
+/// ASDU tag (universal SEQUENCE).
+const ASDU_TAG: u8 = 0x30;
+
+/// Bytes per channel in seqData under 9-2LE (INT32 value + 32-bit quality).
+const BYTES_PER_CHANNEL: usize = 8;
+
+// ─── Result Structs ───────────────────────────────────────────────────────────
+
+/// Fields extracted from one SV frame.
+#[derive(Debug, Clone, Serialize)]
+pub struct SvInfo {
+    /// Application identifier from the frame header
+    pub app_id: u16,
+    /// Simulation / test flag (Ed. 2 header bit)
+    pub simulation: bool,
+    /// ASDUs carried in this frame (noASDU is usually 1 or 8)
+    pub asdus: Vec<SvAsdu>,
+}
+
+/// One Application Service Data Unit — a single sample of a dataset.
+#[derive(Debug, Clone, Serialize)]
+pub struct SvAsdu {
+    /// Sampled value control block identifier, e.g. "MU01MU/LLN0$SV$MSVCB01"
+    pub sv_id: String,
+    /// Dataset reference (optional in 9-2LE)
+    pub dat_set: Option<String>,
+    /// Sample counter
+    pub smp_cnt: u16,
+    /// Configuration revision of the dataset
+    pub conf_rev: u32,
+    /// Clock synchronisation: 0 = none, 1 = local, 2 = global
+    pub smp_synch: Option<u8>,
+    /// Declared sample rate (unit given by smpMod; samples per nominal period by default)
+    pub smp_rate: Option<u16>,
+    /// 0 = samples per nominal period, 1 = samples per second, 2 = seconds per sample
+    pub smp_mod: Option<u8>,
+    /// Number of measurement channels in seqData
+    pub channel_count: usize,
+}
+
+/// State of one SV publication (publisher MAC + svID) across frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SvStream {
+    pub sv_id: String,
+    /// Source MAC of the merging unit
+    pub publisher_mac: String,
+    /// Multicast destination MAC
+    pub destination_mac: String,
+    pub app_id: u16,
+    pub dat_set: Option<String>,
+    pub conf_rev: u32,
+    pub channel_count: usize,
+    pub smp_synch: Option<u8>,
+    pub smp_rate: Option<u16>,
+    pub smp_mod: Option<u8>,
+    /// Samples per second, from smpCnt rollover or an explicit smpRate/smpMod
+    pub sample_rate_hz: Option<u32>,
+    pub asdu_count: u64,
+    pub last_smp_cnt: u16,
+    /// Discontinuities in smpCnt other than rollover (lost or injected samples)
+    pub smp_cnt_gaps: u64,
+    /// confRev changed mid-stream (publisher reconfigured)
+    pub conf_rev_changed: bool,
+    /// Any frame carried the simulation / test flag
+    pub simulation_seen: bool,
+}
+
+impl SvStream {
+    /// Start tracking a publication from its first ASDU.
+    pub fn new(info: &SvInfo, asdu: &SvAsdu, publisher_mac: &str, destination_mac: &str) -> Self {
+        let mut stream = Self {
+            sv_id: asdu.sv_id.clone(),
+            publisher_mac: publisher_mac.to_string(),
+            destination_mac: destination_mac.to_string(),
+            app_id: info.app_id,
+            dat_set: asdu.dat_set.clone(),
+            conf_rev: asdu.conf_rev,
+            channel_count: asdu.channel_count,
+            smp_synch: asdu.smp_synch,
+            smp_rate: asdu.smp_rate,
+            smp_mod: asdu.smp_mod,
+            sample_rate_hz: None,
+            asdu_count: 1,
+            last_smp_cnt: asdu.smp_cnt,
+            smp_cnt_gaps: 0,
+            conf_rev_changed: false,
+            simulation_seen: info.simulation,
+        };
+        if asdu.smp_mod == Some(1) {
+            stream.sample_rate_hz = asdu.smp_rate.map(u32::from);
+        }
+        stream
+    }
+
+    /// Update the stream with a subsequent ASDU from the same publication.
+    pub fn observe(&mut self, info: &SvInfo, asdu: &SvAsdu) {
+        self.asdu_count += 1;
+        self.simulation_seen |= info.simulation;
+        self.channel_count = asdu.channel_count;
+        if asdu.smp_synch.is_some() {
+            self.smp_synch = asdu.smp_synch;
+        }
+        if asdu.conf_rev != self.conf_rev {
+            self.conf_rev_changed = true;
+            self.conf_rev = asdu.conf_rev;
+        }
+
+        let (last, cnt) = (self.last_smp_cnt, asdu.smp_cnt);
+        if cnt == last {
+            // Duplicate (PRP/HSR or a mirrored port)
+            return;
+        }
+        if cnt == 0 && self.smp_mod.is_none_or(|m| m == 0) {
+            // Rollover: smpCnt ran 0..=last, so the rate is last + 1. Keep
+            // the highest rollover seen in case a frame around it was lost.
+            let rate = last as u32 + 1;
+            self.sample_rate_hz = Some(self.sample_rate_hz.map_or(rate, |r| r.max(rate)));
+        } else if cnt != last.wrapping_add(1) {
+            self.smp_cnt_gaps += 1;
+        }
+        self.last_smp_cnt = cnt;
+    }
+}
+
+// ─── Parser ───────────────────────────────────────────────────────────────────
+
+/// Parse an SV frame (payload starting at the APPID, after the EtherType).
+///
+/// Returns None if the header is truncated, the PDU is not a savPdu, or no
+/// ASDU carries the mandatory svID / smpCnt / confRev fields.
+pub fn parse(payload: &[u8]) -> Option<SvInfo> {
+    if payload.len() < HEADER_LEN {
+        return None;
+    }
+    let app_id = u16::from_be_bytes([payload[0], payload[1]]);
+    let simulation = payload[4] & 0x80 != 0;
+
+    let pdu = read_tlv(payload, HEADER_LEN)?;
+    if pdu.ident != SAV_PDU_TAG {
+        return None;
+    }
+
+    let seq = children(payload, pdu.start, pdu.end)
+        .into_iter()
+        .find(|f| f.is_context() && f.is_constructed() && f.number == 2)?;
+
+    let asdus: Vec<SvAsdu> = children(payload, seq.start, seq.end)
+        .into_iter()
+        .filter(|a| a.ident == ASDU_TAG)
+        .filter_map(|a| parse_asdu(&payload[a.start..a.end]))
+        .collect();
+
+    if asdus.is_empty() {
+        return None;
+    }
+    Some(SvInfo {
+        app_id,
+        simulation,
+        asdus,
+    })
+}
+
+fn parse_asdu(data: &[u8]) -> Option<SvAsdu> {
+    let mut sv_id = None;
+    let mut smp_cnt = None;
+    let mut conf_rev = None;
+    let mut asdu = SvAsdu {
+        sv_id: String::new(),
+        dat_set: None,
+        smp_cnt: 0,
+        conf_rev: 0,
+        smp_synch: None,
+        smp_rate: None,
+        smp_mod: None,
+        channel_count: 0,
+    };
+
+    for field in children(data, 0, data.len()) {
+        if !field.is_context() {
+            continue;
+        }
+        let body = &data[field.start..field.end];
+        match field.number {
+            0 => sv_id = ber_string(body),
+            1 => asdu.dat_set = ber_string(body),
+            2 => smp_cnt = ber_uint(body).and_then(|v| u16::try_from(v).ok()),
+            3 => conf_rev = ber_uint(body),
+            5 => asdu.smp_synch = ber_uint(body).and_then(|v| u8::try_from(v).ok()),
+            6 => asdu.smp_rate = ber_uint(body).and_then(|v| u16::try_from(v).ok()),
+            7 => asdu.channel_count = body.len() / BYTES_PER_CHANNEL,
+            8 => asdu.smp_mod = ber_uint(body).and_then(|v| u8::try_from(v).ok()),
+            _ => {}
+        }
+    }
+
+    asdu.sv_id = sv_id?;
+    asdu.smp_cnt = smp_cnt?;
+    asdu.conf_rev = conf_rev?;
+    Some(asdu)
+}
+
+// ─── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    fn asdu(smp_cnt: u16) -> Vec<u8> {
+        let [hi, lo] = smp_cnt.to_be_bytes();
+        let mut fields = vec![
+            0x80, 0x08, b'M', b'U', b'0', b'1', b'_', b'S', b'V', b'1', // svID
+            0x82, 0x02, hi, lo,                                         // smpCnt
+            0x83, 0x04, 0x00, 0x00, 0x00, 0x01,                         // confRev 1
+            0x85, 0x01, 0x02,                                           // smpSynch global
+            0x87, 0x40,                                                 // seqData, 8 channels
+        ];
+        fields.extend_from_slice(&[0u8; 64]);
+        let mut out = vec![ASDU_TAG, fields.len() as u8];
+        out.extend_from_slice(&fields);
+        out
+    }
+
+    /// Build an SV payload (from APPID onwards) around the given ASDUs.
+    fn frame(asdus: &[Vec<u8>]) -> Vec<u8> {
+        let seq: Vec<u8> = asdus.concat();
+        let mut body = vec![0x80, 0x01, asdus.len() as u8]; // noASDU
+        body.push(0xA2); // seqASDU
+        body.extend_from_slice(&ber_len(seq.len()));
+        body.extend_from_slice(&seq);
+        let mut pdu = vec![SAV_PDU_TAG];
+        pdu.extend_from_slice(&ber_len(body.len()));
+        pdu.extend_from_slice(&body);
+        let len = (HEADER_LEN + pdu.len()) as u16;
+        let mut out = vec![0x40, 0x00];
+        out.extend_from_slice(&len.to_be_bytes());
+        out.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        out.extend_from_slice(&pdu);
+        out
+    }
+
+    fn ber_len(len: usize) -> Vec<u8> {
+        if len < 0x80 {
+            vec![len as u8]
+        } else {
+            vec![0x82, (len >> 8) as u8, len as u8]
+        }
+    }
+
+    #[test]
+    fn test_parse_sv_frame() {
+        let info = parse(&frame(&[asdu(3999)])).unwrap();
+        assert_eq!(info.app_id, 0x4000);
+        assert!(!info.simulation);
+        assert_eq!(info.asdus.len(), 1);
+        let a = &info.asdus[0];
+        assert_eq!(a.sv_id, "MU01_SV1");
+        assert_eq!(a.smp_cnt, 3999);
+        assert_eq!(a.conf_rev, 1);
+        assert_eq!(a.smp_synch, Some(2));
+        assert_eq!(a.channel_count, 8);
+        assert!(a.dat_set.is_none());
+        assert!(a.smp_rate.is_none());
+    }
+
+    #[test]
+    fn test_parse_multiple_asdus_and_simulation() {
+        let mut payload = frame(&[asdu(10), asdu(11)]);
+        payload[4] = 0x80;
+        let info = parse(&payload).unwrap();
+        assert!(info.simulation);
+        let counts: Vec<u16> = info.asdus.iter().map(|a| a.smp_cnt).collect();
+        assert_eq!(counts, vec![10, 11]);
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        assert!(parse(&[]).is_none());
+        assert!(parse(&[0x40, 0x00, 0x00, 0x08, 0, 0, 0, 0]).is_none());
+        // goosePdu tag is not a savPdu
+        let mut payload = frame(&[asdu(1)]);
+        payload[HEADER_LEN] = 0x61;
+        assert!(parse(&payload).is_none());
+        // ASDU without svID
+        #[rustfmt::skip]
+        let bare = vec![ASDU_TAG, 0x0A, 0x82, 0x02, 0x00, 0x01, 0x83, 0x04, 0, 0, 0, 1];
+        assert!(parse(&frame(&[bare])).is_none());
+    }
+
+    #[test]
+    fn test_stream_rate_from_rollover() {
+        let first = parse(&frame(&[asdu(3990)])).unwrap();
+        let mut stream = SvStream::new(
+            &first,
+            &first.asdus[0],
+            "00:0c:cd:10:00:01",
+            "01:0c:cd:04:00:01",
+        );
+        for cnt in (3991..4000).chain(0..5) {
+            let info = parse(&frame(&[asdu(cnt)])).unwrap();
+            stream.observe(&info, &info.asdus[0]);
+        }
+        assert_eq!(stream.sample_rate_hz, Some(4000));
+        assert_eq!(stream.asdu_count, 15);
+        assert_eq!(stream.smp_cnt_gaps, 0);
+        assert_eq!(stream.channel_count, 8);
+
+        // Duplicates are ignored; a jump is a gap
+        let dup = parse(&frame(&[asdu(4)])).unwrap();
+        stream.observe(&dup, &dup.asdus[0]);
+        let jump = parse(&frame(&[asdu(50)])).unwrap();
+        stream.observe(&jump, &jump.asdus[0]);
+        assert_eq!(stream.smp_cnt_gaps, 1);
+        assert_eq!(stream.last_smp_cnt, 50);
+    }
+}
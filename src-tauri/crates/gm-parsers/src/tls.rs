@@ -0,0 +1,472 @@
+//! TLS handshake inspection.
+//!
+//! OT gateways reach cloud platforms over TLS (HTTPS, MQTT/8883, AMQP/5671),
+//! and OPC UA over HTTPS and vendor web management sit behind it too, so the
+//! hello messages are the only cleartext left on the wire. This module reads
+//! the Server Name Indication, the offered or chosen version and cipher
+//! suites, and computes JA3 (ClientHello) and JA3S (ServerHello)
+//! fingerprints, which identify the TLS stack — and so often the product —
+//! without decrypting anything. Only the first segment of a handshake is
+//! read; records split across segments are not reassembled.
+//!
+//! Layout (RFC 8446 §4.1.2–4.1.3, RFC 6066 §3):
+//!   Record:      [0] type 0x16, [1..3] version, [3..5] length
+//!   Handshake:   [5] type 0x01 / 0x02, [6..9] length, [9..11] legacy_version,
+//!                [11..43] random, session_id<0..32>
+//!   ClientHello: cipher_suites<2..>, compression_methods<1..>, extensions<0..>
+//!   ServerHello: cipher_suite (2), compression_method (1), extensions<0..>
+//!   server_name (type 0): list length, name_type 0 (host_name), name<1..>
+//!
+//! JA3 is `version,ciphers,extensions,curves,point_formats` and JA3S is
+//! `version,cipher,extensions`, each field a `-`-joined list of decimal
+//! values with GREASE values (RFC 8701) removed, hashed with MD5.
+
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+
+/// TLS record content type: handshake.
+const CONTENT_HANDSHAKE: u8 = 0x16;
+/// Handshake message type: ClientHello.
+const HANDSHAKE_CLIENT_HELLO: u8 = 0x01;
+/// Handshake message type: ServerHello.
+const HANDSHAKE_SERVER_HELLO: u8 = 0x02;
+/// Extension type: server_name.
+const EXT_SERVER_NAME: u16 = 0x0000;
+/// Extension type: supported_groups (formerly elliptic_curves).
+const EXT_SUPPORTED_GROUPS: u16 = 0x000A;
+/// Extension type: ec_point_formats.
+const EXT_EC_POINT_FORMATS: u16 = 0x000B;
+/// Extension type: application_layer_protocol_negotiation.
+const EXT_ALPN: u16 = 0x0010;
+/// Extension type: supported_versions.
+const EXT_SUPPORTED_VERSIONS: u16 = 0x002B;
+/// ServerNameList entry type: host_name.
+const NAME_TYPE_HOST: u8 = 0x00;
+
+/// Which hello a fingerprint was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TlsHelloKind {
+    /// ClientHello; the fingerprint is JA3
+    Client,
+    /// ServerHello; the fingerprint is JA3S
+    Server,
+}
+
+/// A parsed ClientHello or ServerHello with its fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsHello {
+    pub kind: TlsHelloKind,
+    /// Highest version offered (client) or version chosen (server), taking
+    /// the supported_versions extension into account, e.g. 0x0304 for TLS 1.3
+    pub version: u16,
+    /// SNI host name, lowercased (ClientHello only)
+    pub sni: Option<String>,
+    /// Suites offered by the client, or the one the server chose; GREASE removed
+    pub cipher_suites: Vec<u16>,
+    /// ALPN protocols offered (client) or selected (server), e.g. "h2", "mqtt"
+    pub alpn: Vec<String>,
+    /// JA3 or JA3S string before hashing
+    pub ja3_string: String,
+    /// MD5 of `ja3_string` as lowercase hex
+    pub ja3: String,
+}
+
+impl TlsHello {
+    /// Display name of `version`, e.g. "TLS 1.2".
+    pub fn version_name(&self) -> String {
+        version_name(self.version)
+    }
+}
+
+/// Display name of a TLS protocol version number.
+pub fn version_name(version: u16) -> String {
+    match version {
+        0x0300 => "SSL 3.0".to_string(),
+        0x0301 => "TLS 1.0".to_string(),
+        0x0302 => "TLS 1.1".to_string(),
+        0x0303 => "TLS 1.2".to_string(),
+        0x0304 => "TLS 1.3".to_string(),
+        other => format!("0x{:04x}", other),
+    }
+}
+
+/// Returns true if the payload starts with a TLS handshake record carrying
+/// a ClientHello.
+pub fn is_client_hello(payload: &[u8]) -> bool {
+    is_handshake(payload, HANDSHAKE_CLIENT_HELLO)
+}
+
+fn is_handshake(payload: &[u8], handshake_type: u8) -> bool {
+    payload.len() > 5
+        && payload[0] == CONTENT_HANDSHAKE
+        && payload[1] == 0x03
+        && payload[5] == handshake_type
+}
+
+/// Extract the SNI host name from a TLS ClientHello.
+///
+/// Returns None for anything that is not a ClientHello, for ClientHellos
+/// without a server_name extension, and for truncated messages.
+pub fn client_hello_sni(payload: &[u8]) -> Option<String> {
+    parse(payload)?.sni
+}
+
+/// Parse a ClientHello or ServerHello at the start of a TCP payload.
+///
+/// Returns None for other records and for hellos cut short by the segment
+/// boundary, since a fingerprint over part of the extensions would be wrong.
+pub fn parse(payload: &[u8]) -> Option<TlsHello> {
+    if is_handshake(payload, HANDSHAKE_CLIENT_HELLO) {
+        parse_client_hello(payload.get(9..)?)
+    } else if is_handshake(payload, HANDSHAKE_SERVER_HELLO) {
+        parse_server_hello(payload.get(9..)?)
+    } else {
+        None
+    }
+}
+
+fn parse_client_hello(body: &[u8]) -> Option<TlsHello> {
+    let mut r = Reader::new(body);
+    let legacy_version = r.u16()?;
+    r.skip(32)?; // random
+    let session_id_len = r.u8()? as usize;
+    r.skip(session_id_len)?;
+    let suites_len = r.u16()? as usize;
+    let cipher_suites: Vec<u16> = u16_list(r.take(suites_len)?)
+        .filter(|&v| !is_grease(v))
+        .collect();
+    let compression_len = r.u8()? as usize;
+    r.skip(compression_len)?;
+
+    let mut extensions = Vec::new();
+    let mut curves = Vec::new();
+    let mut point_formats: Vec<u8> = Vec::new();
+    let mut sni = None;
+    let mut alpn = Vec::new();
+    let mut version = legacy_version;
+
+    // Extensions are optional in a ClientHello
+    if r.remaining() > 0 {
+        let extensions_len = r.u16()? as usize;
+        let mut ext = Reader::new(r.take(extensions_len)?);
+        while ext.remaining() > 0 {
+            let ext_type = ext.u16()?;
+            let ext_len = ext.u16()? as usize;
+            let data = ext.take(ext_len)?;
+            if is_grease(ext_type) {
+                continue;
+            }
+            extensions.push(ext_type);
+            let mut d = Reader::new(data);
+            match ext_type {
+                EXT_SERVER_NAME => sni = server_name(&mut d),
+                EXT_SUPPORTED_GROUPS => {
+                    let len = d.u16()? as usize;
+                    curves = u16_list(d.take(len)?).filter(|&v| !is_grease(v)).collect();
+                }
+                EXT_EC_POINT_FORMATS => {
+                    let len = d.u8()? as usize;
+                    point_formats = d.take(len)?.to_vec();
+                }
+                EXT_ALPN => alpn = alpn_list(&mut d)?,
+                EXT_SUPPORTED_VERSIONS => {
+                    let len = d.u8()? as usize;
+                    if let Some(max) = u16_list(d.take(len)?).filter(|&v| !is_grease(v)).max() {
+                        version = max;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let ja3_string = format!(
+        "{},{},{},{},{}",
+        legacy_version,
+        join(cipher_suites.iter()),
+        join(extensions.iter()),
+        join(curves.iter()),
+        join(point_formats.iter()),
+    );
+    Some(TlsHello {
+        kind: TlsHelloKind::Client,
+        version,
+        sni,
+        cipher_suites,
+        alpn,
+        ja3: md5_hex(&ja3_string),
+        ja3_string,
+    })
+}
+
+fn parse_server_hello(body: &[u8]) -> Option<TlsHello> {
+    let mut r = Reader::new(body);
+    let legacy_version = r.u16()?;
+    r.skip(32)?; // random
+    let session_id_len = r.u8()? as usize;
+    r.skip(session_id_len)?;
+    let cipher_suite = r.u16()?;
+    r.skip(1)?; // compression_method
+
+    let mut extensions = Vec::new();
+    let mut alpn = Vec::new();
+    let mut version = legacy_version;
+
+    if r.remaining() > 0 {
+        let extensions_len = r.u16()? as usize;
+        let mut ext = Reader::new(r.take(extensions_len)?);
+        while ext.remaining() > 0 {
+            let ext_type = ext.u16()?;
+            let ext_len = ext.u16()? as usize;
+            let data = ext.take(ext_len)?;
+            extensions.push(ext_type);
+            let mut d = Reader::new(data);
+            match ext_type {
+                EXT_ALPN => alpn = alpn_list(&mut d)?,
+                EXT_SUPPORTED_VERSIONS => version = d.u16()?,
+                _ => {}
+            }
+        }
+    }
+
+    let ja3_string = format!(
+        "{},{},{}",
+        legacy_version,
+        cipher_suite,
+        join(extensions.iter())
+    );
+    Some(TlsHello {
+        kind: TlsHelloKind::Server,
+        version,
+        sni: None,
+        cipher_suites: vec![cipher_suite],
+        alpn,
+        ja3: md5_hex(&ja3_string),
+        ja3_string,
+    })
+}
+
+/// First host_name entry of a server_name extension, lowercased.
+fn server_name(d: &mut Reader<'_>) -> Option<String> {
+    let list_len = d.u16()? as usize;
+    let mut list = Reader::new(d.take(list_len)?);
+    while list.remaining() >= 3 {
+        let name_type = list.u8()?;
+        let name_len = list.u16()? as usize;
+        let name = list.take(name_len)?;
+        if name_type == NAME_TYPE_HOST {
+            let host = std::str::from_utf8(name).ok()?;
+            return Some(host.to_ascii_lowercase());
+        }
+    }
+    None
+}
+
+/// Protocol names of an ALPN extension.
+fn alpn_list(d: &mut Reader<'_>) -> Option<Vec<String>> {
+    let list_len = d.u16()? as usize;
+    let mut list = Reader::new(d.take(list_len)?);
+    let mut protocols = Vec::new();
+    while list.remaining() > 0 {
+        let len = list.u8()? as usize;
+        protocols.push(String::from_utf8_lossy(list.take(len)?).into_owned());
+    }
+    Some(protocols)
+}
+
+/// GREASE values (RFC 8701): 0x0A0A, 0x1A1A, … 0xFAFA. Clients sprinkle
+/// them in at random, so JA3 leaves them out.
+fn is_grease(value: u16) -> bool {
+    value & 0x0F0F == 0x0A0A && value >> 8 == value & 0xFF
+}
+
+fn u16_list(data: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    data.chunks_exact(2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn join<T: ToString>(values: impl Iterator<Item = T>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join("-")
+}
+
+fn md5_hex(s: &str) -> String {
+    Md5::digest(s.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Bounds-checked big-endian cursor over a byte slice.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let out = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(out)
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        self.take(n).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal TLS 1.2 ClientHello with the given SNI (and a dummy
+    /// extension in front of it).
+    fn client_hello(sni: &str) -> Vec<u8> {
+        let mut ext = Vec::new();
+        // supported_groups, ignored by the parser
+        ext.extend_from_slice(&[0x00, 0x0A, 0x00, 0x04, 0x00, 0x02, 0x00, 0x17]);
+        let name = sni.as_bytes();
+        ext.extend_from_slice(&EXT_SERVER_NAME.to_be_bytes());
+        ext.extend_from_slice(&((name.len() + 5) as u16).to_be_bytes());
+        ext.extend_from_slice(&((name.len() + 3) as u16).to_be_bytes());
+        ext.push(NAME_TYPE_HOST);
+        ext.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        ext.extend_from_slice(name);
+
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[0xAB; 32]); // random
+        body.push(0); // session id
+        body.extend_from_slice(&[0x00, 0x02, 0xC0, 0x2F]); // one cipher suite
+        body.extend_from_slice(&[0x01, 0x00]); // null compression
+        body.extend_from_slice(&(ext.len() as u16).to_be_bytes());
+        body.extend_from_slice(&ext);
+
+        let mut hs = vec![HANDSHAKE_CLIENT_HELLO];
+        hs.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        hs.extend_from_slice(&body);
+
+        let mut record = vec![CONTENT_HANDSHAKE, 0x03, 0x01];
+        record.extend_from_slice(&(hs.len() as u16).to_be_bytes());
+        record.extend_from_slice(&hs);
+        record
+    }
+
+    #[test]
+    fn test_extracts_sni() {
+        let hello = client_hello("A1B2C3-ats.iot.eu-west-1.amazonaws.com");
+        assert!(is_client_hello(&hello));
+        assert_eq!(
+            client_hello_sni(&hello).as_deref(),
+            Some("a1b2c3-ats.iot.eu-west-1.amazonaws.com")
+        );
+    }
+
+    #[test]
+    fn test_truncated_and_non_tls() {
+        let hello = client_hello("plant.azure-devices.net");
+        assert_eq!(client_hello_sni(&hello[..60]), None);
+        assert_eq!(client_hello_sni(b"GET / HTTP/1.1\r\n"), None);
+        // ServerHello (type 2) is not a ClientHello
+        let mut server_hello = hello.clone();
+        server_hello[5] = 0x02;
+        assert_eq!(client_hello_sni(&server_hello), None);
+    }
+
+    /// Wrap a handshake body in a handshake message and a TLS record.
+    fn record(handshake_type: u8, body: &[u8]) -> Vec<u8> {
+        let mut hs = vec![handshake_type];
+        hs.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        hs.extend_from_slice(body);
+        let mut record = vec![CONTENT_HANDSHAKE, 0x03, 0x01];
+        record.extend_from_slice(&(hs.len() as u16).to_be_bytes());
+        record.extend_from_slice(&hs);
+        record
+    }
+
+    fn extension(ext_type: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = ext_type.to_be_bytes().to_vec();
+        out.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    #[test]
+    fn test_ja3_skips_grease() {
+        let mut ext = extension(0x1A1A, &[]); // GREASE
+        ext.extend(extension(
+            EXT_SERVER_NAME,
+            &[0x00, 0x07, 0x00, 0x00, 0x04, b'o', b'p', b'c', b'1'],
+        ));
+        ext.extend(extension(
+            EXT_SUPPORTED_GROUPS,
+            &[0x00, 0x06, 0x2A, 0x2A, 0x00, 0x1D, 0x00, 0x17],
+        ));
+        ext.extend(extension(EXT_EC_POINT_FORMATS, &[0x01, 0x00]));
+        ext.extend(extension(
+            EXT_ALPN,
+            &[0x00, 0x05, 0x04, b'm', b'q', b't', b't'],
+        ));
+        ext.extend(extension(
+            EXT_SUPPORTED_VERSIONS,
+            &[0x06, 0x3A, 0x3A, 0x03, 0x04, 0x03, 0x03],
+        ));
+
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[0x11; 32]);
+        body.push(0);
+        body.extend_from_slice(&[0x00, 0x06, 0x0A, 0x0A, 0x13, 0x01, 0xC0, 0x2F]);
+        body.extend_from_slice(&[0x01, 0x00]);
+        body.extend_from_slice(&(ext.len() as u16).to_be_bytes());
+        body.extend_from_slice(&ext);
+
+        let hello = parse(&record(HANDSHAKE_CLIENT_HELLO, &body)).unwrap();
+        assert_eq!(hello.kind, TlsHelloKind::Client);
+        assert_eq!(hello.version_name(), "TLS 1.3");
+        assert_eq!(hello.sni.as_deref(), Some("opc1"));
+        assert_eq!(hello.cipher_suites, vec![0x1301, 0xC02F]);
+        assert_eq!(hello.alpn, vec!["mqtt".to_string()]);
+        assert_eq!(hello.ja3_string, "771,4865-49199,0-10-11-16-43,29-23,0");
+        assert_eq!(hello.ja3, "ba56e367277299892e1a86aefd53de70");
+    }
+
+    #[test]
+    fn test_ja3s_from_server_hello() {
+        let mut ext = extension(EXT_SUPPORTED_VERSIONS, &[0x03, 0x04]);
+        ext.extend(extension(0x0033, &[0x00, 0x1D, 0x00, 0x00]));
+
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[0x22; 32]);
+        body.push(0);
+        body.extend_from_slice(&[0x13, 0x01, 0x00]);
+        body.extend_from_slice(&(ext.len() as u16).to_be_bytes());
+        body.extend_from_slice(&ext);
+
+        let hello = parse(&record(HANDSHAKE_SERVER_HELLO, &body)).unwrap();
+        assert_eq!(hello.kind, TlsHelloKind::Server);
+        assert_eq!(hello.version, 0x0304);
+        assert_eq!(hello.sni, None);
+        assert_eq!(hello.cipher_suites, vec![0x1301]);
+        assert_eq!(hello.ja3_string, "771,4865,43-51");
+        assert_eq!(hello.ja3, "f4febc55ea12b31ae17cfb7e614afda8");
+
+        // A hello cut short inside its extensions has no fingerprint
+        let full = record(HANDSHAKE_SERVER_HELLO, &body);
+        assert_eq!(parse(&full[..full.len() - 2]), None);
+    }
+}
@@ -0,0 +1,263 @@
+//! Triconex TriStation protocol parser.
+//!
+//! TriStation 1131 is the engineering software for Schneider Electric
+//! Triconex safety instrumented systems. It talks to the controller's
+//! communication module over UDP 1502 to download and upload the safety
+//! application, change the run state and read controller status — the
+//! channel the TRITON/TRISIS malware used against a Triconex SIS.
+//!
+//! Every datagram carries one TCM (Triconex communication module) frame:
+//!
+//! ```text
+//! ┌──────────┬─────────┬──────────────┬──────────────────────┬──────────┐
+//! │ Msg type │ Channel │ Length (LE)  │ Data (Length bytes)  │ CRC-16   │
+//! │ 1 byte   │ 1 byte  │ 2 bytes      │                      │ 2 bytes  │
+//! └──────────┴─────────┴──────────────┴──────────────────────┴──────────┘
+//! ```
+//!
+//! Execute-command frames (type 5) carry a TriStation message whose third
+//! byte is the command code: `[0]` node, `[1]` sequence, `[2]` command.
+//! Command names follow the table recovered in public analyses of TRITON's
+//! TriStation library. The CRC is not checked.
+
+use serde::{Deserialize, Serialize};
+
+/// TriStation UDP port.
+pub const TRISTATION_PORT: u16 = 1502;
+
+const TCM_HEADER_LEN: usize = 4;
+const TCM_CRC_LEN: usize = 2;
+
+// ─── Enums ────────────────────────────────────────────────────────────────────
+
+/// Client/server role for a TriStation device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriStationRole {
+    /// TriStation 1131 workstation sending commands
+    Client,
+    /// Triconex controller answering on port 1502
+    Server,
+}
+
+/// TCM frame type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TcmMessageType {
+    ConnectRequest,
+    ConnectReply,
+    DisconnectRequest,
+    DisconnectReply,
+    ExecCommand,
+    Ping,
+    ConnectionLimitReached,
+    NotConnected,
+    MpsAreDead,
+    AccessDenied,
+    ConnectionFailed,
+}
+
+impl TcmMessageType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+            1 => TcmMessageType::ConnectRequest,
+            2 => TcmMessageType::ConnectReply,
+            3 => TcmMessageType::DisconnectRequest,
+            4 => TcmMessageType::DisconnectReply,
+            5 => TcmMessageType::ExecCommand,
+            6 => TcmMessageType::Ping,
+            7 => TcmMessageType::ConnectionLimitReached,
+            8 => TcmMessageType::NotConnected,
+            9 => TcmMessageType::MpsAreDead,
+            10 => TcmMessageType::AccessDenied,
+            11 => TcmMessageType::ConnectionFailed,
+            _ => return None,
+        })
+    }
+}
+
+/// TriStation command carried in an execute-command frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriStationCommand {
+    StartDownloadAll,
+    StartDownloadChange,
+    UpdateConfiguration,
+    UploadProgram,
+    CancelDownload,
+    DownloadComplete,
+    RunProgram,
+    HaltProgram,
+    GetChassisStatus,
+    GetCpStatus,
+    AllocateProgram,
+    AllocateFunction,
+    Other(u8),
+}
+
+impl TriStationCommand {
+    pub fn from_u8(code: u8) -> Self {
+        match code {
+            0x01 => TriStationCommand::StartDownloadAll,
+            0x02 => TriStationCommand::StartDownloadChange,
+            0x03 => TriStationCommand::UpdateConfiguration,
+            0x04 => TriStationCommand::UploadProgram,
+            0x05 => TriStationCommand::CancelDownload,
+            0x06 => TriStationCommand::DownloadComplete,
+            0x0B => TriStationCommand::RunProgram,
+            0x0C => TriStationCommand::HaltProgram,
+            0x1A => TriStationCommand::GetChassisStatus,
+            0x1D => TriStationCommand::GetCpStatus,
+            0x37 => TriStationCommand::AllocateProgram,
+            0x38 => TriStationCommand::AllocateFunction,
+            other => TriStationCommand::Other(other),
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            TriStationCommand::StartDownloadAll => "Start Download All".to_string(),
+            TriStationCommand::StartDownloadChange => "Start Download Change".to_string(),
+            TriStationCommand::UpdateConfiguration => "Update Configuration".to_string(),
+            TriStationCommand::UploadProgram => "Upload Program".to_string(),
+            TriStationCommand::CancelDownload => "Cancel Download".to_string(),
+            TriStationCommand::DownloadComplete => "Download Complete".to_string(),
+            TriStationCommand::RunProgram => "Run Program".to_string(),
+            TriStationCommand::HaltProgram => "Halt Program".to_string(),
+            TriStationCommand::GetChassisStatus => "Get Chassis Status".to_string(),
+            TriStationCommand::GetCpStatus => "Get CP Status".to_string(),
+            TriStationCommand::AllocateProgram => "Allocate Program".to_string(),
+            TriStationCommand::AllocateFunction => "Allocate Function".to_string(),
+            TriStationCommand::Other(code) => format!("Command 0x{:02X}", code),
+        }
+    }
+
+    /// Commands that write a program or configuration to the controller.
+    pub fn is_download(self) -> bool {
+        matches!(
+            self,
+            TriStationCommand::StartDownloadAll
+                | TriStationCommand::StartDownloadChange
+                | TriStationCommand::UpdateConfiguration
+                | TriStationCommand::DownloadComplete
+                | TriStationCommand::AllocateProgram
+                | TriStationCommand::AllocateFunction
+        )
+    }
+
+    /// Commands that read the safety program off the controller.
+    pub fn is_upload(self) -> bool {
+        self == TriStationCommand::UploadProgram
+    }
+
+    /// Commands that start or halt the safety program.
+    pub fn is_run_state_change(self) -> bool {
+        matches!(
+            self,
+            TriStationCommand::RunProgram | TriStationCommand::HaltProgram
+        )
+    }
+}
+
+// ─── Structs ──────────────────────────────────────────────────────────────────
+
+/// Parsed TriStation datagram information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriStationInfo {
+    pub role: TriStationRole,
+    pub message_type: TcmMessageType,
+    pub channel: u8,
+    /// Command sent by the workstation (requests only)
+    pub command: Option<TriStationCommand>,
+}
+
+// ─── Parser ───────────────────────────────────────────────────────────────────
+
+/// Attempt to parse a TriStation datagram.
+///
+/// Returns None unless the payload is exactly one TCM frame with a known
+/// message type.
+pub fn parse(payload: &[u8], src_port: u16, dst_port: u16) -> Option<TriStationInfo> {
+    if payload.len() < TCM_HEADER_LEN + TCM_CRC_LEN {
+        return None;
+    }
+    let message_type = TcmMessageType::from_u8(payload[0])?;
+    let len = u16::from_le_bytes([payload[2], payload[3]]) as usize;
+    if TCM_HEADER_LEN + len + TCM_CRC_LEN != payload.len() {
+        return None;
+    }
+    let data = &payload[TCM_HEADER_LEN..TCM_HEADER_LEN + len];
+
+    let role = if src_port == TRISTATION_PORT && dst_port != TRISTATION_PORT {
+        TriStationRole::Server
+    } else {
+        TriStationRole::Client
+    };
+
+    let command = match (message_type, role) {
+        (TcmMessageType::ExecCommand, TriStationRole::Client) if data.len() >= 3 => {
+            Some(TriStationCommand::from_u8(data[2]))
+        }
+        _ => None,
+    };
+
+    Some(TriStationInfo {
+        role,
+        message_type,
+        channel: payload[1],
+        command,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(msg_type: u8, data: &[u8]) -> Vec<u8> {
+        let mut f = vec![msg_type, 0x00];
+        f.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        f.extend_from_slice(data);
+        f.extend_from_slice(&[0x00, 0x00]);
+        f
+    }
+
+    #[test]
+    fn test_exec_command_request() {
+        let info = parse(&frame(5, &[0x01, 0x07, 0x02, 0x00]), 50000, TRISTATION_PORT).unwrap();
+        assert_eq!(info.role, TriStationRole::Client);
+        assert_eq!(info.message_type, TcmMessageType::ExecCommand);
+        let command = info.command.unwrap();
+        assert_eq!(command, TriStationCommand::StartDownloadChange);
+        assert!(command.is_download());
+        assert_eq!(command.name(), "Start Download Change");
+
+        let halt = parse(&frame(5, &[0x01, 0x08, 0x0C]), 50000, TRISTATION_PORT).unwrap();
+        assert!(halt.command.unwrap().is_run_state_change());
+
+        let other = parse(&frame(5, &[0x01, 0x09, 0x99]), 50000, TRISTATION_PORT).unwrap();
+        assert_eq!(other.command.unwrap().name(), "Command 0x99");
+    }
+
+    #[test]
+    fn test_replies_and_session_frames() {
+        // Controller replies carry no command
+        let reply = parse(&frame(5, &[0x01, 0x07, 0x82]), TRISTATION_PORT, 50000).unwrap();
+        assert_eq!(reply.role, TriStationRole::Server);
+        assert!(reply.command.is_none());
+
+        let connect = parse(&frame(1, &[]), 50000, TRISTATION_PORT).unwrap();
+        assert_eq!(connect.message_type, TcmMessageType::ConnectRequest);
+        assert!(connect.command.is_none());
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        assert!(parse(&[], 50000, TRISTATION_PORT).is_none());
+        // Unknown message type
+        assert!(parse(&frame(0x40, &[1, 2, 3]), 50000, TRISTATION_PORT).is_none());
+        // Length field disagrees with the datagram size
+        let mut bad = frame(5, &[1, 2, 3]);
+        bad.push(0);
+        assert!(parse(&bad, 50000, TRISTATION_PORT).is_none());
+    }
+}
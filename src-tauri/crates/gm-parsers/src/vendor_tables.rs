@@ -37,6 +37,37 @@ pub fn cip_vendor_name(vendor_id: u16) -> &'static str {
     }
 }
 
+/// Look up the CIP device profile name for a ListIdentity device type.
+pub fn cip_device_type_name(device_type: u16) -> &'static str {
+    match device_type {
+        0x00 => "Generic Device",
+        0x02 => "AC Drive",
+        0x03 => "Motor Overload",
+        0x04 => "Limit Switch",
+        0x05 => "Inductive Proximity Switch",
+        0x06 => "Photoelectric Sensor",
+        0x07 => "General Purpose Discrete I/O",
+        0x09 => "Resolver",
+        0x0C => "Communications Adapter",
+        0x0E => "Programmable Logic Controller",
+        0x10 => "Position Controller",
+        0x13 => "DC Drive",
+        0x15 => "Contactor",
+        0x16 => "Motor Starter",
+        0x17 => "Soft Start",
+        0x18 => "Human-Machine Interface",
+        0x1A => "Mass Flow Controller",
+        0x1B => "Pneumatic Valve",
+        0x22 => "Encoder",
+        0x23 => "Safety Discrete I/O",
+        0x24 => "Fluid Flow Controller",
+        0x25 => "CIP Motion Drive",
+        0x2B => "Generic Device (keyable)",
+        0x2C => "Managed Ethernet Switch",
+        _ => "Unknown Device Type",
+    }
+}
+
 /// Look up PROFINET vendor name from PI International assigned vendor ID.
 /// Used for PROFINET DCP Identify responses.
 pub fn profinet_vendor_name(vendor_id: u16) -> &'static str {
@@ -113,6 +144,13 @@ mod tests {
         assert_eq!(cip_vendor_name(9999), "Unknown Vendor");
     }
 
+    #[test]
+    fn test_cip_device_types() {
+        assert_eq!(cip_device_type_name(0x0E), "Programmable Logic Controller");
+        assert_eq!(cip_device_type_name(0x0C), "Communications Adapter");
+        assert_eq!(cip_device_type_name(0x7777), "Unknown Device Type");
+    }
+
     #[test]
     fn test_profinet_known_vendors() {
         assert_eq!(profinet_vendor_name(0x002A), "Siemens");
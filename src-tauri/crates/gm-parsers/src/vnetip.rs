@@ -0,0 +1,146 @@
+//! Yokogawa Vnet/IP control network parser.
+//!
+//! Vnet/IP is the real-time control bus of CENTUM VP/CS 3000 and ProSafe-RS:
+//! field control stations (FCS), safety control stations (SCS), human
+//! interface stations (HIS) and engineering stations exchange control and
+//! system data over UDP 20171/20172 on a redundant pair of buses.
+//!
+//! The frame format is proprietary and not publicly documented, but station
+//! addressing is fixed by the system: every Vnet/IP interface address is
+//! derived from the station's domain and station numbers,
+//!
+//! ```text
+//! Bus 1:  192.168.<domain>.<station>
+//! Bus 2:  192.168.<domain + 128>.<station>
+//! ```
+//!
+//! so the domain/station pair — the same numbers that appear in station
+//! names such as FCS0101 or HIS0164 — can be read from the packet's
+//! endpoints. Traffic on the Vnet/IP ports whose addresses do not follow the
+//! scheme is not treated as Vnet/IP.
+
+use std::net::Ipv4Addr;
+
+use serde::{Deserialize, Serialize};
+
+/// Vnet/IP UDP ports.
+pub const VNETIP_PORTS: [u16; 2] = [20171, 20172];
+
+/// Highest domain number (domains 1–16 in CENTUM VP; the bus 2 offset
+/// leaves room for up to 127).
+const MAX_DOMAIN: u8 = 127;
+
+/// Domain and station number of a Vnet/IP station interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VnetIpStation {
+    pub domain: u8,
+    pub station: u8,
+    /// Bus the interface is on (1 or 2)
+    pub bus: u8,
+}
+
+impl VnetIpStation {
+    /// Decode a station from a Vnet/IP interface address.
+    pub fn from_ip(ip: &str) -> Option<Self> {
+        let octets = ip.parse::<Ipv4Addr>().ok()?.octets();
+        if octets[0] != 192 || octets[1] != 168 {
+            return None;
+        }
+        let (domain, bus) = if octets[2] > 128 {
+            (octets[2] - 128, 2)
+        } else {
+            (octets[2], 1)
+        };
+        let station = octets[3];
+        if domain == 0 || domain > MAX_DOMAIN || station == 0 || station == 255 {
+            return None;
+        }
+        Some(Self {
+            domain,
+            station,
+            bus,
+        })
+    }
+
+    /// Station number in Yokogawa's DDSS form, e.g. "0164" for domain 1,
+    /// station 64 (the numeric part of names like HIS0164).
+    pub fn ddss(&self) -> String {
+        format!("{:02}{:02}", self.domain, self.station)
+    }
+}
+
+/// Parsed Vnet/IP datagram information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VnetIpInfo {
+    /// Sending station, if its address follows the Vnet/IP scheme
+    pub src: Option<VnetIpStation>,
+    /// Receiving station (None for broadcasts and off-scheme addresses)
+    pub dst: Option<VnetIpStation>,
+    pub payload_len: usize,
+}
+
+/// Attempt to parse a Vnet/IP datagram.
+///
+/// Returns None for empty payloads, for traffic not on a Vnet/IP port, and
+/// when neither endpoint decodes as a Vnet/IP station.
+pub fn parse(
+    payload: &[u8],
+    src_ip: &str,
+    dst_ip: &str,
+    src_port: u16,
+    dst_port: u16,
+) -> Option<VnetIpInfo> {
+    if payload.is_empty() || !(VNETIP_PORTS.contains(&src_port) || VNETIP_PORTS.contains(&dst_port))
+    {
+        return None;
+    }
+    let src = VnetIpStation::from_ip(src_ip);
+    let dst = VnetIpStation::from_ip(dst_ip);
+    if src.is_none() && dst.is_none() {
+        return None;
+    }
+    Some(VnetIpInfo {
+        src,
+        dst,
+        payload_len: payload.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_station_addressing() {
+        let his = VnetIpStation::from_ip("192.168.1.64").unwrap();
+        assert_eq!((his.domain, his.station, his.bus), (1, 64, 1));
+        assert_eq!(his.ddss(), "0164");
+
+        let fcs = VnetIpStation::from_ip("192.168.130.1").unwrap();
+        assert_eq!((fcs.domain, fcs.station, fcs.bus), (2, 1, 2));
+        assert_eq!(fcs.ddss(), "0201");
+
+        assert!(VnetIpStation::from_ip("10.0.1.64").is_none());
+        assert!(VnetIpStation::from_ip("192.168.0.10").is_none());
+        assert!(VnetIpStation::from_ip("192.168.1.255").is_none());
+        assert!(VnetIpStation::from_ip("192.168.128.5").is_none());
+        assert!(VnetIpStation::from_ip("fe80::1").is_none());
+    }
+
+    #[test]
+    fn test_parse_datagram() {
+        let info = parse(&[0u8; 48], "192.168.1.1", "192.168.1.64", 20171, 20171).unwrap();
+        assert_eq!(info.src.unwrap().station, 1);
+        assert_eq!(info.dst.unwrap().station, 64);
+        assert_eq!(info.payload_len, 48);
+
+        // Broadcast destination still yields the sender
+        let info = parse(&[1, 2, 3], "192.168.1.2", "192.168.1.255", 20172, 20172).unwrap();
+        assert!(info.src.is_some());
+        assert!(info.dst.is_none());
+
+        assert!(parse(&[], "192.168.1.1", "192.168.1.64", 20171, 20171).is_none());
+        assert!(parse(&[1], "10.0.0.1", "10.0.0.2", 20171, 20171).is_none());
+        assert!(parse(&[1], "192.168.1.1", "192.168.1.64", 5000, 5001).is_none());
+    }
+}
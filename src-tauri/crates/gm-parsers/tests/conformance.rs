@@ -0,0 +1,474 @@
+//! Parser conformance suite against real ICS captures.
+//!
+//! Hand-built byte arrays in the unit tests pin individual fields; this
+//! suite runs whole public captures through PCAP reading, protocol
+//! identification, and deep parsing, and checks the results against the
+//! expectations recorded in `tests/pcaps/conformance.yaml`.
+//!
+//! Opt-in, since the captures are not committed:
+//!
+//! ```text
+//! cargo test -p gm-parsers --features conformance --test conformance
+//! ```
+//!
+//! Environment:
+//! - `KK_CONFORMANCE_DIR` — directory holding the manifest and captures
+//!   (default: `<repo>/tests/pcaps`)
+//! - `KK_CONFORMANCE_FETCH=1` — download missing captures from their
+//!   `source` URL with `curl` before checking them
+//! - `KK_CONFORMANCE_RECORD=1` — print what each capture produces as a
+//!   manifest `expect:` block instead of asserting, for pinning a new sample
+
+#![cfg(feature = "conformance")]
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::Read;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use gm_capture::{ParsedPacket, PcapReader};
+use gm_parsers::vendor_tables::{bacnet_vendor_name, cip_vendor_name, profinet_vendor_name};
+use gm_parsers::{
+    deep_parse, detect_engineering_software, identify_protocol, parse_goose, parse_sv,
+    DeepParseResult, IcsProtocol,
+};
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    samples: Vec<Sample>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sample {
+    /// Capture file name, relative to the conformance directory
+    file: String,
+    /// Where the capture is published (used by KK_CONFORMANCE_FETCH)
+    #[serde(default)]
+    source: Option<String>,
+    /// Expected SHA-256 of the capture; verified when present
+    #[serde(default)]
+    sha256: Option<String>,
+    #[serde(default)]
+    expect: Expect,
+}
+
+/// Expectations for one capture. Every field is optional; unset fields
+/// are not checked.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Expect {
+    /// Minimum number of packets read from the capture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_packets: Option<u64>,
+    /// Exact number of distinct addresses seen (the app's asset count)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    assets: Option<usize>,
+    /// Exact number of distinct addresses per identified protocol
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    protocols: BTreeMap<String, usize>,
+    /// Vendor names that must appear in deep-parse identities (case-insensitive substring)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    vendors: Vec<String>,
+    /// Function codes / services that must be observed, per protocol
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    function_codes: BTreeMap<String, Vec<serde_yaml::Value>>,
+    /// Engineering software that must be fingerprinted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    engineering_software: Vec<String>,
+}
+
+/// What the parsers actually produced for a capture.
+#[derive(Debug, Default)]
+struct Observed {
+    packets: u64,
+    addresses: HashSet<String>,
+    protocol_addresses: HashMap<IcsProtocol, HashSet<String>>,
+    vendors: BTreeSet<String>,
+    function_codes: BTreeMap<String, BTreeSet<String>>,
+    engineering_software: BTreeSet<String>,
+}
+
+impl Observed {
+    fn record(&mut self, packet: &ParsedPacket) {
+        self.packets += 1;
+
+        // Layer-2 IEC 61850 publishers are keyed by MAC, like the app's assets
+        if let Some(mac) = packet.src_ip.strip_prefix("goose:") {
+            if parse_goose(&packet.payload).is_some() {
+                self.add_protocol(IcsProtocol::Goose, &[mac]);
+            }
+            return;
+        }
+        if let Some(mac) = packet.src_ip.strip_prefix("sv:") {
+            if parse_sv(&packet.payload).is_some() {
+                self.add_protocol(IcsProtocol::SampledValues, &[mac]);
+            }
+            return;
+        }
+        // Other sentinel packets (LLDP, redundancy) carry no addresses
+        if packet.src_ip.parse::<IpAddr>().is_err() {
+            return;
+        }
+
+        self.addresses.insert(packet.src_ip.clone());
+        self.addresses.insert(packet.dst_ip.clone());
+
+        let protocol = identify_protocol(packet).protocol;
+        if protocol != IcsProtocol::Unknown {
+            self.add_protocol(protocol, &[&packet.src_ip, &packet.dst_ip]);
+        }
+
+        if let Some(fp) =
+            detect_engineering_software(&packet.payload, packet.src_port, packet.dst_port)
+        {
+            self.engineering_software
+                .insert(fp.software.display_name().to_string());
+        }
+
+        let Some(result) = deep_parse(packet, protocol) else {
+            return;
+        };
+        match result {
+            DeepParseResult::Modbus { pdus } => {
+                for info in pdus {
+                    self.add_code("modbus", info.function_code.to_string());
+                    if let Some(vendor) = info.device_id.and_then(|d| d.vendor_name) {
+                        self.vendors.insert(vendor);
+                    }
+                }
+            }
+            DeepParseResult::Dnp3(info) => {
+                if let Some(fc) = info.function_code {
+                    self.add_code("dnp3", fc.to_string());
+                }
+            }
+            DeepParseResult::Enip(info) => {
+                if let Some(ref service) = info.cip_service {
+                    self.add_code("ethernet_ip", serde_name(service));
+                }
+                if let Some(identity) = info.identity {
+                    self.vendors
+                        .insert(cip_vendor_name(identity.vendor_id).to_string());
+                }
+            }
+            DeepParseResult::S7(info) => {
+                if let Some(ref function) = info.s7_function {
+                    self.add_code("s7comm", serde_name(function));
+                }
+            }
+            DeepParseResult::Bacnet(info) => {
+                if let Some(ref service) = info.service {
+                    self.add_code("bacnet", serde_name(service));
+                }
+                if let Some(iam) = info.iam {
+                    self.vendors
+                        .insert(bacnet_vendor_name(iam.vendor_id).to_string());
+                }
+            }
+            DeepParseResult::Iec104(info) => {
+                if let Some(ref type_id) = info.type_id {
+                    self.add_code("iec104", serde_name(type_id));
+                }
+            }
+            DeepParseResult::Mms(info) => {
+                if let Some(ref service) = info.service {
+                    self.add_code("iec61850_mms", serde_name(service));
+                }
+                if let Some(vendor) = info.identity.and_then(|i| i.vendor) {
+                    self.vendors.insert(vendor);
+                }
+            }
+            DeepParseResult::ProfinetDcp(info) => {
+                let device = info.device_info;
+                if let Some(vendor) = device.vendor_name {
+                    self.vendors.insert(vendor);
+                } else if let Some(id) = device.vendor_id {
+                    self.vendors.insert(profinet_vendor_name(id).to_string());
+                }
+            }
+            DeepParseResult::Lldp(info) => {
+                if let Some(vendor) = info.vendor {
+                    self.vendors.insert(vendor);
+                }
+            }
+        }
+    }
+
+    fn add_protocol(&mut self, protocol: IcsProtocol, addresses: &[&str]) {
+        let set = self.protocol_addresses.entry(protocol).or_default();
+        for addr in addresses {
+            self.addresses.insert(addr.to_string());
+            set.insert(addr.to_string());
+        }
+    }
+
+    fn add_code(&mut self, protocol: &str, code: String) {
+        self.function_codes
+            .entry(protocol.to_string())
+            .or_default()
+            .insert(code);
+    }
+
+    fn protocol_counts(&self) -> BTreeMap<String, usize> {
+        self.protocol_addresses
+            .iter()
+            .map(|(p, addrs)| (p.to_name().to_string(), addrs.len()))
+            .collect()
+    }
+
+    /// The observations as an `expect:` block, for recording a new sample.
+    fn as_expect(&self) -> Expect {
+        Expect {
+            min_packets: Some(self.packets),
+            assets: Some(self.addresses.len()),
+            protocols: self.protocol_counts(),
+            vendors: self.vendors.iter().cloned().collect(),
+            function_codes: self
+                .function_codes
+                .iter()
+                .map(|(p, codes)| {
+                    let values = codes
+                        .iter()
+                        .map(|c| match c.parse::<u64>() {
+                            Ok(n) => serde_yaml::Value::from(n),
+                            Err(_) => serde_yaml::Value::from(c.as_str()),
+                        })
+                        .collect();
+                    (p.clone(), values)
+                })
+                .collect(),
+            engineering_software: self.engineering_software.iter().cloned().collect(),
+        }
+    }
+
+    /// Compare against expectations, returning one message per mismatch.
+    fn check(&self, expect: &Expect) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        if let Some(min) = expect.min_packets {
+            if self.packets < min {
+                failures.push(format!(
+                    "read {} packets, expected at least {}",
+                    self.packets, min
+                ));
+            }
+        }
+        if let Some(assets) = expect.assets {
+            if self.addresses.len() != assets {
+                failures.push(format!(
+                    "{} assets, expected {}",
+                    self.addresses.len(),
+                    assets
+                ));
+            }
+        }
+
+        let counts = self.protocol_counts();
+        for (protocol, expected) in &expect.protocols {
+            let actual = counts.get(protocol).copied().unwrap_or(0);
+            if actual != *expected {
+                failures.push(format!(
+                    "{} assets speaking {}, expected {}",
+                    actual, protocol, expected
+                ));
+            }
+        }
+
+        for vendor in &expect.vendors {
+            let needle = vendor.to_lowercase();
+            if !self
+                .vendors
+                .iter()
+                .any(|v| v.to_lowercase().contains(&needle))
+            {
+                failures.push(format!(
+                    "vendor {:?} not identified (saw {:?})",
+                    vendor, self.vendors
+                ));
+            }
+        }
+
+        for (protocol, codes) in &expect.function_codes {
+            let seen = self.function_codes.get(protocol);
+            for code in codes {
+                let code = value_string(code);
+                if !seen.is_some_and(|s| s.contains(&code)) {
+                    failures.push(format!(
+                        "{} function code {} not observed (saw {:?})",
+                        protocol,
+                        code,
+                        seen.cloned().unwrap_or_default()
+                    ));
+                }
+            }
+        }
+
+        for software in &expect.engineering_software {
+            if !self.engineering_software.contains(software) {
+                failures.push(format!(
+                    "engineering software {:?} not fingerprinted (saw {:?})",
+                    software, self.engineering_software
+                ));
+            }
+        }
+
+        failures
+    }
+}
+
+/// Serde (snake_case) name of a parser enum; data-carrying variants such
+/// as `Unknown(0x42)` fall back to their Debug form.
+fn serde_name<T: Serialize + std::fmt::Debug>(value: &T) -> String {
+    match serde_yaml::to_value(value) {
+        Ok(serde_yaml::Value::String(s)) => s,
+        _ => format!("{:?}", value),
+    }
+}
+
+fn value_string(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.clone(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    }
+}
+
+fn conformance_dir() -> PathBuf {
+    std::env::var_os("KK_CONFORMANCE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../tests/pcaps"))
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn fetch(source: &str, dest: &Path) -> Result<(), String> {
+    let status = Command::new("curl")
+        .args([
+            "--fail",
+            "--location",
+            "--silent",
+            "--show-error",
+            "--output",
+        ])
+        .arg(dest)
+        .arg(source)
+        .status()
+        .map_err(|e| format!("could not run curl: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        let _ = std::fs::remove_file(dest);
+        Err(format!("curl exited with {}", status))
+    }
+}
+
+/// Locate (fetching if allowed), verify, and run one sample.
+fn run_sample(dir: &Path, sample: &Sample, record: bool) -> Result<Vec<String>, String> {
+    let path = dir.join(&sample.file);
+    if !path.exists() {
+        match (&sample.source, env_flag("KK_CONFORMANCE_FETCH")) {
+            (Some(source), true) => fetch(source, &path)?,
+            (Some(source), false) => {
+                return Err(format!(
+                    "missing; download it from {} or set KK_CONFORMANCE_FETCH=1",
+                    source
+                ))
+            }
+            (None, _) => return Err("missing and no source URL in the manifest".to_string()),
+        }
+    }
+
+    if let Some(ref expected) = sample.sha256 {
+        let actual = sha256_file(&path).map_err(|e| e.to_string())?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!("sha256 {} does not match the manifest", actual));
+        }
+    }
+
+    let packets = PcapReader::new()
+        .read_file(&path)
+        .map_err(|e| e.to_string())?;
+    let mut observed = Observed::default();
+    for packet in &packets {
+        observed.record(packet);
+    }
+
+    if record {
+        let block = serde_yaml::to_string(&observed.as_expect()).map_err(|e| e.to_string())?;
+        println!("# {}\nexpect:\n{}", sample.file, indent(&block));
+        return Ok(Vec::new());
+    }
+    Ok(observed.check(&sample.expect))
+}
+
+fn indent(block: &str) -> String {
+    block
+        .lines()
+        .map(|l| format!("  {}", l))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn public_captures_match_recorded_expectations() {
+    let dir = conformance_dir();
+    let manifest_path = dir.join("conformance.yaml");
+    let text = std::fs::read_to_string(&manifest_path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", manifest_path.display(), e));
+    let manifest: Manifest = serde_yaml::from_str(&text)
+        .unwrap_or_else(|e| panic!("invalid {}: {}", manifest_path.display(), e));
+
+    if manifest.samples.is_empty() {
+        eprintln!(
+            "conformance: no samples listed in {}",
+            manifest_path.display()
+        );
+        return;
+    }
+
+    let record = env_flag("KK_CONFORMANCE_RECORD");
+    let mut report = Vec::new();
+    for sample in &manifest.samples {
+        match run_sample(&dir, sample, record) {
+            Ok(failures) if failures.is_empty() => {
+                eprintln!("conformance: {} ok", sample.file);
+            }
+            Ok(failures) => {
+                for failure in failures {
+                    report.push(format!("{}: {}", sample.file, failure));
+                }
+            }
+            Err(e) => report.push(format!("{}: {}", sample.file, e)),
+        }
+    }
+
+    assert!(
+        report.is_empty(),
+        "{} conformance failure(s):\n  {}",
+        report.len(),
+        report.join("\n  ")
+    );
+}
@@ -0,0 +1,312 @@
+//! LLDP and CDP advertisements observed in captured traffic.
+//!
+//! Switches and many field devices send an LLDP frame (EtherType 0x88CC)
+//! out of every port every 30 seconds; Cisco gear also sends CDP once a
+//! minute. Each frame names the sending device and the port it left from,
+//! which is exactly what a `show lldp neighbors` / `show cdp neighbors`
+//! line on the receiving side would hold. Turning those frames into
+//! [`CdpNeighbor`] records lets the physical topology be built from a
+//! capture alone, without switch config exports.
+//!
+//! A capture taken on a tap between two devices contains the discovery
+//! frames of both ends, so when a capture segment (PCAP file or live interface)
+//! holds advertisements from exactly two devices they are linked to each
+//! other.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{normalize_mac, CdpNeighbor, PhysicalPort, PhysicalSwitch, PhysicalTopology};
+
+/// One device's LLDP or CDP advertisement as seen on the wire.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeighborAdvertisement {
+    /// Discovery protocol: "lldp" or "cdp"
+    pub protocol: String,
+    /// Source MAC of the frame (the advertising port's MAC)
+    pub source_mac: String,
+    /// LLDP Chassis ID (CDP has none)
+    pub chassis_id: Option<String>,
+    /// The advertising device's own port (LLDP Port ID / CDP Port ID)
+    pub port_id: Option<String>,
+    /// LLDP Port Description
+    pub port_description: Option<String>,
+    /// LLDP System Name / CDP Device ID
+    pub system_name: Option<String>,
+    /// Model from the LLDP System Description or the CDP Platform
+    pub model: Option<String>,
+    /// Firmware from the LLDP System Description or CDP Software Version
+    pub firmware: Option<String>,
+    /// First IPv4 management address
+    pub management_ip: Option<String>,
+    /// Enabled capabilities in the protocol's own names
+    /// ("Bridge", "Router", "Station" for LLDP; "Switch", "Host" for CDP)
+    pub capabilities: Vec<String>,
+    /// VLAN IDs (LLDP 802.1 TLVs / CDP native VLAN)
+    pub vlan_ids: Vec<u16>,
+    /// Capture segment the frame was seen on (PCAP file name or interface)
+    pub segment: String,
+}
+
+impl NeighborAdvertisement {
+    /// Name the advertising device goes by: system name, else chassis ID,
+    /// else the frame's source MAC.
+    pub fn device_id(&self) -> String {
+        self.system_name
+            .clone()
+            .or_else(|| self.chassis_id.clone())
+            .unwrap_or_else(|| normalize_mac(&self.source_mac))
+    }
+
+    /// The advertising port, falling back to the source MAC.
+    pub fn port_name(&self) -> String {
+        self.port_id
+            .clone()
+            .unwrap_or_else(|| normalize_mac(&self.source_mac))
+    }
+
+    /// Whether the device forwards frames for others (bridge or router).
+    pub fn is_switch(&self) -> bool {
+        self.capabilities
+            .iter()
+            .any(|c| matches!(c.as_str(), "Bridge" | "Switch" | "Trans-Bridge" | "Router"))
+    }
+
+    /// The neighbor record a device receiving this frame would list.
+    ///
+    /// Capability names follow CDP ("Switch" rather than LLDP's "Bridge")
+    /// so neighbors from imports and from traffic read the same.
+    pub fn to_neighbor(&self) -> CdpNeighbor {
+        CdpNeighbor {
+            device_id: self.device_id(),
+            remote_port: self.port_name(),
+            platform: self.model.clone(),
+            ip_address: self.management_ip.clone(),
+            capabilities: self
+                .capabilities
+                .iter()
+                .map(|c| match c.as_str() {
+                    "Bridge" => "Switch".to_string(),
+                    other => other.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PhysicalTopology {
+    /// Merge LLDP advertisements seen in traffic into the topology.
+    ///
+    /// Every advertising switch or router is added (or updated, if already
+    /// imported from its config) together with the port it advertised from.
+    /// Where a segment holds advertisements from exactly two devices, each
+    /// switch-side port gets the other device as its neighbor. Returns the
+    /// number of neighbor records applied; call [`Self::build_links`]
+    /// afterwards to refresh the link list.
+    pub fn apply_neighbor_advertisements(&mut self, adverts: &[NeighborAdvertisement]) -> usize {
+        for advert in adverts.iter().filter(|a| a.is_switch()) {
+            self.upsert_advertised_port(advert);
+        }
+
+        // segment → device ID → advertisement (one per device suffices)
+        let mut by_segment: BTreeMap<&str, BTreeMap<String, &NeighborAdvertisement>> =
+            BTreeMap::new();
+        for advert in adverts {
+            by_segment
+                .entry(advert.segment.as_str())
+                .or_default()
+                .entry(advert.device_id())
+                .or_insert(advert);
+        }
+
+        let mut applied = 0;
+        for devices in by_segment.values() {
+            if devices.len() != 2 {
+                continue;
+            }
+            let pair: Vec<&NeighborAdvertisement> = devices.values().copied().collect();
+            for (local, remote) in [(pair[0], pair[1]), (pair[1], pair[0])] {
+                if !local.is_switch() {
+                    continue;
+                }
+                let neighbor = remote.to_neighbor();
+                let port = self.upsert_advertised_port(local);
+                port.cdp_neighbor = Some(neighbor);
+                applied += 1;
+            }
+        }
+        applied
+    }
+
+    /// Find or create the switch and port an advertisement came from.
+    fn upsert_advertised_port(&mut self, advert: &NeighborAdvertisement) -> &mut PhysicalPort {
+        let hostname = advert.device_id();
+        let sw_idx = match self.switches.iter().position(|s| s.hostname == hostname) {
+            Some(idx) => idx,
+            None => {
+                self.switches.push(PhysicalSwitch {
+                    hostname,
+                    management_ip: None,
+                    model: None,
+                    ios_version: None,
+                    ports: Vec::new(),
+                    vlans: Default::default(),
+                });
+                self.switches.len() - 1
+            }
+        };
+
+        let sw = &mut self.switches[sw_idx];
+        if sw.management_ip.is_none() {
+            sw.management_ip = advert.management_ip.clone();
+        }
+        if sw.model.is_none() {
+            sw.model = advert.model.clone();
+        }
+        if sw.ios_version.is_none() {
+            sw.ios_version = advert.firmware.clone();
+        }
+
+        let port_name = advert.port_name();
+        let port_idx = match sw
+            .ports
+            .iter()
+            .position(|p| p.name == port_name || p.short_name == port_name)
+        {
+            Some(idx) => idx,
+            None => {
+                sw.ports.push(PhysicalPort {
+                    name: port_name.clone(),
+                    short_name: port_name,
+                    description: None,
+                    vlans: Vec::new(),
+                    mode: "unknown".to_string(),
+                    shutdown: false,
+                    ip_address: None,
+                    subnet_mask: None,
+                    mac_addresses: Vec::new(),
+                    ip_addresses: Vec::new(),
+                    cdp_neighbor: None,
+                    speed: None,
+                    duplex: None,
+                });
+                sw.ports.len() - 1
+            }
+        };
+
+        let port = &mut sw.ports[port_idx];
+        if port.description.is_none() {
+            port.description = advert.port_description.clone();
+        }
+        for vlan in &advert.vlan_ids {
+            if !port.vlans.contains(vlan) {
+                port.vlans.push(*vlan);
+            }
+        }
+        port
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advert(name: &str, port: &str, caps: &[&str], segment: &str) -> NeighborAdvertisement {
+        NeighborAdvertisement {
+            protocol: "lldp".to_string(),
+            source_mac: "00:1b:1b:00:00:01".to_string(),
+            chassis_id: None,
+            port_id: Some(port.to_string()),
+            port_description: None,
+            system_name: Some(name.to_string()),
+            model: Some("SCALANCE XC208".to_string()),
+            firmware: None,
+            management_ip: Some("10.0.0.2".to_string()),
+            capabilities: caps.iter().map(|c| c.to_string()).collect(),
+            vlan_ids: vec![10],
+            segment: segment.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_to_neighbor_uses_cdp_capability_names() {
+        let neighbor = advert("sw-cell-1", "port 3", &["Bridge", "Router"], "a.pcap").to_neighbor();
+        assert_eq!(neighbor.device_id, "sw-cell-1");
+        assert_eq!(neighbor.remote_port, "port 3");
+        assert_eq!(neighbor.platform.as_deref(), Some("SCALANCE XC208"));
+        assert_eq!(neighbor.capabilities, vec!["Switch", "Router"]);
+    }
+
+    #[test]
+    fn test_cdp_switch_links_to_lldp_device() {
+        let cdp = NeighborAdvertisement {
+            protocol: "cdp".to_string(),
+            model: Some("cisco WS-C2960X-24PS-L".to_string()),
+            ..advert(
+                "sw-access-3",
+                "GigabitEthernet1/0/7",
+                &["Switch", "IGMP"],
+                "span",
+            )
+        };
+        assert!(cdp.is_switch());
+        let adverts = vec![cdp, advert("hmi-02", "eth0", &["Station"], "span")];
+
+        let mut topo = PhysicalTopology::default();
+        assert_eq!(topo.apply_neighbor_advertisements(&adverts), 1);
+        let sw = &topo.switches[0];
+        assert_eq!(sw.model.as_deref(), Some("cisco WS-C2960X-24PS-L"));
+        assert_eq!(sw.ports[0].name, "GigabitEthernet1/0/7");
+        assert_eq!(
+            sw.ports[0].cdp_neighbor.as_ref().unwrap().device_id,
+            "hmi-02"
+        );
+    }
+
+    #[test]
+    fn test_tapped_link_builds_switch_and_neighbor() {
+        let mut topo = PhysicalTopology::default();
+        let adverts = vec![
+            advert("sw-core", "Gi1/0/24", &["Bridge"], "uplink.pcap"),
+            advert("plc-01", "X1 P1", &["Station"], "uplink.pcap"),
+            // Seen alone on another segment: recorded, but no neighbor
+            advert("sw-cell-2", "port 1", &["Bridge"], "cell2.pcap"),
+        ];
+
+        assert_eq!(topo.apply_neighbor_advertisements(&adverts), 1);
+        topo.build_links();
+
+        assert_eq!(topo.switches.len(), 2);
+        let core = topo
+            .switches
+            .iter()
+            .find(|s| s.hostname == "sw-core")
+            .unwrap();
+        assert_eq!(core.management_ip.as_deref(), Some("10.0.0.2"));
+        assert_eq!(core.ports[0].vlans, vec![10]);
+        assert_eq!(
+            core.ports[0].cdp_neighbor.as_ref().unwrap().device_id,
+            "plc-01"
+        );
+        assert_eq!(topo.links.len(), 1);
+        assert_eq!(topo.links[0].dst_port, "X1 P1");
+    }
+
+    #[test]
+    fn test_reapplying_is_idempotent() {
+        let mut topo = PhysicalTopology::default();
+        let adverts = vec![
+            advert("sw-a", "Gi1/0/1", &["Bridge"], "tap"),
+            advert("sw-b", "Gi0/48", &["Bridge"], "tap"),
+        ];
+        topo.apply_neighbor_advertisements(&adverts);
+        // Re-applying (e.g. on the next live-capture refresh) changes nothing
+        assert_eq!(topo.apply_neighbor_advertisements(&adverts), 2);
+        topo.build_links();
+
+        assert_eq!(topo.switches.len(), 2);
+        assert!(topo.switches.iter().all(|s| s.ports.len() == 1));
+        assert_eq!(topo.links.len(), 2);
+    }
+}
@@ -3,10 +3,12 @@
 //! Parses network device configs and MAC/ARP tables to build a physical
 //! switch-port topology. Supports Cisco IOS, Juniper JunOS, HP/Aruba
 //! ProCurve, and generic CSV/JSON import. Includes traffic-inference
-//! to derive topology structure from observed packet flows.
+//! to derive topology structure from observed packet flows, and LLDP/CDP
+//! advertisements captured on the wire as a neighbor source.
 
 pub mod aruba;
 pub mod cisco;
+pub mod discovery;
 pub mod error;
 pub mod generic;
 pub mod inference;
@@ -15,6 +17,7 @@ pub mod juniper;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub use discovery::NeighborAdvertisement;
 pub use error::PhysicalError;
 pub use generic::GenericDevice;
 pub use inference::{
@@ -0,0 +1,207 @@
+//! Deterministic pseudonymization for demo / training material.
+//!
+//! An [`Anonymizer`] is keyed by a per-session seed. The same input always
+//! maps to the same pseudonym under the same seed, so the topology, asset
+//! list, connection table, and every export stay consistent with each other
+//! and across reloads of the session — but nothing maps back to the capture
+//! without the seed.
+//!
+//! - IPv4 addresses are prefix-preserving per /24: every host in one real
+//!   subnet lands in the same pseudo subnet under `10.0.0.0/8`, and the host
+//!   octet is kept so gateways (`.1`) and broadcast (`.255`) stay recognisable.
+//! - IPv6 addresses map into the `fd00::/8` unique-local range.
+//! - MAC addresses become locally administered (`02:…`).
+//! - Hostnames and vendor strings become opaque tokens (`host-3fa2c1`,
+//!   `Vendor-7c1e`).
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ExportAsset, ExportConnection};
+
+/// FNV-1a 64-bit offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a 64-bit prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Seeded, deterministic pseudonymizer for identifying strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Anonymizer {
+    seed: u64,
+}
+
+impl Anonymizer {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// The seed this anonymizer was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Keyed hash of `value` within a namespace (`"net"`, `"mac"`, …).
+    ///
+    /// FNV-1a is used instead of `DefaultHasher` because its output is stable
+    /// across Rust releases, which keeps saved sessions' pseudonyms stable.
+    fn hash(&self, namespace: &str, value: &str) -> u64 {
+        let mut h = FNV_OFFSET;
+        for b in self
+            .seed
+            .to_le_bytes()
+            .iter()
+            .chain(namespace.as_bytes())
+            .chain(&[0u8])
+            .chain(value.as_bytes())
+        {
+            h ^= *b as u64;
+            h = h.wrapping_mul(FNV_PRIME);
+        }
+        // Final avalanche so short inputs still spread across all bits
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h
+    }
+
+    /// Pseudonymize an IP address. Non-IP strings get an opaque token.
+    pub fn ip(&self, ip: &str) -> String {
+        if let Ok(v4) = ip.parse::<std::net::Ipv4Addr>() {
+            let o = v4.octets();
+            let net = self.hash("net", &format!("{}.{}.{}", o[0], o[1], o[2]));
+            return format!("10.{}.{}.{}", (net >> 8) as u8, net as u8, o[3]);
+        }
+        if let Ok(v6) = ip.parse::<std::net::Ipv6Addr>() {
+            let h = self.hash("ip6", &v6.to_string());
+            let l = self.hash("ip6-low", &v6.to_string());
+            return std::net::Ipv6Addr::new(
+                0xfd00 | ((h >> 56) as u16 & 0xff),
+                (h >> 40) as u16,
+                (h >> 24) as u16,
+                (h >> 8) as u16,
+                (l >> 48) as u16,
+                (l >> 32) as u16,
+                (l >> 16) as u16,
+                l as u16,
+            )
+            .to_string();
+        }
+        format!("addr-{:06x}", self.hash("addr", ip) & 0xff_ffff)
+    }
+
+    /// Pseudonymize a MAC address as a locally administered unicast address.
+    pub fn mac(&self, mac: &str) -> String {
+        let h = self.hash("mac", &mac.to_ascii_lowercase()).to_be_bytes();
+        format!(
+            "02:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            h[0], h[1], h[2], h[3], h[4]
+        )
+    }
+
+    /// Pseudonymize a hostname.
+    pub fn hostname(&self, hostname: &str) -> String {
+        format!(
+            "host-{:06x}",
+            self.hash("host", &hostname.to_ascii_lowercase()) & 0xff_ffff
+        )
+    }
+
+    /// Pseudonymize a vendor / product string.
+    pub fn vendor(&self, vendor: &str) -> String {
+        format!("Vendor-{:04x}", self.hash("vendor", vendor) & 0xffff)
+    }
+
+    /// Pseudonymize every identifying field of an exported asset in place.
+    pub fn anonymize_asset(&self, asset: &mut ExportAsset) {
+        asset.ip_address = self.ip(&asset.ip_address);
+        asset.mac_address = asset.mac_address.as_deref().map(|m| self.mac(m));
+        asset.hostname = asset.hostname.as_deref().map(|h| self.hostname(h));
+        asset.vendor = asset.vendor.as_deref().map(|v| self.vendor(v));
+        asset.oui_vendor = asset.oui_vendor.as_deref().map(|v| self.vendor(v));
+        asset.product_family = asset.product_family.as_deref().map(|v| self.vendor(v));
+        // Free text may name people, sites, or addresses
+        asset.notes.clear();
+    }
+
+    /// Pseudonymize the endpoints of an exported connection in place.
+    pub fn anonymize_connection(&self, conn: &mut ExportConnection) {
+        conn.src_ip = self.ip(&conn.src_ip);
+        conn.dst_ip = self.ip(&conn.dst_ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic_per_seed() {
+        let a = Anonymizer::new(42);
+        let b = Anonymizer::new(42);
+        let c = Anonymizer::new(43);
+        assert_eq!(a.ip("192.168.1.10"), b.ip("192.168.1.10"));
+        assert_eq!(a.mac("00:1b:1b:aa:bb:cc"), b.mac("00:1B:1B:AA:BB:CC"));
+        assert_ne!(a.ip("192.168.1.10"), c.ip("192.168.1.10"));
+        assert_ne!(a.hostname("plc-01"), c.hostname("plc-01"));
+    }
+
+    #[test]
+    fn test_ipv4_preserves_subnet_and_host_octet() {
+        let a = Anonymizer::new(7);
+        let x = a.ip("192.168.1.10");
+        let y = a.ip("192.168.1.20");
+        let z = a.ip("192.168.2.10");
+        assert!(x.starts_with("10."));
+        assert!(x.ends_with(".10"));
+        assert_eq!(x.rsplit_once('.').unwrap().0, y.rsplit_once('.').unwrap().0);
+        assert_ne!(x.rsplit_once('.').unwrap().0, z.rsplit_once('.').unwrap().0);
+    }
+
+    #[test]
+    fn test_ipv6_and_non_ip() {
+        let a = Anonymizer::new(1);
+        let v6 = a.ip("2001:db8::1");
+        assert!(v6.starts_with("fd"));
+        assert!(v6.parse::<std::net::Ipv6Addr>().is_ok());
+        assert!(a.ip("lldp:00:11:22:33:44:55").starts_with("addr-"));
+    }
+
+    #[test]
+    fn test_mac_is_locally_administered() {
+        let a = Anonymizer::new(99);
+        let mac = a.mac("00:0e:8c:01:02:03");
+        assert!(mac.starts_with("02:"));
+        assert_eq!(mac.len(), 17);
+    }
+
+    #[test]
+    fn test_anonymize_asset_fields() {
+        let a = Anonymizer::new(5);
+        let mut asset = ExportAsset {
+            ip_address: "10.1.1.5".to_string(),
+            mac_address: Some("00:0e:8c:01:02:03".to_string()),
+            hostname: Some("plant-a-plc1".to_string()),
+            device_type: "plc".to_string(),
+            vendor: Some("Siemens".to_string()),
+            product_family: Some("S7-1500".to_string()),
+            protocols: vec!["s7comm".to_string()],
+            confidence: 5,
+            purdue_level: Some(1),
+            oui_vendor: Some("Siemens AG".to_string()),
+            country: None,
+            is_public_ip: false,
+            first_seen: String::new(),
+            last_seen: String::new(),
+            notes: "Cabinet 4, Line 2".to_string(),
+            tags: vec![],
+            packet_count: 10,
+        };
+        a.anonymize_asset(&mut asset);
+        assert_eq!(asset.ip_address, a.ip("10.1.1.5"));
+        assert!(asset.hostname.unwrap().starts_with("host-"));
+        assert!(asset.vendor.unwrap().starts_with("Vendor-"));
+        assert!(asset.notes.is_empty());
+        // Non-identifying fields survive
+        assert_eq!(asset.device_type, "plc");
+        assert_eq!(asset.protocols, vec!["s7comm".to_string()]);
+    }
+}
@@ -63,13 +63,13 @@ pub fn connections_to_csv(connections: &[ExportConnection]) -> Result<String, Re
     writeln!(
         buf,
         "Source IP,Source Port,Destination IP,Destination Port,Protocol,\
-         Transport,Packet Count,Byte Count,First Seen,Last Seen"
+         Transport,Packet Count,Byte Count,First Seen,Last Seen,Direction Basis"
     )?;
 
     for conn in connections {
         writeln!(
             buf,
-            "{},{},{},{},{},{},{},{},{},{}",
+            "{},{},{},{},{},{},{},{},{},{},{}",
             csv_escape(&conn.src_ip),
             conn.src_port,
             csv_escape(&conn.dst_ip),
@@ -80,6 +80,7 @@ pub fn connections_to_csv(connections: &[ExportConnection]) -> Result<String, Re
             conn.byte_count,
             csv_escape(&conn.first_seen),
             csv_escape(&conn.last_seen),
+            csv_escape(&conn.direction_basis),
         )?;
     }
 
@@ -130,6 +131,7 @@ mod tests {
             byte_count: 5000,
             first_seen: "2025-01-01T00:00:00Z".to_string(),
             last_seen: "2025-01-02T00:00:00Z".to_string(),
+            direction_basis: "role".to_string(),
         }
     }
 
@@ -148,6 +150,8 @@ mod tests {
         assert!(csv.starts_with("Source IP,"));
         assert!(csv.contains("192.168.1.10"));
         assert!(csv.contains("modbus"));
+        assert!(csv.lines().next().unwrap().ends_with(",Direction Basis"));
+        assert!(csv.lines().nth(1).unwrap().ends_with(",role"));
     }
 
     #[test]
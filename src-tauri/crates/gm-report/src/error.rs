@@ -15,4 +15,7 @@ pub enum ReportError {
 
     #[error("No data available: {0}")]
     NoData(String),
+
+    #[error("Unsupported export format: {0}")]
+    UnsupportedFormat(String),
 }
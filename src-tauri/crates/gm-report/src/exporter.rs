@@ -0,0 +1,486 @@
+//! Pluggable export sinks.
+//!
+//! Every output format implements [`Exporter`] and is looked up by its
+//! format name in an [`ExporterRegistry`], so the app exposes a single
+//! export command instead of one per format. Adding a format means
+//! implementing the trait and registering it in
+//! [`ExporterRegistry::with_builtin`].
+//!
+//! The topology graph formats (GraphML, GEXF, DOT) wrap gm-topology's own
+//! writers and expect the caller to attach the graph to the bundle (see
+//! [`Exporter::needs_topology`]).
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ReportConfig, ReportData, ReportError};
+
+/// Per-export settings. Each exporter reads only the fields it needs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// PDF report settings (defaults apply when absent)
+    #[serde(default)]
+    pub report: Option<ReportConfig>,
+}
+
+/// A format the registry can write, as listed to the frontend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportFormatInfo {
+    /// Registry key, e.g. "assets_csv"
+    pub format: String,
+    /// Human-readable name, e.g. "Assets (CSV)"
+    pub label: String,
+    /// File extension without the dot
+    pub extension: String,
+}
+
+/// An output format that writes a [`ReportData`] bundle to a file.
+pub trait Exporter: Send + Sync {
+    /// Registry key, e.g. "assets_csv" or "pdf".
+    fn format(&self) -> &'static str;
+
+    /// Human-readable name for format pickers.
+    fn label(&self) -> &'static str;
+
+    /// File extension without the dot.
+    fn extension(&self) -> &'static str;
+
+    /// Whether the caller must fill in [`ReportData::topology`] first.
+    /// Building the full graph is not free, so it is only done for the
+    /// formats that write it.
+    fn needs_topology(&self) -> bool {
+        false
+    }
+
+    /// Write the export to `output_path`.
+    fn export(
+        &self,
+        data: &ReportData,
+        options: &ExportOptions,
+        output_path: &str,
+    ) -> Result<(), ReportError>;
+}
+
+/// Exporters keyed by format name.
+pub struct ExporterRegistry {
+    exporters: BTreeMap<&'static str, Box<dyn Exporter>>,
+}
+
+impl ExporterRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self {
+            exporters: BTreeMap::new(),
+        }
+    }
+
+    /// A registry holding every format gm-report ships.
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(AssetsCsv));
+        registry.register(Box::new(ConnectionsCsv));
+        registry.register(Box::new(AssetsJson));
+        registry.register(Box::new(TopologyJson));
+        registry.register(Box::new(PdfReport));
+        registry.register(Box::new(SbomCsv));
+        registry.register(Box::new(SbomJson));
+        registry.register(Box::new(StixBundle));
+        registry.register(Box::new(TopologyGraphMl));
+        registry.register(Box::new(TopologyGexf));
+        registry.register(Box::new(TopologyDot));
+        registry
+    }
+
+    /// Add an exporter, replacing any previous one with the same format name.
+    pub fn register(&mut self, exporter: Box<dyn Exporter>) {
+        self.exporters.insert(exporter.format(), exporter);
+    }
+
+    /// Look up an exporter by format name.
+    pub fn get(&self, format: &str) -> Option<&dyn Exporter> {
+        self.exporters.get(format).map(|e| e.as_ref())
+    }
+
+    /// Registered formats, sorted by format name.
+    pub fn formats(&self) -> Vec<ExportFormatInfo> {
+        self.exporters
+            .values()
+            .map(|e| ExportFormatInfo {
+                format: e.format().to_string(),
+                label: e.label().to_string(),
+                extension: e.extension().to_string(),
+            })
+            .collect()
+    }
+
+    /// Export `data` in the named format.
+    pub fn export(
+        &self,
+        format: &str,
+        data: &ReportData,
+        options: &ExportOptions,
+        output_path: &str,
+    ) -> Result<(), ReportError> {
+        let exporter = self
+            .get(format)
+            .ok_or_else(|| ReportError::UnsupportedFormat(format.to_string()))?;
+        exporter.export(data, options, output_path)
+    }
+}
+
+impl Default for ExporterRegistry {
+    fn default() -> Self {
+        Self::with_builtin()
+    }
+}
+
+// ─── Built-in Exporters ──────────────────────────────────────
+
+struct AssetsCsv;
+
+impl Exporter for AssetsCsv {
+    fn format(&self) -> &'static str {
+        "assets_csv"
+    }
+    fn label(&self) -> &'static str {
+        "Assets (CSV)"
+    }
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        let csv = crate::csv_export::assets_to_csv(&data.assets)?;
+        crate::csv_export::write_csv_file(path, &csv)
+    }
+}
+
+struct ConnectionsCsv;
+
+impl Exporter for ConnectionsCsv {
+    fn format(&self) -> &'static str {
+        "connections_csv"
+    }
+    fn label(&self) -> &'static str {
+        "Connections (CSV)"
+    }
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        let csv = crate::csv_export::connections_to_csv(&data.connections)?;
+        crate::csv_export::write_csv_file(path, &csv)
+    }
+}
+
+struct AssetsJson;
+
+impl Exporter for AssetsJson {
+    fn format(&self) -> &'static str {
+        "assets_json"
+    }
+    fn label(&self) -> &'static str {
+        "Assets (JSON)"
+    }
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        let json = crate::json_export::assets_to_json(&data.assets)?;
+        crate::json_export::write_json_file(path, &json)
+    }
+}
+
+struct TopologyJson;
+
+impl Exporter for TopologyJson {
+    fn format(&self) -> &'static str {
+        "topology_json"
+    }
+    fn label(&self) -> &'static str {
+        "Topology (JSON)"
+    }
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        let json = crate::json_export::topology_to_json(
+            &data.assets,
+            &data.connections,
+            &data.protocol_stats,
+            data.session_name.as_deref(),
+        )?;
+        crate::json_export::write_json_file(path, &json)
+    }
+}
+
+struct PdfReport;
+
+impl Exporter for PdfReport {
+    fn format(&self) -> &'static str {
+        "pdf"
+    }
+    fn label(&self) -> &'static str {
+        "Assessment report (PDF)"
+    }
+    fn extension(&self) -> &'static str {
+        "pdf"
+    }
+    fn export(
+        &self,
+        data: &ReportData,
+        options: &ExportOptions,
+        path: &str,
+    ) -> Result<(), ReportError> {
+        let config = options.report.clone().unwrap_or_default();
+        crate::pdf::generate_report(&config, data, path)
+    }
+}
+
+struct SbomCsv;
+
+impl Exporter for SbomCsv {
+    fn format(&self) -> &'static str {
+        "sbom_csv"
+    }
+    fn label(&self) -> &'static str {
+        "SBOM (CSV)"
+    }
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        let entries = crate::sbom::assets_to_sbom(&data.assets);
+        std::fs::write(path, crate::sbom::sbom_to_csv(&entries)?)?;
+        Ok(())
+    }
+}
+
+struct SbomJson;
+
+impl Exporter for SbomJson {
+    fn format(&self) -> &'static str {
+        "sbom_json"
+    }
+    fn label(&self) -> &'static str {
+        "SBOM (JSON)"
+    }
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        let entries = crate::sbom::assets_to_sbom(&data.assets);
+        std::fs::write(path, crate::sbom::sbom_to_json(&entries)?)?;
+        Ok(())
+    }
+}
+
+struct StixBundle;
+
+impl Exporter for StixBundle {
+    fn format(&self) -> &'static str {
+        "stix"
+    }
+    fn label(&self) -> &'static str {
+        "STIX 2.1 bundle (JSON)"
+    }
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        let json =
+            crate::stix::generate_stix_bundle(&data.assets, &data.connections, &data.findings)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// The topology the caller attached for a graph format.
+fn topology(data: &ReportData) -> Result<&gm_topology::TopologyGraph, ReportError> {
+    data.topology
+        .as_ref()
+        .ok_or_else(|| ReportError::NoData("topology graph was not built".to_string()))
+}
+
+struct TopologyGraphMl;
+
+impl Exporter for TopologyGraphMl {
+    fn format(&self) -> &'static str {
+        "graphml"
+    }
+    fn label(&self) -> &'static str {
+        "Topology graph (GraphML)"
+    }
+    fn extension(&self) -> &'static str {
+        "graphml"
+    }
+    fn needs_topology(&self) -> bool {
+        true
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        std::fs::write(path, topology(data)?.to_graphml())?;
+        Ok(())
+    }
+}
+
+struct TopologyGexf;
+
+impl Exporter for TopologyGexf {
+    fn format(&self) -> &'static str {
+        "gexf"
+    }
+    fn label(&self) -> &'static str {
+        "Topology graph (GEXF)"
+    }
+    fn extension(&self) -> &'static str {
+        "gexf"
+    }
+    fn needs_topology(&self) -> bool {
+        true
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        std::fs::write(path, topology(data)?.to_gexf())?;
+        Ok(())
+    }
+}
+
+struct TopologyDot;
+
+impl Exporter for TopologyDot {
+    fn format(&self) -> &'static str {
+        "dot"
+    }
+    fn label(&self) -> &'static str {
+        "Topology graph (Graphviz DOT)"
+    }
+    fn extension(&self) -> &'static str {
+        "dot"
+    }
+    fn needs_topology(&self) -> bool {
+        true
+    }
+    fn export(&self, data: &ReportData, _: &ExportOptions, path: &str) -> Result<(), ReportError> {
+        std::fs::write(path, topology(data)?.to_dot())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_data() -> ReportData {
+        ReportData {
+            assets: vec![],
+            connections: vec![],
+            protocol_stats: vec![],
+            findings: vec![],
+            session_name: None,
+            unapproved_write_paths: vec![],
+            external_connectivity: vec![],
+            top_talkers: vec![],
+            topology: None,
+        }
+    }
+
+    #[test]
+    fn test_builtin_formats_registered() {
+        let registry = ExporterRegistry::with_builtin();
+        let formats: Vec<String> = registry.formats().into_iter().map(|f| f.format).collect();
+        for expected in [
+            "assets_csv",
+            "connections_csv",
+            "assets_json",
+            "topology_json",
+            "pdf",
+            "sbom_csv",
+            "sbom_json",
+            "stix",
+            "graphml",
+            "gexf",
+            "dot",
+        ] {
+            assert!(formats.contains(&expected.to_string()), "{}", expected);
+        }
+        assert_eq!(registry.get("sbom_csv").unwrap().extension(), "csv");
+    }
+
+    #[test]
+    fn test_graph_formats_use_attached_topology() {
+        let registry = ExporterRegistry::with_builtin();
+        assert!(registry.get("gexf").unwrap().needs_topology());
+        assert!(!registry.get("assets_csv").unwrap().needs_topology());
+
+        let path = std::env::temp_dir().join(format!("gm-report-graph-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut data = empty_data();
+        let err = registry
+            .export("dot", &data, &ExportOptions::default(), path)
+            .unwrap_err();
+        assert!(matches!(err, ReportError::NoData(_)));
+
+        data.topology = Some(gm_topology::TopologyGraph::default());
+        registry
+            .export("dot", &data, &ExportOptions::default(), path)
+            .unwrap();
+        assert!(std::fs::read_to_string(path)
+            .unwrap()
+            .starts_with("digraph topology {"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_format_rejected() {
+        let registry = ExporterRegistry::new();
+        let err = registry
+            .export("docx", &empty_data(), &ExportOptions::default(), "unused")
+            .unwrap_err();
+        assert!(matches!(err, ReportError::UnsupportedFormat(ref f) if f == "docx"));
+    }
+
+    #[test]
+    fn test_register_custom_and_export() {
+        struct Marker;
+        impl Exporter for Marker {
+            fn format(&self) -> &'static str {
+                "assets_csv"
+            }
+            fn label(&self) -> &'static str {
+                "Marker"
+            }
+            fn extension(&self) -> &'static str {
+                "txt"
+            }
+            fn export(
+                &self,
+                _: &ReportData,
+                _: &ExportOptions,
+                path: &str,
+            ) -> Result<(), ReportError> {
+                std::fs::write(path, "marker")?;
+                Ok(())
+            }
+        }
+
+        let path = std::env::temp_dir().join(format!("gm-report-exporter-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut registry = ExporterRegistry::with_builtin();
+        registry
+            .export("assets_csv", &empty_data(), &ExportOptions::default(), path)
+            .unwrap();
+        assert!(std::fs::read_to_string(path)
+            .unwrap()
+            .starts_with("IP Address,"));
+
+        // A registered exporter replaces the built-in with the same name
+        registry.register(Box::new(Marker));
+        assert_eq!(registry.get("assets_csv").unwrap().label(), "Marker");
+        registry
+            .export("assets_csv", &empty_data(), &ExportOptions::default(), path)
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "marker");
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,14 +1,21 @@
 //! gm-report: PDF report generation, CSV/JSON/SBOM/STIX export
 //! for Kusanagi Kajiki ICS/SCADA network assessment tool.
+//!
+//! Formats are reached through [`ExporterRegistry`], keyed by format name.
 
+pub mod anonymize;
 pub mod csv_export;
 pub mod error;
+pub mod exporter;
 pub mod json_export;
+pub mod netbox;
 pub mod pdf;
 pub mod sbom;
 pub mod stix;
 
+pub use anonymize::Anonymizer;
 pub use error::ReportError;
+pub use exporter::{ExportFormatInfo, ExportOptions, Exporter, ExporterRegistry};
 
 use serde::{Deserialize, Serialize};
 
@@ -86,6 +93,9 @@ pub struct ExportConnection {
     pub byte_count: u64,
     pub first_seen: String,
     pub last_seen: String,
+    /// How the client (src) and server (dst) ends were told apart
+    #[serde(default)]
+    pub direction_basis: String,
 }
 
 /// Protocol statistics for export.
@@ -108,6 +118,51 @@ pub struct ExportFinding {
     pub recommendation: String,
 }
 
+/// A write-capable master → target relationship with no active approval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportWritePath {
+    pub master_ip: String,
+    pub slave_ip: String,
+    pub protocol: String,
+    /// Write function codes used by the master
+    pub function_codes: Vec<u8>,
+    pub write_count: u64,
+}
+
+/// An outbound connection from a plant asset to a cloud or remote-access service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportExternalConnection {
+    pub local_ip: String,
+    pub local_device_type: String,
+    pub remote_ip: String,
+    pub remote_port: u16,
+    pub remote_hostname: Option<String>,
+    /// Service name, e.g. "Azure IoT Hub"
+    pub service: String,
+    /// Human-readable category, e.g. "Remote access"
+    pub category: String,
+    /// How the service was identified, e.g. "TLS SNI"
+    pub evidence: String,
+    pub packet_count: u64,
+}
+
+/// One of the connections carrying the most bytes, with its busiest period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportTopTalker {
+    pub src_ip: String,
+    pub dst_ip: String,
+    pub protocol: String,
+    pub byte_count: u64,
+    /// Width of the time buckets traffic was counted in, in seconds
+    pub bucket_secs: u32,
+    /// Start of the busiest bucket (ISO 8601); None if the traffic was not
+    /// timed
+    pub peak_at: Option<String>,
+    pub peak_bytes: u64,
+    /// Buckets carrying several times the connection's mean
+    pub burst_count: usize,
+}
+
 /// Complete data bundle for report generation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReportData {
@@ -116,6 +171,19 @@ pub struct ReportData {
     pub protocol_stats: Vec<ExportProtocolStat>,
     pub findings: Vec<ExportFinding>,
     pub session_name: Option<String>,
+    /// Write paths awaiting approval, listed up front in the report
+    #[serde(default)]
+    pub unapproved_write_paths: Vec<ExportWritePath>,
+    /// Cloud / remote-access connections, reported under protocol analysis
+    #[serde(default)]
+    pub external_connectivity: Vec<ExportExternalConnection>,
+    /// Busiest connections with their peak traffic, under protocol analysis
+    #[serde(default)]
+    pub top_talkers: Vec<ExportTopTalker>,
+    /// Full topology for the graph file formats; only filled in when one of
+    /// them is requested (see [`Exporter::needs_topology`])
+    #[serde(skip)]
+    pub topology: Option<gm_topology::TopologyGraph>,
 }
 
 #[cfg(test)]
@@ -141,6 +209,10 @@ mod tests {
             protocol_stats: vec![],
             findings: vec![],
             session_name: Some("Test Session".to_string()),
+            unapproved_write_paths: vec![],
+            external_connectivity: vec![],
+            top_talkers: vec![],
+            topology: None,
         };
         let json = serde_json::to_string(&data).unwrap();
         assert!(json.contains("Test Session"));
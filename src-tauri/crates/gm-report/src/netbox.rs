@@ -0,0 +1,507 @@
+//! NetBox export.
+//!
+//! Maps the asset inventory onto NetBox's DCIM/IPAM model: one device per
+//! asset (role from the device type, manufacturer and device type from the
+//! identified vendor/product), an interface carrying the MAC address, and
+//! the asset's IP address assigned to that interface as its primary IP.
+//!
+//! The same mapping feeds both the CSV bulk-import files (one per object
+//! type, imported in the order returned) and the REST API push done by the
+//! app. Names and column headers follow NetBox 3.6+ (`role`, not
+//! `device_role`). The target site must already exist in NetBox.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ExportAsset, ReportError};
+
+/// Options for mapping assets onto NetBox objects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetboxOptions {
+    /// Name of the existing NetBox site the devices belong to
+    pub site: String,
+    /// Device and IP status (NetBox choice value, e.g. "active", "planned")
+    pub status: String,
+    /// Tag slug added to every exported object so discovered assets can be
+    /// filtered in NetBox
+    pub tag: String,
+}
+
+impl Default for NetboxOptions {
+    fn default() -> Self {
+        Self {
+            site: String::new(),
+            status: "active".to_string(),
+            tag: "kusanagi-kajiki".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetboxManufacturer {
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetboxDeviceRole {
+    pub name: String,
+    pub slug: String,
+    /// Hex colour without the leading '#'
+    pub color: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetboxDeviceType {
+    /// Manufacturer name
+    pub manufacturer: String,
+    pub manufacturer_slug: String,
+    pub model: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetboxDevice {
+    /// Unique within the site: hostname if known, else the asset address
+    pub name: String,
+    pub role_slug: String,
+    pub manufacturer: String,
+    pub manufacturer_slug: String,
+    pub device_type: String,
+    pub device_type_slug: String,
+    pub site: String,
+    pub status: String,
+    pub description: String,
+    /// Discovery details (protocols, confidence, Purdue level, sightings)
+    pub comments: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetboxInterface {
+    pub device: String,
+    pub name: String,
+    /// NetBox interface type choice value
+    pub interface_type: String,
+    pub mac_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetboxIpAddress {
+    /// Address with prefix length, e.g. "10.0.1.5/32"
+    pub address: String,
+    pub status: String,
+    pub device: String,
+    pub interface: String,
+    pub is_primary: bool,
+    pub description: String,
+}
+
+/// All NetBox objects needed to represent the inventory.
+#[derive(Debug, Clone, Serialize)]
+pub struct NetboxExport {
+    pub options: NetboxOptions,
+    pub manufacturers: Vec<NetboxManufacturer>,
+    pub device_roles: Vec<NetboxDeviceRole>,
+    pub device_types: Vec<NetboxDeviceType>,
+    pub devices: Vec<NetboxDevice>,
+    pub interfaces: Vec<NetboxInterface>,
+    pub ip_addresses: Vec<NetboxIpAddress>,
+}
+
+/// Name of the interface created on every exported device.
+pub const INTERFACE_NAME: &str = "eth0";
+
+/// Description stamped on exported devices and IP addresses.
+const DESCRIPTION: &str = "Discovered by Kusanagi Kajiki (passive)";
+
+/// NetBox role (name, colour) for a Kusanagi Kajiki device type.
+fn role_for(device_type: &str) -> (&'static str, &'static str) {
+    match device_type {
+        "plc" => ("PLC", "2196f3"),
+        "rtu" => ("RTU", "00bcd4"),
+        "ied" => ("IED", "009688"),
+        "hmi" => ("HMI", "8bc34a"),
+        "historian" => ("Historian", "ff9800"),
+        "engineering_workstation" => ("Engineering Workstation", "9c27b0"),
+        "scada_server" => ("SCADA Server", "673ab7"),
+        "it_device" => ("IT Device", "607d8b"),
+        _ => ("Unclassified OT Device", "9e9e9e"),
+    }
+}
+
+/// NetBox-style slug: lowercase ASCII letters, digits, '-' and '_', at most
+/// 100 characters.
+pub fn slugify(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let mut slug: String = slug.trim_end_matches('-').chars().take(100).collect();
+    if slug.is_empty() {
+        slug.push_str("unknown");
+    }
+    slug
+}
+
+/// Map assets onto NetBox objects.
+///
+/// Device names must be unique within a site, so a hostname shared by
+/// several assets gets the asset address appended.
+pub fn assets_to_netbox(assets: &[ExportAsset], options: &NetboxOptions) -> NetboxExport {
+    let mut hostname_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for asset in assets {
+        if let Some(ref h) = asset.hostname {
+            *hostname_counts.entry(h.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let mut manufacturers = BTreeMap::new();
+    let mut roles = BTreeMap::new();
+    let mut device_types = BTreeMap::new();
+    let mut devices = Vec::new();
+    let mut interfaces = Vec::new();
+    let mut ip_addresses = Vec::new();
+
+    for asset in assets {
+        let name = match asset.hostname.as_deref() {
+            Some(h) if hostname_counts.get(h) == Some(&1) => h.to_string(),
+            Some(h) => format!("{} ({})", h, asset.ip_address),
+            None => asset.ip_address.clone(),
+        };
+
+        let (role_name, color) = role_for(&asset.device_type);
+        let role_slug = slugify(role_name);
+        roles
+            .entry(role_slug.clone())
+            .or_insert_with(|| NetboxDeviceRole {
+                name: role_name.to_string(),
+                slug: role_slug.clone(),
+                color: color.to_string(),
+            });
+
+        let manufacturer = asset
+            .vendor
+            .clone()
+            .or_else(|| asset.oui_vendor.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let manufacturer_slug = slugify(&manufacturer);
+        manufacturers
+            .entry(manufacturer_slug.clone())
+            .or_insert_with(|| NetboxManufacturer {
+                name: manufacturer.clone(),
+                slug: manufacturer_slug.clone(),
+            });
+
+        let model = asset
+            .product_family
+            .clone()
+            .unwrap_or_else(|| format!("Generic {}", role_name));
+        let model_slug = slugify(&model);
+        device_types
+            .entry((manufacturer_slug.clone(), model_slug.clone()))
+            .or_insert_with(|| NetboxDeviceType {
+                manufacturer: manufacturer.clone(),
+                manufacturer_slug: manufacturer_slug.clone(),
+                model: model.clone(),
+                slug: model_slug.clone(),
+            });
+
+        devices.push(NetboxDevice {
+            name: name.clone(),
+            role_slug,
+            manufacturer,
+            manufacturer_slug,
+            device_type: model,
+            device_type_slug: model_slug,
+            site: options.site.clone(),
+            status: options.status.clone(),
+            description: DESCRIPTION.to_string(),
+            comments: device_comments(asset),
+        });
+
+        let ip = asset.ip_address.parse::<IpAddr>().ok();
+        if ip.is_none() && asset.mac_address.is_none() {
+            continue;
+        }
+        interfaces.push(NetboxInterface {
+            device: name.clone(),
+            name: INTERFACE_NAME.to_string(),
+            interface_type: "other".to_string(),
+            mac_address: asset.mac_address.as_ref().map(|m| m.to_uppercase()),
+        });
+
+        // Layer-2-only assets are keyed by MAC and have no address to assign
+        if let Some(ip) = ip {
+            let prefix_len = if ip.is_ipv4() { 32 } else { 128 };
+            ip_addresses.push(NetboxIpAddress {
+                address: format!("{}/{}", ip, prefix_len),
+                status: options.status.clone(),
+                device: name,
+                interface: INTERFACE_NAME.to_string(),
+                is_primary: true,
+                description: DESCRIPTION.to_string(),
+            });
+        }
+    }
+
+    NetboxExport {
+        options: options.clone(),
+        manufacturers: manufacturers.into_values().collect(),
+        device_roles: roles.into_values().collect(),
+        device_types: device_types.into_values().collect(),
+        devices,
+        interfaces,
+        ip_addresses,
+    }
+}
+
+/// Markdown comments for a device: what passive discovery knows about it.
+fn device_comments(asset: &ExportAsset) -> String {
+    let mut lines = Vec::new();
+    if !asset.protocols.is_empty() {
+        lines.push(format!("Protocols: {}", asset.protocols.join(", ")));
+    }
+    lines.push(format!("Identification confidence: {}/5", asset.confidence));
+    if let Some(level) = asset.purdue_level {
+        lines.push(format!("Purdue level: {}", level));
+    }
+    lines.push(format!(
+        "First seen: {}, last seen: {}",
+        asset.first_seen, asset.last_seen
+    ));
+    if !asset.tags.is_empty() {
+        lines.push(format!("Tags: {}", asset.tags.join(", ")));
+    }
+    if !asset.notes.is_empty() {
+        lines.push(String::new());
+        lines.push(asset.notes.clone());
+    }
+    lines.join("\n")
+}
+
+/// Render the export as NetBox CSV bulk-import files.
+///
+/// Returns `(file name, content)` pairs in the order they must be imported,
+/// since later files reference objects created by earlier ones.
+pub fn netbox_to_csv(export: &NetboxExport) -> Result<Vec<(&'static str, String)>, ReportError> {
+    let tag = &export.options.tag;
+    let mut files = Vec::new();
+
+    let mut buf = Vec::new();
+    writeln!(buf, "name,slug")?;
+    writeln!(buf, "{},{}", csv_escape(tag), csv_escape(tag))?;
+    files.push(("01_tags.csv", into_string(buf)?));
+
+    let mut buf = Vec::new();
+    writeln!(buf, "name,slug")?;
+    for m in &export.manufacturers {
+        writeln!(buf, "{},{}", csv_escape(&m.name), csv_escape(&m.slug))?;
+    }
+    files.push(("02_manufacturers.csv", into_string(buf)?));
+
+    let mut buf = Vec::new();
+    writeln!(buf, "name,slug,color")?;
+    for r in &export.device_roles {
+        writeln!(
+            buf,
+            "{},{},{}",
+            csv_escape(&r.name),
+            csv_escape(&r.slug),
+            r.color
+        )?;
+    }
+    files.push(("03_device_roles.csv", into_string(buf)?));
+
+    let mut buf = Vec::new();
+    writeln!(buf, "manufacturer,model,slug")?;
+    for t in &export.device_types {
+        writeln!(
+            buf,
+            "{},{},{}",
+            csv_escape(&t.manufacturer),
+            csv_escape(&t.model),
+            csv_escape(&t.slug)
+        )?;
+    }
+    files.push(("04_device_types.csv", into_string(buf)?));
+
+    let mut buf = Vec::new();
+    writeln!(
+        buf,
+        "name,role,manufacturer,device_type,site,status,description,comments,tags"
+    )?;
+    for d in &export.devices {
+        writeln!(
+            buf,
+            "{},{},{},{},{},{},{},{},{}",
+            csv_escape(&d.name),
+            csv_escape(&d.role_slug),
+            csv_escape(&d.manufacturer),
+            csv_escape(&d.device_type),
+            csv_escape(&d.site),
+            csv_escape(&d.status),
+            csv_escape(&d.description),
+            csv_escape(&d.comments),
+            csv_escape(tag),
+        )?;
+    }
+    files.push(("05_devices.csv", into_string(buf)?));
+
+    let mut buf = Vec::new();
+    writeln!(buf, "device,name,type,mac_address,tags")?;
+    for i in &export.interfaces {
+        writeln!(
+            buf,
+            "{},{},{},{},{}",
+            csv_escape(&i.device),
+            csv_escape(&i.name),
+            i.interface_type,
+            i.mac_address.as_deref().unwrap_or(""),
+            csv_escape(tag),
+        )?;
+    }
+    files.push(("06_interfaces.csv", into_string(buf)?));
+
+    let mut buf = Vec::new();
+    writeln!(
+        buf,
+        "address,status,device,interface,is_primary,description,tags"
+    )?;
+    for ip in &export.ip_addresses {
+        writeln!(
+            buf,
+            "{},{},{},{},{},{},{}",
+            ip.address,
+            csv_escape(&ip.status),
+            csv_escape(&ip.device),
+            csv_escape(&ip.interface),
+            ip.is_primary,
+            csv_escape(&ip.description),
+            csv_escape(tag),
+        )?;
+    }
+    files.push(("07_ip_addresses.csv", into_string(buf)?));
+
+    Ok(files)
+}
+
+fn into_string(buf: Vec<u8>) -> Result<String, ReportError> {
+    String::from_utf8(buf).map_err(|e| ReportError::Pdf(e.to_string()))
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(ip: &str, hostname: Option<&str>, device_type: &str) -> ExportAsset {
+        ExportAsset {
+            ip_address: ip.to_string(),
+            mac_address: Some("00:1b:1b:12:34:56".to_string()),
+            hostname: hostname.map(str::to_string),
+            device_type: device_type.to_string(),
+            vendor: Some("Siemens".to_string()),
+            product_family: Some("S7-1500".to_string()),
+            protocols: vec!["s7comm".to_string(), "profinet".to_string()],
+            confidence: 4,
+            purdue_level: Some(1),
+            oui_vendor: Some("Siemens AG".to_string()),
+            country: None,
+            is_public_ip: false,
+            first_seen: "2025-01-01T00:00:00Z".to_string(),
+            last_seen: "2025-01-02T00:00:00Z".to_string(),
+            notes: String::new(),
+            tags: vec![],
+            packet_count: 100,
+        }
+    }
+
+    fn options() -> NetboxOptions {
+        NetboxOptions {
+            site: "Plant 1".to_string(),
+            ..NetboxOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Schneider Electric"), "schneider-electric");
+        assert_eq!(slugify("S7-1500 (CPU 1516)"), "s7-1500-cpu-1516");
+        assert_eq!(slugify("  --  "), "unknown");
+        assert_eq!(slugify("Rockwell/Allen-Bradley"), "rockwell-allen-bradley");
+    }
+
+    #[test]
+    fn test_assets_to_netbox() {
+        let mut hmi = asset("10.0.2.20", None, "hmi");
+        hmi.vendor = None;
+        hmi.product_family = None;
+        let assets = vec![
+            asset("10.0.1.10", Some("plc-01"), "plc"),
+            asset("10.0.1.11", Some("plc-01"), "plc"),
+            hmi,
+        ];
+        let export = assets_to_netbox(&assets, &options());
+
+        let names: Vec<&str> = export.devices.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["plc-01 (10.0.1.10)", "plc-01 (10.0.1.11)", "10.0.2.20"]
+        );
+        // Shared objects are deduplicated
+        assert_eq!(export.device_types.len(), 2);
+        assert_eq!(export.device_roles.len(), 2);
+        let manufacturers: Vec<&str> = export
+            .manufacturers
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(manufacturers, ["Siemens", "Siemens AG"]);
+        // The HMI falls back to its OUI vendor and a generic model
+        assert_eq!(export.devices[2].manufacturer, "Siemens AG");
+        assert_eq!(export.devices[2].device_type, "Generic HMI");
+        assert_eq!(export.ip_addresses[0].address, "10.0.1.10/32");
+        assert_eq!(
+            export.interfaces[0].mac_address.as_deref(),
+            Some("00:1B:1B:12:34:56")
+        );
+    }
+
+    #[test]
+    fn test_mac_only_asset_has_no_ip() {
+        let l2 = asset("00:1b:1b:12:34:56", None, "ied");
+        let export = assets_to_netbox(&[l2], &options());
+        assert_eq!(export.devices.len(), 1);
+        assert_eq!(export.interfaces.len(), 1);
+        assert!(export.ip_addresses.is_empty());
+    }
+
+    #[test]
+    fn test_netbox_to_csv() {
+        let export = assets_to_netbox(&[asset("fd00::10", Some("rtu, north"), "rtu")], &options());
+        let files = netbox_to_csv(&export).unwrap();
+        let names: Vec<&str> = files.iter().map(|(n, _)| *n).collect();
+        assert_eq!(names.len(), 7);
+        assert!(names.windows(2).all(|w| w[0] < w[1]));
+
+        let devices = &files[4].1;
+        assert!(devices.starts_with("name,role,manufacturer,device_type,site,status"));
+        assert!(devices.contains("\"rtu, north\",rtu,Siemens,S7-1500,Plant 1,active"));
+        let ips = &files[6].1;
+        assert!(ips.contains("fd00::10/128,active,\"rtu, north\",eth0,true"));
+    }
+}
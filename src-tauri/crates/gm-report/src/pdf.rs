@@ -204,6 +204,11 @@ fn add_executive_summary(doc: &mut Document, data: &ReportData) {
     doc.push(elements::Paragraph::new(summary));
     doc.push(elements::Break::new(1.5));
 
+    if !data.unapproved_write_paths.is_empty() {
+        add_unapproved_write_paths(doc, data);
+        doc.push(elements::Break::new(1.5));
+    }
+
     // Device type breakdown
     add_subsection_header(doc, "Device Type Breakdown");
 
@@ -237,6 +242,49 @@ fn add_executive_summary(doc: &mut Document, data: &ReportData) {
     }
 }
 
+/// Add the table of write paths no one has approved yet.
+fn add_unapproved_write_paths(doc: &mut Document, data: &ReportData) {
+    add_subsection_header(doc, "Unapproved Write Paths");
+
+    let paths = &data.unapproved_write_paths;
+    doc.push(elements::Paragraph::new(format!(
+        "{} master-to-device relationship(s) carried write or control commands \
+         without a recorded approval. Confirm each is expected and approve it \
+         with a justification, or investigate it as unauthorized.",
+        paths.len()
+    )));
+    doc.push(elements::Break::new(0.5));
+
+    let mut table = elements::TableLayout::new(vec![2, 2, 1, 2, 1]);
+    table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+
+    push_header_row(
+        &mut table,
+        &["Master", "Target", "Protocol", "Function Codes", "Writes"],
+    );
+    for path in paths {
+        let fcs = path
+            .function_codes
+            .iter()
+            .map(|fc| fc.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let writes = format_number(path.write_count);
+        push_data_row(
+            &mut table,
+            &[
+                &path.master_ip,
+                &path.slave_ip,
+                &path.protocol,
+                &fcs,
+                &writes,
+            ],
+        );
+    }
+
+    doc.push(table);
+}
+
 /// Add asset inventory section.
 fn add_asset_inventory(doc: &mut Document, data: &ReportData) {
     add_section_header(doc, "2. Asset Inventory");
@@ -367,6 +415,121 @@ fn add_protocol_analysis(doc: &mut Document, data: &ReportData) {
         ot_count,
         it_count
     )));
+
+    if !data.top_talkers.is_empty() {
+        doc.push(elements::Break::new(1.5));
+        add_top_talkers(doc, data);
+    }
+
+    if !data.external_connectivity.is_empty() {
+        doc.push(elements::Break::new(1.5));
+        add_external_connectivity(doc, data);
+    }
+}
+
+/// Add the table of the busiest connections and their peak rates.
+fn add_top_talkers(doc: &mut Document, data: &ReportData) {
+    add_subsection_header(doc, "Top Talkers");
+
+    let bursty = data
+        .top_talkers
+        .iter()
+        .filter(|t| t.burst_count > 0)
+        .count();
+    doc.push(elements::Paragraph::new(format!(
+        "The {} connections carrying the most traffic. Peak is the busiest \
+         interval's average rate; a burst is an interval carrying several \
+         times the connection's mean, and {} of these connections had one.",
+        data.top_talkers.len(),
+        bursty
+    )));
+    doc.push(elements::Break::new(0.5));
+
+    let mut table = elements::TableLayout::new(vec![2, 2, 1, 1, 3, 1]);
+    table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+
+    push_header_row(
+        &mut table,
+        &[
+            "Source",
+            "Destination",
+            "Protocol",
+            "Bytes",
+            "Peak",
+            "Bursts",
+        ],
+    );
+    for talker in &data.top_talkers {
+        let bytes = format_bytes(talker.byte_count);
+        let peak = match &talker.peak_at {
+            Some(at) => format!(
+                "{}/s at {}",
+                format_bytes(talker.peak_bytes / u64::from(talker.bucket_secs.max(1))),
+                at
+            ),
+            None => "-".to_string(),
+        };
+        let bursts = talker.burst_count.to_string();
+        push_data_row(
+            &mut table,
+            &[
+                &talker.src_ip,
+                &talker.dst_ip,
+                &talker.protocol,
+                &bytes,
+                &peak,
+                &bursts,
+            ],
+        );
+    }
+
+    doc.push(table);
+}
+
+/// Add the table of outbound cloud / remote-access connections.
+fn add_external_connectivity(doc: &mut Document, data: &ReportData) {
+    add_subsection_header(doc, "External Connectivity");
+
+    let conns = &data.external_connectivity;
+    let mut assets: Vec<&str> = conns.iter().map(|c| c.local_ip.as_str()).collect();
+    assets.sort_unstable();
+    assets.dedup();
+    doc.push(elements::Paragraph::new(format!(
+        "{} asset(s) opened {} connection(s) to cloud platforms, vendor clouds, \
+         remote-access brokers or OPC UA reverse-connect clients. Each is a path \
+         into the plant that bypasses the site firewall's inbound rules and \
+         should be documented and owned.",
+        assets.len(),
+        conns.len()
+    )));
+    doc.push(elements::Break::new(0.5));
+
+    let mut table = elements::TableLayout::new(vec![2, 2, 3, 2, 1]);
+    table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+
+    push_header_row(
+        &mut table,
+        &["Asset", "Service", "Endpoint", "Evidence", "Packets"],
+    );
+    for conn in conns {
+        let asset = format!(
+            "{} ({})",
+            conn.local_ip,
+            device_type_label(&conn.local_device_type)
+        );
+        let service = format!("{} ({})", conn.service, conn.category);
+        let endpoint = match &conn.remote_hostname {
+            Some(host) => format!("{}:{} ({})", host, conn.remote_port, conn.remote_ip),
+            None => format!("{}:{}", conn.remote_ip, conn.remote_port),
+        };
+        let packets = format_number(conn.packet_count);
+        push_data_row(
+            &mut table,
+            &[&asset, &service, &endpoint, &conn.evidence, &packets],
+        );
+    }
+
+    doc.push(table);
 }
 
 /// Add findings section.
@@ -528,6 +691,7 @@ fn device_type_label(dtype: &str) -> String {
     match dtype {
         "plc" => "PLC".to_string(),
         "rtu" => "RTU".to_string(),
+        "ied" => "IED".to_string(),
         "hmi" => "HMI".to_string(),
         "historian" => "Historian".to_string(),
         "scada_server" => "SCADA Server".to_string(),
@@ -554,6 +718,15 @@ fn is_ot_protocol(protocol: &str) -> bool {
             | "foundation_fieldbus"
             | "ge_srtp"
             | "wonderware_suitelink"
+            | "fins"
+            | "slmp"
+            | "codesys"
+            | "niagara_fox"
+            | "crimson"
+            | "vnet_ip"
+            | "tristation"
+            | "ads"
+            | "opc_da"
     )
 }
 
@@ -597,6 +770,27 @@ fn generate_auto_findings(data: &ReportData) -> Vec<(&str, &str, String)> {
         ));
     }
 
+    // Check for OT assets reaching cloud / remote-access services
+    let mut cloud_assets: Vec<&str> = data
+        .external_connectivity
+        .iter()
+        .filter(|c| c.local_device_type != "it_device")
+        .map(|c| c.local_ip.as_str())
+        .collect();
+    cloud_assets.sort_unstable();
+    cloud_assets.dedup();
+    if !cloud_assets.is_empty() {
+        findings.push((
+            "warning",
+            "OT Assets With External Connectivity",
+            format!(
+                "{} OT assets connect out to cloud or remote-access services. \
+                 Confirm each connector is approved and monitored.",
+                cloud_assets.len()
+            ),
+        ));
+    }
+
     // Check for unencrypted OT protocols
     let unencrypted_ot = data
         .protocol_stats
@@ -699,10 +893,17 @@ mod tests {
             }],
             findings: vec![],
             session_name: None,
+            unapproved_write_paths: vec![],
+            external_connectivity: vec![],
+            top_talkers: vec![],
+            topology: None,
         };
         let findings = generate_auto_findings(&data);
         // Should find unencrypted OT protocols
         assert!(!findings.is_empty());
+        assert!(!findings
+            .iter()
+            .any(|f| f.1 == "OT Assets With External Connectivity"));
 
         // Add a public IP asset
         data.assets.push(crate::ExportAsset {
@@ -728,6 +929,24 @@ mod tests {
         assert!(findings
             .iter()
             .any(|f| f.1 == "Public IP Addresses Detected"));
+
+        // A PLC pushing telemetry to Azure IoT Hub
+        data.external_connectivity
+            .push(crate::ExportExternalConnection {
+                local_ip: "10.0.0.1".to_string(),
+                local_device_type: "plc".to_string(),
+                remote_ip: "52.1.2.3".to_string(),
+                remote_port: 8883,
+                remote_hostname: Some("plant.azure-devices.net".to_string()),
+                service: "Azure IoT Hub".to_string(),
+                category: "IoT platform".to_string(),
+                evidence: "TLS SNI".to_string(),
+                packet_count: 40,
+            });
+        let findings = generate_auto_findings(&data);
+        assert!(findings
+            .iter()
+            .any(|f| f.1 == "OT Assets With External Connectivity"));
     }
 
     #[test]
@@ -194,7 +194,7 @@ pub fn generate_stix_bundle(
 /// Map internal device types to STIX infrastructure types.
 fn map_device_type(device_type: &str) -> &str {
     match device_type {
-        "plc" | "rtu" => "control-system",
+        "plc" | "rtu" | "ied" => "control-system",
         "hmi" => "workstation",
         "historian" => "hosting-target-lists",
         "scada_server" => "command-and-control",
@@ -253,6 +253,7 @@ mod tests {
             byte_count: 2500,
             first_seen: "2025-01-01T00:00:00Z".to_string(),
             last_seen: "2025-01-02T00:00:00Z".to_string(),
+            direction_basis: "syn".to_string(),
         }]
     }
 
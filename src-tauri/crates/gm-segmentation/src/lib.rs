@@ -8,6 +8,7 @@
 //! - **15C** `matrix`         — per-zone-pair least-privilege allow rules
 //! - **15D** `enforcement`    — export rules as Cisco ACL / Suricata / JSON
 //! - **15E** `simulation`     — replay traffic against policy, quantify impact
+//! - **15F** `gap_worksheet`  — per-zone SL-T vs SL-A gap worksheet (62443-3-3)
 //!
 //! All modules receive a [`SegmentationInput`] assembled by the Tauri command
 //! layer and return components that are bundled into a [`SegmentationReport`].
@@ -15,6 +16,7 @@
 
 pub mod enforcement;
 pub mod error;
+pub mod gap_worksheet;
 pub mod identity_groups;
 pub mod matrix;
 pub mod simulation;
@@ -22,6 +24,7 @@ pub mod zones;
 
 pub use enforcement::build_enforcement_config;
 pub use error::SegmentationError;
+pub use gap_worksheet::{build_gap_worksheet, worksheet_to_csv};
 pub use identity_groups::build_policy_groups;
 pub use matrix::build_communication_matrix;
 pub use simulation::run_simulation;
@@ -249,6 +252,18 @@ pub enum SecurityLevel {
     Sl4,
 }
 
+impl SecurityLevel {
+    /// Numeric level (1–4), as used in SL-T / SL-A vectors.
+    pub fn as_u8(self) -> u8 {
+        match self {
+            SecurityLevel::Sl1 => 1,
+            SecurityLevel::Sl2 => 2,
+            SecurityLevel::Sl3 => 3,
+            SecurityLevel::Sl4 => 4,
+        }
+    }
+}
+
 /// Asset/group criticality level (mirrors `risk.rs` CriticalityLevel).
 ///
 /// Ordering: Unknown < Low < Medium < High < Critical (for `max()` operations).
@@ -521,6 +536,59 @@ pub struct SimulationResult {
     pub zone_block_summaries: Vec<ZoneBlockSummary>,
 }
 
+// ── 15F Output: GapWorksheet ─────────────────────────────────────────────────
+
+/// Outcome of comparing a zone's passive SL-A ceiling against its SL-T.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GapStatus {
+    /// Observed evidence caps the achievable level below the target.
+    Gap,
+    /// Nothing observed contradicts the target (still requires on-site verification).
+    NoGapObserved,
+    /// The zone had no observed traffic to evaluate.
+    NotAssessed,
+}
+
+/// One IEC 62443-3-3 system requirement evaluated for one zone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequirementGap {
+    /// System requirement ID (e.g., `"SR 3.1"`).
+    pub requirement: String,
+    /// Requirement title (e.g., `"Communication integrity"`).
+    pub title: String,
+    /// Foundational requirement the SR belongs to (e.g., `"FR 3"`).
+    pub foundational_requirement: String,
+    /// Highest SL the passive evidence allows (0–4). 4 = no limiting evidence.
+    pub achieved_sl: u8,
+    pub status: GapStatus,
+    /// Observed evidence behind `achieved_sl`, one line per fact.
+    pub evidence: Vec<String>,
+}
+
+/// Gap worksheet rows for a single zone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneGap {
+    pub zone_id: String,
+    pub zone_name: String,
+    /// Target security level (SL-T).
+    pub target_sl: SecurityLevel,
+    /// True if SL-T was declared by the user rather than taken from the zone model.
+    pub target_declared: bool,
+    /// Lowest per-requirement ceiling — the zone's overall passive SL-A.
+    pub achieved_sl: u8,
+    pub gap_count: usize,
+    pub requirements: Vec<RequirementGap>,
+}
+
+/// Per-zone SL-T vs SL-A gap worksheet — the output of Phase 15F.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GapWorksheet {
+    pub zones: Vec<ZoneGap>,
+    /// RFC 3339 timestamp when this worksheet was generated.
+    pub generated_at: String,
+}
+
 // ── SegmentationReport ────────────────────────────────────────────────────────
 
 /// Complete segmentation analysis output — all Phase 15A–15E results bundled.
@@ -3,9 +3,12 @@
 //! Loads YAML signatures from a directory, matches them against
 //! pre-parsed packet data, and returns confidence-scored results.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+use gm_parsers::{TlsHello, TlsHelloKind};
+use serde::{Deserialize, Serialize};
+
 use crate::error::SignatureError;
 use crate::signature::{
     ExtractedValue, PayloadExtractor, Signature, SignatureFilter, SignatureMatch,
@@ -27,6 +30,8 @@ pub struct PacketData {
     pub protocol: String,  // IcsProtocol variant name (lowercase)
     pub payload: Vec<u8>,
     pub length: usize,
+    /// TLS ClientHello / ServerHello carried by the payload, if any
+    pub tls: Option<TlsHello>,
 }
 
 /// The signature matching engine.
@@ -39,6 +44,76 @@ pub struct SignatureEngine {
     compiled_patterns: HashMap<usize, Vec<CompiledFilter>>,
     /// Directory being watched for signatures
     signature_dir: Option<PathBuf>,
+    /// Content hash of each signature's source YAML (signature index → hash)
+    content_hashes: HashMap<usize, u64>,
+    /// Source YAML of each signature (signature index → YAML), kept so the
+    /// loaded set can be embedded in a session
+    sources: HashMap<usize, String>,
+}
+
+/// A loaded signature set captured with its YAML sources, so a session can
+/// be re-identified later with exactly the signatures it was processed with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureSetSnapshot {
+    /// Hash over every signature's name and content hash (see
+    /// [`SignatureEngine::fingerprint`])
+    pub fingerprint: String,
+    /// Signatures sorted by name
+    pub signatures: Vec<PinnedSignature>,
+}
+
+/// One signature in a [`SignatureSetSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinnedSignature {
+    pub name: String,
+    /// Content hash of `yaml` as 16 hex digits
+    pub content_hash: String,
+    pub yaml: String,
+}
+
+/// Which signatures changed across a [`SignatureEngine::reload_diff`].
+///
+/// Signatures are keyed by name; a signature whose YAML source changed
+/// (by content hash) but kept its name is reported as `changed`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SignatureDiff {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    /// Signature count after the reload
+    pub total: usize,
+}
+
+impl SignatureDiff {
+    /// Names that need to be re-matched against stored packets.
+    pub fn rematch_names(&self) -> HashSet<String> {
+        self.added.iter().chain(&self.changed).cloned().collect()
+    }
+
+    /// Names whose previous matches are no longer valid.
+    pub fn stale_names(&self) -> HashSet<String> {
+        self.changed.iter().chain(&self.removed).cloned().collect()
+    }
+
+    /// True if the reload did not change any signature.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// How far one signature got against a device's packets, for tracing
+/// "why was this device not identified" reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignatureExplanation {
+    pub signature_name: String,
+    pub matched: bool,
+    /// Packets on which every filter matched
+    pub packets_matched: usize,
+    /// Filters passed on the packet that got furthest
+    pub filters_passed: usize,
+    pub filter_count: usize,
+    /// First filter that failed on that packet (None when matched)
+    pub failed_filter: Option<String>,
 }
 
 /// A pre-compiled filter for faster matching.
@@ -57,6 +132,8 @@ enum CompiledFilter {
     MinLength(usize),
     /// Match MAC OUI prefix (first 3 bytes of MAC, as "xx:xx:xx")
     MacOui(String, String),
+    /// Match a TLS hello field: (field_name, lowercased value)
+    Tls(String, String),
 }
 
 impl SignatureEngine {
@@ -66,9 +143,31 @@ impl SignatureEngine {
             signatures: Vec::new(),
             compiled_patterns: HashMap::new(),
             signature_dir: None,
+            content_hashes: HashMap::new(),
+            sources: HashMap::new(),
         }
     }
 
+    /// Build an engine from a snapshot taken with [`Self::snapshot`].
+    ///
+    /// Each signature's YAML is checked against its recorded content hash
+    /// so an edited snapshot is rejected rather than silently used. The
+    /// engine has no signature directory, so it cannot be reloaded.
+    pub fn from_snapshot(snapshot: &SignatureSetSnapshot) -> Result<Self, SignatureError> {
+        let mut engine = Self::new();
+        for pinned in &snapshot.signatures {
+            let hash = format!("{:016x}", content_hash(&pinned.yaml));
+            if hash != pinned.content_hash {
+                return Err(SignatureError::ValidationError(format!(
+                    "Pinned signature '{}' does not match its content hash",
+                    pinned.name
+                )));
+            }
+            engine.load_yaml(&pinned.yaml)?;
+        }
+        Ok(engine)
+    }
+
     /// Load all .yaml and .yml files from a directory.
     pub fn load_directory(&mut self, dir: &Path) -> Result<usize, SignatureError> {
         self.signature_dir = Some(dir.to_path_buf());
@@ -131,6 +230,8 @@ impl SignatureEngine {
         let idx = self.signatures.len();
         let compiled = compile_filters(&sig.filters)?;
         self.compiled_patterns.insert(idx, compiled);
+        self.content_hashes.insert(idx, content_hash(yaml));
+        self.sources.insert(idx, yaml.to_string());
         self.signatures.push(sig);
 
         Ok(())
@@ -145,9 +246,89 @@ impl SignatureEngine {
 
         self.signatures.clear();
         self.compiled_patterns.clear();
+        self.content_hashes.clear();
+        self.sources.clear();
         self.load_directory(&dir)
     }
 
+    /// Reload all signatures and report which ones were added, changed, or
+    /// removed, so callers can re-match only the affected signatures.
+    pub fn reload_diff(&mut self) -> Result<SignatureDiff, SignatureError> {
+        let before = self.hashes_by_name();
+        self.reload()?;
+        let after = self.hashes_by_name();
+
+        let mut diff = SignatureDiff {
+            total: self.signatures.len(),
+            ..Default::default()
+        };
+        for (name, hash) in &after {
+            match before.get(name) {
+                None => diff.added.push(name.clone()),
+                Some(old) if old != hash => diff.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = before
+            .keys()
+            .filter(|name| !after.contains_key(*name))
+            .cloned()
+            .collect();
+
+        diff.added.sort();
+        diff.changed.sort();
+        diff.removed.sort();
+        Ok(diff)
+    }
+
+    /// Signature name → content hash for every loaded signature.
+    fn hashes_by_name(&self) -> HashMap<String, u64> {
+        self.signatures
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, sig)| {
+                self.content_hashes
+                    .get(&idx)
+                    .map(|h| (sig.name.clone(), *h))
+            })
+            .collect()
+    }
+
+    /// Identifier of the loaded signature set: a hash over every
+    /// signature's name and content hash, independent of load order.
+    /// Two engines with the same fingerprint identify devices identically.
+    pub fn fingerprint(&self) -> String {
+        let mut entries: Vec<(String, u64)> = self.hashes_by_name().into_iter().collect();
+        entries.sort();
+        let joined: String = entries
+            .iter()
+            .map(|(name, hash)| format!("{}:{:016x}\n", name, hash))
+            .collect();
+        format!("{:016x}", content_hash(&joined))
+    }
+
+    /// Capture the loaded signatures and their YAML sources.
+    pub fn snapshot(&self) -> SignatureSetSnapshot {
+        let mut signatures: Vec<PinnedSignature> = self
+            .signatures
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, sig)| {
+                let yaml = self.sources.get(&idx)?;
+                Some(PinnedSignature {
+                    name: sig.name.clone(),
+                    content_hash: format!("{:016x}", content_hash(yaml)),
+                    yaml: yaml.clone(),
+                })
+            })
+            .collect();
+        signatures.sort_by(|a, b| a.name.cmp(&b.name));
+        SignatureSetSnapshot {
+            fingerprint: self.fingerprint(),
+            signatures,
+        }
+    }
+
     /// Get all loaded signatures.
     pub fn signatures(&self) -> &[Signature] {
         &self.signatures
@@ -161,9 +342,40 @@ impl SignatureEngine {
     /// Match all signatures against a single packet.
     /// Returns all matching signatures sorted by confidence (highest first).
     pub fn match_packet(&self, packet: &PacketData) -> Vec<SignatureMatch> {
+        self.match_packet_filtered(packet, None)
+    }
+
+    /// Match all signatures against a batch of packets for a device.
+    /// Returns the best (highest confidence) matches, deduplicated by signature name.
+    pub fn match_device_packets(&self, packets: &[PacketData]) -> Vec<SignatureMatch> {
+        self.match_device_packets_filtered(packets, None)
+    }
+
+    /// Like [`match_device_packets`](Self::match_device_packets), but only
+    /// evaluates the named signatures. Used after a reload to re-match the
+    /// added and changed signatures without re-running the whole set.
+    pub fn match_device_packets_for(
+        &self,
+        packets: &[PacketData],
+        names: &HashSet<String>,
+    ) -> Vec<SignatureMatch> {
+        if names.is_empty() {
+            return Vec::new();
+        }
+        self.match_device_packets_filtered(packets, Some(names))
+    }
+
+    fn match_packet_filtered(
+        &self,
+        packet: &PacketData,
+        names: Option<&HashSet<String>>,
+    ) -> Vec<SignatureMatch> {
         let mut matches = Vec::new();
 
         for (idx, sig) in self.signatures.iter().enumerate() {
+            if names.is_some_and(|n| !n.contains(&sig.name)) {
+                continue;
+            }
             if let Some(compiled) = self.compiled_patterns.get(&idx) {
                 if all_filters_match(compiled, packet) {
                     let extracted = extract_payload_values(&sig.payloads, &packet.payload);
@@ -181,17 +393,19 @@ impl SignatureEngine {
         }
 
         // Sort by confidence descending — highest confidence match first
-        matches.sort_by(|a, b| b.confidence.cmp(&a.confidence));
+        matches.sort_by_key(|m| std::cmp::Reverse(m.confidence));
         matches
     }
 
-    /// Match all signatures against a batch of packets for a device.
-    /// Returns the best (highest confidence) matches, deduplicated by signature name.
-    pub fn match_device_packets(&self, packets: &[PacketData]) -> Vec<SignatureMatch> {
+    fn match_device_packets_filtered(
+        &self,
+        packets: &[PacketData],
+        names: Option<&HashSet<String>>,
+    ) -> Vec<SignatureMatch> {
         let mut best_matches: HashMap<String, SignatureMatch> = HashMap::new();
 
         for packet in packets {
-            for m in self.match_packet(packet) {
+            for m in self.match_packet_filtered(packet, names) {
                 // Keep the highest-confidence match for each signature
                 if best_matches
                     .get(&m.signature_name)
@@ -203,10 +417,58 @@ impl SignatureEngine {
         }
 
         let mut results: Vec<SignatureMatch> = best_matches.into_values().collect();
-        results.sort_by(|a, b| b.confidence.cmp(&a.confidence));
+        results.sort_by_key(|m| std::cmp::Reverse(m.confidence));
         results
     }
 
+    /// Explain, for every signature, how far it got against a device's
+    /// packets: whether it matched, and if not, the first filter that failed
+    /// on the packet that passed the most filters.
+    ///
+    /// Signatures that failed their first filter on every packet are
+    /// omitted; they were never close, and listing them would bury the
+    /// near misses. Results are ordered matches first, then by progress.
+    pub fn explain_device_packets(&self, packets: &[PacketData]) -> Vec<SignatureExplanation> {
+        let mut explanations = Vec::new();
+
+        for (idx, sig) in self.signatures.iter().enumerate() {
+            let Some(compiled) = self.compiled_patterns.get(&idx) else {
+                continue;
+            };
+            let mut packets_matched = 0;
+            let mut best_passed = 0;
+            for packet in packets {
+                let passed = compiled
+                    .iter()
+                    .take_while(|f| filter_matches(f, packet))
+                    .count();
+                if passed == compiled.len() {
+                    packets_matched += 1;
+                }
+                best_passed = best_passed.max(passed);
+            }
+            if packets_matched == 0 && best_passed == 0 {
+                continue;
+            }
+            explanations.push(SignatureExplanation {
+                signature_name: sig.name.clone(),
+                matched: packets_matched > 0,
+                packets_matched,
+                filters_passed: best_passed,
+                filter_count: compiled.len(),
+                failed_filter: compiled.get(best_passed).map(CompiledFilter::describe),
+            });
+        }
+
+        explanations.sort_by(|a, b| {
+            b.matched
+                .cmp(&a.matched)
+                .then(b.filters_passed.cmp(&a.filters_passed))
+                .then_with(|| a.signature_name.cmp(&b.signature_name))
+        });
+        explanations
+    }
+
     /// Test a single YAML signature against packets, returning all matches.
     /// Used by the signature editor "Test" feature.
     pub fn test_signature(
@@ -251,14 +513,32 @@ pub struct TestResult {
     pub extracted_values: Vec<ExtractedValue>,
 }
 
-use serde::Serialize;
-
 impl Default for SignatureEngine {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl CompiledFilter {
+    /// Human-readable form of the filter, e.g. `tcp.dst_port == 502`.
+    fn describe(&self) -> String {
+        match self {
+            CompiledFilter::Port(field, port) => format!("{} == {}", field, port),
+            CompiledFilter::Protocol(proto) => format!("protocol == {}", proto),
+            CompiledFilter::PayloadBytes { bytes, offset } => {
+                let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                match offset {
+                    Some(off) => format!("payload[{}..] == {}", off, hex),
+                    None => format!("payload contains {}", hex),
+                }
+            }
+            CompiledFilter::MinLength(min) => format!("payload length >= {}", min),
+            CompiledFilter::MacOui(field, oui) => format!("{} == {}", field, oui),
+            CompiledFilter::Tls(field, value) => format!("{} == {}", field, value),
+        }
+    }
+}
+
 // ── Filter compilation ──────────────────────────────────────────────
 
 /// Compile YAML filter definitions into efficient match operations.
@@ -307,6 +587,13 @@ fn compile_filters(filters: &[SignatureFilter]) -> Result<Vec<CompiledFilter>, S
                 }
             }
 
+            "tls.ja3" | "tls.ja3s" | "tls.sni" => {
+                if let Some(ref val) = filter.value {
+                    let value = yaml_value_to_string(val).to_lowercase();
+                    compiled.push(CompiledFilter::Tls(filter.field.clone(), value));
+                }
+            }
+
             other => {
                 // For unrecognized fields, check if they have payload pattern
                 if let Some(ref pattern) = filter.pattern {
@@ -384,6 +671,25 @@ fn filter_matches(filter: &CompiledFilter, packet: &PacketData) -> bool {
                 None => false,
             }
         }
+
+        CompiledFilter::Tls(field, value) => {
+            let Some(tls) = packet.tls.as_ref() else {
+                return false;
+            };
+            match field.as_str() {
+                "tls.ja3" => tls.kind == TlsHelloKind::Client && tls.ja3 == *value,
+                "tls.ja3s" => tls.kind == TlsHelloKind::Server && tls.ja3 == *value,
+                // "*.example.com" matches any subdomain
+                "tls.sni" => tls
+                    .sni
+                    .as_deref()
+                    .is_some_and(|sni| match value.strip_prefix('*') {
+                        Some(suffix) => sni.ends_with(suffix),
+                        None => sni == value,
+                    }),
+                _ => false,
+            }
+        }
     }
 }
 
@@ -466,6 +772,19 @@ fn extract_payload_values(extractors: &[PayloadExtractor], payload: &[u8]) -> Ve
 
 // ── Helpers ─────────────────────────────────────────────────────────
 
+/// FNV-1a hash of a signature's YAML source.
+///
+/// Stable across Rust releases (unlike `DefaultHasher`), so a hash only
+/// changes when the file content does.
+fn content_hash(yaml: &str) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in yaml.as_bytes() {
+        h ^= *b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    h
+}
+
 /// Parse a hex pattern string like "\\x53\\x63\\x68" or "536368" into bytes.
 fn parse_hex_pattern(pattern: &str) -> Result<Vec<u8>, SignatureError> {
     let mut bytes = Vec::new();
@@ -576,6 +895,7 @@ mod tests {
             protocol: "modbus".to_string(),
             payload: vec![],
             length: 64,
+            tls: None,
         };
         assert!(filter_matches(&filter, &packet));
     }
@@ -610,6 +930,7 @@ device_type: plc
             protocol: "modbus".to_string(),
             payload: vec![],
             length: 64,
+            tls: None,
         };
 
         let matches = engine.match_packet(&packet);
@@ -651,6 +972,7 @@ device_type: plc
             protocol: "modbus".to_string(),
             payload: b"\x00\x00\x00\x00\x53\x63\x68\x6e\x65\x69\x64\x65\x72".to_vec(),
             length: 13,
+            tls: None,
         };
 
         let matches = engine.match_packet(&matching_packet);
@@ -669,6 +991,7 @@ device_type: plc
             protocol: "modbus".to_string(),
             payload: b"\x00\x00\x00\x00\x00\x00".to_vec(),
             length: 6,
+            tls: None,
         };
         assert!(engine.match_packet(&non_matching).is_empty());
     }
@@ -719,10 +1042,214 @@ device_type: plc
             protocol: "s7comm".to_string(),
             payload: vec![],
             length: 64,
+            tls: None,
         };
 
         let matches = engine.match_packet(&packet);
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].vendor, Some("Siemens".to_string()));
     }
+
+    #[test]
+    fn test_tls_filters() {
+        let yaml = r#"
+name: "historian_agent_tls"
+description: "Historian collector TLS client by JA3 and SNI"
+filters:
+  - field: tls.ja3
+    value: "F4FEBC55EA12B31AE17CFB7E614AFDA8"
+  - field: tls.sni
+    value: "*.plant.local"
+confidence: 4
+device_type: historian
+"#;
+        let mut engine = SignatureEngine::new();
+        engine.load_yaml(yaml).unwrap();
+
+        let hello = |kind: TlsHelloKind, sni: Option<&str>| TlsHello {
+            kind,
+            version: 0x0304,
+            sni: sni.map(str::to_string),
+            cipher_suites: vec![0x1301],
+            alpn: Vec::new(),
+            ja3_string: "771,4865,43-51".to_string(),
+            ja3: "f4febc55ea12b31ae17cfb7e614afda8".to_string(),
+        };
+        let mut packet = PacketData {
+            src_ip: "10.0.0.20".to_string(),
+            dst_ip: "10.0.0.5".to_string(),
+            src_port: 50000,
+            dst_port: 443,
+            src_mac: None,
+            dst_mac: None,
+            transport: "tcp".to_string(),
+            protocol: "unknown".to_string(),
+            payload: vec![0x16, 0x03, 0x03],
+            length: 120,
+            tls: Some(hello(TlsHelloKind::Client, Some("hist.plant.local"))),
+        };
+        assert_eq!(engine.match_packet(&packet).len(), 1);
+
+        // Same hash from a ServerHello is a JA3S, not a JA3
+        packet.tls = Some(hello(TlsHelloKind::Server, Some("hist.plant.local")));
+        assert!(engine.match_packet(&packet).is_empty());
+
+        packet.tls = Some(hello(TlsHelloKind::Client, Some("hist.office.local")));
+        assert!(engine.match_packet(&packet).is_empty());
+
+        packet.tls = None;
+        assert!(engine.match_packet(&packet).is_empty());
+    }
+
+    #[test]
+    fn test_reload_diff_tracks_content_changes() {
+        let dir = std::env::temp_dir().join(format!("gm-sig-diff-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let sig = |name: &str, port: u16| {
+            format!(
+                "name: \"{}\"\ndescription: \"\"\nfilters:\n  - field: tcp.dst_port\n    value: {}\nconfidence: 2\n",
+                name, port
+            )
+        };
+        std::fs::write(dir.join("keep.yaml"), sig("keep", 502)).unwrap();
+        std::fs::write(dir.join("edit.yaml"), sig("edit", 102)).unwrap();
+        std::fs::write(dir.join("drop.yaml"), sig("drop", 20000)).unwrap();
+
+        let mut engine = SignatureEngine::new();
+        engine.load_directory(&dir).unwrap();
+
+        std::fs::write(dir.join("edit.yaml"), sig("edit", 44818)).unwrap();
+        std::fs::remove_file(dir.join("drop.yaml")).unwrap();
+        std::fs::write(dir.join("new.yaml"), sig("new", 2404)).unwrap();
+
+        let diff = engine.reload_diff().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(diff.added, vec!["new".to_string()]);
+        assert_eq!(diff.changed, vec!["edit".to_string()]);
+        assert_eq!(diff.removed, vec!["drop".to_string()]);
+        assert_eq!(diff.total, 3);
+        assert!(diff.rematch_names().contains("edit"));
+        assert!(!diff.rematch_names().contains("keep"));
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_and_fingerprint() {
+        let sig = |name: &str, port: u16| {
+            format!(
+                "name: \"{}\"\ndescription: \"\"\nfilters:\n  - field: tcp.dst_port\n    value: {}\nconfidence: 2\n",
+                name, port
+            )
+        };
+        let mut engine = SignatureEngine::new();
+        engine.load_yaml(&sig("b", 102)).unwrap();
+        engine.load_yaml(&sig("a", 502)).unwrap();
+
+        // Load order does not change the fingerprint
+        let mut reordered = SignatureEngine::new();
+        reordered.load_yaml(&sig("a", 502)).unwrap();
+        reordered.load_yaml(&sig("b", 102)).unwrap();
+        assert_eq!(engine.fingerprint(), reordered.fingerprint());
+
+        let snapshot = engine.snapshot();
+        assert_eq!(snapshot.signatures[0].name, "a");
+        let restored = SignatureEngine::from_snapshot(&snapshot).unwrap();
+        assert_eq!(restored.signature_count(), 2);
+        assert_eq!(restored.fingerprint(), snapshot.fingerprint);
+
+        // Any content change produces a different set
+        let mut edited = SignatureEngine::new();
+        edited.load_yaml(&sig("a", 502)).unwrap();
+        edited.load_yaml(&sig("b", 44818)).unwrap();
+        assert_ne!(edited.fingerprint(), engine.fingerprint());
+
+        // A snapshot whose YAML was altered is rejected
+        let mut tampered = snapshot.clone();
+        tampered.signatures[1].yaml = sig("b", 44818);
+        assert!(SignatureEngine::from_snapshot(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_match_device_packets_for_subset() {
+        let mut engine = SignatureEngine::new();
+        for name in ["a", "b"] {
+            engine
+                .load_yaml(&format!(
+                    "name: \"{}\"\ndescription: \"\"\nfilters:\n  - field: tcp.dst_port\n    value: 502\nconfidence: 1\n",
+                    name
+                ))
+                .unwrap();
+        }
+        let packet = PacketData {
+            src_ip: "192.168.1.10".to_string(),
+            dst_ip: "192.168.1.100".to_string(),
+            src_port: 49152,
+            dst_port: 502,
+            src_mac: None,
+            dst_mac: None,
+            transport: "tcp".to_string(),
+            protocol: "modbus".to_string(),
+            payload: vec![],
+            length: 64,
+            tls: None,
+        };
+
+        assert_eq!(
+            engine
+                .match_device_packets(std::slice::from_ref(&packet))
+                .len(),
+            2
+        );
+        let only_b: HashSet<String> = ["b".to_string()].into_iter().collect();
+        let matches = engine.match_device_packets_for(std::slice::from_ref(&packet), &only_b);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].signature_name, "b");
+        assert!(engine
+            .match_device_packets_for(&[packet], &HashSet::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_explain_device_packets_reports_failed_filter() {
+        let mut engine = SignatureEngine::new();
+        engine
+            .load_yaml(
+                "name: \"near_miss\"\ndescription: \"\"\nfilters:\n  - field: tcp.dst_port\n    value: 502\n  - field: payload\n    pattern: \"2b0e\"\nconfidence: 4\n",
+            )
+            .unwrap();
+        engine
+            .load_yaml("name: \"port\"\ndescription: \"\"\nfilters:\n  - field: tcp.dst_port\n    value: 502\nconfidence: 1\n")
+            .unwrap();
+        engine
+            .load_yaml("name: \"dnp3\"\ndescription: \"\"\nfilters:\n  - field: tcp.dst_port\n    value: 20000\nconfidence: 1\n")
+            .unwrap();
+        let packet = PacketData {
+            src_ip: "192.168.1.10".to_string(),
+            dst_ip: "192.168.1.100".to_string(),
+            src_port: 49152,
+            dst_port: 502,
+            src_mac: None,
+            dst_mac: None,
+            transport: "tcp".to_string(),
+            protocol: "modbus".to_string(),
+            payload: vec![0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03],
+            length: 72,
+            tls: None,
+        };
+
+        let explained = engine.explain_device_packets(&[packet]);
+        // "dnp3" never got past its first filter and is omitted
+        assert_eq!(explained.len(), 2);
+        assert_eq!(explained[0].signature_name, "port");
+        assert!(explained[0].matched);
+        assert_eq!(explained[0].failed_filter, None);
+        assert_eq!(explained[1].signature_name, "near_miss");
+        assert!(!explained[1].matched);
+        assert_eq!(explained[1].filters_passed, 1);
+        assert_eq!(
+            explained[1].failed_filter.as_deref(),
+            Some("payload contains 2b0e")
+        );
+    }
 }
@@ -0,0 +1,746 @@
+//! Converter for original GRASSMARLIN fingerprint XML.
+//!
+//! GRASSMARLIN 3.x shipped its fingerprints as XML documents: a header,
+//! one or more `<Filter>` groups naming a `<Payload>`, and payload
+//! operations that `<Return>` a confidence and device details. This module
+//! translates the subset that fits the YAML signature model and reports
+//! everything else instead of silently dropping it.
+//!
+//! ## Mapping
+//!
+//! | GRASSMARLIN                             | YAML signature                           |
+//! |-----------------------------------------|------------------------------------------|
+//! | `<Filter>` (one per group)              | one signature (per transport)            |
+//! | `SrcPort` / `DstPort`                   | `tcp.*_port` / `udp.*_port`              |
+//! | `TransportProtocol` 6 / 17              | selects `tcp.` / `udp.` port fields      |
+//! | `DSizeWithin Min`                       | `payload` `min_length`                   |
+//! | `<Match>` HEX/STRING `Content`          | `payload` `pattern` (+ `offset`)         |
+//! | `<Return Confidence>`                   | `confidence` (clamped to 1-5)            |
+//! | `Category` / `Role`                     | `device_type` / `role`                   |
+//! | `Detail` Vendor / Product / ICSProtocol | `vendor` / `product_family` / `protocol` |
+//! | `<Extract>`                             | `payloads` extractor                     |
+//!
+//! Regex content, `ByteTestFunction`, `ByteJumpFunction`,
+//! `IsDataAtFunction`, `Anchor`, nested `<Match>`, relative or windowed
+//! matches, and TCP header filters (flags, window, TTL, sequence numbers)
+//! have no YAML equivalent and are listed in
+//! [`GmConversion::unconverted`].
+
+use std::collections::HashMap;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SignatureError;
+use crate::signature::{PayloadExtractor, Signature, SignatureFilter};
+
+/// Result of converting one GRASSMARLIN fingerprint document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GmConversion {
+    /// Fingerprint name from the XML header
+    pub fingerprint: String,
+    /// Signatures produced, ready to serialize with [`signature_to_yaml`]
+    pub signatures: Vec<Signature>,
+    /// Constructs that were dropped or approximated
+    pub unconverted: Vec<UnconvertedConstruct>,
+}
+
+/// A GRASSMARLIN construct the YAML schema cannot express.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnconvertedConstruct {
+    /// Where it was found, e.g. "Filter 'Dst Port'" or "Payload 'BACnet'"
+    pub context: String,
+    /// XML element name, e.g. "ByteTestFunction"
+    pub construct: String,
+    /// Why it was dropped or how it was approximated
+    pub reason: String,
+}
+
+/// Convert a GRASSMARLIN fingerprint XML document into YAML signatures.
+///
+/// Fails only when the XML itself cannot be read or is not a
+/// `<Fingerprint>` document; unsupported content is reported in the
+/// returned [`GmConversion`].
+pub fn convert_grassmarlin_xml(xml: &str) -> Result<GmConversion, SignatureError> {
+    let root = parse_tree(xml)?;
+    if root.name != "Fingerprint" {
+        return Err(SignatureError::ParseError(format!(
+            "Expected <Fingerprint> root element, found <{}>",
+            root.name
+        )));
+    }
+
+    let header = root.child("Header");
+    let fingerprint = header
+        .and_then(|h| h.child_text("Name"))
+        .unwrap_or("unnamed")
+        .to_string();
+    let description = header
+        .and_then(|h| h.child_text("Description"))
+        .unwrap_or_default()
+        .to_string();
+
+    let mut conversion = GmConversion {
+        fingerprint: fingerprint.clone(),
+        ..Default::default()
+    };
+
+    let payloads: HashMap<&str, &Element> = root
+        .children_named("Payload")
+        .filter_map(|p| p.attr("For").map(|name| (name, p)))
+        .collect();
+
+    for (filter_idx, filter) in root.children_named("Filter").enumerate() {
+        let filter_name = filter.attr("Name").unwrap_or("unnamed");
+        let context = format!("Filter '{}'", filter_name);
+
+        let Some(conditions) = convert_filter(filter, &context, &mut conversion.unconverted) else {
+            continue;
+        };
+
+        let payload_name = filter.attr("For").unwrap_or_default();
+        let Some(payload) = payloads.get(payload_name) else {
+            conversion.unconverted.push(UnconvertedConstruct {
+                context,
+                construct: "Filter".to_string(),
+                reason: format!("references missing payload '{}'", payload_name),
+            });
+            continue;
+        };
+
+        let rules = convert_payload(payload, &mut conversion.unconverted);
+        for (rule_idx, rule) in rules.iter().enumerate() {
+            for transport in &conditions.transports {
+                let mut filters: Vec<SignatureFilter> = conditions
+                    .ports
+                    .iter()
+                    .map(|(field, port)| port_filter(&format!("{}.{}", transport, field), *port))
+                    .collect();
+                if let Some(min) = conditions.min_length {
+                    filters.push(payload_filter(None, None, Some(min)));
+                }
+                if let Some(ref content) = rule.content {
+                    filters.push(payload_filter(
+                        Some(hex_escape(&content.bytes)),
+                        content.offset,
+                        None,
+                    ));
+                }
+                if filters.is_empty() {
+                    continue;
+                }
+
+                let mut name = format!(
+                    "gm_{}_{}_{}",
+                    slug(&fingerprint),
+                    slug(filter_name),
+                    filter_idx
+                );
+                if rules.len() > 1 {
+                    name.push_str(&format!("_{}", rule_idx));
+                }
+                if conditions.transports.len() > 1 {
+                    name.push_str(&format!("_{}", transport));
+                }
+
+                conversion.signatures.push(Signature {
+                    name,
+                    description: description.clone(),
+                    vendor: rule.details.vendor.clone(),
+                    product_family: rule.details.product_family.clone(),
+                    protocol: rule.details.protocol.clone(),
+                    filters,
+                    payloads: rule.details.extractors.clone(),
+                    confidence: rule.details.confidence,
+                    role: rule.details.role.clone(),
+                    device_type: rule.details.device_type.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(conversion)
+}
+
+/// Serialize a signature as a standalone YAML signature file.
+pub fn signature_to_yaml(signature: &Signature) -> Result<String, SignatureError> {
+    serde_yaml::to_string(signature)
+        .map_err(|e| SignatureError::ParseError(format!("YAML serialization failed: {}", e)))
+}
+
+// ── Filters ─────────────────────────────────────────────────────────
+
+/// Header conditions of one `<Filter>` group.
+struct FilterConditions {
+    /// ("src_port" | "dst_port", port)
+    ports: Vec<(&'static str, u16)>,
+    min_length: Option<usize>,
+    /// Transports to emit a signature for ("tcp", "udp")
+    transports: Vec<&'static str>,
+}
+
+/// Translate a `<Filter>` group. Returns `None` when nothing usable is
+/// left, since a signature without filters would match every packet.
+fn convert_filter(
+    filter: &Element,
+    context: &str,
+    unconverted: &mut Vec<UnconvertedConstruct>,
+) -> Option<FilterConditions> {
+    let mut conditions = FilterConditions {
+        ports: Vec::new(),
+        min_length: None,
+        transports: vec!["tcp", "udp"],
+    };
+    let mut note = |construct: &str, reason: &str| {
+        unconverted.push(UnconvertedConstruct {
+            context: context.to_string(),
+            construct: construct.to_string(),
+            reason: reason.to_string(),
+        })
+    };
+
+    for item in &filter.children {
+        match item.name.as_str() {
+            "SrcPort" | "DstPort" => match item.text.trim().parse::<u16>() {
+                Ok(port) => {
+                    let field = if item.name == "SrcPort" {
+                        "src_port"
+                    } else {
+                        "dst_port"
+                    };
+                    conditions.ports.push((field, port));
+                }
+                Err(_) => note(&item.name, "port is not a number"),
+            },
+            "TransportProtocol" => match item.text.trim() {
+                "6" => conditions.transports = vec!["tcp"],
+                "17" => conditions.transports = vec!["udp"],
+                other => {
+                    note(
+                        &item.name,
+                        &format!("IP protocol {} is not matched by signatures", other),
+                    );
+                    return None;
+                }
+            },
+            "DSizeWithin" => {
+                if item.attr("Max").is_some() {
+                    note(&item.name, "maximum payload size dropped");
+                }
+                conditions.min_length = item.attr("Min").and_then(|m| m.parse().ok());
+            }
+            "DSize" => {
+                note(&item.name, "exact payload size relaxed to a minimum length");
+                conditions.min_length = item.text.trim().parse().ok();
+            }
+            other => note(other, "packet header field is not available to signatures"),
+        }
+    }
+
+    if conditions.ports.is_empty() && conditions.min_length.is_none() {
+        note("Filter", "no convertible conditions; filter skipped");
+        return None;
+    }
+    // Port fields are transport-specific, so without ports a single
+    // transport-agnostic signature is enough.
+    if conditions.ports.is_empty() {
+        conditions.transports.truncate(1);
+    }
+    Some(conditions)
+}
+
+fn port_filter(field: &str, port: u16) -> SignatureFilter {
+    SignatureFilter {
+        field: field.to_string(),
+        value: Some(serde_yaml::Value::Number(port.into())),
+        pattern: None,
+        min_length: None,
+        offset: None,
+    }
+}
+
+fn payload_filter(
+    pattern: Option<String>,
+    offset: Option<usize>,
+    min_length: Option<usize>,
+) -> SignatureFilter {
+    SignatureFilter {
+        field: "payload".to_string(),
+        value: None,
+        pattern,
+        min_length,
+        offset,
+    }
+}
+
+// ── Payloads ────────────────────────────────────────────────────────
+
+/// One way a payload can identify a device: an optional content match
+/// plus the details it returns.
+struct PayloadRule {
+    content: Option<ContentMatch>,
+    details: ReturnDetails,
+}
+
+struct ContentMatch {
+    bytes: Vec<u8>,
+    /// `Some` when the match is anchored at this offset
+    offset: Option<usize>,
+}
+
+/// Translate a `<Payload>` into one rule per convertible `<Match>`, plus
+/// the `<Always>` return when present.
+fn convert_payload(
+    payload: &Element,
+    unconverted: &mut Vec<UnconvertedConstruct>,
+) -> Vec<PayloadRule> {
+    let context = format!("Payload '{}'", payload.attr("For").unwrap_or("unnamed"));
+    let mut note = |construct: &str, reason: String| {
+        unconverted.push(UnconvertedConstruct {
+            context: context.clone(),
+            construct: construct.to_string(),
+            reason,
+        })
+    };
+    let mut rules = Vec::new();
+
+    for item in &payload.children {
+        match item.name.as_str() {
+            "Description" => {}
+            "Always" => {
+                if let Some(ret) = item.child("Return") {
+                    rules.push(PayloadRule {
+                        content: None,
+                        details: convert_return(ret, &mut note),
+                    });
+                }
+            }
+            "Match" => match convert_match(item) {
+                Ok((content, ret)) => {
+                    for nested in item.children.iter().flat_map(|c| &c.children) {
+                        if nested.name != "Return" {
+                            note(
+                                &nested.name,
+                                "nested operations after a match are dropped".into(),
+                            );
+                        }
+                    }
+                    let Some(ret) = ret else {
+                        note("Match", "match without a Return identifies nothing".into());
+                        continue;
+                    };
+                    rules.push(PayloadRule {
+                        content: Some(content),
+                        details: convert_return(ret, &mut note),
+                    });
+                }
+                Err(reason) => note("Match", reason),
+            },
+            other => note(
+                other,
+                "payload operation has no signature equivalent".into(),
+            ),
+        }
+    }
+
+    rules
+}
+
+/// Translate a `<Match>` into a byte pattern and its `<AndThen><Return>`.
+fn convert_match(item: &Element) -> Result<(ContentMatch, Option<&Element>), String> {
+    for flag in ["Relative", "Reverse", "NoCase", "Within", "MoveCursors"] {
+        if item.attr(flag).is_some_and(|v| v != "false" && v != "0") {
+            return Err(format!("'{}' matching is not supported", flag));
+        }
+    }
+    if item.child("Pattern").is_some() {
+        return Err("regular expression content is not supported".into());
+    }
+    let content = item
+        .child("Content")
+        .ok_or_else(|| "match has no Content".to_string())?;
+    let bytes = match content.attr("Type").unwrap_or("STRING") {
+        "HEX" => decode_hex(&content.text)
+            .ok_or_else(|| format!("invalid HEX content '{}'", content.text))?,
+        "STRING" => content.text.as_bytes().to_vec(),
+        other => return Err(format!("'{}' content is not supported", other)),
+    };
+    if bytes.is_empty() {
+        return Err("empty content".into());
+    }
+
+    let start: usize = item
+        .attr("Offset")
+        .and_then(|o| o.parse().ok())
+        .unwrap_or(0);
+    let depth: Option<usize> = item.attr("Depth").and_then(|d| d.parse().ok());
+    let offset = match depth {
+        // Depth equal to the content length pins the match to Offset
+        Some(d) if d == bytes.len() => Some(start),
+        None if start == 0 => None,
+        _ => return Err("search windows (Offset/Depth) are not supported".into()),
+    };
+
+    let ret = item.child("AndThen").and_then(|a| a.child("Return"));
+    Ok((ContentMatch { bytes, offset }, ret))
+}
+
+/// Details carried by a `<Return>`.
+#[derive(Default)]
+struct ReturnDetails {
+    confidence: u8,
+    vendor: Option<String>,
+    product_family: Option<String>,
+    protocol: Option<String>,
+    role: Option<String>,
+    device_type: Option<String>,
+    extractors: Vec<PayloadExtractor>,
+}
+
+fn convert_return(ret: &Element, note: &mut impl FnMut(&str, String)) -> ReturnDetails {
+    let mut details = ReturnDetails {
+        confidence: ret
+            .attr("Confidence")
+            .and_then(|c| c.parse::<u8>().ok())
+            .unwrap_or(1)
+            .clamp(1, 5),
+        ..Default::default()
+    };
+
+    if let Some(info) = ret.child("Details") {
+        details.device_type = info.child_text("Category").map(|c| c.to_lowercase());
+        details.role = info.child_text("Role").map(|r| r.to_lowercase());
+        for detail in info.children_named("Detail") {
+            let value = detail.text.trim();
+            if value.is_empty() {
+                continue;
+            }
+            match detail
+                .attr("Name")
+                .unwrap_or_default()
+                .to_lowercase()
+                .as_str()
+            {
+                "vendor" => details.vendor = Some(value.to_string()),
+                "product" | "model" | "product_family" => {
+                    details.product_family = Some(value.to_string())
+                }
+                "icsprotocol" | "protocol" => details.protocol = Some(slug(value)),
+                _ => {}
+            }
+        }
+    }
+
+    for extract in ret.children_named("Extract") {
+        match convert_extract(extract) {
+            Ok(extractor) => details.extractors.push(extractor),
+            Err(reason) => note("Extract", reason),
+        }
+    }
+
+    details
+}
+
+/// Translate `<Extract Name From To Endian><Post Convert/></Extract>`.
+fn convert_extract(extract: &Element) -> Result<PayloadExtractor, String> {
+    let name = extract.attr("Name").unwrap_or("extracted").to_string();
+    let from: usize = extract
+        .attr("From")
+        .and_then(|f| f.parse().ok())
+        .ok_or_else(|| format!("'{}' has no numeric From offset", name))?;
+    let to: Option<usize> = extract.attr("To").and_then(|t| t.parse().ok());
+    let length = match to {
+        Some(to) if to > from => Some(to - from),
+        Some(_) => return Err(format!("'{}' has an empty byte range", name)),
+        None => None,
+    };
+
+    let convert = extract
+        .child("Post")
+        .and_then(|p| p.attr("Convert"))
+        .unwrap_or("RAW_BYTES");
+    let little = extract.attr("Endian") == Some("LITTLE");
+    let format = match (convert, length) {
+        ("STRING", _) => "ascii",
+        ("INTEGER", Some(2)) if little => "uint16_le",
+        ("INTEGER", Some(2)) => "uint16_be",
+        ("INTEGER", _) => return Err(format!("'{}': only 2-byte integers are supported", name)),
+        _ => "hex",
+    };
+
+    Ok(PayloadExtractor {
+        extract: format!("grassmarlin.{}", slug(&name)),
+        display: Some(name),
+        offset: Some(from),
+        length,
+        format: format.to_string(),
+    })
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+/// Decode GRASSMARLIN HEX content ("810a", "81 0a" or "0x81 0x0a").
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: String = text
+        .split_whitespace()
+        .map(|t| t.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Render bytes in the `\x..` pattern form used by the YAML signatures.
+fn hex_escape(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("\\x{:02x}", b)).collect()
+}
+
+/// Lowercase identifier with runs of other characters collapsed to `_`.
+fn slug(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+// ── Minimal XML tree ────────────────────────────────────────────────
+
+/// Generic element tree. Fingerprint payloads are ordered lists of mixed
+/// operations, so walking a tree keeps unknown elements visible for the
+/// unconverted report.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .map(|c| c.text.trim())
+            .filter(|t| !t.is_empty())
+    }
+}
+
+fn parse_tree(xml: &str) -> Result<Element, SignatureError> {
+    let xml_err = |e: &dyn std::fmt::Display| {
+        SignatureError::ParseError(format!("Invalid GRASSMARLIN XML: {}", e))
+    };
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<Element> = Vec::new();
+    loop {
+        match reader.read_event().map_err(|e| xml_err(&e))? {
+            Event::Start(start) => stack.push(open_element(&start)?),
+            Event::Empty(start) => {
+                let element = open_element(&start)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(current) = stack.last_mut() {
+                    current
+                        .text
+                        .push_str(&text.unescape().map_err(|e| xml_err(&e))?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or_else(|| xml_err(&"unbalanced end tag"))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Eof => return Err(xml_err(&"no root element")),
+            _ => {}
+        }
+    }
+}
+
+fn open_element(start: &BytesStart) -> Result<Element, SignatureError> {
+    let mut element = Element {
+        name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+        ..Default::default()
+    };
+    for attr in start.attributes() {
+        let attr = attr.map_err(|e| {
+            SignatureError::ParseError(format!("Invalid GRASSMARLIN XML attribute: {}", e))
+        })?;
+        let value = attr.unescape_value().map_err(|e| {
+            SignatureError::ParseError(format!("Invalid GRASSMARLIN XML attribute: {}", e))
+        })?;
+        element.attrs.push((
+            String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned(),
+            value.into_owned(),
+        ));
+    }
+    Ok(element)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{PacketData, SignatureEngine};
+
+    const BACNET_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Fingerprint xmlns="ICSwiki.org/GRASSMARLIN/Fingerprint">
+  <Header>
+    <Name>BACnet</Name>
+    <Author>Default</Author>
+    <Description>BACnet/IP devices</Description>
+  </Header>
+  <Type>Passive</Type>
+  <Filter For="BACnet Payload" Name="Dst Port">
+    <DstPort>47808</DstPort>
+    <TransportProtocol>17</TransportProtocol>
+  </Filter>
+  <Filter For="BACnet Payload" Name="Flagged">
+    <DstPort>47808</DstPort>
+    <Flags>SYN</Flags>
+  </Filter>
+  <Payload For="BACnet Payload">
+    <Description>BVLC header</Description>
+    <Match Offset="0" Depth="1">
+      <Content Type="HEX">81</Content>
+      <AndThen>
+        <Return Direction="DESTINATION" Confidence="4">
+          <Details>
+            <Category>PLC</Category>
+            <Role>SERVER</Role>
+            <Detail Name="ICSProtocol">BACnet</Detail>
+            <Detail Name="Vendor">Acme Controls</Detail>
+          </Details>
+          <Extract Name="BVLC Function" From="1" To="2"/>
+        </Return>
+      </AndThen>
+    </Match>
+    <ByteTestFunction PostOffset="0" Relative="false" Value="10" GT="true"/>
+  </Payload>
+</Fingerprint>"#;
+
+    #[test]
+    fn test_convert_match_and_report_unsupported() {
+        let conv = convert_grassmarlin_xml(BACNET_XML).unwrap();
+        assert_eq!(conv.fingerprint, "BACnet");
+
+        // UDP-only filter → one signature; transport-agnostic filter → tcp + udp
+        let names: Vec<&str> = conv.signatures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "gm_bacnet_dst_port_0",
+                "gm_bacnet_flagged_1_tcp",
+                "gm_bacnet_flagged_1_udp"
+            ]
+        );
+
+        let sig = &conv.signatures[0];
+        assert_eq!(sig.confidence, 4);
+        assert_eq!(sig.vendor.as_deref(), Some("Acme Controls"));
+        assert_eq!(sig.protocol.as_deref(), Some("bacnet"));
+        assert_eq!(sig.device_type.as_deref(), Some("plc"));
+        assert_eq!(sig.role.as_deref(), Some("server"));
+        assert_eq!(sig.filters[0].field, "udp.dst_port");
+        assert_eq!(sig.filters[1].pattern.as_deref(), Some("\\x81"));
+        assert_eq!(sig.filters[1].offset, Some(0));
+        assert_eq!(sig.payloads[0].offset, Some(1));
+        assert_eq!(sig.payloads[0].length, Some(1));
+        assert_eq!(sig.payloads[0].format, "hex");
+
+        let constructs: Vec<&str> = conv
+            .unconverted
+            .iter()
+            .map(|u| u.construct.as_str())
+            .collect();
+        assert!(constructs.contains(&"Flags"));
+        assert!(constructs.contains(&"ByteTestFunction"));
+    }
+
+    #[test]
+    fn test_converted_signature_loads_and_matches() {
+        let conv = convert_grassmarlin_xml(BACNET_XML).unwrap();
+        let yaml = signature_to_yaml(&conv.signatures[0]).unwrap();
+
+        let mut engine = SignatureEngine::new();
+        engine.load_yaml(&yaml).unwrap();
+
+        let packet = PacketData {
+            src_ip: "10.0.0.5".into(),
+            dst_ip: "10.0.0.9".into(),
+            src_port: 47808,
+            dst_port: 47808,
+            src_mac: None,
+            dst_mac: None,
+            transport: "udp".into(),
+            protocol: "bacnet".into(),
+            payload: vec![0x81, 0x0a, 0x00, 0x11],
+            length: 4,
+            tls: None,
+        };
+        let matches = engine.match_packet(&packet);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].extracted_values[0].value, "0a");
+    }
+
+    #[test]
+    fn test_unsupported_match_and_bad_root() {
+        let xml = r#"<Fingerprint>
+  <Header><Name>Regex Only</Name></Header>
+  <Filter For="P" Name="Port"><SrcPort>502</SrcPort></Filter>
+  <Payload For="P">
+    <Match Offset="4" Depth="20"><Content Type="HEX">00 00</Content></Match>
+    <Match><Pattern>^Modicon</Pattern></Match>
+    <Always><Return Confidence="9"/></Always>
+  </Payload>
+</Fingerprint>"#;
+        let conv = convert_grassmarlin_xml(xml).unwrap();
+        // Only the Always return survives, on each transport, with clamped confidence
+        assert_eq!(conv.signatures.len(), 2);
+        assert!(conv.signatures.iter().all(|s| s.confidence == 5));
+        assert_eq!(
+            conv.unconverted
+                .iter()
+                .filter(|u| u.construct == "Match")
+                .count(),
+            2
+        );
+
+        assert!(convert_grassmarlin_xml("<Signatures/>").is_err());
+        assert!(convert_grassmarlin_xml("<Fingerprint><Header>").is_err());
+    }
+}
@@ -18,11 +18,21 @@
 //! 3. MAC OUI vendor match (e.g., 00:0E:8C = Siemens)
 //! 4. Payload byte pattern match (e.g., "Schneider" string in Modbus response)
 //! 5. Deep protocol parse confirmation (e.g., Modbus FC 43 Device ID response)
+//!
+//! Original GRASSMARLIN fingerprint XML can be converted into this schema
+//! with [`convert_grassmarlin_xml`].
 
 mod engine;
 mod error;
+mod grassmarlin;
 mod signature;
 
-pub use engine::{PacketData, SignatureEngine, TestResult};
+pub use engine::{
+    PacketData, PinnedSignature, SignatureDiff, SignatureEngine, SignatureExplanation,
+    SignatureSetSnapshot, TestResult,
+};
 pub use error::SignatureError;
+pub use grassmarlin::{
+    convert_grassmarlin_xml, signature_to_yaml, GmConversion, UnconvertedConstruct,
+};
 pub use signature::{ExtractedValue, PayloadExtractor, Signature, SignatureFilter, SignatureMatch};
@@ -55,7 +55,8 @@ pub struct Signature {
 /// for the signature to fire on a given packet.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignatureFilter {
-    /// The field to check (e.g., "tcp.dst_port", "udp.src_port", "payload", "mac.src_oui")
+    /// The field to check (e.g., "tcp.dst_port", "udp.src_port", "payload", "mac.src_oui",
+    /// "tls.ja3", "tls.ja3s", "tls.sni")
     pub field: String,
 
     /// Exact value match (for port numbers, protocol names, etc.)
@@ -0,0 +1,219 @@
+//! Centrality metrics for the nodes of a [`TopologyGraph`].
+//!
+//! Two kinds of device matter most when they fail or are compromised:
+//! ones many others talk *through* (high betweenness — a jump host or a
+//! router-like gateway between cells) and ones that talk *to* many others
+//! (high fan-out — the one HMI or SCADA server polling every PLC). Both are
+//! flagged as choke points.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use crate::TopologyGraph;
+
+/// Betweenness at or above which a node counts as a choke point.
+pub const CHOKE_POINT_BETWEENNESS: f64 = 0.1;
+
+/// Fan-out at or above which a node counts as a choke point.
+pub const CHOKE_POINT_FAN_OUT: usize = 10;
+
+/// Above this many nodes, betweenness is estimated from a sample of source
+/// nodes rather than computed exactly (Brandes is O(nodes × edges)).
+const EXACT_BETWEENNESS_LIMIT: usize = 2_000;
+
+/// Source nodes sampled for the estimate on larger graphs.
+const BETWEENNESS_SAMPLES: usize = 500;
+
+/// How central a node is to the observed communication.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct NodeCentrality {
+    /// Distinct peers, in either direction
+    pub degree: usize,
+    /// Distinct peers that sent traffic to this node
+    pub fan_in: usize,
+    /// Distinct peers this node sent traffic to
+    pub fan_out: usize,
+    /// Share of the shortest paths between other pairs of nodes that pass
+    /// through this one, from 0 to 1
+    pub betweenness: f64,
+    /// Betweenness or fan-out crosses its choke-point threshold
+    pub choke_point: bool,
+}
+
+/// Set `centrality` on every node of `graph`.
+pub(crate) fn assign_centrality(graph: &mut TopologyGraph) {
+    let n = graph.nodes.len();
+    let index: HashMap<&str, usize> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.id.as_str(), i))
+        .collect();
+
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut incoming: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for edge in &graph.edges {
+        if let (Some(&src), Some(&dst)) = (
+            index.get(edge.source.as_str()),
+            index.get(edge.target.as_str()),
+        ) {
+            if src != dst {
+                outgoing[src].push(dst);
+                incoming[dst].push(src);
+            }
+        }
+    }
+    for peers in outgoing.iter_mut().chain(incoming.iter_mut()) {
+        peers.sort_unstable();
+        peers.dedup();
+    }
+
+    // Sample evenly across the nodes in id order so the estimate does not
+    // depend on map iteration order
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| graph.nodes[a].id.cmp(&graph.nodes[b].id));
+    let sources: Vec<usize> = if n > EXACT_BETWEENNESS_LIMIT {
+        (0..BETWEENNESS_SAMPLES)
+            .map(|i| order[i * n / BETWEENNESS_SAMPLES])
+            .collect()
+    } else {
+        order
+    };
+    let scale = n as f64 / sources.len().max(1) as f64;
+    let pairs = (n.saturating_sub(1) * n.saturating_sub(2)) as f64;
+    let betweenness = betweenness(&outgoing, &sources);
+
+    for (i, node) in graph.nodes.iter_mut().enumerate() {
+        let mut peers: Vec<usize> = outgoing[i].iter().chain(&incoming[i]).copied().collect();
+        peers.sort_unstable();
+        peers.dedup();
+
+        let betweenness = if pairs > 0.0 {
+            (betweenness[i] * scale / pairs).min(1.0)
+        } else {
+            0.0
+        };
+        let fan_out = outgoing[i].len();
+        node.centrality = NodeCentrality {
+            degree: peers.len(),
+            fan_in: incoming[i].len(),
+            fan_out,
+            betweenness,
+            choke_point: betweenness >= CHOKE_POINT_BETWEENNESS || fan_out >= CHOKE_POINT_FAN_OUT,
+        };
+    }
+}
+
+/// Brandes' algorithm on an unweighted directed graph: for each source, a
+/// BFS counts shortest paths, then dependencies are accumulated back up the
+/// BFS tree. Returns raw (unnormalised) scores.
+fn betweenness(outgoing: &[Vec<usize>], sources: &[usize]) -> Vec<f64> {
+    let n = outgoing.len();
+    let mut centrality = vec![0.0; n];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut paths = vec![0.0f64; n];
+    let mut distance = vec![usize::MAX; n];
+    let mut dependency = vec![0.0f64; n];
+    let mut stack = Vec::with_capacity(n);
+    let mut queue = VecDeque::new();
+
+    for &source in sources {
+        for v in 0..n {
+            predecessors[v].clear();
+            paths[v] = 0.0;
+            distance[v] = usize::MAX;
+            dependency[v] = 0.0;
+        }
+        paths[source] = 1.0;
+        distance[source] = 0;
+        queue.push_back(source);
+
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            for &w in &outgoing[v] {
+                if distance[w] == usize::MAX {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+
+        while let Some(w) = stack.pop() {
+            for &v in &predecessors[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != source {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+    centrality
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TopologyBuilder;
+    use gm_parsers::IcsProtocol;
+
+    fn centrality_of(graph: &TopologyGraph, id: &str) -> NodeCentrality {
+        graph.nodes.iter().find(|n| n.id == id).unwrap().centrality
+    }
+
+    #[test]
+    fn test_hmi_polling_every_plc_is_choke_point() {
+        let mut builder = TopologyBuilder::new();
+        for i in 1..=12 {
+            let plc = format!("10.0.1.{}", i);
+            builder.add_connection("10.0.0.5", &plc, None, None, IcsProtocol::Modbus, 80);
+            builder.add_connection(&plc, "10.0.0.5", None, None, IcsProtocol::Modbus, 80);
+        }
+        let graph = builder.build();
+
+        let hmi = centrality_of(&graph, "10.0.0.5");
+        assert_eq!((hmi.degree, hmi.fan_in, hmi.fan_out), (12, 12, 12));
+        assert!(hmi.choke_point);
+        // Every PLC reaches every other only through the HMI
+        assert!((hmi.betweenness - 1.0).abs() < 1e-9);
+
+        let plc = centrality_of(&graph, "10.0.1.1");
+        assert_eq!((plc.degree, plc.fan_out), (1, 1));
+        assert_eq!(plc.betweenness, 0.0);
+        assert!(!plc.choke_point);
+    }
+
+    #[test]
+    fn test_betweenness_follows_traffic_direction() {
+        let mut builder = TopologyBuilder::new();
+        builder.add_connection("10.0.0.1", "10.0.0.2", None, None, IcsProtocol::Rdp, 100);
+        builder.add_connection("10.0.0.2", "10.0.0.3", None, None, IcsProtocol::S7comm, 100);
+        let graph = builder.build();
+
+        // Of the two ordered pairs not involving the jump host, only
+        // 10.0.0.1 → 10.0.0.3 is connected, and only through it
+        let jump = centrality_of(&graph, "10.0.0.2");
+        assert!((jump.betweenness - 0.5).abs() < 1e-9);
+        assert!(jump.choke_point);
+        assert_eq!((jump.fan_in, jump.fan_out), (1, 1));
+        assert_eq!(centrality_of(&graph, "10.0.0.1").betweenness, 0.0);
+    }
+
+    #[test]
+    fn test_betweenness_splits_between_equal_paths() {
+        let mut builder = TopologyBuilder::new();
+        for via in ["10.0.0.2", "10.0.0.3"] {
+            builder.add_connection("10.0.0.1", via, None, None, IcsProtocol::Smb, 100);
+            builder.add_connection(via, "10.0.0.4", None, None, IcsProtocol::Smb, 100);
+        }
+        let graph = builder.build();
+        let a = centrality_of(&graph, "10.0.0.2").betweenness;
+        let b = centrality_of(&graph, "10.0.0.3").betweenness;
+        assert!((a - b).abs() < 1e-9);
+        assert!((a - 0.5 / 6.0).abs() < 1e-9);
+    }
+}
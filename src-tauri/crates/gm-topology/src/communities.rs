@@ -0,0 +1,239 @@
+//! Community detection over a [`TopologyGraph`].
+//!
+//! ICS networks are built as cells or areas — a line controller with its
+//! PLCs, drives and HMI, a substation bay with its IEDs — that talk mostly
+//! among themselves. Those groups show up in the traffic graph as densely
+//! connected communities, which Louvain modularity optimisation finds
+//! without needing any addressing plan. Each connected pair of devices
+//! counts once whatever its volume, so a chatty historian poll does not
+//! outweigh the cell structure.
+//!
+//! The pass is deterministic: nodes are visited in id order and ties go to
+//! the lowest community, so the same graph always clusters the same way and
+//! live snapshots do not reshuffle groups between refreshes.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::TopologyGraph;
+
+/// Cap on local-move sweeps per level; Louvain usually settles in a few.
+const MAX_SWEEPS: usize = 32;
+
+/// Modularity gains smaller than this are rounding noise, not a move.
+const MIN_GAIN: f64 = 1e-12;
+
+/// Weighted undirected adjacency; a node may list itself (a self-loop
+/// holding the weight inside an aggregated community).
+type Adjacency = Vec<BTreeMap<usize, f64>>;
+
+/// Set `cluster_id` on every node of `graph`.
+///
+/// Clusters are numbered from 0, largest first (ties by lowest member id).
+/// Nodes that end up in a community of their own get `None`.
+pub(crate) fn assign_clusters(graph: &mut TopologyGraph) {
+    let mut order: Vec<usize> = (0..graph.nodes.len()).collect();
+    order.sort_by(|&a, &b| graph.nodes[a].id.cmp(&graph.nodes[b].id));
+
+    let membership = {
+        let position: HashMap<&str, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(pos, &i)| (graph.nodes[i].id.as_str(), pos))
+            .collect();
+        let mut adjacency: Adjacency = vec![BTreeMap::new(); order.len()];
+        for edge in &graph.edges {
+            if let (Some(&a), Some(&b)) = (
+                position.get(edge.source.as_str()),
+                position.get(edge.target.as_str()),
+            ) {
+                if a != b {
+                    adjacency[a].insert(b, 1.0);
+                    adjacency[b].insert(a, 1.0);
+                }
+            }
+        }
+        louvain(adjacency)
+    };
+
+    // Positions are visited in order, so each member list is sorted
+    let mut communities: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (pos, &community) in membership.iter().enumerate() {
+        communities.entry(community).or_default().push(pos);
+    }
+    let mut clusters: Vec<Vec<usize>> = communities
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .collect();
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+
+    for node in &mut graph.nodes {
+        node.cluster_id = None;
+    }
+    for (cluster_id, members) in clusters.iter().enumerate() {
+        for &pos in members {
+            graph.nodes[order[pos]].cluster_id = Some(cluster_id as u32);
+        }
+    }
+}
+
+/// Louvain: alternate local moves and aggregation until no level merges
+/// anything. Returns the community of each input node.
+fn louvain(mut adjacency: Adjacency) -> Vec<usize> {
+    let mut membership: Vec<usize> = (0..adjacency.len()).collect();
+    loop {
+        let (community, count) = renumber(&local_moves(&adjacency));
+        if count == adjacency.len() {
+            return membership;
+        }
+        for m in &mut membership {
+            *m = community[*m];
+        }
+        adjacency = aggregate(&adjacency, &community, count);
+    }
+}
+
+/// Move each node to the neighbouring community with the best modularity
+/// gain, sweeping until nothing moves.
+fn local_moves(adjacency: &Adjacency) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut community: Vec<usize> = (0..n).collect();
+    let degree: Vec<f64> = adjacency.iter().map(|links| links.values().sum()).collect();
+    // Twice the total edge weight
+    let total: f64 = degree.iter().sum();
+    if total == 0.0 {
+        return community;
+    }
+    let mut community_degree = degree.clone();
+
+    for _ in 0..MAX_SWEEPS {
+        let mut moved = false;
+        for node in 0..n {
+            let current = community[node];
+            community_degree[current] -= degree[node];
+
+            let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+            for (&neighbour, &weight) in &adjacency[node] {
+                if neighbour != node {
+                    *links.entry(community[neighbour]).or_default() += weight;
+                }
+            }
+            let gain = |c: usize, weight: f64| weight - community_degree[c] * degree[node] / total;
+
+            let mut best = current;
+            let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+            for (&c, &weight) in &links {
+                let g = gain(c, weight);
+                if g > best_gain + MIN_GAIN {
+                    best = c;
+                    best_gain = g;
+                }
+            }
+
+            community_degree[best] += degree[node];
+            if best != current {
+                community[node] = best;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    community
+}
+
+/// Relabel communities 0..count in order of first appearance.
+fn renumber(community: &[usize]) -> (Vec<usize>, usize) {
+    let mut labels: HashMap<usize, usize> = HashMap::new();
+    let renumbered = community
+        .iter()
+        .map(|&c| {
+            let next = labels.len();
+            *labels.entry(c).or_insert(next)
+        })
+        .collect();
+    (renumbered, labels.len())
+}
+
+/// Collapse each community into a single node, keeping the weight between
+/// and within communities.
+fn aggregate(adjacency: &Adjacency, community: &[usize], count: usize) -> Adjacency {
+    let mut next: Adjacency = vec![BTreeMap::new(); count];
+    for (node, links) in adjacency.iter().enumerate() {
+        for (&neighbour, &weight) in links {
+            *next[community[node]]
+                .entry(community[neighbour])
+                .or_default() += weight;
+        }
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TopologyBuilder;
+    use gm_parsers::IcsProtocol;
+
+    fn cluster_of(graph: &crate::TopologyGraph, id: &str) -> Option<u32> {
+        graph.nodes.iter().find(|n| n.id == id).unwrap().cluster_id
+    }
+
+    /// Two fully meshed cells joined by a single supervisory link.
+    fn two_cells() -> crate::TopologyGraph {
+        let mut builder = TopologyBuilder::new();
+        let cells = [
+            ["10.1.0.1", "10.1.0.2", "10.1.0.3", "10.1.0.4", "10.1.0.5"],
+            ["10.2.0.1", "10.2.0.2", "10.2.0.3", "10.2.0.4", "10.2.0.5"],
+        ];
+        for cell in &cells {
+            for (i, src) in cell.iter().enumerate() {
+                for dst in &cell[i + 1..] {
+                    builder.add_connection(src, dst, None, None, IcsProtocol::Modbus, 100);
+                }
+            }
+        }
+        builder.add_connection("10.1.0.1", "10.2.0.1", None, None, IcsProtocol::OpcUa, 100);
+        builder.build()
+    }
+
+    #[test]
+    fn test_cells_become_clusters() {
+        let graph = two_cells();
+        let first = cluster_of(&graph, "10.1.0.3").unwrap();
+        let second = cluster_of(&graph, "10.2.0.3").unwrap();
+        assert_ne!(first, second);
+        for node in &graph.nodes {
+            let expected = if node.id.starts_with("10.1.") {
+                first
+            } else {
+                second
+            };
+            assert_eq!(node.cluster_id, Some(expected), "{}", node.id);
+        }
+        // Equal sizes: the cell with the lowest member id is numbered first
+        assert_eq!(first, 0);
+    }
+
+    #[test]
+    fn test_clustering_is_stable() {
+        let graph = two_cells();
+        for _ in 0..5 {
+            let again = two_cells();
+            for node in &graph.nodes {
+                assert_eq!(cluster_of(&again, &node.id), node.cluster_id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unconnected_node_has_no_cluster() {
+        let mut builder = TopologyBuilder::new();
+        builder.add_connection("10.0.0.1", "10.0.0.2", None, None, IcsProtocol::Dnp3, 60);
+        // Traffic to itself connects a node to nothing else
+        builder.add_connection("10.0.0.9", "10.0.0.9", None, None, IcsProtocol::Dns, 60);
+        let graph = builder.build();
+        assert_eq!(cluster_of(&graph, "10.0.0.1"), Some(0));
+        assert_eq!(cluster_of(&graph, "10.0.0.2"), Some(0));
+        assert_eq!(cluster_of(&graph, "10.0.0.9"), None);
+    }
+}
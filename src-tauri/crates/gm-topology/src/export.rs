@@ -0,0 +1,413 @@
+//! Graph file formats for opening the topology in other tools.
+//!
+//! - GraphML for yEd and most graph libraries,
+//! - GEXF for Gephi, with first/last-seen times as the node and edge
+//!   lifetimes so its timeline can replay the capture,
+//! - Graphviz DOT for documentation pipelines.
+//!
+//! All three carry the same node and edge attributes. Nodes are written in
+//! id order and edges by endpoints, so exporting the same graph twice gives
+//! the same file.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::{TopoEdge, TopoNode, TopologyGraph};
+
+/// Attribute value type.
+#[derive(Clone, Copy)]
+enum Kind {
+    Text,
+    Count,
+    Real,
+    Flag,
+}
+
+impl Kind {
+    /// Type name, the same in GraphML and GEXF
+    fn type_name(self) -> &'static str {
+        match self {
+            Kind::Text => "string",
+            Kind::Count => "long",
+            Kind::Real => "double",
+            Kind::Flag => "boolean",
+        }
+    }
+}
+
+const NODE_ATTRS: &[(&str, Kind)] = &[
+    ("ip_address", Kind::Text),
+    ("mac_address", Kind::Text),
+    ("addresses", Kind::Text),
+    ("device_type", Kind::Text),
+    ("vendor", Kind::Text),
+    ("subnet", Kind::Text),
+    ("vlans", Kind::Text),
+    ("protocols", Kind::Text),
+    ("packet_count", Kind::Count),
+    ("cluster_id", Kind::Count),
+    ("degree", Kind::Count),
+    ("betweenness", Kind::Real),
+    ("choke_point", Kind::Flag),
+    ("first_seen", Kind::Text),
+    ("last_seen", Kind::Text),
+];
+
+const EDGE_ATTRS: &[(&str, Kind)] = &[
+    ("protocol", Kind::Text),
+    ("protocols", Kind::Text),
+    ("packet_count", Kind::Count),
+    ("byte_count", Kind::Count),
+    ("bidirectional", Kind::Flag),
+    ("first_seen", Kind::Text),
+    ("last_seen", Kind::Text),
+];
+
+/// Values for [`NODE_ATTRS`], in order; None where unknown.
+fn node_values(node: &TopoNode) -> Vec<Option<String>> {
+    let protocols: Vec<&str> = node.protocols.iter().map(|p| p.to_name()).collect();
+    let vlans: Vec<String> = node.vlans.iter().map(u16::to_string).collect();
+    vec![
+        Some(node.ip_address.clone()),
+        node.mac_address.clone(),
+        Some(node.addresses.join(",")).filter(|a| !a.is_empty()),
+        Some(node.device_type.clone()),
+        node.vendor.clone(),
+        Some(node.subnet.clone()),
+        Some(vlans.join(",")).filter(|v| !v.is_empty()),
+        Some(protocols.join(",")),
+        Some(node.packet_count.to_string()),
+        node.cluster_id.map(|c| c.to_string()),
+        Some(node.centrality.degree.to_string()),
+        Some(format!("{:.6}", node.centrality.betweenness)),
+        Some(node.centrality.choke_point.to_string()),
+        node.first_seen.map(timestamp),
+        node.last_seen.map(timestamp),
+    ]
+}
+
+/// Values for [`EDGE_ATTRS`], in order; None where unknown.
+fn edge_values(edge: &TopoEdge) -> Vec<Option<String>> {
+    let mut protocols: Vec<&str> = edge
+        .protocol_breakdown
+        .keys()
+        .map(|p| p.to_name())
+        .collect();
+    protocols.sort_unstable();
+    vec![
+        Some(edge.protocol.to_name().to_string()),
+        Some(protocols.join(",")),
+        Some(edge.packet_count.to_string()),
+        Some(edge.byte_count.to_string()),
+        Some(edge.bidirectional.to_string()),
+        edge.first_seen.map(timestamp),
+        edge.last_seen.map(timestamp),
+    ]
+}
+
+fn timestamp(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn sorted_nodes(graph: &TopologyGraph) -> Vec<&TopoNode> {
+    let mut nodes: Vec<&TopoNode> = graph.nodes.iter().collect();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+    nodes
+}
+
+fn sorted_edges(graph: &TopologyGraph) -> Vec<&TopoEdge> {
+    let mut edges: Vec<&TopoEdge> = graph.edges.iter().collect();
+    edges.sort_by(|a, b| (&a.source, &a.target, &a.id).cmp(&(&b.source, &b.target, &b.id)));
+    edges
+}
+
+/// Escape text for an XML attribute value or element body.
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Not allowed in XML 1.0 at all
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Quote a DOT identifier or attribute value.
+fn dot_quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl TopologyGraph {
+    /// The graph as GraphML, one `<key>` per node and edge attribute.
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        for (scope, prefix, attrs) in [("node", "n", NODE_ATTRS), ("edge", "e", EDGE_ATTRS)] {
+            for (name, kind) in attrs {
+                let _ = writeln!(
+                    out,
+                    "  <key id=\"{prefix}_{name}\" for=\"{scope}\" attr.name=\"{name}\" attr.type=\"{}\"/>",
+                    kind.type_name()
+                );
+            }
+        }
+        out.push_str("  <graph id=\"topology\" edgedefault=\"directed\">\n");
+        for node in sorted_nodes(self) {
+            let _ = writeln!(out, "    <node id=\"{}\">", xml_escape(&node.id));
+            for ((name, _), value) in NODE_ATTRS.iter().zip(node_values(node)) {
+                if let Some(value) = value {
+                    let _ = writeln!(
+                        out,
+                        "      <data key=\"n_{name}\">{}</data>",
+                        xml_escape(&value)
+                    );
+                }
+            }
+            out.push_str("    </node>\n");
+        }
+        for edge in sorted_edges(self) {
+            let _ = writeln!(
+                out,
+                "    <edge id=\"{}\" source=\"{}\" target=\"{}\">",
+                xml_escape(&edge.id),
+                xml_escape(&edge.source),
+                xml_escape(&edge.target)
+            );
+            for ((name, _), value) in EDGE_ATTRS.iter().zip(edge_values(edge)) {
+                if let Some(value) = value {
+                    let _ = writeln!(
+                        out,
+                        "      <data key=\"e_{name}\">{}</data>",
+                        xml_escape(&value)
+                    );
+                }
+            }
+            out.push_str("    </edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    /// The graph as GEXF 1.3. Nodes and edges with first/last-seen times get
+    /// them as `start`/`end`, so Gephi can play the capture back.
+    pub fn to_gexf(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+        out.push_str(
+            "  <graph defaultedgetype=\"directed\" mode=\"dynamic\" timeformat=\"dateTime\">\n",
+        );
+        for (scope, attrs) in [("node", NODE_ATTRS), ("edge", EDGE_ATTRS)] {
+            let _ = writeln!(out, "    <attributes class=\"{scope}\">");
+            for (i, (name, kind)) in attrs.iter().enumerate() {
+                let _ = writeln!(
+                    out,
+                    "      <attribute id=\"{i}\" title=\"{name}\" type=\"{}\"/>",
+                    kind.type_name()
+                );
+            }
+            out.push_str("    </attributes>\n");
+        }
+
+        out.push_str("    <nodes>\n");
+        for node in sorted_nodes(self) {
+            let _ = writeln!(
+                out,
+                "      <node id=\"{}\" label=\"{}\"{}>",
+                xml_escape(&node.id),
+                xml_escape(&node.ip_address),
+                lifetime(node.first_seen, node.last_seen)
+            );
+            write_gexf_values(&mut out, node_values(node));
+            out.push_str("      </node>\n");
+        }
+        out.push_str("    </nodes>\n    <edges>\n");
+        for edge in sorted_edges(self) {
+            let _ = writeln!(
+                out,
+                "      <edge id=\"{}\" source=\"{}\" target=\"{}\" label=\"{}\" weight=\"{}\"{}>",
+                xml_escape(&edge.id),
+                xml_escape(&edge.source),
+                xml_escape(&edge.target),
+                edge.protocol.to_name(),
+                edge.packet_count,
+                lifetime(edge.first_seen, edge.last_seen)
+            );
+            write_gexf_values(&mut out, edge_values(edge));
+            out.push_str("      </edge>\n");
+        }
+        out.push_str("    </edges>\n  </graph>\n</gexf>\n");
+        out
+    }
+
+    /// The graph as a Graphviz `digraph`. Nodes are labelled with their
+    /// address and device type, edges with their dominant protocol; every
+    /// other attribute is carried along for tools that read the file.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph topology {\n");
+        out.push_str("  node [shape=box];\n");
+        for node in sorted_nodes(self) {
+            let label = format!("{}\n{}", node.ip_address, node.device_type);
+            let _ = write!(
+                out,
+                "  {} [label={}",
+                dot_quote(&node.id),
+                dot_quote(&label)
+            );
+            for ((name, _), value) in NODE_ATTRS.iter().zip(node_values(node)) {
+                if let Some(value) = value {
+                    let _ = write!(out, ", {name}={}", dot_quote(&value));
+                }
+            }
+            out.push_str("];\n");
+        }
+        for edge in sorted_edges(self) {
+            let _ = write!(
+                out,
+                "  {} -> {} [label={}",
+                dot_quote(&edge.source),
+                dot_quote(&edge.target),
+                dot_quote(edge.protocol.to_name())
+            );
+            for ((name, _), value) in EDGE_ATTRS.iter().zip(edge_values(edge)) {
+                if let Some(value) = value {
+                    let _ = write!(out, ", {name}={}", dot_quote(&value));
+                }
+            }
+            out.push_str("];\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// GEXF `start`/`end` attributes, if the time range is known.
+fn lifetime(first: Option<DateTime<Utc>>, last: Option<DateTime<Utc>>) -> String {
+    match (first, last) {
+        (Some(first), Some(last)) => format!(
+            " start=\"{}\" end=\"{}\"",
+            timestamp(first),
+            timestamp(last)
+        ),
+        _ => String::new(),
+    }
+}
+
+fn write_gexf_values(out: &mut String, values: Vec<Option<String>>) {
+    if values.iter().all(Option::is_none) {
+        return;
+    }
+    out.push_str("        <attvalues>\n");
+    for (i, value) in values.into_iter().enumerate() {
+        if let Some(value) = value {
+            let _ = writeln!(
+                out,
+                "          <attvalue for=\"{i}\" value=\"{}\"/>",
+                xml_escape(&value)
+            );
+        }
+    }
+    out.push_str("        </attvalues>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::TopologyBuilder;
+    use gm_parsers::IcsProtocol;
+
+    fn plant() -> TopologyGraph {
+        let mut builder = TopologyBuilder::new();
+        builder.add_connection(
+            "10.0.0.5",
+            "10.0.0.100",
+            Some("00:50:56:00:00:05"),
+            Some("00:0e:8c:00:00:10"),
+            IcsProtocol::S7comm,
+            120,
+        );
+        builder.mark_seen(
+            "10.0.0.5",
+            "10.0.0.100",
+            Utc.with_ymd_and_hms(2024, 3, 1, 8, 0, 0).unwrap(),
+        );
+        builder.add_connection(
+            "10.0.0.100",
+            "10.0.0.5",
+            None,
+            None,
+            IcsProtocol::S7comm,
+            80,
+        );
+        builder.build()
+    }
+
+    #[test]
+    fn test_graphml_carries_attributes() {
+        let mut graph = plant();
+        graph.nodes[0].vendor = Some("Smith & Sons <Controls>".to_string());
+        let xml = graph.to_graphml();
+
+        assert!(xml.contains("attr.name=\"device_type\" attr.type=\"string\""));
+        assert!(xml.contains("<node id=\"10.0.0.100\">"));
+        assert!(xml.contains("<data key=\"n_mac_address\">00:0e:8c:00:00:10</data>"));
+        assert!(xml.contains("Smith &amp; Sons &lt;Controls&gt;"));
+        assert!(xml.contains("source=\"10.0.0.5\" target=\"10.0.0.100\""));
+        assert!(xml.contains("<data key=\"e_bidirectional\">true</data>"));
+        assert!(xml.contains("<data key=\"e_first_seen\">2024-03-01T08:00:00.000Z</data>"));
+        assert_eq!(xml.matches("<node ").count(), 2);
+        assert_eq!(xml.matches("<edge ").count(), 2);
+    }
+
+    #[test]
+    fn test_gexf_lifetimes() {
+        let gexf = plant().to_gexf();
+        assert!(gexf.contains("<attribute id=\"0\" title=\"ip_address\" type=\"string\"/>"));
+        // Only the timed edge gets a lifetime
+        assert!(gexf.contains(
+            "source=\"10.0.0.5\" target=\"10.0.0.100\" label=\"s7comm\" weight=\"1\" \
+             start=\"2024-03-01T08:00:00.000Z\" end=\"2024-03-01T08:00:00.000Z\">"
+        ));
+        assert!(gexf
+            .contains("source=\"10.0.0.100\" target=\"10.0.0.5\" label=\"s7comm\" weight=\"1\">"));
+    }
+
+    #[test]
+    fn test_dot_is_deterministic() {
+        let mut graph = plant();
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph topology {\n"));
+        assert!(dot.contains("\"10.0.0.5\" -> \"10.0.0.100\" [label=\"s7comm\""));
+        assert!(dot.contains("label=\"10.0.0.5\\nunknown\""));
+
+        graph.nodes.reverse();
+        graph.edges.reverse();
+        assert_eq!(graph.to_dot(), dot);
+
+        graph.nodes[0].vendor = Some("say \"hi\"".to_string());
+        assert!(graph.to_dot().contains("vendor=\"say \\\"hi\\\"\""));
+    }
+}
@@ -0,0 +1,276 @@
+//! Gateway inference from IP-to-MAC bindings.
+//!
+//! A routed packet carries the remote host's IP address but the router's
+//! MAC address, so a router shows up as one MAC fronting many IPs. Two
+//! signs mark a MAC as a gateway:
+//!
+//! - it fronts at least [`MIN_ROUTED_IPS`] addresses spread over more than
+//!   one subnet, or
+//! - an address appears behind several MACs, and two of them are only ever
+//!   used by peers in different subnets (the host is reached through a
+//!   different router from each side).
+//!
+//! The gateway's own address is the one it fronts in the subnet of the hosts
+//! sending through it; if none is seen, the gateway is known by its MAC.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::{is_group_mac, SubnetPlan, TopoNode};
+
+/// Addresses a MAC must front (across subnets) to count as a gateway.
+pub const MIN_ROUTED_IPS: usize = 3;
+
+/// A MAC address that forwards traffic for other IP addresses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Gateway {
+    /// Lowercase MAC address
+    pub mac_address: String,
+    /// The gateway's own address, when it could be told apart from the
+    /// hosts behind it
+    pub ip_address: Option<String>,
+    /// Addresses reached through this gateway, sorted
+    pub routed_ips: Vec<String>,
+    /// Subnets of the hosts that talked through it, sorted
+    pub local_subnets: Vec<String>,
+}
+
+impl Gateway {
+    /// Topology node id: the gateway's IP, or its MAC if the IP is unknown.
+    pub fn node_id(&self) -> &str {
+        self.ip_address.as_deref().unwrap_or(&self.mac_address)
+    }
+
+    /// Whether `ip` is reached through this gateway rather than being it.
+    pub fn routes(&self, ip: &str) -> bool {
+        self.routed_ips
+            .binary_search_by(|r| r.as_str().cmp(ip))
+            .is_ok()
+    }
+}
+
+/// MAC → IP → subnets of the peers that used that binding.
+pub(crate) type Bindings = BTreeMap<String, BTreeMap<String, BTreeSet<String>>>;
+
+/// `(src_ip, dst_ip)` → first `(src_mac, dst_mac)` seen on that edge.
+pub(crate) type EdgeMacs = HashMap<(String, String), (Option<String>, Option<String>)>;
+
+/// Collect the unicast IP-to-MAC bindings seen on each `(src_ip, dst_ip)`
+/// edge.
+pub(crate) fn bindings(edge_macs: &EdgeMacs, subnets: &SubnetPlan) -> Bindings {
+    let mut bindings: Bindings = BTreeMap::new();
+    for ((src, dst), (src_mac, dst_mac)) in edge_macs {
+        for (ip, mac, peer) in [(src, src_mac, dst), (dst, dst_mac, src)] {
+            if let Some(mac) = mac.as_deref().filter(|m| !is_group_mac(m)) {
+                bindings
+                    .entry(mac.to_string())
+                    .or_default()
+                    .entry(ip.clone())
+                    .or_default()
+                    .insert(subnets.subnet_of(peer));
+            }
+        }
+    }
+    bindings
+}
+
+/// Infer gateways from the MACs seen on each `(src_ip, dst_ip)` edge.
+pub(crate) fn infer(
+    edge_macs: &EdgeMacs,
+    nodes: &HashMap<String, TopoNode>,
+    subnets: &SubnetPlan,
+) -> Vec<Gateway> {
+    let bindings = bindings(edge_macs, subnets);
+    let mut gateway_macs: BTreeSet<&str> = bindings
+        .iter()
+        .filter(|(_, ips)| {
+            let spanned: BTreeSet<String> = ips.keys().map(|ip| subnets.subnet_of(ip)).collect();
+            ips.len() >= MIN_ROUTED_IPS && spanned.len() > 1
+        })
+        .map(|(mac, _)| mac.as_str())
+        .collect();
+
+    // The same address behind different MACs from disjoint sides
+    let mut macs_by_ip: BTreeMap<&str, Vec<(&str, &BTreeSet<String>)>> = BTreeMap::new();
+    for (mac, ips) in &bindings {
+        for (ip, peers) in ips {
+            macs_by_ip.entry(ip).or_default().push((mac, peers));
+        }
+    }
+    for macs in macs_by_ip.values().filter(|m| m.len() > 1) {
+        for &(mac, peers) in macs {
+            if macs
+                .iter()
+                .any(|&(other, others)| other != mac && peers.is_disjoint(others))
+            {
+                gateway_macs.insert(mac);
+            }
+        }
+    }
+
+    gateway_macs
+        .into_iter()
+        .map(|mac| {
+            let ips = &bindings[mac];
+            let local_subnets: BTreeSet<&String> = ips.values().flatten().collect();
+            let own_ip = ips
+                .keys()
+                .filter(|ip| local_subnets.contains(&subnets.subnet_of(ip)))
+                .max_by(|a, b| {
+                    let packets = |ip: &str| nodes.get(ip).map_or(0, |n| n.packet_count);
+                    packets(a).cmp(&packets(b)).then_with(|| b.cmp(a))
+                })
+                .cloned();
+            Gateway {
+                mac_address: mac.to_string(),
+                routed_ips: ips
+                    .keys()
+                    .filter(|ip| Some(*ip) != own_ip.as_ref())
+                    .cloned()
+                    .collect(),
+                ip_address: own_ip,
+                local_subnets: local_subnets.into_iter().cloned().collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TopologyBuilder;
+    use gm_parsers::IcsProtocol;
+
+    const ROUTER: &str = "00:1b:54:aa:00:01";
+
+    /// Two local hosts reaching three remote PLCs through one router, plus
+    /// the router answering a ping itself.
+    fn routed() -> TopologyBuilder {
+        let mut builder = TopologyBuilder::new();
+        let hosts = [
+            ("10.1.0.10", "00:50:56:00:00:10"),
+            ("10.1.0.11", "00:50:56:00:00:11"),
+        ];
+        for (host, mac) in hosts {
+            for plc in ["10.2.0.5", "10.2.0.6", "10.3.0.5"] {
+                builder.add_connection(host, plc, Some(mac), Some(ROUTER), IcsProtocol::Modbus, 90);
+                builder.add_connection(plc, host, Some(ROUTER), Some(mac), IcsProtocol::Modbus, 90);
+            }
+        }
+        builder.add_connection(
+            "10.1.0.10",
+            "10.1.0.1",
+            Some("00:50:56:00:00:10"),
+            Some(ROUTER),
+            IcsProtocol::Unknown,
+            64,
+        );
+        builder
+    }
+
+    #[test]
+    fn test_mac_fronting_many_ips_is_gateway() {
+        let gateways = routed().gateways();
+        assert_eq!(gateways.len(), 1);
+        let gateway = &gateways[0];
+        assert_eq!(gateway.mac_address, ROUTER);
+        assert_eq!(gateway.ip_address.as_deref(), Some("10.1.0.1"));
+        assert_eq!(gateway.routed_ips, ["10.2.0.5", "10.2.0.6", "10.3.0.5"]);
+        assert_eq!(gateway.local_subnets, ["10.1.0.0/24"]);
+        assert!(gateway.routes("10.2.0.6"));
+        assert!(!gateway.routes("10.1.0.1"));
+    }
+
+    #[test]
+    fn test_routed_edges_split_at_gateway() {
+        let graph = routed().build();
+        let node = |id: &str| graph.nodes.iter().find(|n| n.id == id).unwrap();
+        let has_edge =
+            |s: &str, t: &str| graph.edges.iter().any(|e| e.source == s && e.target == t);
+
+        assert_eq!(node("10.1.0.1").device_type, "gateway");
+        assert_eq!(node("10.1.0.1").mac_address.as_deref(), Some(ROUTER));
+        // The router's MAC is not the PLC's
+        assert_eq!(node("10.2.0.5").mac_address, None);
+
+        assert!(!has_edge("10.1.0.10", "10.2.0.5"));
+        assert!(has_edge("10.1.0.10", "10.1.0.1"));
+        assert!(has_edge("10.1.0.1", "10.2.0.5"));
+        assert!(has_edge("10.2.0.5", "10.1.0.1"));
+        assert!(has_edge("10.1.0.1", "10.1.0.11"));
+
+        // Both hosts' traffic to the PLC now shares the router's edge
+        let to_plc = graph
+            .edges
+            .iter()
+            .find(|e| e.source == "10.1.0.1" && e.target == "10.2.0.5")
+            .unwrap();
+        assert_eq!(to_plc.packet_count, 2);
+        // The ping plus the three split connections
+        let from_host = graph
+            .edges
+            .iter()
+            .find(|e| e.source == "10.1.0.10" && e.target == "10.1.0.1")
+            .unwrap();
+        assert_eq!(from_host.packet_count, 4);
+    }
+
+    #[test]
+    fn test_ip_behind_macs_from_disjoint_sides() {
+        let mut builder = TopologyBuilder::new();
+        // One historian reached from two cells, each through its own router
+        builder.add_connection(
+            "10.1.0.10",
+            "10.9.0.50",
+            Some("00:50:56:00:00:10"),
+            Some("00:1b:54:aa:00:01"),
+            IcsProtocol::OpcUa,
+            200,
+        );
+        builder.add_connection(
+            "10.2.0.10",
+            "10.9.0.50",
+            Some("00:50:56:00:00:20"),
+            Some("00:1b:54:bb:00:01"),
+            IcsProtocol::OpcUa,
+            200,
+        );
+        let gateways = builder.gateways();
+        assert_eq!(gateways.len(), 2);
+        assert!(gateways.iter().all(|g| g.ip_address.is_none()));
+        assert!(gateways.iter().all(|g| g.routes("10.9.0.50")));
+
+        // Unknown own address: the gateway node is its MAC
+        let graph = builder.build();
+        let router = graph
+            .nodes
+            .iter()
+            .find(|n| n.id == "00:1b:54:aa:00:01")
+            .unwrap();
+        assert_eq!(router.device_type, "gateway");
+        assert_eq!(router.subnet, "10.1.0.0/24");
+    }
+
+    #[test]
+    fn test_one_mac_per_host_is_not_gateway() {
+        let mut builder = TopologyBuilder::new();
+        for i in 1..=5 {
+            let plc = format!("10.1.0.{}", 100 + i);
+            let mac = format!("00:0e:8c:00:00:{:02x}", i);
+            builder.add_connection(
+                "10.1.0.10",
+                &plc,
+                Some("00:50:56:00:00:10"),
+                Some(&mac),
+                IcsProtocol::S7comm,
+                120,
+            );
+        }
+        assert!(builder.gateways().is_empty());
+        assert!(builder
+            .build()
+            .nodes
+            .iter()
+            .all(|n| n.device_type == "unknown"));
+    }
+}
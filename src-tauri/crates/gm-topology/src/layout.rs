@@ -0,0 +1,209 @@
+//! Layout hints for the Purdue-layered (ISA-95) diagram.
+//!
+//! Each node goes in the band of its Purdue level, top (L5) to bottom (L0);
+//! nodes without a level share the top band with L5, as in the drawn
+//! diagram. Within a band, nodes are ordered to keep edges short: starting
+//! from subnet-then-address order, a few downward and upward sweeps move
+//! each node to the mean position of its neighbours in the bands already
+//! placed (the barycenter heuristic). The result depends only on the graph,
+//! so the same capture always draws the same way.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::TopologyGraph;
+
+/// Highest Purdue level; the top band.
+const TOP_LEVEL: u8 = 5;
+
+/// Down-and-up sweeps of the ordering pass.
+const SWEEPS: usize = 4;
+
+/// Where a node goes in the layered diagram.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LayoutHint {
+    /// Purdue level, None if unassigned (drawn in the L5 band)
+    pub level: Option<u8>,
+    /// Position within the band, left to right from 0
+    pub order: usize,
+}
+
+impl TopologyGraph {
+    /// Set every node's `layout` from its Purdue level (looked up by the
+    /// node's addresses in `levels`) and order the nodes within each band.
+    pub fn assign_purdue_layout(&mut self, levels: &HashMap<String, u8>) {
+        for node in &mut self.nodes {
+            let level = std::iter::once(&node.ip_address)
+                .chain(&node.addresses)
+                .find_map(|ip| levels.get(ip).copied());
+            node.layout = LayoutHint { level, order: 0 };
+        }
+
+        let index: HashMap<&str, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), i))
+            .collect();
+        let mut neighbours: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); self.nodes.len()];
+        for edge in &self.edges {
+            if let (Some(&a), Some(&b)) = (
+                index.get(edge.source.as_str()),
+                index.get(edge.target.as_str()),
+            ) {
+                if a != b {
+                    neighbours[a].insert(b);
+                    neighbours[b].insert(a);
+                }
+            }
+        }
+
+        // Bands top to bottom, each in subnet-then-id order to start
+        let band_of = |level: Option<u8>| TOP_LEVEL - level.map_or(TOP_LEVEL, |l| l.min(TOP_LEVEL));
+        let mut bands: Vec<Vec<usize>> = vec![Vec::new(); TOP_LEVEL as usize + 1];
+        for (i, node) in self.nodes.iter().enumerate() {
+            bands[band_of(node.layout.level) as usize].push(i);
+        }
+        let nodes = &self.nodes;
+        for band in &mut bands {
+            band.sort_by(|&a, &b| {
+                (&nodes[a].subnet, &nodes[a].id).cmp(&(&nodes[b].subnet, &nodes[b].id))
+            });
+        }
+
+        // Relative position (0..1) of every node within its band
+        let mut position = vec![0.0; nodes.len()];
+        let mut band_index = vec![0; nodes.len()];
+        for (b, band) in bands.iter().enumerate() {
+            for (order, &i) in band.iter().enumerate() {
+                band_index[i] = b;
+                position[i] = (order as f64 + 0.5) / band.len() as f64;
+            }
+        }
+
+        for sweep in 0..SWEEPS * 2 {
+            let downward = sweep % 2 == 0;
+            let order: Vec<usize> = if downward {
+                (1..bands.len()).collect()
+            } else {
+                (0..bands.len() - 1).rev().collect()
+            };
+            for b in order {
+                let placed = |other: usize| {
+                    if downward {
+                        band_index[other] < b
+                    } else {
+                        band_index[other] > b
+                    }
+                };
+                let barycenter: HashMap<usize, f64> = bands[b]
+                    .iter()
+                    .map(|&i| {
+                        let (sum, count) = neighbours[i]
+                            .iter()
+                            .filter(|&&j| placed(j))
+                            .fold((0.0, 0usize), |(s, c), &j| (s + position[j], c + 1));
+                        // A node with nothing placed to pull it keeps its spot
+                        let at = if count == 0 {
+                            position[i]
+                        } else {
+                            sum / count as f64
+                        };
+                        (i, at)
+                    })
+                    .collect();
+                bands[b].sort_by(|x, y| {
+                    barycenter[x]
+                        .total_cmp(&barycenter[y])
+                        .then_with(|| nodes[*x].subnet.cmp(&nodes[*y].subnet))
+                        .then_with(|| nodes[*x].id.cmp(&nodes[*y].id))
+                });
+                let len = bands[b].len() as f64;
+                for (order, &i) in bands[b].iter().enumerate() {
+                    position[i] = (order as f64 + 0.5) / len;
+                }
+            }
+        }
+
+        for band in &bands {
+            for (order, &i) in band.iter().enumerate() {
+                self.nodes[i].layout.order = order;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TopologyBuilder;
+    use gm_parsers::IcsProtocol;
+
+    fn hint(graph: &TopologyGraph, id: &str) -> LayoutHint {
+        graph.nodes.iter().find(|n| n.id == id).unwrap().layout
+    }
+
+    /// Two HMIs, each polling its own pair of PLCs, named so that plain
+    /// address order would cross the lines.
+    fn plant() -> (TopologyGraph, HashMap<String, u8>) {
+        let mut builder = TopologyBuilder::new();
+        for (hmi, plcs) in [
+            ("10.0.2.1", ["10.0.1.2", "10.0.1.4"]),
+            ("10.0.2.2", ["10.0.1.1", "10.0.1.3"]),
+        ] {
+            for plc in plcs {
+                builder.add_connection(hmi, plc, None, None, IcsProtocol::Modbus, 80);
+            }
+        }
+        builder.add_connection("10.0.3.5", "10.0.2.1", None, None, IcsProtocol::OpcUa, 300);
+        let mut levels: HashMap<String, u8> = HashMap::new();
+        for ip in ["10.0.2.1", "10.0.2.2"] {
+            levels.insert(ip.to_string(), 2);
+        }
+        for ip in ["10.0.1.1", "10.0.1.2", "10.0.1.3", "10.0.1.4"] {
+            levels.insert(ip.to_string(), 1);
+        }
+        (builder.build(), levels)
+    }
+
+    #[test]
+    fn test_levels_and_unassigned_band() {
+        let (mut graph, levels) = plant();
+        graph.assign_purdue_layout(&levels);
+        assert_eq!(hint(&graph, "10.0.2.2").level, Some(2));
+        assert_eq!(hint(&graph, "10.0.1.3").level, Some(1));
+        assert_eq!(
+            hint(&graph, "10.0.3.5"),
+            LayoutHint {
+                level: None,
+                order: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_order_follows_neighbours() {
+        let (mut graph, levels) = plant();
+        graph.assign_purdue_layout(&levels);
+        let order = |id: &str| hint(&graph, id).order;
+
+        assert_eq!(order("10.0.2.1"), 0);
+        assert_eq!(order("10.0.2.2"), 1);
+        // Each HMI's PLCs sit under it
+        let mut left = [order("10.0.1.2"), order("10.0.1.4")];
+        left.sort_unstable();
+        assert_eq!(left, [0, 1]);
+        let mut right = [order("10.0.1.1"), order("10.0.1.3")];
+        right.sort_unstable();
+        assert_eq!(right, [2, 3]);
+
+        // Node order in the graph does not matter
+        let mut shuffled = graph.clone();
+        shuffled.nodes.reverse();
+        shuffled.assign_purdue_layout(&levels);
+        for node in &graph.nodes {
+            assert_eq!(hint(&shuffled, &node.id), node.layout);
+        }
+    }
+}
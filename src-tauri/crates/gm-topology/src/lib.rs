@@ -3,28 +3,130 @@
 //! Network topology graph engine.
 //! Builds a directed graph from parsed packets, where:
 //! - **Nodes** = unique IP addresses (network devices)
-//! - **Edges** = observed connections between devices
+//! - **Edges** = observed connections between devices, one per direction
+//!   per device pair, with a breakdown of the protocols seen on it
 //!
-//! Uses `petgraph` for the underlying graph data structure.
+//! Each node's subnet comes from a [`SubnetPlan`] — the plant's own
+//! prefixes when configured, /24 or /64 otherwise.
+//!
+//! Layer-2-only traffic (GOOSE) is tracked by MAC address. When the graph is
+//! built, a MAC node whose address belongs to a known IP node is folded into
+//! it, so an IED that speaks both MMS and GOOSE appears once.
+//!
+//! Path and reachability queries run on a `petgraph` index of the built
+//! graph (see [`TopologyIndex`]). Every snapshot also groups the nodes into
+//! communities of densely connected devices, exposed as `cluster_id`, and
+//! scores each node's centrality to pick out choke-point devices.
+//!
+//! Routers are inferred from MACs that front many IP addresses (see
+//! [`Gateway`]); traffic they forward is drawn through the gateway node
+//! rather than straight between the two hosts.
+//!
+//! Addresses that belong to one device (aliases sharing a MAC, or NICs the
+//! analyst confirmed) are merged into a single node; see
+//! [`TopologyGraph::merge_devices`].
+//!
+//! Nodes and edges carry first/last-seen times when the caller supplies
+//! them ([`TopologyBuilder::mark_seen`]), so a built graph can be cut down
+//! to the part active in a time range ([`TopologyGraph::between`]).
+//!
+//! VLAN membership is not visible in the packet graph; it is filled in from
+//! tags and switch data with [`TopologyGraph::assign_vlans`].
+//!
+//! Given each device's Purdue level, [`TopologyGraph::assign_purdue_layout`]
+//! places the nodes in the bands of the ISA-95 layered diagram and orders
+//! each band so the drawing comes out the same every time.
+//!
+//! Each edge also counts its traffic in time buckets (see
+//! [`TrafficHistogram`]), so bandwidth over time and bursts can be shown
+//! per connection ([`TopologyGraph::top_talkers`]).
+//!
+//! A built graph can be written out as GraphML, GEXF or Graphviz DOT for
+//! Gephi, yEd and documentation tooling ([`TopologyGraph::to_graphml`]).
+
+mod centrality;
+mod communities;
+mod export;
+mod gateways;
+mod layout;
+mod merge;
+mod paths;
+mod subnets;
+mod traffic;
+mod vlans;
+mod window;
+
+pub use centrality::{NodeCentrality, CHOKE_POINT_BETWEENNESS, CHOKE_POINT_FAN_OUT};
+pub use gateways::{Gateway, MIN_ROUTED_IPS};
+pub use layout::LayoutHint;
+pub use paths::{PathHop, Reachability, ReachableNode, TopologyIndex, TopologyPath};
+pub use subnets::SubnetPlan;
+pub use traffic::{
+    EdgeTraffic, TrafficBucket, TrafficHistogram, BURST_FACTOR, DEFAULT_TRAFFIC_BUCKET_SECS,
+    MAX_TRAFFIC_BUCKETS,
+};
+pub use vlans::{VlanGroup, VlanMap};
 
+/// Port pairs listed per edge. A client that opens a new connection for
+/// every poll uses a fresh source port each time, so pairs beyond this are
+/// only counted in the edge totals.
+pub const MAX_EDGE_PORT_PAIRS: usize = 64;
+
+use chrono::{DateTime, Utc};
 use gm_parsers::IcsProtocol;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::net::Ipv6Addr;
 
 /// A node in the network topology graph (represents a device).
 #[derive(Debug, Clone, Serialize)]
 pub struct TopoNode {
     pub id: String,
+    /// IP address, or the MAC address for Layer-2-only nodes
     pub ip_address: String,
     pub mac_address: Option<String>,
+    /// Every IP address of the device, sorted; more than one when several
+    /// interfaces were merged into this node, none for Layer-2-only nodes
+    pub addresses: Vec<String>,
     pub device_type: String,
     pub vendor: Option<String>,
     pub protocols: Vec<IcsProtocol>,
     pub subnet: String,
+    /// 802.1Q VLANs the device is in, sorted; empty when unknown (see
+    /// [`TopologyGraph::assign_vlans`])
+    pub vlans: Vec<u16>,
+    pub packet_count: u64,
+    /// Community of densely connected devices (a cell or area) this node
+    /// belongs to; None if it is not grouped with any other node
+    pub cluster_id: Option<u32>,
+    pub centrality: NodeCentrality,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    /// Band and position in the Purdue-layered diagram (see
+    /// [`TopologyGraph::assign_purdue_layout`])
+    pub layout: LayoutHint,
+}
+
+/// Traffic counters for one protocol on an edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EdgeProtocolStats {
     pub packet_count: u64,
+    pub byte_count: u64,
+}
+
+/// Traffic counters for one (source port, destination port) pair on an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EdgePortStats {
+    pub src_port: u16,
+    pub dst_port: u16,
+    pub packet_count: u64,
+    pub byte_count: u64,
 }
 
 /// An edge in the topology graph (represents a connection).
+///
+/// All protocols observed from `source` to `target` share one edge;
+/// `protocol` is the one carrying the most packets.
 #[derive(Debug, Clone, Serialize)]
 pub struct TopoEdge {
     pub id: String,
@@ -34,6 +136,120 @@ pub struct TopoEdge {
     pub packet_count: u64,
     pub byte_count: u64,
     pub bidirectional: bool,
+    /// Per-protocol packet/byte counts
+    pub protocol_breakdown: HashMap<IcsProtocol, EdgeProtocolStats>,
+    /// Per-port-pair packet/byte counts, sorted by destination then source
+    /// port; at most [`MAX_EDGE_PORT_PAIRS`]
+    pub ports: Vec<EdgePortStats>,
+    /// More port pairs were seen than are listed in `ports`
+    pub ports_truncated: bool,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    /// Traffic over time; left out of the serialized graph and served
+    /// through [`TopologyGraph::top_talkers`]
+    #[serde(skip)]
+    pub traffic: TrafficHistogram,
+}
+
+impl TopoEdge {
+    fn new(
+        id: String,
+        source: &str,
+        target: &str,
+        protocol: IcsProtocol,
+        bucket_secs: u32,
+    ) -> Self {
+        TopoEdge {
+            id,
+            source: source.to_string(),
+            target: target.to_string(),
+            protocol,
+            packet_count: 0,
+            byte_count: 0,
+            bidirectional: false,
+            protocol_breakdown: HashMap::new(),
+            ports: Vec::new(),
+            ports_truncated: false,
+            first_seen: None,
+            last_seen: None,
+            traffic: TrafficHistogram::new(bucket_secs),
+        }
+    }
+
+    /// Count traffic between `src_port` and `dst_port`.
+    fn record_ports(&mut self, src_port: u16, dst_port: u16, packets: u64, bytes: u64) {
+        match self
+            .ports
+            .binary_search_by_key(&(dst_port, src_port), |p| (p.dst_port, p.src_port))
+        {
+            Ok(i) => {
+                self.ports[i].packet_count += packets;
+                self.ports[i].byte_count += bytes;
+            }
+            Err(_) if self.ports.len() >= MAX_EDGE_PORT_PAIRS => self.ports_truncated = true,
+            Err(i) => self.ports.insert(
+                i,
+                EdgePortStats {
+                    src_port,
+                    dst_port,
+                    packet_count: packets,
+                    byte_count: bytes,
+                },
+            ),
+        }
+    }
+
+    /// Count traffic for `protocol`, keeping the dominant protocol current.
+    fn record(&mut self, protocol: IcsProtocol, packets: u64, bytes: u64) {
+        self.packet_count += packets;
+        self.byte_count += bytes;
+        let stats = self.protocol_breakdown.entry(protocol).or_default();
+        stats.packet_count += packets;
+        stats.byte_count += bytes;
+        let packets_for_protocol = stats.packet_count;
+
+        let dominant = self
+            .protocol_breakdown
+            .get(&self.protocol)
+            .map_or(0, |s| s.packet_count);
+        if packets_for_protocol > dominant {
+            self.protocol = protocol;
+        }
+    }
+
+    /// Merge another edge between the same endpoints into this one.
+    fn absorb(&mut self, other: &TopoEdge) {
+        for (protocol, stats) in &other.protocol_breakdown {
+            self.record(*protocol, stats.packet_count, stats.byte_count);
+        }
+        for p in &other.ports {
+            self.record_ports(p.src_port, p.dst_port, p.packet_count, p.byte_count);
+        }
+        self.ports_truncated |= other.ports_truncated;
+        self.traffic.absorb(&other.traffic);
+        self.bidirectional |= other.bidirectional;
+        widen_seen(
+            &mut self.first_seen,
+            &mut self.last_seen,
+            other.first_seen,
+            other.last_seen,
+        );
+    }
+}
+
+/// Widen a first/last-seen window to cover another.
+fn widen_seen(
+    first: &mut Option<DateTime<Utc>>,
+    last: &mut Option<DateTime<Utc>>,
+    other_first: Option<DateTime<Utc>>,
+    other_last: Option<DateTime<Utc>>,
+) {
+    if let Some(t) = other_first {
+        *first = Some(first.map_or(t, |f| f.min(t)));
+    }
+    if let Some(t) = other_last {
+        *last = Some(last.map_or(t, |l| l.max(t)));
+    }
 }
 
 /// Serializable topology graph for the frontend.
@@ -45,14 +261,23 @@ pub struct TopologyGraph {
 
 /// Builds the topology graph from connection data.
 ///
-/// This is a simplified builder for Phase 1-2. In later phases,
-/// it will integrate with petgraph for more sophisticated analysis
-/// (shortest paths, community detection, subnet clustering).
+/// Observations are accumulated in maps keyed by address pair; graph
+/// queries over the result go through [`TopologyIndex`].
 pub struct TopologyBuilder {
     /// Map IP address → node info
     nodes: HashMap<String, TopoNode>,
-    /// Map (src_ip, dst_ip, protocol) → edge info
-    edges: HashMap<(String, String, String), TopoEdge>,
+    /// Map (src_ip, dst_ip) → edge info
+    edges: HashMap<(String, String), TopoEdge>,
+    /// Map MAC address → Layer-2-only node info
+    l2_nodes: HashMap<String, TopoNode>,
+    /// Map (src_mac, dst_mac) → Layer-2 edge info
+    l2_edges: HashMap<(String, String), TopoEdge>,
+    /// Map (src_ip, dst_ip) → first (src_mac, dst_mac) seen, lowercase
+    edge_macs: gateways::EdgeMacs,
+    /// Prefixes nodes are grouped into
+    subnets: SubnetPlan,
+    /// Width of new edges' traffic buckets
+    traffic_bucket_secs: u32,
     edge_counter: u64,
 }
 
@@ -61,10 +286,31 @@ impl TopologyBuilder {
         TopologyBuilder {
             nodes: HashMap::new(),
             edges: HashMap::new(),
+            l2_nodes: HashMap::new(),
+            l2_edges: HashMap::new(),
+            edge_macs: HashMap::new(),
+            subnets: SubnetPlan::new(),
+            traffic_bucket_secs: DEFAULT_TRAFFIC_BUCKET_SECS,
             edge_counter: 0,
         }
     }
 
+    /// A builder that groups nodes by the given addressing plan rather
+    /// than by /24 and /64.
+    pub fn with_subnets(subnets: SubnetPlan) -> Self {
+        TopologyBuilder {
+            subnets,
+            ..Self::new()
+        }
+    }
+
+    /// Count each edge's traffic in `secs`-wide time buckets rather than
+    /// one-minute ones.
+    pub fn with_traffic_buckets(mut self, secs: u32) -> Self {
+        self.traffic_bucket_secs = secs.max(1);
+        self
+    }
+
     /// Add a connection observation to the topology.
     pub fn add_connection(
         &mut self,
@@ -80,40 +326,140 @@ impl TopologyBuilder {
         self.ensure_node(dst_ip, dst_mac, &protocol);
 
         // Add or update edge
-        let proto_str = format!("{:?}", protocol);
-        let key = (src_ip.to_string(), dst_ip.to_string(), proto_str.clone());
+        let key = (src_ip.to_string(), dst_ip.to_string());
+
+        let macs = self.edge_macs.entry(key.clone()).or_default();
+        if macs.0.is_none() {
+            macs.0 = src_mac.map(str::to_ascii_lowercase);
+        }
+        if macs.1.is_none() {
+            macs.1 = dst_mac.map(str::to_ascii_lowercase);
+        }
 
         // Check for bidirectional traffic before mutably borrowing
-        let reverse_key = (dst_ip.to_string(), src_ip.to_string(), proto_str.clone());
+        let reverse_key = (dst_ip.to_string(), src_ip.to_string());
         let has_reverse = self.edges.contains_key(&reverse_key);
 
         let edge = self.edges.entry(key).or_insert_with(|| {
             self.edge_counter += 1;
-            TopoEdge {
-                id: format!("e{}", self.edge_counter),
-                source: src_ip.to_string(),
-                target: dst_ip.to_string(),
+            TopoEdge::new(
+                format!("e{}", self.edge_counter),
+                src_ip,
+                dst_ip,
                 protocol,
-                packet_count: 0,
-                byte_count: 0,
-                bidirectional: false,
-            }
+                self.traffic_bucket_secs,
+            )
         });
 
-        edge.packet_count += 1;
-        edge.byte_count += bytes;
+        edge.record(protocol, 1, bytes);
 
         if has_reverse {
             edge.bidirectional = true;
         }
     }
 
+    /// Add a Layer-2-only observation (no IP header), keyed by MAC address.
+    ///
+    /// Multicast destinations (I/G bit set) become `"multicast_group"` nodes.
+    pub fn add_l2_connection(
+        &mut self,
+        src_mac: &str,
+        dst_mac: &str,
+        protocol: IcsProtocol,
+        bytes: u64,
+    ) {
+        self.ensure_l2_node(src_mac, &protocol);
+        self.ensure_l2_node(dst_mac, &protocol);
+
+        let key = (src_mac.to_string(), dst_mac.to_string());
+        let edge = self.l2_edges.entry(key).or_insert_with(|| {
+            self.edge_counter += 1;
+            TopoEdge::new(
+                format!("e{}", self.edge_counter),
+                src_mac,
+                dst_mac,
+                protocol,
+                self.traffic_bucket_secs,
+            )
+        });
+        edge.record(protocol, 1, bytes);
+    }
+
+    /// Record the transport ports of a connection already given to
+    /// `add_connection`, counted as one more observation of that port pair.
+    /// Portless traffic (both ports 0) is not listed.
+    pub fn add_ports(
+        &mut self,
+        src_ip: &str,
+        dst_ip: &str,
+        src_port: u16,
+        dst_port: u16,
+        bytes: u64,
+    ) {
+        if src_port == 0 && dst_port == 0 {
+            return;
+        }
+        let key = (src_ip.to_string(), dst_ip.to_string());
+        if let Some(edge) = self.edges.get_mut(&key) {
+            edge.record_ports(src_port, dst_port, 1, bytes);
+        }
+    }
+
+    /// Record that the connection from `src` to `dst` was active at `seen`,
+    /// widening the first/last-seen times of the edge and both nodes.
+    ///
+    /// `src` and `dst` are the addresses given to `add_connection`, or the
+    /// MACs given to `add_l2_connection`.
+    pub fn mark_seen(&mut self, src: &str, dst: &str, seen: DateTime<Utc>) {
+        let key = (src.to_string(), dst.to_string());
+        let (nodes, edge) = match self.edges.get_mut(&key) {
+            Some(edge) => (&mut self.nodes, Some(edge)),
+            None => (&mut self.l2_nodes, self.l2_edges.get_mut(&key)),
+        };
+        if let Some(edge) = edge {
+            widen_seen(
+                &mut edge.first_seen,
+                &mut edge.last_seen,
+                Some(seen),
+                Some(seen),
+            );
+        }
+        for addr in [src, dst] {
+            if let Some(node) = nodes.get_mut(addr) {
+                widen_seen(
+                    &mut node.first_seen,
+                    &mut node.last_seen,
+                    Some(seen),
+                    Some(seen),
+                );
+            }
+        }
+    }
+
+    /// Count `bytes` of the connection from `src` to `dst` in the traffic
+    /// bucket covering `at`.
+    ///
+    /// `src` and `dst` are the addresses given to `add_connection`, or the
+    /// MACs given to `add_l2_connection`.
+    pub fn add_traffic(&mut self, src: &str, dst: &str, at: DateTime<Utc>, bytes: u64) {
+        let key = (src.to_string(), dst.to_string());
+        let edge = match self.edges.get_mut(&key) {
+            Some(edge) => Some(edge),
+            None => self.l2_edges.get_mut(&key),
+        };
+        if let Some(edge) = edge {
+            edge.traffic.record(at, 1, bytes);
+        }
+    }
+
+    /// Routers inferred from the MACs seen with each address pair.
+    pub fn gateways(&self) -> Vec<Gateway> {
+        gateways::infer(&self.edge_macs, &self.nodes, &self.subnets)
+    }
+
     /// Build the final topology graph, consuming the builder.
     pub fn build(self) -> TopologyGraph {
-        TopologyGraph {
-            nodes: self.nodes.into_values().collect(),
-            edges: self.edges.into_values().collect(),
-        }
+        self.snapshot()
     }
 
     /// Create a snapshot of the current topology without consuming the builder.
@@ -121,9 +467,228 @@ impl TopologyBuilder {
     /// Used by live capture to periodically export the topology while
     /// continuing to accumulate data.
     pub fn snapshot(&self) -> TopologyGraph {
-        TopologyGraph {
-            nodes: self.nodes.values().cloned().collect(),
-            edges: self.edges.values().cloned().collect(),
+        let mut nodes = self.nodes.clone();
+        let mut edges = self.edges.clone();
+
+        // A routed host was first seen with its router's MAC, which is not
+        // its own; the router's own address gets the MAC instead
+        let gateways = self.gateways();
+        for gateway in &gateways {
+            for ip in &gateway.routed_ips {
+                if let Some(node) = nodes.get_mut(ip) {
+                    if node
+                        .mac_address
+                        .as_deref()
+                        .is_some_and(|m| m.eq_ignore_ascii_case(&gateway.mac_address))
+                    {
+                        node.mac_address = None;
+                    }
+                }
+            }
+            if let Some(node) = gateway.ip_address.as_ref().and_then(|ip| nodes.get_mut(ip)) {
+                node.device_type = "gateway".to_string();
+                node.mac_address = Some(gateway.mac_address.clone());
+            }
+        }
+
+        // MAC → IP node id, for folding Layer-2 nodes into known devices
+        let ip_by_mac: HashMap<String, String> = nodes
+            .values()
+            .filter_map(|n| {
+                n.mac_address
+                    .as_ref()
+                    .map(|m| (m.to_ascii_lowercase(), n.id.clone()))
+            })
+            .collect();
+        let resolve = |mac: &str| {
+            ip_by_mac
+                .get(&mac.to_ascii_lowercase())
+                .cloned()
+                .unwrap_or_else(|| mac.to_string())
+        };
+
+        for (mac, l2) in &self.l2_nodes {
+            match ip_by_mac.get(&mac.to_ascii_lowercase()) {
+                Some(ip) => {
+                    if let Some(node) = nodes.get_mut(ip) {
+                        node.packet_count += l2.packet_count;
+                        widen_seen(
+                            &mut node.first_seen,
+                            &mut node.last_seen,
+                            l2.first_seen,
+                            l2.last_seen,
+                        );
+                        for p in &l2.protocols {
+                            if !node.protocols.contains(p) {
+                                node.protocols.push(*p);
+                            }
+                        }
+                    }
+                }
+                None => {
+                    nodes.insert(mac.clone(), l2.clone());
+                }
+            }
+        }
+
+        // Layer-2 traffic between folded devices joins their IP edge
+        for edge in self.l2_edges.values() {
+            let key = (resolve(&edge.source), resolve(&edge.target));
+            match edges.get_mut(&key) {
+                Some(existing) => existing.absorb(edge),
+                None => {
+                    let mut edge = edge.clone();
+                    edge.source = key.0.clone();
+                    edge.target = key.1.clone();
+                    edges.insert(key, edge);
+                }
+            }
+        }
+
+        self.route_through_gateways(&gateways, &mut nodes, &mut edges);
+
+        let mut graph = TopologyGraph {
+            nodes: nodes.into_values().collect(),
+            edges: edges.into_values().collect(),
+        };
+        let bindings = gateways::bindings(&self.edge_macs, &self.subnets);
+        let aliases = merge::shared_mac_groups(&bindings, &gateways, &self.subnets);
+        merge::merge_nodes(&mut graph, &aliases);
+        centrality::assign_centrality(&mut graph);
+        communities::assign_clusters(&mut graph);
+        graph
+    }
+
+    /// Replace each routed edge with hops through its gateway(s), merging
+    /// them into existing edges between the same nodes. Gateways whose own
+    /// address is unknown get a node keyed by MAC.
+    fn route_through_gateways(
+        &self,
+        gateways: &[Gateway],
+        nodes: &mut HashMap<String, TopoNode>,
+        edges: &mut HashMap<(String, String), TopoEdge>,
+    ) {
+        let by_mac: HashMap<&str, &Gateway> = gateways
+            .iter()
+            .map(|g| (g.mac_address.as_str(), g))
+            .collect();
+        let via = |ip: &str, mac: &Option<String>| {
+            mac.as_deref()
+                .and_then(|m| by_mac.get(m).copied())
+                .filter(|g| g.routes(ip))
+        };
+
+        for gateway in gateways.iter().filter(|g| g.ip_address.is_none()) {
+            let mac = &gateway.mac_address;
+            let node = nodes.entry(mac.clone()).or_insert_with(|| TopoNode {
+                id: mac.clone(),
+                ip_address: mac.clone(),
+                mac_address: Some(mac.clone()),
+                addresses: Vec::new(),
+                device_type: String::new(),
+                vendor: None,
+                protocols: Vec::new(),
+                subnet: gateway
+                    .local_subnets
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "layer2".to_string()),
+                packet_count: 0,
+                cluster_id: None,
+                vlans: Vec::new(),
+                layout: LayoutHint::default(),
+                centrality: NodeCentrality::default(),
+                first_seen: None,
+                last_seen: None,
+            });
+            node.device_type = "gateway".to_string();
+        }
+
+        let routed: Vec<(String, String)> = edges
+            .keys()
+            .filter(|key| {
+                self.edge_macs.get(*key).is_some_and(|(src_mac, dst_mac)| {
+                    via(&key.0, src_mac).is_some() || via(&key.1, dst_mac).is_some()
+                })
+            })
+            .cloned()
+            .collect();
+        let mut forwarded: HashMap<String, u64> = HashMap::new();
+        for key in routed {
+            let (Some(edge), Some((src_mac, dst_mac))) =
+                (edges.remove(&key), self.edge_macs.get(&key))
+            else {
+                continue;
+            };
+            let mut hops = vec![key.0.as_str()];
+            hops.extend(via(&key.0, src_mac).map(Gateway::node_id));
+            hops.extend(via(&key.1, dst_mac).map(Gateway::node_id));
+            hops.push(key.1.as_str());
+            hops.dedup();
+
+            for hop in &hops[1..hops.len() - 1] {
+                *forwarded.entry(hop.to_string()).or_default() += edge.packet_count;
+                if let Some(node) = nodes.get_mut(*hop) {
+                    widen_seen(
+                        &mut node.first_seen,
+                        &mut node.last_seen,
+                        edge.first_seen,
+                        edge.last_seen,
+                    );
+                }
+            }
+            for (i, pair) in hops.windows(2).enumerate() {
+                let hop_key = (pair[0].to_string(), pair[1].to_string());
+                match edges.get_mut(&hop_key) {
+                    Some(existing) => existing.absorb(&edge),
+                    None => {
+                        let mut hop = edge.clone();
+                        hop.id = format!("{}.{}", edge.id, i + 1);
+                        hop.source = hop_key.0.clone();
+                        hop.target = hop_key.1.clone();
+                        edges.insert(hop_key, hop);
+                    }
+                }
+            }
+        }
+
+        // A MAC-only gateway has no traffic of its own to count
+        for gateway in gateways.iter().filter(|g| g.ip_address.is_none()) {
+            if let Some(node) = nodes.get_mut(&gateway.mac_address) {
+                node.packet_count += forwarded.get(gateway.node_id()).copied().unwrap_or(0);
+            }
+        }
+    }
+
+    fn ensure_l2_node(&mut self, mac: &str, protocol: &IcsProtocol) {
+        let node = self
+            .l2_nodes
+            .entry(mac.to_string())
+            .or_insert_with(|| TopoNode {
+                id: mac.to_string(),
+                ip_address: mac.to_string(),
+                mac_address: Some(mac.to_string()),
+                addresses: Vec::new(),
+                device_type: if is_group_mac(mac) {
+                    "multicast_group".to_string()
+                } else {
+                    "unknown".to_string()
+                },
+                vendor: None,
+                protocols: Vec::new(),
+                subnet: "layer2".to_string(),
+                packet_count: 0,
+                cluster_id: None,
+                vlans: Vec::new(),
+                layout: LayoutHint::default(),
+                centrality: NodeCentrality::default(),
+                first_seen: None,
+                last_seen: None,
+            });
+
+        node.packet_count += 1;
+        if !node.protocols.contains(protocol) {
+            node.protocols.push(*protocol);
         }
     }
 
@@ -135,11 +700,18 @@ impl TopologyBuilder {
                 id: ip.to_string(),
                 ip_address: ip.to_string(),
                 mac_address: mac.map(String::from),
+                addresses: vec![ip.to_string()],
                 device_type: "unknown".to_string(),
                 vendor: None,
                 protocols: Vec::new(),
-                subnet: extract_subnet(ip),
+                subnet: self.subnets.subnet_of(ip),
                 packet_count: 0,
+                cluster_id: None,
+                vlans: Vec::new(),
+                layout: LayoutHint::default(),
+                centrality: NodeCentrality::default(),
+                first_seen: None,
+                last_seen: None,
             });
 
         node.packet_count += 1;
@@ -156,13 +728,26 @@ impl TopologyBuilder {
     }
 }
 
-/// Extract /24 subnet from an IPv4 address.
+/// Whether a MAC address has the individual/group bit set (multicast/broadcast).
+fn is_group_mac(mac: &str) -> bool {
+    mac.get(0..2)
+        .and_then(|b| u8::from_str_radix(b, 16).ok())
+        .is_some_and(|b| b & 0x01 != 0)
+}
+
+/// Extract the subnet an address belongs to: /24 for IPv4, /64 for IPv6
+/// (the standard interface-identifier boundary).
 fn extract_subnet(ip: &str) -> String {
+    if let Ok(v6) = ip.parse::<Ipv6Addr>() {
+        let mut segments = v6.segments();
+        segments[4..].fill(0);
+        return format!("{}/64", Ipv6Addr::from(segments));
+    }
     let parts: Vec<&str> = ip.split('.').collect();
     if parts.len() == 4 {
         format!("{}.{}.{}.0/24", parts[0], parts[1], parts[2])
     } else {
-        // IPv6 or malformed — just return as-is for now
+        // Malformed — just return as-is
         ip.to_string()
     }
 }
@@ -211,9 +796,154 @@ mod tests {
         assert_eq!(graph.edges.len(), 2); // one per direction
     }
 
+    #[test]
+    fn test_l2_nodes_fold_into_ip_nodes_by_mac() {
+        let mut builder = TopologyBuilder::new();
+
+        // Publisher seen via GOOSE before its MMS traffic
+        builder.add_l2_connection(
+            "00:0c:cd:00:00:10",
+            "01:0c:cd:01:00:01",
+            IcsProtocol::Goose,
+            120,
+        );
+        builder.add_l2_connection(
+            "00:0c:cd:00:00:20",
+            "01:0c:cd:01:00:01",
+            IcsProtocol::Goose,
+            120,
+        );
+        builder.add_connection(
+            "10.0.0.5",
+            "10.0.0.10",
+            Some("00:1b:1b:00:00:01"),
+            Some("00:0c:cd:00:00:10"),
+            IcsProtocol::Iec61850Mms,
+            200,
+        );
+
+        let graph = builder.snapshot();
+        // 2 IP nodes + unresolved publisher MAC + multicast group
+        assert_eq!(graph.nodes.len(), 4);
+        let ied = graph.nodes.iter().find(|n| n.id == "10.0.0.10").unwrap();
+        assert!(ied.protocols.contains(&IcsProtocol::Goose));
+        assert!(ied.protocols.contains(&IcsProtocol::Iec61850Mms));
+        let group = graph
+            .nodes
+            .iter()
+            .find(|n| n.id == "01:0c:cd:01:00:01")
+            .unwrap();
+        assert_eq!(group.device_type, "multicast_group");
+        assert!(graph
+            .nodes
+            .iter()
+            .any(|n| n.id == "00:0c:cd:00:00:20" && n.device_type == "unknown"));
+
+        let goose_edges: Vec<&TopoEdge> = graph
+            .edges
+            .iter()
+            .filter(|e| e.protocol == IcsProtocol::Goose)
+            .collect();
+        assert_eq!(goose_edges.len(), 2);
+        assert!(goose_edges
+            .iter()
+            .any(|e| e.source == "10.0.0.10" && e.target == "01:0c:cd:01:00:01"));
+    }
+
+    #[test]
+    fn test_protocols_between_pair_share_one_edge() {
+        let mut builder = TopologyBuilder::new();
+
+        builder.add_connection("10.0.0.5", "10.0.0.20", None, None, IcsProtocol::Modbus, 80);
+        for _ in 0..3 {
+            builder.add_connection(
+                "10.0.0.5",
+                "10.0.0.20",
+                None,
+                None,
+                IcsProtocol::S7comm,
+                200,
+            );
+        }
+        builder.add_connection(
+            "10.0.0.20",
+            "10.0.0.5",
+            None,
+            None,
+            IcsProtocol::S7comm,
+            120,
+        );
+
+        let graph = builder.build();
+        assert_eq!(graph.edges.len(), 2);
+
+        let edge = graph.edges.iter().find(|e| e.source == "10.0.0.5").unwrap();
+        assert_eq!(edge.protocol, IcsProtocol::S7comm);
+        assert_eq!(edge.packet_count, 4);
+        assert_eq!(edge.byte_count, 680);
+        assert_eq!(edge.protocol_breakdown.len(), 2);
+        assert_eq!(
+            edge.protocol_breakdown[&IcsProtocol::Modbus],
+            EdgeProtocolStats {
+                packet_count: 1,
+                byte_count: 80
+            }
+        );
+        assert_eq!(
+            edge.protocol_breakdown[&IcsProtocol::S7comm].packet_count,
+            3
+        );
+
+        let reverse = graph
+            .edges
+            .iter()
+            .find(|e| e.source == "10.0.0.20")
+            .unwrap();
+        assert!(reverse.bidirectional);
+        assert_eq!(reverse.protocol_breakdown.len(), 1);
+    }
+
+    #[test]
+    fn test_port_pairs_under_edge() {
+        let mut builder = TopologyBuilder::new();
+        let mut poll = |src_port: u16, dst_port: u16, protocol, bytes| {
+            builder.add_connection("10.0.0.5", "10.0.0.100", None, None, protocol, bytes);
+            builder.add_ports("10.0.0.5", "10.0.0.100", src_port, dst_port, bytes);
+        };
+        poll(49152, 502, IcsProtocol::Modbus, 80);
+        poll(49152, 502, IcsProtocol::Modbus, 80);
+        poll(49153, 80, IcsProtocol::Http, 400);
+        poll(0, 0, IcsProtocol::Icmp, 64);
+        // A fresh source port for every poll
+        for src_port in 50000..50100 {
+            poll(src_port, 502, IcsProtocol::Modbus, 80);
+        }
+        let graph = builder.build();
+        let edge = &graph.edges[0];
+
+        assert_eq!(edge.ports.len(), MAX_EDGE_PORT_PAIRS);
+        assert!(edge.ports_truncated);
+        assert_eq!(
+            edge.ports[0],
+            EdgePortStats {
+                src_port: 49153,
+                dst_port: 80,
+                packet_count: 1,
+                byte_count: 400
+            }
+        );
+        assert_eq!(
+            (edge.ports[1].src_port, edge.ports[1].packet_count),
+            (49152, 2)
+        );
+        assert!(edge.ports.iter().all(|p| p.dst_port != 0));
+    }
+
     #[test]
     fn test_subnet_extraction() {
         assert_eq!(extract_subnet("192.168.1.100"), "192.168.1.0/24");
         assert_eq!(extract_subnet("10.0.0.1"), "10.0.0.0/24");
+        assert_eq!(extract_subnet("2001:db8:10:20::5:1"), "2001:db8:10:20::/64");
+        assert_eq!(extract_subnet("fe80::20c:29ff:fe12:3456"), "fe80::/64");
     }
 }
@@ -0,0 +1,272 @@
+//! Merging the addresses of one device into a single node.
+//!
+//! A device with several addresses — aliases on one NIC, or a server or
+//! firewall with a leg in more than one subnet — otherwise appears once per
+//! address. Addresses bound to the same MAC are merged when the graph is
+//! built, as long as each binding was seen from the address's own subnet (a
+//! router's MAC also fronts the remote hosts it forwards for, which are not
+//! the router). Addresses on different NICs can only be told apart by the
+//! analyst, who confirms them through [`TopologyGraph::merge_devices`].
+//!
+//! The merged node keeps the id of its busiest address, so lookups by IP
+//! still find it, and lists every address in `addresses`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::gateways::Bindings;
+use crate::{
+    centrality, communities, widen_seen, Gateway, SubnetPlan, TopoEdge, TopoNode, TopologyGraph,
+};
+
+impl TopologyGraph {
+    /// Collapse each group of node ids (the addresses of one device) into a
+    /// single node and move their edges onto it. Ids not in the graph are
+    /// ignored and overlapping groups are joined. Centrality and clusters
+    /// are recomputed if anything merged.
+    pub fn merge_devices(&mut self, groups: &[Vec<String>]) {
+        if merge_nodes(self, groups) {
+            centrality::assign_centrality(self);
+            communities::assign_clusters(self);
+        }
+    }
+}
+
+/// Addresses sharing a unicast MAC, each seen from its own subnet. Gateway
+/// MACs are left out.
+pub(crate) fn shared_mac_groups(
+    bindings: &Bindings,
+    gateways: &[Gateway],
+    subnets: &SubnetPlan,
+) -> Vec<Vec<String>> {
+    let gateway_macs: HashSet<&str> = gateways.iter().map(|g| g.mac_address.as_str()).collect();
+    bindings
+        .iter()
+        .filter(|(mac, _)| !gateway_macs.contains(mac.as_str()))
+        .map(|(_, ips)| {
+            ips.iter()
+                .filter(|(ip, peers)| peers.contains(&subnets.subnet_of(ip)))
+                .map(|(ip, _)| ip.clone())
+                .collect::<Vec<_>>()
+        })
+        .filter(|ips| ips.len() > 1)
+        .collect()
+}
+
+/// Merge each group of node ids into its busiest member. Returns whether
+/// any node was merged.
+pub(crate) fn merge_nodes(graph: &mut TopologyGraph, groups: &[Vec<String>]) -> bool {
+    let packets: HashMap<&str, u64> = graph
+        .nodes
+        .iter()
+        .map(|n| (n.id.as_str(), n.packet_count))
+        .collect();
+
+    // Join overlapping groups, dropping ids not in the graph
+    let mut devices: Vec<BTreeSet<&str>> = Vec::new();
+    for group in groups {
+        let mut device: BTreeSet<&str> = group
+            .iter()
+            .map(String::as_str)
+            .filter(|id| packets.contains_key(id))
+            .collect();
+        devices.retain(|other| {
+            if other.is_disjoint(&device) {
+                return true;
+            }
+            device.extend(other);
+            false
+        });
+        devices.push(device);
+    }
+
+    // Busiest address first, ties to the lowest
+    let mut primary_of: HashMap<String, String> = HashMap::new();
+    for device in devices.iter().filter(|d| d.len() > 1) {
+        let Some(&primary) = device
+            .iter()
+            .max_by(|a, b| packets[*a].cmp(&packets[*b]).then_with(|| b.cmp(a)))
+        else {
+            continue;
+        };
+        for &id in device.iter().filter(|&&id| id != primary) {
+            primary_of.insert(id.to_string(), primary.to_string());
+        }
+    }
+    if primary_of.is_empty() {
+        return false;
+    }
+
+    let (mut secondary, mut nodes): (Vec<TopoNode>, Vec<TopoNode>) =
+        std::mem::take(&mut graph.nodes)
+            .into_iter()
+            .partition(|n| primary_of.contains_key(&n.id));
+    let index: HashMap<String, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.clone(), i))
+        .collect();
+    // Sorted so protocol order does not depend on node order
+    secondary.sort_by(|a, b| a.id.cmp(&b.id));
+    for node in secondary {
+        let primary = &mut nodes[index[&primary_of[&node.id]]];
+        absorb_node(primary, node);
+    }
+    graph.nodes = nodes;
+
+    let resolve = |id: &String| primary_of.get(id).unwrap_or(id).clone();
+    let mut edges: Vec<TopoEdge> = Vec::new();
+    let mut position: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for mut edge in std::mem::take(&mut graph.edges) {
+        let key = (resolve(&edge.source), resolve(&edge.target));
+        // Traffic between two addresses of the merged device
+        if key.0 == key.1 && edge.source != edge.target {
+            continue;
+        }
+        match position.get(&key) {
+            Some(&i) => edges[i].absorb(&edge),
+            None => {
+                edge.source = key.0.clone();
+                edge.target = key.1.clone();
+                position.insert(key, edges.len());
+                edges.push(edge);
+            }
+        }
+    }
+    for edge in &mut edges {
+        let reverse = (edge.target.clone(), edge.source.clone());
+        edge.bidirectional |= position.contains_key(&reverse);
+    }
+    graph.edges = edges;
+    true
+}
+
+/// Fold `other` into `node`, keeping `node`'s identity and filling in what
+/// it lacks.
+fn absorb_node(node: &mut TopoNode, other: TopoNode) {
+    node.addresses.extend(other.addresses);
+    node.addresses.sort();
+    node.addresses.dedup();
+    node.vlans.extend(other.vlans);
+    node.vlans.sort_unstable();
+    node.vlans.dedup();
+    if node.mac_address.is_none() {
+        node.mac_address = other.mac_address;
+    }
+    if node.device_type == "unknown" {
+        node.device_type = other.device_type;
+    }
+    if node.vendor.is_none() {
+        node.vendor = other.vendor;
+    }
+    for protocol in other.protocols {
+        if !node.protocols.contains(&protocol) {
+            node.protocols.push(protocol);
+        }
+    }
+    node.packet_count += other.packet_count;
+    widen_seen(
+        &mut node.first_seen,
+        &mut node.last_seen,
+        other.first_seen,
+        other.last_seen,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TopologyBuilder, TopologyGraph};
+    use gm_parsers::IcsProtocol;
+
+    fn node<'a>(graph: &'a TopologyGraph, id: &str) -> Option<&'a crate::TopoNode> {
+        graph.nodes.iter().find(|n| n.id == id)
+    }
+
+    #[test]
+    fn test_aliases_on_one_mac_merge() {
+        let mut builder = TopologyBuilder::new();
+        let hmi = Some("00:50:56:00:00:05");
+        let plc = Some("00:0e:8c:00:00:10");
+        // The PLC answers on its primary address and on an alias
+        for _ in 0..3 {
+            builder.add_connection("10.0.0.5", "10.0.0.10", hmi, plc, IcsProtocol::S7comm, 120);
+        }
+        builder.add_connection("10.0.0.5", "10.0.0.11", hmi, plc, IcsProtocol::Http, 300);
+        builder.add_connection("10.0.0.11", "10.0.0.5", plc, hmi, IcsProtocol::Http, 900);
+        let graph = builder.build();
+
+        assert_eq!(graph.nodes.len(), 2);
+        let merged = node(&graph, "10.0.0.10").unwrap();
+        assert_eq!(merged.addresses, ["10.0.0.10", "10.0.0.11"]);
+        assert_eq!(merged.packet_count, 5);
+        assert!(merged.protocols.contains(&IcsProtocol::Http));
+        assert!(node(&graph, "10.0.0.11").is_none());
+
+        // Both directions now run between the HMI and the merged PLC
+        let to_plc = graph
+            .edges
+            .iter()
+            .find(|e| e.source == "10.0.0.5" && e.target == "10.0.0.10")
+            .unwrap();
+        assert_eq!(to_plc.packet_count, 4);
+        assert!(to_plc.bidirectional);
+        assert_eq!(graph.edges.len(), 2);
+    }
+
+    #[test]
+    fn test_router_mac_does_not_merge_remote_host() {
+        let mut builder = TopologyBuilder::new();
+        let host = Some("00:50:56:00:00:10");
+        let router = Some("00:1b:54:aa:00:01");
+        // Too few routed addresses to infer the gateway, but the remote PLC
+        // is only ever reached from another subnet
+        builder.add_connection("10.1.0.10", "10.1.0.1", host, router, IcsProtocol::Icmp, 64);
+        builder.add_connection(
+            "10.1.0.10",
+            "10.2.0.5",
+            host,
+            router,
+            IcsProtocol::Modbus,
+            90,
+        );
+        let graph = builder.build();
+        assert_eq!(graph.nodes.len(), 3);
+        assert!(graph.nodes.iter().all(|n| n.addresses.len() == 1));
+    }
+
+    #[test]
+    fn test_confirmed_interfaces_merge() {
+        let mut builder = TopologyBuilder::new();
+        // A historian with one NIC in the control network and one in the DMZ
+        builder.add_connection(
+            "10.0.0.20",
+            "10.0.0.100",
+            Some("00:50:56:00:00:20"),
+            Some("00:0e:8c:00:01:00"),
+            IcsProtocol::OpcUa,
+            400,
+        );
+        builder.add_connection(
+            "172.16.0.20",
+            "172.16.0.50",
+            Some("00:50:56:00:00:21"),
+            Some("00:50:56:00:00:50"),
+            IcsProtocol::Https,
+            1400,
+        );
+        let mut graph = builder.build();
+        assert_eq!(graph.nodes.len(), 4);
+
+        graph.merge_devices(&[
+            vec!["10.0.0.20".to_string(), "172.16.0.20".to_string()],
+            vec!["10.9.9.9".to_string()],
+        ]);
+        assert_eq!(graph.nodes.len(), 3);
+        let historian = node(&graph, "10.0.0.20").unwrap();
+        assert_eq!(historian.addresses, ["10.0.0.20", "172.16.0.20"]);
+        assert!(graph
+            .edges
+            .iter()
+            .any(|e| e.source == "10.0.0.20" && e.target == "172.16.0.50"));
+        assert_eq!(historian.centrality.degree, 2);
+    }
+}
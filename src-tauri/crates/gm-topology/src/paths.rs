@@ -0,0 +1,262 @@
+//! Path and reachability queries over a [`TopologyGraph`].
+//!
+//! Edges are observed traffic, so a path here is a chain of devices each of
+//! which has been seen talking to the next — the route an attacker holding
+//! the first device could pivot along using connections that already exist
+//! (corporate laptop → jump host → engineering workstation → PLC). Edges are
+//! followed in the direction the traffic was sent; a bidirectional
+//! conversation has an edge each way and can be followed either way.
+
+use std::collections::HashMap;
+
+use gm_parsers::IcsProtocol;
+use petgraph::algo::{astar, dijkstra};
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::Reversed;
+use serde::{Deserialize, Serialize};
+
+use crate::TopologyGraph;
+
+/// One step of a [`TopologyPath`]: an observed connection from `from` to `to`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PathHop {
+    pub from: String,
+    pub to: String,
+    /// Id of the topology edge taken
+    pub edge_id: String,
+    /// Protocol carrying the most packets on that edge
+    pub protocol: IcsProtocol,
+    /// Every protocol seen on the edge, busiest first
+    pub protocols: Vec<IcsProtocol>,
+    pub packet_count: u64,
+}
+
+/// A chain of observed connections from one node to another.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopologyPath {
+    /// Node ids from source to destination, both included
+    pub nodes: Vec<String>,
+    pub hops: Vec<PathHop>,
+}
+
+/// Which way a reachability query follows the edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reachability {
+    /// Nodes the queried node can reach
+    From,
+    /// Nodes that can reach the queried node
+    To,
+}
+
+/// A node found by a reachability query, with its distance in hops.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReachableNode {
+    pub id: String,
+    pub hops: usize,
+}
+
+/// A petgraph index over a [`TopologyGraph`] for answering path queries.
+///
+/// Building it is linear in the size of the graph; build one per batch of
+/// queries rather than per query on large topologies.
+pub struct TopologyIndex<'a> {
+    topology: &'a TopologyGraph,
+    /// Node weights index `topology.nodes`, edge weights `topology.edges`
+    graph: DiGraph<usize, usize>,
+    by_id: HashMap<&'a str, NodeIndex>,
+}
+
+impl<'a> TopologyIndex<'a> {
+    pub fn new(topology: &'a TopologyGraph) -> Self {
+        let mut graph = DiGraph::with_capacity(topology.nodes.len(), topology.edges.len());
+        let by_id: HashMap<&str, NodeIndex> = topology
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), graph.add_node(i)))
+            .collect();
+        for (i, edge) in topology.edges.iter().enumerate() {
+            if let (Some(&source), Some(&target)) = (
+                by_id.get(edge.source.as_str()),
+                by_id.get(edge.target.as_str()),
+            ) {
+                graph.add_edge(source, target, i);
+            }
+        }
+        TopologyIndex {
+            topology,
+            graph,
+            by_id,
+        }
+    }
+
+    /// The path with the fewest hops from `src` to `dst` (node ids, i.e. IP
+    /// addresses, or MACs for Layer-2-only nodes). None if either node is
+    /// unknown or no chain of observed connections leads from one to the
+    /// other.
+    pub fn shortest_path(&self, src: &str, dst: &str) -> Option<TopologyPath> {
+        let start = *self.by_id.get(src)?;
+        let goal = *self.by_id.get(dst)?;
+        let (_, path) = astar(&self.graph, start, |n| n == goal, |_| 1usize, |_| 0)?;
+
+        let hops = path
+            .windows(2)
+            .filter_map(|pair| {
+                let edge = self.graph.find_edge(pair[0], pair[1])?;
+                Some(self.hop(self.graph[edge]))
+            })
+            .collect();
+        Some(TopologyPath {
+            nodes: path.iter().map(|&n| self.node_id(n).to_string()).collect(),
+            hops,
+        })
+    }
+
+    /// Every node reachable from `id` (or that can reach it), nearest first.
+    /// The queried node itself is not included.
+    pub fn reachable(&self, id: &str, direction: Reachability) -> Vec<ReachableNode> {
+        let Some(&start) = self.by_id.get(id) else {
+            return Vec::new();
+        };
+        let distances = match direction {
+            Reachability::From => dijkstra(&self.graph, start, None, |_| 1usize),
+            Reachability::To => dijkstra(Reversed(&self.graph), start, None, |_| 1usize),
+        };
+        let mut nodes: Vec<ReachableNode> = distances
+            .into_iter()
+            .filter(|&(n, _)| n != start)
+            .map(|(n, hops)| ReachableNode {
+                id: self.node_id(n).to_string(),
+                hops,
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.hops.cmp(&b.hops).then_with(|| a.id.cmp(&b.id)));
+        nodes
+    }
+
+    fn node_id(&self, node: NodeIndex) -> &'a str {
+        &self.topology.nodes[self.graph[node]].id
+    }
+
+    fn hop(&self, edge_index: usize) -> PathHop {
+        let edge = &self.topology.edges[edge_index];
+        let mut breakdown: Vec<_> = edge.protocol_breakdown.iter().collect();
+        breakdown.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.packet_count));
+        PathHop {
+            from: edge.source.clone(),
+            to: edge.target.clone(),
+            edge_id: edge.id.clone(),
+            protocol: edge.protocol,
+            protocols: breakdown.into_iter().map(|(p, _)| *p).collect(),
+            packet_count: edge.packet_count,
+        }
+    }
+}
+
+impl TopologyGraph {
+    /// The path with the fewest hops from `src_ip` to `dst_ip`; see
+    /// [`TopologyIndex::shortest_path`].
+    pub fn shortest_path(&self, src_ip: &str, dst_ip: &str) -> Option<TopologyPath> {
+        TopologyIndex::new(self).shortest_path(src_ip, dst_ip)
+    }
+
+    /// Nodes reachable from `ip`, or that can reach it; see
+    /// [`TopologyIndex::reachable`].
+    pub fn reachable(&self, ip: &str, direction: Reachability) -> Vec<ReachableNode> {
+        TopologyIndex::new(self).reachable(ip, direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TopologyBuilder;
+
+    /// laptop → jump → ews → plc, plus a one-way historian feed out of the
+    /// control network.
+    fn pivot_chain() -> TopologyGraph {
+        let mut builder = TopologyBuilder::new();
+        let mut link = |src: &str, dst: &str, protocol: IcsProtocol| {
+            builder.add_connection(src, dst, None, None, protocol, 100);
+        };
+        link("10.1.0.50", "10.2.0.5", IcsProtocol::Rdp);
+        link("10.2.0.5", "10.1.0.50", IcsProtocol::Rdp);
+        link("10.2.0.5", "10.3.0.10", IcsProtocol::Smb);
+        link("10.3.0.10", "10.3.0.100", IcsProtocol::S7comm);
+        link("10.3.0.10", "10.3.0.100", IcsProtocol::S7comm);
+        link("10.3.0.10", "10.3.0.100", IcsProtocol::Modbus);
+        link("10.3.0.100", "10.4.0.20", IcsProtocol::OpcUa);
+        builder.build()
+    }
+
+    #[test]
+    fn test_shortest_path_hop_chain() {
+        let graph = pivot_chain();
+        let path = graph.shortest_path("10.1.0.50", "10.3.0.100").unwrap();
+        assert_eq!(
+            path.nodes,
+            ["10.1.0.50", "10.2.0.5", "10.3.0.10", "10.3.0.100"]
+        );
+        assert_eq!(path.hops.len(), 3);
+        assert_eq!(path.hops[0].protocol, IcsProtocol::Rdp);
+        let last = &path.hops[2];
+        assert_eq!(
+            (last.from.as_str(), last.to.as_str()),
+            ("10.3.0.10", "10.3.0.100")
+        );
+        assert_eq!(last.protocols, [IcsProtocol::S7comm, IcsProtocol::Modbus]);
+        assert_eq!(last.packet_count, 3);
+        assert!(graph.edges.iter().any(|e| e.id == last.edge_id));
+    }
+
+    #[test]
+    fn test_path_follows_traffic_direction() {
+        let graph = pivot_chain();
+        // The PLC never initiated anything towards the EWS
+        assert!(graph.shortest_path("10.3.0.100", "10.3.0.10").is_none());
+        // The bidirectional RDP session can be followed back
+        let back = graph.shortest_path("10.2.0.5", "10.1.0.50").unwrap();
+        assert_eq!(back.hops.len(), 1);
+        assert!(graph.shortest_path("10.1.0.50", "192.0.2.1").is_none());
+
+        let same = graph.shortest_path("10.2.0.5", "10.2.0.5").unwrap();
+        assert_eq!(same.nodes, ["10.2.0.5"]);
+        assert!(same.hops.is_empty());
+    }
+
+    #[test]
+    fn test_reachability_both_directions() {
+        let graph = pivot_chain();
+        let from_laptop = graph.reachable("10.1.0.50", Reachability::From);
+        assert_eq!(
+            from_laptop,
+            [
+                ReachableNode {
+                    id: "10.2.0.5".into(),
+                    hops: 1
+                },
+                ReachableNode {
+                    id: "10.3.0.10".into(),
+                    hops: 2
+                },
+                ReachableNode {
+                    id: "10.3.0.100".into(),
+                    hops: 3
+                },
+                ReachableNode {
+                    id: "10.4.0.20".into(),
+                    hops: 4
+                },
+            ]
+        );
+
+        let to_plc: Vec<_> = graph
+            .reachable("10.3.0.100", Reachability::To)
+            .into_iter()
+            .map(|n| n.id)
+            .collect();
+        assert_eq!(to_plc, ["10.3.0.10", "10.2.0.5", "10.1.0.50"]);
+        assert!(graph.reachable("10.9.9.9", Reachability::To).is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! Subnet assignment from the plant's addressing plan.
+//!
+//! Each address goes in the most specific configured prefix that contains
+//! it. Prefixes come from analyst-supplied CIDRs or from switch interface
+//! (SVI) addresses and masks. An address outside every prefix falls back to
+//! its /24 (IPv4) or /64 (IPv6), which is also all there is to go on when
+//! no plan is configured.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::extract_subnet;
+
+/// A network prefix with its host bits cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Prefix {
+    network: IpAddr,
+    len: u8,
+}
+
+impl Prefix {
+    fn new(addr: IpAddr, len: u8) -> Option<Self> {
+        let network = match addr {
+            IpAddr::V4(v4) if len <= 32 => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(len)).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+            }
+            IpAddr::V6(v6) if len <= 128 => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(len)).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+            }
+            _ => return None,
+        };
+        Some(Prefix { network, len })
+    }
+
+    fn contains(&self, addr: IpAddr) -> bool {
+        Prefix::new(addr, self.len).is_some_and(|p| p.network == self.network)
+    }
+}
+
+impl std::fmt::Display for Prefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.network, self.len)
+    }
+}
+
+/// The subnets devices are grouped into.
+#[derive(Debug, Clone, Default)]
+pub struct SubnetPlan {
+    /// Most specific first
+    prefixes: Vec<Prefix>,
+}
+
+impl SubnetPlan {
+    /// An empty plan: every address gets its /24 or /64.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a prefix in CIDR notation, e.g. `"10.20.0.0/16"` or
+    /// `"fd00:10::/48"`. Host bits are ignored, so an interface address such
+    /// as `"10.20.4.1/22"` adds the subnet it sits in.
+    pub fn add_cidr(&mut self, cidr: &str) -> Result<(), String> {
+        let (addr, len) = cidr
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| format!("'{}' is not in CIDR notation", cidr))?;
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("'{}': invalid address", cidr))?;
+        let len: u8 = len
+            .parse()
+            .map_err(|_| format!("'{}': invalid prefix length", cidr))?;
+        let prefix =
+            Prefix::new(addr, len).ok_or_else(|| format!("'{}': prefix length too long", cidr))?;
+        self.insert(prefix);
+        Ok(())
+    }
+
+    /// Add the subnet of an interface address given with a dotted-quad
+    /// mask (`"255.255.252.0"`) or a prefix length, as found in switch
+    /// configs.
+    pub fn add_interface(&mut self, ip: &str, mask: &str) -> Result<(), String> {
+        let len = match mask.parse::<u8>() {
+            Ok(len) => len,
+            Err(_) => {
+                let bits = mask
+                    .parse::<Ipv4Addr>()
+                    .map(u32::from)
+                    .map_err(|_| format!("'{}': invalid subnet mask", mask))?;
+                let len = bits.leading_ones();
+                if bits.checked_shl(len).unwrap_or(0) != 0 {
+                    return Err(format!("'{}': mask bits are not contiguous", mask));
+                }
+                len as u8
+            }
+        };
+        self.add_cidr(&format!("{}/{}", ip, len))
+    }
+
+    fn insert(&mut self, prefix: Prefix) {
+        if !self.prefixes.contains(&prefix) {
+            self.prefixes.push(prefix);
+            self.prefixes.sort_by_key(|p| std::cmp::Reverse(p.len));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty()
+    }
+
+    /// The subnet `ip` belongs to, in CIDR notation.
+    pub fn subnet_of(&self, ip: &str) -> String {
+        ip.parse::<IpAddr>()
+            .ok()
+            .and_then(|addr| self.prefixes.iter().find(|p| p.contains(addr)))
+            .map_or_else(|| extract_subnet(ip), |p| p.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TopologyBuilder;
+    use gm_parsers::IcsProtocol;
+
+    #[test]
+    fn test_most_specific_prefix_wins() {
+        let mut plan = SubnetPlan::new();
+        plan.add_cidr("10.20.0.0/16").unwrap();
+        plan.add_cidr("10.20.8.0/21").unwrap();
+        plan.add_cidr("fd00:10::/48").unwrap();
+
+        assert_eq!(plan.subnet_of("10.20.9.40"), "10.20.8.0/21");
+        assert_eq!(plan.subnet_of("10.20.200.1"), "10.20.0.0/16");
+        assert_eq!(plan.subnet_of("fd00:10:0:7::12"), "fd00:10::/48");
+        // Outside the plan
+        assert_eq!(plan.subnet_of("192.168.5.7"), "192.168.5.0/24");
+        assert_eq!(plan.subnet_of("2001:db8:1:2::9"), "2001:db8:1:2::/64");
+    }
+
+    #[test]
+    fn test_interface_address_and_mask() {
+        let mut plan = SubnetPlan::new();
+        plan.add_interface("172.16.4.1", "255.255.252.0").unwrap();
+        plan.add_interface("172.16.9.1", "23").unwrap();
+        assert_eq!(plan.subnet_of("172.16.7.200"), "172.16.4.0/22");
+        assert_eq!(plan.subnet_of("172.16.8.3"), "172.16.8.0/23");
+
+        assert!(plan.add_interface("172.16.4.1", "255.0.255.0").is_err());
+        assert!(plan.add_cidr("10.0.0.0/33").is_err());
+        assert!(plan.add_cidr("10.0.0.0").is_err());
+    }
+
+    #[test]
+    fn test_plan_shapes_topology() {
+        let mut plan = SubnetPlan::new();
+        plan.add_cidr("10.20.0.0/22").unwrap();
+        let mut builder = TopologyBuilder::with_subnets(plan);
+        let hmi = Some("00:50:56:00:00:05");
+        let plc = Some("00:0e:8c:00:00:10");
+        // One PLC answering on addresses either side of a /24 boundary
+        for ip in ["10.20.1.10", "10.20.2.10", "10.20.3.10"] {
+            builder.add_connection("10.20.0.5", ip, hmi, plc, IcsProtocol::Modbus, 90);
+        }
+        assert!(builder.gateways().is_empty());
+
+        let graph = builder.build();
+        assert!(graph.nodes.iter().all(|n| n.subnet == "10.20.0.0/22"));
+        // Aliases in one subnet, not a router fronting three
+        assert_eq!(graph.nodes.len(), 2);
+    }
+}
@@ -0,0 +1,329 @@
+//! Traffic over time on each edge.
+//!
+//! Every edge counts its packets and bytes in fixed-width time buckets (one
+//! minute unless the builder is given another width), so bandwidth can be
+//! charted per connection and bursts picked out. Buckets are sparse — an
+//! edge idle for an hour stores nothing for it — and once an edge has been
+//! active in more than [`MAX_TRAFFIC_BUCKETS`] of them the width doubles,
+//! trading resolution for a bounded footprint on long captures.
+//!
+//! A burst is a bucket carrying more than [`BURST_FACTOR`] times the edge's
+//! mean over its active span, idle buckets included: a PLC polled at a
+//! steady rate has none, a nightly backup over the same link stands out.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use gm_parsers::IcsProtocol;
+use serde::Serialize;
+
+use crate::TopologyGraph;
+
+/// Bucket width used unless the builder is given another.
+pub const DEFAULT_TRAFFIC_BUCKET_SECS: u32 = 60;
+
+/// Active buckets kept per edge before the width doubles.
+pub const MAX_TRAFFIC_BUCKETS: usize = 360;
+
+/// How many times the mean a bucket must carry to count as a burst.
+pub const BURST_FACTOR: f64 = 4.0;
+
+/// Traffic on an edge in one time bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TrafficBucket {
+    /// Start of the bucket, a multiple of the width since the Unix epoch
+    pub start: DateTime<Utc>,
+    pub packet_count: u64,
+    pub byte_count: u64,
+}
+
+/// Packet and byte counts of one edge over time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrafficHistogram {
+    bucket_secs: u32,
+    /// Buckets with traffic, in time order
+    buckets: Vec<TrafficBucket>,
+}
+
+/// Bandwidth over time on one edge, for charting.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EdgeTraffic {
+    pub edge_id: String,
+    pub source: String,
+    pub target: String,
+    pub protocol: IcsProtocol,
+    pub packet_count: u64,
+    pub byte_count: u64,
+    pub bucket_secs: u32,
+    /// Buckets with traffic, in time order; empty if the edge was never
+    /// timed
+    pub buckets: Vec<TrafficBucket>,
+    /// The busiest bucket
+    pub peak: Option<TrafficBucket>,
+    /// Buckets over [`BURST_FACTOR`] times the mean, in time order
+    pub bursts: Vec<TrafficBucket>,
+}
+
+impl TrafficHistogram {
+    pub fn new(bucket_secs: u32) -> Self {
+        TrafficHistogram {
+            bucket_secs: bucket_secs.max(1),
+            buckets: Vec::new(),
+        }
+    }
+
+    /// Current bucket width; a multiple of the one it started with.
+    pub fn bucket_secs(&self) -> u32 {
+        self.bucket_secs
+    }
+
+    pub fn buckets(&self) -> &[TrafficBucket] {
+        &self.buckets
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Count traffic seen at `at`.
+    pub fn record(&mut self, at: DateTime<Utc>, packets: u64, bytes: u64) {
+        self.add(at, packets, bytes);
+        self.enforce_cap();
+    }
+
+    /// Merge another edge's histogram into this one, at the coarser of the
+    /// two widths.
+    pub fn absorb(&mut self, other: &TrafficHistogram) {
+        while self.bucket_secs < other.bucket_secs {
+            self.coarsen();
+        }
+        for bucket in &other.buckets {
+            self.add(bucket.start, bucket.packet_count, bucket.byte_count);
+        }
+        self.enforce_cap();
+    }
+
+    /// The bucket with the most bytes, the earliest on a tie.
+    pub fn peak(&self) -> Option<TrafficBucket> {
+        self.buckets
+            .iter()
+            .rev()
+            .max_by_key(|b| b.byte_count)
+            .copied()
+    }
+
+    /// Buckets carrying more than [`BURST_FACTOR`] times the mean bytes per
+    /// bucket between the first and last active one.
+    pub fn bursts(&self) -> Vec<TrafficBucket> {
+        let (Some(first), Some(last)) = (self.buckets.first(), self.buckets.last()) else {
+            return Vec::new();
+        };
+        let span = (last.start - first.start).num_seconds() / i64::from(self.bucket_secs) + 1;
+        let total: u64 = self.buckets.iter().map(|b| b.byte_count).sum();
+        let threshold = BURST_FACTOR * total as f64 / span as f64;
+        self.buckets
+            .iter()
+            .filter(|b| b.byte_count as f64 > threshold)
+            .copied()
+            .collect()
+    }
+
+    fn bucket_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let secs = at.timestamp();
+        DateTime::from_timestamp(secs - secs.rem_euclid(i64::from(self.bucket_secs)), 0)
+            .unwrap_or(at)
+    }
+
+    fn add(&mut self, at: DateTime<Utc>, packets: u64, bytes: u64) {
+        let start = self.bucket_start(at);
+        // Packets mostly arrive in time order, so this usually hits the last
+        // bucket or appends
+        match self.buckets.binary_search_by_key(&start, |b| b.start) {
+            Ok(i) => {
+                self.buckets[i].packet_count += packets;
+                self.buckets[i].byte_count += bytes;
+            }
+            Err(i) => self.buckets.insert(
+                i,
+                TrafficBucket {
+                    start,
+                    packet_count: packets,
+                    byte_count: bytes,
+                },
+            ),
+        }
+    }
+
+    fn enforce_cap(&mut self) {
+        while self.buckets.len() > MAX_TRAFFIC_BUCKETS {
+            self.coarsen();
+        }
+    }
+
+    /// Double the width, merging each pair of buckets.
+    fn coarsen(&mut self) {
+        self.bucket_secs = self.bucket_secs.saturating_mul(2);
+        let mut merged: Vec<TrafficBucket> = Vec::with_capacity(self.buckets.len());
+        for mut bucket in std::mem::take(&mut self.buckets) {
+            bucket.start = self.bucket_start(bucket.start);
+            match merged.last_mut() {
+                Some(last) if last.start == bucket.start => {
+                    last.packet_count += bucket.packet_count;
+                    last.byte_count += bucket.byte_count;
+                }
+                _ => merged.push(bucket),
+            }
+        }
+        self.buckets = merged;
+    }
+}
+
+impl Default for TrafficHistogram {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRAFFIC_BUCKET_SECS)
+    }
+}
+
+impl TopologyGraph {
+    /// The `limit` edges carrying the most bytes, busiest first, with their
+    /// traffic over time.
+    pub fn top_talkers(&self, limit: usize) -> Vec<EdgeTraffic> {
+        let mut edges: Vec<_> = self.edges.iter().collect();
+        edges.sort_by(|a, b| {
+            b.byte_count
+                .cmp(&a.byte_count)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        edges
+            .into_iter()
+            .take(limit)
+            .map(|edge| EdgeTraffic {
+                edge_id: edge.id.clone(),
+                source: edge.source.clone(),
+                target: edge.target.clone(),
+                protocol: edge.protocol,
+                packet_count: edge.packet_count,
+                byte_count: edge.byte_count,
+                bucket_secs: edge.traffic.bucket_secs(),
+                buckets: edge.traffic.buckets().to_vec(),
+                peak: edge.traffic.peak(),
+                bursts: edge.traffic.bursts(),
+            })
+            .collect()
+    }
+
+    /// Give each edge with no traffic history that of the edge between the
+    /// same nodes in `previous`. For a graph rebuilt from stored
+    /// connections, which have no per-packet times to bucket.
+    pub fn carry_traffic(&mut self, previous: &TopologyGraph) {
+        let histograms: HashMap<(&str, &str), &TrafficHistogram> = previous
+            .edges
+            .iter()
+            .map(|e| ((e.source.as_str(), e.target.as_str()), &e.traffic))
+            .collect();
+        for edge in self.edges.iter_mut().filter(|e| e.traffic.is_empty()) {
+            if let Some(histogram) = histograms.get(&(edge.source.as_str(), edge.target.as_str())) {
+                edge.traffic = (*histogram).clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::TopologyBuilder;
+
+    fn at(second: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 8, 0, 0).unwrap() + Duration::seconds(second)
+    }
+
+    #[test]
+    fn test_buckets_and_bursts() {
+        let mut histogram = TrafficHistogram::new(60);
+        // A poll every 10 s for ten minutes, then a file transfer
+        for second in (0..600).step_by(10) {
+            histogram.record(at(second), 1, 100);
+        }
+        for second in 420..425 {
+            histogram.record(at(second), 10, 15_000);
+        }
+        histogram.record(at(5), 1, 100);
+
+        assert_eq!(histogram.buckets().len(), 10);
+        assert_eq!(histogram.buckets()[0].start, at(0));
+        assert_eq!(histogram.buckets()[0].packet_count, 7);
+        assert_eq!(histogram.buckets()[0].byte_count, 700);
+        let peak = histogram.peak().unwrap();
+        assert_eq!(peak.start, at(420));
+        assert_eq!(peak.byte_count, 75_600);
+        assert_eq!(histogram.bursts(), [peak]);
+
+        // Steady traffic alone has no bursts
+        let mut steady = TrafficHistogram::new(60);
+        for second in (0..600).step_by(10) {
+            steady.record(at(second), 1, 100);
+        }
+        assert!(steady.bursts().is_empty());
+    }
+
+    #[test]
+    fn test_width_doubles_past_cap() {
+        let mut histogram = TrafficHistogram::new(60);
+        for minute in 0..=MAX_TRAFFIC_BUCKETS as i64 {
+            histogram.record(at(minute * 60), 1, 64);
+        }
+        assert_eq!(histogram.bucket_secs(), 120);
+        assert_eq!(histogram.buckets().len(), MAX_TRAFFIC_BUCKETS / 2 + 1);
+        assert_eq!(histogram.buckets()[0].packet_count, 2);
+        let total: u64 = histogram.buckets().iter().map(|b| b.byte_count).sum();
+        assert_eq!(total, 64 * (MAX_TRAFFIC_BUCKETS as u64 + 1));
+
+        // A finer histogram is merged at the coarser width
+        let mut fine = TrafficHistogram::new(60);
+        fine.record(at(60), 1, 10);
+        fine.absorb(&histogram);
+        assert_eq!(fine.bucket_secs(), 120);
+        assert_eq!(fine.buckets()[0].packet_count, 3);
+    }
+
+    #[test]
+    fn test_top_talkers() {
+        let mut builder = TopologyBuilder::new().with_traffic_buckets(30);
+        for poll in 0..3 {
+            builder.add_connection("10.0.0.5", "10.0.0.10", None, None, IcsProtocol::Modbus, 80);
+            builder.add_traffic("10.0.0.5", "10.0.0.10", at(poll * 20), 80);
+        }
+        builder.add_connection("10.0.0.10", "10.0.0.5", None, None, IcsProtocol::Modbus, 60);
+        builder.add_traffic("10.0.0.10", "10.0.0.5", at(0), 60);
+
+        let graph = builder.build();
+        let talkers = graph.top_talkers(1);
+        assert_eq!(talkers.len(), 1);
+        let talker = &talkers[0];
+        assert_eq!(
+            (talker.source.as_str(), talker.target.as_str()),
+            ("10.0.0.5", "10.0.0.10")
+        );
+        assert_eq!(talker.bucket_secs, 30);
+        let bytes: Vec<u64> = talker.buckets.iter().map(|b| b.byte_count).collect();
+        assert_eq!(bytes, [160, 80]);
+        assert_eq!(talker.peak.unwrap().start, at(0));
+
+        // A rebuild from stored connections keeps the history
+        let mut rebuilt = TopologyBuilder::new();
+        rebuilt.add_connection(
+            "10.0.0.5",
+            "10.0.0.10",
+            None,
+            None,
+            IcsProtocol::Modbus,
+            240,
+        );
+        let mut rebuilt = rebuilt.build();
+        rebuilt.carry_traffic(&graph);
+        assert_eq!(rebuilt.top_talkers(1)[0].buckets, talker.buckets);
+    }
+}
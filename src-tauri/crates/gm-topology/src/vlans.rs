@@ -0,0 +1,216 @@
+//! VLAN membership of topology nodes.
+//!
+//! The packet graph knows addresses, not VLANs, so membership comes from
+//! elsewhere: 802.1Q tags on a device's own frames, the access port a
+//! switch's MAC or ARP table puts it on, or the SVI whose subnet it sits
+//! in. A [`VlanMap`] collects these and [`TopologyGraph::assign_vlans`]
+//! copies them onto the nodes. Tags and switch tables are direct evidence;
+//! the SVI subnet is only used for nodes with neither.
+//!
+//! A node can be in several VLANs — a router with an SVI in each, or a host
+//! whose tagged frames were captured on a trunk.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::{TopoNode, TopologyGraph};
+
+/// Where each address, MAC and subnet sits, and what the switches call
+/// each VLAN.
+#[derive(Debug, Clone, Default)]
+pub struct VlanMap {
+    by_address: HashMap<String, BTreeSet<u16>>,
+    /// Lowercase MAC → VLANs
+    by_mac: HashMap<String, BTreeSet<u16>>,
+    by_subnet: HashMap<String, BTreeSet<u16>>,
+    names: BTreeMap<u16, String>,
+}
+
+/// The nodes in one VLAN.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VlanGroup {
+    pub vlan: u16,
+    /// Name from the switch configuration, if any
+    pub name: Option<String>,
+    /// Ids of the member nodes, sorted
+    pub node_ids: Vec<String>,
+}
+
+impl VlanMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `ip` was seen in `vlan` (a tagged frame, or an ARP entry).
+    pub fn add_address(&mut self, ip: &str, vlan: u16) {
+        self.by_address
+            .entry(ip.to_string())
+            .or_default()
+            .insert(vlan);
+    }
+
+    /// `mac` was learned in `vlan` (a MAC address table entry).
+    pub fn add_mac(&mut self, mac: &str, vlan: u16) {
+        self.by_mac
+            .entry(mac.to_ascii_lowercase())
+            .or_default()
+            .insert(vlan);
+    }
+
+    /// `subnet` is the one routed on `vlan`'s SVI, in the CIDR form nodes
+    /// carry in `subnet`.
+    pub fn add_subnet(&mut self, subnet: &str, vlan: u16) {
+        self.by_subnet
+            .entry(subnet.to_string())
+            .or_default()
+            .insert(vlan);
+    }
+
+    /// The switch configuration's name for `vlan`. Placeholder names such
+    /// as `"VLAN10"` are not worth showing and are ignored.
+    pub fn name_vlan(&mut self, vlan: u16, name: &str) {
+        let name = name.trim();
+        if !name.is_empty() && name != format!("VLAN{}", vlan) {
+            self.names.insert(vlan, name.to_string());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_address.is_empty() && self.by_mac.is_empty() && self.by_subnet.is_empty()
+    }
+
+    /// VLANs of `node`, sorted.
+    fn vlans_of(&self, node: &TopoNode) -> Vec<u16> {
+        let mut vlans = BTreeSet::new();
+        let addresses = node
+            .addresses
+            .iter()
+            .chain(node.addresses.is_empty().then_some(&node.ip_address));
+        for address in addresses {
+            vlans.extend(self.by_address.get(address).into_iter().flatten());
+        }
+        if let Some(mac) = &node.mac_address {
+            vlans.extend(
+                self.by_mac
+                    .get(&mac.to_ascii_lowercase())
+                    .into_iter()
+                    .flatten(),
+            );
+        }
+        if vlans.is_empty() {
+            vlans.extend(self.by_subnet.get(&node.subnet).into_iter().flatten());
+        }
+        vlans.into_iter().collect()
+    }
+}
+
+impl TopologyGraph {
+    /// Set every node's `vlans` from `map`, replacing what was there.
+    pub fn assign_vlans(&mut self, map: &VlanMap) {
+        for node in &mut self.nodes {
+            node.vlans = map.vlans_of(node);
+        }
+    }
+
+    /// Nodes grouped by the VLANs assigned to them, in VLAN order. A node
+    /// in several VLANs is listed in each; nodes in none are left out.
+    pub fn vlan_groups(&self, map: &VlanMap) -> Vec<VlanGroup> {
+        let mut members: BTreeMap<u16, Vec<String>> = BTreeMap::new();
+        for node in &self.nodes {
+            for vlan in &node.vlans {
+                members.entry(*vlan).or_default().push(node.id.clone());
+            }
+        }
+        members
+            .into_iter()
+            .map(|(vlan, mut node_ids)| {
+                node_ids.sort();
+                VlanGroup {
+                    vlan,
+                    name: map.names.get(&vlan).cloned(),
+                    node_ids,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TopologyBuilder;
+    use gm_parsers::IcsProtocol;
+
+    fn cell() -> TopologyGraph {
+        let mut builder = TopologyBuilder::new();
+        builder.add_connection(
+            "10.10.0.5",
+            "10.10.0.100",
+            Some("00:50:56:00:00:05"),
+            Some("00:0E:8C:00:00:10"),
+            IcsProtocol::S7comm,
+            120,
+        );
+        builder.add_connection(
+            "10.10.0.5",
+            "10.20.0.7",
+            Some("00:50:56:00:00:05"),
+            Some("00:50:56:00:00:07"),
+            IcsProtocol::OpcUa,
+            300,
+        );
+        builder.add_connection("10.10.0.5", "10.30.0.9", None, None, IcsProtocol::Http, 400);
+        builder.build()
+    }
+
+    fn node<'a>(graph: &'a TopologyGraph, id: &str) -> &'a TopoNode {
+        graph.nodes.iter().find(|n| n.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_direct_evidence_before_subnet() {
+        let mut map = VlanMap::new();
+        // The HMI's frames were tagged on a trunk carrying two VLANs
+        map.add_address("10.10.0.5", 10);
+        map.add_address("10.10.0.5", 20);
+        map.add_mac("00:0e:8c:00:00:10", 10);
+        map.add_subnet("10.20.0.0/24", 20);
+        map.add_subnet("10.10.0.0/24", 99);
+
+        let mut graph = cell();
+        graph.assign_vlans(&map);
+        assert_eq!(node(&graph, "10.10.0.5").vlans, [10, 20]);
+        assert_eq!(node(&graph, "10.10.0.100").vlans, [10]);
+        assert_eq!(node(&graph, "10.20.0.7").vlans, [20]);
+        assert!(node(&graph, "10.30.0.9").vlans.is_empty());
+    }
+
+    #[test]
+    fn test_groups_carry_names() {
+        let mut map = VlanMap::new();
+        map.add_subnet("10.10.0.0/24", 10);
+        map.add_subnet("10.20.0.0/24", 20);
+        map.name_vlan(10, "CELL-1");
+        map.name_vlan(20, "VLAN20");
+
+        let mut graph = cell();
+        graph.assign_vlans(&map);
+        let groups = graph.vlan_groups(&map);
+        assert_eq!(
+            groups,
+            [
+                VlanGroup {
+                    vlan: 10,
+                    name: Some("CELL-1".to_string()),
+                    node_ids: vec!["10.10.0.100".to_string(), "10.10.0.5".to_string()],
+                },
+                VlanGroup {
+                    vlan: 20,
+                    name: None,
+                    node_ids: vec!["10.20.0.7".to_string()],
+                },
+            ]
+        );
+    }
+}
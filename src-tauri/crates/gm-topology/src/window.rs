@@ -0,0 +1,153 @@
+//! Time-windowed views of a [`TopologyGraph`].
+//!
+//! Only first/last-seen times are kept, so an edge counts as active in a
+//! window if those overlap it; its packet and byte counts stay the totals
+//! for the whole capture. Edges with no recorded time (imported without
+//! timestamps) cannot be placed and are kept in every window.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+
+use crate::{centrality, communities, TopologyGraph};
+
+/// Whether `[first, last]` overlaps `[start, end]`; unknown times overlap.
+fn overlaps(
+    first: Option<DateTime<Utc>>,
+    last: Option<DateTime<Utc>>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> bool {
+    match (first, last) {
+        (Some(first), Some(last)) => first <= end && last >= start,
+        _ => true,
+    }
+}
+
+impl TopologyGraph {
+    /// Earliest first-seen and latest last-seen time over all nodes, the
+    /// range a timeline can scrub across. None if nothing is timed.
+    pub fn time_span(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let first = self.nodes.iter().filter_map(|n| n.first_seen).min()?;
+        let last = self.nodes.iter().filter_map(|n| n.last_seen).max()?;
+        Some((first, last))
+    }
+
+    /// The part of the graph active between `start` and `end` (inclusive):
+    /// edges whose first/last-seen range overlaps the window, their
+    /// endpoints, and any other node seen in it. Centrality and clusters are
+    /// recomputed for the smaller graph.
+    pub fn between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> TopologyGraph {
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .filter(|e| overlaps(e.first_seen, e.last_seen, start, end))
+            .cloned()
+            .collect();
+        let endpoints: HashSet<&str> = edges
+            .iter()
+            .flat_map(|e| [e.source.as_str(), e.target.as_str()])
+            .collect();
+        let nodes = self
+            .nodes
+            .iter()
+            .filter(|n| {
+                endpoints.contains(n.id.as_str())
+                    || (n.first_seen.is_some() && overlaps(n.first_seen, n.last_seen, start, end))
+            })
+            .cloned()
+            .collect();
+
+        let mut graph = TopologyGraph { nodes, edges };
+        centrality::assign_centrality(&mut graph);
+        communities::assign_clusters(&mut graph);
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use crate::TopologyBuilder;
+    use gm_parsers::IcsProtocol;
+
+    use super::*;
+
+    fn at(minute: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 8, 0, 0).unwrap() + Duration::minutes(minute)
+    }
+
+    /// Polling all morning, plus an engineering session from 10 to 20 min.
+    fn shift() -> TopologyGraph {
+        let mut builder = TopologyBuilder::new();
+        for minute in 0..60 {
+            builder.add_connection(
+                "10.0.0.5",
+                "10.0.0.100",
+                None,
+                None,
+                IcsProtocol::Modbus,
+                80,
+            );
+            builder.mark_seen("10.0.0.5", "10.0.0.100", at(minute));
+        }
+        for minute in 10..=20 {
+            builder.add_connection(
+                "10.0.0.9",
+                "10.0.0.100",
+                None,
+                None,
+                IcsProtocol::S7comm,
+                200,
+            );
+            builder.mark_seen("10.0.0.9", "10.0.0.100", at(minute));
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_first_last_seen_recorded() {
+        let graph = shift();
+        let ews = graph.nodes.iter().find(|n| n.id == "10.0.0.9").unwrap();
+        assert_eq!(
+            (ews.first_seen, ews.last_seen),
+            (Some(at(10)), Some(at(20)))
+        );
+        let plc = graph.nodes.iter().find(|n| n.id == "10.0.0.100").unwrap();
+        assert_eq!((plc.first_seen, plc.last_seen), (Some(at(0)), Some(at(59))));
+        assert_eq!(graph.time_span(), Some((at(0), at(59))));
+    }
+
+    #[test]
+    fn test_window_keeps_only_active_part() {
+        let graph = shift();
+
+        let early = graph.between(at(0), at(5));
+        assert_eq!(early.edges.len(), 1);
+        assert!(early.nodes.iter().all(|n| n.id != "10.0.0.9"));
+
+        let session = graph.between(at(15), at(16));
+        assert_eq!(session.edges.len(), 2);
+        assert_eq!(session.nodes.len(), 3);
+        // Counts stay whole-capture totals
+        let s7 = session
+            .edges
+            .iter()
+            .find(|e| e.source == "10.0.0.9")
+            .unwrap();
+        assert_eq!(s7.packet_count, 11);
+
+        assert!(graph.between(at(120), at(130)).nodes.is_empty());
+    }
+
+    #[test]
+    fn test_untimed_edges_kept() {
+        let mut builder = TopologyBuilder::new();
+        builder.add_connection("10.0.0.1", "10.0.0.2", None, None, IcsProtocol::Dnp3, 60);
+        let graph = builder.build();
+        assert_eq!(graph.time_span(), None);
+        let window = graph.between(at(0), at(1));
+        assert_eq!((window.nodes.len(), window.edges.len()), (2, 1));
+    }
+}
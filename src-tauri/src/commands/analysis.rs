@@ -10,13 +10,16 @@ use std::collections::{HashMap, HashSet};
 
 use gm_analysis::{
     assess_switch_security, detect_malware_patterns, generate_compliance_report, AnalysisInput,
-    AnalysisResult, AnomalyScore, AssetSnapshot, BacnetSnapshot, CaptureContext, ComplianceMapping,
-    ConnectionSnapshot, CredentialChecker, CriticalityAssessment, CveMatch, CveMatcher,
-    DeepParseSnapshot, DefaultCredential, Dnp3Snapshot, EnipSnapshot, FcSnapshot, Finding,
+    AnalysisResult, AnomalyScore, ArpSnapshot, AssetSnapshot, BacnetSnapshot, CaptureContext,
+    CodesysSnapshot, ComplianceMapping, ConnectionSnapshot, CredentialChecker, CrimsonSnapshot,
+    CriticalityAssessment, CveMatch, CveMatcher, DeepParseSnapshot, DefaultCredential,
+    DhcpSnapshot, Dnp3BindingSnapshot, Dnp3Snapshot, EnipSnapshot, FcSnapshot, Finding,
     Iec104Snapshot, MalwareFinding, ModbusSnapshot, NamingSuggestion, PollingSnapshot,
-    ProfinetDcpSnapshot, PurdueAssignment, RelationshipSnapshot, S7Snapshot, SwitchSecurityFinding,
-    SwitchSecurityInput,
+    ProfinetDcpSnapshot, PurdueAssignment, RelationshipSnapshot, S7Snapshot, SmbShareSnapshot,
+    SmbSnapshot, SwitchSecurityFinding, SwitchSecurityInput, TriStationSnapshot, WriteApproval,
+    WritePath,
 };
+use gm_db::{WriteApprovalInput, WriteApprovalRow};
 
 use super::AppState;
 
@@ -51,6 +54,16 @@ fn build_analysis_input(state: &super::AppStateInner) -> AnalysisInput {
         })
         .collect();
 
+    let arp: Vec<ArpSnapshot> = state
+        .arp_bindings
+        .iter()
+        .map(|b| ArpSnapshot {
+            ip_address: b.ip_address.clone(),
+            mac_addresses: b.mac_addresses.clone(),
+            gratuitous_count: b.gratuitous_count,
+        })
+        .collect();
+
     let mut deep_parse = std::collections::HashMap::new();
     for (ip, dp) in &state.deep_parse_info {
         let modbus = dp.modbus.as_ref().map(|m| ModbusSnapshot {
@@ -109,6 +122,17 @@ fn build_analysis_input(state: &super::AppStateInner) -> AnalysisInput {
                     packet_count: r.packet_count,
                 })
                 .collect(),
+            master_bindings: d
+                .master_bindings
+                .iter()
+                .map(|b| Dnp3BindingSnapshot {
+                    master_address: b.master_address,
+                    outstation_address: b.outstation_address,
+                    outstation_ip: b.outstation_ip.clone(),
+                    first_seen: b.first_seen.clone(),
+                    last_seen: b.last_seen.clone(),
+                })
+                .collect(),
         });
 
         let enip = dp.enip.as_ref().map(|e| EnipSnapshot {
@@ -142,6 +166,48 @@ fn build_analysis_input(state: &super::AppStateInner) -> AnalysisInput {
             role: p.role.clone(),
         });
 
+        let codesys = dp.codesys.as_ref().map(|c| CodesysSnapshot {
+            role: c.role.clone(),
+            has_login: c.has_login,
+            has_download: c.has_download,
+            has_online_change: c.has_online_change,
+            has_control_commands: c.has_control_commands,
+        });
+        let crimson = dp.crimson.as_ref().map(|c| CrimsonSnapshot {
+            role: c.role.clone(),
+            has_download: c.has_download,
+            has_upload: c.has_upload,
+            transfer_peers: c.transfer_peers.clone(),
+        });
+        let tristation = dp.tristation.as_ref().map(|t| TriStationSnapshot {
+            role: t.role.clone(),
+            has_download: t.has_download,
+            has_upload: t.has_upload,
+            has_run_state_change: t.has_run_state_change,
+            peers: t.peers.clone(),
+        });
+        let smb = dp.smb.as_ref().map(|s| SmbSnapshot {
+            role: s.role.clone(),
+            shares: s
+                .shares
+                .iter()
+                .filter(|share| share.remote_role == "server")
+                .map(|share| SmbShareSnapshot {
+                    server_ip: share.remote_ip.clone(),
+                    share: share.share.clone(),
+                    files: share.files.clone(),
+                    files_written: share.files_written.clone(),
+                    bytes_written: share.bytes_written,
+                    bytes_read: share.bytes_read,
+                })
+                .collect(),
+        });
+        let dhcp = dp.dhcp.as_ref().map(|d| DhcpSnapshot {
+            role: d.role.clone(),
+            clients: d.leases.iter().map(|l| l.client_mac.clone()).collect(),
+            replies: d.replies,
+        });
+
         deep_parse.insert(
             ip.clone(),
             DeepParseSnapshot {
@@ -152,6 +218,11 @@ fn build_analysis_input(state: &super::AppStateInner) -> AnalysisInput {
                 bacnet,
                 iec104,
                 profinet_dcp,
+                codesys,
+                crimson,
+                tristation,
+                smb,
+                dhcp,
             },
         );
     }
@@ -160,6 +231,7 @@ fn build_analysis_input(state: &super::AppStateInner) -> AnalysisInput {
         assets,
         connections,
         deep_parse,
+        arp,
     }
 }
 
@@ -169,6 +241,7 @@ fn build_capture_context(state: &super::AppStateInner) -> CaptureContext {
     let ot_device_types = [
         "plc",
         "rtu",
+        "ied",
         "hmi",
         "historian",
         "engineering_workstation",
@@ -185,10 +258,21 @@ fn build_capture_context(state: &super::AppStateInner) -> CaptureContext {
         "Bacnet",
         "OpcUa",
         "Iec104",
+        "Iec61850Mms",
         "ProfinetDcp",
         "HartIp",
         "GeSrtp",
         "WonderwareSuitelink",
+        "Fins",
+        "Slmp",
+        "NiagaraFox",
+        "Crimson",
+        "VnetIp",
+        "TriStation",
+        "Ads",
+        "OpcDa",
+        // Codesys is left out: speaking it doesn't make a host OT, and the
+        // PIPEDREAM check looks for CODESYS clients that are not OT devices
     ];
 
     let mut ot_device_ips: HashSet<String> = state
@@ -205,8 +289,9 @@ fn build_capture_context(state: &super::AppStateInner) -> CaptureContext {
 
     // Also include IPs from connections to OT ports (passive inference).
     let ot_ports: &[u16] = &[
-        102, 502, 1089, 1090, 1091, 2222, 2404, 4840, 5007, 5094, 18245, 18246, 20000, 34962,
-        34963, 34964, 44818, 47808,
+        102, 502, 789, 1089, 1090, 1091, 1200, 1217, 1502, 1911, 2222, 2404, 2455, 4840, 4911,
+        5006, 5007, 5094, 9600, 11740, 18245, 18246, 20000, 20171, 20172, 34962, 34963, 34964,
+        44818, 47808, 48898,
     ];
     for conn in &state.connections {
         if ot_ports.contains(&conn.dst_port) {
@@ -246,6 +331,15 @@ fn build_capture_context(state: &super::AppStateInner) -> CaptureContext {
             }
         }
     }
+    // Every MAC that answered for an address in ARP
+    for binding in &state.arp_bindings {
+        let macs = ip_to_macs.entry(binding.ip_address.clone()).or_default();
+        for mac in &binding.mac_addresses {
+            if !macs.contains(mac) {
+                macs.push(mac.clone());
+            }
+        }
+    }
     let mut mac_to_ips: HashMap<String, Vec<String>> = HashMap::new();
     for (ip, macs) in &ip_to_macs {
         for mac in macs {
@@ -324,6 +418,24 @@ fn build_capture_context(state: &super::AppStateInner) -> CaptureContext {
             .insert(conn.dst_port);
     }
 
+    // ICMP echo fan-out and the targets that answered.
+    let mut per_source_echo_targets: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut per_source_echo_responders: HashMap<String, HashSet<String>> = HashMap::new();
+    for activity in &state.icmp_activity {
+        if !activity.echo_targets.is_empty() {
+            per_source_echo_targets.insert(
+                activity.ip_address.clone(),
+                activity.echo_targets.iter().cloned().collect(),
+            );
+        }
+        if !activity.responsive_targets.is_empty() {
+            per_source_echo_responders.insert(
+                activity.ip_address.clone(),
+                activity.responsive_targets.iter().cloned().collect(),
+            );
+        }
+    }
+
     // Write targets and write rates from Modbus deep parse.
     let mut per_source_write_targets: HashMap<String, HashSet<String>> = HashMap::new();
     let mut per_connection_write_rate: HashMap<(String, String), u64> = HashMap::new();
@@ -334,7 +446,7 @@ fn build_capture_context(state: &super::AppStateInner) -> CaptureContext {
                 let write_count: u64 = modbus
                     .function_codes
                     .iter()
-                    .filter(|fc| matches!(fc.code, 5 | 6 | 15 | 16))
+                    .filter(|fc| fc.is_write)
                     .map(|fc| fc.count)
                     .sum();
                 for rel in &modbus.relationships {
@@ -381,16 +493,73 @@ fn build_capture_context(state: &super::AppStateInner) -> CaptureContext {
         per_source_read_targets,
         per_source_write_targets,
         per_source_dst_ports,
+        per_source_echo_targets,
+        per_source_echo_responders,
         per_connection_write_rate,
         ot_device_ips,
         external_ips,
     }
 }
 
-/// Maximum findings returned by get_findings — nobody reads 50 000 findings.
-const MAX_FINDINGS: usize = 1_000;
-/// Maximum anomaly scores returned by get_anomalies.
-const MAX_ANOMALIES: usize = 500;
+/// Tag added to assets identified as honeypots.
+const HONEYPOT_TAG: &str = "honeypot";
+
+/// Collect the identity data the honeypot pass looks at for every asset.
+fn build_honeypot_profiles(inner: &super::AppStateInner) -> Vec<gm_analysis::HoneypotProfile> {
+    inner
+        .assets
+        .iter()
+        .map(|asset| {
+            let deep = inner.deep_parse_info.get(&asset.ip_address);
+            let mut server_protocols = Vec::new();
+            if let Some(deep) = deep {
+                let roles = [
+                    ("modbus", deep.modbus.as_ref().map(|d| &d.role)),
+                    ("s7comm", deep.s7.as_ref().map(|d| &d.role)),
+                    ("ethernet_ip", deep.enip.as_ref().map(|d| &d.role)),
+                    ("bacnet", deep.bacnet.as_ref().map(|d| &d.role)),
+                    ("fins", deep.fins.as_ref().map(|d| &d.role)),
+                    ("slmp", deep.slmp.as_ref().map(|d| &d.role)),
+                    ("ge_srtp", deep.ge_srtp.as_ref().map(|d| &d.role)),
+                    ("dnp3", deep.dnp3.as_ref().map(|d| &d.role)),
+                ];
+                for (protocol, role) in roles {
+                    let serving = role.is_some_and(|r| {
+                        matches!(
+                            r.as_str(),
+                            "slave" | "server" | "adapter" | "outstation" | "both"
+                        )
+                    });
+                    if serving {
+                        server_protocols.push(protocol.to_string());
+                    }
+                }
+            }
+            let modbus_id = deep
+                .and_then(|d| d.modbus.as_ref())
+                .and_then(|m| m.device_id.as_ref());
+            let snmp = deep.and_then(|d| d.snmp.as_ref());
+            gm_analysis::HoneypotProfile {
+                ip_address: asset.ip_address.clone(),
+                mac_address: asset.mac_address.clone(),
+                device_type: asset.device_type.clone(),
+                signature_names: asset
+                    .signature_matches
+                    .iter()
+                    .map(|m| m.signature_name.clone())
+                    .collect(),
+                server_protocols,
+                modbus_vendor: modbus_id.and_then(|id| id.vendor_name.clone()),
+                modbus_product_code: modbus_id.and_then(|id| id.product_code.clone()),
+                modbus_revision: modbus_id.and_then(|id| id.revision.clone()),
+                oui_vendor: asset.oui_vendor.clone(),
+                snmp_sys_descr: snmp.and_then(|s| s.sys_descr.clone()),
+                snmp_sys_name: snmp.and_then(|s| s.sys_name.clone()),
+                snmp_sys_location: snmp.and_then(|s| s.sys_location.clone()),
+            }
+        })
+        .collect()
+}
 
 /// Run the full security analysis pipeline.
 ///
@@ -399,13 +568,68 @@ const MAX_ANOMALIES: usize = 500;
 #[tauri::command]
 pub fn run_analysis(state: State<'_, AppState>) -> Result<AnalysisResult, String> {
     let mut state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    analyze(&mut state_inner)
+}
 
-    let input = build_analysis_input(&state_inner);
-    let ctx = build_capture_context(&state_inner);
-    let result = gm_analysis::run_full_analysis(&input, &ctx);
+/// Body of [`run_analysis`]: the stored results keep the real addresses,
+/// the returned copy is pseudonymized in demo mode.
+fn analyze(state_inner: &mut super::AppStateInner) -> Result<AnalysisResult, String> {
+    let input = build_analysis_input(state_inner);
+    let ctx = build_capture_context(state_inner);
+    let mut result = gm_analysis::run_full_analysis(&input, &ctx);
+
+    // Decommissioned / planned assets that show up in the traffic
+    let lifecycle: Vec<gm_analysis::LifecycleObservation> = state_inner
+        .assets
+        .iter()
+        .filter(|a| a.lifecycle != gm_analysis::AssetLifecycle::Active)
+        .map(|a| gm_analysis::LifecycleObservation {
+            ip_address: a.ip_address.clone(),
+            lifecycle: a.lifecycle,
+            changed_at: a.lifecycle_changed_at.clone(),
+            last_seen: a.last_seen.clone(),
+            packet_count: a.packet_count,
+        })
+        .collect();
+    let mut extra_findings = gm_analysis::detect_lifecycle_findings(&lifecycle);
+
+    // Deception hosts posing as PLCs
+    let honeypots = gm_analysis::detect_honeypots(&build_honeypot_profiles(state_inner));
+    extra_findings.extend(honeypots.iter().map(|v| v.finding()));
+
+    if !extra_findings.is_empty() {
+        result.findings.extend(extra_findings);
+        result
+            .findings
+            .sort_by_key(|f| std::cmp::Reverse(f.severity));
+    }
+
+    // Approved write paths silence their write findings until they expire
+    let approvals = load_write_approvals(state_inner)?;
+    let now = chrono::Utc::now();
+    let write_paths = gm_analysis::detect_write_paths(&input, &approvals, now);
+    gm_analysis::suppress_approved_findings(&mut result, &write_paths, &approvals, now);
+    gm_analysis::suppress_disabled_techniques(
+        &mut result,
+        &state_inner.settings.profile.analysis.disabled_techniques,
+    );
+
+    // Conditions still present since the last run keep their finding
+    let dedup_window = state_inner
+        .settings
+        .profile
+        .analysis
+        .finding_dedup_window_secs;
+    gm_analysis::merge_recurring_findings(
+        &state_inner.findings,
+        &mut result.findings,
+        chrono::Duration::seconds(dedup_window.min(i64::MAX as u64) as i64),
+        now,
+    );
 
     // Store results in AppState
     state_inner.findings = result.findings.clone();
+    state_inner.write_paths = write_paths;
     state_inner.purdue_assignments = result.purdue_assignments.clone();
     state_inner.anomalies = result.anomalies.clone();
 
@@ -418,6 +642,11 @@ pub fn run_analysis(state: State<'_, AppState>) -> Result<AnalysisResult, String
         .collect();
 
     for asset in &mut state_inner.assets {
+        if honeypots.iter().any(|v| v.ip_address == asset.ip_address)
+            && !asset.tags.iter().any(|t| t == HONEYPOT_TAG)
+        {
+            asset.tags.push(HONEYPOT_TAG.to_string());
+        }
         if asset.purdue_level.is_none() {
             if let Some(&level) = purdue_map.get(asset.ip_address.as_str()) {
                 asset.purdue_level = Some(level);
@@ -425,34 +654,61 @@ pub fn run_analysis(state: State<'_, AppState>) -> Result<AnalysisResult, String
         }
     }
 
+    if let Some(anon) = super::demo::active_anonymizer(state_inner) {
+        super::demo::anonymize_analysis(&anon, state_inner, &mut result);
+    }
     Ok(result)
 }
 
-/// Get findings from the last analysis run (capped at MAX_FINDINGS = 1 000).
+/// Get findings from the last analysis run, capped at the active
+/// profile's `max_findings` — nobody reads 50 000 findings.
 #[tauri::command]
 pub fn get_findings(state: State<'_, AppState>) -> Result<Vec<Finding>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    if state_inner.findings.len() <= MAX_FINDINGS {
-        return Ok(state_inner.findings.clone());
+    let max_findings = state_inner.settings.profile.analysis.max_findings;
+    let mut findings = if state_inner.findings.len() <= max_findings {
+        state_inner.findings.clone()
+    } else {
+        state_inner.findings[..max_findings].to_vec()
+    };
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for finding in &mut findings {
+            super::demo::anonymize_finding(&anon, &state_inner, finding);
+        }
     }
-    Ok(state_inner.findings[..MAX_FINDINGS].to_vec())
+    Ok(findings)
 }
 
 /// Get Purdue level assignments from the last analysis run.
 #[tauri::command]
 pub fn get_purdue_assignments(state: State<'_, AppState>) -> Result<Vec<PurdueAssignment>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    Ok(state_inner.purdue_assignments.clone())
+    let mut assignments = state_inner.purdue_assignments.clone();
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for assignment in &mut assignments {
+            super::demo::anonymize_purdue_assignment(&anon, &state_inner, assignment);
+        }
+    }
+    Ok(assignments)
 }
 
-/// Get anomaly scores from the last analysis run (capped at MAX_ANOMALIES = 500).
+/// Get anomaly scores from the last analysis run, capped at the active
+/// profile's `max_anomalies`.
 #[tauri::command]
 pub fn get_anomalies(state: State<'_, AppState>) -> Result<Vec<AnomalyScore>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    if state_inner.anomalies.len() <= MAX_ANOMALIES {
-        return Ok(state_inner.anomalies.clone());
+    let max_anomalies = state_inner.settings.profile.analysis.max_anomalies;
+    let mut anomalies = if state_inner.anomalies.len() <= max_anomalies {
+        state_inner.anomalies.clone()
+    } else {
+        state_inner.anomalies[..max_anomalies].to_vec()
+    };
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for anomaly in &mut anomalies {
+            super::demo::anonymize_anomaly(&anon, &state_inner, anomaly);
+        }
     }
-    Ok(state_inner.anomalies[..MAX_ANOMALIES].to_vec())
+    Ok(anomalies)
 }
 
 /// Get credential warnings for all discovered devices.
@@ -481,18 +737,55 @@ pub fn get_credential_warnings(
 }
 
 /// Assess criticality for all discovered assets.
+///
+/// Choke points in the topology (see `NodeCentrality`) are raised a level.
 #[tauri::command]
 pub fn get_criticality(state: State<'_, AppState>) -> Result<Vec<CriticalityAssessment>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
     let input = build_analysis_input(&state_inner);
-    Ok(gm_analysis::assess_criticality_all(&input.assets))
+    let mut assessments = gm_analysis::assess_criticality_all(&input.assets);
+
+    let choke_points: HashMap<String, String> = state_inner
+        .topology
+        .nodes
+        .iter()
+        .filter(|n| n.centrality.choke_point)
+        .map(|n| {
+            let c = &n.centrality;
+            let mut why = Vec::new();
+            if c.betweenness >= gm_topology::CHOKE_POINT_BETWEENNESS {
+                why.push(format!(
+                    "on {:.0}% of paths between other devices",
+                    c.betweenness * 100.0
+                ));
+            }
+            if c.fan_out >= gm_topology::CHOKE_POINT_FAN_OUT {
+                why.push(format!("talks to {} devices", c.fan_out));
+            }
+            (n.ip_address.clone(), why.join(", "))
+        })
+        .collect();
+    gm_analysis::raise_for_choke_points(&mut assessments, &choke_points);
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for assessment in &mut assessments {
+            assessment.ip_address = anon.ip(&assessment.ip_address);
+            assessment.reason = super::demo::scrub_text(&anon, &state_inner, &assessment.reason);
+        }
+    }
+    Ok(assessments)
 }
 
 /// Get naming suggestions for all discovered assets.
 #[tauri::command]
 pub fn get_naming_suggestions(state: State<'_, AppState>) -> Result<Vec<NamingSuggestion>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let input = build_analysis_input(&state_inner);
+    let mut input = build_analysis_input(&state_inner);
+    // Suggested names embed address octets, so derive them from the pseudonyms
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for asset in &mut input.assets {
+            asset.ip_address = anon.ip(&asset.ip_address);
+        }
+    }
     Ok(gm_analysis::suggest_names_all(&input.assets))
 }
 
@@ -578,7 +871,19 @@ pub fn get_switch_security_findings(
         default_cred_switch_ips,
     };
 
-    Ok(assess_switch_security(&input))
+    let mut findings = assess_switch_security(&input);
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for finding in &mut findings {
+            finding.title = super::demo::scrub_text(&anon, &state_inner, &finding.title);
+            finding.description =
+                super::demo::scrub_text(&anon, &state_inner, &finding.description);
+            finding.evidence = super::demo::scrub_text(&anon, &state_inner, &finding.evidence);
+            for ip in &mut finding.affected_assets {
+                *ip = anon.ip(ip);
+            }
+        }
+    }
+    Ok(findings)
 }
 
 /// Detect ICS malware behavioral patterns in the current capture.
@@ -645,7 +950,17 @@ pub fn get_malware_findings(state: State<'_, AppState>) -> Result<Vec<MalwareFin
         );
     }
 
-    Ok(detect_malware_patterns(&ctx, &connections, &deep_parse))
+    let mut findings = detect_malware_patterns(&ctx, &connections, &deep_parse);
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for finding in &mut findings {
+            finding.source_ip = anon.ip(&finding.source_ip);
+            for ip in &mut finding.target_ips {
+                *ip = anon.ip(ip);
+            }
+            finding.evidence = super::demo::scrub_text(&anon, &state_inner, &finding.evidence);
+        }
+    }
+    Ok(findings)
 }
 
 /// Get CVE warnings for a specific device based on its LLDP/SNMP identity.
@@ -655,6 +970,7 @@ pub fn get_malware_findings(state: State<'_, AppState>) -> Result<Vec<MalwareFin
 #[tauri::command]
 pub fn get_cve_warnings(ip: String, state: State<'_, AppState>) -> Result<Vec<CveMatch>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let ip = super::demo::resolve_ip(&state_inner, &ip);
 
     // Priority for vendor/model/firmware: LLDP > SNMP > asset info
     let dp = state_inner.deep_parse_info.get(&ip);
@@ -714,10 +1030,157 @@ pub fn get_compliance_report(
     }
 
     let input = build_analysis_input(&state_inner);
-    Ok(generate_compliance_report(
+    let mut report = generate_compliance_report(
         &state_inner.findings,
         &input.assets,
         &input.connections,
         &framework,
-    ))
+    );
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for mapping in &mut report {
+            mapping.evidence = super::demo::scrub_text(&anon, &state_inner, &mapping.evidence);
+        }
+    }
+    Ok(report)
+}
+
+// ─── Write Approvals ─────────────────────────────────────────
+
+/// Stored approvals in the form the analysis engine takes.
+///
+/// Without a database there is nothing to approve, so no approvals apply.
+fn load_write_approvals(state: &super::AppStateInner) -> Result<Vec<WriteApproval>, String> {
+    let Some(ref db) = state.db else {
+        return Ok(Vec::new());
+    };
+    let rows = db.list_write_approvals().map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|r| WriteApproval {
+            master_ip: r.master_ip,
+            slave_ip: r.slave_ip,
+            justification: r.justification,
+            approved_by: r.approved_by,
+            expires_at: r.expires_at,
+        })
+        .collect())
+}
+
+/// Get write-capable master → target paths from the last analysis run.
+///
+/// Unapproved paths come first, since they are the ones needing review.
+#[tauri::command]
+pub fn get_write_paths(state: State<'_, AppState>) -> Result<Vec<WritePath>, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let mut paths = state_inner.write_paths.clone();
+    paths.sort_by_key(|p| p.approval.is_some());
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for path in &mut paths {
+            path.master_ip = anon.ip(&path.master_ip);
+            path.slave_ip = anon.ip(&path.slave_ip);
+            if let Some(ref mut approval) = path.approval {
+                approval.master_ip = anon.ip(&approval.master_ip);
+                approval.slave_ip = anon.ip(&approval.slave_ip);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// List all stored write approvals, including expired ones.
+#[tauri::command]
+pub fn list_write_approvals(state: State<'_, AppState>) -> Result<Vec<WriteApprovalRow>, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = state_inner.db.as_ref().ok_or("Database not available")?;
+    let mut rows = db.list_write_approvals().map_err(|e| e.to_string())?;
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        for row in &mut rows {
+            row.master_ip = anon.ip(&row.master_ip);
+            row.slave_ip = anon.ip(&row.slave_ip);
+        }
+    }
+    Ok(rows)
+}
+
+/// Mark writes from `master_ip` to `slave_ip` as approved/expected.
+///
+/// Takes effect on the next analysis run. `expires_at` is an RFC 3339
+/// timestamp or a YYYY-MM-DD date; omit it for a standing approval.
+#[tauri::command]
+pub fn approve_write_path(
+    master_ip: String,
+    slave_ip: String,
+    justification: String,
+    approved_by: Option<String>,
+    expires_at: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<WriteApprovalRow, String> {
+    if justification.trim().is_empty() {
+        return Err("A justification is required to approve a write path".to_string());
+    }
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = state_inner.db.as_ref().ok_or("Database not available")?;
+    let input = WriteApprovalInput {
+        master_ip: super::demo::resolve_ip(&state_inner, &master_ip),
+        slave_ip: super::demo::resolve_ip(&state_inner, &slave_ip),
+        justification: justification.trim().to_string(),
+        approved_by: approved_by.unwrap_or_default(),
+        expires_at: expires_at.filter(|e| !e.trim().is_empty()),
+    };
+    let mut row = db
+        .upsert_write_approval(&input)
+        .map_err(|e| e.to_string())?;
+    log::info!(
+        "Approved write path {} -> {} (expires: {})",
+        row.master_ip,
+        row.slave_ip,
+        row.expires_at.as_deref().unwrap_or("never")
+    );
+    if let Some(anon) = super::demo::active_anonymizer(&state_inner) {
+        row.master_ip = anon.ip(&row.master_ip);
+        row.slave_ip = anon.ip(&row.slave_ip);
+    }
+    Ok(row)
+}
+
+/// Revoke a write approval; its findings return on the next analysis run.
+#[tauri::command]
+pub fn revoke_write_approval(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = state_inner.db.as_ref().ok_or("Database not available")?;
+    db.delete_write_approval(id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{enable_demo_mode, imported_state, tcp_packet};
+
+    /// Modbus/TCP write single register (unit 1, register 1 = 3); the
+    /// response echoes the request.
+    const MODBUS_WRITE: [u8; 12] = [
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x06, 0x00, 0x01, 0x00, 0x03,
+    ];
+
+    #[test]
+    fn test_run_analysis_pseudonymizes_in_demo_mode() {
+        let mut inner = imported_state(&[
+            tcp_packet("192.168.1.10", 49153, "192.168.1.20", 502, &MODBUS_WRITE),
+            tcp_packet("192.168.1.20", 502, "192.168.1.10", 49153, &MODBUS_WRITE),
+        ]);
+        enable_demo_mode(&mut inner);
+
+        let result = analyze(&mut inner).unwrap();
+        assert!(!result.purdue_assignments.is_empty());
+        let json = serde_json::to_string(&result).unwrap();
+        for ip in ["192.168.1.10", "192.168.1.20"] {
+            assert!(!json.contains(ip), "{ip} leaked: {json}");
+        }
+
+        // The stored results keep the real addresses
+        assert!(inner
+            .purdue_assignments
+            .iter()
+            .any(|a| a.ip_address == "192.168.1.20"));
+    }
 }
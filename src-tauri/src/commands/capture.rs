@@ -1,15 +1,39 @@
+#[cfg(feature = "gui")]
+use serde::Deserialize;
 use serde::Serialize;
-use std::collections::HashSet;
-use std::sync::atomic::Ordering;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "gui")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "gui")]
 use std::sync::mpsc;
+#[cfg(feature = "gui")]
 use std::thread::{self, JoinHandle};
+#[cfg(feature = "gui")]
 use std::time::{Duration, Instant};
+#[cfg(feature = "gui")]
 use tauri::{Emitter, Manager, State};
 
-use gm_capture::{CaptureError, LiveCaptureConfig, LiveCaptureHandle, ParsedPacket, PcapReader};
-
+#[cfg(feature = "gui")]
+use gm_capture::{
+    list_capture_files, CaptureError, ImportSampling, IpfixConfig, IpfixExporter,
+    LiveCaptureConfig, LiveCaptureHandle, ParsedPacket, PcapReader, QueueReceiver,
+};
+use gm_capture::{QueuePolicy, RemoteSource, RotationConfig};
+use gm_db::CaptureLimits;
+#[cfg(feature = "gui")]
+use gm_parsers::{identify_protocol, DeepParseSampling, IcsProtocol};
+#[cfg(feature = "gui")]
+use gm_topology::SubnetPlan;
+
+use super::physical::{apply_captured_arp, apply_captured_neighbors};
 use super::processor::PacketProcessor;
-use super::AppState;
+#[cfg(feature = "gui")]
+use super::session::SessionInfo;
+#[cfg(feature = "gui")]
+use super::trace::{PipelineTrace, TraceSummary, TRACE_LIMIT_BYTES};
+#[cfg(feature = "gui")]
+use super::{AppState, ConnectionInfo};
+use super::{AppStateInner, DeepParseInfo};
 
 /// Payload for a real-time ATT&CK alert emitted during live capture.
 #[derive(Debug, Clone, Serialize)]
@@ -25,6 +49,7 @@ pub struct LiveAttackAlert {
 
 // ─── PCAP Import ─────────────────────────────────────────────
 
+#[cfg(feature = "gui")]
 #[derive(Serialize)]
 pub struct ImportResult {
     pub file_count: usize,
@@ -34,16 +59,22 @@ pub struct ImportResult {
     pub protocols_detected: Vec<String>,
     pub duration_ms: u64,
     pub per_file: Vec<FileImportResult>,
+    /// Pipeline trace written for this import (trace mode only)
+    pub trace: Option<TraceSummary>,
 }
 
+#[cfg(feature = "gui")]
 #[derive(Serialize)]
 pub struct FileImportResult {
     pub filename: String,
     pub packet_count: usize,
     pub status: String,
+    /// Packets left out by the import sampling options
+    pub packets_filtered: u64,
 }
 
 /// Progress payload emitted as the `import_progress` event during PCAP import.
+#[cfg(feature = "gui")]
 #[derive(Serialize, Clone)]
 pub struct ImportProgressPayload {
     pub current_file: String,
@@ -61,9 +92,20 @@ pub struct ImportProgressPayload {
 /// Processing runs on a blocking thread so the Tauri async executor stays
 /// responsive. Progress is emitted as `import_progress` events roughly every
 /// 500ms. The import can be cancelled via the `cancel_import` command.
+///
+/// With `trace` set, a pipeline trace is written under
+/// `~/.kusanaginokajiki/traces/` (see [`super::trace`]). With `sampling`
+/// set, only packets in its time window, every Nth packet, and/or the
+/// first packets of each flow are imported. `clock_offsets` maps a path to
+/// seconds added to every timestamp in that file, so captures from sensors
+/// with skewed clocks line up on one timeline.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn import_pcap(
     paths: Vec<String>,
+    trace: Option<bool>,
+    sampling: Option<ImportSampling>,
+    clock_offsets: Option<HashMap<String, f64>>,
     state: State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<ImportResult, String> {
@@ -75,12 +117,37 @@ pub async fn import_pcap(
     let app_clone = app_handle.clone();
     let paths_clone = paths.clone();
     let file_count = paths.len();
+    let (port_map, subnets, bucket_secs) = {
+        let inner = state.inner.lock().map_err(|e| e.to_string())?;
+        (
+            super::system::port_map(&inner.settings.profile),
+            super::system::subnet_plan(&inner.settings.profile, &inner.physical_topology),
+            inner.settings.profile.traffic_bucket_secs,
+        )
+    };
+    let pipeline_trace = if trace.unwrap_or(false) {
+        Some(PipelineTrace::create(TRACE_LIMIT_BYTES)?)
+    } else {
+        None
+    };
 
     // Run packet streaming on a blocking thread — reading from a PCAP file is
     // synchronous I/O and must not block the Tauri async executor.
     let blocking_result = tauri::async_runtime::spawn_blocking(move || {
-        let reader = PcapReader::new();
-        let mut processor = PacketProcessor::new();
+        let mut reader = PcapReader::new()
+            .with_tcp_reassembly(super::system::tcp_reassembly_ports(&port_map))
+            .with_sampling(sampling.unwrap_or_default());
+        for (path, offset_secs) in clock_offsets.unwrap_or_default() {
+            if offset_secs.is_finite() && offset_secs != 0.0 {
+                let offset = chrono::Duration::nanoseconds((offset_secs * 1e9).round() as i64);
+                reader = reader.with_clock_offset(path, offset);
+            }
+        }
+        let mut processor = PacketProcessor::new()
+            .with_port_map(port_map)
+            .with_subnets(subnets)
+            .with_traffic_buckets(bucket_secs)
+            .with_trace(pipeline_trace);
         let mut per_file_results: Vec<FileImportResult> = Vec::new();
 
         for (file_idx, path) in paths_clone.iter().enumerate() {
@@ -88,55 +155,17 @@ pub async fn import_pcap(
                 return Err("Import cancelled by user".to_string());
             }
 
-            let filename = std::path::Path::new(path)
-                .file_name()
-                .map(|f| f.to_string_lossy().into_owned())
-                .unwrap_or_else(|| path.clone());
-
-            let stream_result = reader.stream_file(
+            match stream_into(
+                &reader,
+                &mut processor,
                 path,
-                // on_packet: process each packet immediately, no buffering
-                |packet| {
-                    processor.process_packet(packet);
-                },
-                // on_progress: emit Tauri event to frontend (throttled to ~2/sec)
-                |progress| {
-                    let _ = app_clone.emit(
-                        "import_progress",
-                        ImportProgressPayload {
-                            current_file: progress.current_file,
-                            file_index: file_idx,
-                            file_count,
-                            packets_processed: progress.packets_processed,
-                            bytes_processed: progress.bytes_processed,
-                            file_size: progress.file_size,
-                            progress_percent: progress.progress_percent,
-                            elapsed_secs: progress.elapsed_secs,
-                        },
-                    );
-                },
-                cancelled.as_ref(),
-            );
-
-            match stream_result {
-                Ok(stats) => {
-                    per_file_results.push(FileImportResult {
-                        filename,
-                        packet_count: stats.packet_count as usize,
-                        status: "ok".to_string(),
-                    });
-                }
-                Err(CaptureError::Cancelled) => {
-                    return Err("Import cancelled by user".to_string());
-                }
-                Err(e) => {
-                    log::warn!("Failed to read {}: {}", path, e);
-                    per_file_results.push(FileImportResult {
-                        filename,
-                        packet_count: 0,
-                        status: format!("error: {}", e),
-                    });
-                }
+                file_idx,
+                file_count,
+                &app_clone,
+                &cancelled,
+            ) {
+                Some(result) => per_file_results.push(result),
+                None => return Err("Import cancelled by user".to_string()),
             }
         }
 
@@ -156,14 +185,19 @@ pub async fn import_pcap(
     let deep_parse_info = processor.build_deep_parse_info();
 
     // Lock state to run signature matching (needs SignatureEngine + OUI + GeoIP)
-    let (assets, sig_results) = {
+    let (assets, sig_results, trace_summary) = {
         let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-        processor.build_assets(
+        let (mut assets, sig_results) = processor.build_assets(
             &state_inner.signature_engine,
             &deep_parse_info,
             &state_inner.oui_lookup,
             &state_inner.geoip_lookup,
-        )
+        );
+        let trace_summary =
+            processor.finish_trace(&state_inner.signature_engine, &assets, &deep_parse_info);
+        super::carry_over_lifecycle(&state_inner.assets, &mut assets);
+        super::carry_over_manual_hostnames(&state_inner.assets, &mut assets);
+        (assets, sig_results, trace_summary)
     };
 
     // Build topology enriched with signature data
@@ -185,8 +219,17 @@ pub async fn import_pcap(
 
     let connection_list = processor.get_connections();
     let packet_summaries = processor.get_packet_summaries();
+    let signature_packets = processor.signature_packets();
     let (connection_stats, pattern_anomalies) = processor.build_pattern_results();
+    let shift_activity = processor.build_shift_activity();
     let redundancy_protocols = processor.build_redundancy_info();
+    let neighbor_advertisements = processor.build_neighbor_advertisements();
+    let arp_bindings = processor.build_arp_bindings();
+    let icmp_activity = processor.build_icmp_activity();
+    let goose_streams = processor.build_goose_streams();
+    let sv_streams = processor.build_sv_streams();
+    let profinet_rt_streams = processor.build_profinet_rt_streams();
+    let protocol_sessions = processor.build_protocol_sessions();
     let asset_count = assets.len();
     let connection_count = connection_list.len();
     let protocols_detected = processor.get_protocols_detected();
@@ -199,13 +242,24 @@ pub async fn import_pcap(
 
     let mut state_inner = state.inner.lock().map_err(|e| e.to_string())?;
     state_inner.topology = topology;
+    super::merge_confirmed_devices(&mut state_inner);
     state_inner.assets = assets;
     state_inner.connections = connection_list;
     state_inner.packet_summaries = packet_summaries;
+    state_inner.signature_packets = signature_packets;
     state_inner.deep_parse_info = deep_parse_info;
     state_inner.connection_stats = connection_stats;
     state_inner.pattern_anomalies = pattern_anomalies;
+    state_inner.shift_activity = shift_activity;
     state_inner.redundancy_protocols = redundancy_protocols;
+    apply_captured_neighbors(&mut state_inner.physical_topology, &neighbor_advertisements);
+    apply_captured_arp(&mut state_inner.physical_topology, &arp_bindings);
+    state_inner.arp_bindings = arp_bindings;
+    state_inner.icmp_activity = icmp_activity;
+    state_inner.goose_streams = goose_streams;
+    state_inner.sv_streams = sv_streams;
+    state_inner.profinet_rt_streams = profinet_rt_streams;
+    state_inner.protocol_sessions = protocol_sessions;
     state_inner.imported_files.extend(imported_files);
     state_inner.imported_files.sort();
     state_inner.imported_files.dedup();
@@ -229,10 +283,335 @@ pub async fn import_pcap(
         protocols_detected,
         duration_ms,
         per_file: per_file_results,
+        trace: trace_summary,
+    })
+}
+
+/// Stream one capture file through `processor`, emitting `import_progress`
+/// events. Read errors are reported in the result; returns None if the
+/// import was cancelled.
+#[cfg(feature = "gui")]
+fn stream_into(
+    reader: &PcapReader,
+    processor: &mut PacketProcessor,
+    path: &str,
+    file_index: usize,
+    file_count: usize,
+    app: &tauri::AppHandle,
+    cancelled: &AtomicBool,
+) -> Option<FileImportResult> {
+    let filename = std::path::Path::new(path)
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string());
+
+    let stream_result = reader.stream_file(
+        path,
+        // on_packet: process each packet immediately, no buffering
+        |packet| {
+            processor.process_packet(packet);
+        },
+        // on_progress: emit Tauri event to frontend (throttled to ~2/sec)
+        |progress| {
+            let _ = app.emit(
+                "import_progress",
+                ImportProgressPayload {
+                    current_file: progress.current_file,
+                    file_index,
+                    file_count,
+                    packets_processed: progress.packets_processed,
+                    bytes_processed: progress.bytes_processed,
+                    file_size: progress.file_size,
+                    progress_percent: progress.progress_percent,
+                    elapsed_secs: progress.elapsed_secs,
+                },
+            );
+        },
+        cancelled,
+    );
+
+    match stream_result {
+        Ok(stats) => Some(FileImportResult {
+            filename,
+            packet_count: stats.packet_count as usize,
+            status: "ok".to_string(),
+            packets_filtered: stats.filtered,
+        }),
+        Err(CaptureError::Cancelled) => None,
+        Err(e) => {
+            log::warn!("Failed to read {}: {}", path, e);
+            Some(FileImportResult {
+                filename,
+                packet_count: 0,
+                status: format!("error: {}", e),
+                packets_filtered: 0,
+            })
+        }
+    }
+}
+
+// ─── Batch (Directory) Import ────────────────────────────────
+
+/// How a batch import turns a directory of captures into sessions.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchImportMode {
+    /// All files feed one pipeline; the result replaces the current state
+    /// and is saved as one session
+    Merged,
+    /// Each file is processed on its own and saved as its own session;
+    /// the current state is left untouched
+    PerFile,
+}
+
+/// One row of the batch import summary table.
+#[cfg(feature = "gui")]
+#[derive(Debug, Serialize)]
+pub struct BatchFileSummary {
+    pub filename: String,
+    pub packet_count: usize,
+    /// Devices seen in this file
+    pub asset_count: usize,
+    /// Connections seen in this file
+    pub connection_count: usize,
+    /// "ok" or "error: ..."
+    pub status: String,
+    /// Session the file was saved to (per-file mode)
+    pub session_id: Option<String>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Serialize)]
+pub struct BatchImportResult {
+    pub directory: String,
+    pub mode: BatchImportMode,
+    pub file_count: usize,
+    pub packet_count: usize,
+    pub duration_ms: u64,
+    pub files: Vec<BatchFileSummary>,
+    /// Sessions created: one in merged mode, one per readable file otherwise
+    pub sessions: Vec<SessionInfo>,
+}
+
+/// Import every capture file in a directory (rotated sensor captures, for
+/// example), either merged into one session or as one session per file.
+///
+/// Files are taken in capture order (see [`gm_capture::list_capture_files`]).
+/// Sessions are named `<session_name> — <file>` in per-file mode;
+/// `session_name` defaults to the directory name.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub async fn import_pcap_directory(
+    directory: String,
+    mode: BatchImportMode,
+    session_name: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BatchImportResult, String> {
+    let start = Instant::now();
+
+    let files = list_capture_files(&directory).map_err(|e| e.to_string())?;
+    if files.is_empty() {
+        return Err(format!("No capture files found in {}", directory));
+    }
+    let paths: Vec<String> = files
+        .iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect();
+    let base_name = session_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| {
+            std::path::Path::new(&directory)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| directory.clone())
+        });
+    let description = format!("Batch import of {} files from {}", paths.len(), directory);
+
+    let (files, sessions) = match mode {
+        BatchImportMode::Merged => {
+            let result = import_pcap(paths, None, None, None, state.clone(), app_handle).await?;
+            let inner = state.inner.lock().map_err(|e| e.to_string())?;
+            let files = result
+                .per_file
+                .into_iter()
+                .map(|file| merged_file_summary(file, &inner.connections))
+                .collect();
+            let session = super::session::store_session(
+                &inner,
+                &base_name,
+                &description,
+                &inner.assets,
+                &inner.connections,
+                &inner.deep_parse_info,
+                &inner.imported_files,
+            )?;
+            (files, vec![session])
+        }
+        BatchImportMode::PerFile => {
+            state.import_cancelled.store(false, Ordering::SeqCst);
+            let cancelled = state.import_cancelled.clone();
+            let (port_map, subnets, bucket_secs) = {
+                let inner = state.inner.lock().map_err(|e| e.to_string())?;
+                (
+                    super::system::port_map(&inner.settings.profile),
+                    super::system::subnet_plan(&inner.settings.profile, &inner.physical_topology),
+                    inner.settings.profile.traffic_bucket_secs,
+                )
+            };
+            tauri::async_runtime::spawn_blocking(move || {
+                import_per_file(
+                    &paths,
+                    &port_map,
+                    &subnets,
+                    bucket_secs,
+                    &base_name,
+                    &description,
+                    &app_handle,
+                    &cancelled,
+                )
+            })
+            .await
+            .map_err(|e| e.to_string())??
+        }
+    };
+
+    let packet_count = files.iter().map(|f| f.packet_count).sum();
+    let duration_ms = start.elapsed().as_millis() as u64;
+    log::info!(
+        "Batch import of {} ({:?}): {} files, {} packets, {} sessions in {}ms",
+        directory,
+        mode,
+        files.len(),
+        packet_count,
+        sessions.len(),
+        duration_ms
+    );
+
+    Ok(BatchImportResult {
+        directory,
+        mode,
+        file_count: files.len(),
+        packet_count,
+        duration_ms,
+        files,
+        sessions,
     })
 }
 
+/// Per-file counts for a merged import, from the connections that carry
+/// the file in their origin list.
+#[cfg(feature = "gui")]
+fn merged_file_summary(file: FileImportResult, connections: &[ConnectionInfo]) -> BatchFileSummary {
+    let mut ips: HashSet<&str> = HashSet::new();
+    let mut connection_count = 0;
+    for conn in connections
+        .iter()
+        .filter(|c| c.origin_files.contains(&file.filename))
+    {
+        connection_count += 1;
+        ips.insert(&conn.src_ip);
+        ips.insert(&conn.dst_ip);
+    }
+    BatchFileSummary {
+        filename: file.filename,
+        packet_count: file.packet_count,
+        asset_count: ips.len(),
+        connection_count,
+        status: file.status,
+        session_id: None,
+    }
+}
+
+/// Process each file through its own pipeline and save it as a session.
+/// Runs on a blocking thread; the state lock is only held while building
+/// assets and writing each session.
+#[cfg(feature = "gui")]
+#[allow(clippy::too_many_arguments)]
+fn import_per_file(
+    paths: &[String],
+    port_map: &HashMap<u16, IcsProtocol>,
+    subnets: &SubnetPlan,
+    bucket_secs: u32,
+    base_name: &str,
+    description: &str,
+    app: &tauri::AppHandle,
+    cancelled: &AtomicBool,
+) -> Result<(Vec<BatchFileSummary>, Vec<SessionInfo>), String> {
+    let reader =
+        PcapReader::new().with_tcp_reassembly(super::system::tcp_reassembly_ports(port_map));
+    let mut files = Vec::with_capacity(paths.len());
+    let mut sessions = Vec::new();
+
+    for (file_idx, path) in paths.iter().enumerate() {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("Import cancelled by user".to_string());
+        }
+        let mut processor = PacketProcessor::new()
+            .with_port_map(port_map.clone())
+            .with_subnets(subnets.clone())
+            .with_traffic_buckets(bucket_secs);
+        let file = stream_into(
+            &reader,
+            &mut processor,
+            path,
+            file_idx,
+            paths.len(),
+            app,
+            cancelled,
+        )
+        .ok_or("Import cancelled by user")?;
+
+        if file.status != "ok" || file.packet_count == 0 {
+            files.push(BatchFileSummary {
+                filename: file.filename,
+                packet_count: file.packet_count,
+                asset_count: 0,
+                connection_count: 0,
+                status: file.status,
+                session_id: None,
+            });
+            continue;
+        }
+
+        let deep_parse_info = processor.build_deep_parse_info();
+        let connections = processor.get_connections();
+        let state = app.state::<AppState>();
+        let inner = state.inner.lock().map_err(|e| e.to_string())?;
+        let (assets, _) = processor.build_assets(
+            &inner.signature_engine,
+            &deep_parse_info,
+            &inner.oui_lookup,
+            &inner.geoip_lookup,
+        );
+        let session = super::session::store_session(
+            &inner,
+            &format!("{} — {}", base_name, file.filename),
+            description,
+            &assets,
+            &connections,
+            &deep_parse_info,
+            std::slice::from_ref(&file.filename),
+        )?;
+
+        files.push(BatchFileSummary {
+            filename: file.filename,
+            packet_count: file.packet_count,
+            asset_count: assets.len(),
+            connection_count: connections.len(),
+            status: file.status,
+            session_id: Some(session.id.clone()),
+        });
+        sessions.push(session);
+    }
+
+    Ok((files, sessions))
+}
+
 /// Cancel an in-progress PCAP import. Safe to call even when no import is running.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn cancel_import(state: State<'_, AppState>) -> Result<(), String> {
     state.import_cancelled.store(true, Ordering::SeqCst);
@@ -243,6 +622,7 @@ pub async fn cancel_import(state: State<'_, AppState>) -> Result<(), String> {
 // ─── Live Capture ────────────────────────────────────────────
 
 /// Statistics emitted to the frontend during live capture.
+#[cfg(feature = "gui")]
 #[derive(Serialize, Clone)]
 pub struct CaptureStatsPayload {
     pub packets_captured: u64,
@@ -251,9 +631,103 @@ pub struct CaptureStatsPayload {
     pub active_connections: usize,
     pub asset_count: usize,
     pub elapsed_seconds: f64,
+    /// IPFIX flow records sent so far (0 when export is off)
+    pub flows_exported: u64,
+    /// Deep-parse parsed/skipped counters per budgeted protocol
+    pub deep_parse_sampling: Vec<DeepParseSampling>,
+    pub bytes_per_second: u64,
+    /// Packets dropped by the kernel because the capture fell behind
+    pub kernel_dropped: u64,
+    /// Packets dropped by the interface or driver, where reported
+    pub interface_dropped: u64,
+    /// Parsed packets waiting for the processing thread, and its capacity
+    pub queue_depth: usize,
+    pub queue_capacity: usize,
+    /// Packets discarded because processing fell behind capture
+    pub queue_dropped: u64,
+    /// Rates per identified protocol, busiest first
+    pub protocol_rates: Vec<ProtocolRate>,
+}
+
+/// Traffic rate of one protocol over the last rate window.
+#[cfg(feature = "gui")]
+#[derive(Serialize, Clone)]
+pub struct ProtocolRate {
+    /// Protocol name as returned by `IcsProtocol::to_name()`
+    pub protocol: String,
+    pub packets_per_second: u64,
+    pub bytes_per_second: u64,
+}
+
+/// Rates are recomputed at most this often, so short flushes don't jitter.
+#[cfg(feature = "gui")]
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Counters at the start of the current rate window and the rates from
+/// the last completed one.
+#[cfg(feature = "gui")]
+struct RateWindow {
+    started: Instant,
+    packets: u64,
+    bytes: u64,
+    protocols: HashMap<IcsProtocol, (u64, u64)>,
+    packets_per_second: u64,
+    bytes_per_second: u64,
+    protocol_rates: Vec<ProtocolRate>,
+}
+
+#[cfg(feature = "gui")]
+impl RateWindow {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            packets: 0,
+            bytes: 0,
+            protocols: HashMap::new(),
+            packets_per_second: 0,
+            bytes_per_second: 0,
+            protocol_rates: Vec::new(),
+        }
+    }
+
+    /// Recompute the rates if the window has run its length.
+    fn update(&mut self, packets: u64, bytes: u64, protocols: &HashMap<IcsProtocol, (u64, u64)>) {
+        let elapsed = self.started.elapsed();
+        if elapsed < RATE_WINDOW {
+            return;
+        }
+        let seconds = elapsed.as_secs_f64();
+        let rate = |now: u64, then: u64| (now.saturating_sub(then) as f64 / seconds) as u64;
+
+        self.packets_per_second = rate(packets, self.packets);
+        self.bytes_per_second = rate(bytes, self.bytes);
+        self.protocol_rates = protocols
+            .iter()
+            .map(|(protocol, &(packets, bytes))| {
+                let (prev_packets, prev_bytes) =
+                    self.protocols.get(protocol).copied().unwrap_or_default();
+                ProtocolRate {
+                    protocol: protocol.to_name().to_string(),
+                    packets_per_second: rate(packets, prev_packets),
+                    bytes_per_second: rate(bytes, prev_bytes),
+                }
+            })
+            .collect();
+        self.protocol_rates.sort_by(|a, b| {
+            b.bytes_per_second
+                .cmp(&a.bytes_per_second)
+                .then_with(|| a.protocol.cmp(&b.protocol))
+        });
+
+        self.started = Instant::now();
+        self.packets = packets;
+        self.bytes = bytes;
+        self.protocols.clone_from(protocols);
+    }
 }
 
 /// Result of stopping a capture.
+#[cfg(feature = "gui")]
 #[derive(Serialize)]
 pub struct StopCaptureResult {
     pub packets_captured: u64,
@@ -262,6 +736,68 @@ pub struct StopCaptureResult {
     pub pcap_saved: bool,
     pub pcap_path: Option<String>,
     pub packets_saved: usize,
+    /// Rotating capture files written during the session, oldest first
+    pub capture_files: Vec<String>,
+}
+
+/// Capture-to-disk settings for writing into `directory`, using the
+/// profile's rotation limits. Files are prefixed with the interface name.
+pub(crate) fn rotation_config(
+    limits: &CaptureLimits,
+    directory: &str,
+    interface: &str,
+) -> RotationConfig {
+    // Windows interface names look like \Device\NPF_{GUID}
+    let prefix: String = interface
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    RotationConfig {
+        directory: directory.into(),
+        file_prefix: prefix,
+        max_file_bytes: limits.rotate_file_mb * 1024 * 1024,
+        max_file_seconds: limits.rotate_file_minutes * 60,
+        max_files: limits.rotate_max_files,
+    }
+}
+
+/// The profile's kernel capture buffer size in bytes.
+pub(crate) fn buffer_size(limits: &CaptureLimits) -> i32 {
+    (limits.buffer_size_mb.min(super::system::MAX_BUFFER_SIZE_MB) * 1024 * 1024) as i32
+}
+
+/// The profile's policy for a full capture-to-processing queue.
+pub(crate) fn queue_policy(limits: &CaptureLimits) -> QueuePolicy {
+    match limits.queue_policy {
+        gm_db::QueuePolicy::DropOldest => QueuePolicy::DropOldest,
+        gm_db::QueuePolicy::Block => QueuePolicy::Block,
+    }
+}
+
+/// A capture "interface" given as an `ssh://` or `rpcap://` URL names an
+/// interface on a remote sensor rather than a local one.
+pub(crate) fn remote_source(interface: &str) -> Result<Option<RemoteSource>, String> {
+    if interface.contains("://") {
+        RemoteSource::parse(interface)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "gui")]
+fn path_strings(paths: Vec<std::path::PathBuf>) -> Vec<String> {
+    paths
+        .into_iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect()
 }
 
 /// Start a live packet capture on a network interface.
@@ -270,28 +806,74 @@ pub struct StopCaptureResult {
 /// Spawns a background capture thread and a processing thread that runs the
 /// full pipeline (protocol ID → deep parse → signatures → topology).
 /// Emits `capture-stats` events to the frontend at ~10 updates/sec.
+///
+/// With `ipfix_collector` ("host:port") set, flows seen on the interface are
+/// also exported as IPFIX over UDP, metering one packet in every
+/// `ipfix_sampling` (default 1 = unsampled).
+///
+/// With `capture_dir` set, every captured frame is also written to rotating
+/// PCAP files in that directory, using the active profile's rotation limits.
+///
+/// An `interface_name` of the form `ssh://[user@]host[:port]/interface` or
+/// `rpcap://host[:port]/interface` captures on a remote sensor instead.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn start_capture(
     interface_name: String,
     bpf_filter: Option<String>,
+    ipfix_collector: Option<String>,
+    ipfix_sampling: Option<u32>,
+    capture_dir: Option<String>,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
 ) -> Result<(), String> {
     // Check if a capture is already running
-    {
+    let (limits, reassembly_ports) = {
         let inner = state.inner.lock().map_err(|e| e.to_string())?;
         if inner.live_capture.is_some() {
             return Err("A capture is already running. Stop it first.".to_string());
         }
-    }
+        let port_map = super::system::port_map(&inner.settings.profile);
+        (
+            inner.settings.profile.capture.clone(),
+            super::system::tcp_reassembly_ports(&port_map),
+        )
+    };
+
+    // Open the flow exporter first so a bad collector address fails fast
+    let exporter = match ipfix_collector.filter(|c| !c.trim().is_empty()) {
+        Some(collector) => {
+            let mut ipfix = IpfixConfig::new(collector.trim());
+            ipfix.sampling_interval = ipfix_sampling.unwrap_or(1).max(1);
+            let exporter = IpfixExporter::new(&ipfix).map_err(|e| e.to_string())?;
+            log::info!(
+                "IPFIX export to {} (1:{} sampling)",
+                ipfix.collector,
+                ipfix.sampling_interval
+            );
+            Some(exporter)
+        }
+        None => None,
+    };
 
     // Configure and start the capture
     let config = LiveCaptureConfig {
         interface_name: interface_name.clone(),
+        remote: remote_source(&interface_name)?,
         bpf_filter: bpf_filter.clone(),
-        promiscuous: true,
-        ring_buffer_size: 1_000_000,
-        snaplen: 65535,
+        promiscuous: limits.promiscuous,
+        ring_buffer_size: limits.ring_buffer_size,
+        snaplen: limits.snaplen,
+        buffer_size: buffer_size(&limits),
+        monitor_mode: limits.monitor_mode,
+        rotation: capture_dir
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(|dir| rotation_config(&limits, dir, &interface_name)),
+        tcp_reassembly_ports: reassembly_ports,
+        queue_capacity: limits.queue_capacity,
+        queue_policy: queue_policy(&limits),
     };
 
     let (handle, rx) = LiveCaptureHandle::start(config).map_err(|e| e.to_string())?;
@@ -303,7 +885,7 @@ pub async fn start_capture(
     );
 
     // Spawn the processing thread
-    let processing_handle = spawn_processing_thread(rx, app);
+    let processing_handle = spawn_processing_thread(rx, exporter, app);
 
     // Store handles in app state
     let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -314,6 +896,7 @@ pub async fn start_capture(
 }
 
 /// Stop the live capture, optionally saving to a PCAP file.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn stop_capture(
     save_path: Option<String>,
@@ -354,10 +937,12 @@ pub async fn stop_capture(
     };
 
     log::info!(
-        "Live capture stopped: {} packets, {} bytes, {:.1}s",
+        "Live capture stopped: {} packets, {} bytes, {:.1}s, {} kernel drops, {} queue drops",
         stats.packets_captured,
         stats.bytes_captured,
-        stats.elapsed_seconds
+        stats.elapsed_seconds,
+        stats.kernel_dropped,
+        stats.queue_dropped
     );
 
     Ok(StopCaptureResult {
@@ -367,10 +952,12 @@ pub async fn stop_capture(
         pcap_saved,
         pcap_path,
         packets_saved,
+        capture_files: path_strings(handle.capture_files()),
     })
 }
 
 /// Pause the live capture (packets arriving while paused are not captured).
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn pause_capture(state: State<'_, AppState>) -> Result<(), String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -384,6 +971,7 @@ pub async fn pause_capture(state: State<'_, AppState>) -> Result<(), String> {
 }
 
 /// Resume a paused live capture.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn resume_capture(state: State<'_, AppState>) -> Result<(), String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -397,6 +985,7 @@ pub async fn resume_capture(state: State<'_, AppState>) -> Result<(), String> {
 }
 
 /// Get the current capture status.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn get_capture_status(state: State<'_, AppState>) -> Result<CaptureStatusInfo, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -408,6 +997,13 @@ pub async fn get_capture_status(state: State<'_, AppState>) -> Result<CaptureSta
             packets_captured: stats.packets_captured,
             bytes_captured: stats.bytes_captured,
             elapsed_seconds: stats.elapsed_seconds,
+            kernel_dropped: stats.kernel_dropped,
+            interface_dropped: stats.interface_dropped,
+            queue_depth: stats.queue_depth,
+            queue_capacity: stats.queue_capacity,
+            queue_peak_depth: stats.queue_peak_depth,
+            queue_dropped: stats.queue_dropped,
+            capture_files: path_strings(handle.capture_files()),
         })
     } else {
         Ok(CaptureStatusInfo {
@@ -416,10 +1012,18 @@ pub async fn get_capture_status(state: State<'_, AppState>) -> Result<CaptureSta
             packets_captured: 0,
             bytes_captured: 0,
             elapsed_seconds: 0.0,
+            kernel_dropped: 0,
+            interface_dropped: 0,
+            queue_depth: 0,
+            queue_capacity: 0,
+            queue_peak_depth: 0,
+            queue_dropped: 0,
+            capture_files: Vec::new(),
         })
     }
 }
 
+#[cfg(feature = "gui")]
 #[derive(Serialize)]
 pub struct CaptureStatusInfo {
     pub is_running: bool,
@@ -427,24 +1031,49 @@ pub struct CaptureStatusInfo {
     pub packets_captured: u64,
     pub bytes_captured: u64,
     pub elapsed_seconds: f64,
+    pub kernel_dropped: u64,
+    pub interface_dropped: u64,
+    /// Parsed packets waiting for the processing thread
+    pub queue_depth: usize,
+    pub queue_capacity: usize,
+    /// Highest queue depth reached during the capture
+    pub queue_peak_depth: usize,
+    /// Packets discarded because processing fell behind capture
+    pub queue_dropped: u64,
+    /// Rotating capture files on disk, oldest first
+    pub capture_files: Vec<String>,
 }
 
 // ─── Processing Thread ───────────────────────────────────────
 
 /// Spawn a background thread that receives parsed packets from the capture
 /// thread, processes them through the pipeline, updates AppState, and emits
-/// events to the frontend.
+/// events to the frontend. Flows are also fed to the IPFIX exporter, if any.
+#[cfg(feature = "gui")]
 fn spawn_processing_thread(
-    rx: mpsc::Receiver<ParsedPacket>,
+    rx: QueueReceiver<ParsedPacket>,
+    mut exporter: Option<IpfixExporter>,
     app: tauri::AppHandle,
 ) -> JoinHandle<()> {
     thread::spawn(move || {
         let state = app.state::<AppState>();
-        let mut processor = PacketProcessor::new();
+        let (port_map, budgets, subnets, bucket_secs) = match state.inner.lock() {
+            Ok(inner) => (
+                super::system::port_map(&inner.settings.profile),
+                super::system::deep_parse_budgets(&inner.settings.profile),
+                super::system::subnet_plan(&inner.settings.profile, &inner.physical_topology),
+                inner.settings.profile.traffic_bucket_secs,
+            ),
+            Err(_) => Default::default(),
+        };
+        let mut processor = PacketProcessor::new()
+            .with_port_map(port_map)
+            .with_deep_parse_budgets(budgets)
+            .with_subnets(subnets)
+            .with_traffic_buckets(bucket_secs);
         let mut batch: Vec<ParsedPacket> = Vec::new();
         let mut last_flush = Instant::now();
-        let mut prev_packet_count: u64 = 0;
-        let mut prev_stat_time = Instant::now();
+        let mut rates = RateWindow::new();
         let flush_interval = Duration::from_millis(100);
         // Track how many connections were checked to detect only new ones
         let mut alert_connection_watermark: usize = 0;
@@ -459,10 +1088,10 @@ fn spawn_processing_thread(
                         flush_batch(
                             &mut processor,
                             &mut batch,
+                            exporter.as_mut(),
                             &state,
                             &app,
-                            &mut prev_packet_count,
-                            &mut prev_stat_time,
+                            &mut rates,
                             &mut alert_connection_watermark,
                         );
                         last_flush = Instant::now();
@@ -474,13 +1103,18 @@ fn spawn_processing_thread(
                         flush_batch(
                             &mut processor,
                             &mut batch,
+                            exporter.as_mut(),
                             &state,
                             &app,
-                            &mut prev_packet_count,
-                            &mut prev_stat_time,
+                            &mut rates,
                             &mut alert_connection_watermark,
                         );
                         last_flush = Instant::now();
+                    } else if let Some(ref mut exporter) = exporter {
+                        // Quiet link: idle flows still need to reach the collector
+                        if let Err(e) = exporter.export_expired(chrono::Utc::now()) {
+                            log::warn!("IPFIX export failed: {}", e);
+                        }
                     }
                 }
                 Err(mpsc::RecvTimeoutError::Disconnected) => {
@@ -489,13 +1123,31 @@ fn spawn_processing_thread(
                         flush_batch(
                             &mut processor,
                             &mut batch,
+                            exporter.as_mut(),
                             &state,
                             &app,
-                            &mut prev_packet_count,
-                            &mut prev_stat_time,
+                            &mut rates,
                             &mut alert_connection_watermark,
                         );
                     }
+                    if let Some(ref mut exporter) = exporter {
+                        match exporter.finish() {
+                            Ok(_) => log::info!(
+                                "IPFIX export finished: {} flow records",
+                                exporter.records_exported()
+                            ),
+                            Err(e) => log::warn!("IPFIX export failed: {}", e),
+                        }
+                    }
+                    for s in processor.deep_parse_sampling() {
+                        log::info!(
+                            "Deep parse sampling for {}: {} parsed, {} skipped (budget {}/s)",
+                            s.protocol,
+                            s.parsed,
+                            s.skipped,
+                            s.max_per_second
+                        );
+                    }
                     log::info!("Processing thread exiting (capture stopped)");
                     break;
                 }
@@ -505,18 +1157,35 @@ fn spawn_processing_thread(
 }
 
 /// Process a batch of packets, update AppState, and emit events.
+#[cfg(feature = "gui")]
 fn flush_batch(
     processor: &mut PacketProcessor,
     batch: &mut Vec<ParsedPacket>,
+    mut exporter: Option<&mut IpfixExporter>,
     state: &AppState,
     app: &tauri::AppHandle,
-    prev_packet_count: &mut u64,
-    prev_stat_time: &mut Instant,
+    rates: &mut RateWindow,
     alert_connection_watermark: &mut usize,
 ) {
     // Process each packet through the pipeline
     for packet in batch.drain(..) {
         processor.process_packet(&packet);
+        if let Some(ref mut exporter) = exporter {
+            let application = match identify_protocol(&packet).protocol {
+                IcsProtocol::Unknown => "",
+                protocol => protocol.to_name(),
+            };
+            exporter.observe(&packet, application);
+        }
+    }
+
+    // Export flows that timed out; a collector hiccup must not stop the capture
+    let mut flows_exported = 0;
+    if let Some(exporter) = exporter {
+        if let Err(e) = exporter.export_expired(chrono::Utc::now()) {
+            log::warn!("IPFIX export failed: {}", e);
+        }
+        flows_exported = exporter.records_exported();
     }
 
     // Build deep parse info from accumulators
@@ -524,85 +1193,45 @@ fn flush_batch(
 
     // Lock state to run signature matching and update
     let update_result: Result<CaptureStatsPayload, String> = (|| {
-        let inner = state.inner.lock().map_err(|e| e.to_string())?;
-
-        // Run signature matching with OUI + GeoIP enrichment
-        let (assets, sig_results) = processor.build_assets(
-            &inner.signature_engine,
-            &deep_parse_info,
-            &inner.oui_lookup,
-            &inner.geoip_lookup,
-        );
-
-        // Build topology snapshot, enriched with signature data
-        let mut topology = processor.topo_builder.snapshot();
-        for node in &mut topology.nodes {
-            if let Some(sig_matches) = sig_results.get(&node.ip_address) {
-                if let Some(best) = sig_matches.first() {
-                    if let Some(ref v) = best.vendor {
-                        node.vendor = Some(v.clone());
-                    }
-                    if let Some(ref dt) = best.device_type {
-                        if best.confidence >= 3 {
-                            node.device_type = dt.clone();
-                        }
-                    }
-                }
-            }
-        }
-
-        let connections = processor.get_connections();
-        let packet_summaries = processor.get_packet_summaries();
-        let (connection_stats, pattern_anomalies) = processor.build_pattern_results();
-        let redundancy_protocols = processor.build_redundancy_info();
-        let asset_count = assets.len();
-        let connection_count = connections.len();
-        let total_packets = processor.total_packets;
-
-        // Drop the immutable borrow and get a mutable one
-        drop(inner);
         let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
-
-        inner.topology = topology;
-        inner.assets = assets;
-        inner.connections = connections;
-        inner.packet_summaries = packet_summaries;
-        inner.deep_parse_info = deep_parse_info;
-        inner.connection_stats = connection_stats;
-        inner.pattern_anomalies = pattern_anomalies;
-        inner.redundancy_protocols = redundancy_protocols;
-
-        // Compute PPS
-        let elapsed = prev_stat_time.elapsed().as_secs_f64();
-        let pps = if elapsed > 0.0 {
-            ((total_packets - *prev_packet_count) as f64 / elapsed) as u64
-        } else {
-            0
-        };
+        apply_snapshot(processor, deep_parse_info, &mut inner);
+        let asset_count = inner.assets.len();
+        let connection_count = inner.connections.len();
+        let total_packets = processor.total_packets;
 
         // Get capture stats from the live capture handle
-        let (bytes_captured, elapsed_seconds) = if let Some(ref handle) = inner.live_capture {
-            let stats = handle.stats();
-            (stats.bytes_captured, stats.elapsed_seconds)
-        } else {
-            (0, 0.0)
-        };
+        let stats = inner
+            .live_capture
+            .as_ref()
+            .map(|handle| handle.stats())
+            .unwrap_or_default();
+        rates.update(
+            total_packets,
+            stats.bytes_captured,
+            processor.protocol_traffic(),
+        );
 
         Ok(CaptureStatsPayload {
             packets_captured: total_packets,
-            packets_per_second: pps,
-            bytes_captured,
+            packets_per_second: rates.packets_per_second,
+            bytes_captured: stats.bytes_captured,
             active_connections: connection_count,
             asset_count,
-            elapsed_seconds,
+            elapsed_seconds: stats.elapsed_seconds,
+            flows_exported,
+            deep_parse_sampling: processor.deep_parse_sampling(),
+            bytes_per_second: rates.bytes_per_second,
+            kernel_dropped: stats.kernel_dropped,
+            interface_dropped: stats.interface_dropped,
+            queue_depth: stats.queue_depth,
+            queue_capacity: stats.queue_capacity,
+            queue_dropped: stats.queue_dropped,
+            protocol_rates: rates.protocol_rates.clone(),
         })
     })();
 
     match update_result {
         Ok(stats) => {
-            *prev_packet_count = stats.packets_captured;
-            *prev_stat_time = Instant::now();
-
             // Emit stats event to frontend
             if let Err(e) = app.emit("capture-stats", &stats) {
                 log::warn!("Failed to emit capture-stats event: {}", e);
@@ -619,6 +1248,76 @@ fn flush_batch(
     }
 }
 
+/// Replace the dataset in state with the processor's current view.
+///
+/// Runs signature matching with OUI + GeoIP enrichment and keeps analyst
+/// edits (lifecycle, manual hostnames) made to assets since the last update.
+pub(crate) fn apply_snapshot(
+    processor: &mut PacketProcessor,
+    deep_parse_info: HashMap<String, DeepParseInfo>,
+    inner: &mut AppStateInner,
+) {
+    let (mut assets, sig_results) = processor.build_assets(
+        &inner.signature_engine,
+        &deep_parse_info,
+        &inner.oui_lookup,
+        &inner.geoip_lookup,
+    );
+    super::carry_over_lifecycle(&inner.assets, &mut assets);
+    super::carry_over_manual_hostnames(&inner.assets, &mut assets);
+
+    // Build topology snapshot, enriched with signature data
+    let mut topology = processor.topo_builder.snapshot();
+    for node in &mut topology.nodes {
+        if let Some(sig_matches) = sig_results.get(&node.ip_address) {
+            if let Some(best) = sig_matches.first() {
+                if let Some(ref v) = best.vendor {
+                    node.vendor = Some(v.clone());
+                }
+                if let Some(ref dt) = best.device_type {
+                    if best.confidence >= 3 {
+                        node.device_type = dt.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    let connections = processor.get_connections();
+    let packet_summaries = processor.get_packet_summaries();
+    let signature_packets = processor.signature_packets();
+    let (connection_stats, pattern_anomalies) = processor.build_pattern_results();
+    let shift_activity = processor.build_shift_activity();
+    let redundancy_protocols = processor.build_redundancy_info();
+    let neighbor_advertisements = processor.build_neighbor_advertisements();
+    let arp_bindings = processor.build_arp_bindings();
+    let icmp_activity = processor.build_icmp_activity();
+    let goose_streams = processor.build_goose_streams();
+    let sv_streams = processor.build_sv_streams();
+    let profinet_rt_streams = processor.build_profinet_rt_streams();
+    let protocol_sessions = processor.build_protocol_sessions();
+
+    inner.topology = topology;
+    super::merge_confirmed_devices(inner);
+    inner.assets = assets;
+    inner.connections = connections;
+    inner.packet_summaries = packet_summaries;
+    inner.signature_packets = signature_packets;
+    inner.deep_parse_info = deep_parse_info;
+    inner.connection_stats = connection_stats;
+    inner.pattern_anomalies = pattern_anomalies;
+    inner.shift_activity = shift_activity;
+    inner.redundancy_protocols = redundancy_protocols;
+    apply_captured_neighbors(&mut inner.physical_topology, &neighbor_advertisements);
+    apply_captured_arp(&mut inner.physical_topology, &arp_bindings);
+    inner.arp_bindings = arp_bindings;
+    inner.icmp_activity = icmp_activity;
+    inner.goose_streams = goose_streams;
+    inner.sv_streams = sv_streams;
+    inner.profinet_rt_streams = profinet_rt_streams;
+    inner.protocol_sessions = protocol_sessions;
+}
+
 /// Check new connections against lightweight Group 1 ATT&CK rules.
 ///
 /// Only runs checks on connections added since the last call (using the
@@ -629,17 +1328,31 @@ fn flush_batch(
 /// - T0867: Lateral tool transfer (SMB/FTP) to OT device
 /// - T0868: Remote service (SSH/Telnet) to OT device
 /// - T0885: Web management UI (HTTP/HTTPS) to OT device
+#[cfg(feature = "gui")]
 fn run_live_attack_detection(state: &AppState, app: &tauri::AppHandle, watermark: &mut usize) {
-    let inner = match state.inner.lock() {
-        Ok(g) => g,
+    let alerts = match state.inner.lock() {
+        Ok(inner) => new_live_alerts(&inner, watermark),
         Err(_) => return,
     };
+    for alert in alerts {
+        if let Err(e) = app.emit("live_attack_alert", &alert) {
+            log::warn!("Failed to emit live_attack_alert: {}", e);
+        }
+    }
+}
 
+/// Run the lightweight ATT&CK rules over connections added since
+/// `watermark`, advancing it past them.
+pub(crate) fn new_live_alerts(
+    inner: &AppStateInner,
+    watermark: &mut usize,
+) -> Vec<LiveAttackAlert> {
     let connections = &inner.connections;
+    let mut alerts = Vec::new();
     if connections.len() <= *watermark {
         // No new connections
         *watermark = connections.len();
-        return;
+        return alerts;
     }
 
     // Build OT device IP set from asset inventory
@@ -671,14 +1384,11 @@ fn run_live_attack_detection(state: &AppState, app: &tauri::AppHandle, watermark
             conn.src_port
         };
 
-        if let Some(alert) = check_connection_for_live_alert(target_ip, port, conn, &now) {
-            if let Err(e) = app.emit("live_attack_alert", &alert) {
-                log::warn!("Failed to emit live_attack_alert: {}", e);
-            }
-        }
+        alerts.extend(check_connection_for_live_alert(target_ip, port, conn, &now));
     }
 
     *watermark = connections.len();
+    alerts
 }
 
 /// Check a single connection for live ATT&CK alert conditions.
@@ -764,6 +1474,6 @@ fn check_connection_for_live_alert(
 fn is_ot_device_type(device_type: &str) -> bool {
     matches!(
         device_type,
-        "plc" | "rtu" | "hmi" | "scada_server" | "historian"
+        "plc" | "rtu" | "ied" | "hmi" | "scada_server" | "historian"
     )
 }
@@ -0,0 +1,131 @@
+//! External connectivity inventory.
+//!
+//! Lists the outbound connections from plant assets to cloud IoT platforms,
+//! vendor clouds, remote-access brokers and OPC UA reverse-connect clients
+//! that `PacketProcessor` recognised from TLS SNI, MQTT CONNECT and OPC UA
+//! ReverseHello messages. Unlike the generic public-IP findings, every row
+//! names the service on the other end.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tauri::State;
+
+use gm_db::GeoIpLookup;
+use gm_parsers::CloudCategory;
+
+use super::{AppState, AppStateInner};
+
+/// One local asset → external service connection.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalConnection {
+    pub local_ip: String,
+    pub local_hostname: Option<String>,
+    /// Device type of the local asset ("it_device" for non-OT hosts)
+    pub local_device_type: String,
+    pub remote_ip: String,
+    pub remote_port: u16,
+    /// Host name from TLS SNI or the MQTT username, if seen
+    pub remote_hostname: Option<String>,
+    /// ISO 3166-1 alpha-2 country of the remote address (public IPs only)
+    pub remote_country: Option<String>,
+    pub remote_is_public: bool,
+    pub service: String,
+    pub category: CloudCategory,
+    pub evidence: String,
+    /// MQTT client ID or OPC UA ServerUri announced by the local asset
+    pub identity: Option<String>,
+    pub packet_count: u64,
+    pub byte_count: u64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+/// Flatten the per-device cloud endpoints into one row per connection,
+/// with traffic totals taken from the connection table (both directions).
+pub(crate) fn external_connections(inner: &AppStateInner) -> Vec<ExternalConnection> {
+    let assets: HashMap<&str, _> = inner
+        .assets
+        .iter()
+        .map(|a| (a.ip_address.as_str(), a))
+        .collect();
+
+    let mut rows = Vec::new();
+    for (local_ip, info) in &inner.deep_parse_info {
+        let Some(cloud) = &info.cloud else {
+            continue;
+        };
+        let asset = assets.get(local_ip.as_str());
+        for endpoint in &cloud.endpoints {
+            let mut packet_count = 0;
+            let mut byte_count = 0;
+            let mut first_seen = endpoint.first_seen.clone();
+            let mut last_seen = endpoint.first_seen.clone();
+            for conn in &inner.connections {
+                let outbound = conn.src_ip == *local_ip
+                    && conn.dst_ip == endpoint.remote_ip
+                    && conn.dst_port == endpoint.remote_port;
+                let inbound = conn.dst_ip == *local_ip
+                    && conn.src_ip == endpoint.remote_ip
+                    && conn.src_port == endpoint.remote_port;
+                if !outbound && !inbound {
+                    continue;
+                }
+                packet_count += conn.packet_count;
+                byte_count += conn.byte_count;
+                if conn.first_seen < first_seen {
+                    first_seen = conn.first_seen.clone();
+                }
+                if conn.last_seen > last_seen {
+                    last_seen = conn.last_seen.clone();
+                }
+            }
+
+            rows.push(ExternalConnection {
+                local_ip: local_ip.clone(),
+                local_hostname: asset.and_then(|a| a.hostname.clone()),
+                local_device_type: asset
+                    .map(|a| a.device_type.clone())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                remote_ip: endpoint.remote_ip.clone(),
+                remote_port: endpoint.remote_port,
+                remote_hostname: endpoint.hostname.clone(),
+                remote_country: inner.geoip_lookup.lookup_country(&endpoint.remote_ip),
+                remote_is_public: GeoIpLookup::is_public_ip(&endpoint.remote_ip),
+                service: endpoint.service.clone(),
+                category: endpoint.category,
+                evidence: endpoint.evidence.clone(),
+                identity: endpoint.identity.clone(),
+                packet_count,
+                byte_count,
+                first_seen,
+                last_seen,
+            });
+        }
+    }
+    rows.sort_by(|a, b| {
+        a.local_ip
+            .cmp(&b.local_ip)
+            .then_with(|| a.first_seen.cmp(&b.first_seen))
+    });
+    rows
+}
+
+/// Get outbound cloud / remote-access connections for the current dataset.
+#[tauri::command]
+pub fn get_external_connectivity(
+    state: State<'_, AppState>,
+) -> Result<Vec<ExternalConnection>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let mut rows = external_connections(&inner);
+    if let Some(anon) = super::demo::active_anonymizer(&inner) {
+        for row in &mut rows {
+            row.local_ip = anon.ip(&row.local_ip);
+            row.local_hostname = row.local_hostname.as_deref().map(|h| anon.hostname(h));
+            row.remote_ip = anon.ip(&row.remote_ip);
+            row.remote_hostname = row.remote_hostname.as_deref().map(|h| anon.hostname(h));
+            row.identity = row.identity.as_deref().map(|h| anon.hostname(h));
+        }
+    }
+    Ok(rows)
+}
@@ -55,6 +55,7 @@ pub async fn get_correlated_alerts(
             .cmp(&b.severity)
             .then(b.timestamp.cmp(&a.timestamp))
     });
+    anonymize_alerts(&inner, &mut alerts);
     Ok(alerts)
 }
 
@@ -65,6 +66,7 @@ pub async fn get_alerts_for_ip(
     state: State<'_, AppState>,
 ) -> Result<Vec<CorrelatedAlert>, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let ip = super::demo::resolve_ip(&inner, &ip);
     let mut alerts: Vec<CorrelatedAlert> = inner
         .imported_alerts
         .iter()
@@ -76,6 +78,7 @@ pub async fn get_alerts_for_ip(
             .cmp(&b.severity)
             .then(b.timestamp.cmp(&a.timestamp))
     });
+    anonymize_alerts(&inner, &mut alerts);
     Ok(alerts)
 }
 
@@ -115,6 +118,21 @@ fn correlate_alert(alert: &StoredAlert, inner: &AppStateInner) -> CorrelatedAler
     }
 }
 
+/// Pseudonymize alert endpoints, their hostnames, and any addresses in the
+/// rule text when demo mode is on.
+fn anonymize_alerts(inner: &AppStateInner, alerts: &mut [CorrelatedAlert]) {
+    let Some(anon) = super::demo::active_anonymizer(inner) else {
+        return;
+    };
+    for alert in alerts {
+        alert.src_ip = anon.ip(&alert.src_ip);
+        alert.dst_ip = anon.ip(&alert.dst_ip);
+        alert.src_hostname = alert.src_hostname.as_deref().map(|h| anon.hostname(h));
+        alert.dst_hostname = alert.dst_hostname.as_deref().map(|h| anon.hostname(h));
+        alert.signature = super::demo::scrub_text(&anon, inner, &alert.signature);
+    }
+}
+
 /// Look up a device by IP in the asset inventory.
 /// Returns (hostname, device_type, purdue_level).
 fn lookup_device(ip: &str, inner: &AppStateInner) -> (Option<String>, Option<String>, Option<u8>) {
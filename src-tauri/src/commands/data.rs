@@ -2,17 +2,26 @@ use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 use tauri::State;
 
+use super::demo::{
+    active_anonymizer, anonymize_asset, anonymize_connection, anonymize_deep_parse,
+    anonymize_packet_summary, anonymize_topology, resolve_ip,
+};
 use super::{
-    AppState, AssetInfo, ConnectionInfo, DeepParseInfo, FunctionCodeStat, PacketSummary,
-    ProtocolStatInfo,
+    merge_confirmed_devices, AppState, AppStateInner, AssetInfo, ConnectionInfo, DeepParseInfo,
+    FunctionCodeStat, ModbusRegisterMap, PacketSummary, ProtocolStatInfo,
+};
+use gm_parsers::FunctionCodeProtocol;
+use gm_topology::{
+    EdgeTraffic, Reachability, ReachableNode, TopologyGraph, TopologyPath, VlanGroup,
 };
-use gm_topology::TopologyGraph;
 
 /// Maximum nodes returned by get_topology. Excess nodes (by packet count) are
 /// dropped to prevent the webview from being asked to render a massive graph.
 const MAX_TOPOLOGY_NODES: usize = 5_000;
 /// Maximum edges returned by get_topology.
 const MAX_TOPOLOGY_EDGES: usize = 20_000;
+/// Edges returned by get_top_talkers when no limit is given.
+const DEFAULT_TOP_TALKERS: usize = 20;
 
 /// Get the current network topology graph for visualization.
 ///
@@ -23,10 +32,120 @@ const MAX_TOPOLOGY_EDGES: usize = 20_000;
 #[tauri::command]
 pub fn get_topology(state: State<'_, AppState>) -> Result<TopologyGraph, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let topo = &state_inner.topology;
+    Ok(served_topology(&state_inner, &state_inner.topology))
+}
+
+/// Get the part of the topology active between two RFC 3339 times, for the
+/// timeline scrubber. Capped the same way as `get_topology`.
+#[tauri::command]
+pub fn get_topology_window(
+    start: String,
+    end: String,
+    state: State<'_, AppState>,
+) -> Result<TopologyGraph, String> {
+    let parse = |t: &str| {
+        chrono::DateTime::parse_from_rfc3339(t)
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .map_err(|e| format!("Invalid time '{}': {}", t, e))
+    };
+    let (start, end) = (parse(&start)?, parse(&end)?);
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let window = state_inner.topology.between(start, end);
+    Ok(served_topology(&state_inner, &window))
+}
+
+/// Confirm that the given addresses belong to one device (a host with
+/// several NICs) and merge them into one topology node. The grouping is
+/// kept and applied again whenever the topology is rebuilt. Returns the
+/// updated topology.
+#[tauri::command]
+pub fn merge_topology_devices(
+    ips: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<TopologyGraph, String> {
+    if ips.len() < 2 {
+        return Err("At least two addresses are needed to merge devices".to_string());
+    }
+    let mut state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let ips = ips.iter().map(|ip| resolve_ip(&state_inner, ip)).collect();
+    state_inner.device_identities.push(ips);
+    merge_confirmed_devices(&mut state_inner);
+    Ok(served_topology(&state_inner, &state_inner.topology))
+}
+
+/// Topology nodes grouped by VLAN, with the switch configuration's VLAN
+/// names, for layouts that cluster by VLAN rather than by subnet.
+#[tauri::command]
+pub fn get_vlan_groups(state: State<'_, AppState>) -> Result<Vec<VlanGroup>, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let vlans = super::physical::vlan_map(&state_inner);
+    let mut graph = state_inner.topology.clone();
+    graph.assign_vlans(&vlans);
+    let mut groups = graph.vlan_groups(&vlans);
+    if let Some(anon) = active_anonymizer(&state_inner) {
+        for group in &mut groups {
+            for id in &mut group.node_ids {
+                *id = anon.ip(id);
+            }
+        }
+    }
+    Ok(groups)
+}
 
+/// The topology edges carrying the most bytes, with their traffic over time
+/// and the buckets that stand out as bursts, for top-talker charts.
+#[tauri::command]
+pub fn get_top_talkers(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<EdgeTraffic>, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let mut talkers = state_inner
+        .topology
+        .top_talkers(limit.unwrap_or(DEFAULT_TOP_TALKERS));
+    if let Some(anon) = active_anonymizer(&state_inner) {
+        for talker in &mut talkers {
+            talker.source = anon.ip(&talker.source);
+            talker.target = anon.ip(&talker.target);
+        }
+    }
+    Ok(talkers)
+}
+
+/// A topology as the frontend gets it: capped, with VLAN membership filled
+/// in from the current assets and switch data, laid out in Purdue bands,
+/// and pseudonymized in demo mode.
+fn served_topology(inner: &AppStateInner, topo: &TopologyGraph) -> TopologyGraph {
+    let mut graph = capped_topology(topo);
+    graph.assign_vlans(&super::physical::vlan_map(inner));
+    graph.assign_purdue_layout(&purdue_levels(inner));
+    if let Some(anon) = active_anonymizer(inner) {
+        anonymize_topology(&anon, &mut graph);
+    }
+    graph
+}
+
+/// Purdue level of each address: the asset's own level (set by the analyst
+/// or applied from the last analysis), else the analysis assignment.
+fn purdue_levels(inner: &AppStateInner) -> HashMap<String, u8> {
+    let mut levels: HashMap<String, u8> = inner
+        .purdue_assignments
+        .iter()
+        .map(|a| (a.ip_address.clone(), a.level))
+        .collect();
+    for asset in &inner.assets {
+        if let Some(level) = asset.purdue_level {
+            levels.insert(asset.ip_address.clone(), level);
+        }
+    }
+    levels
+}
+
+/// Cap a topology to MAX_TOPOLOGY_NODES / MAX_TOPOLOGY_EDGES, keeping the
+/// highest-traffic nodes and edges.
+fn capped_topology(topo: &TopologyGraph) -> TopologyGraph {
     if topo.nodes.len() <= MAX_TOPOLOGY_NODES && topo.edges.len() <= MAX_TOPOLOGY_EDGES {
-        return Ok(topo.clone());
+        return topo.clone();
     }
 
     // Cap nodes: keep the highest-traffic devices.
@@ -47,7 +166,54 @@ pub fn get_topology(state: State<'_, AppState>) -> Result<TopologyGraph, String>
     edges.sort_by(|a, b| b.packet_count.cmp(&a.packet_count));
     edges.truncate(MAX_TOPOLOGY_EDGES);
 
-    Ok(TopologyGraph { nodes, edges })
+    TopologyGraph { nodes, edges }
+}
+
+/// Find the shortest chain of observed connections from one device to
+/// another ("how can the corporate laptop reach this PLC"). Returns None
+/// when no such chain exists.
+///
+/// Runs on the full topology, not the capped graph `get_topology` returns.
+#[tauri::command]
+pub fn find_topology_path(
+    src_ip: String,
+    dst_ip: String,
+    state: State<'_, AppState>,
+) -> Result<Option<TopologyPath>, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let src_ip = resolve_ip(&state_inner, &src_ip);
+    let dst_ip = resolve_ip(&state_inner, &dst_ip);
+    let mut path = state_inner.topology.shortest_path(&src_ip, &dst_ip);
+    if let (Some(path), Some(anon)) = (path.as_mut(), active_anonymizer(&state_inner)) {
+        for node in &mut path.nodes {
+            *node = anon.ip(node);
+        }
+        for hop in &mut path.hops {
+            hop.from = anon.ip(&hop.from);
+            hop.to = anon.ip(&hop.to);
+        }
+    }
+    Ok(path)
+}
+
+/// List the devices reachable from `ip` over observed connections
+/// (`direction` "from"), or the devices that can reach it ("to"), with the
+/// number of hops each is away.
+#[tauri::command]
+pub fn get_reachability(
+    ip: String,
+    direction: Reachability,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReachableNode>, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let ip = resolve_ip(&state_inner, &ip);
+    let mut nodes = state_inner.topology.reachable(&ip, direction);
+    if let Some(anon) = active_anonymizer(&state_inner) {
+        for node in &mut nodes {
+            node.id = anon.ip(&node.id);
+        }
+    }
+    Ok(nodes)
 }
 
 // ─── Paginated data responses ──────────────────────────────────
@@ -116,11 +282,16 @@ pub fn get_assets(
 
     // Paginate
     let start = page * page_size;
-    let assets = if start < total {
+    let mut assets: Vec<AssetInfo> = if start < total {
         all_assets.into_iter().skip(start).take(page_size).collect()
     } else {
         Vec::new()
     };
+    if let Some(anon) = active_anonymizer(&state_inner) {
+        for asset in &mut assets {
+            anonymize_asset(&anon, asset);
+        }
+    }
     let has_more = start + page_size < total;
 
     Ok(AssetPage {
@@ -164,7 +335,7 @@ pub fn get_connections(
     }
 
     let start = page * page_size;
-    let connections = if start < total {
+    let mut connections: Vec<ConnectionInfo> = if start < total {
         all_connections
             .into_iter()
             .skip(start)
@@ -173,6 +344,11 @@ pub fn get_connections(
     } else {
         Vec::new()
     };
+    if let Some(anon) = active_anonymizer(&state_inner) {
+        for conn in &mut connections {
+            anonymize_connection(&anon, conn);
+        }
+    }
     let has_more = start + page_size < total;
 
     Ok(ConnectionPage {
@@ -250,11 +426,17 @@ pub fn get_connection_packets(
     state: State<'_, AppState>,
 ) -> Result<Vec<PacketSummary>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    Ok(state_inner
+    let mut summaries = state_inner
         .packet_summaries
         .get(&connection_id)
         .cloned()
-        .unwrap_or_default())
+        .unwrap_or_default();
+    if let Some(anon) = active_anonymizer(&state_inner) {
+        for summary in &mut summaries {
+            anonymize_packet_summary(&anon, summary);
+        }
+    }
+    Ok(summaries)
 }
 
 /// Get deep parse information for a specific device by IP address.
@@ -267,7 +449,37 @@ pub fn get_deep_parse_info(
     state: State<'_, AppState>,
 ) -> Result<Option<DeepParseInfo>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    Ok(state_inner.deep_parse_info.get(&ip_address).cloned())
+    let ip_address = resolve_ip(&state_inner, &ip_address);
+    let mut info = state_inner.deep_parse_info.get(&ip_address).cloned();
+    if let (Some(info), Some(anon)) = (info.as_mut(), active_anonymizer(&state_inner)) {
+        anonymize_deep_parse(&anon, &state_inner, info);
+    }
+    Ok(info)
+}
+
+/// Get the register address-space map of one unit ID on a Modbus slave.
+///
+/// Regions are the addresses masters read or wrote, split wherever their
+/// requests overlap, with read and write counts. Returns None when no
+/// register requests to that unit were seen.
+#[tauri::command]
+pub fn get_register_map(
+    ip: String,
+    unit_id: u8,
+    state: State<'_, AppState>,
+) -> Result<Option<ModbusRegisterMap>, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let ip = resolve_ip(&state_inner, &ip);
+    let mut map = state_inner
+        .deep_parse_info
+        .get(&ip)
+        .and_then(|info| info.modbus.as_ref())
+        .and_then(|modbus| modbus.register_maps.iter().find(|m| m.unit_id == unit_id))
+        .cloned();
+    if let (Some(map), Some(anon)) = (map.as_mut(), active_anonymizer(&state_inner)) {
+        map.masters = map.masters.iter().map(|m| anon.ip(m)).collect();
+    }
+    Ok(map)
 }
 
 /// Get function code distribution across all protocols.
@@ -301,11 +513,8 @@ pub fn get_function_code_stats(
     if !modbus_fcs.is_empty() {
         let mut fcs: Vec<FunctionCodeStat> = modbus_fcs
             .into_iter()
-            .map(|(code, count)| FunctionCodeStat {
-                code,
-                name: gm_parsers::modbus_function_code_name(code).to_string(),
-                count,
-                is_write: matches!(code, 5 | 6 | 15 | 16 | 22 | 23),
+            .map(|(code, count)| {
+                FunctionCodeStat::from_registry(FunctionCodeProtocol::Modbus, code, count)
             })
             .collect();
         fcs.sort_by(|a, b| b.count.cmp(&a.count));
@@ -315,11 +524,8 @@ pub fn get_function_code_stats(
     if !dnp3_fcs.is_empty() {
         let mut fcs: Vec<FunctionCodeStat> = dnp3_fcs
             .into_iter()
-            .map(|(code, count)| FunctionCodeStat {
-                code,
-                name: gm_parsers::dnp3_function_code_name(code).to_string(),
-                count,
-                is_write: matches!(code, 2..=6),
+            .map(|(code, count)| {
+                FunctionCodeStat::from_registry(FunctionCodeProtocol::Dnp3, code, count)
             })
             .collect();
         fcs.sort_by(|a, b| b.count.cmp(&a.count));
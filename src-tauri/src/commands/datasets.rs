@@ -0,0 +1,112 @@
+//! Bundled reference data (OUI, GeoIP, signatures) loading and status.
+//!
+//! Datasets ship as Tauri resources and are listed with their SHA-256 in
+//! `data/manifest.json`, which is compiled into the binary. At startup each
+//! dataset is located (bundle resource directory first, then the dev-tree
+//! paths), verified, and loaded; the per-dataset outcome is kept in state so
+//! the Settings view can show what is actually in use.
+
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "gui")]
+use tauri::State;
+
+use gm_db::datasets;
+use gm_db::{DataManifest, DatasetKind, DatasetState, DatasetStatus, GeoIpLookup, OuiLookup};
+use gm_signatures::SignatureEngine;
+
+#[cfg(feature = "gui")]
+use super::AppState;
+
+/// Manifest of bundled datasets and their expected checksums.
+const BUNDLED_MANIFEST: &str = include_str!("../../data/manifest.json");
+
+/// Reference data loaded at startup.
+pub(crate) struct BundledData {
+    pub signature_engine: SignatureEngine,
+    pub oui_lookup: OuiLookup,
+    pub geoip_lookup: GeoIpLookup,
+    pub status: Vec<DatasetStatus>,
+}
+
+/// Candidate roots that may contain `data/` and `signatures/`.
+fn data_roots(resource_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(dir) = resource_dir {
+        roots.push(dir.to_path_buf());
+    }
+    // Dev: CWD is src-tauri/ under `cargo run`, the project root under `cargo tauri dev`
+    roots.push(PathBuf::from("."));
+    roots.push(PathBuf::from("../src-tauri"));
+    roots.push(PathBuf::from("src-tauri"));
+    roots
+}
+
+/// Locate, verify, and load every dataset in the bundled manifest.
+///
+/// Never fails: a dataset that is missing or unreadable leaves its lookup
+/// empty and is reported through [`BundledData::status`].
+pub(crate) fn load_bundled_data(resource_dir: Option<&Path>) -> BundledData {
+    let manifest =
+        DataManifest::from_json(BUNDLED_MANIFEST).expect("bundled data/manifest.json is valid");
+    let roots = data_roots(resource_dir);
+
+    let mut data = BundledData {
+        signature_engine: SignatureEngine::new(),
+        oui_lookup: OuiLookup::empty(),
+        geoip_lookup: GeoIpLookup::empty(),
+        status: Vec::new(),
+    };
+
+    for entry in &manifest.datasets {
+        let mut resolved = datasets::resolve(entry, &roots);
+        let mut records = 0;
+
+        if let Some(path) = resolved.path.clone() {
+            match entry.kind {
+                DatasetKind::Oui => match OuiLookup::load_from_file(&path) {
+                    Ok(lookup) => {
+                        records = lookup.len();
+                        data.oui_lookup = lookup;
+                    }
+                    Err(e) => resolved.failed(e),
+                },
+                DatasetKind::GeoIp => match GeoIpLookup::load_from_file(&path) {
+                    Ok(lookup) => data.geoip_lookup = lookup,
+                    Err(e) => resolved.failed(e),
+                },
+                DatasetKind::Signatures => match data.signature_engine.load_directory(&path) {
+                    Ok(count) => records = count,
+                    Err(e) => resolved.failed(e),
+                },
+            }
+        }
+
+        match resolved.state {
+            DatasetState::Verified => {
+                log::info!("{}: verified ({} records)", entry.name, records)
+            }
+            DatasetState::Missing if entry.optional => {
+                log::info!("{}: not bundled", entry.name)
+            }
+            state => log::warn!(
+                "{}: {:?} — {}",
+                entry.name,
+                state,
+                resolved.message.as_deref().unwrap_or("")
+            ),
+        }
+
+        data.status.push(resolved.status(records));
+    }
+
+    data
+}
+
+/// Get the load/verification status of the bundled datasets.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn get_data_status(state: State<'_, AppState>) -> Result<Vec<DatasetStatus>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner.data_status.clone())
+}
@@ -0,0 +1,538 @@
+//! Demo / anonymized mode.
+//!
+//! When enabled, the asset, connection, topology, protocol-detail, analysis,
+//! physical-topology, segmentation, alert, and host-event views — and the
+//! report and CSV exports built from them — are passed through a seeded
+//! `gm_report::Anonymizer` so screenshots and training material can be
+//! produced from real captures. Free text (finding descriptions, evidence,
+//! event details) is scrubbed of known asset addresses, names, and vendors
+//! with [`scrub_text`]. The seed is scoped to the session: it is generated
+//! the first time demo mode is switched on, saved with the session metadata,
+//! and restored on load so pseudonyms stay stable across reloads.
+//!
+//! The underlying state is never modified — pseudonyms are applied on the
+//! way out, and incoming IDs from the frontend are resolved back with
+//! [`resolve_ip`] and [`resolve_switch`].
+//!
+//! Outputs the pseudonyms cannot be applied to are refused while demo mode
+//! is on: PCAP slice exports and Wireshark launches, which read the capture
+//! files, session archive exports, which must stay re-importable, and NetBox
+//! pushes and CSVs, which would create fake devices in a real inventory.
+
+use std::net::IpAddr;
+
+use serde::Serialize;
+#[cfg(feature = "gui")]
+use tauri::State;
+
+use gm_analysis::{AnalysisResult, AnomalyScore, Finding, PurdueAssignment};
+use gm_physical::{InferredTopology, PhysicalTopology};
+use gm_report::Anonymizer;
+use gm_segmentation::SegmentationInput;
+use gm_topology::TopologyGraph;
+
+use super::{AppState, AppStateInner, AssetInfo, ConnectionInfo, DeepParseInfo, PacketSummary};
+
+/// Demo mode status returned to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct DemoModeStatus {
+    pub enabled: bool,
+}
+
+/// Get whether demo (anonymized) mode is active.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn get_demo_mode(state: State<'_, AppState>) -> Result<DemoModeStatus, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(DemoModeStatus {
+        enabled: inner.demo_mode,
+    })
+}
+
+/// Enable or disable demo (anonymized) mode for the loaded session.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn set_demo_mode(enabled: bool, state: State<'_, AppState>) -> Result<DemoModeStatus, String> {
+    let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
+    if enabled && inner.demo_seed.is_none() {
+        inner.demo_seed = Some(uuid::Uuid::new_v4().as_u128() as u64);
+    }
+    inner.demo_mode = enabled;
+    log::info!("Demo mode {}", if enabled { "enabled" } else { "disabled" });
+    Ok(DemoModeStatus { enabled })
+}
+
+/// The anonymizer to apply to outgoing data, if demo mode is on.
+pub(crate) fn active_anonymizer(inner: &AppStateInner) -> Option<Anonymizer> {
+    if inner.demo_mode {
+        inner.demo_seed.map(Anonymizer::new)
+    } else {
+        None
+    }
+}
+
+/// Map an IP (or IP-keyed ID) sent back by the frontend to the real address.
+///
+/// Outside demo mode this is the identity. In demo mode the pseudonym is
+/// looked up against the known assets; unknown values pass through as-is.
+pub(crate) fn resolve_ip(inner: &AppStateInner, value: &str) -> String {
+    match active_anonymizer(inner) {
+        Some(anon) => inner
+            .assets
+            .iter()
+            .find(|a| anon.ip(&a.ip_address) == value)
+            .map(|a| a.ip_address.clone())
+            .unwrap_or_else(|| value.to_string()),
+        None => value.to_string(),
+    }
+}
+
+/// Map a switch hostname sent back by the frontend to the real one, the
+/// way [`resolve_ip`] does for addresses.
+pub(crate) fn resolve_switch(inner: &AppStateInner, value: &str) -> String {
+    match active_anonymizer(inner) {
+        Some(anon) => inner
+            .physical_topology
+            .switches
+            .iter()
+            .find(|s| anon.hostname(&s.hostname) == value)
+            .map(|s| s.hostname.clone())
+            .unwrap_or_else(|| value.to_string()),
+        None => value.to_string(),
+    }
+}
+
+pub(crate) fn anonymize_asset(anon: &Anonymizer, asset: &mut AssetInfo) {
+    asset.id = anon.ip(&asset.id);
+    asset.ip_address = anon.ip(&asset.ip_address);
+    asset.mac_address = asset.mac_address.as_deref().map(|m| anon.mac(m));
+    asset.hostname = asset.hostname.as_deref().map(|h| anon.hostname(h));
+    asset.vendor = asset.vendor.as_deref().map(|v| anon.vendor(v));
+    asset.oui_vendor = asset.oui_vendor.as_deref().map(|v| anon.vendor(v));
+    asset.product_family = asset.product_family.as_deref().map(|v| anon.vendor(v));
+    asset.notes.clear();
+    for m in &mut asset.signature_matches {
+        m.vendor = m.vendor.as_deref().map(|v| anon.vendor(v));
+        m.product_family = m.product_family.as_deref().map(|v| anon.vendor(v));
+    }
+}
+
+pub(crate) fn anonymize_connection(anon: &Anonymizer, conn: &mut ConnectionInfo) {
+    conn.src_ip = anon.ip(&conn.src_ip);
+    conn.dst_ip = anon.ip(&conn.dst_ip);
+    conn.src_mac = conn.src_mac.as_deref().map(|m| anon.mac(m));
+    conn.dst_mac = conn.dst_mac.as_deref().map(|m| anon.mac(m));
+    conn.origin_files.clear();
+}
+
+pub(crate) fn anonymize_packet_summary(anon: &Anonymizer, summary: &mut PacketSummary) {
+    summary.src_ip = anon.ip(&summary.src_ip);
+    summary.dst_ip = anon.ip(&summary.dst_ip);
+    summary.origin_file.clear();
+}
+
+pub(crate) fn anonymize_topology(anon: &Anonymizer, topo: &mut TopologyGraph) {
+    for node in &mut topo.nodes {
+        node.id = anon.ip(&node.id);
+        node.ip_address = anon.ip(&node.ip_address);
+        for address in &mut node.addresses {
+            *address = anon.ip(address);
+        }
+        node.mac_address = node.mac_address.as_deref().map(|m| anon.mac(m));
+        node.vendor = node.vendor.as_deref().map(|v| anon.vendor(v));
+        node.subnet = anonymize_cidr(anon, &node.subnet);
+    }
+    for edge in &mut topo.edges {
+        edge.source = anon.ip(&edge.source);
+        edge.target = anon.ip(&edge.target);
+    }
+}
+
+/// Pseudonymize a network in CIDR notation, keeping its prefix length.
+fn anonymize_cidr(anon: &Anonymizer, cidr: &str) -> String {
+    match cidr.split_once('/') {
+        Some((net, len)) => format!("{}/{}", anon.ip(net), len),
+        None => anon.ip(cidr),
+    }
+}
+
+/// Pseudonymize imported switch configs: names, addresses, MACs, and the
+/// CDP/LLDP neighbors and device locations derived from them. Port
+/// descriptions are free text written by the site, so they are dropped.
+pub(crate) fn anonymize_physical_topology(anon: &Anonymizer, topo: &mut PhysicalTopology) {
+    let ips = |ips: &mut Vec<String>| ips.iter_mut().for_each(|ip| *ip = anon.ip(ip));
+    let ip = |ip: &mut Option<String>| *ip = ip.as_deref().map(|ip| anon.ip(ip));
+    let mac = |mac: &mut Option<String>| *mac = mac.as_deref().map(|m| anon.mac(m));
+
+    for switch in &mut topo.switches {
+        switch.hostname = anon.hostname(&switch.hostname);
+        ip(&mut switch.management_ip);
+        switch.model = switch.model.as_deref().map(|m| anon.vendor(m));
+        for port in &mut switch.ports {
+            port.description = None;
+            ip(&mut port.ip_address);
+            ips(&mut port.ip_addresses);
+            for m in &mut port.mac_addresses {
+                *m = anon.mac(m);
+            }
+            if let Some(ref mut neighbor) = port.cdp_neighbor {
+                neighbor.device_id = anon.hostname(&neighbor.device_id);
+                neighbor.platform = neighbor.platform.as_deref().map(|p| anon.vendor(p));
+                ip(&mut neighbor.ip_address);
+            }
+        }
+    }
+    for link in &mut topo.links {
+        link.src_switch = anon.hostname(&link.src_switch);
+        link.dst_switch = anon.hostname(&link.dst_switch);
+    }
+    topo.device_locations = std::mem::take(&mut topo.device_locations)
+        .into_iter()
+        .map(|(addr, mut location)| {
+            location.ip_address = anon.ip(&location.ip_address);
+            mac(&mut location.mac_address);
+            location.switch_hostname = anon.hostname(&location.switch_hostname);
+            (anon.ip(&addr), location)
+        })
+        .collect();
+}
+
+/// Pseudonymize a traffic-inferred topology.
+pub(crate) fn anonymize_inferred_topology(anon: &Anonymizer, topo: &mut InferredTopology) {
+    let ips = |ips: &mut Vec<String>| ips.iter_mut().for_each(|ip| *ip = anon.ip(ip));
+    let ip = |ip: &mut Option<String>| *ip = ip.as_deref().map(|ip| anon.ip(ip));
+    let mac = |mac: &mut Option<String>| *mac = mac.as_deref().map(|m| anon.mac(m));
+
+    for subnet in &mut topo.subnets {
+        subnet.network = anonymize_cidr(anon, &subnet.network);
+        ips(&mut subnet.member_ips);
+        ip(&mut subnet.gateway_ip);
+    }
+    for gateway in &mut topo.gateways {
+        gateway.ip_address = anon.ip(&gateway.ip_address);
+        mac(&mut gateway.mac_address);
+        for subnet in &mut gateway.connected_subnets {
+            *subnet = anonymize_cidr(anon, subnet);
+        }
+    }
+    for candidate in &mut topo.switch_candidates {
+        ip(&mut candidate.ip_address);
+        mac(&mut candidate.mac_address);
+        ips(&mut candidate.connected_ips);
+    }
+    for domain in &mut topo.broadcast_domains {
+        domain.network = anonymize_cidr(anon, &domain.network);
+        ips(&mut domain.member_ips);
+        ip(&mut domain.gateway_ip);
+    }
+}
+
+/// Pseudonymize a finding, including IPs embedded in its free text.
+pub(crate) fn anonymize_finding(anon: &Anonymizer, inner: &AppStateInner, finding: &mut Finding) {
+    finding.title = scrub_text(anon, inner, &finding.title);
+    finding.description = scrub_text(anon, inner, &finding.description);
+    finding.evidence = scrub_text(anon, inner, &finding.evidence);
+    for ip in &mut finding.affected_assets {
+        *ip = anon.ip(ip);
+    }
+}
+
+/// Pseudonymize a Purdue level assignment, including its reasoning.
+pub(crate) fn anonymize_purdue_assignment(
+    anon: &Anonymizer,
+    inner: &AppStateInner,
+    assignment: &mut PurdueAssignment,
+) {
+    assignment.ip_address = anon.ip(&assignment.ip_address);
+    assignment.reason = scrub_text(anon, inner, &assignment.reason);
+}
+
+/// Pseudonymize an anomaly score, including its evidence text.
+pub(crate) fn anonymize_anomaly(
+    anon: &Anonymizer,
+    inner: &AppStateInner,
+    anomaly: &mut AnomalyScore,
+) {
+    anomaly.affected_asset = anon.ip(&anomaly.affected_asset);
+    anomaly.evidence = scrub_text(anon, inner, &anomaly.evidence);
+}
+
+/// Pseudonymize everything an analysis run returns.
+pub(crate) fn anonymize_analysis(
+    anon: &Anonymizer,
+    inner: &AppStateInner,
+    result: &mut AnalysisResult,
+) {
+    for finding in &mut result.findings {
+        anonymize_finding(anon, inner, finding);
+    }
+    for assignment in &mut result.purdue_assignments {
+        anonymize_purdue_assignment(anon, inner, assignment);
+    }
+    for anomaly in &mut result.anomalies {
+        anonymize_anomaly(anon, inner, anomaly);
+    }
+}
+
+/// Pseudonymize a segmentation input, so that reports built from it — the
+/// enforcement configs included — only carry pseudonyms.
+pub(crate) fn anonymize_segmentation_input(
+    anon: &Anonymizer,
+    inner: &AppStateInner,
+    input: &mut SegmentationInput,
+) {
+    for asset in &mut input.assets {
+        asset.ip = anon.ip(&asset.ip);
+        asset.mac = asset.mac.as_deref().map(|m| anon.mac(m));
+        asset.hostname = asset.hostname.as_deref().map(|h| anon.hostname(h));
+        asset.vendor = asset.vendor.as_deref().map(|v| anon.vendor(v));
+        asset.product_name = asset
+            .product_name
+            .as_deref()
+            .map(|p| scrub_text(anon, inner, p));
+        asset.subnet = asset.subnet.as_deref().map(|s| anonymize_cidr(anon, s));
+    }
+    for conn in &mut input.connections {
+        conn.src_ip = anon.ip(&conn.src_ip);
+        conn.dst_ip = anon.ip(&conn.dst_ip);
+    }
+    for finding in &mut input.findings {
+        finding
+            .affected_ips
+            .iter_mut()
+            .for_each(|ip| *ip = anon.ip(ip));
+        finding.description = scrub_text(anon, inner, &finding.description);
+    }
+}
+
+/// Pseudonymize the protocol details of a device: peer addresses, MACs,
+/// and the names, vendors, and models devices report about themselves.
+pub(crate) fn anonymize_deep_parse(
+    anon: &Anonymizer,
+    inner: &AppStateInner,
+    info: &mut DeepParseInfo,
+) {
+    let ips = |ips: &mut Vec<String>| ips.iter_mut().for_each(|ip| *ip = anon.ip(ip));
+    let hosts = |names: &mut Vec<String>| names.iter_mut().for_each(|n| *n = anon.hostname(n));
+    let host = |h: &mut Option<String>| *h = h.as_deref().map(|h| anon.hostname(h));
+    let vendor = |v: &mut Option<String>| *v = v.as_deref().map(|v| anon.vendor(v));
+
+    if let Some(ref mut modbus) = info.modbus {
+        for rel in &mut modbus.relationships {
+            rel.remote_ip = anon.ip(&rel.remote_ip);
+        }
+        for map in &mut modbus.register_maps {
+            ips(&mut map.masters);
+        }
+        if let Some(ref mut id) = modbus.device_id {
+            vendor(&mut id.vendor_name);
+            vendor(&mut id.vendor_url);
+            vendor(&mut id.product_name);
+            vendor(&mut id.model_name);
+        }
+    }
+    if let Some(ref mut dnp3) = info.dnp3 {
+        for rel in &mut dnp3.relationships {
+            rel.remote_ip = anon.ip(&rel.remote_ip);
+        }
+        for binding in &mut dnp3.master_bindings {
+            binding.outstation_ip = anon.ip(&binding.outstation_ip);
+        }
+    }
+    if let Some(identity) = info.enip.as_mut().and_then(|e| e.identity.as_mut()) {
+        identity.vendor_name = anon.vendor(&identity.vendor_name);
+        identity.product_name = anon.vendor(&identity.product_name);
+        identity.serial_number = anon.vendor(&identity.serial_number);
+    }
+    if let Some(device) = info.bacnet.as_mut().and_then(|b| b.device.as_mut()) {
+        host(&mut device.object_name);
+        vendor(&mut device.vendor_name);
+        vendor(&mut device.model_name);
+    }
+    if let Some(ref mut fins) = info.fins {
+        vendor(&mut fins.controller_model);
+    }
+    if let Some(ref mut slmp) = info.slmp {
+        vendor(&mut slmp.cpu_model);
+    }
+    if let Some(ref mut ge) = info.ge_srtp {
+        vendor(&mut ge.controller_model);
+        host(&mut ge.controller_id);
+    }
+    if let Some(ref mut fox) = info.fox {
+        host(&mut fox.station_name);
+        host(&mut fox.host_name);
+        host(&mut fox.host_id);
+    }
+    if let Some(ref mut crimson) = info.crimson {
+        vendor(&mut crimson.manufacturer);
+        vendor(&mut crimson.model);
+        ips(&mut crimson.transfer_peers);
+    }
+    if let Some(ref mut vnet) = info.vnet_ip {
+        vnet.other_bus_ip = vnet.other_bus_ip.as_deref().map(|ip| anon.ip(ip));
+        ips(&mut vnet.peers);
+    }
+    if let Some(ref mut tristation) = info.tristation {
+        ips(&mut tristation.peers);
+    }
+    if let Some(ref mut ads) = info.ads {
+        // AMS Net IDs are usually the host IP with ".1.1" appended
+        ips(&mut ads.ams_net_ids);
+        host(&mut ads.device_name);
+        for rel in &mut ads.relationships {
+            rel.remote_ip = anon.ip(&rel.remote_ip);
+            ips(&mut rel.remote_net_ids);
+        }
+    }
+    if let Some(ref mut opc) = info.opc_da {
+        ips(&mut opc.peers);
+    }
+    if let Some(ref mut mms) = info.mms {
+        vendor(&mut mms.vendor);
+        vendor(&mut mms.model);
+    }
+    if let Some(ref mut goose) = info.goose {
+        for stream in &mut goose.publications {
+            stream.publisher_mac = anon.mac(&stream.publisher_mac);
+            stream.destination_mac = anon.mac(&stream.destination_mac);
+        }
+    }
+    if let Some(ref mut sv) = info.sv {
+        for stream in &mut sv.publications {
+            stream.publisher_mac = anon.mac(&stream.publisher_mac);
+            stream.destination_mac = anon.mac(&stream.destination_mac);
+        }
+    }
+    if let Some(ref mut dcp) = info.profinet_dcp {
+        host(&mut dcp.device_name);
+        vendor(&mut dcp.vendor);
+        vendor(&mut dcp.product);
+    }
+    if let Some(ref mut rt) = info.profinet_rt {
+        for stream in &mut rt.relations {
+            stream.provider_mac = anon.mac(&stream.provider_mac);
+            stream.consumer_mac = anon.mac(&stream.consumer_mac);
+        }
+    }
+    if let Some(ref mut lldp) = info.lldp {
+        host(&mut lldp.system_name);
+        vendor(&mut lldp.system_description);
+        host(&mut lldp.chassis_id);
+        ips(&mut lldp.management_addresses);
+        vendor(&mut lldp.vendor);
+        vendor(&mut lldp.model);
+    }
+    if let Some(ref mut snmp) = info.snmp {
+        host(&mut snmp.sys_name);
+        vendor(&mut snmp.sys_descr);
+        vendor(&mut snmp.sys_location);
+        vendor(&mut snmp.sys_contact);
+        vendor(&mut snmp.vendor);
+        vendor(&mut snmp.model);
+    }
+    if let Some(ref mut smb) = info.smb {
+        hosts(&mut smb.users);
+        for share in &mut smb.shares {
+            share.remote_ip = anon.ip(&share.remote_ip);
+            share.share = scrub_text(anon, inner, &share.share);
+        }
+    }
+    if let Some(ref mut dhcp) = info.dhcp {
+        host(&mut dhcp.hostname);
+        vendor(&mut dhcp.vendor_class);
+        dhcp.server_ip = dhcp.server_ip.as_deref().map(|ip| anon.ip(ip));
+        for lease in &mut dhcp.leases {
+            lease.client_mac = anon.mac(&lease.client_mac);
+            lease.ip = lease.ip.as_deref().map(|ip| anon.ip(ip));
+            host(&mut lease.hostname);
+        }
+    }
+    if let Some(ref mut sessions) = info.sessions {
+        sessions.ip = anon.ip(&sessions.ip);
+    }
+    if let Some(ref mut cloud) = info.cloud {
+        for endpoint in &mut cloud.endpoints {
+            endpoint.remote_ip = anon.ip(&endpoint.remote_ip);
+            host(&mut endpoint.hostname);
+            vendor(&mut endpoint.identity);
+            endpoint.evidence = scrub_text(anon, inner, &endpoint.evidence);
+        }
+    }
+    if let Some(ref mut tls) = info.tls {
+        for fingerprint in &mut tls.fingerprints {
+            ips(&mut fingerprint.peers);
+            hosts(&mut fingerprint.server_names);
+        }
+    }
+    if let Some(ref mut mqtt) = info.mqtt {
+        hosts(&mut mqtt.client_ids);
+        ips(&mut mqtt.peers);
+        for topic in &mut mqtt.topics {
+            topic.topic = scrub_text(anon, inner, &topic.topic);
+            ips(&mut topic.peers);
+        }
+    }
+    if let Some(ref mut gateway) = info.serial_gateway {
+        for line in &mut gateway.lines {
+            ips(&mut line.hosts);
+        }
+    }
+}
+
+/// Replace every known asset IP, hostname, and vendor string in `text`.
+///
+/// Values only match as whole tokens, so "10.0.0.1" leaves "10.0.0.15"
+/// alone; where two overlap at the same spot the longer wins.
+pub(crate) fn scrub_text(anon: &Anonymizer, inner: &AppStateInner, text: &str) -> String {
+    let mut replacements: Vec<(String, String)> = Vec::new();
+    for asset in &inner.assets {
+        replacements.push((asset.ip_address.clone(), anon.ip(&asset.ip_address)));
+        if let Some(ref h) = asset.hostname {
+            replacements.push((h.clone(), anon.hostname(h)));
+        }
+        if let Some(ref v) = asset.vendor {
+            replacements.push((v.clone(), anon.vendor(v)));
+        }
+    }
+    replacements.retain(|(from, _)| !from.is_empty());
+    replacements.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    'scan: while let Some(c) = text[i..].chars().next() {
+        for (from, to) in &replacements {
+            let end = i + from.len();
+            if text[i..].starts_with(from.as_str()) && on_token_boundary(text, i, end, from) {
+                out.push_str(to);
+                i = end;
+                continue 'scan;
+            }
+        }
+        out.push(c);
+        i += c.len_utf8();
+    }
+    out
+}
+
+/// Whether `text[start..end]` (which holds `value`) stands on its own rather
+/// than being part of a longer word, hostname, or address.
+fn on_token_boundary(text: &str, start: usize, end: usize, value: &str) -> bool {
+    let addr = value.parse::<IpAddr>().ok();
+    // An address continues through a separator followed by another digit
+    // (so "10.0.0.1:502" still matches "10.0.0.1"); names continue through
+    // letters, digits, '_' and '-'
+    let continues = |next: Option<char>, after: Option<char>| match (next, addr) {
+        (Some('.'), Some(_)) | (Some(':'), Some(IpAddr::V6(_))) => {
+            after.is_some_and(|c| c.is_ascii_hexdigit())
+        }
+        (Some('-'), _) => addr.is_none(),
+        (Some(c), _) => c.is_alphanumeric() || c == '_',
+        (None, _) => false,
+    };
+    let mut before = text[..start].chars().rev();
+    let (b1, b2) = (before.next(), before.next());
+    let mut after = text[end..].chars();
+    let (a1, a2) = (after.next(), after.next());
+    !continues(b1, b2) && !continues(a1, a2)
+}
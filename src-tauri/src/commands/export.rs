@@ -1,10 +1,10 @@
-//! Export & reporting commands: CSV, JSON, PDF, SBOM, STIX, and allowlist.
+//! Export & reporting commands: CSV, JSON, PDF, SBOM, STIX, NetBox, and allowlist.
 //!
-//! These commands serialize the current AppState data into various
-//! export formats using the gm-report crate, plus the allowlist generator
-//! from gm-analysis.
+//! File formats go through gm-report's exporter registry behind a single
+//! `export` command; NetBox, images, PCAP slices and the allowlist
+//! generator from gm-analysis have their own commands.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use tauri::State;
 
@@ -12,16 +12,27 @@ use gm_analysis::{
     allowlist_to_csv, format_firewall_rules, generate_allowlist, AllowlistEntry, AssetSnapshot,
     ConnectionSnapshot,
 };
+use gm_capture::ConversationFilter;
+use gm_report::netbox::{NetboxExport, NetboxOptions};
 use gm_report::{
-    ExportAsset, ExportConnection, ExportFinding, ExportProtocolStat, ReportConfig, ReportData,
+    ExportAsset, ExportConnection, ExportExternalConnection, ExportFormatInfo, ExportOptions,
+    ExportProtocolStat, ExportTopTalker, ExportWritePath, ExporterRegistry, ReportConfig,
+    ReportData,
 };
+use gm_topology::TopologyGraph;
 
 use super::AppState;
 
+/// Busiest connections listed in reports.
+const REPORT_TOP_TALKERS: usize = 10;
+
 // ─── Conversion Helpers ──────────────────────────────────────
 
 /// Convert the in-memory AppState assets to ExportAsset format.
+///
+/// In demo mode the identifying fields are pseudonymized.
 fn state_assets_to_export(state: &super::AppStateInner) -> Vec<ExportAsset> {
+    let anon = super::demo::active_anonymizer(state);
     state
         .assets
         .iter()
@@ -44,11 +55,18 @@ fn state_assets_to_export(state: &super::AppStateInner) -> Vec<ExportAsset> {
             tags: a.tags.clone(),
             packet_count: a.packet_count,
         })
+        .map(|mut a| {
+            if let Some(ref anon) = anon {
+                anon.anonymize_asset(&mut a);
+            }
+            a
+        })
         .collect()
 }
 
 /// Convert the in-memory connections to ExportConnection format.
 fn state_connections_to_export(state: &super::AppStateInner) -> Vec<ExportConnection> {
+    let anon = super::demo::active_anonymizer(state);
     state
         .connections
         .iter()
@@ -63,6 +81,13 @@ fn state_connections_to_export(state: &super::AppStateInner) -> Vec<ExportConnec
             byte_count: c.byte_count,
             first_seen: c.first_seen.clone(),
             last_seen: c.last_seen.clone(),
+            direction_basis: c.direction_basis.as_str().to_string(),
+        })
+        .map(|mut c| {
+            if let Some(ref anon) = anon {
+                anon.anonymize_connection(&mut c);
+            }
+            c
         })
         .collect()
 }
@@ -103,6 +128,15 @@ fn compute_protocol_stats(state: &super::AppStateInner) -> Vec<ExportProtocolSta
     result
 }
 
+/// Session name for export headers; withheld in demo mode.
+fn export_session_name(state: &super::AppStateInner) -> Option<String> {
+    if state.demo_mode {
+        Some("Demo Session".to_string())
+    } else {
+        state.current_session_name.clone()
+    }
+}
+
 /// Build a complete ReportData from current state.
 fn build_report_data(state: &super::AppStateInner) -> ReportData {
     ReportData {
@@ -110,81 +144,100 @@ fn build_report_data(state: &super::AppStateInner) -> ReportData {
         connections: state_connections_to_export(state),
         protocol_stats: compute_protocol_stats(state),
         findings: Vec::new(), // Findings will come from Phase 10
-        session_name: state.current_session_name.clone(),
+        session_name: export_session_name(state),
+        unapproved_write_paths: state_unapproved_write_paths(state),
+        external_connectivity: state_external_connectivity(state),
+        top_talkers: state_top_talkers(state),
+        topology: None,
     }
 }
 
-// ─── CSV Export Commands ─────────────────────────────────────
-
-/// Export all assets as CSV, writing to the specified file path.
-#[tauri::command]
-pub async fn export_assets_csv(
-    output_path: String,
-    state: State<'_, AppState>,
-) -> Result<String, String> {
-    let inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let assets = state_assets_to_export(&inner);
-    let csv = gm_report::csv_export::assets_to_csv(&assets).map_err(|e| e.to_string())?;
-    gm_report::csv_export::write_csv_file(&output_path, &csv).map_err(|e| e.to_string())?;
-    log::info!("Exported {} assets to CSV: {}", assets.len(), output_path);
-    Ok(output_path)
+/// The full topology for the graph file formats. Unlike `get_topology` it
+/// is not capped, since nothing has to render it here.
+fn state_topology_graph(state: &super::AppStateInner) -> TopologyGraph {
+    let mut graph = state.topology.clone();
+    graph.assign_vlans(&super::physical::vlan_map(state));
+    if let Some(anon) = super::demo::active_anonymizer(state) {
+        super::demo::anonymize_topology(&anon, &mut graph);
+    }
+    graph
 }
 
-/// Export all connections as CSV, writing to the specified file path.
-#[tauri::command]
-pub async fn export_connections_csv(
-    output_path: String,
-    state: State<'_, AppState>,
-) -> Result<String, String> {
-    let inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let connections = state_connections_to_export(&inner);
-    let csv = gm_report::csv_export::connections_to_csv(&connections).map_err(|e| e.to_string())?;
-    gm_report::csv_export::write_csv_file(&output_path, &csv).map_err(|e| e.to_string())?;
-    log::info!(
-        "Exported {} connections to CSV: {}",
-        connections.len(),
-        output_path
-    );
-    Ok(output_path)
+/// The busiest topology edges with their peak traffic, for the report.
+fn state_top_talkers(state: &super::AppStateInner) -> Vec<ExportTopTalker> {
+    let anon = super::demo::active_anonymizer(state);
+    let ip = |value: &str| match anon {
+        Some(ref anon) => anon.ip(value),
+        None => value.to_string(),
+    };
+    state
+        .topology
+        .top_talkers(REPORT_TOP_TALKERS)
+        .into_iter()
+        .map(|t| ExportTopTalker {
+            src_ip: ip(&t.source),
+            dst_ip: ip(&t.target),
+            protocol: t.protocol.display_name().to_string(),
+            byte_count: t.byte_count,
+            bucket_secs: t.bucket_secs,
+            peak_at: t
+                .peak
+                .map(|p| p.start.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            peak_bytes: t.peak.map_or(0, |p| p.byte_count),
+            burst_count: t.bursts.len(),
+        })
+        .collect()
 }
 
-// ─── JSON Export Commands ────────────────────────────────────
-
-/// Export the full topology (assets + connections + stats) as JSON.
-#[tauri::command]
-pub async fn export_topology_json(
-    output_path: String,
-    state: State<'_, AppState>,
-) -> Result<String, String> {
-    let inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let assets = state_assets_to_export(&inner);
-    let connections = state_connections_to_export(&inner);
-    let stats = compute_protocol_stats(&inner);
-    let session_name = inner.current_session_name.as_deref();
-
-    let json =
-        gm_report::json_export::topology_to_json(&assets, &connections, &stats, session_name)
-            .map_err(|e| e.to_string())?;
-    gm_report::json_export::write_json_file(&output_path, &json).map_err(|e| e.to_string())?;
-    log::info!("Exported topology JSON to: {}", output_path);
-    Ok(output_path)
+/// Outbound cloud / remote-access connections for the report.
+fn state_external_connectivity(state: &super::AppStateInner) -> Vec<ExportExternalConnection> {
+    let anon = super::demo::active_anonymizer(state);
+    super::connectivity::external_connections(state)
+        .into_iter()
+        .map(|c| {
+            let mut row = ExportExternalConnection {
+                local_ip: c.local_ip,
+                local_device_type: c.local_device_type,
+                remote_ip: c.remote_ip,
+                remote_port: c.remote_port,
+                remote_hostname: c.remote_hostname,
+                service: c.service,
+                category: c.category.display_name().to_string(),
+                evidence: c.evidence,
+                packet_count: c.packet_count,
+            };
+            if let Some(ref anon) = anon {
+                row.local_ip = anon.ip(&row.local_ip);
+                row.remote_ip = anon.ip(&row.remote_ip);
+                row.remote_hostname = row.remote_hostname.as_deref().map(|h| anon.hostname(h));
+            }
+            row
+        })
+        .collect()
 }
 
-/// Export all assets as JSON.
-#[tauri::command]
-pub async fn export_assets_json(
-    output_path: String,
-    state: State<'_, AppState>,
-) -> Result<String, String> {
-    let inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let assets = state_assets_to_export(&inner);
-    let json = gm_report::json_export::assets_to_json(&assets).map_err(|e| e.to_string())?;
-    gm_report::json_export::write_json_file(&output_path, &json).map_err(|e| e.to_string())?;
-    log::info!("Exported {} assets to JSON: {}", assets.len(), output_path);
-    Ok(output_path)
+/// Write paths from the last analysis run that have no active approval.
+fn state_unapproved_write_paths(state: &super::AppStateInner) -> Vec<ExportWritePath> {
+    let anon = super::demo::active_anonymizer(state);
+    let ip = |value: &str| match anon {
+        Some(ref anon) => anon.ip(value),
+        None => value.to_string(),
+    };
+    state
+        .write_paths
+        .iter()
+        .filter(|p| p.approval.is_none())
+        .map(|p| ExportWritePath {
+            master_ip: ip(&p.master_ip),
+            slave_ip: ip(&p.slave_ip),
+            protocol: p.protocol.clone(),
+            function_codes: p.function_codes.clone(),
+            write_count: p.write_count,
+        })
+        .collect()
 }
 
-// ─── PDF Report Command ─────────────────────────────────────
+// ─── Format Exports ─────────────────────────────────────────
 
 /// Report configuration from the frontend.
 #[derive(Debug, Deserialize)]
@@ -200,95 +253,448 @@ pub struct ReportConfigInput {
     pub include_recommendations: bool,
 }
 
-/// Generate a PDF assessment report.
-#[tauri::command]
-pub async fn generate_pdf_report(
-    config: ReportConfigInput,
-    output_path: String,
-    state: State<'_, AppState>,
-) -> Result<String, String> {
-    let inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let data = build_report_data(&inner);
-
-    let report_config = ReportConfig {
-        assessor_name: config.assessor_name,
-        client_name: config.client_name,
-        assessment_date: config
-            .assessment_date
-            .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d").to_string()),
-        title: config.title,
-        include_executive_summary: config.include_executive_summary,
-        include_asset_inventory: config.include_asset_inventory,
-        include_protocol_analysis: config.include_protocol_analysis,
-        include_findings: config.include_findings,
-        include_recommendations: config.include_recommendations,
-    };
-
-    gm_report::pdf::generate_report(&report_config, &data, &output_path)
-        .map_err(|e| e.to_string())?;
+/// Per-export settings from the frontend; each format reads what it needs.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportOptionsInput {
+    /// PDF report settings
+    pub report: Option<ReportConfigInput>,
+}
 
-    log::info!("Generated PDF report: {}", output_path);
-    Ok(output_path)
+impl From<ExportOptionsInput> for ExportOptions {
+    fn from(input: ExportOptionsInput) -> Self {
+        let report = input.report.map(|config| ReportConfig {
+            assessor_name: config.assessor_name,
+            client_name: config.client_name,
+            assessment_date: config
+                .assessment_date
+                .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d").to_string()),
+            title: config.title,
+            include_executive_summary: config.include_executive_summary,
+            include_asset_inventory: config.include_asset_inventory,
+            include_protocol_analysis: config.include_protocol_analysis,
+            include_findings: config.include_findings,
+            include_recommendations: config.include_recommendations,
+        });
+        ExportOptions { report }
+    }
 }
 
-// ─── SBOM Export Command ────────────────────────────────────
+/// List the formats accepted by `export`.
+#[tauri::command]
+pub async fn list_export_formats() -> Result<Vec<ExportFormatInfo>, String> {
+    Ok(ExporterRegistry::with_builtin().formats())
+}
 
-/// Export asset inventory as SBOM (CISA BOD 23-01 format).
-/// `format` can be "csv" or "json".
+/// Export the current session in a registered format (CSV, JSON, PDF,
+/// SBOM, STIX, topology graph files — see `list_export_formats`), writing
+/// to `output_path`.
 #[tauri::command]
-pub async fn export_sbom(
+pub async fn export(
     format: String,
     output_path: String,
+    options: Option<ExportOptionsInput>,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
+    let registry = ExporterRegistry::with_builtin();
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let assets = state_assets_to_export(&inner);
-    let entries = gm_report::sbom::assets_to_sbom(&assets);
-
-    let content = match format.as_str() {
-        "csv" => gm_report::sbom::sbom_to_csv(&entries).map_err(|e| e.to_string())?,
-        "json" => gm_report::sbom::sbom_to_json(&entries).map_err(|e| e.to_string())?,
-        _ => {
-            return Err(format!(
-                "Unsupported SBOM format: {}. Use 'csv' or 'json'.",
-                format
-            ))
-        }
-    };
+    let mut data = build_report_data(&inner);
+    if registry.get(&format).is_some_and(|e| e.needs_topology()) {
+        data.topology = Some(state_topology_graph(&inner));
+    }
+    let options = ExportOptions::from(options.unwrap_or_default());
+
+    registry
+        .export(&format, &data, &options, &output_path)
+        .map_err(|e| e.to_string())?;
 
-    std::fs::write(&output_path, content).map_err(|e| e.to_string())?;
     log::info!(
-        "Exported SBOM ({}) with {} entries to: {}",
+        "Exported {} ({} assets, {} connections) to: {}",
         format,
-        entries.len(),
+        data.assets.len(),
+        data.connections.len(),
         output_path
     );
     Ok(output_path)
 }
 
-// ─── STIX 2.1 Export Command ─────────────────────────────────
+// ─── NetBox Export Commands ─────────────────────────────────
+
+/// NetBox target settings from the frontend.
+#[derive(Debug, Deserialize)]
+pub struct NetboxConfigInput {
+    /// Name of an existing NetBox site
+    pub site: String,
+    /// Device/IP status (default "active")
+    pub status: Option<String>,
+    /// Tag slug applied to exported objects (default "kusanagi-kajiki")
+    pub tag: Option<String>,
+}
+
+impl NetboxConfigInput {
+    fn to_options(&self) -> Result<NetboxOptions, String> {
+        if self.site.trim().is_empty() {
+            return Err("A NetBox site name is required".to_string());
+        }
+        let defaults = NetboxOptions::default();
+        Ok(NetboxOptions {
+            site: self.site.trim().to_string(),
+            status: self.status.clone().unwrap_or(defaults.status),
+            tag: self
+                .tag
+                .as_deref()
+                .map(gm_report::netbox::slugify)
+                .unwrap_or(defaults.tag),
+        })
+    }
+}
 
-/// Export as STIX 2.1 bundle (JSON).
+/// NetBox is an inventory of record: pushing or importing pseudonyms would
+/// create fake devices next to the real ones, so both are refused in demo mode.
+const DEMO_MODE_NETBOX_ERROR: &str =
+    "NetBox export is unavailable in demo mode: it would create pseudonymized devices";
+
+/// The NetBox objects for the current asset inventory. Refused in demo mode.
+fn netbox_export(
+    state: &super::AppStateInner,
+    options: &NetboxOptions,
+) -> Result<NetboxExport, String> {
+    if super::demo::active_anonymizer(state).is_some() {
+        return Err(DEMO_MODE_NETBOX_ERROR.to_string());
+    }
+    Ok(gm_report::netbox::assets_to_netbox(
+        &state_assets_to_export(state),
+        options,
+    ))
+}
+
+/// Export the asset inventory as NetBox CSV bulk-import files.
+///
+/// Writes one file per object type into `output_dir` and returns their
+/// paths in the order they must be imported. Refused in demo mode.
 #[tauri::command]
-pub async fn export_stix_bundle(
-    output_path: String,
+pub async fn export_netbox_csv(
+    config: NetboxConfigInput,
+    output_dir: String,
     state: State<'_, AppState>,
-) -> Result<String, String> {
+) -> Result<Vec<String>, String> {
+    let options = config.to_options()?;
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let assets = state_assets_to_export(&inner);
-    let connections = state_connections_to_export(&inner);
-    let findings: Vec<ExportFinding> = Vec::new(); // Phase 10 will populate
+    let export = netbox_export(&inner, &options)?;
+    let files = gm_report::netbox::netbox_to_csv(&export).map_err(|e| e.to_string())?;
+
+    let dir = std::path::Path::new(&output_dir);
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let mut paths = Vec::new();
+    for (name, content) in files {
+        let path = dir.join(name);
+        std::fs::write(&path, content).map_err(|e| e.to_string())?;
+        paths.push(path.to_string_lossy().to_string());
+    }
+    log::info!(
+        "Exported {} assets as NetBox import files to: {}",
+        export.devices.len(),
+        output_dir
+    );
+    Ok(paths)
+}
 
-    let json = gm_report::stix::generate_stix_bundle(&assets, &connections, &findings)
-        .map_err(|e| e.to_string())?;
-    std::fs::write(&output_path, json).map_err(|e| e.to_string())?;
+/// Outcome of a NetBox API push.
+#[derive(Debug, Default, Serialize)]
+pub struct NetboxPushResult {
+    pub devices_created: usize,
+    /// Devices that already existed in the site and were left untouched
+    pub devices_existing: usize,
+    pub ip_addresses_created: usize,
+    /// Per-device failures; the push continues past them
+    pub errors: Vec<String>,
+}
 
-    log::info!("Exported STIX 2.1 bundle to: {}", output_path);
-    Ok(output_path)
+/// Minimal NetBox REST API client (token auth, JSON).
+struct NetboxClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl NetboxClient {
+    fn url(&self, endpoint: &str) -> String {
+        format!("{}/api/{}/", self.base_url, endpoint)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+        let response = request
+            .header("Authorization", format!("Token {}", self.token))
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| format!("NetBox request failed: {}", e))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            return Err(format!("NetBox returned {}: {}", status, body));
+        }
+        serde_json::from_str(&body).map_err(|e| e.to_string())
+    }
+
+    /// ID of the first object matching `query`, if any.
+    async fn find(&self, endpoint: &str, query: &[(&str, &str)]) -> Result<Option<u64>, String> {
+        let body = self
+            .send(self.http.get(self.url(endpoint)).query(query))
+            .await?;
+        Ok(body["results"]
+            .get(0)
+            .and_then(|object| object["id"].as_u64()))
+    }
+
+    async fn create(&self, endpoint: &str, object: serde_json::Value) -> Result<u64, String> {
+        let body = self
+            .send(self.http.post(self.url(endpoint)).json(&object))
+            .await?;
+        body["id"]
+            .as_u64()
+            .ok_or_else(|| format!("NetBox did not return an ID for the new {}", endpoint))
+    }
+
+    /// Look an object up and create it if it does not exist yet.
+    async fn ensure(
+        &self,
+        endpoint: &str,
+        query: &[(&str, &str)],
+        object: serde_json::Value,
+    ) -> Result<u64, String> {
+        match self.find(endpoint, query).await? {
+            Some(id) => Ok(id),
+            None => self.create(endpoint, object).await,
+        }
+    }
+}
+
+/// Push the asset inventory to NetBox through its REST API.
+///
+/// Manufacturers, roles, device types, and the tag are created on demand.
+/// Devices already present in the site (matched by name) are left as they
+/// are, since NetBox is the source of truth; new devices get an interface
+/// and their discovered IP as primary address. Refused in demo mode.
+#[tauri::command]
+pub async fn push_to_netbox(
+    config: NetboxConfigInput,
+    url: String,
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<NetboxPushResult, String> {
+    let options = config.to_options()?;
+    let export = {
+        let inner = state.inner.lock().map_err(|e| e.to_string())?;
+        netbox_export(&inner, &options)?
+    };
+
+    let client = NetboxClient {
+        http: reqwest::Client::new(),
+        base_url: url.trim().trim_end_matches('/').to_string(),
+        token,
+    };
+
+    let site_id = client
+        .find("dcim/sites", &[("name", &options.site)])
+        .await?
+        .ok_or_else(|| format!("NetBox site '{}' does not exist", options.site))?;
+    client
+        .ensure(
+            "extras/tags",
+            &[("slug", &options.tag)],
+            serde_json::json!({ "name": options.tag, "slug": options.tag }),
+        )
+        .await?;
+
+    let mut manufacturer_ids = HashMap::new();
+    for m in &export.manufacturers {
+        let id = client
+            .ensure(
+                "dcim/manufacturers",
+                &[("slug", &m.slug)],
+                serde_json::json!({ "name": m.name, "slug": m.slug }),
+            )
+            .await?;
+        manufacturer_ids.insert(m.slug.clone(), id);
+    }
+
+    let mut role_ids = HashMap::new();
+    for r in &export.device_roles {
+        let id = client
+            .ensure(
+                "dcim/device-roles",
+                &[("slug", &r.slug)],
+                serde_json::json!({ "name": r.name, "slug": r.slug, "color": r.color }),
+            )
+            .await?;
+        role_ids.insert(r.slug.clone(), id);
+    }
+
+    let mut device_type_ids = HashMap::new();
+    for t in &export.device_types {
+        let manufacturer_id = manufacturer_ids[&t.manufacturer_slug];
+        let id = client
+            .ensure(
+                "dcim/device-types",
+                &[
+                    ("manufacturer_id", &manufacturer_id.to_string()),
+                    ("slug", &t.slug),
+                ],
+                serde_json::json!({
+                    "manufacturer": manufacturer_id,
+                    "model": t.model,
+                    "slug": t.slug,
+                }),
+            )
+            .await?;
+        device_type_ids.insert((t.manufacturer_slug.clone(), t.slug.clone()), id);
+    }
+
+    let mut result = NetboxPushResult::default();
+    for device in &export.devices {
+        match push_netbox_device(
+            &client,
+            &export,
+            device,
+            site_id,
+            role_ids[&device.role_slug],
+            device_type_ids[&(
+                device.manufacturer_slug.clone(),
+                device.device_type_slug.clone(),
+            )],
+        )
+        .await
+        {
+            Ok(None) => result.devices_existing += 1,
+            Ok(Some(ip_created)) => {
+                result.devices_created += 1;
+                if ip_created {
+                    result.ip_addresses_created += 1;
+                }
+            }
+            Err(e) => result.errors.push(format!("{}: {}", device.name, e)),
+        }
+    }
+
+    log::info!(
+        "NetBox push to {}: {} created, {} existing, {} errors",
+        client.base_url,
+        result.devices_created,
+        result.devices_existing,
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+/// Create one device with its interface and primary IP.
+///
+/// Returns None if the device already exists, otherwise whether an IP
+/// address was created for it. An address NetBox already tracks is left
+/// with its current assignment.
+async fn push_netbox_device(
+    client: &NetboxClient,
+    export: &NetboxExport,
+    device: &gm_report::netbox::NetboxDevice,
+    site_id: u64,
+    role_id: u64,
+    device_type_id: u64,
+) -> Result<Option<bool>, String> {
+    let site = site_id.to_string();
+    if client
+        .find(
+            "dcim/devices",
+            &[("name", &device.name), ("site_id", &site)],
+        )
+        .await?
+        .is_some()
+    {
+        return Ok(None);
+    }
+
+    let tags = serde_json::json!([{ "slug": export.options.tag }]);
+    let device_id = client
+        .create(
+            "dcim/devices",
+            serde_json::json!({
+                "name": device.name,
+                "role": role_id,
+                "device_type": device_type_id,
+                "site": site_id,
+                "status": device.status,
+                "description": device.description,
+                "comments": device.comments,
+                "tags": tags,
+            }),
+        )
+        .await?;
+
+    let Some(interface) = export.interfaces.iter().find(|i| i.device == device.name) else {
+        return Ok(Some(false));
+    };
+    let mut interface_body = serde_json::json!({
+        "device": device_id,
+        "name": interface.name,
+        "type": interface.interface_type,
+        "tags": tags,
+    });
+    if let Some(ref mac) = interface.mac_address {
+        interface_body["mac_address"] = serde_json::Value::String(mac.clone());
+    }
+    let interface_id = client.create("dcim/interfaces", interface_body).await?;
+
+    let Some(ip) = export
+        .ip_addresses
+        .iter()
+        .find(|ip| ip.device == device.name)
+    else {
+        return Ok(Some(false));
+    };
+    if client
+        .find("ipam/ip-addresses", &[("address", &ip.address)])
+        .await?
+        .is_some()
+    {
+        // Already assigned elsewhere in NetBox; don't create a duplicate
+        return Ok(Some(false));
+    }
+    let ip_id = client
+        .create(
+            "ipam/ip-addresses",
+            serde_json::json!({
+                "address": ip.address,
+                "status": ip.status,
+                "assigned_object_type": "dcim.interface",
+                "assigned_object_id": interface_id,
+                "description": ip.description,
+                "tags": tags,
+            }),
+        )
+        .await?;
+    if ip.is_primary {
+        let field = if ip.address.contains(':') {
+            "primary_ip6"
+        } else {
+            "primary_ip4"
+        };
+        client
+            .send(
+                client
+                    .http
+                    .patch(format!("{}{}/", client.url("dcim/devices"), device_id))
+                    .json(&serde_json::json!({ field: ip_id })),
+            )
+            .await?;
+    }
+    Ok(Some(true))
 }
 
 // ─── Filtered PCAP Export Command ───────────────────────────
 
+/// Capture files hold the real addresses and payloads, which the demo-mode
+/// pseudonyms cannot be applied to, so PCAP exports are refused instead.
+const DEMO_MODE_PCAP_ERROR: &str =
+    "PCAP export is unavailable in demo mode: capture files hold the real addresses";
+
 /// Result of a filtered PCAP export operation.
 #[derive(serde::Serialize)]
 pub struct FilteredPcapResult {
@@ -306,6 +712,7 @@ pub struct FilteredPcapResult {
 /// If both filters are empty, all packets are exported (full copy).
 ///
 /// Only real PCAP file paths are read; ingest-source tags like `[Suricata]` are skipped.
+/// Refused in demo mode.
 #[tauri::command]
 pub async fn export_filtered_pcap(
     filter_ips: Vec<String>,
@@ -315,6 +722,9 @@ pub async fn export_filtered_pcap(
 ) -> Result<FilteredPcapResult, String> {
     let input_paths = {
         let inner = state.inner.lock().map_err(|e| e.to_string())?;
+        if super::demo::active_anonymizer(&inner).is_some() {
+            return Err(DEMO_MODE_PCAP_ERROR.to_string());
+        }
         inner.imported_files.clone()
     };
 
@@ -342,6 +752,76 @@ pub async fn export_filtered_pcap(
     })
 }
 
+/// Export the packets of one or more connections (both directions, full
+/// payloads) from the capture files they were seen in.
+///
+/// Connections are matched on their endpoints, ports and transport, so a
+/// later connection reusing the same client port is included too. Refused
+/// in demo mode.
+#[tauri::command]
+pub async fn export_connection_pcap(
+    connection_ids: Vec<String>,
+    output_path: String,
+    state: State<'_, AppState>,
+) -> Result<FilteredPcapResult, String> {
+    let (conversations, input_paths) = {
+        let inner = state.inner.lock().map_err(|e| e.to_string())?;
+        if super::demo::active_anonymizer(&inner).is_some() {
+            return Err(DEMO_MODE_PCAP_ERROR.to_string());
+        }
+        let mut conversations = Vec::new();
+        let mut input_paths: Vec<String> = Vec::new();
+        for id in &connection_ids {
+            let conn = inner
+                .connections
+                .iter()
+                .find(|c| &c.id == id)
+                .ok_or_else(|| format!("Connection {} not found", id))?;
+            conversations.push(ConversationFilter {
+                ip_a: conn.src_ip.clone(),
+                port_a: conn.src_port,
+                ip_b: conn.dst_ip.clone(),
+                port_b: conn.dst_port,
+                ip_protocol: match conn.transport.as_str() {
+                    "tcp" => Some(6),
+                    "udp" => Some(17),
+                    "icmp" => Some(1),
+                    "icmpv6" => Some(58),
+                    _ => None,
+                },
+            });
+            for file in &conn.origin_files {
+                if !file.starts_with('[') && !input_paths.contains(file) {
+                    input_paths.push(file.clone());
+                }
+            }
+        }
+        (conversations, input_paths)
+    };
+
+    if input_paths.is_empty() {
+        return Err("The selected connections were not seen in any PCAP file.".to_string());
+    }
+    let source_files = input_paths.len();
+    let packets_written =
+        gm_capture::export_conversations_pcap(&input_paths, &conversations, &output_path)
+            .map_err(|e| e.to_string())?;
+
+    log::info!(
+        "Connection PCAP export: {} packets of {} connections from {} files → {}",
+        packets_written,
+        conversations.len(),
+        source_files,
+        output_path
+    );
+
+    Ok(FilteredPcapResult {
+        output_path,
+        packets_written,
+        source_files,
+    })
+}
+
 // ─── Topology Image Export Command ──────────────────────────
 
 /// Save topology image data (PNG base64 or SVG string) to a file.
@@ -406,6 +886,16 @@ fn state_connections_to_snapshots(state: &super::AppStateInner) -> Vec<Connectio
         .collect()
 }
 
+/// Pseudonymize allowlist endpoints when demo mode is active.
+fn anonymize_allowlist(anon: Option<&gm_report::Anonymizer>, entries: &mut [AllowlistEntry]) {
+    if let Some(anon) = anon {
+        for entry in entries {
+            entry.src_ip = anon.ip(&entry.src_ip);
+            entry.dst_ip = anon.ip(&entry.dst_ip);
+        }
+    }
+}
+
 /// Generate a communication allowlist from observed network traffic.
 ///
 /// Returns one entry per unique observed flow, enriched with frequency,
@@ -418,9 +908,12 @@ pub async fn generate_communication_allowlist(
     let assets = state_assets_to_snapshots(&inner);
     let connections = state_connections_to_snapshots(&inner);
     let comm_stats = inner.connection_stats.clone();
+    let anon = super::demo::active_anonymizer(&inner);
     drop(inner);
 
-    Ok(generate_allowlist(&connections, &assets, &comm_stats))
+    let mut entries = generate_allowlist(&connections, &assets, &comm_stats);
+    anonymize_allowlist(anon.as_ref(), &mut entries);
+    Ok(entries)
 }
 
 /// Export the communication allowlist as a CSV file.
@@ -433,9 +926,11 @@ pub async fn export_allowlist_csv(
     let assets = state_assets_to_snapshots(&inner);
     let connections = state_connections_to_snapshots(&inner);
     let comm_stats = inner.connection_stats.clone();
+    let anon = super::demo::active_anonymizer(&inner);
     drop(inner);
 
-    let entries = generate_allowlist(&connections, &assets, &comm_stats);
+    let mut entries = generate_allowlist(&connections, &assets, &comm_stats);
+    anonymize_allowlist(anon.as_ref(), &mut entries);
     let csv = allowlist_to_csv(&entries);
     std::fs::write(&output_path, csv).map_err(|e| e.to_string())?;
     log::info!(
@@ -456,9 +951,11 @@ pub async fn export_firewall_rules(
     let assets = state_assets_to_snapshots(&inner);
     let connections = state_connections_to_snapshots(&inner);
     let comm_stats = inner.connection_stats.clone();
+    let anon = super::demo::active_anonymizer(&inner);
     drop(inner);
 
-    let entries = generate_allowlist(&connections, &assets, &comm_stats);
+    let mut entries = generate_allowlist(&connections, &assets, &comm_stats);
+    anonymize_allowlist(anon.as_ref(), &mut entries);
     let rules = format_firewall_rules(&entries);
     std::fs::write(&output_path, rules).map_err(|e| e.to_string())?;
     log::info!(
@@ -499,3 +996,19 @@ fn base64_decode(input: &str) -> Result<Vec<u8>, String> {
 
     Ok(output)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::{empty_state, enable_demo_mode};
+
+    #[test]
+    fn test_netbox_export_refused_in_demo_mode() {
+        let mut inner = empty_state();
+        assert!(netbox_export(&inner, &NetboxOptions::default()).is_ok());
+
+        enable_demo_mode(&mut inner);
+        let err = netbox_export(&inner, &NetboxOptions::default()).unwrap_err();
+        assert_eq!(err, DEMO_MODE_NETBOX_ERROR);
+    }
+}
@@ -1,6 +1,7 @@
 //! Tauri commands for importing external tool data.
 //!
-//! Imports Zeek logs, Suricata eve.json, Nmap XML, and Masscan JSON.
+//! Imports Zeek logs, Suricata eve.json, Nmap XML, Masscan JSON, and
+//! Windows event logs.
 //! Ingested data is merged into the existing pipeline alongside PCAP data.
 
 use serde::Serialize;
@@ -10,12 +11,17 @@ use tauri::State;
 
 use std::collections::HashMap;
 
-use gm_ingest::{IngestResult, IngestSource, IngestedAlert, IngestedAsset};
-use gm_parsers::IcsProtocol;
+use gm_analysis::AssetLifecycle;
+use gm_ingest::correlate::{self, FlowKey};
+use gm_ingest::{
+    FlowProvenance, IngestResult, IngestSource, IngestedAlert, IngestedAsset, IngestedConnection,
+    IngestedHostEvent,
+};
+use gm_parsers::{DirectionBasis, IcsProtocol};
 
 use super::{
-    AppState, AppStateInner, AssetInfo, ConnectionInfo, DeviceZeekEvents, StoredAlert,
-    ZeekEventSummary,
+    mark_connection_seen, merge_confirmed_devices, AppState, AppStateInner, AssetInfo,
+    ConnectionInfo, DeviceZeekEvents, StoredAlert, ZeekEventSummary,
 };
 
 /// Result returned to the frontend from an ingest operation.
@@ -25,7 +31,11 @@ pub struct IngestImportResult {
     pub files_processed: usize,
     pub asset_count: usize,
     pub connection_count: usize,
+    /// Ingested flows merged onto a connection already loaded from a PCAP or another source
+    pub correlated_connections: usize,
     pub alert_count: usize,
+    /// Host events kept from Windows event logs
+    pub host_event_count: usize,
     pub new_assets: usize,
     pub updated_assets: usize,
     pub duration_ms: u64,
@@ -164,7 +174,10 @@ pub async fn import_masscan_json(
 /// Assets are merged by IP address — if an asset already exists from PCAP data,
 /// the ingested data enriches it (hostname, OS, open ports) without overwriting.
 /// New assets are created for IPs not yet seen.
-/// Connections are appended with the ingest source tagged.
+/// Connections describing a flow that is already loaded (same 5-tuple in
+/// either direction, overlapping time window) are merged onto it and the
+/// source record is added to its provenance; other connections are appended
+/// with the ingest source tagged.
 fn merge_ingest_result(
     ingest: IngestResult,
     state: &AppState,
@@ -183,6 +196,7 @@ fn merge_ingest_result(
     let total_assets = ingest.assets.len();
     let total_connections = ingest.connections.len();
     let total_alerts = ingest.alerts.len();
+    let total_host_events = ingest.host_events.len();
 
     // Merge assets
     let mut new_count = 0;
@@ -206,22 +220,32 @@ fn merge_ingest_result(
     }
 
     // Merge connections — tag with ingest source in origin_files
+    let mut flow_index: HashMap<FlowKey, Vec<usize>> = HashMap::new();
+    for (idx, c) in inner.connections.iter().enumerate() {
+        flow_index
+            .entry(FlowKey::new(
+                &c.src_ip,
+                c.src_port,
+                &c.dst_ip,
+                c.dst_port,
+                &c.transport,
+            ))
+            .or_default()
+            .push(idx);
+    }
+
+    let mut correlated_count = 0;
     for ingested_conn in &ingest.connections {
         let origin = format!("[{}]", source_name);
+        let key = FlowKey::of(ingested_conn);
 
-        // Check if this connection already exists
-        if let Some(existing) = inner.connections.iter_mut().find(|c| {
-            c.src_ip == ingested_conn.src_ip
-                && c.dst_ip == ingested_conn.dst_ip
-                && c.src_port == ingested_conn.src_port
-                && c.dst_port == ingested_conn.dst_port
-        }) {
-            // Update counts
-            existing.packet_count += ingested_conn.packet_count;
-            existing.byte_count += ingested_conn.byte_count;
-            if !existing.origin_files.contains(&origin) {
-                existing.origin_files.push(origin);
-            }
+        let matched = flow_index
+            .get(&key)
+            .and_then(|candidates| find_correlated(&inner.connections, candidates, ingested_conn));
+
+        if let Some(idx) = matched {
+            merge_correlated(&mut inner.connections[idx], ingested_conn, origin);
+            correlated_count += 1;
         } else {
             // New connection
             let conn = ConnectionInfo {
@@ -245,7 +269,15 @@ fn merge_ingest_result(
                     .map(|t| t.to_rfc3339())
                     .unwrap_or_default(),
                 origin_files: vec![origin],
+                provenance: vec![provenance_of(ingested_conn)],
+                direction_basis: DirectionBasis::FlowRecord,
+                vlan_ids: Vec::new(),
+                vxlan_vnis: Vec::new(),
             };
+            flow_index
+                .entry(key)
+                .or_default()
+                .push(inner.connections.len());
             inner.connections.push(conn);
         }
     }
@@ -262,6 +294,8 @@ fn merge_ingest_result(
     for alert in &ingest.alerts {
         inner.imported_alerts.push(ingested_alert_to_stored(alert));
     }
+    inner.host_events.extend(ingest.host_events);
+    inner.host_events.sort_by_key(|e| e.timestamp);
 
     // Rebuild per-device Zeek event summaries after any Zeek import
     if ingest_source == Some(IngestSource::Zeek) {
@@ -270,7 +304,10 @@ fn merge_ingest_result(
 
     // Rebuild topology from updated connections
     // The topology builder needs to be re-run with new data
-    let mut topo = gm_topology::TopologyBuilder::new();
+    let mut topo = gm_topology::TopologyBuilder::with_subnets(super::system::subnet_plan(
+        &inner.settings.profile,
+        &inner.physical_topology,
+    ));
     for conn in &inner.connections {
         let protocol = IcsProtocol::from_name(&conn.protocol);
         topo.add_connection(
@@ -281,8 +318,20 @@ fn merge_ingest_result(
             protocol,
             conn.byte_count,
         );
+        topo.add_ports(
+            &conn.src_ip,
+            &conn.dst_ip,
+            conn.src_port,
+            conn.dst_port,
+            conn.byte_count,
+        );
+        mark_connection_seen(&mut topo, conn);
     }
-    inner.topology = topo.snapshot();
+    let previous = std::mem::replace(&mut inner.topology, topo.snapshot());
+    merge_confirmed_devices(&mut inner);
+    // Connections have no per-packet times, so keep the traffic histograms
+    // bucketed when the packets were processed
+    inner.topology.carry_traffic(&previous);
 
     // Enrich topology nodes with asset data
     // Collect asset lookup first to avoid borrow conflict
@@ -315,7 +364,9 @@ fn merge_ingest_result(
         files_processed: ingest.files_processed,
         asset_count: total_assets,
         connection_count: total_connections,
+        correlated_connections: correlated_count,
         alert_count: total_alerts,
+        host_event_count: total_host_events,
         new_assets: new_count,
         updated_assets: updated_count,
         duration_ms,
@@ -323,6 +374,120 @@ fn merge_ingest_result(
     })
 }
 
+/// Provenance entry for an ingested flow record.
+fn provenance_of(conn: &IngestedConnection) -> FlowProvenance {
+    FlowProvenance {
+        source: conn.source.display_name().to_string(),
+        uid: conn.uid.clone(),
+    }
+}
+
+/// Pick the loaded connection an ingested flow describes, if any.
+///
+/// `candidates` share the flow's direction-insensitive 5-tuple. A connection
+/// that already carries the same source UID is a re-import and always
+/// matches; otherwise the time windows must overlap. Same-direction
+/// connections are preferred over the reverse direction of a PCAP flow.
+fn find_correlated(
+    connections: &[ConnectionInfo],
+    candidates: &[usize],
+    ingested: &IngestedConnection,
+) -> Option<usize> {
+    let provenance = provenance_of(ingested);
+    if provenance.uid.is_some() {
+        if let Some(&idx) = candidates
+            .iter()
+            .find(|&&idx| connections[idx].provenance.contains(&provenance))
+        {
+            return Some(idx);
+        }
+    }
+
+    let overlapping = candidates.iter().copied().filter(|&idx| {
+        let c = &connections[idx];
+        correlate::windows_overlap(
+            (
+                correlate::parse_timestamp(&c.first_seen),
+                correlate::parse_timestamp(&c.last_seen),
+            ),
+            (ingested.first_seen, ingested.last_seen),
+        )
+    });
+    overlapping.min_by_key(|&idx| {
+        let c = &connections[idx];
+        // false sorts first: same direction wins
+        !(c.src_ip == ingested.src_ip && c.src_port == ingested.src_port)
+    })
+}
+
+/// Merge an ingested flow onto the connection it was correlated with.
+///
+/// Counts from a PCAP are packet-exact and are kept as-is. Between ingest
+/// sources, a second record of the same flow from another sensor takes the
+/// larger count (same traffic), while further records from the same source
+/// (a long flow split by the sensor) add up. A re-imported record changes
+/// nothing but the time window.
+fn merge_correlated(existing: &mut ConnectionInfo, ingested: &IngestedConnection, origin: String) {
+    let provenance = provenance_of(ingested);
+    let reimport = provenance.uid.is_some() && existing.provenance.contains(&provenance);
+
+    // The flow record's originator is better evidence than the order
+    // packets happened to be captured in
+    let weak_basis = matches!(
+        existing.direction_basis,
+        DirectionBasis::FirstPacket | DirectionBasis::Unknown
+    );
+    if weak_basis {
+        if existing.src_ip == ingested.dst_ip && existing.src_port == ingested.dst_port {
+            existing.swap_ends();
+        }
+        existing.direction_basis = DirectionBasis::FlowRecord;
+    }
+    let has_pcap = existing.origin_files.iter().any(|f| !f.starts_with('['));
+
+    if !reimport && !has_pcap {
+        if existing
+            .provenance
+            .iter()
+            .any(|p| p.source == provenance.source)
+        {
+            existing.packet_count += ingested.packet_count;
+            existing.byte_count += ingested.byte_count;
+        } else {
+            existing.packet_count = existing.packet_count.max(ingested.packet_count);
+            existing.byte_count = existing.byte_count.max(ingested.byte_count);
+        }
+    }
+
+    // PCAP labels unrecognised payloads "Unknown"; Zeek/Suricata analyzers
+    // may know better
+    if correlate::is_generic_protocol(&existing.protocol)
+        && !correlate::is_generic_protocol(&ingested.protocol)
+    {
+        existing.protocol = ingested.protocol.clone();
+    }
+
+    if let Some(first) = ingested.first_seen {
+        match correlate::parse_timestamp(&existing.first_seen) {
+            Some(current) if current <= first => {}
+            _ => existing.first_seen = first.to_rfc3339(),
+        }
+    }
+    if let Some(last) = ingested.last_seen {
+        match correlate::parse_timestamp(&existing.last_seen) {
+            Some(current) if current >= last => {}
+            _ => existing.last_seen = last.to_rfc3339(),
+        }
+    }
+
+    if !existing.origin_files.contains(&origin) {
+        existing.origin_files.push(origin);
+    }
+    if !existing.provenance.contains(&provenance) {
+        existing.provenance.push(provenance);
+    }
+}
+
 /// Enrich an existing asset with data from an ingested asset.
 fn enrich_asset(existing: &mut AssetInfo, ingested: &IngestedAsset, is_active: bool) {
     // Add new protocols
@@ -335,6 +500,7 @@ fn enrich_asset(existing: &mut AssetInfo, ingested: &IngestedAsset, is_active: b
     // Hostname — prefer existing, fill if missing
     if existing.hostname.is_none() && ingested.hostname.is_some() {
         existing.hostname = ingested.hostname.clone();
+        existing.hostname_source = Some("import".to_string());
     }
 
     // Vendor — prefer existing (from signatures/OUI), fill if missing
@@ -408,8 +574,8 @@ fn create_asset_from_ingested(ingested: &IngestedAsset, is_active: bool) -> Asse
         .map(|p| IcsProtocol::from_name(p))
         .collect();
     let has_server_ports = ingested.open_ports.iter().any(|p| {
-        matches!(p.port, 102 | 502 | 1089..=1091 | 1883 | 2222 | 2404 | 4840
-            | 5007 | 5094 | 8883 | 18245 | 18246 | 20000 | 34962..=34964 | 44818 | 47808)
+        matches!(p.port, 102 | 502 | 789 | 1089..=1091 | 1200 | 1217 | 1502 | 1883 | 1911 | 2222 | 2404 | 2455 | 4840
+            | 4911 | 5006 | 5007 | 5094 | 8883 | 9600 | 11740 | 18245 | 18246 | 20000 | 20171 | 20172 | 34962..=34964 | 44818 | 47808 | 48898)
     });
     let device_type = ingested
         .device_type
@@ -421,6 +587,7 @@ fn create_asset_from_ingested(ingested: &IngestedAsset, is_active: bool) -> Asse
         ip_address: ingested.ip_address.clone(),
         mac_address: ingested.mac_address.clone(),
         hostname: ingested.hostname.clone(),
+        hostname_source: ingested.hostname.as_ref().map(|_| "import".to_string()),
         device_type,
         vendor: ingested.vendor.clone(),
         protocols: ingested.protocols.clone(),
@@ -433,9 +600,13 @@ fn create_asset_from_ingested(ingested: &IngestedAsset, is_active: bool) -> Asse
         confidence: if ingested.vendor.is_some() { 2 } else { 1 },
         product_family: None,
         signature_matches: Vec::new(),
+        protocol_evidence: Vec::new(),
         oui_vendor: None,
         country: None,
         is_public_ip: gm_db::GeoIpLookup::is_public_ip(&ingested.ip_address),
+        lifecycle: AssetLifecycle::Active,
+        lifecycle_changed_at: None,
+        vlan_ids: Vec::new(),
     }
 }
 
@@ -542,14 +713,19 @@ pub async fn get_device_zeek_events(
     state: State<'_, AppState>,
 ) -> Result<DeviceZeekEvents, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
-    Ok(inner
+    let mut events = inner
         .zeek_device_events
-        .get(&device_ip)
+        .get(&super::demo::resolve_ip(&inner, &device_ip))
         .cloned()
-        .unwrap_or_else(|| DeviceZeekEvents {
-            device_ip: device_ip.clone(),
-            ..Default::default()
-        }))
+        .unwrap_or_default();
+    events.device_ip = device_ip;
+    if let Some(anon) = super::demo::active_anonymizer(&inner) {
+        for event in &mut events.sample_events {
+            event.peer_ip = anon.ip(&event.peer_ip);
+            event.summary = super::demo::scrub_text(&anon, &inner, &event.summary);
+        }
+    }
+    Ok(events)
 }
 
 /// Import a SINEMA Server CSV device inventory export.
@@ -603,3 +779,168 @@ pub async fn import_tia_xml(
 
     Ok(import_result)
 }
+
+/// Import Windows Security/System event log exports (XML or JSON).
+///
+/// Events name the computer that logged them; `host_ip` ties them to an
+/// asset when that name is not known from traffic.
+#[tauri::command]
+pub async fn import_windows_events(
+    paths: Vec<String>,
+    host_ip: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IngestImportResult, String> {
+    let start = Instant::now();
+
+    let path_refs: Vec<&Path> = paths.iter().map(|p| Path::new(p.as_str())).collect();
+    let mut ingest_result =
+        gm_ingest::winevent::parse_windows_events(&path_refs).map_err(|e| e.to_string())?;
+    if let Some(ip) = host_ip.filter(|ip| !ip.trim().is_empty()) {
+        let ip = {
+            let inner = state.inner.lock().map_err(|e| e.to_string())?;
+            super::demo::resolve_ip(&inner, ip.trim())
+        };
+        for event in &mut ingest_result.host_events {
+            event.host_ip = Some(ip.clone());
+        }
+    }
+
+    let import_result = merge_ingest_result(ingest_result, &state, start)?;
+
+    log::info!(
+        "Windows event log import: {} files → {} host events, {} assets ({} new), {}ms",
+        import_result.files_processed,
+        import_result.host_event_count,
+        import_result.asset_count,
+        import_result.new_assets,
+        import_result.duration_ms
+    );
+
+    Ok(import_result)
+}
+
+/// How long after a host event OT traffic from the host is linked to it.
+const HOST_EVENT_FOLLOW_SECS: i64 = 3600;
+
+/// A Windows event relating to one asset, with the OT traffic that followed.
+#[derive(Serialize)]
+pub struct HostEventView {
+    #[serde(flatten)]
+    pub event: IngestedHostEvent,
+    /// "host" when the asset logged the event, "remote" when a logon came from it
+    pub relation: &'static str,
+    /// First OT conversation the asset opened after the event, within the window
+    pub next_ot_connection: Option<FollowingConnection>,
+}
+
+/// An OT connection started shortly after a host event.
+#[derive(Serialize)]
+pub struct FollowingConnection {
+    pub connection_id: String,
+    pub protocol: String,
+    pub dst_ip: String,
+    pub dst_port: u16,
+    pub first_seen: String,
+    /// Seconds between the host event and the connection's first packet
+    pub delay_secs: i64,
+}
+
+/// Windows events logged on an asset or naming it as a logon's origin, in
+/// time order, each with the first OT connection the asset opened within
+/// `window_secs` (default one hour) after it.
+///
+/// An event belongs to the asset when its import gave the asset's address,
+/// or else when its computer name matches the asset's hostname (first label,
+/// case-insensitive), so "EWS01.plant.local" matches a NetBIOS name "EWS01".
+#[tauri::command]
+pub async fn get_host_events(
+    ip: String,
+    window_secs: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<HostEventView>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let ip = super::demo::resolve_ip(&inner, &ip);
+    let window = chrono::Duration::seconds(window_secs.unwrap_or(HOST_EVENT_FOLLOW_SECS).max(0));
+    let asset_name = inner
+        .assets
+        .iter()
+        .find(|a| a.ip_address == ip)
+        .and_then(|a| a.hostname.as_deref())
+        .map(short_host_name);
+
+    // OT conversations the asset opened, by start time
+    let mut ot_connections: Vec<(chrono::DateTime<chrono::Utc>, &ConnectionInfo)> = inner
+        .connections
+        .iter()
+        .filter(|c| c.src_ip == ip && IcsProtocol::from_name(&c.protocol).is_ot())
+        .filter_map(|c| {
+            chrono::DateTime::parse_from_rfc3339(&c.first_seen)
+                .ok()
+                .map(|t| (t.with_timezone(&chrono::Utc), c))
+        })
+        .collect();
+    ot_connections.sort_by_key(|(t, _)| *t);
+
+    let mut views: Vec<HostEventView> = inner
+        .host_events
+        .iter()
+        .filter_map(|event| {
+            let logged_here = match event.host_ip.as_deref() {
+                Some(host_ip) => host_ip == ip,
+                None => asset_name.as_deref() == Some(short_host_name(&event.computer).as_str()),
+            };
+            let relation = if logged_here {
+                "host"
+            } else if event.remote_ip.as_deref() == Some(ip.as_str()) {
+                "remote"
+            } else {
+                return None;
+            };
+
+            let next_ot_connection = ot_connections
+                .iter()
+                .find(|(t, _)| *t >= event.timestamp)
+                .filter(|(t, _)| *t - event.timestamp <= window)
+                .map(|(t, c)| FollowingConnection {
+                    connection_id: c.id.clone(),
+                    protocol: c.protocol.clone(),
+                    dst_ip: c.dst_ip.clone(),
+                    dst_port: c.dst_port,
+                    first_seen: c.first_seen.clone(),
+                    delay_secs: (*t - event.timestamp).num_seconds(),
+                });
+
+            Some(HostEventView {
+                event: event.clone(),
+                relation,
+                next_ot_connection,
+            })
+        })
+        .collect();
+
+    if let Some(anon) = super::demo::active_anonymizer(&inner) {
+        let host = |h: &mut Option<String>| *h = h.as_deref().map(|h| anon.hostname(h));
+        let ip = |ip: &mut Option<String>| *ip = ip.as_deref().map(|ip| anon.ip(ip));
+        for view in &mut views {
+            let event = &mut view.event;
+            event.computer = anon.hostname(&event.computer);
+            ip(&mut event.host_ip);
+            host(&mut event.user);
+            ip(&mut event.remote_ip);
+            host(&mut event.remote_host);
+            event.detail = event
+                .detail
+                .as_deref()
+                .map(|d| super::demo::scrub_text(&anon, &inner, d));
+            if let Some(ref mut next) = view.next_ot_connection {
+                next.dst_ip = anon.ip(&next.dst_ip);
+            }
+        }
+    }
+    Ok(views)
+}
+
+/// First label of a host name, lowercased ("EWS01.plant.local" → "ews01").
+fn short_host_name(name: &str) -> String {
+    name.split('.').next().unwrap_or(name).to_ascii_lowercase()
+}
@@ -1,33 +1,74 @@
+#[cfg(feature = "gui")]
 pub mod analysis;
+#[cfg(feature = "gui")]
 pub mod baseline;
 pub mod capture;
+#[cfg(feature = "gui")]
+pub mod connectivity;
+#[cfg(feature = "gui")]
 pub mod correlation;
+#[cfg(feature = "gui")]
 pub mod data;
+pub mod datasets;
+#[cfg(feature = "gui")]
+pub mod demo;
+#[cfg(feature = "gui")]
 pub mod export;
+#[cfg(feature = "gui")]
 pub mod ingest;
+#[cfg(feature = "gui")]
 pub mod patterns;
 pub mod physical;
 pub mod processor;
+#[cfg(feature = "gui")]
 pub mod projects;
+#[cfg(feature = "gui")]
 pub mod segmentation;
 pub mod session;
+#[cfg(feature = "gui")]
 pub mod signatures;
 pub mod system;
+#[cfg(test)]
+mod test_support;
+pub mod trace;
+#[cfg(feature = "gui")]
 pub mod wireshark;
 
-use gm_analysis::{AnomalyScore, ConnectionStats, Finding, PatternAnomaly, PurdueAssignment};
+#[cfg(feature = "gui")]
+use gm_analysis::{AnomalyScore, Finding, PurdueAssignment, WritePath};
+use gm_analysis::{
+    AssetLifecycle, ConnectionStats, DeviceSessionHealth, PatternAnomaly, ProtocolSession,
+    ShiftActivity,
+};
+#[cfg(feature = "gui")]
 use gm_capture::LiveCaptureHandle;
-use gm_db::{Database, GeoIpLookup, OuiLookup};
-use gm_parsers::IcsProtocol;
-use gm_parsers::RedundancyInfo;
-use gm_physical::{InferredTopology, PhysicalTopology};
+#[cfg(feature = "gui")]
+use gm_db::DatasetStatus;
+use gm_db::{Database, GeoIpLookup, NamedProfile, OpenMode, OuiLookup};
+use gm_ingest::FlowProvenance;
+#[cfg(feature = "gui")]
+use gm_ingest::IngestedHostEvent;
+use gm_parsers::{
+    ArpBinding, GooseStream, IcmpActivity, ProfinetRtStream, RedundancyInfo, SvStream,
+};
+use gm_parsers::{
+    CloudCategory, DirectionBasis, IcsProtocol, IdentificationMethod, ProtocolIdentification,
+};
+#[cfg(feature = "gui")]
+use gm_physical::InferredTopology;
+use gm_physical::PhysicalTopology;
+#[cfg(feature = "gui")]
 use gm_segmentation::SegmentationReport;
-use gm_signatures::SignatureEngine;
+use gm_signatures::{PacketData, SignatureEngine};
+#[cfg(feature = "gui")]
+use gm_topology::TopologyBuilder;
 use gm_topology::TopologyGraph;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::atomic::AtomicBool;
-use std::sync::{Arc, Mutex};
+use std::sync::Mutex;
+#[cfg(feature = "gui")]
+use std::sync::{atomic::AtomicBool, Arc};
+#[cfg(feature = "gui")]
 use std::thread::JoinHandle;
 
 /// Shared application state, managed by Tauri.
@@ -40,12 +81,16 @@ pub struct AppState {
     /// Set to true to cancel an in-progress PCAP import. Lives outside the
     /// Mutex so it can be read/written by the import thread and the cancel
     /// command without acquiring the heavy state lock.
+    #[cfg(feature = "gui")]
     pub import_cancelled: Arc<AtomicBool>,
 }
 
 pub struct AppStateInner {
     /// The current network topology graph
     pub topology: TopologyGraph,
+    /// Groups of addresses the analyst confirmed belong to one device
+    /// (a host with several NICs), merged into one node on every rebuild
+    pub device_identities: Vec<Vec<String>>,
     /// All discovered assets
     pub assets: Vec<AssetInfo>,
     /// All observed connections
@@ -56,51 +101,105 @@ pub struct AppStateInner {
     pub imported_files: Vec<String>,
     /// Signature engine for device fingerprinting
     pub signature_engine: SignatureEngine,
+    /// The global signature engine, set aside while `signature_engine` is
+    /// a session's pinned signature set (`None` when nothing is pinned)
+    #[cfg(feature = "gui")]
+    pub unpinned_signature_engine: Option<SignatureEngine>,
+    /// Per-IP packet samples from the last import/capture, kept so reloaded
+    /// signatures can be re-matched without re-reading the PCAP (not persisted)
+    pub signature_packets: HashMap<String, Vec<PacketData>>,
     /// Deep parse results grouped by IP address
     pub deep_parse_info: HashMap<String, DeepParseInfo>,
     /// Handle to the running live capture (None if not capturing)
+    #[cfg(feature = "gui")]
     pub live_capture: Option<LiveCaptureHandle>,
     /// Join handle for the live capture processing thread
+    #[cfg(feature = "gui")]
     pub processing_thread: Option<JoinHandle<()>>,
     /// IEEE OUI vendor lookup table
     pub oui_lookup: OuiLookup,
     /// GeoIP country lookup
     pub geoip_lookup: GeoIpLookup,
+    /// Load/verification status of bundled OUI/GeoIP/signature datasets
+    #[cfg(feature = "gui")]
+    pub data_status: Vec<DatasetStatus>,
     /// SQLite database for persistence
     pub db: Option<Database>,
+    /// Why `db` was opened read-only (another instance holds the writer lock)
+    #[cfg(feature = "gui")]
+    pub db_notice: Option<String>,
     /// Currently loaded session ID (None if no session loaded)
+    #[cfg(feature = "gui")]
     pub current_session_id: Option<String>,
     /// Currently loaded session name
+    #[cfg(feature = "gui")]
     pub current_session_name: Option<String>,
     /// Active project ID (None if no project selected)
     pub current_project_id: Option<i64>,
     /// Physical topology from Cisco/JunOS/Aruba config/CAM/CDP/ARP imports
     pub physical_topology: PhysicalTopology,
     /// Traffic-inferred topology from packet analysis
+    #[cfg(feature = "gui")]
     pub inferred_topology: Option<InferredTopology>,
     /// Security findings from the last analysis run
+    #[cfg(feature = "gui")]
     pub findings: Vec<Finding>,
     /// Purdue level assignments from the last analysis run
+    #[cfg(feature = "gui")]
     pub purdue_assignments: Vec<PurdueAssignment>,
     /// Anomaly scores from the last analysis run
+    #[cfg(feature = "gui")]
     pub anomalies: Vec<AnomalyScore>,
+    /// Write-capable master → target paths from the last analysis run,
+    /// with their approval status
+    #[cfg(feature = "gui")]
+    pub write_paths: Vec<WritePath>,
     /// Per-connection timing statistics (computed after import / capture)
     pub connection_stats: Vec<ConnectionStats>,
     /// Communication pattern anomalies (computed alongside connection_stats)
     pub pattern_anomalies: Vec<PatternAnomaly>,
+    /// Traffic per quarter hour of the week, sliced into shifts on request
+    pub shift_activity: ShiftActivity,
     /// Redundancy protocol frames observed (MRP/RSTP/HSR/PRP/DLR)
     pub redundancy_protocols: Vec<RedundancyInfo>,
+    /// IPv4 → MAC bindings learned from ARP, with every MAC that claimed each address
+    pub arp_bindings: Vec<ArpBinding>,
+    /// ICMP echo fan-out and unreachable reports per discovering host
+    pub icmp_activity: Vec<IcmpActivity>,
+    /// IEC 61850 GOOSE control blocks observed, with stNum/sqNum sequence state
+    pub goose_streams: Vec<GooseStream>,
+    /// IEC 61850-9-2 Sampled Values publications observed, with sample rates
+    pub sv_streams: Vec<SvStream>,
+    /// PROFINET IO cyclic RT relations observed, with cycle times
+    pub profinet_rt_streams: Vec<ProfinetRtStream>,
+    /// Modbus/DNP3 TCP sessions with their protocol state
+    pub protocol_sessions: Vec<ProtocolSession>,
     /// Alerts imported from external IDS/SIEM tools (Suricata, Wazuh)
+    #[cfg(feature = "gui")]
     pub imported_alerts: Vec<StoredAlert>,
+    /// Logons, service installs and similar events from imported Windows event logs
+    #[cfg(feature = "gui")]
+    pub host_events: Vec<IngestedHostEvent>,
     /// Per-device Zeek event summaries (rebuilt on each Zeek import)
+    #[cfg(feature = "gui")]
     pub zeek_device_events: HashMap<String, DeviceZeekEvents>,
     /// Cached result of the last segmentation analysis run (Phase 15)
+    #[cfg(feature = "gui")]
     pub segmentation_report: Option<SegmentationReport>,
+    /// Demo mode: pseudonymize identifiers in all views and exports
+    #[cfg(feature = "gui")]
+    pub demo_mode: bool,
+    /// Session-scoped pseudonymization seed (persisted with the session)
+    pub demo_seed: Option<u64>,
+    /// Active settings profile: capture limits, retention, analysis rules
+    /// and custom port maps
+    pub settings: NamedProfile,
 }
 
 /// An alert imported from an external IDS/SIEM and stored in AppState.
 ///
 /// Flattened from IngestedAlert for direct serialization.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredAlert {
     /// RFC 3339 timestamp of the alert
@@ -122,6 +221,7 @@ pub struct StoredAlert {
 }
 
 /// A single Zeek-observed event summarised for display.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ZeekEventSummary {
     /// RFC 3339 timestamp (connection first_seen)
@@ -137,6 +237,7 @@ pub struct ZeekEventSummary {
 /// Per-device aggregate of Zeek-observed events.
 ///
 /// Built from connections that have "[Zeek]" in their origin_files.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DeviceZeekEvents {
     pub device_ip: String,
@@ -161,6 +262,10 @@ pub struct AssetInfo {
     pub ip_address: String,
     pub mac_address: Option<String>,
     pub hostname: Option<String>,
+    /// Where the hostname came from: "manual" for analyst edits (never
+    /// replaced by a re-import), otherwise the protocol that named the host
+    #[serde(default)]
+    pub hostname_source: Option<String>,
     pub device_type: String,
     pub vendor: Option<String>,
     pub protocols: Vec<String>,
@@ -176,6 +281,9 @@ pub struct AssetInfo {
     pub product_family: Option<String>,
     /// All signature matches for this asset
     pub signature_matches: Vec<AssetSignatureMatch>,
+    /// How each protocol in `protocols` was identified
+    #[serde(default)]
+    pub protocol_evidence: Vec<ProtocolEvidence>,
     /// Vendor name from IEEE OUI database (MAC prefix lookup)
     #[serde(default)]
     pub oui_vendor: Option<String>,
@@ -185,6 +293,15 @@ pub struct AssetInfo {
     /// Whether this IP is a public (routable) address
     #[serde(default)]
     pub is_public_ip: bool,
+    /// Analyst-set lifecycle state (active, quarantined, decommissioned, planned)
+    #[serde(default)]
+    pub lifecycle: AssetLifecycle,
+    /// When the lifecycle state was last changed (RFC 3339)
+    #[serde(default)]
+    pub lifecycle_changed_at: Option<String>,
+    /// 802.1Q VLAN IDs the asset's own frames were tagged with
+    #[serde(default)]
+    pub vlan_ids: Vec<u16>,
 }
 
 /// A signature match result attached to an asset.
@@ -198,6 +315,26 @@ pub struct AssetSignatureMatch {
     pub role: Option<String>,
 }
 
+/// Why a protocol label was applied to an asset: the strongest
+/// identification seen for it across the asset's traffic.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolEvidence {
+    pub protocol: String,
+    pub method: IdentificationMethod,
+    /// 0-100
+    pub confidence: u8,
+}
+
+impl ProtocolEvidence {
+    pub fn from_identification(identification: &ProtocolIdentification) -> Self {
+        Self {
+            protocol: format!("{:?}", identification.protocol).to_lowercase(),
+            method: identification.method,
+            confidence: identification.confidence,
+        }
+    }
+}
+
 /// Connection information stored in application state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionInfo {
@@ -216,6 +353,28 @@ pub struct ConnectionInfo {
     pub last_seen: String,
     /// Which PCAP files contributed packets to this connection
     pub origin_files: Vec<String>,
+    /// Zeek/Suricata flow records correlated onto this connection
+    #[serde(default)]
+    pub provenance: Vec<FlowProvenance>,
+    /// How the client (src) and server (dst) ends were told apart
+    #[serde(default)]
+    pub direction_basis: DirectionBasis,
+    /// 802.1Q VLAN IDs seen on the connection's frames (two for a routed
+    /// connection captured on a trunk carrying both sides)
+    #[serde(default)]
+    pub vlan_ids: Vec<u16>,
+    /// VXLAN network identifiers of the overlay(s) the connection ran in
+    #[serde(default)]
+    pub vxlan_vnis: Vec<u32>,
+}
+
+impl ConnectionInfo {
+    /// Exchange the source and destination ends.
+    pub fn swap_ends(&mut self) {
+        std::mem::swap(&mut self.src_ip, &mut self.dst_ip);
+        std::mem::swap(&mut self.src_port, &mut self.dst_port);
+        std::mem::swap(&mut self.src_mac, &mut self.dst_mac);
+    }
 }
 
 /// Lightweight packet summary for the connection tree detail view.
@@ -233,6 +392,7 @@ pub struct PacketSummary {
 }
 
 /// Protocol statistics.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize)]
 pub struct ProtocolStatInfo {
     pub protocol: String,
@@ -261,12 +421,189 @@ pub struct DeepParseInfo {
     pub bacnet: Option<BacnetDetail>,
     /// IEC 60870-5-104 details (present if device speaks IEC 104)
     pub iec104: Option<Iec104Detail>,
+    /// OMRON FINS details (present if device speaks FINS)
+    pub fins: Option<FinsDetail>,
+    /// MELSEC SLMP / MC protocol details (present if device speaks SLMP)
+    pub slmp: Option<SlmpDetail>,
+    /// GE SRTP details (present if device speaks SRTP)
+    pub ge_srtp: Option<GeSrtpDetail>,
+    /// CODESYS runtime protocol details (present if device speaks CODESYS)
+    pub codesys: Option<CodesysDetail>,
+    /// Niagara Fox details (present if device speaks Fox)
+    pub fox: Option<FoxDetail>,
+    /// Red Lion Crimson v3 details (present if device speaks Crimson)
+    pub crimson: Option<CrimsonDetail>,
+    /// Yokogawa Vnet/IP details (present if device is a Vnet/IP station)
+    pub vnet_ip: Option<VnetIpDetail>,
+    /// Triconex TriStation details (present if device speaks TriStation)
+    pub tristation: Option<TriStationDetail>,
+    /// Beckhoff ADS/AMS details (present if device speaks ADS)
+    pub ads: Option<AdsDetail>,
+    /// Classic OPC DA / A&E details (present if device spoke OPC over DCOM)
+    pub opc_da: Option<OpcDaDetail>,
+    /// IEC 61850 MMS details (present if device speaks MMS)
+    pub mms: Option<MmsDetail>,
+    /// IEC 61850 GOOSE details (present if the device's MAC publishes GOOSE)
+    pub goose: Option<GooseDetail>,
+    /// IEC 61850 Sampled Values details (present if the device's MAC publishes SV)
+    pub sv: Option<SvDetail>,
     /// PROFINET DCP details (present if device speaks PROFINET DCP)
     pub profinet_dcp: Option<ProfinetDcpDetail>,
+    /// PROFINET IO cyclic RT details (present if the device's MAC exchanges IO data)
+    pub profinet_rt: Option<ProfinetRtDetail>,
     /// LLDP details (present if device advertised itself via LLDP)
     pub lldp: Option<LldpDetail>,
     /// SNMP device identity (present if device responded to SNMP GET)
     pub snmp: Option<SnmpDetail>,
+    /// SMB file-sharing details (present if device mounted or served shares)
+    pub smb: Option<SmbDetail>,
+    /// DHCP details (present if device took a lease or handed leases out)
+    pub dhcp: Option<DhcpDetail>,
+    /// Engineering software fingerprints (present if device ran PLC programming tools)
+    pub engineering: Option<EngineeringDetail>,
+    /// Modbus/DNP3 TCP session health (present if the device took part in a session)
+    pub sessions: Option<DeviceSessionHealth>,
+    /// Cloud / remote-access endpoints this device connected out to
+    pub cloud: Option<CloudDetail>,
+    /// JA3 / JA3S fingerprints of the device's TLS handshakes
+    pub tls: Option<TlsDetail>,
+    /// MQTT client IDs and topics (present if device spoke cleartext MQTT)
+    pub mqtt: Option<MqttDetail>,
+    /// Serial lines tunneled through the device (present if it is a Moxa or
+    /// Lantronix serial device server)
+    pub serial_gateway: Option<SerialGatewayDetail>,
+}
+
+/// A serial device server and the field devices polled through it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialGatewayDetail {
+    /// Operating mode, e.g. "Moxa NPort (TCP Server)"
+    pub gateway: String,
+    pub vendor: String,
+    pub lines: Vec<SerialLineDetail>,
+}
+
+/// One serial line of a device server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialLineDetail {
+    /// TCP port the line is tunneled on
+    pub port: u16,
+    /// 1-based serial port number on the gateway
+    pub channel: u8,
+    /// "raw" or "rfc2217"
+    pub framing: String,
+    /// Set over RFC 2217
+    pub baud_rate: Option<u32>,
+    /// Data bits, parity and stop bits, e.g. "8N1" (set over RFC 2217)
+    pub line_format: Option<String>,
+    /// Serial protocols seen on the line: "modbus_rtu", "dnp3"
+    pub protocols: Vec<String>,
+    /// Hosts that polled through the line
+    pub hosts: Vec<String>,
+    /// Field devices addressed on the line
+    pub devices: Vec<SerialDevice>,
+}
+
+/// A field device behind a serial device server, known by its link address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialDevice {
+    /// "modbus_rtu" or "dnp3"
+    pub protocol: String,
+    /// Modbus slave address or DNP3 outstation address
+    pub address: u16,
+    pub frame_count: u64,
+}
+
+/// Engineering software observed from a workstation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineeringDetail {
+    /// Product names, e.g. "Siemens STEP 7 / TIA Portal"
+    pub software: Vec<String>,
+}
+
+/// Cloud and remote-access endpoints a device connected out to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudDetail {
+    /// Endpoints ordered by first sighting
+    pub endpoints: Vec<CloudEndpoint>,
+}
+
+/// A single outbound connection to a cloud service, broker or reverse-connect client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudEndpoint {
+    pub remote_ip: String,
+    pub remote_port: u16,
+    /// Host name from TLS SNI or the MQTT username, if seen
+    pub hostname: Option<String>,
+    /// Service name, e.g. "Azure IoT Hub" or "MQTT broker"
+    pub service: String,
+    pub category: CloudCategory,
+    /// What identified the connection: "TLS SNI", "MQTT CONNECT" or "OPC UA ReverseHello"
+    pub evidence: String,
+    /// MQTT client ID or OPC UA ServerUri announced by the device
+    pub identity: Option<String>,
+    pub first_seen: String,
+}
+
+/// TLS stacks a device was seen using.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsDetail {
+    /// Fingerprints ordered by first sighting
+    pub fingerprints: Vec<TlsFingerprint>,
+}
+
+/// One TLS hello fingerprint of a device: JA3 when it opened the handshake,
+/// JA3S when it answered one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsFingerprint {
+    pub role: gm_parsers::TlsHelloKind,
+    /// MD5 of the fingerprint string, the form JA3 lists are published in
+    pub hash: String,
+    /// Unhashed JA3 / JA3S string
+    pub fingerprint: String,
+    /// Highest version offered or version chosen, e.g. "TLS 1.2"
+    pub version: String,
+    pub cipher_suites: Vec<u16>,
+    pub alpn: Vec<String>,
+    /// SNI host names sent with this fingerprint (client side only)
+    pub server_names: Vec<String>,
+    /// Hosts on the other end of these handshakes
+    pub peers: Vec<String>,
+    pub handshakes: u64,
+    pub first_seen: String,
+}
+
+/// MQTT sessions of a device and the topics it moved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttDetail {
+    /// Detected role: "client" or "broker"
+    pub role: String,
+    /// Client IDs the device connected with, or that connected to it as broker
+    pub client_ids: Vec<String>,
+    /// Brokers the device connected to, or clients of this broker
+    pub peers: Vec<String>,
+    /// Topics published or subscribed to, ordered by first sighting
+    pub topics: Vec<MqttTopic>,
+}
+
+/// One topic a device published to or subscribed to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttTopic {
+    /// Topic name, or topic filter (may contain `+` / `#`) for subscriptions
+    pub topic: String,
+    /// "publish" or "subscribe"
+    pub action: String,
+    /// Highest QoS used or requested
+    pub qos: u8,
+    /// At least one PUBLISH had the retain flag set
+    pub retained: bool,
+    /// PUBLISH or SUBSCRIBE packets seen
+    pub messages: u64,
+    /// Application payload bytes published
+    pub bytes: u64,
+    /// Hosts the packets were sent to
+    pub peers: Vec<String>,
+    pub first_seen: String,
 }
 
 /// EtherNet/IP aggregated details for a device.
@@ -280,6 +617,28 @@ pub struct EnipDetail {
     pub cip_file_access: bool,
     /// IP sent ListIdentity requests (network discovery)
     pub list_identity_requests: bool,
+    /// Identity from the device's own ListIdentity response
+    #[serde(default)]
+    pub identity: Option<EnipIdentityInfo>,
+}
+
+/// Device identity reported in an EtherNet/IP ListIdentity response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnipIdentityInfo {
+    pub vendor_id: u16,
+    pub vendor_name: String,
+    /// CIP device profile code (0x0E = PLC, 0x0C = communications adapter)
+    pub device_type: u16,
+    pub device_type_name: String,
+    pub product_code: u16,
+    pub product_name: String,
+    /// Firmware revision as "major.minor"
+    pub revision: String,
+    /// Serial number as 8 hex digits
+    pub serial_number: String,
+    pub status: u16,
+    /// 0 = nonexistent, 3 = operational, 5 = major fault
+    pub state: u8,
 }
 
 /// S7comm aggregated details for a device.
@@ -304,6 +663,9 @@ pub struct BacnetDetail {
     pub reinitialize_device: bool,
     /// DeviceCommunicationControl service was seen
     pub device_communication_control: bool,
+    /// Device object identity from I-Am and ReadProperty/ReadPropertyMultiple acks
+    #[serde(default)]
+    pub device: Option<gm_parsers::BacnetDeviceProperties>,
 }
 
 /// PROFINET DCP aggregated details for a device.
@@ -313,6 +675,30 @@ pub struct ProfinetDcpDetail {
     pub role: String,
     /// Station name from DCP Name-of-Station block
     pub device_name: Option<String>,
+    /// "Type of Station" string from the DCP Manufacturer block (e.g. "S7-1500")
+    #[serde(default)]
+    pub station_type: Option<String>,
+    /// PI-assigned vendor ID from the DCP Device ID block
+    #[serde(default)]
+    pub vendor_id: Option<u16>,
+    /// Vendor-assigned device ID from the DCP Device ID block
+    #[serde(default)]
+    pub device_id: Option<u16>,
+    /// Vendor name resolved from `vendor_id`
+    #[serde(default)]
+    pub vendor: Option<String>,
+    /// Product line resolved from `vendor_id` + `device_id`, when known
+    #[serde(default)]
+    pub product: Option<String>,
+}
+
+/// PROFINET IO cyclic real-time traffic of a device, matched by MAC address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfinetRtDetail {
+    /// Role from the cyclic traffic: "io_controller", "io_device", or "unknown"
+    pub role: String,
+    /// Cyclic relations the device provides or consumes, with cycle times
+    pub relations: Vec<ProfinetRtStream>,
 }
 
 /// LLDP (Link Layer Discovery Protocol) details for a device.
@@ -357,6 +743,63 @@ pub struct SnmpDetail {
     pub sys_contact: Option<String>,
     /// Vendor name inferred from enterprise OID
     pub vendor: Option<String>,
+    /// Model designation taken from sysDescr
+    pub model: Option<String>,
+}
+
+/// SMB file-sharing details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmbDetail {
+    /// Detected role: "client" (mounts shares) or "server" (file server)
+    pub role: String,
+    /// NTLM accounts that authenticated from this device (`DOMAIN\user`)
+    pub users: Vec<String>,
+    /// Device spoke SMB1
+    pub smb1: bool,
+    /// Shares this device mounted or served
+    pub shares: Vec<SmbShareAccess>,
+}
+
+/// Activity on one SMB share between a client and a file server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmbShareAccess {
+    pub remote_ip: String,
+    /// "client" or "server" — what the REMOTE device is
+    pub remote_role: String,
+    /// UNC path of the share (e.g. `\\hist01\exports`)
+    pub share: String,
+    /// Files opened on the share (first 50)
+    pub files: Vec<String>,
+    /// Files opened for writing or created
+    pub files_written: Vec<String>,
+    pub bytes_written: u64,
+    pub bytes_read: u64,
+}
+
+/// DHCP details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DhcpDetail {
+    /// Detected role: "client" (took a lease) or "server" (handed leases out)
+    pub role: String,
+    /// Client: hostname the device sent in option 12 (or option 81)
+    pub hostname: Option<String>,
+    /// Client: vendor class identifier from option 60 (e.g. "MSFT 5.0")
+    pub vendor_class: Option<String>,
+    /// Client: server that acknowledged the lease
+    pub server_ip: Option<String>,
+    /// Server: clients it sent offers or ACKs to
+    pub leases: Vec<DhcpLease>,
+    /// Server: offers, ACKs and NAKs sent
+    pub replies: u64,
+}
+
+/// A client a DHCP server answered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DhcpLease {
+    pub client_mac: String,
+    /// Address the client ended up with, if an ACK or renewal was seen
+    pub ip: Option<String>,
+    pub hostname: Option<String>,
 }
 
 /// IEC 60870-5-104 aggregated details for a device.
@@ -372,6 +815,257 @@ pub struct Iec104Detail {
     pub has_interrogation: bool,
 }
 
+/// OMRON FINS aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinsDetail {
+    /// Detected role: "client" (HMI/SCADA/engineering tool) or "server" (PLC)
+    pub role: String,
+    /// Command codes sent or answered, as "MRCSRC Name" (e.g. "0101 Memory Area Read")
+    pub commands_seen: Vec<String>,
+    /// FINS node addresses used by this device
+    pub node_addresses: Vec<u8>,
+    /// Device sent memory, parameter, file, forced-bit or clock writes
+    pub has_writes: bool,
+    /// Device sent Run/Stop or program area writes
+    pub has_control_commands: bool,
+    /// CPU model from a Controller Data Read (05 01) response
+    pub controller_model: Option<String>,
+    /// CPU version from a Controller Data Read (05 01) response
+    pub controller_version: Option<String>,
+}
+
+/// Mitsubishi MELSEC SLMP / MC protocol aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlmpDetail {
+    /// Detected role: "client" (HMI/SCADA/GX Works) or "server" (PLC)
+    pub role: String,
+    /// Commands sent by this device, as "CMD Name" (e.g. "0401 Batch Read")
+    pub commands_seen: Vec<String>,
+    /// Device areas read or written on this PLC (e.g. "D", "M", "X")
+    pub device_areas: Vec<String>,
+    /// Device sent device, buffer memory or file writes
+    pub has_writes: bool,
+    /// Device sent remote RUN/STOP/PAUSE/RESET or latch clear
+    pub has_control_commands: bool,
+    /// CPU model from a Read CPU Model Name (0101) response
+    pub cpu_model: Option<String>,
+    /// CPU model code from a Read CPU Model Name (0101) response
+    pub cpu_model_code: Option<u16>,
+}
+
+/// GE SRTP aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeSrtpDetail {
+    /// Detected role: "client" (HMI/historian/Machine Edition) or "server" (PLC)
+    pub role: String,
+    /// Service requests sent by this device, as "CODE Name" (e.g. "04 Read System Memory")
+    pub services_seen: Vec<String>,
+    /// Memory types read or written on this PLC (e.g. "%R", "%M")
+    pub memory_segments: Vec<String>,
+    /// Device sent memory, program block, clock or fault-table writes
+    pub has_writes: bool,
+    /// Device sent Set PLC State, privilege changes or program downloads
+    pub has_control_commands: bool,
+    /// Last PLC state reported in a response status word
+    pub plc_state: Option<String>,
+    /// Catalog number from a Return Controller Type and ID (43) response
+    pub controller_model: Option<String>,
+    /// Controller ID from a Return Controller Type and ID (43) response
+    pub controller_id: Option<String>,
+    /// Firmware revision from a Return Controller Type and ID (43) response
+    pub firmware: Option<String>,
+}
+
+/// CODESYS runtime protocol aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodesysDetail {
+    /// Detected role: "client" (Development System/gateway) or "server" (runtime)
+    pub role: String,
+    /// Protocol generations observed ("V2", "V3")
+    pub versions: Vec<String>,
+    /// Services requested by this device (e.g. "Application: Download")
+    pub services_seen: Vec<String>,
+    /// Device logged into a runtime
+    pub has_login: bool,
+    /// Device downloaded an application to a runtime
+    pub has_download: bool,
+    /// Device applied an online change to a running application
+    pub has_online_change: bool,
+    /// Device started, stopped or reset an application
+    pub has_control_commands: bool,
+}
+
+/// Niagara Fox aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoxDetail {
+    /// Detected role: "client" (Workbench/Supervisor) or "server" (station)
+    pub role: String,
+    /// Station name from the station's hello
+    pub station_name: Option<String>,
+    /// Host name the device reported
+    pub host_name: Option<String>,
+    /// Application name ("Station", "Workbench")
+    pub app_name: Option<String>,
+    /// Niagara version (e.g. "4.10.1.36")
+    pub niagara_version: Option<String>,
+    /// Niagara AX (3.x), which is end of life
+    pub is_legacy: bool,
+    pub os_name: Option<String>,
+    pub os_version: Option<String>,
+    pub vm_name: Option<String>,
+    /// Hardware host ID (licensing)
+    pub host_id: Option<String>,
+    /// OEM brand ID (e.g. "vykon", "Honeywell")
+    pub brand_id: Option<String>,
+    /// Strongest authentication scheme the station offers
+    pub auth_mode: Option<String>,
+    /// Authentication agents the station advertises
+    pub auth_agents: Vec<String>,
+}
+
+/// Red Lion Crimson v3 aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrimsonDetail {
+    /// Detected role: "client" (Crimson software) or "server" (Red Lion device)
+    pub role: String,
+    /// Manufacturer string the device reported
+    pub manufacturer: Option<String>,
+    /// Model string the device reported (e.g. "G306A", "DA30D")
+    pub model: Option<String>,
+    /// Device sent a configuration database to a Red Lion device
+    pub has_download: bool,
+    /// Device read a configuration database from a Red Lion device
+    pub has_upload: bool,
+    /// Peers on the other end of configuration transfers
+    pub transfer_peers: Vec<String>,
+    /// Bulk transfer bytes sent by this device
+    pub bulk_bytes_sent: u64,
+}
+
+/// Yokogawa Vnet/IP aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VnetIpDetail {
+    /// Control domain number
+    pub domain: u8,
+    /// Station number within the domain
+    pub station: u8,
+    /// Vnet/IP bus this address is on (1 or 2)
+    pub bus: u8,
+    /// Station number in DDSS form (e.g. "0164")
+    pub station_number: String,
+    /// The same station's address on the other bus, if seen
+    pub other_bus_ip: Option<String>,
+    /// Vnet/IP stations this station exchanged datagrams with
+    pub peers: Vec<String>,
+}
+
+/// Triconex TriStation aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriStationDetail {
+    /// Detected role: "client" (TriStation 1131 workstation) or "server" (Triconex controller)
+    pub role: String,
+    /// Commands sent by this device (e.g. "Start Download Change", "Halt Program")
+    pub commands: Vec<String>,
+    /// Device downloaded a program or configuration to a controller
+    pub has_download: bool,
+    /// Device uploaded the safety program from a controller
+    pub has_upload: bool,
+    /// Device ran or halted the safety program
+    pub has_run_state_change: bool,
+    /// Workstations or controllers on the other end of TriStation commands
+    pub peers: Vec<String>,
+}
+
+/// Beckhoff ADS/AMS aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdsDetail {
+    /// Detected role: "client" (engineering station, HMI) or "server" (TwinCAT runtime)
+    pub role: String,
+    /// AMS NetIDs this device used (e.g. "5.23.20.78.1.1")
+    pub ams_net_ids: Vec<String>,
+    /// AMS ports addressed on this device (851 = first TwinCAT 3 PLC runtime)
+    pub ams_ports: Vec<u16>,
+    /// ADS commands sent by this device
+    pub commands: Vec<String>,
+    /// Device name from Read Device Info (e.g. "Plc30 App")
+    pub device_name: Option<String>,
+    /// TwinCAT version from Read Device Info (e.g. "3.1.4024")
+    pub twincat_version: Option<String>,
+    /// Last ADS state the device reported (e.g. "Run")
+    pub ads_state: Option<String>,
+    /// Device wrote PLC variables
+    pub has_write: bool,
+    /// ADS states this device requested with Write Control (e.g. "Stop")
+    pub state_changes: Vec<String>,
+    /// ADS peers, busiest first
+    pub relationships: Vec<AdsRelationship>,
+}
+
+/// A relationship between an ADS client and a TwinCAT runtime.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdsRelationship {
+    pub remote_ip: String,
+    /// "client" or "server" — what the REMOTE device is
+    pub remote_role: String,
+    /// AMS NetIDs the remote device used
+    pub remote_net_ids: Vec<String>,
+    pub request_count: u64,
+}
+
+/// Classic OPC (DCOM) aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpcDaDetail {
+    /// Detected role: "client" (HMI, historian collector) or "server" (OPC server)
+    pub role: String,
+    /// OPC interfaces bound between this device and its peers (e.g. "IOPCSyncIO")
+    pub interfaces: Vec<String>,
+    /// IOPCEventServer was used (OPC Alarms & Events)
+    pub has_alarms_events: bool,
+    /// The server pushes subscription data back through IOPCDataCallback
+    pub has_callbacks: bool,
+    /// OPC clients or servers on the other end
+    pub peers: Vec<String>,
+    /// DCE/RPC requests exchanged with those peers
+    pub request_count: u64,
+}
+
+/// IEC 61850 MMS aggregated details for a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MmsDetail {
+    /// Detected role: "client" (SCADA/gateway/HMI) or "server" (IED)
+    pub role: String,
+    /// MMS services observed from this device (snake_case names, sorted)
+    pub services_seen: Vec<String>,
+    /// Logical devices (MMS domains) hosted by this IED
+    pub logical_devices: Vec<String>,
+    /// Logical nodes referenced on this IED (e.g. "XCBR1", "MMXU1")
+    pub logical_nodes: Vec<String>,
+    /// Report IDs (RptID) of reports published by this IED
+    pub report_ids: Vec<String>,
+    /// Device sent MMS Write or other state-changing services
+    pub has_writes: bool,
+    /// Vendor name from an MMS Identify response
+    pub vendor: Option<String>,
+    /// Model name from an MMS Identify response
+    pub model: Option<String>,
+    /// Revision from an MMS Identify response
+    pub revision: Option<String>,
+}
+
+/// IEC 61850 GOOSE publications from a device, matched by MAC address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GooseDetail {
+    /// One entry per GOOSE control block the device publishes
+    pub publications: Vec<GooseStream>,
+}
+
+/// IEC 61850-9-2 Sampled Values publications from a device, matched by MAC address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SvDetail {
+    /// One entry per svID the device publishes
+    pub publications: Vec<SvStream>,
+}
+
 /// Aggregated Modbus details for a device.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModbusDetail {
@@ -389,6 +1083,27 @@ pub struct ModbusDetail {
     pub relationships: Vec<ModbusRelationship>,
     /// Polling intervals detected (in milliseconds)
     pub polling_intervals: Vec<PollingInterval>,
+    /// Whether this device sent raw Modbus RTU frames over TCP (no MBAP)
+    #[serde(default)]
+    pub rtu_over_tcp: bool,
+    /// Register address-space map per unit ID, built from the requests
+    /// this device received as a slave
+    #[serde(default)]
+    pub register_maps: Vec<ModbusRegisterMap>,
+    /// Request/response pairing per unit ID, for devices that answered
+    /// as a slave: latency, exceptions, timeouts and retries
+    #[serde(default)]
+    pub transaction_stats: Vec<gm_analysis::ModbusTransactionStats>,
+}
+
+/// The registers of one slave unit that masters actually read or wrote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusRegisterMap {
+    pub unit_id: u8,
+    /// Masters whose requests make up the map
+    pub masters: Vec<String>,
+    /// Exercised regions, by table and address
+    pub regions: Vec<gm_parsers::RegisterRegion>,
 }
 
 /// DNP3 aggregated details for a device.
@@ -402,8 +1117,19 @@ pub struct Dnp3Detail {
     pub function_codes: Vec<FunctionCodeStat>,
     /// Whether unsolicited responses were detected from this device
     pub has_unsolicited: bool,
+    /// Whether this device sent Secure Authentication (SAv5) challenges,
+    /// replies, or group 120 objects
+    #[serde(default)]
+    pub uses_secure_auth: bool,
+    /// Link-layer CRC mismatches in frames this device sent; usually a
+    /// noisy serial segment behind a serial-to-IP gateway
+    #[serde(default)]
+    pub crc_errors: u64,
     /// IPs this device communicates with
     pub relationships: Vec<Dnp3Relationship>,
+    /// Master link addresses this device sent frames from
+    #[serde(default)]
+    pub master_bindings: Vec<Dnp3AddressBinding>,
 }
 
 /// Function code usage statistics.
@@ -416,6 +1142,21 @@ pub struct FunctionCodeStat {
     pub is_write: bool,
 }
 
+impl FunctionCodeStat {
+    /// Name and write/control flag from the function code registry.
+    pub fn from_registry(protocol: gm_parsers::FunctionCodeProtocol, code: u8, count: u64) -> Self {
+        let def = gm_parsers::function_code_info(protocol, code);
+        Self {
+            code,
+            name: def
+                .as_ref()
+                .map_or_else(|| "Unknown".to_string(), |d| d.name.clone()),
+            count,
+            is_write: def.is_some_and(|d| d.is_write_or_control()),
+        }
+    }
+}
+
 /// Register range accessed by a Modbus device.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegisterRangeInfo {
@@ -456,6 +1197,19 @@ pub struct Dnp3Relationship {
     pub packet_count: u64,
 }
 
+/// A DNP3 master link address as used from one IP towards one outstation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dnp3AddressBinding {
+    /// Link-layer source address of the master frames
+    pub master_address: u16,
+    /// Link-layer destination (outstation) address
+    pub outstation_address: u16,
+    pub outstation_ip: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub frame_count: u64,
+}
+
 /// Detected polling interval for a master→slave relationship.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PollingInterval {
@@ -473,126 +1227,164 @@ pub struct PollingInterval {
 }
 
 impl AppState {
-    pub fn new() -> Self {
-        let mut engine = SignatureEngine::new();
-
-        // Load default signatures from the bundled signatures/ directory.
-        // Try multiple paths: relative to binary (production) and relative to src-tauri/ (dev).
-        let signature_dirs = [
-            std::path::PathBuf::from("signatures"),
-            std::path::PathBuf::from("../src-tauri/signatures"),
-            // When running via `cargo tauri dev`, CWD is the project root
-            std::path::PathBuf::from("src-tauri/signatures"),
-        ];
-
-        for dir in &signature_dirs {
-            if dir.exists() {
-                match engine.load_directory(dir) {
-                    Ok(count) => {
-                        log::info!("Loaded {} signatures from {}", count, dir.display());
-                        break;
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to load signatures from {}: {}", dir.display(), e);
-                    }
-                }
-            }
-        }
+    /// Create the application state, loading bundled datasets from
+    /// `resource_dir` (the Tauri resource directory) or the dev tree.
+    ///
+    /// Opens `db_path`, or ~/.kusanaginokajiki/data.db when None, falling
+    /// back to read-only if another instance is writing to it.
+    pub fn new(
+        resource_dir: Option<&std::path::Path>,
+        db_path: Option<&std::path::Path>,
+        db_mode: OpenMode,
+    ) -> Self {
+        let bundled = datasets::load_bundled_data(resource_dir);
 
-        // Load OUI database
-        let oui_paths = [
-            std::path::PathBuf::from("data/oui.tsv"),
-            std::path::PathBuf::from("../src-tauri/data/oui.tsv"),
-            std::path::PathBuf::from("src-tauri/data/oui.tsv"),
-        ];
-        let mut oui_lookup = OuiLookup::empty();
-        for path in &oui_paths {
-            if path.exists() {
-                match OuiLookup::load_from_file(path) {
-                    Ok(lookup) => {
-                        oui_lookup = lookup;
-                        break;
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to load OUI from {}: {}", path.display(), e);
-                    }
+        // Without the desktop app, the read-only notice is only logged
+        #[cfg_attr(not(feature = "gui"), allow(unused_variables))]
+        let (db, db_notice) = match db_path
+            .map(std::path::Path::to_path_buf)
+            .or_else(system::default_db_path)
+        {
+            Some(db_path) => match system::open_database(&db_path, db_mode) {
+                Ok((db, notice)) => (Some(db), notice),
+                Err(e) => {
+                    log::warn!("Failed to open database at {}: {}", db_path.display(), e);
+                    (None, None)
                 }
-            }
-        }
-
-        // Load GeoIP database
-        let geoip_paths = [
-            std::path::PathBuf::from("data/dbip-country-lite.mmdb"),
-            std::path::PathBuf::from("../src-tauri/data/dbip-country-lite.mmdb"),
-            std::path::PathBuf::from("src-tauri/data/dbip-country-lite.mmdb"),
-        ];
-        let mut geoip_lookup = GeoIpLookup::empty();
-        for path in &geoip_paths {
-            if path.exists() {
-                match GeoIpLookup::load_from_file(path) {
-                    Ok(lookup) => {
-                        geoip_lookup = lookup;
-                        break;
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to load GeoIP from {}: {}", path.display(), e);
-                    }
-                }
-            }
-        }
-
-        // Open SQLite database at ~/.kusanaginokajiki/data.db
-        let db = match dirs::home_dir() {
-            Some(home) => {
-                let db_path = home.join(".kusanaginokajiki").join("data.db");
-                match Database::open(&db_path) {
-                    Ok(db) => Some(db),
-                    Err(e) => {
-                        log::warn!("Failed to open database at {}: {}", db_path.display(), e);
-                        None
-                    }
-                }
-            }
+            },
             None => {
                 log::warn!("Could not determine home directory for database");
-                None
+                (None, None)
             }
         };
 
+        let settings = system::load_startup_settings(db.as_ref());
+
         AppState {
+            #[cfg(feature = "gui")]
             import_cancelled: Arc::new(AtomicBool::new(false)),
             inner: Mutex::new(AppStateInner {
                 topology: TopologyGraph::default(),
+                device_identities: Vec::new(),
                 assets: Vec::new(),
                 connections: Vec::new(),
                 packet_summaries: HashMap::new(),
                 imported_files: Vec::new(),
-                signature_engine: engine,
+                signature_engine: bundled.signature_engine,
+                #[cfg(feature = "gui")]
+                unpinned_signature_engine: None,
+                signature_packets: HashMap::new(),
                 deep_parse_info: HashMap::new(),
+                #[cfg(feature = "gui")]
                 live_capture: None,
+                #[cfg(feature = "gui")]
                 processing_thread: None,
-                oui_lookup,
-                geoip_lookup,
+                oui_lookup: bundled.oui_lookup,
+                geoip_lookup: bundled.geoip_lookup,
+                #[cfg(feature = "gui")]
+                data_status: bundled.status,
                 db,
+                #[cfg(feature = "gui")]
+                db_notice,
+                #[cfg(feature = "gui")]
                 current_session_id: None,
+                #[cfg(feature = "gui")]
                 current_session_name: None,
                 current_project_id: None,
                 physical_topology: PhysicalTopology::default(),
+                #[cfg(feature = "gui")]
                 inferred_topology: None,
+                #[cfg(feature = "gui")]
                 findings: Vec::new(),
+                #[cfg(feature = "gui")]
                 purdue_assignments: Vec::new(),
+                #[cfg(feature = "gui")]
                 anomalies: Vec::new(),
+                #[cfg(feature = "gui")]
+                write_paths: Vec::new(),
                 connection_stats: Vec::new(),
                 pattern_anomalies: Vec::new(),
+                shift_activity: ShiftActivity::new(),
                 redundancy_protocols: Vec::new(),
+                arp_bindings: Vec::new(),
+                icmp_activity: Vec::new(),
+                goose_streams: Vec::new(),
+                sv_streams: Vec::new(),
+                profinet_rt_streams: Vec::new(),
+                protocol_sessions: Vec::new(),
+                #[cfg(feature = "gui")]
                 imported_alerts: Vec::new(),
+                #[cfg(feature = "gui")]
+                host_events: Vec::new(),
+                #[cfg(feature = "gui")]
                 zeek_device_events: HashMap::new(),
+                #[cfg(feature = "gui")]
                 segmentation_report: None,
+                #[cfg(feature = "gui")]
+                demo_mode: false,
+                demo_seed: None,
+                settings,
             }),
         }
     }
 }
 
+/// Keep analyst-set lifecycle states when a new import or capture rebuilds
+/// the asset list.
+///
+/// States are matched by asset ID. Non-active assets that are absent from the
+/// new traffic (planned devices not yet installed, retired devices that stayed
+/// retired) are kept so the inventory does not silently forget them.
+pub(crate) fn carry_over_lifecycle(previous: &[AssetInfo], assets: &mut Vec<AssetInfo>) {
+    let mut previous: HashMap<&str, &AssetInfo> = previous
+        .iter()
+        .filter(|a| a.lifecycle != AssetLifecycle::Active)
+        .map(|a| (a.id.as_str(), a))
+        .collect();
+    for asset in assets.iter_mut() {
+        if let Some(prev) = previous.remove(asset.id.as_str()) {
+            asset.lifecycle = prev.lifecycle;
+            asset.lifecycle_changed_at = prev.lifecycle_changed_at.clone();
+        }
+    }
+    let mut missing: Vec<AssetInfo> = previous.into_values().cloned().collect();
+    missing.sort_by(|a, b| a.id.cmp(&b.id));
+    assets.extend(missing);
+}
+
+/// Carry a connection's first/last-seen times onto its topology edge when a
+/// topology is rebuilt from stored connections.
+#[cfg(feature = "gui")]
+pub(crate) fn mark_connection_seen(topo: &mut TopologyBuilder, conn: &ConnectionInfo) {
+    for seen in [&conn.first_seen, &conn.last_seen] {
+        if let Ok(t) = chrono::DateTime::parse_from_rfc3339(seen) {
+            topo.mark_seen(&conn.src_ip, &conn.dst_ip, t.with_timezone(&chrono::Utc));
+        }
+    }
+}
+
+/// Merge the analyst-confirmed multi-interface devices into single nodes of
+/// a freshly built topology.
+pub(crate) fn merge_confirmed_devices(inner: &mut AppStateInner) {
+    inner.topology.merge_devices(&inner.device_identities);
+}
+
+/// Keep analyst-set hostnames when a new import or capture rebuilds the
+/// asset list, so names from DNS, DHCP or LLDP never replace them.
+pub(crate) fn carry_over_manual_hostnames(previous: &[AssetInfo], assets: &mut [AssetInfo]) {
+    let manual: HashMap<&str, &Option<String>> = previous
+        .iter()
+        .filter(|a| a.hostname_source.as_deref() == Some("manual"))
+        .map(|a| (a.id.as_str(), &a.hostname))
+        .collect();
+    for asset in assets.iter_mut() {
+        if let Some(hostname) = manual.get(asset.id.as_str()) {
+            asset.hostname = (*hostname).clone();
+            asset.hostname_source = Some("manual".to_string());
+        }
+    }
+}
+
 /// Infer device type based on which protocols it speaks and its role.
 pub fn infer_device_type(protocols: &[IcsProtocol], is_server: bool) -> String {
     // If it responds on OT protocol ports, it's likely an OT device
@@ -602,18 +1394,40 @@ pub fn infer_device_type(protocols: &[IcsProtocol], is_server: bool) -> String {
     let has_s7 = protocols.contains(&IcsProtocol::S7comm);
     let has_bacnet = protocols.contains(&IcsProtocol::Bacnet);
     let has_opc_ua = protocols.contains(&IcsProtocol::OpcUa);
+    let has_opc_da = protocols.contains(&IcsProtocol::OpcDa);
     let has_ge_srtp = protocols.contains(&IcsProtocol::GeSrtp);
     let has_suitelink = protocols.contains(&IcsProtocol::WonderwareSuitelink);
+    let has_fins = protocols.contains(&IcsProtocol::Fins);
+    let has_slmp = protocols.contains(&IcsProtocol::Slmp);
+    let has_codesys = protocols.contains(&IcsProtocol::Codesys);
+    let has_crimson = protocols.contains(&IcsProtocol::Crimson);
+    let has_mms = protocols.contains(&IcsProtocol::Iec61850Mms);
 
     let ot_protocol_count = protocols.iter().filter(|p| p.is_ot()).count();
 
     if is_server && ot_protocol_count >= 1 {
         // Server responding on OT ports → likely PLC/RTU
-        if has_ethernet_ip || has_s7 || has_ge_srtp || has_bacnet {
-            // Allen-Bradley (EtherNet/IP), Siemens (S7), GE (SRTP), BACnet controller
+        if has_mms {
+            // IEC 61850 MMS server — substation IED (protection relay, bay controller)
+            "ied".to_string()
+        } else if has_crimson {
+            // Crimson v3 server — Red Lion operator panel or Data Station
+            "hmi".to_string()
+        } else if has_ethernet_ip
+            || has_s7
+            || has_ge_srtp
+            || has_fins
+            || has_slmp
+            || has_codesys
+            || has_bacnet
+        {
+            // Allen-Bradley (EtherNet/IP), Siemens (S7), GE (SRTP), OMRON (FINS), Mitsubishi (SLMP),
+            // CODESYS runtime, BACnet controller
             "plc".to_string()
         } else if has_modbus || has_dnp3 {
             "rtu".to_string()
+        } else if has_opc_da {
+            "scada_server".to_string()
         } else {
             "unknown".to_string()
         }
@@ -622,7 +1436,7 @@ pub fn infer_device_type(protocols: &[IcsProtocol], is_server: bool) -> String {
     } else if ot_protocol_count >= 2 {
         // Client talking multiple OT protocols → likely HMI or SCADA server
         "hmi".to_string()
-    } else if has_opc_ua && ot_protocol_count == 1 {
+    } else if (has_opc_ua || has_opc_da) && ot_protocol_count == 1 {
         "historian".to_string()
     } else if ot_protocol_count == 0 {
         "it_device".to_string()
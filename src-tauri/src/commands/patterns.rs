@@ -5,8 +5,12 @@
 //! PCAP import or live capture.
 
 use super::AppState;
-use gm_analysis::{ConnectionStats, PatternAnomaly};
-use gm_parsers::RedundancyInfo;
+use gm_analysis::{
+    default_shifts, ConnectionStats, PatternAnomaly, ProtocolSession, ShiftReport, ShiftWindow,
+};
+use gm_parsers::{
+    ArpBinding, GooseStream, IcmpActivity, ProfinetRtStream, RedundancyInfo, SvStream,
+};
 use tauri::State;
 
 /// Get per-connection timing statistics for the current dataset.
@@ -23,6 +27,39 @@ pub fn get_pattern_anomalies(state: State<'_, AppState>) -> Result<Vec<PatternAn
     Ok(inner.pattern_anomalies.clone())
 }
 
+/// Compare traffic across recurring shift windows.
+///
+/// `shifts` defaults to a weekday day/swing/night schedule plus the
+/// weekend; `utc_offset_minutes` is the site's offset from UTC.
+#[tauri::command]
+pub fn get_shift_report(
+    state: State<'_, AppState>,
+    shifts: Option<Vec<ShiftWindow>>,
+    utc_offset_minutes: Option<i32>,
+) -> Result<ShiftReport, String> {
+    let shifts = shifts.unwrap_or_else(default_shifts);
+    if let Some(bad) = shifts
+        .iter()
+        .find(|s| s.days.iter().any(|&d| d > 6) || s.start_minute >= 1440 || s.end_minute > 1440)
+    {
+        return Err(format!("Invalid shift window '{}'", bad.name));
+    }
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner
+        .shift_activity
+        .report(&shifts, utc_offset_minutes.unwrap_or(0)))
+}
+
+/// Get Modbus/DNP3 TCP sessions with their protocol state.
+///
+/// One entry per TCP session (a reused 4-tuple after teardown is a new
+/// session), including unanswered request and half-open indicators.
+#[tauri::command]
+pub fn get_protocol_sessions(state: State<'_, AppState>) -> Result<Vec<ProtocolSession>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner.protocol_sessions.clone())
+}
+
 /// Get observed Layer-2 redundancy protocol frames (MRP/RSTP/HSR/PRP/DLR).
 ///
 /// Returns one entry per unique source MAC address (last-frame-wins).
@@ -32,3 +69,53 @@ pub fn get_redundancy_protocols(state: State<'_, AppState>) -> Result<Vec<Redund
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
     Ok(inner.redundancy_protocols.clone())
 }
+
+/// Get the IPv4 → MAC bindings learned from ARP.
+///
+/// One entry per address; more than one MAC means the address was claimed
+/// by several hosts (duplicate IP, ARP spoofing, or a failover pair).
+#[tauri::command]
+pub fn get_arp_bindings(state: State<'_, AppState>) -> Result<Vec<ArpBinding>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner.arp_bindings.clone())
+}
+
+/// Get the ICMP discovery activity of each host that pinged or was told a
+/// destination was unreachable, widest echo fan-out first.
+#[tauri::command]
+pub fn get_icmp_activity(state: State<'_, AppState>) -> Result<Vec<IcmpActivity>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner.icmp_activity.clone())
+}
+
+/// Get observed IEC 61850 GOOSE control blocks (Layer 2, EtherType 0x88B8).
+///
+/// One entry per (publisher MAC, gocbRef), including stNum/sqNum regression
+/// counters used to spot replayed or injected GOOSE messages.
+#[tauri::command]
+pub fn get_goose_streams(state: State<'_, AppState>) -> Result<Vec<GooseStream>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner.goose_streams.clone())
+}
+
+/// Get observed IEC 61850-9-2 Sampled Values publications (Layer 2, EtherType 0x88BA).
+///
+/// One entry per (publisher MAC, svID) with the derived sample rate and
+/// smpCnt discontinuity counter.
+#[tauri::command]
+pub fn get_sv_streams(state: State<'_, AppState>) -> Result<Vec<SvStream>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner.sv_streams.clone())
+}
+
+/// Get observed PROFINET IO cyclic RT relations (Layer 2, EtherType 0x8892).
+///
+/// One entry per (provider MAC, consumer MAC, FrameID) with the update time
+/// from the frame cycle counter and the measured inter-frame intervals.
+#[tauri::command]
+pub fn get_profinet_rt_streams(
+    state: State<'_, AppState>,
+) -> Result<Vec<ProfinetRtStream>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner.profinet_rt_streams.clone())
+}
@@ -2,20 +2,34 @@
 //!
 //! Supports importing Cisco IOS, Juniper JunOS, and HP/Aruba ProCurve
 //! configs, MAC address tables, LLDP/CDP neighbors, and ARP tables.
-//! Also supports traffic-inferred topology from observed packet flows.
+//! Also supports traffic-inferred topology from observed packet flows,
+//! and picks up LLDP/CDP frames from captures as neighbor data and ARP
+//! from captures as IP → MAC bindings. Switch VLANs, together with the
+//! 802.1Q tags seen on each asset's frames, give the logical topology its
+//! VLAN grouping.
 
+#[cfg(feature = "gui")]
 use std::path::Path;
+#[cfg(feature = "gui")]
 use tauri::State;
 
+use gm_parsers::ArpBinding;
+#[cfg(feature = "gui")]
 use gm_physical::inference::{AssetSnapshot as InfAssetSnapshot, ConnSnapshot, InferenceInput};
-use gm_physical::{aruba, cisco, inference, juniper, InferredTopology, PhysicalTopology};
+#[cfg(feature = "gui")]
+use gm_physical::{aruba, cisco, inference, juniper, InferredTopology};
+use gm_physical::{ArpEntry, NeighborAdvertisement, PhysicalTopology};
+#[cfg(feature = "gui")]
+use gm_topology::VlanMap;
 
-use super::AppState;
+#[cfg(feature = "gui")]
+use super::{AppState, AppStateInner};
 
 /// Import a Cisco IOS running-config file.
 ///
 /// Parses the config for hostname, interfaces, VLANs, and IPs,
 /// then adds the switch to the physical topology.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn import_cisco_config(
     path: String,
@@ -45,19 +59,24 @@ pub fn import_cisco_config(
         path
     );
 
-    Ok(state_inner.physical_topology.clone())
+    Ok(physical_topology_view(&state_inner))
 }
 
 /// Import a `show mac address-table` output file.
 ///
 /// Associates MAC addresses with switch ports. Requires a switch
 /// hostname to know which switch this data belongs to.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn import_mac_table(
     path: String,
     switch_hostname: String,
     state: State<'_, AppState>,
 ) -> Result<PhysicalTopology, String> {
+    let switch_hostname = {
+        let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+        super::demo::resolve_switch(&state_inner, &switch_hostname)
+    };
     let file_path = Path::new(&path);
     let entries = cisco::parse_mac_table_file(file_path).map_err(|e| e.to_string())?;
 
@@ -90,18 +109,23 @@ pub fn import_mac_table(
         switch_hostname
     );
 
-    Ok(state_inner.physical_topology.clone())
+    Ok(physical_topology_view(&state_inner))
 }
 
 /// Import a `show cdp neighbors detail` output file.
 ///
 /// Discovers physical adjacencies between switches.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn import_cdp_neighbors(
     path: String,
     switch_hostname: String,
     state: State<'_, AppState>,
 ) -> Result<PhysicalTopology, String> {
+    let switch_hostname = {
+        let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+        super::demo::resolve_switch(&state_inner, &switch_hostname)
+    };
     let file_path = Path::new(&path);
     let neighbors = cisco::parse_cdp_neighbors_file(file_path).map_err(|e| e.to_string())?;
 
@@ -133,13 +157,14 @@ pub fn import_cdp_neighbors(
         switch_hostname
     );
 
-    Ok(state_inner.physical_topology.clone())
+    Ok(physical_topology_view(&state_inner))
 }
 
 /// Import a `show arp` / `show ip arp` output file.
 ///
 /// Correlates IP addresses with MAC addresses and maps them to
 /// switch ports via the MAC address table.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn import_arp_table(
     path: String,
@@ -158,17 +183,101 @@ pub fn import_arp_table(
 
     log::info!("Imported {} ARP entries", count);
 
-    Ok(state_inner.physical_topology.clone())
+    Ok(physical_topology_view(&state_inner))
+}
+
+/// Fold LLDP/CDP advertisements seen in captured traffic into the physical
+/// topology and refresh the links and device locations derived from it.
+pub(crate) fn apply_captured_neighbors(
+    topology: &mut PhysicalTopology,
+    adverts: &[NeighborAdvertisement],
+) {
+    if adverts.is_empty() {
+        return;
+    }
+    let neighbors = topology.apply_neighbor_advertisements(adverts);
+    topology.build_links();
+    topology.correlate_arp_to_ports();
+    log::debug!(
+        "Applied {} captured LLDP/CDP advertisements ({} neighbor records)",
+        adverts.len(),
+        neighbors
+    );
+}
+
+/// Fold IP → MAC bindings learned from captured ARP into the physical
+/// topology, locating their hosts on switch ports through the MAC tables.
+/// Each address is placed by the first MAC that claimed it.
+pub(crate) fn apply_captured_arp(topology: &mut PhysicalTopology, bindings: &[ArpBinding]) {
+    if bindings.is_empty() {
+        return;
+    }
+    let entries: Vec<ArpEntry> = bindings
+        .iter()
+        .map(|b| ArpEntry {
+            ip_address: b.ip_address.clone(),
+            mac_address: b.mac_address().to_string(),
+            interface: None,
+            vlan: b.vlan,
+        })
+        .collect();
+    topology.apply_arp_entries(&entries);
+    topology.correlate_arp_to_ports();
+    log::debug!("Applied {} captured ARP bindings", entries.len());
+}
+
+/// VLAN membership known for the current data: the tags on each asset's
+/// frames, device locations and access-port MAC tables from the switches,
+/// and the subnet behind each SVI.
+#[cfg(feature = "gui")]
+pub(crate) fn vlan_map(inner: &AppStateInner) -> VlanMap {
+    let mut map = VlanMap::new();
+    for asset in &inner.assets {
+        for vlan in &asset.vlan_ids {
+            map.add_address(&asset.ip_address, *vlan);
+        }
+    }
+
+    let physical = &inner.physical_topology;
+    for location in physical.device_locations.values() {
+        if let Some(vlan) = location.vlan {
+            map.add_address(&location.ip_address, vlan);
+        }
+    }
+    let subnets = super::system::subnet_plan(&inner.settings.profile, physical);
+    for switch in &physical.switches {
+        for (vlan, name) in &switch.vlans {
+            map.name_vlan(*vlan, name);
+        }
+        for port in &switch.ports {
+            // A trunk's MAC table holds everything behind the next switch
+            if let ("access", [vlan]) = (port.mode.as_str(), port.vlans.as_slice()) {
+                for mac in &port.mac_addresses {
+                    map.add_mac(mac, *vlan);
+                }
+            }
+            let svi = port
+                .name
+                .strip_prefix("Vlan")
+                .and_then(|id| id.parse::<u16>().ok());
+            if let (Some(vlan), Some(ip)) = (svi, &port.ip_address) {
+                map.add_subnet(&subnets.subnet_of(ip), vlan);
+            }
+        }
+    }
+    map
 }
 
 /// Get the current physical topology.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn get_physical_topology(state: State<'_, AppState>) -> Result<PhysicalTopology, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    Ok(state_inner.physical_topology.clone())
+    Ok(physical_topology_view(&state_inner))
 }
 
 /// Clear all physical topology data.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn clear_physical_topology(state: State<'_, AppState>) -> Result<(), String> {
     let mut state_inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -183,6 +292,7 @@ pub fn clear_physical_topology(state: State<'_, AppState>) -> Result<(), String>
 ///
 /// Detects Cisco IOS, Juniper JunOS, or HP/Aruba ProCurve by content
 /// signatures and dispatches to the appropriate parser.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn import_network_config(
     path: String,
@@ -221,19 +331,24 @@ pub fn import_network_config(
         hostname,
         path
     );
-    Ok(state_inner.physical_topology.clone())
+    Ok(physical_topology_view(&state_inner))
 }
 
 /// Import a MAC address table with automatic vendor detection.
 ///
 /// The switch must already be imported (via import_network_config or
 /// import_cisco_config) before calling this command.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn import_mac_table_auto(
     path: String,
     switch_hostname: String,
     state: State<'_, AppState>,
 ) -> Result<PhysicalTopology, String> {
+    let switch_hostname = {
+        let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+        super::demo::resolve_switch(&state_inner, &switch_hostname)
+    };
     let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
     let file_path = Path::new(&path);
 
@@ -275,18 +390,23 @@ pub fn import_mac_table_auto(
         count,
         switch_hostname
     );
-    Ok(state_inner.physical_topology.clone())
+    Ok(physical_topology_view(&state_inner))
 }
 
 /// Import an LLDP/CDP neighbor table with automatic vendor detection.
 ///
 /// The switch must already be imported before calling this command.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn import_neighbor_table(
     path: String,
     switch_hostname: String,
     state: State<'_, AppState>,
 ) -> Result<PhysicalTopology, String> {
+    let switch_hostname = {
+        let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+        super::demo::resolve_switch(&state_inner, &switch_hostname)
+    };
     let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
     let file_path = Path::new(&path);
 
@@ -330,7 +450,7 @@ pub fn import_neighbor_table(
         count,
         switch_hostname
     );
-    Ok(state_inner.physical_topology.clone())
+    Ok(physical_topology_view(&state_inner))
 }
 
 /// Run traffic-inferred topology analysis from the current dataset.
@@ -338,6 +458,7 @@ pub fn import_neighbor_table(
 /// Derives subnet structure, gateway candidates, switch candidates, and
 /// broadcast domains purely from observed packet flows — no switch config
 /// files required.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn run_topology_inference(state: State<'_, AppState>) -> Result<InferredTopology, String> {
     let mut state_inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -374,14 +495,39 @@ pub fn run_topology_inference(state: State<'_, AppState>) -> Result<InferredTopo
     );
 
     state_inner.inferred_topology = Some(result.clone());
-    Ok(result)
+    Ok(inferred_topology_view(&state_inner, result))
 }
 
 /// Get the last computed inferred topology (or None if not yet run).
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn get_inferred_topology(
     state: State<'_, AppState>,
 ) -> Result<Option<InferredTopology>, String> {
     let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    Ok(state_inner.inferred_topology.clone())
+    Ok(state_inner
+        .inferred_topology
+        .clone()
+        .map(|topo| inferred_topology_view(&state_inner, topo)))
+}
+
+/// The physical topology as returned to the frontend, pseudonymized in
+/// demo mode.
+#[cfg(feature = "gui")]
+fn physical_topology_view(inner: &AppStateInner) -> PhysicalTopology {
+    let mut topo = inner.physical_topology.clone();
+    if let Some(anon) = super::demo::active_anonymizer(inner) {
+        super::demo::anonymize_physical_topology(&anon, &mut topo);
+    }
+    topo
+}
+
+/// An inferred topology as returned to the frontend, pseudonymized in
+/// demo mode.
+#[cfg(feature = "gui")]
+fn inferred_topology_view(inner: &AppStateInner, mut topo: InferredTopology) -> InferredTopology {
+    if let Some(anon) = super::demo::active_anonymizer(inner) {
+        super::demo::anonymize_inferred_topology(&anon, &mut topo);
+    }
+    topo
 }
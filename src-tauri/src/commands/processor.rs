@@ -4,56 +4,426 @@
 //! PCAP import and live capture: protocol identification → deep parse →
 //! connection tracking → topology building → signature matching.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use uuid::Uuid;
 
-use gm_analysis::{ConnectionStats, PatternAnalyzer, PatternAnomaly};
-use gm_capture::ParsedPacket;
+use gm_analysis::{
+    AssetLifecycle, ConnectionStats, ModbusMessage, ModbusTransactionStats,
+    ModbusTransactionTracker, PatternAnalyzer, PatternAnomaly, ProtocolSession, SessionMessage,
+    SessionPacket, SessionPdu, SessionTracker, ShiftActivity,
+};
+use gm_capture::{tcp_flags, ParsedPacket, TransportProtocol};
 use gm_db::{GeoIpLookup, OuiLookup};
+use gm_parsers::vendor_tables::{
+    bacnet_vendor_name, cip_device_type_name, cip_vendor_name, profinet_device_name,
+    profinet_vendor_name,
+};
+#[cfg(feature = "gui")]
+use gm_parsers::DeepParseSampling;
 use gm_parsers::{
-    deep_parse, dnp3_function_code_name, identify_protocol, modbus_function_code_name, parse_lldp,
-    parse_redundancy, parse_snmp_response, AsduTypeId, BacnetObjectType, BacnetRole, BacnetService,
-    CipClass, CipService, DeepParseResult, Dnp3Role, EnipCommand, EnipRole, IcsProtocol,
-    Iec104Role, LldpInfo, ModbusDeviceId, ModbusRole, ProfinetRole, RedundancyInfo, S7Function,
-    S7Role, SnmpDeviceInfo,
+    build_register_map, classify_cloud_host, codesys_service_name, deep_parse_stream,
+    detect_serial_tunnel, dnp3_is_write_function_code, identify_protocol,
+    modbus_is_write_function_code, orient, parse_arp, parse_cdp, parse_dhcp, parse_dnp3, parse_dns,
+    parse_dns_tcp, parse_goose, parse_icmp, parse_lldp, parse_modbus, parse_mqtt_connect,
+    parse_mqtt_packets, parse_nbns, parse_opc_da, parse_opcua_reverse_hello,
+    parse_profinet_dcp_frame, parse_profinet_rt, parse_redundancy, parse_snmp_response, parse_sv,
+    parse_tls_hello, tls_client_hello_sni, tls_version_name, AdsCommand, AdsDeviceInfo, AdsRole,
+    AdsState, ArpBinding, AsduTypeId, BacnetDeviceProperties, BacnetObjectType, BacnetPduType,
+    BacnetRole, BacnetService, CdpInfo, CipClass, CipService, CloudCategory, CodesysRole,
+    CodesysVersion, ComPortSettings, CrimsonIdentity, CrimsonRole, DceRpcPacketType, DcpServiceId,
+    DcpServiceType, DeepParseLimiter, DeepParseResult, DhcpInfo, DhcpMessageType, DirectionBasis,
+    DirectionEvidence, Dnp3Role, DnsBindingSource, DnsInfo, EngineeringSoftware, EnipCommand,
+    EnipIdentity, EnipRole, FinsControllerData, FinsRole, FinsTcpCommand, FoxHello, FoxRole,
+    FunctionCodeProtocol, GooseStream, IcmpActivity, IcmpInfo, IcsProtocol, IdentificationMethod,
+    Iec104Role, LldpInfo, MmsIdentity, MmsPduType, MmsRole, MmsService, ModbusDeviceId,
+    ModbusFraming, ModbusRole, MqttPacket, OpcDaInfo, OpcInterface, PduReassembler,
+    ProfinetDcpInfo, ProfinetRole, ProfinetRtFrame, ProfinetRtStream, ProtocolIdentification,
+    RedundancyInfo, RegisterAccess, RegisterRange, RegisterType, S7Function, S7PduType, S7Role,
+    SerialFraming, SerialGateway, SerialTunnelInfo, SlmpCpuModel, SlmpRole, Smb2Command, SmbRole,
+    SnmpDeviceInfo, SrtpControllerInfo, SrtpPlcState, SrtpRole, SvStream, TlsHello, TlsHelloKind,
+    TriStationCommand, TriStationRole, TunneledProtocol, VnetIpStation, DHCP_CLIENT_PORT,
+    DHCP_SERVER_PORT, DNS_PORT, LLMNR_PORT, MDNS_PORT, NBNS_PORT,
 };
+use gm_physical::NeighborAdvertisement;
 use gm_signatures::{PacketData, SignatureEngine};
-use gm_topology::TopologyBuilder;
+use gm_topology::{SubnetPlan, TopologyBuilder};
 
+use super::trace::PipelineTrace;
+#[cfg(feature = "gui")]
+use super::trace::{DeviceTrace, TraceSummary};
 use super::{
-    infer_device_type, AssetInfo, AssetSignatureMatch, BacnetDetail, ConnectionInfo, DeepParseInfo,
-    Dnp3Detail, Dnp3Relationship, EnipDetail, FunctionCodeStat, Iec104Detail, LldpDetail,
-    ModbusDetail, ModbusDeviceIdInfo, ModbusRelationship, PacketSummary, PollingInterval,
-    ProfinetDcpDetail, RegisterRangeInfo, S7Detail, SnmpDetail,
+    infer_device_type, AdsDetail, AdsRelationship, AssetInfo, AssetSignatureMatch, BacnetDetail,
+    CloudDetail, CloudEndpoint, CodesysDetail, ConnectionInfo, CrimsonDetail, DeepParseInfo,
+    DhcpDetail, DhcpLease, Dnp3AddressBinding, Dnp3Detail, Dnp3Relationship, EngineeringDetail,
+    EnipDetail, EnipIdentityInfo, FinsDetail, FoxDetail, FunctionCodeStat, GeSrtpDetail,
+    GooseDetail, Iec104Detail, LldpDetail, MmsDetail, ModbusDetail, ModbusDeviceIdInfo,
+    ModbusRegisterMap, ModbusRelationship, MqttDetail, MqttTopic, OpcDaDetail, PacketSummary,
+    PollingInterval, ProfinetDcpDetail, ProfinetRtDetail, ProtocolEvidence, RegisterRangeInfo,
+    S7Detail, SerialDevice, SerialGatewayDetail, SerialLineDetail, SlmpDetail, SmbDetail,
+    SmbShareAccess, SnmpDetail, SvDetail, TlsDetail, TlsFingerprint, TriStationDetail,
+    VnetIpDetail,
 };
 
+/// One serial line of a device server, seen through its tunnel port.
+struct SerialLineActivity {
+    channel: u8,
+    rfc2217: bool,
+    settings: ComPortSettings,
+    protocols: BTreeSet<&'static str>,
+    /// Hosts on the IP side of the tunnel
+    hosts: BTreeSet<String>,
+    /// (serial protocol, link address) → frames
+    devices: BTreeMap<(&'static str, u16), u64>,
+}
+
+/// A serial device server and its lines, keyed by tunnel port.
+struct SerialGatewayActivity {
+    gateway: SerialGateway,
+    lines: BTreeMap<u16, SerialLineActivity>,
+}
+
+/// First/last sighting and frame count of a Layer-2-only publisher.
+struct L2Activity {
+    first_seen: String,
+    last_seen: String,
+    frames: u64,
+}
+
+/// Bulk Crimson v3 bytes in one direction that mark a configuration
+/// download or upload rather than identification traffic.
+const CRIMSON_TRANSFER_BYTES: u64 = 32 * 1024;
+
+/// Files and bytes one client moved over one SMB share.
+#[derive(Default)]
+struct SmbShareActivity {
+    files: HashSet<String>,
+    files_written: HashSet<String>,
+    bytes_written: u64,
+    bytes_read: u64,
+}
+
+/// Register requests one Modbus slave unit received.
+#[derive(Default)]
+struct RegisterAccessLog {
+    /// (start, count, table, write) → requests
+    ranges: HashMap<(u16, u16, RegisterType, bool), u64>,
+    masters: HashSet<String>,
+}
+
+/// What one DHCP client (keyed by hardware address) said about itself.
+#[derive(Default)]
+struct DhcpClientRecord {
+    hostname: Option<String>,
+    vendor_class: Option<String>,
+    /// Address bound by the last ACK or renewal
+    ip: Option<String>,
+    /// Server whose ACK bound that address
+    server_ip: Option<String>,
+}
+
+/// Replies one DHCP server sent.
+#[derive(Default)]
+struct DhcpServerRecord {
+    replies: u64,
+    /// Client MACs it offered or acknowledged leases to
+    clients: HashSet<String>,
+}
+
+/// File names kept per SMB share and client; bulk copies open thousands.
+const SMB_MAX_FILES: usize = 50;
+
+/// SNI names and peers kept per TLS fingerprint; a browser-like client
+/// would otherwise list every site it visited.
+const TLS_MAX_NAMES: usize = 32;
+
+/// Topics kept per MQTT device; a broker relays every topic of every client.
+const MQTT_MAX_TOPICS: usize = 256;
+
+/// Client IDs and peers kept per MQTT device and per topic.
+const MQTT_MAX_PEERS: usize = 32;
+
+/// MQTT session state of one device.
+#[derive(Default)]
+struct MqttActivity {
+    /// Sent from the broker port or received a CONNECT
+    broker: bool,
+    client_ids: Vec<String>,
+    peers: Vec<String>,
+}
+
 /// Well-known OT/ICS service ports — if a device listens on one of these,
 /// it's considered a "server" (PLC/RTU/etc.) for classification purposes.
 fn is_server_port(port: u16) -> bool {
     matches!(
         port,
         102 | 502
+            | 789
             | 1089
             | 1090
             | 1091
+            | 1200
+            | 1217
+            | 1502
             | 1883
+            | 1911
             | 2222
             | 2404
+            | 2455
             | 4840
+            | 4911
+            | 5006
             | 5007
             | 5094
             | 8883
+            | 9600
+            | 11740
             | 18245
             | 18246
             | 20000
+            | 20171
+            | 20172
             | 34962
             | 34963
             | 34964
             | 44818
             | 47808
+            | 48898
     )
 }
 
+/// Whether a MAC address is a group (multicast/broadcast) address (I/G bit).
+fn is_group_mac(mac: &str) -> bool {
+    mac.get(..2)
+        .and_then(|b| u8::from_str_radix(b, 16).ok())
+        .is_some_and(|b| b & 0x01 != 0)
+}
+
+/// Deep parser name for the pipeline trace.
+fn deep_parse_kind(result: &DeepParseResult) -> &'static str {
+    match result {
+        DeepParseResult::Modbus { .. } => "modbus",
+        DeepParseResult::Dnp3(_) => "dnp3",
+        DeepParseResult::Enip(_) => "ethernet_ip",
+        DeepParseResult::S7(_) => "s7comm",
+        DeepParseResult::Bacnet(_) => "bacnet",
+        DeepParseResult::Fins(_) => "fins",
+        DeepParseResult::Slmp(_) => "slmp",
+        DeepParseResult::GeSrtp(_) => "ge_srtp",
+        DeepParseResult::Codesys(_) => "codesys",
+        DeepParseResult::Fox(_) => "niagara_fox",
+        DeepParseResult::Crimson(_) => "crimson",
+        DeepParseResult::VnetIp(_) => "vnet_ip",
+        DeepParseResult::TriStation(_) => "tristation",
+        DeepParseResult::Ads(_) => "ads",
+        DeepParseResult::OpcDa(_) => "opc_da",
+        DeepParseResult::Iec104(_) => "iec104",
+        DeepParseResult::Mms(_) => "iec61850_mms",
+        DeepParseResult::ProfinetDcp(_) => "profinet_dcp",
+        DeepParseResult::Smb(_) => "smb",
+        DeepParseResult::Lldp(_) => "lldp",
+    }
+}
+
+/// Whether a deep-parsed packet is a request that writes to the target.
+fn is_write_request(result: &DeepParseResult) -> bool {
+    match result {
+        DeepParseResult::Modbus { pdus } => pdus.iter().any(|pdu| {
+            pdu.role == ModbusRole::Master && modbus_is_write_function_code(pdu.function_code)
+        }),
+        DeepParseResult::Dnp3(info) => {
+            info.from_master && info.function_code.is_some_and(dnp3_is_write_function_code)
+        }
+        DeepParseResult::Enip(info) => {
+            !info.is_response
+                && matches!(
+                    info.cip_service,
+                    Some(CipService::Write) | Some(CipService::ReadModifyWrite)
+                )
+        }
+        DeepParseResult::S7(info) => {
+            info.s7_pdu_type == Some(S7PduType::Job)
+                && info.s7_function == Some(S7Function::WriteVar)
+        }
+        DeepParseResult::Bacnet(info) => {
+            info.pdu_type == Some(BacnetPduType::ConfirmedRequest)
+                && matches!(
+                    info.service,
+                    Some(BacnetService::WriteProperty) | Some(BacnetService::WritePropertyMultiple)
+                )
+        }
+        DeepParseResult::Fins(info) => info.is_write,
+        DeepParseResult::Mms(info) => {
+            info.pdu_type == MmsPduType::ConfirmedRequest
+                && info.service.is_some_and(|s| s.is_write())
+        }
+        _ => false,
+    }
+}
+
+/// Identity evidence available for a device, for the pipeline trace.
+#[cfg(feature = "gui")]
+fn identity_sources(asset: &AssetInfo, dp: Option<&DeepParseInfo>, is_server: bool) -> Vec<String> {
+    let mut sources = Vec::new();
+    if let Some(dp) = dp {
+        let identities = [
+            (
+                "Modbus FC 43 device ID",
+                dp.modbus.as_ref().is_some_and(|m| m.device_id.is_some()),
+            ),
+            (
+                "MMS Identify",
+                dp.mms
+                    .as_ref()
+                    .is_some_and(|m| m.vendor.is_some() || m.model.is_some()),
+            ),
+            (
+                "FINS controller data",
+                dp.fins
+                    .as_ref()
+                    .is_some_and(|f| f.controller_model.is_some()),
+            ),
+            (
+                "SLMP CPU model",
+                dp.slmp.as_ref().is_some_and(|s| s.cpu_model.is_some()),
+            ),
+            (
+                "SRTP controller ID",
+                dp.ge_srtp
+                    .as_ref()
+                    .is_some_and(|g| g.controller_model.is_some()),
+            ),
+            (
+                "Niagara Fox hello",
+                dp.fox.as_ref().is_some_and(|f| f.niagara_version.is_some()),
+            ),
+            (
+                "Crimson identification",
+                dp.crimson.as_ref().is_some_and(|c| c.model.is_some()),
+            ),
+            (
+                "EtherNet/IP ListIdentity",
+                dp.enip.as_ref().is_some_and(|e| e.identity.is_some()),
+            ),
+            (
+                "BACnet device properties",
+                dp.bacnet
+                    .as_ref()
+                    .and_then(|b| b.device.as_ref())
+                    .is_some_and(|d| d.vendor_name.is_some() || d.model_name.is_some()),
+            ),
+            ("Vnet/IP station address", dp.vnet_ip.is_some()),
+            (
+                "TriStation controller",
+                dp.tristation.as_ref().is_some_and(|t| t.role == "server"),
+            ),
+            (
+                "ADS device info",
+                dp.ads.as_ref().is_some_and(|a| a.device_name.is_some()),
+            ),
+            ("PROFINET DCP", dp.profinet_dcp.is_some()),
+            ("serial device server tunnel", dp.serial_gateway.is_some()),
+            ("LLDP", dp.lldp.is_some()),
+            ("SNMP system group", dp.snmp.is_some()),
+            (
+                "DHCP hostname",
+                dp.dhcp.as_ref().is_some_and(|d| d.hostname.is_some()),
+            ),
+        ];
+        sources.extend(
+            identities
+                .iter()
+                .filter(|(_, present)| *present)
+                .map(|(name, _)| format!("deep parse: {}", name)),
+        );
+
+        let roles = [
+            ("modbus", dp.modbus.as_ref().map(|d| d.role.as_str())),
+            ("ethernet_ip", dp.enip.as_ref().map(|d| d.role.as_str())),
+            ("s7comm", dp.s7.as_ref().map(|d| d.role.as_str())),
+            ("bacnet", dp.bacnet.as_ref().map(|d| d.role.as_str())),
+            ("fins", dp.fins.as_ref().map(|d| d.role.as_str())),
+            ("slmp", dp.slmp.as_ref().map(|d| d.role.as_str())),
+            ("ge_srtp", dp.ge_srtp.as_ref().map(|d| d.role.as_str())),
+            ("codesys", dp.codesys.as_ref().map(|d| d.role.as_str())),
+            ("niagara_fox", dp.fox.as_ref().map(|d| d.role.as_str())),
+            ("crimson", dp.crimson.as_ref().map(|d| d.role.as_str())),
+            (
+                "tristation",
+                dp.tristation.as_ref().map(|d| d.role.as_str()),
+            ),
+            ("ads", dp.ads.as_ref().map(|d| d.role.as_str())),
+            ("iec61850_mms", dp.mms.as_ref().map(|d| d.role.as_str())),
+            ("smb", dp.smb.as_ref().map(|d| d.role.as_str())),
+            ("dhcp", dp.dhcp.as_ref().map(|d| d.role.as_str())),
+        ];
+        sources.extend(
+            roles
+                .iter()
+                .filter_map(|(proto, role)| role.map(|r| format!("{} role: {}", proto, r))),
+        );
+
+        if let Some(ref eng) = dp.engineering {
+            sources.push(format!("engineering software: {}", eng.software.join(", ")));
+        }
+    }
+    match asset.hostname_source.as_deref() {
+        Some("dns") => sources.push("DNS name".to_string()),
+        Some("netbios") => sources.push("NetBIOS name".to_string()),
+        Some("mdns") => sources.push("mDNS name".to_string()),
+        Some("llmnr") => sources.push("LLMNR name".to_string()),
+        _ => {}
+    }
+    if is_server {
+        sources.push("serves a well-known OT port".to_string());
+    }
+    if let Some(ref oui) = asset.oui_vendor {
+        sources.push(format!("MAC OUI: {}", oui));
+    }
+    sources
+}
+
+/// Classify a TCP payload as a Modbus or DNP3 PDU for session tracking.
+///
+/// Both protocols are tried regardless of port, so a session that switches
+/// protocol mid-stream is noticed.
+fn session_pdu(packet: &ParsedPacket) -> Option<SessionPdu<'static>> {
+    let payload = &packet.payload;
+    if let Some(info) = parse_dnp3(payload, packet.src_port, packet.dst_port) {
+        let message = match info.function_code {
+            // CONFIRM (0x00) expects no reply; 0x01-0x21 are requests
+            Some(0x01..=0x21) if info.from_master => SessionMessage::Request,
+            Some(0x81) => SessionMessage::Response,
+            _ => SessionMessage::Other,
+        };
+        return Some(SessionPdu {
+            protocol: "Dnp3",
+            message,
+            count: 1,
+        });
+    }
+
+    // The first MBAP length must fit within the segment, which keeps
+    // arbitrary payloads with a zero protocol ID from passing as Modbus
+    let mbap_framed = payload.len() >= 8
+        && 6 + u16::from_be_bytes([payload[4], payload[5]]) as usize <= payload.len();
+    if mbap_framed {
+        let pdus = parse_modbus(payload, packet.src_port, packet.dst_port);
+        if let Some(first) = pdus.first().filter(|p| p.framing == ModbusFraming::Tcp) {
+            let message = match first.role {
+                ModbusRole::Master => SessionMessage::Request,
+                ModbusRole::Slave => SessionMessage::Response,
+                ModbusRole::Unknown => SessionMessage::Other,
+            };
+            return Some(SessionPdu {
+                protocol: "Modbus",
+                message,
+                count: pdus.len() as u32,
+            });
+        }
+    }
+    None
+}
+
 /// Processes packets through the full pipeline:
 /// protocol identification → deep parse → connection tracking → topology building.
 ///
@@ -63,6 +433,8 @@ pub struct PacketProcessor {
     connections: HashMap<String, ConnectionInfo>,
     packet_summaries: HashMap<String, Vec<PacketSummary>>,
     asset_protocols: HashMap<String, HashSet<IcsProtocol>>,
+    /// Strongest identification seen per (asset, protocol)
+    asset_protocol_evidence: HashMap<String, HashMap<IcsProtocol, ProtocolIdentification>>,
     asset_macs: HashMap<String, String>,
     asset_packet_counts: HashMap<String, u64>,
     asset_first_seen: HashMap<String, String>,
@@ -70,6 +442,15 @@ pub struct PacketProcessor {
     server_ips: HashSet<String>,
     all_protocols: HashSet<String>,
     conn_origin_files: HashMap<String, HashSet<String>>,
+    /// Per connection key: whether the first bare SYN came from the
+    /// connection's recorded source
+    conn_syn_from_src: HashMap<String, bool>,
+    /// 802.1Q VLANs per connection key
+    conn_vlans: HashMap<String, HashSet<u16>>,
+    /// VXLAN VNIs per connection key
+    conn_vnis: HashMap<String, HashSet<u32>>,
+    /// 802.1Q VLANs per asset (IP, or MAC for Layer-2-only senders)
+    asset_vlans: HashMap<String, HashSet<u16>>,
 
     // Deep parse accumulators
     modbus_fc_counts: HashMap<String, HashMap<u8, u64>>,
@@ -81,18 +462,31 @@ pub struct PacketProcessor {
     #[allow(clippy::type_complexity)]
     modbus_relationships: HashMap<String, HashMap<String, (String, HashSet<u8>, u64)>>,
     modbus_polling_timestamps: HashMap<(String, String, u8, u8), Vec<f64>>,
+    /// IPs that sent raw RTU frames over TCP (serial gateways and their masters)
+    modbus_rtu_over_tcp: HashSet<String>,
+    /// Register requests per (slave IP, unit ID), for the slave's register map
+    modbus_register_access: HashMap<(String, u8), RegisterAccessLog>,
 
     dnp3_fc_counts: HashMap<String, HashMap<u8, u64>>,
     dnp3_addresses: HashMap<String, HashSet<u16>>,
     dnp3_roles: HashMap<String, HashSet<String>>,
     dnp3_unsolicited: HashMap<String, bool>,
+    /// IPs that sent DNP3 Secure Authentication messages
+    dnp3_secure_auth: HashSet<String>,
+    /// Link-layer CRC mismatches in DNP3 frames each IP sent
+    dnp3_crc_errors: HashMap<String, u64>,
     dnp3_relationships: HashMap<String, HashMap<String, (String, u64)>>,
+    /// Link addresses used by frames with the DIR (from-master) bit set:
+    /// source IP → (master address, outstation address) → binding
+    dnp3_master_bindings: HashMap<String, HashMap<(u16, u16), Dnp3AddressBinding>>,
 
     // EtherNet/IP accumulators
     enip_roles: HashMap<String, String>,
     enip_cip_writes_to_assembly: HashSet<String>,
     enip_cip_file_access: HashSet<String>,
     enip_list_identity: HashSet<String>,
+    /// ListIdentity responses, keyed by the responding device's IP
+    enip_identities: HashMap<String, EnipIdentity>,
 
     // S7comm accumulators
     s7_roles: HashMap<String, String>,
@@ -100,6 +494,8 @@ pub struct PacketProcessor {
 
     // BACnet accumulators
     bacnet_roles: HashMap<String, String>,
+    /// Device object identity per IP, from I-Am and ReadProperty acks
+    bacnet_devices: HashMap<String, BacnetDeviceProperties>,
     bacnet_write_to_output: HashSet<String>,
     bacnet_write_to_notification_class: HashSet<String>,
     bacnet_reinitialize: HashSet<String>,
@@ -111,9 +507,168 @@ pub struct PacketProcessor {
     iec104_reset_process: HashSet<String>,
     iec104_interrogation: HashSet<String>,
 
+    // OMRON FINS accumulators
+    fins_roles: HashMap<String, String>,
+    fins_commands: HashMap<String, HashSet<u16>>,
+    /// FINS node addresses keyed by the IP that owns them
+    fins_nodes: HashMap<String, HashSet<u8>>,
+    /// Controller Data Read results keyed by the PLC IP
+    fins_controllers: HashMap<String, FinsControllerData>,
+    fins_writes: HashSet<String>,
+    fins_control_commands: HashSet<String>,
+
+    // MELSEC SLMP / MC protocol accumulators
+    slmp_roles: HashMap<String, String>,
+    slmp_commands: HashMap<String, HashSet<u16>>,
+    /// Device areas (e.g. "D", "M") addressed on each PLC IP
+    slmp_devices: HashMap<String, HashSet<String>>,
+    slmp_cpu_models: HashMap<String, SlmpCpuModel>,
+    /// Outstanding Read CPU Model Name requests: (client, server, 4E serial)
+    slmp_model_reads: HashSet<(String, String, Option<u16>)>,
+    slmp_writes: HashSet<String>,
+    slmp_control_commands: HashSet<String>,
+    /// IPs that have answered SLMP on port 5007 (shared with SuiteLink)
+    slmp_servers: HashSet<String>,
+
+    // GE SRTP accumulators
+    ge_srtp_roles: HashMap<String, String>,
+    ge_srtp_services: HashMap<String, HashSet<u8>>,
+    /// Memory types (e.g. "%R", "%M") addressed on each PLC IP
+    ge_srtp_segments: HashMap<String, HashSet<String>>,
+    /// Last PLC state reported by each PLC IP
+    ge_srtp_states: HashMap<String, SrtpPlcState>,
+    ge_srtp_controllers: HashMap<String, SrtpControllerInfo>,
+    /// Outstanding Return Controller Type and ID requests: (client, server, sequence)
+    ge_srtp_id_reads: HashSet<(String, String, u8)>,
+    ge_srtp_writes: HashSet<String>,
+    ge_srtp_control_commands: HashSet<String>,
+
+    // CODESYS runtime protocol accumulators
+    codesys_roles: HashMap<String, String>,
+    codesys_versions: HashMap<String, HashSet<CodesysVersion>>,
+    /// Requested services as (V3 service group, service id / V2 code)
+    codesys_services: HashMap<String, HashSet<(Option<u16>, u16)>>,
+    codesys_logins: HashSet<String>,
+    codesys_downloads: HashSet<String>,
+    codesys_online_changes: HashSet<String>,
+    codesys_control_commands: HashSet<String>,
+
+    // Niagara Fox accumulators
+    fox_roles: HashMap<String, String>,
+    /// Most informative hello seen from each device
+    fox_hellos: HashMap<String, FoxHello>,
+
+    // Red Lion Crimson v3 accumulators
+    crimson_roles: HashMap<String, String>,
+    crimson_manufacturers: HashMap<String, String>,
+    crimson_models: HashMap<String, String>,
+    /// Bulk bytes per (client, server): (client → server, server → client)
+    crimson_bulk: HashMap<(String, String), (u64, u64)>,
+
+    // Yokogawa Vnet/IP accumulators
+    vnetip_stations: HashMap<String, VnetIpStation>,
+    vnetip_peers: HashMap<String, HashSet<String>>,
+
+    // Triconex TriStation accumulators
+    tristation_roles: HashMap<String, String>,
+    /// Commands sent by each workstation
+    tristation_commands: HashMap<String, HashSet<TriStationCommand>>,
+    /// Workstation ↔ controller pairs that exchanged commands
+    tristation_peers: HashMap<String, HashSet<String>>,
+
+    // Beckhoff ADS/AMS accumulators
+    ads_roles: HashMap<String, String>,
+    /// AMS NetIDs each device used as its source address
+    ads_net_ids: HashMap<String, HashSet<String>>,
+    /// AMS ports addressed on each device (the services it hosts)
+    ads_ports: HashMap<String, HashSet<u16>>,
+    ads_commands: HashMap<String, HashSet<AdsCommand>>,
+    /// States requested with Write Control, per client
+    ads_state_changes: HashMap<String, HashSet<AdsState>>,
+    ads_device_info: HashMap<String, AdsDeviceInfo>,
+    /// Last state reported by Read State, per server
+    ads_states: HashMap<String, AdsState>,
+    /// Request count per (client, server)
+    ads_links: HashMap<(String, String), u64>,
+
+    // Classic OPC (DCOM) accumulators
+    opc_da_roles: HashMap<String, String>,
+    /// OPC interfaces named between each client and server, keyed by both
+    opc_da_interfaces: HashMap<String, HashSet<OpcInterface>>,
+    /// Request count per (OPC client, OPC server)
+    opc_da_links: HashMap<(String, String), u64>,
+    /// DCOM endpoints bound to an OPC interface; `true` for a callback
+    /// object the OPC client hosts (IOPCDataCallback, IOPCShutdown)
+    opc_da_endpoints: HashMap<(String, u16), bool>,
+
+    // IEC 61850 MMS accumulators
+    mms_roles: HashMap<String, String>,
+    mms_services: HashMap<String, HashSet<MmsService>>,
+    /// Logical devices (MMS domains) keyed by the IED (server) IP
+    mms_logical_devices: HashMap<String, HashSet<String>>,
+    /// Logical nodes keyed by the IED (server) IP
+    mms_logical_nodes: HashMap<String, HashSet<String>>,
+    mms_report_ids: HashMap<String, HashSet<String>>,
+    mms_identity: HashMap<String, MmsIdentity>,
+    mms_writes: HashSet<String>,
+    /// IPs that have answered as an MMS server on port 102
+    mms_servers: HashSet<String>,
+
     // PROFINET DCP accumulators
     profinet_roles: HashMap<String, String>,
     profinet_device_names: HashMap<String, String>,
+    /// DCP "Type of Station" string (e.g. "S7-1500")
+    profinet_station_types: HashMap<String, String>,
+    /// DCP Device ID block: (PI vendor ID, device ID)
+    profinet_device_ids: HashMap<String, (u16, u16)>,
+    /// Cyclic RT relations keyed by (provider MAC, consumer MAC, FrameID)
+    profinet_rt_streams: HashMap<(String, String, u16), ProfinetRtStream>,
+    /// Activity of each MAC that sends cyclic RT frames. IO-devices without
+    /// IP traffic in the capture get MAC-only assets from this.
+    profinet_rt_stations: HashMap<String, L2Activity>,
+
+    /// Engineering software fingerprints, keyed by the requesting host's IP.
+    engineering_software: HashMap<String, HashSet<EngineeringSoftware>>,
+    /// Hosts with at least one conclusive engineering fingerprint (project
+    /// transfer, S7comm-plus, UMAS, CODESYS) rather than just a PG connection.
+    conclusive_engineering: HashSet<String>,
+
+    /// Outbound cloud / remote-access endpoints keyed by
+    /// (local IP, remote IP, remote port)
+    cloud_endpoints: HashMap<(String, String, u16), CloudEndpoint>,
+
+    /// TLS fingerprints keyed by (sending host IP, hello kind, JA3/JA3S hash)
+    tls_fingerprints: HashMap<(String, TlsHelloKind, String), TlsFingerprint>,
+
+    /// Serial device servers keyed by gateway IP
+    serial_gateways: HashMap<String, SerialGatewayActivity>,
+
+    /// MQTT sessions keyed by device IP
+    mqtt_activity: HashMap<String, MqttActivity>,
+    /// MQTT topics keyed by (sending device IP, topic, subscribe)
+    mqtt_topics: HashMap<(String, String, bool), MqttTopic>,
+
+    // SMB accumulators
+    smb_roles: HashMap<String, String>,
+    /// Tree Connect requests awaiting a response, keyed (client, server, MessageId)
+    smb_pending_trees: HashMap<(String, String, u64), String>,
+    /// Share path bound to each (client, server, TreeId)
+    smb_trees: HashMap<(String, String, u32), String>,
+    /// File activity per (client, server, share)
+    smb_shares: HashMap<(String, String, String), SmbShareActivity>,
+    /// NTLM accounts that authenticated from each client
+    smb_users: HashMap<String, HashSet<String>>,
+    smb1_hosts: HashSet<String>,
+
+    /// DHCP clients keyed by hardware address
+    dhcp_clients: HashMap<String, DhcpClientRecord>,
+    /// DHCP servers keyed by server identifier (or source IP)
+    dhcp_servers: HashMap<String, DhcpServerRecord>,
+    /// Names DNS responses give each address (PTR answers replace A/AAAA)
+    dns_names: HashMap<String, (String, DnsBindingSource)>,
+    /// Names hosts announce for themselves, per address and protocol
+    /// ("netbios", "mdns", "llmnr")
+    announced_names: HashMap<String, HashMap<&'static str, String>>,
 
     // Signature matching data — accumulated per-IP
     ip_packets: HashMap<String, Vec<PacketData>>,
@@ -121,17 +676,54 @@ pub struct PacketProcessor {
     /// LLDP info keyed by the sender MAC address (e.g. "aa:bb:cc:dd:ee:ff").
     /// Multiple LLDP frames from the same device are merged (last-write-wins).
     lldp_by_mac: HashMap<String, LldpInfo>,
+    /// CDP info keyed by the sender MAC address (last-write-wins).
+    cdp_by_mac: HashMap<String, CdpInfo>,
+    /// Capture segment (origin file or interface) each LLDP/CDP sender was seen on
+    discovery_segments: HashMap<String, String>,
 
     /// Redundancy protocol frames observed. Keyed by source MAC; last-write-wins
     /// within each MAC so we keep the most recent frame per sender.
     redundancy_by_mac: HashMap<String, RedundancyInfo>,
 
+    /// GOOSE control blocks keyed by (publisher MAC, gocbRef).
+    goose_streams: HashMap<(String, String), GooseStream>,
+
+    /// Sampled Values publications keyed by (publisher MAC, svID).
+    sv_streams: HashMap<(String, String), SvStream>,
+
+    /// IPv4 → MAC bindings learned from ARP senders, keyed by IP.
+    arp_bindings: HashMap<String, ArpBinding>,
+
+    /// ICMP discovery activity (echo fan-out, replies, unreachables) keyed
+    /// by the IP of the host doing the discovering.
+    icmp_activity: HashMap<String, IcmpActivity>,
+
+    /// Activity of each SV publisher MAC. Merging units often have no IP
+    /// traffic at all, so this backs the MAC-only assets built for them.
+    sv_publishers: HashMap<String, L2Activity>,
+
     /// SNMP device identity extracted from GET-Response packets.
     /// Keyed by the responding device's IP (src_ip when src_port == 161).
     snmp_device_info: HashMap<String, SnmpDeviceInfo>,
 
     /// Communication pattern analyzer — collects timestamps per connection pair
     pattern_analyzer: PatternAnalyzer,
+    /// Traffic per quarter hour of the week, for shift comparisons
+    shift_activity: ShiftActivity,
+    /// Modbus/DNP3 TCP session state machine
+    session_tracker: SessionTracker,
+    /// Modbus request/response pairing by transaction ID
+    modbus_transactions: ModbusTransactionTracker,
+    /// Site-specific port → protocol overrides from the active settings profile
+    port_map: HashMap<u16, IcsProtocol>,
+    /// Per-protocol deep-parse budgets (live capture only)
+    deep_parse_limiter: DeepParseLimiter,
+    /// Partial application PDUs per TCP connection direction
+    pdu_reassembler: PduReassembler,
+    /// Opt-in pipeline trace (None unless the import asked for one)
+    trace: Option<PipelineTrace>,
+    /// Packets and bytes per identified protocol, for live rate reporting
+    protocol_traffic: HashMap<IcsProtocol, (u64, u64)>,
 
     pub total_packets: u64,
 }
@@ -143,6 +735,7 @@ impl PacketProcessor {
             connections: HashMap::new(),
             packet_summaries: HashMap::new(),
             asset_protocols: HashMap::new(),
+            asset_protocol_evidence: HashMap::new(),
             asset_macs: HashMap::new(),
             asset_packet_counts: HashMap::new(),
             asset_first_seen: HashMap::new(),
@@ -150,25 +743,36 @@ impl PacketProcessor {
             server_ips: HashSet::new(),
             all_protocols: HashSet::new(),
             conn_origin_files: HashMap::new(),
+            conn_syn_from_src: HashMap::new(),
+            conn_vlans: HashMap::new(),
+            conn_vnis: HashMap::new(),
+            asset_vlans: HashMap::new(),
             modbus_fc_counts: HashMap::new(),
             modbus_unit_ids: HashMap::new(),
             modbus_register_ranges: HashMap::new(),
             modbus_roles: HashMap::new(),
             modbus_device_ids: HashMap::new(),
+            modbus_rtu_over_tcp: HashSet::new(),
+            modbus_register_access: HashMap::new(),
             modbus_relationships: HashMap::new(),
             modbus_polling_timestamps: HashMap::new(),
             dnp3_fc_counts: HashMap::new(),
             dnp3_addresses: HashMap::new(),
             dnp3_roles: HashMap::new(),
             dnp3_unsolicited: HashMap::new(),
+            dnp3_secure_auth: HashSet::new(),
+            dnp3_crc_errors: HashMap::new(),
             dnp3_relationships: HashMap::new(),
+            dnp3_master_bindings: HashMap::new(),
             enip_roles: HashMap::new(),
             enip_cip_writes_to_assembly: HashSet::new(),
             enip_cip_file_access: HashSet::new(),
             enip_list_identity: HashSet::new(),
+            enip_identities: HashMap::new(),
             s7_roles: HashMap::new(),
             s7_functions_seen: HashMap::new(),
             bacnet_roles: HashMap::new(),
+            bacnet_devices: HashMap::new(),
             bacnet_write_to_output: HashSet::new(),
             bacnet_write_to_notification_class: HashSet::new(),
             bacnet_reinitialize: HashSet::new(),
@@ -177,25 +781,194 @@ impl PacketProcessor {
             iec104_control_commands: HashSet::new(),
             iec104_reset_process: HashSet::new(),
             iec104_interrogation: HashSet::new(),
+            fins_roles: HashMap::new(),
+            fins_commands: HashMap::new(),
+            fins_nodes: HashMap::new(),
+            fins_controllers: HashMap::new(),
+            fins_writes: HashSet::new(),
+            fins_control_commands: HashSet::new(),
+            slmp_roles: HashMap::new(),
+            slmp_commands: HashMap::new(),
+            slmp_devices: HashMap::new(),
+            slmp_cpu_models: HashMap::new(),
+            slmp_model_reads: HashSet::new(),
+            slmp_writes: HashSet::new(),
+            slmp_control_commands: HashSet::new(),
+            slmp_servers: HashSet::new(),
+            ge_srtp_roles: HashMap::new(),
+            ge_srtp_services: HashMap::new(),
+            ge_srtp_segments: HashMap::new(),
+            ge_srtp_states: HashMap::new(),
+            ge_srtp_controllers: HashMap::new(),
+            ge_srtp_id_reads: HashSet::new(),
+            ge_srtp_writes: HashSet::new(),
+            ge_srtp_control_commands: HashSet::new(),
+            codesys_roles: HashMap::new(),
+            codesys_versions: HashMap::new(),
+            codesys_services: HashMap::new(),
+            codesys_logins: HashSet::new(),
+            codesys_downloads: HashSet::new(),
+            codesys_online_changes: HashSet::new(),
+            codesys_control_commands: HashSet::new(),
+            fox_roles: HashMap::new(),
+            fox_hellos: HashMap::new(),
+            crimson_roles: HashMap::new(),
+            crimson_manufacturers: HashMap::new(),
+            crimson_models: HashMap::new(),
+            crimson_bulk: HashMap::new(),
+            vnetip_stations: HashMap::new(),
+            vnetip_peers: HashMap::new(),
+            tristation_roles: HashMap::new(),
+            tristation_commands: HashMap::new(),
+            tristation_peers: HashMap::new(),
+            ads_roles: HashMap::new(),
+            ads_net_ids: HashMap::new(),
+            ads_ports: HashMap::new(),
+            ads_commands: HashMap::new(),
+            ads_state_changes: HashMap::new(),
+            ads_device_info: HashMap::new(),
+            ads_states: HashMap::new(),
+            ads_links: HashMap::new(),
+            opc_da_roles: HashMap::new(),
+            opc_da_interfaces: HashMap::new(),
+            opc_da_links: HashMap::new(),
+            opc_da_endpoints: HashMap::new(),
+            mms_roles: HashMap::new(),
+            mms_services: HashMap::new(),
+            mms_logical_devices: HashMap::new(),
+            mms_logical_nodes: HashMap::new(),
+            mms_report_ids: HashMap::new(),
+            mms_identity: HashMap::new(),
+            mms_writes: HashSet::new(),
+            mms_servers: HashSet::new(),
             profinet_roles: HashMap::new(),
             profinet_device_names: HashMap::new(),
+            profinet_station_types: HashMap::new(),
+            profinet_device_ids: HashMap::new(),
+            profinet_rt_streams: HashMap::new(),
+            profinet_rt_stations: HashMap::new(),
+            engineering_software: HashMap::new(),
+            conclusive_engineering: HashSet::new(),
+            cloud_endpoints: HashMap::new(),
+            tls_fingerprints: HashMap::new(),
+            serial_gateways: HashMap::new(),
+            mqtt_activity: HashMap::new(),
+            mqtt_topics: HashMap::new(),
+            smb_roles: HashMap::new(),
+            smb_pending_trees: HashMap::new(),
+            smb_trees: HashMap::new(),
+            smb_shares: HashMap::new(),
+            smb_users: HashMap::new(),
+            smb1_hosts: HashSet::new(),
+            dhcp_clients: HashMap::new(),
+            dhcp_servers: HashMap::new(),
+            dns_names: HashMap::new(),
+            announced_names: HashMap::new(),
             ip_packets: HashMap::new(),
             lldp_by_mac: HashMap::new(),
+            cdp_by_mac: HashMap::new(),
+            discovery_segments: HashMap::new(),
             redundancy_by_mac: HashMap::new(),
+            goose_streams: HashMap::new(),
+            sv_streams: HashMap::new(),
+            arp_bindings: HashMap::new(),
+            icmp_activity: HashMap::new(),
+            sv_publishers: HashMap::new(),
             snmp_device_info: HashMap::new(),
             pattern_analyzer: PatternAnalyzer::new(),
+            shift_activity: ShiftActivity::new(),
+            session_tracker: SessionTracker::new(),
+            modbus_transactions: ModbusTransactionTracker::new(),
+            port_map: HashMap::new(),
+            deep_parse_limiter: DeepParseLimiter::default(),
+            pdu_reassembler: PduReassembler::new(),
+            trace: None,
+            protocol_traffic: HashMap::new(),
             total_packets: 0,
         }
     }
 
+    /// Use custom port → protocol assignments ahead of the well-known ports.
+    pub fn with_port_map(mut self, port_map: HashMap<u16, IcsProtocol>) -> Self {
+        self.port_map = port_map;
+        self
+    }
+
+    /// Cap deep parsing per protocol to N packets per second of capture time.
+    pub fn with_deep_parse_budgets(mut self, budgets: HashMap<IcsProtocol, u32>) -> Self {
+        self.deep_parse_limiter = DeepParseLimiter::new(budgets);
+        self
+    }
+
+    /// Group topology nodes by the plant's subnets rather than /24 and /64.
+    pub fn with_subnets(mut self, subnets: SubnetPlan) -> Self {
+        self.topo_builder = TopologyBuilder::with_subnets(subnets);
+        self
+    }
+
+    /// Count each topology edge's traffic in `secs`-wide time buckets.
+    pub fn with_traffic_buckets(mut self, secs: u32) -> Self {
+        self.topo_builder = std::mem::take(&mut self.topo_builder).with_traffic_buckets(secs);
+        self
+    }
+
+    /// Record a pipeline trace for every packet processed.
+    #[cfg(feature = "gui")]
+    pub fn with_trace(mut self, trace: Option<PipelineTrace>) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    /// Parsed/skipped counters for each budgeted protocol.
+    #[cfg(feature = "gui")]
+    pub fn deep_parse_sampling(&self) -> Vec<DeepParseSampling> {
+        self.deep_parse_limiter.sampling()
+    }
+
+    /// Packet and byte totals for each identified protocol so far.
+    #[cfg(feature = "gui")]
+    pub fn protocol_traffic(&self) -> &HashMap<IcsProtocol, (u64, u64)> {
+        &self.protocol_traffic
+    }
+
     /// Process a single packet through the pipeline.
     pub fn process_packet(&mut self, packet: &ParsedPacket) {
+        self.run_pipeline(packet);
+        if let Some(trace) = self.trace.as_mut() {
+            trace.end_packet(packet);
+        }
+    }
+
+    /// Note an accumulator updated by the current packet (trace mode only).
+    fn trace_note(&mut self, accumulator: &'static str) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.note(accumulator);
+        }
+    }
+
+    fn run_pipeline(&mut self, packet: &ParsedPacket) {
         // LLDP packets have a sentinel src_ip of "lldp:<mac>" — handle them
         // separately before the IP-based pipeline since they carry no IP header.
         if packet.src_ip.starts_with("lldp:") {
             if let Some(ref mac) = packet.src_mac {
                 if let Some(lldp_info) = parse_lldp(&packet.payload) {
                     self.lldp_by_mac.insert(mac.clone(), lldp_info);
+                    self.discovery_segments
+                        .insert(mac.clone(), packet.origin_file.clone());
+                    self.trace_note("lldp");
+                }
+            }
+            return;
+        }
+
+        // CDP packets use the "cdp:<mac>" sentinel in the same way
+        if packet.src_ip.starts_with("cdp:") {
+            if let Some(ref mac) = packet.src_mac {
+                if let Some(cdp_info) = parse_cdp(&packet.payload) {
+                    self.cdp_by_mac.insert(mac.clone(), cdp_info);
+                    self.discovery_segments
+                        .insert(mac.clone(), packet.origin_file.clone());
+                    self.trace_note("cdp");
                 }
             }
             return;
@@ -207,15 +980,64 @@ impl PacketProcessor {
             if let Some(ref mac) = packet.src_mac {
                 if let Some(info) = parse_redundancy(&packet.payload, proto_hint, mac) {
                     self.redundancy_by_mac.insert(mac.clone(), info);
+                    self.trace_note("redundancy");
                 }
             }
             return;
         }
 
-        let protocol = identify_protocol(packet);
+        // GOOSE packets use the sentinel prefix "goose:<mac>" on both ends;
+        // they are tracked per control block and drawn as MAC-keyed nodes.
+        if packet.src_ip.starts_with("goose:") {
+            self.trace_note("goose");
+            self.note_l2_vlan(packet);
+            self.process_goose(packet);
+            return;
+        }
+
+        // Sampled Values use "sv:<mac>" the same way
+        if packet.src_ip.starts_with("sv:") {
+            self.trace_note("sampled_values");
+            self.note_l2_vlan(packet);
+            self.process_sv(packet);
+            return;
+        }
+
+        // PROFINET frames over Ethernet ("profinet:<mac>"): cyclic RT IO
+        // data, and DCP Hello / Identify responses that name the sender
+        if packet.src_ip.starts_with("profinet:") {
+            self.trace_note("profinet");
+            self.note_l2_vlan(packet);
+            self.process_profinet_frame(packet);
+            return;
+        }
+
+        // ARP ("arp:<mac>") only contributes the sender's IP → MAC binding
+        if packet.src_ip.starts_with("arp:") {
+            self.trace_note("arp");
+            self.process_arp(packet);
+            return;
+        }
+
+        let identified = self.identify(packet);
+        let protocol = self.refine_iso_tsap(packet, identified.protocol);
+        let protocol = self.refine_melsec_port(packet, protocol);
+        let protocol = self.refine_opc_da(packet, protocol);
+        // Refinements relabel from payloads already seen on the same server
+        let identification = if protocol == identified.protocol {
+            identified
+        } else {
+            ProtocolIdentification::new(protocol, IdentificationMethod::PayloadProbe)
+        };
         let proto_str = format!("{:?}", protocol);
         self.all_protocols.insert(proto_str.clone());
         self.total_packets += 1;
+        let traffic = self.protocol_traffic.entry(protocol).or_default();
+        traffic.0 += 1;
+        traffic.1 += packet.length as u64;
+        if let Some(trace) = self.trace.as_mut() {
+            trace.set_protocol(proto_str.clone(), identification.method.as_str());
+        }
 
         let timestamp = packet.timestamp.to_rfc3339();
 
@@ -228,6 +1050,8 @@ impl PacketProcessor {
             .entry(packet.dst_ip.clone())
             .or_default()
             .insert(protocol);
+        self.note_protocol_evidence(&packet.src_ip, identification);
+        self.note_protocol_evidence(&packet.dst_ip, identification);
 
         // Track MACs
         if let Some(ref mac) = packet.src_mac {
@@ -241,6 +1065,19 @@ impl PacketProcessor {
                 .or_insert_with(|| mac.clone());
         }
 
+        // A frame's VLAN tag is credited to the ends whose own MAC is on it,
+        // so a routed packet does not put the remote host on the local VLAN
+        if let Some(vlan) = packet.vlan_id {
+            for (ip, mac) in [
+                (&packet.src_ip, &packet.src_mac),
+                (&packet.dst_ip, &packet.dst_mac),
+            ] {
+                if mac.is_some() && self.asset_macs.get(ip) == mac.as_ref() {
+                    self.asset_vlans.entry(ip.clone()).or_default().insert(vlan);
+                }
+            }
+        }
+
         // Track packet counts
         *self
             .asset_packet_counts
@@ -271,11 +1108,16 @@ impl PacketProcessor {
             self.server_ips.insert(packet.src_ip.clone());
         }
 
-        // Build connection key (directional: src→dst on protocol)
-        let conn_key = format!(
-            "{}:{}->{}:{}:{}",
-            packet.src_ip, packet.src_port, packet.dst_ip, packet.dst_port, proto_str
-        );
+        // Build connection key (both directions of a conversation share it;
+        // the recorded src→dst is the first packet's and is oriented
+        // client→server when connections are collected)
+        let src_end = format!("{}:{}", packet.src_ip, packet.src_port);
+        let dst_end = format!("{}:{}", packet.dst_ip, packet.dst_port);
+        let conn_key = if src_end <= dst_end {
+            format!("{}<->{}:{}", src_end, dst_end, proto_str)
+        } else {
+            format!("{}<->{}:{}", dst_end, src_end, proto_str)
+        };
 
         let conn = self
             .connections
@@ -295,8 +1137,30 @@ impl PacketProcessor {
                 first_seen: timestamp.clone(),
                 last_seen: timestamp.clone(),
                 origin_files: Vec::new(),
+                provenance: Vec::new(),
+                direction_basis: DirectionBasis::Unknown,
+                vlan_ids: Vec::new(),
+                vxlan_vnis: Vec::new(),
             });
 
+        let forward = conn.src_ip == packet.src_ip && conn.src_port == packet.src_port;
+        let (src_mac, dst_mac) = if forward {
+            (&packet.src_mac, &packet.dst_mac)
+        } else {
+            (&packet.dst_mac, &packet.src_mac)
+        };
+        if conn.src_mac.is_none() {
+            conn.src_mac = src_mac.clone();
+        }
+        if conn.dst_mac.is_none() {
+            conn.dst_mac = dst_mac.clone();
+        }
+        if packet.tcp_flags & (tcp_flags::SYN | tcp_flags::ACK) == tcp_flags::SYN {
+            self.conn_syn_from_src
+                .entry(conn_key.clone())
+                .or_insert(forward);
+        }
+
         conn.packet_count += 1;
         conn.byte_count += packet.length as u64;
         conn.last_seen = timestamp.clone();
@@ -306,6 +1170,18 @@ impl PacketProcessor {
             .entry(conn_key.clone())
             .or_default()
             .insert(packet.origin_file.clone());
+        if let Some(vlan) = packet.vlan_id {
+            self.conn_vlans
+                .entry(conn_key.clone())
+                .or_default()
+                .insert(vlan);
+        }
+        if let Some(vni) = packet.vxlan_vni {
+            self.conn_vnis
+                .entry(conn_key.clone())
+                .or_default()
+                .insert(vni);
+        }
 
         // Store packet summary (cap at 1000 per connection)
         let summaries = self.packet_summaries.entry(conn.id.clone()).or_default();
@@ -323,16 +1199,71 @@ impl PacketProcessor {
         }
 
         // ── Deep Protocol Parsing ────────────────────────────────
-        if let Some(deep_result) = deep_parse(packet, protocol) {
+        // Over-budget packets still count toward connections and totals above.
+        let admitted = packet.payload.is_empty()
+            || self
+                .deep_parse_limiter
+                .admit(protocol, packet.timestamp.timestamp());
+        let deep_results = if admitted {
+            deep_parse_stream(&mut self.pdu_reassembler, packet, protocol)
+        } else {
+            // A skipped segment would leave a hole in any PDU being reassembled
+            self.pdu_reassembler.discard(packet);
+            if let Some(trace) = self.trace.as_mut() {
+                trace.set_deep_parse_skipped();
+            }
+            Vec::new()
+        };
+        // Serial device server tunnels; the RTU and DNP3 frames in them are
+        // deep parsed as usual below and name the devices on each line
+        let serial_tunnel = match packet.transport {
+            TransportProtocol::Tcp => {
+                detect_serial_tunnel(&packet.payload, packet.src_port, packet.dst_port)
+            }
+            _ => None,
+        };
+        if let Some(ref tunnel) = serial_tunnel {
+            self.process_serial_tunnel(packet, tunnel);
+            self.trace_note("serial_tunnel");
+        }
+
+        let mut write_request = false;
+        for deep_result in deep_results {
+            if let Some(trace) = self.trace.as_mut() {
+                trace.set_deep_parse(deep_parse_kind(&deep_result));
+            }
+            self.note_protocol_evidence(&packet.src_ip, identification.confirmed());
+            self.note_protocol_evidence(&packet.dst_ip, identification.confirmed());
+            write_request |= is_write_request(&deep_result);
             let ts_epoch = packet.timestamp.timestamp() as f64
-                + packet.timestamp.timestamp_subsec_millis() as f64 / 1000.0;
+                + packet.timestamp.timestamp_subsec_nanos() as f64 / 1_000_000_000.0;
 
             match deep_result {
-                DeepParseResult::Modbus(ref info) => {
-                    self.process_modbus(packet, info, ts_epoch);
+                DeepParseResult::Modbus { ref pdus } => {
+                    for info in pdus {
+                        self.process_modbus(packet, info, ts_epoch);
+                        if info.framing == ModbusFraming::RtuOverTcp {
+                            if let Some(ref tunnel) = serial_tunnel {
+                                self.note_serial_device(
+                                    packet,
+                                    tunnel,
+                                    TunneledProtocol::ModbusRtu,
+                                    u16::from(info.unit_id),
+                                );
+                            }
+                        }
+                    }
                 }
                 DeepParseResult::Dnp3(ref info) => {
                     self.process_dnp3(packet, info);
+                    if let Some(ref tunnel) = serial_tunnel {
+                        let outstation = if info.from_master {
+                            info.destination_address
+                        } else {
+                            info.source_address
+                        };
+                        self.note_serial_device(packet, tunnel, TunneledProtocol::Dnp3, outstation);
+                    }
                 }
                 DeepParseResult::Enip(ref info) => {
                     self.process_enip(packet, info);
@@ -343,11 +1274,47 @@ impl PacketProcessor {
                 DeepParseResult::Bacnet(ref info) => {
                     self.process_bacnet(packet, info);
                 }
+                DeepParseResult::Fins(ref info) => {
+                    self.process_fins(packet, info);
+                }
+                DeepParseResult::Slmp(ref info) => {
+                    self.process_slmp(packet, info);
+                }
+                DeepParseResult::GeSrtp(ref info) => {
+                    self.process_ge_srtp(packet, info);
+                }
+                DeepParseResult::Codesys(ref info) => {
+                    self.process_codesys(packet, info);
+                }
+                DeepParseResult::Fox(ref info) => {
+                    self.process_fox(packet, info);
+                }
+                DeepParseResult::Crimson(ref info) => {
+                    self.process_crimson(packet, info);
+                }
+                DeepParseResult::VnetIp(ref info) => {
+                    self.process_vnetip(packet, info);
+                }
+                DeepParseResult::TriStation(ref info) => {
+                    self.process_tristation(packet, info);
+                }
+                DeepParseResult::Ads(ref info) => {
+                    self.process_ads(packet, info);
+                }
+                DeepParseResult::OpcDa(ref info) => {
+                    self.process_opc_da(packet, info);
+                }
                 DeepParseResult::Iec104(ref info) => {
                     self.process_iec104(packet, info);
                 }
+                DeepParseResult::Mms(ref info) => {
+                    self.process_mms(packet, info);
+                }
                 DeepParseResult::ProfinetDcp(ref info) => {
-                    self.process_profinet_dcp(packet, info);
+                    self.process_profinet_dcp(&packet.src_ip, info);
+                }
+                DeepParseResult::Smb(ref info) => {
+                    self.process_smb(packet, info);
                 }
                 // LLDP is handled by the early-return above; deep_parse()
                 // never returns Lldp since it's not an IP-layer protocol.
@@ -355,11 +1322,120 @@ impl PacketProcessor {
             }
         }
 
+        // Engineering software fingerprints (TIA Portal, Studio 5000, Unity, CODESYS)
+        if let Some(fp) = gm_parsers::detect_engineering_software(
+            &packet.payload,
+            packet.src_port,
+            packet.dst_port,
+        ) {
+            self.engineering_software
+                .entry(packet.src_ip.clone())
+                .or_default()
+                .insert(fp.software);
+            if fp.conclusive {
+                self.conclusive_engineering.insert(packet.src_ip.clone());
+            }
+            self.trace_note("engineering_software");
+        }
+
+        // TLS hellos fingerprint the TLS stack of otherwise opaque sessions
+        let tls_hello = match packet.transport {
+            TransportProtocol::Tcp => parse_tls_hello(&packet.payload),
+            _ => None,
+        };
+        if let Some(ref hello) = tls_hello {
+            self.process_tls_hello(packet, hello);
+            self.trace_note("tls_fingerprints");
+        }
+
+        if protocol == IcsProtocol::Mqtt
+            && packet.transport == TransportProtocol::Tcp
+            && !packet.payload.is_empty()
+        {
+            self.process_mqtt(packet);
+        }
+
+        if packet.transport == TransportProtocol::Tcp && !packet.payload.is_empty() {
+            self.process_cloud_connectivity(packet, protocol);
+        }
+
         // SNMP GET-Response: extract device identity from responses (src port 161)
         if packet.src_port == 161 && !packet.payload.is_empty() {
             if let Some(dev_info) = parse_snmp_response(&packet.payload) {
-                self.snmp_device_info
-                    .insert(packet.src_ip.clone(), dev_info);
+                if let Some(seen) = self.snmp_device_info.get_mut(&packet.src_ip) {
+                    seen.merge(dev_info);
+                } else {
+                    self.snmp_device_info
+                        .insert(packet.src_ip.clone(), dev_info);
+                }
+                self.trace_note("snmp_identity");
+            }
+        }
+
+        // DHCP: client hostnames and vendor classes, leases, and every server that answers
+        if packet.transport == TransportProtocol::Udp
+            && matches!(packet.src_port, DHCP_SERVER_PORT | DHCP_CLIENT_PORT)
+            && matches!(packet.dst_port, DHCP_SERVER_PORT | DHCP_CLIENT_PORT)
+        {
+            if let Some(info) = parse_dhcp(&packet.payload) {
+                self.process_dhcp(packet, &info);
+                self.trace_note("dhcp");
+            }
+        }
+
+        // DNS responses: the names the site's DNS gives to addresses
+        if packet.src_port == DNS_PORT && !packet.payload.is_empty() {
+            let info = match packet.transport {
+                TransportProtocol::Udp => parse_dns(&packet.payload),
+                TransportProtocol::Tcp => parse_dns_tcp(&packet.payload),
+                _ => None,
+            };
+            if let Some(info) = info {
+                self.process_dns(&info);
+                self.trace_note("dns");
+            }
+        }
+
+        // ICMP: echo fan-out and unreachable reports, for discovery detection
+        if matches!(
+            packet.transport,
+            TransportProtocol::Icmp | TransportProtocol::Icmpv6
+        ) {
+            let v6 = packet.transport == TransportProtocol::Icmpv6;
+            if let Some(info) = parse_icmp(&packet.payload, v6) {
+                self.process_icmp(packet, &info);
+                self.trace_note("icmp");
+            }
+        }
+
+        // Name announcements: Windows and Apple hosts stating their own names
+        if packet.transport == TransportProtocol::Udp && !packet.payload.is_empty() {
+            if packet.src_port == NBNS_PORT {
+                let name = parse_nbns(&packet.payload)
+                    .and_then(|info| info.host_name(&packet.src_ip).map(str::to_string));
+                if let Some(name) = name {
+                    self.record_announced_name(&packet.src_ip, "netbios", name);
+                    self.trace_note("netbios");
+                }
+            } else if matches!(packet.src_port, MDNS_PORT | LLMNR_PORT) {
+                let protocol = if packet.src_port == MDNS_PORT {
+                    "mdns"
+                } else {
+                    "llmnr"
+                };
+                // Only names the sender gives its own address: a poisoning
+                // responder answering for every name would otherwise rename
+                // the hosts it impersonates
+                let name = parse_dns(&packet.payload).and_then(|info| {
+                    info.bindings()
+                        .into_iter()
+                        .find(|b| b.ip == packet.src_ip)
+                        .map(|b| b.name)
+                });
+                if let Some(name) = name {
+                    self.record_announced_name(&packet.src_ip, protocol, name);
+                    self.trace_note(protocol);
+                }
             }
         }
 
@@ -372,6 +1448,21 @@ impl PacketProcessor {
             protocol,
             packet.length as u64,
         );
+        self.topo_builder.add_ports(
+            &packet.src_ip,
+            &packet.dst_ip,
+            packet.src_port,
+            packet.dst_port,
+            packet.length as u64,
+        );
+        self.topo_builder
+            .mark_seen(&packet.src_ip, &packet.dst_ip, packet.timestamp);
+        self.topo_builder.add_traffic(
+            &packet.src_ip,
+            &packet.dst_ip,
+            packet.timestamp,
+            packet.length as u64,
+        );
 
         // Record packet for communication pattern analysis (O(1))
         let ts_epoch_pattern = packet.timestamp.timestamp() as f64
@@ -385,6 +1476,36 @@ impl PacketProcessor {
             packet.length as u64,
         );
 
+        // Bucket by time of week for shift comparisons
+        let master = (protocol.is_ot()
+            && is_server_port(packet.dst_port)
+            && !is_server_port(packet.src_port))
+        .then_some(packet.src_ip.as_str());
+        self.shift_activity.record_packet(
+            packet.timestamp,
+            &proto_str,
+            packet.length as u64,
+            master,
+            write_request.then_some(packet.dst_ip.as_str()),
+        );
+
+        // Follow Modbus/DNP3 TCP sessions, including their bare SYN/ACK/FIN segments
+        if packet.transport == TransportProtocol::Tcp {
+            let pdu = session_pdu(packet);
+            if pdu.is_some() || matches!(protocol, IcsProtocol::Modbus | IcsProtocol::Dnp3) {
+                self.session_tracker.record_packet(&SessionPacket {
+                    src_ip: &packet.src_ip,
+                    src_port: packet.src_port,
+                    dst_ip: &packet.dst_ip,
+                    dst_port: packet.dst_port,
+                    tcp_flags: packet.tcp_flags,
+                    pdu,
+                    timestamp_secs: ts_epoch_pattern,
+                });
+                self.trace_note("sessions");
+            }
+        }
+
         // Accumulate signature matching data (PacketData per IP)
         let pkt_data = PacketData {
             src_ip: packet.src_ip.clone(),
@@ -397,6 +1518,7 @@ impl PacketProcessor {
             protocol: format!("{:?}", protocol).to_lowercase(),
             payload: packet.payload.clone(),
             length: packet.length,
+            tls: tls_hello,
         };
 
         // Cap signature-matching packet storage at 200 per IP.
@@ -404,13 +1526,20 @@ impl PacketProcessor {
         // storing all packets would consume gigabytes for large captures.
         const IP_PACKET_CAP: usize = 200;
         let src_entry = self.ip_packets.entry(packet.src_ip.clone()).or_default();
-        if src_entry.len() < IP_PACKET_CAP {
+        let src_sampled = src_entry.len() < IP_PACKET_CAP;
+        if src_sampled {
             src_entry.push(pkt_data.clone());
         }
         let dst_entry = self.ip_packets.entry(packet.dst_ip.clone()).or_default();
-        if dst_entry.len() < IP_PACKET_CAP {
+        let dst_sampled = dst_entry.len() < IP_PACKET_CAP;
+        if dst_sampled {
             dst_entry.push(pkt_data);
         }
+        // Packets past the cap are never seen by signature matching, which
+        // is worth knowing when a signature "should" have matched
+        if src_sampled || dst_sampled {
+            self.trace_note("signature_samples");
+        }
     }
 
     /// Process Modbus deep parse result for a packet.
@@ -454,6 +1583,19 @@ impl PacketProcessor {
                 .or_default()
                 .entry((range.start, range.count, reg_type))
                 .or_insert(0) += 1;
+
+            // The slave side of the same request feeds its register map
+            if info.role == ModbusRole::Master {
+                let write = modbus_is_write_function_code(info.function_code);
+                let log = self
+                    .modbus_register_access
+                    .entry((packet.dst_ip.clone(), info.unit_id))
+                    .or_default();
+                *log.ranges
+                    .entry((range.start, range.count, range.register_type, write))
+                    .or_insert(0) += 1;
+                log.masters.insert(packet.src_ip.clone());
+            }
         }
 
         if let Some(ref dev_id) = info.device_id {
@@ -461,6 +1603,10 @@ impl PacketProcessor {
                 .insert(packet.src_ip.clone(), dev_id.clone());
         }
 
+        if info.framing == ModbusFraming::RtuOverTcp {
+            self.modbus_rtu_over_tcp.insert(packet.src_ip.clone());
+        }
+
         let (local_ip, remote_ip, remote_role) = match info.role {
             ModbusRole::Master => (&packet.src_ip, &packet.dst_ip, "slave"),
             ModbusRole::Slave => (&packet.src_ip, &packet.dst_ip, "master"),
@@ -475,6 +1621,22 @@ impl PacketProcessor {
         rel.1.insert(info.unit_id);
         rel.2 += 1;
 
+        if info.role != ModbusRole::Unknown {
+            self.modbus_transactions.record(&ModbusMessage {
+                src_ip: &packet.src_ip,
+                src_port: packet.src_port,
+                dst_ip: &packet.dst_ip,
+                dst_port: packet.dst_port,
+                transaction_id: info.transaction_id,
+                unit_id: info.unit_id,
+                function_code: info.function_code,
+                is_request: info.role == ModbusRole::Master,
+                exception_code: info.exception_code.filter(|_| info.is_exception),
+                register: info.register_range.as_ref().map(|r| (r.start, r.count)),
+                timestamp_secs: ts_epoch,
+            });
+        }
+
         if info.role == ModbusRole::Master && !info.is_exception {
             let key = (
                 packet.src_ip.clone(),
@@ -521,6 +1683,14 @@ impl PacketProcessor {
             self.dnp3_unsolicited.insert(ip_for_fc.clone(), true);
         }
 
+        if info.is_secure_auth {
+            self.dnp3_secure_auth.insert(ip_for_fc.clone());
+        }
+
+        if info.crc_errors > 0 {
+            *self.dnp3_crc_errors.entry(ip_for_fc.clone()).or_insert(0) += info.crc_errors as u64;
+        }
+
         let remote_role = match info.role {
             Dnp3Role::Master => "outstation",
             Dnp3Role::Outstation => "master",
@@ -533,6 +1703,27 @@ impl PacketProcessor {
             .entry(packet.dst_ip.clone())
             .or_insert_with(|| (remote_role.to_string(), 0));
         rel.1 += 1;
+
+        // Remember which IP speaks for each master link address so a second
+        // station claiming the same address can be spotted later
+        if info.from_master {
+            let timestamp = packet.timestamp.to_rfc3339();
+            let binding = self
+                .dnp3_master_bindings
+                .entry(ip_for_fc.clone())
+                .or_default()
+                .entry((info.source_address, info.destination_address))
+                .or_insert_with(|| Dnp3AddressBinding {
+                    master_address: info.source_address,
+                    outstation_address: info.destination_address,
+                    outstation_ip: packet.dst_ip.clone(),
+                    first_seen: timestamp.clone(),
+                    last_seen: timestamp.clone(),
+                    frame_count: 0,
+                });
+            binding.last_seen = timestamp;
+            binding.frame_count += 1;
+        }
     }
 
     /// Process EtherNet/IP deep parse result for a packet.
@@ -546,9 +1737,18 @@ impl PacketProcessor {
         };
         self.enip_roles.insert(ip.clone(), role_str.to_string());
 
-        // ListIdentity request (not a response) — network discovery
-        if matches!(info.command, EnipCommand::ListIdentity) && !info.is_response {
-            self.enip_list_identity.insert(ip.clone());
+        // ListIdentity without identity data is a request — network
+        // discovery. Responses, usually UDP replies to a broadcast, name the
+        // device outright.
+        if matches!(info.command, EnipCommand::ListIdentity) {
+            match info.identity {
+                Some(ref identity) => {
+                    self.enip_identities.insert(ip.clone(), identity.clone());
+                }
+                None => {
+                    self.enip_list_identity.insert(ip.clone());
+                }
+            }
         }
 
         // CIP Write or ReadModifyWrite to Assembly object — I/O control
@@ -600,6 +1800,63 @@ impl PacketProcessor {
         }
     }
 
+    /// Record a serial device server line from a tunnel segment.
+    fn process_serial_tunnel(&mut self, packet: &ParsedPacket, tunnel: &SerialTunnelInfo) {
+        let (gateway_ip, host_ip) = if tunnel.to_gateway {
+            (&packet.dst_ip, &packet.src_ip)
+        } else {
+            (&packet.src_ip, &packet.dst_ip)
+        };
+        let line = self
+            .serial_gateways
+            .entry(gateway_ip.clone())
+            .or_insert_with(|| SerialGatewayActivity {
+                gateway: tunnel.gateway,
+                lines: BTreeMap::new(),
+            })
+            .lines
+            .entry(tunnel.gateway_port)
+            .or_insert_with(|| SerialLineActivity {
+                channel: tunnel.channel,
+                rfc2217: false,
+                settings: ComPortSettings::default(),
+                protocols: BTreeSet::new(),
+                hosts: BTreeSet::new(),
+                devices: BTreeMap::new(),
+            });
+        line.rfc2217 |= tunnel.framing == SerialFraming::Rfc2217;
+        line.settings.merge(&tunnel.settings);
+        if let Some(protocol) = tunnel.tunneled {
+            line.protocols.insert(protocol.as_str());
+        }
+        line.hosts.insert(host_ip.clone());
+    }
+
+    /// Count a frame to or from a field device on a serial line.
+    fn note_serial_device(
+        &mut self,
+        packet: &ParsedPacket,
+        tunnel: &SerialTunnelInfo,
+        protocol: TunneledProtocol,
+        address: u16,
+    ) {
+        let gateway_ip = if tunnel.to_gateway {
+            &packet.dst_ip
+        } else {
+            &packet.src_ip
+        };
+        if let Some(line) = self
+            .serial_gateways
+            .get_mut(gateway_ip)
+            .and_then(|g| g.lines.get_mut(&tunnel.gateway_port))
+        {
+            *line
+                .devices
+                .entry((protocol.as_str(), address))
+                .or_insert(0) += 1;
+        }
+    }
+
     /// Process BACnet deep parse result for a packet.
     fn process_bacnet(&mut self, packet: &ParsedPacket, info: &gm_parsers::BacnetInfo) {
         let ip = &packet.src_ip;
@@ -611,6 +1868,25 @@ impl PacketProcessor {
         };
         self.bacnet_roles.insert(ip.clone(), role_str.to_string());
 
+        // I-Am gives the instance and vendor ID; acks of Device object reads
+        // add the names and revisions, usually a few properties at a time
+        if let Some(ref iam) = info.iam {
+            self.bacnet_devices
+                .entry(ip.clone())
+                .or_default()
+                .merge(BacnetDeviceProperties {
+                    device_instance: Some(iam.device_instance),
+                    vendor_id: Some(iam.vendor_id),
+                    ..Default::default()
+                });
+        }
+        if let Some(ref props) = info.device_properties {
+            self.bacnet_devices
+                .entry(ip.clone())
+                .or_default()
+                .merge(props.clone());
+        }
+
         match info.service {
             Some(BacnetService::WriteProperty) | Some(BacnetService::WritePropertyMultiple) => {
                 match info.object_type {
@@ -655,31 +1931,1174 @@ impl PacketProcessor {
         }
     }
 
-    /// Process PROFINET DCP deep parse result for a packet.
-    fn process_profinet_dcp(&mut self, packet: &ParsedPacket, info: &gm_parsers::ProfinetDcpInfo) {
+    /// Process OMRON FINS deep parse result for a packet.
+    fn process_fins(&mut self, packet: &ParsedPacket, info: &gm_parsers::FinsInfo) {
         let ip = &packet.src_ip;
 
+        // A PLC that ever answered stays a server (CPUs also send FINS
+        // messages to each other via SEND/RECV instructions)
         let role_str = match info.role {
-            ProfinetRole::IoDevice => "io_device",
+            FinsRole::Client => Some("client"),
+            FinsRole::Server => Some("server"),
+            FinsRole::Unknown => None,
+        };
+        if let Some(role_str) = role_str {
+            if self.fins_roles.get(ip).map(String::as_str) != Some("server") {
+                self.fins_roles.insert(ip.clone(), role_str.to_string());
+            }
+        }
+
+        if let Some(code) = info.command_code {
+            self.fins_commands
+                .entry(ip.clone())
+                .or_default()
+                .insert(code);
+        }
+
+        // Source node of a FINS frame, plus the nodes assigned in the
+        // FINS/TCP node address exchange
+        if let Some(source) = info.source {
+            self.fins_nodes
+                .entry(ip.clone())
+                .or_default()
+                .insert(source.node);
+        }
+        if let Some(node) = info.client_node {
+            let client_ip = if info.tcp_command == Some(FinsTcpCommand::NodeAddressResponse) {
+                &packet.dst_ip
+            } else {
+                &packet.src_ip
+            };
+            self.fins_nodes
+                .entry(client_ip.clone())
+                .or_default()
+                .insert(node);
+        }
+        if let Some(node) = info.server_node {
+            self.fins_nodes.entry(ip.clone()).or_default().insert(node);
+        }
+
+        if info.is_write {
+            self.fins_writes.insert(ip.clone());
+        }
+        if info.is_control {
+            self.fins_control_commands.insert(ip.clone());
+        }
+        if let Some(ref controller) = info.controller {
+            self.fins_controllers.insert(ip.clone(), controller.clone());
+        }
+    }
+
+    /// Process MELSEC SLMP / MC protocol deep parse result for a packet.
+    fn process_slmp(&mut self, packet: &ParsedPacket, info: &gm_parsers::SlmpInfo) {
+        let ip = &packet.src_ip;
+
+        let role_str = match info.role {
+            SlmpRole::Client => "client",
+            SlmpRole::Server => "server",
+        };
+        if self.slmp_roles.get(ip).map(String::as_str) != Some("server") {
+            self.slmp_roles.insert(ip.clone(), role_str.to_string());
+        }
+
+        if let Some(command) = info.command {
+            self.slmp_commands
+                .entry(ip.clone())
+                .or_default()
+                .insert(command);
+            if command == gm_parsers::slmp::READ_CPU_MODEL {
+                self.slmp_model_reads.insert((
+                    packet.src_ip.clone(),
+                    packet.dst_ip.clone(),
+                    info.serial,
+                ));
+            }
+        }
+        if let Some(ref access) = info.device_access {
+            self.slmp_devices
+                .entry(packet.dst_ip.clone())
+                .or_default()
+                .insert(access.device.clone());
+        }
+        if info.is_write {
+            self.slmp_writes.insert(ip.clone());
+        }
+        if info.is_control {
+            self.slmp_control_commands.insert(ip.clone());
+        }
+
+        // Responses don't echo the command: pair with an outstanding
+        // Read CPU Model Name request from the peer
+        if info.is_response {
+            let key = (packet.dst_ip.clone(), packet.src_ip.clone(), info.serial);
+            if self.slmp_model_reads.remove(&key) && info.end_code == Some(0) {
+                if let Some(model) = gm_parsers::parse_slmp_cpu_model(&info.response_data) {
+                    self.slmp_cpu_models.insert(ip.clone(), model);
+                }
+            }
+        }
+    }
+
+    /// Process GE SRTP deep parse result for a packet.
+    fn process_ge_srtp(&mut self, packet: &ParsedPacket, info: &gm_parsers::SrtpInfo) {
+        let ip = &packet.src_ip;
+
+        let role_str = match info.role {
+            SrtpRole::Client => "client",
+            SrtpRole::Server => "server",
+        };
+        if self.ge_srtp_roles.get(ip).map(String::as_str) != Some("server") {
+            self.ge_srtp_roles.insert(ip.clone(), role_str.to_string());
+        }
+
+        if let Some(code) = info.service_code {
+            self.ge_srtp_services
+                .entry(ip.clone())
+                .or_default()
+                .insert(code);
+            if code == gm_parsers::ge_srtp::RETURN_CONTROLLER_INFO {
+                self.ge_srtp_id_reads.insert((
+                    packet.src_ip.clone(),
+                    packet.dst_ip.clone(),
+                    info.sequence,
+                ));
+            }
+        }
+        if let Some(ref access) = info.memory_access {
+            self.ge_srtp_segments
+                .entry(packet.dst_ip.clone())
+                .or_default()
+                .insert(access.segment.clone());
+        }
+        if info.is_write {
+            self.ge_srtp_writes.insert(ip.clone());
+        }
+        if info.is_control {
+            self.ge_srtp_control_commands.insert(ip.clone());
+        }
+        if let Some(state) = info.plc_state {
+            self.ge_srtp_states.insert(ip.clone(), state);
+        }
+
+        // Responses carry only the sequence number: pair with an outstanding
+        // Return Controller Type and ID request from the peer
+        if info.status.is_some() {
+            let key = (packet.dst_ip.clone(), packet.src_ip.clone(), info.sequence);
+            if self.ge_srtp_id_reads.remove(&key) && info.status == Some(0) {
+                if let Some(controller) =
+                    gm_parsers::parse_ge_srtp_controller_info(&info.response_data)
+                {
+                    self.ge_srtp_controllers.insert(ip.clone(), controller);
+                }
+            }
+        }
+    }
+
+    /// Process CODESYS runtime protocol deep parse result for a packet.
+    fn process_codesys(&mut self, packet: &ParsedPacket, info: &gm_parsers::CodesysInfo) {
+        let ip = &packet.src_ip;
+
+        let role_str = match info.role {
+            CodesysRole::Client => "client",
+            CodesysRole::Server => "server",
+        };
+        if self.codesys_roles.get(ip).map(String::as_str) != Some("server") {
+            self.codesys_roles.insert(ip.clone(), role_str.to_string());
+        }
+        self.codesys_versions
+            .entry(ip.clone())
+            .or_default()
+            .insert(info.version);
+
+        if !info.is_response {
+            if let Some(service) = info.service_id {
+                self.codesys_services
+                    .entry(ip.clone())
+                    .or_default()
+                    .insert((info.service_group, service));
+            }
+        }
+        if info.is_login {
+            self.codesys_logins.insert(ip.clone());
+        }
+        if info.is_download {
+            self.codesys_downloads.insert(ip.clone());
+        }
+        if info.is_online_change {
+            self.codesys_online_changes.insert(ip.clone());
+        }
+        if info.is_control {
+            self.codesys_control_commands.insert(ip.clone());
+        }
+    }
+
+    /// Process Niagara Fox deep parse result for a packet.
+    fn process_fox(&mut self, packet: &ParsedPacket, info: &gm_parsers::FoxInfo) {
+        let ip = &packet.src_ip;
+
+        let role_str = match info.role {
+            FoxRole::Client => "client",
+            FoxRole::Server => "server",
+        };
+        if self.fox_roles.get(ip).map(String::as_str) != Some("server") {
+            self.fox_roles.insert(ip.clone(), role_str.to_string());
+        }
+
+        // A station hello (with station.name) outranks a client hello from
+        // the same host, e.g. a Supervisor that also connects out to JACEs
+        if let Some(ref hello) = info.hello {
+            let replace = match self.fox_hellos.get(ip) {
+                None => true,
+                Some(existing) => existing.station_name.is_none() && hello.station_name.is_some(),
+            };
+            if replace {
+                self.fox_hellos.insert(ip.clone(), hello.clone());
+            }
+        }
+    }
+
+    /// Process Red Lion Crimson v3 deep parse result for a packet.
+    fn process_crimson(&mut self, packet: &ParsedPacket, info: &gm_parsers::CrimsonInfo) {
+        let ip = &packet.src_ip;
+
+        let role_str = match info.role {
+            CrimsonRole::Client => "client",
+            CrimsonRole::Server => "server",
+        };
+        if self.crimson_roles.get(ip).map(String::as_str) != Some("server") {
+            self.crimson_roles.insert(ip.clone(), role_str.to_string());
+        }
+
+        for identity in &info.identity {
+            match identity {
+                CrimsonIdentity::Manufacturer(m) => {
+                    self.crimson_manufacturers.insert(ip.clone(), m.clone());
+                }
+                CrimsonIdentity::Model(m) => {
+                    self.crimson_models.insert(ip.clone(), m.clone());
+                }
+            }
+        }
+
+        if info.bulk_bytes > 0 {
+            let bytes = info.bulk_bytes as u64;
+            match info.role {
+                CrimsonRole::Client => {
+                    let key = (packet.src_ip.clone(), packet.dst_ip.clone());
+                    self.crimson_bulk.entry(key).or_default().0 += bytes;
+                }
+                CrimsonRole::Server => {
+                    let key = (packet.dst_ip.clone(), packet.src_ip.clone());
+                    self.crimson_bulk.entry(key).or_default().1 += bytes;
+                }
+            }
+        }
+    }
+
+    /// Process Yokogawa Vnet/IP deep parse result for a packet.
+    fn process_vnetip(&mut self, packet: &ParsedPacket, info: &gm_parsers::VnetIpInfo) {
+        if let Some(src) = info.src {
+            self.vnetip_stations.insert(packet.src_ip.clone(), src);
+        }
+        if let Some(dst) = info.dst {
+            self.vnetip_stations.insert(packet.dst_ip.clone(), dst);
+        }
+        if info.src.is_some() && info.dst.is_some() {
+            self.vnetip_peers
+                .entry(packet.src_ip.clone())
+                .or_default()
+                .insert(packet.dst_ip.clone());
+            self.vnetip_peers
+                .entry(packet.dst_ip.clone())
+                .or_default()
+                .insert(packet.src_ip.clone());
+        }
+    }
+
+    /// Process Triconex TriStation deep parse result for a packet.
+    fn process_tristation(&mut self, packet: &ParsedPacket, info: &gm_parsers::TriStationInfo) {
+        let ip = &packet.src_ip;
+
+        let role_str = match info.role {
+            TriStationRole::Client => "client",
+            TriStationRole::Server => "server",
+        };
+        if self.tristation_roles.get(ip).map(String::as_str) != Some("server") {
+            self.tristation_roles
+                .insert(ip.clone(), role_str.to_string());
+        }
+
+        if let Some(command) = info.command {
+            self.tristation_commands
+                .entry(ip.clone())
+                .or_default()
+                .insert(command);
+            self.tristation_peers
+                .entry(ip.clone())
+                .or_default()
+                .insert(packet.dst_ip.clone());
+            self.tristation_peers
+                .entry(packet.dst_ip.clone())
+                .or_default()
+                .insert(ip.clone());
+        }
+    }
+
+    /// Process Beckhoff ADS/AMS deep parse result for a packet.
+    fn process_ads(&mut self, packet: &ParsedPacket, info: &gm_parsers::AdsInfo) {
+        let ip = &packet.src_ip;
+
+        let role_str = match info.role {
+            AdsRole::Client => "client",
+            AdsRole::Server => "server",
+        };
+        if self.ads_roles.get(ip).map(String::as_str) != Some("server") {
+            self.ads_roles.insert(ip.clone(), role_str.to_string());
+        }
+
+        for msg in &info.messages {
+            self.ads_net_ids
+                .entry(ip.clone())
+                .or_default()
+                .insert(msg.source.net_id.clone());
+
+            if msg.is_response {
+                if let Some(ref device) = msg.device_info {
+                    self.ads_device_info.insert(ip.clone(), device.clone());
+                }
+                if let Some(state) = msg.ads_state {
+                    self.ads_states.insert(ip.clone(), state);
+                }
+                continue;
+            }
+            // Notifications are pushed by the runtime to its subscribers
+            if msg.command == AdsCommand::DeviceNotification {
+                continue;
+            }
+
+            self.ads_ports
+                .entry(packet.dst_ip.clone())
+                .or_default()
+                .insert(msg.target.port);
+            self.ads_commands
+                .entry(ip.clone())
+                .or_default()
+                .insert(msg.command);
+            *self
+                .ads_links
+                .entry((ip.clone(), packet.dst_ip.clone()))
+                .or_insert(0) += 1;
+            if msg.command == AdsCommand::WriteControl {
+                if let Some(state) = msg.ads_state {
+                    self.ads_state_changes
+                        .entry(ip.clone())
+                        .or_default()
+                        .insert(state);
+                }
+            }
+        }
+    }
+
+    /// Process a classic OPC DCE/RPC PDU.
+    ///
+    /// PDUs naming OPC interfaces say who is client and server directly;
+    /// later PDUs on a bound connection are attributed through the endpoint
+    /// they were bound to. The OPC server calls back into the client's
+    /// IOPCDataCallback, so on a callback connection the roles are swapped.
+    fn process_opc_da(&mut self, packet: &ParsedPacket, info: &OpcDaInfo) {
+        let (client, server) = if !info.interfaces.is_empty() {
+            if info.interfaces.iter().all(|i| i.is_callback()) {
+                (&packet.dst_ip, &packet.src_ip)
+            } else {
+                (&packet.src_ip, &packet.dst_ip)
+            }
+        } else {
+            let dst = (packet.dst_ip.clone(), packet.dst_port);
+            let src = (packet.src_ip.clone(), packet.src_port);
+            match (
+                self.opc_da_endpoints.get(&dst),
+                self.opc_da_endpoints.get(&src),
+            ) {
+                (Some(false), _) | (None, Some(true)) => (&packet.src_ip, &packet.dst_ip),
+                (Some(true), _) | (None, Some(false)) => (&packet.dst_ip, &packet.src_ip),
+                // Untagged endpoint-mapper / activation traffic on 135
+                (None, None) => return,
+            }
+        };
+
+        self.opc_da_roles
+            .insert(server.clone(), "server".to_string());
+        if self.opc_da_roles.get(client).map(String::as_str) != Some("server") {
+            self.opc_da_roles
+                .insert(client.clone(), "client".to_string());
+        }
+        for ip in [client, server] {
+            self.opc_da_interfaces
+                .entry(ip.clone())
+                .or_default()
+                .extend(info.interfaces.iter().copied());
+        }
+        let link = self
+            .opc_da_links
+            .entry((client.clone(), server.clone()))
+            .or_insert(0);
+        if info.packet_type == DceRpcPacketType::Request {
+            *link += 1;
+        }
+    }
+
+    /// Record the address → name bindings of a DNS response.
+    ///
+    /// A reverse (PTR) answer is the name the address is registered under,
+    /// so it replaces a name learned from a forward lookup; otherwise the
+    /// first name seen for an address is kept.
+    fn process_dns(&mut self, info: &DnsInfo) {
+        for binding in info.bindings() {
+            let keep_existing = match self.dns_names.get(&binding.ip) {
+                Some((_, DnsBindingSource::Ptr)) => true,
+                Some(_) => binding.source != DnsBindingSource::Ptr,
+                None => false,
+            };
+            if !keep_existing {
+                self.dns_names
+                    .insert(binding.ip, (binding.name, binding.source));
+            }
+        }
+    }
+
+    /// Keep the first name `ip` announced over `protocol`.
+    fn record_announced_name(&mut self, ip: &str, protocol: &'static str, name: String) {
+        self.announced_names
+            .entry(ip.to_string())
+            .or_default()
+            .entry(protocol)
+            .or_insert(name);
+    }
+
+    /// Record a DHCP message.
+    ///
+    /// Relays forward server replies from their own address, so the
+    /// server identifier option names the server when present.
+    fn process_dhcp(&mut self, packet: &ParsedPacket, info: &DhcpInfo) {
+        let client = self
+            .dhcp_clients
+            .entry(info.client_mac.clone())
+            .or_default();
+        if info.message_type.is_server_reply() {
+            let server = info
+                .server_id
+                .clone()
+                .unwrap_or_else(|| packet.src_ip.clone());
+            if info.message_type == DhcpMessageType::Ack {
+                client.server_ip = Some(server.clone());
+            }
+            let record = self.dhcp_servers.entry(server).or_default();
+            record.replies += 1;
+            record.clients.insert(info.client_mac.clone());
+        } else {
+            if info.hostname.is_some() {
+                client.hostname = info.hostname.clone();
+            }
+            if info.vendor_class.is_some() {
+                client.vendor_class = info.vendor_class.clone();
+            }
+        }
+        if let Some(ip) = info.bound_ip() {
+            client.ip = Some(ip.to_string());
+        }
+    }
+
+    /// Process SMB2 deep parse result for a packet.
+    ///
+    /// Creates, reads and writes only carry a TreeId, which the server
+    /// assigns in its Tree Connect response. The requested share path is
+    /// held by MessageId until that response binds it to the TreeId.
+    fn process_smb(&mut self, packet: &ParsedPacket, info: &gm_parsers::SmbInfo) {
+        let (client, server) = match info.role {
+            SmbRole::Client => (&packet.src_ip, &packet.dst_ip),
+            SmbRole::Server => (&packet.dst_ip, &packet.src_ip),
+        };
+        let role_str = match info.role {
+            SmbRole::Client => "client",
+            SmbRole::Server => "server",
+        };
+        if self.smb_roles.get(&packet.src_ip).map(String::as_str) != Some("server") {
+            self.smb_roles
+                .insert(packet.src_ip.clone(), role_str.to_string());
+        }
+        if info.smb1 {
+            self.smb1_hosts.insert(packet.src_ip.clone());
+        }
+
+        for msg in &info.messages {
+            let tree_key = (client.clone(), server.clone(), msg.tree_id);
+            match (msg.command, msg.is_response) {
+                (Smb2Command::SessionSetup, false) => {
+                    if let Some(ref identity) = msg.identity {
+                        self.smb_users
+                            .entry(client.clone())
+                            .or_default()
+                            .insert(identity.account());
+                    }
+                }
+                (Smb2Command::TreeConnect, false) => {
+                    if let Some(ref path) = msg.share_path {
+                        self.smb_pending_trees.insert(
+                            (client.clone(), server.clone(), msg.message_id),
+                            path.clone(),
+                        );
+                    }
+                }
+                (Smb2Command::TreeConnect, true) => {
+                    let pending = (client.clone(), server.clone(), msg.message_id);
+                    if let Some(share) = self.smb_pending_trees.remove(&pending) {
+                        // STATUS_SUCCESS; denied shares were never mounted
+                        if msg.status == 0 {
+                            self.smb_shares
+                                .entry((client.clone(), server.clone(), share.clone()))
+                                .or_default();
+                            self.smb_trees.insert(tree_key, share);
+                        }
+                    }
+                }
+                (Smb2Command::Create, false)
+                | (Smb2Command::Write, false)
+                | (Smb2Command::Read, true) => {
+                    let Some(share) = self.smb_trees.get(&tree_key) else {
+                        continue;
+                    };
+                    let activity = self
+                        .smb_shares
+                        .entry((client.clone(), server.clone(), share.clone()))
+                        .or_default();
+                    match msg.command {
+                        Smb2Command::Create => {
+                            if let Some(ref name) = msg.file_name {
+                                if activity.files.len() < SMB_MAX_FILES {
+                                    activity.files.insert(name.clone());
+                                }
+                                if msg.write_access && activity.files_written.len() < SMB_MAX_FILES
+                                {
+                                    activity.files_written.insert(name.clone());
+                                }
+                            }
+                        }
+                        Smb2Command::Write => activity.bytes_written += msg.data_length as u64,
+                        _ => activity.bytes_read += msg.data_length as u64,
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Record outbound connections to cloud IoT platforms, vendor clouds,
+    /// remote-access brokers and OPC UA reverse-connect clients.
+    ///
+    /// The initiating (source) host is the local side. TLS connections are
+    /// only kept when the SNI names a known service, or when it is MQTT over
+    /// TLS to a public address; plain HTTPS browsing stays out of the list.
+    fn process_cloud_connectivity(&mut self, packet: &ParsedPacket, protocol: IcsProtocol) {
+        let payload = &packet.payload;
+        let dst_public = || GeoIpLookup::is_public_ip(&packet.dst_ip);
+
+        let (hostname, service, evidence, identity) =
+            if let Some(sni) = tls_client_hello_sni(payload) {
+                let service = match classify_cloud_host(&sni) {
+                    Some(svc) => Some((svc.name.to_string(), svc.category)),
+                    None if packet.dst_port == 8883 && dst_public() => {
+                        Some(("MQTT broker".to_string(), CloudCategory::MqttBroker))
+                    }
+                    None => None,
+                };
+                (Some(sni), service, "TLS SNI", None)
+            } else if protocol == IcsProtocol::Mqtt {
+                let Some(connect) = parse_mqtt_connect(payload) else {
+                    return;
+                };
+                let host = connect.username_host().map(str::to_ascii_lowercase);
+                let service = match host.as_deref().and_then(classify_cloud_host) {
+                    Some(svc) => Some((svc.name.to_string(), svc.category)),
+                    None if dst_public() => {
+                        Some(("MQTT broker".to_string(), CloudCategory::MqttBroker))
+                    }
+                    None => None,
+                };
+                let client_id = (!connect.client_id.is_empty()).then_some(connect.client_id);
+                (host, service, "MQTT CONNECT", client_id)
+            } else if let Some(hello) = parse_opcua_reverse_hello(payload) {
+                let service = Some((
+                    "OPC UA reverse connect".to_string(),
+                    CloudCategory::OpcUaReverse,
+                ));
+                let server_uri = (!hello.server_uri.is_empty()).then_some(hello.server_uri);
+                (None, service, "OPC UA ReverseHello", server_uri)
+            } else {
+                return;
+            };
+        let Some((service, category)) = service else {
+            return;
+        };
+        self.trace_note("cloud_endpoints");
+
+        let key = (
+            packet.src_ip.clone(),
+            packet.dst_ip.clone(),
+            packet.dst_port,
+        );
+        let endpoint = self
+            .cloud_endpoints
+            .entry(key)
+            .or_insert_with(|| CloudEndpoint {
+                remote_ip: packet.dst_ip.clone(),
+                remote_port: packet.dst_port,
+                hostname: None,
+                service: service.clone(),
+                category,
+                evidence: evidence.to_string(),
+                identity: None,
+                first_seen: packet.timestamp.to_rfc3339(),
+            });
+        // A named service beats a bare "MQTT broker" guess from an earlier packet
+        if endpoint.category == CloudCategory::MqttBroker && category != CloudCategory::MqttBroker {
+            endpoint.service = service;
+            endpoint.category = category;
+            endpoint.evidence = evidence.to_string();
+        }
+        if endpoint.hostname.is_none() {
+            endpoint.hostname = hostname;
+        }
+        if endpoint.identity.is_none() {
+            endpoint.identity = identity;
+        }
+    }
+
+    /// Record a ClientHello's JA3 or a ServerHello's JA3S against the host
+    /// that sent it.
+    fn process_tls_hello(&mut self, packet: &ParsedPacket, hello: &TlsHello) {
+        let key = (packet.src_ip.clone(), hello.kind, hello.ja3.clone());
+        let record = self
+            .tls_fingerprints
+            .entry(key)
+            .or_insert_with(|| TlsFingerprint {
+                role: hello.kind,
+                hash: hello.ja3.clone(),
+                fingerprint: hello.ja3_string.clone(),
+                version: tls_version_name(hello.version),
+                cipher_suites: hello.cipher_suites.clone(),
+                alpn: hello.alpn.clone(),
+                server_names: Vec::new(),
+                peers: Vec::new(),
+                handshakes: 0,
+                first_seen: packet.timestamp.to_rfc3339(),
+            });
+        record.handshakes += 1;
+        if let Some(ref sni) = hello.sni {
+            if !record.server_names.contains(sni) && record.server_names.len() < TLS_MAX_NAMES {
+                record.server_names.push(sni.clone());
+            }
+        }
+        if !record.peers.contains(&packet.dst_ip) && record.peers.len() < TLS_MAX_NAMES {
+            record.peers.push(packet.dst_ip.clone());
+        }
+    }
+
+    /// Record MQTT client IDs and the topics each side publishes and
+    /// subscribes to.
+    ///
+    /// A PUBLISH from the broker is a delivery to a subscriber, so a
+    /// broker's topic list shows what it relayed.
+    fn process_mqtt(&mut self, packet: &ParsedPacket) {
+        let packets = parse_mqtt_packets(&packet.payload);
+        if packets.is_empty() {
+            return;
+        }
+        self.trace_note("mqtt");
+
+        let broker_port = |port: u16| matches!(port, 1883 | 8883);
+        for (ip, peer, broker) in [
+            (&packet.src_ip, &packet.dst_ip, broker_port(packet.src_port)),
+            (&packet.dst_ip, &packet.src_ip, broker_port(packet.dst_port)),
+        ] {
+            let activity = self.mqtt_activity.entry(ip.clone()).or_default();
+            activity.broker |= broker;
+            if !activity.peers.contains(peer) && activity.peers.len() < MQTT_MAX_PEERS {
+                activity.peers.push(peer.clone());
+            }
+        }
+
+        for mqtt in packets {
+            let (filters, subscribe, retain, bytes) = match mqtt {
+                MqttPacket::Connect(connect) => {
+                    if let Some(broker) = self.mqtt_activity.get_mut(&packet.dst_ip) {
+                        broker.broker = true;
+                    }
+                    if connect.client_id.is_empty() {
+                        continue;
+                    }
+                    for ip in [&packet.src_ip, &packet.dst_ip] {
+                        let ids = &mut self.mqtt_activity.entry(ip.clone()).or_default().client_ids;
+                        if !ids.contains(&connect.client_id) && ids.len() < MQTT_MAX_PEERS {
+                            ids.push(connect.client_id.clone());
+                        }
+                    }
+                    continue;
+                }
+                MqttPacket::Publish(p) => (
+                    vec![(p.topic, p.qos)],
+                    false,
+                    p.retain,
+                    p.payload_len as u64,
+                ),
+                MqttPacket::Subscribe(s) => (s.filters, true, false, 0),
+            };
+
+            for (topic, qos) in filters {
+                let key = (packet.src_ip.clone(), topic, subscribe);
+                if !self.mqtt_topics.contains_key(&key)
+                    && self
+                        .mqtt_topics
+                        .keys()
+                        .filter(|(ip, _, _)| *ip == packet.src_ip)
+                        .count()
+                        >= MQTT_MAX_TOPICS
+                {
+                    continue;
+                }
+                let record = self
+                    .mqtt_topics
+                    .entry(key)
+                    .or_insert_with_key(|(_, topic, _)| MqttTopic {
+                        topic: topic.clone(),
+                        action: if subscribe { "subscribe" } else { "publish" }.to_string(),
+                        qos,
+                        retained: false,
+                        messages: 0,
+                        bytes: 0,
+                        peers: Vec::new(),
+                        first_seen: packet.timestamp.to_rfc3339(),
+                    });
+                record.qos = record.qos.max(qos);
+                record.retained |= retain;
+                record.messages += 1;
+                record.bytes += bytes;
+                if !record.peers.contains(&packet.dst_ip) && record.peers.len() < MQTT_MAX_PEERS {
+                    record.peers.push(packet.dst_ip.clone());
+                }
+            }
+        }
+    }
+
+    /// Identify a packet's protocol, checking the profile's port map first.
+    fn identify(&self, packet: &ParsedPacket) -> ProtocolIdentification {
+        [packet.dst_port, packet.src_port]
+            .iter()
+            .find_map(|port| self.port_map.get(port).copied())
+            .map(|protocol| ProtocolIdentification::new(protocol, IdentificationMethod::PortMap))
+            .unwrap_or_else(|| identify_protocol(packet))
+    }
+
+    /// Keep the strongest identification seen for one of an asset's protocols.
+    fn note_protocol_evidence(&mut self, ip: &str, identification: ProtocolIdentification) {
+        let best = self
+            .asset_protocol_evidence
+            .entry(ip.to_string())
+            .or_default()
+            .entry(identification.protocol)
+            .or_insert(identification);
+        if identification.confidence > best.confidence {
+            *best = identification;
+        }
+    }
+
+    /// Keep port-102 flows to a known MMS server labelled as MMS.
+    ///
+    /// S7comm and IEC 61850 MMS share ISO-TSAP port 102 and only payloads
+    /// carrying a COTP header can be told apart. Empty segments (handshake,
+    /// ACKs) and continuation segments to an IP already seen serving MMS are
+    /// labelled MMS so the IED doesn't also appear to speak S7comm.
+    fn refine_iso_tsap(&mut self, packet: &ParsedPacket, protocol: IcsProtocol) -> IcsProtocol {
+        let server = if packet.dst_port == 102 {
+            &packet.dst_ip
+        } else {
+            &packet.src_ip
+        };
+        match protocol {
+            IcsProtocol::Iec61850Mms => {
+                self.mms_servers.insert(server.clone());
+                protocol
+            }
+            IcsProtocol::S7comm
+                if self.mms_servers.contains(server) && packet.payload.first() != Some(&0x03) =>
+            {
+                IcsProtocol::Iec61850Mms
+            }
+            _ => protocol,
+        }
+    }
+
+    /// Keep DCOM connections bound to an OPC interface labelled as OPC DA.
+    ///
+    /// Only the Bind / Alter Context on a dynamic port names the OPC
+    /// interface; the requests and responses that follow carry just a
+    /// context ID, so the bound endpoint is remembered. Port 135 is shared
+    /// by every DCOM service and is never remembered.
+    fn refine_opc_da(&mut self, packet: &ParsedPacket, protocol: IcsProtocol) -> IcsProtocol {
+        match protocol {
+            IcsProtocol::OpcDa if packet.dst_port != 135 => {
+                if let Some(info) = parse_opc_da(&packet.payload).filter(|info| {
+                    matches!(
+                        info.packet_type,
+                        DceRpcPacketType::Bind | DceRpcPacketType::AlterContext
+                    )
+                }) {
+                    let callback = info.interfaces.iter().all(|i| i.is_callback());
+                    self.opc_da_endpoints
+                        .insert((packet.dst_ip.clone(), packet.dst_port), callback);
+                }
+                protocol
+            }
+            IcsProtocol::Unknown
+                if self
+                    .opc_da_endpoints
+                    .contains_key(&(packet.dst_ip.clone(), packet.dst_port))
+                    || self
+                        .opc_da_endpoints
+                        .contains_key(&(packet.src_ip.clone(), packet.src_port)) =>
+            {
+                IcsProtocol::OpcDa
+            }
+            _ => protocol,
+        }
+    }
+
+    /// Keep port-5007 flows to a known SLMP server labelled as SLMP.
+    ///
+    /// MELSEC PLCs serve SLMP/MC protocol on SuiteLink's default port, and
+    /// only segments carrying a binary 3E/4E header can be told apart.
+    fn refine_melsec_port(&mut self, packet: &ParsedPacket, protocol: IcsProtocol) -> IcsProtocol {
+        let server = if packet.dst_port == 5007 {
+            &packet.dst_ip
+        } else {
+            &packet.src_ip
+        };
+        match protocol {
+            IcsProtocol::Slmp => {
+                self.slmp_servers.insert(server.clone());
+                protocol
+            }
+            IcsProtocol::WonderwareSuitelink if self.slmp_servers.contains(server) => {
+                IcsProtocol::Slmp
+            }
+            _ => protocol,
+        }
+    }
+
+    /// Process IEC 61850 MMS deep parse result for a packet.
+    fn process_mms(&mut self, packet: &ParsedPacket, info: &gm_parsers::MmsInfo) {
+        let ip = &packet.src_ip;
+
+        let role_str = match info.role {
+            MmsRole::Client => "client",
+            MmsRole::Server => "server",
+            MmsRole::Unknown => "unknown",
+        };
+        // An IED that ever answered stays a server (it may also issue requests
+        // to peers, e.g. for interlocking)
+        if self.mms_roles.get(ip).map(String::as_str) != Some("server") {
+            self.mms_roles.insert(ip.clone(), role_str.to_string());
+        }
+
+        if let Some(service) = info.service {
+            self.mms_services
+                .entry(ip.clone())
+                .or_default()
+                .insert(service);
+            if info.pdu_type == MmsPduType::ConfirmedRequest && service.is_write() {
+                self.mms_writes.insert(ip.clone());
+            }
+        }
+
+        // Object names belong to the IED's data model: the request target,
+        // or the sender of a response/report
+        let ied_ip = if info.role == MmsRole::Client {
+            &packet.dst_ip
+        } else {
+            &packet.src_ip
+        };
+        for name in &info.object_names {
+            let (ld, ln) = gm_parsers::mms::split_object_name(name);
+            self.mms_logical_devices
+                .entry(ied_ip.clone())
+                .or_default()
+                .insert(ld.to_string());
+            if let Some(ln) = ln {
+                self.mms_logical_nodes
+                    .entry(ied_ip.clone())
+                    .or_default()
+                    .insert(ln.to_string());
+            }
+        }
+
+        if let Some(ref rpt_id) = info.report_id {
+            self.mms_report_ids
+                .entry(ip.clone())
+                .or_default()
+                .insert(rpt_id.clone());
+        }
+
+        if let Some(ref identity) = info.identity {
+            self.mms_identity.insert(ip.clone(), identity.clone());
+        }
+    }
+
+    /// Process an IEC 61850 GOOSE frame (synthetic `goose:<mac>` packet).
+    fn process_goose(&mut self, packet: &ParsedPacket) {
+        let (Some(src_mac), Some(dst_mac)) = (&packet.src_mac, &packet.dst_mac) else {
+            return;
+        };
+        let Some(info) = parse_goose(&packet.payload) else {
+            return;
+        };
+
+        self.all_protocols
+            .insert(format!("{:?}", IcsProtocol::Goose));
+        self.topo_builder.add_l2_connection(
+            src_mac,
+            dst_mac,
+            IcsProtocol::Goose,
+            packet.length as u64,
+        );
+        self.topo_builder
+            .mark_seen(src_mac, dst_mac, packet.timestamp);
+        self.topo_builder
+            .add_traffic(src_mac, dst_mac, packet.timestamp, packet.length as u64);
+
+        self.goose_streams
+            .entry((src_mac.clone(), info.gocb_ref.clone()))
+            .and_modify(|stream| stream.observe(&info))
+            .or_insert_with(|| GooseStream::new(&info, src_mac, dst_mac));
+    }
+
+    /// Learn the sender's IP → MAC binding from an ARP packet (synthetic
+    /// `arp:<mac>` packet). The first MAC to claim an address replaces any
+    /// MAC credited to it from IP traffic, which for a routed packet is the
+    /// router's; later claimants are kept on the binding for analysis.
+    fn process_arp(&mut self, packet: &ParsedPacket) {
+        let Some(info) = parse_arp(&packet.payload) else {
+            return;
+        };
+        if info.is_probe() {
+            return;
+        }
+        let timestamp = packet.timestamp.to_rfc3339();
+        if let Some(binding) = self.arp_bindings.get_mut(&info.sender_ip) {
+            binding.observe(&info, &timestamp);
+            return;
+        }
+        self.asset_macs
+            .insert(info.sender_ip.clone(), info.sender_mac.clone());
+        self.arp_bindings.insert(
+            info.sender_ip.clone(),
+            ArpBinding::new(&info, packet.vlan_id, &timestamp),
+        );
+    }
+
+    /// Credit an ICMP message to the host whose discovery it is part of.
+    fn process_icmp(&mut self, packet: &ParsedPacket, info: &IcmpInfo) {
+        let Some(host) = IcmpActivity::subject(info, &packet.src_ip, &packet.dst_ip) else {
+            return;
+        };
+        let peer = if host == packet.src_ip {
+            &packet.dst_ip
+        } else {
+            &packet.src_ip
+        };
+        let timestamp = packet.timestamp.to_rfc3339();
+        self.icmp_activity
+            .entry(host.to_string())
+            .or_insert_with(|| IcmpActivity::new(host, &timestamp))
+            .observe(info, peer, &timestamp);
+    }
+
+    /// Record the VLAN of a Layer-2-only frame against the sender's MAC, the
+    /// key of the MAC-only assets built for GOOSE, SV and PROFINET devices.
+    fn note_l2_vlan(&mut self, packet: &ParsedPacket) {
+        if let (Some(vlan), Some(mac)) = (packet.vlan_id, &packet.src_mac) {
+            self.asset_vlans
+                .entry(mac.clone())
+                .or_default()
+                .insert(vlan);
+        }
+    }
+
+    /// Process an IEC 61850-9-2 Sampled Values frame (synthetic `sv:<mac>` packet).
+    fn process_sv(&mut self, packet: &ParsedPacket) {
+        let (Some(src_mac), Some(dst_mac)) = (&packet.src_mac, &packet.dst_mac) else {
+            return;
+        };
+        let Some(info) = parse_sv(&packet.payload) else {
+            return;
+        };
+
+        self.all_protocols
+            .insert(format!("{:?}", IcsProtocol::SampledValues));
+        self.topo_builder.add_l2_connection(
+            src_mac,
+            dst_mac,
+            IcsProtocol::SampledValues,
+            packet.length as u64,
+        );
+        self.topo_builder
+            .mark_seen(src_mac, dst_mac, packet.timestamp);
+        self.topo_builder
+            .add_traffic(src_mac, dst_mac, packet.timestamp, packet.length as u64);
+
+        let timestamp = packet.timestamp.to_rfc3339();
+        let activity = self
+            .sv_publishers
+            .entry(src_mac.clone())
+            .or_insert_with(|| L2Activity {
+                first_seen: timestamp.clone(),
+                last_seen: String::new(),
+                frames: 0,
+            });
+        activity.last_seen = timestamp;
+        activity.frames += 1;
+
+        for asdu in &info.asdus {
+            self.sv_streams
+                .entry((src_mac.clone(), asdu.sv_id.clone()))
+                .and_modify(|stream| stream.observe(&info, asdu))
+                .or_insert_with(|| SvStream::new(&info, asdu, src_mac, dst_mac));
+        }
+    }
+
+    /// Process a PROFINET frame received over Ethernet.
+    ///
+    /// Cyclic RT class 1/2 frames are tracked per communication relation.
+    /// Of the DCP traffic, only messages in which a device describes itself
+    /// (Hello, and responses to Identify/Get) are used. The device is keyed
+    /// by the IP it advertises so it merges with its IP traffic, or by its
+    /// MAC while it has no IP configured. Identify requests from controllers
+    /// and engineering tools are ignored.
+    fn process_profinet_frame(&mut self, packet: &ParsedPacket) {
+        let Some(ref mac) = packet.src_mac else {
+            return;
+        };
+        if let Some(frame) = parse_profinet_rt(&packet.payload) {
+            self.process_profinet_rt(packet, &frame);
+            return;
+        }
+        let Some(info) = parse_profinet_dcp_frame(&packet.payload) else {
+            return;
+        };
+        let self_description = matches!(info.service_id, DcpServiceId::Hello)
+            || matches!(info.service_type, DcpServiceType::ResponseSuccess);
+        if !self_description {
+            return;
+        }
+
+        let key = info
+            .device_info
+            .ip_address
+            .filter(|ip| *ip != [0, 0, 0, 0])
+            .map(|ip| std::net::Ipv4Addr::from(ip).to_string())
+            .unwrap_or_else(|| mac.clone());
+        let timestamp = packet.timestamp.to_rfc3339();
+
+        self.asset_protocols
+            .entry(key.clone())
+            .or_default()
+            .insert(IcsProtocol::Profinet);
+        self.asset_macs
+            .entry(key.clone())
+            .or_insert_with(|| mac.clone());
+        *self.asset_packet_counts.entry(key.clone()).or_insert(0) += 1;
+        self.asset_first_seen
+            .entry(key.clone())
+            .or_insert_with(|| timestamp.clone());
+        self.asset_last_seen.insert(key.clone(), timestamp);
+
+        self.process_profinet_dcp(&key, &info);
+    }
+
+    /// Process a cyclic PROFINET RT frame (IO data between controller and device).
+    fn process_profinet_rt(&mut self, packet: &ParsedPacket, frame: &ProfinetRtFrame) {
+        let (Some(src_mac), Some(dst_mac)) = (&packet.src_mac, &packet.dst_mac) else {
+            return;
+        };
+
+        self.all_protocols
+            .insert(format!("{:?}", IcsProtocol::Profinet));
+        self.topo_builder.add_l2_connection(
+            src_mac,
+            dst_mac,
+            IcsProtocol::Profinet,
+            packet.length as u64,
+        );
+        self.topo_builder
+            .mark_seen(src_mac, dst_mac, packet.timestamp);
+        self.topo_builder
+            .add_traffic(src_mac, dst_mac, packet.timestamp, packet.length as u64);
+
+        let timestamp = packet.timestamp.to_rfc3339();
+        let activity = self
+            .profinet_rt_stations
+            .entry(src_mac.clone())
+            .or_insert_with(|| L2Activity {
+                first_seen: timestamp.clone(),
+                last_seen: String::new(),
+                frames: 0,
+            });
+        activity.last_seen = timestamp;
+        activity.frames += 1;
+
+        let ts = packet.timestamp.timestamp() as f64
+            + packet.timestamp.timestamp_subsec_nanos() as f64 / 1_000_000_000.0;
+        self.profinet_rt_streams
+            .entry((src_mac.clone(), dst_mac.clone(), frame.frame_id))
+            .and_modify(|stream| stream.observe(frame, ts))
+            .or_insert_with(|| ProfinetRtStream::new(frame, src_mac, dst_mac, ts));
+    }
+
+    /// Process PROFINET DCP deep parse result for a device.
+    fn process_profinet_dcp(&mut self, ip: &str, info: &ProfinetDcpInfo) {
+        let role_str = match info.role {
+            ProfinetRole::IoDevice => "io_device",
             ProfinetRole::IoController => "io_controller",
             ProfinetRole::IoSupervisor => "io_supervisor",
             ProfinetRole::Unknown => "unknown",
         };
         // Only update if we have a meaningful role (responses carry the role block)
         if role_str != "unknown" {
-            self.profinet_roles.insert(ip.clone(), role_str.to_string());
+            self.profinet_roles
+                .insert(ip.to_string(), role_str.to_string());
         } else {
             // Record the device even without a role so we know it speaks PROFINET
             self.profinet_roles
-                .entry(ip.clone())
+                .entry(ip.to_string())
                 .or_insert_with(|| "unknown".to_string());
         }
 
         if let Some(ref name) = info.device_info.name_of_station {
             if !name.is_empty() {
-                self.profinet_device_names.insert(ip.clone(), name.clone());
+                self.profinet_device_names
+                    .insert(ip.to_string(), name.clone());
+            }
+        }
+        if let Some(ref station_type) = info.device_info.vendor_name {
+            if !station_type.is_empty() {
+                self.profinet_station_types
+                    .insert(ip.to_string(), station_type.clone());
             }
         }
+        if let (Some(vendor_id), Some(device_id)) =
+            (info.device_info.vendor_id, info.device_info.device_id)
+        {
+            self.profinet_device_ids
+                .insert(ip.to_string(), (vendor_id, device_id));
+        }
     }
 
     /// Build deep parse info from accumulated data.
@@ -687,13 +3106,23 @@ impl PacketProcessor {
         let mut deep_parse_info: HashMap<String, DeepParseInfo> = HashMap::new();
 
         // Aggregate Modbus data
+        // Slaves that never answered still get a register map
         let all_modbus_ips: HashSet<String> = self
             .modbus_fc_counts
             .keys()
             .chain(self.modbus_roles.keys())
+            .chain(self.modbus_register_access.keys().map(|(ip, _)| ip))
             .cloned()
             .collect();
 
+        let mut transaction_stats: HashMap<String, Vec<ModbusTransactionStats>> = HashMap::new();
+        for stats in self.modbus_transactions.stats() {
+            transaction_stats
+                .entry(stats.slave_ip.clone())
+                .or_default()
+                .push(stats);
+        }
+
         for ip in &all_modbus_ips {
             let role = self
                 .modbus_roles
@@ -724,14 +3153,15 @@ impl PacketProcessor {
                 .map(|fc_map| {
                     let mut fcs: Vec<FunctionCodeStat> = fc_map
                         .iter()
-                        .map(|(&code, &count)| FunctionCodeStat {
-                            code,
-                            name: modbus_function_code_name(code).to_string(),
-                            count,
-                            is_write: matches!(code, 5 | 6 | 15 | 16 | 22 | 23),
+                        .map(|(&code, &count)| {
+                            FunctionCodeStat::from_registry(
+                                FunctionCodeProtocol::Modbus,
+                                code,
+                                count,
+                            )
                         })
                         .collect();
-                    fcs.sort_by(|a, b| b.count.cmp(&a.count));
+                    fcs.sort_by_key(|fc| std::cmp::Reverse(fc.count));
                     fcs
                 })
                 .unwrap_or_default();
@@ -751,7 +3181,7 @@ impl PacketProcessor {
                             },
                         )
                         .collect();
-                    ranges.sort_by(|a, b| a.start.cmp(&b.start));
+                    ranges.sort_by_key(|r| r.start);
                     ranges
                 })
                 .unwrap_or_default();
@@ -817,6 +3247,37 @@ impl PacketProcessor {
                 }
             }
 
+            let mut register_maps: Vec<ModbusRegisterMap> = self
+                .modbus_register_access
+                .iter()
+                .filter(|((slave_ip, _), _)| slave_ip == ip)
+                .map(|((_, unit_id), log)| {
+                    let accesses: Vec<RegisterAccess> = log
+                        .ranges
+                        .iter()
+                        .map(
+                            |(&(start, count, register_type, write), &requests)| RegisterAccess {
+                                range: RegisterRange {
+                                    start,
+                                    count,
+                                    register_type,
+                                },
+                                write,
+                                requests,
+                            },
+                        )
+                        .collect();
+                    let mut masters: Vec<String> = log.masters.iter().cloned().collect();
+                    masters.sort();
+                    ModbusRegisterMap {
+                        unit_id: *unit_id,
+                        masters,
+                        regions: build_register_map(&accesses),
+                    }
+                })
+                .collect();
+            register_maps.sort_by_key(|m| m.unit_id);
+
             let modbus_detail = ModbusDetail {
                 role,
                 unit_ids,
@@ -825,6 +3286,9 @@ impl PacketProcessor {
                 device_id,
                 relationships,
                 polling_intervals,
+                rtu_over_tcp: self.modbus_rtu_over_tcp.contains(ip),
+                register_maps,
+                transaction_stats: transaction_stats.remove(ip).unwrap_or_default(),
             };
 
             deep_parse_info.entry(ip.clone()).or_default().modbus = Some(modbus_detail);
@@ -868,19 +3332,18 @@ impl PacketProcessor {
                 .map(|fc_map| {
                     let mut fcs: Vec<FunctionCodeStat> = fc_map
                         .iter()
-                        .map(|(&code, &count)| FunctionCodeStat {
-                            code,
-                            name: dnp3_function_code_name(code).to_string(),
-                            count,
-                            is_write: matches!(code, 2..=6),
+                        .map(|(&code, &count)| {
+                            FunctionCodeStat::from_registry(FunctionCodeProtocol::Dnp3, code, count)
                         })
                         .collect();
-                    fcs.sort_by(|a, b| b.count.cmp(&a.count));
+                    fcs.sort_by_key(|fc| std::cmp::Reverse(fc.count));
                     fcs
                 })
                 .unwrap_or_default();
 
             let has_unsolicited = self.dnp3_unsolicited.get(ip).copied().unwrap_or(false);
+            let uses_secure_auth = self.dnp3_secure_auth.contains(ip);
+            let crc_errors = self.dnp3_crc_errors.get(ip).copied().unwrap_or(0);
 
             let relationships: Vec<Dnp3Relationship> = self
                 .dnp3_relationships
@@ -897,12 +3360,22 @@ impl PacketProcessor {
                 })
                 .unwrap_or_default();
 
+            let mut master_bindings: Vec<Dnp3AddressBinding> = self
+                .dnp3_master_bindings
+                .get(ip)
+                .map(|bindings| bindings.values().cloned().collect())
+                .unwrap_or_default();
+            master_bindings.sort_by_key(|b| (b.master_address, b.outstation_address));
+
             let dnp3_detail = Dnp3Detail {
                 role,
                 addresses,
                 function_codes,
                 has_unsolicited,
+                uses_secure_auth,
+                crc_errors,
                 relationships,
+                master_bindings,
             };
 
             deep_parse_info.entry(ip.clone()).or_default().dnp3 = Some(dnp3_detail);
@@ -910,6 +3383,18 @@ impl PacketProcessor {
 
         // Aggregate EtherNet/IP data
         for ip in self.enip_roles.keys() {
+            let identity = self.enip_identities.get(ip).map(|id| EnipIdentityInfo {
+                vendor_id: id.vendor_id,
+                vendor_name: cip_vendor_name(id.vendor_id).to_string(),
+                device_type: id.device_type,
+                device_type_name: cip_device_type_name(id.device_type).to_string(),
+                product_code: id.product_code,
+                product_name: id.product_name.clone(),
+                revision: format!("{}.{:03}", id.major_revision, id.minor_revision),
+                serial_number: format!("{:08X}", id.serial_number),
+                status: id.status,
+                state: id.state,
+            });
             let role = self
                 .enip_roles
                 .get(ip)
@@ -920,6 +3405,7 @@ impl PacketProcessor {
                 cip_writes_to_assembly: self.enip_cip_writes_to_assembly.contains(ip),
                 cip_file_access: self.enip_cip_file_access.contains(ip),
                 list_identity_requests: self.enip_list_identity.contains(ip),
+                identity,
             };
             deep_parse_info.entry(ip.clone()).or_default().enip = Some(enip_detail);
         }
@@ -957,6 +3443,7 @@ impl PacketProcessor {
                 write_to_notification_class: self.bacnet_write_to_notification_class.contains(ip),
                 reinitialize_device: self.bacnet_reinitialize.contains(ip),
                 device_communication_control: self.bacnet_device_comm_ctrl.contains(ip),
+                device: self.bacnet_devices.get(ip).cloned(),
             };
             deep_parse_info.entry(ip.clone()).or_default().bacnet = Some(bacnet_detail);
         }
@@ -968,9 +3455,20 @@ impl PacketProcessor {
                 .get(ip)
                 .cloned()
                 .unwrap_or_else(|| "unknown".to_string());
+            let device_id = self.profinet_device_ids.get(ip).copied();
             let profinet_detail = ProfinetDcpDetail {
                 role,
                 device_name: self.profinet_device_names.get(ip).cloned(),
+                station_type: self.profinet_station_types.get(ip).cloned(),
+                vendor_id: device_id.map(|(vendor_id, _)| vendor_id),
+                device_id: device_id.map(|(_, device_id)| device_id),
+                vendor: device_id
+                    .map(|(vendor_id, _)| profinet_vendor_name(vendor_id))
+                    .filter(|name| *name != "Unknown Vendor")
+                    .map(str::to_string),
+                product: device_id
+                    .and_then(|(vendor_id, device_id)| profinet_device_name(vendor_id, device_id))
+                    .map(str::to_string),
             };
             deep_parse_info.entry(ip.clone()).or_default().profinet_dcp = Some(profinet_detail);
         }
@@ -991,46 +3489,882 @@ impl PacketProcessor {
             deep_parse_info.entry(ip.clone()).or_default().iec104 = Some(iec104_detail);
         }
 
-        // Aggregate LLDP data: match by MAC address
-        // asset_macs maps IP → MAC; we need the reverse to look up by MAC
-        for (ip, mac) in &self.asset_macs {
-            if let Some(lldp_info) = self.lldp_by_mac.get(mac) {
-                let mgmt_addrs: Vec<String> = lldp_info
-                    .management_addresses
-                    .iter()
-                    .map(|a| format!("{} ({})", a.address, a.addr_type))
-                    .collect();
-                let lldp_detail = LldpDetail {
-                    system_name: lldp_info.system_name.clone(),
-                    system_description: lldp_info.system_description.clone(),
-                    chassis_id: lldp_info.chassis_id.clone(),
-                    port_id: lldp_info.port_id.clone(),
-                    capability_summary: lldp_info.capability_summary.clone(),
-                    management_addresses: mgmt_addrs,
-                    vlan_ids: lldp_info.vlan_ids.clone(),
-                    vendor: lldp_info.vendor.clone(),
-                    model: lldp_info.model.clone(),
-                    firmware: lldp_info.firmware.clone(),
-                };
-                deep_parse_info.entry(ip.clone()).or_default().lldp = Some(lldp_detail);
+        // Aggregate OMRON FINS data
+        for (ip, role) in &self.fins_roles {
+            let mut codes: Vec<u16> = self
+                .fins_commands
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            codes.sort_unstable();
+            let mut node_addresses: Vec<u8> = self
+                .fins_nodes
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            node_addresses.sort_unstable();
+            let controller = self.fins_controllers.get(ip);
+
+            let fins_detail = FinsDetail {
+                role: role.clone(),
+                commands_seen: codes
+                    .iter()
+                    .map(|&c| format!("{:04X} {}", c, gm_parsers::fins_command_code_name(c)))
+                    .collect(),
+                node_addresses,
+                has_writes: self.fins_writes.contains(ip),
+                has_control_commands: self.fins_control_commands.contains(ip),
+                controller_model: controller.map(|c| c.model.clone()),
+                controller_version: controller
+                    .map(|c| c.version.clone())
+                    .filter(|v| !v.is_empty()),
+            };
+            deep_parse_info.entry(ip.clone()).or_default().fins = Some(fins_detail);
+        }
+
+        // Aggregate MELSEC SLMP data
+        for (ip, role) in &self.slmp_roles {
+            let mut commands: Vec<u16> = self
+                .slmp_commands
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            commands.sort_unstable();
+            let mut device_areas: Vec<String> = self
+                .slmp_devices
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect();
+            device_areas.sort();
+            let cpu = self.slmp_cpu_models.get(ip);
+
+            let slmp_detail = SlmpDetail {
+                role: role.clone(),
+                commands_seen: commands
+                    .iter()
+                    .map(|&c| format!("{:04X} {}", c, gm_parsers::slmp_command_name(c)))
+                    .collect(),
+                device_areas,
+                has_writes: self.slmp_writes.contains(ip),
+                has_control_commands: self.slmp_control_commands.contains(ip),
+                cpu_model: cpu.map(|c| c.model.clone()),
+                cpu_model_code: cpu.map(|c| c.model_code),
+            };
+            deep_parse_info.entry(ip.clone()).or_default().slmp = Some(slmp_detail);
+        }
+
+        // Aggregate GE SRTP data
+        for (ip, role) in &self.ge_srtp_roles {
+            let mut services: Vec<u8> = self
+                .ge_srtp_services
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            services.sort_unstable();
+            let mut memory_segments: Vec<String> = self
+                .ge_srtp_segments
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect();
+            memory_segments.sort();
+            let controller = self.ge_srtp_controllers.get(ip);
+
+            let ge_srtp_detail = GeSrtpDetail {
+                role: role.clone(),
+                services_seen: services
+                    .iter()
+                    .map(|&c| format!("{:02X} {}", c, gm_parsers::ge_srtp_service_name(c)))
+                    .collect(),
+                memory_segments,
+                has_writes: self.ge_srtp_writes.contains(ip),
+                has_control_commands: self.ge_srtp_control_commands.contains(ip),
+                plc_state: self.ge_srtp_states.get(ip).map(|s| s.name().to_string()),
+                controller_model: controller.and_then(|c| c.catalog_number.clone()),
+                controller_id: controller.and_then(|c| c.controller_id.clone()),
+                firmware: controller.and_then(|c| c.firmware.clone()),
+            };
+            deep_parse_info.entry(ip.clone()).or_default().ge_srtp = Some(ge_srtp_detail);
+        }
+
+        // Aggregate CODESYS data
+        for (ip, role) in &self.codesys_roles {
+            let mut versions: Vec<CodesysVersion> = self
+                .codesys_versions
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            versions.sort_by_key(|v| v.name());
+            let mut services: Vec<(Option<u16>, u16)> = self
+                .codesys_services
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            services.sort_unstable();
+
+            let codesys_detail = CodesysDetail {
+                role: role.clone(),
+                versions: versions.iter().map(|v| v.name().to_string()).collect(),
+                services_seen: services
+                    .iter()
+                    .map(|&(group, service)| codesys_service_name(group, service))
+                    .collect(),
+                has_login: self.codesys_logins.contains(ip),
+                has_download: self.codesys_downloads.contains(ip),
+                has_online_change: self.codesys_online_changes.contains(ip),
+                has_control_commands: self.codesys_control_commands.contains(ip),
+            };
+            deep_parse_info.entry(ip.clone()).or_default().codesys = Some(codesys_detail);
+        }
+
+        // Aggregate Niagara Fox data
+        for (ip, role) in &self.fox_roles {
+            let hello = self.fox_hellos.get(ip);
+            let field = |f: fn(&FoxHello) -> &Option<String>| hello.and_then(|h| f(h).clone());
+            let fox_detail = FoxDetail {
+                role: role.clone(),
+                station_name: field(|h| &h.station_name),
+                host_name: field(|h| &h.host_name),
+                app_name: field(|h| &h.app_name),
+                niagara_version: field(|h| &h.app_version),
+                is_legacy: hello
+                    .and_then(FoxHello::major_version)
+                    .is_some_and(|v| v < 4),
+                os_name: field(|h| &h.os_name),
+                os_version: field(|h| &h.os_version),
+                vm_name: field(|h| &h.vm_name),
+                host_id: field(|h| &h.host_id),
+                brand_id: field(|h| &h.brand_id),
+                auth_mode: hello.and_then(FoxHello::auth_mode).map(str::to_string),
+                auth_agents: hello.map(|h| h.auth_agents.clone()).unwrap_or_default(),
+            };
+            deep_parse_info.entry(ip.clone()).or_default().fox = Some(fox_detail);
+        }
+
+        // Aggregate Red Lion Crimson data. Identification exchanges are a
+        // few hundred bytes, so a pair only counts as a configuration
+        // transfer once the bulk data in one direction passes the threshold.
+        let mut crimson_transfers: HashMap<&String, (bool, bool, HashSet<&String>)> =
+            HashMap::new();
+        for ((client, server), &(to_server, to_client)) in &self.crimson_bulk {
+            let download = to_server >= CRIMSON_TRANSFER_BYTES;
+            let upload = to_client >= CRIMSON_TRANSFER_BYTES;
+            if !download && !upload {
+                continue;
+            }
+            for (ip, peer) in [(client, server), (server, client)] {
+                let entry = crimson_transfers.entry(ip).or_default();
+                entry.2.insert(peer);
+                if ip == client {
+                    entry.0 |= download;
+                    entry.1 |= upload;
+                }
+            }
+        }
+        for (ip, role) in &self.crimson_roles {
+            let transfers = crimson_transfers.get(ip);
+            let mut transfer_peers: Vec<String> = transfers
+                .into_iter()
+                .flat_map(|t| t.2.iter().map(|p| p.to_string()))
+                .collect();
+            transfer_peers.sort();
+            let bulk_bytes_sent = self
+                .crimson_bulk
+                .iter()
+                .map(|((client, server), &(to_server, to_client))| {
+                    if client == ip {
+                        to_server
+                    } else if server == ip {
+                        to_client
+                    } else {
+                        0
+                    }
+                })
+                .sum();
+            let crimson_detail = CrimsonDetail {
+                role: role.clone(),
+                manufacturer: self.crimson_manufacturers.get(ip).cloned(),
+                model: self.crimson_models.get(ip).cloned(),
+                has_download: transfers.is_some_and(|t| t.0),
+                has_upload: transfers.is_some_and(|t| t.1),
+                transfer_peers,
+                bulk_bytes_sent,
+            };
+            deep_parse_info.entry(ip.clone()).or_default().crimson = Some(crimson_detail);
+        }
+
+        // Aggregate Yokogawa Vnet/IP data. A station has one address per bus;
+        // pair them up so both assets can be recognised as the same station.
+        let by_station: HashMap<(u8, u8, u8), &String> = self
+            .vnetip_stations
+            .iter()
+            .map(|(ip, s)| ((s.domain, s.station, s.bus), ip))
+            .collect();
+        for (ip, station) in &self.vnetip_stations {
+            let other_bus = if station.bus == 1 { 2 } else { 1 };
+            let mut peers: Vec<String> = self
+                .vnetip_peers
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect();
+            peers.sort();
+            let vnet_ip_detail = VnetIpDetail {
+                domain: station.domain,
+                station: station.station,
+                bus: station.bus,
+                station_number: station.ddss(),
+                other_bus_ip: by_station
+                    .get(&(station.domain, station.station, other_bus))
+                    .map(|ip| ip.to_string()),
+                peers,
+            };
+            deep_parse_info.entry(ip.clone()).or_default().vnet_ip = Some(vnet_ip_detail);
+        }
+
+        // Aggregate Triconex TriStation data
+        for (ip, role) in &self.tristation_roles {
+            let commands = self.tristation_commands.get(ip);
+            let any = |f: fn(TriStationCommand) -> bool| {
+                commands.is_some_and(|c| c.iter().any(|&cmd| f(cmd)))
+            };
+            let mut command_names: Vec<String> =
+                commands.into_iter().flatten().map(|c| c.name()).collect();
+            command_names.sort();
+            let mut peers: Vec<String> = self
+                .tristation_peers
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect();
+            peers.sort();
+            let tristation_detail = TriStationDetail {
+                role: role.clone(),
+                commands: command_names,
+                has_download: any(TriStationCommand::is_download),
+                has_upload: any(TriStationCommand::is_upload),
+                has_run_state_change: any(TriStationCommand::is_run_state_change),
+                peers,
+            };
+            deep_parse_info.entry(ip.clone()).or_default().tristation = Some(tristation_detail);
+        }
+
+        // Aggregate Beckhoff ADS data
+        for (ip, role) in &self.ads_roles {
+            let mut ams_net_ids: Vec<String> = self
+                .ads_net_ids
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect();
+            ams_net_ids.sort();
+            let mut ams_ports: Vec<u16> = self
+                .ads_ports
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            ams_ports.sort();
+            let commands = self.ads_commands.get(ip);
+            let mut command_names: Vec<String> =
+                commands.into_iter().flatten().map(|c| c.name()).collect();
+            command_names.sort();
+            let mut state_changes: Vec<String> = self
+                .ads_state_changes
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .map(|s| s.name())
+                .collect();
+            state_changes.sort();
+
+            let mut relationships: Vec<AdsRelationship> = self
+                .ads_links
+                .iter()
+                .filter_map(|((client, server), &count)| {
+                    let (remote, remote_role) = if client == ip {
+                        (server, "server")
+                    } else if server == ip {
+                        (client, "client")
+                    } else {
+                        return None;
+                    };
+                    let mut remote_net_ids: Vec<String> = self
+                        .ads_net_ids
+                        .get(remote)
+                        .into_iter()
+                        .flatten()
+                        .cloned()
+                        .collect();
+                    remote_net_ids.sort();
+                    Some(AdsRelationship {
+                        remote_ip: remote.clone(),
+                        remote_role: remote_role.to_string(),
+                        remote_net_ids,
+                        request_count: count,
+                    })
+                })
+                .collect();
+            relationships.sort_by_key(|r| std::cmp::Reverse(r.request_count));
+
+            let device = self.ads_device_info.get(ip);
+            let ads_detail = AdsDetail {
+                role: role.clone(),
+                ams_net_ids,
+                ams_ports,
+                commands: command_names,
+                device_name: device.map(|d| d.name.clone()).filter(|n| !n.is_empty()),
+                twincat_version: device.map(AdsDeviceInfo::version),
+                ads_state: self.ads_states.get(ip).map(|s| s.name()),
+                has_write: commands.is_some_and(|c| c.iter().any(|cmd| cmd.is_write())),
+                state_changes,
+                relationships,
+            };
+            deep_parse_info.entry(ip.clone()).or_default().ads = Some(ads_detail);
+        }
+
+        // Aggregate classic OPC data
+        for (ip, role) in &self.opc_da_roles {
+            let interfaces = self.opc_da_interfaces.get(ip);
+            let mut interface_names: Vec<String> = interfaces
+                .into_iter()
+                .flatten()
+                .map(|i| i.name().to_string())
+                .collect();
+            interface_names.sort();
+            let mut peers: Vec<String> = Vec::new();
+            let mut request_count = 0;
+            for ((client, server), &count) in &self.opc_da_links {
+                if client == ip {
+                    peers.push(server.clone());
+                } else if server == ip {
+                    peers.push(client.clone());
+                } else {
+                    continue;
+                }
+                request_count += count;
+            }
+            peers.sort();
+            peers.dedup();
+            let opc_da_detail = OpcDaDetail {
+                role: role.clone(),
+                interfaces: interface_names,
+                has_alarms_events: interfaces
+                    .is_some_and(|i| i.contains(&OpcInterface::EventServer)),
+                has_callbacks: interfaces.is_some_and(|i| i.contains(&OpcInterface::DataCallback)),
+                peers,
+                request_count,
+            };
+            deep_parse_info.entry(ip.clone()).or_default().opc_da = Some(opc_da_detail);
+        }
+
+        // Aggregate IEC 61850 MMS data
+        for (ip, role) in &self.mms_roles {
+            let sorted = |set: Option<&HashSet<String>>| -> Vec<String> {
+                let mut v: Vec<String> = set.into_iter().flatten().cloned().collect();
+                v.sort();
+                v
+            };
+            let mut services_seen: Vec<String> = self
+                .mms_services
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .map(|s| s.name().to_string())
+                .collect();
+            services_seen.sort();
+            services_seen.dedup();
+            let identity = self.mms_identity.get(ip);
+
+            let mms_detail = MmsDetail {
+                role: role.clone(),
+                services_seen,
+                logical_devices: sorted(self.mms_logical_devices.get(ip)),
+                logical_nodes: sorted(self.mms_logical_nodes.get(ip)),
+                report_ids: sorted(self.mms_report_ids.get(ip)),
+                has_writes: self.mms_writes.contains(ip),
+                vendor: identity.and_then(|i| i.vendor.clone()),
+                model: identity.and_then(|i| i.model.clone()),
+                revision: identity.and_then(|i| i.revision.clone()),
+            };
+            deep_parse_info.entry(ip.clone()).or_default().mms = Some(mms_detail);
+        }
+
+        // Aggregate LLDP data: match by MAC address
+        // asset_macs maps IP → MAC; we need the reverse to look up by MAC
+        for (ip, mac) in &self.asset_macs {
+            if let Some(lldp_info) = self.lldp_by_mac.get(mac) {
+                let mgmt_addrs: Vec<String> = lldp_info
+                    .management_addresses
+                    .iter()
+                    .map(|a| format!("{} ({})", a.address, a.addr_type))
+                    .collect();
+                let lldp_detail = LldpDetail {
+                    system_name: lldp_info.system_name.clone(),
+                    system_description: lldp_info.system_description.clone(),
+                    chassis_id: lldp_info.chassis_id.clone(),
+                    port_id: lldp_info.port_id.clone(),
+                    capability_summary: lldp_info.capability_summary.clone(),
+                    management_addresses: mgmt_addrs,
+                    vlan_ids: lldp_info.vlan_ids.clone(),
+                    vendor: lldp_info.vendor.clone(),
+                    model: lldp_info.model.clone(),
+                    firmware: lldp_info.firmware.clone(),
+                };
+                deep_parse_info.entry(ip.clone()).or_default().lldp = Some(lldp_detail);
+            }
+        }
+
+        // Aggregate GOOSE publications: match by publisher MAC address
+        if !self.goose_streams.is_empty() {
+            let streams = self.build_goose_streams();
+            for (ip, mac) in &self.asset_macs {
+                let publications: Vec<GooseStream> = streams
+                    .iter()
+                    .filter(|s| s.publisher_mac.eq_ignore_ascii_case(mac))
+                    .cloned()
+                    .collect();
+                if !publications.is_empty() {
+                    deep_parse_info.entry(ip.clone()).or_default().goose =
+                        Some(GooseDetail { publications });
+                }
+            }
+        }
+
+        // Aggregate SV publications: match by publisher MAC address; publishers
+        // with no IP asset are keyed by their MAC, matching their asset ID
+        if !self.sv_streams.is_empty() {
+            let streams = self.build_sv_streams();
+            let keys = self
+                .asset_macs
+                .iter()
+                .map(|(ip, mac)| (ip.clone(), mac.clone()))
+                .chain(
+                    self.mac_only_sv_publishers()
+                        .map(|mac| (mac.clone(), mac.clone())),
+                );
+            for (key, mac) in keys {
+                let publications: Vec<SvStream> = streams
+                    .iter()
+                    .filter(|s| s.publisher_mac.eq_ignore_ascii_case(&mac))
+                    .cloned()
+                    .collect();
+                if !publications.is_empty() {
+                    deep_parse_info.entry(key).or_default().sv = Some(SvDetail { publications });
+                }
+            }
+        }
+
+        // Aggregate PROFINET cyclic RT relations by MAC; stations with no IP
+        // or DCP asset are keyed by their MAC
+        if !self.profinet_rt_streams.is_empty() {
+            let streams = self.build_profinet_rt_streams();
+            let roles = self.profinet_rt_roles();
+            let keys = self
+                .asset_macs
+                .iter()
+                .map(|(ip, mac)| (ip.clone(), mac.clone()))
+                .chain(
+                    self.mac_only_profinet_rt_stations()
+                        .map(|mac| (mac.clone(), mac.clone())),
+                );
+            for (key, mac) in keys {
+                let relations: Vec<ProfinetRtStream> = streams
+                    .iter()
+                    .filter(|s| {
+                        s.provider_mac.eq_ignore_ascii_case(&mac)
+                            || s.consumer_mac.eq_ignore_ascii_case(&mac)
+                    })
+                    .cloned()
+                    .collect();
+                if !relations.is_empty() {
+                    let role = roles
+                        .get(&mac.to_lowercase())
+                        .cloned()
+                        .unwrap_or_else(|| "unknown".to_string());
+                    deep_parse_info.entry(key).or_default().profinet_rt =
+                        Some(ProfinetRtDetail { role, relations });
+                }
+            }
+        }
+
+        // Aggregate SNMP device identity (keyed directly by IP)
+        for (ip, snmp_info) in &self.snmp_device_info {
+            let snmp_detail = SnmpDetail {
+                sys_descr: snmp_info.sys_descr.clone(),
+                sys_name: snmp_info.sys_name.clone(),
+                sys_location: snmp_info.sys_location.clone(),
+                sys_object_id: snmp_info.sys_object_id.clone(),
+                sys_uptime_cs: snmp_info.sys_uptime_cs,
+                sys_contact: snmp_info.sys_contact.clone(),
+                vendor: snmp_info.vendor.clone(),
+                model: snmp_info.model(),
+            };
+            deep_parse_info.entry(ip.clone()).or_default().snmp = Some(snmp_detail);
+        }
+
+        // Per-device Modbus/DNP3 session health
+        for health in SessionTracker::device_health(&self.session_tracker.sessions()) {
+            let ip = health.ip.clone();
+            deep_parse_info.entry(ip).or_default().sessions = Some(health);
+        }
+
+        // Aggregate outbound cloud endpoints per local device
+        let mut cloud_by_ip: HashMap<&str, Vec<CloudEndpoint>> = HashMap::new();
+        for ((local_ip, _, _), endpoint) in &self.cloud_endpoints {
+            cloud_by_ip
+                .entry(local_ip.as_str())
+                .or_default()
+                .push(endpoint.clone());
+        }
+        for (ip, mut endpoints) in cloud_by_ip {
+            endpoints.sort_by(|a, b| {
+                a.first_seen
+                    .cmp(&b.first_seen)
+                    .then_with(|| a.remote_ip.cmp(&b.remote_ip))
+            });
+            deep_parse_info.entry(ip.to_string()).or_default().cloud =
+                Some(CloudDetail { endpoints });
+        }
+
+        // Aggregate TLS fingerprints per sending host
+        let mut tls_by_ip: HashMap<&str, Vec<TlsFingerprint>> = HashMap::new();
+        for ((ip, _, _), fingerprint) in &self.tls_fingerprints {
+            tls_by_ip
+                .entry(ip.as_str())
+                .or_default()
+                .push(fingerprint.clone());
+        }
+        for (ip, mut fingerprints) in tls_by_ip {
+            fingerprints.sort_by(|a, b| {
+                a.first_seen
+                    .cmp(&b.first_seen)
+                    .then_with(|| a.hash.cmp(&b.hash))
+            });
+            deep_parse_info.entry(ip.to_string()).or_default().tls =
+                Some(TlsDetail { fingerprints });
+        }
+
+        // Aggregate serial device server lines
+        for (ip, activity) in &self.serial_gateways {
+            let lines = activity
+                .lines
+                .iter()
+                .map(|(&port, line)| SerialLineDetail {
+                    port,
+                    channel: line.channel,
+                    framing: if line.rfc2217 { "rfc2217" } else { "raw" }.to_string(),
+                    baud_rate: line.settings.baud_rate,
+                    line_format: line.settings.line_format(),
+                    protocols: line.protocols.iter().map(|p| p.to_string()).collect(),
+                    hosts: line.hosts.iter().cloned().collect(),
+                    devices: line
+                        .devices
+                        .iter()
+                        .map(|(&(protocol, address), &frame_count)| SerialDevice {
+                            protocol: protocol.to_string(),
+                            address,
+                            frame_count,
+                        })
+                        .collect(),
+                })
+                .collect();
+            deep_parse_info
+                .entry(ip.clone())
+                .or_default()
+                .serial_gateway = Some(SerialGatewayDetail {
+                gateway: activity.gateway.display_name().to_string(),
+                vendor: activity.gateway.vendor().to_string(),
+                lines,
+            });
+        }
+
+        // Aggregate MQTT sessions and topics
+        let mut mqtt_topics_by_ip: HashMap<&str, Vec<MqttTopic>> = HashMap::new();
+        for ((ip, _, _), topic) in &self.mqtt_topics {
+            mqtt_topics_by_ip
+                .entry(ip.as_str())
+                .or_default()
+                .push(topic.clone());
+        }
+        for (ip, activity) in &self.mqtt_activity {
+            let mut topics = mqtt_topics_by_ip.remove(ip.as_str()).unwrap_or_default();
+            topics.sort_by(|a, b| {
+                a.first_seen
+                    .cmp(&b.first_seen)
+                    .then_with(|| a.topic.cmp(&b.topic))
+            });
+            deep_parse_info.entry(ip.clone()).or_default().mqtt = Some(MqttDetail {
+                role: if activity.broker { "broker" } else { "client" }.to_string(),
+                client_ids: activity.client_ids.clone(),
+                peers: activity.peers.clone(),
+                topics,
+            });
+        }
+
+        // Aggregate SMB share activity
+        for (ip, role) in &self.smb_roles {
+            let mut shares: Vec<SmbShareAccess> = self
+                .smb_shares
+                .iter()
+                .filter_map(|((client, server, share), activity)| {
+                    let (remote, remote_role) = if client == ip {
+                        (server, "server")
+                    } else if server == ip {
+                        (client, "client")
+                    } else {
+                        return None;
+                    };
+                    let sorted = |set: &HashSet<String>| -> Vec<String> {
+                        let mut v: Vec<String> = set.iter().cloned().collect();
+                        v.sort();
+                        v
+                    };
+                    Some(SmbShareAccess {
+                        remote_ip: remote.clone(),
+                        remote_role: remote_role.to_string(),
+                        share: share.clone(),
+                        files: sorted(&activity.files),
+                        files_written: sorted(&activity.files_written),
+                        bytes_written: activity.bytes_written,
+                        bytes_read: activity.bytes_read,
+                    })
+                })
+                .collect();
+            shares.sort_by(|a, b| {
+                a.share
+                    .cmp(&b.share)
+                    .then_with(|| a.remote_ip.cmp(&b.remote_ip))
+            });
+            let mut users: Vec<String> = self
+                .smb_users
+                .get(ip)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect();
+            users.sort();
+
+            let smb_detail = SmbDetail {
+                role: role.clone(),
+                users,
+                smb1: self.smb1_hosts.contains(ip),
+                shares,
+            };
+            deep_parse_info.entry(ip.clone()).or_default().smb = Some(smb_detail);
+        }
+
+        // DHCP servers and the clients they answered
+        for (ip, server) in &self.dhcp_servers {
+            let mut leases: Vec<DhcpLease> = server
+                .clients
+                .iter()
+                .map(|mac| {
+                    let client = self.dhcp_clients.get(mac);
+                    DhcpLease {
+                        client_mac: mac.clone(),
+                        ip: client.and_then(|c| c.ip.clone()),
+                        hostname: client.and_then(|c| c.hostname.clone()),
+                    }
+                })
+                .collect();
+            leases.sort_by(|a, b| a.client_mac.cmp(&b.client_mac));
+            deep_parse_info.entry(ip.clone()).or_default().dhcp = Some(DhcpDetail {
+                role: "server".to_string(),
+                hostname: None,
+                vendor_class: None,
+                server_ip: None,
+                leases,
+                replies: server.replies,
+            });
+        }
+
+        // DHCP clients, under their bound address or the IP last seen with their MAC
+        for (mac, client) in &self.dhcp_clients {
+            let ip = client.ip.clone().or_else(|| {
+                self.asset_macs
+                    .iter()
+                    .find(|(_, m)| *m == mac)
+                    .map(|(ip, _)| ip.clone())
+            });
+            let Some(ip) = ip else {
+                continue;
+            };
+            let entry = deep_parse_info.entry(ip).or_default();
+            if entry.dhcp.is_some() {
+                continue;
+            }
+            entry.dhcp = Some(DhcpDetail {
+                role: "client".to_string(),
+                hostname: client.hostname.clone(),
+                vendor_class: client.vendor_class.clone(),
+                server_ip: client.server_ip.clone(),
+                leases: Vec::new(),
+                replies: 0,
+            });
+        }
+
+        // Aggregate engineering software fingerprints
+        for (ip, software) in &self.engineering_software {
+            let mut names: Vec<String> = software
+                .iter()
+                .map(|s| s.display_name().to_string())
+                .collect();
+            names.sort();
+            deep_parse_info.entry(ip.clone()).or_default().engineering =
+                Some(EngineeringDetail { software: names });
+        }
+
+        deep_parse_info
+    }
+
+    /// Collect all observed GOOSE control blocks, ordered by publisher and gocbRef.
+    pub fn build_goose_streams(&self) -> Vec<GooseStream> {
+        let mut streams: Vec<GooseStream> = self.goose_streams.values().cloned().collect();
+        streams
+            .sort_by(|a, b| (&a.publisher_mac, &a.gocb_ref).cmp(&(&b.publisher_mac, &b.gocb_ref)));
+        streams
+    }
+
+    /// Collect the IP → MAC bindings learned from ARP, ordered by address.
+    pub fn build_arp_bindings(&self) -> Vec<ArpBinding> {
+        let mut bindings: Vec<ArpBinding> = self.arp_bindings.values().cloned().collect();
+        bindings.sort_by_key(|b| b.ip_address.parse::<std::net::Ipv4Addr>().ok());
+        bindings
+    }
+
+    /// Collect ICMP activity, busiest pinger first.
+    pub fn build_icmp_activity(&self) -> Vec<IcmpActivity> {
+        let mut activity: Vec<IcmpActivity> = self.icmp_activity.values().cloned().collect();
+        activity.sort_by(|a, b| {
+            b.fan_out()
+                .cmp(&a.fan_out())
+                .then_with(|| a.ip_address.cmp(&b.ip_address))
+        });
+        activity
+    }
+
+    /// Collect all observed SV publications, ordered by publisher and svID.
+    pub fn build_sv_streams(&self) -> Vec<SvStream> {
+        let mut streams: Vec<SvStream> = self.sv_streams.values().cloned().collect();
+        streams.sort_by(|a, b| (&a.publisher_mac, &a.sv_id).cmp(&(&b.publisher_mac, &b.sv_id)));
+        streams
+    }
+
+    /// Collect all observed PROFINET cyclic RT relations, ordered by provider,
+    /// consumer, and FrameID.
+    pub fn build_profinet_rt_streams(&self) -> Vec<ProfinetRtStream> {
+        let mut streams: Vec<ProfinetRtStream> =
+            self.profinet_rt_streams.values().cloned().collect();
+        streams.sort_by(|a, b| {
+            (&a.provider_mac, &a.consumer_mac, a.frame_id).cmp(&(
+                &b.provider_mac,
+                &b.consumer_mac,
+                b.frame_id,
+            ))
+        });
+        streams
+    }
+
+    /// IO-controller / IO-device roles of cyclic RT stations, keyed by MAC.
+    ///
+    /// A controller runs one output relation, with its own FrameID, to each
+    /// IO-device, while a device only answers its controller. So a station
+    /// providing FrameIDs to two or more unicast peers is a controller and
+    /// those peers are devices. One-to-one relations fall back to the role a
+    /// station advertised over DCP; anything else stays unresolved.
+    fn profinet_rt_roles(&self) -> HashMap<String, String> {
+        let mut peers: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for (provider, consumer, _) in self.profinet_rt_streams.keys() {
+            if !is_group_mac(consumer) {
+                peers.entry(provider).or_default().insert(consumer);
+            }
+        }
+
+        let mut roles: HashMap<String, String> = HashMap::new();
+        for (provider, consumers) in &peers {
+            if consumers.len() >= 2 {
+                roles.insert(provider.to_string(), "io_controller".to_string());
+                for consumer in consumers {
+                    roles
+                        .entry(consumer.to_string())
+                        .or_insert_with(|| "io_device".to_string());
+                }
             }
         }
 
-        // Aggregate SNMP device identity (keyed directly by IP)
-        for (ip, snmp_info) in &self.snmp_device_info {
-            let snmp_detail = SnmpDetail {
-                sys_descr: snmp_info.sys_descr.clone(),
-                sys_name: snmp_info.sys_name.clone(),
-                sys_location: snmp_info.sys_location.clone(),
-                sys_object_id: snmp_info.sys_object_id.clone(),
-                sys_uptime_cs: snmp_info.sys_uptime_cs,
-                sys_contact: snmp_info.sys_contact.clone(),
-                vendor: snmp_info.vendor.clone(),
-            };
-            deep_parse_info.entry(ip.clone()).or_default().snmp = Some(snmp_detail);
+        for (provider, consumers) in &peers {
+            for consumer in consumers {
+                for (mac, peer) in [(*provider, *consumer), (*consumer, *provider)] {
+                    if roles.contains_key(mac) {
+                        continue;
+                    }
+                    let (own_role, peer_role) = match self.profinet_dcp_role(mac) {
+                        Some("io_controller") => ("io_controller", "io_device"),
+                        Some("io_device") => ("io_device", "io_controller"),
+                        _ => continue,
+                    };
+                    roles.insert(mac.to_string(), own_role.to_string());
+                    roles
+                        .entry(peer.to_string())
+                        .or_insert_with(|| peer_role.to_string());
+                }
+            }
         }
+        roles
+    }
 
-        deep_parse_info
+    /// The role a station advertised in DCP, looked up through its MAC.
+    fn profinet_dcp_role(&self, mac: &str) -> Option<&str> {
+        self.profinet_roles
+            .iter()
+            .find(|(key, role)| {
+                role.as_str() != "unknown"
+                    && self
+                        .asset_macs
+                        .get(*key)
+                        .is_some_and(|m| m.eq_ignore_ascii_case(mac))
+            })
+            .map(|(_, role)| role.as_str())
+    }
+
+    /// Cyclic RT senders that have no IP or DCP asset of their own.
+    fn mac_only_profinet_rt_stations(&self) -> impl Iterator<Item = &String> {
+        self.profinet_rt_stations.keys().filter(|mac| {
+            !self
+                .asset_macs
+                .values()
+                .any(|m| m.eq_ignore_ascii_case(mac))
+        })
+    }
+
+    /// SV publisher MACs that never appeared on an IP packet.
+    fn mac_only_sv_publishers(&self) -> impl Iterator<Item = &String> {
+        self.sv_publishers.keys().filter(|mac| {
+            !self
+                .asset_macs
+                .values()
+                .any(|m| m.eq_ignore_ascii_case(mac))
+        })
     }
 
     /// Collect all observed redundancy protocol frames as a flat list.
@@ -1040,6 +4374,66 @@ impl PacketProcessor {
         self.redundancy_by_mac.values().cloned().collect()
     }
 
+    /// Convert captured LLDP and CDP frames into advertisements for the
+    /// physical topology, one per sender MAC. A port speaking both protocols
+    /// is represented by its LLDP advertisement.
+    pub fn build_neighbor_advertisements(&self) -> Vec<NeighborAdvertisement> {
+        let segment = |mac: &String| {
+            self.discovery_segments
+                .get(mac)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let mut adverts: Vec<NeighborAdvertisement> = self
+            .lldp_by_mac
+            .iter()
+            .map(|(mac, lldp)| NeighborAdvertisement {
+                protocol: "lldp".to_string(),
+                source_mac: mac.clone(),
+                chassis_id: lldp.chassis_id.clone(),
+                port_id: lldp.port_id.clone(),
+                port_description: lldp.port_description.clone(),
+                system_name: lldp.system_name.clone(),
+                model: lldp.model.clone(),
+                firmware: lldp.firmware.clone(),
+                management_ip: lldp
+                    .management_addresses
+                    .iter()
+                    .find(|a| a.addr_type == "ipv4")
+                    .map(|a| a.address.clone()),
+                capabilities: lldp
+                    .capability_summary
+                    .as_deref()
+                    .filter(|s| *s != "Unknown")
+                    .map(|s| s.split(", ").map(str::to_string).collect())
+                    .unwrap_or_default(),
+                vlan_ids: lldp.vlan_ids.clone(),
+                segment: segment(mac),
+            })
+            .collect();
+        adverts.extend(
+            self.cdp_by_mac
+                .iter()
+                .filter(|(mac, _)| !self.lldp_by_mac.contains_key(*mac))
+                .map(|(mac, cdp)| NeighborAdvertisement {
+                    protocol: "cdp".to_string(),
+                    source_mac: mac.clone(),
+                    chassis_id: None,
+                    port_id: cdp.port_id.clone(),
+                    port_description: None,
+                    system_name: cdp.device_id.clone(),
+                    model: cdp.platform.clone(),
+                    firmware: cdp.software_version.clone(),
+                    management_ip: cdp.primary_address().map(str::to_string),
+                    capabilities: cdp.capability_names(),
+                    vlan_ids: cdp.native_vlan.into_iter().collect(),
+                    segment: segment(mac),
+                }),
+        );
+        adverts.sort_by(|a, b| a.source_mac.cmp(&b.source_mac));
+        adverts
+    }
+
     /// Run signature matching and build the final asset list.
     ///
     /// Requires references to the SignatureEngine, OUI lookup, and GeoIP lookup.
@@ -1072,25 +4466,91 @@ impl PacketProcessor {
             }
         }
 
+        // Routers inferred from MACs fronting many addresses; a routed
+        // host's first-seen MAC may be its router's
+        let gateways = self.topo_builder.gateways();
+        let gateway_ips: HashSet<&str> = gateways
+            .iter()
+            .filter_map(|g| g.ip_address.as_deref())
+            .collect();
+        let routed_by: HashMap<&str, &str> = gateways
+            .iter()
+            .flat_map(|g| {
+                g.routed_ips
+                    .iter()
+                    .map(move |ip| (ip.as_str(), g.mac_address.as_str()))
+            })
+            .collect();
+
         // Build assets
         let all_ips: HashSet<String> = self.asset_protocols.keys().cloned().collect();
         let mut assets: Vec<AssetInfo> = Vec::new();
 
         for ip in &all_ips {
-            let protocols: Vec<IcsProtocol> = self
+            let mut protocols: Vec<IcsProtocol> = self
                 .asset_protocols
                 .get(ip)
                 .map(|s| s.iter().copied().collect())
                 .unwrap_or_default();
 
+            // GOOSE is Layer 2, so it reaches an IP asset through its MAC
+            let publishes_goose = self.asset_macs.get(ip).is_some_and(|mac| {
+                self.goose_streams
+                    .keys()
+                    .any(|(publisher, _)| publisher.eq_ignore_ascii_case(mac))
+            });
+            if publishes_goose && !protocols.contains(&IcsProtocol::Goose) {
+                protocols.push(IcsProtocol::Goose);
+            }
+            let publishes_sv = self.asset_macs.get(ip).is_some_and(|mac| {
+                self.sv_publishers
+                    .keys()
+                    .any(|publisher| publisher.eq_ignore_ascii_case(mac))
+            });
+            if publishes_sv && !protocols.contains(&IcsProtocol::SampledValues) {
+                protocols.push(IcsProtocol::SampledValues);
+            }
+            let profinet_rt = deep_parse_info.get(ip).and_then(|d| d.profinet_rt.as_ref());
+            if profinet_rt.is_some() && !protocols.contains(&IcsProtocol::Profinet) {
+                protocols.push(IcsProtocol::Profinet);
+            }
+
+            // Port-102 handshakes seen before the first MMS PDU are labelled
+            // S7comm; drop that label for MMS devices that never sent S7 PDUs.
+            if protocols.contains(&IcsProtocol::Iec61850Mms) && !self.s7_roles.contains_key(ip) {
+                protocols.retain(|p| *p != IcsProtocol::S7comm);
+            }
+
             let is_server = self.server_ips.contains(ip);
             let mut device_type = infer_device_type(&protocols, is_server);
 
             let sig_matches = sig_results.get(ip).cloned().unwrap_or_default();
             let best_match = sig_matches.first();
 
+            // GOOSE, SV and PROFINET added above come from decoded frames
+            let protocol_evidence: Vec<ProtocolEvidence> = protocols
+                .iter()
+                .map(|p| {
+                    let identification = self
+                        .asset_protocol_evidence
+                        .get(ip)
+                        .and_then(|evidence| evidence.get(p))
+                        .copied()
+                        .unwrap_or_else(|| {
+                            ProtocolIdentification::new(*p, IdentificationMethod::DeepParse)
+                        });
+                    ProtocolEvidence::from_identification(&identification)
+                })
+                .collect();
+
+            // Without a signature, a protocol confirmed from its payload
+            // counts as a pattern match; a port alone is the weakest evidence
             let mut confidence = best_match.map(|m| m.confidence).unwrap_or(
-                if protocols.iter().any(|p| *p != IcsProtocol::Unknown) {
+                if protocol_evidence.iter().any(|e| {
+                    e.protocol != "unknown" && e.method >= IdentificationMethod::PayloadProbe
+                }) {
+                    2
+                } else if protocols.iter().any(|p| *p != IcsProtocol::Unknown) {
                     1
                 } else {
                     0
@@ -1101,7 +4561,11 @@ impl PacketProcessor {
             let mut product_family = best_match.and_then(|m| m.product_family.clone());
 
             // OUI vendor lookup from MAC address
-            let mac = self.asset_macs.get(ip);
+            let mac = self.asset_macs.get(ip).filter(|m| {
+                !routed_by
+                    .get(ip.as_str())
+                    .is_some_and(|gw| m.eq_ignore_ascii_case(gw))
+            });
             let oui_vendor = mac.and_then(|m| oui_lookup.lookup(m).map(|v| v.to_string()));
 
             // If no signature vendor but OUI found, use OUI vendor + confidence 3
@@ -1137,6 +4601,97 @@ impl PacketProcessor {
                 }
             }
 
+            // A ListIdentity response carries the device's own vendor ID,
+            // device profile and catalog name
+            if let Some(id) = deep_parse_info
+                .get(ip)
+                .and_then(|d| d.enip.as_ref())
+                .and_then(|e| e.identity.as_ref())
+            {
+                confidence = 5;
+                if id.vendor_name != "Unknown Vendor" {
+                    vendor = Some(id.vendor_name.clone());
+                }
+                if !id.product_name.is_empty() {
+                    product_family = Some(id.product_name.clone());
+                }
+            }
+
+            // BACnet Device object properties read back by a workstation are
+            // the controller's own vendor-name and model-name
+            if let Some(dev) = deep_parse_info
+                .get(ip)
+                .and_then(|d| d.bacnet.as_ref())
+                .and_then(|b| b.device.as_ref())
+                .filter(|d| d.vendor_name.is_some() || d.model_name.is_some())
+            {
+                confidence = 5;
+                let registered = dev
+                    .vendor_id
+                    .map(bacnet_vendor_name)
+                    .filter(|v| *v != "Unknown Vendor");
+                if let Some(v) = dev
+                    .vendor_name
+                    .clone()
+                    .or_else(|| registered.map(str::to_string))
+                {
+                    vendor = Some(v);
+                }
+                if dev.model_name.is_some() {
+                    product_family = dev.model_name.clone();
+                }
+            }
+
+            // MMS Identify response (vendor/model/revision) is a deep parse
+            // confirmation, same as Modbus Device ID
+            if let Some(mms) = deep_parse_info.get(ip).and_then(|d| d.mms.as_ref()) {
+                if mms.vendor.is_some() || mms.model.is_some() {
+                    confidence = 5;
+                    if mms.vendor.is_some() {
+                        vendor = mms.vendor.clone();
+                    }
+                    if mms.model.is_some() {
+                        product_family = mms.model.clone();
+                    }
+                }
+            }
+
+            // FINS Controller Data Read names the exact OMRON CPU model
+            if let Some(model) = deep_parse_info
+                .get(ip)
+                .and_then(|d| d.fins.as_ref())
+                .and_then(|f| f.controller_model.clone())
+            {
+                confidence = 5;
+                vendor = Some("OMRON".to_string());
+                product_family = Some(model);
+            }
+
+            // SLMP Read CPU Model Name does the same for MELSEC CPUs
+            if let Some(model) = deep_parse_info
+                .get(ip)
+                .and_then(|d| d.slmp.as_ref())
+                .and_then(|s| s.cpu_model.clone())
+            {
+                confidence = 5;
+                vendor = Some("Mitsubishi Electric".to_string());
+                product_family = Some(model);
+            }
+
+            // SRTP Return Controller Type and ID gives the GE catalog number
+            if let Some(model) = deep_parse_info
+                .get(ip)
+                .and_then(|d| d.ge_srtp.as_ref())
+                .and_then(|g| g.controller_model.clone())
+            {
+                confidence = 5;
+                vendor = Some("GE".to_string());
+                product_family = Some(match gm_parsers::ge_srtp::product_family(&model) {
+                    Some(family) => format!("{} {}", family, model),
+                    None => model,
+                });
+            }
+
             if let Some(m) = best_match {
                 if let Some(ref sig_device_type) = m.device_type {
                     if m.confidence >= 3 {
@@ -1145,12 +4700,242 @@ impl PacketProcessor {
                 }
             }
 
+            // The CIP device profile in ListIdentity is the device's own claim
+            match deep_parse_info
+                .get(ip)
+                .and_then(|d| d.enip.as_ref())
+                .and_then(|e| e.identity.as_ref())
+                .map(|id| id.device_type)
+            {
+                Some(0x0E) => device_type = "plc".to_string(),
+                Some(0x18) => device_type = "hmi".to_string(),
+                _ => {}
+            }
+
+            // Serving IEC 61850 MMS or publishing GOOSE/SV is payload evidence
+            // of an IED, which also keeps Siemens SIPROTEC relays from being
+            // classified as S7 PLCs by OUI/port signatures.
+            if self.mms_roles.get(ip).map(String::as_str) == Some("server")
+                || publishes_goose
+                || publishes_sv
+            {
+                device_type = "ied".to_string();
+                if confidence < 4 {
+                    confidence = 4;
+                }
+            }
+
+            // Answering FINS, SLMP, SRTP or CODESYS requests is what an OMRON,
+            // MELSEC, GE or CODESYS-based CPU (or its Ethernet module) does
+            if self.fins_roles.get(ip).map(String::as_str) == Some("server")
+                || self.slmp_roles.get(ip).map(String::as_str) == Some("server")
+                || self.ge_srtp_roles.get(ip).map(String::as_str) == Some("server")
+                || self.codesys_roles.get(ip).map(String::as_str) == Some("server")
+            {
+                device_type = "plc".to_string();
+                if confidence < 4 {
+                    confidence = 4;
+                }
+            }
+
+            // Classic OPC servers run on Windows SCADA hosts and their ports
+            // are handed out at runtime, so port heuristics miss them. A
+            // client that speaks no other OT protocol is collecting from
+            // OPC servers rather than driving a process.
+            match self.opc_da_roles.get(ip).map(String::as_str) {
+                Some("server") => {
+                    device_type = "scada_server".to_string();
+                    if confidence < 4 {
+                        confidence = 4;
+                    }
+                }
+                Some("client")
+                    if protocols
+                        .iter()
+                        .all(|p| !p.is_ot() || *p == IcsProtocol::OpcDa) =>
+                {
+                    device_type = "historian".to_string();
+                    if confidence < 4 {
+                        confidence = 4;
+                    }
+                }
+                _ => {}
+            }
+
+            // A Fox station on an embedded OS is a JACE-class building
+            // controller; on a server OS it is a Niagara Supervisor
+            let fox = deep_parse_info.get(ip).and_then(|d| d.fox.as_ref());
+            if let Some(fox) = fox {
+                let is_station = fox.role == "server" || fox.station_name.is_some();
+                if is_station {
+                    let on_server_os = fox.os_name.as_deref().is_some_and(|os| {
+                        let os = os.to_ascii_lowercase();
+                        os.contains("windows") || os.contains("linux")
+                    });
+                    device_type = if on_server_os { "scada_server" } else { "plc" }.to_string();
+                    if confidence < 4 {
+                        confidence = 4;
+                    }
+                } else if fox.app_name.as_deref() == Some("Workbench") {
+                    device_type = "engineering_workstation".to_string();
+                    if confidence < 4 {
+                        confidence = 4;
+                    }
+                }
+                if let Some(ref version) = fox.niagara_version {
+                    confidence = 5;
+                    vendor = Some(match fox.brand_id.as_deref() {
+                        Some(brand) if !brand.eq_ignore_ascii_case("tridium") => {
+                            format!("Tridium ({})", brand)
+                        }
+                        _ => "Tridium".to_string(),
+                    });
+                    let line = if fox.is_legacy {
+                        "Niagara AX"
+                    } else {
+                        "Niagara"
+                    };
+                    let app = fox.app_name.as_deref().unwrap_or("Station");
+                    product_family = Some(format!("{} {} {}", line, version, app));
+                }
+            }
+
+            // Red Lion devices answer Crimson on 789: Data Stations and edge
+            // controllers act as RTUs/protocol converters, the rest are panels.
+            // A workstation that moves configuration databases runs Crimson.
+            let crimson = deep_parse_info.get(ip).and_then(|d| d.crimson.as_ref());
+            if let Some(crimson) = crimson {
+                if crimson.role == "server" {
+                    let is_data_station = crimson
+                        .model
+                        .as_deref()
+                        .is_some_and(gm_parsers::crimson::is_data_station);
+                    device_type = if is_data_station { "rtu" } else { "hmi" }.to_string();
+                    if confidence < 4 {
+                        confidence = 4;
+                    }
+                } else if crimson.has_download || crimson.has_upload {
+                    device_type = "engineering_workstation".to_string();
+                    if confidence < 4 {
+                        confidence = 4;
+                    }
+                }
+                if let Some(ref model) = crimson.model {
+                    confidence = 5;
+                    vendor = Some(
+                        crimson
+                            .manufacturer
+                            .clone()
+                            .unwrap_or_else(|| "Red Lion Controls".to_string()),
+                    );
+                    product_family = Some(model.clone());
+                }
+            }
+
+            // Only Yokogawa DCS stations use the Vnet/IP ports and addressing
+            let vnet_ip = deep_parse_info.get(ip).and_then(|d| d.vnet_ip.as_ref());
+            if vnet_ip.is_some() {
+                if confidence < 4 {
+                    vendor = Some("Yokogawa".to_string());
+                    confidence = 4;
+                }
+                if product_family.is_none() {
+                    product_family = Some("CENTUM / ProSafe-RS station (Vnet/IP)".to_string());
+                }
+            }
+
+            // TriStation on 1502 is only spoken by Triconex safety controllers
+            // and the TriStation 1131 workstations that program them
+            let tristation = deep_parse_info.get(ip).and_then(|d| d.tristation.as_ref());
+            if let Some(tristation) = tristation {
+                if tristation.role == "server" {
+                    device_type = "plc".to_string();
+                    if confidence < 4 {
+                        vendor = Some("Schneider Electric".to_string());
+                        confidence = 4;
+                    }
+                    if product_family.is_none() {
+                        product_family = Some("Triconex safety controller".to_string());
+                    }
+                } else if !tristation.commands.is_empty() {
+                    device_type = "engineering_workstation".to_string();
+                    if confidence < 4 {
+                        confidence = 4;
+                    }
+                }
+            }
+
+            // Answering ADS on 48898 means a TwinCAT runtime (PLC/IPC); a client
+            // that changes the runtime state is a TwinCAT engineering station
+            let ads = deep_parse_info.get(ip).and_then(|d| d.ads.as_ref());
+            if let Some(ads) = ads {
+                if ads.role == "server" {
+                    device_type = "plc".to_string();
+                    if confidence < 4 {
+                        vendor = Some("Beckhoff".to_string());
+                        confidence = 4;
+                    }
+                    if let Some(ref version) = ads.twincat_version {
+                        confidence = 5;
+                        vendor = Some("Beckhoff".to_string());
+                        product_family = Some(match ads.device_name {
+                            Some(ref name) => format!("TwinCAT {} ({})", version, name),
+                            None => format!("TwinCAT {}", version),
+                        });
+                    }
+                } else if !ads.state_changes.is_empty() {
+                    device_type = "engineering_workstation".to_string();
+                    if confidence < 4 {
+                        confidence = 4;
+                    }
+                }
+            }
+
+            // Driving cyclic IO to several devices is what a PROFINET controller does
+            if profinet_rt.is_some_and(|rt| rt.role == "io_controller") {
+                device_type = "plc".to_string();
+                if confidence < 4 {
+                    confidence = 4;
+                }
+            }
+
+            // Engineering software on the wire is payload evidence (confidence 4)
+            // and outranks port-based inference and generic signature matches.
+            // A PG connection alone only fills in a type nothing else decided:
+            // HMIs and diagnostic tools open PG connections too.
+            if self.engineering_software.contains_key(ip)
+                && (self.conclusive_engineering.contains(ip)
+                    || matches!(device_type.as_str(), "unknown" | "it_device"))
+            {
+                device_type = "engineering_workstation".to_string();
+                if confidence < 4 {
+                    confidence = 4;
+                }
+            }
+
+            // Passing CRC-valid serial frames on a device server's tunnel port
+            // is what a Moxa/Lantronix gateway does, whatever it speaks upstream
+            if let Some(sg) = deep_parse_info
+                .get(ip)
+                .and_then(|d| d.serial_gateway.as_ref())
+            {
+                device_type = "gateway".to_string();
+                if vendor.is_none() {
+                    vendor = Some(sg.vendor.clone());
+                }
+                if confidence < 3 {
+                    confidence = 3;
+                }
+            }
+
             // LLDP enrichment (confidence 4 — better than OUI/port, lower than deep parse)
             let mut hostname: Option<String> = None;
+            let mut hostname_source: Option<&str> = None;
             if let Some(mac_addr) = self.asset_macs.get(ip) {
                 if let Some(lldp) = self.lldp_by_mac.get(mac_addr) {
                     if let Some(ref sn) = lldp.system_name {
                         hostname = Some(sn.clone());
+                        hostname_source = Some("lldp");
                     }
                     if vendor.is_none() {
                         if let Some(ref lv) = lldp.vendor {
@@ -1181,6 +4966,111 @@ impl PacketProcessor {
                 }
             }
 
+            // A MAC fronting many addresses across subnets is forwarding
+            // for them
+            if device_type == "unknown" && gateway_ips.contains(ip.as_str()) {
+                device_type = "gateway".to_string();
+            }
+
+            // SNMP system group seen in passing GetResponses: sysName is the
+            // configured hostname and the sysObjectID enterprise arc is the
+            // vendor's own claim, so it carries LLDP-level confidence
+            if let Some(snmp) = self.snmp_device_info.get(ip) {
+                if hostname.is_none() {
+                    hostname = snmp.sys_name.clone().filter(|n| !n.is_empty());
+                    hostname_source = hostname.as_ref().map(|_| "snmp");
+                }
+                if let Some(snmp_vendor) = snmp.known_vendor() {
+                    if vendor.is_none() || confidence < 4 {
+                        vendor = Some(snmp_vendor.to_string());
+                        if confidence < 4 {
+                            confidence = 4;
+                        }
+                    }
+                }
+                if product_family.is_none() {
+                    product_family = snmp.model();
+                }
+            }
+
+            // PROFINET DCP: the Name-of-Station is the device's configured
+            // name, and a PI-registered vendor ID outranks OUI guesses
+            if let Some(pn) = deep_parse_info
+                .get(ip)
+                .and_then(|d| d.profinet_dcp.as_ref())
+            {
+                if hostname.is_none() {
+                    hostname = pn.device_name.clone();
+                    hostname_source = hostname.as_ref().map(|_| "profinet_dcp");
+                }
+                if let Some(ref pn_vendor) = pn.vendor {
+                    if confidence < 4 {
+                        vendor = Some(pn_vendor.clone());
+                        confidence = 4;
+                    }
+                }
+                if product_family.is_none() {
+                    product_family = pn.product.clone().or_else(|| pn.station_type.clone());
+                }
+            }
+
+            // Niagara Fox: the station name is the name the integrator gave it
+            if hostname.is_none() {
+                hostname = fox.and_then(|f| f.station_name.clone().or_else(|| f.host_name.clone()));
+                hostname_source = hostname.as_ref().map(|_| "niagara_fox");
+            }
+
+            // BACnet: the Device object-name is unique on the internetwork
+            if hostname.is_none() {
+                hostname = deep_parse_info
+                    .get(ip)
+                    .and_then(|d| d.bacnet.as_ref())
+                    .and_then(|b| b.device.as_ref())
+                    .and_then(|d| d.object_name.clone());
+                hostname_source = hostname.as_ref().map(|_| "bacnet");
+            }
+
+            // Vnet/IP: name the station the way CENTUM does (FCS0101, HIS0164)
+            if hostname.is_none() {
+                hostname = vnet_ip.map(|v| {
+                    let prefix = match device_type.as_str() {
+                        "plc" => "FCS",
+                        "hmi" | "engineering_workstation" => "HIS",
+                        _ => "STN",
+                    };
+                    format!("{}{}", prefix, v.station_number)
+                });
+                hostname_source = hostname.as_ref().map(|_| "vnet_ip");
+            }
+
+            // DHCP: the name the host gave itself when asking for a lease
+            if hostname.is_none() {
+                hostname = deep_parse_info
+                    .get(ip)
+                    .and_then(|d| d.dhcp.as_ref())
+                    .and_then(|d| d.hostname.clone());
+                hostname_source = hostname.as_ref().map(|_| "dhcp");
+            }
+
+            // DNS: the name the site's DNS gives the address
+            if hostname.is_none() {
+                hostname = self.dns_names.get(ip).map(|(name, _)| name.clone());
+                hostname_source = hostname.as_ref().map(|_| "dns");
+            }
+
+            // Names the host announced for itself: NetBIOS, then mDNS, then LLMNR
+            if hostname.is_none() {
+                if let Some(names) = self.announced_names.get(ip) {
+                    if let Some((protocol, name)) = ["netbios", "mdns", "llmnr"]
+                        .into_iter()
+                        .find_map(|p| names.get(p).map(|name| (p, name.clone())))
+                    {
+                        hostname = Some(name);
+                        hostname_source = Some(protocol);
+                    }
+                }
+            }
+
             // GeoIP enrichment
             let is_public_ip = GeoIpLookup::is_public_ip(ip);
             let country = geoip_lookup.lookup_country(ip);
@@ -1188,8 +5078,9 @@ impl PacketProcessor {
             assets.push(AssetInfo {
                 id: ip.clone(),
                 ip_address: ip.clone(),
-                mac_address: self.asset_macs.get(ip).cloned(),
+                mac_address: mac.cloned(),
                 hostname,
+                hostname_source: hostname_source.map(str::to_string),
                 device_type,
                 vendor,
                 protocols: protocols
@@ -1205,9 +5096,107 @@ impl PacketProcessor {
                 confidence,
                 product_family,
                 signature_matches: sig_matches,
+                protocol_evidence,
                 oui_vendor,
                 country,
                 is_public_ip,
+                lifecycle: AssetLifecycle::Active,
+                lifecycle_changed_at: None,
+                vlan_ids: self.vlan_ids_of(ip),
+            });
+        }
+
+        // Merging units on a process bus may only ever publish SV. Give them a
+        // MAC-keyed asset (same ID as their Layer-2 topology node).
+        for mac in self.mac_only_sv_publishers() {
+            let activity = &self.sv_publishers[mac];
+            let oui_vendor = oui_lookup.lookup(mac).map(|v| v.to_string());
+            assets.push(AssetInfo {
+                id: mac.clone(),
+                ip_address: mac.clone(),
+                mac_address: Some(mac.clone()),
+                hostname: self
+                    .lldp_by_mac
+                    .get(mac)
+                    .and_then(|lldp| lldp.system_name.clone()),
+                hostname_source: self
+                    .lldp_by_mac
+                    .get(mac)
+                    .and_then(|lldp| lldp.system_name.as_ref())
+                    .map(|_| "lldp".to_string()),
+                device_type: "ied".to_string(),
+                vendor: oui_vendor.clone(),
+                protocols: vec![format!("{:?}", IcsProtocol::SampledValues).to_lowercase()],
+                first_seen: activity.first_seen.clone(),
+                last_seen: activity.last_seen.clone(),
+                notes: String::new(),
+                purdue_level: None,
+                tags: Vec::new(),
+                packet_count: activity.frames,
+                confidence: 4,
+                product_family: None,
+                signature_matches: Vec::new(),
+                protocol_evidence: vec![ProtocolEvidence::from_identification(
+                    &ProtocolIdentification::new(
+                        IcsProtocol::SampledValues,
+                        IdentificationMethod::DeepParse,
+                    ),
+                )],
+                oui_vendor,
+                country: None,
+                is_public_ip: false,
+                lifecycle: AssetLifecycle::Active,
+                lifecycle_changed_at: None,
+                vlan_ids: self.vlan_ids_of(mac),
+            });
+        }
+
+        // PROFINET IO-devices can run cyclic IO without any IP traffic in
+        // the capture. Key them by MAC, like their Layer-2 topology node.
+        for mac in self.mac_only_profinet_rt_stations() {
+            let activity = &self.profinet_rt_stations[mac];
+            let oui_vendor = oui_lookup.lookup(mac).map(|v| v.to_string());
+            let is_controller = deep_parse_info
+                .get(mac)
+                .and_then(|d| d.profinet_rt.as_ref())
+                .is_some_and(|rt| rt.role == "io_controller");
+            assets.push(AssetInfo {
+                id: mac.clone(),
+                ip_address: mac.clone(),
+                mac_address: Some(mac.clone()),
+                hostname: self
+                    .lldp_by_mac
+                    .get(mac)
+                    .and_then(|lldp| lldp.system_name.clone()),
+                hostname_source: self
+                    .lldp_by_mac
+                    .get(mac)
+                    .and_then(|lldp| lldp.system_name.as_ref())
+                    .map(|_| "lldp".to_string()),
+                device_type: if is_controller { "plc" } else { "unknown" }.to_string(),
+                vendor: oui_vendor.clone(),
+                protocols: vec![format!("{:?}", IcsProtocol::Profinet).to_lowercase()],
+                first_seen: activity.first_seen.clone(),
+                last_seen: activity.last_seen.clone(),
+                notes: String::new(),
+                purdue_level: None,
+                tags: Vec::new(),
+                packet_count: activity.frames,
+                confidence: if is_controller { 4 } else { 3 },
+                product_family: None,
+                signature_matches: Vec::new(),
+                protocol_evidence: vec![ProtocolEvidence::from_identification(
+                    &ProtocolIdentification::new(
+                        IcsProtocol::Profinet,
+                        IdentificationMethod::DeepParse,
+                    ),
+                )],
+                oui_vendor,
+                country: None,
+                is_public_ip: false,
+                lifecycle: AssetLifecycle::Active,
+                lifecycle_changed_at: None,
+                vlan_ids: self.vlan_ids_of(mac),
             });
         }
 
@@ -1221,15 +5210,142 @@ impl PacketProcessor {
         (assets, sig_results)
     }
 
-    /// Finalize connections with origin file tracking.
+    /// Sorted VLAN IDs recorded for an asset key (IP or MAC).
+    fn vlan_ids_of(&self, key: &str) -> Vec<u16> {
+        let mut vlans: Vec<u16> = self
+            .asset_vlans
+            .get(key)
+            .map(|v| v.iter().copied().collect())
+            .unwrap_or_default();
+        vlans.sort_unstable();
+        vlans
+    }
+
+    /// Per-IP packet samples used for signature matching.
+    /// Write a device event per asset to the pipeline trace and close it.
+    ///
+    /// Call after `build_assets`, with the same engine. Returns None when
+    /// tracing is off.
+    #[cfg(feature = "gui")]
+    pub fn finish_trace(
+        &mut self,
+        engine: &SignatureEngine,
+        assets: &[AssetInfo],
+        deep_parse_info: &HashMap<String, DeepParseInfo>,
+    ) -> Option<TraceSummary> {
+        let mut trace = self.trace.take()?;
+        for asset in assets {
+            let ip = &asset.ip_address;
+            let signatures = self
+                .ip_packets
+                .get(ip)
+                .map(|packets| engine.explain_device_packets(packets))
+                .unwrap_or_default();
+            trace.device(DeviceTrace {
+                ip: ip.clone(),
+                device_type: asset.device_type.clone(),
+                confidence: asset.confidence,
+                vendor: asset.vendor.clone(),
+                product_family: asset.product_family.clone(),
+                identity_sources: identity_sources(
+                    asset,
+                    deep_parse_info.get(ip),
+                    self.server_ips.contains(ip),
+                ),
+                signatures,
+            });
+        }
+        Some(trace.finish())
+    }
+
+    pub fn signature_packets(&self) -> HashMap<String, Vec<PacketData>> {
+        self.ip_packets.clone()
+    }
+
+    /// Finalize connections with origin file tracking, oriented so the
+    /// client is the source and the server the destination.
     pub fn get_connections(&mut self) -> Vec<ConnectionInfo> {
         for (conn_key, conn) in &mut self.connections {
             if let Some(files) = self.conn_origin_files.get(conn_key) {
                 conn.origin_files = files.iter().cloned().collect();
                 conn.origin_files.sort();
             }
+            if let Some(vlans) = self.conn_vlans.get(conn_key) {
+                conn.vlan_ids = vlans.iter().copied().collect();
+                conn.vlan_ids.sort_unstable();
+            }
+            if let Some(vnis) = self.conn_vnis.get(conn_key) {
+                conn.vxlan_vnis = vnis.iter().copied().collect();
+                conn.vxlan_vnis.sort_unstable();
+            }
+        }
+        self.connections
+            .iter()
+            .map(|(conn_key, conn)| {
+                // Orient a copy: the stored orientation is what the SYN
+                // evidence is relative to, and live capture calls this repeatedly
+                let mut conn = conn.clone();
+                let orientation = orient(&DirectionEvidence {
+                    src_port: conn.src_port,
+                    dst_port: conn.dst_port,
+                    syn_from_src: self.conn_syn_from_src.get(conn_key).copied(),
+                    role_server_is_dst: match (
+                        self.has_server_role(&conn.src_ip),
+                        self.has_server_role(&conn.dst_ip),
+                    ) {
+                        (false, true) => Some(true),
+                        (true, false) => Some(false),
+                        _ => None,
+                    },
+                });
+                if orientation.swap {
+                    conn.swap_ends();
+                }
+                conn.direction_basis = orientation.basis;
+                conn
+            })
+            .collect()
+    }
+
+    /// Whether deep parsing saw `ip` answer as a server, slave or
+    /// outstation in any protocol (and never as the polling side).
+    fn has_server_role(&self, ip: &str) -> bool {
+        const SERVER_ROLES: [&str; 5] = ["server", "slave", "outstation", "adapter", "io_device"];
+        const CLIENT_ROLES: [&str; 5] = [
+            "client",
+            "master",
+            "scanner",
+            "io_controller",
+            "io_supervisor",
+        ];
+        let mut roles: Vec<&str> = [
+            &self.enip_roles,
+            &self.s7_roles,
+            &self.bacnet_roles,
+            &self.iec104_roles,
+            &self.fins_roles,
+            &self.slmp_roles,
+            &self.ge_srtp_roles,
+            &self.codesys_roles,
+            &self.fox_roles,
+            &self.crimson_roles,
+            &self.tristation_roles,
+            &self.ads_roles,
+            &self.opc_da_roles,
+            &self.mms_roles,
+            &self.profinet_roles,
+        ]
+        .into_iter()
+        .filter_map(|roles| roles.get(ip).map(String::as_str))
+        .collect();
+        for set in [self.modbus_roles.get(ip), self.dnp3_roles.get(ip)]
+            .into_iter()
+            .flatten()
+        {
+            roles.extend(set.iter().map(String::as_str));
         }
-        self.connections.values().cloned().collect()
+        roles.iter().any(|r| SERVER_ROLES.contains(r))
+            && !roles.iter().any(|r| CLIENT_ROLES.contains(r))
     }
 
     /// Get a snapshot of packet summaries.
@@ -1238,6 +5354,7 @@ impl PacketProcessor {
     }
 
     /// Get protocols detected so far.
+    #[cfg(feature = "gui")]
     pub fn get_protocols_detected(&self) -> Vec<String> {
         self.all_protocols.iter().cloned().collect()
     }
@@ -1248,7 +5365,160 @@ impl PacketProcessor {
     /// Safe to call multiple times — no mutable state in PatternAnalyzer.
     pub fn build_pattern_results(&mut self) -> (Vec<ConnectionStats>, Vec<PatternAnomaly>) {
         let stats = self.pattern_analyzer.compute_stats();
-        let anomalies = PatternAnalyzer::detect_anomalies(&stats);
+        let mut anomalies = PatternAnalyzer::detect_anomalies(&stats);
+        anomalies.extend(SessionTracker::detect_anomalies(
+            &self.session_tracker.sessions(),
+        ));
         (stats, anomalies)
     }
+
+    /// Per-quarter-hour traffic seen so far, for shift reports.
+    pub fn build_shift_activity(&self) -> ShiftActivity {
+        self.shift_activity.clone()
+    }
+
+    /// Summarise every Modbus/DNP3 TCP session seen so far.
+    pub fn build_protocol_sessions(&self) -> Vec<ProtocolSession> {
+        self.session_tracker.sessions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::tcp_packet;
+
+    /// COTP connection request to a PG TSAP (rack 0, slot 2).
+    const PG_CONNECTION_REQUEST: [u8; 22] = [
+        0x03, 0x00, 0x00, 0x16, 0x11, 0xE0, 0x00, 0x00, 0x00, 0x01, 0x00, 0xC1, 0x02, 0x01, 0x00,
+        0xC2, 0x02, 0x01, 0x02, 0xC0, 0x01, 0x0A,
+    ];
+
+    /// Modbus/TCP read holding registers request (unit 1, 10 registers at 0).
+    const MODBUS_READ_REQUEST: [u8; 12] = [
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x0A,
+    ];
+
+    /// Response to [`MODBUS_READ_REQUEST`] carrying ten zeroed registers.
+    const MODBUS_READ_RESPONSE: [u8; 29] = [
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x17, 0x01, 0x03, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    fn device_type_of(processor: &PacketProcessor, ip: &str) -> String {
+        let deep_parse_info = processor.build_deep_parse_info();
+        let (assets, _) = processor.build_assets(
+            &SignatureEngine::new(),
+            &deep_parse_info,
+            &OuiLookup::empty(),
+            &GeoIpLookup::empty(),
+        );
+        assets
+            .into_iter()
+            .find(|a| a.ip_address == ip)
+            .map(|a| a.device_type)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_pg_connection_keeps_hmi_type() {
+        let mut processor = PacketProcessor::new();
+        processor.process_packet(&tcp_packet(
+            "10.0.0.10",
+            49152,
+            "10.0.0.1",
+            102,
+            &PG_CONNECTION_REQUEST,
+        ));
+        processor.process_packet(&tcp_packet(
+            "10.0.0.10",
+            49153,
+            "10.0.0.2",
+            502,
+            &MODBUS_READ_REQUEST,
+        ));
+
+        assert_eq!(device_type_of(&processor, "10.0.0.10"), "hmi");
+    }
+
+    #[test]
+    fn test_s7comm_plus_marks_engineering_workstation() {
+        let mut processor = PacketProcessor::new();
+        processor.process_packet(&tcp_packet(
+            "10.0.0.10",
+            49152,
+            "10.0.0.1",
+            102,
+            &[
+                0x03, 0x00, 0x00, 0x0B, 0x02, 0xF0, 0x80, 0x72, 0x01, 0x00, 0x00,
+            ],
+        ));
+        processor.process_packet(&tcp_packet(
+            "10.0.0.10",
+            49153,
+            "10.0.0.2",
+            502,
+            &MODBUS_READ_REQUEST,
+        ));
+
+        assert_eq!(
+            device_type_of(&processor, "10.0.0.10"),
+            "engineering_workstation"
+        );
+    }
+
+    #[test]
+    fn test_deep_parse_info_merges_sessions_with_protocol_detail() {
+        let mut processor = PacketProcessor::new();
+        processor.process_packet(&tcp_packet(
+            "10.0.0.10",
+            49153,
+            "10.0.0.2",
+            502,
+            &MODBUS_READ_REQUEST,
+        ));
+        processor.process_packet(&tcp_packet(
+            "10.0.0.2",
+            502,
+            "10.0.0.10",
+            49153,
+            &MODBUS_READ_RESPONSE,
+        ));
+
+        let info = processor.build_deep_parse_info();
+        for ip in ["10.0.0.10", "10.0.0.2"] {
+            let device = &info[ip];
+            assert!(device.modbus.is_some(), "{ip}: missing Modbus detail");
+            let sessions = device.sessions.as_ref().unwrap();
+            assert_eq!(sessions.ip, ip);
+            assert_eq!(sessions.total_sessions, 1);
+            assert_eq!(sessions.unanswered_requests, 0);
+        }
+    }
+
+    #[test]
+    fn test_deep_parse_info_counts_unanswered_requests() {
+        let mut processor = PacketProcessor::new();
+        processor.process_packet(&tcp_packet(
+            "10.0.0.10",
+            49153,
+            "10.0.0.2",
+            502,
+            &MODBUS_READ_REQUEST,
+        ));
+        // A later request on another session moves the capture end past
+        // the response timeout of the first
+        let mut later = tcp_packet("10.0.0.11", 49154, "10.0.0.3", 502, &MODBUS_READ_REQUEST);
+        later.timestamp += chrono::Duration::seconds(10);
+        processor.process_packet(&later);
+
+        let info = processor.build_deep_parse_info();
+        for ip in ["10.0.0.10", "10.0.0.2"] {
+            let sessions = info[ip].sessions.as_ref().unwrap();
+            assert_eq!(sessions.unanswered_requests, 1, "{ip}");
+            assert_eq!(sessions.sessions_with_unanswered, 1, "{ip}");
+        }
+        let sessions = info["10.0.0.3"].sessions.as_ref().unwrap();
+        assert_eq!(sessions.unanswered_requests, 0);
+    }
 }
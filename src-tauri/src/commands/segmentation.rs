@@ -9,8 +9,9 @@ use tauri::State;
 use std::collections::{HashMap, HashSet};
 
 use gm_segmentation::{
-    run_segmentation_analysis, AssetProfile, EnforcementFormat, ObservedConnection, ProtocolRole,
-    SecurityFinding, SegmentationInput, SegmentationReport,
+    build_gap_worksheet, run_segmentation_analysis, worksheet_to_csv, AssetProfile,
+    EnforcementFormat, GapWorksheet, ObservedConnection, ProtocolRole, SecurityFinding,
+    SecurityLevel, SegmentationInput, SegmentationReport,
 };
 
 use super::AppState;
@@ -38,6 +39,8 @@ fn build_segmentation_input(state: &super::AppStateInner) -> SegmentationInput {
                 "Bacnet",
                 "iec104",
                 "Iec104",
+                "iec61850_mms",
+                "Iec61850Mms",
                 "profinet_dcp",
                 "ProfinetDcp",
                 "hart_ip",
@@ -46,6 +49,24 @@ fn build_segmentation_input(state: &super::AppStateInner) -> SegmentationInput {
                 "GeSrtp",
                 "wonderware_suitelink",
                 "WonderwareSuitelink",
+                "fins",
+                "Fins",
+                "slmp",
+                "Slmp",
+                "codesys",
+                "Codesys",
+                "niagara_fox",
+                "NiagaraFox",
+                "crimson",
+                "Crimson",
+                "vnet_ip",
+                "VnetIp",
+                "tristation",
+                "TriStation",
+                "ads",
+                "Ads",
+                "opc_da",
+                "OpcDa",
             ]
             .iter()
             .copied()
@@ -260,6 +281,15 @@ fn build_segmentation_input(state: &super::AppStateInner) -> SegmentationInput {
     }
 }
 
+/// The segmentation input with addresses and names pseudonymized, when demo
+/// mode is on. Reports re-run from it are shown instead of the cached one.
+fn demo_segmentation_input(state: &super::AppStateInner) -> Option<SegmentationInput> {
+    let anon = super::demo::active_anonymizer(state)?;
+    let mut input = build_segmentation_input(state);
+    super::demo::anonymize_segmentation_input(&anon, state, &mut input);
+    Some(input)
+}
+
 /// Compute the /24 subnet string for a given IPv4 address.
 fn compute_subnet_24(ip: &str) -> Option<String> {
     let parts: Vec<&str> = ip.split('.').collect();
@@ -295,7 +325,10 @@ pub fn run_segmentation(state: State<'_, AppState>) -> Result<SegmentationReport
         report.communication_matrix.zone_pairs.len(),
     );
 
-    Ok(report)
+    match demo_segmentation_input(&inner) {
+        Some(input) => Ok(run_segmentation_analysis(&input)),
+        None => Ok(report),
+    }
 }
 
 /// Export one of the five enforcement config formats from the last segmentation run.
@@ -309,9 +342,11 @@ pub fn export_enforcement_config(
 ) -> Result<String, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
 
-    let report = inner.segmentation_report.as_ref().ok_or_else(|| {
+    let cached = inner.segmentation_report.as_ref().ok_or_else(|| {
         "No segmentation report available. Run segmentation analysis first.".to_string()
     })?;
+    let demo_report = demo_segmentation_input(&inner).map(|i| run_segmentation_analysis(&i));
+    let report = demo_report.as_ref().unwrap_or(cached);
 
     let fmt = parse_enforcement_format(&format)?;
 
@@ -324,6 +359,63 @@ pub fn export_enforcement_config(
     Ok(config.content.clone())
 }
 
+/// Build the IEC 62443 SL-T vs SL-A gap worksheet for the zones of the last
+/// segmentation run.
+///
+/// `targets` maps zone IDs to user-declared SL-T values; omitted zones use
+/// the level proposed by the zone model.
+#[tauri::command]
+pub fn get_sl_gap_worksheet(
+    targets: HashMap<String, SecurityLevel>,
+    state: State<'_, AppState>,
+) -> Result<GapWorksheet, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    gap_worksheet(&inner, &targets)
+}
+
+/// Export the gap worksheet as CSV, writing to the specified file path.
+#[tauri::command]
+pub fn export_sl_gap_worksheet(
+    targets: HashMap<String, SecurityLevel>,
+    output_path: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let worksheet = gap_worksheet(&inner, &targets)?;
+    std::fs::write(&output_path, worksheet_to_csv(&worksheet)).map_err(|e| e.to_string())?;
+    log::info!(
+        "Exported SL gap worksheet for {} zones: {}",
+        worksheet.zones.len(),
+        output_path
+    );
+    Ok(output_path)
+}
+
+fn gap_worksheet(
+    inner: &super::AppStateInner,
+    targets: &HashMap<String, SecurityLevel>,
+) -> Result<GapWorksheet, String> {
+    let report = inner.segmentation_report.as_ref().ok_or_else(|| {
+        "No segmentation report available. Run segmentation analysis first.".to_string()
+    })?;
+    if let Some(input) = demo_segmentation_input(inner) {
+        let report = run_segmentation_analysis(&input);
+        return Ok(build_gap_worksheet(
+            &report.zone_model,
+            &report.policy_groups,
+            &input,
+            targets,
+        ));
+    }
+    let input = build_segmentation_input(inner);
+    Ok(build_gap_worksheet(
+        &report.zone_model,
+        &report.policy_groups,
+        &input,
+        targets,
+    ))
+}
+
 /// Parse enforcement format string to enum.
 fn parse_enforcement_format(s: &str) -> Result<EnforcementFormat, String> {
     match s {
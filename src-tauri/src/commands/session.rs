@@ -3,12 +3,25 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(feature = "gui")]
 use tauri::State;
 
+#[cfg(feature = "gui")]
+use gm_analysis::AssetLifecycle;
 use gm_db::{AssetRow, ConnectionRow};
+#[cfg(feature = "gui")]
+use gm_parsers::DirectionBasis;
+#[cfg(feature = "gui")]
+use gm_signatures::SignatureEngine;
+use gm_signatures::SignatureSetSnapshot;
+#[cfg(feature = "gui")]
 use gm_topology::TopologyBuilder;
 
-use super::{AppState, AssetInfo, ConnectionInfo, DeepParseInfo};
+#[cfg(feature = "gui")]
+use super::demo::{active_anonymizer, anonymize_asset, resolve_ip};
+#[cfg(feature = "gui")]
+use super::{mark_connection_seen, merge_confirmed_devices, AppState};
+use super::{AppStateInner, AssetInfo, ConnectionInfo, DeepParseInfo};
 
 // ─── Types ──────────────────────────────────────────────────
 
@@ -25,6 +38,7 @@ pub struct SessionInfo {
 }
 
 /// Partial updates for an asset (from the frontend edit form).
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Deserialize)]
 pub struct AssetUpdate {
     pub device_type: Option<String>,
@@ -32,6 +46,8 @@ pub struct AssetUpdate {
     pub notes: Option<String>,
     pub purdue_level: Option<u8>,
     pub tags: Option<Vec<String>>,
+    /// Lifecycle state name (active, quarantined, decommissioned, planned)
+    pub lifecycle: Option<String>,
 }
 
 /// Session metadata stored as JSON in the database.
@@ -39,11 +55,19 @@ pub struct AssetUpdate {
 struct SessionMetadata {
     deep_parse_info: HashMap<String, DeepParseInfo>,
     imported_files: Vec<String>,
+    /// Demo-mode pseudonymization seed, so pseudonyms survive a reload
+    #[serde(default)]
+    demo_seed: Option<u64>,
+    /// Signatures active when the session was saved, so it can be reloaded
+    /// with exactly the same set (absent in sessions from older versions)
+    #[serde(default)]
+    signature_set: Option<SignatureSetSnapshot>,
 }
 
 // ─── Session Commands ───────────────────────────────────────
 
 /// Save the current state as a named session.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn save_session(
     name: String,
@@ -51,42 +75,63 @@ pub async fn save_session(
     state: State<'_, AppState>,
 ) -> Result<SessionInfo, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    store_session(
+        &inner,
+        &name,
+        &description.unwrap_or_default(),
+        &inner.assets,
+        &inner.connections,
+        &inner.deep_parse_info,
+        &inner.imported_files,
+    )
+}
 
+/// Write assets, connections and deep parse info to the database as a new
+/// session (scoped to the active project, if any).
+///
+/// Used by [`save_session`] for the current state, and by batch import for
+/// per-file sessions that are never loaded into the current state.
+pub(crate) fn store_session(
+    inner: &AppStateInner,
+    name: &str,
+    description: &str,
+    assets: &[AssetInfo],
+    connections: &[ConnectionInfo],
+    deep_parse_info: &HashMap<String, DeepParseInfo>,
+    imported_files: &[String],
+) -> Result<SessionInfo, String> {
     let db = inner.db.as_ref().ok_or("Database not available")?;
     let session_id = uuid::Uuid::new_v4().to_string();
-    let desc = description.unwrap_or_default();
 
     // Serialize metadata (deep parse info + imported files)
     let metadata = SessionMetadata {
-        deep_parse_info: inner.deep_parse_info.clone(),
-        imported_files: inner.imported_files.clone(),
+        deep_parse_info: deep_parse_info.clone(),
+        imported_files: imported_files.to_vec(),
+        demo_seed: inner.demo_seed,
+        signature_set: Some(inner.signature_engine.snapshot()),
     };
     let metadata_json = serde_json::to_string(&metadata).map_err(|e| e.to_string())?;
 
     // Create session record
     let session_row = db
-        .create_session(&session_id, &name, &desc, &metadata_json)
+        .create_session(&session_id, name, description, &metadata_json)
         .map_err(|e| e.to_string())?;
 
     // Insert all assets
-    for asset in &inner.assets {
+    for asset in assets {
         let row = asset_info_to_row(asset, &session_id);
         db.insert_asset(&row).map_err(|e| e.to_string())?;
     }
 
     // Insert all connections
-    for conn in &inner.connections {
+    for conn in connections {
         let row = connection_info_to_row(conn, &session_id);
         db.insert_connection(&row).map_err(|e| e.to_string())?;
     }
 
     // Update counts
-    db.update_session_counts(
-        &session_id,
-        inner.assets.len() as i64,
-        inner.connections.len() as i64,
-    )
-    .map_err(|e| e.to_string())?;
+    db.update_session_counts(&session_id, assets.len() as i64, connections.len() as i64)
+        .map_err(|e| e.to_string())?;
 
     // Scope to active project if one is set
     if let Some(project_id) = inner.current_project_id {
@@ -98,8 +143,8 @@ pub async fn save_session(
         "Saved session '{}' ({}) with {} assets, {} connections",
         name,
         session_id,
-        inner.assets.len(),
-        inner.connections.len()
+        assets.len(),
+        connections.len()
     );
 
     Ok(SessionInfo {
@@ -108,15 +153,20 @@ pub async fn save_session(
         description: session_row.description,
         created_at: session_row.created_at,
         updated_at: session_row.updated_at,
-        asset_count: inner.assets.len() as i64,
-        connection_count: inner.connections.len() as i64,
+        asset_count: assets.len() as i64,
+        connection_count: connections.len() as i64,
     })
 }
 
 /// Load a session by ID, replacing the current state.
+///
+/// With `pin_signatures`, the signature set embedded in the session
+/// replaces the global one until a session is loaded without pinning.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn load_session(
     session_id: String,
+    pin_signatures: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<SessionInfo, String> {
     let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -131,6 +181,8 @@ pub async fn load_session(
         serde_json::from_str(&session_row.metadata).unwrap_or(SessionMetadata {
             deep_parse_info: HashMap::new(),
             imported_files: Vec::new(),
+            demo_seed: None,
+            signature_set: None,
         });
 
     // Load assets from DB
@@ -145,7 +197,10 @@ pub async fn load_session(
         conn_rows.into_iter().map(row_to_connection_info).collect();
 
     // Rebuild topology from loaded connections
-    let mut topo_builder = TopologyBuilder::new();
+    let mut topo_builder = TopologyBuilder::with_subnets(super::system::subnet_plan(
+        &inner.settings.profile,
+        &inner.physical_topology,
+    ));
     for conn in &connections {
         let protocol = gm_parsers::IcsProtocol::from_name(&conn.protocol);
         topo_builder.add_connection(
@@ -156,16 +211,32 @@ pub async fn load_session(
             protocol,
             conn.byte_count,
         );
+        topo_builder.add_ports(
+            &conn.src_ip,
+            &conn.dst_ip,
+            conn.src_port,
+            conn.dst_port,
+            conn.byte_count,
+        );
+        mark_connection_seen(&mut topo_builder, conn);
     }
     let topology = topo_builder.snapshot();
 
     // Replace state (preserve signature_engine, oui_lookup, geoip_lookup, db)
     inner.topology = topology;
+    merge_confirmed_devices(&mut inner);
     inner.assets = assets;
     inner.connections = connections;
     inner.packet_summaries = HashMap::new(); // Not persisted (too large)
+    inner.signature_packets = HashMap::new();
     inner.imported_files = metadata.imported_files;
     inner.deep_parse_info = metadata.deep_parse_info;
+    inner.demo_seed = metadata.demo_seed;
+    apply_signature_pin(
+        &mut inner,
+        metadata.signature_set.as_ref(),
+        pin_signatures.unwrap_or(false),
+    )?;
     inner.current_session_id = Some(session_id.clone());
     inner.current_session_name = Some(session_row.name.clone());
 
@@ -183,6 +254,7 @@ pub async fn load_session(
 }
 
 /// List saved sessions. When a project is active, returns only that project's sessions.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn list_sessions(state: State<'_, AppState>) -> Result<Vec<SessionInfo>, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -210,6 +282,7 @@ pub async fn list_sessions(state: State<'_, AppState>) -> Result<Vec<SessionInfo
 }
 
 /// Delete a session by ID.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn delete_session(session_id: String, state: State<'_, AppState>) -> Result<(), String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
@@ -222,6 +295,7 @@ pub async fn delete_session(session_id: String, state: State<'_, AppState>) -> R
 // ─── Asset Update Commands ──────────────────────────────────
 
 /// Update a single asset's editable fields.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn update_asset(
     asset_id: String,
@@ -229,6 +303,8 @@ pub async fn update_asset(
     state: State<'_, AppState>,
 ) -> Result<AssetInfo, String> {
     let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let asset_id = resolve_ip(&inner, &asset_id);
+    let anon = active_anonymizer(&inner);
 
     // Find and update the asset in memory
     let asset = inner
@@ -246,6 +322,8 @@ pub async fn update_asset(
         } else {
             Some(hostname.clone())
         };
+        // A cleared hostname may be filled again from traffic
+        asset.hostname_source = asset.hostname.as_ref().map(|_| "manual".to_string());
     }
     if let Some(ref notes) = updates.notes {
         asset.notes = notes.clone();
@@ -256,8 +334,21 @@ pub async fn update_asset(
     if let Some(ref tags) = updates.tags {
         asset.tags = tags.clone();
     }
+    // Only a real transition restarts the "seen since" clock
+    let new_lifecycle = updates
+        .lifecycle
+        .as_deref()
+        .map(AssetLifecycle::from_name)
+        .filter(|l| *l != asset.lifecycle);
+    if let Some(state) = new_lifecycle {
+        asset.lifecycle = state;
+        asset.lifecycle_changed_at = Some(chrono::Utc::now().to_rfc3339());
+    }
 
-    let updated = asset.clone();
+    let mut updated = asset.clone();
+    if let Some(ref anon) = anon {
+        anonymize_asset(anon, &mut updated);
+    }
 
     // Persist to DB if a session is loaded
     if let (Some(ref db), Some(ref _session_id)) = (&inner.db, &inner.current_session_id) {
@@ -277,12 +368,16 @@ pub async fn update_asset(
             let tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string());
             let _ = db.update_asset_field(&asset_id, "tags", &tags_json);
         }
+        if let Some(state) = new_lifecycle {
+            let _ = db.update_asset_field(&asset_id, "lifecycle", state.as_str());
+        }
     }
 
     Ok(updated)
 }
 
 /// Bulk update assets (same field on multiple assets).
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn bulk_update_assets(
     asset_ids: Vec<String>,
@@ -290,7 +385,11 @@ pub async fn bulk_update_assets(
     state: State<'_, AppState>,
 ) -> Result<usize, String> {
     let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let asset_ids: Vec<String> = asset_ids.iter().map(|id| resolve_ip(&inner, id)).collect();
 
+    let lifecycle = updates.lifecycle.as_deref().map(AssetLifecycle::from_name);
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut lifecycle_changed = Vec::new();
     let mut count = 0;
     for asset in &mut inner.assets {
         if asset_ids.contains(&asset.id) {
@@ -303,6 +402,7 @@ pub async fn bulk_update_assets(
                 } else {
                     Some(hostname.clone())
                 };
+                asset.hostname_source = asset.hostname.as_ref().map(|_| "manual".to_string());
             }
             if let Some(ref notes) = updates.notes {
                 asset.notes = notes.clone();
@@ -313,6 +413,11 @@ pub async fn bulk_update_assets(
             if let Some(ref tags) = updates.tags {
                 asset.tags = tags.clone();
             }
+            if let Some(state) = lifecycle.filter(|l| *l != asset.lifecycle) {
+                asset.lifecycle = state;
+                asset.lifecycle_changed_at = Some(now.clone());
+                lifecycle_changed.push(asset.id.clone());
+            }
             count += 1;
         }
     }
@@ -325,6 +430,9 @@ pub async fn bulk_update_assets(
         if let Some(ref notes) = updates.notes {
             let _ = db.bulk_update_asset_field(&asset_ids, "notes", notes);
         }
+        if let Some(state) = lifecycle {
+            let _ = db.bulk_update_asset_field(&lifecycle_changed, "lifecycle", state.as_str());
+        }
     }
 
     Ok(count)
@@ -332,7 +440,14 @@ pub async fn bulk_update_assets(
 
 // ─── Session Archive (ZIP) ──────────────────────────────────
 
-/// Export a session to a .kkj ZIP archive.
+/// Archives are re-imported as working sessions, so they must keep the real
+/// data; they are refused in demo mode rather than pseudonymized.
+#[cfg(feature = "gui")]
+const DEMO_MODE_ARCHIVE_ERROR: &str =
+    "Session archive export is unavailable in demo mode: archives hold the real data";
+
+/// Export a session to a .kkj ZIP archive. Refused in demo mode.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn export_session_archive(
     session_id: String,
@@ -340,6 +455,9 @@ pub async fn export_session_archive(
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    if super::demo::active_anonymizer(&inner).is_some() {
+        return Err(DEMO_MODE_ARCHIVE_ERROR.to_string());
+    }
     let db = inner.db.as_ref().ok_or("Database not available")?;
 
     // Load session data from DB
@@ -363,12 +481,25 @@ pub async fn export_session_archive(
         "connections": connections,
     });
 
+    // The signature set travels inside session.json's metadata; the
+    // manifest names it so archives can be compared without unpacking
+    let signature_set = serde_json::from_str::<SessionMetadata>(&session.metadata)
+        .ok()
+        .and_then(|m| m.signature_set)
+        .map(|s| {
+            serde_json::json!({
+                "fingerprint": s.fingerprint,
+                "signature_count": s.signatures.len(),
+            })
+        });
+
     let manifest = serde_json::json!({
         "version": "1.0",
         "app_version": env!("CARGO_PKG_VERSION"),
         "created_at": chrono::Utc::now().to_rfc3339(),
         "asset_count": assets.len(),
         "connection_count": connections.len(),
+        "signature_set": signature_set,
     });
 
     // Create ZIP file
@@ -406,9 +537,13 @@ pub async fn export_session_archive(
 }
 
 /// Import a session from a .kkj ZIP archive.
+///
+/// `pin_signatures` behaves as in [`load_session`].
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub async fn import_session_archive(
     archive_path: String,
+    pin_signatures: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<SessionInfo, String> {
     // Read the ZIP file
@@ -477,6 +612,8 @@ pub async fn import_session_archive(
         serde_json::from_str(&metadata_str).unwrap_or(SessionMetadata {
             deep_parse_info: HashMap::new(),
             imported_files: Vec::new(),
+            demo_seed: None,
+            signature_set: None,
         });
 
     let loaded_assets = db.list_assets(&new_session_id).map_err(|e| e.to_string())?;
@@ -491,7 +628,10 @@ pub async fn import_session_archive(
         .collect();
 
     // Rebuild topology
-    let mut topo_builder = TopologyBuilder::new();
+    let mut topo_builder = TopologyBuilder::with_subnets(super::system::subnet_plan(
+        &inner.settings.profile,
+        &inner.physical_topology,
+    ));
     for conn in &conns_vec {
         let protocol = gm_parsers::IcsProtocol::from_name(&conn.protocol);
         topo_builder.add_connection(
@@ -502,14 +642,30 @@ pub async fn import_session_archive(
             protocol,
             conn.byte_count,
         );
+        topo_builder.add_ports(
+            &conn.src_ip,
+            &conn.dst_ip,
+            conn.src_port,
+            conn.dst_port,
+            conn.byte_count,
+        );
+        mark_connection_seen(&mut topo_builder, conn);
     }
 
     inner.topology = topo_builder.snapshot();
+    merge_confirmed_devices(&mut inner);
     inner.assets = assets_vec;
     inner.connections = conns_vec;
     inner.packet_summaries = HashMap::new();
+    inner.signature_packets = HashMap::new();
     inner.imported_files = metadata.imported_files;
     inner.deep_parse_info = metadata.deep_parse_info;
+    inner.demo_seed = metadata.demo_seed;
+    apply_signature_pin(
+        &mut inner,
+        metadata.signature_set.as_ref(),
+        pin_signatures.unwrap_or(false),
+    )?;
     inner.current_session_id = Some(new_session_id);
     inner.current_session_name = Some(session_name.clone());
 
@@ -530,6 +686,40 @@ pub async fn import_session_archive(
     })
 }
 
+/// Swap the session's pinned signature set in, or restore the global set.
+///
+/// Pinning a session saved before signature sets were recorded is an
+/// error rather than a silent fallback to the global signatures.
+#[cfg(feature = "gui")]
+fn apply_signature_pin(
+    inner: &mut AppStateInner,
+    signature_set: Option<&SignatureSetSnapshot>,
+    pin: bool,
+) -> Result<(), String> {
+    if !pin {
+        if let Some(global) = inner.unpinned_signature_engine.take() {
+            inner.signature_engine = global;
+            log::info!("Restored global signature set");
+        }
+        return Ok(());
+    }
+
+    let snapshot =
+        signature_set.ok_or("Session was saved without a signature set and cannot be pinned")?;
+    let pinned = SignatureEngine::from_snapshot(snapshot).map_err(|e| e.to_string())?;
+    let previous = std::mem::replace(&mut inner.signature_engine, pinned);
+    // Keep the first global engine when switching between pinned sessions
+    if inner.unpinned_signature_engine.is_none() {
+        inner.unpinned_signature_engine = Some(previous);
+    }
+    log::info!(
+        "Pinned signature set {} ({} signatures)",
+        snapshot.fingerprint,
+        snapshot.signatures.len()
+    );
+    Ok(())
+}
+
 // ─── Conversion Helpers ─────────────────────────────────────
 
 fn asset_info_to_row(asset: &AssetInfo, session_id: &str) -> AssetRow {
@@ -539,6 +729,7 @@ fn asset_info_to_row(asset: &AssetInfo, session_id: &str) -> AssetRow {
         ip_address: asset.ip_address.clone(),
         mac_address: asset.mac_address.clone(),
         hostname: asset.hostname.clone(),
+        hostname_source: asset.hostname_source.clone(),
         device_type: asset.device_type.clone(),
         vendor: asset.vendor.clone(),
         product_family: asset.product_family.clone(),
@@ -550,11 +741,16 @@ fn asset_info_to_row(asset: &AssetInfo, session_id: &str) -> AssetRow {
         packet_count: asset.packet_count as i64,
         signature_matches: serde_json::to_string(&asset.signature_matches)
             .unwrap_or_else(|_| "[]".to_string()),
+        protocol_evidence: serde_json::to_string(&asset.protocol_evidence)
+            .unwrap_or_else(|_| "[]".to_string()),
         oui_vendor: asset.oui_vendor.clone(),
         country: asset.country.clone(),
         is_public_ip: asset.is_public_ip,
         first_seen: asset.first_seen.clone(),
         last_seen: asset.last_seen.clone(),
+        lifecycle: asset.lifecycle.as_str().to_string(),
+        lifecycle_changed_at: asset.lifecycle_changed_at.clone(),
+        vlan_ids: serde_json::to_string(&asset.vlan_ids).unwrap_or_else(|_| "[]".to_string()),
     }
 }
 
@@ -576,19 +772,27 @@ fn connection_info_to_row(conn: &ConnectionInfo, session_id: &str) -> Connection
         last_seen: conn.last_seen.clone(),
         origin_files: serde_json::to_string(&conn.origin_files)
             .unwrap_or_else(|_| "[]".to_string()),
+        provenance: serde_json::to_string(&conn.provenance).unwrap_or_else(|_| "[]".to_string()),
+        direction_basis: conn.direction_basis.as_str().to_string(),
+        vlan_ids: serde_json::to_string(&conn.vlan_ids).unwrap_or_else(|_| "[]".to_string()),
+        vxlan_vnis: serde_json::to_string(&conn.vxlan_vnis).unwrap_or_else(|_| "[]".to_string()),
     }
 }
 
+#[cfg(feature = "gui")]
 fn row_to_asset_info(row: AssetRow) -> AssetInfo {
     let protocols: Vec<String> = serde_json::from_str(&row.protocols).unwrap_or_default();
     let tags: Vec<String> = serde_json::from_str(&row.tags).unwrap_or_default();
     let signature_matches = serde_json::from_str(&row.signature_matches).unwrap_or_default();
+    let protocol_evidence = serde_json::from_str(&row.protocol_evidence).unwrap_or_default();
+    let vlan_ids = serde_json::from_str(&row.vlan_ids).unwrap_or_default();
 
     AssetInfo {
         id: row.id,
         ip_address: row.ip_address,
         mac_address: row.mac_address,
         hostname: row.hostname,
+        hostname_source: row.hostname_source,
         device_type: row.device_type,
         vendor: row.vendor,
         protocols,
@@ -601,14 +805,22 @@ fn row_to_asset_info(row: AssetRow) -> AssetInfo {
         confidence: row.confidence as u8,
         product_family: row.product_family,
         signature_matches,
+        protocol_evidence,
         oui_vendor: row.oui_vendor,
         country: row.country,
         is_public_ip: row.is_public_ip,
+        lifecycle: AssetLifecycle::from_name(&row.lifecycle),
+        lifecycle_changed_at: row.lifecycle_changed_at,
+        vlan_ids,
     }
 }
 
+#[cfg(feature = "gui")]
 fn row_to_connection_info(row: ConnectionRow) -> ConnectionInfo {
     let origin_files: Vec<String> = serde_json::from_str(&row.origin_files).unwrap_or_default();
+    let provenance = serde_json::from_str(&row.provenance).unwrap_or_default();
+    let vlan_ids = serde_json::from_str(&row.vlan_ids).unwrap_or_default();
+    let vxlan_vnis = serde_json::from_str(&row.vxlan_vnis).unwrap_or_default();
 
     ConnectionInfo {
         id: row.id,
@@ -625,5 +837,9 @@ fn row_to_connection_info(row: ConnectionRow) -> ConnectionInfo {
         first_seen: row.first_seen,
         last_seen: row.last_seen,
         origin_files,
+        provenance,
+        direction_basis: DirectionBasis::from_name(&row.direction_basis),
+        vlan_ids,
+        vxlan_vnis,
     }
 }
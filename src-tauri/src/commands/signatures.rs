@@ -1,147 +1,328 @@
-//! Tauri commands for signature management.
-//!
-//! Provides commands to list, reload, and test signatures from the frontend.
-
-use serde::Serialize;
-use tauri::State;
-
-use gm_signatures::{PacketData, Signature};
-
-use super::AppState;
-
-/// Information about a loaded signature, for the frontend.
-#[derive(Debug, Clone, Serialize)]
-pub struct SignatureInfo {
-    pub name: String,
-    pub description: String,
-    pub vendor: Option<String>,
-    pub product_family: Option<String>,
-    pub protocol: Option<String>,
-    pub confidence: u8,
-    pub role: Option<String>,
-    pub device_type: Option<String>,
-    pub filter_count: usize,
-}
-
-impl From<&Signature> for SignatureInfo {
-    fn from(sig: &Signature) -> Self {
-        SignatureInfo {
-            name: sig.name.clone(),
-            description: sig.description.clone(),
-            vendor: sig.vendor.clone(),
-            product_family: sig.product_family.clone(),
-            protocol: sig.protocol.clone(),
-            confidence: sig.confidence,
-            role: sig.role.clone(),
-            device_type: sig.device_type.clone(),
-            filter_count: sig.filters.len(),
-        }
-    }
-}
-
-/// Summary of loaded signatures.
-#[derive(Debug, Clone, Serialize)]
-pub struct SignatureSummary {
-    pub total_count: usize,
-    pub signatures: Vec<SignatureInfo>,
-}
-
-/// Result of testing a signature against loaded PCAP data.
-#[derive(Debug, Clone, Serialize)]
-pub struct SignatureTestResult {
-    pub match_count: usize,
-    pub matches: Vec<TestResultInfo>,
-}
-
-#[derive(Debug, Clone, Serialize)]
-pub struct TestResultInfo {
-    pub packet_index: usize,
-    pub src_ip: String,
-    pub dst_ip: String,
-    pub src_port: u16,
-    pub dst_port: u16,
-    pub confidence: u8,
-}
-
-/// Get all loaded signatures.
-#[tauri::command]
-pub fn get_signatures(state: State<'_, AppState>) -> Result<SignatureSummary, String> {
-    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let sigs: Vec<SignatureInfo> = state_inner
-        .signature_engine
-        .signatures()
-        .iter()
-        .map(SignatureInfo::from)
-        .collect();
-
-    Ok(SignatureSummary {
-        total_count: sigs.len(),
-        signatures: sigs,
-    })
-}
-
-/// Reload signatures from disk.
-#[tauri::command]
-pub fn reload_signatures(state: State<'_, AppState>) -> Result<usize, String> {
-    let mut state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-    let count = state_inner
-        .signature_engine
-        .reload()
-        .map_err(|e| e.to_string())?;
-    log::info!("Reloaded {} signatures", count);
-    Ok(count)
-}
-
-/// Test a YAML signature against the currently loaded PCAP data.
-///
-/// The frontend sends raw YAML text; we parse it, run it against
-/// all stored packet summaries' connection data, and return matches.
-#[tauri::command]
-pub fn test_signature(
-    yaml: String,
-    state: State<'_, AppState>,
-) -> Result<SignatureTestResult, String> {
-    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
-
-    // Build PacketData from stored connections for testing.
-    // We don't have full payload data in packet summaries (they're lightweight),
-    // so we create basic PacketData from connection info for filter testing.
-    let mut test_packets: Vec<PacketData> = Vec::new();
-
-    for conn in &state_inner.connections {
-        test_packets.push(PacketData {
-            src_ip: conn.src_ip.clone(),
-            dst_ip: conn.dst_ip.clone(),
-            src_port: conn.src_port,
-            dst_port: conn.dst_port,
-            src_mac: conn.src_mac.clone(),
-            dst_mac: conn.dst_mac.clone(),
-            transport: conn.transport.clone(),
-            protocol: conn.protocol.to_lowercase(),
-            payload: Vec::new(), // No payload in summaries
-            length: 0,
-        });
-    }
-
-    let results = state_inner
-        .signature_engine
-        .test_signature(&yaml, &test_packets)
-        .map_err(|e| e.to_string())?;
-
-    let matches: Vec<TestResultInfo> = results
-        .into_iter()
-        .map(|r| TestResultInfo {
-            packet_index: r.packet_index,
-            src_ip: r.src_ip,
-            dst_ip: r.dst_ip,
-            src_port: r.src_port,
-            dst_port: r.dst_port,
-            confidence: r.confidence,
-        })
-        .collect();
-
-    Ok(SignatureTestResult {
-        match_count: matches.len(),
-        matches,
-    })
-}
+//! Tauri commands for signature management.
+//!
+//! Provides commands to list, reload, and test signatures from the frontend,
+//! and to convert original GRASSMARLIN fingerprint XML into YAML signatures.
+
+use serde::Serialize;
+use tauri::State;
+
+use gm_signatures::{
+    convert_grassmarlin_xml, signature_to_yaml, PacketData, Signature, UnconvertedConstruct,
+};
+
+use super::{AppState, AssetSignatureMatch};
+
+/// Information about a loaded signature, for the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureInfo {
+    pub name: String,
+    pub description: String,
+    pub vendor: Option<String>,
+    pub product_family: Option<String>,
+    pub protocol: Option<String>,
+    pub confidence: u8,
+    pub role: Option<String>,
+    pub device_type: Option<String>,
+    pub filter_count: usize,
+}
+
+impl From<&Signature> for SignatureInfo {
+    fn from(sig: &Signature) -> Self {
+        SignatureInfo {
+            name: sig.name.clone(),
+            description: sig.description.clone(),
+            vendor: sig.vendor.clone(),
+            product_family: sig.product_family.clone(),
+            protocol: sig.protocol.clone(),
+            confidence: sig.confidence,
+            role: sig.role.clone(),
+            device_type: sig.device_type.clone(),
+            filter_count: sig.filters.len(),
+        }
+    }
+}
+
+/// Summary of loaded signatures.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureSummary {
+    pub total_count: usize,
+    pub signatures: Vec<SignatureInfo>,
+    /// Identifier of the loaded signature set (see `SignatureEngine::fingerprint`)
+    pub fingerprint: String,
+    /// Whether the set is a loaded session's pinned signatures
+    pub pinned: bool,
+}
+
+/// Result of testing a signature against loaded PCAP data.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureTestResult {
+    pub match_count: usize,
+    pub matches: Vec<TestResultInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestResultInfo {
+    pub packet_index: usize,
+    pub src_ip: String,
+    pub dst_ip: String,
+    pub src_port: u16,
+    pub dst_port: u16,
+    pub confidence: u8,
+}
+
+/// A signature converted from a GRASSMARLIN fingerprint.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConvertedSignature {
+    pub name: String,
+    pub yaml: String,
+}
+
+/// Result of converting one GRASSMARLIN fingerprint XML file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FingerprintConversionResult {
+    pub fingerprint: String,
+    pub signatures: Vec<ConvertedSignature>,
+    pub unconverted: Vec<UnconvertedConstruct>,
+    /// Files written when an output directory was given
+    pub written: Vec<String>,
+}
+
+/// Get all loaded signatures.
+#[tauri::command]
+pub fn get_signatures(state: State<'_, AppState>) -> Result<SignatureSummary, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let sigs: Vec<SignatureInfo> = state_inner
+        .signature_engine
+        .signatures()
+        .iter()
+        .map(SignatureInfo::from)
+        .collect();
+
+    Ok(SignatureSummary {
+        total_count: sigs.len(),
+        signatures: sigs,
+        fingerprint: state_inner.signature_engine.fingerprint(),
+        pinned: state_inner.unpinned_signature_engine.is_some(),
+    })
+}
+
+/// Result of reloading signatures from disk.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureReloadResult {
+    pub total_count: usize,
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    /// Assets whose signature matches changed as a result of the reload
+    pub assets_updated: usize,
+}
+
+/// Reload signatures from disk.
+///
+/// Only signatures that were added or changed (by content hash) are
+/// re-matched against the stored per-IP packet samples; matches from
+/// changed or removed signatures are dropped first. Asset vendor, product
+/// family, and device type are upgraded when the new best match outranks
+/// the current identification.
+#[tauri::command]
+pub fn reload_signatures(state: State<'_, AppState>) -> Result<SignatureReloadResult, String> {
+    let mut state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+    if state_inner.unpinned_signature_engine.is_some() {
+        return Err(
+            "Signatures are pinned to the loaded session; load it without pinning to reload"
+                .to_string(),
+        );
+    }
+    let diff = state_inner
+        .signature_engine
+        .reload_diff()
+        .map_err(|e| e.to_string())?;
+
+    let mut assets_updated = 0;
+    if !diff.is_empty() {
+        let stale = diff.stale_names();
+        let rematch = diff.rematch_names();
+        let inner = &mut *state_inner;
+
+        for asset in &mut inner.assets {
+            let new_matches: Vec<AssetSignatureMatch> = inner
+                .signature_packets
+                .get(&asset.ip_address)
+                .map(|packets| {
+                    inner
+                        .signature_engine
+                        .match_device_packets_for(packets, &rematch)
+                })
+                .unwrap_or_default()
+                .into_iter()
+                .map(|m| AssetSignatureMatch {
+                    signature_name: m.signature_name,
+                    confidence: m.confidence,
+                    vendor: m.vendor,
+                    product_family: m.product_family,
+                    device_type: m.device_type,
+                    role: m.role,
+                })
+                .collect();
+
+            let before = asset.signature_matches.len();
+            asset
+                .signature_matches
+                .retain(|m| !stale.contains(&m.signature_name));
+            if asset.signature_matches.len() == before && new_matches.is_empty() {
+                continue;
+            }
+            asset.signature_matches.extend(new_matches);
+            asset
+                .signature_matches
+                .sort_by_key(|m| std::cmp::Reverse(m.confidence));
+            assets_updated += 1;
+
+            if let Some(best) = asset.signature_matches.first() {
+                if best.confidence >= asset.confidence {
+                    asset.confidence = best.confidence;
+                    if best.vendor.is_some() {
+                        asset.vendor = best.vendor.clone();
+                    }
+                    if best.product_family.is_some() {
+                        asset.product_family = best.product_family.clone();
+                    }
+                    if let Some(ref dt) = best.device_type {
+                        if best.confidence >= 3 {
+                            asset.device_type = dt.clone();
+                        }
+                    }
+                }
+            }
+
+            if let Some(node) = inner
+                .topology
+                .nodes
+                .iter_mut()
+                .find(|n| n.ip_address == asset.ip_address)
+            {
+                node.vendor = asset.vendor.clone();
+                node.device_type = asset.device_type.clone();
+            }
+        }
+    }
+
+    log::info!(
+        "Reloaded {} signatures ({} added, {} changed, {} removed), {} assets updated",
+        diff.total,
+        diff.added.len(),
+        diff.changed.len(),
+        diff.removed.len(),
+        assets_updated
+    );
+    Ok(SignatureReloadResult {
+        total_count: diff.total,
+        added: diff.added,
+        changed: diff.changed,
+        removed: diff.removed,
+        assets_updated,
+    })
+}
+
+/// Test a YAML signature against the currently loaded PCAP data.
+///
+/// The frontend sends raw YAML text; we parse it, run it against
+/// all stored packet summaries' connection data, and return matches.
+#[tauri::command]
+pub fn test_signature(
+    yaml: String,
+    state: State<'_, AppState>,
+) -> Result<SignatureTestResult, String> {
+    let state_inner = state.inner.lock().map_err(|e| e.to_string())?;
+
+    // Build PacketData from stored connections for testing.
+    // We don't have full payload data in packet summaries (they're lightweight),
+    // so we create basic PacketData from connection info for filter testing.
+    let mut test_packets: Vec<PacketData> = Vec::new();
+
+    for conn in &state_inner.connections {
+        test_packets.push(PacketData {
+            src_ip: conn.src_ip.clone(),
+            dst_ip: conn.dst_ip.clone(),
+            src_port: conn.src_port,
+            dst_port: conn.dst_port,
+            src_mac: conn.src_mac.clone(),
+            dst_mac: conn.dst_mac.clone(),
+            transport: conn.transport.clone(),
+            protocol: conn.protocol.to_lowercase(),
+            payload: Vec::new(), // No payload in summaries
+            length: 0,
+            tls: None,
+        });
+    }
+
+    let results = state_inner
+        .signature_engine
+        .test_signature(&yaml, &test_packets)
+        .map_err(|e| e.to_string())?;
+
+    let matches: Vec<TestResultInfo> = results
+        .into_iter()
+        .map(|r| TestResultInfo {
+            packet_index: r.packet_index,
+            src_ip: r.src_ip,
+            dst_ip: r.dst_ip,
+            src_port: r.src_port,
+            dst_port: r.dst_port,
+            confidence: r.confidence,
+        })
+        .collect();
+
+    Ok(SignatureTestResult {
+        match_count: matches.len(),
+        matches,
+    })
+}
+
+/// Convert a GRASSMARLIN fingerprint XML file into YAML signatures.
+///
+/// Returns the YAML for each converted signature and the constructs that
+/// could not be converted. When `output_dir` is given, each signature is
+/// also written there as `<name>.yaml`, ready for `reload_signatures` once
+/// the directory is the signature directory.
+#[tauri::command]
+pub fn convert_grassmarlin_fingerprint(
+    path: String,
+    output_dir: Option<String>,
+) -> Result<FingerprintConversionResult, String> {
+    let xml =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let conversion = convert_grassmarlin_xml(&xml).map_err(|e| e.to_string())?;
+
+    let mut signatures = Vec::with_capacity(conversion.signatures.len());
+    for sig in &conversion.signatures {
+        signatures.push(ConvertedSignature {
+            name: sig.name.clone(),
+            yaml: signature_to_yaml(sig).map_err(|e| e.to_string())?,
+        });
+    }
+
+    let mut written = Vec::new();
+    if let Some(dir) = output_dir {
+        let dir = std::path::Path::new(&dir);
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        for sig in &signatures {
+            let file = dir.join(format!("{}.yaml", sig.name));
+            std::fs::write(&file, &sig.yaml).map_err(|e| e.to_string())?;
+            written.push(file.display().to_string());
+        }
+    }
+
+    log::info!(
+        "Converted GRASSMARLIN fingerprint '{}': {} signatures, {} unconverted constructs",
+        conversion.fingerprint,
+        signatures.len(),
+        conversion.unconverted.len()
+    );
+    Ok(FingerprintConversionResult {
+        fingerprint: conversion.fingerprint,
+        signatures,
+        unconverted: conversion.unconverted,
+        written,
+    })
+}
@@ -1,15 +1,41 @@
+#[cfg(feature = "gui")]
+use gm_capture::MAX_SNAPLEN;
+#[cfg(feature = "gui")]
+use gm_db::settings::SettingsExport;
+use gm_db::{
+    Database, DbError, NamedProfile, OpenMode, SettingsProfile, UserSettings, DEFAULT_PROFILE,
+};
+use gm_parsers::{reassembly, IcsProtocol};
+#[cfg(feature = "gui")]
+use gm_parsers::{FunctionCodeDef, FunctionCodeProtocol, FunctionCodeRegistry};
+use gm_physical::PhysicalTopology;
+use gm_topology::SubnetPlan;
+#[cfg(feature = "gui")]
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use tauri::State;
+
+#[cfg(feature = "gui")]
+use super::AppState;
+use super::AppStateInner;
+
+/// Largest kernel capture buffer a profile may ask for; libpcap takes the
+/// size in bytes as an `i32`.
+pub(crate) const MAX_BUFFER_SIZE_MB: u32 = 2047;
 
 /// List all available network interfaces.
 ///
 /// This is the Phase 0 deliverable — proof that the Rust backend
 /// is communicating with the SvelteKit frontend via Tauri IPC.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn list_interfaces() -> Result<Vec<gm_capture::NetworkInterface>, String> {
     gm_capture::list_interfaces().map_err(|e| e.to_string())
 }
 
+#[cfg(feature = "gui")]
 #[derive(Serialize)]
 pub struct AppInfo {
     version: String,
@@ -17,6 +43,7 @@ pub struct AppInfo {
 }
 
 /// Get application version info.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn get_app_info() -> AppInfo {
     AppInfo {
@@ -25,61 +52,477 @@ pub fn get_app_info() -> AppInfo {
     }
 }
 
+// ─── Database Location & Locking ────────────────────────────
+
+/// Default database location: ~/.kusanaginokajiki/data.db
+pub fn default_db_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".kusanaginokajiki").join("data.db"))
+}
+
+/// Open a database, falling back to read-only when another instance holds
+/// the writer lock. The second value explains a fallback to the user.
+pub fn open_database(path: &Path, mode: OpenMode) -> Result<(Database, Option<String>), DbError> {
+    match Database::open_with_mode(path, mode) {
+        Ok(db) => Ok((db, None)),
+        Err(DbError::Locked(msg)) => {
+            log::warn!("{}; opening read-only", msg);
+            let db = Database::open_with_mode(path, OpenMode::ReadOnly)?;
+            Ok((db, Some(format!("{}. Opened read-only.", msg))))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Location and mode of the open database.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseStatus {
+    /// False if no database could be opened (nothing is persisted)
+    pub available: bool,
+    pub path: Option<String>,
+    pub read_only: bool,
+    /// Why the database fell back to read-only, if it did
+    pub notice: Option<String>,
+}
+
+#[cfg(feature = "gui")]
+fn database_status(inner: &AppStateInner) -> DatabaseStatus {
+    DatabaseStatus {
+        available: inner.db.is_some(),
+        path: inner
+            .db
+            .as_ref()
+            .and_then(|db| db.path())
+            .map(|p| p.display().to_string()),
+        read_only: inner.db.as_ref().is_some_and(|db| db.is_read_only()),
+        notice: inner.db_notice.clone(),
+    }
+}
+
+/// Report which database is open and whether it is writable.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn get_database_status(state: State<'_, AppState>) -> Result<DatabaseStatus, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(database_status(&inner))
+}
+
+/// Switch to another database file (e.g. one per workspace), or back to
+/// the default when `path` is None.
+///
+/// The current database is closed first so reopening the same file in
+/// another mode works; if the new one fails to open, the previous one is
+/// reopened. The loaded session is detached from the old database.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn switch_database(
+    path: Option<String>,
+    read_only: bool,
+    state: State<'_, AppState>,
+) -> Result<DatabaseStatus, String> {
+    let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let target = match path.filter(|p| !p.trim().is_empty()) {
+        Some(p) => PathBuf::from(p.trim()),
+        None => default_db_path().ok_or("Could not determine home directory")?,
+    };
+    let mode = if read_only {
+        OpenMode::ReadOnly
+    } else {
+        OpenMode::ReadWrite
+    };
+
+    let previous = inner
+        .db
+        .take()
+        .and_then(|db| db.path().map(|p| (p.to_path_buf(), db.mode())));
+    let (db, notice) = match open_database(&target, mode) {
+        Ok(opened) => opened,
+        Err(e) => {
+            if let Some((prev_path, prev_mode)) = previous {
+                match open_database(&prev_path, prev_mode) {
+                    Ok((db, notice)) => {
+                        inner.db = Some(db);
+                        inner.db_notice = notice;
+                    }
+                    Err(e) => log::error!("Failed to reopen {}: {}", prev_path.display(), e),
+                }
+            }
+            return Err(format!("Failed to open {}: {}", target.display(), e));
+        }
+    };
+
+    inner.db = Some(db);
+    inner.db_notice = notice;
+    inner.current_session_id = None;
+    inner.current_session_name = None;
+    inner.current_project_id = None;
+    inner.settings = load_startup_settings(inner.db.as_ref());
+    log::info!("Switched database to {}", target.display());
+    Ok(database_status(&inner))
+}
+
 // ─── Settings Persistence (Phase 11) ────────────────────────
 
-/// Persistent user settings stored as JSON at ~/.kusanaginokajiki/settings.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserSettings {
-    /// Theme mode: "dark", "light", or "system"
-    #[serde(default = "default_theme")]
-    pub theme: String,
+/// Legacy settings file from before settings moved into the database.
+fn legacy_settings_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".kusanaginokajiki").join("settings.json"))
+}
+
+/// Copy GUI preferences from the legacy settings.json into the database,
+/// then rename the file so the migration runs once.
+fn migrate_legacy_settings(db: &Database) {
+    let Some(path) = legacy_settings_path().filter(|p| p.exists()) else {
+        return;
+    };
+    let migrated = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|c| serde_json::from_str::<UserSettings>(&c).map_err(|e| e.to_string()))
+        .and_then(|user| db.save_user_settings(&user).map_err(|e| e.to_string()));
+    match migrated {
+        Ok(()) => {
+            let _ = std::fs::rename(&path, path.with_extension("json.migrated"));
+            log::info!("Migrated settings from {}", path.display());
+        }
+        Err(e) => log::warn!("Failed to migrate {}: {}", path.display(), e),
+    }
 }
 
-fn default_theme() -> String {
-    "dark".to_string()
+/// Load the active settings profile at startup and apply its retention
+/// policy and function code files. Falls back to built-in defaults when the
+/// database is unavailable.
+pub fn load_startup_settings(db: Option<&Database>) -> NamedProfile {
+    let active = read_startup_settings(db);
+    apply_function_codes(&active.profile);
+    active
 }
 
-impl Default for UserSettings {
-    fn default() -> Self {
-        Self {
-            theme: default_theme(),
+fn read_startup_settings(db: Option<&Database>) -> NamedProfile {
+    let fallback = || NamedProfile {
+        name: DEFAULT_PROFILE.to_string(),
+        profile: SettingsProfile::default(),
+        updated_at: String::new(),
+    };
+    let Some(db) = db else {
+        return fallback();
+    };
+    if !db.is_read_only() {
+        migrate_legacy_settings(db);
+    }
+
+    let active = match db.active_settings_profile() {
+        Ok(active) => active,
+        Err(e) => {
+            log::warn!("Failed to load settings profile: {}", e);
+            return fallback();
         }
+    };
+    if db.is_read_only() {
+        log::info!("Database is read-only; retention policy not applied");
+    } else if let Err(e) = db.apply_retention(&active.profile.retention) {
+        log::warn!("Failed to apply retention policy: {}", e);
     }
+    log::info!("Settings profile '{}' active", active.name);
+    active
 }
 
-/// Get the settings file path.
-fn settings_path() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
-    Ok(home.join(".kusanaginokajiki").join("settings.json"))
+/// Custom port → protocol assignments from a profile, for the packet processor.
+pub fn port_map(profile: &SettingsProfile) -> HashMap<u16, IcsProtocol> {
+    profile
+        .port_maps
+        .iter()
+        .map(|m| (m.port, IcsProtocol::from_name(&m.protocol)))
+        .filter(|(_, p)| *p != IcsProtocol::Unknown)
+        .collect()
+}
+
+/// Subnets to group topology nodes by: the profile's CIDRs, then the
+/// addresses and masks of switch interfaces (SVIs) from imported configs.
+/// Entries that do not parse are skipped with a warning.
+pub fn subnet_plan(profile: &SettingsProfile, physical: &PhysicalTopology) -> SubnetPlan {
+    let mut plan = SubnetPlan::new();
+    for cidr in &profile.subnets {
+        if let Err(e) = plan.add_cidr(cidr) {
+            log::warn!("Subnet ignored: {}", e);
+        }
+    }
+    let interfaces = physical.switches.iter().flat_map(|s| &s.ports);
+    for port in interfaces {
+        if let (Some(ip), Some(mask)) = (&port.ip_address, &port.subnet_mask) {
+            if let Err(e) = plan.add_interface(ip, mask) {
+                log::warn!("Interface {} subnet ignored: {}", port.name, e);
+            }
+        }
+    }
+    plan
+}
+
+/// TCP ports gm-capture reassembles into in-order streams: the well-known
+/// ports of length-framed protocols, plus custom ports mapped to one.
+pub fn tcp_reassembly_ports(port_map: &HashMap<u16, IcsProtocol>) -> Vec<u16> {
+    let mut ports = reassembly::STREAM_PORTS.to_vec();
+    ports.extend(
+        port_map
+            .iter()
+            .filter(|(_, protocol)| reassembly::framing_for(**protocol).is_some())
+            .map(|(port, _)| *port),
+    );
+    ports
+}
+
+/// Per-protocol deep-parse budgets from a profile, for live capture.
+pub fn deep_parse_budgets(profile: &SettingsProfile) -> HashMap<IcsProtocol, u32> {
+    profile
+        .capture
+        .deep_parse_budgets
+        .iter()
+        .map(|b| (IcsProtocol::from_name(&b.protocol), b.max_per_second))
+        .filter(|(p, _)| *p != IcsProtocol::Unknown)
+        .collect()
+}
+
+/// Check that a YAML function code file can be read and parsed.
+#[cfg(feature = "gui")]
+fn check_function_code_file(path: &str) -> Result<(), String> {
+    let yaml = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    FunctionCodeRegistry::default()
+        .load_yaml(&yaml)
+        .map(|_| ())
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Reset the function code registry to the built-in table plus the
+/// profile's function code files. A file that has gone missing since the
+/// profile was saved is skipped with a warning.
+pub fn apply_function_codes(profile: &SettingsProfile) {
+    gm_parsers::reset_function_codes();
+    for path in &profile.function_code_files {
+        let loaded = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|yaml| {
+                gm_parsers::register_function_codes_yaml(&yaml).map_err(|e| e.to_string())
+            });
+        match loaded {
+            Ok(count) => log::info!("Registered {} function code(s) from {}", count, path),
+            Err(e) => log::warn!("Function code file {} not loaded: {}", path, e),
+        }
+    }
 }
 
-/// Load user settings from disk. Returns defaults if file doesn't exist.
+/// Reject profiles that would be unusable at runtime.
+#[cfg(feature = "gui")]
+fn validate_profile(profile: &SettingsProfile) -> Result<(), String> {
+    for mapping in &profile.port_maps {
+        if IcsProtocol::from_name(&mapping.protocol) == IcsProtocol::Unknown {
+            return Err(format!(
+                "Port {}: unknown protocol '{}'",
+                mapping.port, mapping.protocol
+            ));
+        }
+    }
+    for budget in &profile.capture.deep_parse_budgets {
+        if IcsProtocol::from_name(&budget.protocol) == IcsProtocol::Unknown {
+            return Err(format!(
+                "Deep-parse budget: unknown protocol '{}'",
+                budget.protocol
+            ));
+        }
+    }
+    for path in &profile.function_code_files {
+        check_function_code_file(path)?;
+    }
+    let mut subnets = SubnetPlan::new();
+    for cidr in &profile.subnets {
+        subnets
+            .add_cidr(cidr)
+            .map_err(|e| format!("Subnet {}", e))?;
+    }
+    if profile.traffic_bucket_secs == 0 {
+        return Err("Traffic bucket width must be positive".to_string());
+    }
+    if profile.capture.ring_buffer_size == 0 || profile.capture.snaplen <= 0 {
+        return Err("Capture ring buffer size and snaplen must be positive".to_string());
+    }
+    if profile.capture.queue_capacity == 0 {
+        return Err("Capture processing queue capacity must be positive".to_string());
+    }
+    if profile.capture.snaplen > MAX_SNAPLEN {
+        return Err(format!(
+            "Capture snaplen must be at most {} bytes",
+            MAX_SNAPLEN
+        ));
+    }
+    if profile.capture.buffer_size_mb > MAX_BUFFER_SIZE_MB {
+        return Err(format!(
+            "Capture buffer size must be at most {} MB",
+            MAX_BUFFER_SIZE_MB
+        ));
+    }
+    Ok(())
+}
+
+/// Make a profile active: persist the choice, apply its retention policy,
+/// and use it for subsequent captures, imports and analysis runs.
+pub fn activate_profile(inner: &mut AppStateInner, name: &str) -> Result<NamedProfile, String> {
+    let db = inner.db.as_ref().ok_or("Database not available")?;
+    let active = db
+        .activate_settings_profile(name)
+        .map_err(|e| e.to_string())?;
+    let removed = db
+        .apply_retention(&active.profile.retention)
+        .map_err(|e| e.to_string())?;
+    log::info!(
+        "Settings profile '{}' activated ({} session(s) removed by retention)",
+        active.name,
+        removed
+    );
+    apply_function_codes(&active.profile);
+    inner.settings = active.clone();
+    Ok(active)
+}
+
+/// Load GUI preferences. Returns defaults if the database is unavailable.
+#[cfg(feature = "gui")]
 #[tauri::command]
-pub fn get_settings() -> Result<UserSettings, String> {
-    let path = settings_path()?;
-    if !path.exists() {
-        return Ok(UserSettings::default());
+pub fn get_settings(state: State<'_, AppState>) -> Result<UserSettings, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    match inner.db.as_ref() {
+        Some(db) => db.get_user_settings().map_err(|e| e.to_string()),
+        None => Ok(UserSettings::default()),
     }
-    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&content).map_err(|e| e.to_string())
 }
 
-/// Save user settings to disk.
+/// Save GUI preferences.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn save_settings(settings: UserSettings, state: State<'_, AppState>) -> Result<(), String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = inner.db.as_ref().ok_or("Database not available")?;
+    db.save_user_settings(&settings).map_err(|e| e.to_string())
+}
+
+/// List all settings profiles.
+#[cfg(feature = "gui")]
 #[tauri::command]
-pub fn save_settings(settings: UserSettings) -> Result<(), String> {
-    let path = settings_path()?;
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    std::fs::write(&path, content).map_err(|e| e.to_string())?;
-    log::info!("Settings saved to {}", path.display());
+pub fn list_settings_profiles(state: State<'_, AppState>) -> Result<Vec<NamedProfile>, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = inner.db.as_ref().ok_or("Database not available")?;
+    db.list_settings_profiles().map_err(|e| e.to_string())
+}
+
+/// Get the profile currently in effect.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn get_active_settings_profile(state: State<'_, AppState>) -> Result<NamedProfile, String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    Ok(inner.settings.clone())
+}
+
+/// Create or replace a settings profile. Saving the active profile takes
+/// effect immediately.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn save_settings_profile(
+    name: String,
+    profile: SettingsProfile,
+    state: State<'_, AppState>,
+) -> Result<NamedProfile, String> {
+    validate_profile(&profile)?;
+    let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = inner.db.as_ref().ok_or("Database not available")?;
+    let saved = db
+        .save_settings_profile(&name, &profile)
+        .map_err(|e| e.to_string())?;
+    if saved.name == inner.settings.name {
+        apply_function_codes(&saved.profile);
+        inner.settings = saved.clone();
+    }
+    Ok(saved)
+}
+
+/// Delete a settings profile (not the default or the active one).
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn delete_settings_profile(name: String, state: State<'_, AppState>) -> Result<(), String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = inner.db.as_ref().ok_or("Database not available")?;
+    db.delete_settings_profile(&name).map_err(|e| e.to_string())
+}
+
+/// Switch to another settings profile at runtime.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn activate_settings_profile(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<NamedProfile, String> {
+    let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
+    activate_profile(&mut inner, &name)
+}
+
+/// Export settings profiles to a JSON file for deployment on other
+/// sensors. Exports every profile when `names` is empty or omitted.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn export_settings_profiles(
+    path: String,
+    names: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = inner.db.as_ref().ok_or("Database not available")?;
+    let json = db
+        .export_settings_profiles(&names.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    log::info!("Settings profiles exported to {}", path);
     Ok(())
 }
 
+/// Import settings profiles from an exported JSON file, replacing
+/// profiles with the same names. Returns the imported names.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn import_settings_profiles(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let export: SettingsExport = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    for named in &export.profiles {
+        validate_profile(&named.profile).map_err(|e| format!("{}: {}", named.name, e))?;
+    }
+
+    let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
+    let db = inner.db.as_ref().ok_or("Database not available")?;
+    let imported = db
+        .import_settings_profiles(&content)
+        .map_err(|e| e.to_string())?;
+    if imported.contains(&inner.settings.name) {
+        let name = inner.settings.name.clone();
+        activate_profile(&mut inner, &name)?;
+    }
+    log::info!(
+        "Imported {} settings profile(s) from {}",
+        imported.len(),
+        path
+    );
+    Ok(imported)
+}
+
+/// Function codes currently known for a protocol ("modbus" or "dnp3"):
+/// the built-in table plus the active profile's function code files.
+#[cfg(feature = "gui")]
+#[tauri::command]
+pub fn list_function_codes(protocol: String) -> Result<Vec<FunctionCodeDef>, String> {
+    let protocol = FunctionCodeProtocol::from_name(&protocol)
+        .ok_or_else(|| format!("No function code registry for '{}'", protocol))?;
+    Ok(gm_parsers::list_function_codes(protocol))
+}
+
 // ─── Plugin Discovery (Phase 11) ────────────────────────────
 
 /// A plugin manifest describing a plugin pack.
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginManifest {
     pub name: String,
@@ -93,6 +536,7 @@ pub struct PluginManifest {
 /// List plugins found in the plugins directory.
 ///
 /// Scans ~/.kusanaginokajiki/plugins/ for manifest.json files.
+#[cfg(feature = "gui")]
 #[tauri::command]
 pub fn list_plugins() -> Result<Vec<PluginManifest>, String> {
     let home = dirs::home_dir().ok_or("Could not determine home directory")?;
@@ -0,0 +1,80 @@
+//! Shared fixtures for command tests.
+
+use gm_capture::{tcp_flags, ParsedPacket, TransportProtocol};
+#[cfg(feature = "gui")]
+use gm_db::OpenMode;
+
+#[cfg(feature = "gui")]
+use super::processor::PacketProcessor;
+#[cfg(feature = "gui")]
+use super::{AppState, AppStateInner};
+
+/// A TCP segment from `src:src_port` to `dst:dst_port` carrying `payload`.
+pub(crate) fn tcp_packet(
+    src: &str,
+    src_port: u16,
+    dst: &str,
+    dst_port: u16,
+    payload: &[u8],
+) -> ParsedPacket {
+    ParsedPacket {
+        timestamp: chrono::Utc::now(),
+        src_mac: None,
+        dst_mac: None,
+        vlan_id: None,
+        outer_vlan_id: None,
+        mpls_labels: Vec::new(),
+        vxlan_vni: None,
+        src_ip: src.to_string(),
+        dst_ip: dst.to_string(),
+        transport: TransportProtocol::Tcp,
+        src_port,
+        dst_port,
+        tcp_flags: tcp_flags::PSH | tcp_flags::ACK,
+        tcp_seq: 1,
+        length: 54 + payload.len(),
+        payload: payload.to_vec(),
+        origin_file: "test.pcap".to_string(),
+    }
+}
+
+/// Fresh application state over a throwaway database.
+#[cfg(feature = "gui")]
+pub(crate) fn empty_state() -> AppStateInner {
+    let dir = std::env::temp_dir().join(format!("kkj-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    AppState::new(None, Some(&dir.join("data.db")), OpenMode::ReadWrite)
+        .inner
+        .into_inner()
+        .unwrap()
+}
+
+/// State holding the assets, connections and topology built from
+/// `packets`, as after a PCAP import.
+#[cfg(feature = "gui")]
+pub(crate) fn imported_state(packets: &[ParsedPacket]) -> AppStateInner {
+    let mut inner = empty_state();
+    let mut processor = PacketProcessor::new();
+    for packet in packets {
+        processor.process_packet(packet);
+    }
+    let deep_parse_info = processor.build_deep_parse_info();
+    let (assets, _) = processor.build_assets(
+        &inner.signature_engine,
+        &deep_parse_info,
+        &inner.oui_lookup,
+        &inner.geoip_lookup,
+    );
+    inner.topology = processor.topo_builder.snapshot();
+    inner.assets = assets;
+    inner.connections = processor.get_connections();
+    inner.deep_parse_info = deep_parse_info;
+    inner
+}
+
+/// Switch demo mode on with a fixed seed.
+#[cfg(feature = "gui")]
+pub(crate) fn enable_demo_mode(inner: &mut AppStateInner) {
+    inner.demo_mode = true;
+    inner.demo_seed = Some(42);
+}
@@ -0,0 +1,233 @@
+//! Opt-in processing pipeline trace.
+//!
+//! With tracing on, an import writes one JSON line per packet saying how
+//! its protocol was identified, which deep parser handled it and which
+//! accumulators it fed. After assets are built it adds one line per device
+//! with the identity sources that were available and, for each signature
+//! that got past its first filter, whether it matched or which filter it
+//! failed on. This is what a user attaches to a "why wasn't my PLC
+//! identified" report.
+//!
+//! Packet lines stop once the file reaches its size cap (the count of
+//! dropped lines is kept); device lines are always written, since there is
+//! only one per asset and they are the part that answers the question.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use gm_capture::ParsedPacket;
+#[cfg(feature = "gui")]
+use gm_signatures::SignatureExplanation;
+
+/// Size cap for packet events in a trace file.
+#[cfg(feature = "gui")]
+pub const TRACE_LIMIT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// One line of the trace file.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TraceEvent {
+    Packet(PacketTrace),
+    #[cfg(feature = "gui")]
+    Device(DeviceTrace),
+}
+
+/// What the pipeline did with one packet.
+#[derive(Debug, Serialize)]
+pub struct PacketTrace {
+    /// 1-based position in the import, across all files
+    pub index: u64,
+    pub timestamp: String,
+    pub origin_file: String,
+    pub src: String,
+    pub dst: String,
+    pub src_port: u16,
+    pub dst_port: u16,
+    pub transport: String,
+    pub protocol: String,
+    /// "port", "port_map", "payload_probe", "l2_frame" or "unidentified"
+    pub identified_by: &'static str,
+    /// Deep parser that produced a result, if any
+    pub deep_parse: Option<&'static str>,
+    /// Deep parsing was skipped by a per-protocol budget
+    pub deep_parse_skipped: bool,
+    /// Accumulators updated by this packet
+    pub accumulators: Vec<&'static str>,
+}
+
+/// How one device ended up identified.
+#[cfg(feature = "gui")]
+#[derive(Debug, Serialize)]
+pub struct DeviceTrace {
+    pub ip: String,
+    pub device_type: String,
+    pub confidence: u8,
+    pub vendor: Option<String>,
+    pub product_family: Option<String>,
+    /// Identity evidence available for the device (deep parse identity,
+    /// server roles, OUI, LLDP, SNMP, engineering software)
+    pub identity_sources: Vec<String>,
+    /// Signatures that matched or got past their first filter
+    pub signatures: Vec<SignatureExplanation>,
+}
+
+/// Where a trace was written and how much of it was kept.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceSummary {
+    pub path: String,
+    pub events: u64,
+    /// Packet events not written because the size cap was reached
+    pub dropped: u64,
+    pub bytes: u64,
+}
+
+/// Per-packet notes collected while the packet moves through the pipeline.
+#[derive(Debug, Default)]
+struct PacketNotes {
+    protocol: Option<String>,
+    identified_by: Option<&'static str>,
+    deep_parse: Option<&'static str>,
+    deep_parse_skipped: bool,
+    accumulators: Vec<&'static str>,
+}
+
+/// Bounded JSON-lines trace writer.
+pub struct PipelineTrace {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    limit: u64,
+    written: u64,
+    events: u64,
+    dropped: u64,
+    packet_index: u64,
+    notes: PacketNotes,
+}
+
+impl PipelineTrace {
+    /// Create a new trace file under `~/.kusanaginokajiki/traces/`.
+    #[cfg(feature = "gui")]
+    pub fn create(limit: u64) -> Result<Self, String> {
+        let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+        let dir = home.join(".kusanaginokajiki").join("traces");
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let name = format!(
+            "trace-{}.jsonl",
+            chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+        );
+        let path = dir.join(name);
+        let file = File::create(&path).map_err(|e| e.to_string())?;
+        Ok(Self {
+            path,
+            writer: Some(BufWriter::new(file)),
+            limit,
+            written: 0,
+            events: 0,
+            dropped: 0,
+            packet_index: 0,
+            notes: PacketNotes::default(),
+        })
+    }
+
+    /// Record how the current packet's protocol was identified.
+    pub fn set_protocol(&mut self, protocol: String, identified_by: &'static str) {
+        self.notes.protocol = Some(protocol);
+        self.notes.identified_by = Some(identified_by);
+    }
+
+    /// Record the deep parser that handled the current packet.
+    pub fn set_deep_parse(&mut self, parser: &'static str) {
+        self.notes.deep_parse = Some(parser);
+    }
+
+    /// Record that a deep-parse budget skipped the current packet.
+    pub fn set_deep_parse_skipped(&mut self) {
+        self.notes.deep_parse_skipped = true;
+    }
+
+    /// Record an accumulator updated by the current packet.
+    pub fn note(&mut self, accumulator: &'static str) {
+        if !self.notes.accumulators.contains(&accumulator) {
+            self.notes.accumulators.push(accumulator);
+        }
+    }
+
+    /// Write the event for a packet that has left the pipeline.
+    pub fn end_packet(&mut self, packet: &ParsedPacket) {
+        self.packet_index += 1;
+        let notes = std::mem::take(&mut self.notes);
+        if self.written >= self.limit {
+            self.dropped += 1;
+            return;
+        }
+        let event = TraceEvent::Packet(PacketTrace {
+            index: self.packet_index,
+            timestamp: packet.timestamp.to_rfc3339(),
+            origin_file: packet.origin_file.clone(),
+            src: packet.src_ip.clone(),
+            dst: packet.dst_ip.clone(),
+            src_port: packet.src_port,
+            dst_port: packet.dst_port,
+            transport: format!("{:?}", packet.transport).to_lowercase(),
+            protocol: notes.protocol.unwrap_or_else(|| "Unknown".to_string()),
+            identified_by: notes.identified_by.unwrap_or("l2_frame"),
+            deep_parse: notes.deep_parse,
+            deep_parse_skipped: notes.deep_parse_skipped,
+            accumulators: notes.accumulators,
+        });
+        self.write(&event);
+    }
+
+    /// Write a device event (not subject to the size cap).
+    #[cfg(feature = "gui")]
+    pub fn device(&mut self, device: DeviceTrace) {
+        self.write(&TraceEvent::Device(device));
+    }
+
+    /// Flush the file and report what was written.
+    #[cfg(feature = "gui")]
+    pub fn finish(mut self) -> TraceSummary {
+        if let Some(mut writer) = self.writer.take() {
+            if let Err(e) = writer.flush() {
+                log::warn!("Failed to flush trace {}: {}", self.path.display(), e);
+            }
+        }
+        log::info!(
+            "Pipeline trace: {} events ({} dropped) → {}",
+            self.events,
+            self.dropped,
+            self.path.display()
+        );
+        TraceSummary {
+            path: self.path.to_string_lossy().into_owned(),
+            events: self.events,
+            dropped: self.dropped,
+            bytes: self.written,
+        }
+    }
+
+    fn write(&mut self, event: &TraceEvent) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        let mut line = match serde_json::to_vec(event) {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Failed to serialize trace event: {}", e);
+                return;
+            }
+        };
+        line.push(b'\n');
+        if let Err(e) = writer.write_all(&line) {
+            // Stop tracing rather than failing the import
+            log::warn!("Trace write to {} failed: {}", self.path.display(), e);
+            self.writer = None;
+            return;
+        }
+        self.written += line.len() as u64;
+        self.events += 1;
+    }
+}
@@ -36,6 +36,11 @@ pub struct FrameRow {
     pub origin_file: String,
 }
 
+/// Wireshark reads the capture files directly, so the demo-mode pseudonyms
+/// cannot be applied and opening a connection is refused instead.
+const DEMO_MODE_WIRESHARK_ERROR: &str =
+    "Opening in Wireshark is unavailable in demo mode: capture files hold the real addresses";
+
 /// Detect Wireshark installation path.
 ///
 /// Searches well-known locations on Linux, macOS, and Windows.
@@ -62,6 +67,7 @@ pub async fn detect_wireshark() -> Result<WiresharkInfo, String> {
 ///
 /// If origin PCAP files are available, opens them in Wireshark
 /// with a filter matching the connection's endpoints and ports.
+/// Refused in demo mode.
 #[tauri::command]
 pub async fn open_in_wireshark(
     connection_id: String,
@@ -74,6 +80,9 @@ pub async fn open_in_wireshark(
     // Get connection info and build display filter
     let (filter, pcap_files) = {
         let inner = state.inner.lock().map_err(|e| e.to_string())?;
+        if super::demo::active_anonymizer(&inner).is_some() {
+            return Err(DEMO_MODE_WIRESHARK_ERROR.to_string());
+        }
         let conn = inner
             .connections
             .iter()
@@ -147,11 +156,7 @@ pub async fn get_connection_frames(
 ) -> Result<Vec<FrameRow>, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
 
-    let packets = inner
-        .packet_summaries
-        .get(&connection_id)
-        .cloned()
-        .unwrap_or_default();
+    let packets = connection_packets(&inner, &connection_id);
 
     let frames: Vec<FrameRow> = packets
         .iter()
@@ -180,11 +185,7 @@ pub async fn export_frames_csv(
 ) -> Result<String, String> {
     let inner = state.inner.lock().map_err(|e| e.to_string())?;
 
-    let packets = inner
-        .packet_summaries
-        .get(&connection_id)
-        .cloned()
-        .unwrap_or_default();
+    let packets = connection_packets(&inner, &connection_id);
 
     let mut csv =
         String::from("No,Timestamp,Source,SrcPort,Destination,DstPort,Protocol,Length,File\n");
@@ -221,6 +222,24 @@ pub async fn save_frames_csv(
 
 // ── Internal helpers ─────────────────────────────────────────
 
+/// Packet summaries recorded for a connection, pseudonymized in demo mode.
+fn connection_packets(
+    inner: &super::AppStateInner,
+    connection_id: &str,
+) -> Vec<super::PacketSummary> {
+    let mut packets = inner
+        .packet_summaries
+        .get(connection_id)
+        .cloned()
+        .unwrap_or_default();
+    if let Some(anon) = super::demo::active_anonymizer(inner) {
+        for pkt in &mut packets {
+            super::demo::anonymize_packet_summary(&anon, pkt);
+        }
+    }
+    packets
+}
+
 /// Build a Wireshark display filter for a connection.
 fn build_display_filter(conn: &super::ConnectionInfo) -> String {
     let mut parts = Vec::new();
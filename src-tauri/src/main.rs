@@ -1,10 +1,16 @@
-#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+#![cfg_attr(
+    all(not(debug_assertions), feature = "gui"),
+    windows_subsystem = "windows"
+)]
 
 use clap::Parser;
+#[cfg(feature = "gui")]
 use std::sync::Mutex;
+#[cfg(feature = "gui")]
 use tauri::Manager;
 
 mod commands;
+mod sensor;
 
 /// Kusanagi Kajiki — Modern ICS/SCADA passive network discovery tool
 #[derive(Parser, Debug, Clone)]
@@ -17,14 +23,121 @@ struct Cli {
     /// Import a PCAP file directly on startup
     #[arg(long = "import-pcap")]
     import_pcap: Option<String>,
+
+    /// Activate a named settings profile on startup (e.g. "low-memory sensor")
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Use this database file instead of ~/.kusanaginokajiki/data.db
+    /// (e.g. one database per workspace)
+    #[arg(long)]
+    db: Option<std::path::PathBuf>,
+
+    /// Open the database read-only, alongside another instance that is writing to it
+    #[arg(long = "read-only")]
+    read_only: bool,
+
+    /// Write a pipeline trace for the startup PCAP import (for bug reports)
+    #[arg(long)]
+    trace: bool,
+
+    /// Run headless as a capture sensor on this interface (no window),
+    /// checkpointing the dataset as a session until stopped. A remote
+    /// sensor's interface is given as ssh://[user@]host[:port]/IFACE or
+    /// rpcap://host[:port]/IFACE
+    #[arg(long, value_name = "INTERFACE")]
+    sensor: Option<String>,
+
+    /// BPF filter for --sensor (e.g. "not port 22")
+    #[arg(long, requires = "sensor")]
+    bpf: Option<String>,
+
+    /// Session name for --sensor (default: "Sensor <interface> <start time>")
+    #[arg(long = "session-name", requires = "sensor")]
+    session_name: Option<String>,
+
+    /// Minutes between session checkpoints in --sensor mode
+    #[arg(
+        long = "checkpoint-minutes",
+        requires = "sensor",
+        default_value_t = 15,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    checkpoint_minutes: u64,
+
+    /// Forward live alerts in --sensor mode to a syslog collector (host:port, UDP)
+    #[arg(long = "alert-syslog", value_name = "HOST:PORT", requires = "sensor")]
+    alert_syslog: Option<String>,
+
+    /// Also write raw packets in --sensor mode to rotating PCAP files in this
+    /// directory (file size, age and count limits come from the profile)
+    #[arg(long = "capture-dir", value_name = "DIR", requires = "sensor")]
+    capture_dir: Option<String>,
 }
 
 fn main() {
-    env_logger::init();
-
     // Parse CLI arguments before Tauri takes over
     let cli = Cli::parse();
 
+    // A headless sensor has no other way to say what it is doing
+    let default_level = if cli.sensor.is_some() {
+        "info"
+    } else {
+        "error"
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_level))
+        .init();
+
+    #[cfg(feature = "gui")]
+    let context = tauri::generate_context!();
+
+    if let Some(interface) = cli.sensor.clone() {
+        log::info!(
+            "Kusanagi Kajiki v{} starting in sensor mode",
+            env!("CARGO_PKG_VERSION")
+        );
+        #[cfg(feature = "gui")]
+        let resource_dir =
+            tauri::utils::platform::resource_dir(context.package_info(), &tauri::Env::default())
+                .ok();
+        // A sensor-only build has no app bundle; datasets sit beside the binary
+        #[cfg(not(feature = "gui"))]
+        let resource_dir = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(std::path::Path::to_path_buf));
+        let state = commands::AppState::new(
+            resource_dir.as_deref(),
+            cli.db.as_deref(),
+            gm_db::OpenMode::ReadWrite,
+        );
+        let config = sensor::SensorConfig {
+            interface,
+            bpf_filter: cli.bpf.clone(),
+            profile: cli.profile.clone(),
+            session_name: cli.session_name.clone(),
+            checkpoint_interval: std::time::Duration::from_secs(cli.checkpoint_minutes * 60),
+            alert_syslog: cli.alert_syslog.clone(),
+            capture_dir: cli.capture_dir.clone(),
+        };
+        if let Err(e) = sensor::run(&config, &state) {
+            log::error!("Sensor: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    #[cfg(feature = "gui")]
+    run_app(cli, context);
+    #[cfg(not(feature = "gui"))]
+    {
+        eprintln!("This build has no desktop app (built without the \"gui\" feature); run it with --sensor <INTERFACE>");
+        std::process::exit(2);
+    }
+}
+
+/// Start the desktop app.
+#[cfg(feature = "gui")]
+fn run_app(cli: Cli, context: tauri::Context<tauri::Wry>) {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
@@ -32,7 +145,17 @@ fn main() {
             log::info!("Kusanagi Kajiki v{} starting", env!("CARGO_PKG_VERSION"));
 
             // Initialize application state
-            app.manage(commands::AppState::new());
+            let resource_dir = app.path().resource_dir().ok();
+            let db_mode = if cli.read_only {
+                gm_db::OpenMode::ReadOnly
+            } else {
+                gm_db::OpenMode::ReadWrite
+            };
+            app.manage(commands::AppState::new(
+                resource_dir.as_deref(),
+                cli.db.as_deref(),
+                db_mode,
+            ));
 
             // Store CLI args for deferred processing after window is ready
             app.manage(CliArgs(Mutex::new(cli)));
@@ -52,12 +175,24 @@ fn main() {
                     }
                 };
 
+                if let Some(ref name) = args.profile {
+                    let state = app_handle.state::<commands::AppState>();
+                    if let Ok(mut inner) = state.inner.lock() {
+                        match commands::system::activate_profile(&mut inner, name) {
+                            Ok(_) => log::info!("CLI: activated settings profile '{}'", name),
+                            Err(e) => {
+                                log::error!("CLI: failed to activate profile '{}': {}", name, e)
+                            }
+                        }
+                    };
+                }
+
                 if let Some(ref path) = args.import_pcap {
                     log::info!("CLI: importing PCAP from {}", path);
                     let state = app_handle.state::<commands::AppState>();
                     let inner = state.inner.lock();
                     if let Ok(mut inner) = inner {
-                        match import_pcap_file(path, &mut inner) {
+                        match import_pcap_file(path, args.trace, &mut inner) {
                             Ok(count) => {
                                 log::info!("CLI: imported {} packets from {}", count, path)
                             }
@@ -73,7 +208,7 @@ fn main() {
                         let state = app_handle.state::<commands::AppState>();
                         let inner = state.inner.lock();
                         if let Ok(mut inner) = inner {
-                            match import_pcap_file(path, &mut inner) {
+                            match import_pcap_file(path, args.trace, &mut inner) {
                                 Ok(count) => {
                                     log::info!("CLI: imported {} packets from {}", count, path)
                                 }
@@ -92,9 +227,20 @@ fn main() {
             commands::system::get_app_info,
             commands::system::get_settings,
             commands::system::save_settings,
+            commands::system::list_settings_profiles,
+            commands::system::get_active_settings_profile,
+            commands::system::save_settings_profile,
+            commands::system::delete_settings_profile,
+            commands::system::activate_settings_profile,
+            commands::system::export_settings_profiles,
+            commands::system::import_settings_profiles,
+            commands::system::list_function_codes,
             commands::system::list_plugins,
+            commands::system::get_database_status,
+            commands::system::switch_database,
             // Capture / Import
             commands::capture::import_pcap,
+            commands::capture::import_pcap_directory,
             commands::capture::cancel_import,
             // Live Capture
             commands::capture::start_capture,
@@ -104,18 +250,31 @@ fn main() {
             commands::capture::get_capture_status,
             // Data queries
             commands::data::get_topology,
+            commands::data::find_topology_path,
+            commands::data::get_reachability,
+            commands::data::get_topology_window,
+            commands::data::get_vlan_groups,
+            commands::data::get_top_talkers,
+            commands::data::merge_topology_devices,
             commands::data::get_assets,
             commands::data::get_connections,
             commands::data::get_data_counts,
             commands::data::get_protocol_stats,
             commands::data::get_connection_packets,
             commands::data::get_deep_parse_info,
+            commands::data::get_register_map,
             commands::data::get_function_code_stats,
             commands::data::get_timeline_range,
+            // Demo / anonymized mode
+            commands::demo::get_demo_mode,
+            commands::demo::set_demo_mode,
+            // Bundled reference data
+            commands::datasets::get_data_status,
             // Signatures
             commands::signatures::get_signatures,
             commands::signatures::reload_signatures,
             commands::signatures::test_signature,
+            commands::signatures::convert_grassmarlin_fingerprint,
             // Sessions & Asset Updates (Phase 6)
             commands::session::save_session,
             commands::session::load_session,
@@ -147,7 +306,9 @@ fn main() {
             commands::ingest::import_wazuh_alerts,
             commands::ingest::import_sinema_csv,
             commands::ingest::import_tia_xml,
+            commands::ingest::import_windows_events,
             commands::ingest::get_device_zeek_events,
+            commands::ingest::get_host_events,
             // Alert Correlation (Phase 14D)
             commands::correlation::get_correlated_alerts,
             commands::correlation::get_alerts_for_ip,
@@ -160,15 +321,13 @@ fn main() {
             commands::wireshark::export_frames_csv,
             commands::wireshark::save_frames_csv,
             // Export & Reporting (Phase 9)
-            commands::export::export_assets_csv,
-            commands::export::export_connections_csv,
-            commands::export::export_topology_json,
-            commands::export::export_assets_json,
-            commands::export::generate_pdf_report,
-            commands::export::export_sbom,
-            commands::export::export_stix_bundle,
+            commands::export::list_export_formats,
+            commands::export::export,
+            commands::export::export_netbox_csv,
+            commands::export::push_to_netbox,
             commands::export::save_topology_image,
             commands::export::export_filtered_pcap,
+            commands::export::export_connection_pcap,
             // Communication Allowlist (Phase 14E)
             commands::export::generate_communication_allowlist,
             commands::export::export_allowlist_csv,
@@ -182,6 +341,11 @@ fn main() {
             commands::analysis::get_criticality,
             commands::analysis::get_naming_suggestions,
             commands::analysis::get_switch_security_findings,
+            // Write-path approvals
+            commands::analysis::get_write_paths,
+            commands::analysis::list_write_approvals,
+            commands::analysis::approve_write_path,
+            commands::analysis::revoke_write_approval,
             // ICS Malware Signatures + Compliance (Phase 14E)
             commands::analysis::get_malware_findings,
             commands::analysis::get_compliance_report,
@@ -190,7 +354,16 @@ fn main() {
             // Communication Pattern Analysis
             commands::patterns::get_connection_stats,
             commands::patterns::get_pattern_anomalies,
+            commands::patterns::get_shift_report,
+            commands::patterns::get_protocol_sessions,
             commands::patterns::get_redundancy_protocols,
+            commands::patterns::get_arp_bindings,
+            commands::patterns::get_icmp_activity,
+            commands::patterns::get_goose_streams,
+            commands::patterns::get_sv_streams,
+            commands::patterns::get_profinet_rt_streams,
+            // External (cloud / remote-access) connectivity
+            commands::connectivity::get_external_connectivity,
             // Project Management
             commands::projects::create_project,
             commands::projects::list_projects,
@@ -202,25 +375,52 @@ fn main() {
             // Microsegmentation (Phase 15)
             commands::segmentation::run_segmentation,
             commands::segmentation::export_enforcement_config,
+            commands::segmentation::get_sl_gap_worksheet,
+            commands::segmentation::export_sl_gap_worksheet,
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
 
 /// Wrapper to store CLI args in Tauri managed state.
+#[cfg(feature = "gui")]
 struct CliArgs(Mutex<Cli>);
 
 /// Import a PCAP file into the current state (used by CLI).
-fn import_pcap_file(path: &str, inner: &mut commands::AppStateInner) -> Result<usize, String> {
+#[cfg(feature = "gui")]
+fn import_pcap_file(
+    path: &str,
+    trace: bool,
+    inner: &mut commands::AppStateInner,
+) -> Result<usize, String> {
+    use commands::trace::{PipelineTrace, TRACE_LIMIT_BYTES};
     use gm_capture::PcapReader;
 
-    let reader = PcapReader::new();
-    let packets = reader.read_file(path).map_err(|e| e.to_string())?;
-    let count = packets.len();
+    // Packets are processed as they are read so memory stays flat on
+    // multi-gigabyte captures
+    let port_map = commands::system::port_map(&inner.settings.profile);
+    let packets = PcapReader::new()
+        .with_tcp_reassembly(commands::system::tcp_reassembly_ports(&port_map))
+        .packets(path)
+        .map_err(|e| e.to_string())?;
 
-    let mut processor = commands::processor::PacketProcessor::new();
-    for packet in &packets {
-        processor.process_packet(packet);
+    let pipeline_trace = if trace {
+        Some(PipelineTrace::create(TRACE_LIMIT_BYTES)?)
+    } else {
+        None
+    };
+    let mut processor = commands::processor::PacketProcessor::new()
+        .with_port_map(port_map)
+        .with_subnets(commands::system::subnet_plan(
+            &inner.settings.profile,
+            &inner.physical_topology,
+        ))
+        .with_traffic_buckets(inner.settings.profile.traffic_bucket_secs)
+        .with_trace(pipeline_trace);
+    let mut count = 0;
+    for packet in packets {
+        processor.process_packet(&packet);
+        count += 1;
     }
 
     let deep_parse_info = processor.build_deep_parse_info();
@@ -230,6 +430,11 @@ fn import_pcap_file(path: &str, inner: &mut commands::AppStateInner) -> Result<u
         &inner.oui_lookup,
         &inner.geoip_lookup,
     );
+    if let Some(summary) =
+        processor.finish_trace(&inner.signature_engine, &assets, &deep_parse_info)
+    {
+        log::info!("CLI: pipeline trace written to {}", summary.path);
+    }
 
     // Build topology, enriched with signature data
     let mut topo = processor.topo_builder.snapshot();
@@ -252,6 +457,7 @@ fn import_pcap_file(path: &str, inner: &mut commands::AppStateInner) -> Result<u
     inner.assets = assets;
     inner.connections = processor.get_connections();
     inner.packet_summaries = processor.get_packet_summaries();
+    inner.signature_packets = processor.signature_packets();
     inner.deep_parse_info = deep_parse_info;
     inner.imported_files.push(path.to_string());
 
@@ -0,0 +1,378 @@
+//! Headless sensor mode (`--sensor <interface>`).
+//!
+//! The interface can also be a remote sensor's, as an `ssh://` or
+//! `rpcap://` URL (see [`gm_capture::RemoteSource`]).
+//!
+//! Runs live capture, the processing pipeline, persistence and alert
+//! forwarding without creating a window, so a box can be left on site as a
+//! systemd unit or Windows service. The dataset is checkpointed to the
+//! database as a session every few minutes (the previous checkpoint is
+//! removed once the new one is written), and once more on shutdown. The
+//! desktop app opens the session later from the same database file; while
+//! the sensor is still writing, it falls back to read-only.
+
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use gm_capture::{LiveCaptureConfig, LiveCaptureHandle};
+
+use crate::commands::capture::{
+    apply_snapshot, buffer_size, new_live_alerts, queue_policy, remote_source, rotation_config,
+    LiveAttackAlert,
+};
+use crate::commands::processor::PacketProcessor;
+use crate::commands::session::store_session;
+use crate::commands::{system, AppState};
+
+/// How often assets are rebuilt and new connections checked for alerts.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Options for a sensor run, taken from the command line.
+pub struct SensorConfig {
+    pub interface: String,
+    pub bpf_filter: Option<String>,
+    /// Settings profile to activate before capturing
+    pub profile: Option<String>,
+    /// Session name; defaults to "Sensor <interface> <start time>"
+    pub session_name: Option<String>,
+    pub checkpoint_interval: Duration,
+    /// Syslog collector ("host:port") that receives live alerts over UDP
+    pub alert_syslog: Option<String>,
+    /// Directory for rotating PCAP files of the raw capture
+    pub capture_dir: Option<String>,
+}
+
+/// Capture until SIGINT/SIGTERM (Ctrl-C on Windows), checkpointing the
+/// dataset as a session along the way.
+pub fn run(config: &SensorConfig, state: &AppState) -> Result<(), String> {
+    let (capture_config, port_map, budgets, subnets, bucket_secs) = {
+        let mut inner = state.inner.lock().map_err(|e| e.to_string())?;
+        match inner.db.as_ref() {
+            None => return Err("Sensor mode needs a database to write sessions to".to_string()),
+            Some(db) if db.is_read_only() => {
+                return Err("Database is read-only; is another instance writing to it?".to_string())
+            }
+            Some(_) => {}
+        }
+        if let Some(ref name) = config.profile {
+            system::activate_profile(&mut inner, name)?;
+            log::info!("Sensor: activated settings profile '{}'", name);
+        }
+        let limits = &inner.settings.profile.capture;
+        let port_map = system::port_map(&inner.settings.profile);
+        let capture_config = LiveCaptureConfig {
+            interface_name: config.interface.clone(),
+            remote: remote_source(&config.interface)?,
+            bpf_filter: config.bpf_filter.clone(),
+            promiscuous: limits.promiscuous,
+            ring_buffer_size: limits.ring_buffer_size,
+            snaplen: limits.snaplen,
+            buffer_size: buffer_size(limits),
+            monitor_mode: limits.monitor_mode,
+            rotation: config
+                .capture_dir
+                .as_deref()
+                .map(|dir| rotation_config(limits, dir, &config.interface)),
+            tcp_reassembly_ports: system::tcp_reassembly_ports(&port_map),
+            queue_capacity: limits.queue_capacity,
+            queue_policy: queue_policy(limits),
+        };
+        (
+            capture_config,
+            port_map,
+            system::deep_parse_budgets(&inner.settings.profile),
+            system::subnet_plan(&inner.settings.profile, &inner.physical_topology),
+            inner.settings.profile.traffic_bucket_secs,
+        )
+    };
+
+    // Resolve the collector before capturing so a typo fails at startup
+    let forwarder = config
+        .alert_syslog
+        .as_deref()
+        .map(SyslogForwarder::connect)
+        .transpose()?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    spawn_shutdown_listener(Arc::clone(&stop));
+
+    let (mut handle, rx) = LiveCaptureHandle::start(capture_config).map_err(|e| e.to_string())?;
+    let started = Utc::now();
+    log::info!(
+        "Sensor: capturing on {} (filter: {:?}), checkpoint every {} min",
+        config.interface,
+        config.bpf_filter,
+        config.checkpoint_interval.as_secs() / 60
+    );
+
+    let mut processor = PacketProcessor::new()
+        .with_port_map(port_map)
+        .with_deep_parse_budgets(budgets)
+        .with_subnets(subnets)
+        .with_traffic_buckets(bucket_secs);
+    let mut checkpoint = Checkpoint::new(config, started);
+    let mut alert_watermark = 0;
+    let mut last_refresh = Instant::now();
+    let mut last_checkpoint = Instant::now();
+    let mut reported_drops = 0;
+    let mut reported_queue_drops = 0;
+
+    while !stop.load(Ordering::Relaxed) {
+        match rx.recv_timeout(Duration::from_millis(250)) {
+            Ok(packet) => processor.process_packet(&packet),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                log::error!("Sensor: capture on {} ended unexpectedly", config.interface);
+                break;
+            }
+        }
+        if last_refresh.elapsed() >= REFRESH_INTERVAL {
+            refresh(
+                &mut processor,
+                state,
+                &mut alert_watermark,
+                forwarder.as_ref(),
+            );
+            last_refresh = Instant::now();
+
+            let stats = handle.stats();
+            let dropped = stats.kernel_dropped;
+            if dropped > reported_drops {
+                log::warn!(
+                    "Sensor: kernel dropped {} packets ({} total); traffic is being lost",
+                    dropped - reported_drops,
+                    dropped
+                );
+                reported_drops = dropped;
+            }
+            if stats.queue_dropped > reported_queue_drops {
+                log::warn!(
+                    "Sensor: processing fell behind, {} packets not analyzed ({} total, queue {}/{})",
+                    stats.queue_dropped - reported_queue_drops,
+                    stats.queue_dropped,
+                    stats.queue_depth,
+                    stats.queue_capacity
+                );
+                reported_queue_drops = stats.queue_dropped;
+            }
+        }
+        if last_checkpoint.elapsed() >= config.checkpoint_interval {
+            if let Err(e) = checkpoint.save(state) {
+                log::error!("Sensor: checkpoint failed: {}", e);
+            }
+            last_checkpoint = Instant::now();
+        }
+    }
+
+    let stats = handle.stats();
+    handle.stop().map_err(|e| e.to_string())?;
+    for packet in rx.try_iter() {
+        processor.process_packet(&packet);
+    }
+    refresh(
+        &mut processor,
+        state,
+        &mut alert_watermark,
+        forwarder.as_ref(),
+    );
+    checkpoint.save(state)?;
+
+    log::info!(
+        "Sensor stopped: {} packets, {} bytes in {:.0}s ({} kernel drops, {} interface drops, {} queue drops)",
+        stats.packets_captured,
+        stats.bytes_captured,
+        stats.elapsed_seconds,
+        stats.kernel_dropped,
+        stats.interface_dropped,
+        stats.queue_dropped
+    );
+    let capture_files = handle.capture_files();
+    if !capture_files.is_empty() {
+        log::info!(
+            "Sensor: {} capture files kept in {}",
+            capture_files.len(),
+            config.capture_dir.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Publish the processor's view to state and forward alerts for
+/// connections that appeared since the last refresh.
+fn refresh(
+    processor: &mut PacketProcessor,
+    state: &AppState,
+    watermark: &mut usize,
+    forwarder: Option<&SyslogForwarder>,
+) {
+    let deep_parse_info = processor.build_deep_parse_info();
+    let alerts = match state.inner.lock() {
+        Ok(mut inner) => {
+            apply_snapshot(processor, deep_parse_info, &mut inner);
+            new_live_alerts(&inner, watermark)
+        }
+        Err(e) => {
+            log::error!("Sensor: failed to update state: {}", e);
+            return;
+        }
+    };
+
+    for alert in &alerts {
+        log::warn!(
+            "{} {}: {} ({})",
+            alert.technique_id,
+            alert.title,
+            alert.description,
+            alert.evidence
+        );
+        if let Some(forwarder) = forwarder {
+            if let Err(e) = forwarder.send(alert) {
+                log::warn!("Sensor: syslog forwarding failed: {}", e);
+            }
+        }
+    }
+}
+
+/// The session the sensor keeps rewriting.
+struct Checkpoint {
+    name: String,
+    description: String,
+    /// ID of the last checkpoint written
+    session_id: Option<String>,
+}
+
+impl Checkpoint {
+    fn new(config: &SensorConfig, started: DateTime<Utc>) -> Self {
+        let name = config.session_name.clone().unwrap_or_else(|| {
+            format!(
+                "Sensor {} {}",
+                config.interface,
+                started.format("%Y-%m-%d %H:%M")
+            )
+        });
+        Self {
+            name,
+            description: format!(
+                "Headless capture on {} since {}",
+                config.interface,
+                started.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+            session_id: None,
+        }
+    }
+
+    /// Write the current dataset as a new session, then drop the previous
+    /// checkpoint, so a power loss never leaves the sensor without one.
+    fn save(&mut self, state: &AppState) -> Result<(), String> {
+        let inner = state.inner.lock().map_err(|e| e.to_string())?;
+        let description = format!(
+            "{} (checkpoint {})",
+            self.description,
+            Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+        );
+        let saved = store_session(
+            &inner,
+            &self.name,
+            &description,
+            &inner.assets,
+            &inner.connections,
+            &inner.deep_parse_info,
+            &inner.imported_files,
+        )?;
+        if let Some(previous) = self.session_id.replace(saved.id) {
+            let db = inner.db.as_ref().ok_or("Database not available")?;
+            db.delete_session(&previous).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// Sends live alerts to a syslog collector as RFC 5424 messages over UDP,
+/// facility local0, with the alert as JSON in the message body.
+struct SyslogForwarder {
+    socket: UdpSocket,
+}
+
+impl SyslogForwarder {
+    fn connect(collector: &str) -> Result<Self, String> {
+        let addr = collector
+            .to_socket_addrs()
+            .map_err(|e| format!("Invalid syslog collector '{}': {}", collector, e))?
+            .next()
+            .ok_or_else(|| format!("Syslog collector '{}' did not resolve", collector))?;
+        let bind = if addr.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+        socket.connect(addr).map_err(|e| e.to_string())?;
+        log::info!("Sensor: forwarding alerts to syslog at {}", addr);
+        Ok(Self { socket })
+    }
+
+    fn send(&self, alert: &LiveAttackAlert) -> Result<(), String> {
+        let severity = match alert.severity.as_str() {
+            "critical" => 2,
+            "high" => 3,
+            "medium" => 4,
+            _ => 5,
+        };
+        let body = serde_json::to_string(alert).map_err(|e| e.to_string())?;
+        let message = format!(
+            "<{}>1 {} - kusanaginokajiki - {} - {}",
+            16 * 8 + severity,
+            Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            alert.technique_id,
+            body
+        );
+        self.socket
+            .send(message.as_bytes())
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Set `stop` on SIGINT or SIGTERM (what systemd sends on `stop`), or on
+/// Ctrl-C / a service wrapper's console close on Windows.
+fn spawn_shutdown_listener(stop: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                log::error!("Sensor: cannot listen for shutdown signals: {}", e);
+                return;
+            }
+        };
+        runtime.block_on(async {
+            #[cfg(unix)]
+            {
+                use tokio::signal::unix::{signal, SignalKind};
+                match signal(SignalKind::terminate()) {
+                    Ok(mut terminate) => {
+                        tokio::select! {
+                            _ = tokio::signal::ctrl_c() => {}
+                            _ = terminate.recv() => {}
+                        }
+                    }
+                    Err(_) => {
+                        let _ = tokio::signal::ctrl_c().await;
+                    }
+                }
+            }
+            #[cfg(not(unix))]
+            {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        });
+        log::info!("Sensor: shutting down");
+        stop.store(true, Ordering::Relaxed);
+    });
+}
@@ -6,7 +6,9 @@
  * Use with:           cy.layout({ name: 'purdue' }).run()
  *
  * Node data field `purdueLevel` (number | null) determines band placement.
- * Unknown / null nodes are placed in the L5 (top) band.
+ * Unknown / null nodes are placed in the L5 (top) band. Within a band, nodes
+ * follow `purdueOrder`, the backend's crossing-reducing order, so the same
+ * topology always draws the same way.
  */
 
 /** Visual band definitions — top (index 0) to bottom (index 6). */
@@ -97,6 +99,11 @@ export class PurdueLayout {
 		// Position nodes within each row, evenly spaced horizontally
 		rows.forEach((nodes, rowIdx) => {
 			if (nodes.length === 0) return;
+			nodes.sort((a: any, b: any) => {
+				const oa = a.data('purdueOrder') ?? Number.MAX_SAFE_INTEGER;
+				const ob = b.data('purdueOrder') ?? Number.MAX_SAFE_INTEGER;
+				return oa - ob || (a.id() < b.id() ? -1 : a.id() > b.id() ? 1 : 0);
+			});
 			// Row 0 is top, row 6 is bottom; centre vertically within the band
 			const y = paddingY + (rowIdx + 0.5) * rowHeight;
 			const spacing = usableWidth / (nodes.length + 1);
@@ -129,7 +129,16 @@ export const captureStats = writable<CaptureStatsEvent>({
 	bytes_captured: 0,
 	active_connections: 0,
 	asset_count: 0,
-	elapsed_seconds: 0
+	elapsed_seconds: 0,
+	flows_exported: 0,
+	deep_parse_sampling: [],
+	bytes_per_second: 0,
+	kernel_dropped: 0,
+	interface_dropped: 0,
+	queue_depth: 0,
+	queue_capacity: 0,
+	queue_dropped: 0,
+	protocol_rates: []
 });
 
 // ─── UI State ─────────────────────────────────────────────────
@@ -138,7 +147,7 @@ export const captureStats = writable<CaptureStatsEvent>({
 export const selectedAssetId = writable<string | null>(null);
 
 /** Currently active view/tab */
-export type ViewTab = 'projects' | 'topology' | 'physical' | 'inventory' | 'capture' | 'signatures' | 'protocol_stats' | 'export' | 'analysis' | 'settings' | 'comm_patterns' | 'segmentation';
+export type ViewTab = 'projects' | 'topology' | 'physical' | 'inventory' | 'capture' | 'signatures' | 'protocol_stats' | 'export' | 'analysis' | 'settings' | 'comm_patterns' | 'external_connectivity' | 'segmentation';
 export const activeTab = writable<ViewTab>('projects');
 
 /** Currently active project (null if no project selected) */
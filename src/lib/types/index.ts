@@ -10,9 +10,12 @@
 
 export interface NetworkInterface {
 	name: string;
+	/** OS display name ("Ethernet 2") when it differs from the capture name */
+	friendly_name: string | null;
 	description: string | null;
 	addresses: InterfaceAddress[];
 	flags: InterfaceFlags;
+	link_speed_mbps: number | null;
 }
 
 export interface InterfaceAddress {
@@ -25,6 +28,9 @@ export interface InterfaceFlags {
 	is_up: boolean;
 	is_loopback: boolean;
 	is_running: boolean;
+	is_wireless: boolean;
+	/** Cable or wireless association present; null when unknown */
+	connected: boolean | null;
 }
 
 // ─── Capture ──────────────────────────────────────────────────
@@ -45,12 +51,62 @@ export interface ImportResult {
 	protocols_detected: string[];
 	duration_ms: number;
 	per_file: FileImportResult[];
+	/** Pipeline trace written for this import (trace mode only) */
+	trace: TraceSummary | null;
+}
+
+/** Where a pipeline trace was written */
+export interface TraceSummary {
+	path: string;
+	events: number;
+	/** Packet events not written because the size cap was reached */
+	dropped: number;
+	bytes: number;
 }
 
 export interface FileImportResult {
 	filename: string;
 	packet_count: number;
 	status: string;
+	/** Packets left out by the import sampling options */
+	packets_filtered: number;
+}
+
+/** Import only part of each capture file (all fields optional) */
+export interface ImportSampling {
+	/** RFC 3339; skip packets captured before this time */
+	start?: string;
+	/** RFC 3339; skip packets captured after this time */
+	end?: string;
+	/** Keep one packet in this many (0 or 1 = every packet) */
+	every_nth?: number;
+	/** Keep at most this many packets of each flow (0 = no limit) */
+	max_packets_per_flow?: number;
+}
+
+/** How a directory import turns its captures into sessions */
+export type BatchImportMode = 'merged' | 'per_file';
+
+/** One row of the directory import summary table */
+export interface BatchFileSummary {
+	filename: string;
+	packet_count: number;
+	asset_count: number;
+	connection_count: number;
+	/** "ok" or "error: ..." */
+	status: string;
+	/** Session the file was saved to (per-file mode) */
+	session_id: string | null;
+}
+
+export interface BatchImportResult {
+	directory: string;
+	mode: BatchImportMode;
+	file_count: number;
+	packet_count: number;
+	duration_ms: number;
+	files: BatchFileSummary[];
+	sessions: SessionInfo[];
 }
 
 // ─── Assets ───────────────────────────────────────────────────
@@ -58,11 +114,16 @@ export interface FileImportResult {
 export type DeviceType =
 	| 'plc'
 	| 'rtu'
+	| 'ied'
 	| 'hmi'
 	| 'historian'
 	| 'engineering_workstation'
 	| 'scada_server'
 	| 'it_device'
+	/** Router inferred from a MAC fronting many addresses, or a serial device server */
+	| 'gateway'
+	/** Topology only: Layer-2 multicast destination (e.g. a GOOSE group MAC) */
+	| 'multicast_group'
 	| 'unknown';
 
 export type PurdueLevel = 0 | 1 | 2 | 3 | 4 | 5;
@@ -72,6 +133,8 @@ export interface Asset {
 	ip_address: string;
 	mac_address: string | null;
 	hostname: string | null;
+	/** Where the hostname came from: 'manual' for analyst edits, else the protocol (e.g. 'dns', 'dhcp', 'lldp') */
+	hostname_source?: string | null;
 	device_type: DeviceType;
 	vendor: string | null;
 	protocols: IcsProtocol[];
@@ -87,14 +150,25 @@ export interface Asset {
 	product_family: string | null;
 	/** All signature matches for this asset */
 	signature_matches: AssetSignatureMatch[];
+	/** How each protocol in `protocols` was identified (absent in older sessions) */
+	protocol_evidence?: ProtocolEvidence[];
 	/** Vendor from IEEE OUI database (MAC prefix lookup) */
 	oui_vendor: string | null;
 	/** ISO 3166-1 alpha-2 country code (public IPs only) */
 	country: string | null;
 	/** Whether this IP is a public (routable) address */
 	is_public_ip: boolean;
+	/** Analyst-set lifecycle state */
+	lifecycle: AssetLifecycle;
+	/** When the lifecycle state was last changed (RFC 3339) */
+	lifecycle_changed_at: string | null;
+	/** 802.1Q VLAN IDs the asset's own frames were tagged with (absent in older sessions) */
+	vlan_ids?: number[];
 }
 
+/** Asset lifecycle state. Decommissioned/planned assets seen in traffic raise findings. */
+export type AssetLifecycle = 'active' | 'quarantined' | 'decommissioned' | 'planned';
+
 /** A signature match result attached to an asset */
 export interface AssetSignatureMatch {
 	signature_name: string;
@@ -105,6 +179,17 @@ export interface AssetSignatureMatch {
 	role: string | null;
 }
 
+/** How a protocol label was arrived at, weakest evidence first */
+export type IdentificationMethod = 'unidentified' | 'port' | 'port_map' | 'payload_probe' | 'deep_parse';
+
+/** The strongest identification seen for one of an asset's protocols */
+export interface ProtocolEvidence {
+	protocol: IcsProtocol;
+	method: IdentificationMethod;
+	/** 0-100 */
+	confidence: number;
+}
+
 // ─── Protocols ────────────────────────────────────────────────
 
 export type IcsProtocol =
@@ -116,17 +201,31 @@ export type IcsProtocol =
 	| 'opc_ua'
 	| 'profinet'
 	| 'iec104'
+	| 'iec61850_mms'
+	| 'goose'
+	| 'sampled_values'
 	| 'mqtt'
 	| 'hart_ip'
 	| 'foundation_fieldbus'
 	| 'ge_srtp'
 	| 'wonderware_suitelink'
+	| 'fins'
+	| 'slmp'
+	| 'codesys'
+	| 'niagara_fox'
+	| 'crimson'
+	| 'vnet_ip'
+	| 'tristation'
+	| 'ads'
+	| 'opc_da'
 	| 'http'
 	| 'https'
 	| 'dns'
 	| 'ssh'
 	| 'rdp'
 	| 'snmp'
+	| 'smb'
+	| 'icmp'
 	| 'unknown';
 
 export interface ProtocolStats {
@@ -180,6 +279,30 @@ export interface Connection {
 	first_seen: string;
 	last_seen: string;
 	origin_files: string[];
+	/** Zeek/Suricata flow records correlated onto this connection */
+	provenance: FlowProvenance[];
+	/** How the client (src) and server (dst) ends were told apart */
+	direction_basis: DirectionBasis;
+	/** 802.1Q VLAN IDs seen on the connection's frames (absent in older sessions) */
+	vlan_ids?: number[];
+	/** VXLAN network identifiers the connection was carried in (absent in older sessions) */
+	vxlan_vnis?: number[];
+}
+
+export type DirectionBasis =
+	| 'syn'
+	| 'server_port'
+	| 'role'
+	| 'flow_record'
+	| 'first_packet'
+	| 'unknown';
+
+/** One external flow record that contributed to a connection */
+export interface FlowProvenance {
+	/** Source display name ("Zeek", "Suricata") */
+	source: string;
+	/** Zeek uid or Suricata flow_id */
+	uid: string | null;
 }
 
 // ─── Packet Summary (for connection tree) ─────────────────────
@@ -206,21 +329,125 @@ export interface TopologyNode {
 	id: string;
 	ip_address: string;
 	mac_address: string | null;
+	/** Every IP address of the device; several when interfaces were merged */
+	addresses: string[];
 	device_type: DeviceType;
 	vendor: string | null;
 	protocols: IcsProtocol[];
 	subnet: string;
+	/** 802.1Q VLANs the device is in, from tags and switch data; empty if unknown */
+	vlans: number[];
 	packet_count: number;
+	/** Community of densely connected devices (cell/area); null if ungrouped */
+	cluster_id: number | null;
+	centrality: NodeCentrality;
+	/** First/last time the device was seen (RFC 3339); null if untimed */
+	first_seen: string | null;
+	last_seen: string | null;
+	/** Band and position in the Purdue-layered diagram */
+	layout: LayoutHint;
+}
+
+/** Where a node goes in the Purdue-layered diagram, computed by the backend */
+export interface LayoutHint {
+	/** Purdue level; null if unassigned (drawn in the L5 band) */
+	level: number | null;
+	/** Position within the band, left to right from 0 */
+	order: number;
+}
+
+/** How central a node is to the observed communication */
+export interface NodeCentrality {
+	/** Distinct peers, in either direction */
+	degree: number;
+	fan_in: number;
+	fan_out: number;
+	/** Share of shortest paths between other nodes passing through this one (0–1) */
+	betweenness: number;
+	/** High betweenness or fan-out: many devices depend on this one */
+	choke_point: boolean;
 }
 
 export interface TopologyEdge {
 	id: string;
 	source: string; // node id
 	target: string; // node id
-	protocol: IcsProtocol;
+	protocol: IcsProtocol; // protocol carrying the most packets
 	packet_count: number;
 	byte_count: number;
 	bidirectional: boolean;
+	/** Packets/bytes per protocol seen between the two nodes */
+	protocol_breakdown: Partial<Record<IcsProtocol, EdgeProtocolStats>>;
+	/** Packets/bytes per (source port, destination port) pair, capped */
+	ports: EdgePortStats[];
+	/** More port pairs were seen than are listed */
+	ports_truncated: boolean;
+	first_seen: string | null;
+	last_seen: string | null;
+}
+
+export interface EdgeProtocolStats {
+	packet_count: number;
+	byte_count: number;
+}
+
+export interface EdgePortStats {
+	src_port: number;
+	dst_port: number;
+	packet_count: number;
+	byte_count: number;
+}
+
+/** Traffic on an edge in one time bucket */
+export interface TrafficBucket {
+	/** Start of the bucket (ISO 8601) */
+	start: string;
+	packet_count: number;
+	byte_count: number;
+}
+
+/** Bandwidth over time on one topology edge */
+export interface EdgeTraffic {
+	edge_id: string;
+	source: string;
+	target: string;
+	protocol: IcsProtocol;
+	packet_count: number;
+	byte_count: number;
+	/** Bucket width in seconds; doubles on long captures */
+	bucket_secs: number;
+	/** Buckets with traffic, in time order; empty if the edge was never timed */
+	buckets: TrafficBucket[];
+	/** The busiest bucket */
+	peak: TrafficBucket | null;
+	/** Buckets carrying several times the edge's mean, in time order */
+	bursts: TrafficBucket[];
+}
+
+/** One observed connection along a topology path */
+export interface PathHop {
+	from: string;
+	to: string;
+	edge_id: string;
+	protocol: IcsProtocol;
+	/** Every protocol seen on the edge, busiest first */
+	protocols: IcsProtocol[];
+	packet_count: number;
+}
+
+/** Shortest chain of observed connections between two nodes */
+export interface TopologyPath {
+	/** Node ids from source to destination, both included */
+	nodes: string[];
+	hops: PathHop[];
+}
+
+/** 'from' = nodes the queried node can reach; 'to' = nodes that can reach it */
+export type Reachability = 'from' | 'to';
+
+export interface ReachableNode {
+	id: string;
+	hops: number;
 }
 
 // ─── Connection Tree ──────────────────────────────────────────
@@ -251,6 +478,37 @@ export interface CaptureStatsEvent {
 	active_connections: number;
 	asset_count: number;
 	elapsed_seconds: number;
+	/** IPFIX flow records sent so far (0 when export is off) */
+	flows_exported: number;
+	/** Deep-parse parsed/skipped counters per budgeted protocol */
+	deep_parse_sampling: DeepParseSampling[];
+	bytes_per_second: number;
+	/** Packets dropped by the kernel because the capture fell behind */
+	kernel_dropped: number;
+	/** Packets dropped by the interface or driver, where reported */
+	interface_dropped: number;
+	/** Parsed packets waiting for the processing thread, and its capacity */
+	queue_depth: number;
+	queue_capacity: number;
+	/** Packets discarded because processing fell behind capture */
+	queue_dropped: number;
+	/** Rates per identified protocol, busiest first */
+	protocol_rates: ProtocolRate[];
+}
+
+/** Traffic rate of one identified protocol during live capture */
+export interface ProtocolRate {
+	protocol: string;
+	packets_per_second: number;
+	bytes_per_second: number;
+}
+
+/** Deep-parse sampling counters for one budgeted protocol during live capture */
+export interface DeepParseSampling {
+	protocol: string;
+	max_per_second: number;
+	parsed: number;
+	skipped: number;
 }
 
 /** Result of stopping a capture */
@@ -261,6 +519,8 @@ export interface StopCaptureResult {
 	pcap_saved: boolean;
 	pcap_path: string | null;
 	packets_saved: number;
+	/** Rotating capture files written during the session, oldest first */
+	capture_files: string[];
 }
 
 /** Current capture status from the backend */
@@ -270,12 +530,29 @@ export interface CaptureStatusInfo {
 	packets_captured: number;
 	bytes_captured: number;
 	elapsed_seconds: number;
+	kernel_dropped: number;
+	interface_dropped: number;
+	queue_depth: number;
+	queue_capacity: number;
+	/** Highest queue depth reached during the capture */
+	queue_peak_depth: number;
+	queue_dropped: number;
+	/** Rotating capture files on disk, oldest first */
+	capture_files: string[];
 }
 
 // ─── Topology Views (Phase 2) ────────────────────────────────
 
 /** How to group/cluster nodes in the topology graph */
-export type GroupingMode = 'subnet' | 'protocol' | 'device_role' | 'vendor' | 'none';
+export type GroupingMode = 'subnet' | 'vlan' | 'protocol' | 'device_role' | 'vendor' | 'cluster' | 'none';
+
+/** Topology nodes in one VLAN */
+export interface VlanGroup {
+	vlan: number;
+	/** Name from the switch configuration, if any */
+	name: string | null;
+	node_ids: string[];
+}
 
 /** A topology sub-tab (logical, mesh, filtered view, or watch tab) */
 export interface TopologyTab {
@@ -320,6 +597,19 @@ export interface SignatureInfo {
 export interface SignatureSummary {
 	total_count: number;
 	signatures: SignatureInfo[];
+	/** Identifier of the loaded signature set */
+	fingerprint: string;
+	/** True while a loaded session's pinned signature set is active */
+	pinned: boolean;
+}
+
+export interface SignatureReloadResult {
+	total_count: number;
+	added: string[];
+	changed: string[];
+	removed: string[];
+	/** Assets whose signature matches changed as a result of the reload */
+	assets_updated: number;
 }
 
 /** Result of testing a signature against loaded data */
@@ -328,6 +618,24 @@ export interface SignatureTestResult {
 	matches: SignatureTestMatch[];
 }
 
+export interface FingerprintConversionResult {
+	fingerprint: string;
+	signatures: ConvertedSignature[];
+	unconverted: UnconvertedConstruct[];
+	written: string[];
+}
+
+export interface ConvertedSignature {
+	name: string;
+	yaml: string;
+}
+
+export interface UnconvertedConstruct {
+	context: string;
+	construct: string;
+	reason: string;
+}
+
 export interface SignatureTestMatch {
 	packet_index: number;
 	src_ip: string;
@@ -347,9 +655,31 @@ export interface DeepParseInfo {
 	s7: S7Detail | null;
 	bacnet: BacnetDetail | null;
 	iec104: Iec104Detail | null;
+	fins: FinsDetail | null;
+	slmp: SlmpDetail | null;
+	ge_srtp: GeSrtpDetail | null;
+	codesys: CodesysDetail | null;
+	fox: FoxDetail | null;
+	crimson: CrimsonDetail | null;
+	vnet_ip: VnetIpDetail | null;
+	tristation: TriStationDetail | null;
+	ads: AdsDetail | null;
+	opc_da: OpcDaDetail | null;
+	mms: MmsDetail | null;
+	goose: GooseDetail | null;
+	sv: SvDetail | null;
 	profinet_dcp: ProfinetDcpDetail | null;
+	profinet_rt: ProfinetRtDetail | null;
 	lldp: LldpDetail | null;
 	snmp: SnmpDetail | null;
+	smb: SmbDetail | null;
+	dhcp: DhcpDetail | null;
+	engineering: EngineeringDetail | null;
+	sessions: DeviceSessionHealth | null;
+	cloud: CloudDetail | null;
+	tls: TlsDetail | null;
+	mqtt: MqttDetail | null;
+	serial_gateway?: SerialGatewayDetail | null;
 }
 
 /** EtherNet/IP aggregated details for a device */
@@ -362,6 +692,25 @@ export interface EnipDetail {
 	cip_file_access: boolean;
 	/** IP sent ListIdentity requests — network discovery */
 	list_identity_requests: boolean;
+	/** Identity from the device's own ListIdentity response */
+	identity?: EnipIdentityInfo | null;
+}
+
+/** Device identity from an EtherNet/IP ListIdentity response */
+export interface EnipIdentityInfo {
+	vendor_id: number;
+	vendor_name: string;
+	/** CIP device profile code (0x0E = PLC) */
+	device_type: number;
+	device_type_name: string;
+	product_code: number;
+	product_name: string;
+	/** Firmware revision, "major.minor" */
+	revision: string;
+	/** Serial number, 8 hex digits */
+	serial_number: string;
+	status: number;
+	state: number;
 }
 
 /** S7comm aggregated details for a device */
@@ -384,6 +733,25 @@ export interface BacnetDetail {
 	reinitialize_device: boolean;
 	/** DeviceCommunicationControl service was seen (T0811) */
 	device_communication_control: boolean;
+	/** Device object identity from I-Am and ReadProperty/ReadPropertyMultiple acks */
+	device?: BacnetDeviceProperties | null;
+}
+
+/** BACnet Device object properties (ASHRAE 135 property identifiers in parentheses) */
+export interface BacnetDeviceProperties {
+	device_instance: number | null;
+	/** object-name (77) */
+	object_name: string | null;
+	/** vendor-name (121) */
+	vendor_name: string | null;
+	/** vendor-identifier (120) */
+	vendor_id: number | null;
+	/** model-name (70) */
+	model_name: string | null;
+	/** firmware-revision (44) */
+	firmware_revision: string | null;
+	/** application-software-version (12) */
+	application_software_version: string | null;
 }
 
 /** IEC 60870-5-104 aggregated details for a device */
@@ -398,12 +766,448 @@ export interface Iec104Detail {
 	has_interrogation: boolean;
 }
 
+/** OMRON FINS aggregated details for a device */
+export interface FinsDetail {
+	/** "client" (HMI/SCADA/engineering tool) or "server" (PLC) */
+	role: string;
+	/** Command codes sent or answered, e.g. "0101 Memory Area Read" */
+	commands_seen: string[];
+	/** FINS node addresses used by this device */
+	node_addresses: number[];
+	/** Device sent memory/parameter/file/clock writes (T0855) */
+	has_writes: boolean;
+	/** Device sent Run/Stop or program area writes (T0858/T0843) */
+	has_control_commands: boolean;
+	/** CPU model from Controller Data Read response */
+	controller_model: string | null;
+	/** CPU version from Controller Data Read response */
+	controller_version: string | null;
+}
+
+/** Mitsubishi MELSEC SLMP / MC protocol aggregated details for a device */
+export interface SlmpDetail {
+	/** "client" (HMI/SCADA/GX Works) or "server" (PLC) */
+	role: string;
+	/** Commands sent by this device, e.g. "0401 Batch Read" */
+	commands_seen: string[];
+	/** Device areas read or written on this PLC (e.g. "D", "M", "X") */
+	device_areas: string[];
+	/** Device sent device/buffer memory/file writes (T0855) */
+	has_writes: boolean;
+	/** Device sent remote RUN/STOP/PAUSE/RESET (T0858) */
+	has_control_commands: boolean;
+	/** CPU model from Read CPU Model Name response */
+	cpu_model: string | null;
+	/** CPU model code from Read CPU Model Name response */
+	cpu_model_code: number | null;
+}
+
+/** GE SRTP aggregated details for a device */
+export interface GeSrtpDetail {
+	/** "client" (HMI/historian/Machine Edition) or "server" (PLC) */
+	role: string;
+	/** Service requests sent by this device, e.g. "04 Read System Memory" */
+	services_seen: string[];
+	/** Memory types read or written on this PLC (e.g. "%R", "%M") */
+	memory_segments: string[];
+	/** Device sent memory/program/clock/fault-table writes (T0855) */
+	has_writes: boolean;
+	/** Device sent Set PLC State, privilege change or program download (T0858) */
+	has_control_commands: boolean;
+	/** Last PLC state reported in a response status word */
+	plc_state: string | null;
+	/** Catalog number from Return Controller Type and ID response */
+	controller_model: string | null;
+	/** Controller ID from Return Controller Type and ID response */
+	controller_id: string | null;
+	/** Firmware revision from Return Controller Type and ID response */
+	firmware: string | null;
+}
+
+/** CODESYS runtime protocol aggregated details for a device */
+export interface CodesysDetail {
+	/** "client" (Development System/gateway) or "server" (runtime) */
+	role: string;
+	/** Protocol generations observed ("V2", "V3") */
+	versions: string[];
+	/** Services requested by this device, e.g. "Application: Download" */
+	services_seen: string[];
+	/** Device logged into a runtime */
+	has_login: boolean;
+	/** Device downloaded an application (T0843) */
+	has_download: boolean;
+	/** Device applied an online change (T0843) */
+	has_online_change: boolean;
+	/** Device started/stopped/reset an application (T0858) */
+	has_control_commands: boolean;
+}
+
+export interface FoxDetail {
+	/** "client" (Workbench/Supervisor) or "server" (station) */
+	role: string;
+	station_name: string | null;
+	host_name: string | null;
+	/** "Station" or "Workbench" */
+	app_name: string | null;
+	/** Niagara version, e.g. "4.10.1.36" */
+	niagara_version: string | null;
+	/** Niagara AX (3.x), end of life */
+	is_legacy: boolean;
+	os_name: string | null;
+	os_version: string | null;
+	vm_name: string | null;
+	host_id: string | null;
+	/** OEM brand ID, e.g. "vykon", "Honeywell" */
+	brand_id: string | null;
+	/** Strongest authentication scheme offered */
+	auth_mode: string | null;
+	auth_agents: string[];
+}
+
+export interface CrimsonDetail {
+	/** "client" (Crimson software) or "server" (Red Lion device) */
+	role: string;
+	manufacturer: string | null;
+	/** Model reported by the device, e.g. "G306A", "DA30D" */
+	model: string | null;
+	/** Sent a configuration database to a device (T0843) */
+	has_download: boolean;
+	/** Read a configuration database from a device (T0845) */
+	has_upload: boolean;
+	transfer_peers: string[];
+	bulk_bytes_sent: number;
+}
+
+export interface VnetIpDetail {
+	domain: number;
+	station: number;
+	/** Vnet/IP bus this address is on (1 or 2) */
+	bus: number;
+	/** Station number in DDSS form, e.g. "0164" */
+	station_number: string;
+	/** The same station's address on the other bus */
+	other_bus_ip: string | null;
+	peers: string[];
+}
+
+export interface TriStationDetail {
+	role: string;
+	/** Commands sent by this device, e.g. "Start Download Change" */
+	commands: string[];
+	has_download: boolean;
+	has_upload: boolean;
+	has_run_state_change: boolean;
+	peers: string[];
+}
+
+export interface OpcDaDetail {
+	/** "client" (HMI, historian collector) or "server" (OPC server) */
+	role: string;
+	/** OPC interfaces bound with peers, e.g. "IOPCSyncIO" */
+	interfaces: string[];
+	has_alarms_events: boolean;
+	has_callbacks: boolean;
+	peers: string[];
+	request_count: number;
+}
+
+export interface AdsDetail {
+	role: string;
+	/** AMS NetIDs this device used, e.g. "5.23.20.78.1.1" */
+	ams_net_ids: string[];
+	/** AMS ports addressed on this device (851 = TwinCAT 3 PLC runtime 1) */
+	ams_ports: number[];
+	commands: string[];
+	device_name: string | null;
+	twincat_version: string | null;
+	/** Last ADS state the device reported, e.g. "Run" */
+	ads_state: string | null;
+	has_write: boolean;
+	/** ADS states requested with Write Control, e.g. "Stop" */
+	state_changes: string[];
+	relationships: AdsRelationship[];
+}
+
+export interface AdsRelationship {
+	remote_ip: string;
+	remote_role: string;
+	remote_net_ids: string[];
+	request_count: number;
+}
+
+/** Kind of external service a device connects out to */
+export type CloudCategory =
+	| 'iot_platform'
+	| 'vendor_cloud'
+	| 'remote_access'
+	| 'mqtt_broker'
+	| 'opc_ua_reverse';
+
+/** Cloud / remote-access endpoints a device connected out to */
+export interface CloudDetail {
+	endpoints: CloudEndpoint[];
+}
+
+/** A single outbound connection to a cloud service, broker or reverse-connect client */
+export interface CloudEndpoint {
+	remote_ip: string;
+	remote_port: number;
+	/** Host name from TLS SNI or the MQTT username */
+	hostname: string | null;
+	/** Service name, e.g. "Azure IoT Hub" or "MQTT broker" */
+	service: string;
+	category: CloudCategory;
+	/** "TLS SNI", "MQTT CONNECT" or "OPC UA ReverseHello" */
+	evidence: string;
+	/** MQTT client ID or OPC UA ServerUri announced by the device */
+	identity: string | null;
+	first_seen: string;
+}
+
+/** TLS stacks a device was seen using */
+export interface TlsDetail {
+	fingerprints: TlsFingerprint[];
+}
+
+/** A JA3 (device opened the handshake) or JA3S (device answered it) fingerprint */
+export interface TlsFingerprint {
+	role: 'client' | 'server';
+	/** MD5 of the fingerprint string */
+	hash: string;
+	/** Unhashed JA3 / JA3S string */
+	fingerprint: string;
+	/** e.g. "TLS 1.2" */
+	version: string;
+	cipher_suites: number[];
+	alpn: string[];
+	/** SNI host names sent with this fingerprint */
+	server_names: string[];
+	peers: string[];
+	handshakes: number;
+	first_seen: string;
+}
+
+/** MQTT sessions of a device and the topics it moved */
+export interface MqttDetail {
+	role: 'client' | 'broker';
+	/** Client IDs the device connected with, or that connected to it as broker */
+	client_ids: string[];
+	/** Brokers connected to, or clients of this broker */
+	peers: string[];
+	topics: MqttTopic[];
+}
+
+/** A Moxa/Lantronix serial device server and the field devices polled through it */
+export interface SerialGatewayDetail {
+	/** Operating mode, e.g. "Moxa NPort (TCP Server)" */
+	gateway: string;
+	vendor: string;
+	lines: SerialLineDetail[];
+}
+
+export interface SerialLineDetail {
+	/** TCP port the line is tunneled on */
+	port: number;
+	/** 1-based serial port number on the gateway */
+	channel: number;
+	framing: 'raw' | 'rfc2217';
+	baud_rate: number | null;
+	/** e.g. "8N1" */
+	line_format: string | null;
+	/** 'modbus_rtu' | 'dnp3' */
+	protocols: string[];
+	hosts: string[];
+	devices: SerialDevice[];
+}
+
+/** A field device behind a serial device server, known by its link address */
+export interface SerialDevice {
+	protocol: 'modbus_rtu' | 'dnp3';
+	/** Modbus slave address or DNP3 outstation address */
+	address: number;
+	frame_count: number;
+}
+
+/** A topic published to, or a topic filter subscribed to */
+export interface MqttTopic {
+	topic: string;
+	action: 'publish' | 'subscribe';
+	/** Highest QoS used or requested */
+	qos: number;
+	retained: boolean;
+	messages: number;
+	bytes: number;
+	peers: string[];
+	first_seen: string;
+}
+
+/** IEC 61850 MMS aggregated details for a device */
+export interface MmsDetail {
+	/** "client" (SCADA/gateway/HMI) or "server" (IED) */
+	role: string;
+	/** MMS services observed from this device (snake_case names) */
+	services_seen: string[];
+	/** Logical devices (MMS domains) hosted by this IED */
+	logical_devices: string[];
+	/** Logical nodes referenced on this IED (e.g. "XCBR1", "MMXU1") */
+	logical_nodes: string[];
+	/** Report IDs of reports published by this IED */
+	report_ids: string[];
+	/** Device sent MMS Write or other state-changing services (T0855) */
+	has_writes: boolean;
+	/** Vendor name from MMS Identify response */
+	vendor: string | null;
+	/** Model name from MMS Identify response */
+	model: string | null;
+	/** Revision from MMS Identify response */
+	revision: string | null;
+}
+
+/** An IPv4 address as seen in ARP, with every MAC that claimed it */
+export interface ArpBinding {
+	ip_address: string;
+	/** First claimant first; more than one means a duplicate IP or ARP spoofing */
+	mac_addresses: string[];
+	/** VLAN the first claim was seen on */
+	vlan: number | null;
+	packet_count: number;
+	/** Gratuitous ARPs announcing the address */
+	gratuitous_count: number;
+	first_seen: string;
+	last_seen: string;
+}
+
+/** ICMP discovery activity of one host: what it pinged and what answered */
+export interface IcmpActivity {
+	ip_address: string;
+	echo_requests: number;
+	/** Distinct addresses sent an echo request */
+	echo_targets: string[];
+	echo_replies: number;
+	/** Distinct addresses that answered an echo request */
+	responsive_targets: string[];
+	/** Destination-unreachable and time-exceeded errors sent to this host */
+	errors_received: number;
+	/** Destinations those errors reported as unreachable */
+	unreachable_targets: string[];
+	first_seen: string;
+	last_seen: string;
+}
+
+/** IEC 61850 GOOSE publications from a device (matched by MAC) */
+export interface GooseDetail {
+	publications: GooseStream[];
+}
+
+/** One GOOSE control block, tracked across frames */
+export interface GooseStream {
+	/** Control block reference, e.g. "IED1LD0/LLN0$GO$gcbTrip" */
+	gocb_ref: string;
+	publisher_mac: string;
+	/** Multicast destination MAC (01:0C:CD:01:xx:xx) */
+	destination_mac: string;
+	app_id: number;
+	go_id: string | null;
+	dat_set: string | null;
+	conf_rev: number | null;
+	frame_count: number;
+	/** stNum increments observed (dataset state changes) */
+	state_changes: number;
+	last_st_num: number;
+	last_sq_num: number;
+	/** Frames whose stNum went backwards — replay / injection indicator */
+	st_num_regressions: number;
+	/** Frames repeating the current stNum with an older sqNum */
+	sq_num_regressions: number;
+	/** Publisher restarts (stNum 1 / sqNum 0) */
+	restarts: number;
+	conf_rev_changed: boolean;
+	simulation_seen: boolean;
+}
+
+/** IEC 61850-9-2 Sampled Values publications from a device (matched by MAC) */
+export interface SvDetail {
+	publications: SvStream[];
+}
+
+/** One Sampled Values publication (publisher MAC + svID), tracked across frames */
+export interface SvStream {
+	/** Publication identifier, e.g. "MU01MU/LLN0$SV$MSVCB01" */
+	sv_id: string;
+	publisher_mac: string;
+	/** Multicast destination MAC (01:0C:CD:04:xx:xx) */
+	destination_mac: string;
+	app_id: number;
+	dat_set: string | null;
+	conf_rev: number;
+	/** Measurement channels per sample */
+	channel_count: number;
+	/** 0 = not synchronised, 1 = local clock, 2 = global (PTP/GPS) */
+	smp_synch: number | null;
+	smp_rate: number | null;
+	smp_mod: number | null;
+	/** Samples per second, from smpCnt rollover or an explicit smpRate */
+	sample_rate_hz: number | null;
+	asdu_count: number;
+	last_smp_cnt: number;
+	/** smpCnt discontinuities other than rollover (lost or injected samples) */
+	smp_cnt_gaps: number;
+	conf_rev_changed: boolean;
+	simulation_seen: boolean;
+}
+
 /** PROFINET DCP aggregated details for a device */
 export interface ProfinetDcpDetail {
 	/** "io_device", "io_controller", "io_supervisor", or "unknown" */
 	role: string;
 	/** Station name from DCP Name-of-Station block */
 	device_name: string | null;
+	/** "Type of Station" from the DCP Manufacturer block (e.g. "S7-1500") */
+	station_type: string | null;
+	/** PI-assigned vendor ID from the DCP Device ID block */
+	vendor_id: number | null;
+	/** Vendor-assigned device ID from the DCP Device ID block */
+	device_id: number | null;
+	/** Vendor name resolved from vendor_id */
+	vendor: string | null;
+	/** Product line resolved from vendor_id + device_id, when known */
+	product: string | null;
+}
+
+/** One PROFINET IO cyclic RT communication relation (provider → consumer, FrameID) */
+export interface ProfinetRtStream {
+	frame_id: number;
+	rt_class: 'rt_class1' | 'rt_class2';
+	/** Station sending the IO data */
+	provider_mac: string;
+	/** Station (or multicast group) receiving it */
+	consumer_mac: string;
+	/** IO data block length in bytes */
+	data_length: number;
+	frame_count: number;
+	/** Configured update time from the frame cycle counter */
+	counter_cycle_ms: number | null;
+	/** Measured inter-frame intervals on the wire */
+	avg_interval_ms: number | null;
+	min_interval_ms: number | null;
+	max_interval_ms: number | null;
+	interval_count: number;
+	last_cycle_counter: number;
+	/** APDU DataStatus byte of the last frame */
+	data_status: number;
+	invalid_data_frames: number;
+	/** Frames sent while the provider was in STOP */
+	provider_stopped_frames: number;
+	/** Station problem indicator seen (diagnosis pending) */
+	station_problem_seen: boolean;
+}
+
+/** PROFINET IO cyclic real-time traffic of a device */
+export interface ProfinetRtDetail {
+	/** "io_controller", "io_device", or "unknown" */
+	role: string;
+	/** Cyclic relations the device provides or consumes */
+	relations: ProfinetRtStream[];
 }
 
 /** LLDP (Link Layer Discovery Protocol) aggregated details for a device */
@@ -430,6 +1234,12 @@ export interface LldpDetail {
 	firmware: string | null;
 }
 
+/** Engineering software fingerprinted from a workstation's traffic */
+export interface EngineeringDetail {
+	/** Product names, e.g. "Siemens STEP 7 / TIA Portal" */
+	software: string[];
+}
+
 /** SNMP device identity extracted from GET-Response packets */
 export interface SnmpDetail {
 	/** sysDescr — free-text description of the device */
@@ -446,6 +1256,57 @@ export interface SnmpDetail {
 	sys_contact: string | null;
 	/** Vendor name inferred from enterprise OID */
 	vendor: string | null;
+	/** Model designation taken from sysDescr */
+	model: string | null;
+}
+
+/** SMB file-sharing details for a device */
+export interface SmbDetail {
+	/** "client" (mounts shares) or "server" (file server) */
+	role: string;
+	/** NTLM accounts that authenticated from this device (DOMAIN\user) */
+	users: string[];
+	/** Device spoke SMB1 */
+	smb1: boolean;
+	shares: SmbShareAccess[];
+}
+
+/** Activity on one SMB share between a client and a file server */
+export interface SmbShareAccess {
+	remote_ip: string;
+	/** "client" or "server" — what the REMOTE device is */
+	remote_role: string;
+	/** UNC path of the share */
+	share: string;
+	/** Files opened on the share (first 50) */
+	files: string[];
+	/** Files opened for writing or created */
+	files_written: string[];
+	bytes_written: number;
+	bytes_read: number;
+}
+
+/** DHCP details for a device */
+export interface DhcpDetail {
+	/** "client" (took a lease) or "server" (handed leases out) */
+	role: string;
+	/** Client: hostname sent in option 12 (or option 81) */
+	hostname: string | null;
+	/** Client: vendor class identifier (option 60) */
+	vendor_class: string | null;
+	/** Client: server that acknowledged the lease */
+	server_ip: string | null;
+	/** Server: clients it sent offers or ACKs to */
+	leases: DhcpLease[];
+	/** Server: offers, ACKs and NAKs sent */
+	replies: number;
+}
+
+/** A client a DHCP server answered */
+export interface DhcpLease {
+	client_mac: string;
+	ip: string | null;
+	hostname: string | null;
 }
 
 /** Redundancy protocol frame observed in the capture */
@@ -495,6 +1356,54 @@ export interface ModbusDetail {
 	device_id: ModbusDeviceIdInfo | null;
 	relationships: ModbusRelationship[];
 	polling_intervals: PollingInterval[];
+	/** Sent raw RTU frames over TCP (serial gateway, no MBAP header) */
+	rtu_over_tcp: boolean;
+	/** Register address-space map per unit ID (slaves only) */
+	register_maps: ModbusRegisterMap[];
+	/** Request/response pairing per unit ID (slaves only) */
+	transaction_stats?: ModbusTransactionStats[];
+}
+
+/** Response latency and error counts of one Modbus slave unit */
+export interface ModbusTransactionStats {
+	slave_ip: string;
+	unit_id: number;
+	requests: number;
+	/** Responses matched to a request within the timeout */
+	responses: number;
+	exceptions: number;
+	/** Requests never answered, or answered too late */
+	timeouts: number;
+	/** Requests repeated while an identical one was unanswered */
+	retries: number;
+	unmatched_responses: number;
+	/** Exceptions as a fraction of matched responses (0-1) */
+	exception_rate: number;
+	avg_latency_ms: number;
+	min_latency_ms: number;
+	max_latency_ms: number;
+	/** [exception code, count] pairs */
+	exception_codes: [number, number][];
+}
+
+/** Modbus register table */
+export type RegisterType = 'coil' | 'discrete_input' | 'input_register' | 'holding_register';
+
+/** A run of addresses in one register table that saw the same traffic */
+export interface RegisterRegion {
+	register_type: RegisterType;
+	start: number;
+	/** Last address (inclusive) */
+	end: number;
+	reads: number;
+	writes: number;
+}
+
+/** The registers of one slave unit that masters read or wrote */
+export interface ModbusRegisterMap {
+	unit_id: number;
+	masters: string[];
+	regions: RegisterRegion[];
 }
 
 /** DNP3 protocol details for a device */
@@ -503,7 +1412,13 @@ export interface Dnp3Detail {
 	addresses: number[];
 	function_codes: FunctionCodeStat[];
 	has_unsolicited: boolean;
+	/** Sent DNP3 Secure Authentication (SAv5) messages */
+	uses_secure_auth: boolean;
+	/** Link-layer CRC mismatches in frames this device sent */
+	crc_errors?: number;
 	relationships: Dnp3Relationship[];
+	/** Master link addresses this device sent frames from */
+	master_bindings: Dnp3AddressBinding[];
 }
 
 /** Function code usage statistics */
@@ -547,6 +1462,16 @@ export interface Dnp3Relationship {
 	packet_count: number;
 }
 
+/** A DNP3 master link address as used from one IP towards one outstation */
+export interface Dnp3AddressBinding {
+	master_address: number;
+	outstation_address: number;
+	outstation_ip: string;
+	first_seen: string;
+	last_seen: string;
+	frame_count: number;
+}
+
 /** Detected polling interval */
 export interface PollingInterval {
 	remote_ip: string;
@@ -578,6 +1503,7 @@ export interface AssetUpdate {
 	notes?: string;
 	purdue_level?: number;
 	tags?: string[];
+	lifecycle?: AssetLifecycle;
 }
 
 // ─── Physical Topology (Phase 7) ─────────────────────────
@@ -695,7 +1621,11 @@ export interface IngestImportResult {
 	files_processed: number;
 	asset_count: number;
 	connection_count: number;
+	/** Ingested flows merged onto an already-loaded connection */
+	correlated_connections: number;
 	alert_count: number;
+	/** Host events kept from Windows event logs */
+	host_event_count: number;
 	new_assets: number;
 	updated_assets: number;
 	duration_ms: number;
@@ -739,6 +1669,53 @@ export interface ReportConfig {
 	include_recommendations: boolean;
 }
 
+/** Format names accepted by `exportData` */
+export type ExportFormat =
+	| 'assets_csv'
+	| 'connections_csv'
+	| 'assets_json'
+	| 'topology_json'
+	| 'pdf'
+	| 'sbom_csv'
+	| 'sbom_json'
+	| 'stix'
+	| 'graphml'
+	| 'gexf'
+	| 'dot';
+
+/** An export format registered in the backend */
+export interface ExportFormatInfo {
+	format: string;
+	label: string;
+	/** File extension without the dot */
+	extension: string;
+}
+
+/** Per-export settings; each format reads only what it needs */
+export interface ExportOptions {
+	/** PDF report settings */
+	report?: ReportConfig;
+}
+
+/** Target settings for NetBox export */
+export interface NetboxConfig {
+	/** Name of an existing NetBox site */
+	site: string;
+	/** Device/IP status (default "active") */
+	status?: string;
+	/** Tag slug applied to exported objects (default "kusanagi-kajiki") */
+	tag?: string;
+}
+
+/** Outcome of a NetBox API push */
+export interface NetboxPushResult {
+	devices_created: number;
+	/** Devices already in the site, left untouched */
+	devices_existing: number;
+	ip_addresses_created: number;
+	errors: string[];
+}
+
 /** SBOM entry (CISA BOD 23-01 format) */
 export interface SbomEntry {
 	ip_address: string;
@@ -757,9 +1734,6 @@ export interface SbomEntry {
 	tags: string;
 }
 
-/** Export format options */
-export type ExportFormat = 'csv' | 'json' | 'pdf' | 'sbom_csv' | 'sbom_json' | 'stix';
-
 // ─── Security Analysis (Phase 10) ────────────────────────
 
 /** Finding type classification */
@@ -778,7 +1752,20 @@ export interface Finding {
 	affected_assets: string[];
 	evidence: string;
 	technique_id: string | null;
+	/** When this condition was first detected */
 	created_at: string;
+	/** When this condition was most recently detected */
+	last_seen: string;
+	/** Analysis runs that detected this condition */
+	occurrence_count: number;
+	/** Detection history, oldest first */
+	occurrences: FindingOccurrence[];
+}
+
+/** One detection of a finding's condition */
+export interface FindingOccurrence {
+	seen_at: string;
+	severity: FindingSeverity;
 }
 
 /** Purdue level assignment method */
@@ -793,7 +1780,12 @@ export interface PurdueAssignment {
 }
 
 /** Anomaly type classification */
-export type AnomalyType = 'polling_deviation' | 'role_reversal' | 'new_device' | 'unexpected_public_ip';
+export type AnomalyType =
+	| 'polling_deviation'
+	| 'role_reversal'
+	| 'new_device'
+	| 'unexpected_public_ip'
+	| 'duplicate_ip';
 
 /** An anomaly score from analysis */
 export interface AnomalyScore {
@@ -825,6 +1817,36 @@ export interface AnalysisSummary {
 	assets_analyzed: number;
 	connections_analyzed: number;
 	unencrypted_ot_percent: number;
+	/** Write findings suppressed by active write-path approvals */
+	suppressed_findings: number;
+}
+
+/** Analyst approval of writes from a master to one target */
+export interface WriteApproval {
+	master_ip: string;
+	slave_ip: string;
+	justification: string;
+	approved_by: string;
+	/** RFC 3339 timestamp or YYYY-MM-DD; null = never expires */
+	expires_at: string | null;
+}
+
+/** Stored write approval (including expired ones) */
+export interface WriteApprovalRow extends WriteApproval {
+	id: number;
+	created_at: string;
+}
+
+/** Write-capable master → target relationship from the last analysis run */
+export interface WritePath {
+	master_ip: string;
+	slave_ip: string;
+	protocol: string;
+	function_codes: number[];
+	/** Write commands sent by the master across all of its targets */
+	write_count: number;
+	/** Active approval covering this path, if any */
+	approval: WriteApproval | null;
 }
 
 // ─── Phase 13A Quick-Win Features ───────────────────────
@@ -924,6 +1946,128 @@ export interface UserSettings {
 	theme: ThemeMode;
 }
 
+/** Live capture limits in a settings profile */
+export interface CaptureLimits {
+	ring_buffer_size: number;
+	snaplen: number;
+	promiscuous: boolean;
+	/** Kernel capture buffer in MB (0 = libpcap default) */
+	buffer_size_mb: number;
+	/** Capture Wi-Fi interfaces in monitor (rfmon) mode */
+	monitor_mode: boolean;
+	/** Per-protocol deep-parse limits for live capture (empty = parse everything) */
+	deep_parse_budgets: DeepParseBudget[];
+	/** Capture-to-disk file size limit in MB (0 = no size limit) */
+	rotate_file_mb: number;
+	/** Capture-to-disk file time limit in minutes (0 = no time limit) */
+	rotate_file_minutes: number;
+	/** Capture-to-disk file count limit, oldest deleted first (0 = keep all) */
+	rotate_max_files: number;
+	/** Most parsed packets queued between capture and processing */
+	queue_capacity: number;
+	/** Full queue: discard the oldest packets, or stall capture */
+	queue_policy: QueuePolicy;
+}
+
+export type QueuePolicy = 'drop_oldest' | 'block';
+
+/** Deep parse at most max_per_second packets of one protocol during live capture */
+export interface DeepParseBudget {
+	protocol: string;
+	max_per_second: number;
+}
+
+/** Saved-session retention policy (null = unlimited) */
+export interface RetentionPolicy {
+	max_session_age_days: number | null;
+	max_sessions: number | null;
+}
+
+/** Analysis rules in a settings profile */
+export interface AnalysisRules {
+	/** ATT&CK technique IDs whose findings are dropped */
+	disabled_techniques: string[];
+	max_findings: number;
+	max_anomalies: number;
+	/** Seconds within which a repeated detection updates the existing finding (0 = off) */
+	finding_dedup_window_secs: number;
+}
+
+/** Site-specific port → protocol assignment (e.g. Modbus on 5020) */
+export interface PortMapping {
+	port: number;
+	protocol: string;
+}
+
+/** Versioned bundle of capture, retention, analysis and port-map settings */
+export interface SettingsProfile {
+	version: number;
+	description: string;
+	capture: CaptureLimits;
+	retention: RetentionPolicy;
+	analysis: AnalysisRules;
+	port_maps: PortMapping[];
+	/** YAML files of extra Modbus/DNP3 function codes */
+	function_code_files?: string[];
+	/** Plant subnets in CIDR notation; devices outside them group by /24 or /64 */
+	subnets?: string[];
+	/** Width in seconds of the time buckets each connection's traffic is counted in */
+	traffic_bucket_secs?: number;
+}
+
+/** A Modbus or DNP3 function code from the registry */
+export interface FunctionCodeDef {
+	code: number;
+	name: string;
+	/** Changes data on the target */
+	write: boolean;
+	/** Changes how the target operates (operate, restart, start/stop) */
+	control: boolean;
+}
+
+/** A stored settings profile with its name */
+export interface NamedProfile {
+	name: string;
+	profile: SettingsProfile;
+	updated_at: string;
+}
+
+/** Location and mode of the open database */
+export interface DatabaseStatus {
+	/** False if no database could be opened (nothing is persisted) */
+	available: boolean;
+	path: string | null;
+	read_only: boolean;
+	/** Why the database fell back to read-only, if it did */
+	notice: string | null;
+}
+
+/** Demo (anonymized) mode status */
+export interface DemoModeStatus {
+	enabled: boolean;
+}
+
+// ─── Bundled Data ────────────────────────────────────────
+
+export type DatasetKind = 'oui' | 'geo_ip' | 'signatures';
+
+/** verified: checksum matches; unverified: no checksum pinned; modified: checksum mismatch; missing/failed: not loaded */
+export type DatasetState = 'verified' | 'unverified' | 'modified' | 'missing' | 'failed';
+
+/** Load/verification status of a bundled dataset */
+export interface DatasetStatus {
+	name: string;
+	kind: DatasetKind;
+	state: DatasetState;
+	optional: boolean;
+	/** Path the dataset was loaded from */
+	path: string | null;
+	/** Records loaded (OUI prefixes, signatures); 0 for GeoIP */
+	records: number;
+	/** Explanation for any state other than verified */
+	message: string | null;
+}
+
 // ─── Timeline (Phase 11) ─────────────────────────────────
 
 /** Timeline range for the scrubber */
@@ -944,6 +2088,30 @@ export interface PluginManifest {
 	author: string | null;
 }
 
+// ─── External Connectivity ───────────────────────────────
+
+/** One local asset → cloud / remote-access service connection */
+export interface ExternalConnection {
+	local_ip: string;
+	local_hostname: string | null;
+	/** Device type of the local asset ("it_device" for non-OT hosts) */
+	local_device_type: string;
+	remote_ip: string;
+	remote_port: number;
+	remote_hostname: string | null;
+	/** ISO 3166-1 alpha-2 country of the remote address (public IPs only) */
+	remote_country: string | null;
+	remote_is_public: boolean;
+	service: string;
+	category: CloudCategory;
+	evidence: string;
+	identity: string | null;
+	packet_count: number;
+	byte_count: number;
+	first_seen: string;
+	last_seen: string;
+}
+
 // ─── Communication Patterns ──────────────────────────────
 
 /** Per-connection timing and traffic statistics */
@@ -976,6 +2144,96 @@ export interface PatternAnomaly {
 	severity: string;
 }
 
+/** A recurring time-of-day window, e.g. night shift 22:00–06:00 Mon–Fri */
+export interface ShiftWindow {
+	name: string;
+	/** Days the shift starts on, 0 = Monday … 6 = Sunday */
+	days: number[];
+	/** Minutes after local midnight */
+	start_minute: number;
+	/** At or before start_minute the shift runs past midnight */
+	end_minute: number;
+}
+
+/** Traffic compared across shift windows */
+export interface ShiftReport {
+	utc_offset_minutes: number;
+	shifts: ShiftProfile[];
+	/** Activity seen in a single shift only */
+	exclusive: ShiftExclusive[];
+}
+
+/** Traffic of one shift across every occurrence in the capture */
+export interface ShiftProfile {
+	name: string;
+	/** Time in this shift that carried traffic */
+	observed_hours: number;
+	packets: number;
+	bytes: number;
+	packets_per_hour: number;
+	protocols: { protocol: string; packets: number; bytes: number; packets_per_hour: number }[];
+	/** Hosts that sent requests to OT servers */
+	masters: { ip: string; packets: number }[];
+	write_count: number;
+	writes: { master: string; target: string; protocol: string; count: number }[];
+}
+
+/** A protocol, master or write path seen in one shift only */
+export interface ShiftExclusive {
+	kind: 'write_path' | 'master' | 'protocol';
+	subject: string;
+	shift: string;
+	packets: number;
+}
+
+/** Protocol-level state of a Modbus/DNP3 TCP session */
+export type SessionState =
+	| 'open'
+	| 'identified'
+	| 'request_outstanding'
+	| 'response_matched'
+	| 'torn_down';
+
+/** One Modbus/DNP3 TCP session and its request/response bookkeeping */
+export interface ProtocolSession {
+	client_ip: string;
+	client_port: number;
+	server_ip: string;
+	server_port: number;
+	/** Protocols in the order they appeared; more than one is a mid-stream switch */
+	protocols: string[];
+	state: SessionState;
+	/** Whether the TCP handshake was captured (false for mid-stream sessions) */
+	handshake_seen: boolean;
+	/** Whether the server side sent anything at all */
+	server_responded: boolean;
+	requests: number;
+	responses: number;
+	/** Requests that timed out or were still pending at teardown */
+	unanswered_requests: number;
+	/** Responses with no outstanding request */
+	unmatched_responses: number;
+	/** SYN never answered, or only one side sent FIN */
+	half_open: boolean;
+	packet_count: number;
+	first_seen: number;
+	last_seen: number;
+}
+
+/** Per-device Modbus/DNP3 session health counters */
+export interface DeviceSessionHealth {
+	ip: string;
+	total_sessions: number;
+	/** Sessions not torn down by the end of the capture */
+	active_sessions: number;
+	torn_down_sessions: number;
+	half_open_sessions: number;
+	/** Sessions with at least one unanswered request */
+	sessions_with_unanswered: number;
+	unanswered_requests: number;
+	protocol_switches: number;
+}
+
 // ─── Projects ─────────────────────────────────────────────
 
 /** A named engagement project (top-level container for sessions). */
@@ -1113,6 +2371,47 @@ export interface DeviceZeekEvents {
 	sample_events: ZeekEventSummary[];
 }
 
+/** Kind of event kept from a Windows event log. */
+export type HostEventKind =
+	| 'logon'
+	| 'logon_failure'
+	| 'explicit_credentials'
+	| 'service_install'
+	| 'account_created'
+	| 'log_cleared';
+
+/** An OT connection an asset opened shortly after a host event. */
+export interface FollowingConnection {
+	connection_id: string;
+	protocol: string;
+	dst_ip: string;
+	dst_port: number;
+	first_seen: string;
+	/** Seconds between the host event and the connection's first packet */
+	delay_secs: number;
+}
+
+/** A Windows event logged on an asset, or a logon that came from it. */
+export interface HostEvent {
+	timestamp: string;
+	computer: string;
+	host_ip: string | null;
+	event_id: number;
+	channel: string;
+	kind: HostEventKind;
+	/** Acting account as DOMAIN\user */
+	user: string | null;
+	/** 2 interactive, 3 network, 10 remote interactive */
+	logon_type: number | null;
+	remote_ip: string | null;
+	remote_host: string | null;
+	detail: string | null;
+	source: string;
+	/** 'host': logged on this asset; 'remote': a logon came from this asset */
+	relation: 'host' | 'remote';
+	next_ot_connection: FollowingConnection | null;
+}
+
 /** Compliance status for a single framework requirement. */
 export type ComplianceStatus = 'gap' | 'partial' | 'met' | 'not_assessed';
 
@@ -1254,6 +2553,37 @@ export interface SimulationResult {
 	zone_block_summaries: ZoneBlockSummary[];
 }
 
+/** Outcome of comparing a zone's passive SL-A ceiling against its SL-T */
+export type GapStatus = 'gap' | 'no_gap_observed' | 'not_assessed';
+
+/** One IEC 62443-3-3 system requirement evaluated for one zone */
+export interface RequirementGap {
+	requirement: string;
+	title: string;
+	foundational_requirement: string;
+	/** Highest SL (0–4) the passive evidence allows; 4 = nothing limiting observed */
+	achieved_sl: number;
+	status: GapStatus;
+	evidence: string[];
+}
+
+/** Gap worksheet rows for a single zone */
+export interface ZoneGap {
+	zone_id: string;
+	zone_name: string;
+	target_sl: SecurityLevel;
+	target_declared: boolean;
+	achieved_sl: number;
+	gap_count: number;
+	requirements: RequirementGap[];
+}
+
+/** Per-zone SL-T vs SL-A gap worksheet (Phase 15F) */
+export interface GapWorksheet {
+	zones: ZoneGap[];
+	generated_at: string;
+}
+
 /** Complete segmentation analysis output (Phases 15A–15E). */
 export interface SegmentationReport {
 	policy_groups: PolicyGroup[];
@@ -89,17 +89,31 @@ export const PROTOCOL_COLORS: Record<string, string> = {
 	opc_ua: '#ec4899',
 	profinet: '#f97316',
 	iec104: '#84cc16',
+	iec61850_mms: '#22c55e',
+	goose: '#16a34a',
+	sampled_values: '#15803d',
 	mqtt: '#14b8a6',
 	hart_ip: '#a855f7',
 	foundation_fieldbus: '#d946ef',
 	ge_srtp: '#0ea5e9',
 	wonderware_suitelink: '#e11d48',
+	fins: '#38bdf8',
+	slmp: '#f43f5e',
+	codesys: '#facc15',
+	niagara_fox: '#2dd4bf',
+	crimson: '#dc2626',
+	vnet_ip: '#0ea5e9',
+	tristation: '#e11d48',
+	ads: '#a3e635',
+	opc_da: '#fb923c',
 	http: '#475569',
 	https: '#64748b',
 	dns: '#78716c',
 	ssh: '#57534e',
 	rdp: '#71717a',
 	snmp: '#6b7280',
+	smb: '#8b5cf6',
+	icmp: '#94a3b8',
 	unknown: '#64748b'
 };
 
@@ -107,11 +121,14 @@ export const PROTOCOL_COLORS: Record<string, string> = {
 export const DEVICE_COLORS: Record<string, string> = {
 	plc: '#f59e0b',
 	rtu: '#10b981',
+	ied: '#14b8a6',
 	hmi: '#3b82f6',
 	historian: '#8b5cf6',
 	engineering_workstation: '#06b6d4',
 	scada_server: '#ec4899',
 	it_device: '#475569',
+	gateway: '#eab308',
+	multicast_group: '#94a3b8',
 	unknown: '#64748b'
 };
 
@@ -119,11 +136,14 @@ export const DEVICE_COLORS: Record<string, string> = {
 export const DEVICE_LABELS: Record<string, string> = {
 	plc: 'PLC',
 	rtu: 'RTU',
+	ied: 'IED',
 	hmi: 'HMI',
 	historian: 'Historian',
 	engineering_workstation: 'Eng. WS',
 	scada_server: 'SCADA Server',
 	it_device: 'IT Device',
+	gateway: 'Gateway',
+	multicast_group: 'Multicast Group',
 	unknown: 'Unknown'
 };
 
@@ -138,11 +158,23 @@ export function isOtProtocol(proto: string): boolean {
 		'opc_ua',
 		'profinet',
 		'iec104',
+		'iec61850_mms',
+		'goose',
+		'sampled_values',
 		'mqtt',
 		'hart_ip',
 		'foundation_fieldbus',
 		'ge_srtp',
-		'wonderware_suitelink'
+		'wonderware_suitelink',
+		'fins',
+		'slmp',
+		'codesys',
+		'niagara_fox',
+		'crimson',
+		'vnet_ip',
+		'tristation',
+		'ads',
+		'opc_da'
 	]);
 	return otProtocols.has(proto);
 }
@@ -158,44 +190,95 @@ export function primaryProtocol(node: TopologyNode): string {
 	return 'unknown';
 }
 
+/**
+ * Protocols carried by an edge, busiest first.
+ */
+export function edgeProtocols(edge: TopologyEdge): string[] {
+	const entries = Object.entries(edge.protocol_breakdown ?? {});
+	if (entries.length === 0) return [edge.protocol as string];
+	return entries
+		.sort(([, a], [, b]) => (b?.packet_count ?? 0) - (a?.packet_count ?? 0))
+		.map(([proto]) => proto);
+}
+
+/** Traffic on one service port of an edge, summed over its port pairs */
+export interface EdgeService {
+	port: number;
+	pairs: number;
+	packet_count: number;
+	byte_count: number;
+}
+
+/**
+ * Services carried by an edge, busiest first. The lower port of each pair is
+ * taken as the service; the other is usually the client's ephemeral port.
+ */
+export function edgeServices(edge: TopologyEdge): EdgeService[] {
+	const services = new Map<number, EdgeService>();
+	for (const pair of edge.ports ?? []) {
+		const port = Math.min(pair.src_port, pair.dst_port);
+		const service = services.get(port) ?? { port, pairs: 0, packet_count: 0, byte_count: 0 };
+		service.pairs += 1;
+		service.packet_count += pair.packet_count;
+		service.byte_count += pair.byte_count;
+		services.set(port, service);
+	}
+	return [...services.values()].sort((a, b) => b.packet_count - a.packet_count);
+}
+
 /**
  * Determine group parent ID for a node based on the active grouping mode.
  */
 export function getGroupId(
 	node: TopologyNode,
-	mode: 'subnet' | 'protocol' | 'device_role' | 'vendor' | 'none'
+	mode: 'subnet' | 'vlan' | 'protocol' | 'device_role' | 'vendor' | 'cluster' | 'none'
 ): string | null {
 	switch (mode) {
 		case 'subnet':
 			return `group:${node.subnet}`;
+		case 'vlan':
+			// Routers and trunk-attached hosts span VLANs and stay outside
+			return node.vlans?.length === 1 ? `group:vlan-${node.vlans[0]}` : null;
 		case 'protocol':
 			return `group:${primaryProtocol(node)}`;
 		case 'device_role':
 			return `group:${node.device_type}`;
 		case 'vendor':
 			return `group:${node.vendor ?? 'Unknown'}`;
+		case 'cluster':
+			// Devices not grouped with any other stay outside the compounds
+			return node.cluster_id === null ? null : `group:cluster-${node.cluster_id}`;
 		case 'none':
 			return null;
 	}
 }
 
 /**
- * Get display label for a group parent node.
+ * Get display label for a group parent node. VLAN groups are labelled with
+ * the switch's VLAN name when one is given.
  */
 export function getGroupLabel(
 	groupId: string,
-	mode: 'subnet' | 'protocol' | 'device_role' | 'vendor' | 'none'
+	mode: 'subnet' | 'vlan' | 'protocol' | 'device_role' | 'vendor' | 'cluster' | 'none',
+	vlanNames?: Map<number, string>
 ): string {
 	const value = groupId.replace('group:', '');
 	switch (mode) {
 		case 'subnet':
 			return value;
+		case 'vlan': {
+			const vlan = Number(value.replace('vlan-', ''));
+			const name = vlanNames?.get(vlan);
+			return name ? `VLAN ${vlan} (${name})` : `VLAN ${vlan}`;
+		}
 		case 'protocol':
 			return (value.charAt(0).toUpperCase() + value.slice(1)).replace('_', '/');
 		case 'device_role':
 			return DEVICE_LABELS[value] ?? value;
 		case 'vendor':
 			return value;
+		case 'cluster':
+			return `Cluster ${Number(value.replace('cluster-', '')) + 1}`;
 		case 'none':
 			return value;
 	}
@@ -11,7 +11,15 @@ import { listen } from '@tauri-apps/api/event';
 import type {
 	NetworkInterface,
 	ImportResult,
+	ImportSampling,
+	BatchImportMode,
+	BatchImportResult,
 	TopologyGraph,
+	VlanGroup,
+	EdgeTraffic,
+	TopologyPath,
+	Reachability,
+	ReachableNode,
 	Asset,
 	AssetPage,
 	Connection,
@@ -23,9 +31,12 @@ import type {
 	CaptureStatsEvent,
 	CaptureStatusInfo,
 	StopCaptureResult,
+	SignatureReloadResult,
 	SignatureSummary,
 	SignatureTestResult,
+	FingerprintConversionResult,
 	DeepParseInfo,
+	ModbusRegisterMap,
 	FunctionCodeStat,
 	SessionInfo,
 	AssetUpdate,
@@ -34,23 +45,43 @@ import type {
 	IngestImportResult,
 	WiresharkInfo,
 	FrameRow,
-	ReportConfig,
+	ExportFormat,
+	ExportFormatInfo,
+	ExportOptions,
+	NetboxConfig,
+	NetboxPushResult,
 	AnalysisResult,
+	WritePath,
+	WriteApprovalRow,
 	Finding,
 	PurdueAssignment,
 	AnomalyScore,
 	BaselineDiff,
 	UserSettings,
+	NamedProfile,
+	DatabaseStatus,
+	SettingsProfile,
+	DemoModeStatus,
+	DatasetStatus,
 	TimelineRange,
 	PluginManifest,
 	DefaultCredential,
 	CriticalityAssessment,
 	NamingSuggestion,
+	ExternalConnection,
 	ConnectionStats,
 	PatternAnomaly,
+	ShiftReport,
+	ShiftWindow,
+	ProtocolSession,
 	Project,
 	ProjectSummary,
 	RedundancyInfo,
+	ArpBinding,
+	IcmpActivity,
+	GooseStream,
+	SvStream,
+	ProfinetRtStream,
 	SwitchSecurityFinding,
 	CorrelatedAlert,
 	LiveAttackAlert,
@@ -60,8 +91,12 @@ import type {
 	ComplianceMapping,
 	CveMatch,
 	DeviceZeekEvents,
+	HostEvent,
 	SegmentationReport,
-	EnforcementFormat
+	EnforcementFormat,
+	GapWorksheet,
+	SecurityLevel,
+	FunctionCodeDef
 } from '$lib/types';
 
 // ─── System Commands ──────────────────────────────────────────
@@ -79,8 +114,26 @@ export async function getAppInfo(): Promise<{ version: string; rust_version: str
 // ─── PCAP Import ──────────────────────────────────────────────
 
 /** Import one or more PCAP files and parse their contents */
-export async function importPcap(paths: string[]): Promise<ImportResult> {
-	return invoke<ImportResult>('import_pcap', { paths });
+export async function importPcap(
+	paths: string[],
+	trace = false,
+	sampling?: ImportSampling,
+	/** Seconds added to each file's timestamps, keyed by path */
+	clockOffsets?: Record<string, number>
+): Promise<ImportResult> {
+	return invoke<ImportResult>('import_pcap', { paths, trace, sampling, clockOffsets });
+}
+
+/**
+ * Import every capture in a directory, merged into one session or as one
+ * session per file. Session names default to the directory name.
+ */
+export async function importPcapDirectory(
+	directory: string,
+	mode: BatchImportMode,
+	sessionName?: string
+): Promise<BatchImportResult> {
+	return invoke<BatchImportResult>('import_pcap_directory', { directory, mode, sessionName });
 }
 
 /** Cancel an in-progress PCAP import */
@@ -116,6 +169,36 @@ export async function getTopology(): Promise<TopologyGraph> {
 	return invoke<TopologyGraph>('get_topology');
 }
 
+/** Shortest chain of observed connections from one device to another (null if none) */
+export async function findTopologyPath(srcIp: string, dstIp: string): Promise<TopologyPath | null> {
+	return invoke<TopologyPath | null>('find_topology_path', { srcIp, dstIp });
+}
+
+/** Devices reachable from `ip` ('from'), or that can reach it ('to'), nearest first */
+export async function getReachability(ip: string, direction: Reachability): Promise<ReachableNode[]> {
+	return invoke<ReachableNode[]>('get_reachability', { ip, direction });
+}
+
+/** Get the part of the topology active between two RFC 3339 times */
+export async function getTopologyWindow(start: string, end: string): Promise<TopologyGraph> {
+	return invoke<TopologyGraph>('get_topology_window', { start, end });
+}
+
+/** Topology nodes grouped by VLAN, with the switch configuration's VLAN names */
+export async function getVlanGroups(): Promise<VlanGroup[]> {
+	return invoke<VlanGroup[]>('get_vlan_groups');
+}
+
+/** The topology edges carrying the most bytes, with bandwidth over time and bursts */
+export async function getTopTalkers(limit?: number): Promise<EdgeTraffic[]> {
+	return invoke<EdgeTraffic[]>('get_top_talkers', { limit: limit ?? null });
+}
+
+/** Confirm that addresses belong to one multi-interface device; returns the merged topology */
+export async function mergeTopologyDevices(ips: string[]): Promise<TopologyGraph> {
+	return invoke<TopologyGraph>('merge_topology_devices', { ips });
+}
+
 // ─── Assets ───────────────────────────────────────────────────
 
 /** Get discovered assets, paginated */
@@ -172,9 +255,9 @@ export async function getSignatures(): Promise<SignatureSummary> {
 	return invoke<SignatureSummary>('get_signatures');
 }
 
-/** Reload signatures from disk */
-export async function reloadSignatures(): Promise<number> {
-	return invoke<number>('reload_signatures');
+/** Reload signatures from disk and re-match added/changed signatures */
+export async function reloadSignatures(): Promise<SignatureReloadResult> {
+	return invoke<SignatureReloadResult>('reload_signatures');
 }
 
 /** Test a YAML signature against loaded PCAP data */
@@ -182,6 +265,17 @@ export async function testSignature(yaml: string): Promise<SignatureTestResult>
 	return invoke<SignatureTestResult>('test_signature', { yaml });
 }
 
+/** Convert a GRASSMARLIN fingerprint XML file into YAML signatures */
+export async function convertGrassmarlinFingerprint(
+	path: string,
+	outputDir?: string
+): Promise<FingerprintConversionResult> {
+	return invoke<FingerprintConversionResult>('convert_grassmarlin_fingerprint', {
+		path,
+		outputDir: outputDir ?? null
+	});
+}
+
 // ─── Deep Parse (Phase 4) ─────────────────────────────────────
 
 /** Get deep parse details for a specific device */
@@ -189,6 +283,11 @@ export async function getDeepParseInfo(ipAddress: string): Promise<DeepParseInfo
 	return invoke<DeepParseInfo | null>('get_deep_parse_info', { ipAddress });
 }
 
+/** Get the register map of one unit ID on a Modbus slave */
+export async function getRegisterMap(ip: string, unitId: number): Promise<ModbusRegisterMap | null> {
+	return invoke<ModbusRegisterMap | null>('get_register_map', { ip, unitId });
+}
+
 /** Get function code distribution across all protocols */
 export async function getFunctionCodeStats(): Promise<Record<string, FunctionCodeStat[]>> {
 	return invoke<Record<string, FunctionCodeStat[]>>('get_function_code_stats');
@@ -197,8 +296,20 @@ export async function getFunctionCodeStats(): Promise<Record<string, FunctionCod
 // ─── Live Capture (Phase 5) ───────────────────────────────────
 
 /** Start live packet capture on an interface */
-export async function startCapture(interfaceName: string, bpfFilter?: string): Promise<void> {
-	return invoke('start_capture', { interfaceName, bpfFilter: bpfFilter ?? null });
+export async function startCapture(
+	interfaceName: string,
+	bpfFilter?: string,
+	ipfixCollector?: string,
+	ipfixSampling?: number,
+	captureDir?: string
+): Promise<void> {
+	return invoke('start_capture', {
+		interfaceName,
+		bpfFilter: bpfFilter ?? null,
+		ipfixCollector: ipfixCollector ?? null,
+		ipfixSampling: ipfixSampling ?? null,
+		captureDir: captureDir ?? null
+	});
 }
 
 /** Stop live packet capture, optionally saving to a PCAP file */
@@ -245,9 +356,10 @@ export async function saveSession(name: string, description?: string): Promise<S
 	return invoke<SessionInfo>('save_session', { name, description: description ?? null });
 }
 
-/** Load a saved session by ID, replacing current state */
-export async function loadSession(sessionId: string): Promise<SessionInfo> {
-	return invoke<SessionInfo>('load_session', { sessionId });
+/** Load a saved session by ID, replacing current state.
+ * With pinSignatures, the signature set saved with the session replaces the global one. */
+export async function loadSession(sessionId: string, pinSignatures?: boolean): Promise<SessionInfo> {
+	return invoke<SessionInfo>('load_session', { sessionId, pinSignatures: pinSignatures ?? null });
 }
 
 /** List all saved sessions */
@@ -266,8 +378,14 @@ export async function exportSessionArchive(sessionId: string, outputPath: string
 }
 
 /** Import a session from a .kkj ZIP archive */
-export async function importSessionArchive(archivePath: string): Promise<SessionInfo> {
-	return invoke<SessionInfo>('import_session_archive', { archivePath });
+export async function importSessionArchive(
+	archivePath: string,
+	pinSignatures?: boolean
+): Promise<SessionInfo> {
+	return invoke<SessionInfo>('import_session_archive', {
+		archivePath,
+		pinSignatures: pinSignatures ?? null
+	});
 }
 
 // ─── Baseline Drift (Phase 11) ──────────────────────────────────
@@ -390,39 +508,24 @@ export async function saveFramesCsv(connectionId: string, outputPath: string): P
 
 // ─── Export & Reporting (Phase 9) ────────────────────────────────
 
-/** Export all assets as CSV to a file */
-export async function exportAssetsCsv(outputPath: string): Promise<string> {
-	return invoke<string>('export_assets_csv', { outputPath });
-}
-
-/** Export all connections as CSV to a file */
-export async function exportConnectionsCsv(outputPath: string): Promise<string> {
-	return invoke<string>('export_connections_csv', { outputPath });
+/** List the export formats registered in the backend */
+export async function listExportFormats(): Promise<ExportFormatInfo[]> {
+	return invoke<ExportFormatInfo[]>('list_export_formats');
 }
 
-/** Export full topology as JSON to a file */
-export async function exportTopologyJson(outputPath: string): Promise<string> {
-	return invoke<string>('export_topology_json', { outputPath });
+/** Export the session in a registered format (CSV, JSON, PDF, SBOM, STIX) to a file */
+export async function exportData(format: ExportFormat, outputPath: string, options?: ExportOptions): Promise<string> {
+	return invoke<string>('export', { format, outputPath, options: options ?? null });
 }
 
-/** Export all assets as JSON to a file */
-export async function exportAssetsJson(outputPath: string): Promise<string> {
-	return invoke<string>('export_assets_json', { outputPath });
+/** Export assets as NetBox CSV bulk-import files; returns the written paths in import order */
+export async function exportNetboxCsv(config: NetboxConfig, outputDir: string): Promise<string[]> {
+	return invoke<string[]>('export_netbox_csv', { config, outputDir });
 }
 
-/** Generate a PDF assessment report */
-export async function generatePdfReport(config: ReportConfig, outputPath: string): Promise<string> {
-	return invoke<string>('generate_pdf_report', { config, outputPath });
-}
-
-/** Export SBOM (CISA BOD 23-01 format) as CSV or JSON */
-export async function exportSbom(format: 'csv' | 'json', outputPath: string): Promise<string> {
-	return invoke<string>('export_sbom', { format, outputPath });
-}
-
-/** Export STIX 2.1 bundle as JSON */
-export async function exportStixBundle(outputPath: string): Promise<string> {
-	return invoke<string>('export_stix_bundle', { outputPath });
+/** Push assets to a NetBox instance via its REST API (existing devices are not modified) */
+export async function pushToNetbox(config: NetboxConfig, url: string, token: string): Promise<NetboxPushResult> {
+	return invoke<NetboxPushResult>('push_to_netbox', { config, url, token });
 }
 
 /** Save topology image (PNG/SVG) from frontend-captured data */
@@ -442,6 +545,32 @@ export async function getFindings(): Promise<Finding[]> {
 	return invoke<Finding[]>('get_findings');
 }
 
+/** Get write-capable master → target paths from the last analysis run (unapproved first) */
+export async function getWritePaths(): Promise<WritePath[]> {
+	return invoke<WritePath[]>('get_write_paths');
+}
+
+/** List all stored write-path approvals, including expired ones */
+export async function listWriteApprovals(): Promise<WriteApprovalRow[]> {
+	return invoke<WriteApprovalRow[]>('list_write_approvals');
+}
+
+/** Approve writes from master to slave; applies on the next analysis run */
+export async function approveWritePath(
+	masterIp: string,
+	slaveIp: string,
+	justification: string,
+	approvedBy?: string,
+	expiresAt?: string
+): Promise<WriteApprovalRow> {
+	return invoke<WriteApprovalRow>('approve_write_path', { masterIp, slaveIp, justification, approvedBy, expiresAt });
+}
+
+/** Revoke a write-path approval */
+export async function revokeWriteApproval(id: number): Promise<void> {
+	return invoke<void>('revoke_write_approval', { id });
+}
+
 /** Get Purdue level assignments from the last analysis run */
 export async function getPurdueAssignments(): Promise<PurdueAssignment[]> {
 	return invoke<PurdueAssignment[]>('get_purdue_assignments');
@@ -471,16 +600,85 @@ export async function getNamingSuggestions(): Promise<NamingSuggestion[]> {
 
 // ─── Settings (Phase 11) ────────────────────────────────────────
 
-/** Load user settings from disk */
+/** Load GUI preferences */
 export async function getSettings(): Promise<UserSettings> {
 	return invoke<UserSettings>('get_settings');
 }
 
-/** Save user settings to disk */
+/** Save GUI preferences */
 export async function saveSettings(settings: UserSettings): Promise<void> {
 	return invoke('save_settings', { settings });
 }
 
+/** List all settings profiles */
+export async function listSettingsProfiles(): Promise<NamedProfile[]> {
+	return invoke<NamedProfile[]>('list_settings_profiles');
+}
+
+/** Get the settings profile currently in effect */
+export async function getActiveSettingsProfile(): Promise<NamedProfile> {
+	return invoke<NamedProfile>('get_active_settings_profile');
+}
+
+/** Create or replace a settings profile */
+export async function saveSettingsProfile(name: string, profile: SettingsProfile): Promise<NamedProfile> {
+	return invoke<NamedProfile>('save_settings_profile', { name, profile });
+}
+
+/** Delete a settings profile (not the default or active one) */
+export async function deleteSettingsProfile(name: string): Promise<void> {
+	return invoke('delete_settings_profile', { name });
+}
+
+/** Switch to another settings profile */
+export async function activateSettingsProfile(name: string): Promise<NamedProfile> {
+	return invoke<NamedProfile>('activate_settings_profile', { name });
+}
+
+/** Export settings profiles (all if names is omitted) to a JSON file */
+export async function exportSettingsProfiles(path: string, names?: string[]): Promise<void> {
+	return invoke('export_settings_profiles', { path, names: names ?? null });
+}
+
+/** Import settings profiles from a JSON file; returns the imported names */
+export async function importSettingsProfiles(path: string): Promise<string[]> {
+	return invoke<string[]>('import_settings_profiles', { path });
+}
+
+/** Function codes known for 'modbus' or 'dnp3', including the active profile's files */
+export async function listFunctionCodes(protocol: 'modbus' | 'dnp3'): Promise<FunctionCodeDef[]> {
+	return invoke<FunctionCodeDef[]>('list_function_codes', { protocol });
+}
+
+/** Which database is open and whether it is writable */
+export async function getDatabaseStatus(): Promise<DatabaseStatus> {
+	return invoke<DatabaseStatus>('get_database_status');
+}
+
+/** Switch to another database file, or the default when path is null */
+export async function switchDatabase(path: string | null, readOnly: boolean): Promise<DatabaseStatus> {
+	return invoke<DatabaseStatus>('switch_database', { path, readOnly });
+}
+
+// ─── Demo Mode ──────────────────────────────────────────────────
+
+/** Get whether demo (anonymized) mode is active */
+export async function getDemoMode(): Promise<DemoModeStatus> {
+	return invoke<DemoModeStatus>('get_demo_mode');
+}
+
+/** Enable or disable demo mode — pseudonymizes IPs, MACs, hostnames, and vendors in all views and exports */
+export async function setDemoMode(enabled: boolean): Promise<DemoModeStatus> {
+	return invoke<DemoModeStatus>('set_demo_mode', { enabled });
+}
+
+// ─── Bundled Data ───────────────────────────────────────────────
+
+/** Get load/verification status of the bundled OUI, GeoIP, and signature datasets */
+export async function getDataStatus(): Promise<DatasetStatus[]> {
+	return invoke<DatasetStatus[]>('get_data_status');
+}
+
 // ─── Timeline (Phase 11) ────────────────────────────────────────
 
 /** Get the time range of the current dataset */
@@ -495,6 +693,13 @@ export async function listPlugins(): Promise<PluginManifest[]> {
 	return invoke<PluginManifest[]>('list_plugins');
 }
 
+// ─── External Connectivity ───────────────────────────────
+
+/** Get outbound cloud / remote-access connections for the current dataset */
+export async function getExternalConnectivity(): Promise<ExternalConnection[]> {
+	return invoke<ExternalConnection[]>('get_external_connectivity');
+}
+
 // ─── Communication Patterns ──────────────────────────────
 
 /** Get per-connection timing statistics for the current dataset */
@@ -507,11 +712,52 @@ export async function getPatternAnomalies(): Promise<PatternAnomaly[]> {
 	return invoke<PatternAnomaly[]>('get_pattern_anomalies');
 }
 
+/** Compare traffic across shift windows (default schedule when omitted) */
+export async function getShiftReport(
+	shifts?: ShiftWindow[],
+	utcOffsetMinutes?: number
+): Promise<ShiftReport> {
+	return invoke<ShiftReport>('get_shift_report', {
+		shifts: shifts ?? null,
+		utcOffsetMinutes: utcOffsetMinutes ?? null
+	});
+}
+
+/** Get Modbus/DNP3 TCP sessions with their protocol state */
+export async function getProtocolSessions(): Promise<ProtocolSession[]> {
+	return invoke<ProtocolSession[]>('get_protocol_sessions');
+}
+
 /** Get observed Layer-2 redundancy protocol frames (MRP/RSTP/HSR/PRP/DLR) */
 export async function getRedundancyProtocols(): Promise<RedundancyInfo[]> {
 	return invoke<RedundancyInfo[]>('get_redundancy_protocols');
 }
 
+/** Get IPv4 → MAC bindings learned from ARP, with every MAC that claimed each address */
+export async function getArpBindings(): Promise<ArpBinding[]> {
+	return invoke<ArpBinding[]>('get_arp_bindings');
+}
+
+/** Get per-host ICMP echo fan-out, replies and unreachables, widest sweep first */
+export async function getIcmpActivity(): Promise<IcmpActivity[]> {
+	return invoke<IcmpActivity[]>('get_icmp_activity');
+}
+
+/** Get observed IEC 61850 GOOSE control blocks with stNum/sqNum sequence state */
+export async function getGooseStreams(): Promise<GooseStream[]> {
+	return invoke<GooseStream[]>('get_goose_streams');
+}
+
+/** Get observed IEC 61850 Sampled Values publications with derived sample rates */
+export async function getSvStreams(): Promise<SvStream[]> {
+	return invoke<SvStream[]>('get_sv_streams');
+}
+
+/** Get observed PROFINET IO cyclic RT relations with cycle times */
+export async function getProfinetRtStreams(): Promise<ProfinetRtStream[]> {
+	return invoke<ProfinetRtStream[]>('get_profinet_rt_streams');
+}
+
 /** Run switch port security assessment and return findings */
 export async function getSwitchSecurityFindings(): Promise<SwitchSecurityFinding[]> {
 	return invoke<SwitchSecurityFinding[]>('get_switch_security_findings');
@@ -623,6 +869,14 @@ export async function exportFilteredPcap(
 	});
 }
 
+/** Export the packets of the given connections from their PCAP files to a new PCAP file */
+export async function exportConnectionPcap(
+	connectionIds: string[],
+	outputPath: string
+): Promise<FilteredPcapResult> {
+	return invoke<FilteredPcapResult>('export_connection_pcap', { connectionIds, outputPath });
+}
+
 /** Listen for real-time ATT&CK alerts during live capture */
 export async function onLiveAttackAlert(
 	callback: (alert: LiveAttackAlert) => void
@@ -681,6 +935,23 @@ export async function getDeviceZeekEvents(deviceIp: string): Promise<DeviceZeekE
 	return invoke<DeviceZeekEvents>('get_device_zeek_events', { deviceIp });
 }
 
+/**
+ * Import Windows Security/System event log exports (XML or JSON).
+ * `hostIp` ties the events to an asset when the computer name is unknown.
+ */
+export async function importWindowsEvents(paths: string[], hostIp?: string): Promise<IngestImportResult> {
+	return invoke<IngestImportResult>('import_windows_events', { paths, hostIp: hostIp ?? null });
+}
+
+/**
+ * Get Windows events logged on a device or naming it as a logon's origin,
+ * each with the first OT connection the device opened within `windowSecs`
+ * (default one hour) after it.
+ */
+export async function getHostEvents(ip: string, windowSecs?: number): Promise<HostEvent[]> {
+	return invoke<HostEvent[]>('get_host_events', { ip, windowSecs: windowSecs ?? null });
+}
+
 // ─── Phase 15: Microsegmentation ──────────────────────────────
 
 /** Run the full segmentation analysis pipeline (Phases 15A–15E) */
@@ -692,3 +963,18 @@ export async function runSegmentation(): Promise<SegmentationReport> {
 export async function exportEnforcementConfig(format: EnforcementFormat): Promise<string> {
 	return invoke<string>('export_enforcement_config', { format });
 }
+
+/** Build the IEC 62443 SL-T vs SL-A gap worksheet; targets map zone IDs to declared SL-T */
+export async function getSlGapWorksheet(
+	targets: Record<string, SecurityLevel>
+): Promise<GapWorksheet> {
+	return invoke<GapWorksheet>('get_sl_gap_worksheet', { targets });
+}
+
+/** Export the gap worksheet as CSV to the given path */
+export async function exportSlGapWorksheet(
+	targets: Record<string, SecurityLevel>,
+	outputPath: string
+): Promise<string> {
+	return invoke<string>('export_sl_gap_worksheet', { targets, outputPath });
+}